@@ -2,6 +2,11 @@ mod export;
 mod jsonrpc_lite;
 mod protocol;
 
+/// Revision of the app-server protocol, reported by `initialize`. Bumped
+/// whenever methods, notifications, or fields are added, so clients can
+/// detect what a server supports.
+pub const PROTOCOL_VERSION: u32 = 2;
+
 pub use export::generate_json;
 pub use export::generate_ts;
 pub use export::generate_types;
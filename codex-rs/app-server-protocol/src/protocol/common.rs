@@ -129,10 +129,38 @@ client_request_definitions! {
         params: v2::ThreadLoadedListParams,
         response: v2::ThreadLoadedListResponse,
     },
+    ThreadAnnotationAdd => "thread/annotation/add" {
+        params: v2::ThreadAnnotationAddParams,
+        response: v2::ThreadAnnotationAddResponse,
+    },
+    ThreadAnnotationList => "thread/annotation/list" {
+        params: v2::ThreadAnnotationListParams,
+        response: v2::ThreadAnnotationListResponse,
+    },
+    ThreadAnnotationRemove => "thread/annotation/remove" {
+        params: v2::ThreadAnnotationRemoveParams,
+        response: v2::ThreadAnnotationRemoveResponse,
+    },
+    ThreadVariablesSet => "thread/variables/set" {
+        params: v2::ThreadVariablesSetParams,
+        response: v2::ThreadVariablesSetResponse,
+    },
     SkillsList => "skills/list" {
         params: v2::SkillsListParams,
         response: v2::SkillsListResponse,
     },
+    HooksList => "hooks/list" {
+        params: v2::HooksListParams,
+        response: v2::HooksListResponse,
+    },
+    HookSetEnabled => "hooks/setEnabled" {
+        params: v2::HookSetEnabledParams,
+        response: v2::HookSetEnabledResponse,
+    },
+    HooksSubscribe => "hooks/subscribe" {
+        params: v2::HooksSubscribeParams,
+        response: v2::HooksSubscribeResponse,
+    },
     TurnStart => "turn/start" {
         params: v2::TurnStartParams,
         response: v2::TurnStartResponse,
@@ -562,6 +590,7 @@ server_notification_definitions! {
     ReasoningTextDelta => "item/reasoning/textDelta" (v2::ReasoningTextDeltaNotification),
     ContextCompacted => "thread/compacted" (v2::ContextCompactedNotification),
     DeprecationNotice => "deprecationNotice" (v2::DeprecationNoticeNotification),
+    HookTriggered => "hook/triggered" (v2::HookTriggeredNotification),
 
     /// Notifies the user of world-writable directories on Windows, which cannot be protected by the sandbox.
     WindowsWorldWritableWarning => "windows/worldWritableWarning" (v2::WindowsWorldWritableWarningNotification),
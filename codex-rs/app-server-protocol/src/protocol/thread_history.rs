@@ -377,6 +377,7 @@ mod tests {
             }),
             EventMsg::TurnAborted(TurnAbortedEvent {
                 reason: TurnAbortReason::Replaced,
+                cancelled: Vec::new(),
             }),
             EventMsg::UserMessage(UserMessageEvent {
                 message: "Let's try again".into(),
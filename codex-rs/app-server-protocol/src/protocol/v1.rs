@@ -45,6 +45,9 @@ pub struct ClientInfo {
 #[serde(rename_all = "camelCase")]
 pub struct InitializeResponse {
     pub user_agent: String,
+    /// The server's [`PROTOCOL_VERSION`](crate::PROTOCOL_VERSION).
+    #[serde(default)]
+    pub protocol_version: u32,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
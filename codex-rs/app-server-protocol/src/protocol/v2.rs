@@ -18,6 +18,9 @@ use codex_protocol::plan_tool::StepStatus as CorePlanStepStatus;
 use codex_protocol::protocol::AskForApproval as CoreAskForApproval;
 use codex_protocol::protocol::CodexErrorInfo as CoreCodexErrorInfo;
 use codex_protocol::protocol::CreditsSnapshot as CoreCreditsSnapshot;
+use codex_protocol::protocol::HookFiring as CoreHookFiring;
+use codex_protocol::protocol::HookSource as CoreHookSource;
+use codex_protocol::protocol::HookSummary as CoreHookSummary;
 use codex_protocol::protocol::NetworkAccess as CoreNetworkAccess;
 use codex_protocol::protocol::RateLimitSnapshot as CoreRateLimitSnapshot;
 use codex_protocol::protocol::RateLimitWindow as CoreRateLimitWindow;
@@ -30,6 +33,7 @@ use codex_protocol::protocol::TokenUsageInfo as CoreTokenUsageInfo;
 use codex_protocol::user_input::UserInput as CoreUserInput;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use mcp_types::ContentBlock as McpContentBlock;
+use mcp_types::Prompt as McpPrompt;
 use mcp_types::Resource as McpResource;
 use mcp_types::ResourceTemplate as McpResourceTemplate;
 use mcp_types::Tool as McpTool;
@@ -97,6 +101,9 @@ pub enum CodexErrorInfo {
         #[ts(rename = "httpStatusCode")]
         http_status_code: Option<u16>,
     },
+    /// The response SSE stream delivered data that could not be parsed; the
+    /// request is retried.
+    ResponseStreamMalformed,
     /// Reached the retry limit for responses.
     ResponseTooManyFailedAttempts {
         #[serde(rename = "httpStatusCode")]
@@ -125,6 +132,7 @@ impl From<CoreCodexErrorInfo> for CodexErrorInfo {
             CoreCodexErrorInfo::ResponseStreamDisconnected { http_status_code } => {
                 CodexErrorInfo::ResponseStreamDisconnected { http_status_code }
             }
+            CoreCodexErrorInfo::ResponseStreamMalformed => CodexErrorInfo::ResponseStreamMalformed,
             CoreCodexErrorInfo::ResponseTooManyFailedAttempts { http_status_code } => {
                 CodexErrorInfo::ResponseTooManyFailedAttempts { http_status_code }
             }
@@ -254,6 +262,9 @@ pub enum ConfigLayerSource {
         dot_codex_folder: AbsolutePathBuf,
     },
 
+    /// Overrides from `CODEX_CONFIG_*` environment variables.
+    Environment,
+
     /// Session-layer overrides supplied via `-c`/`--config`.
     SessionFlags,
 
@@ -279,6 +290,7 @@ impl ConfigLayerSource {
             ConfigLayerSource::System { .. } => 10,
             ConfigLayerSource::User { .. } => 20,
             ConfigLayerSource::Project { .. } => 25,
+            ConfigLayerSource::Environment => 28,
             ConfigLayerSource::SessionFlags => 30,
             ConfigLayerSource::LegacyManagedConfigTomlFromFile { .. } => 40,
             ConfigLayerSource::LegacyManagedConfigTomlFromMdm => 50,
@@ -459,6 +471,10 @@ pub struct ConfigReadResponse {
 pub struct ConfigRequirements {
     pub allowed_approval_policies: Option<Vec<AskForApproval>>,
     pub allowed_sandbox_modes: Option<Vec<SandboxMode>>,
+    /// Tool names the model may not call; a trailing `*` matches any suffix.
+    pub banned_tools: Option<Vec<String>>,
+    /// Names of the hooks that always run and cannot be disabled.
+    pub required_hooks: Option<Vec<String>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -927,6 +943,7 @@ pub struct McpServerStatus {
     pub tools: std::collections::HashMap<String, McpTool>,
     pub resources: Vec<McpResource>,
     pub resource_templates: Vec<McpResourceTemplate>,
+    pub prompts: Vec<McpPrompt>,
     pub auth_status: McpAuthStatus,
 }
 
@@ -1201,6 +1218,103 @@ pub struct ThreadLoadedListResponse {
     pub next_cursor: Option<String>,
 }
 
+/// What a reviewer attached to a turn.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[ts(tag = "type")]
+#[ts(export_to = "v2/")]
+pub enum TurnAnnotationBody {
+    Comment {
+        text: String,
+    },
+    /// Positive for a good turn and negative for a bad one.
+    Rating {
+        score: i32,
+    },
+    Label {
+        label: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TurnAnnotation {
+    pub id: String,
+    /// 1-based number of the annotated turn; turn `n` is the `turn-n` of a
+    /// `thread/resume` response.
+    pub turn: u32,
+    pub author: Option<String>,
+    /// RFC 3339 time the annotation was added.
+    pub created_at: String,
+    pub body: TurnAnnotationBody,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadAnnotationAddParams {
+    pub thread_id: String,
+    /// 1-based number of the turn to annotate.
+    pub turn: u32,
+    #[serde(default)]
+    pub author: Option<String>,
+    pub body: TurnAnnotationBody,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadAnnotationAddResponse {
+    pub annotation: TurnAnnotation,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadAnnotationListParams {
+    pub thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadAnnotationListResponse {
+    /// Annotations of the thread, oldest first.
+    pub data: Vec<TurnAnnotation>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadAnnotationRemoveParams {
+    pub thread_id: String,
+    pub annotation_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadAnnotationRemoveResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadVariablesSetParams {
+    pub thread_id: String,
+    /// Variables to set, by name; a `null` value clears the variable. An empty
+    /// map changes nothing and returns the current variables.
+    pub variables: HashMap<String, Option<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadVariablesSetResponse {
+    /// Every variable of the thread after the change.
+    pub variables: HashMap<String, String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1221,6 +1335,133 @@ pub struct SkillsListResponse {
     pub data: Vec<SkillsListEntry>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct HooksListParams {
+    pub thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct HooksListResponse {
+    /// Hooks attached to the thread, in registration order.
+    pub data: Vec<Hook>,
+    /// Most recent hook runs, oldest first.
+    pub recent_runs: Vec<HookRun>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct HookSetEnabledParams {
+    pub thread_id: String,
+    pub name: String,
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct HookSetEnabledResponse {
+    /// Hooks attached to the thread after the change.
+    pub data: Vec<Hook>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct HooksSubscribeParams {
+    pub thread_id: String,
+    /// Event names (the snake_case `type` of a core event, e.g. `task_complete`)
+    /// to receive as `hook/triggered` notifications; `"*"` matches every event.
+    /// Replaces any previous subscription for the thread; an empty list
+    /// unsubscribes.
+    pub events: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct HooksSubscribeResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(rename_all = "camelCase", export_to = "v2/")]
+pub enum HookSource {
+    /// Shipped with Codex; cannot be disabled.
+    BuiltIn,
+    /// Configured by the user, e.g. under `[hooks.commands]`.
+    External,
+    /// Required by an administrator in `requirements.toml`; cannot be disabled.
+    Managed,
+}
+
+impl From<CoreHookSource> for HookSource {
+    fn from(value: CoreHookSource) -> Self {
+        match value {
+            CoreHookSource::BuiltIn => Self::BuiltIn,
+            CoreHookSource::External => Self::External,
+            CoreHookSource::Managed => Self::Managed,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct Hook {
+    pub name: String,
+    /// Event names that fire the hook; `"*"` matches every event.
+    pub events: Vec<String>,
+    pub source: HookSource,
+    pub enabled: bool,
+    /// Hooks with a higher priority run first.
+    pub priority: i32,
+    /// Whether the session waits for the hook, which can then veto the event.
+    pub blocking: bool,
+}
+
+impl From<CoreHookSummary> for Hook {
+    fn from(value: CoreHookSummary) -> Self {
+        Self {
+            name: value.name,
+            events: value.events,
+            source: value.source.into(),
+            enabled: value.enabled,
+            priority: value.priority,
+            blocking: value.blocking,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct HookRun {
+    pub hook: String,
+    /// Name of the event that fired the hook.
+    pub event: String,
+    /// RFC 3339 timestamp of when the hook started.
+    pub started_at: String,
+    pub duration_ms: u64,
+    /// Why the hook failed, if it did.
+    pub error: Option<String>,
+}
+
+impl From<CoreHookFiring> for HookRun {
+    fn from(value: CoreHookFiring) -> Self {
+        Self {
+            hook: value.hook,
+            event: value.event,
+            started_at: value.started_at,
+            duration_ms: value.duration_ms,
+            error: value.error,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 #[ts(rename_all = "snake_case")]
@@ -1522,6 +1763,10 @@ pub struct TurnStartResponse {
 pub struct TurnInterruptParams {
     pub thread_id: String,
     pub turn_id: String,
+    /// Stop generating but keep the part of the reply streamed so far in the
+    /// thread history.
+    #[serde(default)]
+    pub keep_output: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -1535,10 +1780,29 @@ pub struct TurnInterruptResponse {}
 #[ts(tag = "type")]
 #[ts(export_to = "v2/")]
 pub enum UserInput {
-    Text { text: String },
-    Image { url: String },
-    LocalImage { path: PathBuf },
-    Skill { name: String, path: PathBuf },
+    Text {
+        text: String,
+    },
+    Image {
+        url: String,
+    },
+    LocalImage {
+        path: PathBuf,
+    },
+    Skill {
+        name: String,
+        path: PathBuf,
+    },
+    McpResource {
+        server: String,
+        uri: String,
+    },
+    McpPrompt {
+        server: String,
+        name: String,
+        #[serde(default)]
+        arguments: HashMap<String, String>,
+    },
 }
 
 impl UserInput {
@@ -1548,6 +1812,16 @@ impl UserInput {
             UserInput::Image { url } => CoreUserInput::Image { image_url: url },
             UserInput::LocalImage { path } => CoreUserInput::LocalImage { path },
             UserInput::Skill { name, path } => CoreUserInput::Skill { name, path },
+            UserInput::McpResource { server, uri } => CoreUserInput::McpResource { server, uri },
+            UserInput::McpPrompt {
+                server,
+                name,
+                arguments,
+            } => CoreUserInput::McpPrompt {
+                server,
+                name,
+                arguments,
+            },
         }
     }
 }
@@ -1559,6 +1833,16 @@ impl From<CoreUserInput> for UserInput {
             CoreUserInput::Image { image_url } => UserInput::Image { url: image_url },
             CoreUserInput::LocalImage { path } => UserInput::LocalImage { path },
             CoreUserInput::Skill { name, path } => UserInput::Skill { name, path },
+            CoreUserInput::McpResource { server, uri } => UserInput::McpResource { server, uri },
+            CoreUserInput::McpPrompt {
+                server,
+                name,
+                arguments,
+            } => UserInput::McpPrompt {
+                server,
+                name,
+                arguments,
+            },
             _ => unreachable!("unsupported user input variant"),
         }
     }
@@ -2087,6 +2371,18 @@ pub struct AccountLoginCompletedNotification {
     pub error: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct HookTriggeredNotification {
+    pub thread_id: String,
+    pub turn_id: String,
+    /// Name of the event, as used in `hooks/subscribe`.
+    pub event: String,
+    /// The core event payload, serialized as-is.
+    pub payload: JsonValue,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -2148,6 +2444,15 @@ mod tests {
                     name: "skill-creator".to_string(),
                     path: PathBuf::from("/repo/.codex/skills/skill-creator/SKILL.md"),
                 },
+                CoreUserInput::McpResource {
+                    server: "docs".to_string(),
+                    uri: "file:///guide.md".to_string(),
+                },
+                CoreUserInput::McpPrompt {
+                    server: "docs".to_string(),
+                    name: "summarize".to_string(),
+                    arguments: HashMap::from([("topic".to_string(), "auth".to_string())]),
+                },
             ],
         });
 
@@ -2169,6 +2474,15 @@ mod tests {
                         name: "skill-creator".to_string(),
                         path: PathBuf::from("/repo/.codex/skills/skill-creator/SKILL.md"),
                     },
+                    UserInput::McpResource {
+                        server: "docs".to_string(),
+                        uri: "file:///guide.md".to_string(),
+                    },
+                    UserInput::McpPrompt {
+                        server: "docs".to_string(),
+                        name: "summarize".to_string(),
+                        arguments: HashMap::from([("topic".to_string(), "auth".to_string())]),
+                    },
                 ],
             }
         );
@@ -1,6 +1,9 @@
 use crate::codex_message_processor::ApiVersion;
+use crate::codex_message_processor::HookRequestKind;
+use crate::codex_message_processor::PendingHookRequests;
 use crate::codex_message_processor::PendingInterrupts;
 use crate::codex_message_processor::PendingRollbacks;
+use crate::codex_message_processor::PendingVariableRequests;
 use crate::codex_message_processor::TurnSummary;
 use crate::codex_message_processor::TurnSummaryStore;
 use crate::codex_message_processor::read_event_msgs_from_rollout;
@@ -31,6 +34,8 @@ use codex_app_server_protocol::FileChangeOutputDeltaNotification;
 use codex_app_server_protocol::FileChangeRequestApprovalParams;
 use codex_app_server_protocol::FileChangeRequestApprovalResponse;
 use codex_app_server_protocol::FileUpdateChange;
+use codex_app_server_protocol::HookSetEnabledResponse;
+use codex_app_server_protocol::HooksListResponse;
 use codex_app_server_protocol::InterruptConversationResponse;
 use codex_app_server_protocol::ItemCompletedNotification;
 use codex_app_server_protocol::ItemStartedNotification;
@@ -51,6 +56,7 @@ use codex_app_server_protocol::ThreadItem;
 use codex_app_server_protocol::ThreadRollbackResponse;
 use codex_app_server_protocol::ThreadTokenUsage;
 use codex_app_server_protocol::ThreadTokenUsageUpdatedNotification;
+use codex_app_server_protocol::ThreadVariablesSetResponse;
 use codex_app_server_protocol::Turn;
 use codex_app_server_protocol::TurnCompletedNotification;
 use codex_app_server_protocol::TurnDiffUpdatedNotification;
@@ -69,6 +75,7 @@ use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::FileChange as CoreFileChange;
+use codex_core::protocol::ListHooksResponseEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::Op;
@@ -97,6 +104,8 @@ pub(crate) async fn apply_bespoke_event_handling(
     outgoing: Arc<OutgoingMessageSender>,
     pending_interrupts: PendingInterrupts,
     pending_rollbacks: PendingRollbacks,
+    pending_hook_requests: PendingHookRequests,
+    pending_variable_requests: PendingVariableRequests,
     turn_summary_store: TurnSummaryStore,
     api_version: ApiVersion,
     fallback_model_provider: String,
@@ -106,6 +115,54 @@ pub(crate) async fn apply_bespoke_event_handling(
         msg,
     } = event;
     match msg {
+        EventMsg::ListHooksResponse(ev) => {
+            handle_hook_response(event_turn_id, Ok(ev), &pending_hook_requests, &outgoing).await;
+        }
+        EventMsg::Error(ev)
+            if pending_hook_requests
+                .lock()
+                .await
+                .contains_key(&event_turn_id) =>
+        {
+            handle_hook_response(
+                event_turn_id,
+                Err(ev.message),
+                &pending_hook_requests,
+                &outgoing,
+            )
+            .await;
+        }
+        EventMsg::VariablesUpdated(ev) => {
+            let pending = pending_variable_requests
+                .lock()
+                .await
+                .remove(&event_turn_id);
+            if let Some(request_id) = pending {
+                let response = ThreadVariablesSetResponse {
+                    variables: ev.variables.into_iter().collect(),
+                };
+                outgoing.send_response(request_id, response).await;
+            }
+        }
+        EventMsg::Error(ev)
+            if pending_variable_requests
+                .lock()
+                .await
+                .contains_key(&event_turn_id) =>
+        {
+            let pending = pending_variable_requests
+                .lock()
+                .await
+                .remove(&event_turn_id);
+            if let Some(request_id) = pending {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: ev.message,
+                    data: None,
+                };
+                outgoing.send_error(request_id, error).await;
+            }
+        }
         EventMsg::TurnComplete(_ev) => {
             handle_turn_complete(
                 conversation_id,
@@ -197,6 +254,7 @@ pub(crate) async fn apply_bespoke_event_handling(
             reason,
             proposed_execpolicy_amendment,
             parsed_cmd,
+            operations: _,
         }) => match api_version {
             ApiVersion::V1 => {
                 let params = ExecCommandApprovalParams {
@@ -992,6 +1050,49 @@ async fn handle_turn_interrupted(
     .await;
 }
 
+/// Replies to the `hooks/list` or `hooks/setEnabled` request that sent
+/// submission `sub_id`, if any.
+async fn handle_hook_response(
+    sub_id: String,
+    result: Result<ListHooksResponseEvent, String>,
+    pending_hook_requests: &PendingHookRequests,
+    outgoing: &OutgoingMessageSender,
+) {
+    let Some((request_id, kind)) = pending_hook_requests.lock().await.remove(&sub_id) else {
+        return;
+    };
+    let ListHooksResponseEvent {
+        hooks,
+        recent_firings,
+    } = match result {
+        Ok(ev) => ev,
+        Err(message) => {
+            let error = JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message,
+                data: None,
+            };
+            outgoing.send_error(request_id, error).await;
+            return;
+        }
+    };
+    let data = hooks.into_iter().map(Into::into).collect();
+    match kind {
+        HookRequestKind::List => {
+            let response = HooksListResponse {
+                data,
+                recent_runs: recent_firings.into_iter().map(Into::into).collect(),
+            };
+            outgoing.send_response(request_id, response).await;
+        }
+        HookRequestKind::SetEnabled => {
+            outgoing
+                .send_response(request_id, HookSetEnabledResponse { data })
+                .await;
+        }
+    }
+}
+
 async fn handle_thread_rollback_failed(
     conversation_id: ThreadId,
     message: String,
@@ -43,6 +43,11 @@ use codex_app_server_protocol::GetUserAgentResponse;
 use codex_app_server_protocol::GetUserSavedConfigResponse;
 use codex_app_server_protocol::GitDiffToRemoteResponse;
 use codex_app_server_protocol::GitInfo as ApiGitInfo;
+use codex_app_server_protocol::HookSetEnabledParams;
+use codex_app_server_protocol::HookTriggeredNotification;
+use codex_app_server_protocol::HooksListParams;
+use codex_app_server_protocol::HooksSubscribeParams;
+use codex_app_server_protocol::HooksSubscribeResponse;
 use codex_app_server_protocol::InputItem as WireInputItem;
 use codex_app_server_protocol::InterruptConversationParams;
 use codex_app_server_protocol::JSONRPCErrorError;
@@ -86,6 +91,12 @@ use codex_app_server_protocol::SetDefaultModelResponse;
 use codex_app_server_protocol::SkillsListParams;
 use codex_app_server_protocol::SkillsListResponse;
 use codex_app_server_protocol::Thread;
+use codex_app_server_protocol::ThreadAnnotationAddParams;
+use codex_app_server_protocol::ThreadAnnotationAddResponse;
+use codex_app_server_protocol::ThreadAnnotationListParams;
+use codex_app_server_protocol::ThreadAnnotationListResponse;
+use codex_app_server_protocol::ThreadAnnotationRemoveParams;
+use codex_app_server_protocol::ThreadAnnotationRemoveResponse;
 use codex_app_server_protocol::ThreadArchiveParams;
 use codex_app_server_protocol::ThreadArchiveResponse;
 use codex_app_server_protocol::ThreadForkParams;
@@ -101,7 +112,10 @@ use codex_app_server_protocol::ThreadRollbackParams;
 use codex_app_server_protocol::ThreadStartParams;
 use codex_app_server_protocol::ThreadStartResponse;
 use codex_app_server_protocol::ThreadStartedNotification;
+use codex_app_server_protocol::ThreadVariablesSetParams;
 use codex_app_server_protocol::Turn;
+use codex_app_server_protocol::TurnAnnotation;
+use codex_app_server_protocol::TurnAnnotationBody;
 use codex_app_server_protocol::TurnError;
 use codex_app_server_protocol::TurnInterruptParams;
 use codex_app_server_protocol::TurnStartParams;
@@ -136,16 +150,25 @@ use codex_core::exec_env::create_env;
 use codex_core::features::Feature;
 use codex_core::find_thread_path_by_id_str;
 use codex_core::git_info::git_diff_to_remote;
+use codex_core::hooks::pattern_matches;
 use codex_core::mcp::collect_mcp_snapshot;
 use codex_core::mcp::group_tools_by_server;
 use codex_core::parse_cursor;
+use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDelivery as CoreReviewDelivery;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::ReviewTarget as CoreReviewTarget;
 use codex_core::protocol::SessionConfiguredEvent;
+use codex_core::protocol::Submission;
 use codex_core::read_head_for_summary;
+use codex_core::rollout_annotations::AnnotationBody;
+use codex_core::rollout_annotations::AnnotationError;
+use codex_core::rollout_annotations::TurnAnnotation as CoreTurnAnnotation;
+use codex_core::rollout_annotations::add_annotation;
+use codex_core::rollout_annotations::read_annotations;
+use codex_core::rollout_annotations::remove_annotation;
 use codex_core::sandboxing::SandboxPermissions;
 use codex_feedback::CodexFeedback;
 use codex_login::ServerOptions as LoginServerOptions;
@@ -188,6 +211,25 @@ pub(crate) type PendingInterrupts = Arc<Mutex<HashMap<ThreadId, PendingInterrupt
 
 pub(crate) type PendingRollbacks = Arc<Mutex<HashMap<ThreadId, RequestId>>>;
 
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum HookRequestKind {
+    List,
+    SetEnabled,
+}
+
+/// In-flight `hooks/list` and `hooks/setEnabled` requests, keyed by the id of
+/// the submission sent to the thread. We reply when its `ListHooksResponse`
+/// (or error) arrives.
+pub(crate) type PendingHookRequests = Arc<Mutex<HashMap<String, (RequestId, HookRequestKind)>>>;
+
+/// In-flight `thread/variables/set` requests, keyed by the id of the
+/// submission sent to the thread. We reply when its `VariablesUpdated` (or
+/// error) arrives.
+pub(crate) type PendingVariableRequests = Arc<Mutex<HashMap<String, RequestId>>>;
+
+/// Event names each thread forwards as `hook/triggered` notifications.
+type HookSubscriptions = Arc<Mutex<HashMap<ThreadId, Vec<String>>>>;
+
 /// Per-conversation accumulation of the latest states e.g. error message while a turn runs.
 #[derive(Default, Clone)]
 pub(crate) struct TurnSummary {
@@ -232,6 +274,9 @@ pub(crate) struct CodexMessageProcessor {
     pending_interrupts: PendingInterrupts,
     // Queue of pending rollback requests per conversation. We reply when ThreadRollback arrives.
     pending_rollbacks: PendingRollbacks,
+    pending_hook_requests: PendingHookRequests,
+    pending_variable_requests: PendingVariableRequests,
+    hook_subscriptions: HookSubscriptions,
     turn_summary_store: TurnSummaryStore,
     pending_fuzzy_searches: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
     feedback: CodexFeedback,
@@ -287,6 +332,9 @@ impl CodexMessageProcessor {
             active_login: Arc::new(Mutex::new(None)),
             pending_interrupts: Arc::new(Mutex::new(HashMap::new())),
             pending_rollbacks: Arc::new(Mutex::new(HashMap::new())),
+            pending_hook_requests: Arc::new(Mutex::new(HashMap::new())),
+            pending_variable_requests: Arc::new(Mutex::new(HashMap::new())),
+            hook_subscriptions: Arc::new(Mutex::new(HashMap::new())),
             turn_summary_store: Arc::new(Mutex::new(HashMap::new())),
             pending_fuzzy_searches: Arc::new(Mutex::new(HashMap::new())),
             feedback,
@@ -389,9 +437,48 @@ impl CodexMessageProcessor {
             ClientRequest::ThreadLoadedList { request_id, params } => {
                 self.thread_loaded_list(request_id, params).await;
             }
+            ClientRequest::ThreadAnnotationAdd { request_id, params } => {
+                self.thread_annotation_add(request_id, params).await;
+            }
+            ClientRequest::ThreadAnnotationList { request_id, params } => {
+                self.thread_annotation_list(request_id, params).await;
+            }
+            ClientRequest::ThreadAnnotationRemove { request_id, params } => {
+                self.thread_annotation_remove(request_id, params).await;
+            }
+            ClientRequest::ThreadVariablesSet { request_id, params } => {
+                self.thread_variables_set(request_id, params).await;
+            }
             ClientRequest::SkillsList { request_id, params } => {
                 self.skills_list(request_id, params).await;
             }
+            ClientRequest::HooksList { request_id, params } => {
+                let HooksListParams { thread_id } = params;
+                self.submit_hook_request(
+                    request_id,
+                    thread_id,
+                    HookRequestKind::List,
+                    Op::ListHooks,
+                )
+                .await;
+            }
+            ClientRequest::HookSetEnabled { request_id, params } => {
+                let HookSetEnabledParams {
+                    thread_id,
+                    name,
+                    enabled,
+                } = params;
+                self.submit_hook_request(
+                    request_id,
+                    thread_id,
+                    HookRequestKind::SetEnabled,
+                    Op::SetHookEnabled { name, enabled },
+                )
+                .await;
+            }
+            ClientRequest::HooksSubscribe { request_id, params } => {
+                self.hooks_subscribe(request_id, params).await;
+            }
             ClientRequest::TurnStart { request_id, params } => {
                 self.turn_start(request_id, params).await;
             }
@@ -1609,6 +1696,133 @@ impl CodexMessageProcessor {
         self.outgoing.send_response(request_id, response).await;
     }
 
+    async fn thread_annotation_add(
+        &self,
+        request_id: RequestId,
+        params: ThreadAnnotationAddParams,
+    ) {
+        let ThreadAnnotationAddParams {
+            thread_id,
+            turn,
+            author,
+            body,
+        } = params;
+        let Some(rollout_path) = self.annotated_rollout_path(&request_id, &thread_id).await else {
+            return;
+        };
+        let body = match body {
+            TurnAnnotationBody::Comment { text } => AnnotationBody::Comment { text },
+            TurnAnnotationBody::Rating { score } => AnnotationBody::Rating { score },
+            TurnAnnotationBody::Label { label } => AnnotationBody::Label { label },
+        };
+        match add_annotation(&rollout_path, turn, author, body).await {
+            Ok(annotation) => {
+                let response = ThreadAnnotationAddResponse {
+                    annotation: api_turn_annotation(annotation),
+                };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err @ (AnnotationError::TurnNotFound { .. } | AnnotationError::Empty)) => {
+                self.send_invalid_request_error(request_id, err.to_string())
+                    .await;
+            }
+            Err(err) => {
+                self.send_internal_error(request_id, format!("failed to add annotation: {err}"))
+                    .await;
+            }
+        }
+    }
+
+    async fn thread_annotation_list(
+        &self,
+        request_id: RequestId,
+        params: ThreadAnnotationListParams,
+    ) {
+        let Some(rollout_path) = self
+            .annotated_rollout_path(&request_id, &params.thread_id)
+            .await
+        else {
+            return;
+        };
+        match read_annotations(&rollout_path).await {
+            Ok(annotations) => {
+                let response = ThreadAnnotationListResponse {
+                    data: annotations.into_iter().map(api_turn_annotation).collect(),
+                };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                self.send_internal_error(request_id, format!("failed to read annotations: {err}"))
+                    .await;
+            }
+        }
+    }
+
+    async fn thread_annotation_remove(
+        &self,
+        request_id: RequestId,
+        params: ThreadAnnotationRemoveParams,
+    ) {
+        let ThreadAnnotationRemoveParams {
+            thread_id,
+            annotation_id,
+        } = params;
+        let Some(rollout_path) = self.annotated_rollout_path(&request_id, &thread_id).await else {
+            return;
+        };
+        match remove_annotation(&rollout_path, &annotation_id).await {
+            Ok(()) => {
+                self.outgoing
+                    .send_response(request_id, ThreadAnnotationRemoveResponse {})
+                    .await;
+            }
+            Err(err @ AnnotationError::NotFound(_)) => {
+                self.send_invalid_request_error(request_id, err.to_string())
+                    .await;
+            }
+            Err(err) => {
+                self.send_internal_error(request_id, format!("failed to remove annotation: {err}"))
+                    .await;
+            }
+        }
+    }
+
+    /// Rollout of the stored thread `thread_id`. Replies to `request_id`
+    /// with an error and returns `None` if there is none.
+    async fn annotated_rollout_path(
+        &self,
+        request_id: &RequestId,
+        thread_id: &str,
+    ) -> Option<PathBuf> {
+        if let Err(err) = ThreadId::from_string(thread_id) {
+            self.send_invalid_request_error(
+                request_id.clone(),
+                format!("invalid thread id: {err}"),
+            )
+            .await;
+            return None;
+        }
+        match find_thread_path_by_id_str(&self.config.codex_home, thread_id).await {
+            Ok(Some(path)) => Some(path),
+            Ok(None) => {
+                self.send_invalid_request_error(
+                    request_id.clone(),
+                    format!("no rollout found for thread id {thread_id}"),
+                )
+                .await;
+                None
+            }
+            Err(err) => {
+                self.send_internal_error(
+                    request_id.clone(),
+                    format!("failed to locate thread id {thread_id}: {err}"),
+                )
+                .await;
+                None
+            }
+        }
+    }
+
     async fn thread_loaded_list(&self, request_id: RequestId, params: ThreadLoadedListParams) {
         let ThreadLoadedListParams { cursor, limit } = params;
         let mut data = self
@@ -2478,6 +2692,7 @@ impl CodexMessageProcessor {
                     .get(name)
                     .cloned()
                     .unwrap_or_default(),
+                prompts: snapshot.prompts.get(name).cloned().unwrap_or_default(),
                 auth_status: snapshot
                     .auth_statuses
                     .get(name)
@@ -3006,7 +3221,17 @@ impl CodexMessageProcessor {
                 .codex_home
                 .join(codex_core::ARCHIVED_SESSIONS_SUBDIR);
             tokio::fs::create_dir_all(&archive_folder).await?;
-            tokio::fs::rename(&canonical_rollout_path, &archive_folder.join(&file_name)).await?;
+            let archived_path = archive_folder.join(&file_name);
+            tokio::fs::rename(&canonical_rollout_path, &archived_path).await?;
+            for sidecar_path in [
+                codex_core::rollout_metadata::metadata_path,
+                codex_core::rollout_annotations::annotations_path,
+            ] {
+                let sidecar = sidecar_path(&canonical_rollout_path);
+                if sidecar.exists() {
+                    tokio::fs::rename(&sidecar, sidecar_path(&archived_path)).await?;
+                }
+            }
             Ok(())
         }
         .await;
@@ -3131,6 +3356,93 @@ impl CodexMessageProcessor {
             .await;
     }
 
+    async fn submit_hook_request(
+        &self,
+        request_id: RequestId,
+        thread_id: String,
+        kind: HookRequestKind,
+        op: Op,
+    ) {
+        let (_, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        // Register before submitting so the response cannot outrun us.
+        let sub_id = format!("hooks-{}", Uuid::new_v4());
+        self.pending_hook_requests
+            .lock()
+            .await
+            .insert(sub_id.clone(), (request_id.clone(), kind));
+        let submission = Submission {
+            id: sub_id.clone(),
+            op,
+        };
+        if let Err(err) = thread.submit_with_id(submission).await {
+            self.pending_hook_requests.lock().await.remove(&sub_id);
+            self.send_internal_error(request_id, format!("failed to query hooks: {err}"))
+                .await;
+        }
+    }
+
+    async fn thread_variables_set(&self, request_id: RequestId, params: ThreadVariablesSetParams) {
+        let ThreadVariablesSetParams {
+            thread_id,
+            variables,
+        } = params;
+        let (_, thread) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        // Register before submitting so the response cannot outrun us.
+        let sub_id = format!("variables-{}", Uuid::new_v4());
+        self.pending_variable_requests
+            .lock()
+            .await
+            .insert(sub_id.clone(), request_id.clone());
+        let submission = Submission {
+            id: sub_id.clone(),
+            op: Op::SetVariables {
+                variables: variables.into_iter().collect(),
+            },
+        };
+        if let Err(err) = thread.submit_with_id(submission).await {
+            self.pending_variable_requests.lock().await.remove(&sub_id);
+            self.send_internal_error(request_id, format!("failed to set variables: {err}"))
+                .await;
+        }
+    }
+
+    async fn hooks_subscribe(&self, request_id: RequestId, params: HooksSubscribeParams) {
+        let HooksSubscribeParams { thread_id, events } = params;
+        let (thread_id, _) = match self.load_thread(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        {
+            let mut subscriptions = self.hook_subscriptions.lock().await;
+            if events.is_empty() {
+                subscriptions.remove(&thread_id);
+            } else {
+                subscriptions.insert(thread_id, events);
+            }
+        }
+        self.outgoing
+            .send_response(request_id, HooksSubscribeResponse {})
+            .await;
+    }
+
     async fn interrupt_conversation(
         &mut self,
         request_id: RequestId,
@@ -3192,6 +3504,7 @@ impl CodexMessageProcessor {
                     model: params.model,
                     effort: params.effort.map(Some),
                     summary: params.summary,
+                    verbosity: None,
                 })
                 .await;
         }
@@ -3451,7 +3764,11 @@ impl CodexMessageProcessor {
     }
 
     async fn turn_interrupt(&mut self, request_id: RequestId, params: TurnInterruptParams) {
-        let TurnInterruptParams { thread_id, .. } = params;
+        let TurnInterruptParams {
+            thread_id,
+            keep_output,
+            ..
+        } = params;
 
         let (thread_uuid, thread) = match self.load_thread(&thread_id).await {
             Ok(v) => v,
@@ -3470,7 +3787,8 @@ impl CodexMessageProcessor {
         }
 
         // Submit the interrupt; we'll respond upon TurnAborted.
-        let _ = thread.submit(Op::Interrupt).await;
+        let op = if keep_output { Op::Stop } else { Op::Interrupt };
+        let _ = thread.submit(op).await;
     }
 
     async fn add_conversation_listener(
@@ -3545,6 +3863,9 @@ impl CodexMessageProcessor {
         let outgoing_for_task = self.outgoing.clone();
         let pending_interrupts = self.pending_interrupts.clone();
         let pending_rollbacks = self.pending_rollbacks.clone();
+        let pending_hook_requests = self.pending_hook_requests.clone();
+        let pending_variable_requests = self.pending_variable_requests.clone();
+        let hook_subscriptions = self.hook_subscriptions.clone();
         let turn_summary_store = self.turn_summary_store.clone();
         let api_version_for_task = api_version;
         let fallback_model_provider = self.config.model_provider_id.clone();
@@ -3601,6 +3922,14 @@ impl CodexMessageProcessor {
                             })
                             .await;
 
+                        forward_hook_subscription(
+                            &event,
+                            conversation_id,
+                            &hook_subscriptions,
+                            &outgoing_for_task,
+                        )
+                        .await;
+
                         apply_bespoke_event_handling(
                             event.clone(),
                             conversation_id,
@@ -3608,6 +3937,8 @@ impl CodexMessageProcessor {
                             outgoing_for_task.clone(),
                             pending_interrupts.clone(),
                             pending_rollbacks.clone(),
+                            pending_hook_requests.clone(),
+                            pending_variable_requests.clone(),
                             turn_summary_store.clone(),
                             api_version_for_task,
                             fallback_model_provider.clone(),
@@ -3777,6 +4108,47 @@ impl CodexMessageProcessor {
     }
 }
 
+/// Sends `event` as a `hook/triggered` notification if the thread's client
+/// subscribed to it with `hooks/subscribe`. Events are matched by their wire
+/// `type`, e.g. `task_complete`.
+async fn forward_hook_subscription(
+    event: &Event,
+    thread_id: ThreadId,
+    hook_subscriptions: &HookSubscriptions,
+    outgoing: &OutgoingMessageSender,
+) {
+    let Some(patterns) = hook_subscriptions.lock().await.get(&thread_id).cloned() else {
+        return;
+    };
+    let payload = match serde_json::to_value(&event.msg) {
+        Ok(payload) => payload,
+        Err(err) => {
+            error!("failed to serialize event: {err}");
+            return;
+        }
+    };
+    let event_name = payload
+        .get("type")
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    if !patterns
+        .iter()
+        .any(|pattern| pattern_matches(pattern, &event_name))
+    {
+        return;
+    }
+    let notification = HookTriggeredNotification {
+        thread_id: thread_id.to_string(),
+        turn_id: event.id.clone(),
+        event: event_name,
+        payload,
+    };
+    outgoing
+        .send_server_notification(ServerNotification::HookTriggered(notification))
+        .await;
+}
+
 fn skills_to_info(
     skills: &[codex_core::skills::SkillMetadata],
 ) -> Vec<codex_app_server_protocol::SkillMetadata> {
@@ -3964,6 +4336,28 @@ fn map_git_info(git_info: &CoreGitInfo) -> ConversationGitInfo {
     }
 }
 
+fn api_turn_annotation(annotation: CoreTurnAnnotation) -> TurnAnnotation {
+    let CoreTurnAnnotation {
+        id,
+        turn,
+        author,
+        created_at,
+        body,
+    } = annotation;
+    let body = match body {
+        AnnotationBody::Comment { text } => TurnAnnotationBody::Comment { text },
+        AnnotationBody::Rating { score } => TurnAnnotationBody::Rating { score },
+        AnnotationBody::Label { label } => TurnAnnotationBody::Label { label },
+    };
+    TurnAnnotation {
+        id,
+        turn,
+        author,
+        created_at,
+        body,
+    }
+}
+
 fn parse_datetime(timestamp: Option<&str>) -> Option<DateTime<Utc>> {
     timestamp.and_then(|ts| {
         chrono::DateTime::parse_from_rfc3339(ts)
@@ -84,6 +84,10 @@ fn map_requirements_toml_to_api(requirements: ConfigRequirementsToml) -> ConfigR
                 .filter_map(map_sandbox_mode_requirement_to_api)
                 .collect()
         }),
+        banned_tools: requirements.banned_tools,
+        required_hooks: requirements
+            .required_hooks
+            .map(|hooks| hooks.into_keys().collect()),
     }
 }
 
@@ -135,6 +139,8 @@ mod tests {
                 CoreSandboxModeRequirement::ReadOnly,
                 CoreSandboxModeRequirement::ExternalSandbox,
             ]),
+            banned_tools: Some(vec!["shell".to_string()]),
+            required_hooks: None,
         };
 
         let mapped = map_requirements_toml_to_api(requirements);
@@ -150,5 +156,6 @@ mod tests {
             mapped.allowed_sandbox_modes,
             Some(vec![SandboxMode::ReadOnly]),
         );
+        assert_eq!(mapped.banned_tools, Some(vec!["shell".to_string()]));
     }
 }
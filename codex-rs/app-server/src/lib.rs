@@ -127,6 +127,11 @@ pub async fn run_main(
         .with(otel_tracing_layer)
         .try_init();
 
+    codex_core::crash_report::install(&config, "codex-app-server");
+    codex_core::otel_init::start_prometheus_endpoint(&config).map_err(|e| {
+        std::io::Error::new(e.kind(), format!("error starting Prometheus endpoint: {e}"))
+    })?;
+
     // Task: process incoming messages.
     let processor_handle = tokio::spawn({
         let outgoing_message_sender = OutgoingMessageSender::new(outgoing_tx);
@@ -16,6 +16,7 @@ use codex_app_server_protocol::JSONRPCErrorError;
 use codex_app_server_protocol::JSONRPCNotification;
 use codex_app_server_protocol::JSONRPCRequest;
 use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::PROTOCOL_VERSION;
 use codex_app_server_protocol::RequestId;
 use codex_core::AuthManager;
 use codex_core::ThreadManager;
@@ -150,7 +151,10 @@ impl MessageProcessor {
                     }
 
                     let user_agent = get_codex_user_agent();
-                    let response = InitializeResponse { user_agent };
+                    let response = InitializeResponse {
+                        user_agent,
+                        protocol_version: PROTOCOL_VERSION,
+                    };
                     self.outgoing.send_response(request_id, response).await;
 
                     self.initialized = true;
@@ -24,6 +24,9 @@ use codex_app_server_protocol::FeedbackUploadParams;
 use codex_app_server_protocol::ForkConversationParams;
 use codex_app_server_protocol::GetAccountParams;
 use codex_app_server_protocol::GetAuthStatusParams;
+use codex_app_server_protocol::HookSetEnabledParams;
+use codex_app_server_protocol::HooksListParams;
+use codex_app_server_protocol::HooksSubscribeParams;
 use codex_app_server_protocol::InitializeParams;
 use codex_app_server_protocol::InterruptConversationParams;
 use codex_app_server_protocol::JSONRPCError;
@@ -43,6 +46,9 @@ use codex_app_server_protocol::SendUserMessageParams;
 use codex_app_server_protocol::SendUserTurnParams;
 use codex_app_server_protocol::ServerRequest;
 use codex_app_server_protocol::SetDefaultModelParams;
+use codex_app_server_protocol::ThreadAnnotationAddParams;
+use codex_app_server_protocol::ThreadAnnotationListParams;
+use codex_app_server_protocol::ThreadAnnotationRemoveParams;
 use codex_app_server_protocol::ThreadArchiveParams;
 use codex_app_server_protocol::ThreadForkParams;
 use codex_app_server_protocol::ThreadListParams;
@@ -387,6 +393,60 @@ impl McpProcess {
         self.send_request("thread/loaded/list", params).await
     }
 
+    /// Send a `thread/annotation/add` JSON-RPC request.
+    pub async fn send_thread_annotation_add_request(
+        &mut self,
+        params: ThreadAnnotationAddParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/annotation/add", params).await
+    }
+
+    /// Send a `thread/annotation/list` JSON-RPC request.
+    pub async fn send_thread_annotation_list_request(
+        &mut self,
+        params: ThreadAnnotationListParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/annotation/list", params).await
+    }
+
+    /// Send a `thread/annotation/remove` JSON-RPC request.
+    pub async fn send_thread_annotation_remove_request(
+        &mut self,
+        params: ThreadAnnotationRemoveParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/annotation/remove", params).await
+    }
+
+    /// Send a `hooks/list` JSON-RPC request.
+    pub async fn send_hooks_list_request(
+        &mut self,
+        params: HooksListParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("hooks/list", params).await
+    }
+
+    /// Send a `hooks/setEnabled` JSON-RPC request.
+    pub async fn send_hook_set_enabled_request(
+        &mut self,
+        params: HookSetEnabledParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("hooks/setEnabled", params).await
+    }
+
+    /// Send a `hooks/subscribe` JSON-RPC request.
+    pub async fn send_hooks_subscribe_request(
+        &mut self,
+        params: HooksSubscribeParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("hooks/subscribe", params).await
+    }
+
     /// Send a `model/list` JSON-RPC request.
     pub async fn send_list_models_request(
         &mut self,
@@ -0,0 +1,193 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_mock_responses_server_repeating_assistant;
+use app_test_support::to_response;
+use codex_app_server_protocol::Hook;
+use codex_app_server_protocol::HookSetEnabledParams;
+use codex_app_server_protocol::HookSetEnabledResponse;
+use codex_app_server_protocol::HookSource;
+use codex_app_server_protocol::HookTriggeredNotification;
+use codex_app_server_protocol::HooksListParams;
+use codex_app_server_protocol::HooksListResponse;
+use codex_app_server_protocol::HooksSubscribeParams;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadStartParams;
+use codex_app_server_protocol::ThreadStartResponse;
+use codex_app_server_protocol::TurnStartParams;
+use codex_app_server_protocol::UserInput as V2UserInput;
+use pretty_assertions::assert_eq;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn hooks_list_and_set_enabled() -> Result<()> {
+    let server = create_mock_responses_server_repeating_assistant("Done").await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+    let thread_id = start_thread(&mut mcp).await?;
+
+    let list_id = mcp
+        .send_hooks_list_request(HooksListParams {
+            thread_id: thread_id.clone(),
+        })
+        .await?;
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(list_id)),
+    )
+    .await??;
+    let HooksListResponse { data, .. } = to_response::<HooksListResponse>(resp)?;
+    let notify = Hook {
+        name: "notify".to_string(),
+        events: vec!["task_complete".to_string()],
+        source: HookSource::External,
+        enabled: true,
+        priority: 0,
+        blocking: false,
+    };
+    assert_eq!(
+        data.into_iter()
+            .find(|hook| hook.name == "notify")
+            .expect("hook from config.toml"),
+        notify
+    );
+
+    let set_id = mcp
+        .send_hook_set_enabled_request(HookSetEnabledParams {
+            thread_id: thread_id.clone(),
+            name: "notify".to_string(),
+            enabled: false,
+        })
+        .await?;
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(set_id)),
+    )
+    .await??;
+    let HookSetEnabledResponse { data } = to_response::<HookSetEnabledResponse>(resp)?;
+    assert_eq!(
+        data.into_iter().find(|hook| hook.name == "notify"),
+        Some(Hook {
+            enabled: false,
+            ..notify
+        })
+    );
+
+    let missing_id = mcp
+        .send_hook_set_enabled_request(HookSetEnabledParams {
+            thread_id,
+            name: "missing".to_string(),
+            enabled: false,
+        })
+        .await?;
+    let err: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(missing_id)),
+    )
+    .await??;
+    assert_eq!(err.error.message, "no hook named `missing`");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn hooks_subscribe_forwards_matching_events() -> Result<()> {
+    let server = create_mock_responses_server_repeating_assistant("Done").await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+    let thread_id = start_thread(&mut mcp).await?;
+
+    let subscribe_id = mcp
+        .send_hooks_subscribe_request(HooksSubscribeParams {
+            thread_id: thread_id.clone(),
+            events: vec!["task_complete".to_string()],
+        })
+        .await?;
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(subscribe_id)),
+    )
+    .await??;
+
+    let turn_id = mcp
+        .send_turn_start_request(TurnStartParams {
+            thread_id: thread_id.clone(),
+            input: vec![V2UserInput::Text {
+                text: "Hello".to_string(),
+            }],
+            ..Default::default()
+        })
+        .await?;
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(turn_id)),
+    )
+    .await??;
+
+    let notification = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("hook/triggered"),
+    )
+    .await??;
+    let triggered: HookTriggeredNotification =
+        serde_json::from_value(notification.params.expect("params"))?;
+    assert_eq!(triggered.thread_id, thread_id);
+    assert_eq!(triggered.event, "task_complete");
+    assert_eq!(triggered.payload["last_agent_message"], "Done");
+
+    Ok(())
+}
+
+fn create_config_toml(codex_home: &Path, server_uri: &str) -> std::io::Result<()> {
+    let config_toml = codex_home.join("config.toml");
+    std::fs::write(
+        config_toml,
+        format!(
+            r#"
+model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+
+model_provider = "mock_provider"
+
+[model_providers.mock_provider]
+name = "Mock provider for test"
+base_url = "{server_uri}/v1"
+wire_api = "responses"
+request_max_retries = 0
+stream_max_retries = 0
+
+[hooks.commands.notify]
+on = ["task_complete"]
+command = ["true"]
+"#
+        ),
+    )
+}
+
+async fn start_thread(mcp: &mut McpProcess) -> Result<String> {
+    let req_id = mcp
+        .send_thread_start_request(ThreadStartParams {
+            model: Some("gpt-5.1".to_string()),
+            ..Default::default()
+        })
+        .await?;
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(req_id)),
+    )
+    .await??;
+    let ThreadStartResponse { thread, .. } = to_response::<ThreadStartResponse>(resp)?;
+    Ok(thread.id)
+}
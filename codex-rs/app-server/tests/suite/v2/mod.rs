@@ -1,10 +1,12 @@
 mod account;
 mod config_rpc;
+mod hooks;
 mod initialize;
 mod model_list;
 mod output_schema;
 mod rate_limits;
 mod review;
+mod thread_annotations;
 mod thread_archive;
 mod thread_fork;
 mod thread_list;
@@ -0,0 +1,112 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_fake_rollout;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadAnnotationAddParams;
+use codex_app_server_protocol::ThreadAnnotationAddResponse;
+use codex_app_server_protocol::ThreadAnnotationListParams;
+use codex_app_server_protocol::ThreadAnnotationListResponse;
+use codex_app_server_protocol::ThreadAnnotationRemoveParams;
+use codex_app_server_protocol::ThreadAnnotationRemoveResponse;
+use codex_app_server_protocol::TurnAnnotationBody;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+async fn list_annotations(
+    mcp: &mut McpProcess,
+    thread_id: &str,
+) -> Result<ThreadAnnotationListResponse> {
+    let request_id = mcp
+        .send_thread_annotation_list_request(ThreadAnnotationListParams {
+            thread_id: thread_id.to_string(),
+        })
+        .await?;
+    let resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    to_response::<ThreadAnnotationListResponse>(resp)
+}
+
+#[tokio::test]
+async fn annotations_are_added_listed_and_removed() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let thread_id = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-05T12-00-00",
+        "2025-01-05T12:00:00Z",
+        "Fix the login test",
+        Some("mock_provider"),
+        None,
+    )?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let add_id = mcp
+        .send_thread_annotation_add_request(ThreadAnnotationAddParams {
+            thread_id: thread_id.clone(),
+            turn: 1,
+            author: Some("eval-bot".to_string()),
+            body: TurnAnnotationBody::Rating { score: -1 },
+        })
+        .await?;
+    let add_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(add_id)),
+    )
+    .await??;
+    let ThreadAnnotationAddResponse { annotation } =
+        to_response::<ThreadAnnotationAddResponse>(add_resp)?;
+    assert_eq!(annotation.turn, 1);
+    assert_eq!(annotation.author.as_deref(), Some("eval-bot"));
+    assert_eq!(annotation.body, TurnAnnotationBody::Rating { score: -1 });
+
+    // The rollout has a single turn.
+    let missing_turn_id = mcp
+        .send_thread_annotation_add_request(ThreadAnnotationAddParams {
+            thread_id: thread_id.clone(),
+            turn: 2,
+            author: None,
+            body: TurnAnnotationBody::Label {
+                label: "regression".to_string(),
+            },
+        })
+        .await?;
+    let error: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(missing_turn_id)),
+    )
+    .await??;
+    assert!(error.error.message.contains("turn 2 does not exist"));
+
+    let listed = list_annotations(&mut mcp, &thread_id).await?;
+    assert_eq!(listed.data, vec![annotation.clone()]);
+
+    let remove_id = mcp
+        .send_thread_annotation_remove_request(ThreadAnnotationRemoveParams {
+            thread_id: thread_id.clone(),
+            annotation_id: annotation.id,
+        })
+        .await?;
+    let remove_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(remove_id)),
+    )
+    .await??;
+    let _: ThreadAnnotationRemoveResponse =
+        to_response::<ThreadAnnotationRemoveResponse>(remove_resp)?;
+    assert_eq!(
+        list_annotations(&mut mcp, &thread_id).await?.data,
+        Vec::new()
+    );
+
+    Ok(())
+}
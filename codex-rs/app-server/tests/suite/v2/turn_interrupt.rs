@@ -94,6 +94,7 @@ async fn turn_interrupt_aborts_running_turn() -> Result<()> {
         .send_turn_interrupt_request(TurnInterruptParams {
             thread_id: thread_id.clone(),
             turn_id: turn.id,
+            keep_output: false,
         })
         .await?;
     let interrupt_resp: JSONRPCResponse = timeout(
@@ -0,0 +1,93 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use codex_common::CliConfigOverrides;
+use codex_core::approval_rules::ApprovalRules;
+use codex_core::approval_rules::Verdict;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+
+/// Inspect the approval rules.
+#[derive(Debug, clap::Parser)]
+pub struct ApprovalCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: ApprovalSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ApprovalSubcommand {
+    /// Explain whether the approval rules allow, deny or ask about an action.
+    Explain(ExplainArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ExplainArgs {
+    #[command(subcommand)]
+    pub action: ExplainAction,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ExplainAction {
+    /// A command the agent runs, e.g. `codex approval explain exec cargo test`.
+    Exec {
+        #[arg(
+            value_name = "COMMAND",
+            required = true,
+            trailing_var_arg = true,
+            allow_hyphen_values = true
+        )]
+        command: Vec<String>,
+    },
+
+    /// A file the agent writes, relative to the working directory.
+    Write {
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+    },
+}
+
+impl ApprovalCli {
+    pub async fn run(self) -> Result<()> {
+        let cli_kv_overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides_and_harness_overrides(
+            cli_kv_overrides,
+            ConfigOverrides::default(),
+        )
+        .await?;
+        let rules = ApprovalRules::load(&config.config_layer_stack, &config.approval_rules).await?;
+        let ApprovalSubcommand::Explain(args) = self.subcommand;
+        let verdict = match &args.action {
+            ExplainAction::Exec { command } => {
+                // Judge the command the way the agent runs it: as a script.
+                let script = vec!["bash".to_string(), "-lc".to_string(), command.join(" ")];
+                rules.evaluate_exec(&script)
+            }
+            ExplainAction::Write { path } => rules.evaluate_write([Path::new(path)], &config.cwd),
+        };
+        print_line(&render(&verdict, &config));
+        Ok(())
+    }
+}
+
+fn render(verdict: &Verdict, config: &Config) -> String {
+    let mut text = verdict.explain();
+    if verdict.decision().is_none() {
+        text.push_str(&format!(
+            "\napproval policy: {}",
+            config.approval_policy.value()
+        ));
+    }
+    text
+}
+
+#[allow(clippy::print_stdout)]
+fn print_line(line: &str) {
+    println!("{line}");
+}
@@ -0,0 +1,414 @@
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::Result;
+use codex_app_server_protocol::ConfigLayerSource;
+use codex_common::CliConfigOverrides;
+use codex_core::config::CONFIG_TOML_FILE;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::config::find_codex_home;
+use codex_core::config::schema::ConfigDiagnostic;
+use codex_core::config::schema::DiagnosticSeverity;
+use codex_core::config::schema::config_schema;
+use codex_core::config::schema::validate_config_file;
+use codex_core::config::types::McpServerTransportConfig;
+use codex_core::config_loader::CONFIG_ENV_PREFIX;
+use codex_core::config_loader::ConfigLayerEntry;
+use codex_core::config_loader::ConfigLayerStack;
+use codex_core::config_loader::ConfigLayerStackOrdering;
+use codex_core::config_loader::LoaderOverrides;
+use codex_core::config_loader::load_config_layers_state;
+use codex_utils_absolute_path::AbsolutePathBuf;
+use tokio::net::TcpStream;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Inspect and validate `config.toml`.
+#[derive(Debug, clap::Parser)]
+pub struct ConfigCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: ConfigSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ConfigSubcommand {
+    /// Print the effective config after merging every layer.
+    Show(ShowArgs),
+
+    /// Print the JSON Schema for `config.toml`.
+    Schema,
+
+    /// Check the config files for errors, unknown keys, and deprecated
+    /// options, and check that the configured MCP servers can be reached.
+    Doctor(DoctorArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ShowArgs {
+    /// Print each value on its own line with the layer it comes from.
+    #[arg(long)]
+    pub origin: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct DoctorArgs {
+    /// Do not check whether MCP servers can be reached.
+    #[arg(long)]
+    pub skip_mcp: bool,
+}
+
+impl ConfigCli {
+    /// Returns `false` when `doctor` found problems.
+    pub async fn run(self) -> Result<bool> {
+        let cli_overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        match self.subcommand {
+            ConfigSubcommand::Show(args) => {
+                run_show(cli_overrides, args).await?;
+                Ok(true)
+            }
+            ConfigSubcommand::Schema => {
+                print_line(&serde_json::to_string_pretty(config_schema())?);
+                Ok(true)
+            }
+            ConfigSubcommand::Doctor(args) => run_doctor(cli_overrides, args).await,
+        }
+    }
+}
+
+async fn run_show(cli_overrides: Vec<(String, toml::Value)>, args: ShowArgs) -> Result<()> {
+    let codex_home = find_codex_home()?;
+    let cwd = AbsolutePathBuf::current_dir()?;
+    let stack = load_config_layers_state(
+        &codex_home,
+        Some(cwd),
+        &cli_overrides,
+        LoaderOverrides::default(),
+    )
+    .await?;
+    if args.origin {
+        for line in effective_values_with_origins(&stack) {
+            print_line(&line);
+        }
+    } else {
+        print_line(toml::to_string_pretty(&stack.effective_config())?.trim_end());
+    }
+    Ok(())
+}
+
+/// Renders every effective value as `key = value  # origin`, where the origin
+/// is the highest-precedence layer that sets the key. Arrays are replaced
+/// rather than merged across layers, so they are shown as a single value.
+fn effective_values_with_origins(stack: &ConfigLayerStack) -> Vec<String> {
+    let mut leaves = Vec::new();
+    collect_leaves(&stack.effective_config(), &mut Vec::new(), &mut leaves);
+    let layers = stack.get_layers(ConfigLayerStackOrdering::HighestPrecedenceFirst);
+    leaves
+        .into_iter()
+        .map(|(path, value)| {
+            let key = path
+                .iter()
+                .map(|segment| format_key_segment(segment))
+                .collect::<Vec<_>>()
+                .join(".");
+            let origin = layers
+                .iter()
+                .find(|layer| lookup(&layer.config, &path).is_some())
+                .map_or_else(
+                    || "unknown".to_string(),
+                    |layer| describe_layer(&layer.name, &path),
+                );
+            format!("{key} = {value}  # {origin}")
+        })
+        .collect()
+}
+
+fn collect_leaves(
+    value: &toml::Value,
+    path: &mut Vec<String>,
+    leaves: &mut Vec<(Vec<String>, toml::Value)>,
+) {
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                path.push(key.clone());
+                collect_leaves(value, path, leaves);
+                path.pop();
+            }
+        }
+        value => leaves.push((path.clone(), value.clone())),
+    }
+}
+
+fn lookup<'v>(value: &'v toml::Value, path: &[String]) -> Option<&'v toml::Value> {
+    path.iter()
+        .try_fold(value, |value, segment| value.as_table()?.get(segment))
+}
+
+fn format_key_segment(segment: &str) -> String {
+    let bare = !segment.is_empty()
+        && segment
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare {
+        segment.to_string()
+    } else {
+        toml::Value::String(segment.to_string()).to_string()
+    }
+}
+
+fn describe_layer(source: &ConfigLayerSource, path: &[String]) -> String {
+    match source {
+        ConfigLayerSource::Mdm { domain, key } => format!("managed preferences ({domain}:{key})"),
+        ConfigLayerSource::System { file } => format!("system ({})", file.display()),
+        ConfigLayerSource::User { file } => format!("user ({})", file.display()),
+        ConfigLayerSource::Project { dot_codex_folder } => format!(
+            "project ({})",
+            dot_codex_folder.as_path().join(CONFIG_TOML_FILE).display()
+        ),
+        ConfigLayerSource::Environment => format!(
+            "env ({CONFIG_ENV_PREFIX}{})",
+            path.iter()
+                .map(|segment| segment.to_ascii_uppercase())
+                .collect::<Vec<_>>()
+                .join("__")
+        ),
+        ConfigLayerSource::SessionFlags => "-c flag".to_string(),
+        ConfigLayerSource::LegacyManagedConfigTomlFromFile { file } => {
+            format!("managed config ({})", file.display())
+        }
+        ConfigLayerSource::LegacyManagedConfigTomlFromMdm => "managed config (MDM)".to_string(),
+    }
+}
+
+async fn run_doctor(cli_overrides: Vec<(String, toml::Value)>, args: DoctorArgs) -> Result<bool> {
+    let codex_home = find_codex_home()?;
+    let cwd = AbsolutePathBuf::current_dir()?;
+    let mut healthy = true;
+
+    // A file that is not valid TOML stops the loader, so fall back to checking
+    // the user config directly.
+    let files: Vec<PathBuf> = match load_config_layers_state(
+        &codex_home,
+        Some(cwd),
+        &cli_overrides,
+        LoaderOverrides::default(),
+    )
+    .await
+    {
+        Ok(stack) => stack
+            .get_layers(ConfigLayerStackOrdering::LowestPrecedenceFirst)
+            .into_iter()
+            .filter_map(ConfigLayerEntry::config_file)
+            .filter(|file| file.exists())
+            .collect(),
+        Err(_) => vec![codex_home.join(CONFIG_TOML_FILE)],
+    };
+    if files.is_empty() {
+        print_line("No config files found.");
+    }
+    for file in &files {
+        match validate_config_file(file) {
+            Ok(diagnostics) => healthy &= report_file(file, &diagnostics),
+            Err(err) => {
+                print_line(&format!("{}: error: cannot read: {err}", file.display()));
+                healthy = false;
+            }
+        }
+    }
+
+    if args.skip_mcp {
+        return Ok(healthy);
+    }
+    let config = match Config::load_with_cli_overrides_and_harness_overrides(
+        cli_overrides,
+        ConfigOverrides::default(),
+    )
+    .await
+    {
+        Ok(config) => config,
+        Err(err) => {
+            print_line(&format!(
+                "MCP servers not checked; the config failed to load: {err}"
+            ));
+            return Ok(false);
+        }
+    };
+    let mut servers: Vec<_> = config
+        .mcp_servers
+        .iter()
+        .filter(|(_, server)| server.enabled)
+        .collect();
+    servers.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (name, server) in servers {
+        match probe_mcp_server(&server.transport, &config.cwd).await {
+            Ok(detail) => print_line(&format!("mcp_servers.{name}: ok ({detail})")),
+            Err(reason) => {
+                print_line(&format!("mcp_servers.{name}: unreachable: {reason}"));
+                healthy = false;
+            }
+        }
+    }
+    Ok(healthy)
+}
+
+/// Prints the diagnostics for `file`; returns `false` if any is an error.
+fn report_file(file: &Path, diagnostics: &[ConfigDiagnostic]) -> bool {
+    if diagnostics.is_empty() {
+        print_line(&format!("{}: ok", file.display()));
+        return true;
+    }
+    for diagnostic in diagnostics {
+        print_line(&diagnostic.to_string());
+    }
+    !diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity() == DiagnosticSeverity::Error)
+}
+
+/// Checks that a stdio server's command exists, or that an HTTP server
+/// accepts connections. Returns a short description of what was found.
+async fn probe_mcp_server(
+    transport: &McpServerTransportConfig,
+    cwd: &Path,
+) -> Result<String, String> {
+    match transport {
+        McpServerTransportConfig::Stdio {
+            command,
+            cwd: server_cwd,
+            ..
+        } => {
+            let command_path = Path::new(command);
+            if command_path.components().count() > 1 {
+                let base = server_cwd.as_deref().unwrap_or(cwd);
+                let resolved = base.join(command_path);
+                if resolved.is_file() {
+                    Ok(format!("stdio: {}", resolved.display()))
+                } else {
+                    Err(format!("command `{}` does not exist", resolved.display()))
+                }
+            } else {
+                which::which(command)
+                    .map(|path| format!("stdio: {}", path.display()))
+                    .map_err(|_| format!("command `{command}` was not found on PATH"))
+            }
+        }
+        McpServerTransportConfig::StreamableHttp { url, .. } => {
+            check_reachable(url).await?;
+            Ok(format!("http: {url}"))
+        }
+    }
+}
+
+/// Checks that the host of `url` accepts TCP connections on its port.
+pub(crate) async fn check_reachable(url: &str) -> Result<(), String> {
+    let parsed = url::Url::parse(url).map_err(|err| format!("invalid url `{url}`: {err}"))?;
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| format!("url `{url}` has no host"))?;
+    let port = parsed
+        .port_or_known_default()
+        .ok_or_else(|| format!("url `{url}` has no port"))?;
+    match tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect((host, port))).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(err)) => Err(format!("cannot connect to {host}:{port}: {err}")),
+        Err(_) => Err(format!(
+            "timed out connecting to {host}:{port} after {}s",
+            CONNECT_TIMEOUT.as_secs()
+        )),
+    }
+}
+
+#[allow(clippy::print_stdout)]
+fn print_line(line: &str) {
+    println!("{line}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::config_loader::ConfigRequirements;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn show_origin_reports_the_highest_layer_setting_each_key() {
+        let user_file = AbsolutePathBuf::from_absolute_path("/home/me/.codex/config.toml")
+            .expect("absolute path");
+        let layer = |name, toml: &str| {
+            ConfigLayerEntry::new(name, toml::from_str(toml).expect("valid toml"))
+        };
+        let stack = ConfigLayerStack::new(
+            vec![
+                layer(
+                    ConfigLayerSource::User { file: user_file },
+                    r#"
+model = "gpt-5"
+notify = ["say", "done"]
+
+[projects."/home/me/work"]
+trust_level = "trusted"
+"#,
+                ),
+                layer(ConfigLayerSource::Environment, r#"model = "o3""#),
+                layer(ConfigLayerSource::SessionFlags, "notify = []"),
+            ],
+            ConfigRequirements::default(),
+            Default::default(),
+        )
+        .expect("stack");
+
+        assert_eq!(
+            effective_values_with_origins(&stack),
+            vec![
+                r#"model = "o3"  # env (CODEX_CONFIG_MODEL)"#.to_string(),
+                "notify = []  # -c flag".to_string(),
+                r#"projects."/home/me/work".trust_level = "trusted"  # user (/home/me/.codex/config.toml)"#
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn probe_reports_missing_stdio_command() {
+        let transport = McpServerTransportConfig::Stdio {
+            command: "codex-doctor-test-missing-command".to_string(),
+            args: Vec::new(),
+            env: None,
+            env_vars: Vec::new(),
+            cwd: None,
+        };
+        assert_eq!(
+            probe_mcp_server(&transport, Path::new("/")).await,
+            Err("command `codex-doctor-test-missing-command` was not found on PATH".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn probe_connects_to_http_servers() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind listener");
+        let port = listener.local_addr().expect("local addr").port();
+        let url = format!("http://127.0.0.1:{port}/mcp");
+        let transport = McpServerTransportConfig::StreamableHttp {
+            url: url.clone(),
+            bearer_token_env_var: None,
+            http_headers: None,
+            env_http_headers: None,
+        };
+        assert_eq!(
+            probe_mcp_server(&transport, Path::new("/")).await,
+            Ok(format!("http: {url}"))
+        );
+
+        drop(listener);
+        assert!(probe_mcp_server(&transport, Path::new("/")).await.is_err());
+    }
+}
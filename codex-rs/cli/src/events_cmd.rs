@@ -0,0 +1,203 @@
+use std::path::Path;
+
+use anyhow::Result;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::event_socket::SocketEvent;
+use codex_core::find_thread_path_by_id_str;
+use codex_core::hooks::EventExpr;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+
+/// Longest event payload printed in the human format, in characters.
+const MAX_DETAIL_CHARS: usize = 160;
+
+/// Watch the events of a session.
+#[derive(Debug, clap::Parser)]
+pub struct EventsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: EventsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum EventsSubcommand {
+    /// Print a running session's events as they happen, or replay the events
+    /// of a finished session.
+    Tail(TailArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct TailArgs {
+    /// Id of the session. Defaults to the most recently started running
+    /// session.
+    #[arg(long, value_name = "SESSION_ID")]
+    pub session: Option<String>,
+
+    /// Only print events matching this expression, e.g.
+    /// `event == "exec_command_end" && data.exit_code != 0`.
+    #[arg(long, value_name = "EXPR")]
+    pub filter: Option<EventExpr>,
+
+    /// Print each event as a JSON line.
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl EventsCli {
+    pub async fn run(self) -> Result<()> {
+        let cli_kv_overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides_and_harness_overrides(
+            cli_kv_overrides,
+            ConfigOverrides::default(),
+        )
+        .await?;
+        match self.subcommand {
+            EventsSubcommand::Tail(args) => run_tail(&config, args).await,
+        }
+    }
+}
+
+async fn run_tail(config: &Config, args: TailArgs) -> Result<()> {
+    if tail_live(&config.codex_home, &args).await? {
+        return Ok(());
+    }
+    let Some(session) = args.session.as_deref() else {
+        anyhow::bail!("no running session found; pass --session to replay a finished session");
+    };
+    let path = find_thread_path_by_id_str(&config.codex_home, session)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("no session found with id {session}"))?;
+    let contents = std::fs::read_to_string(&path)?;
+    for line in contents.lines() {
+        let Ok(RolloutLine {
+            timestamp,
+            item: RolloutItem::EventMsg(msg),
+        }) = serde_json::from_str::<RolloutLine>(line)
+        else {
+            continue;
+        };
+        let event = SocketEvent {
+            timestamp,
+            name: msg.to_string(),
+            event: Event {
+                id: String::new(),
+                msg,
+            },
+        };
+        print_event(&event, &args);
+    }
+    Ok(())
+}
+
+/// Streams events from a running session's socket until the session ends.
+/// Returns `false` if no running session could be reached.
+#[cfg(unix)]
+async fn tail_live(codex_home: &Path, args: &TailArgs) -> Result<bool> {
+    use tokio::io::AsyncBufReadExt;
+    use tokio::io::BufReader;
+    use tokio::net::UnixStream;
+
+    let candidates = match &args.session {
+        Some(session) => vec![codex_core::event_socket::event_socket_path(
+            codex_home, session,
+        )],
+        None => running_sockets(codex_home),
+    };
+    for path in candidates {
+        // A session that crashed leaves a socket nobody accepts on.
+        let Ok(stream) = UnixStream::connect(&path).await else {
+            continue;
+        };
+        let mut lines = BufReader::new(stream).lines();
+        while let Some(line) = lines.next_line().await? {
+            if let Ok(event) = serde_json::from_str::<SocketEvent>(&line) {
+                print_event(&event, args);
+            }
+        }
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+#[cfg(not(unix))]
+async fn tail_live(_codex_home: &Path, _args: &TailArgs) -> Result<bool> {
+    Ok(false)
+}
+
+/// Sockets in the run directory, most recently created first.
+#[cfg(unix)]
+fn running_sockets(codex_home: &Path) -> Vec<std::path::PathBuf> {
+    let dir = codex_home.join(codex_core::event_socket::EVENT_SOCKET_DIR);
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut sockets: Vec<_> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "sock"))
+        .map(|path| {
+            let modified = std::fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
+            (modified, path)
+        })
+        .collect();
+    sockets.sort_by(|a, b| b.0.cmp(&a.0));
+    sockets.into_iter().map(|(_, path)| path).collect()
+}
+
+fn print_event(event: &SocketEvent, args: &TailArgs) {
+    if let Some(filter) = &args.filter
+        && !filter.matches(&event.name, &event.event)
+    {
+        return;
+    }
+    if args.json {
+        if let Ok(line) = serde_json::to_string(event) {
+            print_line(&line);
+        }
+        return;
+    }
+    print_line(&format_event(event));
+}
+
+/// `HH:MM:SS name payload`, with the payload trimmed to one short line.
+fn format_event(event: &SocketEvent) -> String {
+    let time = event
+        .timestamp
+        .split_once('T')
+        .and_then(|(_, time)| time.get(..8))
+        .unwrap_or(&event.timestamp);
+    let mut details = match serde_json::to_value(&event.event.msg) {
+        Ok(serde_json::Value::Object(mut fields)) => {
+            fields.remove("type");
+            fields.retain(|_, value| !value.is_null());
+            if fields.is_empty() {
+                String::new()
+            } else {
+                serde_json::Value::Object(fields).to_string()
+            }
+        }
+        _ => String::new(),
+    };
+    if details.chars().count() > MAX_DETAIL_CHARS {
+        details = details.chars().take(MAX_DETAIL_CHARS).collect();
+        details.push('…');
+    }
+    format!("{time} {} {details}", event.name)
+        .trim_end()
+        .to_string()
+}
+
+#[allow(clippy::print_stdout)]
+fn print_line(line: &str) {
+    println!("{line}");
+}
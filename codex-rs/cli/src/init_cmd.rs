@@ -0,0 +1,684 @@
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use codex_cli::login::login_with_chatgpt;
+use codex_common::CliConfigOverrides;
+use codex_common::oss::get_default_model_for_oss_provider;
+use codex_core::LMSTUDIO_OSS_PROVIDER_ID;
+use codex_core::OLLAMA_OSS_PROVIDER_ID;
+use codex_core::auth::AuthCredentialsStoreMode;
+use codex_core::auth::login_with_api_key;
+use codex_core::built_in_model_providers;
+use codex_core::config::CONFIG_TOML_FILE;
+use codex_core::config::edit::ConfigEditsBuilder;
+use codex_core::config::find_codex_home;
+use codex_core::config::schema::DiagnosticSeverity;
+use codex_core::config::schema::validate_config_file;
+use codex_core::git_info::get_git_repo_root;
+use toml::Value as TomlValue;
+
+use crate::config_cmd::check_reachable;
+
+const OPENAI_PROVIDER_ID: &str = "openai";
+
+/// Copy of the previous config.toml, written before it is updated.
+const CONFIG_BACKUP_FILE: &str = "config.toml.bak";
+
+const CONFIG_HEADER: &str = "# Written by `codex init`; see docs/config.md for every option.\n";
+
+const PROVIDERS: &[(&str, &str)] = &[
+    (
+        OPENAI_PROVIDER_ID,
+        "OpenAI (sign in with ChatGPT or use an API key)",
+    ),
+    (OLLAMA_OSS_PROVIDER_ID, "Ollama, running on this machine"),
+    (
+        LMSTUDIO_OSS_PROVIDER_ID,
+        "LM Studio, running on this machine",
+    ),
+];
+
+const SANDBOX_MODES: &[(&str, &str)] = &[
+    ("read-only", "read-only: read files, change nothing"),
+    (
+        "workspace-write",
+        "workspace-write: edit files in the project, no network",
+    ),
+    (
+        "danger-full-access",
+        "danger-full-access: no sandbox at all",
+    ),
+];
+
+const APPROVAL_POLICIES: &[(&str, &str)] = &[
+    ("untrusted", "untrusted: ask before anything but safe reads"),
+    (
+        "on-failure",
+        "on-failure: ask when a sandboxed command fails",
+    ),
+    ("on-request", "on-request: the model asks when it needs to"),
+    ("never", "never: never ask"),
+];
+
+const PROJECT_CONFIG_TEMPLATE: &str = r#"# Project settings for Codex. They apply when Codex runs in this repository
+# and override ~/.codex/config.toml; see docs/config.md for every option.
+
+# model = "gpt-5.1-codex"
+# approval_policy = "on-request"
+# sandbox_mode = "workspace-write"
+
+# [commands.test]
+# prompt = "Run the test suite and fix what fails in $ARGUMENTS."
+# description = "Run and fix the tests"
+"#;
+
+const AGENTS_MD_TEMPLATE: &str = r#"# AGENTS.md
+
+Guidance for coding agents working in this repository.
+
+## Project overview
+
+<!-- What the project does and how the code is organized. -->
+
+## Build and test
+
+<!-- The commands to build, lint and test, e.g. `make test`. -->
+
+## Conventions
+
+<!-- Code style, naming, and anything a reviewer would ask for. -->
+"#;
+
+/// Set up Codex: write config.toml, sign in, and scaffold the project.
+#[derive(Debug, clap::Parser)]
+pub struct InitCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Accept the default answer to every question.
+    #[arg(long, short = 'y')]
+    pub yes: bool,
+
+    /// Update an existing config.toml without asking.
+    #[arg(long)]
+    pub force: bool,
+
+    /// Do not check that the chosen model provider can be reached.
+    #[arg(long)]
+    pub skip_check: bool,
+}
+
+/// The answers that shape config.toml.
+#[derive(Debug, Clone, PartialEq)]
+struct Setup {
+    provider: &'static str,
+    model: Option<String>,
+    sandbox_mode: &'static str,
+    approval_policy: &'static str,
+    /// Command run by a hook when a turn completes.
+    turn_complete_hook: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Login {
+    ChatGpt,
+    ApiKey,
+    Later,
+}
+
+impl InitCli {
+    pub async fn run(self) -> Result<()> {
+        let codex_home = find_codex_home()?;
+        let cwd = std::env::current_dir()?;
+        let mut prompter = Prompter {
+            input: BufReader::new(io::stdin()),
+            output: io::stdout(),
+            assume_defaults: self.yes,
+        };
+
+        let config_path = codex_home.join(CONFIG_TOML_FILE);
+        let update_config = self.force
+            || !config_path.exists()
+            || prompter.confirm(
+                &format!(
+                    "{} already exists. Update it with your answers? Its other settings are kept.",
+                    config_path.display()
+                ),
+                false,
+            )?;
+        if update_config {
+            let setup = ask_setup(&mut prompter)?;
+            let values = config_values(&setup, &self.config_overrides)?;
+            if let Some(backup) = write_config(&codex_home, &values)? {
+                prompter.say(&format!(
+                    "Saved the previous config to {}",
+                    backup.display()
+                ))?;
+            }
+            prompter.say(&format!("Wrote {}", config_path.display()))?;
+            report_diagnostics(&mut prompter, &config_path)?;
+
+            let login = if setup.provider == OPENAI_PROVIDER_ID {
+                ask_login(&mut prompter)?
+            } else {
+                Login::Later
+            };
+            sign_in(&mut prompter, &codex_home, login).await?;
+            if !self.skip_check {
+                check_provider(&mut prompter, setup.provider, login).await?;
+            }
+        }
+
+        let project_root = get_git_repo_root(&cwd).unwrap_or(cwd);
+        if prompter.confirm(
+            &format!(
+                "Add a .codex/ folder and an AGENTS.md to {}?",
+                project_root.display()
+            ),
+            true,
+        )? {
+            for (path, created) in scaffold_project(&project_root)? {
+                let verb = if created { "Created" } else { "Kept existing" };
+                prompter.say(&format!("{verb} {}", path.display()))?;
+            }
+        }
+        prompter.say("All set. Run `codex` to start a session.")?;
+        Ok(())
+    }
+}
+
+fn ask_setup<R: BufRead, W: Write>(prompter: &mut Prompter<R, W>) -> io::Result<Setup> {
+    let provider = prompter.choose("Which model provider do you want to use?", PROVIDERS, 0)?;
+    let default_model = get_default_model_for_oss_provider(provider);
+    let model = prompter.ask(
+        "Model (leave empty for the provider's default)",
+        default_model,
+    )?;
+    let sandbox_mode = prompter.choose("What may commands run by Codex do?", SANDBOX_MODES, 1)?;
+    let approval_policy = prompter.choose(
+        "When should Codex ask for your approval?",
+        APPROVAL_POLICIES,
+        2,
+    )?;
+    let turn_complete_hook = if prompter.confirm(
+        "Run a command of yours (e.g. a notification) whenever Codex finishes a turn?",
+        false,
+    )? {
+        prompter
+            .ask(
+                "Command to run, with its arguments separated by spaces",
+                None,
+            )?
+            .map(|command| command.split_whitespace().map(str::to_string).collect())
+            .filter(|argv: &Vec<String>| !argv.is_empty())
+    } else {
+        None
+    };
+    Ok(Setup {
+        provider,
+        model,
+        sandbox_mode,
+        approval_policy,
+        turn_complete_hook,
+    })
+}
+
+/// The settings chosen in `setup`, with the `-c` overrides applied on top.
+fn config_values(setup: &Setup, overrides: &CliConfigOverrides) -> Result<TomlValue> {
+    let mut config = toml::Table::new();
+    if setup.provider != OPENAI_PROVIDER_ID {
+        config.insert("model_provider".to_string(), setup.provider.into());
+    }
+    if let Some(model) = &setup.model {
+        config.insert("model".to_string(), model.as_str().into());
+    }
+    config.insert("sandbox_mode".to_string(), setup.sandbox_mode.into());
+    config.insert("approval_policy".to_string(), setup.approval_policy.into());
+    if let Some(command) = &setup.turn_complete_hook {
+        let mut hook = toml::Table::new();
+        hook.insert("on".to_string(), vec!["task_complete"].into());
+        hook.insert("command".to_string(), command.clone().into());
+        let mut commands = toml::Table::new();
+        commands.insert("on-turn-complete".to_string(), hook.into());
+        let mut hooks = toml::Table::new();
+        hooks.insert("commands".to_string(), commands.into());
+        config.insert("hooks".to_string(), hooks.into());
+    }
+    let mut config = TomlValue::Table(config);
+    overrides
+        .apply_on_value(&mut config)
+        .map_err(anyhow::Error::msg)?;
+    Ok(config)
+}
+
+/// Merges `values` into the config.toml in `codex_home`, keeping the
+/// settings and comments already there. An existing file is first copied to
+/// [`CONFIG_BACKUP_FILE`], whose path is returned.
+fn write_config(codex_home: &Path, values: &TomlValue) -> Result<Option<PathBuf>> {
+    let config_path = codex_home.join(CONFIG_TOML_FILE);
+    std::fs::create_dir_all(codex_home)?;
+    let backup = if config_path.exists() {
+        let backup = codex_home.join(CONFIG_BACKUP_FILE);
+        std::fs::copy(&config_path, &backup)?;
+        Some(backup)
+    } else {
+        None
+    };
+    ConfigEditsBuilder::new(codex_home)
+        .merge_values(values)?
+        .apply_blocking()?;
+    if backup.is_none() {
+        let contents = std::fs::read_to_string(&config_path)?;
+        std::fs::write(&config_path, format!("{CONFIG_HEADER}{contents}"))?;
+    }
+    Ok(backup)
+}
+
+fn report_diagnostics<R: BufRead, W: Write>(
+    prompter: &mut Prompter<R, W>,
+    config_path: &Path,
+) -> Result<()> {
+    let diagnostics = validate_config_file(config_path)?;
+    for diagnostic in &diagnostics {
+        prompter.say(&diagnostic.to_string())?;
+    }
+    if diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity() == DiagnosticSeverity::Error)
+    {
+        prompter.say("Fix the errors above, or run `codex init --force` to start over.")?;
+    }
+    Ok(())
+}
+
+fn ask_login<R: BufRead, W: Write>(prompter: &mut Prompter<R, W>) -> io::Result<Login> {
+    let choice = prompter.choose(
+        "How do you want to sign in?",
+        &[
+            ("chatgpt", "Sign in with ChatGPT (opens your browser)"),
+            ("api-key", "Use an OpenAI API key"),
+            ("later", "Later, with `codex login`"),
+        ],
+        0,
+    )?;
+    Ok(match choice {
+        "chatgpt" => Login::ChatGpt,
+        "api-key" => Login::ApiKey,
+        _ => Login::Later,
+    })
+}
+
+async fn sign_in<R: BufRead, W: Write>(
+    prompter: &mut Prompter<R, W>,
+    codex_home: &Path,
+    login: Login,
+) -> Result<()> {
+    let result = match login {
+        Login::Later => return Ok(()),
+        Login::ChatGpt => {
+            login_with_chatgpt(
+                codex_home.to_path_buf(),
+                None,
+                AuthCredentialsStoreMode::default(),
+            )
+            .await
+        }
+        Login::ApiKey => {
+            let from_env = std::env::var("OPENAI_API_KEY")
+                .ok()
+                .filter(|key| !key.trim().is_empty());
+            let use_env =
+                from_env.is_some() && prompter.confirm("Use the key in OPENAI_API_KEY?", true)?;
+            let key = if use_env {
+                from_env
+            } else {
+                prompter.ask_secret("Paste your API key (it will not be shown)")?
+            };
+            let Some(key) = key else {
+                prompter.say("No key given; run `codex login --with-api-key` later.")?;
+                return Ok(());
+            };
+            login_with_api_key(codex_home, key.trim(), AuthCredentialsStoreMode::default())
+        }
+    };
+    match result {
+        Ok(()) => prompter.say("Signed in.")?,
+        Err(err) => prompter.say(&format!(
+            "Could not sign in: {err}. Run `codex login` to try again."
+        ))?,
+    }
+    Ok(())
+}
+
+async fn check_provider<R: BufRead, W: Write>(
+    prompter: &mut Prompter<R, W>,
+    provider: &str,
+    login: Login,
+) -> Result<()> {
+    let Some(info) = built_in_model_providers().remove(provider) else {
+        return Ok(());
+    };
+    let url = info.base_url.unwrap_or_else(|| {
+        if login == Login::ChatGpt {
+            "https://chatgpt.com/backend-api/codex".to_string()
+        } else {
+            "https://api.openai.com/v1".to_string()
+        }
+    });
+    match check_reachable(&url).await {
+        Ok(()) => prompter.say(&format!("Reached {url}."))?,
+        Err(reason) => prompter.say(&format!(
+            "Could not reach {url}: {reason}. Check your network or that the server is running."
+        ))?,
+    }
+    Ok(())
+}
+
+/// Writes `.codex/config.toml` and `AGENTS.md` under `root`, keeping files
+/// that already exist. Returns each path and whether it was created.
+fn scaffold_project(root: &Path) -> io::Result<Vec<(PathBuf, bool)>> {
+    let dot_codex = root.join(".codex");
+    std::fs::create_dir_all(&dot_codex)?;
+    [
+        (dot_codex.join(CONFIG_TOML_FILE), PROJECT_CONFIG_TEMPLATE),
+        (root.join("AGENTS.md"), AGENTS_MD_TEMPLATE),
+    ]
+    .into_iter()
+    .map(|(path, contents)| {
+        let created = !path.exists();
+        if created {
+            std::fs::write(&path, contents)?;
+        }
+        Ok((path, created))
+    })
+    .collect()
+}
+
+/// Asks questions on `output` and reads the answers from `input`. An empty
+/// answer, or the end of the input, picks the default.
+struct Prompter<R, W> {
+    input: R,
+    output: W,
+    assume_defaults: bool,
+}
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    fn say(&mut self, line: &str) -> io::Result<()> {
+        writeln!(self.output, "{line}")
+    }
+
+    fn answer(&mut self, prompt: &str) -> io::Result<String> {
+        write!(self.output, "{prompt}")?;
+        if self.assume_defaults {
+            writeln!(self.output)?;
+            return Ok(String::new());
+        }
+        self.output.flush()?;
+        let mut line = String::new();
+        self.input.read_line(&mut line)?;
+        Ok(line.trim().to_string())
+    }
+
+    /// Returns the value of the chosen entry of `choices`, given as
+    /// `(value, description)` pairs.
+    fn choose(
+        &mut self,
+        question: &str,
+        choices: &[(&'static str, &str)],
+        default: usize,
+    ) -> io::Result<&'static str> {
+        self.say(question)?;
+        for (index, (_, description)) in choices.iter().enumerate() {
+            self.say(&format!("  {}) {description}", index + 1))?;
+        }
+        loop {
+            let answer = self.answer(&format!("Choice [{}]: ", default + 1))?;
+            if answer.is_empty() {
+                return Ok(choices[default].0);
+            }
+            let picked = answer
+                .parse::<usize>()
+                .ok()
+                .and_then(|number| choices.get(number.checked_sub(1)?))
+                .or_else(|| choices.iter().find(|(value, _)| *value == answer));
+            if let Some((value, _)) = picked {
+                return Ok(*value);
+            }
+            self.say(&format!("Enter a number from 1 to {}.", choices.len()))?;
+        }
+    }
+
+    fn ask(&mut self, question: &str, default: Option<&str>) -> io::Result<Option<String>> {
+        let prompt = match default {
+            Some(default) => format!("{question} [{default}]: "),
+            None => format!("{question}: "),
+        };
+        let answer = self.answer(&prompt)?;
+        Ok(if answer.is_empty() {
+            default.map(str::to_string)
+        } else {
+            Some(answer)
+        })
+    }
+
+    /// Asks for a value that must not appear on screen, such as an API key.
+    fn ask_secret(&mut self, question: &str) -> io::Result<Option<String>> {
+        let _echo = EchoGuard::disable();
+        let answer = self.answer(&format!("{question}: "))?;
+        Ok((!answer.is_empty()).then_some(answer))
+    }
+
+    fn confirm(&mut self, question: &str, default: bool) -> io::Result<bool> {
+        let hint = if default { "Y/n" } else { "y/N" };
+        loop {
+            let answer = self.answer(&format!("{question} [{hint}] "))?;
+            match answer.to_ascii_lowercase().as_str() {
+                "" => return Ok(default),
+                "y" | "yes" => return Ok(true),
+                "n" | "no" => return Ok(false),
+                _ => self.say("Answer y or n.")?,
+            }
+        }
+    }
+}
+
+/// Turns off echo on the terminal attached to stdin until dropped. Does
+/// nothing when stdin is not a terminal, and on Windows.
+struct EchoGuard {
+    #[cfg(unix)]
+    original: Option<libc::termios>,
+}
+
+impl EchoGuard {
+    #[cfg(unix)]
+    fn disable() -> Self {
+        // SAFETY: `termios` is plain data, filled in by `tcgetattr` before it
+        // is read.
+        let mut original: libc::termios = unsafe { std::mem::zeroed() };
+        // SAFETY: both calls only inspect stdin and write to `original`.
+        let is_terminal = unsafe {
+            libc::isatty(libc::STDIN_FILENO) == 1
+                && libc::tcgetattr(libc::STDIN_FILENO, &mut original) == 0
+        };
+        if !is_terminal {
+            return Self { original: None };
+        }
+        let mut silent = original;
+        // Keep echoing the newline so the next line starts on its own.
+        silent.c_lflag &= !libc::ECHO;
+        silent.c_lflag |= libc::ECHONL;
+        // SAFETY: `silent` is a valid `termios` read from this terminal.
+        let disabled = unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &silent) } == 0;
+        Self {
+            original: disabled.then_some(original),
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn disable() -> Self {
+        Self {}
+    }
+}
+
+#[cfg(unix)]
+impl Drop for EchoGuard {
+    fn drop(&mut self) {
+        if let Some(original) = &self.original {
+            // SAFETY: `original` is the state read from this terminal.
+            unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, original) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn setup_from(answers: &str) -> Setup {
+        let mut prompter = Prompter {
+            input: answers.as_bytes(),
+            output: Vec::new(),
+            assume_defaults: false,
+        };
+        ask_setup(&mut prompter).expect("answers")
+    }
+
+    #[test]
+    fn defaults_write_a_minimal_config() {
+        let setup = setup_from("");
+        let expected: TomlValue = toml::from_str(
+            r#"
+approval_policy = "on-request"
+sandbox_mode = "workspace-write"
+"#,
+        )
+        .expect("valid toml");
+        assert_eq!(
+            config_values(&setup, &CliConfigOverrides::default()).expect("values"),
+            expected
+        );
+    }
+
+    #[test]
+    fn overrides_apply_on_top_of_the_answers() {
+        let setup = setup_from("");
+        let overrides = CliConfigOverrides {
+            raw_overrides: vec!["sandbox_mode=read-only".to_string(), "model=o3".to_string()],
+        };
+        let expected: TomlValue = toml::from_str(
+            r#"
+approval_policy = "on-request"
+model = "o3"
+sandbox_mode = "read-only"
+"#,
+        )
+        .expect("valid toml");
+        assert_eq!(config_values(&setup, &overrides).expect("values"), expected);
+    }
+
+    #[test]
+    fn existing_config_is_backed_up_and_merged() -> Result<()> {
+        let codex_home = tempfile::tempdir()?;
+        let config_path = codex_home.path().join(CONFIG_TOML_FILE);
+        let original = "# Mine\nmodel = \"o3\"\n\n[mcp_servers.docs]\ncommand = \"docs\"\n";
+        std::fs::write(&config_path, original)?;
+        let values = config_values(&setup_from(""), &CliConfigOverrides::default())?;
+
+        let backup = write_config(codex_home.path(), &values)?;
+
+        assert_eq!(backup, Some(codex_home.path().join(CONFIG_BACKUP_FILE)));
+        assert_eq!(
+            std::fs::read_to_string(codex_home.path().join(CONFIG_BACKUP_FILE))?,
+            original
+        );
+        let merged = std::fs::read_to_string(&config_path)?;
+        assert!(merged.starts_with("# Mine\n"), "{merged}");
+        let merged: TomlValue = toml::from_str(&merged)?;
+        let expected: TomlValue = toml::from_str(
+            r#"
+model = "o3"
+approval_policy = "on-request"
+sandbox_mode = "workspace-write"
+
+[mcp_servers.docs]
+command = "docs"
+"#,
+        )?;
+        assert_eq!(merged, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn new_config_starts_with_the_header() -> Result<()> {
+        let codex_home = tempfile::tempdir()?;
+        let values = config_values(&setup_from(""), &CliConfigOverrides::default())?;
+
+        let backup = write_config(codex_home.path(), &values)?;
+
+        assert_eq!(backup, None);
+        assert_eq!(
+            std::fs::read_to_string(codex_home.path().join(CONFIG_TOML_FILE))?,
+            format!(
+                "{CONFIG_HEADER}approval_policy = \"on-request\"\nsandbox_mode = \"workspace-write\"\n"
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn answers_choose_provider_policies_and_hook() {
+        let setup = setup_from("2\n\nread-only\n9\n1\ny\nnotify-send Done\n");
+        assert_eq!(
+            setup,
+            Setup {
+                provider: OLLAMA_OSS_PROVIDER_ID,
+                model: Some("gpt-oss:20b".to_string()),
+                sandbox_mode: "read-only",
+                approval_policy: "untrusted",
+                turn_complete_hook: Some(vec!["notify-send".to_string(), "Done".to_string()]),
+            }
+        );
+        let rendered = config_values(&setup, &CliConfigOverrides::default()).expect("values");
+        let expected: TomlValue = toml::from_str(
+            r#"
+approval_policy = "untrusted"
+model = "gpt-oss:20b"
+model_provider = "ollama"
+sandbox_mode = "read-only"
+
+[hooks.commands.on-turn-complete]
+on = ["task_complete"]
+command = ["notify-send", "Done"]
+"#,
+        )
+        .expect("valid toml");
+        assert_eq!(rendered, expected);
+    }
+
+    #[test]
+    fn scaffold_keeps_existing_files() -> io::Result<()> {
+        let root = tempfile::tempdir()?;
+        std::fs::write(root.path().join("AGENTS.md"), "# Ours\n")?;
+
+        let written = scaffold_project(root.path())?;
+
+        assert_eq!(
+            written,
+            vec![
+                (root.path().join(".codex").join(CONFIG_TOML_FILE), true),
+                (root.path().join("AGENTS.md"), false),
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.path().join("AGENTS.md"))?,
+            "# Ours\n"
+        );
+        Ok(())
+    }
+}
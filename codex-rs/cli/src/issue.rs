@@ -0,0 +1,308 @@
+//! `codex fix --issue <url>`: start a session that works on a GitHub or
+//! GitLab issue.
+//!
+//! Public issues need no credentials. For private repositories set
+//! `GITHUB_TOKEN` (or `GH_TOKEN`) or `GITLAB_TOKEN`.
+
+use anyhow::Context;
+use codex_core::default_client::create_client;
+use serde_json::Value;
+
+/// Comments beyond this many are left out of the prompt.
+const MAX_COMMENTS: usize = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum IssueTracker {
+    GitHub,
+    GitLab { host: String },
+}
+
+/// An issue identified by its web URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct IssueRef {
+    pub(crate) tracker: IssueTracker,
+    /// `owner/repo` on GitHub, the full project path on GitLab.
+    pub(crate) project: String,
+    pub(crate) number: u64,
+    pub(crate) url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct IssueComment {
+    pub(crate) author: String,
+    pub(crate) body: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Issue {
+    pub(crate) reference: IssueRef,
+    pub(crate) title: String,
+    pub(crate) author: String,
+    pub(crate) body: String,
+    pub(crate) comments: Vec<IssueComment>,
+}
+
+impl IssueRef {
+    /// Parses `https://github.com/<owner>/<repo>/issues/<n>` or
+    /// `https://<host>/<group>/<project>/-/issues/<n>`.
+    pub(crate) fn parse(url: &str) -> anyhow::Result<Self> {
+        let trimmed = url.trim().trim_end_matches('/');
+        let rest = trimmed
+            .strip_prefix("https://")
+            .or_else(|| trimmed.strip_prefix("http://"))
+            .with_context(|| format!("`{url}` is not an http(s) URL"))?;
+        let rest = rest.split(['?', '#']).next().unwrap_or_default();
+        let (host, path) = rest
+            .split_once('/')
+            .with_context(|| format!("`{url}` does not point to an issue"))?;
+
+        let (project, number, tracker) = if let Some((project, number)) =
+            path.split_once("/-/issues/")
+        {
+            let tracker = IssueTracker::GitLab {
+                host: host.to_string(),
+            };
+            (project, number, tracker)
+        } else if host == "github.com"
+            && let Some((project, number)) = path.split_once("/issues/")
+        {
+            (project, number, IssueTracker::GitHub)
+        } else {
+            anyhow::bail!(
+                "`{url}` is not a GitHub (`github.com/<owner>/<repo>/issues/<n>`) or GitLab (`<host>/<project>/-/issues/<n>`) issue URL"
+            );
+        };
+        if tracker == IssueTracker::GitHub && project.split('/').count() != 2 {
+            anyhow::bail!("`{url}` is not a GitHub issue URL");
+        }
+        let number = number
+            .parse()
+            .with_context(|| format!("`{number}` is not an issue number"))?;
+        Ok(Self {
+            tracker,
+            project: project.to_string(),
+            number,
+            url: trimmed.to_string(),
+        })
+    }
+
+    /// Label recorded in the session metadata, e.g.
+    /// `issue:github.com/openai/codex#123`.
+    pub(crate) fn label(&self) -> String {
+        let host = match &self.tracker {
+            IssueTracker::GitHub => "github.com",
+            IssueTracker::GitLab { host } => host,
+        };
+        format!("issue:{host}/{}#{}", self.project, self.number)
+    }
+
+    fn api_urls(&self) -> (String, String) {
+        match &self.tracker {
+            IssueTracker::GitHub => {
+                let issue = format!(
+                    "https://api.github.com/repos/{}/issues/{}",
+                    self.project, self.number
+                );
+                let comments = format!("{issue}/comments?per_page={MAX_COMMENTS}");
+                (issue, comments)
+            }
+            IssueTracker::GitLab { host } => {
+                let project = self.project.replace('/', "%2F");
+                let issue = format!(
+                    "https://{host}/api/v4/projects/{project}/issues/{}",
+                    self.number
+                );
+                let comments = format!("{issue}/notes?sort=asc&per_page={MAX_COMMENTS}");
+                (issue, comments)
+            }
+        }
+    }
+}
+
+/// Fetches the issue and its comments.
+pub(crate) async fn fetch_issue(reference: IssueRef) -> anyhow::Result<Issue> {
+    let (issue_url, comments_url) = reference.api_urls();
+    let issue = get_json(&reference.tracker, &issue_url).await?;
+    let comments = get_json(&reference.tracker, &comments_url).await?;
+    match reference.tracker {
+        IssueTracker::GitHub => Ok(parse_github_issue(reference, &issue, &comments)),
+        IssueTracker::GitLab { .. } => Ok(parse_gitlab_issue(reference, &issue, &comments)),
+    }
+}
+
+async fn get_json(tracker: &IssueTracker, url: &str) -> anyhow::Result<Value> {
+    let mut request = create_client().get(url);
+    match tracker {
+        IssueTracker::GitHub => {
+            request = request.header("Accept", "application/vnd.github+json");
+            if let Ok(token) = std::env::var("GITHUB_TOKEN").or_else(|_| std::env::var("GH_TOKEN"))
+            {
+                request = request.bearer_auth(token);
+            }
+        }
+        IssueTracker::GitLab { .. } => {
+            if let Ok(token) = std::env::var("GITLAB_TOKEN") {
+                request = request.header("PRIVATE-TOKEN", token);
+            }
+        }
+    }
+    let response = request
+        .send()
+        .await
+        .with_context(|| format!("failed to fetch {url}"))?;
+    let status = response.status();
+    let text = response.text().await?;
+    if !status.is_success() {
+        anyhow::bail!("fetching {url} failed with {status}: {text}");
+    }
+    serde_json::from_str(&text).with_context(|| format!("unexpected response from {url}"))
+}
+
+fn str_field(value: &Value, pointer: &str) -> String {
+    value
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn parse_github_issue(reference: IssueRef, issue: &Value, comments: &Value) -> Issue {
+    let comments = comments
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|comment| IssueComment {
+            author: str_field(comment, "/user/login"),
+            body: str_field(comment, "/body"),
+        })
+        .collect();
+    Issue {
+        reference,
+        title: str_field(issue, "/title"),
+        author: str_field(issue, "/user/login"),
+        body: str_field(issue, "/body"),
+        comments,
+    }
+}
+
+fn parse_gitlab_issue(reference: IssueRef, issue: &Value, notes: &Value) -> Issue {
+    let comments = notes
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|note| !note.get("system").and_then(Value::as_bool).unwrap_or(false))
+        .map(|note| IssueComment {
+            author: str_field(note, "/author/username"),
+            body: str_field(note, "/body"),
+        })
+        .collect();
+    Issue {
+        reference,
+        title: str_field(issue, "/title"),
+        author: str_field(issue, "/author/username"),
+        body: str_field(issue, "/description"),
+        comments,
+    }
+}
+
+/// Builds the first message of the session. `instructions` are extra
+/// directions from the command line.
+pub(crate) fn build_prompt(issue: &Issue, instructions: Option<&str>) -> String {
+    let reference = &issue.reference;
+    let mut prompt = format!(
+        "Fix issue #{} of {} in this repository.\n\n",
+        reference.number, reference.project
+    );
+    prompt.push_str(&format!("# {}\n\n", issue.title.trim()));
+    prompt.push_str(&format!(
+        "Opened by @{} at {}\n\n",
+        issue.author, reference.url
+    ));
+    let body = issue.body.trim();
+    prompt.push_str(if body.is_empty() {
+        "(no description)"
+    } else {
+        body
+    });
+    prompt.push('\n');
+    if !issue.comments.is_empty() {
+        prompt.push_str("\n## Comments\n");
+        for comment in &issue.comments {
+            prompt.push_str(&format!(
+                "\n@{} wrote:\n{}\n",
+                comment.author,
+                comment.body.trim()
+            ));
+        }
+    }
+    prompt.push_str(
+        "\nFind the cause in the code, fix it, and add or update tests that cover the fix. \
+         Finish with a short summary of the change that can go into a pull request.",
+    );
+    if let Some(instructions) = instructions.map(str::trim).filter(|s| !s.is_empty()) {
+        prompt.push_str(&format!("\n\n{instructions}"));
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn parses_github_and_gitlab_urls() {
+        let github = IssueRef::parse("https://github.com/openai/codex/issues/123#issuecomment-1")
+            .expect("github url");
+        assert_eq!(
+            github,
+            IssueRef {
+                tracker: IssueTracker::GitHub,
+                project: "openai/codex".to_string(),
+                number: 123,
+                url: "https://github.com/openai/codex/issues/123#issuecomment-1".to_string(),
+            }
+        );
+        assert_eq!(github.label(), "issue:github.com/openai/codex#123");
+
+        let gitlab = IssueRef::parse("https://gitlab.example.com/group/sub/app/-/issues/7/")
+            .expect("gitlab url");
+        assert_eq!(gitlab.label(), "issue:gitlab.example.com/group/sub/app#7");
+        assert_eq!(
+            gitlab.api_urls().0,
+            "https://gitlab.example.com/api/v4/projects/group%2Fsub%2Fapp/issues/7"
+        );
+
+        assert!(IssueRef::parse("https://github.com/openai/codex/pull/1").is_err());
+        assert!(IssueRef::parse("github.com/openai/codex/issues/1").is_err());
+    }
+
+    #[test]
+    fn prompt_includes_issue_and_comments() {
+        let reference =
+            IssueRef::parse("https://github.com/acme/app/issues/9").expect("github url");
+        let issue = parse_github_issue(
+            reference,
+            &json!({
+                "title": "Crash on empty config",
+                "user": {"login": "ana"},
+                "body": "Running with an empty file panics.",
+            }),
+            &json!([{"user": {"login": "bo"}, "body": "Same here on 0.5."}]),
+        );
+
+        assert_eq!(
+            build_prompt(&issue, Some("Keep the fix small.")),
+            "Fix issue #9 of acme/app in this repository.\n\n\
+             # Crash on empty config\n\n\
+             Opened by @ana at https://github.com/acme/app/issues/9\n\n\
+             Running with an empty file panics.\n\n\
+             ## Comments\n\n\
+             @bo wrote:\nSame here on 0.5.\n\n\
+             Find the cause in the code, fix it, and add or update tests that cover the fix. \
+             Finish with a short summary of the change that can go into a pull request.\n\n\
+             Keep the fix small."
+        );
+    }
+}
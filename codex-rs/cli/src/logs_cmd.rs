@@ -0,0 +1,319 @@
+use std::fs::File;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use anyhow::Result;
+use anyhow::bail;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::session_logs::LOG_FILE_NAME;
+use codex_core::session_logs::SessionLogDir;
+use codex_core::session_logs::UNSCOPED_DIR;
+use codex_core::session_logs::list_session_log_dirs;
+use codex_core::session_logs::session_log_files;
+use serde_json::Value;
+use tracing::Level;
+
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Print the structured logs of a session.
+#[derive(Debug, clap::Parser)]
+pub struct LogsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Session id, or a unique prefix of one. Defaults to the session that
+    /// logged most recently.
+    #[arg(value_name = "SESSION_ID")]
+    pub session_id: Option<String>,
+
+    /// List sessions that have logs, most recent first.
+    #[arg(long, conflicts_with = "session_id")]
+    pub list: bool,
+
+    /// Only show events at this level or more severe (error, warn, info, debug, trace).
+    #[arg(long)]
+    pub level: Option<Level>,
+
+    /// Only show events whose target starts with this prefix, e.g. `codex_core::exec`.
+    #[arg(long)]
+    pub target: Option<String>,
+
+    /// Only show events whose message or fields contain this text.
+    #[arg(long)]
+    pub grep: Option<String>,
+
+    /// Print matching events as raw JSON lines.
+    #[arg(long)]
+    pub json: bool,
+
+    /// Keep printing new events as the session writes them.
+    #[arg(long, short = 'f')]
+    pub follow: bool,
+}
+
+impl LogsCli {
+    pub async fn run(self) -> Result<()> {
+        let cli_kv_overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides_and_harness_overrides(
+            cli_kv_overrides,
+            ConfigOverrides::default(),
+        )
+        .await?;
+        let log_dir = codex_core::config::log_dir(&config)?;
+        let sessions = list_session_log_dirs(&log_dir)?;
+
+        if self.list {
+            print_sessions(&sessions);
+            return Ok(());
+        }
+
+        let session = resolve_session(&sessions, self.session_id.as_deref())?;
+        let filter = LogFilter {
+            level: self.level,
+            target: self.target,
+            grep: self.grep,
+        };
+        for path in session_log_files(&session.path) {
+            let mut contents = String::new();
+            File::open(&path)?.read_to_string(&mut contents)?;
+            print_lines(&contents, &filter, self.json);
+        }
+        if self.follow {
+            follow(&session.path, &filter, self.json).await?;
+        }
+        Ok(())
+    }
+}
+
+struct LogFilter {
+    level: Option<Level>,
+    target: Option<String>,
+    grep: Option<String>,
+}
+
+impl LogFilter {
+    fn matches(&self, line: &str, event: &Value) -> bool {
+        if let Some(level) = self.level {
+            let event_level = event
+                .get("level")
+                .and_then(Value::as_str)
+                .and_then(|level| Level::from_str(level).ok());
+            // More verbose levels compare greater, so `INFO <= WARN` is false.
+            if event_level.is_none_or(|event_level| event_level > level) {
+                return false;
+            }
+        }
+        if let Some(prefix) = &self.target {
+            let target = event.get("target").and_then(Value::as_str).unwrap_or("");
+            if !target.starts_with(prefix.as_str()) {
+                return false;
+            }
+        }
+        if let Some(needle) = &self.grep
+            && !line.contains(needle.as_str())
+        {
+            return false;
+        }
+        true
+    }
+}
+
+fn resolve_session<'a>(
+    sessions: &'a [SessionLogDir],
+    session_id: Option<&str>,
+) -> Result<&'a SessionLogDir> {
+    let Some(session_id) = session_id else {
+        return sessions
+            .iter()
+            .find(|session| session.session != UNSCOPED_DIR)
+            .or_else(|| sessions.first())
+            .ok_or_else(|| anyhow::anyhow!("no session logs found"));
+    };
+    if let Some(session) = sessions.iter().find(|s| s.session == session_id) {
+        return Ok(session);
+    }
+    let matches: Vec<&SessionLogDir> = sessions
+        .iter()
+        .filter(|session| session.session.starts_with(session_id))
+        .collect();
+    match matches.as_slice() {
+        [session] => Ok(session),
+        [] => bail!("no logs found for session {session_id}"),
+        _ => {
+            let ids: Vec<&str> = matches.iter().map(|s| s.session.as_str()).collect();
+            bail!(
+                "session id prefix {session_id} is ambiguous: {}",
+                ids.join(", ")
+            )
+        }
+    }
+}
+
+#[allow(clippy::print_stdout)]
+fn print_sessions(sessions: &[SessionLogDir]) {
+    let now = SystemTime::now();
+    for session in sessions {
+        let age = now
+            .duration_since(session.modified)
+            .unwrap_or(Duration::ZERO);
+        println!("{}\t{}", session.session, format_age(age));
+    }
+}
+
+#[allow(clippy::print_stdout)]
+fn print_lines(contents: &str, filter: &LogFilter, json: bool) {
+    for line in contents.lines() {
+        let Ok(event) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        if !filter.matches(line, &event) {
+            continue;
+        }
+        if json {
+            println!("{line}");
+        } else {
+            println!("{}", format_event(&event));
+        }
+    }
+}
+
+/// Polls the active log file for appended lines, picking up the rest of the
+/// previous file when it is rotated out from under us.
+async fn follow(dir: &Path, filter: &LogFilter, json: bool) -> Result<()> {
+    let active = dir.join(LOG_FILE_NAME);
+    let mut offset = std::fs::metadata(&active).map_or(0, |metadata| metadata.len());
+    let mut pending = String::new();
+    loop {
+        tokio::time::sleep(FOLLOW_POLL_INTERVAL).await;
+        let len = std::fs::metadata(&active).map_or(0, |metadata| metadata.len());
+        if len < offset {
+            let rotated: PathBuf = dir.join(format!("{LOG_FILE_NAME}.1"));
+            pending.push_str(&read_from(&rotated, offset)?);
+            offset = 0;
+        }
+        if len > offset {
+            let appended = read_from(&active, offset)?;
+            offset += appended.len() as u64;
+            pending.push_str(&appended);
+        }
+        // Only print complete lines; keep a trailing partial write for the next poll.
+        if let Some(end) = pending.rfind('\n') {
+            let complete: String = pending.drain(..=end).collect();
+            print_lines(&complete, filter, json);
+        }
+    }
+}
+
+fn read_from(path: &Path, offset: u64) -> Result<String> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    Ok(contents)
+}
+
+fn format_event(event: &Value) -> String {
+    let field = |name: &str| event.get(name).and_then(Value::as_str).unwrap_or("");
+    let mut out = format!(
+        "{} {:>5} {}: {}",
+        field("timestamp"),
+        field("level"),
+        field("target"),
+        field("message")
+    );
+    if let Some(fields) = event.get("fields").and_then(Value::as_object) {
+        for (key, value) in fields {
+            let value = match value {
+                Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            out.push_str(&format!(" {key}={value}"));
+        }
+    }
+    out
+}
+
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..60 => format!("{secs}s ago"),
+        60..3600 => format!("{}m ago", secs / 60),
+        3600..86400 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn session(name: &str, age_secs: u64) -> SessionLogDir {
+        SessionLogDir {
+            session: name.to_string(),
+            path: PathBuf::from(name),
+            modified: SystemTime::UNIX_EPOCH + Duration::from_secs(1_000 - age_secs),
+        }
+    }
+
+    #[test]
+    fn filter_applies_level_target_and_text() {
+        let line = r#"{"level":"INFO","target":"codex_core::exec","message":"spawned","fields":{"pid":7}}"#;
+        let event: Value = serde_json::from_str(line).expect("json");
+        let filter = |level, target: Option<&str>, grep: Option<&str>| LogFilter {
+            level,
+            target: target.map(str::to_string),
+            grep: grep.map(str::to_string),
+        };
+
+        assert!(filter(Some(Level::INFO), None, None).matches(line, &event));
+        assert!(filter(Some(Level::DEBUG), None, None).matches(line, &event));
+        assert!(!filter(Some(Level::WARN), None, None).matches(line, &event));
+        assert!(filter(None, Some("codex_core"), None).matches(line, &event));
+        assert!(!filter(None, Some("codex_tui"), None).matches(line, &event));
+        assert!(filter(None, None, Some("\"pid\":7")).matches(line, &event));
+        assert!(!filter(None, None, Some("missing")).matches(line, &event));
+    }
+
+    #[test]
+    fn resolves_sessions_by_prefix_and_recency() {
+        let sessions = vec![
+            session(UNSCOPED_DIR, 0),
+            session("0199a-first", 10),
+            session("0199b-second", 20),
+        ];
+
+        let latest = resolve_session(&sessions, None).expect("latest");
+        assert_eq!(latest.session, "0199a-first");
+        let by_prefix = resolve_session(&sessions, Some("0199b")).expect("prefix");
+        assert_eq!(by_prefix.session, "0199b-second");
+        assert!(resolve_session(&sessions, Some("0199")).is_err());
+        assert!(resolve_session(&sessions, Some("ffff")).is_err());
+    }
+
+    #[test]
+    fn formats_events_as_text() {
+        let event = serde_json::json!({
+            "timestamp": "2025-01-01T00:00:00.000Z",
+            "level": "WARN",
+            "target": "codex_core::client",
+            "message": "retrying",
+            "fields": {"attempt": 2, "reason": "timeout"},
+        });
+        assert_eq!(
+            format_event(&event),
+            "2025-01-01T00:00:00.000Z  WARN codex_core::client: retrying attempt=2 reason=timeout"
+        );
+    }
+}
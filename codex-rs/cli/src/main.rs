@@ -21,7 +21,9 @@ use codex_common::CliConfigOverrides;
 use codex_core::env::is_headless_environment;
 use codex_exec::Cli as ExecCli;
 use codex_exec::Command as ExecCommand;
+use codex_exec::EvalCli;
 use codex_exec::ReviewArgs;
+use codex_exec::RunCli;
 use codex_execpolicy::ExecPolicyCheckCommand;
 use codex_responses_api_proxy::Args as ResponsesApiProxyArgs;
 use codex_tui::AppExitInfo;
@@ -32,11 +34,32 @@ use owo_colors::OwoColorize;
 use std::path::PathBuf;
 use supports_color::Stream;
 
+mod approval_cmd;
+mod config_cmd;
+mod events_cmd;
+mod init_cmd;
+mod issue;
+mod logs_cmd;
 mod mcp_cmd;
+mod replay_cmd;
+mod self_update_cmd;
+mod serve_cmd;
+mod sessions_cmd;
+mod trust_cmd;
 #[cfg(not(windows))]
 mod wsl_paths;
 
+use crate::approval_cmd::ApprovalCli;
+use crate::config_cmd::ConfigCli;
+use crate::events_cmd::EventsCli;
+use crate::init_cmd::InitCli;
+use crate::logs_cmd::LogsCli;
 use crate::mcp_cmd::McpCli;
+use crate::replay_cmd::ReplayCli;
+use crate::self_update_cmd::SelfUpdateCli;
+use crate::serve_cmd::ServeCli;
+use crate::sessions_cmd::SessionsCli;
+use crate::trust_cmd::TrustCli;
 
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
@@ -86,12 +109,24 @@ enum Subcommand {
     /// Run a code review non-interactively.
     Review(ReviewArgs),
 
+    /// Run the prompts listed in a task file non-interactively and report the results.
+    Run(RunCli),
+
+    /// Score a suite of tasks across models and settings.
+    Eval(EvalCli),
+
+    /// Set up Codex interactively: write config.toml, sign in, and scaffold the project.
+    Init(InitCli),
+
     /// Manage login.
     Login(LoginCommand),
 
     /// Remove stored authentication credentials.
     Logout(LogoutCommand),
 
+    /// Update a standalone codex binary to the latest release.
+    SelfUpdate(SelfUpdateCli),
+
     /// [experimental] Run Codex as an MCP server and manage MCP servers.
     Mcp(McpCli),
 
@@ -119,6 +154,36 @@ enum Subcommand {
     /// Resume a previous interactive session (picker by default; use --last to continue the most recent).
     Resume(ResumeCommand),
 
+    /// Start a session that works on a GitHub or GitLab issue.
+    Fix(FixCommand),
+
+    /// Restore the working tree to the checkpoint taken before a turn of a session.
+    Rollback(RollbackCommand),
+
+    /// List the shell commands and patches of a session, or run them again with --execute.
+    Replay(ReplayCli),
+
+    /// Show the structured logs of a session.
+    Logs(LogsCli),
+
+    /// Follow a session's events live, or replay a finished session.
+    Events(EventsCli),
+
+    /// Serve a web dashboard of sessions on localhost (--web).
+    Serve(ServeCli),
+
+    /// Inspect and validate config.toml.
+    Config(ConfigCli),
+
+    /// Explain how the approval rules judge a command or a file write.
+    Approval(ApprovalCli),
+
+    /// List, rename, tag or delete sessions, or bundle one for a teammate.
+    Sessions(SessionsCli),
+
+    /// Show or change which projects Codex trusts with their config and hooks.
+    Trust(TrustCli),
+
     /// [EXPERIMENTAL] Browse tasks from Codex Cloud and apply changes locally.
     #[clap(name = "cloud", alias = "cloud-tasks")]
     Cloud(CloudTasksCli),
@@ -161,6 +226,34 @@ struct ResumeCommand {
     config_overrides: TuiCli,
 }
 
+#[derive(Debug, Parser)]
+struct FixCommand {
+    /// URL of the issue to work on.
+    #[arg(long = "issue", value_name = "URL")]
+    issue: String,
+
+    /// Options for the interactive session; a PROMPT adds instructions to the
+    /// issue.
+    #[clap(flatten)]
+    config_overrides: TuiCli,
+}
+
+#[derive(Debug, Parser)]
+struct RollbackCommand {
+    /// Conversation/session id (UUID). If omitted, use --last to pick the most
+    /// recent recorded session.
+    #[arg(value_name = "SESSION_ID", required_unless_present = "last")]
+    session_id: Option<String>,
+
+    /// Roll back the most recent session.
+    #[arg(long = "last", default_value_t = false, conflicts_with = "session_id")]
+    last: bool,
+
+    /// Turn to undo, along with every turn after it; 1 is the session's first message.
+    #[arg(long = "turn", value_name = "N")]
+    turn: usize,
+}
+
 #[derive(Debug, Parser)]
 struct SandboxArgs {
     #[command(subcommand)]
@@ -464,9 +557,84 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             );
             codex_exec::run_main(exec_cli, codex_linux_sandbox_exe).await?;
         }
+        Some(Subcommand::Run(mut run_cli)) => {
+            prepend_config_flags(&mut run_cli.config_overrides, root_config_overrides.clone());
+            if !codex_exec::batch::run_batch(run_cli).await? {
+                std::process::exit(1);
+            }
+        }
+        Some(Subcommand::Eval(mut eval_cli)) => {
+            prepend_config_flags(
+                &mut eval_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            if !codex_exec::eval::run_eval(eval_cli).await? {
+                std::process::exit(1);
+            }
+        }
         Some(Subcommand::McpServer) => {
             codex_mcp_server::run_main(codex_linux_sandbox_exe, root_config_overrides).await?;
         }
+        Some(Subcommand::Replay(mut replay_cli)) => {
+            prepend_config_flags(
+                &mut replay_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            if !replay_cli.run(codex_linux_sandbox_exe).await? {
+                std::process::exit(1);
+            }
+        }
+        Some(Subcommand::Logs(mut logs_cli)) => {
+            prepend_config_flags(
+                &mut logs_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            logs_cli.run().await?;
+        }
+        Some(Subcommand::Events(mut events_cli)) => {
+            prepend_config_flags(
+                &mut events_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            events_cli.run().await?;
+        }
+        Some(Subcommand::Serve(mut serve_cli)) => {
+            prepend_config_flags(
+                &mut serve_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            serve_cli.run().await?;
+        }
+        Some(Subcommand::Config(mut config_cli)) => {
+            prepend_config_flags(
+                &mut config_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            if !config_cli.run().await? {
+                std::process::exit(1);
+            }
+        }
+        Some(Subcommand::Approval(mut approval_cli)) => {
+            prepend_config_flags(
+                &mut approval_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            approval_cli.run().await?;
+        }
+        Some(Subcommand::Sessions(mut sessions_cli)) => {
+            prepend_config_flags(
+                &mut sessions_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            sessions_cli.run().await?;
+        }
+        Some(Subcommand::Trust(mut trust_cli)) => {
+            prepend_config_flags(
+                &mut trust_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            trust_cli.run().await?;
+        }
         Some(Subcommand::Mcp(mut mcp_cli)) => {
             // Propagate any root-level config overrides (e.g. `-c key=value`).
             prepend_config_flags(&mut mcp_cli.config_overrides, root_config_overrides.clone());
@@ -508,6 +676,33 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             let exit_info = run_interactive_tui(interactive, codex_linux_sandbox_exe).await?;
             handle_app_exit(exit_info)?;
         }
+        Some(Subcommand::Fix(FixCommand {
+            issue,
+            config_overrides: mut fix_cli,
+        })) => {
+            let reference = issue::IssueRef::parse(&issue)?;
+            let label = reference.label();
+            let issue = issue::fetch_issue(reference).await?;
+            fix_cli.prompt = Some(issue::build_prompt(&issue, fix_cli.prompt.as_deref()));
+            merge_resume_cli_flags(&mut interactive, fix_cli);
+            prepend_config_flags(
+                &mut interactive.config_overrides,
+                root_config_overrides.clone(),
+            );
+            interactive.session_labels.push(label);
+            let exit_info = run_interactive_tui(interactive, codex_linux_sandbox_exe).await?;
+            handle_app_exit(exit_info)?;
+        }
+        Some(Subcommand::Rollback(rollback_cli)) => {
+            run_rollback(rollback_cli, root_config_overrides).await?;
+        }
+        Some(Subcommand::Init(mut init_cli)) => {
+            prepend_config_flags(
+                &mut init_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            init_cli.run().await?;
+        }
         Some(Subcommand::Login(mut login_cli)) => {
             prepend_config_flags(
                 &mut login_cli.config_overrides,
@@ -553,6 +748,13 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             );
             run_logout(logout_cli.config_overrides).await;
         }
+        Some(Subcommand::SelfUpdate(mut self_update_cli)) => {
+            prepend_config_flags(
+                &mut self_update_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            self_update_cli.run().await?;
+        }
         Some(Subcommand::Completion(completion_cli)) => {
             print_completion(completion_cli);
         }
@@ -656,6 +858,59 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
     Ok(())
 }
 
+async fn run_rollback(
+    cmd: RollbackCommand,
+    root_config_overrides: CliConfigOverrides,
+) -> anyhow::Result<()> {
+    let cli_kv_overrides = root_config_overrides
+        .parse_overrides()
+        .map_err(anyhow::Error::msg)?;
+    let config = Config::load_with_cli_overrides_and_harness_overrides(
+        cli_kv_overrides,
+        ConfigOverrides::default(),
+    )
+    .await?;
+
+    let rollout_path = if cmd.last {
+        let provider_filter = vec![config.model_provider_id.clone()];
+        codex_core::RolloutRecorder::list_threads(
+            &config.codex_home,
+            1,
+            None,
+            &[],
+            Some(provider_filter.as_slice()),
+            &config.model_provider_id,
+        )
+        .await?
+        .items
+        .into_iter()
+        .next()
+        .map(|item| item.path)
+    } else if let Some(session_id) = cmd.session_id.as_deref() {
+        codex_core::find_thread_path_by_id_str(&config.codex_home, session_id).await?
+    } else {
+        None
+    };
+    let Some(rollout_path) = rollout_path else {
+        anyhow::bail!("no recorded session found");
+    };
+
+    let checkpoint = codex_core::checkpoints::rollback_to_turn(
+        &rollout_path,
+        cmd.turn,
+        config.ghost_snapshot.clone(),
+        &config.codex_home,
+    )
+    .await?;
+    println!(
+        "Restored {} to checkpoint {}, taken before turn {}.",
+        checkpoint.cwd.display(),
+        checkpoint.short_id(),
+        checkpoint.turn
+    );
+    Ok(())
+}
+
 /// Prepend root-level overrides so they have lower precedence than
 /// CLI-specific ones specified after the subcommand (if any).
 fn prepend_config_flags(
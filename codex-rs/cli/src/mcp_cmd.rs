@@ -245,6 +245,7 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
         tool_timeout_sec: None,
         enabled_tools: None,
         disabled_tools: None,
+        sampling: None,
     };
 
     servers.insert(name.clone(), new_entry);
@@ -0,0 +1,173 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use codex_common::CliConfigOverrides;
+use codex_common::SandboxModeCliArg;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::exec_env::create_env;
+use codex_core::find_thread_path_by_id_str;
+use codex_core::replay::ReplayContext;
+use codex_core::replay::ReplayStep;
+use codex_core::replay::StepResult;
+use codex_core::replay::StepStatus;
+use codex_core::replay::fresh_workspace;
+use codex_core::replay::parse_recorded_session;
+use codex_core::replay::run_step;
+use codex_core::shell::default_user_shell;
+
+/// Replay the shell commands and patches of a recorded session.
+#[derive(Debug, clap::Parser)]
+pub struct ReplayCli {
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Id of the session to replay.
+    #[arg(value_name = "SESSION_ID")]
+    pub session_id: String,
+
+    /// Run the steps. Without this flag they are only listed.
+    #[arg(long, default_value_t = false)]
+    pub execute: bool,
+
+    /// Directory to replay the session in. Defaults to a new, empty
+    /// temporary directory.
+    #[arg(long = "cd", short = 'C', value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Sandbox the steps run under. Defaults to the configured sandbox.
+    #[arg(long = "sandbox", short = 's', value_enum)]
+    pub sandbox_mode: Option<SandboxModeCliArg>,
+
+    /// Run the remaining steps after one fails or is denied.
+    #[arg(long, default_value_t = false)]
+    pub keep_going: bool,
+
+    /// Print one JSON object per step.
+    #[arg(long, default_value_t = false)]
+    pub json: bool,
+}
+
+impl ReplayCli {
+    /// Lists or runs the session's steps. Returns whether every step that
+    /// ran succeeded.
+    pub async fn run(self, codex_linux_sandbox_exe: Option<PathBuf>) -> Result<bool> {
+        let cli_kv_overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides_and_harness_overrides(
+            cli_kv_overrides,
+            ConfigOverrides {
+                cwd: self.cwd.clone(),
+                sandbox_mode: self.sandbox_mode.map(Into::into),
+                codex_linux_sandbox_exe: codex_linux_sandbox_exe.clone(),
+                ..Default::default()
+            },
+        )
+        .await?;
+        let path = find_thread_path_by_id_str(&config.codex_home, &self.session_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("no session found with id {}", self.session_id))?;
+        let session = parse_recorded_session(
+            &tokio::fs::read_to_string(&path).await?,
+            &default_user_shell(),
+        );
+
+        if !self.execute {
+            for (index, step) in session.steps.iter().enumerate() {
+                self.print_step(index, step, None)?;
+            }
+            return Ok(true);
+        }
+
+        // Without `--cd` the steps must not land in whatever directory the
+        // command was started from.
+        let workspace = match &self.cwd {
+            Some(_) => config.cwd.clone(),
+            None => {
+                let workspace = fresh_workspace()?;
+                if !self.json {
+                    eprintln!("Replaying in {}", workspace.display());
+                }
+                workspace
+            }
+        };
+        let ctx = ReplayContext {
+            workspace,
+            recorded_cwd: session.cwd,
+            sandbox_policy: config.sandbox_policy.get().clone(),
+            codex_exe: std::env::current_exe()?,
+            codex_linux_sandbox_exe,
+            env: create_env(&config.shell_environment_policy),
+        };
+        let mut all_succeeded = true;
+        for (index, step) in session.steps.iter().enumerate() {
+            let result = run_step(step, &ctx).await;
+            self.print_step(index, step, Some(&result))?;
+            if matches!(result.status, StepStatus::Succeeded | StepStatus::Skipped) {
+                continue;
+            }
+            all_succeeded = false;
+            if !self.keep_going {
+                if !self.json {
+                    eprintln!(
+                        "Stopped at step {}; pass --keep-going to run the remaining {}.",
+                        index + 1,
+                        session.steps.len() - index - 1
+                    );
+                }
+                break;
+            }
+        }
+        Ok(all_succeeded)
+    }
+
+    fn print_step(
+        &self,
+        index: usize,
+        step: &ReplayStep,
+        result: Option<&StepResult>,
+    ) -> Result<()> {
+        if self.json {
+            let mut line = serde_json::to_value(step)?;
+            if let Some(result) = result
+                && let serde_json::Value::Object(fields) = &mut line
+            {
+                fields.insert("result".to_string(), serde_json::to_value(result)?);
+            }
+            print_line(&line.to_string());
+            return Ok(());
+        }
+        let status = match result.map(|result| result.status) {
+            None => String::new(),
+            Some(StepStatus::Succeeded) => "ok  ".to_string(),
+            Some(StepStatus::Skipped) => "-   ".to_string(),
+            Some(StepStatus::Denied) => "DENIED  ".to_string(),
+            Some(StepStatus::TimedOut) => "TIMED OUT  ".to_string(),
+            Some(StepStatus::Failed) => match result.and_then(|result| result.exit_code) {
+                Some(code) => format!("FAILED ({code})  "),
+                None => "FAILED  ".to_string(),
+            },
+        };
+        print_line(&format!(
+            "{status}[{}] turn {}: {}",
+            index + 1,
+            step.turn,
+            step.action.describe()
+        ));
+        if let Some(result) = result
+            && !matches!(result.status, StepStatus::Succeeded | StepStatus::Skipped)
+        {
+            for line in result.output.lines() {
+                print_line(&format!("    {line}"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[allow(clippy::print_stdout)]
+fn print_line(line: &str) {
+    println!("{line}");
+}
@@ -0,0 +1,86 @@
+use anyhow::Result;
+use codex_common::CliConfigOverrides;
+use codex_core::config::CONFIG_TOML_FILE;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::config::types::UpdateChannel;
+use codex_core::releases;
+use codex_tui::update_action::get_update_action;
+
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Replace this standalone `codex` binary with the latest release.
+#[derive(Debug, clap::Parser)]
+pub struct SelfUpdateCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Only report whether an update is available.
+    #[arg(long)]
+    pub check: bool,
+
+    /// Include pre-releases, whatever `update_channel` is set to.
+    #[arg(long)]
+    pub beta: bool,
+}
+
+impl SelfUpdateCli {
+    pub async fn run(self) -> Result<()> {
+        let cli_kv_overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides_and_harness_overrides(
+            cli_kv_overrides,
+            ConfigOverrides::default(),
+        )
+        .await?;
+        if config.offline {
+            anyhow::bail!("updates need the network, which `offline = true` turns off");
+        }
+        // Package managers keep their own record of what is installed.
+        if let Some(action) = get_update_action() {
+            anyhow::bail!(
+                "this Codex was installed by a package manager; run `{}` to update it",
+                action.command_str()
+            );
+        }
+
+        let channel = if self.beta {
+            UpdateChannel::Beta
+        } else {
+            config.update_channel
+        };
+        let releases = releases::fetch_releases(channel).await?;
+        let Some(latest) = releases.first() else {
+            anyhow::bail!("no Codex releases found");
+        };
+        let release = latest;
+        let latest = release.version.as_str();
+        if !releases::is_newer(latest, CURRENT_VERSION).unwrap_or(false) {
+            println!("Codex {CURRENT_VERSION} is up to date.");
+            return Ok(());
+        }
+        println!("Codex {latest} is available (you have {CURRENT_VERSION}).");
+
+        let user_config = std::fs::read_to_string(config.codex_home.join(CONFIG_TOML_FILE))
+            .ok()
+            .and_then(|contents| toml::from_str::<toml::Table>(&contents).ok())
+            .unwrap_or_default();
+        let notes = releases::relevant_notes(&releases, CURRENT_VERSION, latest, &user_config);
+        if !notes.is_empty() {
+            println!("\nChanges that affect your config:");
+            for note in notes {
+                println!("  - {note}");
+            }
+        }
+        if self.check {
+            return Ok(());
+        }
+
+        let exe = std::env::current_exe()?;
+        releases::install_release(release, &exe).await?;
+        println!("\nUpdated {} to {latest}.", exe.display());
+        Ok(())
+    }
+}
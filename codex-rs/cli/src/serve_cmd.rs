@@ -0,0 +1,580 @@
+//! `codex serve --web`: a dashboard on localhost for watching sessions.
+//!
+//! Everything it shows comes from what sessions already record: the session
+//! list and transcripts from rollout files and their metadata, and live events
+//! from the event socket of each running session. With `--allow-approvals`,
+//! the approval buttons write their decisions back to sessions that accept
+//! them. The server only binds to 127.0.0.1 and speaks just enough HTTP/1.1
+//! for a browser.
+
+use std::io;
+use std::net::Ipv4Addr;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Result;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::event_socket::SocketEvent;
+use codex_core::event_socket::SocketOp;
+use codex_core::event_socket::event_socket_token_path;
+use codex_core::event_socket::is_socket_op;
+use codex_core::find_thread_path_by_id_str;
+use codex_core::rollout_metadata::ThreadStatus;
+use codex_core::rollout_metadata::list_thread_metadata;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::Op;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::TurnDiffEvent;
+use tokio::io::AsyncBufRead;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWrite;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+
+const DASHBOARD_HTML: &str = include_str!("serve_dashboard.html");
+const DEFAULT_PORT: u16 = 8765;
+/// Largest request line plus headers the server reads.
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 64 * 1024;
+/// Interval of the comments that keep an idle event stream open and notice
+/// when the browser went away.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Serve a view of Codex sessions.
+#[derive(Debug, clap::Parser)]
+pub struct ServeCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Serve the web dashboard on localhost: sessions, live events,
+    /// transcripts, diffs and approvals.
+    #[arg(long)]
+    pub web: bool,
+
+    /// Port to listen on.
+    #[arg(long, default_value_t = DEFAULT_PORT)]
+    pub port: u16,
+
+    /// Let the dashboard answer approval requests of sessions that enable
+    /// `event_socket_approvals`. Only the printed URL carries the token that
+    /// allows it.
+    #[arg(long)]
+    pub allow_approvals: bool,
+}
+
+impl ServeCli {
+    pub async fn run(self) -> Result<()> {
+        if !self.web {
+            anyhow::bail!("nothing to serve; pass --web for the dashboard");
+        }
+        let cli_kv_overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides_and_harness_overrides(
+            cli_kv_overrides,
+            ConfigOverrides::default(),
+        )
+        .await?;
+
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, self.port)).await?;
+        let port = listener.local_addr()?.port();
+        let token = self
+            .allow_approvals
+            .then(codex_core::event_socket::new_approval_token);
+        let url = match &token {
+            Some(token) => format!("http://localhost:{port}/#token={token}"),
+            None => format!("http://localhost:{port}/"),
+        };
+        print_line(&format!("Codex dashboard at {url} (Ctrl+C to stop)"));
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let codex_home = config.codex_home.clone();
+            let token = token.clone();
+            tokio::spawn(async move {
+                if let Err(err) =
+                    handle_connection(stream, &codex_home, port, token.as_deref()).await
+                {
+                    tracing::debug!("dashboard connection failed: {err}");
+                }
+            });
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Request {
+    method: String,
+    /// Request target without the query string.
+    path: String,
+    host: Option<String>,
+    content_type: Option<String>,
+    /// `X-Codex-Token` header sent with approval decisions.
+    token: Option<String>,
+    body: Vec<u8>,
+}
+
+/// Reads one request. `None` when the client closed the connection before
+/// sending anything.
+async fn read_request<R: AsyncBufRead + Unpin>(reader: &mut R) -> io::Result<Option<Request>> {
+    let mut head = Vec::new();
+    let mut head_len = 0;
+    loop {
+        let mut line = String::new();
+        let limit = (MAX_HEAD_BYTES - head_len) as u64;
+        head_len += (&mut *reader).take(limit).read_line(&mut line).await?;
+        if !line.ends_with('\n') {
+            if head.is_empty() && line.is_empty() {
+                return Ok(None);
+            }
+            return Err(invalid_request("request head is truncated or too large"));
+        }
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            break;
+        }
+        head.push(line.to_string());
+    }
+
+    let mut request_line = head
+        .first()
+        .map(String::as_str)
+        .unwrap_or_default()
+        .split(' ');
+    let (Some(method), Some(target)) = (request_line.next(), request_line.next()) else {
+        return Err(invalid_request("malformed request line"));
+    };
+    let mut request = Request {
+        method: method.to_string(),
+        path: target.split('?').next().unwrap_or_default().to_string(),
+        host: None,
+        content_type: None,
+        token: None,
+        body: Vec::new(),
+    };
+    let mut content_length = 0;
+    for header in head.iter().skip(1) {
+        let Some((name, value)) = header.split_once(':') else {
+            return Err(invalid_request("malformed header"));
+        };
+        let value = value.trim().to_string();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "host" => request.host = Some(value),
+            "content-type" => request.content_type = Some(value),
+            "x-codex-token" => request.token = Some(value),
+            "content-length" => {
+                content_length = value
+                    .parse::<usize>()
+                    .map_err(|_| invalid_request("malformed Content-Length"))?;
+            }
+            _ => {}
+        }
+    }
+    if content_length > MAX_BODY_BYTES {
+        return Err(invalid_request("request body too large"));
+    }
+    request.body = vec![0; content_length];
+    reader.read_exact(&mut request.body).await?;
+    Ok(Some(request))
+}
+
+fn invalid_request(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Whether the request was addressed to this server by a local name. Pages
+/// on other sites can make the browser connect to localhost, but not with
+/// these `Host` headers.
+fn is_local_host(host: Option<&str>, port: u16) -> bool {
+    host.is_some_and(|host| {
+        ["localhost", "127.0.0.1", "[::1]"]
+            .iter()
+            .any(|name| host == format!("{name}:{port}"))
+    })
+}
+
+/// Session ids are UUIDs; anything else must not reach a file path.
+fn is_session_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+}
+
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn json(value: serde_json::Value) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/json",
+            body: value.to_string().into_bytes(),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: message.as_bytes().to_vec(),
+        }
+    }
+
+    async fn write_to<W: AsyncWrite + Unpin>(&self, writer: &mut W) -> io::Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            403 => "Forbidden",
+            404 => "Not Found",
+            415 => "Unsupported Media Type",
+            _ => "Internal Server Error",
+        };
+        let head = format!(
+            "HTTP/1.1 {} {reason}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+            self.status,
+            self.content_type,
+            self.body.len()
+        );
+        writer.write_all(head.as_bytes()).await?;
+        writer.write_all(&self.body).await?;
+        writer.flush().await
+    }
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    codex_home: &Path,
+    port: u16,
+    token: Option<&str>,
+) -> io::Result<()> {
+    let mut reader = BufReader::new(stream);
+    let request = match read_request(&mut reader).await {
+        Ok(Some(request)) => request,
+        Ok(None) => return Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+            return Response::error(400, &err.to_string())
+                .write_to(&mut reader.into_inner())
+                .await;
+        }
+        Err(err) => return Err(err),
+    };
+    let mut stream = reader.into_inner();
+    if !is_local_host(request.host.as_deref(), port) {
+        return Response::error(403, "unexpected Host header")
+            .write_to(&mut stream)
+            .await;
+    }
+
+    let segments = request
+        .path
+        .trim_matches('/')
+        .split('/')
+        .collect::<Vec<_>>();
+    let response = match (request.method.as_str(), segments.as_slice()) {
+        ("GET", [""]) => Response {
+            status: 200,
+            content_type: "text/html; charset=utf-8",
+            body: DASHBOARD_HTML.as_bytes().to_vec(),
+        },
+        ("GET", ["api", "sessions"]) => list_sessions(codex_home).await,
+        (_, ["api", "sessions", id, ..]) if !is_session_id(id) => {
+            Response::error(404, "no such session")
+        }
+        ("GET", ["api", "sessions", id, "transcript"]) => transcript(codex_home, id).await,
+        ("GET", ["api", "sessions", id, "events"]) => {
+            return stream_events(stream, codex_home, id).await;
+        }
+        ("POST", ["api", "sessions", id, "ops"]) => {
+            submit_op(codex_home, id, &request, token).await
+        }
+        _ => Response::error(404, "not found"),
+    };
+    response.write_to(&mut stream).await
+}
+
+/// `GET /api/sessions`: every recorded session with its metadata and
+/// whether it is running now, most recently active first.
+async fn list_sessions(codex_home: &Path) -> Response {
+    let threads = match list_thread_metadata(codex_home).await {
+        Ok(threads) => threads,
+        Err(err) => return Response::error(500, &err.to_string()),
+    };
+    let mut sessions = Vec::new();
+    for thread in threads {
+        let running = thread.metadata.status == ThreadStatus::Active
+            && connect_session(codex_home, &thread.metadata.thread_id)
+                .await
+                .is_some();
+        let Ok(mut session) = serde_json::to_value(&thread) else {
+            continue;
+        };
+        if let Some(fields) = session.as_object_mut() {
+            fields.insert("running".to_string(), running.into());
+        }
+        sessions.push(session);
+    }
+    Response::json(serde_json::Value::Array(sessions))
+}
+
+/// `GET /api/sessions/{id}/transcript`: the recorded events of a session, in
+/// the shape the event stream sends them.
+async fn transcript(codex_home: &Path, id: &str) -> Response {
+    let path = match find_thread_path_by_id_str(codex_home, id).await {
+        Ok(Some(path)) => path,
+        Ok(None) => return Response::error(404, "no such session"),
+        Err(err) => return Response::error(500, &err.to_string()),
+    };
+    match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => match serde_json::to_value(transcript_events(&contents)) {
+            Ok(events) => Response::json(events),
+            Err(err) => Response::error(500, &err.to_string()),
+        },
+        Err(err) => Response::error(500, &err.to_string()),
+    }
+}
+
+/// Events of a rollout file, with each turn's diff as a `turn_diff` event
+/// whose id is the turn.
+fn transcript_events(rollout: &str) -> Vec<SocketEvent> {
+    rollout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<RolloutLine>(line).ok())
+        .filter_map(|RolloutLine { timestamp, item }| {
+            let (id, msg) = match item {
+                RolloutItem::EventMsg(msg) => (String::new(), msg),
+                RolloutItem::TurnDiff(diff) => (
+                    diff.turn_id,
+                    EventMsg::TurnDiff(TurnDiffEvent {
+                        unified_diff: diff.unified_diff,
+                    }),
+                ),
+                _ => return None,
+            };
+            Some(SocketEvent {
+                timestamp,
+                name: msg.to_string(),
+                event: Event { id, msg },
+            })
+        })
+        .collect()
+}
+
+/// `GET /api/sessions/{id}/events`: the session's live events as
+/// server-sent events, one [`SocketEvent`] per message, until the session
+/// ends or the browser goes away.
+async fn stream_events(mut stream: TcpStream, codex_home: &Path, id: &str) -> io::Result<()> {
+    let Some(socket) = connect_session(codex_home, id).await else {
+        return Response::error(404, "session is not running")
+            .write_to(&mut stream)
+            .await;
+    };
+    stream
+        .write_all(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        )
+        .await?;
+    let mut lines = BufReader::new(socket).lines();
+    let mut keep_alive = tokio::time::interval(KEEP_ALIVE_INTERVAL);
+    loop {
+        tokio::select! {
+            line = lines.next_line() => match line? {
+                Some(line) => stream.write_all(format!("data: {line}\n\n").as_bytes()).await?,
+                None => return Ok(()),
+            },
+            _ = keep_alive.tick() => stream.write_all(b": keep-alive\n\n").await?,
+        }
+    }
+}
+
+/// `POST /api/sessions/{id}/ops`: hands an approval decision, an [`Op`] in
+/// JSON, to the running session. Requires the server's token, and a session
+/// that accepts approvals over its socket.
+async fn submit_op(
+    codex_home: &Path,
+    id: &str,
+    request: &Request,
+    token: Option<&str>,
+) -> Response {
+    let Some(token) = token else {
+        return Response::error(
+            403,
+            "the dashboard is read-only; restart it with --allow-approvals",
+        );
+    };
+    if request.token.as_deref() != Some(token) {
+        return Response::error(403, "missing or wrong token");
+    }
+    // Browsers only send JSON to another origin after asking it first.
+    if !request
+        .content_type
+        .as_deref()
+        .is_some_and(|content_type| content_type.starts_with("application/json"))
+    {
+        return Response::error(415, "expected application/json");
+    }
+    let op = match serde_json::from_slice::<Op>(&request.body) {
+        Ok(op) if is_socket_op(&op) => op,
+        Ok(_) => return Response::error(400, "only approval decisions can be sent"),
+        Err(err) => return Response::error(400, &err.to_string()),
+    };
+    let Ok(session_token) =
+        tokio::fs::read_to_string(event_socket_token_path(codex_home, id)).await
+    else {
+        return Response::error(
+            403,
+            "the session does not accept approvals; set event_socket_approvals = true",
+        );
+    };
+    let Some(mut socket) = connect_session(codex_home, id).await else {
+        return Response::error(404, "session is not running");
+    };
+    let socket_op = SocketOp {
+        token: session_token,
+        op,
+    };
+    let mut line = match serde_json::to_vec(&socket_op) {
+        Ok(line) => line,
+        Err(err) => return Response::error(500, &err.to_string()),
+    };
+    line.push(b'\n');
+    match socket.write_all(&line).await {
+        Ok(()) => Response::json(serde_json::json!({ "submitted": true })),
+        Err(err) => Response::error(500, &err.to_string()),
+    }
+}
+
+/// Connects to the event socket of a running session.
+#[cfg(unix)]
+async fn connect_session(codex_home: &Path, id: &str) -> Option<tokio::net::UnixStream> {
+    let path = codex_core::event_socket::event_socket_path(codex_home, id);
+    tokio::net::UnixStream::connect(path).await.ok()
+}
+
+/// Sessions only have event sockets on Unix; elsewhere none is running as
+/// far as the dashboard can tell.
+#[cfg(not(unix))]
+async fn connect_session(_codex_home: &Path, _id: &str) -> Option<tokio::io::DuplexStream> {
+    None
+}
+
+#[allow(clippy::print_stdout)]
+fn print_line(line: &str) {
+    println!("{line}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    async fn parse(raw: &str) -> io::Result<Option<Request>> {
+        read_request(&mut BufReader::new(raw.as_bytes())).await
+    }
+
+    #[tokio::test]
+    async fn reads_requests_with_and_without_bodies() -> io::Result<()> {
+        assert_eq!(
+            parse("GET /api/sessions?fresh=1 HTTP/1.1\r\nHost: localhost:8765\r\n\r\n").await?,
+            Some(Request {
+                method: "GET".to_string(),
+                path: "/api/sessions".to_string(),
+                host: Some("localhost:8765".to_string()),
+                content_type: None,
+                token: None,
+                body: Vec::new(),
+            })
+        );
+
+        let body = r#"{"type":"exec_approval","id":"3","decision":"approved"}"#;
+        let request = parse(&format!(
+            "POST /api/sessions/x/ops HTTP/1.1\r\ncontent-type: application/json\r\nX-Codex-Token: abc\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        ))
+        .await?
+        .expect("request");
+        assert_eq!(request.body, body.as_bytes());
+        assert_eq!(request.content_type.as_deref(), Some("application/json"));
+        assert_eq!(request.token.as_deref(), Some("abc"));
+
+        assert_eq!(parse("").await?, None);
+        assert!(parse("GET / HTTP/1.1\r\nHost").await.is_err());
+        assert!(
+            parse("POST / HTTP/1.1\r\nContent-Length: 999999\r\n\r\n")
+                .await
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn only_local_hosts_and_session_ids_are_accepted() {
+        assert!(is_local_host(Some("localhost:8765"), 8765));
+        assert!(is_local_host(Some("127.0.0.1:8765"), 8765));
+        assert!(!is_local_host(Some("localhost:9000"), 8765));
+        assert!(!is_local_host(Some("evil.example:8765"), 8765));
+        assert!(!is_local_host(None, 8765));
+
+        assert!(is_session_id("0194d1a0-5d2e-7c3b-8a4f-1b2c3d4e5f60"));
+        assert!(!is_session_id(".."));
+        assert!(!is_session_id(""));
+    }
+
+    #[tokio::test]
+    async fn approvals_need_the_server_token() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let request = |token: Option<&str>| Request {
+            method: "POST".to_string(),
+            path: "/api/sessions/1/ops".to_string(),
+            host: Some("localhost:8765".to_string()),
+            content_type: Some("application/json".to_string()),
+            token: token.map(str::to_string),
+            body: br#"{"type":"exec_approval","id":"3","decision":"approved"}"#.to_vec(),
+        };
+
+        let read_only = submit_op(codex_home.path(), "1", &request(Some("abc")), None).await;
+        let wrong_token =
+            submit_op(codex_home.path(), "1", &request(Some("xyz")), Some("abc")).await;
+        let no_session_token =
+            submit_op(codex_home.path(), "1", &request(Some("abc")), Some("abc")).await;
+        assert_eq!(
+            [
+                read_only.status,
+                wrong_token.status,
+                no_session_token.status
+            ],
+            [403, 403, 403]
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&no_session_token.body),
+            "the session does not accept approvals; set event_socket_approvals = true"
+        );
+    }
+
+    #[test]
+    fn transcripts_carry_events_and_turn_diffs() {
+        let rollout = [
+            r#"{"timestamp":"2025-01-31T10:00:00.000Z","type":"event_msg","payload":{"type":"user_message","message":"fix the build"}}"#,
+            r#"{"timestamp":"2025-01-31T10:00:01.000Z","type":"response_item","payload":{"type":"message","role":"assistant","content":[]}}"#,
+            r#"{"timestamp":"2025-01-31T10:00:02.000Z","type":"turn_diff","payload":{"turn_id":"1","unified_diff":"+fixed"}}"#,
+        ]
+        .join("\n");
+
+        let events = transcript_events(&rollout);
+        let summary = events
+            .iter()
+            .map(|event| (event.name.as_str(), event.event.id.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(summary, vec![("user_message", ""), ("turn_diff", "1")]);
+        assert!(
+            matches!(&events[1].event.msg, EventMsg::TurnDiff(diff) if diff.unified_diff == "+fixed")
+        );
+    }
+}
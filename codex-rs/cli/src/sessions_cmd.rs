@@ -0,0 +1,311 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use codex_common::CliConfigOverrides;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::config::log_dir;
+use codex_core::find_thread_path_by_id_str;
+use codex_core::rollout_metadata::ThreadFilter;
+use codex_core::rollout_metadata::ThreadStatus;
+use codex_core::rollout_metadata::ThreadSummary;
+use codex_core::rollout_metadata::list_thread_metadata;
+use codex_core::rollout_metadata::load_metadata;
+use codex_core::rollout_metadata::remove_thread;
+use codex_core::rollout_metadata::rename_thread;
+use codex_core::rollout_metadata::tag_thread;
+use codex_core::session_bundle::create_bundle;
+use codex_core::session_bundle::import_bundle;
+use codex_core::session_bundle::read_bundle;
+use codex_core::session_bundle::write_bundle;
+use codex_core::session_logs::sessions_root;
+
+/// List, rename, tag, remove and share sessions.
+#[derive(Debug, clap::Parser)]
+pub struct SessionsCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: SessionsSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum SessionsSubcommand {
+    /// List recorded sessions, most recently active first.
+    List(ListArgs),
+
+    /// Set the title shown for a session.
+    Rename(RenameArgs),
+
+    /// Add tags to a session, or remove them.
+    Tag(TagArgs),
+
+    /// Delete sessions along with their logs.
+    Rm(RmArgs),
+
+    /// Pack a session into a portable file with secrets scrubbed.
+    Bundle(BundleArgs),
+
+    /// Add a bundled session to this machine so it can be resumed.
+    Import(ImportArgs),
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ListArgs {
+    /// Only list sessions with this tag. Repeat to require several.
+    #[arg(long = "tag", value_name = "TAG")]
+    pub tags: Vec<String>,
+
+    /// Only list sessions whose id, title, project or tags contain this
+    /// text.
+    #[arg(long, value_name = "TEXT")]
+    pub search: Option<String>,
+
+    /// Print each session as a JSON line.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct RenameArgs {
+    /// Id of the session to rename.
+    #[arg(value_name = "SESSION_ID")]
+    pub session_id: String,
+
+    /// New title. An empty title goes back to the start of the first
+    /// message.
+    #[arg(value_name = "TITLE")]
+    pub title: String,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct TagArgs {
+    /// Id of the session to tag.
+    #[arg(value_name = "SESSION_ID")]
+    pub session_id: String,
+
+    /// Tags to add, or to remove with `--remove`.
+    #[arg(value_name = "TAG", required = true)]
+    pub tags: Vec<String>,
+
+    /// Remove the tags instead of adding them.
+    #[arg(long, short = 'r')]
+    pub remove: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct RmArgs {
+    /// Ids of the sessions to delete.
+    #[arg(value_name = "SESSION_ID", required = true)]
+    pub session_ids: Vec<String>,
+
+    /// Delete sessions that are still marked as running.
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct BundleArgs {
+    /// Id of the session to bundle.
+    #[arg(value_name = "SESSION_ID")]
+    pub session_id: String,
+
+    /// Where to write the bundle. Defaults to `codex-session-<id>.bundle` in
+    /// the current directory.
+    #[arg(long, short = 'o', value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, clap::Parser)]
+pub struct ImportArgs {
+    /// Bundle written by `codex sessions bundle`.
+    #[arg(value_name = "FILE")]
+    pub bundle: PathBuf,
+
+    /// Replace a session with the same id if one exists.
+    #[arg(long)]
+    pub force: bool,
+}
+
+impl SessionsCli {
+    pub async fn run(self) -> Result<()> {
+        let cli_kv_overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let config = Config::load_with_cli_overrides_and_harness_overrides(
+            cli_kv_overrides,
+            ConfigOverrides::default(),
+        )
+        .await?;
+        match self.subcommand {
+            SessionsSubcommand::List(args) => run_list(&config, args).await,
+            SessionsSubcommand::Rename(args) => run_rename(&config, args).await,
+            SessionsSubcommand::Tag(args) => run_tag(&config, args).await,
+            SessionsSubcommand::Rm(args) => run_rm(&config, args).await,
+            SessionsSubcommand::Bundle(args) => run_bundle(&config, args).await,
+            SessionsSubcommand::Import(args) => run_import(&config, args).await,
+        }
+    }
+}
+
+async fn run_list(config: &Config, args: ListArgs) -> Result<()> {
+    let filter = ThreadFilter {
+        tags: args.tags,
+        text: args.search,
+    };
+    for summary in list_thread_metadata(&config.codex_home).await? {
+        if !filter.matches(&summary.metadata) {
+            continue;
+        }
+        if args.json {
+            print_line(&serde_json::to_string(&summary)?);
+        } else {
+            print_line(&format_summary(&summary));
+        }
+    }
+    Ok(())
+}
+
+/// `id  YYYY-MM-DD HH:MM  status  tokens  project  title  #tag...`
+fn format_summary(summary: &ThreadSummary) -> String {
+    let metadata = &summary.metadata;
+    let last_activity = metadata
+        .last_activity
+        .get(..16)
+        .unwrap_or(&metadata.last_activity)
+        .replace('T', " ");
+    let status = match metadata.status {
+        ThreadStatus::Active => "active",
+        ThreadStatus::Closed => "closed",
+    };
+    let project = metadata
+        .project
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let tags = metadata
+        .tags
+        .iter()
+        .map(|tag| format!("#{tag}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "{}  {last_activity:<16}  {status:<6}  {:>9}  {project}  {}  {tags}",
+        metadata.thread_id,
+        metadata.tokens.total_tokens,
+        metadata.title.as_deref().unwrap_or("")
+    )
+    .trim_end()
+    .to_string()
+}
+
+async fn run_rename(config: &Config, args: RenameArgs) -> Result<()> {
+    let path = find_session(config, &args.session_id).await?;
+    let metadata = rename_thread(&path, &args.title).await?;
+    print_line(&format!(
+        "Renamed session {} to \"{}\"",
+        args.session_id,
+        metadata.title.unwrap_or_default()
+    ));
+    Ok(())
+}
+
+async fn run_tag(config: &Config, args: TagArgs) -> Result<()> {
+    let path = find_session(config, &args.session_id).await?;
+    let (add, remove) = if args.remove {
+        (Vec::new(), args.tags)
+    } else {
+        (args.tags, Vec::new())
+    };
+    let metadata = tag_thread(&path, &add, &remove).await?;
+    let tags = if metadata.tags.is_empty() {
+        "no tags".to_string()
+    } else {
+        metadata.tags.join(", ")
+    };
+    print_line(&format!("Session {} now has {tags}", args.session_id));
+    Ok(())
+}
+
+async fn run_rm(config: &Config, args: RmArgs) -> Result<()> {
+    // Check every id before deleting anything.
+    let mut paths = Vec::with_capacity(args.session_ids.len());
+    for id in &args.session_ids {
+        let path = find_session(config, id).await?;
+        if !args.force && load_metadata(&path).await?.status == ThreadStatus::Active {
+            anyhow::bail!("session {id} is still running; pass --force to delete it anyway");
+        }
+        paths.push(path);
+    }
+    let logs = sessions_root(&log_dir(config)?);
+    for (id, path) in args.session_ids.iter().zip(paths) {
+        remove_thread(&path).await?;
+        let log_dir = logs.join(id);
+        if log_dir.exists() {
+            tokio::fs::remove_dir_all(&log_dir).await?;
+        }
+        print_line(&format!("Deleted session {id}"));
+    }
+    Ok(())
+}
+
+async fn find_session(config: &Config, id: &str) -> Result<PathBuf> {
+    find_thread_path_by_id_str(&config.codex_home, id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("no session found with id {id}"))
+}
+
+async fn run_bundle(config: &Config, args: BundleArgs) -> Result<()> {
+    let bundle = create_bundle(config, &args.session_id).await?;
+    let output = args
+        .output
+        .unwrap_or_else(|| PathBuf::from(format!("codex-session-{}.bundle", args.session_id)));
+    write_bundle(&bundle, &output)?;
+    print_line(&format!("Wrote {}", output.display()));
+    let redacted: u32 = bundle.redactions.values().sum();
+    if redacted > 0 {
+        let rules = bundle
+            .redactions
+            .iter()
+            .map(|(rule, count)| format!("{rule} x{count}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        print_line(&format!("Scrubbed {redacted} secret(s): {rules}"));
+    }
+    if !bundle.sources.is_empty() {
+        print_line(&format!(
+            "Context drawn from {} source(s):",
+            bundle.sources.len()
+        ));
+        for source in &bundle.sources {
+            print_line(&format!("  {source}"));
+        }
+    }
+    Ok(())
+}
+
+async fn run_import(config: &Config, args: ImportArgs) -> Result<()> {
+    let bundle = read_bundle(&args.bundle)?;
+    let imported = import_bundle(config, &bundle, args.force).await?;
+    print_line(&format!(
+        "Imported session {} to {}",
+        imported.thread_id,
+        imported.rollout_path.display()
+    ));
+    print_line(&format!(
+        "Config snapshot and turn diffs: {}",
+        imported.extras_dir.display()
+    ));
+    print_line(&format!(
+        "Resume it with `codex resume {}`",
+        imported.thread_id
+    ));
+    Ok(())
+}
+
+#[allow(clippy::print_stdout)]
+fn print_line(line: &str) {
+    println!("{line}");
+}
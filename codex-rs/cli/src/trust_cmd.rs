@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use codex_common::CliConfigOverrides;
+use codex_core::config::ProjectConfig;
+use codex_core::config::edit::ConfigEdit;
+use codex_core::config::edit::ConfigEditsBuilder;
+use codex_core::config::find_codex_home;
+use codex_core::config::load_config_as_toml_with_cli_overrides;
+use codex_core::git_info::resolve_root_git_project_for_trust;
+use codex_protocol::config_types::TrustLevel;
+use codex_utils_absolute_path::AbsolutePathBuf;
+
+/// Manage which projects Codex trusts.
+#[derive(Debug, clap::Parser)]
+pub struct TrustCli {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    pub subcommand: Option<TrustSubcommand>,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum TrustSubcommand {
+    /// Show the trust decision for a project (the default).
+    Show {
+        /// A folder in the project; defaults to the current directory.
+        #[arg(value_name = "PATH")]
+        path: Option<PathBuf>,
+    },
+
+    /// Record a trust decision for a project.
+    Set {
+        #[arg(value_enum)]
+        level: TrustArg,
+
+        /// A folder in the project; defaults to the current directory.
+        #[arg(value_name = "PATH")]
+        path: Option<PathBuf>,
+    },
+
+    /// List every project with a trust decision.
+    List,
+
+    /// Forget the decision for a project so Codex asks again next time.
+    Forget {
+        /// A folder in the project; defaults to the current directory.
+        #[arg(value_name = "PATH")]
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum TrustArg {
+    /// Edit files, run commands, and load the project's `.codex/` config and hooks.
+    Full,
+    /// Read the project but never change it; its `.codex/` config is ignored.
+    ReadOnly,
+    /// Edit files, but ask before running commands; its `.codex/` config is ignored.
+    Untrusted,
+    /// Ignore the project's AGENTS.md and `.codex/` config and never change it.
+    Deny,
+}
+
+impl From<TrustArg> for TrustLevel {
+    fn from(arg: TrustArg) -> Self {
+        match arg {
+            TrustArg::Full => TrustLevel::Trusted,
+            TrustArg::ReadOnly => TrustLevel::ReadOnly,
+            TrustArg::Untrusted => TrustLevel::Untrusted,
+            TrustArg::Deny => TrustLevel::Deny,
+        }
+    }
+}
+
+impl TrustCli {
+    pub async fn run(self) -> Result<()> {
+        let cli_overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(anyhow::Error::msg)?;
+        let codex_home = find_codex_home()?;
+        match self
+            .subcommand
+            .unwrap_or(TrustSubcommand::Show { path: None })
+        {
+            TrustSubcommand::Show { path } => {
+                let project = project_for(path)?;
+                let cwd = AbsolutePathBuf::from_absolute_path(&project)?;
+                let config =
+                    load_config_as_toml_with_cli_overrides(&codex_home, &cwd, cli_overrides)
+                        .await?;
+                let level = config
+                    .get_active_project(&project)
+                    .and_then(|project| project.trust_level);
+                print_line(&format!("{}: {}", project.display(), describe(level)));
+            }
+            TrustSubcommand::Set { level, path } => {
+                let project = project_for(path)?;
+                let level = TrustLevel::from(level);
+                ConfigEditsBuilder::new(&codex_home)
+                    .set_project_trust_level(&project, level)
+                    .apply()
+                    .await?;
+                print_line(&format!("{}: {}", project.display(), describe(Some(level))));
+            }
+            TrustSubcommand::List => {
+                let cwd = AbsolutePathBuf::current_dir()?;
+                let config =
+                    load_config_as_toml_with_cli_overrides(&codex_home, &cwd, cli_overrides)
+                        .await?;
+                let lines = list_projects(&config.projects.unwrap_or_default());
+                if lines.is_empty() {
+                    print_line("No trust decisions recorded.");
+                }
+                for line in lines {
+                    print_line(&line);
+                }
+            }
+            TrustSubcommand::Forget { path } => {
+                let project = project_for(path)?;
+                ConfigEditsBuilder::new(&codex_home)
+                    .with_edits([ConfigEdit::ClearPath {
+                        segments: vec![
+                            "projects".to_string(),
+                            project.to_string_lossy().to_string(),
+                            "trust_level".to_string(),
+                        ],
+                    }])
+                    .apply()
+                    .await?;
+                print_line(&format!(
+                    "{}: forgotten; Codex will ask again the next time it starts there",
+                    project.display()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The folder a trust decision is recorded for: the repository containing
+/// `path`, the same folder the startup prompt records.
+fn project_for(path: Option<PathBuf>) -> Result<PathBuf> {
+    let path = std::env::current_dir()?.join(path.unwrap_or_default());
+    let path = path.canonicalize()?;
+    Ok(resolve_root_git_project_for_trust(&path).unwrap_or(path))
+}
+
+fn describe(level: Option<TrustLevel>) -> &'static str {
+    match level {
+        Some(TrustLevel::Trusted) => {
+            "trusted; Codex may edit files and loads the project's .codex/ config and hooks"
+        }
+        Some(TrustLevel::ReadOnly) => {
+            "read-only; Codex may read the project but not change it, and ignores its .codex/ config"
+        }
+        Some(TrustLevel::Untrusted) => {
+            "untrusted; Codex asks before running commands and ignores the project's .codex/ config"
+        }
+        Some(TrustLevel::Deny) => {
+            "denied; Codex ignores the project's AGENTS.md and .codex/ config and may not change it"
+        }
+        None => "no decision yet; Codex will ask the next time it starts there",
+    }
+}
+
+fn list_projects(projects: &HashMap<String, ProjectConfig>) -> Vec<String> {
+    let mut decided = projects
+        .iter()
+        .filter_map(|(path, project)| Some((path, project.trust_level?)))
+        .collect::<Vec<_>>();
+    decided.sort_by(|(a, _), (b, _)| a.cmp(b));
+    decided
+        .into_iter()
+        .map(|(path, level)| format!("{:<10} {path}", level.to_string()))
+        .collect()
+}
+
+fn print_line(line: &str) {
+    println!("{line}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn list_shows_only_projects_with_a_decision() {
+        let project = |trust_level| ProjectConfig {
+            trust_level,
+            ..Default::default()
+        };
+        let projects = HashMap::from([
+            ("/work/web".to_string(), project(Some(TrustLevel::ReadOnly))),
+            ("/work/api".to_string(), project(Some(TrustLevel::Trusted))),
+            ("/work/docs".to_string(), project(None)),
+            ("/tmp/download".to_string(), project(Some(TrustLevel::Deny))),
+        ]);
+
+        assert_eq!(
+            list_projects(&projects),
+            vec![
+                "deny       /tmp/download".to_string(),
+                "trusted    /work/api".to_string(),
+                "read-only  /work/web".to_string(),
+            ]
+        );
+    }
+}
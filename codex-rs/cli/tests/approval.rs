@@ -0,0 +1,36 @@
+use std::fs;
+
+use assert_cmd::Command;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+
+#[test]
+fn approval_explain_names_the_deciding_rule() -> Result<(), Box<dyn std::error::Error>> {
+    let codex_home = TempDir::new()?;
+    let policy_path = codex_home.path().join("approval.policy");
+    fs::write(
+        &policy_path,
+        "allow exec \"cargo *\"\ndeny exec \"rm -rf *\"\n",
+    )?;
+
+    let output = Command::new(codex_utils_cargo_bin::cargo_bin("codex")?)
+        .env("CODEX_HOME", codex_home.path())
+        .args([
+            "approval",
+            "explain",
+            "exec",
+            "cargo build && rm -rf target",
+        ])
+        .output()?;
+
+    assert!(output.status.success());
+    let policy = policy_path.display();
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        format!(
+            "denied by approval rules\n  cargo build: matches `allow exec \"cargo *\"` ({policy}:1)\n  rm -rf target: matches `deny exec \"rm -rf *\"` ({policy}:2)\n"
+        )
+    );
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use std::fs;
+
+use assert_cmd::Command;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+
+const SESSION_ID: &str = "0194d1a0-5d2e-7c3b-8a4f-1b2c3d4e5f60";
+
+fn codex(codex_home: &TempDir) -> Result<Command, Box<dyn std::error::Error>> {
+    let mut cmd = Command::new(codex_utils_cargo_bin::cargo_bin("codex")?);
+    cmd.env("CODEX_HOME", codex_home.path());
+    Ok(cmd)
+}
+
+#[test]
+fn tail_replays_the_recorded_events_that_pass_the_filter() -> Result<(), Box<dyn std::error::Error>>
+{
+    let home = TempDir::new()?;
+    let rollout_dir = home.path().join("sessions/2025/01/31");
+    fs::create_dir_all(&rollout_dir)?;
+    let rollout = [
+        r#"{"timestamp":"2025-01-31T10:00:00.000Z","type":"event_msg","payload":{"type":"agent_message","message":"running the tests"}}"#,
+        r#"{"timestamp":"2025-01-31T10:00:01.000Z","type":"event_msg","payload":{"type":"error","message":"quota exceeded"}}"#,
+        r#"{"timestamp":"2025-01-31T10:00:02.000Z","type":"turn_diff","payload":{"turn_id":"1","unified_diff":""}}"#,
+    ];
+    fs::write(
+        rollout_dir.join(format!("rollout-2025-01-31T10-00-00-{SESSION_ID}.jsonl")),
+        rollout.join("\n"),
+    )?;
+
+    let output = codex(&home)?
+        .args(["events", "tail", "--session", SESSION_ID])
+        .output()?;
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        String::from_utf8(output.stdout)?,
+        concat!(
+            "10:00:00 agent_message {\"message\":\"running the tests\"}\n",
+            "10:00:01 error {\"message\":\"quota exceeded\"}\n",
+        )
+    );
+
+    let output = codex(&home)?
+        .args([
+            "events",
+            "tail",
+            "--session",
+            SESSION_ID,
+            "--json",
+            "--filter",
+        ])
+        .arg(r#"event == "error" && data.message contains "quota""#)
+        .output()?;
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8(output.stdout)?;
+    let lines: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["timestamp"], "2025-01-31T10:00:01.000Z");
+    assert_eq!(lines[0]["name"], "error");
+    assert_eq!(lines[0]["event"]["msg"]["message"], "quota exceeded");
+
+    let output = codex(&home)?
+        .args([
+            "events",
+            "tail",
+            "--session",
+            SESSION_ID,
+            "--filter",
+            "exit_code != 0",
+        ])
+        .output()?;
+    assert!(!output.status.success());
+
+    let output = codex(&home)?.args(["events", "tail"]).output()?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)?.contains("pass --session"));
+
+    Ok(())
+}
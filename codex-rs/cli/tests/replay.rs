@@ -0,0 +1,65 @@
+use std::fs;
+
+use assert_cmd::Command;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+
+const SESSION_ID: &str = "0194d1a0-5d2e-7c3b-8a4f-1b2c3d4e5f61";
+
+fn codex(codex_home: &TempDir) -> Result<Command, Box<dyn std::error::Error>> {
+    let mut cmd = Command::new(codex_utils_cargo_bin::cargo_bin("codex")?);
+    cmd.env("CODEX_HOME", codex_home.path());
+    Ok(cmd)
+}
+
+#[cfg(unix)]
+#[test]
+fn replay_lists_then_runs_recorded_steps() -> Result<(), Box<dyn std::error::Error>> {
+    let home = TempDir::new()?;
+    let rollout_dir = home.path().join("sessions/2025/01/31");
+    fs::create_dir_all(&rollout_dir)?;
+    let rollout = [
+        r#"{"timestamp":"2025-01-31T10:00:00.000Z","type":"event_msg","payload":{"type":"user_message","message":"set it up"}}"#,
+        r#"{"timestamp":"2025-01-31T10:00:01.000Z","type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{\"command\":[\"mkdir\",\"-p\",\"out\"]}","call_id":"c1"}}"#,
+        r#"{"timestamp":"2025-01-31T10:00:02.000Z","type":"response_item","payload":{"type":"custom_tool_call","name":"apply_patch","input":"*** Begin Patch\n*** Add File: out/notes.txt\n+replayed\n*** End Patch","call_id":"c2"}}"#,
+        r#"{"timestamp":"2025-01-31T10:00:03.000Z","type":"response_item","payload":{"type":"function_call","name":"read_file","arguments":"{\"path\":\"out/notes.txt\"}","call_id":"c3"}}"#,
+    ];
+    fs::write(
+        rollout_dir.join(format!("rollout-2025-01-31T10-00-00-{SESSION_ID}.jsonl")),
+        rollout.join("\n"),
+    )?;
+    let workspace = TempDir::new()?;
+
+    let output = codex(&home)?
+        .args(["replay", SESSION_ID, "--cd"])
+        .arg(workspace.path())
+        .output()?;
+    assert!(output.status.success(), "{output:?}");
+    let listed = String::from_utf8(output.stdout)?;
+    assert!(listed.contains("[1] turn 1: mkdir -p out"), "{listed}");
+    assert!(
+        listed.contains("[2] turn 1: apply_patch: add out/notes.txt"),
+        "{listed}"
+    );
+    assert!(listed.contains("[3] turn 1: skip read_file"), "{listed}");
+    assert!(!workspace.path().join("out").exists());
+
+    let output = codex(&home)?
+        .args([
+            "replay",
+            SESSION_ID,
+            "--execute",
+            "--sandbox",
+            "danger-full-access",
+            "--cd",
+        ])
+        .arg(workspace.path())
+        .output()?;
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        fs::read_to_string(workspace.path().join("out/notes.txt"))?,
+        "replayed\n"
+    );
+
+    Ok(())
+}
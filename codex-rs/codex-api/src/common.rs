@@ -27,6 +27,8 @@ pub struct Prompt {
     pub parallel_tool_calls: bool,
     /// Optional output schema used to build the `text.format` controls.
     pub output_schema: Option<Value>,
+    /// Most tokens the model may generate in its response.
+    pub max_output_tokens: Option<u64>,
 }
 
 /// Canonical input payload for the compaction endpoint.
@@ -47,6 +49,12 @@ pub enum ResponseEvent {
         token_usage: Option<TokenUsage>,
     },
     OutputTextDelta(String),
+    /// A fragment of the arguments of the function call `item_id`, streamed
+    /// before the call's `OutputItemDone`.
+    ToolCallInputDelta {
+        item_id: String,
+        delta: String,
+    },
     ReasoningSummaryDelta {
         delta: String,
         summary_index: i64,
@@ -134,6 +142,8 @@ pub struct ResponsesApiRequest<'a> {
     pub prompt_cache_key: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub text: Option<TextControls>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u64>,
 }
 
 pub fn create_text_param_for_request(
@@ -61,6 +61,7 @@ impl<T: HttpTransport, A: AuthProvider> ChatClient<T, A> {
 
         let request =
             ChatRequestBuilder::new(model, &prompt.instructions, &prompt.input, &prompt.tools)
+                .max_tokens(prompt.max_output_tokens)
                 .conversation_id(conversation_id)
                 .session_source(session_source)
                 .build(self.streaming.provider())?;
@@ -236,6 +237,7 @@ impl Stream for AggregatedStream {
                     }
                 }
                 Poll::Ready(Some(Ok(ResponseEvent::ReasoningSummaryDelta { .. }))) => continue,
+                Poll::Ready(Some(Ok(ResponseEvent::ToolCallInputDelta { .. }))) => continue,
                 Poll::Ready(Some(Ok(ResponseEvent::ReasoningSummaryPartAdded { .. }))) => {
                     continue;
                 }
@@ -136,6 +136,7 @@ mod tests {
                 retry_transport: true,
             },
             stream_idle_timeout: Duration::from_secs(1),
+            bandwidth_limit: None,
         }
     }
 
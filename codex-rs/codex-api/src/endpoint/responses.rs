@@ -89,6 +89,7 @@ impl<T: HttpTransport, A: AuthProvider> ResponsesClient<T, A> {
             .include(include)
             .prompt_cache_key(prompt_cache_key)
             .text(text)
+            .max_output_tokens(prompt.max_output_tokens)
             .conversation(conversation_id)
             .session_source(session_source)
             .store_override(store_override)
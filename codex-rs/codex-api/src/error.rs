@@ -12,6 +12,10 @@ pub enum ApiError {
     Api { status: StatusCode, message: String },
     #[error("stream error: {0}")]
     Stream(String),
+    /// The stream delivered data that could not be parsed or exceeded a
+    /// buffering limit. Retryable, like [`ApiError::Stream`].
+    #[error("malformed stream: {0}")]
+    MalformedStream(String),
     #[error("context window exceeded")]
     ContextWindowExceeded,
     #[error("quota exceeded")]
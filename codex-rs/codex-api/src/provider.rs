@@ -55,6 +55,8 @@ pub struct Provider {
     pub headers: HeaderMap,
     pub retry: RetryConfig,
     pub stream_idle_timeout: Duration,
+    /// Caps requests and responses at this many bytes per second.
+    pub bandwidth_limit: Option<u64>,
 }
 
 impl Provider {
@@ -90,6 +92,7 @@ impl Provider {
             body: None,
             compression: RequestCompression::None,
             timeout: None,
+            bandwidth_limit: self.bandwidth_limit,
         }
     }
 
@@ -24,6 +24,7 @@ pub struct ChatRequestBuilder<'a> {
     instructions: &'a str,
     input: &'a [ResponseItem],
     tools: &'a [Value],
+    max_tokens: Option<u64>,
     conversation_id: Option<String>,
     session_source: Option<SessionSource>,
 }
@@ -40,11 +41,17 @@ impl<'a> ChatRequestBuilder<'a> {
             instructions,
             input,
             tools,
+            max_tokens: None,
             conversation_id: None,
             session_source: None,
         }
     }
 
+    pub fn max_tokens(mut self, max_tokens: Option<u64>) -> Self {
+        self.max_tokens = max_tokens;
+        self
+    }
+
     pub fn conversation_id(mut self, id: Option<String>) -> Self {
         self.conversation_id = id;
         self
@@ -291,12 +298,17 @@ impl<'a> ChatRequestBuilder<'a> {
             }
         }
 
-        let payload = json!({
+        let mut payload = json!({
             "model": self.model,
             "messages": messages,
             "stream": true,
             "tools": self.tools,
         });
+        if let Some(max_tokens) = self.max_tokens
+            && let Some(obj) = payload.as_object_mut()
+        {
+            obj.insert("max_tokens".to_string(), json!(max_tokens));
+        }
 
         let mut headers = build_conversation_headers(self.conversation_id);
         if let Some(subagent) = subagent_header(&self.session_source) {
@@ -375,6 +387,7 @@ mod tests {
                 retry_transport: true,
             },
             stream_idle_timeout: Duration::from_secs(1),
+            bandwidth_limit: None,
         }
     }
 
@@ -1,6 +1,8 @@
 use crate::common::ResponseEvent;
 use crate::common::ResponseStream;
 use crate::error::ApiError;
+use crate::sse::spawn_until_receiver_dropped;
+use crate::sse::tool_calls::ToolCallAssembler;
 use crate::telemetry::SseTelemetry;
 use codex_client::StreamResponse;
 use codex_protocol::models::ContentItem;
@@ -9,14 +11,12 @@ use codex_protocol::models::ResponseItem;
 use eventsource_stream::Eventsource;
 use futures::Stream;
 use futures::StreamExt;
-use std::collections::HashMap;
-use std::collections::HashSet;
 use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::time::Instant;
 use tokio::time::timeout;
-use tracing::debug;
 use tracing::trace;
+use tracing::warn;
 
 pub(crate) fn spawn_chat_stream(
     stream_response: StreamResponse,
@@ -24,9 +24,10 @@ pub(crate) fn spawn_chat_stream(
     telemetry: Option<std::sync::Arc<dyn SseTelemetry>>,
 ) -> ResponseStream {
     let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent, ApiError>>(1600);
-    tokio::spawn(async move {
-        process_chat_sse(stream_response.bytes, tx_event, idle_timeout, telemetry).await;
-    });
+    spawn_until_receiver_dropped(
+        tx_event.clone(),
+        process_chat_sse(stream_response.bytes, tx_event, idle_timeout, telemetry),
+    );
     ResponseStream { rx_event }
 }
 
@@ -37,14 +38,17 @@ pub(crate) fn spawn_chat_stream(
 /// (without brackets) instead.
 ///
 /// `eventsource_stream` delivers these sentinels as regular events rather than signaling
-/// end-of-stream. If we try to parse them as JSON, we log and skip them, then keep
-/// polling for more events.
+/// end-of-stream, so they are recognised before any JSON parsing.
 ///
 /// On servers that keep the HTTP connection open after emitting the sentinel (notably
 /// wiremock on Windows), skipping the sentinel means we never emit `ResponseEvent::Completed`.
 /// Higher-level workflows/tests that wait for completion before issuing subsequent model
 /// calls will then stall, which shows up as "expected N requests, got 1" verification
 /// failures in the mock server.
+///
+/// Any other chunk that is not valid JSON is logged and skipped. Tool-call
+/// arguments are assembled incrementally by [`ToolCallAssembler`], which ends
+/// the stream with [`ApiError::MalformedStream`] when they cannot be.
 pub async fn process_chat_sse<S>(
     stream: S,
     tx_event: mpsc::Sender<Result<ResponseEvent, ApiError>>,
@@ -55,19 +59,7 @@ pub async fn process_chat_sse<S>(
 {
     let mut stream = stream.eventsource();
 
-    #[derive(Default, Debug)]
-    struct ToolCallState {
-        id: Option<String>,
-        name: Option<String>,
-        arguments: String,
-    }
-
-    let mut tool_calls: HashMap<usize, ToolCallState> = HashMap::new();
-    let mut tool_call_order: Vec<usize> = Vec::new();
-    let mut tool_call_order_seen: HashSet<usize> = HashSet::new();
-    let mut tool_call_index_by_id: HashMap<String, usize> = HashMap::new();
-    let mut next_tool_call_index = 0usize;
-    let mut last_tool_call_index: Option<usize> = None;
+    let mut tool_calls = ToolCallAssembler::default();
     let mut assistant_item: Option<ResponseItem> = None;
     let mut reasoning_item: Option<ResponseItem> = None;
     let mut completed_sent = false;
@@ -141,10 +133,7 @@ pub async fn process_chat_sse<S>(
         let value: serde_json::Value = match serde_json::from_str(data) {
             Ok(val) => val,
             Err(err) => {
-                debug!(
-                    "Failed to parse ChatCompletions SSE event: {err}, data: {}",
-                    data
-                );
+                warn!("Skipping unparseable ChatCompletions SSE data: {err}, data: {data}");
                 continue;
             }
         };
@@ -188,55 +177,10 @@ pub async fn process_chat_sse<S>(
 
                 if let Some(tool_call_values) = delta.get("tool_calls").and_then(|c| c.as_array()) {
                     for tool_call in tool_call_values {
-                        let mut index = tool_call
-                            .get("index")
-                            .and_then(serde_json::Value::as_u64)
-                            .map(|i| i as usize);
-
-                        let mut call_id_for_lookup = None;
-                        if let Some(call_id) = tool_call.get("id").and_then(|i| i.as_str()) {
-                            call_id_for_lookup = Some(call_id.to_string());
-                            if let Some(existing) = tool_call_index_by_id.get(call_id) {
-                                index = Some(*existing);
-                            }
-                        }
-
-                        if index.is_none() && call_id_for_lookup.is_none() {
-                            index = last_tool_call_index;
-                        }
-
-                        let index = index.unwrap_or_else(|| {
-                            while tool_calls.contains_key(&next_tool_call_index) {
-                                next_tool_call_index += 1;
-                            }
-                            let idx = next_tool_call_index;
-                            next_tool_call_index += 1;
-                            idx
-                        });
-
-                        let call_state = tool_calls.entry(index).or_default();
-                        if tool_call_order_seen.insert(index) {
-                            tool_call_order.push(index);
-                        }
-
-                        if let Some(id) = tool_call.get("id").and_then(|i| i.as_str()) {
-                            call_state.id.get_or_insert_with(|| id.to_string());
-                            tool_call_index_by_id.entry(id.to_string()).or_insert(index);
-                        }
-
-                        if let Some(func) = tool_call.get("function") {
-                            if let Some(fname) = func.get("name").and_then(|n| n.as_str())
-                                && !fname.is_empty()
-                            {
-                                call_state.name.get_or_insert_with(|| fname.to_string());
-                            }
-                            if let Some(arguments) = func.get("arguments").and_then(|a| a.as_str())
-                            {
-                                call_state.arguments.push_str(arguments);
-                            }
+                        if let Err(err) = tool_calls.push_delta(tool_call) {
+                            let _ = tx_event.send(Err(err)).await;
+                            return;
                         }
-
-                        last_tool_call_index = Some(index);
                     }
                 }
             }
@@ -290,26 +234,7 @@ pub async fn process_chat_sse<S>(
                         .await;
                 }
 
-                for index in tool_call_order.drain(..) {
-                    let Some(state) = tool_calls.remove(&index) else {
-                        continue;
-                    };
-                    tool_call_order_seen.remove(&index);
-                    let ToolCallState {
-                        id,
-                        name,
-                        arguments,
-                    } = state;
-                    let Some(name) = name else {
-                        debug!("Skipping tool call at index {index} because name is missing");
-                        continue;
-                    };
-                    let item = ResponseItem::FunctionCall {
-                        id: None,
-                        name,
-                        arguments,
-                        call_id: id.unwrap_or_else(|| format!("tool-call-{index}")),
-                    };
+                for item in tool_calls.finish() {
                     let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
                 }
             }
@@ -405,6 +330,15 @@ mod tests {
         assert_matches!(&events[..], [ResponseEvent::Completed { .. }]);
     }
 
+    #[tokio::test]
+    async fn unparseable_chunks_are_skipped() {
+        let events = collect_events(
+            "event: message\ndata: {\"choices\": [\n\nevent: message\ndata: [DONE]\n\n",
+        )
+        .await;
+        assert_matches!(&events[..], [ResponseEvent::Completed { .. }]);
+    }
+
     async fn collect_events(body: &str) -> Vec<ResponseEvent> {
         let reader = ReaderStream::new(std::io::Cursor::new(body.to_string()))
             .map_err(|err| codex_client::TransportError::Network(err.to_string()));
@@ -1,6 +1,30 @@
+use std::future::Future;
+
+use tokio::sync::mpsc;
+use tracing::trace;
+
 pub mod chat;
 pub mod responses;
+mod tool_calls;
 
 pub use responses::process_sse;
 pub use responses::spawn_response_stream;
 pub use responses::stream_from_fixture;
+
+/// Runs `process` on a background task until it finishes or every receiver of
+/// `tx_event` (a clone of the sender `process` writes to) is gone. Stopping early drops the HTTP body, so an abandoned
+/// response (e.g. an interrupted turn) closes its connection right away
+/// instead of when the server sends its next event.
+pub(crate) fn spawn_until_receiver_dropped<T: Send + 'static>(
+    tx_event: mpsc::Sender<T>,
+    process: impl Future<Output = ()> + Send + 'static,
+) {
+    tokio::spawn(async move {
+        tokio::select! {
+            () = process => {}
+            () = tx_event.closed() => {
+                trace!("response stream dropped; closing connection");
+            }
+        }
+    });
+}
@@ -2,6 +2,7 @@ use crate::common::ResponseEvent;
 use crate::common::ResponseStream;
 use crate::error::ApiError;
 use crate::rate_limits::parse_rate_limit;
+use crate::sse::spawn_until_receiver_dropped;
 use crate::telemetry::SseTelemetry;
 use codex_client::ByteStream;
 use codex_client::StreamResponse;
@@ -23,6 +24,7 @@ use tokio::time::timeout;
 use tokio_util::io::ReaderStream;
 use tracing::debug;
 use tracing::trace;
+use tracing::warn;
 
 /// Streams SSE events from an on-disk fixture for tests.
 pub fn stream_from_fixture(
@@ -57,7 +59,7 @@ pub fn spawn_response_stream(
         .and_then(|v| v.to_str().ok())
         .map(ToString::to_string);
     let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent, ApiError>>(1600);
-    tokio::spawn(async move {
+    spawn_until_receiver_dropped(tx_event.clone(), async move {
         if let Some(snapshot) = rate_limits {
             let _ = tx_event.send(Ok(ResponseEvent::RateLimits(snapshot))).await;
         }
@@ -132,6 +134,7 @@ struct SseEvent {
     response: Option<Value>,
     item: Option<Value>,
     delta: Option<String>,
+    item_id: Option<String>,
     summary_index: Option<i64>,
     content_index: Option<i64>,
 }
@@ -160,21 +163,7 @@ pub async fn process_sse(
                 return;
             }
             Ok(None) => {
-                match response_completed.take() {
-                    Some(ResponseCompleted { id, usage }) => {
-                        let event = ResponseEvent::Completed {
-                            response_id: id,
-                            token_usage: usage.map(Into::into),
-                        };
-                        let _ = tx_event.send(Ok(event)).await;
-                    }
-                    None => {
-                        let error = response_error.unwrap_or(ApiError::Stream(
-                            "stream closed before response.completed".into(),
-                        ));
-                        let _ = tx_event.send(Err(error)).await;
-                    }
-                }
+                finish_stream(&tx_event, response_completed, response_error).await;
                 return;
             }
             Err(_) => {
@@ -188,10 +177,22 @@ pub async fn process_sse(
         let raw = sse.data.clone();
         trace!("SSE event: {raw}");
 
-        let event: SseEvent = match serde_json::from_str(&sse.data) {
+        let data = sse.data.trim();
+        if data.is_empty() {
+            continue;
+        }
+        // OpenAI-compatible servers end the stream with a sentinel, and some
+        // keep the connection open after it.
+        if data == "[DONE]" {
+            finish_stream(&tx_event, response_completed, response_error).await;
+            return;
+        }
+        // Lines that are not events, such as keep-alive text from a proxy,
+        // are skipped rather than failing the whole response.
+        let event: SseEvent = match serde_json::from_str(data) {
             Ok(event) => event,
             Err(e) => {
-                debug!("Failed to parse SSE event: {e}, data: {}", &sse.data);
+                warn!("Skipping unparseable SSE data: {e}, data: {data}");
                 continue;
             }
         };
@@ -199,9 +200,12 @@ pub async fn process_sse(
         match event.kind.as_str() {
             "response.output_item.done" => {
                 let Some(item_val) = event.item else { continue };
-                let Ok(item) = serde_json::from_value::<ResponseItem>(item_val) else {
-                    debug!("failed to parse ResponseItem from output_item.done");
-                    continue;
+                let item = match serde_json::from_value::<ResponseItem>(item_val) {
+                    Ok(item) => item,
+                    Err(e) => {
+                        warn!("Skipping unparseable item in response.output_item.done: {e}");
+                        continue;
+                    }
                 };
 
                 let event = ResponseEvent::OutputItemDone(item);
@@ -217,6 +221,14 @@ pub async fn process_sse(
                     }
                 }
             }
+            "response.function_call_arguments.delta" => {
+                if let (Some(delta), Some(item_id)) = (event.delta, event.item_id) {
+                    let event = ResponseEvent::ToolCallInputDelta { item_id, delta };
+                    if tx_event.send(Ok(event)).await.is_err() {
+                        return;
+                    }
+                }
+            }
             "response.reasoning_summary_text.delta" => {
                 if let (Some(delta), Some(summary_index)) = (event.delta, event.summary_index) {
                     let event = ResponseEvent::ReasoningSummaryDelta {
@@ -308,6 +320,30 @@ pub async fn process_sse(
     }
 }
 
+/// Reports the end of the stream: the completed response, or why the stream
+/// ended without one.
+async fn finish_stream(
+    tx_event: &mpsc::Sender<Result<ResponseEvent, ApiError>>,
+    response_completed: Option<ResponseCompleted>,
+    response_error: Option<ApiError>,
+) {
+    match response_completed {
+        Some(ResponseCompleted { id, usage }) => {
+            let event = ResponseEvent::Completed {
+                response_id: id,
+                token_usage: usage.map(Into::into),
+            };
+            let _ = tx_event.send(Ok(event)).await;
+        }
+        None => {
+            let error = response_error.unwrap_or(ApiError::Stream(
+                "stream closed before response.completed".into(),
+            ));
+            let _ = tx_event.send(Err(error)).await;
+        }
+    }
+}
+
 fn try_parse_retry_after(err: &Error) -> Option<Duration> {
     if err.code.as_deref() != Some("rate_limit_exceeded") {
         return None;
@@ -473,6 +509,30 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn parses_function_call_argument_deltas() {
+        let events = run_sse(vec![
+            json!({
+                "type": "response.function_call_arguments.delta",
+                "item_id": "fc_1",
+                "output_index": 0,
+                "delta": "{\"file_path\":"
+            }),
+            json!({
+                "type": "response.completed",
+                "response": { "id": "resp1" }
+            }),
+        ])
+        .await;
+
+        assert_eq!(events.len(), 2);
+        assert_matches!(
+            &events[0],
+            ResponseEvent::ToolCallInputDelta { item_id, delta }
+                if item_id == "fc_1" && delta == "{\"file_path\":"
+        );
+    }
+
     #[tokio::test]
     async fn error_when_missing_completed() {
         let item1 = json!({
@@ -501,6 +561,44 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn unparseable_data_is_skipped_and_done_ends_the_stream() {
+        let item1 = json!({
+            "type": "response.output_item.done",
+            "item": {
+                "type": "message",
+                "role": "assistant",
+                "content": [{"type": "output_text", "text": "Hello"}]
+            }
+        })
+        .to_string();
+
+        let sse1 = format!("event: response.output_item.done\ndata: {item1}\n\n");
+        let sse2 = "event: response.output_item.done\ndata: {\"type\": \"response.out\n\n";
+        let completed = json!({
+            "type": "response.completed",
+            "response": {"id": "resp1"}
+        });
+        let sse3 = format!("event: response.completed\ndata: {completed}\n\n");
+        // Nothing after the sentinel is read.
+        let sse4 = "data: [DONE]\n\nevent: response.output_item.done\ndata: {}\n\n";
+
+        let events = collect_events(&[
+            sse1.as_bytes(),
+            sse2.as_bytes(),
+            sse3.as_bytes(),
+            sse4.as_bytes(),
+        ])
+        .await;
+
+        assert_eq!(events.len(), 2);
+        assert_matches!(events[0], Ok(ResponseEvent::OutputItemDone(_)));
+        assert_matches!(
+            &events[1],
+            Ok(ResponseEvent::Completed { response_id, .. }) if response_id == "resp1"
+        );
+    }
+
     #[tokio::test]
     async fn error_when_error_event() {
         let raw_error = r#"{"type":"response.failed","sequence_number":3,"response":{"id":"resp_689bcf18d7f08194bf3440ba62fe05d803fee0cdac429894","object":"response","created_at":1755041560,"status":"failed","background":false,"error":{"code":"rate_limit_exceeded","message":"Rate limit reached for gpt-5.1 in organization org-AAA on tokens per min (TPM): Limit 30000, Used 22999, Requested 12528. Please try again in 11.054s. Visit https://platform.openai.com/account/rate-limits to learn more."}, "usage":null,"user":null,"metadata":{}}}"#;
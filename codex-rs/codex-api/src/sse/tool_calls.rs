@@ -0,0 +1,207 @@
+//! Incremental assembly of Chat Completions tool calls.
+//!
+//! The Chat Completions API streams each tool call as a series of deltas: the
+//! first usually carries the call id and function name, later ones append
+//! fragments of the JSON arguments. [`ToolCallAssembler`] appends each fragment
+//! as it arrives and caps the bytes buffered for a response, so an oversized
+//! payload fails the stream (and is retried) instead of growing without bound.
+
+use std::collections::HashMap;
+
+use codex_protocol::models::ResponseItem;
+use serde_json::Value;
+use tracing::debug;
+
+use crate::error::ApiError;
+
+/// Upper bound on the tool-call argument bytes buffered for one response.
+pub(crate) const MAX_BUFFERED_TOOL_CALL_BYTES: usize = 16 * 1024 * 1024;
+
+#[derive(Default, Debug)]
+struct ToolCallState {
+    id: Option<String>,
+    name: Option<String>,
+    arguments: String,
+}
+
+#[derive(Debug)]
+pub(crate) struct ToolCallAssembler {
+    calls: HashMap<usize, ToolCallState>,
+    /// Indices in the order their first delta arrived.
+    order: Vec<usize>,
+    index_by_id: HashMap<String, usize>,
+    next_index: usize,
+    last_index: Option<usize>,
+    buffered_bytes: usize,
+    max_buffered_bytes: usize,
+}
+
+impl Default for ToolCallAssembler {
+    fn default() -> Self {
+        Self::with_limit(MAX_BUFFERED_TOOL_CALL_BYTES)
+    }
+}
+
+impl ToolCallAssembler {
+    pub(crate) fn with_limit(max_buffered_bytes: usize) -> Self {
+        Self {
+            calls: HashMap::new(),
+            order: Vec::new(),
+            index_by_id: HashMap::new(),
+            next_index: 0,
+            last_index: None,
+            buffered_bytes: 0,
+            max_buffered_bytes,
+        }
+    }
+
+    /// Applies one entry of a `delta.tool_calls` array.
+    ///
+    /// Deltas are matched to a call by `index`, then by `id`, and otherwise
+    /// continue the previous call. Fails with [`ApiError::MalformedStream`]
+    /// once the buffered arguments exceed the limit.
+    pub(crate) fn push_delta(&mut self, tool_call: &Value) -> Result<(), ApiError> {
+        let mut index = tool_call
+            .get("index")
+            .and_then(Value::as_u64)
+            .map(|i| i as usize);
+
+        let call_id = tool_call.get("id").and_then(Value::as_str);
+        if let Some(call_id) = call_id
+            && let Some(existing) = self.index_by_id.get(call_id)
+        {
+            index = Some(*existing);
+        }
+
+        if index.is_none() && call_id.is_none() {
+            index = self.last_index;
+        }
+
+        let index = index.unwrap_or_else(|| {
+            while self.calls.contains_key(&self.next_index) {
+                self.next_index += 1;
+            }
+            let idx = self.next_index;
+            self.next_index += 1;
+            idx
+        });
+
+        if !self.calls.contains_key(&index) {
+            self.order.push(index);
+        }
+        let call_state = self.calls.entry(index).or_default();
+
+        if let Some(id) = call_id {
+            call_state.id.get_or_insert_with(|| id.to_string());
+            self.index_by_id.entry(id.to_string()).or_insert(index);
+        }
+
+        if let Some(func) = tool_call.get("function") {
+            if let Some(fname) = func.get("name").and_then(Value::as_str)
+                && !fname.is_empty()
+            {
+                call_state.name.get_or_insert_with(|| fname.to_string());
+            }
+            if let Some(arguments) = func.get("arguments").and_then(Value::as_str) {
+                self.buffered_bytes += arguments.len();
+                if self.buffered_bytes > self.max_buffered_bytes {
+                    return Err(ApiError::MalformedStream(format!(
+                        "tool call arguments exceeded {} bytes",
+                        self.max_buffered_bytes
+                    )));
+                }
+                call_state.arguments.push_str(arguments);
+            }
+        }
+
+        self.last_index = Some(index);
+        Ok(())
+    }
+
+    /// Drains the assembled calls in the order they started. Calls that never
+    /// received a function name are dropped.
+    pub(crate) fn finish(&mut self) -> Vec<ResponseItem> {
+        let mut items = Vec::with_capacity(self.order.len());
+        for index in self.order.drain(..) {
+            let Some(state) = self.calls.remove(&index) else {
+                continue;
+            };
+            self.buffered_bytes = self.buffered_bytes.saturating_sub(state.arguments.len());
+            let ToolCallState {
+                id,
+                name,
+                arguments,
+            } = state;
+            let Some(name) = name else {
+                debug!("Skipping tool call at index {index} because name is missing");
+                continue;
+            };
+            items.push(ResponseItem::FunctionCall {
+                id: None,
+                name,
+                arguments,
+                call_id: id.unwrap_or_else(|| format!("tool-call-{index}")),
+            });
+        }
+        items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn appends_argument_fragments_in_order() {
+        let mut assembler = ToolCallAssembler::default();
+        for delta in [
+            json!({"id": "call_a", "index": 0, "function": {"name": "do_a"}}),
+            json!({"index": 0, "function": {"arguments": "{\"foo\":"}}),
+            json!({"function": {"arguments": "1}"}}),
+        ] {
+            assembler.push_delta(&delta).expect("within limit");
+        }
+
+        assert_eq!(
+            assembler.finish(),
+            vec![ResponseItem::FunctionCall {
+                id: None,
+                name: "do_a".to_string(),
+                arguments: "{\"foo\":1}".to_string(),
+                call_id: "call_a".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn rejects_arguments_over_the_limit() {
+        let mut assembler = ToolCallAssembler::with_limit(8);
+        assembler
+            .push_delta(
+                &json!({"id": "call_a", "function": {"name": "do_a", "arguments": "{\"a\":"}}),
+            )
+            .expect("within limit");
+
+        let err = assembler
+            .push_delta(&json!({"function": {"arguments": "\"too long\"}"}}))
+            .expect_err("over limit");
+
+        assert_matches!(err, ApiError::MalformedStream(_));
+    }
+
+    #[test]
+    fn finish_releases_the_buffer_budget() {
+        let mut assembler = ToolCallAssembler::with_limit(3);
+        assembler
+            .push_delta(&json!({"id": "a", "function": {"name": "f", "arguments": "{}"}}))
+            .expect("within limit");
+        assert_eq!(assembler.finish().len(), 1);
+
+        assembler
+            .push_delta(&json!({"id": "b", "function": {"name": "f", "arguments": "{}"}}))
+            .expect("budget released by finish");
+    }
+}
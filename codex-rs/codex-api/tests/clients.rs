@@ -134,6 +134,7 @@ fn provider(name: &str, wire: WireApi) -> Provider {
             retry_transport: true,
         },
         stream_idle_timeout: Duration::from_millis(10),
+        bandwidth_limit: None,
     }
 }
 
@@ -312,6 +313,7 @@ async fn streaming_client_retries_on_transport_error() -> Result<()> {
         tools: Vec::<Value>::new(),
         parallel_tool_calls: false,
         output_schema: None,
+        max_output_tokens: None,
     };
 
     let options = ResponsesOptions::default();
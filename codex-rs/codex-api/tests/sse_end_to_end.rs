@@ -76,6 +76,7 @@ fn provider(name: &str, wire: WireApi) -> Provider {
             retry_transport: true,
         },
         stream_idle_timeout: Duration::from_millis(50),
+        bandwidth_limit: None,
     }
 }
 
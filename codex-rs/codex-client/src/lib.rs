@@ -4,6 +4,7 @@ mod request;
 mod retry;
 mod sse;
 mod telemetry;
+mod throttle;
 mod transport;
 
 pub use crate::default_client::CodexHttpClient;
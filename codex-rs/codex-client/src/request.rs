@@ -20,6 +20,8 @@ pub struct Request {
     pub body: Option<Value>,
     pub compression: RequestCompression,
     pub timeout: Option<Duration>,
+    /// Caps the request body and the response at this many bytes per second.
+    pub bandwidth_limit: Option<u64>,
 }
 
 impl Request {
@@ -31,6 +33,7 @@ impl Request {
             body: None,
             compression: RequestCompression::None,
             timeout: None,
+            bandwidth_limit: None,
         }
     }
 
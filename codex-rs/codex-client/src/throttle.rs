@@ -0,0 +1,65 @@
+use bytes::Bytes;
+use futures::Stream;
+use futures::StreamExt;
+use futures::stream;
+use tokio::time::Duration;
+use tokio::time::Instant;
+use tokio::time::sleep_until;
+
+/// Size of the pieces a throttled request body is sent in.
+const UPLOAD_CHUNK_BYTES: usize = 16 * 1024;
+
+/// Paces `stream` so that it yields at most `bytes_per_sec` on average.
+///
+/// Reading a response slower than the server sends it lets TCP flow control
+/// slow the transfer itself down, so this caps downloads as well as uploads.
+pub(crate) fn throttle<S, E>(stream: S, bytes_per_sec: u64) -> impl Stream<Item = Result<Bytes, E>>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+{
+    let bytes_per_sec = bytes_per_sec.max(1) as f64;
+    let start = Instant::now();
+    stream::unfold(
+        (stream, 0u64),
+        move |(mut stream, transferred)| async move {
+            let item = stream.next().await?;
+            let transferred = match &item {
+                Ok(chunk) => transferred + chunk.len() as u64,
+                Err(_) => transferred,
+            };
+            sleep_until(start + Duration::from_secs_f64(transferred as f64 / bytes_per_sec)).await;
+            Some((item, (stream, transferred)))
+        },
+    )
+}
+
+/// Request body that is sent at most `bytes_per_sec`.
+pub(crate) fn throttled_body(body: Vec<u8>, bytes_per_sec: u64) -> reqwest::Body {
+    let body = Bytes::from(body);
+    let chunks: Vec<Result<Bytes, std::io::Error>> = (0..body.len())
+        .step_by(UPLOAD_CHUNK_BYTES)
+        .map(|offset| Ok(body.slice(offset..(offset + UPLOAD_CHUNK_BYTES).min(body.len()))))
+        .collect();
+    reqwest::Body::wrap_stream(throttle(stream::iter(chunks), bytes_per_sec))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn throttled_stream_keeps_bytes_and_paces_them() {
+        let chunks: Vec<Result<Bytes, ()>> =
+            (0..4).map(|_| Ok(Bytes::from(vec![7u8; 25]))).collect();
+        let started = std::time::Instant::now();
+
+        let received: Vec<Result<Bytes, ()>> = throttle(stream::iter(chunks), 500).collect().await;
+
+        assert!(started.elapsed() >= std::time::Duration::from_millis(200));
+        let bytes: Vec<u8> = received
+            .into_iter()
+            .flat_map(|chunk| chunk.unwrap_or_default().to_vec())
+            .collect();
+        assert_eq!(bytes, vec![7u8; 100]);
+    }
+}
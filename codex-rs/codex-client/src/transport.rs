@@ -4,9 +4,12 @@ use crate::error::TransportError;
 use crate::request::Request;
 use crate::request::RequestCompression;
 use crate::request::Response;
+use crate::throttle::throttle;
+use crate::throttle::throttled_body;
 use async_trait::async_trait;
 use bytes::Bytes;
 use futures::StreamExt;
+use futures::TryStreamExt;
 use futures::stream::BoxStream;
 use http::HeaderMap;
 use http::Method;
@@ -49,6 +52,7 @@ impl ReqwestTransport {
             body,
             compression,
             timeout,
+            bandwidth_limit,
         } = req;
 
         let mut builder = self.client.request(
@@ -100,7 +104,23 @@ impl ReqwestTransport {
                     "Compressed request body with zstd"
                 );
 
+                let compressed = match bandwidth_limit {
+                    Some(bytes_per_sec) => throttled_body(compressed, bytes_per_sec),
+                    None => compressed.into(),
+                };
                 builder = builder.headers(headers).body(compressed);
+            } else if let Some(bytes_per_sec) = bandwidth_limit {
+                let json = serde_json::to_vec(&body)
+                    .map_err(|err| TransportError::Build(err.to_string()))?;
+                if !headers.contains_key(http::header::CONTENT_TYPE) {
+                    headers.insert(
+                        http::header::CONTENT_TYPE,
+                        http::HeaderValue::from_static("application/json"),
+                    );
+                }
+                builder = builder
+                    .headers(headers)
+                    .body(throttled_body(json, bytes_per_sec));
             } else {
                 builder = builder.headers(headers).json(&body);
             }
@@ -132,11 +152,21 @@ impl HttpTransport for ReqwestTransport {
         }
 
         let url = req.url.clone();
+        let bandwidth_limit = req.bandwidth_limit;
         let builder = self.build(req)?;
         let resp = builder.send().await.map_err(Self::map_error)?;
         let status = resp.status();
         let headers = resp.headers().clone();
-        let bytes = resp.bytes().await.map_err(Self::map_error)?;
+        let bytes = match bandwidth_limit {
+            Some(bytes_per_sec) => {
+                let chunks: Vec<Bytes> = throttle(resp.bytes_stream().boxed(), bytes_per_sec)
+                    .map(|chunk| chunk.map_err(Self::map_error))
+                    .try_collect()
+                    .await?;
+                Bytes::from(chunks.concat())
+            }
+            None => resp.bytes().await.map_err(Self::map_error)?,
+        };
         if !status.is_success() {
             let body = String::from_utf8(bytes.to_vec()).ok();
             return Err(TransportError::Http {
@@ -164,6 +194,7 @@ impl HttpTransport for ReqwestTransport {
         }
 
         let url = req.url.clone();
+        let bandwidth_limit = req.bandwidth_limit;
         let builder = self.build(req)?;
         let resp = builder.send().await.map_err(Self::map_error)?;
         let status = resp.status();
@@ -177,13 +208,18 @@ impl HttpTransport for ReqwestTransport {
                 body,
             });
         }
-        let stream = resp
-            .bytes_stream()
-            .map(|result| result.map_err(Self::map_error));
+        let stream: ByteStream = Box::pin(
+            resp.bytes_stream()
+                .map(|result| result.map_err(Self::map_error)),
+        );
+        let bytes: ByteStream = match bandwidth_limit {
+            Some(bytes_per_sec) => Box::pin(throttle(stream, bytes_per_sec)),
+            None => stream,
+        };
         Ok(StreamResponse {
             status,
             headers,
-            bytes: Box::pin(stream),
+            bytes,
         })
     }
 }
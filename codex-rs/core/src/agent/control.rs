@@ -154,6 +154,10 @@ mod tests {
     async fn on_event_updates_status_from_task_complete() {
         let status = agent_status_from_event(&EventMsg::TurnComplete(TurnCompleteEvent {
             last_agent_message: Some("done".to_string()),
+            timing: None,
+            unified_diff: None,
+            verification: None,
+            context_dropped: Vec::new(),
         }));
         let expected = AgentStatus::Completed(Some("done".to_string()));
         assert_eq!(status, Some(expected));
@@ -174,6 +178,7 @@ mod tests {
     async fn on_event_updates_status_from_turn_aborted() {
         let status = agent_status_from_event(&EventMsg::TurnAborted(TurnAbortedEvent {
             reason: TurnAbortReason::Interrupted,
+            cancelled: Vec::new(),
         }));
 
         let expected = AgentStatus::Errored("Interrupted".to_string());
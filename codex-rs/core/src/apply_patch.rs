@@ -1,12 +1,28 @@
+use crate::approval_rules::RuleDecision;
+use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::config::types::PatchValidationMode;
+use crate::exec::SandboxType;
 use crate::function_tool::FunctionCallError;
+use crate::patch_validation::SyntaxError;
+use crate::patch_validation::syntax_errors_feedback;
+use crate::patch_validation::validate_patch;
+use crate::protocol::AskForApproval;
 use crate::protocol::FileChange;
+use crate::protocol::RejectedHunk;
+use crate::protocol::SandboxPolicy;
+use crate::redaction::introduced_placeholder;
 use crate::safety::SafetyCheck;
 use crate::safety::assess_patch_safety;
+use crate::safety::get_platform_sandbox;
 use crate::tools::sandboxing::ExecApprovalRequirement;
 use codex_apply_patch::ApplyPatchAction;
 use codex_apply_patch::ApplyPatchFileChange;
+use serde_json::json;
+use similar::ChangeTag;
+use similar::TextDiff;
 use std::collections::HashMap;
+use std::path::Path;
 use std::path::PathBuf;
 
 pub const CODEX_APPLY_PATCH_ARG1: &str = "--codex-run-as-apply-patch";
@@ -31,18 +47,65 @@ pub(crate) struct ApplyPatchExec {
     pub(crate) action: ApplyPatchAction,
     pub(crate) auto_approved: bool,
     pub(crate) exec_approval_requirement: ExecApprovalRequirement,
+    /// Errors found by `[patch_validation]` in `warn` mode, reported to the
+    /// model once the patch is applied.
+    pub(crate) syntax_errors: Vec<SyntaxError>,
 }
 
 pub(crate) async fn apply_patch(
+    sess: &Session,
     turn_context: &TurnContext,
     action: ApplyPatchAction,
 ) -> InternalApplyPatchInvocation {
-    match assess_patch_safety(
-        &action,
-        turn_context.approval_policy,
-        &turn_context.sandbox_policy,
-        &turn_context.cwd,
-    ) {
+    if let Some(rejection) = placeholder_rejection(&action) {
+        return InternalApplyPatchInvocation::Output(Err(FunctionCallError::RespondToModel(
+            rejection,
+        )));
+    }
+    let validation = turn_context.client.config().patch_validation.mode;
+    let syntax_errors = match validation {
+        PatchValidationMode::Off => Vec::new(),
+        PatchValidationMode::Warn | PatchValidationMode::Reject => validate_patch(&action),
+    };
+    if validation == PatchValidationMode::Reject && !syntax_errors.is_empty() {
+        return InternalApplyPatchInvocation::Output(Err(FunctionCallError::RespondToModel(
+            format!(
+                "patch rejected: it would leave these files with syntax errors. Nothing was applied; fix the patch and try again.\n{}",
+                syntax_errors_feedback(&syntax_errors)
+            ),
+        )));
+    }
+
+    // Approval rules on the written paths take precedence over the approval
+    // policy; `allow` only skips the prompt, the sandbox still applies (see
+    // `allowed_by_rule`).
+    let verdict = sess
+        .services
+        .approval_rules
+        .evaluate_write(patch_paths(&action), &turn_context.cwd);
+    let rule_reason = verdict.reason();
+    let safety = match verdict.decision() {
+        Some(RuleDecision::Allow) => allowed_by_rule(&turn_context.sandbox_policy),
+        Some(RuleDecision::Ask) if turn_context.approval_policy != AskForApproval::Never => {
+            SafetyCheck::AskUser
+        }
+        Some(RuleDecision::Ask) => SafetyCheck::Reject {
+            reason: format!(
+                "{}, but AskForApproval is set to Never",
+                rule_reason.clone().unwrap_or_default()
+            ),
+        },
+        Some(RuleDecision::Deny) => SafetyCheck::Reject {
+            reason: rule_reason.clone().unwrap_or_default(),
+        },
+        None => assess_patch_safety(
+            &action,
+            turn_context.approval_policy,
+            &turn_context.sandbox_policy,
+            &turn_context.cwd,
+        ),
+    };
+    match safety {
         SafetyCheck::AutoApprove {
             user_explicitly_approved,
             ..
@@ -53,6 +116,7 @@ pub(crate) async fn apply_patch(
                 bypass_sandbox: false,
                 proposed_execpolicy_amendment: None,
             },
+            syntax_errors,
         }),
         SafetyCheck::AskUser => {
             // Delegate the approval prompt (including cached approvals) to the
@@ -62,9 +126,10 @@ pub(crate) async fn apply_patch(
                 action,
                 auto_approved: false,
                 exec_approval_requirement: ExecApprovalRequirement::NeedsApproval {
-                    reason: None,
+                    reason: rule_reason,
                     proposed_execpolicy_amendment: None,
                 },
+                syntax_errors,
             })
         }
         SafetyCheck::Reject { reason } => InternalApplyPatchInvocation::Output(Err(
@@ -73,6 +138,69 @@ pub(crate) async fn apply_patch(
     }
 }
 
+/// The safety check for a patch an `allow` rule approved. The rule stands in
+/// for the prompt, not for the sandbox: the patch runs in the platform
+/// sandbox unless the policy opts out of sandboxing, and when no sandbox can
+/// be enforced the user is still asked, as [`assess_patch_safety`] does.
+fn allowed_by_rule(sandbox_policy: &SandboxPolicy) -> SafetyCheck {
+    if matches!(
+        sandbox_policy,
+        SandboxPolicy::DangerFullAccess | SandboxPolicy::ExternalSandbox { .. }
+    ) {
+        return SafetyCheck::AutoApprove {
+            sandbox_type: SandboxType::None,
+            user_explicitly_approved: false,
+        };
+    }
+    match get_platform_sandbox() {
+        Some(sandbox_type) => SafetyCheck::AutoApprove {
+            sandbox_type,
+            user_explicitly_approved: false,
+        },
+        None => SafetyCheck::AskUser,
+    }
+}
+
+/// Redaction never touches what a patch writes, so a patch that would write a
+/// `[REDACTED:<rule>]` placeholder the model copied from a redacted output
+/// is refused rather than let it replace the secret on disk.
+fn placeholder_rejection(action: &ApplyPatchAction) -> Option<String> {
+    for (path, change) in action.changes() {
+        let (original, updated) = match change {
+            ApplyPatchFileChange::Add { content } => (String::new(), content),
+            ApplyPatchFileChange::Update { new_content, .. } => (
+                std::fs::read_to_string(path).unwrap_or_default(),
+                new_content,
+            ),
+            ApplyPatchFileChange::Delete { .. } => continue,
+        };
+        if let Some(placeholder) = introduced_placeholder(&original, updated) {
+            return Some(format!(
+                "patch rejected: it would write `{placeholder}` into {}. That placeholder stands for a secret that was hidden from you, not for the file's contents; leave those lines out of the patch. Nothing was applied.",
+                path.display()
+            ));
+        }
+    }
+    None
+}
+
+/// Every path the patch writes, including move destinations.
+fn patch_paths(action: &ApplyPatchAction) -> Vec<&Path> {
+    let mut paths = Vec::new();
+    for (path, change) in action.changes() {
+        paths.push(path.as_path());
+        if let ApplyPatchFileChange::Update {
+            move_path: Some(dest),
+            ..
+        } = change
+        {
+            paths.push(dest.as_path());
+        }
+    }
+    paths.sort();
+    paths
+}
+
 pub(crate) fn convert_apply_patch_to_protocol(
     action: &ApplyPatchAction,
 ) -> HashMap<PathBuf, FileChange> {
@@ -100,6 +228,231 @@ pub(crate) fn convert_apply_patch_to_protocol(
     result
 }
 
+/// Rebuilds the patch for `action` without the hunks the user rejected, or
+/// returns `None` when nothing is left to apply.
+///
+/// Hunks are numbered as in the [`FileChange`]s sent with the approval
+/// request. Accepted update hunks are replayed onto the file as it is on disk
+/// and re-expressed as `apply_patch` chunks; a moved file whose hunks are all
+/// rejected stays where it is.
+pub(crate) fn patch_without_rejected_hunks(
+    action: &ApplyPatchAction,
+    rejected: &[RejectedHunk],
+) -> Result<Option<String>, String> {
+    let mut paths: Vec<&PathBuf> = action.changes().keys().collect();
+    paths.sort();
+
+    let mut sections = Vec::new();
+    for path in paths {
+        let Some(change) = action.changes().get(path) else {
+            continue;
+        };
+        let is_rejected = |index: usize| {
+            rejected
+                .iter()
+                .any(|hunk| hunk.path == *path && hunk.hunk_index == index)
+        };
+        match change {
+            ApplyPatchFileChange::Add { content } => {
+                if !is_rejected(0) {
+                    let mut section = format!("*** Add File: {}\n", path.display());
+                    for line in content.lines() {
+                        section.push_str(&format!("+{line}\n"));
+                    }
+                    sections.push(section);
+                }
+            }
+            ApplyPatchFileChange::Delete { .. } => {
+                if !is_rejected(0) {
+                    sections.push(format!("*** Delete File: {}\n", path.display()));
+                }
+            }
+            ApplyPatchFileChange::Update {
+                unified_diff,
+                move_path,
+                ..
+            } => {
+                let accepted: Vec<UnifiedHunk> = parse_unified_hunks(unified_diff)
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(index, _)| !is_rejected(*index))
+                    .map(|(_, hunk)| hunk)
+                    .collect();
+                if accepted.is_empty() {
+                    continue;
+                }
+                let original = std::fs::read_to_string(path)
+                    .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+                let updated = apply_unified_hunks(&original, &accepted)
+                    .map_err(|err| format!("{}: {err}", path.display()))?;
+                let chunks = patch_chunks(&original, &updated);
+                if chunks.is_empty() {
+                    continue;
+                }
+                let mut section = format!("*** Update File: {}\n", path.display());
+                if let Some(dest) = move_path {
+                    section.push_str(&format!("*** Move to: {}\n", dest.display()));
+                }
+                section.push_str(&chunks);
+                sections.push(section);
+            }
+        }
+    }
+
+    if sections.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(format!(
+        "*** Begin Patch\n{}*** End Patch\n",
+        sections.concat()
+    )))
+}
+
+/// Describes the rejected hunks for the model as a JSON list so it can revise
+/// its approach instead of re-proposing the same edits.
+pub(crate) fn rejected_hunks_feedback(
+    action: &ApplyPatchAction,
+    rejected: &[RejectedHunk],
+) -> String {
+    let entries: Vec<serde_json::Value> = rejected
+        .iter()
+        .map(|hunk| {
+            let diff = match action.changes().get(&hunk.path) {
+                Some(ApplyPatchFileChange::Add { content }) => content
+                    .lines()
+                    .map(|line| format!("+{line}\n"))
+                    .collect::<String>(),
+                Some(ApplyPatchFileChange::Delete { .. }) => "(delete file)".to_string(),
+                Some(ApplyPatchFileChange::Update { unified_diff, .. }) => {
+                    parse_unified_hunks(unified_diff)
+                        .get(hunk.hunk_index)
+                        .map(UnifiedHunk::text)
+                        .unwrap_or_default()
+                }
+                None => String::new(),
+            };
+            let path = hunk
+                .path
+                .strip_prefix(&action.cwd)
+                .unwrap_or(&hunk.path)
+                .display()
+                .to_string();
+            json!({
+                "path": path,
+                "hunk_index": hunk.hunk_index,
+                "diff": diff,
+            })
+        })
+        .collect();
+    let entries = serde_json::to_string_pretty(&entries).unwrap_or_default();
+    format!("The user rejected these hunks, so they were not applied:\n{entries}")
+}
+
+struct UnifiedHunk<'a> {
+    header: &'a str,
+    /// 1-based first line of the hunk in the original file (the line before
+    /// the insertion point when the hunk removes nothing).
+    old_start: usize,
+    lines: Vec<&'a str>,
+}
+
+impl UnifiedHunk<'_> {
+    fn text(&self) -> String {
+        let mut text = format!("{}\n", self.header);
+        for line in &self.lines {
+            text.push_str(line);
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Lines of the original file the hunk covers.
+    fn old_lines(&self) -> Vec<&str> {
+        self.lines
+            .iter()
+            .filter_map(|line| line.strip_prefix(' ').or_else(|| line.strip_prefix('-')))
+            .collect()
+    }
+
+    /// Lines that replace [`Self::old_lines`].
+    fn new_lines(&self) -> impl Iterator<Item = &str> {
+        self.lines
+            .iter()
+            .filter_map(|line| line.strip_prefix(' ').or_else(|| line.strip_prefix('+')))
+    }
+}
+
+fn parse_unified_hunks(unified_diff: &str) -> Vec<UnifiedHunk<'_>> {
+    let mut hunks: Vec<UnifiedHunk> = Vec::new();
+    for line in unified_diff.lines() {
+        if line.starts_with("@@") {
+            let old_start = line
+                .split_whitespace()
+                .nth(1)
+                .and_then(|range| range.strip_prefix('-'))
+                .and_then(|range| range.split(',').next())
+                .and_then(|start| start.parse().ok())
+                .unwrap_or(0);
+            hunks.push(UnifiedHunk {
+                header: line,
+                old_start,
+                lines: Vec::new(),
+            });
+        } else if let Some(hunk) = hunks.last_mut() {
+            hunk.lines.push(line);
+        }
+    }
+    hunks
+}
+
+fn apply_unified_hunks(original: &str, hunks: &[UnifiedHunk]) -> Result<String, String> {
+    let original_lines: Vec<&str> = original.lines().collect();
+    let mut out: Vec<&str> = Vec::new();
+    let mut cursor = 0;
+    for hunk in hunks {
+        let old = hunk.old_lines();
+        let start = if old.is_empty() {
+            hunk.old_start
+        } else {
+            hunk.old_start.saturating_sub(1)
+        };
+        if start < cursor || original_lines.get(start..start + old.len()) != Some(old.as_slice()) {
+            return Err(format!(
+                "hunk `{}` no longer matches the file on disk",
+                hunk.header
+            ));
+        }
+        out.extend_from_slice(&original_lines[cursor..start]);
+        out.extend(hunk.new_lines());
+        cursor = start + old.len();
+    }
+    out.extend_from_slice(&original_lines[cursor..]);
+    let mut updated = out.join("\n");
+    updated.push('\n');
+    Ok(updated)
+}
+
+/// Expresses `original` -> `updated` as `apply_patch` update chunks.
+fn patch_chunks(original: &str, updated: &str) -> String {
+    let diff = TextDiff::from_lines(original, updated);
+    let mut chunks = String::new();
+    for group in diff.grouped_ops(3) {
+        chunks.push_str("@@\n");
+        for op in &group {
+            for change in diff.iter_changes(op) {
+                let sign = match change.tag() {
+                    ChangeTag::Equal => ' ',
+                    ChangeTag::Delete => '-',
+                    ChangeTag::Insert => '+',
+                };
+                let line = change.value().trim_end_matches(['\n', '\r']);
+                chunks.push_str(&format!("{sign}{line}\n"));
+            }
+        }
+    }
+    chunks
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,4 +476,110 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn allow_rules_keep_the_sandbox() {
+        let expected = match get_platform_sandbox() {
+            Some(sandbox_type) => SafetyCheck::AutoApprove {
+                sandbox_type,
+                user_explicitly_approved: false,
+            },
+            None => SafetyCheck::AskUser,
+        };
+        assert_eq!(
+            allowed_by_rule(&SandboxPolicy::new_workspace_write_policy()),
+            expected
+        );
+        assert_eq!(
+            allowed_by_rule(&SandboxPolicy::DangerFullAccess),
+            SafetyCheck::AutoApprove {
+                sandbox_type: SandboxType::None,
+                user_explicitly_approved: false,
+            }
+        );
+    }
+
+    #[test]
+    fn patches_writing_redaction_placeholders_are_refused() {
+        let tmp = tempdir().expect("tmp");
+        std::fs::write(
+            tmp.path().join(".env"),
+            "USER=app\nPASSWORD=hunter22-prod\n",
+        )
+        .expect("write");
+        let action = verified_action(
+            tmp.path(),
+            "*** Begin Patch\n*** Update File: .env\n@@\n-USER=app\n-PASSWORD=hunter22-prod\n+USER=admin\n+PASSWORD=[REDACTED:secret_assignment]\n*** End Patch",
+        );
+
+        let rejection = placeholder_rejection(&action).expect("patch should be refused");
+
+        assert!(
+            rejection.contains("`[REDACTED:secret_assignment]`"),
+            "{rejection}"
+        );
+        let harmless = verified_action(
+            tmp.path(),
+            "*** Begin Patch\n*** Update File: .env\n@@\n-USER=app\n+USER=admin\n*** End Patch",
+        );
+        assert_eq!(placeholder_rejection(&harmless), None);
+    }
+
+    fn verified_action(cwd: &std::path::Path, patch: &str) -> ApplyPatchAction {
+        let argv = vec!["apply_patch".to_string(), patch.to_string()];
+        match codex_apply_patch::maybe_parse_apply_patch_verified(&argv, cwd) {
+            codex_apply_patch::MaybeApplyPatchVerified::Body(action) => action,
+            other => panic!("expected a verified patch, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn rejected_hunks_are_left_out_of_the_applied_patch() {
+        let tmp = tempdir().expect("tmp");
+        let path = tmp.path().join("notes.txt");
+        let original: String = (1..=12).map(|n| format!("line {n}\n")).collect();
+        std::fs::write(&path, &original).expect("write");
+        let action = verified_action(
+            tmp.path(),
+            "*** Begin Patch\n*** Update File: notes.txt\n@@\n line 1\n-line 2\n+line two\n@@\n line 10\n-line 11\n+line eleven\n*** End Patch",
+        );
+        let rejected = vec![RejectedHunk {
+            path: path.clone(),
+            hunk_index: 1,
+        }];
+
+        let patch = patch_without_rejected_hunks(&action, &rejected)
+            .expect("filter")
+            .expect("hunks left");
+        codex_apply_patch::apply_patch(&patch, &mut Vec::new(), &mut Vec::new()).expect("apply");
+
+        assert_eq!(
+            std::fs::read_to_string(&path).expect("read"),
+            original.replace("line 2\n", "line two\n")
+        );
+        let feedback = rejected_hunks_feedback(&action, &rejected);
+        assert!(feedback.contains("\"path\": \"notes.txt\""), "{feedback}");
+        assert!(feedback.contains("+line eleven"), "{feedback}");
+    }
+
+    #[test]
+    fn rejecting_every_hunk_leaves_nothing_to_apply() {
+        let tmp = tempdir().expect("tmp");
+        let path = tmp.path().join("new.txt");
+        let action = verified_action(
+            tmp.path(),
+            "*** Begin Patch\n*** Add File: new.txt\n+hello\n*** End Patch",
+        );
+
+        assert_eq!(
+            patch_without_rejected_hunks(
+                &action,
+                &[RejectedHunk {
+                    path,
+                    hunk_index: 0,
+                }]
+            ),
+            Ok(None)
+        );
+    }
 }
@@ -0,0 +1,580 @@
+//! Per-action approval rules.
+//!
+//! Rules are written one per line as `<allow|ask|deny> <exec|write> <pattern>`
+//! and come from the `approval_rules` config key and from `approval.policy`
+//! files in `$CODEX_HOME` and in the `.codex/` folders of trusted projects.
+//!
+//! - `exec` patterns are globs matched against each command of a shell
+//!   invocation, its words joined by single spaces (`"cargo *"`),
+//!   `network:<host glob>` to match commands that name a URL on that host, or
+//!   `op:<operation>` to match commands classified as `read_only`, `write`,
+//!   `network` or `destructive`.
+//! - `write` patterns are path globs matched against each file a patch
+//!   touches, relative to the working directory. `**` spans directories, and
+//!   a pattern without `/` matches the file name at any depth.
+//!
+//! Among the rules matching an action the strictest wins (`deny`, then
+//! `ask`, then `allow`). An action the rules only partly cover, or not at
+//! all, is left to the approval policy.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::parse_command::CommandOperation;
+use codex_protocol::protocol::AskForApproval;
+use thiserror::Error;
+use tokio::fs;
+use url::Url;
+use wildmatch::WildMatch;
+
+use crate::bash::parse_shell_lc_plain_commands;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::command_safety::classify_command::classify_command;
+use crate::config_loader::ConfigLayerStack;
+use crate::config_loader::ConfigLayerStackOrdering;
+use crate::sandboxing::SandboxPermissions;
+use crate::tools::sandboxing::ExecApprovalRequirement;
+
+/// Name of the rule file read from each config folder.
+pub const APPROVAL_POLICY_FILE: &str = "approval.policy";
+
+const NETWORK_PREFIX: &str = "network:";
+const OPERATION_PREFIX: &str = "op:";
+const NETWORK_SCHEMES: &[&str] = &["http", "https", "ssh", "git", "ftp", "ws", "wss"];
+
+/// What a rule does with a matching action, from least to most strict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RuleDecision {
+    Allow,
+    Ask,
+    Deny,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleAction {
+    Exec,
+    Write,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RulePattern {
+    Glob(String),
+    Network(String),
+    Operation(CommandOperation),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalRule {
+    pub decision: RuleDecision,
+    pub action: RuleAction,
+    pattern: RulePattern,
+    /// The rule as written.
+    pub text: String,
+    /// Where the rule was defined, e.g. `/repo/.codex/approval.policy:3`.
+    pub origin: String,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("{origin}: {message}")]
+pub struct ApprovalRuleError {
+    pub origin: String,
+    pub message: String,
+}
+
+/// A rule that matched part of an action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleMatch {
+    /// The command or path the rule matched.
+    pub subject: String,
+    pub rule: ApprovalRule,
+}
+
+/// How the rules judge one action.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Verdict {
+    pub matches: Vec<RuleMatch>,
+    /// Commands or paths no rule matched.
+    pub unmatched: Vec<String>,
+}
+
+impl Verdict {
+    /// The strictest matching decision, or `None` when the approval policy
+    /// decides: no rule matched, or only `allow` rules matched and some part
+    /// of the action is not covered by them.
+    pub fn decision(&self) -> Option<RuleDecision> {
+        match self.matches.iter().map(|m| m.rule.decision).max() {
+            Some(RuleDecision::Allow) if !self.unmatched.is_empty() => None,
+            decision => decision,
+        }
+    }
+
+    /// Why the action was denied or needs approval, naming the deciding rule.
+    pub fn reason(&self) -> Option<String> {
+        let decision = self.decision()?;
+        let deciding = self.matches.iter().find(|m| m.rule.decision == decision)?;
+        let verb = match decision {
+            RuleDecision::Allow => "allowed",
+            RuleDecision::Ask => "requires approval",
+            RuleDecision::Deny => "denied",
+        };
+        Some(format!(
+            "`{}` {verb} by approval rule `{}` ({})",
+            deciding.subject, deciding.rule.text, deciding.rule.origin
+        ))
+    }
+
+    /// Human-readable account of the verdict, one line per matched rule and
+    /// per uncovered part of the action.
+    pub fn explain(&self) -> String {
+        let mut lines = vec![
+            match self.decision() {
+                Some(RuleDecision::Allow) => "allowed by approval rules",
+                Some(RuleDecision::Ask) => "requires approval by approval rules",
+                Some(RuleDecision::Deny) => "denied by approval rules",
+                None => "not decided by approval rules; the approval policy applies",
+            }
+            .to_string(),
+        ];
+        lines.extend(self.matches.iter().map(|m| {
+            format!(
+                "  {}: matches `{}` ({})",
+                m.subject, m.rule.text, m.rule.origin
+            )
+        }));
+        lines.extend(
+            self.unmatched
+                .iter()
+                .map(|subject| format!("  {subject}: no matching rule")),
+        );
+        lines.join("\n")
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApprovalRules {
+    rules: Vec<ApprovalRule>,
+}
+
+impl ApprovalRules {
+    /// Parses rules written one per line. Blank lines and lines starting
+    /// with `#` are ignored; `origin` names the source in errors and
+    /// explanations.
+    pub fn parse(origin: &str, contents: &str) -> Result<Self, ApprovalRuleError> {
+        let mut rules = Self::default();
+        for (idx, line) in contents.lines().enumerate() {
+            rules.push_line(&format!("{origin}:{}", idx + 1), line)?;
+        }
+        Ok(rules)
+    }
+
+    /// Loads the rules of every `approval.policy` file in the config layers,
+    /// lowest precedence first, followed by the `approval_rules` from config.
+    pub async fn load(
+        config_stack: &ConfigLayerStack,
+        config_rules: &[String],
+    ) -> Result<Self, ApprovalRuleError> {
+        let mut rules = Self::default();
+        for layer in config_stack.get_layers(ConfigLayerStackOrdering::LowestPrecedenceFirst) {
+            let Some(config_folder) = layer.config_folder() else {
+                continue;
+            };
+            let path = config_folder.as_path().join(APPROVAL_POLICY_FILE);
+            let contents = match fs::read_to_string(&path).await {
+                Ok(contents) => contents,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(err) => {
+                    return Err(ApprovalRuleError {
+                        origin: path.display().to_string(),
+                        message: format!("failed to read: {err}"),
+                    });
+                }
+            };
+            rules
+                .rules
+                .extend(Self::parse(&path.display().to_string(), &contents)?.rules);
+        }
+        for (idx, line) in config_rules.iter().enumerate() {
+            rules.push_line(&format!("approval_rules[{idx}]"), line)?;
+        }
+        Ok(rules)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Judges running `command`. A `bash -lc` script made of plain commands
+    /// is judged command by command.
+    pub fn evaluate_exec(&self, command: &[String]) -> Verdict {
+        if self.is_empty() {
+            return Verdict::default();
+        }
+        let commands =
+            parse_shell_lc_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()]);
+        let subjects = commands.iter().map(|words| {
+            let text = words.join(" ");
+            let hosts = network_hosts(words);
+            let operations = classify_command(words);
+            (text.clone(), move |pattern: &RulePattern| match pattern {
+                RulePattern::Glob(glob) => WildMatch::new(glob).matches(&text),
+                RulePattern::Network(host) => {
+                    let host = WildMatch::new(host);
+                    hosts.iter().any(|candidate| host.matches(candidate))
+                }
+                RulePattern::Operation(operation) => operations.contains(operation),
+            })
+        });
+        self.evaluate(RuleAction::Exec, subjects)
+    }
+
+    /// Judges writing `paths`, relative paths being resolved against `cwd`.
+    pub fn evaluate_write<'a>(
+        &self,
+        paths: impl IntoIterator<Item = &'a Path>,
+        cwd: &Path,
+    ) -> Verdict {
+        if self.is_empty() {
+            return Verdict::default();
+        }
+        let subjects = paths.into_iter().map(|path| {
+            let path = cwd.join(path);
+            let relative = path.strip_prefix(cwd).ok().map(Path::to_path_buf);
+            let subject = relative.clone().unwrap_or_else(|| path.clone());
+            (
+                subject.display().to_string(),
+                move |pattern: &RulePattern| match pattern {
+                    RulePattern::Glob(glob) => path_matches(glob, &path, relative.as_deref()),
+                    RulePattern::Network(_) | RulePattern::Operation(_) => false,
+                },
+            )
+        });
+        self.evaluate(RuleAction::Write, subjects)
+    }
+
+    fn evaluate<F: Fn(&RulePattern) -> bool>(
+        &self,
+        action: RuleAction,
+        subjects: impl Iterator<Item = (String, F)>,
+    ) -> Verdict {
+        let mut verdict = Verdict::default();
+        for (subject, matches) in subjects {
+            let before = verdict.matches.len();
+            verdict.matches.extend(
+                self.rules
+                    .iter()
+                    .filter(|rule| rule.action == action && matches(&rule.pattern))
+                    .map(|rule| RuleMatch {
+                        subject: subject.clone(),
+                        rule: rule.clone(),
+                    }),
+            );
+            if verdict.matches.len() == before {
+                verdict.unmatched.push(subject);
+            }
+        }
+        verdict
+    }
+
+    fn push_line(&mut self, origin: &str, line: &str) -> Result<(), ApprovalRuleError> {
+        let text = line.trim();
+        if text.is_empty() || text.starts_with('#') {
+            return Ok(());
+        }
+        let (decision, action, pattern) =
+            parse_rule(text).map_err(|message| ApprovalRuleError {
+                origin: origin.to_string(),
+                message,
+            })?;
+        self.rules.push(ApprovalRule {
+            decision,
+            action,
+            pattern,
+            text: text.to_string(),
+            origin: origin.to_string(),
+        });
+        Ok(())
+    }
+}
+
+fn parse_rule(text: &str) -> Result<(RuleDecision, RuleAction, RulePattern), String> {
+    let mut words = text.splitn(3, char::is_whitespace);
+    let decision = match words.next() {
+        Some("allow") => RuleDecision::Allow,
+        Some("ask") => RuleDecision::Ask,
+        Some("deny") => RuleDecision::Deny,
+        other => {
+            return Err(format!(
+                "expected `allow`, `ask` or `deny`, found `{}`",
+                other.unwrap_or_default()
+            ));
+        }
+    };
+    let action = match words.next() {
+        Some("exec") => RuleAction::Exec,
+        Some("write") => RuleAction::Write,
+        Some(other) => return Err(format!("expected `exec` or `write`, found `{other}`")),
+        None => return Err("expected `exec` or `write` after the decision".to_string()),
+    };
+    let raw = words.next().map(str::trim).unwrap_or_default();
+    let pattern = if raw.starts_with('"') {
+        serde_json::from_str::<String>(raw)
+            .map_err(|_| format!("invalid quoted pattern `{raw}`"))?
+    } else if raw.is_empty() || raw.contains(char::is_whitespace) {
+        return Err("expected one pattern; quote patterns that contain spaces".to_string());
+    } else {
+        raw.to_string()
+    };
+    if action == RuleAction::Write
+        && (pattern.starts_with(NETWORK_PREFIX) || pattern.starts_with(OPERATION_PREFIX))
+    {
+        return Err("`network:` and `op:` patterns only apply to `exec` rules".to_string());
+    }
+    let pattern = if let Some(host) = pattern.strip_prefix(NETWORK_PREFIX) {
+        RulePattern::Network(host.to_string())
+    } else if let Some(operation) = pattern.strip_prefix(OPERATION_PREFIX) {
+        let operation = serde_json::from_value(serde_json::Value::String(operation.to_string()))
+            .map_err(|_| {
+                format!(
+                    "unknown operation `{operation}`; expected `read_only`, `write`, `network` or `destructive`"
+                )
+            })?;
+        RulePattern::Operation(operation)
+    } else {
+        RulePattern::Glob(pattern)
+    };
+    Ok((decision, action, pattern))
+}
+
+/// Hosts named by the URLs (or `user@host:path` remotes) among `words`.
+fn network_hosts(words: &[String]) -> Vec<String> {
+    words
+        .iter()
+        .filter_map(|word| {
+            if let Ok(url) = Url::parse(word)
+                && NETWORK_SCHEMES.contains(&url.scheme())
+            {
+                return url.host_str().map(str::to_string);
+            }
+            let (_, remote) = word.split_once('@')?;
+            let (host, _) = remote.split_once(':')?;
+            (!host.is_empty() && !host.contains('/')).then(|| host.to_string())
+        })
+        .collect()
+}
+
+/// Matches a path glob. Absolute globs are matched against the absolute
+/// path, others against the path relative to the working directory; a glob
+/// without `/` matches the file name.
+fn path_matches(glob: &str, absolute: &Path, relative: Option<&Path>) -> bool {
+    let glob = glob.trim_start_matches("./");
+    let target: PathBuf = if glob.starts_with('/') {
+        absolute.to_path_buf()
+    } else if !glob.contains('/') {
+        match absolute.file_name() {
+            Some(name) => PathBuf::from(name),
+            None => return false,
+        }
+    } else {
+        match relative {
+            Some(relative) => relative.to_path_buf(),
+            None => return false,
+        }
+    };
+    let target = target.to_string_lossy().replace('\\', "/");
+    let pattern: Vec<&str> = glob.split('/').filter(|s| !s.is_empty()).collect();
+    let segments: Vec<&str> = target.split('/').filter(|s| !s.is_empty()).collect();
+    segments_match(&pattern, &segments)
+}
+
+fn segments_match(pattern: &[&str], segments: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => segments.is_empty(),
+        Some((&"**", rest)) => {
+            (0..=segments.len()).any(|skip| segments_match(rest, &segments[skip..]))
+        }
+        Some((glob, rest)) => segments.split_first().is_some_and(|(first, tail)| {
+            WildMatch::new(glob).matches(first) && segments_match(rest, tail)
+        }),
+    }
+}
+
+/// Approval requirement for running `command`. A matching approval rule
+/// decides, except that an `allow` rule cannot lift an exec policy
+/// prohibition; otherwise the exec policy and approval policy decide.
+pub(crate) async fn exec_approval_requirement(
+    session: &Session,
+    turn: &TurnContext,
+    command: &[String],
+    sandbox_permissions: SandboxPermissions,
+) -> ExecApprovalRequirement {
+    let verdict = session.services.approval_rules.evaluate_exec(command);
+    let decision = verdict.decision();
+    if matches!(decision, None | Some(RuleDecision::Allow)) {
+        let features = session.features();
+        let requirement = session
+            .services
+            .exec_policy
+            .create_exec_approval_requirement_for_command(
+                &features,
+                command,
+                turn.approval_policy,
+                &turn.sandbox_policy,
+                sandbox_permissions,
+            )
+            .await;
+        if decision.is_none() || matches!(requirement, ExecApprovalRequirement::Forbidden { .. }) {
+            return requirement;
+        }
+        return ExecApprovalRequirement::Skip {
+            bypass_sandbox: false,
+            proposed_execpolicy_amendment: None,
+        };
+    }
+
+    let reason = verdict.reason().unwrap_or_default();
+    match decision {
+        Some(RuleDecision::Ask) if turn.approval_policy != AskForApproval::Never => {
+            ExecApprovalRequirement::NeedsApproval {
+                reason: Some(reason),
+                proposed_execpolicy_amendment: None,
+            }
+        }
+        Some(RuleDecision::Ask) => ExecApprovalRequirement::Forbidden {
+            reason: format!("{reason}, but AskForApproval is set to Never"),
+        },
+        _ => ExecApprovalRequirement::Forbidden { reason },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn rules(contents: &str) -> ApprovalRules {
+        ApprovalRules::parse("test.policy", contents).expect("valid rules")
+    }
+
+    fn command(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_reports_the_offending_line() {
+        let err = ApprovalRules::parse(
+            "approval.policy",
+            "# comment\n\nallow exec \"cargo *\"\npermit exec ls\n",
+        )
+        .expect_err("invalid rule");
+
+        assert_eq!(
+            err,
+            ApprovalRuleError {
+                origin: "approval.policy:4".to_string(),
+                message: "expected `allow`, `ask` or `deny`, found `permit`".to_string(),
+            }
+        );
+        assert!(ApprovalRules::parse("p", "deny write network:*").is_err());
+        assert!(ApprovalRules::parse("p", "deny exec cargo test").is_err());
+    }
+
+    #[test]
+    fn exec_rules_judge_each_command_of_a_script() {
+        let rules = rules("allow exec \"cargo *\"\ndeny exec \"rm -rf *\"\n");
+
+        assert_eq!(
+            rules
+                .evaluate_exec(&command(&["cargo", "test", "--all"]))
+                .decision(),
+            Some(RuleDecision::Allow)
+        );
+        let script = command(&["bash", "-lc", "cargo build && rm -rf target"]);
+        let verdict = rules.evaluate_exec(&script);
+        assert_eq!(verdict.decision(), Some(RuleDecision::Deny));
+        assert_eq!(
+            verdict.reason(),
+            Some(
+                "`rm -rf target` denied by approval rule `deny exec \"rm -rf *\"` (test.policy:2)"
+                    .to_string()
+            )
+        );
+        // `ls` is not covered, so the approval policy decides.
+        let partly_allowed = rules.evaluate_exec(&command(&["bash", "-lc", "cargo fmt && ls"]));
+        assert_eq!(partly_allowed.decision(), None);
+        assert_eq!(partly_allowed.unmatched, vec!["ls".to_string()]);
+    }
+
+    #[test]
+    fn network_patterns_match_url_hosts() {
+        let rules = rules("ask exec network:*\ndeny exec network:*.example.com\n");
+
+        assert_eq!(
+            rules
+                .evaluate_exec(&command(&["curl", "-sL", "https://openai.com/x"]))
+                .decision(),
+            Some(RuleDecision::Ask)
+        );
+        assert_eq!(
+            rules
+                .evaluate_exec(&command(&["git", "clone", "git@api.example.com:org/repo"]))
+                .decision(),
+            Some(RuleDecision::Deny)
+        );
+        assert_eq!(
+            rules
+                .evaluate_exec(&command(&["cat", "notes.txt"]))
+                .decision(),
+            None
+        );
+    }
+
+    #[test]
+    fn operation_patterns_match_classified_commands() {
+        let rules = rules("ask exec op:network\ndeny exec op:destructive\n");
+
+        let script = command(&["bash", "-lc", "cargo test > log.txt && rm -rf target"]);
+        let verdict = rules.evaluate_exec(&script);
+        assert_eq!(verdict.decision(), Some(RuleDecision::Deny));
+        assert_eq!(
+            rules
+                .evaluate_exec(&command(&["git", "pull", "--rebase"]))
+                .decision(),
+            Some(RuleDecision::Ask)
+        );
+        assert_eq!(
+            rules
+                .evaluate_exec(&command(&["cargo", "build"]))
+                .decision(),
+            None
+        );
+        assert!(ApprovalRules::parse("p", "deny exec op:sideways").is_err());
+        assert!(ApprovalRules::parse("p", "deny write op:write").is_err());
+    }
+
+    #[test]
+    fn write_rules_match_path_globs() {
+        let rules =
+            rules("deny write \"**/*.sql\"\nask write \"migrations/**\"\nallow write \"src/**\"\n");
+        let cwd = Path::new("/repo");
+
+        let verdict = |path: &str| rules.evaluate_write([Path::new(path)], cwd).decision();
+        assert_eq!(verdict("db/schema.sql"), Some(RuleDecision::Deny));
+        assert_eq!(verdict("/repo/schema.sql"), Some(RuleDecision::Deny));
+        assert_eq!(verdict("migrations/0001/up.rs"), Some(RuleDecision::Ask));
+        assert_eq!(verdict("src/lib.rs"), Some(RuleDecision::Allow));
+        assert_eq!(verdict("README.md"), None);
+        assert_eq!(verdict("/etc/hosts"), None);
+    }
+
+    #[test]
+    fn explain_lists_matches_and_uncovered_parts() {
+        let rules = rules("allow exec \"cargo *\"\n");
+        let verdict = rules.evaluate_exec(&command(&["bash", "-lc", "cargo test && make"]));
+
+        assert_eq!(
+            verdict.explain(),
+            "not decided by approval rules; the approval policy applies\n  cargo test: matches `allow exec \"cargo *\"` (test.policy:1)\n  make: no matching rule"
+        );
+    }
+}
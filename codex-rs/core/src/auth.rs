@@ -1111,6 +1111,8 @@ mod tests {
             .expect("config should load");
         config.forced_login_method = forced_login_method;
         config.forced_chatgpt_workspace_id = forced_chatgpt_workspace_id;
+        // These tests seed and inspect auth.json directly.
+        config.cli_auth_credentials_store_mode = AuthCredentialsStoreMode::File;
         config
     }
 
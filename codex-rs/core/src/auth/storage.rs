@@ -1,5 +1,6 @@
 use chrono::DateTime;
 use chrono::Utc;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 use sha2::Digest;
@@ -21,15 +22,15 @@ use codex_keyring_store::DefaultKeyringStore;
 use codex_keyring_store::KeyringStore;
 
 /// Determine where Codex should store CLI auth credentials.
-#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum AuthCredentialsStoreMode {
-    #[default]
     /// Persist credentials in CODEX_HOME/auth.json.
     File,
     /// Persist credentials in the keyring. Fail if unavailable.
     Keyring,
     /// Use keyring when available; otherwise, fall back to a file in CODEX_HOME.
+    #[default]
     Auto,
 }
 
@@ -167,6 +168,20 @@ impl KeyringAuthStorage {
         }
     }
 
+    /// Moves credentials left in a plaintext `auth.json`, e.g. from before the
+    /// keyring was used, into the keyring and removes the file.
+    fn migrate_auth_file(&self, key: &str) -> std::io::Result<Option<AuthDotJson>> {
+        let Some(auth) = FileAuthStorage::new(self.codex_home.clone()).load()? else {
+            return Ok(None);
+        };
+        let serialized = serde_json::to_string(&auth).map_err(std::io::Error::other)?;
+        self.save_to_keyring(key, &serialized)?;
+        if let Err(err) = delete_file_if_exists(&self.codex_home) {
+            warn!("failed to remove CLI auth file after moving it to the keyring: {err}");
+        }
+        Ok(Some(auth))
+    }
+
     fn save_to_keyring(&self, key: &str, value: &str) -> std::io::Result<()> {
         match self.keyring_store.save(KEYRING_SERVICE, key, value) {
             Ok(()) => Ok(()),
@@ -185,7 +200,10 @@ impl KeyringAuthStorage {
 impl AuthStorageBackend for KeyringAuthStorage {
     fn load(&self) -> std::io::Result<Option<AuthDotJson>> {
         let key = compute_store_key(&self.codex_home)?;
-        self.load_from_keyring(&key)
+        match self.load_from_keyring(&key)? {
+            Some(auth) => Ok(Some(auth)),
+            None => self.migrate_auth_file(&key),
+        }
     }
 
     fn save(&self, auth: &AuthDotJson) -> std::io::Result<()> {
@@ -459,6 +477,30 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn keyring_auth_storage_load_migrates_plaintext_auth_file() -> anyhow::Result<()> {
+        let codex_home = tempdir()?;
+        let mock_keyring = MockKeyringStore::default();
+        let storage = KeyringAuthStorage::new(
+            codex_home.path().to_path_buf(),
+            Arc::new(mock_keyring.clone()),
+        );
+        let expected = auth_with_prefix("plaintext");
+        FileAuthStorage::new(codex_home.path().to_path_buf()).save(&expected)?;
+
+        let loaded = storage.load()?;
+
+        assert_eq!(loaded, Some(expected.clone()));
+        let key = compute_store_key(codex_home.path())?;
+        assert_keyring_saved_auth_and_removed_fallback(
+            &mock_keyring,
+            &key,
+            codex_home.path(),
+            &expected,
+        );
+        Ok(())
+    }
+
     #[test]
     fn keyring_auth_storage_compute_store_key_for_home_directory() -> anyhow::Result<()> {
         let codex_home = PathBuf::from("~/.codex");
@@ -583,6 +625,10 @@ mod tests {
 
         let loaded = storage.load()?;
         assert_eq!(loaded, Some(expected));
+        assert!(
+            get_auth_file(codex_home.path()).exists(),
+            "auth.json should be kept when the keyring is unavailable"
+        );
         Ok(())
     }
 
@@ -0,0 +1,859 @@
+//! Best-of-N turns: one user turn is run by several candidates at once, each
+//! with its own model or reasoning effort and in its own scratch copy of the
+//! workspace.
+//!
+//! Candidates run headless, with approvals turned off, so the `[verify]`
+//! command their turn runs is what tells a good result from a bad one. When
+//! they are all done, [`select_best`] ranks them and [`apply_candidate`]
+//! copies the winner's changes back into the real workspace. The scratch
+//! copies live under `CODEX_HOME/best_of` until [`BestOfRun::discard`]; in a
+//! git repository they are worktrees, so `.git` and ignored files such as
+//! build output are neither copied nor compared.
+
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use std::ffi::OsStr;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::ThreadId;
+use codex_protocol::approvals::ElicitationAction;
+use codex_protocol::openai_models::ReasoningEffort;
+use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::Op;
+use codex_protocol::protocol::VerificationStatus;
+use codex_protocol::user_input::UserInput;
+use serde::Serialize;
+use tokio::sync::mpsc::UnboundedSender;
+use uuid::Uuid;
+
+use crate::NewThread;
+use crate::ThreadManager;
+use crate::config::Config;
+use crate::workspace_snapshot::SKIPPED_DIRS;
+use crate::workspace_snapshot::file_diff;
+use crate::workspace_snapshot::relative_path;
+
+/// Directory under `CODEX_HOME` holding the scratch copies of each run.
+pub const BEST_OF_DIR: &str = "best_of";
+
+/// What a candidate changes relative to the session's settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CandidateSpec {
+    pub model: Option<String>,
+    pub effort: Option<ReasoningEffort>,
+}
+
+impl CandidateSpec {
+    /// Parses `MODEL[:EFFORT]`. A suffix that is not a reasoning effort stays
+    /// part of the model name, as in `llama3:8b`.
+    pub fn parse(spec: &str) -> Self {
+        let spec = spec.trim();
+        if let Some((model, effort)) = spec.rsplit_once(':')
+            && let Ok(effort) = serde_json::from_value(serde_json::json!(effort))
+        {
+            return Self {
+                model: (!model.is_empty()).then(|| model.to_string()),
+                effort: Some(effort),
+            };
+        }
+        Self {
+            model: (!spec.is_empty()).then(|| spec.to_string()),
+            effort: None,
+        }
+    }
+
+    /// Short description, e.g. `gpt-5.1-codex (high)`.
+    pub fn label(&self) -> String {
+        match (&self.model, self.effort) {
+            (Some(model), Some(effort)) => format!("{model} ({effort})"),
+            (Some(model), None) => model.clone(),
+            (None, Some(effort)) => format!("default model ({effort})"),
+            (None, None) => "default model".to_string(),
+        }
+    }
+}
+
+/// An event from one candidate's session, for callers that stream them.
+#[derive(Debug, Clone)]
+pub struct CandidateEvent {
+    /// 1-based position among the candidates.
+    pub candidate: usize,
+    pub event: Event,
+}
+
+/// How one candidate's turn went.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CandidateOutcome {
+    /// 1-based position among the candidates.
+    pub index: usize,
+    pub label: String,
+    /// The candidate's scratch copy of the workspace.
+    pub workspace: PathBuf,
+    pub thread_id: Option<ThreadId>,
+    pub last_agent_message: Option<String>,
+    pub verification: Option<VerificationStatus>,
+    /// Files added or modified, relative to the workspace.
+    pub changed: Vec<String>,
+    /// Files removed, relative to the workspace.
+    pub removed: Vec<String>,
+    /// Unified diff of `changed` and `removed`.
+    pub diff: String,
+    pub total_tokens: i64,
+    /// Why the candidate did not finish its turn.
+    pub error: Option<String>,
+}
+
+impl CandidateOutcome {
+    fn new(index: usize, spec: &CandidateSpec, workspace: PathBuf) -> Self {
+        Self {
+            index,
+            label: spec.label(),
+            workspace,
+            thread_id: None,
+            last_agent_message: None,
+            verification: None,
+            changed: Vec::new(),
+            removed: Vec::new(),
+            diff: String::new(),
+            total_tokens: 0,
+            error: None,
+        }
+    }
+
+    fn failed(mut self, error: impl ToString) -> Self {
+        self.error = Some(error.to_string());
+        self
+    }
+}
+
+/// The candidates of one best-of-N turn.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BestOfRun {
+    /// Directory holding the candidates' scratch copies.
+    pub dir: PathBuf,
+    pub candidates: Vec<CandidateOutcome>,
+    /// Position in `candidates` of the one [`select_best`] picked.
+    pub best: Option<usize>,
+    /// The workspace the candidates copied, whose git repository tracks
+    /// their worktrees.
+    #[serde(skip)]
+    cwd: PathBuf,
+}
+
+impl BestOfRun {
+    /// Deletes the scratch copies.
+    pub fn discard(&self) -> io::Result<()> {
+        discard(&self.dir, &self.cwd)
+    }
+
+    /// Returns a guard that deletes the scratch copies when dropped, so they
+    /// do not outlive a caller that returns early.
+    pub fn cleanup(&self) -> ScratchCleanup {
+        ScratchCleanup {
+            dir: self.dir.clone(),
+            cwd: self.cwd.clone(),
+            keep: false,
+        }
+    }
+}
+
+/// Deletes a run's scratch copies when dropped unless [`Self::keep`] was
+/// called; see [`BestOfRun::cleanup`].
+#[must_use]
+pub struct ScratchCleanup {
+    dir: PathBuf,
+    cwd: PathBuf,
+    keep: bool,
+}
+
+impl ScratchCleanup {
+    /// Leaves the scratch copies in place.
+    pub fn keep(mut self) {
+        self.keep = true;
+    }
+}
+
+impl Drop for ScratchCleanup {
+    fn drop(&mut self) {
+        if !self.keep
+            && let Err(err) = discard(&self.dir, &self.cwd)
+        {
+            tracing::warn!("failed to delete {}: {err}", self.dir.display());
+        }
+    }
+}
+
+fn discard(dir: &Path, cwd: &Path) -> io::Result<()> {
+    match std::fs::remove_dir_all(dir) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+        _ => {}
+    }
+    // Forget the worktrees that were just deleted; outside a git repository
+    // this fails harmlessly.
+    git(cwd, &["worktree", "prune"]);
+    Ok(())
+}
+
+/// Runs `input` as one turn of each candidate in parallel, each in a fresh
+/// session working in its own copy of `config.cwd`. Every event the
+/// candidates emit is also sent to `events`, when given.
+pub async fn run_best_of(
+    manager: &ThreadManager,
+    config: &Config,
+    specs: &[CandidateSpec],
+    input: Vec<UserInput>,
+    events: Option<UnboundedSender<CandidateEvent>>,
+) -> io::Result<BestOfRun> {
+    let dir = config
+        .codex_home
+        .join(BEST_OF_DIR)
+        .join(Uuid::new_v4().to_string());
+    let cwd = config.cwd.clone();
+    let skip = config.codex_home.clone();
+    let mut run = BestOfRun {
+        dir: dir.clone(),
+        candidates: Vec::new(),
+        best: None,
+        cwd: cwd.clone(),
+    };
+    let cleanup = run.cleanup();
+    let (baseline, workspaces) = {
+        let (cwd, skip, count) = (cwd.clone(), skip.clone(), specs.len());
+        tokio::task::spawn_blocking(move || {
+            let baseline = workspace_files(&cwd, &skip)?;
+            let workspaces = (1..=count)
+                .map(|index| {
+                    prepare_workspace(&cwd, &dir.join(format!("candidate-{index}")), &baseline)
+                })
+                .collect::<io::Result<Vec<_>>>()?;
+            Ok::<_, io::Error>((baseline, workspaces))
+        })
+        .await
+        .map_err(io::Error::other)??
+    };
+
+    let runs = specs
+        .iter()
+        .zip(workspaces)
+        .enumerate()
+        .map(|(index, (spec, workspace))| {
+            let outcome = CandidateOutcome::new(index + 1, spec, workspace);
+            run_candidate(
+                manager,
+                config,
+                spec,
+                outcome,
+                input.clone(),
+                events.clone(),
+            )
+        });
+    let candidates = futures::future::join_all(runs).await;
+    run.candidates = tokio::task::spawn_blocking(move || {
+        candidates
+            .into_iter()
+            .map(|mut candidate| {
+                if let Err(err) = record_changes(&cwd, &baseline, &skip, &mut candidate) {
+                    candidate.error = Some(format!("failed to compare the workspace: {err}"));
+                }
+                candidate
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(io::Error::other)?;
+    run.best = select_best(&run.candidates);
+    cleanup.keep();
+    Ok(run)
+}
+
+async fn run_candidate(
+    manager: &ThreadManager,
+    config: &Config,
+    spec: &CandidateSpec,
+    mut outcome: CandidateOutcome,
+    input: Vec<UserInput>,
+    events: Option<UnboundedSender<CandidateEvent>>,
+) -> CandidateOutcome {
+    let candidate = outcome.index;
+    let forward = |event: &Event| {
+        if let Some(events) = &events {
+            let _ = events.send(CandidateEvent {
+                candidate,
+                event: event.clone(),
+            });
+        }
+    };
+    let mut config = config.clone();
+    config.cwd = outcome.workspace.clone();
+    if let Some(model) = &spec.model {
+        config.model = Some(model.clone());
+    }
+    if let Some(effort) = spec.effort {
+        config.model_reasoning_effort = Some(effort);
+    }
+    // Nobody is there to answer an approval request.
+    if let Err(err) = config.approval_policy.set(AskForApproval::Never) {
+        return outcome.failed(err);
+    }
+    let NewThread {
+        thread_id, thread, ..
+    } = match manager.start_thread(config).await {
+        Ok(new_thread) => new_thread,
+        Err(err) => return outcome.failed(err),
+    };
+    outcome.thread_id = Some(thread_id);
+    if let Err(err) = thread
+        .submit(Op::UserInput {
+            items: input,
+            final_output_json_schema: None,
+        })
+        .await
+    {
+        return outcome.failed(err);
+    }
+
+    loop {
+        let event = match thread.next_event().await {
+            Ok(event) => event,
+            Err(err) => {
+                outcome.error = Some(err.to_string());
+                break;
+            }
+        };
+        forward(&event);
+        match event.msg {
+            EventMsg::TokenCount(ev) => {
+                if let Some(info) = ev.info {
+                    outcome.total_tokens = info.total_token_usage.total_tokens;
+                }
+            }
+            EventMsg::Error(ev) => outcome.error = Some(ev.message),
+            EventMsg::ElicitationRequest(ev) => {
+                let _ = thread
+                    .submit(Op::ResolveElicitation {
+                        server_name: ev.server_name,
+                        request_id: ev.id,
+                        decision: ElicitationAction::Cancel,
+                    })
+                    .await;
+            }
+            EventMsg::TurnComplete(ev) => {
+                outcome.last_agent_message = ev.last_agent_message;
+                outcome.verification = ev.verification;
+                break;
+            }
+            EventMsg::TurnAborted(ev) => {
+                outcome.error = Some(format!("turn aborted: {:?}", ev.reason));
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    if thread.submit(Op::Shutdown).await.is_ok() {
+        while let Ok(event) = thread.next_event().await {
+            forward(&event);
+            if matches!(event.msg, EventMsg::ShutdownComplete) {
+                break;
+            }
+        }
+    }
+    manager.remove_thread(&thread_id).await;
+    outcome
+}
+
+/// Fills in what the candidate changed in its copy of the workspace: every
+/// file [`workspace_files`] lists in `baseline` or in the copy whose contents
+/// differ from the same file in `cwd`.
+fn record_changes(
+    cwd: &Path,
+    baseline: &BTreeSet<String>,
+    skip: &Path,
+    outcome: &mut CandidateOutcome,
+) -> io::Result<()> {
+    let after = workspace_files(&outcome.workspace, skip)?;
+    for path in baseline.union(&after) {
+        let old = baseline
+            .contains(path)
+            .then(|| read_entry(&cwd.join(path), cwd, cwd))
+            .transpose()?;
+        let new = after
+            .contains(path)
+            .then(|| read_entry(&outcome.workspace.join(path), &outcome.workspace, cwd))
+            .transpose()?;
+        if old == new {
+            continue;
+        }
+        outcome
+            .diff
+            .push_str(&file_diff(path, old.as_deref(), new.as_deref()));
+        if new.is_some() {
+            outcome.changed.push(path.clone());
+        } else {
+            outcome.removed.push(path.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Picks the candidate to keep: among those that finished their turn, one
+/// whose verification passed beats one without verification, which beats
+/// one whose verification failed. Then a candidate that changed files wins,
+/// then the one that needed the fewest verification runs, then the one that
+/// used the fewest tokens, then the earliest.
+pub fn select_best(candidates: &[CandidateOutcome]) -> Option<usize> {
+    candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, candidate)| candidate.error.is_none())
+        .max_by_key(|(position, candidate)| {
+            let verified = match &candidate.verification {
+                Some(verification) if verification.passed => 2,
+                Some(_) => 0,
+                None => 1,
+            };
+            let runs = candidate
+                .verification
+                .as_ref()
+                .map_or(0, |verification| verification.runs);
+            (
+                verified,
+                !candidate.diff.is_empty(),
+                Reverse(runs),
+                Reverse(candidate.total_tokens),
+                Reverse(*position),
+            )
+        })
+        .map(|(position, _)| position)
+}
+
+/// Copies the candidate's changes into `workspace`.
+pub fn apply_candidate(candidate: &CandidateOutcome, workspace: &Path) -> io::Result<()> {
+    for path in &candidate.removed {
+        remove_file(&workspace.join(path))?;
+    }
+    for path in &candidate.changed {
+        let source = candidate.workspace.join(path);
+        let target = workspace.join(path);
+        if let Some(dir) = target.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        // Replace rather than write through, in case one side is a symlink.
+        remove_file(&target)?;
+        if std::fs::symlink_metadata(&source)?.is_symlink() {
+            let link = std::fs::read_link(&source)?;
+            symlink(&remap(&link, &candidate.workspace, workspace), &target)?;
+        } else {
+            std::fs::copy(&source, &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// The files under `dir` a candidate works on, relative to it. In a git
+/// repository these are the tracked files and the untracked ones
+/// `.gitignore` does not exclude; elsewhere, every file outside the
+/// dependency and build directories snapshots skip. Anything under `skip`
+/// is left out.
+fn workspace_files(dir: &Path, skip: &Path) -> io::Result<BTreeSet<String>> {
+    let Some(listed) = git(
+        dir,
+        &[
+            "ls-files",
+            "-z",
+            "--cached",
+            "--others",
+            "--exclude-standard",
+        ],
+    ) else {
+        let mut files = BTreeSet::new();
+        walk_files(dir, dir, skip, &mut files)?;
+        return Ok(files);
+    };
+    // Tracked files deleted from the working tree are still listed, and
+    // submodules are listed as directories.
+    Ok(nul_separated(&listed)
+        .filter(|path| {
+            let path = dir.join(path);
+            !path.starts_with(skip)
+                && std::fs::symlink_metadata(&path).is_ok_and(|metadata| !metadata.is_dir())
+        })
+        .collect())
+}
+
+fn walk_files(
+    root: &Path,
+    dir: &Path,
+    skip: &Path,
+    files: &mut BTreeSet<String>,
+) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.starts_with(skip) {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            let name = entry.file_name();
+            if !SKIPPED_DIRS.iter().any(|skipped| name == *skipped) {
+                walk_files(root, &path, skip, files)?;
+            }
+        } else if let Some(relative) = relative_path(root, &path) {
+            files.insert(relative);
+        }
+    }
+    Ok(())
+}
+
+/// Sets up a candidate's copy of `cwd` in `root` holding `files`, and
+/// returns the directory the candidate works in. In a git repository the
+/// copy is a worktree of `HEAD` with the uncommitted changes copied on top,
+/// so `.git` and ignored files are never copied; elsewhere `files` are
+/// copied one by one. Absolute symlinks into `cwd` are pointed at the copy.
+fn prepare_workspace(cwd: &Path, root: &Path, files: &BTreeSet<String>) -> io::Result<PathBuf> {
+    let worktree = git(cwd, &["rev-parse", "--show-prefix"]).filter(|_| {
+        git(
+            cwd,
+            &[
+                OsStr::new("worktree"),
+                OsStr::new("add"),
+                OsStr::new("--quiet"),
+                OsStr::new("--detach"),
+                root.as_os_str(),
+                OsStr::new("HEAD"),
+            ],
+        )
+        .is_some()
+    });
+    let workspace = match worktree {
+        Some(prefix) => {
+            let workspace = root.join(String::from_utf8_lossy(&prefix).trim_end());
+            let uncommitted = git(
+                cwd,
+                &[
+                    "ls-files",
+                    "-z",
+                    "--modified",
+                    "--others",
+                    "--exclude-standard",
+                ],
+            )
+            .unwrap_or_default();
+            for path in nul_separated(&uncommitted) {
+                if files.contains(&path) {
+                    copy_entry(&cwd.join(&path), &workspace.join(&path))?;
+                } else {
+                    remove_file(&workspace.join(&path))?;
+                }
+            }
+            workspace
+        }
+        None => {
+            std::fs::create_dir_all(root)?;
+            for path in files {
+                copy_entry(&cwd.join(path), &root.join(path))?;
+            }
+            root.to_path_buf()
+        }
+    };
+
+    for path in files {
+        let link = workspace.join(path);
+        if std::fs::symlink_metadata(&link).is_ok_and(|metadata| metadata.is_symlink()) {
+            let target = std::fs::read_link(&link)?;
+            if target.starts_with(cwd) {
+                std::fs::remove_file(&link)?;
+                symlink(&remap(&target, cwd, &workspace), &link)?;
+            }
+        }
+    }
+    Ok(workspace)
+}
+
+fn copy_entry(from: &Path, to: &Path) -> io::Result<()> {
+    if let Some(dir) = to.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    remove_file(to)?;
+    if std::fs::symlink_metadata(from)?.is_symlink() {
+        symlink(&std::fs::read_link(from)?, to)
+    } else {
+        std::fs::copy(from, to).map(|_| ())
+    }
+}
+
+/// The contents of the file at `path` in a copy of the workspace rooted at
+/// `root`. For a symlink this is its target, with absolute paths into `root`
+/// mapped to `workspace` so that a copy's rewritten links compare equal to
+/// the originals.
+fn read_entry(path: &Path, root: &Path, workspace: &Path) -> io::Result<Vec<u8>> {
+    if std::fs::symlink_metadata(path)?.is_symlink() {
+        let target = remap(&std::fs::read_link(path)?, root, workspace);
+        return Ok(target.to_string_lossy().into_owned().into_bytes());
+    }
+    std::fs::read(path)
+}
+
+/// `path` moved from under `from` to under `to`; other paths are unchanged.
+fn remap(path: &Path, from: &Path, to: &Path) -> PathBuf {
+    path.strip_prefix(from)
+        .map_or_else(|_| path.to_path_buf(), |inside| to.join(inside))
+}
+
+fn remove_file(path: &Path) -> io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+/// Runs git in `dir` and returns its output, or `None` if it failed, e.g.
+/// because `dir` is not in a git repository.
+fn git<S: AsRef<OsStr>>(dir: &Path, args: &[S]) -> Option<Vec<u8>> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    output.status.success().then_some(output.stdout)
+}
+
+fn nul_separated(output: &[u8]) -> impl Iterator<Item = String> + '_ {
+    output
+        .split(|byte| *byte == 0)
+        .filter(|path| !path.is_empty())
+        .map(|path| String::from_utf8_lossy(path).into_owned())
+}
+
+#[cfg(unix)]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+/// Creating symlinks needs extra privileges on Windows, so they are left
+/// out of the copy.
+#[cfg(not(unix))]
+fn symlink(_target: &Path, _link: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn outcome(index: usize) -> CandidateOutcome {
+        CandidateOutcome::new(index, &CandidateSpec::default(), PathBuf::new())
+    }
+
+    fn verified(passed: bool, runs: u32) -> Option<VerificationStatus> {
+        Some(VerificationStatus {
+            command: "make test".to_string(),
+            passed,
+            exit_code: if passed { 0 } else { 1 },
+            runs,
+        })
+    }
+
+    #[test]
+    fn candidate_specs_parse_model_and_effort() {
+        assert_eq!(
+            CandidateSpec::parse("gpt-5.1-codex:high"),
+            CandidateSpec {
+                model: Some("gpt-5.1-codex".to_string()),
+                effort: Some(ReasoningEffort::High),
+            }
+        );
+        assert_eq!(
+            CandidateSpec::parse("llama3:8b"),
+            CandidateSpec {
+                model: Some("llama3:8b".to_string()),
+                effort: None,
+            }
+        );
+        assert_eq!(
+            CandidateSpec::parse(":low").label(),
+            "default model (low)".to_string()
+        );
+    }
+
+    #[test]
+    fn verified_candidates_win_then_cheaper_ones() {
+        let mut failed = outcome(1);
+        failed.verification = verified(false, 3);
+        failed.diff = "diff".to_string();
+        let mut expensive = outcome(2);
+        expensive.verification = verified(true, 1);
+        expensive.diff = "diff".to_string();
+        expensive.total_tokens = 900;
+        let mut cheap = outcome(3);
+        cheap.verification = verified(true, 1);
+        cheap.diff = "diff".to_string();
+        cheap.total_tokens = 300;
+        let mut errored = outcome(4);
+        errored.verification = verified(true, 1);
+        errored.error = Some("stream disconnected".to_string());
+
+        let candidates = vec![failed, expensive, cheap, errored];
+        assert_eq!(select_best(&candidates), Some(2));
+        assert_eq!(select_best(&candidates[..2]), Some(1));
+        assert_eq!(select_best(&candidates[3..]), None);
+    }
+
+    #[test]
+    fn changes_are_recorded_and_applied() -> io::Result<()> {
+        let workspace = TempDir::new()?;
+        std::fs::write(workspace.path().join("keep.txt"), "same\n")?;
+        std::fs::write(workspace.path().join("edit.txt"), "old\n")?;
+        std::fs::write(workspace.path().join("gone.txt"), "bye\n")?;
+        std::fs::create_dir(workspace.path().join("node_modules"))?;
+        std::fs::write(workspace.path().join("node_modules/dep.js"), "dep\n")?;
+        let scratch = TempDir::new()?;
+        let baseline = workspace_files(workspace.path(), scratch.path())?;
+        assert!(!baseline.contains("node_modules/dep.js"));
+
+        let copy = prepare_workspace(
+            workspace.path(),
+            &scratch.path().join("candidate-1"),
+            &baseline,
+        )?;
+        assert!(!copy.join("node_modules").exists());
+        std::fs::create_dir(copy.join("node_modules"))?;
+        std::fs::write(copy.join("node_modules/dep.js"), "changed\n")?;
+        std::fs::write(copy.join("edit.txt"), "new\n")?;
+        std::fs::remove_file(copy.join("gone.txt"))?;
+        std::fs::create_dir(copy.join("src"))?;
+        std::fs::write(copy.join("src/added.txt"), "hi\n")?;
+
+        let mut candidate = CandidateOutcome::new(1, &CandidateSpec::default(), copy);
+        record_changes(workspace.path(), &baseline, scratch.path(), &mut candidate)?;
+        assert_eq!(
+            candidate.changed,
+            vec!["edit.txt".to_string(), "src/added.txt".to_string()]
+        );
+        assert_eq!(candidate.removed, vec!["gone.txt".to_string()]);
+        assert!(
+            candidate.diff.contains("-old\n+new\n"),
+            "{}",
+            candidate.diff
+        );
+
+        apply_candidate(&candidate, workspace.path())?;
+        assert_eq!(
+            std::fs::read_to_string(workspace.path().join("edit.txt"))?,
+            "new\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(workspace.path().join("src/added.txt"))?,
+            "hi\n"
+        );
+        assert!(!workspace.path().join("gone.txt").exists());
+        assert!(workspace.path().join("keep.txt").exists());
+        Ok(())
+    }
+
+    fn git_in(dir: &Path, args: &[&str]) {
+        assert!(git(dir, args).is_some(), "git {args:?} failed");
+    }
+
+    #[test]
+    fn git_workspaces_are_worktrees_without_ignored_files() -> io::Result<()> {
+        let repo = TempDir::new()?;
+        let cwd = repo.path().join("app");
+        std::fs::create_dir(&cwd)?;
+        std::fs::write(repo.path().join(".gitignore"), "build/\n")?;
+        std::fs::write(cwd.join("main.rs"), "fn main() {}\n")?;
+        std::fs::write(cwd.join("old.rs"), "old\n")?;
+        for args in [
+            &["init", "--quiet"][..],
+            &["config", "user.email", "dev@example.com"],
+            &["config", "user.name", "Dev"],
+            &["add", "--all"],
+            &["commit", "--quiet", "--message", "init"],
+        ] {
+            git_in(repo.path(), args);
+        }
+        std::fs::write(cwd.join("main.rs"), "fn main() { run() }\n")?;
+        std::fs::remove_file(cwd.join("old.rs"))?;
+        std::fs::write(cwd.join("new.rs"), "new\n")?;
+        std::fs::create_dir(repo.path().join("app/build"))?;
+        std::fs::write(cwd.join("build/out"), "artifact\n")?;
+        let scratch = TempDir::new()?;
+
+        let baseline = workspace_files(&cwd, scratch.path())?;
+        let copy = prepare_workspace(&cwd, &scratch.path().join("candidate-1"), &baseline)?;
+
+        assert_eq!(
+            baseline,
+            BTreeSet::from(["main.rs".to_string(), "new.rs".to_string()])
+        );
+        assert_eq!(copy, scratch.path().join("candidate-1/app"));
+        assert_eq!(
+            std::fs::read_to_string(copy.join("main.rs"))?,
+            "fn main() { run() }\n"
+        );
+        assert!(copy.join("new.rs").exists());
+        assert!(!copy.join("old.rs").exists());
+        assert!(!copy.join("build").exists());
+
+        std::fs::create_dir(copy.join("build"))?;
+        std::fs::write(copy.join("build/out"), "rebuilt\n")?;
+        std::fs::write(copy.join("new.rs"), "newer\n")?;
+        let mut candidate = CandidateOutcome::new(1, &CandidateSpec::default(), copy);
+        record_changes(&cwd, &baseline, scratch.path(), &mut candidate)?;
+        assert_eq!(candidate.changed, vec!["new.rs".to_string()]);
+        assert_eq!(candidate.removed, Vec::<String>::new());
+
+        let run = BestOfRun {
+            dir: scratch.path().to_path_buf(),
+            candidates: vec![candidate],
+            best: Some(0),
+            cwd: cwd.clone(),
+        };
+        run.discard()?;
+        let worktrees = git(&cwd, &["worktree", "list", "--porcelain"]).unwrap_or_default();
+        assert_eq!(
+            String::from_utf8_lossy(&worktrees)
+                .matches("worktree ")
+                .count(),
+            1
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn absolute_symlinks_into_the_workspace_point_at_the_copy() -> io::Result<()> {
+        let workspace = TempDir::new()?;
+        let outside = TempDir::new()?;
+        std::fs::write(workspace.path().join("config.toml"), "a = 1\n")?;
+        symlink(
+            &workspace.path().join("config.toml"),
+            &workspace.path().join("inside"),
+        )?;
+        symlink(outside.path(), &workspace.path().join("outside"))?;
+        let scratch = TempDir::new()?;
+        let baseline = workspace_files(workspace.path(), scratch.path())?;
+
+        let copy = prepare_workspace(
+            workspace.path(),
+            &scratch.path().join("candidate-1"),
+            &baseline,
+        )?;
+
+        assert_eq!(
+            std::fs::read_link(copy.join("inside"))?,
+            copy.join("config.toml")
+        );
+        assert_eq!(std::fs::read_link(copy.join("outside"))?, outside.path());
+        let mut candidate = CandidateOutcome::new(1, &CandidateSpec::default(), copy);
+        record_changes(workspace.path(), &baseline, scratch.path(), &mut candidate)?;
+        assert_eq!(candidate.diff, "");
+        Ok(())
+    }
+}
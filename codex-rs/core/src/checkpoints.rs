@@ -0,0 +1,228 @@
+//! Checkpoints recorded in a session's rollout, and rolling the working tree
+//! back to one of them.
+//!
+//! When the `undo` feature is enabled, each turn records a ghost snapshot of
+//! the working tree before the agent runs any tool, or a
+//! [workspace snapshot](crate::workspace_snapshot) when the working tree is
+//! not a git repository. `/undo` restores the most recent ghost snapshot;
+//! [`rollback_to_turn`] restores the checkpoint taken before any turn of a
+//! recorded session.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_git::GhostCommit;
+use codex_git::GhostSnapshotConfig;
+use codex_git::GitToolingError;
+use codex_git::RestoreGhostCommitOptions;
+use codex_git::restore_ghost_commit_with_options;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+
+use crate::rollout::RolloutRecorder;
+use crate::workspace_snapshot::SnapshotStore;
+
+/// Working tree state captured at the start of a turn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    /// 1-based turn number, counting user messages.
+    pub turn: usize,
+    /// Directory the snapshot was taken in.
+    pub cwd: PathBuf,
+    pub snapshot: CheckpointSnapshot,
+}
+
+/// How a checkpoint captured the working tree.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CheckpointSnapshot {
+    /// A ghost commit in the git repository.
+    Git(GhostCommit),
+    /// A workspace snapshot, for a directory outside any git repository.
+    Workspace { snapshot_id: String },
+}
+
+impl Checkpoint {
+    /// Short name of the snapshot, for display.
+    pub fn short_id(&self) -> String {
+        match &self.snapshot {
+            CheckpointSnapshot::Git(ghost_commit) => ghost_commit.id().chars().take(7).collect(),
+            CheckpointSnapshot::Workspace { snapshot_id } => snapshot_id
+                .rsplit('/')
+                .next()
+                .unwrap_or(snapshot_id)
+                .to_string(),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RollbackError {
+    #[error("failed to read session: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("no checkpoint was recorded for turn {turn}{}", describe_available(.available))]
+    NoCheckpoint { turn: usize, available: Vec<usize> },
+    #[error("failed to restore checkpoint: {0}")]
+    Git(#[from] GitToolingError),
+    #[error("failed to restore workspace snapshot: {0}")]
+    Workspace(std::io::Error),
+}
+
+fn describe_available(available: &[usize]) -> String {
+    if available.is_empty() {
+        "; this session has no checkpoints (enable them with `[features] undo = true`)".to_string()
+    } else {
+        let turns: Vec<String> = available.iter().map(ToString::to_string).collect();
+        format!("; checkpoints exist for turns {}", turns.join(", "))
+    }
+}
+
+/// Lists the checkpoints in a rollout in the order they were taken.
+pub fn checkpoints_from_rollout(items: &[RolloutItem]) -> Vec<Checkpoint> {
+    let mut checkpoints = Vec::new();
+    let mut turn = 0;
+    let mut cwd = PathBuf::new();
+    for item in items {
+        match item {
+            RolloutItem::SessionMeta(meta) => cwd = meta.meta.cwd.clone(),
+            RolloutItem::TurnContext(context) => cwd = context.cwd.clone(),
+            RolloutItem::EventMsg(EventMsg::UserMessage(_)) => turn += 1,
+            RolloutItem::ResponseItem(ResponseItem::GhostSnapshot { ghost_commit }) => {
+                checkpoints.push(Checkpoint {
+                    turn,
+                    cwd: cwd.clone(),
+                    snapshot: CheckpointSnapshot::Git(ghost_commit.clone()),
+                });
+            }
+            RolloutItem::EventMsg(EventMsg::WorkspaceSnapshot(event)) => {
+                checkpoints.push(Checkpoint {
+                    turn,
+                    cwd: event.cwd.clone(),
+                    snapshot: CheckpointSnapshot::Workspace {
+                        snapshot_id: event.snapshot_id.clone(),
+                    },
+                });
+            }
+            _ => {}
+        }
+    }
+    checkpoints
+}
+
+/// Restores the working tree to how it was before `turn` of the session
+/// recorded at `rollout_path` ran. Workspace snapshots are read from
+/// `codex_home`.
+pub async fn rollback_to_turn(
+    rollout_path: &Path,
+    turn: usize,
+    ghost_snapshot: GhostSnapshotConfig,
+    codex_home: &Path,
+) -> Result<Checkpoint, RollbackError> {
+    let history = RolloutRecorder::get_rollout_history(rollout_path).await?;
+    let checkpoints = checkpoints_from_rollout(&history.get_rollout_items());
+    let Some(checkpoint) = checkpoints
+        .iter()
+        .find(|checkpoint| checkpoint.turn == turn)
+        .cloned()
+    else {
+        return Err(RollbackError::NoCheckpoint {
+            turn,
+            available: checkpoints
+                .iter()
+                .map(|checkpoint| checkpoint.turn)
+                .collect(),
+        });
+    };
+
+    let restored = checkpoint.clone();
+    match restored.snapshot {
+        CheckpointSnapshot::Git(ghost_commit) => {
+            tokio::task::spawn_blocking(move || {
+                let options =
+                    RestoreGhostCommitOptions::new(&restored.cwd).ghost_snapshot(ghost_snapshot);
+                restore_ghost_commit_with_options(&options, &ghost_commit)
+            })
+            .await
+            .map_err(std::io::Error::other)??;
+        }
+        CheckpointSnapshot::Workspace { snapshot_id } => {
+            let store = SnapshotStore::new(codex_home);
+            tokio::task::spawn_blocking(move || {
+                let snapshot = store.load(&snapshot_id)?;
+                store.restore(&snapshot)
+            })
+            .await
+            .map_err(std::io::Error::other)?
+            .map_err(RollbackError::Workspace)?;
+        }
+    }
+    Ok(checkpoint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::UserMessageEvent;
+    use codex_protocol::protocol::WorkspaceSnapshotEvent;
+    use pretty_assertions::assert_eq;
+
+    fn user_message() -> RolloutItem {
+        RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+            message: "change something".to_string(),
+            images: None,
+        }))
+    }
+
+    fn snapshot(id: &str) -> RolloutItem {
+        RolloutItem::ResponseItem(ResponseItem::GhostSnapshot {
+            ghost_commit: GhostCommit::new(id.to_string(), None, Vec::new(), Vec::new()),
+        })
+    }
+
+    fn workspace_snapshot(id: &str) -> RolloutItem {
+        RolloutItem::EventMsg(EventMsg::WorkspaceSnapshot(WorkspaceSnapshotEvent {
+            snapshot_id: format!("thread/{id}"),
+            cwd: PathBuf::from("/site"),
+            files: 3,
+        }))
+    }
+
+    #[test]
+    fn checkpoints_are_numbered_by_user_turn() {
+        let items = vec![
+            user_message(),
+            snapshot("aaa"),
+            user_message(),
+            user_message(),
+            snapshot("ccc"),
+            user_message(),
+            workspace_snapshot("20250131T100000000000"),
+        ];
+
+        let turns: Vec<(usize, String)> = checkpoints_from_rollout(&items)
+            .iter()
+            .map(|checkpoint| (checkpoint.turn, checkpoint.short_id()))
+            .collect();
+
+        assert_eq!(
+            turns,
+            vec![
+                (1, "aaa".to_string()),
+                (3, "ccc".to_string()),
+                (4, "20250131T100000000000".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_checkpoint_lists_available_turns() {
+        let err = RollbackError::NoCheckpoint {
+            turn: 2,
+            available: vec![1, 3],
+        };
+        assert_eq!(
+            err.to_string(),
+            "no checkpoint was recorded for turn 2; checkpoints exist for turns 1, 3"
+        );
+    }
+}
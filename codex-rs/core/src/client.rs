@@ -46,14 +46,20 @@ use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::client_common::ResponseStream;
 use crate::config::Config;
-use crate::default_client::build_reqwest_client;
+use crate::crash_report;
+use crate::default_client::ProxyDestination;
+use crate::default_client::build_reqwest_client_for;
 use crate::error::CodexErr;
 use crate::error::Result;
 use crate::features::FEATURES;
 use crate::features::Feature;
 use crate::flags::CODEX_RS_SSE_FIXTURE;
+use crate::mock_provider;
+use crate::model_provider_info::MOCK_PROVIDER_ID;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::WireApi;
+use crate::offline;
+use crate::tools::adapter::ToolAdapter;
 use crate::tools::spec::create_tools_json_for_chat_completions_api;
 use crate::tools::spec::create_tools_json_for_responses_api;
 
@@ -118,31 +124,43 @@ impl ModelClient {
     /// For Chat providers, the underlying stream is optionally aggregated
     /// based on the `show_raw_agent_reasoning` flag in the config.
     pub async fn stream(&self, prompt: &Prompt) -> Result<ResponseStream> {
-        match self.provider.wire_api {
-            WireApi::Responses => self.stream_responses_api(prompt).await,
+        offline::ensure_provider_allowed(&self.config, &self.provider)?;
+        let in_flight =
+            crash_report::track_request(self.conversation_id, &self.provider, &self.get_model());
+        let tool_adapter = Arc::new(ToolAdapter::new(&self.provider, &prompt.tools));
+        let mut stream = match self.provider.wire_api {
+            WireApi::Responses => self.stream_responses_api(prompt, &tool_adapter).await?,
             WireApi::Chat => {
-                let api_stream = self.stream_chat_completions(prompt).await?;
+                let api_stream = self.stream_chat_completions(prompt, &tool_adapter).await?;
 
                 if self.config.show_raw_agent_reasoning {
-                    Ok(map_response_stream(
+                    map_response_stream(
                         api_stream.streaming_mode(),
                         self.otel_manager.clone(),
-                    ))
+                        tool_adapter,
+                    )
                 } else {
-                    Ok(map_response_stream(
+                    map_response_stream(
                         api_stream.aggregate(),
                         self.otel_manager.clone(),
-                    ))
+                        tool_adapter,
+                    )
                 }
             }
-        }
+        };
+        stream.in_flight = Some(in_flight);
+        Ok(stream)
     }
 
     /// Streams a turn via the OpenAI Chat Completions API.
     ///
     /// This path is only used when the provider is configured with
     /// `WireApi::Chat`; it does not support `output_schema` today.
-    async fn stream_chat_completions(&self, prompt: &Prompt) -> Result<ApiResponseStream> {
+    async fn stream_chat_completions(
+        &self,
+        prompt: &Prompt,
+        tool_adapter: &ToolAdapter,
+    ) -> Result<ApiResponseStream> {
         if prompt.output_schema.is_some() {
             return Err(CodexErr::UnsupportedOperation(
                 "output_schema is not supported for Chat Completions API".to_string(),
@@ -151,9 +169,12 @@ impl ModelClient {
 
         let auth_manager = self.auth_manager.clone();
         let model_info = self.get_model_info();
-        let instructions = prompt.get_full_instructions(&model_info).into_owned();
-        let tools_json = create_tools_json_for_chat_completions_api(&prompt.tools)?;
-        let api_prompt = build_api_prompt(prompt, instructions, tools_json);
+        let (instructions, tools_json) = tool_adapter.embed_tools(
+            prompt.get_full_instructions(&model_info).into_owned(),
+            tool_adapter.adapt_tools(create_tools_json_for_responses_api(&prompt.tools)?),
+        );
+        let tools_json = create_tools_json_for_chat_completions_api(tools_json);
+        let api_prompt = build_api_prompt(prompt, instructions, tools_json, tool_adapter);
         let conversation_id = self.conversation_id.to_string();
         let session_source = self.session_source.clone();
 
@@ -169,7 +190,10 @@ impl ModelClient {
                 .provider
                 .to_api_provider(auth.as_ref().map(|a| a.mode))?;
             let api_auth = auth_provider_from_auth(auth.clone(), &self.provider)?;
-            let transport = ReqwestTransport::new(build_reqwest_client());
+            let transport = ReqwestTransport::new(build_reqwest_client_for(
+                &self.config.proxy,
+                ProxyDestination::ModelProvider,
+            ));
             let (request_telemetry, sse_telemetry) = self.build_streaming_telemetry();
             let client = ApiChatClient::new(transport, api_provider, api_auth)
                 .with_telemetry(Some(request_telemetry), Some(sse_telemetry));
@@ -200,18 +224,41 @@ impl ModelClient {
     ///
     /// Handles SSE fixtures, reasoning summaries, verbosity, and the
     /// `text` controls used for output schemas.
-    async fn stream_responses_api(&self, prompt: &Prompt) -> Result<ResponseStream> {
+    async fn stream_responses_api(
+        &self,
+        prompt: &Prompt,
+        tool_adapter: &Arc<ToolAdapter>,
+    ) -> Result<ResponseStream> {
+        if self.config.model_provider_id == MOCK_PROVIDER_ID {
+            let stream = mock_provider::stream(
+                &self.config,
+                self.conversation_id,
+                self.provider.stream_idle_timeout(),
+            )?;
+            return Ok(map_response_stream(
+                stream,
+                self.otel_manager.clone(),
+                Arc::clone(tool_adapter),
+            ));
+        }
+
         if let Some(path) = &*CODEX_RS_SSE_FIXTURE {
             warn!(path, "Streaming from fixture");
             let stream = codex_api::stream_from_fixture(path, self.provider.stream_idle_timeout())
                 .map_err(map_api_error)?;
-            return Ok(map_response_stream(stream, self.otel_manager.clone()));
+            return Ok(map_response_stream(
+                stream,
+                self.otel_manager.clone(),
+                Arc::clone(tool_adapter),
+            ));
         }
 
         let auth_manager = self.auth_manager.clone();
         let model_info = self.get_model_info();
-        let instructions = prompt.get_full_instructions(&model_info).into_owned();
-        let tools_json: Vec<Value> = create_tools_json_for_responses_api(&prompt.tools)?;
+        let (instructions, tools_json) = tool_adapter.embed_tools(
+            prompt.get_full_instructions(&model_info).into_owned(),
+            tool_adapter.adapt_tools(create_tools_json_for_responses_api(&prompt.tools)?),
+        );
 
         let default_reasoning_effort = model_info.default_reasoning_level;
         let reasoning = if model_info.supports_reasoning_summaries {
@@ -246,7 +293,7 @@ impl ModelClient {
         };
 
         let text = create_text_param_for_request(verbosity, &prompt.output_schema);
-        let api_prompt = build_api_prompt(prompt, instructions.clone(), tools_json);
+        let api_prompt = build_api_prompt(prompt, instructions.clone(), tools_json, tool_adapter);
         let conversation_id = self.conversation_id.to_string();
         let session_source = self.session_source.clone();
 
@@ -262,7 +309,10 @@ impl ModelClient {
                 .provider
                 .to_api_provider(auth.as_ref().map(|a| a.mode))?;
             let api_auth = auth_provider_from_auth(auth.clone(), &self.provider)?;
-            let transport = ReqwestTransport::new(build_reqwest_client());
+            let transport = ReqwestTransport::new(build_reqwest_client_for(
+                &self.config.proxy,
+                ProxyDestination::ModelProvider,
+            ));
             let (request_telemetry, sse_telemetry) = self.build_streaming_telemetry();
             let compression = if self
                 .config
@@ -299,7 +349,11 @@ impl ModelClient {
 
             match stream_result {
                 Ok(stream) => {
-                    return Ok(map_response_stream(stream, self.otel_manager.clone()));
+                    return Ok(map_response_stream(
+                        stream,
+                        self.otel_manager.clone(),
+                        Arc::clone(tool_adapter),
+                    ));
                 }
                 Err(ApiError::Transport(TransportError::Http { status, .. }))
                     if status == StatusCode::UNAUTHORIZED =>
@@ -355,6 +409,7 @@ impl ModelClient {
         if prompt.input.is_empty() {
             return Ok(Vec::new());
         }
+        offline::ensure_provider_allowed(&self.config, &self.provider)?;
         let auth_manager = self.auth_manager.clone();
         let auth = match auth_manager.as_ref() {
             Some(manager) => manager.auth().await,
@@ -364,7 +419,10 @@ impl ModelClient {
             .provider
             .to_api_provider(auth.as_ref().map(|a| a.mode))?;
         let api_auth = auth_provider_from_auth(auth.clone(), &self.provider)?;
-        let transport = ReqwestTransport::new(build_reqwest_client());
+        let transport = ReqwestTransport::new(build_reqwest_client_for(
+            &self.config.proxy,
+            ProxyDestination::ModelProvider,
+        ));
         let request_telemetry = self.build_request_telemetry();
         let client = ApiCompactClient::new(transport, api_provider, api_auth)
             .with_telemetry(Some(request_telemetry));
@@ -418,13 +476,19 @@ impl ModelClient {
 }
 
 /// Adapts the core `Prompt` type into the `codex-api` payload shape.
-fn build_api_prompt(prompt: &Prompt, instructions: String, tools_json: Vec<Value>) -> ApiPrompt {
+fn build_api_prompt(
+    prompt: &Prompt,
+    instructions: String,
+    tools_json: Vec<Value>,
+    tool_adapter: &ToolAdapter,
+) -> ApiPrompt {
     ApiPrompt {
         instructions,
-        input: prompt.get_formatted_input(),
+        input: tool_adapter.adapt_input(prompt.get_formatted_input()),
         tools: tools_json,
-        parallel_tool_calls: prompt.parallel_tool_calls,
+        parallel_tool_calls: tool_adapter.parallel_tool_calls(prompt.parallel_tool_calls),
         output_schema: prompt.output_schema.clone(),
+        max_output_tokens: prompt.max_output_tokens,
     }
 }
 
@@ -449,7 +513,11 @@ fn beta_feature_headers(config: &Config) -> ApiHeaderMap {
     headers
 }
 
-fn map_response_stream<S>(api_stream: S, otel_manager: OtelManager) -> ResponseStream
+fn map_response_stream<S>(
+    api_stream: S,
+    otel_manager: OtelManager,
+    tool_adapter: Arc<ToolAdapter>,
+) -> ResponseStream
 where
     S: futures::Stream<Item = std::result::Result<ResponseEvent, ApiError>>
         + Unpin
@@ -461,7 +529,16 @@ where
     tokio::spawn(async move {
         let mut logged_error = false;
         let mut api_stream = api_stream;
-        while let Some(event) = api_stream.next().await {
+        loop {
+            // Stop as soon as the consumer goes away, e.g. because the turn was
+            // interrupted, so the HTTP stream underneath is dropped too.
+            let event = tokio::select! {
+                event = api_stream.next() => event,
+                () = tx_event.closed() => return,
+            };
+            let Some(event) = event else {
+                return;
+            };
             match event {
                 Ok(ResponseEvent::Completed {
                     response_id,
@@ -487,6 +564,32 @@ where
                         return;
                     }
                 }
+                Ok(ResponseEvent::OutputItemDone(item)) => {
+                    for item in tool_adapter.restore_items(item) {
+                        if tx_event
+                            .send(Ok(ResponseEvent::OutputItemDone(item)))
+                            .await
+                            .is_err()
+                        {
+                            return;
+                        }
+                    }
+                }
+                // A message may turn out to hold tool calls written as text,
+                // so it is only reported once it is done.
+                Ok(ResponseEvent::OutputItemAdded(ResponseItem::Message { .. }))
+                | Ok(ResponseEvent::OutputTextDelta(_))
+                    if tool_adapter.text_tool_calls() => {}
+                Ok(ResponseEvent::OutputItemAdded(item)) => {
+                    let item = tool_adapter.restore_output(item);
+                    if tx_event
+                        .send(Ok(ResponseEvent::OutputItemAdded(item)))
+                        .await
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
                 Ok(event) => {
                     if tx_event.send(Ok(event)).await.is_err() {
                         return;
@@ -506,7 +609,10 @@ where
         }
     });
 
-    ResponseStream { rx_event }
+    ResponseStream {
+        rx_event,
+        in_flight: None,
+    }
 }
 
 /// Handles a 401 response by optionally refreshing ChatGPT tokens once.
@@ -1,4 +1,5 @@
 use crate::client_common::tools::ToolSpec;
+use crate::crash_report::InFlightRequest;
 use crate::error::Result;
 pub use codex_api::common::ResponseEvent;
 use codex_protocol::models::ResponseItem;
@@ -39,6 +40,9 @@ pub struct Prompt {
 
     /// Optional the output schema for the model's response.
     pub output_schema: Option<Value>,
+
+    /// Most tokens the model may generate in its response.
+    pub(crate) max_output_tokens: Option<u64>,
 }
 
 impl Prompt {
@@ -227,6 +231,8 @@ pub(crate) mod tools {
 
 pub struct ResponseStream {
     pub(crate) rx_event: mpsc::Receiver<Result<ResponseEvent>>,
+    /// Keeps the request listed in crash reports until the stream is dropped.
+    pub(crate) in_flight: Option<InFlightRequest>,
 }
 
 impl Stream for ResponseStream {
@@ -331,6 +337,7 @@ mod tests {
             stream: true,
             include: vec![],
             prompt_cache_key: None,
+            max_output_tokens: None,
             text: Some(TextControls {
                 verbosity: Some(OpenAiVerbosity::Low),
                 format: None,
@@ -372,6 +379,7 @@ mod tests {
             stream: true,
             include: vec![],
             prompt_cache_key: None,
+            max_output_tokens: None,
             text: Some(text_controls),
         };
 
@@ -408,6 +416,7 @@ mod tests {
             stream: true,
             include: vec![],
             prompt_cache_key: None,
+            max_output_tokens: None,
             text: None,
         };
 
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::path::PathBuf;
@@ -12,7 +14,9 @@ use crate::SandboxState;
 use crate::agent::AgentControl;
 use crate::agent::AgentStatus;
 use crate::agent::agent_status_from_event;
+use crate::approval_rules::ApprovalRules;
 use crate::client_common::REVIEW_PROMPT;
+use crate::command_safety::classify_command::classify_command;
 use crate::compact;
 use crate::compact::run_inline_auto_compact_task;
 use crate::compact::should_use_remote_compact_task;
@@ -20,6 +24,19 @@ use crate::compact_remote::run_inline_remote_auto_compact_task;
 use crate::exec_policy::ExecPolicyManager;
 use crate::features::Feature;
 use crate::features::Features;
+use crate::guardrails::TOOL_FAILURE_BREAKER;
+use crate::guardrails::TOOL_RESULT_EVENTS;
+use crate::guardrails::ToolFailureBreaker;
+use crate::hooks::EventFilter;
+use crate::hooks::HookEvent;
+use crate::hooks::HookHandler;
+use crate::hooks::HookRegistration;
+use crate::hooks::HookVeto;
+use crate::hooks::Hooks;
+use crate::injection::InjectionDetector;
+use crate::interceptors::Interception;
+use crate::interceptors::Interceptors;
+use crate::interceptors::TurnInfo;
 use crate::models_manager::manager::ModelsManager;
 use crate::parse_command::parse_command;
 use crate::parse_turn_item;
@@ -28,30 +45,51 @@ use crate::stream_events_utils::handle_non_tool_response_item;
 use crate::stream_events_utils::handle_output_item_done;
 use crate::terminal;
 use crate::truncate::TruncationPolicy;
+use crate::truncate::adaptive_policy;
 use crate::user_notification::UserNotifier;
 use crate::util::error_or_panic;
+use crate::variables::Variables;
+use crate::verify::Verifier;
+use crate::watchdog::Watchdogs;
+use crate::watchdog::await_approval;
+use crate::watchdog::start_session_watchdog;
+use crate::workspace_snapshot::WorkspaceSnapshots;
 use async_channel::Receiver;
 use async_channel::Sender;
+use async_channel::WeakSender;
 use codex_protocol::ThreadId;
 use codex_protocol::approvals::ExecPolicyAmendment;
 use codex_protocol::items::TurnItem;
 use codex_protocol::openai_models::ModelInfo;
+use codex_protocol::protocol::CancelledOperation;
+use codex_protocol::protocol::ContentSource;
+use codex_protocol::protocol::ContextRepairedEvent;
+use codex_protocol::protocol::ContextSource;
+use codex_protocol::protocol::ContextSourcesEvent;
+use codex_protocol::protocol::DroppedContext;
 use codex_protocol::protocol::FileChange;
 use codex_protocol::protocol::HasLegacyEvent;
 use codex_protocol::protocol::ItemCompletedEvent;
 use codex_protocol::protocol::ItemStartedEvent;
+use codex_protocol::protocol::OutputBudgetEvent;
 use codex_protocol::protocol::RawResponseItemEvent;
 use codex_protocol::protocol::ReviewRequest;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::SessionSource;
 use codex_protocol::protocol::TurnAbortReason;
+use codex_protocol::protocol::TurnCompleteEvent;
 use codex_protocol::protocol::TurnContextItem;
+use codex_protocol::protocol::TurnDiffItem;
 use codex_protocol::protocol::TurnStartedEvent;
+use codex_protocol::protocol::TurnTiming;
+use codex_protocol::protocol::VerificationStatus;
 use codex_rmcp_client::ElicitationResponse;
 use futures::future::BoxFuture;
 use futures::prelude::*;
 use futures::stream::FuturesOrdered;
 use mcp_types::CallToolResult;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
 use mcp_types::ListResourceTemplatesRequestParams;
 use mcp_types::ListResourceTemplatesResult;
 use mcp_types::ListResourcesRequestParams;
@@ -84,30 +122,61 @@ use crate::config::Config;
 use crate::config::Constrained;
 use crate::config::ConstraintResult;
 use crate::config::GhostSnapshotConfig;
+use crate::config::types::ContextBudgetConfig;
+use crate::config::types::DevcontainerExecBackend;
+use crate::config::types::ExecBackendConfig;
+use crate::config::types::PullRequestConfig;
+use crate::config::types::ReasoningRetention;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::context_manager::ContextManager;
+use crate::context_manager::apply_context_budget;
+use crate::context_manager::repair_budget;
+use crate::devcontainer::Devcontainer;
+use crate::devcontainer::DevcontainerState;
 use crate::environment_context::EnvironmentContext;
+use crate::environment_fingerprint;
+use crate::environment_fingerprint::fingerprint_notice;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
+use crate::event_socket::EventSocket;
 #[cfg(test)]
 use crate::exec::StreamOutput;
 use crate::exec_policy::ExecPolicyUpdateError;
 use crate::feedback_tags;
+use crate::file_watch::FileWatch;
+use crate::file_watch::changed_files_notice;
+use crate::in_flight::InFlightOperations;
+use crate::mcp::McpPromptExpansion;
+use crate::mcp::McpResourceInjections;
+use crate::mcp::McpToolCache;
 use crate::mcp::auth::compute_auth_statuses;
+use crate::mcp::build_mcp_resource_injections;
+use crate::mcp::expand_mcp_prompts;
+use crate::mcp::session_sampler;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::model_provider_info::CHAT_WIRE_API_DEPRECATION_SUMMARY;
+use crate::prefetch::Prefetcher;
+use crate::prefetch::StreamedReads;
+use crate::project_doc::discover_project_doc_paths;
 use crate::project_doc::get_user_instructions;
+use crate::project_doc_watcher::ProjectDocWatcher;
+use crate::project_doc_watcher::project_docs_update_notice;
 use crate::protocol::AgentMessageContentDeltaEvent;
 use crate::protocol::AgentReasoningSectionBreakEvent;
 use crate::protocol::ApplyPatchApprovalRequestEvent;
 use crate::protocol::AskForApproval;
 use crate::protocol::BackgroundEventEvent;
+use crate::protocol::ConfigOverrideAppliedEvent;
 use crate::protocol::DeprecationNoticeEvent;
 use crate::protocol::ErrorEvent;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecApprovalRequestEvent;
+use crate::protocol::GuardrailTrippedEvent;
+use crate::protocol::HookSource;
+use crate::protocol::ModelSettingsChangedEvent;
 use crate::protocol::Op;
+use crate::protocol::ProjectDocsUpdatedEvent;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::ReasoningContentDeltaEvent;
 use crate::protocol::ReasoningRawContentDeltaEvent;
@@ -123,6 +192,14 @@ use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
 use crate::protocol::TurnDiffEvent;
 use crate::protocol::WarningEvent;
+use crate::protocol::WatchedFilesChangedEvent;
+use crate::provenance;
+use crate::pull_request;
+use crate::reasoning_retention::retain_event;
+use crate::reasoning_retention::retain_rollout_item;
+use crate::redaction;
+use crate::redaction::Redactor;
+use crate::remote_exec::RemoteExec;
 use crate::rollout::RolloutRecorder;
 use crate::rollout::RolloutRecorderParams;
 use crate::rollout::map_session_init_error;
@@ -136,6 +213,7 @@ use crate::skills::build_skill_injections;
 use crate::state::ActiveTurn;
 use crate::state::SessionServices;
 use crate::state::SessionState;
+use crate::symbol_index::SymbolIndex;
 use crate::tasks::GhostSnapshotTask;
 use crate::tasks::ReviewTask;
 use crate::tasks::SessionTask;
@@ -147,6 +225,7 @@ use crate::tools::sandboxing::ApprovalStore;
 use crate::tools::spec::ToolsConfig;
 use crate::tools::spec::ToolsConfigParams;
 use crate::turn_diff_tracker::TurnDiffTracker;
+use crate::turn_timing::TurnTimer;
 use crate::unified_exec::UnifiedExecProcessManager;
 use crate::user_instructions::DeveloperInstructions;
 use crate::user_instructions::UserInstructions;
@@ -155,6 +234,7 @@ use crate::util::backoff;
 use codex_async_utils::OrCancelExt;
 use codex_otel::OtelManager;
 use codex_protocol::config_types::ReasoningSummary as ReasoningSummaryConfig;
+use codex_protocol::config_types::Verbosity;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::ResponseInputItem;
 use codex_protocol::models::ResponseItem;
@@ -173,6 +253,10 @@ pub struct Codex {
     pub(crate) rx_event: Receiver<Event>,
     // Last known status of the agent.
     pub(crate) agent_status: Arc<RwLock<AgentStatus>>,
+    // Timing of the most recently completed turn.
+    pub(crate) last_turn_timing: Arc<RwLock<Option<TurnTiming>>>,
+    pub(crate) hooks: Arc<Hooks>,
+    pub(crate) interceptors: Arc<Interceptors>,
 }
 
 /// Wrapper returned by [`Codex::spawn`] containing the spawned [`Codex`],
@@ -187,6 +271,9 @@ pub struct CodexSpawnOk {
 
 pub(crate) const INITIAL_SUBMIT_ID: &str = "";
 pub(crate) const SUBMISSION_CHANNEL_CAPACITY: usize = 64;
+/// Times a turn retries after the model rejects its request as too long,
+/// before the error is surfaced to the user.
+const MAX_CONTEXT_REPAIRS: u32 = 2;
 static CHAT_WIRE_API_DEPRECATION_EMITTED: AtomicBool = AtomicBool::new(false);
 
 fn maybe_push_chat_wire_api_deprecation(
@@ -215,6 +302,7 @@ fn maybe_push_chat_wire_api_deprecation(
 
 impl Codex {
     /// Spawn a new [`Codex`] and initialize the session.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) async fn spawn(
         config: Config,
         auth_manager: Arc<AuthManager>,
@@ -223,6 +311,7 @@ impl Codex {
         conversation_history: InitialHistory,
         session_source: SessionSource,
         agent_control: AgentControl,
+        embedder_hooks: Vec<HookRegistration>,
     ) -> CodexResult<CodexSpawnOk> {
         let (tx_sub, rx_sub) = async_channel::bounded(SUBMISSION_CHANNEL_CAPACITY);
         let (tx_event, rx_event) = async_channel::unbounded();
@@ -262,6 +351,7 @@ impl Codex {
             model: model.clone(),
             model_reasoning_effort: config.model_reasoning_effort,
             model_reasoning_summary: config.model_reasoning_summary,
+            model_verbosity: config.model_verbosity,
             developer_instructions: config.developer_instructions.clone(),
             user_instructions,
             base_instructions: config.base_instructions.clone(),
@@ -276,6 +366,7 @@ impl Codex {
         // Generate a unique ID for the lifetime of this Codex session.
         let session_source_clone = session_configuration.session_source.clone();
         let agent_status = Arc::new(RwLock::new(AgentStatus::PendingInit));
+        let last_turn_timing = Arc::new(RwLock::new(None));
 
         let session = Session::new(
             session_configuration,
@@ -284,11 +375,14 @@ impl Codex {
             models_manager.clone(),
             exec_policy,
             tx_event.clone(),
+            tx_sub.downgrade(),
             Arc::clone(&agent_status),
+            Arc::clone(&last_turn_timing),
             conversation_history,
             session_source_clone,
             skills_manager,
             agent_control,
+            embedder_hooks,
         )
         .await
         .map_err(|e| {
@@ -296,14 +390,21 @@ impl Codex {
             map_session_init_error(&e, &config.codex_home)
         })?;
         let thread_id = session.conversation_id;
+        let hooks = Arc::clone(&session.services.hooks);
+        let interceptors = Arc::clone(&session.services.interceptors);
 
-        // This task will run until Op::Shutdown is received.
-        tokio::spawn(submission_loop(session, config, rx_sub));
+        // This task will run until Op::Shutdown is received. Running it inside
+        // the session span attributes its logs to this session.
+        let session_span = session.services.otel_manager.current_span().clone();
+        tokio::spawn(submission_loop(session, config, rx_sub).instrument(session_span));
         let codex = Codex {
             next_id: AtomicU64::new(0),
             tx_sub,
             rx_event,
             agent_status,
+            last_turn_timing,
+            hooks,
+            interceptors,
         };
 
         #[allow(deprecated)]
@@ -335,6 +436,12 @@ impl Codex {
         Ok(())
     }
 
+    /// Streams the session's events matching `filter`; see
+    /// [`Hooks::subscribe`].
+    pub fn subscribe(&self, filter: EventFilter) -> impl Stream<Item = HookEvent> + Send + 'static {
+        self.hooks.subscribe(filter)
+    }
+
     pub async fn next_event(&self) -> CodexResult<Event> {
         let event = self
             .rx_event
@@ -348,6 +455,10 @@ impl Codex {
         let status = self.agent_status.read().await;
         status.clone()
     }
+
+    pub(crate) async fn last_turn_timing(&self) -> Option<TurnTiming> {
+        *self.last_turn_timing.read().await
+    }
 }
 
 /// Context for an initialized model agent
@@ -357,6 +468,7 @@ pub(crate) struct Session {
     conversation_id: ThreadId,
     tx_event: Sender<Event>,
     agent_status: Arc<RwLock<AgentStatus>>,
+    last_turn_timing: Arc<RwLock<Option<TurnTiming>>>,
     state: Mutex<SessionState>,
     /// The set of enabled features should be invariant for the lifetime of the
     /// session.
@@ -386,17 +498,120 @@ pub(crate) struct TurnContext {
     pub(crate) ghost_snapshot: GhostSnapshotConfig,
     pub(crate) final_output_json_schema: Option<Value>,
     pub(crate) codex_linux_sandbox_exe: Option<PathBuf>,
+    /// Host that tool commands run on, with `[exec_backend] type = "ssh"`.
+    pub(crate) remote_exec: Option<RemoteExec>,
     pub(crate) tool_call_gate: Arc<ReadinessFlag>,
     pub(crate) truncation_policy: TruncationPolicy,
+    /// How `truncation_policy` was scaled to the room left in the context
+    /// window (`adaptive_truncation` feature); reported on `TurnStarted`.
+    pub(crate) output_budget: Option<OutputBudgetEvent>,
+    /// Started when the turn is submitted; reported on `TurnComplete`.
+    pub(crate) timing: Arc<TurnTimer>,
+    /// Model streams and tool calls still running; reported on `TurnAborted`.
+    pub(crate) in_flight_operations: Arc<InFlightOperations>,
+    /// Diff of the files the turn edited, set when it finishes; reported on
+    /// `TurnComplete`.
+    pub(crate) unified_diff: std::sync::Mutex<Option<String>>,
+    /// Outcome of the `[verify]` command, set when the turn finishes;
+    /// reported on `TurnComplete`.
+    pub(crate) verification: std::sync::Mutex<Option<VerificationStatus>>,
+    /// What the last model request left out to respect `[context_budget]`;
+    /// reported on `TurnComplete`.
+    pub(crate) context_dropped: std::sync::Mutex<Vec<DroppedContext>>,
+    /// Item id and text so far of the assistant message being streamed,
+    /// kept in the history when the turn is stopped with `Op::Stop`.
+    pub(crate) partial_message: std::sync::Mutex<Option<(String, String)>>,
 }
 
 impl TurnContext {
+    /// Whether so little context is left that long command output is
+    /// summarized rather than cut in the middle.
+    pub(crate) fn summarizes_tool_output(&self) -> bool {
+        self.output_budget
+            .as_ref()
+            .is_some_and(|budget| budget.summarize)
+    }
+
     pub(crate) fn resolve_path(&self, path: Option<String>) -> PathBuf {
         path.as_ref()
             .map(PathBuf::from)
             .map_or_else(|| self.cwd.clone(), |p| self.cwd.join(p))
     }
 
+    pub(crate) fn set_unified_diff(&self, unified_diff: String) {
+        let mut guard = match self.unified_diff.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        };
+        *guard = Some(unified_diff);
+    }
+
+    pub(crate) fn take_unified_diff(&self) -> Option<String> {
+        let mut guard = match self.unified_diff.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        };
+        guard.take()
+    }
+
+    pub(crate) fn set_verification(&self, verification: VerificationStatus) {
+        let mut guard = match self.verification.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        };
+        *guard = Some(verification);
+    }
+
+    pub(crate) fn take_verification(&self) -> Option<VerificationStatus> {
+        let mut guard = match self.verification.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        };
+        guard.take()
+    }
+
+    pub(crate) fn set_context_dropped(&self, context_dropped: Vec<DroppedContext>) {
+        let mut guard = match self.context_dropped.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        };
+        *guard = context_dropped;
+    }
+
+    pub(crate) fn take_context_dropped(&self) -> Vec<DroppedContext> {
+        let mut guard = match self.context_dropped.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        };
+        std::mem::take(&mut *guard)
+    }
+
+    pub(crate) fn start_partial_message(&self, item_id: String) {
+        let mut guard = match self.partial_message.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        };
+        *guard = Some((item_id, String::new()));
+    }
+
+    pub(crate) fn push_partial_message(&self, delta: &str) {
+        let mut guard = match self.partial_message.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        };
+        if let Some((_, text)) = guard.as_mut() {
+            text.push_str(delta);
+        }
+    }
+
+    pub(crate) fn take_partial_message(&self) -> Option<(String, String)> {
+        let mut guard = match self.partial_message.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        };
+        guard.take()
+    }
+
     pub(crate) fn compact_prompt(&self) -> &str {
         self.compact_prompt
             .as_deref()
@@ -414,6 +629,7 @@ pub(crate) struct SessionConfiguration {
 
     model_reasoning_effort: Option<ReasoningEffortConfig>,
     model_reasoning_summary: ReasoningSummaryConfig,
+    model_verbosity: Option<Verbosity>,
 
     /// Developer instructions that supplement the base instructions.
     developer_instructions: Option<String>,
@@ -459,6 +675,9 @@ impl SessionConfiguration {
         if let Some(summary) = updates.reasoning_summary {
             next_configuration.model_reasoning_summary = summary;
         }
+        if let Some(verbosity) = updates.verbosity {
+            next_configuration.model_verbosity = verbosity;
+        }
         if let Some(approval_policy) = updates.approval_policy {
             next_configuration.approval_policy.set(approval_policy)?;
         }
@@ -480,6 +699,7 @@ pub(crate) struct SessionSettingsUpdate {
     pub(crate) model: Option<String>,
     pub(crate) reasoning_effort: Option<Option<ReasoningEffortConfig>>,
     pub(crate) reasoning_summary: Option<ReasoningSummaryConfig>,
+    pub(crate) verbosity: Option<Option<Verbosity>>,
     pub(crate) final_output_json_schema: Option<Option<Value>>,
 }
 
@@ -491,6 +711,7 @@ impl Session {
         let mut per_turn_config = (*config).clone();
         per_turn_config.model_reasoning_effort = session_configuration.model_reasoning_effort;
         per_turn_config.model_reasoning_summary = session_configuration.model_reasoning_summary;
+        per_turn_config.model_verbosity = session_configuration.model_verbosity;
         per_turn_config.features = config.features.clone();
         per_turn_config
     }
@@ -527,7 +748,8 @@ impl Session {
         let tools_config = ToolsConfig::new(&ToolsConfigParams {
             model_info: &model_info,
             features: &per_turn_config.features,
-        });
+        })
+        .with_banned_tools(per_turn_config.banned_tools.clone());
 
         TurnContext {
             sub_id,
@@ -544,8 +766,20 @@ impl Session {
             ghost_snapshot: per_turn_config.ghost_snapshot.clone(),
             final_output_json_schema: None,
             codex_linux_sandbox_exe: per_turn_config.codex_linux_sandbox_exe.clone(),
+            remote_exec: RemoteExec::new(
+                &per_turn_config.exec_backend,
+                &session_configuration.cwd,
+                &per_turn_config.codex_home,
+            ),
             tool_call_gate: Arc::new(ReadinessFlag::new()),
             truncation_policy: model_info.truncation_policy.into(),
+            output_budget: None,
+            timing: Arc::new(TurnTimer::new()),
+            in_flight_operations: Arc::default(),
+            unified_diff: Default::default(),
+            verification: Default::default(),
+            context_dropped: Default::default(),
+            partial_message: Default::default(),
         }
     }
 
@@ -557,11 +791,14 @@ impl Session {
         models_manager: Arc<ModelsManager>,
         exec_policy: ExecPolicyManager,
         tx_event: Sender<Event>,
+        submissions: WeakSender<Submission>,
         agent_status: Arc<RwLock<AgentStatus>>,
+        last_turn_timing: Arc<RwLock<Option<TurnTiming>>>,
         initial_history: InitialHistory,
         session_source: SessionSource,
         skills_manager: Arc<SkillsManager>,
         agent_control: AgentControl,
+        embedder_hooks: Vec<HookRegistration>,
     ) -> anyhow::Result<Arc<Self>> {
         debug!(
             "Configuring session: model={}; provider={:?}",
@@ -633,6 +870,14 @@ impl Session {
             });
         }
         maybe_push_chat_wire_api_deprecation(&config, &mut post_session_configured_events);
+        for message in &config.config_warnings {
+            post_session_configured_events.push(Event {
+                id: INITIAL_SUBMIT_ID.to_owned(),
+                msg: EventMsg::Warning(WarningEvent {
+                    message: message.clone(),
+                }),
+            });
+        }
 
         let auth = auth_manager.auth().await;
         let auth = auth.as_ref();
@@ -682,16 +927,60 @@ impl Session {
                     .map(Arc::new);
         }
         let state = SessionState::new(session_configuration.clone());
+        let approval_rules =
+            ApprovalRules::load(&config.config_layer_stack, &config.approval_rules).await?;
+        let variables = Variables::default();
+        let hooks = Hooks::from_config(&config.hooks, &config.required_hooks, &variables)
+            .with_registrations(embedder_hooks)
+            .with_otel_manager(otel_manager.clone());
+        let tool_failure_breaker = Arc::new(ToolFailureBreaker::new(&config.guardrails));
+        if tool_failure_breaker.enabled() {
+            hooks.register(
+                TOOL_FAILURE_BREAKER,
+                TOOL_RESULT_EVENTS.iter().map(ToString::to_string).collect(),
+                HookSource::BuiltIn,
+                Arc::clone(&tool_failure_breaker) as Arc<dyn HookHandler>,
+            );
+        }
+        let hooks = Arc::new(hooks);
+        let event_socket = EventSocket::start(
+            &hooks,
+            submissions,
+            &config.codex_home,
+            conversation_id,
+            config.event_socket_approvals,
+        );
 
         let services = SessionServices {
             mcp_connection_manager: Arc::new(RwLock::new(McpConnectionManager::default())),
             mcp_startup_cancellation_token: CancellationToken::new(),
             unified_exec_manager: UnifiedExecProcessManager::default(),
             notifier: UserNotifier::new(config.notify.clone()),
+            hooks,
+            event_socket,
+            tool_failure_breaker,
+            watchdogs: Watchdogs::new(&config.watchdogs),
+            interceptors: Arc::new(Interceptors::default()),
+            redactor: Redactor::new(&config.redaction)?,
+            injection_detector: InjectionDetector::new(&config.injection_detection),
+            project_doc_watcher: ProjectDocWatcher::new(&config),
+            file_watch: FileWatch::new(config.features.enabled(Feature::WatchReadFiles)),
+            workspace_snapshots: WorkspaceSnapshots::new(
+                config.features.enabled(Feature::GhostCommit),
+                &config.codex_home,
+                conversation_id,
+            ),
+            prefetch: Prefetcher::new(config.features.enabled(Feature::PrefetchReads)),
+            symbol_index: SymbolIndex::default(),
+            devcontainers: Mutex::new(HashMap::new()),
+            publishing: Mutex::new(()),
             rollout: Mutex::new(Some(rollout_recorder)),
             user_shell: Arc::new(default_shell),
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
+            reasoning: config.reasoning,
+            variables,
             exec_policy,
+            approval_rules,
             auth_manager: Arc::clone(&auth_manager),
             otel_manager,
             models_manager: Arc::clone(&models_manager),
@@ -704,6 +993,7 @@ impl Session {
             conversation_id,
             tx_event: tx_event.clone(),
             agent_status: Arc::clone(&agent_status),
+            last_turn_timing,
             state: Mutex::new(state),
             features: config.features.clone(),
             active_turn: Mutex::new(None),
@@ -753,11 +1043,15 @@ impl Session {
                 tx_event.clone(),
                 sess.services.mcp_startup_cancellation_token.clone(),
                 sandbox_state,
+                Some(session_sampler(Arc::downgrade(&sess))),
+                Some(McpToolCache::new(&config.codex_home)),
+                config.proxy.clone(),
             )
             .await;
 
         // record_initial_history can emit events. We record only after the SessionConfiguredEvent is emitted.
         sess.record_initial_history(initial_history).await;
+        start_session_watchdog(&sess);
 
         Ok(sess)
     }
@@ -798,6 +1092,15 @@ impl Session {
                 // Build and record initial items (user instructions + environment context)
                 let items = self.build_initial_context(&turn_context);
                 self.record_conversation_items(&turn_context, &items).await;
+                let config = {
+                    let state = self.state.lock().await;
+                    Arc::clone(&state.session_configuration.original_config_do_not_use)
+                };
+                match discover_project_doc_paths(&config) {
+                    Ok(paths) => self.report_instruction_sources(&turn_context, &paths).await,
+                    Err(err) => warn!("failed to list project docs: {err}"),
+                }
+                self.record_environment_fingerprint(&turn_context).await;
                 // Ensure initial items are visible to immediate readers (e.g., tests, forks).
                 self.flush_rollout().await;
             }
@@ -848,16 +1151,40 @@ impl Session {
                     state.set_token_info(Some(info));
                 }
 
+                if let Some(variables) = Self::last_variables_from_rollout(&rollout_items) {
+                    self.services.variables.replace(variables);
+                }
+
                 // If persisting, persist all rollout items as-is (recorder filters)
                 if persist && !rollout_items.is_empty() {
                     self.persist_rollout_items(&rollout_items).await;
                 }
+                // The history already tells the model about the environment
+                // when the rollout has a fingerprint.
+                let fingerprinted = rollout_items.iter().any(|item| {
+                    matches!(
+                        item,
+                        RolloutItem::EventMsg(EventMsg::EnvironmentFingerprint(_))
+                    )
+                });
+                if !fingerprinted {
+                    self.record_environment_fingerprint(&turn_context).await;
+                }
                 // Flush after seeding history and any persisted rollout copy.
                 self.flush_rollout().await;
             }
         }
     }
 
+    fn last_variables_from_rollout(
+        rollout_items: &[RolloutItem],
+    ) -> Option<BTreeMap<String, String>> {
+        rollout_items.iter().rev().find_map(|item| match item {
+            RolloutItem::EventMsg(EventMsg::VariablesUpdated(ev)) => Some(ev.variables.clone()),
+            _ => None,
+        })
+    }
+
     fn last_token_info_from_rollout(rollout_items: &[RolloutItem]) -> Option<TokenUsageInfo> {
         rollout_items.iter().rev().find_map(|item| match item {
             RolloutItem::EventMsg(EventMsg::TokenCount(ev)) => ev.info.clone(),
@@ -865,16 +1192,41 @@ impl Session {
         })
     }
 
+    /// Applies `updates` to the session configuration and returns the approval
+    /// and sandbox policy values that changed, along with the new model
+    /// settings if the model, effort, summary or verbosity changed. Each
+    /// change is reported by exactly one of the two.
     pub(crate) async fn update_settings(
         &self,
         updates: SessionSettingsUpdate,
-    ) -> ConstraintResult<()> {
+    ) -> ConstraintResult<(
+        ConfigOverrideAppliedEvent,
+        Option<ModelSettingsChangedEvent>,
+    )> {
         let mut state = self.state.lock().await;
 
         match state.session_configuration.apply(&updates) {
             Ok(updated) => {
+                let previous = &state.session_configuration;
+                let applied = ConfigOverrideAppliedEvent {
+                    approval_policy: (updated.approval_policy.get()
+                        != previous.approval_policy.get())
+                    .then(|| updated.approval_policy.value()),
+                    sandbox_policy: (updated.sandbox_policy.get() != previous.sandbox_policy.get())
+                        .then(|| updated.sandbox_policy.get().clone()),
+                };
+                let model_settings = (updated.model != previous.model
+                    || updated.model_reasoning_effort != previous.model_reasoning_effort
+                    || updated.model_reasoning_summary != previous.model_reasoning_summary
+                    || updated.model_verbosity != previous.model_verbosity)
+                    .then(|| ModelSettingsChangedEvent {
+                        model: updated.model.clone(),
+                        effort: updated.model_reasoning_effort,
+                        summary: updated.model_reasoning_summary,
+                        verbosity: updated.model_verbosity,
+                    });
                 state.session_configuration = updated;
-                Ok(())
+                Ok((applied, model_settings))
             }
             Err(err) => {
                 warn!("rejected session settings update: {err}");
@@ -888,14 +1240,15 @@ impl Session {
         sub_id: String,
         updates: SessionSettingsUpdate,
     ) -> ConstraintResult<Arc<TurnContext>> {
-        let (session_configuration, sandbox_policy_changed) = {
+        let (session_configuration, sandbox_state_changed) = {
             let mut state = self.state.lock().await;
             match state.session_configuration.clone().apply(&updates) {
                 Ok(next) => {
-                    let sandbox_policy_changed =
-                        state.session_configuration.sandbox_policy != next.sandbox_policy;
+                    let sandbox_state_changed = state.session_configuration.sandbox_policy
+                        != next.sandbox_policy
+                        || state.session_configuration.cwd != next.cwd;
                     state.session_configuration = next.clone();
-                    (next, sandbox_policy_changed)
+                    (next, sandbox_state_changed)
                 }
                 Err(err) => {
                     drop(state);
@@ -917,7 +1270,7 @@ impl Session {
                 sub_id,
                 session_configuration,
                 updates.final_output_json_schema,
-                sandbox_policy_changed,
+                sandbox_state_changed,
             )
             .await)
     }
@@ -927,11 +1280,11 @@ impl Session {
         sub_id: String,
         session_configuration: SessionConfiguration,
         final_output_json_schema: Option<Option<Value>>,
-        sandbox_policy_changed: bool,
+        sandbox_state_changed: bool,
     ) -> Arc<TurnContext> {
         let per_turn_config = Self::build_per_turn_config(&session_configuration);
 
-        if sandbox_policy_changed {
+        if sandbox_state_changed {
             let sandbox_state = SandboxState {
                 sandbox_policy: per_turn_config.sandbox_policy.get().clone(),
                 codex_linux_sandbox_exe: per_turn_config.codex_linux_sandbox_exe.clone(),
@@ -954,6 +1307,10 @@ impl Session {
             .models_manager
             .construct_model_info(session_configuration.model.as_str(), &per_turn_config)
             .await;
+        let devcontainer = match &per_turn_config.exec_backend {
+            ExecBackendConfig::Devcontainer(backend) => Some(backend.clone()),
+            _ => None,
+        };
         let mut turn_context: TurnContext = Self::make_turn_context(
             Some(Arc::clone(&self.services.auth_manager)),
             &self.services.otel_manager,
@@ -967,9 +1324,101 @@ impl Session {
         if let Some(final_schema) = final_output_json_schema {
             turn_context.final_output_json_schema = final_schema;
         }
+        if let Some(backend) = devcontainer {
+            turn_context.remote_exec = self.devcontainer_exec(&turn_context, &backend).await;
+        }
+        if self.enabled(Feature::AdaptiveTruncation) {
+            self.adapt_truncation(&mut turn_context).await;
+        }
         Arc::new(turn_context)
     }
 
+    /// Scales the turn's limit on each tool output to the room left in the
+    /// context window: generous while most of it is free, tight and with
+    /// summarized command output as it fills up.
+    async fn adapt_truncation(&self, turn_context: &mut TurnContext) {
+        let Some(context_window) = turn_context.client.get_model_context_window() else {
+            return;
+        };
+        let remaining_tokens = context_window
+            .saturating_sub(self.get_total_token_usage().await)
+            .max(0);
+        let default_policy = turn_context.truncation_policy;
+        let (policy, summarize) = adaptive_policy(
+            default_policy,
+            usize::try_from(remaining_tokens).unwrap_or(0),
+        );
+        turn_context.truncation_policy = policy;
+        turn_context.output_budget = Some(OutputBudgetEvent {
+            remaining_tokens,
+            default_policy: default_policy.into(),
+            policy: policy.into(),
+            summarize,
+        });
+    }
+
+    /// How commands run in the dev container for the turn's working
+    /// directory. The first turn that needs it starts bringing it up in the
+    /// background; until it is up, commands run locally. A container that
+    /// cannot be started is reported once; commands then keep running locally.
+    async fn devcontainer_exec(
+        &self,
+        turn_context: &TurnContext,
+        backend: &DevcontainerExecBackend,
+    ) -> Option<RemoteExec> {
+        let failed = |err: &str| {
+            EventMsg::Warning(WarningEvent {
+                message: format!(
+                    "Could not start the dev container, so commands run on this machine: {err}"
+                ),
+            })
+        };
+        let (remote, event) = {
+            let mut devcontainers = self.services.devcontainers.lock().await;
+            let (state, event) = match devcontainers.remove(&turn_context.cwd) {
+                Some(DevcontainerState::Starting(up)) if up.is_finished() => {
+                    match up.await.unwrap_or_else(|err| Err(err.to_string())) {
+                        Ok(remote) => {
+                            let message = format!(
+                                "The dev container {} is up; commands now run in it",
+                                remote.host()
+                            );
+                            (
+                                DevcontainerState::Ready(remote),
+                                Some(EventMsg::BackgroundEvent(BackgroundEventEvent { message })),
+                            )
+                        }
+                        Err(err) => (DevcontainerState::Failed, Some(failed(&err))),
+                    }
+                }
+                Some(state) => (state, None),
+                None => match Devcontainer::locate(backend, &turn_context.cwd) {
+                    Ok(devcontainer) => {
+                        let message = format!(
+                            "Starting the dev container from {}; commands run on this machine until it is up",
+                            devcontainer.config().display()
+                        );
+                        (
+                            devcontainer.start(backend.clone()),
+                            Some(EventMsg::BackgroundEvent(BackgroundEventEvent { message })),
+                        )
+                    }
+                    Err(err) => (DevcontainerState::Failed, Some(failed(&err))),
+                },
+            };
+            let remote = match &state {
+                DevcontainerState::Ready(remote) => Some(remote.clone()),
+                DevcontainerState::Starting(_) | DevcontainerState::Failed => None,
+            };
+            devcontainers.insert(turn_context.cwd.clone(), state);
+            (remote, event)
+        };
+        if let Some(event) = event {
+            self.send_event(turn_context, event).await;
+        }
+        remote
+    }
+
     pub(crate) async fn new_default_turn(&self) -> Arc<TurnContext> {
         self.new_default_turn_with_sub_id(self.next_internal_sub_id())
             .await
@@ -1004,6 +1453,114 @@ impl Session {
         )))
     }
 
+    /// Re-reads the project docs if the watcher saw them change and, when the
+    /// assembled instructions differ, records an update notice for the model
+    /// and uses the new instructions for later turns.
+    async fn refresh_project_docs(&self, turn_context: &TurnContext) {
+        if !self.services.project_doc_watcher.take_changed() {
+            return;
+        }
+        let (config, previous) = {
+            let state = self.state.lock().await;
+            (
+                Arc::clone(&state.session_configuration.original_config_do_not_use),
+                state.session_configuration.user_instructions.clone(),
+            )
+        };
+        let skills = self.services.skills_manager.skills_for_config(&config);
+        let user_instructions = get_user_instructions(&config, Some(&skills.skills)).await;
+        if user_instructions == previous {
+            return;
+        }
+        {
+            let mut state = self.state.lock().await;
+            state.session_configuration.user_instructions = user_instructions.clone();
+        }
+
+        let notice: ResponseItem = UserInstructions {
+            directory: config.cwd.to_string_lossy().into_owned(),
+            text: project_docs_update_notice(user_instructions.as_deref()),
+        }
+        .into();
+        self.record_conversation_items(turn_context, std::slice::from_ref(&notice))
+            .await;
+
+        let paths = match discover_project_doc_paths(&config) {
+            Ok(paths) => paths,
+            Err(err) => {
+                warn!("failed to list project docs: {err}");
+                Vec::new()
+            }
+        };
+        self.report_instruction_sources(turn_context, &paths).await;
+        self.send_event(
+            turn_context,
+            EventMsg::ProjectDocsUpdated(ProjectDocsUpdatedEvent { paths }),
+        )
+        .await;
+    }
+
+    /// Tells the model which of the files it has seen were changed outside
+    /// the session since the last turn (`watch_read_files` feature).
+    async fn notify_changed_files(&self, turn_context: &TurnContext) {
+        let Some(changes) = self.services.file_watch.take_changes() else {
+            return;
+        };
+        self.report_changed_files(turn_context, changes).await;
+    }
+
+    /// Probes the environment the session's commands run in and gives the
+    /// model a summary of it (`environment_fingerprint` feature). Only done
+    /// in trusted projects, since the probes read project files, and not
+    /// while a dev container is still starting, since the probes would
+    /// describe this machine instead.
+    async fn record_environment_fingerprint(&self, turn_context: &TurnContext) {
+        if !self.enabled(Feature::EnvironmentFingerprint) {
+            return;
+        }
+        let config = {
+            let state = self.state.lock().await;
+            Arc::clone(&state.session_configuration.original_config_do_not_use)
+        };
+        let container_pending = matches!(config.exec_backend, ExecBackendConfig::Devcontainer(_))
+            && turn_context.remote_exec.is_none();
+        if !config.active_project.is_trusted() || container_pending {
+            return;
+        }
+        let fingerprint = environment_fingerprint::capture(turn_context).await;
+        let notice = ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: fingerprint_notice(&fingerprint),
+            }],
+        };
+        self.record_conversation_items(turn_context, std::slice::from_ref(&notice))
+            .await;
+        self.send_event(turn_context, EventMsg::EnvironmentFingerprint(fingerprint))
+            .await;
+    }
+
+    /// Records a notice listing `changes`, files changed outside the
+    /// session, for the model and emits it as an event.
+    pub(crate) async fn report_changed_files(
+        &self,
+        turn_context: &TurnContext,
+        changes: WatchedFilesChangedEvent,
+    ) {
+        let notice = ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: changed_files_notice(&changes, &turn_context.cwd),
+            }],
+        };
+        self.record_conversation_items(turn_context, std::slice::from_ref(&notice))
+            .await;
+        self.send_event(turn_context, EventMsg::WatchedFilesChanged(changes))
+            .await;
+    }
+
     /// Persist the event to rollout and send it to clients.
     pub(crate) async fn send_event(&self, turn_context: &TurnContext, msg: EventMsg) {
         let legacy_source = msg.clone();
@@ -1023,34 +1580,83 @@ impl Session {
         }
     }
 
-    pub(crate) async fn send_event_raw(&self, event: Event) {
-        // Record the last known agent status.
-        if let Some(status) = agent_status_from_event(&event.msg) {
+    /// Record the last known agent status and turn timing.
+    async fn record_status(&self, msg: &EventMsg) {
+        if let Some(status) = agent_status_from_event(msg) {
             let mut guard = self.agent_status.write().await;
             *guard = status;
         }
+        if let EventMsg::TurnComplete(TurnCompleteEvent {
+            timing: Some(timing),
+            ..
+        }) = msg
+        {
+            *self.last_turn_timing.write().await = Some(*timing);
+        }
+    }
+
+    pub(crate) async fn send_event_raw(&self, event: Event) {
+        self.record_status(&event.msg).await;
         // Persist the event into rollout (recorder filters as needed)
         let rollout_items = vec![RolloutItem::EventMsg(event.msg.clone())];
         self.persist_rollout_items(&rollout_items).await;
+        let Some(event) = self.captured_event(event) else {
+            return;
+        };
+        if let Some(veto) = self.services.hooks.dispatch(&event).await
+            && self.deny_vetoed_approval(&event, &veto).await
+        {
+            return;
+        }
         if let Err(e) = self.tx_event.send(event).await {
             error!("failed to send tool call event: {e}");
         }
     }
 
+    /// What clients and hooks may see of `event` under `[reasoning].capture`.
+    fn captured_event(&self, event: Event) -> Option<Event> {
+        let Event { id, msg } = event;
+        retain_event(msg, self.services.reasoning.capture).map(|msg| Event { id, msg })
+    }
+
+    /// Denies an approval request that a blocking hook vetoed, instead of
+    /// asking the user. Returns whether `event` was such a request.
+    async fn deny_vetoed_approval(&self, event: &Event, veto: &HookVeto) -> bool {
+        if !matches!(
+            event.msg,
+            EventMsg::ExecApprovalRequest(_) | EventMsg::ApplyPatchApprovalRequest(_)
+        ) {
+            return false;
+        }
+        self.notify_approval(&event.id, ReviewDecision::Denied)
+            .await;
+        let warning = Event {
+            id: event.id.clone(),
+            msg: EventMsg::Warning(WarningEvent {
+                message: format!(
+                    "Hook `{}` denied the approval request: {}",
+                    veto.hook, veto.reason
+                ),
+            }),
+        };
+        Box::pin(self.send_event_raw(warning)).await;
+        true
+    }
+
     /// Persist the event to the rollout file, flush it, and only then deliver it to clients.
     ///
     /// Most events can be delivered immediately after queueing the rollout write, but some
     /// clients (e.g. app-server thread/rollback) re-read the rollout file synchronously on
     /// receipt of the event and depend on the marker already being visible on disk.
     pub(crate) async fn send_event_raw_flushed(&self, event: Event) {
-        // Record the last known agent status.
-        if let Some(status) = agent_status_from_event(&event.msg) {
-            let mut guard = self.agent_status.write().await;
-            *guard = status;
-        }
+        self.record_status(&event.msg).await;
         self.persist_rollout_items(&[RolloutItem::EventMsg(event.msg.clone())])
             .await;
         self.flush_rollout().await;
+        let Some(event) = self.captured_event(event) else {
+            return;
+        };
+        self.services.hooks.dispatch(&event).await;
         if let Err(e) = self.tx_event.send(event).await {
             error!("failed to send tool call event: {e}");
         }
@@ -1147,6 +1753,7 @@ impl Session {
         }
 
         let parsed_cmd = parse_command(&command);
+        let operations = classify_command(&command);
         let event = EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
             call_id,
             turn_id: turn_context.sub_id.clone(),
@@ -1155,9 +1762,10 @@ impl Session {
             reason,
             proposed_execpolicy_amendment,
             parsed_cmd,
+            operations,
         });
         self.send_event(turn_context, event).await;
-        rx_approve.await.unwrap_or_default()
+        await_approval(self, turn_context, rx_approve).await
     }
 
     pub async fn request_patch_approval(
@@ -1239,9 +1847,155 @@ impl Session {
         turn_context: &TurnContext,
         items: &[ResponseItem],
     ) {
-        self.record_into_history(items, turn_context).await;
-        self.persist_rollout_response_items(items).await;
-        self.send_raw_response_items(turn_context, items).await;
+        let items = self.attribute_context_sources(turn_context, items).await;
+        let items = self.redact_tool_outputs(turn_context, items).await;
+        let items = self.screen_tool_outputs(turn_context, items).await;
+        self.record_into_history(&items, turn_context).await;
+        self.persist_rollout_response_items(&items).await;
+        self.send_raw_response_items(turn_context, &items).await;
+    }
+
+    /// Attributes tool outputs and web searches to the files, pages and MCP
+    /// servers they were read from, emitting `ContextSources` for each, and
+    /// withholds outputs read from a source matching `[redaction] sources`.
+    async fn attribute_context_sources<'a>(
+        &self,
+        turn_context: &TurnContext,
+        items: &'a [ResponseItem],
+    ) -> Cow<'a, [ResponseItem]> {
+        let mut withheld = Vec::new();
+        for (index, item) in items.iter().enumerate() {
+            let (call_id, sources) = match item {
+                ResponseItem::FunctionCallOutput { call_id, .. }
+                | ResponseItem::CustomToolCallOutput { call_id, .. } => {
+                    let call = match provenance::find_call(items, call_id) {
+                        Some(call) => Some(call.clone()),
+                        None => self.state.lock().await.find_call(call_id),
+                    };
+                    let Some(call) = call else {
+                        continue;
+                    };
+                    let mcp_tool = match &call {
+                        ResponseItem::FunctionCall { name, .. } => {
+                            self.parse_mcp_tool_name(name).await
+                        }
+                        _ => None,
+                    };
+                    let sources = provenance::sources_for_call(&call, &turn_context.cwd, mcp_tool);
+                    (call_id.clone(), sources)
+                }
+                ResponseItem::WebSearchCall { id: Some(id), .. } => (
+                    id.clone(),
+                    provenance::sources_for_call(item, &turn_context.cwd, None),
+                ),
+                _ => continue,
+            };
+            if sources.is_empty() {
+                continue;
+            }
+            if let Some(withholding) = self.services.redactor.withhold_tool_output(item, &sources) {
+                withheld.push((index, withholding));
+            }
+            self.send_event(
+                turn_context,
+                EventMsg::ContextSources(ContextSourcesEvent {
+                    call_id: Some(call_id),
+                    sources,
+                }),
+            )
+            .await;
+        }
+        if withheld.is_empty() {
+            return Cow::Borrowed(items);
+        }
+        let mut items = items.to_vec();
+        for (index, (replacement, event)) in withheld {
+            items[index] = replacement;
+            self.send_event(turn_context, EventMsg::RedactionApplied(event))
+                .await;
+        }
+        Cow::Owned(items)
+    }
+
+    /// Reports the instruction files the model was given as
+    /// `ContextSources`.
+    async fn report_instruction_sources(&self, turn_context: &TurnContext, paths: &[PathBuf]) {
+        if paths.is_empty() {
+            return;
+        }
+        let sources = paths
+            .iter()
+            .map(|path| ContentSource::Instructions { path: path.clone() })
+            .collect();
+        self.send_event(
+            turn_context,
+            EventMsg::ContextSources(ContextSourcesEvent {
+                call_id: None,
+                sources,
+            }),
+        )
+        .await;
+    }
+
+    /// Replaces secrets in tool outputs before they reach the history and the
+    /// rollout, first with the built-in rules and then with the redaction
+    /// hooks, which are awaited. Emits `RedactionApplied` for every output
+    /// that changed, once the outputs are final.
+    async fn redact_tool_outputs<'a>(
+        &self,
+        turn_context: &TurnContext,
+        mut items: Cow<'a, [ResponseItem]>,
+    ) -> Cow<'a, [ResponseItem]> {
+        let mut events = Vec::new();
+        for index in 0..items.len() {
+            if let Some((redacted, event)) =
+                self.services.redactor.redact_tool_output(&items[index])
+            {
+                items.to_mut()[index] = redacted;
+                events.push(event);
+            }
+            if let Some((redacted, event)) =
+                redaction::redact_tool_output_with_hooks(&items[index], &self.services.hooks).await
+            {
+                items.to_mut()[index] = redacted;
+                events.push(event);
+            }
+        }
+        for event in events {
+            self.send_event(turn_context, EventMsg::RedactionApplied(event))
+                .await;
+        }
+        items
+    }
+
+    /// Flags or strips suspected prompt injection in tool outputs, emitting
+    /// `InjectionSuspected` for every output that matched.
+    async fn screen_tool_outputs<'a>(
+        &self,
+        turn_context: &TurnContext,
+        items: Cow<'a, [ResponseItem]>,
+    ) -> Cow<'a, [ResponseItem]> {
+        let mut screenings = Vec::new();
+        for (index, item) in items.iter().enumerate() {
+            if let Some(screening) = self
+                .services
+                .injection_detector
+                .screen_tool_output(item)
+                .await
+            {
+                screenings.push((index, screening));
+            }
+        }
+        if screenings.is_empty() {
+            return items;
+        }
+        let mut items = items.into_owned();
+        for (index, (screened, event)) in screenings {
+            items[index] = screened;
+            self.send_event(turn_context, EventMsg::InjectionSuspected(event))
+                .await;
+        }
+        Cow::Owned(items)
     }
 
     fn reconstruct_history_from_rollout(
@@ -1359,12 +2113,22 @@ impl Session {
     }
 
     pub(crate) async fn persist_rollout_items(&self, items: &[RolloutItem]) {
+        let items: Cow<'_, [RolloutItem]> = match self.services.reasoning.persist {
+            ReasoningRetention::Keep => Cow::Borrowed(items),
+            persist => Cow::Owned(
+                items
+                    .iter()
+                    .cloned()
+                    .filter_map(|item| retain_rollout_item(item, persist))
+                    .collect(),
+            ),
+        };
         let recorder = {
             let guard = self.services.rollout.lock().await;
             guard.clone()
         };
         if let Some(rec) = recorder
-            && let Err(e) = rec.record_items(items).await
+            && let Err(e) = rec.record_items(&items).await
         {
             error!("failed to record rollout items: {e:#}");
         }
@@ -1470,6 +2234,67 @@ impl Session {
         }
     }
 
+    async fn pull_request_config(&self) -> PullRequestConfig {
+        self.state
+            .lock()
+            .await
+            .session_configuration
+            .original_config_do_not_use
+            .pull_request
+            .clone()
+    }
+
+    /// The files left uncommitted before a task runs, which the
+    /// `[pull_request]` workflow does not commit unless the task changes them.
+    pub(crate) async fn pull_request_baseline(
+        &self,
+        turn_context: &TurnContext,
+    ) -> Option<pull_request::UncommittedFiles> {
+        if !self.pull_request_config().await.enabled {
+            return None;
+        }
+        pull_request::UncommittedFiles::read(&turn_context.cwd).await
+    }
+
+    /// Commits the files the task changed since `baseline` and opens a draft
+    /// pull request, in the background so that the task can end first.
+    pub(crate) async fn maybe_publish_pull_request(
+        self: &Arc<Self>,
+        turn_context: Arc<TurnContext>,
+        last_agent_message: Option<String>,
+        baseline: pull_request::UncommittedFiles,
+    ) {
+        let config = self.pull_request_config().await;
+        if !config.enabled {
+            return;
+        }
+        let prompts = collect_user_messages(self.clone_history().await.raw_items());
+        let draft = pull_request::draft_pull_request(
+            &prompts,
+            last_agent_message.as_deref(),
+            &config,
+            self.conversation_id,
+        );
+        let sess = Arc::clone(self);
+        tokio::spawn(async move {
+            // One publish at a time, so that a later task's commit follows
+            // this one on the branch.
+            let _publishing = sess.services.publishing.lock().await;
+            match pull_request::publish(&turn_context.cwd, &config, draft, &baseline).await {
+                Ok(Some(event)) => {
+                    sess.send_event(&turn_context, EventMsg::PullRequestPublished(event))
+                        .await;
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    let message = format!("Could not open a pull request for this task: {err}");
+                    sess.send_event(&turn_context, EventMsg::Warning(WarningEvent { message }))
+                        .await;
+                }
+            }
+        });
+    }
+
     pub(crate) async fn notify_background_event(
         &self,
         turn_context: &TurnContext,
@@ -1488,8 +2313,11 @@ impl Session {
         codex_error: CodexErr,
     ) {
         let additional_details = codex_error.to_string();
-        let codex_error_info = CodexErrorInfo::ResponseStreamDisconnected {
-            http_status_code: codex_error.http_status_code_value(),
+        let codex_error_info = match &codex_error {
+            CodexErr::MalformedStream(_) => CodexErrorInfo::ResponseStreamMalformed,
+            _ => CodexErrorInfo::ResponseStreamDisconnected {
+                http_status_code: codex_error.http_status_code_value(),
+            },
         };
         let event = EventMsg::StreamError(StreamErrorEvent {
             message: message.into(),
@@ -1608,6 +2436,19 @@ impl Session {
             .await
     }
 
+    pub async fn get_prompt(
+        &self,
+        server: &str,
+        params: GetPromptRequestParams,
+    ) -> anyhow::Result<GetPromptResult> {
+        self.services
+            .mcp_connection_manager
+            .read()
+            .await
+            .get_prompt(server, params)
+            .await
+    }
+
     pub async fn call_tool(
         &self,
         server: &str,
@@ -1669,6 +2510,9 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::Interrupt => {
                 handlers::interrupt(&sess).await;
             }
+            Op::Stop => {
+                handlers::stop(&sess).await;
+            }
             Op::OverrideTurnContext {
                 cwd,
                 approval_policy,
@@ -1676,6 +2520,7 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
                 model,
                 effort,
                 summary,
+                verbosity,
             } => {
                 handlers::override_turn_context(
                     &sess,
@@ -1687,6 +2532,7 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
                         model,
                         reasoning_effort: effort,
                         reasoning_summary: summary,
+                        verbosity,
                         ..Default::default()
                     },
                 )
@@ -1702,6 +2548,9 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::PatchApproval { id, decision } => {
                 handlers::patch_approval(&sess, id, decision).await;
             }
+            Op::AllowCommandPrefix { prefix } => {
+                handlers::allow_command_prefix(&sess, prefix).await;
+            }
             Op::AddToHistory { text } => {
                 handlers::add_to_history(&sess, &config, text).await;
             }
@@ -1713,7 +2562,19 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
                 handlers::list_mcp_tools(&sess, &config, sub.id.clone()).await;
             }
             Op::ListCustomPrompts => {
-                handlers::list_custom_prompts(&sess, sub.id.clone()).await;
+                handlers::list_custom_prompts(&sess, &config, sub.id.clone()).await;
+            }
+            Op::ListHooks => {
+                handlers::list_hooks(&sess, sub.id.clone()).await;
+            }
+            Op::SetHookEnabled { name, enabled } => {
+                handlers::set_hook_enabled(&sess, sub.id.clone(), name, enabled).await;
+            }
+            Op::RunUserCommand { name, arguments } => {
+                handlers::run_user_command(&sess, sub.id.clone(), name, arguments).await;
+            }
+            Op::SetVariables { variables } => {
+                handlers::set_variables(&sess, sub.id.clone(), variables).await;
             }
             Op::ListSkills { cwds, force_reload } => {
                 handlers::list_skills(&sess, sub.id.clone(), cwds, force_reload).await;
@@ -1775,10 +2636,12 @@ mod handlers {
     use crate::tasks::UserShellCommandTask;
     use codex_protocol::custom_prompts::CustomPrompt;
     use codex_protocol::protocol::CodexErrorInfo;
+    use codex_protocol::protocol::ConfigOverrideAppliedEvent;
     use codex_protocol::protocol::ErrorEvent;
     use codex_protocol::protocol::Event;
     use codex_protocol::protocol::EventMsg;
     use codex_protocol::protocol::ListCustomPromptsResponseEvent;
+    use codex_protocol::protocol::ListHooksResponseEvent;
     use codex_protocol::protocol::ListSkillsResponseEvent;
     use codex_protocol::protocol::Op;
     use codex_protocol::protocol::ReviewDecision;
@@ -1786,6 +2649,8 @@ mod handlers {
     use codex_protocol::protocol::SkillsListEntry;
     use codex_protocol::protocol::ThreadRolledBackEvent;
     use codex_protocol::protocol::TurnAbortReason;
+    use codex_protocol::protocol::UserCommandEvent;
+    use codex_protocol::protocol::VariablesUpdatedEvent;
     use codex_protocol::protocol::WarningEvent;
 
     use crate::context_manager::is_user_turn_boundary;
@@ -1793,6 +2658,7 @@ mod handlers {
     use codex_rmcp_client::ElicitationAction;
     use codex_rmcp_client::ElicitationResponse;
     use mcp_types::RequestId;
+    use std::collections::BTreeMap;
     use std::path::PathBuf;
     use std::sync::Arc;
     use tracing::info;
@@ -1802,20 +2668,43 @@ mod handlers {
         sess.interrupt_task().await;
     }
 
+    pub async fn stop(sess: &Arc<Session>) {
+        info!("stop received: abort current task, keeping its partial output");
+        sess.abort_all_tasks(TurnAbortReason::Stopped).await;
+    }
+
     pub async fn override_turn_context(
         sess: &Session,
         sub_id: String,
         updates: SessionSettingsUpdate,
     ) {
-        if let Err(err) = sess.update_settings(updates).await {
-            sess.send_event_raw(Event {
-                id: sub_id,
-                msg: EventMsg::Error(ErrorEvent {
-                    message: err.to_string(),
-                    codex_error_info: Some(CodexErrorInfo::BadRequest),
-                }),
-            })
-            .await;
+        match sess.update_settings(updates).await {
+            Ok((applied, model_settings)) => {
+                if applied != ConfigOverrideAppliedEvent::default() {
+                    sess.send_event_raw(Event {
+                        id: sub_id.clone(),
+                        msg: EventMsg::ConfigOverrideApplied(applied),
+                    })
+                    .await;
+                }
+                if let Some(model_settings) = model_settings {
+                    sess.send_event_raw(Event {
+                        id: sub_id,
+                        msg: EventMsg::ModelSettingsChanged(model_settings),
+                    })
+                    .await;
+                }
+            }
+            Err(err) => {
+                sess.send_event_raw(Event {
+                    id: sub_id,
+                    msg: EventMsg::Error(ErrorEvent {
+                        message: err.to_string(),
+                        codex_error_info: Some(CodexErrorInfo::BadRequest),
+                    }),
+                })
+                .await;
+            }
         }
     }
 
@@ -1860,6 +2749,18 @@ mod handlers {
             _ => unreachable!(),
         };
 
+        if sess.services.watchdogs.session_expired() {
+            sess.send_event_raw(Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: "The session ran past its time limit ([watchdogs.session]); start a new session to continue.".to_string(),
+                    codex_error_info: None,
+                }),
+            })
+            .await;
+            return;
+        }
+
         let Ok(current_context) = sess.new_turn_with_sub_id(sub_id, updates).await else {
             // new_turn_with_sub_id already emits the error event.
             return;
@@ -1871,6 +2772,8 @@ mod handlers {
 
         // Attempt to inject input into current task
         if let Err(items) = sess.inject_input(items).await {
+            sess.refresh_project_docs(&current_context).await;
+            sess.notify_changed_files(&current_context).await;
             if let Some(env_item) =
                 sess.build_environment_update_item(previous_context.as_ref(), &current_context)
             {
@@ -2027,23 +2930,90 @@ mod handlers {
         sess.send_event_raw(event).await;
     }
 
-    pub async fn list_custom_prompts(sess: &Session, sub_id: String) {
+    pub async fn list_custom_prompts(sess: &Session, config: &Arc<Config>, sub_id: String) {
         let custom_prompts: Vec<CustomPrompt> =
             if let Some(dir) = crate::custom_prompts::default_prompts_dir() {
                 crate::custom_prompts::discover_prompts_in(&dir).await
             } else {
                 Vec::new()
             };
+        let custom_commands = crate::custom_prompts::discover_user_commands(
+            &config.codex_home.join("commands"),
+            &config.user_commands,
+            &config.codex_home.join(crate::config::CONFIG_TOML_FILE),
+        )
+        .await;
 
         let event = Event {
             id: sub_id,
             msg: EventMsg::ListCustomPromptsResponse(ListCustomPromptsResponseEvent {
                 custom_prompts,
+                custom_commands,
+            }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
+    pub async fn list_hooks(sess: &Session, sub_id: String) {
+        let hooks = &sess.services.hooks;
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::ListHooksResponse(ListHooksResponseEvent {
+                hooks: hooks.hooks(),
+                recent_firings: hooks.recent_firings(),
             }),
         };
         sess.send_event_raw(event).await;
     }
 
+    /// Emits the `user_command` event for an invocation of a user-defined
+    /// slash command; the hooks subscribed to it run like for any event.
+    pub async fn run_user_command(sess: &Session, sub_id: String, name: String, arguments: String) {
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::UserCommand(UserCommandEvent { name, arguments }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
+    pub async fn allow_command_prefix(sess: &Session, prefix: Vec<String>) {
+        sess.services
+            .tool_approvals
+            .lock()
+            .await
+            .allow_command_prefix(prefix);
+    }
+
+    pub async fn set_hook_enabled(sess: &Session, sub_id: String, name: String, enabled: bool) {
+        if let Err(err) = sess.services.hooks.set_enabled(&name, enabled) {
+            let event = Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: err.to_string(),
+                    codex_error_info: Some(CodexErrorInfo::Other),
+                }),
+            };
+            sess.send_event_raw(event).await;
+            return;
+        }
+        list_hooks(sess, sub_id).await;
+    }
+
+    pub async fn set_variables(
+        sess: &Session,
+        sub_id: String,
+        changes: BTreeMap<String, Option<String>>,
+    ) {
+        let msg = match sess.services.variables.update(changes) {
+            Ok(variables) => EventMsg::VariablesUpdated(VariablesUpdatedEvent { variables }),
+            Err(message) => EventMsg::Error(ErrorEvent {
+                message,
+                codex_error_info: Some(CodexErrorInfo::Other),
+            }),
+        };
+        sess.send_event_raw(Event { id: sub_id, msg }).await;
+    }
+
     pub async fn list_skills(
         sess: &Session,
         sub_id: String,
@@ -2240,7 +3210,8 @@ async fn spawn_review_thread(
     let tools_config = ToolsConfig::new(&ToolsConfigParams {
         model_info: &review_model_info,
         features: &review_features,
-    });
+    })
+    .with_banned_tools(config.banned_tools.clone());
 
     let base_instructions = REVIEW_PROMPT.to_string();
     let review_prompt = resolved.prompt.clone();
@@ -2282,13 +3253,22 @@ async fn spawn_review_thread(
         base_instructions: Some(base_instructions.clone()),
         compact_prompt: parent_turn_context.compact_prompt.clone(),
         approval_policy: parent_turn_context.approval_policy,
-        sandbox_policy: parent_turn_context.sandbox_policy.clone(),
+        // Reviews only read the tree, whatever the session allows.
+        sandbox_policy: SandboxPolicy::new_read_only_policy(),
         shell_environment_policy: parent_turn_context.shell_environment_policy.clone(),
         cwd: parent_turn_context.cwd.clone(),
         final_output_json_schema: None,
         codex_linux_sandbox_exe: parent_turn_context.codex_linux_sandbox_exe.clone(),
+        remote_exec: parent_turn_context.remote_exec.clone(),
         tool_call_gate: Arc::new(ReadinessFlag::new()),
         truncation_policy: model_info.truncation_policy.into(),
+        output_budget: None,
+        timing: Arc::new(TurnTimer::new()),
+        in_flight_operations: Arc::default(),
+        unified_diff: Default::default(),
+        verification: Default::default(),
+        context_dropped: Default::default(),
+        partial_message: Default::default(),
     };
 
     // Seed the child task with the review prompt as the initial user message.
@@ -2360,10 +3340,32 @@ pub(crate) async fn run_turn(
     if total_usage_tokens >= auto_compact_limit {
         run_auto_compact(&sess, &turn_context).await;
     }
+    sess.services.tool_failure_breaker.reset();
+    // An approval that timed out at the end of an earlier turn must not stop
+    // this one.
+    sess.services.watchdogs.take_approval_expired();
     let event = EventMsg::TurnStarted(TurnStartedEvent {
         model_context_window: turn_context.client.get_model_context_window(),
     });
     sess.send_event(&turn_context, event).await;
+    if let Some(budget) = turn_context.output_budget.clone() {
+        sess.send_event(&turn_context, EventMsg::OutputBudget(budget))
+            .await;
+    }
+
+    let McpPromptExpansion {
+        input,
+        warnings: prompt_warnings,
+    } = expand_mcp_prompts(input, &sess).await;
+
+    for message in prompt_warnings {
+        sess.send_event(&turn_context, EventMsg::Warning(WarningEvent { message }))
+            .await;
+    }
+
+    if input.is_empty() {
+        return None;
+    }
 
     let skills_outcome = Some(
         sess.services
@@ -2382,6 +3384,16 @@ pub(crate) async fn run_turn(
             .await;
     }
 
+    let McpResourceInjections {
+        items: resource_items,
+        warnings: resource_warnings,
+    } = build_mcp_resource_injections(&input, &sess).await;
+
+    for message in resource_warnings {
+        sess.send_event(&turn_context, EventMsg::Warning(WarningEvent { message }))
+            .await;
+    }
+
     let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input);
     let response_item: ResponseItem = initial_input_for_turn.clone().into();
     sess.record_response_item_and_emit_turn_item(turn_context.as_ref(), response_item)
@@ -2392,12 +3404,20 @@ pub(crate) async fn run_turn(
             .await;
     }
 
+    if !resource_items.is_empty() {
+        sess.record_conversation_items(&turn_context, &resource_items)
+            .await;
+    }
+
     sess.maybe_start_ghost_snapshot(Arc::clone(&turn_context), cancellation_token.child_token())
         .await;
     let mut last_agent_message: Option<String> = None;
     // Although from the perspective of codex.rs, TurnDiffTracker has the lifecycle of a Task which contains
     // many turns, from the perspective of the user, it is a single turn.
     let turn_diff_tracker = Arc::new(tokio::sync::Mutex::new(TurnDiffTracker::new()));
+    let mut verifier = Verifier::new(&turn_context.client.config().verify);
+    let mut context_budget = turn_context.client.config().context_budget;
+    let mut context_repairs = 0;
 
     loop {
         // Note that pending_input would be something like a message the user
@@ -2411,11 +3431,13 @@ pub(crate) async fn run_turn(
             .collect::<Vec<ResponseItem>>();
 
         // Construct the input that we will send to the model.
-        let turn_input: Vec<ResponseItem> = {
+        let mut turn_input: Vec<ResponseItem> = {
             sess.record_conversation_items(&turn_context, &pending_input)
                 .await;
             sess.clone_history().await.for_prompt()
         };
+        let context_dropped = apply_context_budget(&mut turn_input, &context_budget);
+        turn_context.set_context_dropped(context_dropped);
 
         let turn_input_messages = turn_input
             .iter()
@@ -2439,6 +3461,15 @@ pub(crate) async fn run_turn(
                     needs_follow_up,
                     last_agent_message: turn_last_agent_message,
                 } = turn_output;
+                context_repairs = 0;
+                if sess.services.watchdogs.take_approval_expired() {
+                    break;
+                }
+                if let Some(trip) = sess.services.tool_failure_breaker.take_trip() {
+                    last_agent_message =
+                        Some(record_guardrail_trip(&sess, &turn_context, trip).await);
+                    break;
+                }
                 let total_usage_tokens = sess.get_total_token_usage().await;
                 let token_limit_reached = total_usage_tokens >= auto_compact_limit;
 
@@ -2449,6 +3480,20 @@ pub(crate) async fn run_turn(
                 }
 
                 if !needs_follow_up {
+                    if let Some(verifier) = verifier.as_mut()
+                        && let Some(feedback) = verifier
+                            .verify(
+                                &sess,
+                                &turn_context,
+                                &turn_diff_tracker,
+                                cancellation_token.child_token(),
+                            )
+                            .await
+                    {
+                        sess.record_conversation_items(&turn_context, &[feedback])
+                            .await;
+                        continue;
+                    }
                     last_agent_message = turn_last_agent_message;
                     sess.notifier()
                         .notify(&UserNotification::AgentTurnComplete {
@@ -2466,6 +3511,14 @@ pub(crate) async fn run_turn(
                 // Aborted turn is reported via a different event.
                 break;
             }
+            Err(CodexErr::ContextWindowExceeded) if context_repairs < MAX_CONTEXT_REPAIRS => {
+                context_repairs += 1;
+                let event =
+                    repair_context(&sess, &turn_context, &mut context_budget, context_repairs)
+                        .await;
+                sess.send_event(&turn_context, EventMsg::ContextRepaired(event))
+                    .await;
+            }
             Err(CodexErr::InvalidImageRequest()) => {
                 let mut state = sess.state.lock().await;
                 error_or_panic(
@@ -2483,9 +3536,82 @@ pub(crate) async fn run_turn(
         }
     }
 
+    record_turn_diff(&sess, &turn_context, &turn_diff_tracker).await;
+    if let Some(verification) = verifier.and_then(Verifier::into_status) {
+        turn_context.set_verification(verification);
+    }
     last_agent_message
 }
 
+/// Persists the diff of everything the turn edited to the rollout and keeps
+/// it for the turn's `TurnComplete` event.
+async fn record_turn_diff(
+    sess: &Session,
+    turn_context: &TurnContext,
+    turn_diff_tracker: &SharedTurnDiffTracker,
+) {
+    let unified_diff = match turn_diff_tracker.lock().await.get_unified_diff() {
+        Ok(Some(unified_diff)) => unified_diff,
+        Ok(None) => return,
+        Err(err) => {
+            warn!("failed to compute the turn diff: {err:#}");
+            return;
+        }
+    };
+    sess.persist_rollout_items(&[RolloutItem::TurnDiff(TurnDiffItem {
+        turn_id: turn_context.sub_id.clone(),
+        unified_diff: unified_diff.clone(),
+    })])
+    .await;
+    turn_context.set_unified_diff(unified_diff);
+}
+
+/// Makes room after the model rejected the turn's request as too long. The
+/// first repair halves the tool output and history sent with each request for
+/// the rest of the turn; later ones compact the conversation into a summary.
+async fn repair_context(
+    sess: &Arc<Session>,
+    turn_context: &Arc<TurnContext>,
+    context_budget: &mut ContextBudgetConfig,
+    attempt: u32,
+) -> ContextRepairedEvent {
+    if attempt == 1 {
+        let mut items = sess.clone_history().await.for_prompt();
+        apply_context_budget(&mut items, context_budget);
+        *context_budget = repair_budget(&items, context_budget);
+        let dropped = apply_context_budget(&mut items, context_budget);
+        return ContextRepairedEvent {
+            dropped,
+            compacted: false,
+        };
+    }
+
+    let before = sess.clone_history().await;
+    run_auto_compact(sess, turn_context).await;
+    let after = sess.clone_history().await;
+    // The summary replaces the history, so the budget no longer needs to be
+    // tighter than configured.
+    *context_budget = turn_context.client.config().context_budget;
+    let tokens = before
+        .estimate_token_count(turn_context)
+        .zip(after.estimate_token_count(turn_context))
+        .map_or(0, |(before, after)| before.saturating_sub(after));
+    ContextRepairedEvent {
+        dropped: vec![DroppedContext {
+            source: ContextSource::History,
+            items: u32::try_from(
+                before
+                    .raw_items()
+                    .len()
+                    .saturating_sub(after.raw_items().len()),
+            )
+            .unwrap_or(u32::MAX),
+            tokens: u64::try_from(tokens).unwrap_or(0),
+        }],
+        compacted: true,
+    }
+}
+
 async fn run_auto_compact(sess: &Arc<Session>, turn_context: &Arc<TurnContext>) {
     if should_use_remote_compact_task(sess.as_ref(), &turn_context.client.get_provider()) {
         run_inline_remote_auto_compact_task(Arc::clone(sess), Arc::clone(turn_context)).await;
@@ -2532,13 +3658,25 @@ async fn run_model_turn(
         .get_model_info()
         .supports_parallel_tool_calls;
 
-    let prompt = Prompt {
+    let mut prompt = Prompt {
         input,
         tools: router.specs(),
         parallel_tool_calls: model_supports_parallel,
         base_instructions_override: turn_context.base_instructions.clone(),
         output_schema: turn_context.final_output_json_schema.clone(),
+        max_output_tokens: None,
     };
+    if let Interception::EndTurn { message } = sess
+        .services
+        .interceptors
+        .before_request(&turn_info(&sess, &turn_context), &mut prompt)
+        .await
+    {
+        return Ok(TurnRunResult {
+            needs_follow_up: false,
+            last_agent_message: record_interceptor_message(&sess, &turn_context, message).await,
+        });
+    }
 
     let mut retries = 0;
     loop {
@@ -2665,6 +3803,10 @@ async fn try_run_turn(
     );
 
     sess.persist_rollout_items(&[rollout_item]).await;
+    let request_timer = turn_context.timing.start_model_request();
+    let model_stream = turn_context
+        .in_flight_operations
+        .track(CancelledOperation::ModelStream);
     let mut stream = turn_context
         .client
         .clone()
@@ -2685,6 +3827,7 @@ async fn try_run_turn(
     let mut last_agent_message: Option<String> = None;
     let mut active_item: Option<TurnItem> = None;
     let mut should_emit_turn_diff = false;
+    let mut streamed_reads = StreamedReads::default();
     let receiving_span = trace_span!("receiving_stream");
     let outcome: CodexResult<TurnRunResult> = loop {
         let handle_responses = trace_span!(
@@ -2719,10 +3862,29 @@ async fn try_run_turn(
             .otel_manager
             .record_responses(&handle_responses, &event);
 
+        if is_model_output(&event) {
+            turn_context.timing.record_first_output();
+        }
+
         match event {
             ResponseEvent::Created => {}
             ResponseEvent::OutputItemDone(item) => {
+                if let Interception::EndTurn { message } = sess
+                    .services
+                    .interceptors
+                    .on_response_item(&turn_info(&sess, &turn_context), &item)
+                    .await
+                {
+                    let message = record_interceptor_message(&sess, &turn_context, message).await;
+                    break Ok(TurnRunResult {
+                        needs_follow_up: false,
+                        last_agent_message: message.or(last_agent_message),
+                    });
+                }
                 let previously_active_item = active_item.take();
+                if matches!(item, ResponseItem::Message { .. }) {
+                    turn_context.take_partial_message();
+                }
                 let mut ctx = HandleOutputCtx {
                     sess: sess.clone(),
                     turn_context: turn_context.clone(),
@@ -2734,7 +3896,7 @@ async fn try_run_turn(
                     .instrument(handle_responses)
                     .await?;
                 if let Some(tool_future) = output_result.tool_future {
-                    in_flight.push_back(tool_future);
+                    in_flight.push_back(turn_context.timing.time_tool(tool_future));
                 }
                 if let Some(agent_message) = output_result.last_agent_message {
                     last_agent_message = Some(agent_message);
@@ -2742,7 +3904,13 @@ async fn try_run_turn(
                 needs_follow_up |= output_result.needs_follow_up;
             }
             ResponseEvent::OutputItemAdded(item) => {
+                if sess.services.prefetch.enabled() {
+                    streamed_reads.on_item_added(&item);
+                }
                 if let Some(turn_item) = handle_non_tool_response_item(&item).await {
+                    if matches!(turn_item, TurnItem::AgentMessage(_)) {
+                        turn_context.start_partial_message(turn_item.id());
+                    }
                     let tracked_item = turn_item.clone();
                     sess.emit_turn_item_started(&turn_context, &turn_item).await;
 
@@ -2758,7 +3926,11 @@ async fn try_run_turn(
                 // Update internal state with latest models etag
                 sess.services
                     .models_manager
-                    .refresh_if_new_etag(etag, sess.features.enabled(Feature::RemoteModels))
+                    .refresh_if_new_etag(
+                        etag,
+                        sess.features.enabled(Feature::RemoteModels),
+                        &turn_context.client.config().proxy,
+                    )
                     .await;
             }
             ResponseEvent::Completed {
@@ -2774,10 +3946,16 @@ async fn try_run_turn(
                     last_agent_message,
                 });
             }
+            ResponseEvent::ToolCallInputDelta { item_id, delta } => {
+                if let Some(path) = streamed_reads.on_input_delta(&item_id, &delta) {
+                    sess.services.prefetch.start(&turn_context.cwd, path);
+                }
+            }
             ResponseEvent::OutputTextDelta(delta) => {
                 // In review child threads, suppress assistant text deltas; the
                 // UI will show a selection popup from the final ReviewOutput.
                 if let Some(active) = active_item.as_ref() {
+                    turn_context.push_partial_message(&delta);
                     let event = AgentMessageContentDeltaEvent {
                         thread_id: sess.conversation_id.to_string(),
                         turn_id: turn_context.sub_id.clone(),
@@ -2840,6 +4018,8 @@ async fn try_run_turn(
             }
         }
     };
+    drop(request_timer);
+    drop(model_stream);
 
     drain_in_flight(&mut in_flight, sess.clone(), turn_context.clone()).await?;
 
@@ -2857,6 +4037,70 @@ async fn try_run_turn(
     outcome
 }
 
+fn turn_info(sess: &Session, turn_context: &TurnContext) -> TurnInfo {
+    TurnInfo {
+        thread_id: sess.conversation_id,
+        turn_id: turn_context.sub_id.clone(),
+        model: turn_context.client.get_model(),
+        cwd: turn_context.cwd.clone(),
+    }
+}
+
+/// Records the final message an interceptor ended the turn with, if any, as
+/// an assistant message.
+async fn record_interceptor_message(
+    sess: &Session,
+    turn_context: &TurnContext,
+    message: Option<String>,
+) -> Option<String> {
+    if let Some(text) = &message {
+        let item = ResponseItem::Message {
+            id: None,
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText { text: text.clone() }],
+        };
+        sess.record_response_item_and_emit_turn_item(turn_context, item)
+            .await;
+    }
+    message
+}
+
+/// Reports a tripped guardrail and records its message as the last thing
+/// the agent said, so the model knows why it stopped when the user continues.
+async fn record_guardrail_trip(
+    sess: &Session,
+    turn_context: &TurnContext,
+    trip: GuardrailTrippedEvent,
+) -> String {
+    let message = trip.message.clone();
+    sess.send_event(turn_context, EventMsg::GuardrailTripped(trip))
+        .await;
+    let item = ResponseItem::Message {
+        id: None,
+        role: "assistant".to_string(),
+        content: vec![ContentItem::OutputText {
+            text: message.clone(),
+        }],
+    };
+    sess.record_conversation_items(turn_context, &[item]).await;
+    message
+}
+
+/// Whether `event` carries output from the model, as opposed to stream
+/// bookkeeping such as rate limits.
+fn is_model_output(event: &ResponseEvent) -> bool {
+    matches!(
+        event,
+        ResponseEvent::OutputItemAdded(_)
+            | ResponseEvent::OutputItemDone(_)
+            | ResponseEvent::OutputTextDelta(_)
+            | ResponseEvent::ToolCallInputDelta { .. }
+            | ResponseEvent::ReasoningSummaryDelta { .. }
+            | ResponseEvent::ReasoningSummaryPartAdded { .. }
+            | ResponseEvent::ReasoningContentDelta { .. }
+    )
+}
+
 pub(super) fn get_last_assistant_message_from_turn(responses: &[ResponseItem]) -> Option<String> {
     responses.iter().rev().find_map(|item| {
         if let ResponseItem::Message { role, content, .. } = item {
@@ -3185,6 +4429,7 @@ mod tests {
             model,
             model_reasoning_effort: config.model_reasoning_effort,
             model_reasoning_summary: config.model_reasoning_summary,
+            model_verbosity: config.model_verbosity,
             developer_instructions: config.developer_instructions.clone(),
             user_instructions: config.user_instructions.clone(),
             base_instructions: config.base_instructions.clone(),
@@ -3251,6 +4496,7 @@ mod tests {
             model,
             model_reasoning_effort: config.model_reasoning_effort,
             model_reasoning_summary: config.model_reasoning_summary,
+            model_verbosity: config.model_verbosity,
             developer_instructions: config.developer_instructions.clone(),
             user_instructions: config.user_instructions.clone(),
             base_instructions: config.base_instructions.clone(),
@@ -3501,6 +4747,7 @@ mod tests {
             model,
             model_reasoning_effort: config.model_reasoning_effort,
             model_reasoning_summary: config.model_reasoning_summary,
+            model_verbosity: config.model_verbosity,
             developer_instructions: config.developer_instructions.clone(),
             user_instructions: config.user_instructions.clone(),
             base_instructions: config.base_instructions.clone(),
@@ -3531,10 +4778,27 @@ mod tests {
             mcp_startup_cancellation_token: CancellationToken::new(),
             unified_exec_manager: UnifiedExecProcessManager::default(),
             notifier: UserNotifier::new(None),
+            hooks: Arc::new(Hooks::default()),
+            event_socket: None,
+            tool_failure_breaker: Arc::default(),
+            watchdogs: Watchdogs::default(),
+            interceptors: Arc::new(Interceptors::default()),
+            redactor: Redactor::default(),
+            injection_detector: InjectionDetector::default(),
+            project_doc_watcher: ProjectDocWatcher::default(),
+            file_watch: FileWatch::default(),
+            workspace_snapshots: WorkspaceSnapshots::default(),
+            prefetch: Prefetcher::default(),
+            symbol_index: SymbolIndex::default(),
+            devcontainers: Mutex::new(HashMap::new()),
+            publishing: Mutex::new(()),
             rollout: Mutex::new(None),
             user_shell: Arc::new(default_user_shell()),
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
+            reasoning: config.reasoning,
+            variables: Variables::default(),
             exec_policy,
+            approval_rules: ApprovalRules::default(),
             auth_manager: auth_manager.clone(),
             otel_manager: otel_manager.clone(),
             models_manager: Arc::clone(&models_manager),
@@ -3558,6 +4822,7 @@ mod tests {
             conversation_id,
             tx_event,
             agent_status: Arc::clone(&agent_status),
+            last_turn_timing: Arc::default(),
             state: Mutex::new(state),
             features: config.features.clone(),
             active_turn: Mutex::new(None),
@@ -3595,6 +4860,7 @@ mod tests {
             model,
             model_reasoning_effort: config.model_reasoning_effort,
             model_reasoning_summary: config.model_reasoning_summary,
+            model_verbosity: config.model_verbosity,
             developer_instructions: config.developer_instructions.clone(),
             user_instructions: config.user_instructions.clone(),
             base_instructions: config.base_instructions.clone(),
@@ -3625,10 +4891,27 @@ mod tests {
             mcp_startup_cancellation_token: CancellationToken::new(),
             unified_exec_manager: UnifiedExecProcessManager::default(),
             notifier: UserNotifier::new(None),
+            hooks: Arc::new(Hooks::default()),
+            event_socket: None,
+            tool_failure_breaker: Arc::default(),
+            watchdogs: Watchdogs::default(),
+            interceptors: Arc::new(Interceptors::default()),
+            redactor: Redactor::default(),
+            injection_detector: InjectionDetector::default(),
+            project_doc_watcher: ProjectDocWatcher::default(),
+            file_watch: FileWatch::default(),
+            workspace_snapshots: WorkspaceSnapshots::default(),
+            prefetch: Prefetcher::default(),
+            symbol_index: SymbolIndex::default(),
+            devcontainers: Mutex::new(HashMap::new()),
+            publishing: Mutex::new(()),
             rollout: Mutex::new(None),
             user_shell: Arc::new(default_user_shell()),
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
+            reasoning: config.reasoning,
+            variables: Variables::default(),
             exec_policy,
+            approval_rules: ApprovalRules::default(),
             auth_manager: Arc::clone(&auth_manager),
             otel_manager: otel_manager.clone(),
             models_manager: Arc::clone(&models_manager),
@@ -3652,6 +4935,7 @@ mod tests {
             conversation_id,
             tx_event,
             agent_status: Arc::clone(&agent_status),
+            last_turn_timing: Arc::default(),
             state: Mutex::new(state),
             features: config.features.clone(),
             active_turn: Mutex::new(None),
@@ -3775,6 +5059,33 @@ mod tests {
         assert!(rx.try_recv().is_err());
     }
 
+    #[tokio::test]
+    async fn abort_turn_leaves_a_newer_turn_running() {
+        let (sess, tc, rx) = make_session_and_context_with_rx().await;
+        sess.spawn_task(
+            Arc::clone(&tc),
+            Vec::new(),
+            NeverEndingTask {
+                kind: TaskKind::Regular,
+                listen_to_cancellation_token: true,
+            },
+        )
+        .await;
+
+        sess.abort_turn("an-earlier-turn", TurnAbortReason::TimedOut)
+            .await;
+        assert!(sess.is_turn_running(&tc.sub_id).await);
+        assert!(rx.try_recv().is_err());
+
+        sess.abort_turn(&tc.sub_id, TurnAbortReason::TimedOut).await;
+        assert!(!sess.is_turn_running(&tc.sub_id).await);
+        let evt = rx.recv().await.expect("event");
+        match evt.msg {
+            EventMsg::TurnAborted(e) => assert_eq!(TurnAbortReason::TimedOut, e.reason),
+            other => panic!("unexpected event: {other:?}"),
+        }
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     async fn abort_review_task_emits_exited_then_aborted_and_records_history() {
         let (sess, tc, rx) = make_session_and_context_with_rx().await;
@@ -26,6 +26,7 @@ use crate::codex::TurnContext;
 use crate::config::Config;
 use crate::error::CodexErr;
 use crate::models_manager::manager::ModelsManager;
+use crate::watchdog::await_approval;
 use codex_protocol::protocol::InitialHistory;
 
 /// Start an interactive sub-Codex thread and return IO channels.
@@ -53,6 +54,7 @@ pub(crate) async fn run_codex_thread_interactive(
         initial_history.unwrap_or(InitialHistory::New),
         SessionSource::SubAgent(SubAgentSource::Review),
         parent_session.services.agent_control.clone(),
+        Vec::new(),
     )
     .await?;
     let codex = Arc::new(codex);
@@ -88,6 +90,9 @@ pub(crate) async fn run_codex_thread_interactive(
         tx_sub: tx_ops,
         rx_event: rx_sub,
         agent_status: Arc::clone(&codex.agent_status),
+        last_turn_timing: Arc::clone(&codex.last_turn_timing),
+        hooks: Arc::clone(&codex.hooks),
+        interceptors: Arc::clone(&codex.interceptors),
     })
 }
 
@@ -130,6 +135,9 @@ pub(crate) async fn run_codex_thread_one_shot(
     let (tx_bridge, rx_bridge) = async_channel::bounded(SUBMISSION_CHANNEL_CAPACITY);
     let ops_tx = io.tx_sub.clone();
     let agent_status = Arc::clone(&io.agent_status);
+    let last_turn_timing = Arc::clone(&io.last_turn_timing);
+    let hooks = Arc::clone(&io.hooks);
+    let interceptors = Arc::clone(&io.interceptors);
     let io_for_bridge = io;
     tokio::spawn(async move {
         while let Ok(event) = io_for_bridge.next_event().await {
@@ -162,6 +170,9 @@ pub(crate) async fn run_codex_thread_one_shot(
         rx_event: rx_bridge,
         tx_sub: tx_closed,
         agent_status,
+        last_turn_timing,
+        hooks,
+        interceptors,
     })
 }
 
@@ -325,7 +336,7 @@ async fn handle_patch_approval(
         )
         .await;
     let decision = await_approval_with_cancel(
-        async move { decision_rx.await.unwrap_or_default() },
+        await_approval(parent_session, parent_ctx, decision_rx),
         parent_session,
         &parent_ctx.sub_id,
         cancel_token,
@@ -377,6 +388,9 @@ mod tests {
             tx_sub,
             rx_event: rx_events,
             agent_status: Default::default(),
+            last_turn_timing: Default::default(),
+            hooks: Default::default(),
+            interceptors: Default::default(),
         });
 
         let (session, ctx, _rx_evt) = crate::codex::make_session_and_context_with_rx().await;
@@ -387,6 +401,7 @@ mod tests {
                 id: "full".to_string(),
                 msg: EventMsg::TurnAborted(TurnAbortedEvent {
                     reason: TurnAbortReason::Interrupted,
+                    cancelled: Vec::new(),
                 }),
             })
             .await
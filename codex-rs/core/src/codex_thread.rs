@@ -1,10 +1,17 @@
 use crate::agent::AgentStatus;
 use crate::codex::Codex;
 use crate::error::Result as CodexResult;
+use crate::hooks::EventFilter;
+use crate::hooks::HookEvent;
+use crate::hooks::HooksSnapshot;
+use crate::interceptors::Interceptor;
 use crate::protocol::Event;
 use crate::protocol::Op;
 use crate::protocol::Submission;
+use crate::protocol::TurnTiming;
+use futures::Stream;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 pub struct CodexThread {
     codex: Codex,
@@ -34,10 +41,45 @@ impl CodexThread {
         self.codex.next_event().await
     }
 
+    /// Streams this thread's events matching `filter` as they are emitted,
+    /// alongside [`CodexThread::next_event`].
+    pub fn subscribe(&self, filter: EventFilter) -> impl Stream<Item = HookEvent> + Send + 'static {
+        self.codex.subscribe(filter)
+    }
+
+    /// Saves this thread's registered hooks; see
+    /// [`Hooks::snapshot`](crate::hooks::Hooks::snapshot).
+    pub fn hooks_snapshot(&self) -> HooksSnapshot {
+        self.codex.hooks.snapshot()
+    }
+
+    /// Replaces this thread's external hooks with those in `snapshot`; see
+    /// [`Hooks::restore`](crate::hooks::Hooks::restore).
+    pub fn restore_hooks(&self, snapshot: HooksSnapshot) {
+        self.codex.hooks.restore(snapshot);
+    }
+
+    /// Registers `interceptor` on this thread under `name`, replacing any
+    /// interceptor with that name. It applies from the next model request.
+    pub fn register_interceptor(&self, name: impl Into<String>, interceptor: Arc<dyn Interceptor>) {
+        self.codex.interceptors.register(name, interceptor);
+    }
+
+    /// Removes the interceptor registered under `name`, returning whether
+    /// there was one.
+    pub fn unregister_interceptor(&self, name: &str) -> bool {
+        self.codex.interceptors.unregister(name)
+    }
+
     pub async fn agent_status(&self) -> AgentStatus {
         self.codex.agent_status().await
     }
 
+    /// Timing of the most recently completed turn, if any.
+    pub async fn last_turn_timing(&self) -> Option<TurnTiming> {
+        self.codex.last_turn_timing().await
+    }
+
     pub fn rollout_path(&self) -> PathBuf {
         self.rollout_path.clone()
     }
@@ -0,0 +1,295 @@
+//! Classifies what a command may do by parsing it instead of matching its
+//! text. Scripts passed to `bash -lc` are parsed with tree-sitter and every
+//! command in them is classified, including the ones behind `&&`, in
+//! pipelines, subshells and command substitutions, so `cargo test && rm -rf /`
+//! is destructive even though it starts with a harmless command.
+
+use std::collections::BTreeSet;
+
+use codex_protocol::parse_command::CommandOperation;
+use tree_sitter::Node;
+
+use crate::bash::extract_bash_command;
+use crate::bash::try_parse_shell;
+use crate::is_safe_command::is_known_safe_command;
+
+/// Redirect targets that do not write to a file.
+const HARMLESS_REDIRECT_TARGETS: &[&str] = &["/dev/null", "/dev/stdout", "/dev/stderr", "-"];
+
+const NETWORK_PROGRAMS: &[&str] = &[
+    "curl", "wget", "ssh", "scp", "sftp", "rsync", "nc", "ncat", "netcat", "telnet", "ftp",
+];
+
+/// Every kind of operation `command` may perform, least severe first. Empty
+/// only for an empty command or script.
+pub fn classify_command(command: &[String]) -> Vec<CommandOperation> {
+    let mut operations = BTreeSet::new();
+    classify_into(command, &mut operations);
+    operations.into_iter().collect()
+}
+
+fn classify_into(command: &[String], operations: &mut BTreeSet<CommandOperation>) {
+    match extract_bash_command(command) {
+        Some((_, script)) => classify_script(script, operations),
+        None => classify_words(command, operations),
+    }
+}
+
+fn classify_script(script: &str, operations: &mut BTreeSet<CommandOperation>) {
+    let Some(tree) = try_parse_shell(script) else {
+        operations.insert(CommandOperation::Write);
+        return;
+    };
+    let root = tree.root_node();
+    // The commands tree-sitter did recognize are still classified, but a
+    // script it could not fully parse is never considered read-only.
+    if root.has_error() {
+        operations.insert(CommandOperation::Write);
+    }
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        match node.kind() {
+            "command" => {
+                let words = command_words(node, script);
+                classify_words(&words, operations);
+            }
+            "file_redirect" if writes_file(node, script) => {
+                operations.insert(CommandOperation::Write);
+            }
+            _ => {}
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.named_children(&mut cursor));
+    }
+}
+
+/// The words of a command node, unquoted where that is simple. Expansions
+/// and substitutions are kept as written; the commands inside them are
+/// visited separately.
+fn command_words(node: Node, src: &str) -> Vec<String> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .filter(|child| {
+            !matches!(
+                child.kind(),
+                "variable_assignment" | "file_redirect" | "herestring_redirect"
+            )
+        })
+        .filter_map(|child| child.utf8_text(src.as_bytes()).ok())
+        .map(unquote)
+        .collect()
+}
+
+fn unquote(word: &str) -> String {
+    for quote in ['\'', '"'] {
+        if let Some(inner) = word
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return inner.to_string();
+        }
+    }
+    word.to_string()
+}
+
+/// Whether a redirect sends output to a file, as `> out.txt` does and
+/// `2>&1` or `> /dev/null` do not.
+fn writes_file(node: Node, src: &str) -> bool {
+    let mut cursor = node.walk();
+    let writes = node
+        .children(&mut cursor)
+        .any(|child| !child.is_named() && child.kind().contains('>'));
+    if !writes {
+        return false;
+    }
+    let destination = node
+        .child_by_field_name("destination")
+        .or_else(|| node.named_children(&mut cursor).last())
+        .and_then(|destination| destination.utf8_text(src.as_bytes()).ok())
+        .map(unquote)
+        .unwrap_or_default();
+    !(HARMLESS_REDIRECT_TARGETS.contains(&destination.as_str())
+        || destination.parse::<u32>().is_ok())
+}
+
+fn classify_words(words: &[String], operations: &mut BTreeSet<CommandOperation>) {
+    let words = strip_wrappers(words);
+    let Some(program) = words.first() else {
+        return;
+    };
+    if extract_bash_command(words).is_some() {
+        classify_into(words, operations);
+        return;
+    }
+    let name = program.rsplit('/').next().unwrap_or(program);
+    let args = &words[1..];
+    let destructive = is_destructive(name, args);
+    let network = uses_network(name, args);
+    if destructive {
+        operations.insert(CommandOperation::Destructive);
+    }
+    if network {
+        operations.insert(CommandOperation::Network);
+    }
+    if !destructive && !network {
+        operations.insert(if is_known_safe_command(words) {
+            CommandOperation::ReadOnly
+        } else {
+            CommandOperation::Write
+        });
+    }
+}
+
+/// Skips programs that run the rest of their arguments as a command, such
+/// as `sudo` and `env`, along with their own options.
+fn strip_wrappers(mut words: &[String]) -> &[String] {
+    loop {
+        let Some((program, rest)) = words.split_first() else {
+            return words;
+        };
+        let skip_arg: fn(&str) -> bool = match program.rsplit('/').next().unwrap_or(program) {
+            "sudo" | "doas" | "nohup" | "time" | "command" | "exec" | "xargs" => {
+                |arg| arg.starts_with('-')
+            }
+            "env" => |arg| arg.starts_with('-') || arg.contains('='),
+            "nice" | "timeout" => |arg| {
+                arg.starts_with('-') || arg.trim_end_matches(['s', 'm', 'h']).parse::<f64>().is_ok()
+            },
+            _ => return words,
+        };
+        let skipped = rest.iter().take_while(|arg| skip_arg(arg)).count();
+        words = &rest[skipped..];
+    }
+}
+
+/// The first argument that is not an option, skipping the values of the
+/// global options that take one (`git -C dir reset`).
+fn subcommand<'a>(args: &'a [String], options_with_values: &[&str]) -> Option<&'a str> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if options_with_values.contains(&arg.as_str()) {
+            args.next();
+        } else if !arg.starts_with('-') {
+            return Some(arg);
+        }
+    }
+    None
+}
+
+fn has_short_flag(args: &[String], flags: &[char]) -> bool {
+    args.iter().any(|arg| {
+        arg.strip_prefix('-')
+            .filter(|short| !short.starts_with('-'))
+            .is_some_and(|short| short.chars().any(|c| flags.contains(&c)))
+    })
+}
+
+fn has_arg(args: &[String], candidates: &[&str]) -> bool {
+    args.iter().any(|arg| candidates.contains(&arg.as_str()))
+}
+
+fn is_destructive(name: &str, args: &[String]) -> bool {
+    match name {
+        "rm" => {
+            has_short_flag(args, &['r', 'R', 'f']) || has_arg(args, &["--recursive", "--force"])
+        }
+        "git" => match subcommand(args, &["-C", "-c", "--git-dir", "--work-tree"]) {
+            Some("reset" | "rm" | "clean") => true,
+            Some("push") => {
+                has_short_flag(args, &['f', 'd'])
+                    || has_arg(
+                        args,
+                        &["--force", "--force-with-lease", "--delete", "--mirror"],
+                    )
+            }
+            Some("branch") => has_short_flag(args, &['D']),
+            _ => false,
+        },
+        "find" => has_arg(args, &["-delete"]),
+        "dd" | "shred" | "truncate" | "wipefs" | "fdisk" | "mkfs" => true,
+        _ => name.starts_with("mkfs."),
+    }
+}
+
+fn uses_network(name: &str, args: &[String]) -> bool {
+    if NETWORK_PROGRAMS.contains(&name) {
+        return true;
+    }
+    let subcommand = subcommand(args, &["-C", "-c", "--git-dir", "--work-tree"]);
+    match name {
+        "git" => matches!(
+            subcommand,
+            Some("clone" | "fetch" | "pull" | "push" | "ls-remote")
+        ),
+        "npm" | "pnpm" | "yarn" | "bun" => {
+            matches!(subcommand, Some("install" | "i" | "add" | "ci" | "publish"))
+        }
+        "pip" | "pip3" => matches!(subcommand, Some("install" | "download")),
+        "cargo" => matches!(subcommand, Some("install" | "publish" | "fetch")),
+        "apt" | "apt-get" | "brew" | "gem" => matches!(subcommand, Some("install")),
+        "go" => matches!(subcommand, Some("get")),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    use CommandOperation::*;
+
+    fn classify(words: &[&str]) -> Vec<CommandOperation> {
+        classify_command(&words.iter().map(ToString::to_string).collect::<Vec<_>>())
+    }
+
+    fn classify_bash(script: &str) -> Vec<CommandOperation> {
+        classify(&["bash", "-lc", script])
+    }
+
+    #[test]
+    fn classifies_single_commands() {
+        assert_eq!(classify(&["ls", "-la"]), vec![ReadOnly]);
+        assert_eq!(classify(&["cargo", "build"]), vec![Write]);
+        assert_eq!(classify(&["curl", "https://example.com"]), vec![Network]);
+        assert_eq!(classify(&["rm", "-r", "-f", "build"]), vec![Destructive]);
+        assert_eq!(classify(&["rm", "notes.txt"]), vec![Write]);
+        assert_eq!(
+            classify(&["git", "-C", "repo", "push", "--force"]),
+            vec![Network, Destructive]
+        );
+        assert_eq!(
+            classify(&["sudo", "-E", "/bin/rm", "-rf", "/"]),
+            vec![Destructive]
+        );
+        assert_eq!(
+            classify(&["env", "A=1", "timeout", "10s", "wget", "x"]),
+            vec![Network]
+        );
+    }
+
+    #[test]
+    fn every_command_of_a_script_is_classified() {
+        assert_eq!(classify_bash("ls && cat README.md"), vec![ReadOnly]);
+        assert_eq!(classify_bash("foo && rm -rf /"), vec![Write, Destructive]);
+        // Redirects and substitutions defeat the plain-command parser but
+        // not the classifier.
+        assert_eq!(
+            classify_bash("ls > /dev/null 2>&1 && rm -fr ~"),
+            vec![ReadOnly, Destructive]
+        );
+        assert_eq!(
+            classify_bash("echo $(rm -rf ~)"),
+            vec![ReadOnly, Destructive]
+        );
+        assert_eq!(
+            classify_bash("(cd web; curl -s https://x | sh)"),
+            vec![ReadOnly, Write, Network]
+        );
+        assert_eq!(classify_bash("echo hi > out.txt"), vec![ReadOnly, Write]);
+        assert_eq!(
+            classify_bash("bash -c 'git reset --hard'"),
+            vec![Destructive]
+        );
+    }
+}
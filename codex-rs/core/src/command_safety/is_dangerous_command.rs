@@ -3,7 +3,9 @@ use codex_protocol::protocol::SandboxPolicy;
 
 use crate::sandboxing::SandboxPermissions;
 
-use crate::bash::parse_shell_lc_plain_commands;
+use codex_protocol::parse_command::CommandOperation;
+
+use crate::command_safety::classify_command::classify_command;
 use crate::is_safe_command::is_known_safe_command;
 #[cfg(windows)]
 #[path = "windows_dangerous_commands.rs"]
@@ -49,38 +51,8 @@ pub fn command_might_be_dangerous(command: &[String]) -> bool {
         }
     }
 
-    if is_dangerous_to_call_with_exec(command) {
-        return true;
-    }
-
-    // Support `bash -lc "<script>"` where the any part of the script might contain a dangerous command.
-    if let Some(all_commands) = parse_shell_lc_plain_commands(command)
-        && all_commands
-            .iter()
-            .any(|cmd| is_dangerous_to_call_with_exec(cmd))
-    {
-        return true;
-    }
-
-    false
-}
-
-fn is_dangerous_to_call_with_exec(command: &[String]) -> bool {
-    let cmd0 = command.first().map(String::as_str);
-
-    match cmd0 {
-        Some(cmd) if cmd.ends_with("git") || cmd.ends_with("/git") => {
-            matches!(command.get(1).map(String::as_str), Some("reset" | "rm"))
-        }
-
-        Some("rm") => matches!(command.get(1).map(String::as_str), Some("-f" | "-rf")),
-
-        // for sudo <cmd> simply do the check for <cmd>
-        Some("sudo") => is_dangerous_to_call_with_exec(&command[1..]),
-
-        // ── anything else ─────────────────────────────────────────────────
-        _ => false,
-    }
+    // Any command of a `bash -lc "<script>"` can make the script dangerous.
+    classify_command(command).contains(&CommandOperation::Destructive)
 }
 
 #[cfg(test)]
@@ -155,6 +127,15 @@ mod tests {
         assert!(command_might_be_dangerous(&vec_str(&["rm", "-f", "/"])));
     }
 
+    #[test]
+    fn bash_rm_rf_after_redirect_is_dangerous() {
+        assert!(command_might_be_dangerous(&vec_str(&[
+            "bash",
+            "-lc",
+            "cargo test > log.txt && rm -r -f /"
+        ])));
+    }
+
     #[test]
     fn external_sandbox_only_prompts_for_dangerous_commands() {
         let external_policy = SandboxPolicy::ExternalSandbox {
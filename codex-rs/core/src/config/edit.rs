@@ -1,4 +1,5 @@
 use crate::config::CONFIG_TOML_FILE;
+use crate::config::service::toml_value_to_item;
 use crate::config::types::McpServerConfig;
 use crate::config::types::Notice;
 use anyhow::Context;
@@ -9,6 +10,7 @@ use std::path::Path;
 use std::path::PathBuf;
 use tempfile::NamedTempFile;
 use tokio::task;
+use toml::Value as TomlValue;
 use toml_edit::DocumentMut;
 use toml_edit::Item as TomlItem;
 use toml_edit::Table as TomlTable;
@@ -160,6 +162,15 @@ mod document_helpers {
         {
             entry["disabled_tools"] = array_from_iter(disabled_tools.iter().cloned());
         }
+        if let Some(sampling) = &config.sampling {
+            let mut sampling_table = TomlTable::new();
+            sampling_table.set_implicit(false);
+            sampling_table["approval"] = value(sampling.approval.as_str());
+            if let Some(token_budget) = sampling.token_budget {
+                sampling_table["token_budget"] = value(token_budget as i64);
+            }
+            entry["sampling"] = TomlItem::Table(sampling_table);
+        }
 
         entry
     }
@@ -553,6 +564,28 @@ pub fn apply_blocking(
     Ok(())
 }
 
+fn push_merge_edits(
+    edits: &mut Vec<ConfigEdit>,
+    segments: &mut Vec<String>,
+    value: &TomlValue,
+) -> anyhow::Result<()> {
+    match value {
+        TomlValue::Table(table) => {
+            for (key, value) in table {
+                segments.push(key.clone());
+                let result = push_merge_edits(edits, segments, value);
+                segments.pop();
+                result?;
+            }
+        }
+        leaf => edits.push(ConfigEdit::SetPath {
+            segments: segments.clone(),
+            value: toml_value_to_item(leaf)?,
+        }),
+    }
+    Ok(())
+}
+
 /// Persist edits asynchronously by offloading the blocking writer.
 pub async fn apply(
     codex_home: &Path,
@@ -655,6 +688,24 @@ impl ConfigEditsBuilder {
         self
     }
 
+    /// Record whether tool commands run in the project's dev container.
+    pub fn set_project_devcontainer<P: Into<PathBuf>>(
+        mut self,
+        project_path: P,
+        enabled: bool,
+    ) -> Self {
+        let project_path: PathBuf = project_path.into();
+        self.edits.push(ConfigEdit::SetPath {
+            segments: vec![
+                "projects".to_string(),
+                project_path.to_string_lossy().to_string(),
+                "devcontainer".to_string(),
+            ],
+            value: value(enabled),
+        });
+        self
+    }
+
     /// Enable or disable a feature flag by key under the `[features]` table.
     pub fn set_feature_enabled(mut self, key: &str, enabled: bool) -> Self {
         self.edits.push(ConfigEdit::SetPath {
@@ -664,6 +715,22 @@ impl ConfigEditsBuilder {
         self
     }
 
+    /// Set the TUI color theme under `[tui]`.
+    pub fn set_tui_theme(mut self, theme: &str) -> Self {
+        self.edits.push(ConfigEdit::SetPath {
+            segments: vec!["tui".to_string(), "theme".to_string()],
+            value: value(theme),
+        });
+        self
+    }
+
+    /// Set every value in `values`, a table laid out like `config.toml`,
+    /// leaving the file's other settings and comments as they are.
+    pub fn merge_values(mut self, values: &TomlValue) -> anyhow::Result<Self> {
+        push_merge_edits(&mut self.edits, &mut Vec::new(), values)?;
+        Ok(self)
+    }
+
     pub fn with_edits<I>(mut self, edits: I) -> Self
     where
         I: IntoIterator<Item = ConfigEdit>,
@@ -719,6 +786,25 @@ model_reasoning_effort = "high"
         assert_eq!(contents, expected);
     }
 
+    #[test]
+    fn project_devcontainer_choice_joins_the_project_table() {
+        let tmp = tempdir().expect("tmpdir");
+        let codex_home = tmp.path();
+
+        ConfigEditsBuilder::new(codex_home)
+            .set_project_trust_level("/work/api", TrustLevel::Trusted)
+            .set_project_devcontainer("/work/api", true)
+            .apply_blocking()
+            .expect("persist");
+
+        let contents =
+            std::fs::read_to_string(codex_home.join(CONFIG_TOML_FILE)).expect("read config");
+        let config: TomlValue = toml::from_str(&contents).expect("parse config");
+        let project = &config["projects"]["/work/api"];
+        assert_eq!(project["trust_level"].as_str(), Some("trusted"));
+        assert_eq!(project["devcontainer"].as_bool(), Some(true));
+    }
+
     #[test]
     fn builder_with_edits_applies_custom_paths() {
         let tmp = tempdir().expect("tmpdir");
@@ -737,6 +823,53 @@ model_reasoning_effort = "high"
         assert_eq!(contents, "enabled = true\n");
     }
 
+    #[test]
+    fn merge_values_keeps_other_settings_and_comments() {
+        let tmp = tempdir().expect("tmpdir");
+        let codex_home = tmp.path();
+        std::fs::write(
+            codex_home.join(CONFIG_TOML_FILE),
+            r#"# My settings
+model = "gpt-5.1-codex" # pinned
+
+[hooks.commands.audit]
+on = ["*"]
+command = ["audit"]
+"#,
+        )
+        .expect("seed config");
+        let values: TomlValue = toml::from_str(
+            r#"
+approval_policy = "on-request"
+
+[hooks.commands.notify]
+on = ["task_complete"]
+command = ["notify-send", "Done"]
+"#,
+        )
+        .expect("valid toml");
+
+        ConfigEditsBuilder::new(codex_home)
+            .merge_values(&values)
+            .expect("edits")
+            .apply_blocking()
+            .expect("persist");
+
+        let contents =
+            std::fs::read_to_string(codex_home.join(CONFIG_TOML_FILE)).expect("read config");
+        assert!(contents.starts_with("# My settings\nmodel = \"gpt-5.1-codex\" # pinned\n"));
+        let config: TomlValue = toml::from_str(&contents).expect("parse config");
+        assert_eq!(config["approval_policy"].as_str(), Some("on-request"));
+        assert_eq!(
+            config["hooks"]["commands"]["audit"]["command"],
+            TomlValue::Array(vec!["audit".into()])
+        );
+        assert_eq!(
+            config["hooks"]["commands"]["notify"]["on"],
+            TomlValue::Array(vec!["task_complete".into()])
+        );
+    }
+
     #[test]
     fn blocking_set_model_preserves_inline_table_contents() {
         let tmp = tempdir().expect("tmpdir");
@@ -1127,6 +1260,7 @@ gpt-5 = "gpt-5.1"
                 tool_timeout_sec: None,
                 enabled_tools: Some(vec!["one".to_string(), "two".to_string()]),
                 disabled_tools: None,
+                sampling: None,
             },
         );
 
@@ -1148,6 +1282,7 @@ gpt-5 = "gpt-5.1"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: Some(vec!["forbidden".to_string()]),
+                sampling: None,
             },
         );
 
@@ -1212,6 +1347,7 @@ foo = { command = "cmd" }
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         );
 
@@ -1255,6 +1391,7 @@ foo = { command = "cmd" } # keep me
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         );
 
@@ -1297,6 +1434,7 @@ foo = { command = "cmd", args = ["--flag"] } # keep me
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         );
 
@@ -1340,6 +1478,7 @@ foo = { command = "cmd" }
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         );
 
@@ -1401,6 +1540,26 @@ foo = { command = "cmd" , enabled = false }
         assert_eq!(notifications, Some(false));
     }
 
+    #[test]
+    fn builder_set_tui_theme_keeps_other_tui_settings() {
+        let tmp = tempdir().expect("tmpdir");
+        let codex_home = tmp.path();
+        std::fs::write(
+            codex_home.join(CONFIG_TOML_FILE),
+            "[tui]\nanimations = false\n",
+        )
+        .expect("seed config");
+
+        ConfigEditsBuilder::new(codex_home)
+            .set_tui_theme("light")
+            .apply_blocking()
+            .expect("persist");
+
+        let contents =
+            std::fs::read_to_string(codex_home.join(CONFIG_TOML_FILE)).expect("read config");
+        assert_eq!(contents, "[tui]\nanimations = false\ntheme = \"light\"\n");
+    }
+
     #[tokio::test]
     async fn async_builder_set_model_persists() {
         let tmp = tempdir().expect("tmpdir");
@@ -0,0 +1,346 @@
+//! `${env:VAR}` and `${file:/path}` references in config string values.
+//!
+//! References are resolved on the merged config just before it is
+//! deserialized, so the layers (and anything that displays them) keep the
+//! reference rather than the secret it points to. Only string values are
+//! expanded; keys are left alone. `$${env:VAR}` produces a literal
+//! `${env:VAR}`, and any other `${...}` is kept as written so that shell
+//! snippets such as `${HOME}` in hook commands are unaffected.
+//!
+//! Only values set by the user (`config.toml`, `-c` flags and
+//! `CODEX_CONFIG_*` variables) or by managed configuration are resolved. A
+//! project's `.codex/config.toml` comes with the repository, so references it
+//! sets are kept as written rather than reading the user's environment or
+//! files into settings.
+
+use std::collections::HashMap;
+use std::io::Error;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use codex_app_server_protocol::ConfigLayerMetadata;
+use codex_app_server_protocol::ConfigLayerSource;
+use dirs::home_dir;
+use toml::Value as TomlValue;
+
+use crate::config_loader::ConfigLayerStack;
+
+const ENV_PREFIX: &str = "${env:";
+const FILE_PREFIX: &str = "${file:";
+
+/// Replaces the references in the string values of `value`, the merged
+/// config of `stack`, that were set by a layer allowed to use them.
+pub(crate) fn interpolate_config(
+    value: &mut TomlValue,
+    stack: &ConfigLayerStack,
+) -> std::io::Result<()> {
+    interpolate_value(value, &mut Vec::new(), &stack.origins())
+}
+
+/// Like [`interpolate_config`] for the merged table stored under `key`.
+pub(crate) fn interpolate_config_table(
+    value: &mut TomlValue,
+    key: &str,
+    stack: &ConfigLayerStack,
+) -> std::io::Result<()> {
+    interpolate_value(value, &mut vec![key.to_string()], &stack.origins())
+}
+
+/// Whether references in values set by `source` are resolved.
+fn resolves_references(source: &ConfigLayerSource) -> bool {
+    match source {
+        ConfigLayerSource::Project { .. } => false,
+        ConfigLayerSource::Mdm { .. }
+        | ConfigLayerSource::System { .. }
+        | ConfigLayerSource::User { .. }
+        | ConfigLayerSource::Environment
+        | ConfigLayerSource::SessionFlags
+        | ConfigLayerSource::LegacyManagedConfigTomlFromFile { .. }
+        | ConfigLayerSource::LegacyManagedConfigTomlFromMdm => true,
+    }
+}
+
+/// Whether `value` contains a reference that [`interpolate_config`] would
+/// replace.
+pub(crate) fn contains_reference(value: &str) -> bool {
+    value.contains(ENV_PREFIX) || value.contains(FILE_PREFIX)
+}
+
+fn interpolate_value(
+    value: &mut TomlValue,
+    path: &mut Vec<String>,
+    origins: &HashMap<String, ConfigLayerMetadata>,
+) -> std::io::Result<()> {
+    match value {
+        TomlValue::String(string) => {
+            if contains_reference(string)
+                && origins
+                    .get(&origin_key(path))
+                    .is_some_and(|origin| resolves_references(&origin.name))
+            {
+                *string = interpolate_string(string).map_err(|reason| {
+                    Error::new(
+                        ErrorKind::InvalidData,
+                        format!("config value `{}` {reason}", display_path(path)),
+                    )
+                })?;
+            }
+        }
+        TomlValue::Array(values) => {
+            for (index, value) in values.iter_mut().enumerate() {
+                path.push(format!("[{index}]"));
+                let result = interpolate_value(value, path, origins);
+                path.pop();
+                result?;
+            }
+        }
+        TomlValue::Table(table) => {
+            for (key, value) in table.iter_mut() {
+                path.push(key.clone());
+                let result = interpolate_value(value, path, origins);
+                path.pop();
+                result?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn interpolate_string(input: &str) -> Result<String, String> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(start) = rest.find("${") {
+        let (before, tail) = rest.split_at(start);
+        let prefix = [ENV_PREFIX, FILE_PREFIX]
+            .into_iter()
+            .find(|prefix| tail.starts_with(prefix));
+        let Some(prefix) = prefix else {
+            output.push_str(before);
+            output.push_str("${");
+            rest = &tail[2..];
+            continue;
+        };
+        // `$${env:VAR}` is an escaped reference.
+        if let Some(escaped) = before.strip_suffix('$') {
+            output.push_str(escaped);
+            output.push_str(prefix);
+            rest = &tail[prefix.len()..];
+            continue;
+        }
+        output.push_str(before);
+        let Some(end) = tail.find('}') else {
+            return Err(format!("has an unterminated `{prefix}` reference"));
+        };
+        let name = &tail[prefix.len()..end];
+        let resolved = if prefix == ENV_PREFIX {
+            resolve_env(name)?
+        } else {
+            resolve_file(name)?
+        };
+        output.push_str(&resolved);
+        rest = &tail[end + 1..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+fn resolve_env(name: &str) -> Result<String, String> {
+    if name.is_empty() {
+        return Err("has an empty `${env:}` reference".to_string());
+    }
+    std::env::var(name).map_err(|err| match err {
+        std::env::VarError::NotPresent => {
+            format!("references environment variable `{name}`, which is not set")
+        }
+        std::env::VarError::NotUnicode(_) => {
+            format!("references environment variable `{name}`, which is not valid UTF-8")
+        }
+    })
+}
+
+/// Reads the file at `path`, dropping a single trailing newline so that
+/// secrets written with `echo` work as expected.
+fn resolve_file(path: &str) -> Result<String, String> {
+    let resolved = match path.strip_prefix("~/") {
+        Some(relative) => home_dir()
+            .map(|home| home.join(relative))
+            .ok_or_else(|| format!("references `{path}`, but the home directory is unknown"))?,
+        None => PathBuf::from(path),
+    };
+    if !resolved.is_absolute() {
+        return Err(format!(
+            "references file `{path}`; file references must be absolute or start with `~/`"
+        ));
+    }
+    let mut contents = std::fs::read_to_string(&resolved).map_err(|err| {
+        format!(
+            "references file `{}`, which cannot be read: {err}",
+            resolved.display()
+        )
+    })?;
+    if contents.ends_with('\n') {
+        contents.pop();
+        if contents.ends_with('\r') {
+            contents.pop();
+        }
+    }
+    Ok(contents)
+}
+
+/// The key of `path` in [`ConfigLayerStack::origins`], which numbers array
+/// elements like table keys.
+fn origin_key(path: &[String]) -> String {
+    path.iter()
+        .map(|segment| {
+            segment
+                .strip_prefix('[')
+                .and_then(|index| index.strip_suffix(']'))
+                .unwrap_or(segment)
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn display_path(path: &[String]) -> String {
+    let mut display = String::new();
+    for segment in path {
+        if !segment.starts_with('[') && !display.is_empty() {
+            display.push('.');
+        }
+        display.push_str(segment);
+    }
+    display
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_loader::ConfigLayerEntry;
+    use crate::config_loader::ConfigRequirements;
+    use crate::config_loader::ConfigRequirementsToml;
+    use codex_utils_absolute_path::AbsolutePathBuf;
+    use pretty_assertions::assert_eq;
+
+    fn layer_stack(layers: Vec<(ConfigLayerSource, &str)>) -> ConfigLayerStack {
+        let layers = layers
+            .into_iter()
+            .map(|(source, toml)| {
+                ConfigLayerEntry::new(source, toml::from_str(toml).expect("valid toml"))
+            })
+            .collect();
+        ConfigLayerStack::new(
+            layers,
+            ConfigRequirements::default(),
+            ConfigRequirementsToml::default(),
+        )
+        .expect("layers are ordered")
+    }
+
+    fn user_layer() -> ConfigLayerSource {
+        ConfigLayerSource::User {
+            file: AbsolutePathBuf::from_absolute_path("/home/user/.codex/config.toml")
+                .expect("absolute path"),
+        }
+    }
+
+    fn interpolate(toml: &str) -> std::io::Result<TomlValue> {
+        let stack = layer_stack(vec![(user_layer(), toml)]);
+        let mut value = stack.effective_config();
+        interpolate_config(&mut value, &stack)?;
+        Ok(value)
+    }
+
+    #[test]
+    fn resolves_file_references_and_keeps_other_placeholders() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let secret = dir.path().join("token");
+        std::fs::write(&secret, "s3cret\n").expect("write secret");
+
+        let value = interpolate(&format!(
+            r#"
+[mcp_servers.docs]
+command = "docs"
+args = ["--token=${{file:{}}}", "${{HOME}}/bin", "$${{env:LITERAL}}"]
+env = {{ PATH = "${{env:PATH}}" }}
+"#,
+            secret.display()
+        ))
+        .expect("interpolate");
+
+        let path = std::env::var("PATH").expect("PATH is set");
+        let expected: TomlValue = toml::from_str(&format!(
+            r#"
+[mcp_servers.docs]
+command = "docs"
+args = ["--token=s3cret", "${{HOME}}/bin", "${{env:LITERAL}}"]
+env = {{ PATH = {path:?} }}
+"#
+        ))
+        .expect("valid toml");
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn keeps_references_set_by_project_layers() {
+        let project = ConfigLayerSource::Project {
+            dot_codex_folder: AbsolutePathBuf::from_absolute_path("/repo/.codex")
+                .expect("absolute path"),
+        };
+        let stack = layer_stack(vec![
+            (user_layer(), r#"model_provider = "${env:PATH}""#),
+            (
+                project,
+                r#"
+model = "${env:PATH}"
+notify = ["notify", "${file:/etc/passwd}"]
+"#,
+            ),
+        ]);
+        let mut value = stack.effective_config();
+
+        interpolate_config(&mut value, &stack).expect("interpolate");
+
+        let path = std::env::var("PATH").expect("PATH is set");
+        let expected: TomlValue = toml::from_str(&format!(
+            r#"
+model_provider = {path:?}
+model = "${{env:PATH}}"
+notify = ["notify", "${{file:/etc/passwd}}"]
+"#
+        ))
+        .expect("valid toml");
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn reports_missing_references_with_the_key() {
+        let err = interpolate(
+            r#"
+[model_providers.acme.http_headers]
+"X-Api-Key" = "${env:CODEX_INTERPOLATE_TEST_UNSET}"
+"#,
+        )
+        .expect_err("unset variable");
+        assert_eq!(
+            err.to_string(),
+            "config value `model_providers.acme.http_headers.X-Api-Key` references environment \
+             variable `CODEX_INTERPOLATE_TEST_UNSET`, which is not set"
+        );
+
+        let err = interpolate(r#"notify = ["notify", "${file:/nonexistent/codex-token}"]"#)
+            .expect_err("missing file");
+        assert!(
+            err.to_string().starts_with(
+                "config value `notify[1]` references file `/nonexistent/codex-token`, which cannot be read"
+            ),
+            "{err}"
+        );
+
+        let err = interpolate(r#"model = "${env:MODEL""#).expect_err("unterminated");
+        assert_eq!(
+            err.to_string(),
+            "config value `model` has an unterminated `${env:` reference"
+        );
+    }
+}
@@ -1,35 +1,66 @@
 use crate::auth::AuthCredentialsStoreMode;
+use crate::config::types::CommandHookConfig;
+use crate::config::types::ContextBudgetConfig;
 use crate::config::types::DEFAULT_OTEL_ENVIRONMENT;
+use crate::config::types::DevcontainerExecBackend;
+use crate::config::types::ExecBackendConfig;
+use crate::config::types::GuardrailsConfig;
 use crate::config::types::History;
+use crate::config::types::HooksConfig;
+use crate::config::types::InjectionDetectionConfig;
 use crate::config::types::McpServerConfig;
+use crate::config::types::McpServerTransportConfig;
+use crate::config::types::MockProviderConfig;
+use crate::config::types::ModelPricing;
 use crate::config::types::Notice;
+use crate::config::types::NotificationBackend;
 use crate::config::types::Notifications;
 use crate::config::types::OtelConfig;
 use crate::config::types::OtelConfigToml;
 use crate::config::types::OtelExporterKind;
+use crate::config::types::PatchValidationConfig;
+use crate::config::types::PersistenceConfig;
+use crate::config::types::ProxyConfig;
+use crate::config::types::PullRequestConfig;
+use crate::config::types::QueuedMessageDelivery;
+use crate::config::types::ReasoningConfig;
+use crate::config::types::ReasoningRetention;
+use crate::config::types::RedactionConfig;
 use crate::config::types::SandboxWorkspaceWrite;
 use crate::config::types::ScrollInputMode;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyToml;
 use crate::config::types::Tui;
+use crate::config::types::TuiKeybindings;
+use crate::config::types::TuiStatusExport;
+use crate::config::types::TuiThemePalette;
+use crate::config::types::UpdateChannel;
 use crate::config::types::UriBasedFileOpener;
+use crate::config::types::UserCommandConfig;
+use crate::config::types::VerifyConfig;
+use crate::config::types::WatchdogsConfig;
 use crate::config_loader::ConfigLayerStack;
 use crate::config_loader::ConfigRequirements;
 use crate::config_loader::LoaderOverrides;
 use crate::config_loader::load_config_layers_state;
+use crate::default_client::DIRECT_PROXY;
 use crate::features::Feature;
 use crate::features::FeatureOverrides;
 use crate::features::Features;
 use crate::features::FeaturesToml;
+use crate::git_info::get_git_repo_root;
 use crate::git_info::resolve_root_git_project_for_trust;
+use crate::hooks::EventExpr;
 use crate::model_provider_info::LMSTUDIO_OSS_PROVIDER_ID;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::OLLAMA_OSS_PROVIDER_ID;
 use crate::model_provider_info::built_in_model_providers;
+use crate::offline;
 use crate::project_doc::DEFAULT_PROJECT_DOC_FILENAME;
 use crate::project_doc::LOCAL_PROJECT_DOC_FILENAME;
 use crate::protocol::AskForApproval;
 use crate::protocol::SandboxPolicy;
+use crate::redaction::Redactor;
 use codex_app_server_protocol::Tools;
 use codex_app_server_protocol::UserSavedConfig;
 use codex_protocol::config_types::AltScreenMode;
@@ -39,10 +70,12 @@ use codex_protocol::config_types::SandboxMode;
 use codex_protocol::config_types::TrustLevel;
 use codex_protocol::config_types::Verbosity;
 use codex_protocol::openai_models::ReasoningEffort;
+use codex_protocol::protocol::WatchdogAction;
 use codex_rmcp_client::OAuthCredentialsStoreMode;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use codex_utils_absolute_path::AbsolutePathBufGuard;
 use dirs::home_dir;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 use similar::DiffableStr;
@@ -60,13 +93,17 @@ use toml_edit::DocumentMut;
 
 mod constraint;
 pub mod edit;
+mod interpolate;
 pub mod profile;
+pub mod schema;
 pub mod service;
 pub mod types;
 pub use constraint::Constrained;
 pub use constraint::ConstraintError;
 pub use constraint::ConstraintResult;
 
+use schema::DiagnosticKind;
+use schema::DiagnosticSeverity;
 pub use service::ConfigService;
 pub use service::ConfigServiceError;
 
@@ -81,6 +118,9 @@ pub(crate) const PROJECT_DOC_MAX_BYTES: usize = 32 * 1024; // 32 KiB
 
 pub const CONFIG_TOML_FILE: &str = "config.toml";
 
+/// Per-project file naming the config profile to use inside that project.
+pub const PROJECT_PROFILE_MARKER: &str = ".codex/profile";
+
 #[cfg(test)]
 pub(crate) fn test_config() -> Config {
     let codex_home = tempdir().expect("create temp dir");
@@ -179,9 +219,23 @@ pub struct Config {
     /// and turn completions when not focused.
     pub tui_notifications: Notifications,
 
-    /// Enable ASCII animations and shimmer effects in the TUI.
+    /// How the TUI delivers desktop notifications.
+    ///
+    /// This is the same `tui.notification_backend` value from `config.toml` (see [`Tui`]).
+    pub tui_notification_backend: NotificationBackend,
+
+    /// When the TUI delivers messages submitted while a turn is running.
+    ///
+    /// This is the same `tui.queued_message_delivery` value from `config.toml` (see [`Tui`]).
+    pub tui_queued_message_delivery: QueuedMessageDelivery,
+
+    /// Enable ASCII animations and shimmer effects in the TUI. Always `false`
+    /// when [`Config::tui_screen_reader`] is set.
     pub animations: bool,
 
+    /// Render the TUI for screen readers (see [`Tui::screen_reader`]).
+    pub tui_screen_reader: bool,
+
     /// Show startup tooltips in the TUI welcome screen.
     pub show_tooltips: bool,
 
@@ -245,6 +299,98 @@ pub struct Config {
     /// - `never`: Never use alternate screen (inline mode, preserves scrollback).
     pub tui_alternate_screen: AltScreenMode,
 
+    /// Key overrides for TUI actions.
+    ///
+    /// This is the same `tui.keybindings` table from `config.toml` (see [`Tui`]).
+    pub tui_keybindings: TuiKeybindings,
+
+    /// Name of the TUI color theme, if set.
+    ///
+    /// This is the same `tui.theme` value from `config.toml` (see [`Tui`]).
+    pub tui_theme: Option<String>,
+
+    /// User-defined TUI palettes keyed by theme name.
+    ///
+    /// This is the same `tui.themes` table from `config.toml` (see [`Tui`]).
+    pub tui_themes: BTreeMap<String, TuiThemePalette>,
+
+    /// Where the TUI publishes its state for multiplexer status bars.
+    ///
+    /// This is the same `tui.status_export` table from `config.toml` (see [`Tui`]).
+    pub tui_status_export: TuiStatusExport,
+
+    /// Slash commands defined under `[commands]`, keyed by command name.
+    pub user_commands: BTreeMap<String, UserCommandConfig>,
+
+    /// Hooks that run when the agent emits matching events.
+    pub hooks: HooksConfig,
+
+    /// Hooks required by `requirements.toml`. They always run and replace any
+    /// user hook with the same name.
+    pub required_hooks: BTreeMap<String, CommandHookConfig>,
+
+    /// Tools the model may not call, as set by `requirements.toml`. A trailing
+    /// `*` matches any suffix.
+    pub banned_tools: Vec<String>,
+
+    /// Commit-and-open-a-pull-request workflow run when a task finishes.
+    pub pull_request: PullRequestConfig,
+
+    /// Secret redaction applied to tool output before it reaches the model.
+    pub redaction: RedactionConfig,
+
+    /// Whether clients of the session's event socket may answer approval
+    /// requests. The socket is read-only unless this is set.
+    pub event_socket_approvals: bool,
+
+    /// Prompt-injection screening applied to tool output.
+    pub injection_detection: InjectionDetectionConfig,
+
+    /// Limits that stop runaway turns, such as repeated tool failures.
+    pub guardrails: GuardrailsConfig,
+
+    /// Time limits on turns, the session and approval requests.
+    pub watchdogs: WatchdogsConfig,
+
+    /// Per-model prices used to estimate the cost of turns, overriding the
+    /// built-in table. Keyed by model slug or slug prefix.
+    pub model_pricing: BTreeMap<String, ModelPricing>,
+
+    /// How session rollouts are written to disk.
+    pub persistence: PersistenceConfig,
+
+    /// Proxies Codex's HTTP clients connect through.
+    pub proxy: ProxyConfig,
+
+    /// Scripted responses replayed by the built-in `mock` provider.
+    pub mock_provider: MockProviderConfig,
+
+    /// Command run after turns that edit files to check the agent's work.
+    pub verify: VerifyConfig,
+
+    /// Syntax check of patched files before a patch is applied.
+    pub patch_validation: PatchValidationConfig,
+
+    /// Where tool commands run.
+    pub exec_backend: ExecBackendConfig,
+
+    /// Token limits per kind of content in each model request.
+    pub context_budget: ContextBudgetConfig,
+
+    /// What clients, hooks and the rollout keep of the model's reasoning.
+    pub reasoning: ReasoningConfig,
+
+    /// Per-action approval rules, one `<allow|ask|deny> <exec|write> <pattern>`
+    /// rule per entry.
+    pub approval_rules: Vec<String>,
+
+    /// Labels recorded in the rollout metadata of new sessions.
+    pub session_labels: Vec<String>,
+
+    /// Problems found while validating the config files that did not stop
+    /// them from loading, such as unknown keys or deprecated options.
+    pub config_warnings: Vec<String>,
+
     /// The directory that should be treated as the current working directory
     /// for the session. All relative paths inside the business-logic layer are
     /// resolved against this path.
@@ -356,6 +502,14 @@ pub struct Config {
     /// Defaults to `true`.
     pub check_for_update_on_startup: bool,
 
+    /// Releases the update check and `codex self-update` consider.
+    pub update_channel: UpdateChannel,
+
+    /// When `true`, features that need the network are turned off and model
+    /// requests only go to providers on this machine or the local network.
+    /// See [`crate::offline`].
+    pub offline: bool,
+
     /// When true, disables burst-paste detection for typed input entirely.
     /// All characters are inserted as they are received, and no buffering
     /// or placeholder replacement will occur for fast keypress bursts.
@@ -420,21 +574,49 @@ impl ConfigBuilder {
         let config_layer_stack =
             load_config_layers_state(&codex_home, Some(cwd), &cli_overrides, loader_overrides)
                 .await?;
-        let merged_toml = config_layer_stack.effective_config();
+        let mut merged_toml = config_layer_stack.effective_config();
+        let diagnostics = schema::validate_config_layers(&config_layer_stack);
+        let untrusted_project_folders = config_layer_stack.untrusted_project_folders().to_vec();
+        interpolate::interpolate_config(&mut merged_toml, &config_layer_stack)?;
 
         // Note that each layer in ConfigLayerStack should have resolved
         // relative paths to absolute paths based on the parent folder of the
         // respective config file, so we should be safe to deserialize without
         // AbsolutePathBufGuard here.
-        let config_toml: ConfigToml = merged_toml
-            .try_into()
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
-        Config::load_config_with_layer_stack(
+        let config_toml: ConfigToml = merged_toml.try_into().map_err(|e| {
+            // Prefer pointing at the offending file and line when a layer is
+            // invalid on its own.
+            let message = diagnostics
+                .iter()
+                .find(|d| d.severity() == DiagnosticSeverity::Error)
+                .map_or_else(|| format!("{e}"), ToString::to_string);
+            std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+        })?;
+        let mut config = Config::load_config_with_layer_stack(
             config_toml,
             harness_overrides,
             codex_home,
             config_layer_stack,
-        )
+        )?;
+        config.config_warnings.extend(
+            diagnostics
+                .iter()
+                // Deprecations are announced separately when a session starts.
+                .filter(|d| d.kind == DiagnosticKind::UnknownKey)
+                .map(ToString::to_string),
+        );
+        // Projects the user already decided not to trust stay quiet.
+        if config.active_project.trust_level.is_none() {
+            config
+                .config_warnings
+                .extend(untrusted_project_folders.iter().map(|folder| {
+                    format!(
+                        "Ignored {} because this project is not trusted yet; run `codex trust set full` to trust it.",
+                        folder.as_path().display()
+                    )
+                }));
+        }
+        Ok(config)
     }
 }
 
@@ -484,7 +666,8 @@ pub async fn load_config_as_toml_with_cli_overrides(
     )
     .await?;
 
-    let merged_toml = config_layer_stack.effective_config();
+    let mut merged_toml = config_layer_stack.effective_config();
+    interpolate::interpolate_config(&mut merged_toml, &config_layer_stack)?;
     let cfg = deserialize_config_toml_with_base(merged_toml, codex_home).map_err(|e| {
         tracing::error!("Failed to deserialize overridden config: {e}");
         e
@@ -529,8 +712,9 @@ pub async fn load_global_mcp_servers(
 
     ensure_no_inline_bearer_tokens(servers_value)?;
 
+    let mut servers_value = servers_value.clone();
+    interpolate::interpolate_config_table(&mut servers_value, "mcp_servers", &config_layer_stack)?;
     servers_value
-        .clone()
         .try_into()
         .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
 }
@@ -682,7 +866,7 @@ pub fn set_default_oss_provider(codex_home: &Path, provider: &str) -> std::io::R
 }
 
 /// Base config deserialized from ~/.codex/config.toml.
-#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, JsonSchema)]
 pub struct ConfigToml {
     /// Optional override of model selection.
     pub model: Option<String>,
@@ -733,9 +917,9 @@ pub struct ConfigToml {
     pub forced_login_method: Option<ForcedLoginMethod>,
 
     /// Preferred backend for storing CLI auth credentials.
-    /// file (default): Use a file in the Codex home directory.
+    /// file: Use a file in the Codex home directory.
     /// keyring: Use an OS-specific keyring service.
-    /// auto: Use the keyring if available, otherwise use a file.
+    /// auto (default): Use the keyring if available, otherwise use a file.
     #[serde(default)]
     pub cli_auth_credentials_store: Option<AuthCredentialsStoreMode>,
 
@@ -771,6 +955,80 @@ pub struct ConfigToml {
     #[serde(default)]
     pub profiles: HashMap<String, ConfigProfile>,
 
+    /// User-defined slash commands that expand into prompts.
+    #[serde(default)]
+    pub commands: BTreeMap<String, UserCommandConfig>,
+
+    /// Hooks that run when the agent emits matching events.
+    #[serde(default)]
+    pub hooks: HooksConfig,
+
+    /// Commit-and-open-a-pull-request workflow run when a task finishes.
+    #[serde(default)]
+    pub pull_request: PullRequestConfig,
+
+    /// Secret redaction applied to tool output before it reaches the model.
+    #[serde(default)]
+    pub redaction: RedactionConfig,
+
+    /// Let clients of the session's event socket, such as `codex serve --web`,
+    /// answer approval requests. Defaults to `false`.
+    pub event_socket_approvals: Option<bool>,
+
+    /// Prompt-injection screening applied to tool output.
+    #[serde(default)]
+    pub injection_detection: InjectionDetectionConfig,
+
+    /// Limits that stop runaway turns, such as repeated tool failures.
+    #[serde(default)]
+    pub guardrails: GuardrailsConfig,
+
+    /// Time limits on turns, the session and approval requests.
+    #[serde(default)]
+    pub watchdogs: WatchdogsConfig,
+
+    /// Prices used to estimate the cost of turns, keyed by model slug or slug
+    /// prefix, e.g. `[model_pricing."gpt-5"]`.
+    #[serde(default)]
+    pub model_pricing: BTreeMap<String, ModelPricing>,
+
+    /// How session rollouts are written to disk.
+    #[serde(default)]
+    pub persistence: PersistenceConfig,
+
+    /// Proxies Codex's HTTP clients connect through.
+    #[serde(default)]
+    pub proxy: ProxyConfig,
+
+    /// Scripted responses replayed by the built-in `mock` provider.
+    #[serde(default)]
+    pub mock_provider: MockProviderConfig,
+
+    /// Command run after turns that edit files to check the agent's work.
+    #[serde(default)]
+    pub verify: VerifyConfig,
+
+    /// Syntax check of patched files before a patch is applied.
+    #[serde(default)]
+    pub patch_validation: PatchValidationConfig,
+
+    /// Where tool commands run: locally, or on another host over SSH.
+    #[serde(default)]
+    pub exec_backend: ExecBackendConfig,
+
+    /// Token limits per kind of content in each model request.
+    #[serde(default)]
+    pub context_budget: ContextBudgetConfig,
+
+    /// What clients, hooks and the rollout keep of the model's reasoning.
+    #[serde(default)]
+    pub reasoning: ReasoningConfig,
+
+    /// Per-action approval rules, one `<allow|ask|deny> <exec|write> <pattern>`
+    /// rule per entry.
+    #[serde(default)]
+    pub approval_rules: Vec<String>,
+
     /// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
     #[serde(default)]
     pub history: Option<History>,
@@ -824,6 +1082,19 @@ pub struct ConfigToml {
     /// Defaults to `true`.
     pub check_for_update_on_startup: Option<bool>,
 
+    /// Releases the update check and `codex self-update` consider: `stable`
+    /// (the default) or `beta`, which adds pre-releases.
+    pub update_channel: Option<UpdateChannel>,
+
+    /// When `true`, turns off features that need the network and only sends
+    /// model requests to providers on this machine or the local network.
+    /// Defaults to `false`.
+    pub offline: Option<bool>,
+
+    /// Caps requests to and responses from model providers at this many
+    /// bytes per second, unless a provider sets its own `bandwidth_limit`.
+    pub bandwidth_limit: Option<u64>,
+
     /// When true, disables burst-paste detection for typed input entirely.
     /// All characters are inserted as they are received, and no buffering
     /// or placeholder replacement will occur for fast keypress bursts.
@@ -881,9 +1152,17 @@ impl From<ConfigToml> for UserSavedConfig {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
 pub struct ProjectConfig {
     pub trust_level: Option<TrustLevel>,
+    /// Profile applied automatically when Codex runs inside this project,
+    /// unless `--profile` is given.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Whether tool commands run in the project's dev container, as decided
+    /// when Codex offered it. Unset until then.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub devcontainer: Option<bool>,
 }
 
 impl ProjectConfig {
@@ -894,9 +1173,17 @@ impl ProjectConfig {
     pub fn is_untrusted(&self) -> bool {
         matches!(self.trust_level, Some(TrustLevel::Untrusted))
     }
+
+    pub fn is_read_only(&self) -> bool {
+        matches!(self.trust_level, Some(TrustLevel::ReadOnly))
+    }
+
+    pub fn is_denied(&self) -> bool {
+        matches!(self.trust_level, Some(TrustLevel::Deny))
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, JsonSchema)]
 pub struct ToolsToml {
     #[serde(default, alias = "web_search_request")]
     pub web_search: Option<bool>,
@@ -915,7 +1202,7 @@ impl From<ToolsToml> for Tools {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
 pub struct GhostSnapshotToml {
     /// Exclude untracked files larger than this many bytes from ghost snapshots.
     #[serde(alias = "ignore_untracked_files_over_bytes")]
@@ -946,10 +1233,13 @@ impl ConfigToml {
             .or(profile_sandbox_mode)
             .or(self.sandbox_mode)
             .or_else(|| {
-                // if no sandbox_mode is set, but user has marked directory as trusted or untrusted, use WorkspaceWrite
+                // if no sandbox_mode is set, but user has marked directory as trusted or untrusted, use WorkspaceWrite;
+                // read-only and denied directories get ReadOnly
                 self.get_active_project(resolved_cwd).and_then(|p| {
                     if p.is_trusted() || p.is_untrusted() {
                         Some(SandboxMode::WorkspaceWrite)
+                    } else if p.is_read_only() || p.is_denied() {
+                        Some(SandboxMode::ReadOnly)
                     } else {
                         None
                     }
@@ -992,23 +1282,31 @@ impl ConfigToml {
     /// Resolves the cwd to an existing project, or returns None if ConfigToml
     /// does not contain a project corresponding to cwd or a git repo for cwd
     pub fn get_active_project(&self, resolved_cwd: &Path) -> Option<ProjectConfig> {
-        let projects = self.projects.clone().unwrap_or_default();
+        find_project(self.projects.as_ref()?, resolved_cwd)
+    }
 
-        if let Some(project_config) = projects.get(&resolved_cwd.to_string_lossy().to_string()) {
-            return Some(project_config.clone());
+    /// Returns the profile selected for `resolved_cwd` by its
+    /// `[projects."<path>"]` entry or, in trusted projects, by a
+    /// `.codex/profile` marker between `resolved_cwd` and the repository root.
+    /// A marker naming an unknown profile is ignored with a warning.
+    fn project_profile(&self, resolved_cwd: &Path) -> Option<String> {
+        let project = self.get_active_project(resolved_cwd)?;
+        if project.profile.is_some() {
+            return project.profile;
         }
-
-        // If cwd lives inside a git repo/worktree, check whether the root git project
-        // (the primary repository working directory) is trusted. This lets
-        // worktrees inherit trust from the main project.
-        if let Some(repo_root) = resolve_root_git_project_for_trust(resolved_cwd)
-            && let Some(project_config_for_root) =
-                projects.get(&repo_root.to_string_lossy().to_string_lossy().to_string())
-        {
-            return Some(project_config_for_root.clone());
+        if !project.is_trusted() {
+            return None;
+        }
+        let (marker, profile) = find_profile_marker(resolved_cwd)?;
+        if self.profiles.contains_key(&profile) {
+            Some(profile)
+        } else {
+            tracing::warn!(
+                "ignoring {}: config profile `{profile}` not found",
+                marker.display()
+            );
+            None
         }
-
-        None
     }
 
     pub fn get_config_profile(
@@ -1033,6 +1331,47 @@ impl ConfigToml {
     }
 }
 
+/// The `[projects]` entry for `resolved_cwd`, or for the repository it is in.
+pub(crate) fn find_project(
+    projects: &HashMap<String, ProjectConfig>,
+    resolved_cwd: &Path,
+) -> Option<ProjectConfig> {
+    if let Some(project_config) = projects.get(&resolved_cwd.to_string_lossy().to_string()) {
+        return Some(project_config.clone());
+    }
+
+    // If cwd lives inside a git repo/worktree, check whether the root git project
+    // (the primary repository working directory) is trusted. This lets
+    // worktrees inherit trust from the main project.
+    if let Some(repo_root) = resolve_root_git_project_for_trust(resolved_cwd)
+        && let Some(project_config_for_root) =
+            projects.get(&repo_root.to_string_lossy().to_string_lossy().to_string())
+    {
+        return Some(project_config_for_root.clone());
+    }
+
+    None
+}
+
+/// Looks for a `.codex/profile` file in `cwd` and its parents up to the
+/// enclosing git repository root, returning its path and the profile name on
+/// its first non-empty line.
+fn find_profile_marker(cwd: &Path) -> Option<(PathBuf, String)> {
+    let root = get_git_repo_root(cwd);
+    for dir in cwd.ancestors() {
+        let marker = dir.join(PROJECT_PROFILE_MARKER);
+        if let Ok(contents) = std::fs::read_to_string(&marker)
+            && let Some(name) = contents.lines().map(str::trim).find(|l| !l.is_empty())
+        {
+            return Some((marker, name.to_string()));
+        }
+        if root.as_deref().is_none_or(|root| root == dir) {
+            break;
+        }
+    }
+    None
+}
+
 /// Optional overrides for user configuration (e.g., from CLI flags).
 #[derive(Default, Debug, Clone)]
 pub struct ConfigOverrides {
@@ -1052,6 +1391,10 @@ pub struct ConfigOverrides {
     pub tools_web_search_request: Option<bool>,
     /// Additional directories that should be treated as writable roots for this session.
     pub additional_writable_roots: Vec<PathBuf>,
+    /// Labels recorded in the session's rollout metadata.
+    pub session_labels: Vec<String>,
+    /// Verification command; replaces `verify.command` from `config.toml`.
+    pub verify_command: Option<String>,
 }
 
 /// Resolves the OSS provider from CLI override, profile config, or global config.
@@ -1120,12 +1463,32 @@ impl Config {
             show_raw_agent_reasoning,
             tools_web_search_request: override_tools_web_search_request,
             additional_writable_roots,
+            session_labels,
+            verify_command,
         } = overrides;
 
+        let resolved_cwd = {
+            use std::env;
+
+            match cwd {
+                None => {
+                    tracing::info!("cwd not set, using current dir");
+                    env::current_dir()?
+                }
+                Some(p) if p.is_absolute() => p,
+                Some(p) => {
+                    // Resolve relative path against the current working directory.
+                    tracing::info!("cwd is relative, resolving against current dir");
+                    let mut current = env::current_dir()?;
+                    current.push(p);
+                    current
+                }
+            }
+        };
+
         let active_profile_name = config_profile_key
-            .as_ref()
-            .or(cfg.profile.as_ref())
-            .cloned();
+            .or_else(|| cfg.project_profile(&resolved_cwd))
+            .or_else(|| cfg.profile.clone());
         let config_profile = match active_profile_name.as_ref() {
             Some(key) => cfg
                 .profiles
@@ -1145,7 +1508,20 @@ impl Config {
             web_search_request: override_tools_web_search_request,
         };
 
-        let features = Features::from_config(&cfg, &config_profile, feature_overrides);
+        let offline = cfg.offline.unwrap_or(false);
+        let mut offline_warnings = Vec::new();
+        let mut features = Features::from_config(&cfg, &config_profile, feature_overrides);
+        if offline {
+            for feature in [Feature::WebSearchRequest, Feature::WebSearchCached] {
+                if features.enabled(feature) {
+                    features.disable(feature);
+                    offline_warnings.push(format!(
+                        "`{}` is turned off because `offline = true`: web search needs the network.",
+                        feature.key()
+                    ));
+                }
+            }
+        }
         #[cfg(target_os = "windows")]
         {
             // Base flag controls sandbox on/off; elevated only applies when base is enabled.
@@ -1156,31 +1532,22 @@ impl Config {
             crate::safety::set_windows_elevated_sandbox_enabled(elevated_enabled);
         }
 
-        let resolved_cwd = {
-            use std::env;
-
-            match cwd {
-                None => {
-                    tracing::info!("cwd not set, using current dir");
-                    env::current_dir()?
-                }
-                Some(p) if p.is_absolute() => p,
-                Some(p) => {
-                    // Resolve relative path against the current working directory.
-                    tracing::info!("cwd is relative, resolving against current dir");
-                    let mut current = env::current_dir()?;
-                    current.push(p);
-                    current
-                }
-            }
-        };
         let additional_writable_roots: Vec<AbsolutePathBuf> = additional_writable_roots
             .into_iter()
             .map(|path| AbsolutePathBuf::resolve_path_against_base(path, &resolved_cwd))
             .collect::<Result<Vec<_>, _>>()?;
-        let active_project = cfg
-            .get_active_project(&resolved_cwd)
-            .unwrap_or(ProjectConfig { trust_level: None });
+        let active_project = cfg.get_active_project(&resolved_cwd).unwrap_or_default();
+        // A project the user chose to run in its dev container gets that
+        // backend unless another one was configured.
+        let exec_backend = match config_profile
+            .exec_backend
+            .unwrap_or_else(|| cfg.exec_backend.clone())
+        {
+            ExecBackendConfig::Local if active_project.devcontainer == Some(true) => {
+                ExecBackendConfig::Devcontainer(DevcontainerExecBackend::default())
+            }
+            exec_backend => exec_backend,
+        };
 
         let SandboxPolicyResolution {
             policy: mut sandbox_policy,
@@ -1197,9 +1564,9 @@ impl Config {
             .or(config_profile.approval_policy)
             .or(cfg.approval_policy)
             .unwrap_or_else(|| {
-                if active_project.is_trusted() {
+                if active_project.is_trusted() || active_project.is_read_only() {
                     AskForApproval::OnRequest
-                } else if active_project.is_untrusted() {
+                } else if active_project.is_untrusted() || active_project.is_denied() {
                     AskForApproval::UnlessTrusted
                 } else {
                     AskForApproval::default()
@@ -1220,6 +1587,11 @@ impl Config {
         for (key, provider) in cfg.model_providers.into_iter() {
             model_providers.entry(key).or_insert(provider);
         }
+        if let Some(bandwidth_limit) = cfg.bandwidth_limit {
+            for provider in model_providers.values_mut() {
+                provider.bandwidth_limit.get_or_insert(bandwidth_limit);
+            }
+        }
 
         let model_provider_id = model_provider
             .or(config_profile.model_provider)
@@ -1320,14 +1692,62 @@ impl Config {
             .or(cfg.review_model)
             .unwrap_or_else(default_review_model);
 
-        let check_for_update_on_startup = cfg.check_for_update_on_startup.unwrap_or(true);
+        let check_for_update_on_startup =
+            !offline && cfg.check_for_update_on_startup.unwrap_or(true);
+
+        let mut mcp_servers = cfg.mcp_servers;
+        if offline {
+            let mut turned_off = Vec::new();
+            for (name, server) in &mut mcp_servers {
+                if server.enabled
+                    && let McpServerTransportConfig::StreamableHttp { url, .. } = &server.transport
+                    && !offline::is_local_url(url)
+                {
+                    server.enabled = false;
+                    turned_off.push(name.clone());
+                }
+            }
+            turned_off.sort();
+            offline_warnings.extend(turned_off.into_iter().map(|name| {
+                format!(
+                    "MCP server `{name}` is turned off because `offline = true`: its URL is not on this machine or the local network."
+                )
+            }));
+        }
+
+        validate_watchdogs(&cfg.watchdogs)?;
+        validate_proxy(&cfg.proxy)?;
+
+        // Reject `[redaction.patterns]` that do not compile now rather than
+        // when the first session starts.
+        Redactor::new(&cfg.redaction).map_err(|e| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("invalid pattern in [redaction.patterns]: {e}"),
+            )
+        })?;
+
+        let mut hooks = cfg.hooks.clone();
+        if let Some(profile_hooks) = config_profile.hooks {
+            hooks.commands.extend(profile_hooks.commands);
+            let sinks = profile_hooks.sinks;
+            if sinks.slack.is_some() {
+                hooks.sinks.slack = sinks.slack;
+            }
+            if sinks.discord.is_some() {
+                hooks.sinks.discord = sinks.discord;
+            }
+        }
 
         // Ensure that every field of ConfigRequirements is applied to the final
         // Config.
         let ConfigRequirements {
             approval_policy: mut constrained_approval_policy,
             sandbox_policy: mut constrained_sandbox_policy,
+            banned_tools,
+            required_hooks,
         } = requirements;
+        validate_hook_filters(&hooks, &required_hooks)?;
 
         constrained_approval_policy
             .set(approval_policy)
@@ -1336,6 +1756,17 @@ impl Config {
             .set(sandbox_policy)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{e}")))?;
 
+        // Asking to see the raw reasoning implies capturing it.
+        let mut reasoning = cfg.reasoning;
+        if cfg
+            .show_raw_agent_reasoning
+            .or(show_raw_agent_reasoning)
+            .unwrap_or(false)
+            && reasoning.capture == ReasoningRetention::Summarize
+        {
+            reasoning.capture = ReasoningRetention::Keep;
+        }
+
         let config = Self {
             model,
             review_model,
@@ -1357,7 +1788,7 @@ impl Config {
             // The config.toml omits "_mode" because it's a config file. However, "_mode"
             // is important in code to differentiate the mode from the store implementation.
             cli_auth_credentials_store_mode: cfg.cli_auth_credentials_store.unwrap_or_default(),
-            mcp_servers: cfg.mcp_servers,
+            mcp_servers,
             // The config.toml omits "_mode" because it's a config file. However, "_mode"
             // is important in code to differentiate the mode from the store implementation.
             mcp_oauth_credentials_store_mode: cfg.mcp_oauth_credentials_store.unwrap_or_default(),
@@ -1384,10 +1815,7 @@ impl Config {
             codex_linux_sandbox_exe,
 
             hide_agent_reasoning: cfg.hide_agent_reasoning.unwrap_or(false),
-            show_raw_agent_reasoning: cfg
-                .show_raw_agent_reasoning
-                .or(show_raw_agent_reasoning)
-                .unwrap_or(false),
+            show_raw_agent_reasoning: reasoning.capture == ReasoningRetention::Keep,
             model_reasoning_effort: config_profile
                 .model_reasoning_effort
                 .or(cfg.model_reasoning_effort),
@@ -1413,24 +1841,46 @@ impl Config {
             windows_wsl_setup_acknowledged: cfg.windows_wsl_setup_acknowledged.unwrap_or(false),
             notices: cfg.notice.unwrap_or_default(),
             check_for_update_on_startup,
+            update_channel: cfg.update_channel.unwrap_or_default(),
+            offline,
             disable_paste_burst: cfg.disable_paste_burst.unwrap_or(false),
-            analytics_enabled: config_profile
-                .analytics
-                .as_ref()
-                .and_then(|a| a.enabled)
-                .or(cfg.analytics.as_ref().and_then(|a| a.enabled)),
-            feedback_enabled: cfg
-                .feedback
-                .as_ref()
-                .and_then(|feedback| feedback.enabled)
-                .unwrap_or(true),
+            analytics_enabled: if offline {
+                Some(false)
+            } else {
+                config_profile
+                    .analytics
+                    .as_ref()
+                    .and_then(|a| a.enabled)
+                    .or(cfg.analytics.as_ref().and_then(|a| a.enabled))
+            },
+            feedback_enabled: !offline
+                && cfg
+                    .feedback
+                    .as_ref()
+                    .and_then(|feedback| feedback.enabled)
+                    .unwrap_or(true),
             tui_notifications: cfg
                 .tui
                 .as_ref()
                 .map(|t| t.notifications.clone())
                 .unwrap_or_default(),
-            animations: cfg.tui.as_ref().map(|t| t.animations).unwrap_or(true),
+            animations: cfg
+                .tui
+                .as_ref()
+                .map(|t| t.animations && !t.screen_reader)
+                .unwrap_or(true),
+            tui_screen_reader: cfg.tui.as_ref().is_some_and(|t| t.screen_reader),
             show_tooltips: cfg.tui.as_ref().map(|t| t.show_tooltips).unwrap_or(true),
+            tui_notification_backend: cfg
+                .tui
+                .as_ref()
+                .map(|t| t.notification_backend)
+                .unwrap_or_default(),
+            tui_queued_message_delivery: cfg
+                .tui
+                .as_ref()
+                .map(|t| t.queued_message_delivery)
+                .unwrap_or_default(),
             tui_scroll_events_per_tick: cfg.tui.as_ref().and_then(|t| t.scroll_events_per_tick),
             tui_scroll_wheel_lines: cfg.tui.as_ref().and_then(|t| t.scroll_wheel_lines),
             tui_scroll_trackpad_lines: cfg.tui.as_ref().and_then(|t| t.scroll_trackpad_lines),
@@ -1457,22 +1907,83 @@ impl Config {
                 .as_ref()
                 .map(|t| t.alternate_screen)
                 .unwrap_or_default(),
+            tui_keybindings: cfg
+                .tui
+                .as_ref()
+                .map(|t| t.keybindings.clone())
+                .unwrap_or_default(),
+            tui_theme: cfg.tui.as_ref().and_then(|t| t.theme.clone()),
+            tui_themes: cfg
+                .tui
+                .as_ref()
+                .map(|t| t.themes.clone())
+                .unwrap_or_default(),
+            tui_status_export: cfg
+                .tui
+                .as_ref()
+                .map(|t| t.status_export.clone())
+                .unwrap_or_default(),
+            user_commands: cfg.commands.clone(),
+            hooks,
+            required_hooks,
+            banned_tools,
+            pull_request: cfg.pull_request.clone(),
+            redaction: cfg.redaction.clone(),
+            event_socket_approvals: cfg.event_socket_approvals.unwrap_or(false),
+            injection_detection: cfg.injection_detection.clone(),
+            guardrails: cfg.guardrails.clone(),
+            watchdogs: cfg.watchdogs.clone(),
+            model_pricing: cfg.model_pricing.clone(),
+            persistence: cfg.persistence,
+            proxy: cfg.proxy.clone(),
+            mock_provider: cfg.mock_provider.clone(),
+            verify: VerifyConfig {
+                command: verify_command.or_else(|| cfg.verify.command.clone()),
+                ..cfg.verify.clone()
+            },
+            patch_validation: cfg.patch_validation,
+            exec_backend,
+            context_budget: cfg.context_budget,
+            reasoning,
+            approval_rules: cfg.approval_rules.clone(),
+            session_labels,
             otel: {
                 let t: OtelConfigToml = cfg.otel.unwrap_or_default();
                 let log_user_prompt = t.log_user_prompt.unwrap_or(false);
                 let environment = t
                     .environment
                     .unwrap_or(DEFAULT_OTEL_ENVIRONMENT.to_string());
-                let exporter = t.exporter.unwrap_or(OtelExporterKind::None);
-                let trace_exporter = t.trace_exporter.unwrap_or_else(|| exporter.clone());
+                let explicit_trace_exporter = t.trace_exporter.is_some();
+                let explicit_metrics_exporter = t.metrics_exporter.is_some();
+                let mut exporter = t.exporter.unwrap_or(OtelExporterKind::None);
+                let mut trace_exporter = t.trace_exporter.unwrap_or_else(|| exporter.clone());
+                let mut metrics_exporter = t.metrics_exporter.unwrap_or(OtelExporterKind::Statsig);
+                if offline {
+                    let warnings = &mut offline_warnings;
+                    exporter = offline::offline_exporter(exporter, "exporter", true, warnings);
+                    trace_exporter = offline::offline_exporter(
+                        trace_exporter,
+                        "trace_exporter",
+                        explicit_trace_exporter,
+                        warnings,
+                    );
+                    metrics_exporter = offline::offline_exporter(
+                        metrics_exporter,
+                        "metrics_exporter",
+                        explicit_metrics_exporter,
+                        warnings,
+                    );
+                }
                 OtelConfig {
                     log_user_prompt,
                     environment,
                     exporter,
                     trace_exporter,
-                    metrics_exporter: OtelExporterKind::Statsig,
+                    metrics_exporter,
+                    prometheus_listen_addr: t.prometheus_listen_addr,
                 }
             },
+            config_warnings: offline_warnings,
         };
         Ok(config)
     }
@@ -1572,6 +2083,87 @@ pub fn find_codex_home() -> std::io::Result<PathBuf> {
     Ok(p)
 }
 
+/// `deny` only makes sense for a request that can be denied.
+fn validate_proxy(proxy: &ProxyConfig) -> std::io::Result<()> {
+    let mut urls = vec![
+        ("[proxy] url", proxy.url.as_deref()),
+        (
+            "[proxy.model_provider] url",
+            proxy.model_provider.url.as_deref(),
+        ),
+        ("[proxy.mcp] url", proxy.mcp.url.as_deref()),
+    ];
+    urls.extend(
+        proxy
+            .rules
+            .iter()
+            .filter(|rule| rule.proxy != DIRECT_PROXY)
+            .map(|rule| ("[[proxy.rules]] proxy", Some(rule.proxy.as_str()))),
+    );
+    for (setting, url) in urls {
+        let Some(url) = url else {
+            continue;
+        };
+        let problem = match url::Url::parse(url) {
+            Ok(parsed) if matches!(parsed.scheme(), "http" | "https" | "socks5" | "socks5h") => {
+                continue;
+            }
+            _ => "expected an http://, https://, socks5:// or socks5h:// URL",
+        };
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("{setting} `{url}`: {problem}"),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_watchdogs(watchdogs: &WatchdogsConfig) -> std::io::Result<()> {
+    for (name, watchdog) in [("turn", &watchdogs.turn), ("session", &watchdogs.session)] {
+        if watchdog.is_some_and(|watchdog| watchdog.action == WatchdogAction::Deny) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("[watchdogs.{name}] action `deny` is only valid for [watchdogs.approval]"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Rejects hook filters that do not compile now rather than when the first
+/// session starts.
+fn validate_hook_filters(
+    hooks: &HooksConfig,
+    required_hooks: &BTreeMap<String, CommandHookConfig>,
+) -> std::io::Result<()> {
+    let commands = hooks
+        .commands
+        .iter()
+        .map(|(name, hook)| (format!("hooks.commands.{name}"), &hook.filter))
+        .chain(
+            required_hooks
+                .iter()
+                .map(|(name, hook)| (format!("required_hooks.{name}"), &hook.filter)),
+        );
+    let sinks = [
+        ("slack", &hooks.sinks.slack),
+        ("discord", &hooks.sinks.discord),
+    ]
+    .into_iter()
+    .filter_map(|(name, sink)| Some((format!("hooks.sinks.{name}"), &sink.as_ref()?.filter)));
+    for (table, filter) in commands.chain(sinks) {
+        if let Some(filter) = filter
+            && let Err(err) = EventExpr::parse(filter)
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("[{table}] {err}"),
+            ));
+        }
+    }
+    Ok(())
+}
+
 /// Returns the path to the folder where Codex logs are stored. Does not verify
 /// that the directory exists.
 pub fn log_dir(cfg: &Config) -> std::io::Result<PathBuf> {
@@ -1644,7 +2236,10 @@ persistence = "none"
             tui,
             Tui {
                 notifications: Notifications::Enabled(true),
+                notification_backend: NotificationBackend::Auto,
+                queued_message_delivery: QueuedMessageDelivery::NextTurn,
                 animations: true,
+                screen_reader: false,
                 show_tooltips: true,
                 scroll_events_per_tick: None,
                 scroll_wheel_lines: None,
@@ -1656,10 +2251,29 @@ persistence = "none"
                 scroll_wheel_like_max_duration_ms: None,
                 scroll_invert: false,
                 alternate_screen: AltScreenMode::Auto,
+                keybindings: TuiKeybindings::default(),
+                theme: None,
+                themes: BTreeMap::new(),
+                status_export: TuiStatusExport::default(),
             }
         );
     }
 
+    #[test]
+    fn tui_notification_backend_parses_kebab_case_names() {
+        let cfg = r#"
+[tui]
+notification_backend = "notify-send"
+"#;
+
+        let parsed = toml::from_str::<ConfigToml>(cfg).expect("config should parse");
+
+        assert_eq!(
+            parsed.tui.expect("tui section").notification_backend,
+            NotificationBackend::NotifySend
+        );
+    }
+
     #[test]
     fn test_sandbox_config_parsing() {
         let sandbox_full_access = r#"
@@ -1802,6 +2416,168 @@ trust_level = "trusted"
         }
     }
 
+    #[test]
+    fn deny_is_rejected_outside_the_approval_watchdog() -> std::io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+[watchdogs.approval]
+timeout_secs = 600
+action = "deny"
+
+[watchdogs.turn]
+timeout_secs = 1800
+action = "deny"
+"#,
+        )
+        .expect("valid toml");
+
+        let err = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )
+        .expect_err("deny on the turn watchdog should be rejected");
+
+        assert_eq!(
+            err.to_string(),
+            "[watchdogs.turn] action `deny` is only valid for [watchdogs.approval]"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn proxy_urls_must_be_http_or_socks5() -> std::io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+[proxy]
+url = "http://proxy.corp:8080"
+
+[proxy.mcp]
+url = "socks5h://127.0.0.1:1080"
+
+[[proxy.rules]]
+hosts = ["*.internal.example"]
+proxy = "direct"
+
+[[proxy.rules]]
+hosts = ["api.partner.example"]
+proxy = "ftp://127.0.0.1:2121"
+"#,
+        )
+        .expect("valid toml");
+
+        let err = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )
+        .expect_err("ftp proxies should be rejected");
+
+        assert_eq!(
+            err.to_string(),
+            "[[proxy.rules]] proxy `ftp://127.0.0.1:2121`: expected an http://, https://, socks5:// or socks5h:// URL"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn raw_reasoning_display_implies_keeping_reasoning() -> std::io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+show_raw_agent_reasoning = true
+
+[reasoning]
+persist = "discard"
+"#,
+        )
+        .expect("valid toml");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )?;
+
+        assert_eq!(
+            config.reasoning,
+            ReasoningConfig {
+                capture: ReasoningRetention::Keep,
+                persist: ReasoningRetention::Discard,
+            }
+        );
+        assert!(config.show_raw_agent_reasoning);
+
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+show_raw_agent_reasoning = true
+
+[reasoning]
+capture = "discard"
+"#,
+        )
+        .expect("valid toml");
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )?;
+
+        assert_eq!(config.reasoning.capture, ReasoningRetention::Discard);
+        assert_eq!(config.reasoning.persist, ReasoningRetention::Keep);
+        assert!(!config.show_raw_agent_reasoning);
+        Ok(())
+    }
+
+    #[test]
+    fn offline_turns_off_network_features() -> std::io::Result<()> {
+        let temp_dir = TempDir::new()?;
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+offline = true
+bandwidth_limit = 65536
+
+[features]
+web_search_request = true
+
+[mcp_servers.docs]
+url = "https://docs.example.com/mcp"
+
+[mcp_servers.local]
+url = "http://127.0.0.1:8931/mcp"
+"#,
+        )
+        .expect("valid toml");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            temp_dir.path().to_path_buf(),
+        )?;
+
+        assert!(config.offline);
+        assert!(!config.tools_web_search_request);
+        assert!(!config.check_for_update_on_startup);
+        assert!(!config.feedback_enabled);
+        assert_eq!(config.analytics_enabled, Some(false));
+        assert_eq!(config.otel.metrics_exporter, OtelExporterKind::None);
+        assert!(!config.mcp_servers["docs"].enabled);
+        assert!(config.mcp_servers["local"].enabled);
+        assert_eq!(config.model_provider.bandwidth_limit, Some(65536));
+        assert_eq!(
+            config.config_warnings,
+            vec![
+                "`web_search_request` is turned off because `offline = true`: web search needs the network."
+                    .to_string(),
+                "MCP server `docs` is turned off because `offline = true`: its URL is not on this machine or the local network."
+                    .to_string(),
+            ]
+        );
+        Ok(())
+    }
+
     #[test]
     fn add_dir_override_extends_workspace_writable_roots() -> std::io::Result<()> {
         let temp_dir = TempDir::new()?;
@@ -1854,7 +2630,7 @@ trust_level = "trusted"
     }
 
     #[test]
-    fn config_defaults_to_file_cli_auth_store_mode() -> std::io::Result<()> {
+    fn config_defaults_to_auto_cli_auth_store_mode() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
         let cfg = ConfigToml::default();
 
@@ -1866,7 +2642,7 @@ trust_level = "trusted"
 
         assert_eq!(
             config.cli_auth_credentials_store_mode,
-            AuthCredentialsStoreMode::File,
+            AuthCredentialsStoreMode::Auto,
         );
 
         Ok(())
@@ -2038,6 +2814,92 @@ trust_level = "trusted"
         Ok(())
     }
 
+    fn project_profile_config(cwd: &Path, project: &str) -> ConfigToml {
+        let toml = format!(
+            r#"
+profile = "loose"
+
+[profiles.loose]
+approval_policy = "never"
+
+[profiles.strict]
+approval_policy = "untrusted"
+
+[hooks.commands.notify]
+on = ["task_complete"]
+command = ["notify-send"]
+
+[profiles.strict.hooks.commands.audit]
+on = ["*"]
+command = ["audit-log"]
+
+[projects."{}"]
+{project}
+"#,
+            cwd.display()
+        );
+        toml::from_str(&toml).expect("TOML deserialization should succeed")
+    }
+
+    fn load_in(cwd: &Path, cfg: ConfigToml, profile: Option<&str>) -> std::io::Result<Config> {
+        let codex_home = TempDir::new()?;
+        Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides {
+                cwd: Some(cwd.to_path_buf()),
+                config_profile: profile.map(str::to_string),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )
+    }
+
+    #[test]
+    fn project_profile_mapping_selects_profile_for_cwd() -> std::io::Result<()> {
+        let project = TempDir::new()?;
+        let cwd = project.path().canonicalize()?;
+        let cfg = project_profile_config(&cwd, r#"profile = "strict""#);
+
+        let config = load_in(&cwd, cfg.clone(), None)?;
+        assert_eq!(config.active_profile.as_deref(), Some("strict"));
+        assert_eq!(config.approval_policy.get(), &AskForApproval::UnlessTrusted);
+        assert_eq!(
+            config.hooks.commands.keys().collect::<Vec<_>>(),
+            vec!["audit", "notify"]
+        );
+
+        let config = load_in(&cwd, cfg, Some("loose"))?;
+        assert_eq!(config.active_profile.as_deref(), Some("loose"));
+        assert_eq!(
+            config.hooks.commands.keys().collect::<Vec<_>>(),
+            vec!["notify"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn profile_marker_is_honored_only_in_trusted_projects() -> std::io::Result<()> {
+        let project = TempDir::new()?;
+        let cwd = project.path().canonicalize()?;
+        std::fs::create_dir(cwd.join(".codex"))?;
+        std::fs::write(cwd.join(PROJECT_PROFILE_MARKER), "strict\n")?;
+
+        let trusted = project_profile_config(&cwd, r#"trust_level = "trusted""#);
+        let config = load_in(&cwd, trusted.clone(), None)?;
+        assert_eq!(config.active_profile.as_deref(), Some("strict"));
+
+        let untrusted = project_profile_config(&cwd, r#"trust_level = "untrusted""#);
+        let config = load_in(&cwd, untrusted, None)?;
+        assert_eq!(config.active_profile.as_deref(), Some("loose"));
+
+        std::fs::write(cwd.join(PROJECT_PROFILE_MARKER), "missing\n")?;
+        let config = load_in(&cwd, trusted, None)?;
+        assert_eq!(config.active_profile.as_deref(), Some("loose"));
+
+        Ok(())
+    }
+
     #[test]
     fn feature_table_overrides_legacy_flags() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -2182,6 +3044,7 @@ trust_level = "trusted"
                 tool_timeout_sec: Some(Duration::from_secs(5)),
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         );
 
@@ -2335,6 +3198,7 @@ bearer_token = "secret"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
 
@@ -2403,6 +3267,7 @@ ZIG_VAR = "3"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
 
@@ -2451,6 +3316,7 @@ ZIG_VAR = "3"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
 
@@ -2497,6 +3363,7 @@ ZIG_VAR = "3"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
 
@@ -2559,6 +3426,7 @@ startup_timeout_sec = 2.0
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
         apply_blocking(
@@ -2633,6 +3501,7 @@ X-Auth = "DOCS_AUTH"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
 
@@ -2660,6 +3529,7 @@ X-Auth = "DOCS_AUTH"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         );
         apply_blocking(
@@ -2725,6 +3595,7 @@ url = "https://example.com/mcp"
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    sampling: None,
                 },
             ),
             (
@@ -2742,6 +3613,7 @@ url = "https://example.com/mcp"
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    sampling: None,
                 },
             ),
         ]);
@@ -2822,6 +3694,7 @@ url = "https://example.com/mcp"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
         )]);
 
@@ -2864,6 +3737,7 @@ url = "https://example.com/mcp"
                 tool_timeout_sec: None,
                 enabled_tools: Some(vec!["allowed".to_string()]),
                 disabled_tools: Some(vec!["blocked".to_string()]),
+                sampling: None,
             },
         )]);
 
@@ -3174,7 +4048,9 @@ model_verbosity = "high"
             request_max_retries: Some(4),
             stream_max_retries: Some(10),
             stream_idle_timeout_ms: Some(300_000),
+            bandwidth_limit: None,
             requires_openai_auth: false,
+            tool_format: Default::default(),
         };
         let model_provider_map = {
             let mut model_provider_map = built_in_model_providers();
@@ -3272,13 +4148,18 @@ model_verbosity = "high"
                 ghost_snapshot: GhostSnapshotConfig::default(),
                 features: Features::with_defaults(),
                 active_profile: Some("o3".to_string()),
-                active_project: ProjectConfig { trust_level: None },
+                active_project: ProjectConfig::default(),
                 windows_wsl_setup_acknowledged: false,
                 notices: Default::default(),
                 check_for_update_on_startup: true,
+                update_channel: UpdateChannel::default(),
+                offline: false,
                 disable_paste_burst: false,
                 tui_notifications: Default::default(),
+                tui_notification_backend: Default::default(),
+                tui_queued_message_delivery: Default::default(),
                 animations: true,
+                tui_screen_reader: false,
                 show_tooltips: true,
                 analytics_enabled: Some(true),
                 feedback_enabled: true,
@@ -3292,6 +4173,32 @@ model_verbosity = "high"
                 tui_scroll_wheel_like_max_duration_ms: None,
                 tui_scroll_invert: false,
                 tui_alternate_screen: AltScreenMode::Auto,
+                tui_keybindings: TuiKeybindings::default(),
+                tui_theme: None,
+                tui_themes: BTreeMap::new(),
+                tui_status_export: TuiStatusExport::default(),
+                user_commands: BTreeMap::new(),
+                hooks: HooksConfig::default(),
+                required_hooks: BTreeMap::new(),
+                banned_tools: Vec::new(),
+                pull_request: PullRequestConfig::default(),
+                redaction: RedactionConfig::default(),
+                event_socket_approvals: false,
+                injection_detection: InjectionDetectionConfig::default(),
+                guardrails: GuardrailsConfig::default(),
+                watchdogs: WatchdogsConfig::default(),
+                model_pricing: BTreeMap::new(),
+                persistence: PersistenceConfig::default(),
+                proxy: ProxyConfig::default(),
+                mock_provider: MockProviderConfig::default(),
+                verify: VerifyConfig::default(),
+                patch_validation: PatchValidationConfig::default(),
+                exec_backend: ExecBackendConfig::default(),
+                context_budget: ContextBudgetConfig::default(),
+                reasoning: ReasoningConfig::default(),
+                approval_rules: Vec::new(),
+                session_labels: Vec::new(),
+                config_warnings: Vec::new(),
                 otel: OtelConfig::default(),
             },
             o3_profile_config
@@ -3358,13 +4265,18 @@ model_verbosity = "high"
             ghost_snapshot: GhostSnapshotConfig::default(),
             features: Features::with_defaults(),
             active_profile: Some("gpt3".to_string()),
-            active_project: ProjectConfig { trust_level: None },
+            active_project: ProjectConfig::default(),
             windows_wsl_setup_acknowledged: false,
             notices: Default::default(),
             check_for_update_on_startup: true,
+            update_channel: UpdateChannel::default(),
+            offline: false,
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_notification_backend: Default::default(),
+            tui_queued_message_delivery: Default::default(),
             animations: true,
+            tui_screen_reader: false,
             show_tooltips: true,
             analytics_enabled: Some(true),
             feedback_enabled: true,
@@ -3378,6 +4290,32 @@ model_verbosity = "high"
             tui_scroll_wheel_like_max_duration_ms: None,
             tui_scroll_invert: false,
             tui_alternate_screen: AltScreenMode::Auto,
+            tui_keybindings: TuiKeybindings::default(),
+            tui_theme: None,
+            tui_themes: BTreeMap::new(),
+            tui_status_export: TuiStatusExport::default(),
+            user_commands: BTreeMap::new(),
+            hooks: HooksConfig::default(),
+            required_hooks: BTreeMap::new(),
+            banned_tools: Vec::new(),
+            pull_request: PullRequestConfig::default(),
+            redaction: RedactionConfig::default(),
+            event_socket_approvals: false,
+            injection_detection: InjectionDetectionConfig::default(),
+            guardrails: GuardrailsConfig::default(),
+            watchdogs: WatchdogsConfig::default(),
+            model_pricing: BTreeMap::new(),
+            persistence: PersistenceConfig::default(),
+            proxy: ProxyConfig::default(),
+            mock_provider: MockProviderConfig::default(),
+            verify: VerifyConfig::default(),
+            patch_validation: PatchValidationConfig::default(),
+            exec_backend: ExecBackendConfig::default(),
+            context_budget: ContextBudgetConfig::default(),
+            reasoning: ReasoningConfig::default(),
+            approval_rules: Vec::new(),
+            session_labels: Vec::new(),
+            config_warnings: Vec::new(),
             otel: OtelConfig::default(),
         };
 
@@ -3459,13 +4397,18 @@ model_verbosity = "high"
             ghost_snapshot: GhostSnapshotConfig::default(),
             features: Features::with_defaults(),
             active_profile: Some("zdr".to_string()),
-            active_project: ProjectConfig { trust_level: None },
+            active_project: ProjectConfig::default(),
             windows_wsl_setup_acknowledged: false,
             notices: Default::default(),
             check_for_update_on_startup: true,
+            update_channel: UpdateChannel::default(),
+            offline: false,
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_notification_backend: Default::default(),
+            tui_queued_message_delivery: Default::default(),
             animations: true,
+            tui_screen_reader: false,
             show_tooltips: true,
             analytics_enabled: Some(false),
             feedback_enabled: true,
@@ -3479,6 +4422,32 @@ model_verbosity = "high"
             tui_scroll_wheel_like_max_duration_ms: None,
             tui_scroll_invert: false,
             tui_alternate_screen: AltScreenMode::Auto,
+            tui_keybindings: TuiKeybindings::default(),
+            tui_theme: None,
+            tui_themes: BTreeMap::new(),
+            tui_status_export: TuiStatusExport::default(),
+            user_commands: BTreeMap::new(),
+            hooks: HooksConfig::default(),
+            required_hooks: BTreeMap::new(),
+            banned_tools: Vec::new(),
+            pull_request: PullRequestConfig::default(),
+            redaction: RedactionConfig::default(),
+            event_socket_approvals: false,
+            injection_detection: InjectionDetectionConfig::default(),
+            guardrails: GuardrailsConfig::default(),
+            watchdogs: WatchdogsConfig::default(),
+            model_pricing: BTreeMap::new(),
+            persistence: PersistenceConfig::default(),
+            proxy: ProxyConfig::default(),
+            mock_provider: MockProviderConfig::default(),
+            verify: VerifyConfig::default(),
+            patch_validation: PatchValidationConfig::default(),
+            exec_backend: ExecBackendConfig::default(),
+            context_budget: ContextBudgetConfig::default(),
+            reasoning: ReasoningConfig::default(),
+            approval_rules: Vec::new(),
+            session_labels: Vec::new(),
+            config_warnings: Vec::new(),
             otel: OtelConfig::default(),
         };
 
@@ -3546,13 +4515,18 @@ model_verbosity = "high"
             ghost_snapshot: GhostSnapshotConfig::default(),
             features: Features::with_defaults(),
             active_profile: Some("gpt5".to_string()),
-            active_project: ProjectConfig { trust_level: None },
+            active_project: ProjectConfig::default(),
             windows_wsl_setup_acknowledged: false,
             notices: Default::default(),
             check_for_update_on_startup: true,
+            update_channel: UpdateChannel::default(),
+            offline: false,
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_notification_backend: Default::default(),
+            tui_queued_message_delivery: Default::default(),
             animations: true,
+            tui_screen_reader: false,
             show_tooltips: true,
             analytics_enabled: Some(true),
             feedback_enabled: true,
@@ -3566,6 +4540,32 @@ model_verbosity = "high"
             tui_scroll_wheel_like_max_duration_ms: None,
             tui_scroll_invert: false,
             tui_alternate_screen: AltScreenMode::Auto,
+            tui_keybindings: TuiKeybindings::default(),
+            tui_theme: None,
+            tui_themes: BTreeMap::new(),
+            tui_status_export: TuiStatusExport::default(),
+            user_commands: BTreeMap::new(),
+            hooks: HooksConfig::default(),
+            required_hooks: BTreeMap::new(),
+            banned_tools: Vec::new(),
+            pull_request: PullRequestConfig::default(),
+            redaction: RedactionConfig::default(),
+            event_socket_approvals: false,
+            injection_detection: InjectionDetectionConfig::default(),
+            guardrails: GuardrailsConfig::default(),
+            watchdogs: WatchdogsConfig::default(),
+            model_pricing: BTreeMap::new(),
+            persistence: PersistenceConfig::default(),
+            proxy: ProxyConfig::default(),
+            mock_provider: MockProviderConfig::default(),
+            verify: VerifyConfig::default(),
+            patch_validation: PatchValidationConfig::default(),
+            exec_backend: ExecBackendConfig::default(),
+            context_budget: ContextBudgetConfig::default(),
+            reasoning: ReasoningConfig::default(),
+            approval_rules: Vec::new(),
+            session_labels: Vec::new(),
+            config_warnings: Vec::new(),
             otel: OtelConfig::default(),
         };
 
@@ -3753,6 +4753,39 @@ trust_level = "untrusted"
         Ok(())
     }
 
+    #[test]
+    fn read_only_and_denied_projects_get_read_only_sandbox() -> anyhow::Result<()> {
+        for (level, approval) in [
+            ("read-only", AskForApproval::OnRequest),
+            ("deny", AskForApproval::UnlessTrusted),
+        ] {
+            let codex_home = TempDir::new()?;
+            let project = TempDir::new()?;
+            let cfg = toml::from_str::<ConfigToml>(&format!(
+                "[projects.{}]\ntrust_level = \"{level}\"\n",
+                toml_edit::Value::from(project.path().to_string_lossy().to_string())
+            ))?;
+
+            let config = Config::load_from_base_config_with_overrides(
+                cfg,
+                ConfigOverrides {
+                    cwd: Some(project.path().to_path_buf()),
+                    ..Default::default()
+                },
+                codex_home.path().to_path_buf(),
+            )?;
+
+            assert_eq!(
+                config.sandbox_policy.get(),
+                &SandboxPolicy::ReadOnly,
+                "{level}"
+            );
+            assert_eq!(config.approval_policy.value(), approval, "{level}");
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_resolve_oss_provider_explicit_override() {
         let config_toml = ConfigToml::default();
@@ -3844,6 +4877,7 @@ trust_level = "untrusted"
                     test_path.to_string_lossy().to_string(),
                     ProjectConfig {
                         trust_level: Some(TrustLevel::Untrusted),
+                        ..Default::default()
                     },
                 )])),
                 ..Default::default()
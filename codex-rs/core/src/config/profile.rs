@@ -1,4 +1,5 @@
 use codex_utils_absolute_path::AbsolutePathBuf;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 
@@ -10,7 +11,7 @@ use codex_protocol::openai_models::ReasoningEffort;
 
 /// Collection of common configuration options that a user can define as a unit
 /// in `config.toml`.
-#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ConfigProfile {
     pub model: Option<String>,
     /// The key in the `model_providers` map identifying the
@@ -34,6 +35,10 @@ pub struct ConfigProfile {
     #[serde(default)]
     pub features: Option<crate::features::FeaturesToml>,
     pub oss_provider: Option<String>,
+    /// Hooks added to (or replacing, by name) the top-level `[hooks]`.
+    pub hooks: Option<crate::config::types::HooksConfig>,
+    /// Replaces the top-level `[exec_backend]`.
+    pub exec_backend: Option<crate::config::types::ExecBackendConfig>,
 }
 
 impl From<ConfigProfile> for codex_app_server_protocol::Profile {
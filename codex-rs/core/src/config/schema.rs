@@ -0,0 +1,680 @@
+//! JSON Schema for `config.toml` and the diagnostics derived from it.
+//!
+//! The schema is generated from [`ConfigToml`]. Validation walks a parsed
+//! document alongside the schema so that unknown keys, deprecated options and
+//! invalid enum values are reported with the line and column they appear on,
+//! then falls back to the deserializer for any remaining type errors.
+
+use std::fmt;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use codex_utils_absolute_path::AbsolutePathBufGuard;
+use schemars::r#gen::SchemaSettings;
+use schemars::schema::RootSchema;
+use schemars::schema::Schema;
+use schemars::schema::SchemaObject;
+use schemars::schema::SingleOrVec;
+use toml_edit::Item;
+use toml_edit::TableLike;
+use toml_edit::Value;
+
+use super::ConfigToml;
+use super::interpolate::contains_reference;
+use crate::config_loader::ConfigLayerEntry;
+use crate::config_loader::ConfigLayerStack;
+use crate::config_loader::ConfigLayerStackOrdering;
+use crate::features::Feature;
+use crate::features::legacy_feature_aliases;
+use crate::model_provider_info::CHAT_WIRE_API_DEPRECATION_SUMMARY;
+
+static CONFIG_SCHEMA: LazyLock<RootSchema> = LazyLock::new(|| {
+    SchemaSettings::draft07()
+        .with(|settings| settings.option_add_null_type = false)
+        .into_generator()
+        .into_root_schema_for::<ConfigToml>()
+});
+
+static ROOT_SCHEMA: LazyLock<Schema> =
+    LazyLock::new(|| Schema::Object(CONFIG_SCHEMA.schema.clone()));
+
+/// Top-level and per-profile keys superseded by `[features]` entries.
+const LEGACY_TOGGLES: &[(&str, Feature)] = &[
+    ("experimental_use_unified_exec_tool", Feature::UnifiedExec),
+    (
+        "experimental_use_freeform_apply_patch",
+        Feature::ApplyPatchFreeform,
+    ),
+    ("include_apply_patch_tool", Feature::ApplyPatchFreeform),
+    ("tools_web_search", Feature::WebSearchRequest),
+];
+
+/// Returns the JSON Schema describing `config.toml`.
+pub fn config_schema() -> &'static RootSchema {
+    &CONFIG_SCHEMA
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticSeverity {
+    /// The config cannot be loaded.
+    Error,
+    /// The config loads, but part of it is ignored or deprecated.
+    Warning,
+}
+
+impl fmt::Display for DiagnosticSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => f.write_str("error"),
+            Self::Warning => f.write_str("warning"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    /// The file is not valid TOML.
+    Syntax,
+    /// A value has the wrong type or shape.
+    InvalidType,
+    /// A string is not one of the accepted values.
+    InvalidValue,
+    /// A key Codex does not recognize; it is ignored.
+    UnknownKey,
+    /// A key or value that still works but has a replacement.
+    Deprecated,
+}
+
+impl DiagnosticKind {
+    pub fn severity(self) -> DiagnosticSeverity {
+        match self {
+            Self::Syntax | Self::InvalidType | Self::InvalidValue => DiagnosticSeverity::Error,
+            Self::UnknownKey | Self::Deprecated => DiagnosticSeverity::Warning,
+        }
+    }
+}
+
+/// A problem found in a config file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigDiagnostic {
+    pub kind: DiagnosticKind,
+    /// File the problem was found in, when validating a file.
+    pub file: Option<PathBuf>,
+    /// Dotted path of the offending key, e.g. `profiles.work.modle`. Empty
+    /// when the problem is not tied to a key.
+    pub key: String,
+    /// 1-based line of the problem.
+    pub line: usize,
+    /// 1-based column of the problem.
+    pub column: usize,
+    pub message: String,
+}
+
+impl ConfigDiagnostic {
+    pub fn severity(&self) -> DiagnosticSeverity {
+        self.kind.severity()
+    }
+}
+
+impl fmt::Display for ConfigDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file.display())?;
+        }
+        write!(
+            f,
+            "{}:{}: {}: {}",
+            self.line,
+            self.column,
+            self.severity(),
+            self.message
+        )
+    }
+}
+
+/// Validates the contents of a `config.toml`. Relative paths are resolved
+/// against `base_dir`, as they are when the config is loaded.
+pub fn validate_config_toml(contents: &str, base_dir: &Path) -> Vec<ConfigDiagnostic> {
+    let document = match toml_edit::Document::parse(contents) {
+        Ok(document) => document,
+        Err(err) => {
+            let (line, column) = line_column(contents, err.span());
+            return vec![ConfigDiagnostic {
+                kind: DiagnosticKind::Syntax,
+                file: None,
+                key: String::new(),
+                line,
+                column,
+                message: err.message().to_string(),
+            }];
+        }
+    };
+
+    let schema = config_schema();
+    let mut validator = Validator {
+        source: contents,
+        schema,
+        diagnostics: Vec::new(),
+    };
+    validator.check_table(document.as_table(), &[&ROOT_SCHEMA], &mut Vec::new());
+
+    let mut diagnostics = validator.diagnostics;
+    // Type errors the schema walk does not cover come from the deserializer,
+    // which stops at the first one.
+    if !diagnostics
+        .iter()
+        .any(|d| d.severity() == DiagnosticSeverity::Error)
+    {
+        let _guard = AbsolutePathBufGuard::new(base_dir);
+        if let Err(err) = toml::from_str::<ConfigToml>(contents) {
+            let (line, column) = line_column(contents, err.span());
+            diagnostics.push(ConfigDiagnostic {
+                kind: DiagnosticKind::InvalidType,
+                file: None,
+                key: String::new(),
+                line,
+                column,
+                message: err.message().trim().to_string(),
+            });
+        }
+    }
+    diagnostics.sort_by_key(|d| (d.line, d.column));
+    diagnostics
+}
+
+/// Reads and validates the `config.toml` at `path`. A missing file has no
+/// diagnostics.
+pub fn validate_config_file(path: &Path) -> std::io::Result<Vec<ConfigDiagnostic>> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let base_dir = path.parent().unwrap_or(path);
+    let mut diagnostics = validate_config_toml(&contents, base_dir);
+    for diagnostic in &mut diagnostics {
+        diagnostic.file = Some(path.to_path_buf());
+    }
+    Ok(diagnostics)
+}
+
+/// Validates every file-backed layer of `stack`, lowest precedence first.
+/// Unreadable files are skipped; the loader has already reported them.
+pub fn validate_config_layers(stack: &ConfigLayerStack) -> Vec<ConfigDiagnostic> {
+    stack
+        .get_layers(ConfigLayerStackOrdering::LowestPrecedenceFirst)
+        .into_iter()
+        .filter_map(ConfigLayerEntry::config_file)
+        .filter_map(|file| validate_config_file(&file).ok())
+        .flatten()
+        .collect()
+}
+
+struct Validator<'a> {
+    source: &'a str,
+    schema: &'a RootSchema,
+    diagnostics: Vec<ConfigDiagnostic>,
+}
+
+impl<'a> Validator<'a> {
+    fn check_table(
+        &mut self,
+        table: &dyn TableLike,
+        schemas: &[&'a Schema],
+        path: &mut Vec<String>,
+    ) {
+        let Some(candidates) = self.resolve_all(schemas) else {
+            return;
+        };
+        let objects: Vec<_> = candidates
+            .iter()
+            .filter_map(|candidate| candidate.object.as_deref())
+            .collect();
+        if objects.is_empty() {
+            return;
+        }
+
+        for (key, item) in table.iter() {
+            path.push(key.to_string());
+            let mut children = Vec::new();
+            for object in &objects {
+                if let Some(schema) = object.properties.get(key) {
+                    children.push(schema);
+                } else if let Some(schema) = object.additional_properties.as_deref()
+                    && !matches!(schema, Schema::Bool(false))
+                {
+                    children.push(schema);
+                }
+            }
+            let span = table
+                .key(key)
+                .and_then(toml_edit::Key::span)
+                .or_else(|| item.span());
+            let deprecated = deprecation(path, item);
+            if let Some(message) = &deprecated {
+                self.push(
+                    DiagnosticKind::Deprecated,
+                    path,
+                    span.clone(),
+                    message.clone(),
+                );
+            }
+            if !children.is_empty() {
+                self.check_item(item, &children, path);
+            } else if deprecated.is_none() {
+                let known = objects.iter().flat_map(|object| object.properties.keys());
+                let message = match closest_match(key, known) {
+                    Some(suggestion) => {
+                        format!("unknown key `{key}`; did you mean `{suggestion}`?")
+                    }
+                    None => format!("unknown key `{key}`"),
+                };
+                self.push(DiagnosticKind::UnknownKey, path, span, message);
+            }
+            path.pop();
+        }
+    }
+
+    fn check_item(&mut self, item: &Item, schemas: &[&'a Schema], path: &mut Vec<String>) {
+        match item {
+            Item::Table(table) => self.check_table(table, schemas, path),
+            Item::ArrayOfTables(tables) => {
+                let items = self.array_items(schemas);
+                for table in tables.iter() {
+                    self.check_table(table, &items, path);
+                }
+            }
+            Item::Value(value) => self.check_value(value, schemas, path),
+            Item::None => {}
+        }
+    }
+
+    fn check_value(&mut self, value: &Value, schemas: &[&'a Schema], path: &mut Vec<String>) {
+        match value {
+            Value::InlineTable(table) => self.check_table(table, schemas, path),
+            Value::Array(array) => {
+                let items = self.array_items(schemas);
+                for value in array.iter() {
+                    self.check_value(value, &items, path);
+                }
+            }
+            Value::String(string) => {
+                let Some(allowed) = self.allowed_strings(schemas) else {
+                    return;
+                };
+                let string = string.value();
+                // Resolved when the config is loaded.
+                if contains_reference(string) {
+                    return;
+                }
+                if allowed.iter().any(|allowed| allowed == string) {
+                    return;
+                }
+                let message = match closest_match(string, allowed.iter()) {
+                    Some(suggestion) => {
+                        format!("invalid value `{string}`; did you mean `{suggestion}`?")
+                    }
+                    None => format!(
+                        "invalid value `{string}`; expected one of {}",
+                        allowed
+                            .iter()
+                            .map(|value| format!("`{value}`"))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                };
+                self.push(DiagnosticKind::InvalidValue, path, value.span(), message);
+            }
+            _ => {}
+        }
+    }
+
+    fn array_items(&self, schemas: &[&'a Schema]) -> Vec<&'a Schema> {
+        let Some(candidates) = self.resolve_all(schemas) else {
+            return Vec::new();
+        };
+        candidates
+            .into_iter()
+            .filter_map(
+                |candidate| match candidate.array.as_deref()?.items.as_ref()? {
+                    SingleOrVec::Single(schema) => Some(schema.as_ref()),
+                    SingleOrVec::Vec(_) => None,
+                },
+            )
+            .collect()
+    }
+
+    /// Returns the accepted values when every schema is a string enum.
+    fn allowed_strings(&self, schemas: &[&'a Schema]) -> Option<Vec<String>> {
+        let candidates = self.resolve_all(schemas)?;
+        if candidates.is_empty() {
+            return None;
+        }
+        let mut allowed = Vec::new();
+        for candidate in candidates {
+            if let Some(values) = &candidate.enum_values {
+                allowed.extend(values.iter().filter_map(|v| v.as_str().map(str::to_string)));
+            } else if let Some(serde_json::Value::String(value)) = &candidate.const_value {
+                allowed.push(value.clone());
+            } else {
+                return None;
+            }
+        }
+        Some(allowed)
+    }
+
+    /// Flattens references and combinators into the concrete schemas they
+    /// stand for. Returns `None` if any of them accepts everything.
+    fn resolve_all(&self, schemas: &[&'a Schema]) -> Option<Vec<&'a SchemaObject>> {
+        let mut resolved = Vec::new();
+        for schema in schemas {
+            if !self.resolve(schema, &mut resolved, 0) {
+                return None;
+            }
+        }
+        Some(resolved)
+    }
+
+    fn resolve(&self, schema: &'a Schema, out: &mut Vec<&'a SchemaObject>, depth: usize) -> bool {
+        // Guards against cycles through `$ref`.
+        if depth > 32 {
+            return false;
+        }
+        let object = match schema {
+            Schema::Bool(accepts_all) => return !accepts_all,
+            Schema::Object(object) => object,
+        };
+        if let Some(reference) = &object.reference {
+            let name = reference.rsplit('/').next().unwrap_or(reference);
+            return match self.schema.definitions.get(name) {
+                Some(definition) => self.resolve(definition, out, depth + 1),
+                None => false,
+            };
+        }
+        if let Some(subschemas) = &object.subschemas {
+            let members = [&subschemas.all_of, &subschemas.any_of, &subschemas.one_of];
+            for member in members.into_iter().flatten().flatten() {
+                if !self.resolve(member, out, depth + 1) {
+                    return false;
+                }
+            }
+            if object.instance_type.is_none()
+                && object.enum_values.is_none()
+                && object.const_value.is_none()
+                && object.object.is_none()
+            {
+                return true;
+            }
+        }
+        out.push(object);
+        true
+    }
+
+    fn push(
+        &mut self,
+        kind: DiagnosticKind,
+        path: &[String],
+        span: Option<std::ops::Range<usize>>,
+        message: String,
+    ) {
+        let (line, column) = line_column(self.source, span);
+        self.diagnostics.push(ConfigDiagnostic {
+            kind,
+            file: None,
+            key: path.join("."),
+            line,
+            column,
+            message,
+        });
+    }
+}
+
+/// Describes why the key at `path` is deprecated, if it is. This also covers
+/// serde aliases, which the generated schema does not list.
+fn deprecation(path: &[String], item: &Item) -> Option<String> {
+    let path: Vec<&str> = path.iter().map(String::as_str).collect();
+    // Settings that also live under `[profiles.<name>]`.
+    let scoped = match path.as_slice() {
+        ["profiles", _, rest @ ..] => rest,
+        rest => rest,
+    };
+    let replacement = match scoped {
+        [key] => LEGACY_TOGGLES
+            .iter()
+            .find(|(legacy, _)| legacy == key)
+            .map(|(_, feature)| feature.key()),
+        ["tools", "web_search" | "web_search_request"] => Some(Feature::WebSearchRequest.key()),
+        ["features", key] => legacy_feature_aliases()
+            .find(|(legacy, feature)| legacy == key && feature.key() != *key)
+            .map(|(_, feature)| feature.key()),
+        _ => None,
+    };
+    if let Some(canonical) = replacement {
+        let key = path.join(".");
+        return Some(format!(
+            "`{key}` is deprecated. Use `[features].{canonical}` instead."
+        ));
+    }
+
+    match path.as_slice() {
+        ["model_providers", _, "wire_api"] if item.as_str() == Some("chat") => {
+            Some(CHAT_WIRE_API_DEPRECATION_SUMMARY.to_string())
+        }
+        [
+            "ghost_snapshot",
+            old @ ("ignore_untracked_files_over_bytes" | "large_untracked_dir_warning_threshold"),
+        ] => {
+            let new = if *old == "ignore_untracked_files_over_bytes" {
+                "ignore_large_untracked_files"
+            } else {
+                "ignore_large_untracked_dirs"
+            };
+            Some(format!(
+                "`ghost_snapshot.{old}` is deprecated. Use `ghost_snapshot.{new}` instead."
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Picks the candidate closest to `input` by edit distance, if any is close
+/// enough to be a plausible typo.
+fn closest_match<'c>(
+    input: &str,
+    candidates: impl IntoIterator<Item = &'c String>,
+) -> Option<&'c str> {
+    let max_distance = (input.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(input, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+/// Optimal string alignment distance: edits plus adjacent transpositions, so
+/// `modle` is one edit away from `model`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut best = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                best = best.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = best;
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// Converts a byte span into a 1-based line and column.
+fn line_column(source: &str, span: Option<std::ops::Range<usize>>) -> (usize, usize) {
+    let offset = span.map_or(0, |span| span.start).min(source.len());
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    let column = source[line_start..offset].chars().count() + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn messages(contents: &str) -> Vec<(usize, usize, DiagnosticKind, String)> {
+        validate_config_toml(contents, Path::new("/"))
+            .into_iter()
+            .map(|d| (d.line, d.column, d.kind, d.message))
+            .collect()
+    }
+
+    #[test]
+    fn reports_unknown_keys_with_suggestions() {
+        let contents = r#"modle = "o3"
+
+[profiles.work]
+approval_polcy = "never"
+totally_unrelated = 1
+
+[mcp_servers.docs]
+command = "docs-server"
+env_var = ["HOME"]
+
+[features]
+web_serch_request = true
+"#;
+        assert_eq!(
+            messages(contents),
+            vec![
+                (
+                    1,
+                    1,
+                    DiagnosticKind::UnknownKey,
+                    "unknown key `modle`; did you mean `model`?".to_string()
+                ),
+                (
+                    4,
+                    1,
+                    DiagnosticKind::UnknownKey,
+                    "unknown key `approval_polcy`; did you mean `approval_policy`?".to_string()
+                ),
+                (
+                    5,
+                    1,
+                    DiagnosticKind::UnknownKey,
+                    "unknown key `totally_unrelated`".to_string()
+                ),
+                (
+                    9,
+                    1,
+                    DiagnosticKind::UnknownKey,
+                    "unknown key `env_var`; did you mean `env_vars`?".to_string()
+                ),
+                (
+                    12,
+                    1,
+                    DiagnosticKind::UnknownKey,
+                    "unknown key `web_serch_request`; did you mean `web_search_request`?"
+                        .to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_invalid_enum_values_at_the_value() {
+        assert_eq!(
+            messages("approval_policy = \"on_request\"\n"),
+            vec![(
+                1,
+                19,
+                DiagnosticKind::InvalidValue,
+                "invalid value `on_request`; did you mean `on-request`?".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn reports_deprecated_options() {
+        let contents = r#"experimental_use_unified_exec_tool = true
+
+[tools]
+web_search = true
+
+[model_providers.local]
+name = "local"
+wire_api = "chat"
+"#;
+        assert_eq!(
+            messages(contents),
+            vec![
+                (
+                    1,
+                    1,
+                    DiagnosticKind::Deprecated,
+                    "`experimental_use_unified_exec_tool` is deprecated. Use `[features].unified_exec` instead."
+                        .to_string()
+                ),
+                (
+                    4,
+                    1,
+                    DiagnosticKind::Deprecated,
+                    "`tools.web_search` is deprecated. Use `[features].web_search_request` instead."
+                        .to_string()
+                ),
+                (
+                    8,
+                    1,
+                    DiagnosticKind::Deprecated,
+                    CHAT_WIRE_API_DEPRECATION_SUMMARY.to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_type_and_syntax_errors_with_locations() {
+        let type_error = messages("model = \"o3\"\nmodel_context_window = \"big\"\n");
+        assert_eq!(type_error.len(), 1);
+        assert_eq!(
+            (type_error[0].0, type_error[0].1, type_error[0].2),
+            (2, 24, DiagnosticKind::InvalidType)
+        );
+
+        let syntax_error = messages("model = \"o3\"\n[profiles\n");
+        assert_eq!(syntax_error.len(), 1);
+        assert_eq!(
+            (syntax_error[0].0, syntax_error[0].2),
+            (2, DiagnosticKind::Syntax)
+        );
+    }
+
+    #[test]
+    fn accepts_a_valid_config() {
+        let contents = r#"model = "o3"
+approval_policy = "on-request"
+notify = ["notify-send"]
+
+[features]
+unified_exec = true
+
+[projects."/tmp/project"]
+trust_level = "trusted"
+"#;
+        assert_eq!(messages(contents), Vec::new());
+    }
+}
@@ -492,7 +492,7 @@ fn clear_path(root: &mut TomlValue, segments: &[String]) -> Result<bool, MergeEr
     Ok(parent.remove(last).is_some())
 }
 
-fn toml_value_to_item(value: &TomlValue) -> anyhow::Result<TomlItem> {
+pub(crate) fn toml_value_to_item(value: &TomlValue) -> anyhow::Result<TomlItem> {
     match value {
         TomlValue::Table(table) => {
             let mut table_item = toml_edit::Table::new();
@@ -576,6 +576,9 @@ fn override_message(layer: &ConfigLayerSource) -> String {
             "Overridden by project config: {}/{CONFIG_TOML_FILE}",
             dot_codex_folder.display(),
         ),
+        ConfigLayerSource::Environment => {
+            "Overridden by CODEX_CONFIG_* environment variables".to_string()
+        }
         ConfigLayerSource::SessionFlags => "Overridden by session flags".to_string(),
         ConfigLayerSource::User { file } => {
             format!("Overridden by user config: {}", file.display())
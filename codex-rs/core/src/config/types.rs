@@ -4,13 +4,16 @@
 // definitions that do not contain business logic.
 
 pub use codex_protocol::config_types::AltScreenMode;
+use codex_protocol::protocol::WatchdogAction;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::time::Duration;
 use wildmatch::WildMatchPattern;
 
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Deserializer;
 use serde::Serialize;
@@ -46,6 +49,63 @@ pub struct McpServerConfig {
     /// Explicit deny-list of tools. These tools will be removed after applying `enabled_tools`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub disabled_tools: Option<Vec<String>>,
+
+    /// How `sampling/createMessage` requests from this server are handled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<McpSamplingConfig>,
+}
+
+/// The on-disk shape of an `[mcp_servers.<name>]` table, before the transport
+/// is resolved.
+#[derive(Deserialize, Clone, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+struct RawMcpServerConfig {
+    // stdio
+    command: Option<String>,
+    #[serde(default)]
+    args: Option<Vec<String>>,
+    #[serde(default)]
+    env: Option<HashMap<String, String>>,
+    #[serde(default)]
+    env_vars: Option<Vec<String>>,
+    #[serde(default)]
+    cwd: Option<PathBuf>,
+    http_headers: Option<HashMap<String, String>>,
+    #[serde(default)]
+    env_http_headers: Option<HashMap<String, String>>,
+
+    // streamable_http
+    url: Option<String>,
+    bearer_token: Option<String>,
+    bearer_token_env_var: Option<String>,
+
+    // shared
+    #[serde(default)]
+    startup_timeout_sec: Option<f64>,
+    #[serde(default)]
+    startup_timeout_ms: Option<u64>,
+    #[serde(default, with = "option_duration_secs")]
+    #[schemars(with = "Option<f64>")]
+    tool_timeout_sec: Option<Duration>,
+    #[serde(default)]
+    enabled: Option<bool>,
+    #[serde(default)]
+    enabled_tools: Option<Vec<String>>,
+    #[serde(default)]
+    disabled_tools: Option<Vec<String>>,
+    #[serde(default)]
+    sampling: Option<McpSamplingConfig>,
+}
+
+// The schema describes the TOML table rather than the resolved transport.
+impl JsonSchema for McpServerConfig {
+    fn schema_name() -> String {
+        "McpServerConfig".to_string()
+    }
+
+    fn json_schema(generator: &mut schemars::r#gen::SchemaGenerator) -> schemars::schema::Schema {
+        RawMcpServerConfig::json_schema(generator)
+    }
 }
 
 impl<'de> Deserialize<'de> for McpServerConfig {
@@ -53,42 +113,6 @@ impl<'de> Deserialize<'de> for McpServerConfig {
     where
         D: Deserializer<'de>,
     {
-        #[derive(Deserialize, Clone)]
-        struct RawMcpServerConfig {
-            // stdio
-            command: Option<String>,
-            #[serde(default)]
-            args: Option<Vec<String>>,
-            #[serde(default)]
-            env: Option<HashMap<String, String>>,
-            #[serde(default)]
-            env_vars: Option<Vec<String>>,
-            #[serde(default)]
-            cwd: Option<PathBuf>,
-            http_headers: Option<HashMap<String, String>>,
-            #[serde(default)]
-            env_http_headers: Option<HashMap<String, String>>,
-
-            // streamable_http
-            url: Option<String>,
-            bearer_token: Option<String>,
-            bearer_token_env_var: Option<String>,
-
-            // shared
-            #[serde(default)]
-            startup_timeout_sec: Option<f64>,
-            #[serde(default)]
-            startup_timeout_ms: Option<u64>,
-            #[serde(default, with = "option_duration_secs")]
-            tool_timeout_sec: Option<Duration>,
-            #[serde(default)]
-            enabled: Option<bool>,
-            #[serde(default)]
-            enabled_tools: Option<Vec<String>>,
-            #[serde(default)]
-            disabled_tools: Option<Vec<String>>,
-        }
-
         let mut raw = RawMcpServerConfig::deserialize(deserializer)?;
 
         let startup_timeout_sec = match (raw.startup_timeout_sec, raw.startup_timeout_ms) {
@@ -103,6 +127,7 @@ impl<'de> Deserialize<'de> for McpServerConfig {
         let enabled = raw.enabled.unwrap_or_else(default_enabled);
         let enabled_tools = raw.enabled_tools.clone();
         let disabled_tools = raw.disabled_tools.clone();
+        let sampling = raw.sampling.clone();
 
         fn throw_if_set<E, T>(transport: &str, field: &str, value: Option<&T>) -> Result<(), E>
         where
@@ -156,6 +181,7 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             enabled,
             enabled_tools,
             disabled_tools,
+            sampling,
         })
     }
 }
@@ -164,7 +190,42 @@ const fn default_enabled() -> bool {
     true
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+/// Settings for serving MCP `sampling/createMessage` requests with the
+/// session's model.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, JsonSchema)]
+pub struct McpSamplingConfig {
+    /// Whether each request needs user approval.
+    #[serde(default)]
+    pub approval: McpSamplingApproval,
+
+    /// Total tokens this server may consume through sampling per session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_budget: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum McpSamplingApproval {
+    /// Ask the user before each request is sent to the model.
+    #[default]
+    Prompt,
+    /// Send requests to the model without asking.
+    Auto,
+    /// Reject every request.
+    Deny,
+}
+
+impl McpSamplingApproval {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Prompt => "prompt",
+            Self::Auto => "auto",
+            Self::Deny => "deny",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 #[serde(untagged, deny_unknown_fields, rename_all = "snake_case")]
 pub enum McpServerTransportConfig {
     /// https://modelcontextprotocol.io/specification/2025-06-18/basic/transports#stdio
@@ -222,7 +283,7 @@ mod option_duration_secs {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, JsonSchema)]
 pub enum UriBasedFileOpener {
     #[serde(rename = "vscode")]
     VsCode,
@@ -254,7 +315,7 @@ impl UriBasedFileOpener {
 }
 
 /// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 pub struct History {
     /// If true, history entries will not be written to disk.
     pub persistence: HistoryPersistence,
@@ -264,7 +325,7 @@ pub struct History {
     pub max_bytes: Option<usize>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Default, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub enum HistoryPersistence {
     /// Save all history entries to disk.
@@ -274,16 +335,27 @@ pub enum HistoryPersistence {
     None,
 }
 
+/// Which releases the update check offers.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum UpdateChannel {
+    /// Stable releases only.
+    #[default]
+    Stable,
+    /// Stable releases and the alpha and beta pre-releases leading up to them.
+    Beta,
+}
+
 // ===== Analytics configuration =====
 
 /// Analytics settings loaded from config.toml. Fields are optional so we can apply defaults.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 pub struct AnalyticsConfigToml {
     /// When `false`, disables analytics across Codex product surfaces in this profile.
     pub enabled: Option<bool>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 pub struct FeedbackConfigToml {
     /// When `false`, disables the feedback flow across Codex product surfaces.
     pub enabled: Option<bool>,
@@ -291,7 +363,7 @@ pub struct FeedbackConfigToml {
 
 // ===== OTEL configuration =====
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub enum OtelHttpProtocol {
     /// Binary payload
@@ -300,7 +372,7 @@ pub enum OtelHttpProtocol {
     Json,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub struct OtelTlsConfig {
     pub ca_certificate: Option<AbsolutePathBuf>,
@@ -309,7 +381,7 @@ pub struct OtelTlsConfig {
 }
 
 /// Which OTEL exporter to use.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub enum OtelExporterKind {
     None,
@@ -332,7 +404,7 @@ pub enum OtelExporterKind {
 }
 
 /// OTEL settings loaded from config.toml. Fields are optional so we can apply defaults.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 pub struct OtelConfigToml {
     /// Log user prompt in traces
     pub log_user_prompt: Option<bool>,
@@ -345,6 +417,13 @@ pub struct OtelConfigToml {
 
     /// Optional trace exporter
     pub trace_exporter: Option<OtelExporterKind>,
+
+    /// Optional metrics exporter. Defaults to `statsig`.
+    pub metrics_exporter: Option<OtelExporterKind>,
+
+    /// Address for `codex app-server` / `codex mcp-server` to serve
+    /// Prometheus metrics on (`GET /metrics`). Disabled when unset.
+    pub prometheus_listen_addr: Option<SocketAddr>,
 }
 
 /// Effective OTEL settings after defaults are applied.
@@ -355,6 +434,7 @@ pub struct OtelConfig {
     pub exporter: OtelExporterKind,
     pub trace_exporter: OtelExporterKind,
     pub metrics_exporter: OtelExporterKind,
+    pub prometheus_listen_addr: Option<SocketAddr>,
 }
 
 impl Default for OtelConfig {
@@ -365,11 +445,12 @@ impl Default for OtelConfig {
             exporter: OtelExporterKind::None,
             trace_exporter: OtelExporterKind::None,
             metrics_exporter: OtelExporterKind::Statsig,
+            prometheus_listen_addr: None,
         }
     }
 }
 
-#[derive(Serialize, Debug, Clone, PartialEq, Eq, Deserialize)]
+#[derive(Serialize, Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum Notifications {
     Enabled(bool),
@@ -382,12 +463,41 @@ impl Default for Notifications {
     }
 }
 
+/// How the TUI delivers desktop notifications.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum NotificationBackend {
+    /// Windows toasts under WSL in Windows Terminal, OSC 9 escape sequences
+    /// elsewhere.
+    #[default]
+    Auto,
+    /// OSC 9 escape sequences, handled by the terminal emulator.
+    Osc9,
+    /// macOS Notification Center via `osascript`.
+    Osascript,
+    /// freedesktop notifications via `notify-send`.
+    NotifySend,
+    /// Windows toast notifications via PowerShell.
+    WindowsToast,
+}
+
+/// When a message submitted while a turn is running is delivered.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum QueuedMessageDelivery {
+    /// Wait for the running turn to finish.
+    #[default]
+    NextTurn,
+    /// Interrupt the running turn and send the message right away.
+    Interrupt,
+}
+
 /// How TUI2 should interpret mouse scroll events.
 ///
 /// Terminals generally encode both mouse wheels and trackpads as the same "scroll up/down" mouse
 /// button events, without a magnitude. This setting controls whether Codex uses a heuristic to
 /// infer wheel vs trackpad per stream, or forces a specific behavior.
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ScrollInputMode {
     /// Infer wheel vs trackpad behavior per scroll stream.
@@ -405,18 +515,34 @@ impl Default for ScrollInputMode {
 }
 
 /// Collection of settings that are specific to the TUI.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 pub struct Tui {
     /// Enable desktop notifications from the TUI when the terminal is unfocused.
     /// Defaults to `true`.
     #[serde(default)]
     pub notifications: Notifications,
 
+    /// How desktop notifications are delivered: `auto` (default), `osc9`,
+    /// `osascript`, `notify-send`, or `windows-toast`.
+    #[serde(default)]
+    pub notification_backend: NotificationBackend,
+
+    /// When messages submitted while a turn is running are sent:
+    /// `next-turn` (default) or `interrupt`.
+    #[serde(default)]
+    pub queued_message_delivery: QueuedMessageDelivery,
+
     /// Enable animations (welcome screen, shimmer effects, spinners).
     /// Defaults to `true`.
     #[serde(default = "default_true")]
     pub animations: bool,
 
+    /// Screen-reader friendly output: no animations or ticking timers that
+    /// redraw the screen, no alternate screen, and state changes announced as
+    /// plain lines. Defaults to `false`.
+    #[serde(default)]
+    pub screen_reader: bool,
+
     /// Show startup tooltips in the TUI welcome screen.
     /// Defaults to `true`.
     #[serde(default = "default_true")]
@@ -535,6 +661,620 @@ pub struct Tui {
     /// scrollback in terminal multiplexers like Zellij that follow the xterm spec.
     #[serde(default)]
     pub alternate_screen: AltScreenMode,
+
+    /// Override the keys bound to common TUI actions.
+    #[serde(default)]
+    pub keybindings: TuiKeybindings,
+
+    /// Name of the color theme: `dark` (default), `light`, `high-contrast`, or
+    /// a palette defined under `[tui.themes]`.
+    pub theme: Option<String>,
+
+    /// User-defined palettes, keyed by theme name.
+    #[serde(default)]
+    pub themes: BTreeMap<String, TuiThemePalette>,
+
+    /// Publish the TUI's state for terminal multiplexer status bars.
+    #[serde(default)]
+    pub status_export: TuiStatusExport,
+}
+
+/// A user-defined TUI color palette. Each entry is a space-separated list of
+/// a color (an ANSI name such as `green` or `light-red`, or `#rrggbb`) and
+/// optional modifiers (`bold`, `dim`, `italic`, `underlined`). Unset entries
+/// come from the `base` theme.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TuiThemePalette {
+    /// Built-in theme this palette extends. Defaults to `dark`.
+    pub base: Option<String>,
+    pub diff_add: Option<String>,
+    pub diff_del: Option<String>,
+    pub diff_gutter: Option<String>,
+    pub diff_context: Option<String>,
+    pub syntax_comment: Option<String>,
+    pub syntax_string: Option<String>,
+    pub syntax_keyword: Option<String>,
+    pub syntax_function: Option<String>,
+    pub syntax_number: Option<String>,
+    pub syntax_operator: Option<String>,
+    pub status_header: Option<String>,
+    pub status_detail: Option<String>,
+}
+
+/// Key overrides for TUI actions, written like `"ctrl+t"`, `"alt+x"`, `"esc"`
+/// or `"f2"`. Unset actions keep their default key. Bindings are validated
+/// for conflicts when the TUI starts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TuiKeybindings {
+    /// Interrupt the running turn. Defaults to `esc`.
+    pub interrupt: Option<String>,
+    /// Stop the running turn but keep the reply streamed so far. Defaults to
+    /// `ctrl+s`.
+    pub stop: Option<String>,
+    /// Approve the pending command, patch, or elicitation. Defaults to `y`.
+    pub approve: Option<String>,
+    /// Open or close the transcript pager. Defaults to `ctrl+t`.
+    pub toggle_transcript: Option<String>,
+    /// Paste an image from the clipboard. Defaults to `ctrl+v`.
+    pub paste: Option<String>,
+    /// Interrupt, then quit on a second press. Defaults to `ctrl+c`.
+    pub quit: Option<String>,
+    /// Switch to the next conversation tab. Defaults to `alt+.`.
+    pub next_tab: Option<String>,
+    /// Switch to the previous conversation tab. Defaults to `alt+,`.
+    pub prev_tab: Option<String>,
+}
+
+/// Settings for the `[tui.status_export]` table. The exported state is one of
+/// `waiting`, `running`, or `needs_approval`, taken from the most urgent tab.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TuiStatusExport {
+    /// JSON file rewritten whenever the state changes and removed on exit.
+    /// Relative paths are resolved against `CODEX_HOME`. `{pid}` is replaced
+    /// with the process id and `{pane}` with the tmux or zellij pane id (the
+    /// process id outside a multiplexer).
+    pub file: Option<AbsolutePathBuf>,
+
+    /// Also set the terminal title, e.g. `codex: needs approval · fix the
+    /// build`, which tmux shows as `#{pane_title}`. Defaults to `false`.
+    #[serde(default)]
+    pub title: bool,
+}
+
+/// Settings for the `[hooks]` table.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct HooksConfig {
+    /// External command hooks, keyed by hook name.
+    #[serde(default)]
+    pub commands: BTreeMap<String, CommandHookConfig>,
+    /// Built-in chat notification sinks.
+    #[serde(default)]
+    pub sinks: NotificationSinksConfig,
+}
+
+/// Settings for the `[hooks.sinks]` table.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct NotificationSinksConfig {
+    /// Post to a Slack incoming webhook.
+    pub slack: Option<WebhookSinkConfig>,
+    /// Post to a Discord webhook.
+    pub discord: Option<WebhookSinkConfig>,
+}
+
+/// A chat webhook that is notified about the session, configured under
+/// `[hooks.sinks.<service>]`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookSinkConfig {
+    /// Incoming webhook URL. Use `${env:VAR}` to keep it out of the file.
+    pub webhook_url: String,
+    /// Which notifications to send. Defaults to all of them.
+    pub on: Option<Vec<SinkNotification>>,
+    /// Message templates, by notification.
+    #[serde(default)]
+    pub templates: SinkTemplates,
+    /// Send at most this many messages per minute; the rest are dropped and
+    /// counted in the next message. Defaults to 10.
+    pub max_per_minute: Option<u32>,
+    /// Set to `false` to keep the sink registered but off.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Only turn the sink on when this environment variable is set, as for
+    /// command hooks.
+    pub only_when_env: Option<String>,
+    /// Only notify about events that pass this filter expression.
+    pub filter: Option<String>,
+}
+
+/// A kind of notification a sink can send.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SinkNotification {
+    /// The agent finished a turn.
+    TurnComplete,
+    /// A command or patch is waiting for approval.
+    ApprovalNeeded,
+    /// The session reported an error.
+    Error,
+}
+
+/// Message templates for a sink. `{summary}` expands to the default message;
+/// see the hooks documentation for the other placeholders.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SinkTemplates {
+    pub turn_complete: Option<String>,
+    pub approval_needed: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Settings for the `[pull_request]` table.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PullRequestConfig {
+    /// When a task finishes with uncommitted changes, commit them on a new
+    /// branch, push it, and open a draft pull request.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Prefix for the branches the workflow creates. Defaults to `codex/`.
+    pub branch_prefix: Option<String>,
+    /// Branch the pull request targets. Defaults to the repository's default
+    /// branch.
+    pub base: Option<String>,
+    /// Remote to push to. Defaults to `origin`.
+    pub remote: Option<String>,
+}
+
+/// Settings for the `[redaction]` table.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RedactionConfig {
+    /// Replace secrets in tool output before it is sent to the model or
+    /// written to the rollout. Defaults to `true`.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Also redact long random-looking tokens. Off by default because it also
+    /// catches hashes and other encoded data that is not secret.
+    #[serde(default)]
+    pub entropy: bool,
+    /// Also redact `KEY=value` and `"key": "value"` assignments whose key
+    /// names a secret, such as `DB_PASSWORD=...`. Defaults to `true`. Only
+    /// values that look like literal secrets are replaced, so code such as
+    /// `token = self.token` is left alone.
+    #[serde(default = "default_true")]
+    pub assignments: bool,
+    /// Extra regular expressions, keyed by rule name. When a pattern has a
+    /// capture group, only the first group is replaced.
+    #[serde(default)]
+    pub patterns: BTreeMap<String, String>,
+    /// Glob patterns for sources whose content is withheld entirely: file
+    /// paths, URLs, `mcp:<server>:<uri>` resources and `mcp:<server>.<tool>`
+    /// tools. `*` also matches `/`. Best-effort: shell commands are matched
+    /// by the paths they name, so computed paths are not caught.
+    #[serde(default)]
+    pub sources: Vec<String>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            entropy: false,
+            assignments: true,
+            patterns: BTreeMap::new(),
+            sources: Vec::new(),
+        }
+    }
+}
+
+/// Settings for the `[injection_detection]` table.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct InjectionDetectionConfig {
+    /// What to do with tool output that looks like a prompt injection.
+    /// Defaults to `flag`.
+    #[serde(default)]
+    pub mode: InjectionDetectionMode,
+    /// Classifier program run on every tool output, which it receives on
+    /// stdin. Exiting with status 1 marks the output as suspicious, with
+    /// stdout as the reason; any other failure is logged and ignored.
+    pub classifier: Option<Vec<String>>,
+    /// Kill the classifier if it runs longer than this. Defaults to 5 seconds.
+    pub classifier_timeout_ms: Option<u64>,
+}
+
+/// Settings for the `[guardrails]` table.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GuardrailsConfig {
+    /// End the turn once the same tool has failed this many times in a row.
+    /// Unset leaves the breaker off for tools without an entry in
+    /// `tool_failure_limits`.
+    pub max_consecutive_tool_failures: Option<u32>,
+    /// Limits for individual tools, overriding `max_consecutive_tool_failures`.
+    /// Commands are keyed by program name (`cargo`), patches by `apply_patch`
+    /// and MCP tools by `server/tool`. `0` turns the breaker off for a tool.
+    #[serde(default)]
+    pub tool_failure_limits: BTreeMap<String, u32>,
+}
+
+/// Prices of one model, in US dollars per million tokens, as set under
+/// `[model_pricing.<model>]`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ModelPricing {
+    /// Input tokens not served from the prompt cache.
+    pub input: f64,
+    /// Input tokens served from the prompt cache. Defaults to `input`.
+    pub cached_input: Option<f64>,
+    /// Output tokens, including reasoning tokens.
+    pub output: f64,
+}
+
+/// Settings for the `[watchdogs]` table: time limits for unattended runs.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WatchdogsConfig {
+    /// Longest a single turn may run.
+    pub turn: Option<WatchdogConfig>,
+    /// Longest the session may run, counted from its start.
+    pub session: Option<WatchdogConfig>,
+    /// Longest an approval request may wait for an answer.
+    pub approval: Option<WatchdogConfig>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WatchdogConfig {
+    /// Seconds before the watchdog fires.
+    pub timeout_secs: u64,
+    /// What to do when it fires: `abort` (the default), `notify`, or, for
+    /// approvals only, `deny`.
+    #[serde(default)]
+    pub action: WatchdogAction,
+}
+
+/// Settings for the `[proxy]` table: which HTTP(S) proxy Codex's own HTTP
+/// clients connect through. Destinations that nothing here applies to use
+/// the `HTTPS_PROXY`/`HTTP_PROXY`/`NO_PROXY` environment variables.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ProxyConfig {
+    /// Proxy URL for every destination that does not set its own.
+    pub url: Option<String>,
+    /// Hosts reached without a proxy: exact names, `.example.com` or
+    /// `*.example.com` for a domain and its subdomains, or `*` for all.
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+    /// Proxy for model provider requests.
+    #[serde(default)]
+    pub model_provider: ProxyTargetConfig,
+    /// Proxy for MCP servers reached over HTTP.
+    #[serde(default)]
+    pub mcp: ProxyTargetConfig,
+    /// Host rules checked in order before any of the above; the first rule
+    /// whose `hosts` match decides.
+    #[serde(default)]
+    pub rules: Vec<ProxyRule>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ProxyTargetConfig {
+    /// Proxy URL for this kind of destination.
+    pub url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ProxyRule {
+    /// Host patterns, written like `no_proxy` entries.
+    pub hosts: Vec<String>,
+    /// Proxy URL for matching hosts, or `direct` to connect without one.
+    pub proxy: String,
+}
+
+/// Settings for the `[persistence]` table, which controls how session
+/// rollouts are written to disk.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PersistenceConfig {
+    /// How long rollout lines may stay buffered in memory before they are
+    /// written and fsynced. `0` writes every batch as soon as it is recorded,
+    /// without fsync. Defaults to 100.
+    #[serde(default = "default_flush_interval_ms")]
+    pub flush_interval_ms: u64,
+}
+
+const fn default_flush_interval_ms() -> u64 {
+    100
+}
+
+impl Default for PersistenceConfig {
+    fn default() -> Self {
+        Self {
+            flush_interval_ms: default_flush_interval_ms(),
+        }
+    }
+}
+
+/// Settings for the `[context_budget]` table: approximate token limits on
+/// what each kind of content may take up in a model request. Unset sources
+/// are not limited.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ContextBudgetConfig {
+    /// AGENTS.md instructions.
+    pub project_docs: Option<usize>,
+    /// Output of tool calls.
+    pub tool_outputs: Option<usize>,
+    /// Skills and MCP resources attached to messages.
+    pub pinned: Option<usize>,
+    /// User and assistant messages. Older turns that do not fit are left out
+    /// whole; the latest user message and everything after it are always kept.
+    pub history: Option<usize>,
+}
+
+/// Settings for the `[reasoning]` table: what happens to the model's
+/// reasoning, separately from its answers.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ReasoningConfig {
+    /// What clients and hooks receive. Defaults to `summarize`, or `keep`
+    /// with `show_raw_agent_reasoning = true`.
+    #[serde(default)]
+    pub capture: ReasoningRetention,
+    /// What is written to the session's rollout file. Defaults to `keep`.
+    #[serde(default = "default_reasoning_persist")]
+    pub persist: ReasoningRetention,
+}
+
+const fn default_reasoning_persist() -> ReasoningRetention {
+    ReasoningRetention::Keep
+}
+
+impl Default for ReasoningConfig {
+    fn default() -> Self {
+        Self {
+            capture: ReasoningRetention::default(),
+            persist: default_reasoning_persist(),
+        }
+    }
+}
+
+/// How much of the model's reasoning is kept.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReasoningRetention {
+    /// Summaries and the raw reasoning text, when the model provides it.
+    Keep,
+    /// Only the reasoning summaries.
+    #[default]
+    Summarize,
+    /// No reasoning at all.
+    Discard,
+}
+
+/// Settings for the `[verify]` table: a command run after each turn that
+/// edits files, whose failures are sent back to the model to fix.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct VerifyConfig {
+    /// Shell command run in the session's working directory, such as
+    /// `cargo test`. Verification is off when unset.
+    pub command: Option<String>,
+    /// How many failed runs are sent back to the model before the turn ends
+    /// with the failure. Defaults to 3.
+    #[serde(default = "default_verify_max_iterations")]
+    pub max_iterations: u32,
+}
+
+const fn default_verify_max_iterations() -> u32 {
+    3
+}
+
+impl Default for VerifyConfig {
+    fn default() -> Self {
+        Self {
+            command: None,
+            max_iterations: default_verify_max_iterations(),
+        }
+    }
+}
+
+/// Settings for the `[patch_validation]` table: a syntax check of the files
+/// a patch would write, run before it is applied.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PatchValidationConfig {
+    #[serde(default)]
+    pub mode: PatchValidationMode,
+}
+
+/// What happens to a patch that leaves a file with syntax errors.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PatchValidationMode {
+    /// No validation.
+    #[default]
+    Off,
+    /// Apply the patch and tell the model about the errors.
+    Warn,
+    /// Refuse the patch and send the errors back to the model.
+    Reject,
+}
+
+/// Settings for the `[exec_backend]` table: where tool commands run.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ExecBackendConfig {
+    /// On this machine.
+    #[default]
+    Local,
+    /// On another host, through `ssh`.
+    Ssh(SshExecBackend),
+    /// In the project's dev container, started with the `devcontainer` CLI
+    /// and reached with `docker exec`.
+    Devcontainer(DevcontainerExecBackend),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct SshExecBackend {
+    /// Destination given to `ssh`: a `Host` alias from `~/.ssh/config` or
+    /// `user@host`.
+    pub host: String,
+    /// The working directory's counterpart on the host. Defaults to the same
+    /// path.
+    #[serde(default)]
+    pub remote_cwd: Option<PathBuf>,
+    /// Extra arguments for `ssh`, such as `["-p", "2222"]`.
+    #[serde(default)]
+    pub ssh_args: Vec<String>,
+    /// Local environment variables passed on to remote commands. The rest of
+    /// the local environment describes this machine, not the host.
+    #[serde(default)]
+    pub forward_env: Vec<String>,
+    #[serde(default)]
+    pub sync: RemoteSync,
+    #[serde(default)]
+    pub sandbox: RemoteSandbox,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+pub struct DevcontainerExecBackend {
+    /// `devcontainer.json` to use. Defaults to `.devcontainer/devcontainer.json`
+    /// or `.devcontainer.json` in the working directory or one of its parents
+    /// up to the repository root.
+    #[serde(default)]
+    pub config: Option<PathBuf>,
+    /// Local environment variables passed on to commands in the container.
+    #[serde(default)]
+    pub forward_env: Vec<String>,
+    /// What enforces the sandbox policy inside the container. Bubblewrap by
+    /// default, which the image must ship; `none` trusts the container
+    /// itself as the sandbox.
+    #[serde(default)]
+    pub sandbox: RemoteSandbox,
+}
+
+impl Default for DevcontainerExecBackend {
+    fn default() -> Self {
+        Self {
+            config: None,
+            forward_env: Vec::new(),
+            sandbox: RemoteSandbox::Bwrap,
+        }
+    }
+}
+
+/// How the files `apply_patch` edits reach the host.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteSync {
+    /// Patches edit the local files, which are then copied to the host.
+    #[default]
+    Patches,
+    /// The host sees the local files already, e.g. through a shared mount.
+    None,
+}
+
+/// What enforces the sandbox policy on the host.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteSandbox {
+    /// Bubblewrap (`bwrap`), which must be installed on the host.
+    #[default]
+    Bwrap,
+    /// Nothing: the host itself is trusted as the sandbox.
+    None,
+}
+
+/// Settings for the `[mock_provider]` table, read when `model_provider` is
+/// `"mock"`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MockProviderConfig {
+    /// JSON file with the scripted responses to replay. Relative paths are
+    /// resolved against the session's working directory.
+    pub fixture: Option<PathBuf>,
+}
+
+/// Handling of tool output suspected of containing a prompt injection.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum InjectionDetectionMode {
+    /// Do not scan tool output.
+    Off,
+    /// Keep the output and prepend a notice telling the model to treat it as
+    /// untrusted data.
+    #[default]
+    Flag,
+    /// Remove the suspicious lines, and flag the output when only the
+    /// classifier objected.
+    Strip,
+}
+
+/// An external hook defined under `[hooks.commands.<name>]`. The command runs
+/// whenever one of the `on` events is emitted, with the event serialized as
+/// JSON on stdin, or on every tool output when `redact` is set.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CommandHookConfig {
+    /// Event names (e.g. `task_complete`, `exec_approval_request`) that fire
+    /// this hook. `"*"` matches every event. Ignored by redaction hooks.
+    #[serde(default)]
+    pub on: Vec<String>,
+    /// Program and arguments to run.
+    pub command: Vec<String>,
+    /// Kill the command if it runs longer than this. Defaults to 30 seconds.
+    pub timeout_ms: Option<u64>,
+    /// Hooks with a higher priority run first. Hooks with the same priority
+    /// run in name order. Defaults to 0.
+    #[serde(default)]
+    pub priority: i32,
+    /// On `exec_approval_request` and `apply_patch_approval_request`, wait
+    /// for the hook to finish before the event is delivered and the hooks
+    /// after it run; a blocking hook that fails denies the request. Other
+    /// events never wait for hooks.
+    #[serde(default)]
+    pub blocking: bool,
+    /// Set to `false` to keep the hook registered but off until it is turned
+    /// on from `/hooks`.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Only turn the hook on when this environment variable is set to a
+    /// non-empty value (`"CI"`) or to a given value (`"CI=true"`).
+    pub only_when_env: Option<String>,
+    /// Only run for the `on` events that pass this filter expression, e.g.
+    /// `data.exit_code != 0`.
+    pub filter: Option<String>,
+    /// Make this a redaction hook: it runs on the text of every tool output
+    /// before the output is recorded or sent to the model, is awaited, and
+    /// what it prints to stdout replaces the text. An output is withheld when
+    /// the hook fails.
+    #[serde(default)]
+    pub redact: bool,
+}
+
+/// A slash command defined under `[commands.<name>]` in `config.toml`.
+/// Invoking `/<name>` sends `prompt` with its placeholders expanded, and runs
+/// the hooks subscribed to the `user_command` event.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UserCommandConfig {
+    /// Prompt template. Supports the same `$1`..`$9`, `$ARGUMENTS` and
+    /// `$NAME` placeholders as custom prompts. Without one, the command only
+    /// runs hooks.
+    pub prompt: Option<String>,
+    /// Short description shown in the slash command popup.
+    pub description: Option<String>,
+    /// Hint for the arguments the command expects.
+    pub argument_hint: Option<String>,
 }
 
 const fn default_true() -> bool {
@@ -544,7 +1284,7 @@ const fn default_true() -> bool {
 /// Settings for notices we display to users via the tui and app-server clients
 /// (primarily the Codex IDE extension). NOTE: these are different from
 /// notifications - notices are warnings, NUX screens, acknowledgements, etc.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 pub struct Notice {
     /// Tracks whether the user has acknowledged the full access warning prompt.
     pub hide_full_access_warning: Option<bool>,
@@ -567,7 +1307,7 @@ impl Notice {
     pub(crate) const TABLE_KEY: &'static str = "notice";
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 pub struct SandboxWorkspaceWrite {
     #[serde(default)]
     pub writable_roots: Vec<AbsolutePathBuf>,
@@ -590,7 +1330,7 @@ impl From<SandboxWorkspaceWrite> for codex_app_server_protocol::SandboxSettings
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub enum ShellEnvironmentPolicyInherit {
     /// "Core" environment variables for the platform. On UNIX, this would
@@ -607,7 +1347,7 @@ pub enum ShellEnvironmentPolicyInherit {
 
 /// Policy for building the `env` when spawning a process via either the
 /// `shell` or `local_shell` tool.
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 pub struct ShellEnvironmentPolicyToml {
     pub inherit: Option<ShellEnvironmentPolicyInherit>,
 
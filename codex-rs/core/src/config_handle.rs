@@ -0,0 +1,204 @@
+//! Runtime changes to the settings shared by every thread of a
+//! [`ThreadManager`](crate::ThreadManager).
+//!
+//! A [`ConfigHandle`] records the model, approval policy and sandbox policy
+//! set at runtime and submits them to every live thread as an
+//! `Op::OverrideTurnContext`. Each thread that accepts a change emits
+//! `EventMsg::ModelSettingsChanged` for a new model and
+//! `EventMsg::ConfigOverrideApplied` for new policies, which hooks can
+//! subscribe to as `model_settings_changed` and `config_override_applied`; a
+//! value forbidden by the thread's requirements is rejected with an `Error`
+//! event instead. Threads started later begin
+//! with the recorded settings.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::Op;
+use codex_protocol::protocol::SandboxPolicy;
+use tokio::sync::RwLock;
+
+use crate::codex_thread::CodexThread;
+use crate::config::Config;
+
+/// Settings to change at runtime. `None` leaves a setting as it is.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigChange {
+    pub model: Option<String>,
+    pub approval_policy: Option<AskForApproval>,
+    pub sandbox_policy: Option<SandboxPolicy>,
+}
+
+impl ConfigChange {
+    fn merge(&mut self, other: ConfigChange) {
+        if let Some(model) = other.model {
+            self.model = Some(model);
+        }
+        if let Some(approval_policy) = other.approval_policy {
+            self.approval_policy = Some(approval_policy);
+        }
+        if let Some(sandbox_policy) = other.sandbox_policy {
+            self.sandbox_policy = Some(sandbox_policy);
+        }
+    }
+
+    fn to_op(&self) -> Op {
+        Op::OverrideTurnContext {
+            cwd: None,
+            approval_policy: self.approval_policy,
+            sandbox_policy: self.sandbox_policy.clone(),
+            model: self.model.clone(),
+            effort: None,
+            summary: None,
+            verbosity: None,
+        }
+    }
+}
+
+/// Cheaply cloneable handle for changing settings of running threads.
+#[derive(Clone)]
+pub struct ConfigHandle {
+    threads: Arc<RwLock<HashMap<ThreadId, Arc<CodexThread>>>>,
+    overrides: Arc<std::sync::RwLock<ConfigChange>>,
+}
+
+impl ConfigHandle {
+    pub(crate) fn new(threads: Arc<RwLock<HashMap<ThreadId, Arc<CodexThread>>>>) -> Self {
+        Self {
+            threads,
+            overrides: Arc::default(),
+        }
+    }
+
+    /// Every setting changed through this handle so far.
+    pub fn overrides(&self) -> ConfigChange {
+        match self.overrides.read() {
+            Ok(overrides) => overrides.clone(),
+            Err(err) => err.into_inner().clone(),
+        }
+    }
+
+    pub async fn set_model(&self, model: impl Into<String>) {
+        self.apply(ConfigChange {
+            model: Some(model.into()),
+            ..Default::default()
+        })
+        .await;
+    }
+
+    pub async fn set_approval_policy(&self, approval_policy: AskForApproval) {
+        self.apply(ConfigChange {
+            approval_policy: Some(approval_policy),
+            ..Default::default()
+        })
+        .await;
+    }
+
+    pub async fn set_sandbox_policy(&self, sandbox_policy: SandboxPolicy) {
+        self.apply(ConfigChange {
+            sandbox_policy: Some(sandbox_policy),
+            ..Default::default()
+        })
+        .await;
+    }
+
+    /// Records `change` for threads started later and submits it to every
+    /// live thread. The outcome for each thread is reported on its event
+    /// stream.
+    pub async fn apply(&self, change: ConfigChange) {
+        if change == ConfigChange::default() {
+            return;
+        }
+        match self.overrides.write() {
+            Ok(mut overrides) => overrides.merge(change.clone()),
+            Err(err) => err.into_inner().merge(change.clone()),
+        }
+
+        let threads: Vec<(ThreadId, Arc<CodexThread>)> = self
+            .threads
+            .read()
+            .await
+            .iter()
+            .map(|(thread_id, thread)| (*thread_id, Arc::clone(thread)))
+            .collect();
+        let op = change.to_op();
+        for (thread_id, thread) in threads {
+            if let Err(err) = thread.submit(op.clone()).await {
+                tracing::warn!("failed to apply config change to thread {thread_id}: {err}");
+            }
+        }
+    }
+
+    /// Applies the recorded settings to the config of a thread about to
+    /// start. Settings its requirements forbid are skipped.
+    pub(crate) fn apply_to_config(&self, config: &mut Config) {
+        let overrides = self.overrides();
+        if let Some(model) = overrides.model {
+            config.model = Some(model);
+        }
+        if let Some(approval_policy) = overrides.approval_policy
+            && let Err(err) = config.approval_policy.set(approval_policy)
+        {
+            tracing::warn!("not applying runtime approval_policy to new thread: {err}");
+        }
+        if let Some(sandbox_policy) = overrides.sandbox_policy
+            && let Err(err) = config.sandbox_policy.set(sandbox_policy)
+        {
+            tracing::warn!("not applying runtime sandbox_policy to new thread: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigBuilder;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn later_changes_override_earlier_ones_per_setting() {
+        let handle = ConfigHandle::new(Arc::default());
+
+        handle.set_model("o3").await;
+        handle.set_approval_policy(AskForApproval::OnRequest).await;
+        handle.set_model("gpt-5").await;
+
+        assert_eq!(
+            handle.overrides(),
+            ConfigChange {
+                model: Some("gpt-5".to_string()),
+                approval_policy: Some(AskForApproval::OnRequest),
+                sandbox_policy: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn recorded_settings_apply_to_new_thread_config() -> anyhow::Result<()> {
+        let codex_home = tempfile::tempdir()?;
+        let mut config = ConfigBuilder::default()
+            .codex_home(codex_home.path().to_path_buf())
+            .build()
+            .await?;
+        let handle = ConfigHandle::new(Arc::default());
+        handle
+            .apply(ConfigChange {
+                model: Some("o3".to_string()),
+                approval_policy: Some(AskForApproval::Never),
+                sandbox_policy: Some(SandboxPolicy::DangerFullAccess),
+            })
+            .await;
+
+        handle.apply_to_config(&mut config);
+
+        assert_eq!(config.model.as_deref(), Some("o3"));
+        assert_eq!(config.approval_policy.value(), AskForApproval::Never);
+        assert_eq!(
+            config.sandbox_policy.get(),
+            &SandboxPolicy::DangerFullAccess
+        );
+        Ok(())
+    }
+}
@@ -3,10 +3,12 @@ use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::SandboxPolicy;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::fmt;
 
 use crate::config::Constrained;
 use crate::config::ConstraintError;
+use crate::config::types::CommandHookConfig;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum RequirementSource {
@@ -43,6 +45,11 @@ impl fmt::Display for RequirementSource {
 pub struct ConfigRequirements {
     pub approval_policy: Constrained<AskForApproval>,
     pub sandbox_policy: Constrained<SandboxPolicy>,
+    /// Tool names the model may not call. A trailing `*` matches any suffix,
+    /// e.g. `mcp__github__*`.
+    pub banned_tools: Vec<String>,
+    /// Hooks that always run and cannot be disabled or replaced by the user.
+    pub required_hooks: BTreeMap<String, CommandHookConfig>,
 }
 
 impl Default for ConfigRequirements {
@@ -50,6 +57,8 @@ impl Default for ConfigRequirements {
         Self {
             approval_policy: Constrained::allow_any_from_default(),
             sandbox_policy: Constrained::allow_any(SandboxPolicy::ReadOnly),
+            banned_tools: Vec::new(),
+            required_hooks: BTreeMap::new(),
         }
     }
 }
@@ -59,6 +68,8 @@ impl Default for ConfigRequirements {
 pub struct ConfigRequirementsToml {
     pub allowed_approval_policies: Option<Vec<AskForApproval>>,
     pub allowed_sandbox_modes: Option<Vec<SandboxModeRequirement>>,
+    pub banned_tools: Option<Vec<String>>,
+    pub required_hooks: Option<BTreeMap<String, CommandHookConfig>>,
 }
 
 /// Value paired with the requirement source it came from, for better error
@@ -87,6 +98,8 @@ impl<T> std::ops::Deref for Sourced<T> {
 pub struct ConfigRequirementsWithSources {
     pub allowed_approval_policies: Option<Sourced<Vec<AskForApproval>>>,
     pub allowed_sandbox_modes: Option<Sourced<Vec<SandboxModeRequirement>>>,
+    pub banned_tools: Option<Sourced<Vec<String>>>,
+    pub required_hooks: Option<Sourced<BTreeMap<String, CommandHookConfig>>>,
 }
 
 impl ConfigRequirementsWithSources {
@@ -114,7 +127,12 @@ impl ConfigRequirementsWithSources {
             self,
             other,
             source,
-            { allowed_approval_policies, allowed_sandbox_modes }
+            {
+                allowed_approval_policies,
+                allowed_sandbox_modes,
+                banned_tools,
+                required_hooks,
+            }
         );
     }
 
@@ -122,10 +140,14 @@ impl ConfigRequirementsWithSources {
         let ConfigRequirementsWithSources {
             allowed_approval_policies,
             allowed_sandbox_modes,
+            banned_tools,
+            required_hooks,
         } = self;
         ConfigRequirementsToml {
             allowed_approval_policies: allowed_approval_policies.map(|sourced| sourced.value),
             allowed_sandbox_modes: allowed_sandbox_modes.map(|sourced| sourced.value),
+            banned_tools: banned_tools.map(|sourced| sourced.value),
+            required_hooks: required_hooks.map(|sourced| sourced.value),
         }
     }
 }
@@ -159,7 +181,10 @@ impl From<SandboxMode> for SandboxModeRequirement {
 
 impl ConfigRequirementsToml {
     pub fn is_empty(&self) -> bool {
-        self.allowed_approval_policies.is_none() && self.allowed_sandbox_modes.is_none()
+        self.allowed_approval_policies.is_none()
+            && self.allowed_sandbox_modes.is_none()
+            && self.banned_tools.is_none()
+            && self.required_hooks.is_none()
     }
 }
 
@@ -170,6 +195,8 @@ impl TryFrom<ConfigRequirementsWithSources> for ConfigRequirements {
         let ConfigRequirementsWithSources {
             allowed_approval_policies,
             allowed_sandbox_modes,
+            banned_tools,
+            required_hooks,
         } = toml;
 
         let approval_policy: Constrained<AskForApproval> = match allowed_approval_policies {
@@ -247,6 +274,12 @@ impl TryFrom<ConfigRequirementsWithSources> for ConfigRequirements {
         Ok(ConfigRequirements {
             approval_policy,
             sandbox_policy,
+            banned_tools: banned_tools
+                .map(|sourced| sourced.value)
+                .unwrap_or_default(),
+            required_hooks: required_hooks
+                .map(|sourced| sourced.value)
+                .unwrap_or_default(),
         })
     }
 }
@@ -264,12 +297,17 @@ mod tests {
         let ConfigRequirementsToml {
             allowed_approval_policies,
             allowed_sandbox_modes,
+            banned_tools,
+            required_hooks,
         } = toml;
         ConfigRequirementsWithSources {
             allowed_approval_policies: allowed_approval_policies
                 .map(|value| Sourced::new(value, RequirementSource::Unknown)),
             allowed_sandbox_modes: allowed_sandbox_modes
                 .map(|value| Sourced::new(value, RequirementSource::Unknown)),
+            banned_tools: banned_tools.map(|value| Sourced::new(value, RequirementSource::Unknown)),
+            required_hooks: required_hooks
+                .map(|value| Sourced::new(value, RequirementSource::Unknown)),
         }
     }
 
@@ -283,12 +321,29 @@ mod tests {
             SandboxModeRequirement::WorkspaceWrite,
             SandboxModeRequirement::DangerFullAccess,
         ];
+        let banned_tools = vec!["shell".to_string()];
+        let required_hooks = BTreeMap::from([(
+            "audit".to_string(),
+            CommandHookConfig {
+                on: vec!["*".to_string()],
+                command: vec!["audit-log".to_string()],
+                timeout_ms: None,
+                priority: 0,
+                blocking: false,
+                enabled: true,
+                only_when_env: None,
+                filter: None,
+                redact: false,
+            },
+        )]);
 
         // Intentionally constructed without `..Default::default()` so adding a new field to
         // `ConfigRequirementsToml` forces this test to be updated.
         let other = ConfigRequirementsToml {
             allowed_approval_policies: Some(allowed_approval_policies.clone()),
             allowed_sandbox_modes: Some(allowed_sandbox_modes.clone()),
+            banned_tools: Some(banned_tools.clone()),
+            required_hooks: Some(required_hooks.clone()),
         };
 
         target.merge_unset_fields(source.clone(), other);
@@ -300,7 +355,9 @@ mod tests {
                     allowed_approval_policies,
                     source.clone()
                 )),
-                allowed_sandbox_modes: Some(Sourced::new(allowed_sandbox_modes, source)),
+                allowed_sandbox_modes: Some(Sourced::new(allowed_sandbox_modes, source.clone())),
+                banned_tools: Some(Sourced::new(banned_tools, source.clone())),
+                required_hooks: Some(Sourced::new(required_hooks, source)),
             }
         );
     }
@@ -328,6 +385,8 @@ mod tests {
                     source_location,
                 )),
                 allowed_sandbox_modes: None,
+                banned_tools: None,
+                required_hooks: None,
             }
         );
         Ok(())
@@ -363,6 +422,8 @@ mod tests {
                     existing_source,
                 )),
                 allowed_sandbox_modes: None,
+                banned_tools: None,
+                required_hooks: None,
             }
         );
         Ok(())
@@ -523,4 +584,41 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn deserialize_banned_tools_and_required_hooks() -> Result<()> {
+        let toml_str = r#"
+            banned_tools = ["shell", "mcp__github__*"]
+
+            [required_hooks.redact]
+            on = ["exec_command_end"]
+            command = ["/usr/local/bin/redact"]
+        "#;
+        let config: ConfigRequirementsToml = from_str(toml_str)?;
+        let requirements: ConfigRequirements = with_unknown_source(config).try_into()?;
+
+        assert_eq!(
+            requirements.banned_tools,
+            vec!["shell".to_string(), "mcp__github__*".to_string()]
+        );
+        assert_eq!(
+            requirements.required_hooks,
+            BTreeMap::from([(
+                "redact".to_string(),
+                CommandHookConfig {
+                    on: vec!["exec_command_end".to_string()],
+                    command: vec!["/usr/local/bin/redact".to_string()],
+                    timeout_ms: None,
+                    priority: 0,
+                    blocking: false,
+                    enabled: true,
+                    only_when_env: None,
+                    filter: None,
+                    redact: false,
+                },
+            )])
+        );
+
+        Ok(())
+    }
 }
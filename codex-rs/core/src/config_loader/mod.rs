@@ -12,6 +12,8 @@ mod tests;
 
 use crate::config::CONFIG_TOML_FILE;
 use crate::config::ConfigToml;
+use crate::config::ProjectConfig;
+use crate::config::find_project;
 use crate::config_loader::config_requirements::ConfigRequirementsWithSources;
 use crate::config_loader::layer_io::LoadedConfigLayers;
 use codex_app_server_protocol::ConfigLayerSource;
@@ -20,6 +22,7 @@ use codex_protocol::protocol::AskForApproval;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use codex_utils_absolute_path::AbsolutePathBufGuard;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::io;
 use std::path::Path;
 use toml::Value as TomlValue;
@@ -29,6 +32,7 @@ pub use config_requirements::ConfigRequirementsToml;
 pub use config_requirements::RequirementSource;
 pub use config_requirements::SandboxModeRequirement;
 pub use merge::merge_toml_values;
+pub use overrides::CONFIG_ENV_PREFIX;
 pub use state::ConfigLayerEntry;
 pub use state::ConfigLayerStack;
 pub use state::ConfigLayerStackOrdering;
@@ -63,6 +67,11 @@ const DEFAULT_PROJECT_ROOT_MARKERS: &[&str] = &[".git"];
 /// - cwd       `${PWD}/config.toml`
 /// - tree      parent directories up to root looking for `./.codex/config.toml`
 /// - repo      `$(git rev-parse --show-toplevel)/.codex/config.toml`
+///
+/// The tree and repo layers are only loaded once the project is trusted in
+/// the system or user config, since a repository's own config can run hooks
+/// and allow commands.
+/// - env       `CODEX_CONFIG_*` environment variables
 /// - runtime   e.g., --config flags, model selector in UI
 ///
 /// (*) Only available on macOS via managed device profiles.
@@ -109,6 +118,7 @@ pub async fn load_config_layers_state(
     .await?;
 
     let mut layers = Vec::<ConfigLayerEntry>::new();
+    let mut untrusted_project_folders = Vec::new();
 
     // Include an entry for the "system" config folder, loading its config.toml,
     // if it exists.
@@ -158,8 +168,22 @@ pub async fn load_config_layers_state(
             .unwrap_or_else(default_project_root_markers);
 
         let project_root = find_project_root(&cwd, &project_root_markers).await?;
-        let project_layers = load_project_layers(&cwd, &project_root).await?;
-        layers.extend(project_layers);
+        if project_is_trusted(&merged_so_far, &cwd) {
+            let project_layers = load_project_layers(&cwd, &project_root).await?;
+            layers.extend(project_layers);
+        } else {
+            untrusted_project_folders = project_dot_codex_folders(&cwd, &project_root).await?;
+        }
+    }
+
+    // Add a layer for `CODEX_CONFIG_*` environment variables, if any are set.
+    let env_vars = std::env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)));
+    if let Some(env_layer) = overrides::build_env_overrides_layer(env_vars) {
+        layers.push(ConfigLayerEntry::new(
+            ConfigLayerSource::Environment,
+            env_layer,
+        ));
     }
 
     // Add a layer for runtime overrides from the CLI or UI, if any exist.
@@ -204,11 +228,24 @@ pub async fn load_config_layers_state(
         ));
     }
 
-    ConfigLayerStack::new(
+    Ok(ConfigLayerStack::new(
         layers,
         config_requirements_toml.clone().try_into()?,
         config_requirements_toml.into_toml(),
-    )
+    )?
+    .with_untrusted_project_folders(untrusted_project_folders))
+}
+
+/// Whether the system and user config mark the project containing `cwd` as
+/// trusted. The project's own config cannot vouch for itself.
+fn project_is_trusted(config: &TomlValue, cwd: &AbsolutePathBuf) -> bool {
+    let Some(projects) = config.get("projects").cloned() else {
+        return false;
+    };
+    let Ok(projects) = projects.try_into::<HashMap<String, ProjectConfig>>() else {
+        return false;
+    };
+    find_project(&projects, cwd.as_path()).is_some_and(|project| project.is_trusted())
 }
 
 /// Attempts to load a config.toml file from `config_toml`.
@@ -472,34 +509,8 @@ async fn load_project_layers(
     cwd: &AbsolutePathBuf,
     project_root: &AbsolutePathBuf,
 ) -> io::Result<Vec<ConfigLayerEntry>> {
-    let mut dirs = cwd
-        .as_path()
-        .ancestors()
-        .scan(false, |done, a| {
-            if *done {
-                None
-            } else {
-                if a == project_root.as_path() {
-                    *done = true;
-                }
-                Some(a)
-            }
-        })
-        .collect::<Vec<_>>();
-    dirs.reverse();
-
     let mut layers = Vec::new();
-    for dir in dirs {
-        let dot_codex = dir.join(".codex");
-        if !tokio::fs::metadata(&dot_codex)
-            .await
-            .map(|meta| meta.is_dir())
-            .unwrap_or(false)
-        {
-            continue;
-        }
-
-        let dot_codex_abs = AbsolutePathBuf::from_absolute_path(&dot_codex)?;
+    for dot_codex_abs in project_dot_codex_folders(cwd, project_root).await? {
         let config_file = dot_codex_abs.join(CONFIG_TOML_FILE)?;
         match tokio::fs::read_to_string(&config_file).await {
             Ok(contents) => {
@@ -548,6 +559,42 @@ async fn load_project_layers(
     Ok(layers)
 }
 
+/// The `.codex/` folders between `project_root` and `cwd`, inclusive, ordered
+/// from `project_root` to `cwd`.
+async fn project_dot_codex_folders(
+    cwd: &AbsolutePathBuf,
+    project_root: &AbsolutePathBuf,
+) -> io::Result<Vec<AbsolutePathBuf>> {
+    let mut dirs = cwd
+        .as_path()
+        .ancestors()
+        .scan(false, |done, a| {
+            if *done {
+                None
+            } else {
+                if a == project_root.as_path() {
+                    *done = true;
+                }
+                Some(a)
+            }
+        })
+        .collect::<Vec<_>>();
+    dirs.reverse();
+
+    let mut folders = Vec::new();
+    for dir in dirs {
+        let dot_codex = dir.join(".codex");
+        if tokio::fs::metadata(&dot_codex)
+            .await
+            .map(|meta| meta.is_dir())
+            .unwrap_or(false)
+        {
+            folders.push(AbsolutePathBuf::from_absolute_path(&dot_codex)?);
+        }
+    }
+    Ok(folders)
+}
+
 /// The legacy mechanism for specifying admin-enforced configuration is to read
 /// from a file like `/etc/codex/managed_config.toml` that has the same
 /// structure as `config.toml` where fields like `approval_policy` can specify
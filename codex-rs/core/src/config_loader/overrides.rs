@@ -12,6 +12,43 @@ pub(super) fn build_cli_overrides_layer(cli_overrides: &[(String, TomlValue)]) -
     root
 }
 
+/// Prefix of the environment variables that override config values.
+pub const CONFIG_ENV_PREFIX: &str = "CODEX_CONFIG_";
+
+/// Builds the layer for `CODEX_CONFIG_*` variables, or `None` if none are set.
+/// The rest of the name is the key path, lowercased, with `__` between
+/// segments: `CODEX_CONFIG_TUI__THEME=light` sets `tui.theme`. Values are
+/// parsed as TOML, as with `-c`, and fall back to a plain string.
+pub(super) fn build_env_overrides_layer(
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Option<TomlValue> {
+    let mut overrides: Vec<(String, TomlValue)> = Vec::new();
+    for (name, raw) in vars {
+        let Some(key) = name.strip_prefix(CONFIG_ENV_PREFIX) else {
+            continue;
+        };
+        let segments: Vec<String> = key.split("__").map(str::to_ascii_lowercase).collect();
+        if segments.iter().any(String::is_empty) {
+            tracing::warn!("ignoring {name}: it does not name a config key");
+            continue;
+        }
+        let value = parse_toml_value(&raw).unwrap_or(TomlValue::String(raw));
+        overrides.push((segments.join("."), value));
+    }
+    if overrides.is_empty() {
+        return None;
+    }
+    // Apply in a stable order so that the layer does not depend on how the
+    // environment happens to be ordered.
+    overrides.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Some(build_cli_overrides_layer(&overrides))
+}
+
+fn parse_toml_value(raw: &str) -> Option<TomlValue> {
+    let table: toml::Table = toml::from_str(&format!("_x_ = {raw}")).ok()?;
+    table.get("_x_").cloned()
+}
+
 /// Apply a single dotted-path override onto a TOML value.
 fn apply_toml_override(root: &mut TomlValue, path: &str, value: TomlValue) {
     use toml::value::Table;
@@ -1,3 +1,4 @@
+use crate::config::CONFIG_TOML_FILE;
 use crate::config_loader::ConfigRequirements;
 use crate::config_loader::ConfigRequirementsToml;
 
@@ -55,6 +56,23 @@ impl ConfigLayerEntry {
         }
     }
 
+    /// The `config.toml` this layer was read from, if it came from a file.
+    pub fn config_file(&self) -> Option<PathBuf> {
+        match &self.name {
+            ConfigLayerSource::System { file } | ConfigLayerSource::User { file } => {
+                Some(file.to_path_buf())
+            }
+            ConfigLayerSource::Project { dot_codex_folder } => {
+                Some(dot_codex_folder.as_path().join(CONFIG_TOML_FILE))
+            }
+            ConfigLayerSource::Mdm { .. }
+            | ConfigLayerSource::Environment
+            | ConfigLayerSource::SessionFlags
+            | ConfigLayerSource::LegacyManagedConfigTomlFromFile { .. }
+            | ConfigLayerSource::LegacyManagedConfigTomlFromMdm => None,
+        }
+    }
+
     // Get the `.codex/` folder associated with this config layer, if any.
     pub fn config_folder(&self) -> Option<AbsolutePathBuf> {
         match &self.name {
@@ -62,6 +80,7 @@ impl ConfigLayerEntry {
             ConfigLayerSource::System { file } => file.parent(),
             ConfigLayerSource::User { file } => file.parent(),
             ConfigLayerSource::Project { dot_codex_folder } => Some(dot_codex_folder.clone()),
+            ConfigLayerSource::Environment => None,
             ConfigLayerSource::SessionFlags => None,
             ConfigLayerSource::LegacyManagedConfigTomlFromFile { .. } => None,
             ConfigLayerSource::LegacyManagedConfigTomlFromMdm => None,
@@ -92,6 +111,10 @@ pub struct ConfigLayerStack {
     /// sources. This preserves the original allow-lists so they can be
     /// surfaced via APIs.
     requirements_toml: ConfigRequirementsToml,
+
+    /// Project `.codex/` folders that were not loaded because the project is
+    /// not trusted.
+    untrusted_project_folders: Vec<AbsolutePathBuf>,
 }
 
 impl ConfigLayerStack {
@@ -106,9 +129,21 @@ impl ConfigLayerStack {
             user_layer_index,
             requirements,
             requirements_toml,
+            untrusted_project_folders: Vec::new(),
         })
     }
 
+    pub(crate) fn with_untrusted_project_folders(mut self, folders: Vec<AbsolutePathBuf>) -> Self {
+        self.untrusted_project_folders = folders;
+        self
+    }
+
+    /// Project `.codex/` folders left out of the stack because the project is
+    /// not trusted.
+    pub fn untrusted_project_folders(&self) -> &[AbsolutePathBuf] {
+        &self.untrusted_project_folders
+    }
+
     /// Returns the user config layer, if any.
     pub fn get_user_layer(&self) -> Option<&ConfigLayerEntry> {
         self.user_layer_index
@@ -144,6 +179,7 @@ impl ConfigLayerStack {
                     user_layer_index: self.user_layer_index,
                     requirements: self.requirements.clone(),
                     requirements_toml: self.requirements_toml.clone(),
+                    untrusted_project_folders: self.untrusted_project_folders.clone(),
                 }
             }
             None => {
@@ -165,6 +201,7 @@ impl ConfigLayerStack {
                     user_layer_index: Some(user_layer_index),
                     requirements: self.requirements.clone(),
                     requirements_toml: self.requirements_toml.clone(),
+                    untrusted_project_folders: self.untrusted_project_folders.clone(),
                 }
             }
         }
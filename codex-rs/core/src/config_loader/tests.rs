@@ -3,19 +3,27 @@ use super::load_config_layers_state;
 use crate::config::CONFIG_TOML_FILE;
 use crate::config::ConfigBuilder;
 use crate::config::ConfigOverrides;
+use crate::config::set_project_trust_level;
 use crate::config_loader::ConfigLayerEntry;
 use crate::config_loader::ConfigRequirements;
 use crate::config_loader::config_requirements::ConfigRequirementsWithSources;
 use crate::config_loader::fingerprint::version_for_toml;
 use crate::config_loader::load_requirements_toml;
+use codex_protocol::config_types::TrustLevel;
 use codex_protocol::protocol::AskForApproval;
 #[cfg(target_os = "macos")]
 use codex_protocol::protocol::SandboxPolicy;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use pretty_assertions::assert_eq;
+use std::path::Path;
 use tempfile::tempdir;
 use toml::Value as TomlValue;
 
+/// Project layers are only loaded for trusted projects.
+fn trust_project(codex_home: &Path, project: &Path) -> std::io::Result<()> {
+    set_project_trust_level(codex_home, project, TrustLevel::Trusted).map_err(std::io::Error::other)
+}
+
 #[tokio::test]
 async fn merges_managed_config_layer_on_top() {
     let tmp = tempdir().expect("tempdir");
@@ -365,6 +373,7 @@ async fn project_layers_prefer_closest_cwd() -> std::io::Result<()> {
 
     let codex_home = tmp.path().join("home");
     tokio::fs::create_dir_all(&codex_home).await?;
+    trust_project(&codex_home, &nested)?;
     let cwd = AbsolutePathBuf::from_absolute_path(&nested)?;
     let layers = load_config_layers_state(
         &codex_home,
@@ -429,6 +438,7 @@ experimental_instructions_file = "child.txt"
 
     let codex_home = tmp.path().join("home");
     tokio::fs::create_dir_all(&codex_home).await?;
+    trust_project(&codex_home, &nested)?;
 
     let config = ConfigBuilder::default()
         .codex_home(codex_home)
@@ -458,6 +468,7 @@ async fn project_layer_is_added_when_dot_codex_exists_without_config_toml() -> s
 
     let codex_home = tmp.path().join("home");
     tokio::fs::create_dir_all(&codex_home).await?;
+    trust_project(&codex_home, &nested)?;
     let cwd = AbsolutePathBuf::from_absolute_path(&nested)?;
     let layers = load_config_layers_state(
         &codex_home,
@@ -486,6 +497,63 @@ async fn project_layer_is_added_when_dot_codex_exists_without_config_toml() -> s
     Ok(())
 }
 
+#[tokio::test]
+async fn project_layers_are_skipped_until_the_project_is_trusted() -> std::io::Result<()> {
+    let tmp = tempdir()?;
+    let project_root = tmp.path().join("project");
+    tokio::fs::create_dir_all(project_root.join(".codex")).await?;
+    tokio::fs::write(project_root.join(".git"), "gitdir: here").await?;
+    // Not even parsed while the project is untrusted.
+    tokio::fs::write(
+        project_root.join(".codex").join(CONFIG_TOML_FILE),
+        "[hooks\nbroken",
+    )
+    .await?;
+
+    let codex_home = tmp.path().join("home");
+    tokio::fs::create_dir_all(&codex_home).await?;
+    let load = |codex_home: &Path| {
+        let cwd = AbsolutePathBuf::from_absolute_path(&project_root);
+        let codex_home = codex_home.to_path_buf();
+        async move {
+            load_config_layers_state(
+                &codex_home,
+                Some(cwd?),
+                &[] as &[(String, TomlValue)],
+                LoaderOverrides::default(),
+            )
+            .await
+        }
+    };
+
+    let layers = load(&codex_home).await?;
+    assert!(
+        layers
+            .layers_high_to_low()
+            .iter()
+            .all(|layer| !matches!(layer.name, super::ConfigLayerSource::Project { .. }))
+    );
+    assert_eq!(
+        layers.untrusted_project_folders(),
+        [AbsolutePathBuf::from_absolute_path(
+            project_root.join(".codex")
+        )?]
+    );
+
+    // A project the user keeps read-only is not trusted with its config either.
+    set_project_trust_level(&codex_home, &project_root, TrustLevel::ReadOnly)
+        .map_err(std::io::Error::other)?;
+    let layers = load(&codex_home).await?;
+    assert_eq!(layers.untrusted_project_folders().len(), 1);
+
+    trust_project(&codex_home, &project_root)?;
+    let err = load(&codex_home)
+        .await
+        .expect_err("the project config is now parsed");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    Ok(())
+}
+
 #[tokio::test]
 async fn project_root_markers_supports_alternate_markers() -> std::io::Result<()> {
     let tmp = tempdir()?;
@@ -514,6 +582,7 @@ project_root_markers = [".hg"]
 "#,
     )
     .await?;
+    trust_project(&codex_home, &nested)?;
 
     let cwd = AbsolutePathBuf::from_absolute_path(&nested)?;
     let layers = load_config_layers_state(
@@ -548,3 +617,69 @@ project_root_markers = [".hg"]
 
     Ok(())
 }
+
+#[test]
+fn env_overrides_map_variable_names_to_key_paths() {
+    let layer = super::overrides::build_env_overrides_layer([
+        ("CODEX_CONFIG_MODEL".to_string(), "o3".to_string()),
+        ("CODEX_CONFIG_TUI__THEME".to_string(), "light".to_string()),
+        (
+            "CODEX_CONFIG_SANDBOX_WORKSPACE_WRITE__NETWORK_ACCESS".to_string(),
+            "true".to_string(),
+        ),
+        ("CODEX_CONFIG___BROKEN".to_string(), "x".to_string()),
+        ("CODEX_HOME".to_string(), "/tmp/codex".to_string()),
+    ])
+    .expect("layer");
+
+    let expected: TomlValue = toml::from_str(
+        r#"
+model = "o3"
+
+[tui]
+theme = "light"
+
+[sandbox_workspace_write]
+network_access = true
+"#,
+    )
+    .expect("valid toml");
+    assert_eq!(layer, expected);
+
+    assert_eq!(
+        super::overrides::build_env_overrides_layer([(
+            "CODEX_HOME".to_string(),
+            "/tmp/codex".to_string()
+        )]),
+        None
+    );
+}
+
+#[test]
+fn env_layer_sits_between_project_and_session_flags() -> std::io::Result<()> {
+    let tmp = tempdir()?;
+    let dot_codex_folder = AbsolutePathBuf::try_from(tmp.path().join(".codex"))?;
+    let layer = |name| ConfigLayerEntry::new(name, TomlValue::Table(toml::map::Map::new()));
+
+    let stack = super::ConfigLayerStack::new(
+        vec![
+            layer(super::ConfigLayerSource::Project { dot_codex_folder }),
+            layer(super::ConfigLayerSource::Environment),
+            layer(super::ConfigLayerSource::SessionFlags),
+        ],
+        ConfigRequirements::default(),
+        Default::default(),
+    );
+    assert!(stack.is_ok());
+
+    let misordered = super::ConfigLayerStack::new(
+        vec![
+            layer(super::ConfigLayerSource::SessionFlags),
+            layer(super::ConfigLayerSource::Environment),
+        ],
+        ConfigRequirements::default(),
+        Default::default(),
+    );
+    assert!(misordered.is_err());
+    Ok(())
+}
@@ -0,0 +1,370 @@
+//! Per-source token budgets applied to the prompt before each model request.
+//!
+//! Newer content wins: each source keeps its newest items whole while they
+//! fit, the item that crosses the budget is cut down to what is left, and
+//! older items are reduced to a truncation marker. Conversation history is
+//! trimmed by whole turns instead: the oldest turns that do not fit are left
+//! out together with their tool calls and outputs, so the model never sees a
+//! call without its output or an answer without its question. Only the
+//! request is trimmed; the recorded history is untouched, so raising a budget
+//! later brings the content back.
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::FunctionCallOutputPayload;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ContextSource;
+use codex_protocol::protocol::DroppedContext;
+
+use crate::config::types::ContextBudgetConfig;
+use crate::context_manager::is_user_turn_boundary;
+use crate::context_manager::normalize;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::approx_token_count;
+use crate::truncate::truncate_function_output_items_with_policy;
+use crate::truncate::truncate_text;
+use crate::user_instructions::McpResourceContents;
+use crate::user_instructions::SkillInstructions;
+use crate::user_instructions::UserInstructions;
+
+/// Trims `items` so that every budgeted source fits its limit and reports
+/// what was removed, one entry per source that had to be trimmed.
+pub(crate) fn apply_context_budget(
+    items: &mut Vec<ResponseItem>,
+    budget: &ContextBudgetConfig,
+) -> Vec<DroppedContext> {
+    [
+        (ContextSource::ProjectDocs, budget.project_docs),
+        (ContextSource::Pinned, budget.pinned),
+        (ContextSource::ToolOutputs, budget.tool_outputs),
+        (ContextSource::History, budget.history),
+    ]
+    .into_iter()
+    .filter_map(|(source, limit)| trim_source(items, source, limit?))
+    .collect()
+}
+
+/// Budget for retrying a request the model rejected as too long: tool outputs
+/// and conversation history get half of what they take up in `items`.
+pub(crate) fn repair_budget(
+    items: &[ResponseItem],
+    budget: &ContextBudgetConfig,
+) -> ContextBudgetConfig {
+    let halved = |source| {
+        let used: usize = items
+            .iter()
+            .filter(|item| source_of(item) == Some(source))
+            .map(item_tokens)
+            .sum();
+        Some(used / 2)
+    };
+    ContextBudgetConfig {
+        tool_outputs: halved(ContextSource::ToolOutputs),
+        history: halved(ContextSource::History),
+        ..*budget
+    }
+}
+
+fn trim_source(
+    items: &mut Vec<ResponseItem>,
+    source: ContextSource,
+    limit: usize,
+) -> Option<DroppedContext> {
+    if source == ContextSource::History {
+        return trim_history(items, limit);
+    }
+    let mut remaining = limit;
+    let mut dropped = DroppedContext {
+        source,
+        items: 0,
+        tokens: 0,
+    };
+
+    for idx in (0..items.len()).rev() {
+        if source_of(&items[idx]) != Some(source) {
+            continue;
+        }
+        let tokens = item_tokens(&items[idx]);
+        if tokens <= remaining {
+            remaining -= tokens;
+            continue;
+        }
+
+        items[idx] = truncate_item(&items[idx], remaining);
+        let kept_tokens = item_tokens(&items[idx]);
+        remaining = 0;
+        dropped.items += 1;
+        dropped.tokens += u64::try_from(tokens.saturating_sub(kept_tokens)).unwrap_or(u64::MAX);
+    }
+    (dropped.items > 0).then_some(dropped)
+}
+
+/// Keeps the newest turns whose messages fit `limit` and leaves out every
+/// older turn whole. The turn being answered is always kept, and project docs
+/// and pinned items inside a dropped turn stay, since they have budgets of
+/// their own.
+fn trim_history(items: &mut Vec<ResponseItem>, limit: usize) -> Option<DroppedContext> {
+    let turn_starts: Vec<usize> = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| is_user_turn_boundary(item))
+        .map(|(idx, _)| idx)
+        .collect();
+    let (&current_turn_start, earlier_turn_starts) = turn_starts.split_last()?;
+    let history_tokens = |turn: &[ResponseItem]| -> usize {
+        turn.iter()
+            .filter(|item| source_of(item) == Some(ContextSource::History))
+            .map(item_tokens)
+            .sum()
+    };
+
+    let mut remaining = limit.saturating_sub(history_tokens(&items[current_turn_start..]));
+    let mut turn_end = current_turn_start;
+    let mut dropped_end = None;
+    for &turn_start in earlier_turn_starts.iter().rev() {
+        let tokens = history_tokens(&items[turn_start..turn_end]);
+        if tokens > remaining {
+            dropped_end = Some(turn_end);
+            break;
+        }
+        remaining -= tokens;
+        turn_end = turn_start;
+    }
+    let dropped_range = earlier_turn_starts.first().copied()?..dropped_end?;
+
+    let tokens_before: usize = items.iter().map(item_tokens).sum();
+    let items_before = items.len();
+    let mut removed = Vec::new();
+    for (idx, item) in std::mem::take(items).into_iter().enumerate() {
+        let budgeted_elsewhere = matches!(
+            source_of(&item),
+            Some(ContextSource::ProjectDocs | ContextSource::Pinned)
+        );
+        if dropped_range.contains(&idx) && !budgeted_elsewhere {
+            removed.push(item);
+        } else {
+            items.push(item);
+        }
+    }
+    // A call and its output can straddle a turn boundary; drop the half that
+    // would otherwise be left behind.
+    for item in &removed {
+        normalize::remove_corresponding_for(items, item);
+    }
+
+    let tokens_after: usize = items.iter().map(item_tokens).sum();
+    Some(DroppedContext {
+        source: ContextSource::History,
+        items: u32::try_from(items_before - items.len()).unwrap_or(u32::MAX),
+        tokens: u64::try_from(tokens_before.saturating_sub(tokens_after)).unwrap_or(u64::MAX),
+    })
+}
+
+fn source_of(item: &ResponseItem) -> Option<ContextSource> {
+    match item {
+        ResponseItem::Message { role, content, .. } if role == "user" => {
+            if UserInstructions::is_user_instructions(content) {
+                Some(ContextSource::ProjectDocs)
+            } else if SkillInstructions::is_skill_instructions(content)
+                || McpResourceContents::is_mcp_resource_contents(content)
+            {
+                Some(ContextSource::Pinned)
+            } else if is_user_turn_boundary(item) {
+                Some(ContextSource::History)
+            } else {
+                None
+            }
+        }
+        ResponseItem::Message { role, .. } if role == "assistant" => Some(ContextSource::History),
+        ResponseItem::FunctionCallOutput { .. } | ResponseItem::CustomToolCallOutput { .. } => {
+            Some(ContextSource::ToolOutputs)
+        }
+        _ => None,
+    }
+}
+
+fn item_tokens(item: &ResponseItem) -> usize {
+    let serialized = serde_json::to_string(item).unwrap_or_default();
+    approx_token_count(&serialized)
+}
+
+fn truncate_item(item: &ResponseItem, max_tokens: usize) -> ResponseItem {
+    let policy = TruncationPolicy::Tokens(max_tokens);
+    match item {
+        ResponseItem::Message { id, role, content } => ResponseItem::Message {
+            id: id.clone(),
+            role: role.clone(),
+            content: content
+                .iter()
+                .map(|content_item| match content_item {
+                    ContentItem::InputText { text } => ContentItem::InputText {
+                        text: truncate_text(text, policy),
+                    },
+                    ContentItem::OutputText { text } => ContentItem::OutputText {
+                        text: truncate_text(text, policy),
+                    },
+                    ContentItem::InputImage { .. } => content_item.clone(),
+                })
+                .collect(),
+        },
+        ResponseItem::FunctionCallOutput { call_id, output } => ResponseItem::FunctionCallOutput {
+            call_id: call_id.clone(),
+            output: FunctionCallOutputPayload {
+                content: truncate_text(&output.content, policy),
+                content_items: output
+                    .content_items
+                    .as_ref()
+                    .map(|items| truncate_function_output_items_with_policy(items, policy)),
+                success: output.success,
+            },
+        },
+        ResponseItem::CustomToolCallOutput { call_id, output } => {
+            ResponseItem::CustomToolCallOutput {
+                call_id: call_id.clone(),
+                output: truncate_text(output, policy),
+            }
+        }
+        _ => item.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn message(role: &str, text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    fn tool_output(call_id: &str, content: &str) -> ResponseItem {
+        ResponseItem::FunctionCallOutput {
+            call_id: call_id.to_string(),
+            output: FunctionCallOutputPayload {
+                content: content.to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn oversized_tool_outputs_are_trimmed_oldest_first() {
+        let docs = message("user", "# AGENTS.md instructions for /repo\n\nUse tabs.");
+        let mut items = vec![
+            docs.clone(),
+            message("user", "run the tests"),
+            tool_output("old", &"a".repeat(4_000)),
+            tool_output("new", "ok"),
+        ];
+        let budget = ContextBudgetConfig {
+            tool_outputs: Some(100),
+            project_docs: Some(1_000),
+            ..Default::default()
+        };
+
+        let dropped = apply_context_budget(&mut items, &budget);
+
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].source, ContextSource::ToolOutputs);
+        assert_eq!(dropped[0].items, 1);
+        assert!(dropped[0].tokens > 900, "dropped {:?}", dropped[0]);
+        assert_eq!(items[0], docs);
+        assert_eq!(items[3], tool_output("new", "ok"));
+        let ResponseItem::FunctionCallOutput { output, .. } = &items[2] else {
+            panic!("expected the old output to stay in place");
+        };
+        assert!(output.content.contains("tokens truncated"));
+        assert!(output.content.len() < 500, "kept {:?}", output.content);
+    }
+
+    #[test]
+    fn history_drops_old_messages_but_keeps_the_current_turn() {
+        let current = message("user", &"b".repeat(2_000));
+        let mut items = vec![
+            message("user", "first question"),
+            message("assistant", "first answer"),
+            current.clone(),
+        ];
+        let budget = ContextBudgetConfig {
+            history: Some(10),
+            ..Default::default()
+        };
+
+        let dropped = apply_context_budget(&mut items, &budget);
+
+        assert_eq!(items, vec![current]);
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].source, ContextSource::History);
+        assert_eq!(dropped[0].items, 2);
+    }
+
+    #[test]
+    fn history_drops_whole_turns_with_their_tool_calls() {
+        let call = ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: "{}".to_string(),
+            call_id: "old".to_string(),
+        };
+        let recent = vec![
+            message("user", "second question"),
+            message("assistant", "second answer"),
+        ];
+        let current = message("user", "third question");
+        let mut items = vec![
+            message("user", &"a".repeat(2_000)),
+            call,
+            tool_output("old", "ok"),
+            message("assistant", "first answer"),
+        ];
+        items.extend(recent.clone());
+        items.push(current.clone());
+        let budget = ContextBudgetConfig {
+            history: Some(100),
+            ..Default::default()
+        };
+
+        let dropped = apply_context_budget(&mut items, &budget);
+
+        let mut expected = recent;
+        expected.push(current);
+        assert_eq!(items, expected);
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].source, ContextSource::History);
+        assert_eq!(dropped[0].items, 4);
+    }
+
+    #[test]
+    fn repair_budget_halves_tool_outputs_and_history() {
+        let mut items = vec![
+            message("user", "first question"),
+            message("assistant", &"c".repeat(2_000)),
+            message("user", "run the tests"),
+            tool_output("old", &"a".repeat(4_000)),
+            tool_output("new", &"a".repeat(4_000)),
+        ];
+        let budget = ContextBudgetConfig {
+            project_docs: Some(1_000),
+            ..Default::default()
+        };
+
+        let repaired = repair_budget(&items, &budget);
+        let dropped = apply_context_budget(&mut items, &repaired);
+
+        assert_eq!(repaired.project_docs, Some(1_000));
+        assert_eq!(
+            dropped
+                .iter()
+                .map(|dropped| dropped.source)
+                .collect::<Vec<_>>(),
+            vec![ContextSource::ToolOutputs, ContextSource::History]
+        );
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0], message("user", "run the tests"));
+    }
+}
@@ -5,6 +5,7 @@ use crate::truncate::approx_token_count;
 use crate::truncate::approx_tokens_from_byte_count;
 use crate::truncate::truncate_function_output_items_with_policy;
 use crate::truncate::truncate_text;
+use crate::user_instructions::McpResourceContents;
 use crate::user_instructions::SkillInstructions;
 use crate::user_instructions::UserInstructions;
 use crate::user_shell_command::is_user_shell_command_text;
@@ -12,6 +13,8 @@ use codex_protocol::models::ContentItem;
 use codex_protocol::models::FunctionCallOutputContentItem;
 use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::CHANGED_FILES_OPEN_TAG;
+use codex_protocol::protocol::ENVIRONMENT_FINGERPRINT_OPEN_TAG;
 use codex_protocol::protocol::TokenUsage;
 use codex_protocol::protocol::TokenUsageInfo;
 use std::ops::Deref;
@@ -320,6 +323,8 @@ fn is_session_prefix(text: &str) -> bool {
     let trimmed = text.trim_start();
     let lowered = trimmed.to_ascii_lowercase();
     lowered.starts_with("<environment_context>")
+        || lowered.starts_with(ENVIRONMENT_FINGERPRINT_OPEN_TAG)
+        || lowered.starts_with(CHANGED_FILES_OPEN_TAG)
 }
 
 pub(crate) fn is_user_turn_boundary(item: &ResponseItem) -> bool {
@@ -333,6 +338,7 @@ pub(crate) fn is_user_turn_boundary(item: &ResponseItem) -> bool {
 
     if UserInstructions::is_user_instructions(content)
         || SkillInstructions::is_skill_instructions(content)
+        || McpResourceContents::is_mcp_resource_contents(content)
     {
         return false;
     }
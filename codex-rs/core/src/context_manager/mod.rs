@@ -1,5 +1,8 @@
+mod budget;
 mod history;
 mod normalize;
 
+pub(crate) use budget::apply_context_budget;
+pub(crate) use budget::repair_budget;
 pub(crate) use history::ContextManager;
 pub(crate) use history::is_user_turn_boundary;
@@ -0,0 +1,339 @@
+//! Token, cost and latency accounting for turns and sessions.
+//!
+//! Providers report token counts but not prices, so cost is an estimate: the
+//! tokens of a turn times the list prices of its model, taken from a built-in
+//! table that `[model_pricing]` overrides and extends. Models with no known
+//! price are counted without a cost.
+
+use std::collections::BTreeMap;
+
+use codex_protocol::protocol::TokenUsage;
+use codex_protocol::protocol::TurnTiming;
+
+use crate::config::types::ModelPricing;
+
+/// How a built-in price entry matches model slugs.
+#[derive(Clone, Copy)]
+enum SlugMatch {
+    /// The slug itself or a dated snapshot of it (`o3-2025-04-16`), so that
+    /// differently priced variants such as `o3-pro` do not match.
+    Exact,
+    /// Every slug starting with it, for families priced alike
+    /// (`gpt-5.1-codex`, `gpt-5.1-codex-max`, ...).
+    Prefix,
+}
+
+/// List prices in USD per million tokens: model slug, how it matches, input,
+/// cached input, output. An exact match wins, then the longest prefix.
+const BUILT_IN_PRICING: &[(&str, SlugMatch, f64, f64, f64)] = &[
+    ("gpt-5.2-pro", SlugMatch::Exact, 21.0, 21.0, 168.0),
+    ("gpt-5.2", SlugMatch::Prefix, 1.75, 0.175, 14.0),
+    ("gpt-5.1-codex-mini", SlugMatch::Prefix, 0.25, 0.025, 2.0),
+    ("gpt-5-codex-mini", SlugMatch::Prefix, 0.25, 0.025, 2.0),
+    ("gpt-5-pro", SlugMatch::Exact, 15.0, 15.0, 120.0),
+    ("gpt-5-mini", SlugMatch::Prefix, 0.25, 0.025, 2.0),
+    ("gpt-5-nano", SlugMatch::Prefix, 0.05, 0.005, 0.4),
+    ("gpt-5", SlugMatch::Prefix, 1.25, 0.125, 10.0),
+    ("gpt-4.1-mini", SlugMatch::Exact, 0.4, 0.1, 1.6),
+    ("gpt-4.1-nano", SlugMatch::Exact, 0.1, 0.025, 0.4),
+    ("gpt-4.1", SlugMatch::Exact, 2.0, 0.5, 8.0),
+    ("o4-mini", SlugMatch::Exact, 1.1, 0.275, 4.4),
+    ("o3-pro", SlugMatch::Exact, 20.0, 20.0, 80.0),
+    ("o3-mini", SlugMatch::Exact, 1.1, 0.55, 4.4),
+    ("o3", SlugMatch::Exact, 2.0, 0.5, 8.0),
+    ("codex-mini-latest", SlugMatch::Exact, 1.5, 0.375, 6.0),
+];
+
+/// Prices of `model`: the longest key of `overrides` that is a prefix of
+/// the slug (a key equal to the slug being the longest), else the built-in
+/// table.
+pub fn pricing_for(
+    model: &str,
+    overrides: &BTreeMap<String, ModelPricing>,
+) -> Option<ModelPricing> {
+    let configured = overrides
+        .iter()
+        .filter(|(prefix, _)| model.starts_with(prefix.as_str()))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, pricing)| *pricing);
+    configured.or_else(|| {
+        let exact = BUILT_IN_PRICING
+            .iter()
+            .find(|(slug, how, ..)| matches!(how, SlugMatch::Exact) && is_snapshot_of(model, slug));
+        let prefix = || {
+            BUILT_IN_PRICING
+                .iter()
+                .filter(|(slug, how, ..)| {
+                    matches!(how, SlugMatch::Prefix) && model.starts_with(slug)
+                })
+                .max_by_key(|(slug, ..)| slug.len())
+        };
+        exact
+            .or_else(prefix)
+            .map(|&(_, _, input, cached_input, output)| ModelPricing {
+                input,
+                cached_input: Some(cached_input),
+                output,
+            })
+    })
+}
+
+/// Whether `model` is `slug` or `slug` with a `-YYYY-MM-DD` snapshot date.
+fn is_snapshot_of(model: &str, slug: &str) -> bool {
+    let Some(rest) = model.strip_prefix(slug) else {
+        return false;
+    };
+    rest.is_empty()
+        || rest.strip_prefix('-').is_some_and(|date| {
+            date.len() == 10
+                && date.char_indices().all(|(i, c)| {
+                    if i == 4 || i == 7 {
+                        c == '-'
+                    } else {
+                        c.is_ascii_digit()
+                    }
+                })
+        })
+}
+
+/// Estimated cost of `usage` in USD.
+pub fn estimate_cost(usage: &TokenUsage, pricing: &ModelPricing) -> f64 {
+    let cached_price = pricing.cached_input.unwrap_or(pricing.input);
+    (usage.non_cached_input() as f64 * pricing.input
+        + usage.cached_input() as f64 * cached_price
+        + usage.output_tokens.max(0) as f64 * pricing.output)
+        / 1_000_000.0
+}
+
+/// Tokens, estimated cost and latency of one turn.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TurnStats {
+    pub usage: TokenUsage,
+    /// `None` when the model has no known price.
+    pub cost_usd: Option<f64>,
+    pub timing: TurnTiming,
+}
+
+impl TurnStats {
+    /// Stats of a turn whose token totals went from `before` to `after`.
+    pub fn new(
+        before: &TokenUsage,
+        after: &TokenUsage,
+        timing: TurnTiming,
+        pricing: Option<ModelPricing>,
+    ) -> Self {
+        let usage = TokenUsage {
+            input_tokens: (after.input_tokens - before.input_tokens).max(0),
+            cached_input_tokens: (after.cached_input_tokens - before.cached_input_tokens).max(0),
+            output_tokens: (after.output_tokens - before.output_tokens).max(0),
+            reasoning_output_tokens: (after.reasoning_output_tokens
+                - before.reasoning_output_tokens)
+                .max(0),
+            total_tokens: (after.total_tokens - before.total_tokens).max(0),
+        };
+        let cost_usd = pricing.map(|pricing| estimate_cost(&usage, &pricing));
+        Self {
+            usage,
+            cost_usd,
+            timing,
+        }
+    }
+}
+
+/// Running totals over the turns of a session.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionStats {
+    pub turns: u32,
+    pub usage: TokenUsage,
+    /// Estimated cost of the turns whose model has a known price.
+    pub cost_usd: f64,
+    /// Turns left out of `cost_usd` for lack of a price.
+    pub unpriced_turns: u32,
+    /// Turns that were interrupted, stopped or otherwise aborted; also
+    /// counted in `turns`.
+    pub aborted_turns: u32,
+    pub total_ms: u64,
+    pub model_ms: u64,
+    pub tool_ms: u64,
+    pub slowest_turn_ms: u64,
+    first_token_ms: u64,
+    first_token_turns: u32,
+}
+
+impl SessionStats {
+    pub fn record(&mut self, turn: &TurnStats) {
+        self.turns += 1;
+        self.usage.add_assign(&turn.usage);
+        match turn.cost_usd {
+            Some(cost) => self.cost_usd += cost,
+            None => self.unpriced_turns += 1,
+        }
+        self.total_ms += turn.timing.total_ms;
+        self.model_ms += turn.timing.model_ms;
+        self.tool_ms += turn.timing.tool_ms;
+        self.slowest_turn_ms = self.slowest_turn_ms.max(turn.timing.total_ms);
+        if let Some(first_token_ms) = turn.timing.first_token_ms {
+            self.first_token_ms += first_token_ms;
+            self.first_token_turns += 1;
+        }
+    }
+
+    /// Records a turn that ended without completing. It used tokens all the
+    /// same, so they count towards the totals.
+    pub fn record_aborted(&mut self, turn: &TurnStats) {
+        self.record(turn);
+        self.aborted_turns += 1;
+    }
+
+    pub fn average_turn_ms(&self) -> Option<u64> {
+        (self.turns > 0).then(|| self.total_ms / u64::from(self.turns))
+    }
+
+    pub fn average_first_token_ms(&self) -> Option<u64> {
+        (self.first_token_turns > 0)
+            .then(|| self.first_token_ms / u64::from(self.first_token_turns))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn usage(input: i64, cached: i64, output: i64) -> TokenUsage {
+        TokenUsage {
+            input_tokens: input,
+            cached_input_tokens: cached,
+            output_tokens: output,
+            reasoning_output_tokens: 0,
+            total_tokens: input + output,
+        }
+    }
+
+    #[test]
+    fn longest_prefix_and_overrides_pick_the_price() {
+        let none = BTreeMap::new();
+        assert_eq!(
+            pricing_for("gpt-5.1-codex", &none).map(|p| p.input),
+            Some(1.25)
+        );
+        assert_eq!(
+            pricing_for("gpt-5-mini", &none).map(|p| p.input),
+            Some(0.25)
+        );
+        assert_eq!(
+            pricing_for("gpt-5.2-codex", &none).map(|p| p.output),
+            Some(14.0)
+        );
+        assert_eq!(pricing_for("llama3", &none), None);
+
+        // Variants priced differently from their base model are not matched
+        // by it; dated snapshots are.
+        assert_eq!(pricing_for("o3-pro", &none).map(|p| p.input), Some(20.0));
+        assert_eq!(
+            pricing_for("o3-2025-04-16", &none).map(|p| p.input),
+            Some(2.0)
+        );
+        assert_eq!(pricing_for("o3-deep-research", &none), None);
+        assert_eq!(
+            pricing_for("gpt-5-pro", &none).map(|p| p.output),
+            Some(120.0)
+        );
+
+        let overrides = BTreeMap::from([(
+            "gpt-5.1".to_string(),
+            ModelPricing {
+                input: 1.0,
+                cached_input: None,
+                output: 2.0,
+            },
+        )]);
+        assert_eq!(
+            pricing_for("gpt-5.1-codex", &overrides).map(|p| p.input),
+            Some(1.0)
+        );
+        assert_eq!(
+            pricing_for("gpt-5", &overrides).map(|p| p.input),
+            Some(1.25)
+        );
+
+        let overrides = BTreeMap::from([
+            (
+                "o3".to_string(),
+                ModelPricing {
+                    input: 1.0,
+                    cached_input: None,
+                    output: 4.0,
+                },
+            ),
+            (
+                "o3-pro".to_string(),
+                ModelPricing {
+                    input: 10.0,
+                    cached_input: None,
+                    output: 40.0,
+                },
+            ),
+        ]);
+        assert_eq!(
+            pricing_for("o3-pro", &overrides).map(|p| p.input),
+            Some(10.0)
+        );
+        assert_eq!(
+            pricing_for("o3-mini", &overrides).map(|p| p.input),
+            Some(1.0)
+        );
+    }
+
+    #[test]
+    fn turn_stats_price_the_tokens_the_turn_used() {
+        let pricing = ModelPricing {
+            input: 2.0,
+            cached_input: Some(0.5),
+            output: 10.0,
+        };
+        let timing = TurnTiming {
+            total_ms: 4_000,
+            first_token_ms: Some(500),
+            ..Default::default()
+        };
+        let turn = TurnStats::new(
+            &usage(1_000, 0, 100),
+            &usage(401_000, 200_000, 100_100),
+            timing,
+            Some(pricing),
+        );
+        assert_eq!(turn.usage, usage(400_000, 200_000, 100_000));
+        // 200k uncached at $2, 200k cached at $0.50, 100k output at $10.
+        assert_eq!(turn.cost_usd, Some(1.5));
+
+        let mut session = SessionStats::default();
+        session.record(&turn);
+        session.record(&TurnStats::new(
+            &TokenUsage::default(),
+            &usage(10, 0, 10),
+            TurnTiming {
+                total_ms: 2_000,
+                ..Default::default()
+            },
+            None,
+        ));
+        assert_eq!(session.turns, 2);
+        assert_eq!(session.unpriced_turns, 1);
+        assert_eq!(session.cost_usd, 1.5);
+        assert_eq!(session.usage.input_tokens, 400_010);
+        assert_eq!(session.average_turn_ms(), Some(3_000));
+        assert_eq!(session.average_first_token_ms(), Some(500));
+        assert_eq!(session.slowest_turn_ms, 4_000);
+
+        session.record_aborted(&TurnStats::new(
+            &TokenUsage::default(),
+            &usage(100, 0, 0),
+            TurnTiming::default(),
+            None,
+        ));
+        assert_eq!(session.turns, 3);
+        assert_eq!(session.aborted_turns, 1);
+        assert_eq!(session.usage.input_tokens, 400_110);
+    }
+}
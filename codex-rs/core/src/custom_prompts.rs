@@ -1,4 +1,6 @@
+use crate::config::types::UserCommandConfig;
 use codex_protocol::custom_prompts::CustomPrompt;
+use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
@@ -75,6 +77,28 @@ pub async fn discover_prompts_in_excluding(
     out
 }
 
+/// Discover user-defined slash commands: Markdown files in `dir` (usually
+/// `$CODEX_HOME/commands`) plus `[commands]` entries from `config.toml`, which
+/// take precedence over files of the same name. Configured commands report
+/// `config_path` as their path. Returns entries sorted by name.
+pub async fn discover_user_commands(
+    dir: &Path,
+    configured: &BTreeMap<String, UserCommandConfig>,
+    config_path: &Path,
+) -> Vec<CustomPrompt> {
+    let exclude: HashSet<String> = configured.keys().cloned().collect();
+    let mut out = discover_prompts_in_excluding(dir, &exclude).await;
+    out.extend(configured.iter().map(|(name, command)| CustomPrompt {
+        name: name.clone(),
+        path: config_path.to_path_buf(),
+        content: command.prompt.clone().unwrap_or_default(),
+        description: command.description.clone(),
+        argument_hint: command.argument_hint.clone(),
+    }));
+    out.sort_by(|a, b| a.name.cmp(&b.name));
+    out
+}
+
 /// Parse optional YAML-like frontmatter at the beginning of `content`.
 /// Supported keys:
 /// - `description`: short description shown in the slash popup
@@ -233,6 +257,51 @@ mod tests {
         assert_eq!(p.content, "Actual body with $1 and $ARGUMENTS");
     }
 
+    #[tokio::test]
+    async fn configured_commands_override_command_files() {
+        let tmp = tempdir().expect("create TempDir");
+        let dir = tmp.path();
+        fs::write(dir.join("deploy.md"), b"from file").unwrap();
+        fs::write(
+            dir.join("triage.md"),
+            b"---\ndescription: Triage\n---\nTriage $1",
+        )
+        .unwrap();
+        let config_path = dir.join("config.toml");
+        let configured = BTreeMap::from([(
+            "deploy".to_string(),
+            UserCommandConfig {
+                prompt: Some("Deploy $ARGUMENTS".to_string()),
+                description: Some("Ship it".to_string()),
+                argument_hint: None,
+            },
+        )]);
+
+        let found = discover_user_commands(dir, &configured, &config_path).await;
+        let summary: Vec<(String, PathBuf, String, Option<String>)> = found
+            .into_iter()
+            .map(|c| (c.name, c.path, c.content, c.description))
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "deploy".to_string(),
+                    config_path,
+                    "Deploy $ARGUMENTS".to_string(),
+                    Some("Ship it".to_string()),
+                ),
+                (
+                    "triage".to_string(),
+                    dir.join("triage.md"),
+                    "Triage $1".to_string(),
+                    Some("Triage".to_string()),
+                ),
+            ]
+        );
+    }
+
     #[test]
     fn parse_frontmatter_preserves_body_newlines() {
         let content = "---\r\ndescription: \"Line endings\"\r\nargument_hint: \"[arg]\"\r\n---\r\nFirst line\r\nSecond line\r\n";
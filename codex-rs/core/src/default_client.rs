@@ -1,3 +1,4 @@
+use crate::config::types::ProxyConfig;
 use crate::spawn::CODEX_SANDBOX_ENV_VAR;
 use codex_client::CodexHttpClient;
 pub use codex_client::CodexRequestBuilder;
@@ -160,6 +161,19 @@ pub fn create_client() -> CodexHttpClient {
 }
 
 pub fn build_reqwest_client() -> reqwest::Client {
+    build_client(None)
+}
+
+/// Like [`build_reqwest_client`], routed through the `proxy` settings for
+/// `destination`.
+pub fn build_reqwest_client_for(
+    proxy: &ProxyConfig,
+    destination: ProxyDestination,
+) -> reqwest::Client {
+    build_client(proxy_for(proxy, destination))
+}
+
+fn build_client(proxy: Option<reqwest::Proxy>) -> reqwest::Client {
     use reqwest::header::HeaderMap;
 
     let mut headers = HeaderMap::new();
@@ -172,11 +186,144 @@ pub fn build_reqwest_client() -> reqwest::Client {
         .default_headers(headers);
     if is_sandboxed() {
         builder = builder.no_proxy();
+    } else if let Some(proxy) = proxy {
+        builder = builder.proxy(proxy);
     }
 
     builder.build().unwrap_or_else(|_| reqwest::Client::new())
 }
 
+/// Value of a `[[proxy.rules]]` `proxy` that connects without a proxy.
+pub const DIRECT_PROXY: &str = "direct";
+
+/// Kinds of destinations that `[proxy]` can route through different proxies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyDestination {
+    ModelProvider,
+    Mcp,
+}
+
+/// How [`resolve_proxy`] says to reach a host.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProxyChoice {
+    /// Connect without a proxy.
+    Direct,
+    /// Connect through this proxy URL.
+    Proxy(String),
+    /// None of the settings apply: the proxy environment variables decide.
+    Unset,
+}
+
+/// A proxy implementing the `proxy` settings for `destination`, or `None`
+/// when none of them apply so reqwest keeps following the proxy environment
+/// variables.
+pub fn proxy_for(config: &ProxyConfig, destination: ProxyDestination) -> Option<reqwest::Proxy> {
+    if config.rules.is_empty() && destination_proxy(config, destination).is_none() {
+        return None;
+    }
+    let config = config.clone();
+    Some(reqwest::Proxy::custom(move |url| {
+        let host = url.host_str()?;
+        match resolve_proxy(&config, destination, host) {
+            ProxyChoice::Direct => None,
+            ProxyChoice::Proxy(proxy) => Some(proxy),
+            // A custom proxy turns reqwest's own use of the environment
+            // variables off, so hosts no rule matches fall back to them here.
+            ProxyChoice::Unset => env_proxy(url.scheme(), host, |name| std::env::var(name).ok()),
+        }
+    }))
+}
+
+/// How to reach `host` under `config`.
+pub fn resolve_proxy(
+    config: &ProxyConfig,
+    destination: ProxyDestination,
+    host: &str,
+) -> ProxyChoice {
+    let host = normalize_host(host);
+    if let Some(rule) = config.rules.iter().find(|rule| {
+        rule.hosts
+            .iter()
+            .any(|pattern| host_matches(pattern, &host))
+    }) {
+        return if rule.proxy == DIRECT_PROXY {
+            ProxyChoice::Direct
+        } else {
+            ProxyChoice::Proxy(rule.proxy.clone())
+        };
+    }
+    if config
+        .no_proxy
+        .iter()
+        .any(|pattern| host_matches(pattern, &host))
+    {
+        return ProxyChoice::Direct;
+    }
+    match destination_proxy(config, destination) {
+        Some(proxy) => ProxyChoice::Proxy(proxy.to_string()),
+        None => ProxyChoice::Unset,
+    }
+}
+
+fn destination_proxy(config: &ProxyConfig, destination: ProxyDestination) -> Option<&str> {
+    let specific = match destination {
+        ProxyDestination::ModelProvider => config.model_provider.url.as_deref(),
+        ProxyDestination::Mcp => config.mcp.url.as_deref(),
+    };
+    specific.or(config.url.as_deref())
+}
+
+/// The proxy the `HTTPS_PROXY`, `HTTP_PROXY`, `ALL_PROXY` and `NO_PROXY`
+/// environment variables (or their lowercase forms), as read by `var`, pick
+/// for a `scheme` URL on `host`.
+fn env_proxy(scheme: &str, host: &str, var: impl Fn(&str) -> Option<String>) -> Option<String> {
+    let var = |name: &str| {
+        var(name)
+            .or_else(|| var(&name.to_ascii_lowercase()))
+            .filter(|value| !value.trim().is_empty())
+    };
+    let host = normalize_host(host);
+    if let Some(no_proxy) = var("NO_PROXY")
+        && no_proxy.split(',').any(|entry| {
+            // Unlike `no_proxy` in config, `example.com` here covers its
+            // subdomains too, as in curl.
+            let entry = entry.trim();
+            let domain = entry.trim_start_matches("*.").trim_start_matches('.');
+            entry == "*" || (!domain.is_empty() && host_matches(&format!(".{domain}"), &host))
+        })
+    {
+        return None;
+    }
+    let proxy = match scheme {
+        "https" | "wss" => var("HTTPS_PROXY"),
+        "http" | "ws" => var("HTTP_PROXY"),
+        _ => None,
+    };
+    proxy.or_else(|| var("ALL_PROXY"))
+}
+
+fn normalize_host(host: &str) -> String {
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_ascii_lowercase()
+}
+
+/// `*` matches every host, `.example.com` and `*.example.com` match the
+/// domain and its subdomains, anything else must match exactly.
+fn host_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.trim().to_ascii_lowercase();
+    if pattern == "*" {
+        return true;
+    }
+    match pattern
+        .strip_prefix("*.")
+        .or_else(|| pattern.strip_prefix('.'))
+    {
+        Some(domain) => host == domain || host.ends_with(&format!(".{domain}")),
+        None => host == pattern,
+    }
+}
+
 fn is_sandboxed() -> bool {
     std::env::var(CODEX_SANDBOX_ENV_VAR).as_deref() == Ok("seatbelt")
 }
@@ -184,7 +331,11 @@ fn is_sandboxed() -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::types::ProxyRule;
+    use crate::config::types::ProxyTargetConfig;
     use core_test_support::skip_if_no_network;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
 
     #[test]
     fn test_get_codex_user_agent() {
@@ -242,6 +393,105 @@ mod tests {
         assert_eq!(ua_header.to_str().unwrap(), expected_ua);
     }
 
+    #[test]
+    fn proxy_rules_then_no_proxy_then_destination_then_global() {
+        let config = ProxyConfig {
+            url: Some("http://proxy.corp:8080".to_string()),
+            no_proxy: vec!["localhost".to_string(), ".corp.example".to_string()],
+            model_provider: ProxyTargetConfig {
+                url: Some("http://llm-proxy.corp:3128".to_string()),
+            },
+            mcp: ProxyTargetConfig::default(),
+            rules: vec![
+                ProxyRule {
+                    hosts: vec!["*.internal.example".to_string()],
+                    proxy: DIRECT_PROXY.to_string(),
+                },
+                ProxyRule {
+                    hosts: vec!["api.partner.example".to_string()],
+                    proxy: "http://partner-proxy:8080".to_string(),
+                },
+            ],
+        };
+        let resolve = |destination, host: &str| resolve_proxy(&config, destination, host);
+        let proxy = |url: &str| ProxyChoice::Proxy(url.to_string());
+
+        assert_eq!(
+            resolve(ProxyDestination::ModelProvider, "api.openai.com"),
+            proxy("http://llm-proxy.corp:3128")
+        );
+        assert_eq!(
+            resolve(ProxyDestination::Mcp, "mcp.example.com"),
+            proxy("http://proxy.corp:8080")
+        );
+        assert_eq!(
+            resolve(ProxyDestination::Mcp, "API.Partner.Example"),
+            proxy("http://partner-proxy:8080")
+        );
+        assert_eq!(
+            resolve(ProxyDestination::Mcp, "docs.internal.example"),
+            ProxyChoice::Direct
+        );
+        assert_eq!(
+            resolve(ProxyDestination::Mcp, "corp.example"),
+            ProxyChoice::Direct
+        );
+        assert_eq!(
+            resolve(ProxyDestination::ModelProvider, "localhost"),
+            ProxyChoice::Direct
+        );
+    }
+
+    #[test]
+    fn hosts_without_a_rule_are_left_to_the_environment() {
+        let config = ProxyConfig {
+            mcp: ProxyTargetConfig {
+                url: Some("socks5h://mcp-proxy.corp:1080".to_string()),
+            },
+            rules: vec![ProxyRule {
+                hosts: vec!["api.partner.example".to_string()],
+                proxy: "http://partner-proxy:8080".to_string(),
+            }],
+            ..ProxyConfig::default()
+        };
+
+        assert_eq!(
+            resolve_proxy(&config, ProxyDestination::Mcp, "mcp.example.com"),
+            ProxyChoice::Proxy("socks5h://mcp-proxy.corp:1080".to_string())
+        );
+        assert_eq!(
+            resolve_proxy(&config, ProxyDestination::ModelProvider, "api.openai.com"),
+            ProxyChoice::Unset
+        );
+    }
+
+    #[test]
+    fn environment_proxies_follow_scheme_and_no_proxy() {
+        let env = HashMap::from([
+            ("https_proxy", "http://env-proxy:3128"),
+            ("ALL_PROXY", "socks5://all-proxy:1080"),
+            ("NO_PROXY", "localhost, .corp.example,example.org"),
+        ]);
+        let proxy =
+            |scheme, host| env_proxy(scheme, host, |name| env.get(name).map(ToString::to_string));
+
+        assert_eq!(
+            proxy("https", "api.openai.com"),
+            Some("http://env-proxy:3128".to_string())
+        );
+        assert_eq!(
+            proxy("http", "api.openai.com"),
+            Some("socks5://all-proxy:1080".to_string())
+        );
+        assert_eq!(proxy("https", "localhost"), None);
+        assert_eq!(proxy("https", "git.corp.example"), None);
+        assert_eq!(proxy("https", "docs.example.org"), None);
+        assert_eq!(
+            proxy("https", "example.com"),
+            Some("http://env-proxy:3128".to_string())
+        );
+    }
+
     #[test]
     fn test_invalid_suffix_is_sanitized() {
         let prefix = "codex_cli_rs/0.0.0";
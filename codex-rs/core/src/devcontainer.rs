@@ -0,0 +1,257 @@
+//! Dev containers (`[exec_backend] type = "devcontainer"`).
+//!
+//! A project's `devcontainer.json` pins the toolchain its CI and contributors
+//! use, which often differs from the one installed on this machine. With this
+//! backend Codex brings the container up with the `devcontainer` CLI, which
+//! builds the image on first use and reuses the running container after that,
+//! then runs tool commands in it with `docker exec`; see
+//! [`crate::remote_exec`].
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::Deserialize;
+use tokio::process::Command;
+use tokio::time::timeout;
+use tokio_util::task::AbortOnDropHandle;
+
+use crate::config::types::DevcontainerExecBackend;
+use crate::git_info::get_git_repo_root;
+use crate::remote_exec::RemoteExec;
+
+/// Where `devcontainer.json` is looked for, relative to each directory.
+const CONFIG_LOCATIONS: &[&str] = &[".devcontainer/devcontainer.json", ".devcontainer.json"];
+
+/// Building an image can take a while; a running container is reused at once.
+const UP_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// The `devcontainer.json` for `cwd`, in `cwd` or one of its parents up to
+/// the repository root.
+pub fn find_devcontainer_config(cwd: &Path) -> Option<PathBuf> {
+    let repo_root = get_git_repo_root(cwd);
+    for dir in cwd.ancestors() {
+        for location in CONFIG_LOCATIONS {
+            let candidate = dir.join(location);
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+        if repo_root.as_deref().is_none_or(|root| dir == root) {
+            break;
+        }
+    }
+    None
+}
+
+/// The dev container of a working directory, as far as the session got
+/// bringing it up.
+pub(crate) enum DevcontainerState {
+    /// `devcontainer up` is running; commands run locally until it is done.
+    Starting(AbortOnDropHandle<Result<RemoteExec, String>>),
+    Ready(RemoteExec),
+    /// Starting it failed, and commands run locally for the session.
+    Failed,
+}
+
+/// The dev container for a working directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Devcontainer {
+    /// The folder `devcontainer.json` belongs to, which the container mounts.
+    workspace: PathBuf,
+    config: PathBuf,
+}
+
+impl Devcontainer {
+    pub(crate) fn locate(backend: &DevcontainerExecBackend, cwd: &Path) -> Result<Self, String> {
+        let config = match &backend.config {
+            Some(config) => cwd.join(config),
+            None => find_devcontainer_config(cwd).ok_or_else(|| {
+                format!(
+                    "no .devcontainer/devcontainer.json found for {}",
+                    cwd.display()
+                )
+            })?,
+        };
+        let workspace = workspace_folder(&config)
+            .or_else(|| get_git_repo_root(cwd))
+            .unwrap_or_else(|| cwd.to_path_buf());
+        Ok(Self { workspace, config })
+    }
+
+    pub(crate) fn config(&self) -> &Path {
+        &self.config
+    }
+
+    /// Runs [`Self::up`] in the background.
+    pub(crate) fn start(self, backend: DevcontainerExecBackend) -> DevcontainerState {
+        DevcontainerState::Starting(AbortOnDropHandle::new(tokio::spawn(async move {
+            self.up(&backend).await
+        })))
+    }
+
+    /// Builds or starts the container, or finds it already running, and
+    /// returns how to run commands in it.
+    pub(crate) async fn up(&self, backend: &DevcontainerExecBackend) -> Result<RemoteExec, String> {
+        let output = Command::new("devcontainer")
+            .arg("up")
+            .arg("--workspace-folder")
+            .arg(&self.workspace)
+            .arg("--config")
+            .arg(&self.config)
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output();
+        let output = timeout(UP_TIMEOUT, output)
+            .await
+            .map_err(|_| "`devcontainer up` timed out".to_string())?
+            .map_err(|err| match err.kind() {
+                std::io::ErrorKind::NotFound => {
+                    "the `devcontainer` CLI is not installed (`npm install -g @devcontainers/cli`)"
+                        .to_string()
+                }
+                _ => format!("failed to run `devcontainer up`: {err}"),
+            })?;
+        let up = parse_up_output(&String::from_utf8_lossy(&output.stdout)).map_err(|err| {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            match stderr.trim().lines().last() {
+                Some(last) if !output.status.success() => format!("{err}: {last}"),
+                _ => err,
+            }
+        })?;
+        let remote_workspace = up.remote_workspace_folder.unwrap_or_else(|| {
+            Path::new("/workspaces").join(self.workspace.file_name().unwrap_or_default())
+        });
+        Ok(RemoteExec::container(
+            up.container_id,
+            up.remote_user,
+            self.workspace.clone(),
+            remote_workspace,
+            backend.forward_env.clone(),
+            backend.sandbox,
+        ))
+    }
+}
+
+/// The folder a `devcontainer.json` describes: the parent of its
+/// `.devcontainer` directory, or of a top-level `.devcontainer.json`.
+fn workspace_folder(config: &Path) -> Option<PathBuf> {
+    if config.file_name()? == ".devcontainer.json" {
+        return config.parent().map(Path::to_path_buf);
+    }
+    config
+        .ancestors()
+        .find(|dir| dir.file_name().is_some_and(|name| name == ".devcontainer"))
+        .and_then(Path::parent)
+        .map(Path::to_path_buf)
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct UpResult {
+    container_id: String,
+    remote_user: Option<String>,
+    remote_workspace_folder: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UpOutput {
+    outcome: String,
+    container_id: Option<String>,
+    remote_user: Option<String>,
+    remote_workspace_folder: Option<PathBuf>,
+    message: Option<String>,
+    description: Option<String>,
+}
+
+/// `devcontainer up` logs to stderr and ends its stdout with one JSON line
+/// reporting the outcome.
+fn parse_up_output(stdout: &str) -> Result<UpResult, String> {
+    let Some(output) = stdout
+        .lines()
+        .rev()
+        .find_map(|line| serde_json::from_str::<UpOutput>(line.trim()).ok())
+    else {
+        return Err("`devcontainer up` did not report an outcome".to_string());
+    };
+    match (output.outcome.as_str(), output.container_id) {
+        ("success", Some(container_id)) => Ok(UpResult {
+            container_id,
+            remote_user: output.remote_user,
+            remote_workspace_folder: output.remote_workspace_folder,
+        }),
+        _ => Err(output
+            .message
+            .or(output.description)
+            .unwrap_or_else(|| format!("`devcontainer up` reported {}", output.outcome))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    #[test]
+    fn config_is_found_between_cwd_and_the_repository_root() {
+        let dir = tempdir().expect("tempdir");
+        let repo = dir.path().join("api");
+        let cwd = repo.join("core/src");
+        std::fs::create_dir_all(&cwd).expect("mkdir");
+        std::fs::create_dir_all(repo.join(".git")).expect("mkdir");
+        assert_eq!(find_devcontainer_config(&cwd), None);
+
+        // Outside the repository does not count.
+        std::fs::write(dir.path().join(".devcontainer.json"), "{}").expect("write");
+        assert_eq!(find_devcontainer_config(&cwd), None);
+
+        let config = repo.join(".devcontainer/devcontainer.json");
+        std::fs::create_dir_all(repo.join(".devcontainer")).expect("mkdir");
+        std::fs::write(&config, "{}").expect("write");
+        assert_eq!(find_devcontainer_config(&cwd), Some(config.clone()));
+
+        let located =
+            Devcontainer::locate(&DevcontainerExecBackend::default(), &cwd).expect("located");
+        assert_eq!(
+            located,
+            Devcontainer {
+                workspace: repo.clone(),
+                config,
+            }
+        );
+        assert_eq!(
+            workspace_folder(&repo.join(".devcontainer/rust/devcontainer.json")),
+            Some(repo.clone())
+        );
+        assert_eq!(
+            workspace_folder(&repo.join(".devcontainer.json")),
+            Some(repo)
+        );
+    }
+
+    #[test]
+    fn up_output_reports_the_container_or_the_failure() {
+        let stdout = "[2 ms] Dev Containers CLI\n\
+            {\"outcome\":\"success\",\"containerId\":\"f00dcafe\",\"remoteUser\":\"vscode\",\"remoteWorkspaceFolder\":\"/workspaces/api\"}\n";
+        assert_eq!(
+            parse_up_output(stdout),
+            Ok(UpResult {
+                container_id: "f00dcafe".to_string(),
+                remote_user: Some("vscode".to_string()),
+                remote_workspace_folder: Some(PathBuf::from("/workspaces/api")),
+            })
+        );
+        assert_eq!(
+            parse_up_output(
+                "{\"outcome\":\"error\",\"message\":\"Command failed: docker build\",\"description\":\"An error occurred building the image.\"}"
+            ),
+            Err("Command failed: docker build".to_string())
+        );
+        assert_eq!(
+            parse_up_output(""),
+            Err("`devcontainer up` did not report an outcome".to_string())
+        );
+    }
+}
@@ -0,0 +1,281 @@
+//! Environment fingerprint.
+//!
+//! With the `environment_fingerprint` feature, the machine tool commands run
+//! on is probed when a session in a trusted project starts, or resumes from a
+//! rollout without a fingerprint: OS and architecture, the versions of common
+//! toolchains on `PATH`, the git remote and commit of the working directory,
+//! and signs of running inside a container. The result is recorded in the
+//! rollout and given to the model as a short notice, so it does not spend
+//! turns on `uname` and `--version` probes of its own.
+//!
+//! Probes run like the model's commands, on the `[exec_backend]` host when one
+//! is set, but always under a read-only sandbox: some of them read project
+//! files (`rustc` honors `rust-toolchain.toml`) and must not change anything.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+use codex_protocol::protocol::ENVIRONMENT_FINGERPRINT_CLOSE_TAG;
+use codex_protocol::protocol::ENVIRONMENT_FINGERPRINT_OPEN_TAG;
+use codex_protocol::protocol::EnvironmentFingerprintEvent;
+use codex_protocol::protocol::SandboxPolicy;
+use futures::future::join_all;
+
+use crate::codex::TurnContext;
+use crate::exec::ExecExpiration;
+use crate::exec_env::create_env;
+use crate::git_info::collect_git_info;
+use crate::sandboxing::CommandSpec;
+use crate::sandboxing::SandboxManager;
+use crate::sandboxing::SandboxPermissions;
+use crate::sandboxing::execute_env;
+use crate::tools::sandboxing::SandboxAttempt;
+use crate::tools::sandboxing::SandboxablePreference;
+
+/// Toolchains probed, as the command and the arguments that print its version.
+const TOOLCHAIN_PROBES: &[(&str, &[&str])] = &[
+    ("rustc", &["--version"]),
+    ("cargo", &["--version"]),
+    ("node", &["--version"]),
+    ("npm", &["--version"]),
+    ("python3", &["--version"]),
+    ("go", &["version"]),
+    ("java", &["-version"]),
+    ("ruby", &["--version"]),
+    ("gcc", &["--version"]),
+    ("clang", &["--version"]),
+    ("docker", &["--version"]),
+];
+
+/// How long a single version probe may take before it is given up.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Probes the environment tool commands of `turn` run in.
+pub(crate) async fn capture(turn: &TurnContext) -> EnvironmentFingerprintEvent {
+    let prober = Prober::new(turn);
+    let (toolchains, git) = tokio::join!(probe_toolchains(&prober), collect_git_info(&turn.cwd));
+    let Some(remote) = &turn.remote_exec else {
+        let os_info = os_info::get();
+        let os_version = match os_info.version() {
+            os_info::Version::Unknown => None,
+            version => Some(version.to_string()),
+        };
+        return EnvironmentFingerprintEvent {
+            os: std::env::consts::OS.to_string(),
+            os_version,
+            arch: std::env::consts::ARCH.to_string(),
+            toolchains,
+            git,
+            container: detect_container(),
+        };
+    };
+    let uname = prober.run("uname", &["-s", "-m"]).await.unwrap_or_default();
+    let mut uname = uname.split_whitespace();
+    EnvironmentFingerprintEvent {
+        os: uname.next().unwrap_or("unknown").to_lowercase(),
+        os_version: None,
+        arch: uname.next().unwrap_or("unknown").to_string(),
+        toolchains,
+        git,
+        container: remote.is_container().then(|| "docker".to_string()),
+    }
+}
+
+/// Runs probe commands where, and as, the model's commands of a turn would
+/// run, but read-only.
+struct Prober<'a> {
+    turn: &'a TurnContext,
+    manager: SandboxManager,
+    policy: SandboxPolicy,
+}
+
+impl<'a> Prober<'a> {
+    fn new(turn: &'a TurnContext) -> Self {
+        Self {
+            turn,
+            manager: SandboxManager::new(),
+            policy: SandboxPolicy::new_read_only_policy(),
+        }
+    }
+
+    /// Output of `program` with `args`, `None` if it could not run or
+    /// failed. Some tools (`java -version`) print their version on stderr,
+    /// which is returned when stdout is empty.
+    async fn run(&self, program: &str, args: &[&str]) -> Option<String> {
+        let attempt = SandboxAttempt {
+            sandbox: self
+                .manager
+                .select_initial(&self.policy, SandboxablePreference::Auto),
+            policy: &self.policy,
+            manager: &self.manager,
+            sandbox_cwd: &self.turn.cwd,
+            codex_linux_sandbox_exe: self.turn.codex_linux_sandbox_exe.as_ref(),
+            remote: self.turn.remote_exec.as_ref(),
+        };
+        let spec = CommandSpec {
+            program: program.to_string(),
+            args: args.iter().map(ToString::to_string).collect(),
+            cwd: self.turn.cwd.clone(),
+            env: create_env(&self.turn.shell_environment_policy),
+            expiration: ExecExpiration::Timeout(PROBE_TIMEOUT),
+            sandbox_permissions: SandboxPermissions::UseDefault,
+            justification: None,
+        };
+        let env = attempt.env_for(spec).ok()?;
+        let output = execute_env(env, &self.policy, None).await.ok()?;
+        if output.exit_code != 0 || output.timed_out {
+            return None;
+        }
+        Some(if output.stdout.text.is_empty() {
+            output.stderr.text
+        } else {
+            output.stdout.text
+        })
+    }
+}
+
+async fn probe_toolchains(prober: &Prober<'_>) -> BTreeMap<String, String> {
+    let probes = TOOLCHAIN_PROBES.iter().map(|(program, args)| async move {
+        let version = version_of(&prober.run(program, args).await?)?;
+        Some((program.to_string(), version))
+    });
+    join_all(probes).await.into_iter().flatten().collect()
+}
+
+/// Extracts the version number from the output of a `--version` probe, e.g.
+/// `1.80.0` from `rustc 1.80.0 (051478957 2024-07-21)`.
+fn version_of(output: &str) -> Option<String> {
+    output.lines().next()?.split_whitespace().find_map(|word| {
+        let word = word.trim_matches(|c: char| matches!(c, '"' | '(' | ')'));
+        let word = word
+            .strip_prefix("go")
+            .or_else(|| word.strip_prefix('v'))
+            .unwrap_or(word);
+        let end = word
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(word.len());
+        let version = word[..end].trim_end_matches('.');
+        (version.starts_with(|c: char| c.is_ascii_digit()) && version.contains('.'))
+            .then(|| version.to_string())
+    })
+}
+
+/// Names the container runtime this process appears to run in, if any.
+fn detect_container() -> Option<String> {
+    if std::env::var_os("KUBERNETES_SERVICE_HOST").is_some() {
+        return Some("kubernetes".to_string());
+    }
+    if Path::new("/.dockerenv").exists() {
+        return Some("docker".to_string());
+    }
+    if Path::new("/run/.containerenv").exists() {
+        return Some("podman".to_string());
+    }
+    // systemd-nspawn, podman and others export `container`.
+    if let Ok(runtime) = std::env::var("container")
+        && !runtime.is_empty()
+    {
+        return Some(runtime);
+    }
+    let cgroup = std::fs::read_to_string("/proc/1/cgroup").ok()?;
+    ["docker", "kubepods", "containerd", "lxc"]
+        .into_iter()
+        .find(|runtime| cgroup.contains(runtime))
+        .map(str::to_string)
+}
+
+/// Text of the message giving the model the fingerprint.
+pub(crate) fn fingerprint_notice(fingerprint: &EnvironmentFingerprintEvent) -> String {
+    let mut lines = vec![
+        ENVIRONMENT_FINGERPRINT_OPEN_TAG.to_string(),
+        "Probed when this session started; no need to re-run these checks.".to_string(),
+    ];
+    let os = match &fingerprint.os_version {
+        Some(version) => format!("{} {version}", fingerprint.os),
+        None => fingerprint.os.clone(),
+    };
+    lines.push(format!("os: {os} ({})", fingerprint.arch));
+    if let Some(container) = &fingerprint.container {
+        lines.push(format!("container: {container}"));
+    }
+    if !fingerprint.toolchains.is_empty() {
+        let toolchains = fingerprint
+            .toolchains
+            .iter()
+            .map(|(name, version)| format!("{name} {version}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        lines.push(format!("toolchains: {toolchains}"));
+    }
+    if let Some(git) = &fingerprint.git {
+        let mut parts = Vec::new();
+        if let Some(url) = &git.repository_url {
+            parts.push(format!("remote {url}"));
+        }
+        if let Some(branch) = &git.branch {
+            parts.push(format!("branch {branch}"));
+        }
+        if let Some(commit) = &git.commit_hash {
+            parts.push(format!("commit {}", &commit[..commit.len().min(12)]));
+        }
+        if !parts.is_empty() {
+            lines.push(format!("git: {}", parts.join(", ")));
+        }
+    }
+    lines.push(ENVIRONMENT_FINGERPRINT_CLOSE_TAG.to_string());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::GitInfo;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn versions_are_read_from_common_probe_outputs() {
+        let cases = [
+            ("rustc 1.80.0 (051478957 2024-07-21)", Some("1.80.0")),
+            ("v20.11.1", Some("20.11.1")),
+            ("Python 3.12.1", Some("3.12.1")),
+            ("go version go1.22.1 linux/amd64", Some("1.22.1")),
+            ("openjdk version \"21.0.1\" 2023-10-17", Some("21.0.1")),
+            ("gcc (Ubuntu 11.4.0-1ubuntu1~22.04) 11.4.0", Some("11.4.0")),
+            ("no version here", None),
+        ];
+        for (output, expected) in cases {
+            assert_eq!(version_of(output).as_deref(), expected, "{output}");
+        }
+    }
+
+    #[test]
+    fn notice_lists_what_was_found() {
+        let fingerprint = EnvironmentFingerprintEvent {
+            os: "linux".to_string(),
+            os_version: Some("22.04".to_string()),
+            arch: "x86_64".to_string(),
+            toolchains: BTreeMap::from([
+                ("cargo".to_string(), "1.80.0".to_string()),
+                ("node".to_string(), "20.11.1".to_string()),
+            ]),
+            git: Some(GitInfo {
+                commit_hash: Some("0123456789abcdef0123".to_string()),
+                branch: Some("main".to_string()),
+                repository_url: Some("https://github.com/acme/api".to_string()),
+            }),
+            container: Some("docker".to_string()),
+        };
+
+        assert_eq!(
+            fingerprint_notice(&fingerprint),
+            "<environment_fingerprint>
+Probed when this session started; no need to re-run these checks.
+os: linux 22.04 (x86_64)
+container: docker
+toolchains: cargo 1.80.0, node 20.11.1
+git: remote https://github.com/acme/api, branch main, commit 0123456789ab
+</environment_fingerprint>"
+        );
+    }
+}
@@ -70,6 +70,11 @@ pub enum CodexErr {
     #[error("stream disconnected before completion: {0}")]
     Stream(String, Option<Duration>),
 
+    /// The SSE stream delivered a chunk that could not be parsed or a tool
+    /// call too large to buffer. Retried like [`CodexErr::Stream`].
+    #[error("model stream was malformed: {0}")]
+    MalformedStream(String),
+
     #[error(
         "Codex ran out of room in the model's context window. Start a new thread or clear earlier history before retrying."
     )]
@@ -145,6 +150,10 @@ pub enum CodexErr {
     #[error("unsupported operation: {0}")]
     UnsupportedOperation(String),
 
+    /// A feature that needs the network was used while `offline = true`.
+    #[error("offline: {0}")]
+    Offline(String),
+
     #[error("{0}")]
     RefreshTokenFailed(RefreshTokenFailedError),
 
@@ -194,6 +203,7 @@ impl CodexErr {
             | CodexErr::InvalidRequest(_)
             | CodexErr::RefreshTokenFailed(_)
             | CodexErr::UnsupportedOperation(_)
+            | CodexErr::Offline(_)
             | CodexErr::Sandbox(_)
             | CodexErr::LandlockSandboxExecutableNotProvided
             | CodexErr::RetryLimit(_)
@@ -203,6 +213,7 @@ impl CodexErr {
             | CodexErr::SessionConfiguredNotFirstEvent
             | CodexErr::UsageLimitReached(_) => false,
             CodexErr::Stream(..)
+            | CodexErr::MalformedStream(_)
             | CodexErr::Timeout
             | CodexErr::UnexpectedStatus(_)
             | CodexErr::ResponseStreamFailed(_)
@@ -501,6 +512,7 @@ impl CodexErr {
                 CodexErrorInfo::BadRequest
             }
             CodexErr::Sandbox(_) => CodexErrorInfo::SandboxError,
+            CodexErr::MalformedStream(_) => CodexErrorInfo::ResponseStreamMalformed,
             _ => CodexErrorInfo::Other,
         }
     }
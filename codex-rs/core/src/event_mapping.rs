@@ -13,10 +13,13 @@ use codex_protocol::models::is_image_close_tag_text;
 use codex_protocol::models::is_image_open_tag_text;
 use codex_protocol::models::is_local_image_close_tag_text;
 use codex_protocol::models::is_local_image_open_tag_text;
+use codex_protocol::protocol::CHANGED_FILES_OPEN_TAG;
+use codex_protocol::protocol::ENVIRONMENT_FINGERPRINT_OPEN_TAG;
 use codex_protocol::user_input::UserInput;
 use tracing::warn;
 use uuid::Uuid;
 
+use crate::user_instructions::McpResourceContents;
 use crate::user_instructions::SkillInstructions;
 use crate::user_instructions::UserInstructions;
 use crate::user_shell_command::is_user_shell_command_text;
@@ -25,11 +28,14 @@ fn is_session_prefix(text: &str) -> bool {
     let trimmed = text.trim_start();
     let lowered = trimmed.to_ascii_lowercase();
     lowered.starts_with("<environment_context>")
+        || lowered.starts_with(ENVIRONMENT_FINGERPRINT_OPEN_TAG)
+        || lowered.starts_with(CHANGED_FILES_OPEN_TAG)
 }
 
 fn parse_user_message(message: &[ContentItem]) -> Option<UserMessageItem> {
     if UserInstructions::is_user_instructions(message)
         || SkillInstructions::is_skill_instructions(message)
+        || McpResourceContents::is_mcp_resource_contents(message)
     {
         return None;
     }
@@ -0,0 +1,411 @@
+//! Per-session socket that streams events to `codex events tail` and the
+//! `codex serve --web` dashboard.
+//!
+//! On Unix each session listens on `<codex_home>/run/<thread_id>.sock`. Every
+//! client receives the events dispatched after it connects, one JSON
+//! [`SocketEvent`] per line, and the socket is removed when the session ends.
+//!
+//! The socket is read-only unless `event_socket_approvals` is enabled. Then
+//! the session also writes a random token to `<thread_id>.token` next to the
+//! socket, and clients may answer its approval requests by writing
+//! [`SocketOp`] JSON lines carrying that token; see [`is_socket_op`]. Sandboxed
+//! commands cannot read the `run` directory, so they cannot approve their own
+//! requests.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::SecondsFormat;
+use chrono::Utc;
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::Op;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio_util::task::AbortOnDropHandle;
+
+use crate::hooks::HookEvent;
+
+/// Directory under `CODEX_HOME` holding the sockets of running sessions.
+pub const EVENT_SOCKET_DIR: &str = "run";
+
+/// One line on an event socket.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SocketEvent {
+    /// RFC 3339 time the event was dispatched.
+    pub timestamp: String,
+    /// Event name as used by hooks, e.g. `turn_complete`.
+    pub name: String,
+    pub event: Event,
+}
+
+impl SocketEvent {
+    #[cfg_attr(not(unix), allow(dead_code))]
+    fn now(event: &HookEvent) -> Self {
+        Self {
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            name: event.name.clone(),
+            event: (*event.event).clone(),
+        }
+    }
+}
+
+/// An approval decision written to an event socket.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SocketOp {
+    /// Contents of the session's token file.
+    pub token: String,
+    pub op: Op,
+}
+
+/// Whether a client of the socket may submit `op`: approval decisions only,
+/// so a dashboard can unblock a session but not drive it.
+pub fn is_socket_op(op: &Op) -> bool {
+    matches!(
+        op,
+        Op::ExecApproval { .. } | Op::PatchApproval { .. } | Op::ResolveElicitation { .. }
+    )
+}
+
+/// Where the session `thread_id` serves its events.
+pub fn event_socket_path(codex_home: &Path, thread_id: &str) -> PathBuf {
+    codex_home
+        .join(EVENT_SOCKET_DIR)
+        .join(format!("{thread_id}.sock"))
+}
+
+/// A fresh random token for authorizing approval decisions.
+pub fn new_approval_token() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// Where the session `thread_id` keeps the token clients must send with
+/// approval decisions.
+pub fn event_socket_token_path(codex_home: &Path, thread_id: &str) -> PathBuf {
+    codex_home
+        .join(EVENT_SOCKET_DIR)
+        .join(format!("{thread_id}.token"))
+}
+
+/// Serves a session's events until dropped.
+#[cfg_attr(not(unix), allow(dead_code))]
+pub(crate) struct EventSocket {
+    path: PathBuf,
+    token_path: Option<PathBuf>,
+    _accept: AbortOnDropHandle<()>,
+}
+
+impl EventSocket {
+    /// Starts serving the events of `hooks`. With `accept_approvals`, also
+    /// forwards approval decisions that carry the session token to
+    /// `submissions`. Failing to bind only disables the socket, so it never
+    /// stops a session from starting.
+    #[cfg(unix)]
+    pub(crate) fn start(
+        hooks: &std::sync::Arc<crate::hooks::Hooks>,
+        submissions: async_channel::WeakSender<crate::protocol::Submission>,
+        codex_home: &Path,
+        thread_id: ThreadId,
+        accept_approvals: bool,
+    ) -> Option<Self> {
+        use std::io::Write;
+        use std::os::unix::fs::OpenOptionsExt;
+        use std::os::unix::fs::PermissionsExt;
+
+        use futures::StreamExt;
+        use tokio::io::AsyncBufReadExt;
+        use tokio::io::AsyncWriteExt;
+        use tokio::io::BufReader;
+        use tokio::net::UnixListener;
+
+        use crate::hooks::EventFilter;
+
+        let path = event_socket_path(codex_home, &thread_id.to_string());
+        let bind = || -> std::io::Result<UnixListener> {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            // A resumed session reuses its id; clear the socket a crashed run left.
+            if let Err(err) = std::fs::remove_file(&path)
+                && err.kind() != std::io::ErrorKind::NotFound
+            {
+                return Err(err);
+            }
+            let listener = UnixListener::bind(&path)?;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+            Ok(listener)
+        };
+        let listener = match bind() {
+            Ok(listener) => listener,
+            Err(err) => {
+                tracing::warn!("failed to listen on {}: {err}", path.display());
+                return None;
+            }
+        };
+
+        let token_path = event_socket_token_path(codex_home, &thread_id.to_string());
+        let token = if accept_approvals {
+            let token = new_approval_token();
+            let write_token = || -> std::io::Result<()> {
+                if let Err(err) = std::fs::remove_file(&token_path)
+                    && err.kind() != std::io::ErrorKind::NotFound
+                {
+                    return Err(err);
+                }
+                std::fs::OpenOptions::new()
+                    .write(true)
+                    .create_new(true)
+                    .mode(0o600)
+                    .open(&token_path)?
+                    .write_all(token.as_bytes())
+            };
+            match write_token() {
+                Ok(()) => Some(token),
+                Err(err) => {
+                    // Keep streaming events, but without a token nobody can
+                    // answer approvals.
+                    tracing::warn!("failed to write {}: {err}", token_path.display());
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let token_path = token.is_some().then_some(token_path);
+
+        let hooks = std::sync::Arc::clone(hooks);
+        let accept = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (reader, mut writer) = stream.into_split();
+                let mut events = Box::pin(hooks.subscribe(EventFilter::all()));
+                let writes = tokio::spawn(async move {
+                    while let Some(event) = events.next().await {
+                        let Ok(mut line) = serde_json::to_vec(&SocketEvent::now(&event)) else {
+                            continue;
+                        };
+                        line.push(b'\n');
+                        if writer.write_all(&line).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+                let submissions = submissions.clone();
+                let token = token.clone();
+                tokio::spawn(async move {
+                    // Dropping the handle would not stop the writer, so abort
+                    // it once the client hangs up.
+                    let _writes = AbortOnDropHandle::new(writes);
+                    let mut lines = BufReader::new(reader).lines();
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        let Some(token) = token.as_deref() else {
+                            tracing::warn!("ignoring input on read-only event socket");
+                            continue;
+                        };
+                        let op = match serde_json::from_str::<SocketOp>(&line) {
+                            Ok(socket_op) if socket_op.token != token => {
+                                tracing::warn!("ignoring op with a wrong token from event socket");
+                                continue;
+                            }
+                            Ok(SocketOp { op, .. }) if is_socket_op(&op) => op,
+                            Ok(_) => {
+                                tracing::warn!("ignoring non-approval op from event socket");
+                                continue;
+                            }
+                            Err(err) => {
+                                tracing::warn!("ignoring malformed op from event socket: {err}");
+                                continue;
+                            }
+                        };
+                        let Some(submissions) = submissions.upgrade() else {
+                            break;
+                        };
+                        let submission = crate::protocol::Submission {
+                            id: uuid::Uuid::new_v4().to_string(),
+                            op,
+                        };
+                        if submissions.send(submission).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        Some(Self {
+            path,
+            token_path,
+            _accept: AbortOnDropHandle::new(accept),
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn start(
+        _hooks: &std::sync::Arc<crate::hooks::Hooks>,
+        _submissions: async_channel::WeakSender<crate::protocol::Submission>,
+        _codex_home: &Path,
+        _thread_id: ThreadId,
+        _accept_approvals: bool,
+    ) -> Option<Self> {
+        None
+    }
+}
+
+impl Drop for EventSocket {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+        if let Some(token_path) = &self.token_path {
+            let _ = std::fs::remove_file(token_path);
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::hooks::Hooks;
+    use codex_protocol::protocol::EventMsg;
+    use codex_protocol::protocol::WarningEvent;
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::io::AsyncBufReadExt;
+    use tokio::io::AsyncWriteExt;
+    use tokio::io::BufReader;
+    use tokio::net::UnixStream;
+
+    #[tokio::test]
+    async fn clients_receive_events_as_json_lines() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let hooks = Arc::new(Hooks::default());
+        let (tx_sub, _rx_sub) = async_channel::unbounded();
+        let thread_id = ThreadId::new();
+        let socket = EventSocket::start(
+            &hooks,
+            tx_sub.downgrade(),
+            codex_home.path(),
+            thread_id,
+            false,
+        )
+        .expect("listening");
+        let path = event_socket_path(codex_home.path(), &thread_id.to_string());
+
+        let stream = UnixStream::connect(&path).await.expect("connect");
+        let reader = tokio::spawn(async move {
+            let mut line = String::new();
+            BufReader::new(stream)
+                .read_line(&mut line)
+                .await
+                .expect("read");
+            line
+        });
+        // The client only sees events dispatched after the socket accepted it.
+        let warning = Event {
+            id: "sub-1".to_string(),
+            msg: EventMsg::Warning(WarningEvent {
+                message: "careful".to_string(),
+            }),
+        };
+        for _ in 0..200 {
+            if reader.is_finished() {
+                break;
+            }
+            hooks.dispatch(&warning).await;
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let line = reader.await.expect("join");
+        let event: SocketEvent = serde_json::from_str(&line).expect("socket event");
+        assert_eq!(event.name, "warning");
+        assert_eq!(event.event.id, "sub-1");
+
+        drop(socket);
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn read_only_sockets_ignore_approvals() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let hooks = Arc::new(Hooks::default());
+        let (tx_sub, rx_sub) = async_channel::unbounded();
+        let thread_id = ThreadId::new();
+        let _socket = EventSocket::start(
+            &hooks,
+            tx_sub.downgrade(),
+            codex_home.path(),
+            thread_id,
+            false,
+        )
+        .expect("listening");
+        let path = event_socket_path(codex_home.path(), &thread_id.to_string());
+        let token_path = event_socket_token_path(codex_home.path(), &thread_id.to_string());
+        assert!(!token_path.exists());
+
+        let mut stream = UnixStream::connect(&path).await.expect("connect");
+        stream
+            .write_all(
+                b"{\"token\":\"\",\"op\":{\"type\":\"exec_approval\",\"id\":\"sub-1\",\"decision\":\"approved\"}}\n",
+            )
+            .await
+            .expect("write");
+
+        let received = tokio::time::timeout(Duration::from_millis(200), rx_sub.recv()).await;
+        assert!(received.is_err());
+    }
+
+    #[tokio::test]
+    async fn clients_can_only_submit_approvals_with_the_token() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let hooks = Arc::new(Hooks::default());
+        let (tx_sub, rx_sub) = async_channel::unbounded();
+        let thread_id = ThreadId::new();
+        let socket = EventSocket::start(
+            &hooks,
+            tx_sub.downgrade(),
+            codex_home.path(),
+            thread_id,
+            true,
+        )
+        .expect("listening");
+        let path = event_socket_path(codex_home.path(), &thread_id.to_string());
+        let token_path = event_socket_token_path(codex_home.path(), &thread_id.to_string());
+        let token = std::fs::read_to_string(&token_path).expect("token");
+
+        let approval = |token: &str| SocketOp {
+            token: token.to_string(),
+            op: Op::ExecApproval {
+                id: "sub-1".to_string(),
+                decision: codex_protocol::protocol::ReviewDecision::Approved,
+            },
+        };
+        let interrupt = SocketOp {
+            token: token.clone(),
+            op: Op::Interrupt,
+        };
+        let mut input = String::new();
+        for socket_op in [interrupt, approval("wrong")] {
+            input.push_str(&serde_json::to_string(&socket_op).expect("serialize"));
+            input.push('\n');
+        }
+        input.push_str("not json\n");
+        input.push_str(&serde_json::to_string(&approval(&token)).expect("serialize"));
+        input.push('\n');
+
+        let mut stream = UnixStream::connect(&path).await.expect("connect");
+        stream.write_all(input.as_bytes()).await.expect("write");
+
+        let submission = tokio::time::timeout(Duration::from_secs(5), rx_sub.recv())
+            .await
+            .expect("forwarded in time")
+            .expect("submission");
+        assert_eq!(
+            submission.op,
+            Op::ExecApproval {
+                id: "sub-1".to_string(),
+                decision: codex_protocol::protocol::ReviewDecision::Approved,
+            }
+        );
+        assert!(rx_sub.is_empty());
+
+        drop(socket);
+        assert!(!token_path.exists());
+    }
+}
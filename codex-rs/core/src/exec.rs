@@ -598,6 +598,11 @@ async fn consume_truncated_output(
         Some(agg_tx.clone()),
     ));
 
+    // `kill_on_drop` only reaches the direct child. If this future is dropped
+    // before the child exits, e.g. because the turn was interrupted, take the
+    // rest of its process group down with it.
+    let mut process_group = ProcessGroupGuard::new(&child);
+
     let (exit_status, timed_out) = tokio::select! {
         status_result = child.wait() => {
             let exit_status = status_result?;
@@ -614,6 +619,7 @@ async fn consume_truncated_output(
             (synthetic_exit_status(EXIT_CODE_SIGNAL_BASE + SIGKILL_CODE), false)
         }
     };
+    process_group.disarm();
 
     // Wait for the stdout/stderr collection tasks but guard against them
     // hanging forever. In the normal case, both pipes are closed once the child
@@ -750,6 +756,35 @@ fn synthetic_exit_status(code: i32) -> ExitStatus {
     std::process::ExitStatus::from_raw(code as u32)
 }
 
+/// Kills a child's process group when dropped, unless disarmed first.
+struct ProcessGroupGuard {
+    pgid: Option<u32>,
+}
+
+impl ProcessGroupGuard {
+    /// Arms only when the child leads its own process group, so the guard can
+    /// never signal the group Codex itself runs in.
+    fn new(child: &Child) -> Self {
+        Self {
+            pgid: child.id().filter(|pid| leads_own_process_group(*pid)),
+        }
+    }
+
+    fn disarm(&mut self) {
+        self.pgid = None;
+    }
+}
+
+impl Drop for ProcessGroupGuard {
+    fn drop(&mut self) {
+        if let Some(pgid) = self.pgid.take()
+            && let Err(err) = kill_process_group(pgid)
+        {
+            tracing::warn!("failed to kill process group {pgid}: {err}");
+        }
+    }
+}
+
 #[cfg(unix)]
 fn kill_child_process_group(child: &mut Child) -> io::Result<()> {
     use std::io::ErrorKind;
@@ -765,23 +800,45 @@ fn kill_child_process_group(child: &mut Child) -> io::Result<()> {
             return Ok(());
         }
 
-        let result = unsafe { libc::killpg(pgid, libc::SIGKILL) };
-        if result == -1 {
-            let err = std::io::Error::last_os_error();
-            if err.kind() != ErrorKind::NotFound {
-                return Err(err);
-            }
-        }
+        kill_process_group(pgid as u32)?;
     }
 
     Ok(())
 }
 
+#[cfg(unix)]
+fn kill_process_group(pgid: u32) -> io::Result<()> {
+    let result = unsafe { libc::killpg(pgid as libc::pid_t, libc::SIGKILL) };
+    if result == -1 {
+        let err = std::io::Error::last_os_error();
+        if err.kind() != io::ErrorKind::NotFound {
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn leads_own_process_group(pid: u32) -> bool {
+    let pid = pid as libc::pid_t;
+    unsafe { libc::getpgid(pid) == pid }
+}
+
 #[cfg(not(unix))]
 fn kill_child_process_group(_: &mut Child) -> io::Result<()> {
     Ok(())
 }
 
+#[cfg(not(unix))]
+fn kill_process_group(_: u32) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn leads_own_process_group(_: u32) -> bool {
+    false
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -911,6 +968,61 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(all(unix, not(any(target_os = "freebsd", target_os = "openbsd"))))]
+    #[tokio::test]
+    async fn dropping_exec_kills_grandchildren() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let pid_file = dir.path().join("grandchild.pid");
+        let command = vec![
+            "/bin/bash".to_string(),
+            "-c".to_string(),
+            format!("sleep 60 & echo $! > {}; sleep 60", pid_file.display()),
+        ];
+        let env: HashMap<String, String> = std::env::vars().collect();
+        let params = ExecParams {
+            command,
+            cwd: std::env::current_dir()?,
+            expiration: 60_000.into(),
+            env,
+            sandbox_permissions: SandboxPermissions::UseDefault,
+            justification: None,
+            arg0: None,
+        };
+
+        let handle = tokio::spawn(async move {
+            let policy = SandboxPolicy::ReadOnly;
+            exec(params, SandboxType::None, &policy, None).await
+        });
+        let mut pid = None;
+        for _ in 0..50 {
+            if let Ok(contents) = std::fs::read_to_string(&pid_file)
+                && let Ok(parsed) = contents.trim().parse::<i32>()
+            {
+                pid = Some(parsed);
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        let pid = pid.expect("grandchild pid written");
+
+        handle.abort();
+        let _ = handle.await;
+
+        let mut killed = false;
+        for _ in 0..20 {
+            if unsafe { libc::kill(pid, 0) } == -1
+                && let Some(libc::ESRCH) = std::io::Error::last_os_error().raw_os_error()
+            {
+                killed = true;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        assert!(killed, "grandchild process with pid {pid} is still alive");
+        Ok(())
+    }
+
     #[tokio::test]
     async fn process_exec_tool_call_respects_cancellation_token() -> Result<()> {
         let command = long_running_command();
@@ -8,6 +8,11 @@
 use crate::config::ConfigToml;
 use crate::config::profile::ConfigProfile;
 use codex_otel::OtelManager;
+use schemars::JsonSchema;
+use schemars::schema::InstanceType;
+use schemars::schema::ObjectValidation;
+use schemars::schema::Schema;
+use schemars::schema::SchemaObject;
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::BTreeMap;
@@ -15,6 +20,7 @@ use std::collections::BTreeSet;
 
 mod legacy;
 pub(crate) use legacy::LegacyFeatureToggles;
+pub(crate) use legacy::legacy_feature_aliases;
 
 /// High-level lifecycle stage for a feature.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -96,6 +102,16 @@ pub enum Feature {
     EnableRequestCompression,
     /// Enable collab tools.
     Collab,
+    /// Tell the model when files it read were changed outside the session.
+    WatchReadFiles,
+    /// Read files named by streaming `read_file` calls before the calls complete.
+    PrefetchReads,
+    /// Tell the model about the OS, toolchains and repository at session start.
+    EnvironmentFingerprint,
+    /// Expose `find_symbol`, backed by an index of the workspace's definitions.
+    SymbolIndex,
+    /// Scale the limit on each tool output to the room left in the context window.
+    AdaptiveTruncation,
 }
 
 impl Feature {
@@ -298,6 +314,36 @@ pub struct FeaturesToml {
     pub entries: BTreeMap<String, bool>,
 }
 
+// Known keys (including legacy aliases) are listed so that schema validation
+// can flag misspelled feature names.
+impl JsonSchema for FeaturesToml {
+    fn schema_name() -> String {
+        "FeaturesToml".to_string()
+    }
+
+    fn json_schema(generator: &mut schemars::r#gen::SchemaGenerator) -> Schema {
+        let keys = FEATURES
+            .iter()
+            .map(|spec| spec.key)
+            .chain(legacy::legacy_feature_aliases().map(|(key, _)| key));
+        let mut object = ObjectValidation {
+            additional_properties: Some(Box::new(Schema::Bool(false))),
+            ..Default::default()
+        };
+        for key in keys {
+            object
+                .properties
+                .insert(key.to_string(), generator.subschema_for::<bool>());
+        }
+        SchemaObject {
+            instance_type: Some(InstanceType::Object.into()),
+            object: Some(Box::new(object)),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
 /// Single, easy-to-read registry of all feature definitions.
 #[derive(Debug, Clone, Copy)]
 pub struct FeatureSpec {
@@ -414,6 +460,36 @@ pub const FEATURES: &[FeatureSpec] = &[
         stage: Stage::Experimental,
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::WatchReadFiles,
+        key: "watch_read_files",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::PrefetchReads,
+        key: "prefetch_reads",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::EnvironmentFingerprint,
+        key: "environment_fingerprint",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::SymbolIndex,
+        key: "symbol_index",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::AdaptiveTruncation,
+        key: "adaptive_truncation",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
     FeatureSpec {
         id: Feature::Tui2,
         key: "tui2",
@@ -31,6 +31,14 @@ const ALIASES: &[Alias] = &[
     },
 ];
 
+/// Keys still accepted under `[features]` in place of a canonical feature
+/// key, with the feature each one maps to.
+pub(crate) fn legacy_feature_aliases() -> impl Iterator<Item = (&'static str, Feature)> {
+    ALIASES
+        .iter()
+        .map(|alias| (alias.legacy_key, alias.feature))
+}
+
 pub(crate) fn feature_for_key(key: &str) -> Option<Feature> {
     ALIASES
         .iter()
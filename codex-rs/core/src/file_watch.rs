@@ -0,0 +1,209 @@
+//! Change notices for files the agent has seen.
+//!
+//! With the `watch_read_files` feature, every workspace file the agent reads
+//! or patches is remembered together with its size and modification time.
+//! Those stamps are refreshed when a turn ends, so the agent's own edits are
+//! never reported; when the next turn starts, any file whose stamp differs was
+//! changed by someone else (an editor save, a `git checkout`) and the model is
+//! told to re-read it before relying on its contents.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use codex_protocol::protocol::CHANGED_FILES_CLOSE_TAG;
+use codex_protocol::protocol::CHANGED_FILES_OPEN_TAG;
+use codex_protocol::protocol::WatchedFilesChangedEvent;
+
+/// Upper bound on remembered files, so a session that greps through a huge
+/// tree does not stat thousands of files at every turn.
+const MAX_WATCHED_FILES: usize = 1_000;
+
+/// Size and modification time of a file, used to tell whether it changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FileStamp {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl FileStamp {
+    /// `None` when the file does not exist (or cannot be read).
+    pub(crate) fn of(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Self {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+}
+
+/// Files the agent has read or edited. The default watches nothing.
+#[derive(Default)]
+pub(crate) struct FileWatch {
+    enabled: bool,
+    files: Mutex<BTreeMap<PathBuf, Option<FileStamp>>>,
+}
+
+impl FileWatch {
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            files: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Remembers `path`, resolved against `cwd`, if it lies inside `cwd`.
+    pub(crate) fn track(&self, cwd: &Path, path: &Path) {
+        if !self.enabled {
+            return;
+        }
+        let path = cwd.join(path);
+        if !path.starts_with(cwd) {
+            return;
+        }
+        let mut files = self.lock();
+        if files.len() >= MAX_WATCHED_FILES && !files.contains_key(&path) {
+            return;
+        }
+        let stamp = FileStamp::of(&path);
+        files.insert(path, stamp);
+    }
+
+    /// Takes the current state of every remembered file as the version the
+    /// agent knows. Called when a turn ends.
+    pub(crate) fn refresh(&self) {
+        if !self.enabled {
+            return;
+        }
+        for (path, stamp) in self.lock().iter_mut() {
+            *stamp = FileStamp::of(path);
+        }
+    }
+
+    /// Returns the remembered files that changed since the last refresh and
+    /// takes their new state as known. Deleted files are forgotten.
+    pub(crate) fn take_changes(&self) -> Option<WatchedFilesChangedEvent> {
+        if !self.enabled {
+            return None;
+        }
+        let mut changed = Vec::new();
+        let mut removed = Vec::new();
+        let mut files = self.lock();
+        files.retain(|path, stamp| {
+            let current = FileStamp::of(path);
+            if current == *stamp {
+                return true;
+            }
+            *stamp = current;
+            if current.is_some() {
+                changed.push(path.clone());
+                true
+            } else {
+                removed.push(path.clone());
+                false
+            }
+        });
+        (!changed.is_empty() || !removed.is_empty())
+            .then_some(WatchedFilesChangedEvent { changed, removed })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<PathBuf, Option<FileStamp>>> {
+        match self.files.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        }
+    }
+}
+
+/// Text of the message recorded when watched files changed between turns.
+/// Paths are shown relative to `cwd`.
+pub(crate) fn changed_files_notice(event: &WatchedFilesChangedEvent, cwd: &Path) -> String {
+    let mut lines = vec![
+        CHANGED_FILES_OPEN_TAG.to_string(),
+        "These files changed outside this session since you last read or edited them. Re-read them before relying on or editing their contents.".to_string(),
+    ];
+    let display = |path: &PathBuf| path.strip_prefix(cwd).unwrap_or(path).display().to_string();
+    lines.extend(
+        event
+            .changed
+            .iter()
+            .map(|path| format!("modified: {}", display(path))),
+    );
+    lines.extend(
+        event
+            .removed
+            .iter()
+            .map(|path| format!("deleted: {}", display(path))),
+    );
+    lines.push(CHANGED_FILES_CLOSE_TAG.to_string());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn reports_changes_made_after_the_turn_ended() -> anyhow::Result<()> {
+        let cwd = tempfile::tempdir()?;
+        let edited = cwd.path().join("edited.rs");
+        let deleted = cwd.path().join("deleted.rs");
+        let untouched = cwd.path().join("untouched.rs");
+        for path in [&edited, &deleted, &untouched] {
+            std::fs::write(path, "fn main() {}")?;
+        }
+        let watch = FileWatch::new(true);
+        watch.track(cwd.path(), Path::new("edited.rs"));
+        watch.track(cwd.path(), &deleted);
+        watch.track(cwd.path(), &untouched);
+        watch.track(cwd.path(), Path::new("/etc/hosts"));
+
+        // The agent's own edit during the turn is not reported.
+        std::fs::write(&untouched, "fn main() { run(); }")?;
+        watch.refresh();
+
+        std::fs::write(&edited, "fn main() { edited(); }")?;
+        std::fs::remove_file(&deleted)?;
+
+        assert_eq!(
+            watch.take_changes(),
+            Some(WatchedFilesChangedEvent {
+                changed: vec![edited.clone()],
+                removed: vec![deleted.clone()],
+            })
+        );
+        assert_eq!(watch.take_changes(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn disabled_watch_tracks_nothing() -> anyhow::Result<()> {
+        let cwd = tempfile::tempdir()?;
+        let path = cwd.path().join("file.txt");
+        std::fs::write(&path, "one")?;
+        let watch = FileWatch::default();
+        watch.track(cwd.path(), &path);
+        std::fs::write(&path, "two two")?;
+
+        assert_eq!(watch.take_changes(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn notice_lists_paths_relative_to_cwd() {
+        let event = WatchedFilesChangedEvent {
+            changed: vec![PathBuf::from("/repo/src/lib.rs")],
+            removed: vec![PathBuf::from("/repo/old.rs")],
+        };
+
+        assert_eq!(
+            changed_files_notice(&event, Path::new("/repo")),
+            format!(
+                "{CHANGED_FILES_OPEN_TAG}\nThese files changed outside this session since you last read or edited them. Re-read them before relying on or editing their contents.\nmodified: src/lib.rs\ndeleted: old.rs\n{CHANGED_FILES_CLOSE_TAG}"
+            )
+        );
+    }
+}
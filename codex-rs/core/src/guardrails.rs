@@ -0,0 +1,230 @@
+//! Circuit breaker for tools that keep failing.
+//!
+//! [`ToolFailureBreaker`] is registered as the built-in hook
+//! `tool_failure_breaker` on the events that report tool results
+//! (`exec_command_end`, `mcp_tool_call_end` and `patch_apply_end`). It counts
+//! consecutive failures per tool: commands by program name, patches as
+//! `apply_patch` and MCP tools as `server/tool`. Once a tool reaches its limit
+//! from `[guardrails]` the breaker trips, and the task loop ends the turn with
+//! a `guardrail_tripped` event instead of sending the failure back to the
+//! model for another attempt.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ExecCommandSource;
+use codex_protocol::protocol::GuardrailTrippedEvent;
+
+use crate::bash::parse_shell_lc_plain_commands;
+use crate::config::types::GuardrailsConfig;
+use crate::hooks::HookHandler;
+use crate::hooks::HookIsolation;
+
+/// Hook name the breaker is registered under.
+pub(crate) const TOOL_FAILURE_BREAKER: &str = "tool_failure_breaker";
+
+/// Events that carry the result of a tool call.
+pub(crate) const TOOL_RESULT_EVENTS: &[&str] =
+    &["exec_command_end", "mcp_tool_call_end", "patch_apply_end"];
+
+const APPLY_PATCH_TOOL: &str = "apply_patch";
+
+#[derive(Default)]
+struct BreakerState {
+    /// Consecutive failures so far, by tool.
+    failures: HashMap<String, u32>,
+    tripped: Option<GuardrailTrippedEvent>,
+}
+
+/// Counts consecutive tool failures. The default never trips.
+#[derive(Default)]
+pub(crate) struct ToolFailureBreaker {
+    default_limit: Option<u32>,
+    limits: BTreeMap<String, u32>,
+    state: Mutex<BreakerState>,
+}
+
+impl ToolFailureBreaker {
+    pub(crate) fn new(config: &GuardrailsConfig) -> Self {
+        Self {
+            default_limit: config.max_consecutive_tool_failures,
+            limits: config.tool_failure_limits.clone(),
+            state: Mutex::default(),
+        }
+    }
+
+    /// Whether any tool has a limit, i.e. whether the hook is worth
+    /// registering.
+    pub(crate) fn enabled(&self) -> bool {
+        self.default_limit.is_some_and(|limit| limit > 0) || self.limits.values().any(|l| *l > 0)
+    }
+
+    fn limit(&self, tool: &str) -> Option<u32> {
+        self.limits
+            .get(tool)
+            .copied()
+            .or(self.default_limit)
+            .filter(|limit| *limit > 0)
+    }
+
+    /// Records the outcome of one call of `tool`.
+    pub(crate) fn record(&self, tool: &str, success: bool) {
+        let mut state = self.lock();
+        if success {
+            state.failures.remove(tool);
+            return;
+        }
+        let failures = state.failures.entry(tool.to_string()).or_default();
+        *failures += 1;
+        let failures = *failures;
+        if state.tripped.is_none()
+            && let Some(limit) = self.limit(tool)
+            && failures >= limit
+        {
+            state.tripped = Some(GuardrailTrippedEvent {
+                guardrail: TOOL_FAILURE_BREAKER.to_string(),
+                tool: tool.to_string(),
+                failures,
+                message: format!(
+                    "Stopped after {failures} consecutive failed `{tool}` calls. Fix the underlying problem or raise the limit under [guardrails], then continue."
+                ),
+            });
+        }
+    }
+
+    /// Returns the trip, if the breaker tripped, and starts counting afresh.
+    pub(crate) fn take_trip(&self) -> Option<GuardrailTrippedEvent> {
+        let mut state = self.lock();
+        let tripped = state.tripped.take()?;
+        state.failures.clear();
+        Some(tripped)
+    }
+
+    /// Forgets every failure. Called when a task starts, since the user may
+    /// have fixed the problem in between.
+    pub(crate) fn reset(&self) {
+        *self.lock() = BreakerState::default();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerState> {
+        match self.state.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        }
+    }
+}
+
+#[async_trait]
+impl HookHandler for ToolFailureBreaker {
+    async fn handle(&self, _event: &Event) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// The work happens in `dispatched`, so there is nothing to spawn.
+    fn isolation(&self) -> HookIsolation {
+        HookIsolation::Inline
+    }
+
+    fn dispatched(&self, event: &Event) {
+        match &event.msg {
+            EventMsg::ExecCommandEnd(end)
+                if matches!(
+                    end.source,
+                    ExecCommandSource::Agent | ExecCommandSource::UnifiedExecStartup
+                ) =>
+            {
+                self.record(&command_tool(&end.command), end.exit_code == 0);
+            }
+            EventMsg::McpToolCallEnd(end) => {
+                let tool = format!("{}/{}", end.invocation.server, end.invocation.tool);
+                self.record(&tool, end.is_success());
+            }
+            EventMsg::PatchApplyEnd(end) => self.record(APPLY_PATCH_TOOL, end.success),
+            _ => {}
+        }
+    }
+}
+
+/// Program a command runs, e.g. `cargo` for `bash -lc "cargo test"`.
+fn command_tool(command: &[String]) -> String {
+    let program = parse_shell_lc_plain_commands(command)
+        .and_then(|commands| commands.into_iter().next())
+        .and_then(|words| words.into_iter().next())
+        .or_else(|| command.first().cloned())
+        .unwrap_or_default();
+    Path::new(&program)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or(program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn breaker(default_limit: Option<u32>, limits: &[(&str, u32)]) -> ToolFailureBreaker {
+        ToolFailureBreaker::new(&GuardrailsConfig {
+            max_consecutive_tool_failures: default_limit,
+            tool_failure_limits: limits
+                .iter()
+                .map(|(tool, limit)| (tool.to_string(), *limit))
+                .collect(),
+        })
+    }
+
+    #[test]
+    fn trips_after_consecutive_failures_of_one_tool() {
+        let breaker = breaker(Some(3), &[]);
+        breaker.record("cargo", false);
+        breaker.record("cargo", false);
+        // A failure of another tool does not reset the count; a success does.
+        breaker.record("rg", false);
+        breaker.record("rg", true);
+        assert_eq!(breaker.take_trip(), None);
+
+        breaker.record("cargo", false);
+        let trip = breaker.take_trip().expect("tripped");
+        assert_eq!(trip.tool, "cargo");
+        assert_eq!(trip.failures, 3);
+        assert_eq!(breaker.take_trip(), None);
+    }
+
+    #[test]
+    fn per_tool_limits_override_the_default() {
+        let breaker = breaker(None, &[("apply_patch", 2), ("docs/search", 0)]);
+        assert!(breaker.enabled());
+        for _ in 0..5 {
+            breaker.record("cargo", false);
+            breaker.record("docs/search", false);
+        }
+        assert_eq!(breaker.take_trip(), None);
+
+        breaker.record("apply_patch", false);
+        breaker.record("apply_patch", false);
+        assert_eq!(
+            breaker.take_trip().map(|trip| trip.tool),
+            Some("apply_patch".to_string())
+        );
+        assert!(!ToolFailureBreaker::default().enabled());
+    }
+
+    #[test]
+    fn commands_are_grouped_by_program() {
+        let script = |script: &str| vec!["bash".to_string(), "-lc".to_string(), script.to_string()];
+        assert_eq!(command_tool(&script("cargo test -p core")), "cargo");
+        assert_eq!(
+            command_tool(&script("/usr/bin/make all && echo ok")),
+            "make"
+        );
+        assert_eq!(
+            command_tool(&["npm".to_string(), "test".to_string()]),
+            "npm"
+        );
+    }
+}
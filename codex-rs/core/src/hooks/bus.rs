@@ -0,0 +1,203 @@
+//! Event bus that lets async consumers read session events as a stream
+//! instead of registering a [`HookHandler`](super::HookHandler).
+
+use std::sync::Arc;
+
+use codex_protocol::protocol::Event;
+use futures::Stream;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use super::EventExpr;
+use super::matches_event;
+
+/// Number of events buffered for subscribers before the slowest one starts
+/// missing events.
+pub(super) const DEFAULT_EVENT_BUS_CAPACITY: usize = 1024;
+
+/// An event published on the bus.
+#[derive(Debug, Clone)]
+pub struct HookEvent {
+    /// Event name as used in hook filters, e.g. `task_complete`.
+    pub name: String,
+    pub event: Arc<Event>,
+}
+
+/// What a subscription does when it falls more than the bus capacity behind.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LagPolicy {
+    /// Drop the events that were missed, log a warning and keep streaming.
+    #[default]
+    Skip,
+    /// End the stream, for consumers that cannot tolerate gaps.
+    Close,
+}
+
+/// Selects the events a subscription receives.
+#[derive(Debug, Clone)]
+pub struct EventFilter {
+    events: Vec<String>,
+    expr: Option<EventExpr>,
+    lag_policy: LagPolicy,
+}
+
+impl EventFilter {
+    /// Every event.
+    pub fn all() -> Self {
+        Self::events([super::ALL_EVENTS])
+    }
+
+    /// Only the named events; [`ALL_EVENTS`](super::ALL_EVENTS) matches every
+    /// event.
+    pub fn events<I, S>(events: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            events: events.into_iter().map(Into::into).collect(),
+            expr: None,
+            lag_policy: LagPolicy::default(),
+        }
+    }
+
+    /// Further limits the subscription to events that pass `expr`. Events
+    /// that fail it never reach the stream.
+    pub fn matching(mut self, expr: EventExpr) -> Self {
+        self.expr = Some(expr);
+        self
+    }
+
+    pub fn on_lag(mut self, lag_policy: LagPolicy) -> Self {
+        self.lag_policy = lag_policy;
+        self
+    }
+
+    fn matches(&self, event: &HookEvent) -> bool {
+        matches_event(&self.events, &event.name, true)
+            && self
+                .expr
+                .as_ref()
+                .is_none_or(|expr| expr.matches(&event.name, &event.event))
+    }
+}
+
+impl Default for EventFilter {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+pub(super) fn subscribe(
+    receiver: broadcast::Receiver<HookEvent>,
+    filter: EventFilter,
+) -> impl Stream<Item = HookEvent> + Send + 'static {
+    futures::stream::unfold((receiver, filter), |(mut receiver, filter)| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) if filter.matches(&event) => {
+                    return Some((event, (receiver, filter)));
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => match filter.lag_policy {
+                    LagPolicy::Skip => {
+                        tracing::warn!("event subscriber fell behind; skipped {skipped} events");
+                    }
+                    LagPolicy::Close => return None,
+                },
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hooks::Hooks;
+    use codex_protocol::protocol::EventMsg;
+    use codex_protocol::protocol::WarningEvent;
+    use futures::StreamExt;
+    use pretty_assertions::assert_eq;
+
+    fn warning(id: &str) -> Event {
+        Event {
+            id: id.to_string(),
+            msg: EventMsg::Warning(WarningEvent {
+                message: "careful".to_string(),
+            }),
+        }
+    }
+
+    fn shutdown_complete(id: &str) -> Event {
+        Event {
+            id: id.to_string(),
+            msg: EventMsg::ShutdownComplete,
+        }
+    }
+
+    #[tokio::test]
+    async fn subscribers_receive_matching_events_in_order() {
+        let hooks = Hooks::default();
+        let all = hooks.subscribe(EventFilter::all());
+        let warnings = hooks.subscribe(EventFilter::events(["warning"]));
+
+        hooks.dispatch(&warning("1")).await;
+        hooks.dispatch(&shutdown_complete("2")).await;
+        hooks.dispatch(&warning("3")).await;
+        drop(hooks);
+
+        let all: Vec<(String, String)> = all
+            .map(|event| (event.name, event.event.id.clone()))
+            .collect()
+            .await;
+        let warnings: Vec<String> = warnings.map(|event| event.event.id.clone()).collect().await;
+        assert_eq!(
+            all,
+            vec![
+                ("warning".to_string(), "1".to_string()),
+                ("shutdown_complete".to_string(), "2".to_string()),
+                ("warning".to_string(), "3".to_string()),
+            ]
+        );
+        assert_eq!(warnings, vec!["1".to_string(), "3".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn expressions_narrow_subscriptions() {
+        let hooks = Hooks::default();
+        let expr = EventExpr::parse(r#"data.message contains "disk""#).expect("filter compiles");
+        let disk = hooks.subscribe(EventFilter::events(["warning"]).matching(expr));
+
+        hooks.dispatch(&warning("1")).await;
+        hooks
+            .dispatch(&Event {
+                id: "2".to_string(),
+                msg: EventMsg::Warning(WarningEvent {
+                    message: "disk almost full".to_string(),
+                }),
+            })
+            .await;
+        drop(hooks);
+
+        let ids: Vec<String> = disk.map(|event| event.event.id.clone()).collect().await;
+        assert_eq!(ids, vec!["2".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn lagging_subscribers_follow_their_policy() {
+        let hooks = Hooks::default().with_event_bus_capacity(2);
+        let skipping = hooks.subscribe(EventFilter::all());
+        let closing = hooks.subscribe(EventFilter::all().on_lag(LagPolicy::Close));
+
+        for id in ["1", "2", "3", "4"] {
+            hooks.dispatch(&warning(id)).await;
+        }
+        drop(hooks);
+
+        let skipped: Vec<String> = skipping.map(|event| event.event.id.clone()).collect().await;
+        let closed: Vec<HookEvent> = closing.collect().await;
+        assert_eq!(skipped, vec!["3".to_string(), "4".to_string()]);
+        assert!(closed.is_empty());
+    }
+}
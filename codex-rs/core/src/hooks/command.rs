@@ -0,0 +1,299 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use codex_protocol::protocol::Event;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Child;
+use tokio::process::Command;
+
+use super::HookHandler;
+use super::REDACTION_EVENT;
+use crate::shell::ShellType;
+use crate::shell::detect_shell_type;
+use crate::variables::Variables;
+
+const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Runs an external program for each event. The event is written to the
+/// program's stdin as JSON and its name is exported as `CODEX_HOOK_EVENT`.
+/// `{{name}}` placeholders in the arguments are replaced by the session's
+/// variables; in the script of a `sh -c`-style command they become references
+/// to `CODEX_VAR_<name>` environment variables instead, so values are never
+/// parsed by the shell. Output is discarded; a non-zero exit status counts as
+/// a failure.
+/// A redaction hook is run with [`CommandHook::redact`] instead.
+pub struct CommandHook {
+    command: Vec<String>,
+    timeout: Duration,
+    variables: Variables,
+}
+
+impl CommandHook {
+    pub fn new(command: Vec<String>, timeout_ms: Option<u64>) -> Self {
+        Self {
+            command,
+            timeout: timeout_ms.map_or(DEFAULT_HOOK_TIMEOUT, Duration::from_millis),
+            variables: Variables::default(),
+        }
+    }
+
+    /// Interpolates the arguments with `variables` on every run.
+    pub fn with_variables(mut self, variables: Variables) -> Self {
+        self.variables = variables;
+        self
+    }
+
+    /// Runs the command on the text of a tool output: `text` is written to
+    /// its stdin, `CODEX_HOOK_EVENT` is set to [`REDACTION_EVENT`], and what
+    /// it prints replaces the text. Fails unless it exits with status 0 and
+    /// prints valid UTF-8.
+    pub async fn redact(&self, text: &str) -> Result<String, String> {
+        let (program, mut child) = self.spawn(REDACTION_EVENT, Stdio::piped())?;
+        // Written while stdout is read, so a hook that streams its output
+        // cannot fill a pipe and stall.
+        let stdin = child.stdin.take();
+        let input = text.as_bytes().to_vec();
+        let writer = tokio::spawn(async move {
+            if let Some(mut stdin) = stdin {
+                let _ = stdin.write_all(&input).await;
+            }
+        });
+        let result = tokio::time::timeout(self.timeout, child.wait_with_output()).await;
+        writer.abort();
+        match result {
+            Ok(Ok(output)) if output.status.success() => String::from_utf8(output.stdout)
+                .map_err(|_| format!("`{program}` printed invalid UTF-8")),
+            Ok(Ok(output)) => Err(format!("`{program}` exited with {}", output.status)),
+            Ok(Err(err)) => Err(format!("failed to wait for `{program}`: {err}")),
+            Err(_) => Err(format!(
+                "`{program}` timed out after {}ms",
+                self.timeout.as_millis()
+            )),
+        }
+    }
+
+    /// Spawns the command with its arguments interpolated and stdin piped.
+    fn spawn(&self, event_name: &str, stdout: Stdio) -> Result<(&str, Child), String> {
+        let Some((program, args)) = self.command.split_first() else {
+            return Err("hook command is empty".to_string());
+        };
+        let script_index = shell_script_index(program, args);
+        let mut env = BTreeMap::new();
+        let args: Vec<String> = args
+            .iter()
+            .enumerate()
+            .map(|(index, arg)| {
+                if Some(index) == script_index {
+                    let script = self.variables.shell_script(arg);
+                    env = script.env;
+                    script.script
+                } else {
+                    self.variables.interpolate(arg)
+                }
+            })
+            .collect();
+        let child = Command::new(program)
+            .args(&args)
+            .envs(&env)
+            .env("CODEX_HOOK_EVENT", event_name)
+            .stdin(Stdio::piped())
+            .stdout(stdout)
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|err| format!("failed to spawn `{program}`: {err}"))?;
+        Ok((program, child))
+    }
+}
+
+/// The index in `args` of the script run by a POSIX shell invoked as
+/// `sh -c <script>` (or `bash -lc`, `zsh -c`, ...).
+fn shell_script_index(program: &str, args: &[String]) -> Option<usize> {
+    match detect_shell_type(&PathBuf::from(program))? {
+        ShellType::Sh | ShellType::Bash | ShellType::Zsh => {}
+        ShellType::PowerShell | ShellType::Cmd => return None,
+    }
+    args.iter()
+        .position(|arg| arg.starts_with('-') && !arg.starts_with("--") && arg.contains('c'))
+        .map(|flags| flags + 1)
+        .filter(|&script| script < args.len())
+}
+
+#[async_trait]
+impl HookHandler for CommandHook {
+    async fn handle(&self, event: &Event) -> Result<(), String> {
+        let payload =
+            serde_json::to_vec(event).map_err(|err| format!("failed to serialize event: {err}"))?;
+        let (program, mut child) = self.spawn(&event.msg.to_string(), Stdio::null())?;
+
+        let run = async {
+            if let Some(mut stdin) = child.stdin.take() {
+                // Hooks are free to ignore their input, so a closed pipe is fine.
+                let _ = stdin.write_all(&payload).await;
+            }
+            child.wait().await
+        };
+        match tokio::time::timeout(self.timeout, run).await {
+            Ok(Ok(status)) if status.success() => Ok(()),
+            Ok(Ok(status)) => Err(format!("`{program}` exited with {status}")),
+            Ok(Err(err)) => Err(format!("failed to wait for `{program}`: {err}")),
+            Err(_) => Err(format!(
+                "`{program}` timed out after {}ms",
+                self.timeout.as_millis()
+            )),
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::EventMsg;
+    use codex_protocol::protocol::WarningEvent;
+    use pretty_assertions::assert_eq;
+
+    fn warning() -> Event {
+        Event {
+            id: "sub-1".to_string(),
+            msg: EventMsg::Warning(WarningEvent {
+                message: "careful".to_string(),
+            }),
+        }
+    }
+
+    fn sh(script: &str) -> Vec<String> {
+        vec!["sh".to_string(), "-c".to_string(), script.to_string()]
+    }
+
+    #[tokio::test]
+    async fn passes_event_on_stdin_and_name_in_env() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let out = dir.path().join("event.json");
+        let script = format!(
+            "printf '%s ' \"$CODEX_HOOK_EVENT\" > '{path}'; cat >> '{path}'",
+            path = out.display()
+        );
+
+        CommandHook::new(sh(&script), None)
+            .handle(&warning())
+            .await
+            .expect("hook succeeds");
+
+        assert_eq!(
+            std::fs::read_to_string(&out).expect("read output"),
+            r#"warning {"id":"sub-1","msg":{"type":"warning","message":"careful"}}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn interpolates_variables_in_arguments() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let out = dir.path().join("args.txt");
+        let variables = Variables::default();
+        variables
+            .update(
+                [("branch_name".to_string(), Some("fix/login".to_string()))]
+                    .into_iter()
+                    .collect(),
+            )
+            .expect("valid names");
+        let mut command = sh(&format!("printf '%s' \"$0\" > '{}'", out.display()));
+        command.push("{{branch_name}}".to_string());
+
+        CommandHook::new(command, None)
+            .with_variables(variables)
+            .handle(&warning())
+            .await
+            .expect("hook succeeds");
+
+        assert_eq!(
+            std::fs::read_to_string(&out).expect("read output"),
+            "fix/login"
+        );
+    }
+
+    #[tokio::test]
+    async fn passes_variables_to_shell_scripts_through_the_environment() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let out = dir.path().join("args.txt");
+        let marker = dir.path().join("injected");
+        let title = format!("it's $(touch '{}')", marker.display());
+        let variables = Variables::default();
+        variables
+            .update(
+                [("title".to_string(), Some(title.clone()))]
+                    .into_iter()
+                    .collect(),
+            )
+            .expect("valid names");
+        let command = sh(&format!(
+            "printf '%s|%s|%s' {{{{title}}}} \"{{{{title}}}}\" '{{{{title}}}}' > '{}'",
+            out.display()
+        ));
+
+        CommandHook::new(command, None)
+            .with_variables(variables)
+            .handle(&warning())
+            .await
+            .expect("hook succeeds");
+
+        assert_eq!(
+            std::fs::read_to_string(&out).expect("read output"),
+            format!("{title}|{title}|{title}")
+        );
+        assert!(!marker.exists());
+    }
+
+    #[test]
+    fn finds_the_script_of_shell_commands() {
+        let args = |args: &[&str]| args.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(shell_script_index("sh", &args(&["-c", "echo"])), Some(1));
+        assert_eq!(
+            shell_script_index("/bin/bash", &args(&["-lc", "echo", "arg0"])),
+            Some(1)
+        );
+        assert_eq!(shell_script_index("bash", &args(&["-c"])), None);
+        assert_eq!(
+            shell_script_index("./notify.sh", &args(&["-c", "echo"])),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn redaction_replaces_text_with_stdout() {
+        assert_eq!(
+            CommandHook::new(
+                sh("printf '%s ' \"$CODEX_HOOK_EVENT\"; sed s/hunter2/xxx/"),
+                None
+            )
+            .redact("password: hunter2")
+            .await,
+            Ok(format!("{REDACTION_EVENT} password: xxx"))
+        );
+        assert_eq!(
+            CommandHook::new(sh("cat > /dev/null; exit 1"), None)
+                .redact("password: hunter2")
+                .await,
+            Err("`sh` exited with exit status: 1".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_failures_and_timeouts() {
+        assert_eq!(
+            CommandHook::new(sh("exit 3"), None)
+                .handle(&warning())
+                .await,
+            Err("`sh` exited with exit status: 3".to_string())
+        );
+        assert_eq!(
+            CommandHook::new(sh("sleep 5"), Some(50))
+                .handle(&warning())
+                .await,
+            Err("`sh` timed out after 50ms".to_string())
+        );
+    }
+}
@@ -0,0 +1,543 @@
+//! A small expression language for selecting events.
+//!
+//! Filters such as `event == "exec_command_end" && data.exit_code != 0` are
+//! compiled once into an [`EventExpr`] and evaluated against each event.
+//! `event` is the event name used by hooks, `id` the id of the submission the
+//! event belongs to, and `data.<field>` reads the event's JSON payload; a
+//! number selects an array element, as in `data.command.0`. Missing fields
+//! are `null`.
+//!
+//! Operands compare with `==`, `!=`, `<`, `<=`, `>`, `>=` and `contains`
+//! (substring, or array element), and combine with `&&`, `||`, `!` and
+//! parentheses. An operand on its own is true unless it is `null`, `false`,
+//! `0`, or an empty string, array or object.
+
+use std::cell::OnceCell;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use codex_protocol::protocol::Event;
+use serde_json::Value;
+
+/// Error from compiling a filter, with the 1-based column it occurred at.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid filter at column {column}: {message}")]
+pub struct FilterError {
+    pub column: usize,
+    pub message: String,
+}
+
+/// A compiled event filter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventExpr {
+    source: String,
+    expr: Expr,
+}
+
+impl EventExpr {
+    pub fn parse(source: &str) -> Result<Self, FilterError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+            end: source.chars().count() + 1,
+        };
+        let expr = parser.or()?;
+        if let Some((token, column)) = parser.tokens.get(parser.position) {
+            return Err(FilterError {
+                column: *column,
+                message: format!("unexpected {token}"),
+            });
+        }
+        Ok(Self {
+            source: source.to_string(),
+            expr,
+        })
+    }
+
+    /// The filter as written.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether `event`, named `name`, passes the filter.
+    pub fn matches(&self, name: &str, event: &Event) -> bool {
+        self.matches_input(&FilterInput::new(name, event))
+    }
+
+    pub(crate) fn matches_input(&self, input: &FilterInput<'_>) -> bool {
+        self.expr.eval(input)
+    }
+}
+
+impl FromStr for EventExpr {
+    type Err = FilterError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        Self::parse(source)
+    }
+}
+
+impl fmt::Display for EventExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// An event being filtered. The JSON payload is serialized on first use and
+/// shared by every filter evaluated against the same input.
+pub(crate) struct FilterInput<'a> {
+    name: &'a str,
+    event: &'a Event,
+    data: OnceCell<Value>,
+}
+
+impl<'a> FilterInput<'a> {
+    pub(crate) fn new(name: &'a str, event: &'a Event) -> Self {
+        Self {
+            name,
+            event,
+            data: OnceCell::new(),
+        }
+    }
+
+    fn data(&self) -> &Value {
+        self.data
+            .get_or_init(|| serde_json::to_value(&self.event.msg).unwrap_or(Value::Null))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Operand, CompareOp, Operand),
+    Truthy(Operand),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Literal(Value),
+    Event,
+    Id,
+    Data(Vec<String>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+}
+
+impl Expr {
+    fn eval(&self, input: &FilterInput<'_>) -> bool {
+        match self {
+            Expr::Or(left, right) => left.eval(input) || right.eval(input),
+            Expr::And(left, right) => left.eval(input) && right.eval(input),
+            Expr::Not(expr) => !expr.eval(input),
+            Expr::Compare(left, op, right) => {
+                compare(&left.resolve(input), *op, &right.resolve(input))
+            }
+            Expr::Truthy(operand) => truthy(&operand.resolve(input)),
+        }
+    }
+}
+
+impl Operand {
+    fn resolve(&self, input: &FilterInput<'_>) -> Value {
+        match self {
+            Operand::Literal(value) => value.clone(),
+            Operand::Event => Value::String(input.name.to_string()),
+            Operand::Id => Value::String(input.event.id.clone()),
+            Operand::Data(path) => {
+                let mut value = input.data();
+                for segment in path {
+                    let next = match value {
+                        Value::Object(map) => map.get(segment),
+                        Value::Array(items) => segment
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|index| items.get(index)),
+                        _ => None,
+                    };
+                    match next {
+                        Some(next) => value = next,
+                        None => return Value::Null,
+                    }
+                }
+                value.clone()
+            }
+        }
+    }
+}
+
+fn compare(left: &Value, op: CompareOp, right: &Value) -> bool {
+    match op {
+        CompareOp::Eq => equal(left, right),
+        CompareOp::Ne => !equal(left, right),
+        CompareOp::Contains => match (left, right) {
+            (Value::String(haystack), Value::String(needle)) => haystack.contains(needle.as_str()),
+            (Value::Array(items), needle) => items.iter().any(|item| equal(item, needle)),
+            _ => false,
+        },
+        CompareOp::Lt | CompareOp::Le | CompareOp::Gt | CompareOp::Ge => {
+            let ordering = match (left, right) {
+                (Value::Number(left), Value::Number(right)) => left
+                    .as_f64()
+                    .zip(right.as_f64())
+                    .and_then(|(left, right)| left.partial_cmp(&right)),
+                (Value::String(left), Value::String(right)) => Some(left.cmp(right)),
+                _ => None,
+            };
+            let Some(ordering) = ordering else {
+                return false;
+            };
+            match op {
+                CompareOp::Lt => ordering == Ordering::Less,
+                CompareOp::Le => ordering != Ordering::Greater,
+                CompareOp::Gt => ordering == Ordering::Greater,
+                _ => ordering != Ordering::Less,
+            }
+        }
+    }
+}
+
+/// Like `==` on JSON values, except that `1` equals `1.0`.
+fn equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(left), Value::Number(right)) => left.as_f64() == right.as_f64(),
+        _ => left == right,
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(value) => *value,
+        Value::Number(number) => number.as_f64().is_some_and(|number| number != 0.0),
+        Value::String(value) => !value.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Compare(CompareOp),
+    Literal(Value),
+    Path(Vec<String>),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::LParen => f.write_str("`(`"),
+            Token::RParen => f.write_str("`)`"),
+            Token::And => f.write_str("`&&`"),
+            Token::Or => f.write_str("`||`"),
+            Token::Not => f.write_str("`!`"),
+            Token::Compare(op) => write!(f, "`{}`", op.symbol()),
+            Token::Literal(value) => write!(f, "`{value}`"),
+            Token::Path(path) => write!(f, "`{}`", path.join(".")),
+        }
+    }
+}
+
+impl CompareOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            CompareOp::Eq => "==",
+            CompareOp::Ne => "!=",
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+            CompareOp::Contains => "contains",
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, FilterError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let column = i + 1;
+        let error = |message: String| FilterError { column, message };
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let (token, len) = match (c, next) {
+            (c, _) if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            ('(', _) => (Token::LParen, 1),
+            (')', _) => (Token::RParen, 1),
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('=', Some('=')) => (Token::Compare(CompareOp::Eq), 2),
+            ('!', Some('=')) => (Token::Compare(CompareOp::Ne), 2),
+            ('!', _) => (Token::Not, 1),
+            ('<', Some('=')) => (Token::Compare(CompareOp::Le), 2),
+            ('<', _) => (Token::Compare(CompareOp::Lt), 1),
+            ('>', Some('=')) => (Token::Compare(CompareOp::Ge), 2),
+            ('>', _) => (Token::Compare(CompareOp::Gt), 1),
+            ('"', _) => {
+                let mut value = String::new();
+                let mut end = i + 1;
+                loop {
+                    match chars.get(end) {
+                        None => return Err(error("unterminated string".to_string())),
+                        Some('"') => break,
+                        Some('\\') => {
+                            match chars.get(end + 1) {
+                                Some('n') => value.push('\n'),
+                                Some('t') => value.push('\t'),
+                                Some(escaped @ ('"' | '\\')) => value.push(*escaped),
+                                _ => return Err(error("invalid escape in string".to_string())),
+                            }
+                            end += 2;
+                        }
+                        Some(c) => {
+                            value.push(*c);
+                            end += 1;
+                        }
+                    }
+                }
+                (Token::Literal(Value::String(value)), end + 1 - i)
+            }
+            (c, _) if c.is_ascii_digit() || c == '-' => {
+                let len = chars[i + 1..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit() || **c == '.')
+                    .count()
+                    + 1;
+                let text: String = chars[i..i + len].iter().collect();
+                let value = match text.parse::<i64>() {
+                    Ok(number) => Value::from(number),
+                    Err(_) => text
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(serde_json::Number::from_f64)
+                        .map(Value::Number)
+                        .ok_or_else(|| error(format!("invalid number `{text}`")))?,
+                };
+                (Token::Literal(value), len)
+            }
+            (c, _) if c.is_ascii_alphabetic() || c == '_' => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_alphanumeric() || **c == '_' || **c == '.')
+                    .count();
+                let word: String = chars[i..i + len].iter().collect();
+                let token = match word.as_str() {
+                    "true" => Token::Literal(Value::Bool(true)),
+                    "false" => Token::Literal(Value::Bool(false)),
+                    "null" => Token::Literal(Value::Null),
+                    "contains" => Token::Compare(CompareOp::Contains),
+                    _ => Token::Path(word.split('.').map(str::to_string).collect()),
+                };
+                (token, len)
+            }
+            (c, _) => return Err(error(format!("unexpected `{c}`"))),
+        };
+        tokens.push((token, column));
+        i += len;
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    /// Column reported for errors at the end of the input.
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn column(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .map_or(self.end, |(_, column)| *column)
+    }
+
+    fn error(&self, message: impl Into<String>) -> FilterError {
+        FilterError {
+            column: self.column(),
+            message: message.into(),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, FilterError> {
+        let mut expr = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, FilterError> {
+        let mut expr = self.unary()?;
+        while self.peek() == Some(&Token::And) {
+            self.position += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, FilterError> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.position += 1;
+                Ok(Expr::Not(Box::new(self.unary()?)))
+            }
+            Some(Token::LParen) => {
+                self.position += 1;
+                let expr = self.or()?;
+                if self.peek() != Some(&Token::RParen) {
+                    return Err(self.error("expected `)`"));
+                }
+                self.position += 1;
+                Ok(expr)
+            }
+            _ => {
+                let left = self.operand()?;
+                let Some(Token::Compare(op)) = self.peek() else {
+                    return Ok(Expr::Truthy(left));
+                };
+                let op = *op;
+                self.position += 1;
+                Ok(Expr::Compare(left, op, self.operand()?))
+            }
+        }
+    }
+
+    fn operand(&mut self) -> Result<Operand, FilterError> {
+        let Some((token, _)) = self.tokens.get(self.position) else {
+            return Err(self.error("expected a value"));
+        };
+        let operand = match token {
+            Token::Literal(value) => Operand::Literal(value.clone()),
+            Token::Path(path) => match path.as_slice() {
+                [root] if root == "event" => Operand::Event,
+                [root] if root == "id" => Operand::Id,
+                [root, fields @ ..] if root == "data" && fields.iter().all(|f| !f.is_empty()) => {
+                    Operand::Data(fields.to_vec())
+                }
+                _ => {
+                    return Err(self.error(format!(
+                        "unknown name `{}`; expected `event`, `id` or `data.<field>`",
+                        path.join(".")
+                    )));
+                }
+            },
+            token => return Err(self.error(format!("expected a value, found {token}"))),
+        };
+        self.position += 1;
+        Ok(operand)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::EventMsg;
+    use codex_protocol::protocol::WarningEvent;
+    use pretty_assertions::assert_eq;
+
+    fn warning(message: &str) -> Event {
+        Event {
+            id: "sub-1".to_string(),
+            msg: EventMsg::Warning(WarningEvent {
+                message: message.to_string(),
+            }),
+        }
+    }
+
+    fn matches(filter: &str, event: &Event) -> bool {
+        EventExpr::parse(filter)
+            .expect("filter compiles")
+            .matches(&event.msg.to_string(), event)
+    }
+
+    #[test]
+    fn filters_compare_event_names_ids_and_payload_fields() {
+        let event = warning("disk almost full");
+        assert!(matches(r#"event == "warning""#, &event));
+        assert!(matches(
+            r#"id == "sub-1" && data.message contains "disk""#,
+            &event
+        ));
+        assert!(!matches(
+            r#"event == "error" || data.message == "disk""#,
+            &event
+        ));
+        assert!(matches(
+            r#"!(event == "error") && data.type == "warning""#,
+            &event
+        ));
+        // Missing fields are null, which is not equal to a number.
+        assert!(matches("data.exit_code != 0 && !data.exit_code", &event));
+        assert!(!matches("data.exit_code > 0", &event));
+        assert!(matches("data.message", &event));
+    }
+
+    #[test]
+    fn comparisons_follow_json_types() {
+        let value = |source: &str| match tokenize(source).expect("tokens").remove(0).0 {
+            Token::Literal(value) => value,
+            token => panic!("not a literal: {token}"),
+        };
+        assert!(compare(&value("1"), CompareOp::Eq, &value("1.0")));
+        assert!(compare(&value("-2"), CompareOp::Lt, &value("1.5")));
+        assert!(compare(&value(r#""b""#), CompareOp::Ge, &value(r#""a""#)));
+        assert!(!compare(&value(r#""2""#), CompareOp::Gt, &value("1")));
+        assert!(compare(
+            &serde_json::json!(["cargo", "test"]),
+            CompareOp::Contains,
+            &value(r#""test""#)
+        ));
+    }
+
+    #[test]
+    fn invalid_filters_report_the_column() {
+        let error = |source: &str| EventExpr::parse(source).expect_err("invalid").to_string();
+        assert_eq!(
+            error(r#"event = "warning""#),
+            "invalid filter at column 7: unexpected `=`"
+        );
+        assert_eq!(
+            error("exit_code != 0"),
+            "invalid filter at column 1: unknown name `exit_code`; expected `event`, `id` or `data.<field>`"
+        );
+        assert_eq!(
+            error(r#"(event == "warning""#),
+            "invalid filter at column 20: expected `)`"
+        );
+        assert_eq!(
+            error(r#"event == "warning" data"#),
+            "invalid filter at column 20: unexpected `data`"
+        );
+        assert_eq!(
+            error("event =="),
+            "invalid filter at column 9: expected a value"
+        );
+    }
+}
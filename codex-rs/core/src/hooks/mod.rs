@@ -0,0 +1,1236 @@
+//! Hooks run in response to events emitted by a session.
+//!
+//! A [`Hooks`] registry holds named hooks, each subscribed to a set of event
+//! names (the snake_case `type` of an [`EventMsg`]). Every event a session
+//! emits is dispatched to the enabled hooks that match it, highest priority
+//! first. Most hooks observe events without blocking the session: each run
+//! happens on its own task. Blocking hooks instead run one at a time and are
+//! awaited, and one that fails vetoes the event (see [`HookVeto`]). Where a
+//! run executes, and so what a panic in it can take down, is set by the
+//! handler's [`HookIsolation`]. The
+//! outcome and timing of every run are recorded for introspection (`/hooks`).
+//! Async consumers can instead read events as a stream with
+//! [`Hooks::subscribe`]. Redaction hooks (`redact = true`) do not see events:
+//! [`Hooks::redact`] runs them on the text of every tool output before it is
+//! recorded, and their output replaces it.
+//!
+//! [`EventMsg`]: codex_protocol::protocol::EventMsg
+
+mod bus;
+mod command;
+mod filter;
+mod process;
+mod sink;
+pub mod testing;
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::RwLock;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use chrono::SecondsFormat;
+use chrono::Utc;
+use codex_otel::OtelManager;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::HookFiring;
+use codex_protocol::protocol::HookSource;
+use codex_protocol::protocol::HookSummary;
+use futures::Stream;
+use tokio::sync::broadcast;
+use tracing::Instrument;
+use tracing::info_span;
+
+pub use bus::EventFilter;
+pub use bus::HookEvent;
+pub use bus::LagPolicy;
+pub use command::CommandHook;
+pub use filter::EventExpr;
+pub use filter::FilterError;
+pub use sink::SinkService;
+pub use sink::WebhookSink;
+
+use filter::FilterInput;
+
+use crate::config::types::CommandHookConfig;
+use crate::config::types::HooksConfig;
+use crate::crash_report;
+use crate::variables::Variables;
+
+/// Number of completed hook runs kept for introspection.
+const HOOK_FIRING_HISTORY: usize = 50;
+
+/// Event pattern that matches every event.
+pub const ALL_EVENTS: &str = "*";
+
+/// Event pattern that matches every event carrying model reasoning.
+pub const REASONING_EVENTS: &str = "reasoning";
+
+const REASONING_EVENT_NAMES: &[&str] = &[
+    "agent_reasoning",
+    "agent_reasoning_delta",
+    "agent_reasoning_raw_content",
+    "agent_reasoning_raw_content_delta",
+    "agent_reasoning_section_break",
+    "reasoning_content_delta",
+    "reasoning_raw_content_delta",
+];
+
+/// `CODEX_HOOK_EVENT` of a redaction hook run, and the event its runs are
+/// recorded under.
+pub const REDACTION_EVENT: &str = "redaction";
+
+/// Events a blocking hook can veto. On any other event a blocking hook runs
+/// in the background like the rest, so that streaming is never held up.
+pub const VETOABLE_EVENTS: &[&str] = &["exec_approval_request", "apply_patch_approval_request"];
+
+/// Something that reacts to session events.
+///
+/// A handler is shared by every run of its hook, and runs may overlap, so
+/// its state must be `Send + Sync` and owned or behind an `Arc`. Panics are
+/// never caught with `AssertUnwindSafe`: a [`HookIsolation::Task`] run that
+/// panics is abandoned where it stopped, so a handler with such isolation
+/// must leave its state consistent at every point it can panic, and should
+/// recover poisoned locks with `PoisonError::into_inner`.
+#[async_trait]
+pub trait HookHandler: Send + Sync {
+    /// Handles `event`, returning a short description of the failure if the
+    /// hook did not succeed.
+    async fn handle(&self, event: &Event) -> Result<(), String>;
+
+    /// Called synchronously from [`Hooks::dispatch`], in the order events are
+    /// emitted, before `handle` is spawned. Most hooks only need `handle`.
+    /// Runs on the dispatching task whatever the isolation, so it must not
+    /// panic.
+    fn dispatched(&self, _event: &Event) {}
+
+    /// Where `handle` runs.
+    fn isolation(&self) -> HookIsolation {
+        HookIsolation::Task
+    }
+}
+
+/// Where a hook's [`HookHandler::handle`] runs, from cheapest to most
+/// robust.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HookIsolation {
+    /// Awaited on the task that dispatched the event, so the session waits
+    /// for it even when the hook is not blocking, and a panic unwinds into
+    /// the session. For small handlers that cannot panic.
+    Inline,
+    /// On a Tokio task of its own. Non-blocking hooks never hold up the
+    /// session, and a panic fails the run instead of reaching the session.
+    #[default]
+    Task,
+    /// In a child process forked for each run, for handlers that call into
+    /// native code that may crash. A crash, an abort, or a run longer than
+    /// 30 seconds fails the run and leaves the session untouched. The child
+    /// works on a copy of the session's memory with only the forking thread
+    /// in it: changes the handler makes to its own state are lost, so results
+    /// must go through its return value or the outside world, and it must not
+    /// wait on anything another thread would provide, such as a lock held at
+    /// the time of the fork. Unix only; elsewhere every run fails.
+    Process,
+}
+
+/// A blocking hook that failed on an event. The hooks after it do not run for
+/// that event, and an approval request it vetoes is denied instead of being
+/// shown to the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookVeto {
+    pub hook: String,
+    pub reason: String,
+}
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum HookError {
+    #[error("no hook named `{0}`")]
+    NotFound(String),
+    #[error("built-in hook `{0}` cannot be toggled")]
+    BuiltIn(String),
+    #[error("hook `{0}` is required by your administrator")]
+    Managed(String),
+}
+
+/// A hook registered in code by an embedder rather than in `[hooks]`. Such
+/// hooks are part of the host application, so they cannot be toggled from
+/// `/hooks`.
+#[derive(Clone)]
+pub struct HookRegistration {
+    pub name: String,
+    /// Event names to run on; [`ALL_EVENTS`] matches every event.
+    pub events: Vec<String>,
+    pub handler: Arc<dyn HookHandler>,
+}
+
+#[derive(Clone)]
+struct RegisteredHook {
+    name: String,
+    events: Vec<String>,
+    source: HookSource,
+    enabled: bool,
+    priority: i32,
+    blocking: bool,
+    /// Whether [`ALL_EVENTS`] also matches streaming `*_delta` events. Off for
+    /// command hooks, which would otherwise spawn a process per token.
+    wildcard_deltas: bool,
+    filter: Option<EventExpr>,
+    handler: Arc<dyn HookHandler>,
+}
+
+/// Compiles the `filter` of the configured hook `name`. Loading the config
+/// rejects invalid filters, so a failure here only drops the filter.
+fn compile_filter(name: &str, filter: Option<&str>) -> Option<EventExpr> {
+    match EventExpr::parse(filter?) {
+        Ok(expr) => Some(expr),
+        Err(err) => {
+            tracing::warn!("ignoring the filter of hook `{name}`: {err}");
+            None
+        }
+    }
+}
+
+/// Whether a hook with the `only_when_env` condition `condition` may run in
+/// this process.
+fn env_gate_holds(condition: Option<&str>) -> bool {
+    condition
+        .is_none_or(|condition| env_condition_holds(condition, |name| std::env::var(name).ok()))
+}
+
+/// Whether `condition` holds: `NAME` requires the variable to be set to a
+/// non-empty value, `NAME=value` requires that exact value.
+fn env_condition_holds(condition: &str, var: impl Fn(&str) -> Option<String>) -> bool {
+    match condition.split_once('=') {
+        Some((name, expected)) => var(name.trim()).is_some_and(|value| value == expected),
+        None => var(condition.trim()).is_some_and(|value| !value.is_empty()),
+    }
+}
+
+fn matches_event(patterns: &[String], event_name: &str, wildcard_deltas: bool) -> bool {
+    patterns.iter().any(|pattern| {
+        if pattern == ALL_EVENTS && !wildcard_deltas && is_delta_event(event_name) {
+            return false;
+        }
+        pattern_matches(pattern, event_name)
+    })
+}
+
+/// Whether `event_name` is one of the streaming events emitted per chunk of
+/// output, such as `agent_message_delta`.
+fn is_delta_event(event_name: &str) -> bool {
+    event_name.ends_with("_delta")
+}
+
+/// Whether `pattern`, an event name, [`ALL_EVENTS`] or [`REASONING_EVENTS`],
+/// matches the event named `event_name`.
+pub fn pattern_matches(pattern: &str, event_name: &str) -> bool {
+    pattern == ALL_EVENTS
+        || pattern == event_name
+        || (pattern == REASONING_EVENTS && REASONING_EVENT_NAMES.contains(&event_name))
+}
+
+impl RegisteredHook {
+    fn matches(&self, event_name: &str, input: &FilterInput<'_>) -> bool {
+        self.enabled
+            && matches_event(&self.events, event_name, self.wildcard_deltas)
+            && self
+                .filter
+                .as_ref()
+                .is_none_or(|filter| filter.matches_input(input))
+    }
+
+    fn summary(&self) -> HookSummary {
+        HookSummary {
+            name: self.name.clone(),
+            events: self.events.clone(),
+            source: self.source,
+            enabled: self.enabled,
+            priority: self.priority,
+            blocking: self.blocking,
+        }
+    }
+}
+
+/// The registered hooks of a [`Hooks`] registry and their recent runs, saved
+/// by [`Hooks::snapshot`]. Handlers are shared with the registry they came
+/// from rather than copied.
+#[derive(Clone)]
+pub struct HooksSnapshot {
+    hooks: Vec<RegisteredHook>,
+    firings: VecDeque<HookFiring>,
+}
+
+impl HooksSnapshot {
+    /// The saved hooks in the order they run.
+    pub fn hooks(&self) -> Vec<HookSummary> {
+        self.hooks.iter().map(RegisteredHook::summary).collect()
+    }
+}
+
+/// A configured hook with `redact = true`.
+struct RedactionHook {
+    name: String,
+    handler: CommandHook,
+}
+
+/// A redaction hook that failed, so the text it was given must not be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedactionFailure {
+    pub hook: String,
+    pub reason: String,
+}
+
+/// Registry of the hooks attached to a session.
+pub struct Hooks {
+    hooks: RwLock<Vec<RegisteredHook>>,
+    /// Run in this order on every tool output; see [`Hooks::redact`].
+    redaction_hooks: Vec<RedactionHook>,
+    firings: Arc<Mutex<VecDeque<HookFiring>>>,
+    otel_manager: Option<OtelManager>,
+    bus: broadcast::Sender<HookEvent>,
+}
+
+impl Default for Hooks {
+    fn default() -> Self {
+        Self {
+            hooks: RwLock::default(),
+            redaction_hooks: Vec::new(),
+            firings: Arc::default(),
+            otel_manager: None,
+            bus: broadcast::channel(bus::DEFAULT_EVENT_BUS_CAPACITY).0,
+        }
+    }
+}
+
+impl Hooks {
+    /// Reports each hook run to `otel_manager` as a metric and log event.
+    pub fn with_otel_manager(mut self, otel_manager: OtelManager) -> Self {
+        self.otel_manager = Some(otel_manager);
+        self
+    }
+
+    /// Buffers up to `capacity` events for subscribers; see [`LagPolicy`] for
+    /// what happens to subscribers that fall further behind. Existing
+    /// subscriptions are closed.
+    pub fn with_event_bus_capacity(mut self, capacity: usize) -> Self {
+        self.bus = broadcast::channel(capacity).0;
+        self
+    }
+
+    /// Builds a registry containing the external hooks from `[hooks]` and the
+    /// `required` hooks from `requirements.toml`. A required hook replaces a
+    /// user hook with the same name. The sinks in `[hooks.sinks]` are added as
+    /// external hooks named after their table, e.g. `sinks.slack`. Hooks with
+    /// `enabled = false`, or whose `only_when_env` condition does not hold,
+    /// are registered turned off. Command hooks interpolate their arguments
+    /// with `variables`. Redaction hooks are kept apart, highest priority
+    /// first; a disabled user redaction hook is left out.
+    pub fn from_config(
+        config: &HooksConfig,
+        required: &BTreeMap<String, CommandHookConfig>,
+        variables: &Variables,
+    ) -> Self {
+        let mut hooks = Self::default();
+        let configured = config
+            .commands
+            .iter()
+            .map(|hook| (hook, HookSource::External))
+            .chain(required.iter().map(|hook| (hook, HookSource::Managed)));
+        let mut redaction_hooks = Vec::new();
+        for ((name, hook), source) in configured {
+            if hook.redact {
+                let enabled = match source {
+                    HookSource::Managed => {
+                        hooks.remove(name);
+                        true
+                    }
+                    _ => {
+                        !required.contains_key(name)
+                            && hook.enabled
+                            && env_gate_holds(hook.only_when_env.as_deref())
+                    }
+                };
+                if enabled {
+                    redaction_hooks.push((hook.priority, name, hook));
+                }
+                continue;
+            }
+            hooks.insert(RegisteredHook {
+                name: name.clone(),
+                events: hook.on.clone(),
+                source,
+                enabled: hook.enabled && env_gate_holds(hook.only_when_env.as_deref()),
+                priority: hook.priority,
+                blocking: hook.blocking,
+                wildcard_deltas: false,
+                filter: compile_filter(name, hook.filter.as_deref()),
+                handler: Arc::new(
+                    CommandHook::new(hook.command.clone(), hook.timeout_ms)
+                        .with_variables(variables.clone()),
+                ),
+            });
+        }
+        let sinks = [
+            (SinkService::Slack, &config.sinks.slack),
+            (SinkService::Discord, &config.sinks.discord),
+        ];
+        for (service, sink) in sinks {
+            let Some(sink) = sink else {
+                continue;
+            };
+            let handler = WebhookSink::new(service, sink.clone());
+            let name = service.hook_name();
+            hooks.insert(RegisteredHook {
+                name: name.to_string(),
+                events: handler.events(),
+                source: HookSource::External,
+                enabled: sink.enabled && env_gate_holds(sink.only_when_env.as_deref()),
+                priority: 0,
+                blocking: false,
+                wildcard_deltas: true,
+                filter: compile_filter(name, sink.filter.as_deref()),
+                handler: Arc::new(handler),
+            });
+        }
+        redaction_hooks.sort_by_key(|(priority, ..)| Reverse(*priority));
+        hooks.redaction_hooks = redaction_hooks
+            .into_iter()
+            .map(|(_, name, hook)| RedactionHook {
+                name: name.clone(),
+                handler: CommandHook::new(hook.command.clone(), hook.timeout_ms)
+                    .with_variables(variables.clone()),
+            })
+            .collect();
+        hooks
+    }
+
+    /// Adds the embedder hooks in `registrations`. Each replaces a configured
+    /// hook with the same name, unless that hook is required by the
+    /// administrator.
+    pub fn with_registrations(self, registrations: Vec<HookRegistration>) -> Self {
+        for registration in registrations {
+            let managed = self
+                .hooks()
+                .iter()
+                .any(|hook| hook.name == registration.name && hook.source == HookSource::Managed);
+            if managed {
+                tracing::warn!(
+                    "ignoring hook `{}`: a required hook has the same name",
+                    registration.name
+                );
+                continue;
+            }
+            self.register(
+                registration.name,
+                registration.events,
+                HookSource::BuiltIn,
+                registration.handler,
+            );
+        }
+        self
+    }
+
+    /// Registers `handler` under `name` as a non-blocking hook with the
+    /// default priority, replacing any hook with that name.
+    pub fn register(
+        &self,
+        name: impl Into<String>,
+        events: Vec<String>,
+        source: HookSource,
+        handler: Arc<dyn HookHandler>,
+    ) {
+        self.insert(RegisteredHook {
+            name: name.into(),
+            events,
+            source,
+            enabled: true,
+            priority: 0,
+            blocking: false,
+            wildcard_deltas: true,
+            filter: None,
+            handler,
+        });
+    }
+
+    /// Adds `hook`, replacing any hook with its name, and keeps the hooks
+    /// sorted by priority. Hooks with the same priority keep their
+    /// registration order.
+    fn insert(&self, hook: RegisteredHook) {
+        let mut hooks = match self.hooks.write() {
+            Ok(hooks) => hooks,
+            Err(err) => err.into_inner(),
+        };
+        match hooks.iter_mut().find(|existing| existing.name == hook.name) {
+            Some(existing) => *existing = hook,
+            None => hooks.push(hook),
+        }
+        hooks.sort_by_key(|hook| Reverse(hook.priority));
+    }
+
+    fn remove(&self, name: &str) {
+        let mut hooks = match self.hooks.write() {
+            Ok(hooks) => hooks,
+            Err(err) => err.into_inner(),
+        };
+        hooks.retain(|hook| hook.name != name);
+    }
+
+    /// Registered hooks in the order they run.
+    pub fn hooks(&self) -> Vec<HookSummary> {
+        let hooks = match self.hooks.read() {
+            Ok(hooks) => hooks,
+            Err(err) => err.into_inner(),
+        };
+        hooks.iter().map(RegisteredHook::summary).collect()
+    }
+
+    /// The most recent completed hook runs, oldest first.
+    pub fn recent_firings(&self) -> Vec<HookFiring> {
+        let firings = match self.firings.lock() {
+            Ok(firings) => firings,
+            Err(err) => err.into_inner(),
+        };
+        firings.iter().cloned().collect()
+    }
+
+    /// Enables or disables the external hook `name`. Built-in and managed
+    /// hooks always stay enabled.
+    pub fn set_enabled(&self, name: &str, enabled: bool) -> Result<(), HookError> {
+        let mut hooks = match self.hooks.write() {
+            Ok(hooks) => hooks,
+            Err(err) => err.into_inner(),
+        };
+        let Some(hook) = hooks.iter_mut().find(|hook| hook.name == name) else {
+            return Err(HookError::NotFound(name.to_string()));
+        };
+        match hook.source {
+            HookSource::BuiltIn => return Err(HookError::BuiltIn(name.to_string())),
+            HookSource::Managed => return Err(HookError::Managed(name.to_string())),
+            HookSource::External => {}
+        }
+        hook.enabled = enabled;
+        Ok(())
+    }
+
+    /// Saves the registered hooks, including whether each is enabled, and the
+    /// recent runs.
+    pub fn snapshot(&self) -> HooksSnapshot {
+        let hooks = match self.hooks.read() {
+            Ok(hooks) => hooks.clone(),
+            Err(err) => err.into_inner().clone(),
+        };
+        let firings = match self.firings.lock() {
+            Ok(firings) => firings.clone(),
+            Err(err) => err.into_inner().clone(),
+        };
+        HooksSnapshot { hooks, firings }
+    }
+
+    /// Replaces the external hooks and recent runs with those in
+    /// `snapshot`. Built-in and managed hooks belong to this registry's
+    /// session, e.g. its tool failure breaker, so they are kept, and saved
+    /// external hooks with the same name as one of them are dropped.
+    /// Subscriptions are unaffected, and hook runs already in flight still
+    /// record their outcome.
+    pub fn restore(&self, snapshot: HooksSnapshot) {
+        let HooksSnapshot {
+            hooks: saved_hooks,
+            firings: saved_firings,
+        } = snapshot;
+        {
+            let mut hooks = match self.hooks.write() {
+                Ok(hooks) => hooks,
+                Err(err) => err.into_inner(),
+            };
+            hooks.retain(|hook| hook.source != HookSource::External);
+            let own: Vec<String> = hooks.iter().map(|hook| hook.name.clone()).collect();
+            hooks.extend(
+                saved_hooks.into_iter().filter(|hook| {
+                    hook.source == HookSource::External && !own.contains(&hook.name)
+                }),
+            );
+            hooks.sort_by_key(|hook| Reverse(hook.priority));
+        }
+        let mut firings = match self.firings.lock() {
+            Ok(firings) => firings,
+            Err(err) => err.into_inner(),
+        };
+        *firings = saved_firings;
+    }
+
+    /// Whether any redaction hook is configured.
+    pub fn has_redaction_hooks(&self) -> bool {
+        !self.redaction_hooks.is_empty()
+    }
+
+    /// Passes `text` through every redaction hook in turn and returns what
+    /// the last one printed. Each hook is awaited before the next, so the
+    /// result is final before the text is recorded or sent anywhere. Fails
+    /// with the first hook that fails; its caller must then withhold `text`.
+    pub async fn redact(&self, text: &str) -> Result<String, RedactionFailure> {
+        let mut text = text.to_string();
+        for hook in &self.redaction_hooks {
+            let started_at = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+            let start = Instant::now();
+            let result = hook
+                .handler
+                .redact(&text)
+                .instrument(
+                    info_span!("hook", hook.name = %hook.name, hook.event = REDACTION_EVENT),
+                )
+                .await;
+            let elapsed = start.elapsed();
+            if let Some(otel_manager) = &self.otel_manager {
+                otel_manager.hook_run(&hook.name, REDACTION_EVENT, result.is_ok(), elapsed);
+            }
+            record_firing(
+                &self.firings,
+                HookFiring {
+                    hook: hook.name.clone(),
+                    event: REDACTION_EVENT.to_string(),
+                    started_at,
+                    duration_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+                    error: result.clone().err(),
+                },
+            );
+            match result {
+                Ok(redacted) => text = redacted,
+                Err(reason) => {
+                    tracing::warn!("redaction hook `{}` failed: {reason}", hook.name);
+                    return Err(RedactionFailure {
+                        hook: hook.name.clone(),
+                        reason,
+                    });
+                }
+            }
+        }
+        Ok(text)
+    }
+
+    /// Streams the events matching `filter`, starting with the next one
+    /// dispatched. The stream ends once the registry is dropped.
+    pub fn subscribe(&self, filter: EventFilter) -> impl Stream<Item = HookEvent> + Send + 'static {
+        bus::subscribe(self.bus.subscribe(), filter)
+    }
+
+    /// Runs every enabled hook subscribed to `event`, highest priority
+    /// first, and publishes the event to subscribers. On one of the
+    /// [`VETOABLE_EVENTS`], blocking hooks are awaited one at a time, and the
+    /// first to fail vetoes the event and the hooks after it do not run. All
+    /// other runs are spawned on their own tasks. Must be called from within
+    /// a Tokio runtime.
+    pub async fn dispatch(&self, event: &Event) -> Option<HookVeto> {
+        let event_name = event.msg.to_string();
+        let vetoable = VETOABLE_EVENTS.contains(&event_name.as_str());
+        if self.bus.receiver_count() > 0 {
+            // Fails only when every subscriber has gone away since the check.
+            let _ = self.bus.send(HookEvent {
+                name: event_name.clone(),
+                event: Arc::new(event.clone()),
+            });
+        }
+        let handlers: Vec<(String, bool, Arc<dyn HookHandler>)> = {
+            let input = FilterInput::new(&event_name, event);
+            let hooks = match self.hooks.read() {
+                Ok(hooks) => hooks,
+                Err(err) => err.into_inner(),
+            };
+            hooks
+                .iter()
+                .filter(|hook| hook.matches(&event_name, &input))
+                .map(|hook| {
+                    let blocking = hook.blocking && vetoable;
+                    (hook.name.clone(), blocking, Arc::clone(&hook.handler))
+                })
+                .collect()
+        };
+        let hook_names: Vec<String> = handlers.iter().map(|(name, ..)| name.clone()).collect();
+        crash_report::record_event(&event.id, &event_name, &hook_names);
+        if handlers.is_empty() {
+            return None;
+        }
+
+        for (_, _, handler) in &handlers {
+            handler.dispatched(event);
+        }
+
+        let event = Arc::new(event.clone());
+        for (hook, blocking, handler) in handlers {
+            let isolation = handler.isolation();
+            let run = self.run_hook(hook.clone(), Arc::clone(&event), &event_name, handler);
+            if !blocking && isolation != HookIsolation::Inline {
+                tokio::spawn(run);
+                continue;
+            }
+            if let Err(reason) = run.await
+                && blocking
+            {
+                return Some(HookVeto { hook, reason });
+            }
+        }
+        None
+    }
+
+    fn run_hook(
+        &self,
+        hook: String,
+        event: Arc<Event>,
+        event_name: &str,
+        handler: Arc<dyn HookHandler>,
+    ) -> impl Future<Output = Result<(), String>> + Send + 'static {
+        let event_name = event_name.to_string();
+        let firings = Arc::clone(&self.firings);
+        let otel_manager = self.otel_manager.clone();
+        let span = info_span!("hook", hook.name = %hook, hook.event = %event_name);
+        async move {
+            let started_at = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+            let start = Instant::now();
+            let result = match handler.isolation() {
+                HookIsolation::Inline => handler.handle(&event).await,
+                HookIsolation::Task => {
+                    let event = Arc::clone(&event);
+                    let run = tokio::spawn(async move { handler.handle(&event).await });
+                    match run.await {
+                        Ok(result) => result,
+                        Err(err) if err.is_panic() => {
+                            Err(format!("panicked: {}", panic_message(&*err.into_panic())))
+                        }
+                        Err(err) => Err(err.to_string()),
+                    }
+                }
+                HookIsolation::Process => {
+                    process::run(handler, Arc::clone(&event), process::PROCESS_HOOK_TIMEOUT).await
+                }
+            };
+            let elapsed = start.elapsed();
+            if let Err(err) = &result {
+                tracing::warn!("hook `{hook}` failed on `{event_name}`: {err}");
+            }
+            if let Some(otel_manager) = &otel_manager {
+                otel_manager.hook_run(&hook, &event_name, result.is_ok(), elapsed);
+            }
+            record_firing(
+                &firings,
+                HookFiring {
+                    hook,
+                    event: event_name,
+                    started_at,
+                    duration_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+                    error: result.clone().err(),
+                },
+            );
+            result
+        }
+        .instrument(span)
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("Box<dyn Any>")
+}
+
+fn record_firing(firings: &Mutex<VecDeque<HookFiring>>, firing: HookFiring) {
+    let mut firings = match firings.lock() {
+        Ok(firings) => firings,
+        Err(err) => err.into_inner(),
+    };
+    if firings.len() == HOOK_FIRING_HISTORY {
+        firings.pop_front();
+    }
+    firings.push_back(firing);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::ApplyPatchApprovalRequestEvent;
+    use codex_protocol::protocol::EventMsg;
+    use codex_protocol::protocol::WarningEvent;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    struct FailingHook;
+
+    #[async_trait]
+    impl HookHandler for FailingHook {
+        async fn handle(&self, _event: &Event) -> Result<(), String> {
+            Err("boom".to_string())
+        }
+    }
+
+    struct OkHook;
+
+    #[async_trait]
+    impl HookHandler for OkHook {
+        async fn handle(&self, _event: &Event) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    /// Appends its name to a shared log, then succeeds or fails.
+    struct LoggingHook {
+        name: &'static str,
+        log: Arc<Mutex<Vec<&'static str>>>,
+        fails: bool,
+    }
+
+    #[async_trait]
+    impl HookHandler for LoggingHook {
+        async fn handle(&self, _event: &Event) -> Result<(), String> {
+            self.log.lock().expect("log").push(self.name);
+            if self.fails {
+                return Err("not allowed".to_string());
+            }
+            Ok(())
+        }
+    }
+
+    /// Panics on every event.
+    struct PanickingHook;
+
+    #[async_trait]
+    impl HookHandler for PanickingHook {
+        async fn handle(&self, _event: &Event) -> Result<(), String> {
+            panic!("oops");
+        }
+    }
+
+    /// Records that it ran, on the dispatching task.
+    struct InlineHook {
+        log: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    #[async_trait]
+    impl HookHandler for InlineHook {
+        async fn handle(&self, _event: &Event) -> Result<(), String> {
+            self.log.lock().expect("log").push("inline");
+            Ok(())
+        }
+
+        fn isolation(&self) -> HookIsolation {
+            HookIsolation::Inline
+        }
+    }
+
+    fn warning() -> Event {
+        Event {
+            id: "sub-1".to_string(),
+            msg: EventMsg::Warning(WarningEvent {
+                message: "careful".to_string(),
+            }),
+        }
+    }
+
+    fn patch_approval_request() -> Event {
+        Event {
+            id: "sub-1".to_string(),
+            msg: EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
+                call_id: "call-1".to_string(),
+                turn_id: "sub-1".to_string(),
+                changes: HashMap::new(),
+                reason: None,
+                grant_root: None,
+            }),
+        }
+    }
+
+    async fn wait_for_firings(hooks: &Hooks, count: usize) -> Vec<HookFiring> {
+        for _ in 0..200 {
+            let firings = hooks.recent_firings();
+            if firings.len() >= count {
+                return firings;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        hooks.recent_firings()
+    }
+
+    #[tokio::test]
+    async fn dispatch_runs_matching_hooks_and_records_outcomes() {
+        let hooks = Hooks::default();
+        hooks.register(
+            "fails",
+            vec!["warning".to_string()],
+            HookSource::External,
+            Arc::new(FailingHook),
+        );
+        hooks.register(
+            "other-event",
+            vec!["task_complete".to_string()],
+            HookSource::External,
+            Arc::new(OkHook),
+        );
+
+        hooks.dispatch(&warning()).await;
+
+        let firings = wait_for_firings(&hooks, 1).await;
+        assert_eq!(firings.len(), 1);
+        assert_eq!(firings[0].hook, "fails");
+        assert_eq!(firings[0].event, "warning");
+        assert_eq!(firings[0].error.as_deref(), Some("boom"));
+    }
+
+    #[tokio::test]
+    async fn blocking_hooks_run_by_priority_and_can_veto() {
+        let hooks = Hooks::default();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        for (name, priority, blocking, fails) in [
+            ("logger", 0, false, false),
+            ("policy", 10, true, true),
+            ("audit", 20, true, false),
+        ] {
+            hooks.insert(RegisteredHook {
+                name: name.to_string(),
+                events: vec!["apply_patch_approval_request".to_string()],
+                source: HookSource::External,
+                enabled: true,
+                priority,
+                blocking,
+                wildcard_deltas: true,
+                filter: None,
+                handler: Arc::new(LoggingHook {
+                    name,
+                    log: Arc::clone(&log),
+                    fails,
+                }),
+            });
+        }
+        let order: Vec<String> = hooks.hooks().into_iter().map(|hook| hook.name).collect();
+        assert_eq!(order, vec!["audit", "policy", "logger"]);
+
+        let veto = hooks.dispatch(&patch_approval_request()).await;
+
+        assert_eq!(
+            veto,
+            Some(HookVeto {
+                hook: "policy".to_string(),
+                reason: "not allowed".to_string(),
+            })
+        );
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(*log.lock().expect("log"), vec!["audit", "policy"]);
+    }
+
+    #[tokio::test]
+    async fn blocking_hooks_do_not_hold_up_other_events() {
+        let hooks = Hooks::default();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        hooks.insert(RegisteredHook {
+            name: "policy".to_string(),
+            events: vec![ALL_EVENTS.to_string()],
+            source: HookSource::External,
+            enabled: true,
+            priority: 0,
+            blocking: true,
+            wildcard_deltas: true,
+            filter: None,
+            handler: Arc::new(LoggingHook {
+                name: "policy",
+                log: Arc::clone(&log),
+                fails: true,
+            }),
+        });
+
+        assert_eq!(hooks.dispatch(&warning()).await, None);
+
+        let firings = wait_for_firings(&hooks, 1).await;
+        assert_eq!(firings[0].error.as_deref(), Some("not allowed"));
+    }
+
+    #[tokio::test]
+    async fn isolation_decides_where_hooks_run() {
+        let hooks = Hooks::default();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        hooks.register(
+            "inline",
+            vec![ALL_EVENTS.to_string()],
+            HookSource::External,
+            Arc::new(InlineHook {
+                log: Arc::clone(&log),
+            }),
+        );
+        hooks.insert(RegisteredHook {
+            name: "panics".to_string(),
+            events: vec![ALL_EVENTS.to_string()],
+            source: HookSource::External,
+            enabled: true,
+            priority: 0,
+            blocking: true,
+            wildcard_deltas: true,
+            filter: None,
+            handler: Arc::new(PanickingHook),
+        });
+
+        let veto = hooks.dispatch(&patch_approval_request()).await;
+
+        // The inline hook finished before dispatch returned, and the panic
+        // was reported as a failure instead of unwinding into the caller.
+        assert_eq!(*log.lock().expect("log"), vec!["inline"]);
+        assert_eq!(
+            veto,
+            Some(HookVeto {
+                hook: "panics".to_string(),
+                reason: "panicked: oops".to_string(),
+            })
+        );
+        let firings = hooks.recent_firings();
+        assert_eq!(firings.len(), 2);
+        assert_eq!(firings[1].error.as_deref(), Some("panicked: oops"));
+    }
+
+    #[tokio::test]
+    async fn disabled_hooks_do_not_fire() {
+        let hooks = Hooks::default();
+        hooks.register(
+            "all",
+            vec![ALL_EVENTS.to_string()],
+            HookSource::External,
+            Arc::new(OkHook),
+        );
+        hooks.set_enabled("all", false).expect("toggle");
+
+        hooks.dispatch(&warning()).await;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert_eq!(hooks.recent_firings(), Vec::new());
+        assert!(!hooks.hooks()[0].enabled);
+    }
+
+    #[tokio::test]
+    async fn restoring_a_snapshot_undoes_later_changes() {
+        let hooks = Hooks::default();
+        hooks.register(
+            "kept",
+            vec![ALL_EVENTS.to_string()],
+            HookSource::External,
+            Arc::new(FailingHook),
+        );
+        hooks.dispatch(&warning()).await;
+        wait_for_firings(&hooks, 1).await;
+        let snapshot = hooks.snapshot();
+
+        hooks.set_enabled("kept", false).expect("toggle");
+        hooks.register(
+            "added",
+            vec![ALL_EVENTS.to_string()],
+            HookSource::External,
+            Arc::new(OkHook),
+        );
+        hooks.restore(snapshot.clone());
+
+        assert_eq!(hooks.hooks(), snapshot.hooks());
+        let names: Vec<String> = hooks.hooks().into_iter().map(|hook| hook.name).collect();
+        assert_eq!(names, vec!["kept"]);
+        assert!(hooks.hooks()[0].enabled);
+        assert_eq!(hooks.recent_firings().len(), 1);
+
+        // A fresh registry restored from the snapshot runs the same hooks.
+        let fork = Hooks::default();
+        fork.restore(snapshot);
+        fork.dispatch(&warning()).await;
+        let firings = wait_for_firings(&fork, 2).await;
+        assert_eq!(firings[1].error.as_deref(), Some("boom"));
+    }
+
+    #[test]
+    fn restoring_a_snapshot_keeps_the_registrys_own_hooks() {
+        let parent = Hooks::default();
+        parent.register(
+            "user",
+            vec![ALL_EVENTS.to_string()],
+            HookSource::External,
+            Arc::new(OkHook),
+        );
+        parent.register(
+            "breaker",
+            vec![ALL_EVENTS.to_string()],
+            HookSource::BuiltIn,
+            Arc::new(OkHook),
+        );
+        let fork = Hooks::default();
+        fork.register(
+            "breaker",
+            vec!["exec_command_end".to_string()],
+            HookSource::BuiltIn,
+            Arc::new(FailingHook),
+        );
+        fork.register(
+            "stale",
+            vec![ALL_EVENTS.to_string()],
+            HookSource::External,
+            Arc::new(OkHook),
+        );
+
+        fork.restore(parent.snapshot());
+
+        let hooks: Vec<(String, Vec<String>)> = fork
+            .hooks()
+            .into_iter()
+            .map(|hook| (hook.name, hook.events))
+            .collect();
+        assert_eq!(
+            hooks,
+            vec![
+                ("breaker".to_string(), vec!["exec_command_end".to_string()]),
+                ("user".to_string(), vec![ALL_EVENTS.to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn only_existing_external_hooks_can_be_toggled() {
+        let hooks = Hooks::default();
+        hooks.register(
+            "builtin",
+            vec![ALL_EVENTS.to_string()],
+            HookSource::BuiltIn,
+            Arc::new(OkHook),
+        );
+
+        assert_eq!(
+            hooks.set_enabled("builtin", false),
+            Err(HookError::BuiltIn("builtin".to_string()))
+        );
+        assert_eq!(
+            hooks.set_enabled("missing", false),
+            Err(HookError::NotFound("missing".to_string()))
+        );
+    }
+
+    #[test]
+    fn reasoning_pattern_matches_only_reasoning_events() {
+        assert!(pattern_matches(REASONING_EVENTS, "agent_reasoning_delta"));
+        assert!(pattern_matches(
+            REASONING_EVENTS,
+            "reasoning_raw_content_delta"
+        ));
+        assert!(!pattern_matches(REASONING_EVENTS, "agent_message"));
+        assert!(pattern_matches(ALL_EVENTS, "agent_reasoning"));
+    }
+
+    #[test]
+    fn command_hook_wildcards_skip_streaming_deltas() {
+        let all = vec![ALL_EVENTS.to_string()];
+        assert!(!matches_event(&all, "agent_message_delta", false));
+        assert!(matches_event(&all, "agent_message", false));
+        assert!(matches_event(&all, "agent_message_delta", true));
+        assert!(matches_event(
+            &["agent_message_delta".to_string()],
+            "agent_message_delta",
+            false
+        ));
+        assert!(matches_event(
+            &[REASONING_EVENTS.to_string()],
+            "agent_reasoning_delta",
+            false
+        ));
+    }
+
+    #[test]
+    fn env_conditions_match_set_variables_and_values() {
+        let var = |name: &str| match name {
+            "CI" => Some("true".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        };
+        assert!(env_condition_holds("CI", var));
+        assert!(env_condition_holds("CI=true", var));
+        assert!(!env_condition_holds("CI=1", var));
+        assert!(!env_condition_holds("EMPTY", var));
+        assert!(env_condition_holds("EMPTY=", var));
+        assert!(!env_condition_holds("MISSING", var));
+    }
+
+    #[test]
+    fn required_hooks_replace_user_hooks_and_cannot_be_toggled() {
+        let hook = |program: &str| CommandHookConfig {
+            on: vec![ALL_EVENTS.to_string()],
+            command: vec![program.to_string()],
+            timeout_ms: None,
+            priority: 0,
+            blocking: false,
+            enabled: true,
+            only_when_env: None,
+            filter: None,
+            redact: false,
+        };
+        let config = HooksConfig {
+            commands: BTreeMap::from([
+                ("audit".to_string(), hook("true")),
+                ("notify".to_string(), hook("notify-send")),
+            ]),
+            sinks: Default::default(),
+        };
+        let required = BTreeMap::from([("audit".to_string(), hook("audit-log"))]);
+
+        let hooks = Hooks::from_config(&config, &required, &Variables::default());
+
+        let sources: Vec<(String, HookSource)> = hooks
+            .hooks()
+            .into_iter()
+            .map(|hook| (hook.name, hook.source))
+            .collect();
+        assert_eq!(
+            sources,
+            vec![
+                ("audit".to_string(), HookSource::Managed),
+                ("notify".to_string(), HookSource::External),
+            ]
+        );
+        assert_eq!(
+            hooks.set_enabled("audit", false),
+            Err(HookError::Managed("audit".to_string()))
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn redaction_hooks_run_in_priority_order_and_fail_closed() {
+        let hook = |script: &str, priority: i32| CommandHookConfig {
+            on: Vec::new(),
+            command: vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+            timeout_ms: None,
+            priority,
+            blocking: false,
+            enabled: true,
+            only_when_env: None,
+            filter: None,
+            redact: true,
+        };
+        let config = HooksConfig {
+            commands: BTreeMap::from([
+                ("mask".to_string(), hook("sed s/hunter2/xxx/", 1)),
+                ("tag".to_string(), hook("printf 'tagged: '; cat", 0)),
+            ]),
+            sinks: Default::default(),
+        };
+
+        let hooks = Hooks::from_config(&config, &BTreeMap::new(), &Variables::default());
+
+        assert!(hooks.hooks().is_empty());
+        assert_eq!(
+            hooks.redact("password: hunter2").await,
+            Ok("tagged: password: xxx".to_string())
+        );
+        assert_eq!(hooks.recent_firings().len(), 2);
+
+        let required = BTreeMap::from([("tag".to_string(), hook("cat > /dev/null; exit 2", 0))]);
+        let hooks = Hooks::from_config(&config, &required, &Variables::default());
+        assert_eq!(
+            hooks.redact("password: hunter2").await,
+            Err(RedactionFailure {
+                hook: "tag".to_string(),
+                reason: "`sh` exited with exit status: 2".to_string(),
+            })
+        );
+    }
+}
@@ -0,0 +1,308 @@
+//! [`HookIsolation::Process`]: each run of the handler happens in a child
+//! process forked for it, so a crash in native code, an abort or a hang fails
+//! the run instead of taking the session down.
+//!
+//! [`HookIsolation::Process`]: super::HookIsolation::Process
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use codex_protocol::protocol::Event;
+
+use super::HookHandler;
+
+/// How long a run may take before its process is killed.
+pub(super) const PROCESS_HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Runs `handler` on `event` in a helper process and waits for it. A crash
+/// or a timeout of the process is reported as a failure.
+#[cfg(unix)]
+pub(super) async fn run(
+    handler: Arc<dyn HookHandler>,
+    event: Arc<Event>,
+    timeout: Duration,
+) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || unix::run(handler.as_ref(), &event, timeout))
+        .await
+        .unwrap_or_else(|err| Err(format!("failed to supervise the helper process: {err}")))
+}
+
+#[cfg(not(unix))]
+pub(super) async fn run(
+    _handler: Arc<dyn HookHandler>,
+    _event: Arc<Event>,
+    _timeout: Duration,
+) -> Result<(), String> {
+    Err("process isolation is only supported on Unix".to_string())
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::fs::File;
+    use std::io::ErrorKind;
+    use std::io::Read;
+    use std::io::Write;
+    use std::os::fd::AsRawFd;
+    use std::os::fd::FromRawFd;
+    use std::os::fd::OwnedFd;
+    use std::panic::AssertUnwindSafe;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    use codex_protocol::protocol::Event;
+
+    use super::super::HookHandler;
+    use super::super::panic_message;
+
+    const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    /// Leading byte of the child's report when the handler succeeded.
+    const OK: u8 = 0;
+    /// Leading byte of the child's report when the handler failed; the
+    /// failure follows.
+    const FAILED: u8 = 1;
+
+    /// Longest failure the child reports. The parent only reads the pipe
+    /// once the child has exited, so the report must fit in its buffer.
+    const MAX_REPORT_BYTES: usize = 4000;
+
+    pub(super) fn run(
+        handler: &dyn HookHandler,
+        event: &Event,
+        timeout: Duration,
+    ) -> Result<(), String> {
+        let mut fds = [0; 2];
+        // SAFETY: `fds` has room for the two descriptors `pipe` writes.
+        if unsafe { libc::pipe(fds.as_mut_ptr()) } != 0 {
+            return Err(format!(
+                "failed to create a pipe: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        // SAFETY: `pipe` succeeded, so both descriptors are open and owned
+        // by nothing else.
+        let (reader, writer) =
+            unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+        // Programs other hooks spawn meanwhile must not inherit the pipe.
+        for fd in [&reader, &writer] {
+            // SAFETY: `fd` is an open descriptor owned by this function.
+            unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC) };
+        }
+
+        // SAFETY: the child only runs `run_child`, which never returns into
+        // the caller's frames and ends with `_exit`.
+        let pid = unsafe { libc::fork() };
+        if pid < 0 {
+            return Err(format!(
+                "failed to start the helper process: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        if pid == 0 {
+            drop(reader);
+            run_child(handler, event, writer);
+        }
+        drop(writer);
+        let status = wait(pid, timeout)?;
+        if libc::WIFSIGNALED(status) {
+            return Err(format!(
+                "the helper process crashed (signal {})",
+                libc::WTERMSIG(status)
+            ));
+        }
+        if !libc::WIFEXITED(status) || libc::WEXITSTATUS(status) != 0 {
+            return Err(format!(
+                "the helper process exited with status {}",
+                libc::WEXITSTATUS(status)
+            ));
+        }
+        match read_report(reader).as_slice() {
+            [OK] => Ok(()),
+            [FAILED, reason @ ..] => Err(String::from_utf8_lossy(reason).into_owned()),
+            _ => Err("the helper process exited without reporting a result".to_string()),
+        }
+    }
+
+    /// The forked child: only the thread that forked exists here, so the
+    /// handler runs on a runtime of its own. Nothing in this process outlives
+    /// the run, which is why catching its panics with `AssertUnwindSafe`
+    /// cannot expose broken state.
+    fn run_child(handler: &dyn HookHandler, event: &Event, writer: OwnedFd) -> ! {
+        // The session's panic hook writes crash reports and restores the
+        // terminal; neither belongs to this process.
+        std::panic::set_hook(Box::new(|_| {}));
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build();
+        let outcome = match &runtime {
+            Ok(runtime) => std::panic::catch_unwind(AssertUnwindSafe(|| {
+                runtime.block_on(handler.handle(event))
+            }))
+            .unwrap_or_else(|payload| Err(format!("panicked: {}", panic_message(&*payload)))),
+            Err(err) => Err(format!("failed to start a runtime: {err}")),
+        };
+        let report = match outcome {
+            Ok(()) => vec![OK],
+            Err(mut reason) => {
+                let mut end = reason.len().min(MAX_REPORT_BYTES);
+                while !reason.is_char_boundary(end) {
+                    end -= 1;
+                }
+                reason.truncate(end);
+                let mut report = vec![FAILED];
+                report.extend(reason.into_bytes());
+                report
+            }
+        };
+        let _ = File::from(writer).write_all(&report);
+        // SAFETY: `_exit` ends the process without running the destructors
+        // and exit handlers it inherited from the session.
+        unsafe { libc::_exit(0) }
+    }
+
+    /// Waits for the child `pid` to exit, killing it after `timeout`.
+    fn wait(pid: libc::pid_t, timeout: Duration) -> Result<libc::c_int, String> {
+        let deadline = Instant::now() + timeout;
+        let mut status = 0;
+        loop {
+            // SAFETY: `pid` is a child of this process that has not been
+            // reaped, and `status` is a valid place for its status.
+            let waited = unsafe { libc::waitpid(pid, &mut status, libc::WNOHANG) };
+            if waited == pid {
+                return Ok(status);
+            }
+            if waited < 0 {
+                let err = std::io::Error::last_os_error();
+                if err.kind() == ErrorKind::Interrupted {
+                    continue;
+                }
+                return Err(format!("failed to wait for the helper process: {err}"));
+            }
+            if Instant::now() >= deadline {
+                // SAFETY: as above; the child is killed before it is reaped,
+                // so `pid` cannot have been reused.
+                unsafe {
+                    libc::kill(pid, libc::SIGKILL);
+                    libc::waitpid(pid, &mut status, 0);
+                }
+                return Err(format!("timed out after {}ms", timeout.as_millis()));
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Reads what the exited child wrote. Processes forked for other runs
+    /// may still hold the write end, so the pipe is drained without waiting
+    /// for it to close.
+    fn read_report(reader: OwnedFd) -> Vec<u8> {
+        // SAFETY: `reader` is an open descriptor owned by this function.
+        unsafe {
+            let flags = libc::fcntl(reader.as_raw_fd(), libc::F_GETFL);
+            libc::fcntl(reader.as_raw_fd(), libc::F_SETFL, flags | libc::O_NONBLOCK);
+        }
+        let mut reader = File::from(reader);
+        let mut report = Vec::new();
+        let mut buf = [0; 1024];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => report.extend_from_slice(&buf[..n]),
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(_) => break,
+            }
+        }
+        report
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    use async_trait::async_trait;
+    use codex_protocol::protocol::EventMsg;
+    use codex_protocol::protocol::WarningEvent;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    enum Behavior {
+        Succeed,
+        Fail,
+        Panic,
+        Abort,
+        Hang,
+    }
+
+    struct NativeHook {
+        behavior: Behavior,
+        runs: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl HookHandler for NativeHook {
+        async fn handle(&self, _event: &Event) -> Result<(), String> {
+            self.runs.fetch_add(1, Ordering::SeqCst);
+            match self.behavior {
+                Behavior::Succeed => Ok(()),
+                Behavior::Fail => Err("not allowed".to_string()),
+                Behavior::Panic => panic!("oops"),
+                Behavior::Abort => std::process::abort(),
+                Behavior::Hang => {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    async fn run_hook(behavior: Behavior) -> (Result<(), String>, usize) {
+        let hook = Arc::new(NativeHook {
+            behavior,
+            runs: AtomicUsize::new(0),
+        });
+        let event = Arc::new(Event {
+            id: "sub-1".to_string(),
+            msg: EventMsg::Warning(WarningEvent {
+                message: "careful".to_string(),
+            }),
+        });
+        let handler: Arc<dyn HookHandler> = Arc::clone(&hook) as Arc<dyn HookHandler>;
+        let result = run(handler, event, Duration::from_millis(500)).await;
+        (result, hook.runs.load(Ordering::SeqCst))
+    }
+
+    #[tokio::test]
+    async fn reports_the_handler_result_from_its_process() {
+        // The run happens in another process, so the handler's own state in
+        // this one is untouched.
+        assert_eq!(run_hook(Behavior::Succeed).await, (Ok(()), 0));
+        assert_eq!(
+            run_hook(Behavior::Fail).await,
+            (Err("not allowed".to_string()), 0)
+        );
+        assert_eq!(
+            run_hook(Behavior::Panic).await,
+            (Err("panicked: oops".to_string()), 0)
+        );
+    }
+
+    #[tokio::test]
+    async fn crashes_and_hangs_fail_the_run() {
+        assert_eq!(
+            run_hook(Behavior::Abort).await,
+            (
+                Err(format!(
+                    "the helper process crashed (signal {})",
+                    libc::SIGABRT
+                )),
+                0
+            )
+        );
+        assert_eq!(
+            run_hook(Behavior::Hang).await,
+            (Err("timed out after 500ms".to_string()), 0)
+        );
+    }
+}
@@ -0,0 +1,398 @@
+//! Built-in notification sinks that post to chat webhooks.
+//!
+//! A [`WebhookSink`] turns the events that need someone's attention (a
+//! finished turn, a pending approval, an error) into a short message and posts
+//! it to a Slack or Discord incoming webhook. Messages come from per
+//! notification templates and are rate limited, so a busy session cannot flood
+//! the channel.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use serde_json::json;
+
+use super::HookHandler;
+use crate::config::types::SinkNotification;
+use crate::config::types::WebhookSinkConfig;
+use crate::default_client::build_reqwest_client;
+use crate::parse_command::shlex_join;
+
+const DEFAULT_MAX_PER_MINUTE: u32 = 10;
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+const POST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest agent message or error quoted in a notification, in characters.
+const MAX_QUOTED_CHARS: usize = 300;
+
+const ALL_NOTIFICATIONS: &[SinkNotification] = &[
+    SinkNotification::TurnComplete,
+    SinkNotification::ApprovalNeeded,
+    SinkNotification::Error,
+];
+
+/// Chat service a sink posts to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SinkService {
+    Slack,
+    Discord,
+}
+
+impl SinkService {
+    /// Name the sink is registered under in `/hooks`.
+    pub fn hook_name(self) -> &'static str {
+        match self {
+            SinkService::Slack => "sinks.slack",
+            SinkService::Discord => "sinks.discord",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            SinkService::Slack => "Slack",
+            SinkService::Discord => "Discord",
+        }
+    }
+
+    fn body(self, text: &str) -> serde_json::Value {
+        match self {
+            SinkService::Slack => json!({ "text": text }),
+            SinkService::Discord => json!({ "content": text }),
+        }
+    }
+}
+
+/// Posts notifications to a chat webhook.
+pub struct WebhookSink {
+    service: SinkService,
+    config: WebhookSinkConfig,
+    client: reqwest::Client,
+    limiter: Mutex<RateLimiter>,
+}
+
+impl WebhookSink {
+    pub fn new(service: SinkService, config: WebhookSinkConfig) -> Self {
+        Self {
+            service,
+            config,
+            client: build_reqwest_client(),
+            limiter: Mutex::default(),
+        }
+    }
+
+    /// Names of the events that produce the configured notifications.
+    pub fn events(&self) -> Vec<String> {
+        let notifications = self.config.on.as_deref().unwrap_or(ALL_NOTIFICATIONS);
+        let mut events = Vec::new();
+        for notification in notifications {
+            let names: &[&str] = match notification {
+                SinkNotification::TurnComplete => &["turn_complete"],
+                SinkNotification::ApprovalNeeded => {
+                    &["exec_approval_request", "apply_patch_approval_request"]
+                }
+                SinkNotification::Error => &["error"],
+            };
+            for name in names {
+                if !events.iter().any(|event| event == name) {
+                    events.push((*name).to_string());
+                }
+            }
+        }
+        events
+    }
+
+    fn template(&self, notification: SinkNotification) -> &str {
+        let templates = &self.config.templates;
+        let template = match notification {
+            SinkNotification::TurnComplete => &templates.turn_complete,
+            SinkNotification::ApprovalNeeded => &templates.approval_needed,
+            SinkNotification::Error => &templates.error,
+        };
+        template.as_deref().unwrap_or("{summary}")
+    }
+
+    /// Reserves a slot in the rate limit. Returns the number of messages
+    /// dropped since the last one sent, or `None` if this one must be dropped.
+    fn admit(&self) -> Option<u32> {
+        let max = self.config.max_per_minute.unwrap_or(DEFAULT_MAX_PER_MINUTE);
+        let mut limiter = match self.limiter.lock() {
+            Ok(limiter) => limiter,
+            Err(err) => err.into_inner(),
+        };
+        limiter.admit(Instant::now(), max)
+    }
+}
+
+#[async_trait]
+impl HookHandler for WebhookSink {
+    async fn handle(&self, event: &Event) -> Result<(), String> {
+        let Some((notification, fields)) = notification_fields(&event.msg) else {
+            return Ok(());
+        };
+        let Some(dropped) = self.admit() else {
+            tracing::debug!("{} sink rate limit reached; dropping", self.service.label());
+            return Ok(());
+        };
+        let mut text = render(self.template(notification), &fields);
+        if dropped > 0 {
+            text.push_str(&format!(
+                "\n({dropped} earlier notification(s) dropped by the rate limit)"
+            ));
+        }
+
+        let label = self.service.label();
+        let response = self
+            .client
+            .post(&self.config.webhook_url)
+            .timeout(POST_TIMEOUT)
+            .json(&self.service.body(&text))
+            .send()
+            .await
+            // The webhook URL is the secret, so keep it out of the error.
+            .map_err(|err| format!("failed to post to {label}: {}", err.without_url()))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(format!("{label} webhook returned {status}"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct RateLimiter {
+    sent: VecDeque<Instant>,
+    dropped: u32,
+}
+
+impl RateLimiter {
+    /// Admits a message at `now` if fewer than `max` were sent in the last
+    /// minute. `max == 0` admits everything.
+    fn admit(&mut self, now: Instant, max: u32) -> Option<u32> {
+        while self
+            .sent
+            .front()
+            .is_some_and(|sent| now.duration_since(*sent) >= RATE_WINDOW)
+        {
+            self.sent.pop_front();
+        }
+        if max > 0 && self.sent.len() >= max as usize {
+            self.dropped += 1;
+            return None;
+        }
+        if max > 0 {
+            self.sent.push_back(now);
+        }
+        Some(std::mem::take(&mut self.dropped))
+    }
+}
+
+/// The notification `msg` produces, with the values of its template
+/// placeholders.
+fn notification_fields(msg: &EventMsg) -> Option<(SinkNotification, Vec<(&'static str, String)>)> {
+    match msg {
+        EventMsg::TurnComplete(event) => {
+            let message = quote(event.last_agent_message.as_deref().unwrap_or_default());
+            let summary = if message.is_empty() {
+                "Codex finished a turn.".to_string()
+            } else {
+                format!("Codex finished a turn: {message}")
+            };
+            Some((
+                SinkNotification::TurnComplete,
+                vec![("summary", summary), ("message", message)],
+            ))
+        }
+        EventMsg::ExecApprovalRequest(event) => {
+            let command = shlex_join(&event.command);
+            Some((
+                SinkNotification::ApprovalNeeded,
+                vec![
+                    (
+                        "summary",
+                        format!("Codex is waiting for approval to run `{command}`."),
+                    ),
+                    ("command", command),
+                    ("files", String::new()),
+                    ("cwd", event.cwd.display().to_string()),
+                    ("reason", event.reason.clone().unwrap_or_default()),
+                ],
+            ))
+        }
+        EventMsg::ApplyPatchApprovalRequest(event) => {
+            let mut files: Vec<String> = event
+                .changes
+                .keys()
+                .map(|path| path.display().to_string())
+                .collect();
+            files.sort();
+            let files = files.join(", ");
+            Some((
+                SinkNotification::ApprovalNeeded,
+                vec![
+                    (
+                        "summary",
+                        format!("Codex is waiting for approval to edit {files}."),
+                    ),
+                    ("command", String::new()),
+                    ("files", files),
+                    ("cwd", String::new()),
+                    ("reason", event.reason.clone().unwrap_or_default()),
+                ],
+            ))
+        }
+        EventMsg::Error(event) => {
+            let message = quote(&event.message);
+            Some((
+                SinkNotification::Error,
+                vec![
+                    ("summary", format!("Codex hit an error: {message}")),
+                    ("message", message),
+                ],
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Trims `text` to a length that reads well in a chat message.
+fn quote(text: &str) -> String {
+    let text = text.trim();
+    if text.chars().count() <= MAX_QUOTED_CHARS {
+        return text.to_string();
+    }
+    let mut quoted: String = text.chars().take(MAX_QUOTED_CHARS).collect();
+    quoted.push('…');
+    quoted
+}
+
+/// Expands the `{name}` placeholders in `template`. Unknown placeholders are
+/// kept as written.
+fn render(template: &str, fields: &[(&str, String)]) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after.find('}').and_then(|end| {
+            let name = &after[..end];
+            fields
+                .iter()
+                .find(|(field, _)| *field == name)
+                .map(|(_, value)| (value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                rendered.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                rendered.push('{');
+                rest = after;
+            }
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::SinkTemplates;
+    use codex_protocol::protocol::ErrorEvent;
+    use pretty_assertions::assert_eq;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::body_json;
+    use wiremock::matchers::method;
+
+    fn sink_config(webhook_url: String) -> WebhookSinkConfig {
+        WebhookSinkConfig {
+            webhook_url,
+            on: None,
+            templates: SinkTemplates::default(),
+            max_per_minute: None,
+            enabled: true,
+            only_when_env: None,
+            filter: None,
+        }
+    }
+
+    fn error(message: &str) -> Event {
+        Event {
+            id: "sub-1".to_string(),
+            msg: EventMsg::Error(ErrorEvent {
+                message: message.to_string(),
+                codex_error_info: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn templates_expand_known_placeholders_only() {
+        let fields = vec![
+            ("summary", "Codex finished a turn.".to_string()),
+            ("message", "{summary}".to_string()),
+        ];
+        assert_eq!(
+            render(
+                ":white_check_mark: {summary} {message} {unknown} {",
+                &fields
+            ),
+            ":white_check_mark: Codex finished a turn. {summary} {unknown} {"
+        );
+    }
+
+    #[test]
+    fn rate_limit_drops_and_counts_excess_messages() {
+        let mut limiter = RateLimiter::default();
+        let start = Instant::now();
+        assert_eq!(limiter.admit(start, 2), Some(0));
+        assert_eq!(limiter.admit(start, 2), Some(0));
+        assert_eq!(limiter.admit(start, 2), None);
+        assert_eq!(limiter.admit(start, 2), None);
+        assert_eq!(limiter.admit(start + RATE_WINDOW, 2), Some(2));
+        assert_eq!(limiter.admit(start, 0), Some(0));
+    }
+
+    #[test]
+    fn sinks_listen_to_the_events_of_their_notifications() {
+        let mut config = sink_config("http://localhost".to_string());
+        config.on = Some(vec![
+            SinkNotification::ApprovalNeeded,
+            SinkNotification::Error,
+        ]);
+        assert_eq!(
+            WebhookSink::new(SinkService::Slack, config).events(),
+            vec![
+                "exec_approval_request",
+                "apply_patch_approval_request",
+                "error"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn posts_rendered_messages_in_the_service_format() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_json(
+                json!({ "content": "[ci] Codex hit an error: quota exceeded" }),
+            ))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let mut config = sink_config(server.uri());
+        config.templates.error = Some("[ci] {summary}".to_string());
+
+        let sink = WebhookSink::new(SinkService::Discord, config);
+
+        assert_eq!(sink.handle(&error("quota exceeded")).await, Ok(()));
+    }
+}
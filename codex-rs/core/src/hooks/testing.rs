@@ -0,0 +1,271 @@
+//! Helpers for testing code that reacts to hooks.
+//!
+//! [`RecordingHooks`] records every event dispatched to it, in the order the
+//! session emitted them, so tests can wait for a specific event instead of
+//! polling counters and can assert on the order events arrived in:
+//!
+//! ```no_run
+//! # async fn example(hooks: &codex_core::hooks::Hooks) {
+//! use std::time::Duration;
+//! use codex_core::hooks::testing::RecordingHooks;
+//!
+//! let recorder = RecordingHooks::attach(hooks);
+//! // ... drive the session ...
+//! recorder
+//!     .wait_for("task_complete", Duration::from_secs(5))
+//!     .await
+//!     .expect("turn finished");
+//! recorder.assert_order(&["task_started", "agent_message", "task_complete"]);
+//! # }
+//! ```
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::time::Duration;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::HookSource;
+use tokio::sync::Notify;
+
+use super::ALL_EVENTS;
+use super::HookHandler;
+use super::Hooks;
+
+/// Name [`RecordingHooks::attach`] registers the recorder under.
+pub const RECORDING_HOOK_NAME: &str = "recording";
+
+/// An event captured by [`RecordingHooks`].
+#[derive(Debug, Clone)]
+pub struct RecordedEvent {
+    /// The snake_case event name, as matched by hook subscriptions.
+    pub name: String,
+    pub event: Event,
+    /// When the event was dispatched.
+    pub received_at: Instant,
+}
+
+/// Returned when an awaited event does not arrive in time.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("timed out after {timeout:?} waiting for {expected}; recorded events: {recorded:?}")]
+pub struct WaitTimeout {
+    pub expected: String,
+    pub timeout: Duration,
+    /// Names of the events recorded so far.
+    pub recorded: Vec<String>,
+}
+
+/// Hook that records the events it is dispatched.
+#[derive(Debug, Default)]
+pub struct RecordingHooks {
+    events: Mutex<Vec<RecordedEvent>>,
+    recorded: Notify,
+}
+
+impl RecordingHooks {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Registers a new recorder on `hooks` for every event.
+    pub fn attach(hooks: &Hooks) -> Arc<Self> {
+        let recorder = Self::new();
+        hooks.register(
+            RECORDING_HOOK_NAME,
+            vec![ALL_EVENTS.to_string()],
+            HookSource::External,
+            Arc::clone(&recorder) as Arc<dyn HookHandler>,
+        );
+        recorder
+    }
+
+    /// Events recorded so far, oldest first.
+    pub fn events(&self) -> Vec<RecordedEvent> {
+        self.lock().clone()
+    }
+
+    /// Names of the events recorded so far, oldest first.
+    pub fn event_names(&self) -> Vec<String> {
+        self.lock().iter().map(|event| event.name.clone()).collect()
+    }
+
+    /// Forgets every recorded event.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Waits for the first event named `event_name`, which may already have
+    /// been recorded.
+    pub async fn wait_for(
+        &self,
+        event_name: &str,
+        timeout: Duration,
+    ) -> Result<RecordedEvent, WaitTimeout> {
+        self.wait_for_matching(format!("`{event_name}`"), timeout, |event| {
+            event.name == event_name
+        })
+        .await
+    }
+
+    /// Waits for the first recorded event accepted by `predicate`.
+    /// `description` names the awaited event in the timeout error.
+    pub async fn wait_for_matching(
+        &self,
+        description: impl Into<String>,
+        timeout: Duration,
+        predicate: impl Fn(&RecordedEvent) -> bool,
+    ) -> Result<RecordedEvent, WaitTimeout> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            // Register for the next notification before checking, so an event
+            // recorded in between is not missed.
+            let recorded = self.recorded.notified();
+            tokio::pin!(recorded);
+            recorded.as_mut().enable();
+
+            if let Some(event) = self.lock().iter().find(|event| predicate(event)) {
+                return Ok(event.clone());
+            }
+            if tokio::time::timeout_at(deadline, recorded).await.is_err() {
+                return Err(WaitTimeout {
+                    expected: description.into(),
+                    timeout,
+                    recorded: self.event_names(),
+                });
+            }
+        }
+    }
+
+    /// Asserts that events named `expected` were recorded in this order.
+    /// Other events may come before, between and after them.
+    #[track_caller]
+    pub fn assert_order(&self, expected: &[&str]) {
+        let recorded = self.event_names();
+        let mut remaining = recorded.iter();
+        for name in expected {
+            assert!(
+                remaining.any(|recorded| recorded.as_str() == *name),
+                "expected events {expected:?} in order, but `{name}` is missing or out of order; recorded events: {recorded:?}"
+            );
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<RecordedEvent>> {
+        match self.events.lock() {
+            Ok(events) => events,
+            Err(err) => err.into_inner(),
+        }
+    }
+}
+
+#[async_trait]
+impl HookHandler for RecordingHooks {
+    async fn handle(&self, _event: &Event) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn dispatched(&self, event: &Event) {
+        self.lock().push(RecordedEvent {
+            name: event.msg.to_string(),
+            event: event.clone(),
+            received_at: Instant::now(),
+        });
+        self.recorded.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::EventMsg;
+    use codex_protocol::protocol::WarningEvent;
+    use pretty_assertions::assert_eq;
+
+    fn event(msg: EventMsg) -> Event {
+        Event {
+            id: "sub-1".to_string(),
+            msg,
+        }
+    }
+
+    fn warning(message: &str) -> Event {
+        event(EventMsg::Warning(WarningEvent {
+            message: message.to_string(),
+        }))
+    }
+
+    #[tokio::test]
+    async fn records_dispatched_events_in_order() {
+        let hooks = Hooks::default();
+        let recorder = RecordingHooks::attach(&hooks);
+
+        hooks.dispatch(&warning("first")).await;
+        hooks.dispatch(&event(EventMsg::ShutdownComplete)).await;
+        hooks.dispatch(&warning("second")).await;
+
+        assert_eq!(
+            recorder.event_names(),
+            vec!["warning", "shutdown_complete", "warning"]
+        );
+        recorder.assert_order(&["warning", "shutdown_complete"]);
+        let events = recorder.events();
+        assert!(events[0].received_at <= events[2].received_at);
+    }
+
+    #[test]
+    #[should_panic(expected = "`shutdown_complete` is missing or out of order")]
+    fn assert_order_rejects_out_of_order_events() {
+        let recorder = RecordingHooks::new();
+        recorder.dispatched(&event(EventMsg::ShutdownComplete));
+        recorder.dispatched(&warning("late"));
+
+        recorder.assert_order(&["warning", "shutdown_complete"]);
+    }
+
+    #[tokio::test]
+    async fn wait_for_resolves_when_the_event_arrives() {
+        let recorder = RecordingHooks::new();
+        let waiter = {
+            let recorder = Arc::clone(&recorder);
+            tokio::spawn(async move {
+                recorder
+                    .wait_for_matching("second warning", Duration::from_secs(5), |event| {
+                        matches!(
+                            &event.event.msg,
+                            EventMsg::Warning(WarningEvent { message }) if message == "second"
+                        )
+                    })
+                    .await
+            })
+        };
+
+        recorder.dispatched(&warning("first"));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        recorder.dispatched(&warning("second"));
+
+        let recorded = waiter.await.expect("join").expect("event recorded");
+        assert_eq!(recorded.name, "warning");
+    }
+
+    #[tokio::test]
+    async fn wait_for_times_out_with_recorded_events() {
+        let recorder = RecordingHooks::new();
+        recorder.dispatched(&warning("only"));
+
+        let err = recorder
+            .wait_for("task_complete", Duration::from_millis(20))
+            .await
+            .expect_err("never dispatched");
+
+        assert_eq!(
+            err,
+            WaitTimeout {
+                expected: "`task_complete`".to_string(),
+                timeout: Duration::from_millis(20),
+                recorded: vec!["warning".to_string()],
+            }
+        );
+    }
+}
@@ -0,0 +1,102 @@
+//! Work in flight during a turn.
+//!
+//! Model streams and tool calls register themselves with the turn's
+//! [`InFlightOperations`] for as long as they run. When the turn is aborted
+//! the session snapshots the registry before cancelling, so `TurnAborted` can
+//! report what was cut short.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+
+use codex_protocol::protocol::CancelledOperation;
+
+#[derive(Debug, Default)]
+pub(crate) struct InFlightOperations {
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    next_id: u64,
+    /// Keyed by registration order.
+    operations: BTreeMap<u64, CancelledOperation>,
+}
+
+impl InFlightOperations {
+    /// Registers `operation` until the returned guard is dropped.
+    pub(crate) fn track(self: &Arc<Self>, operation: CancelledOperation) -> InFlightGuard {
+        let mut state = self.lock();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.operations.insert(id, operation);
+        InFlightGuard {
+            operations: Arc::clone(self),
+            id,
+        }
+    }
+
+    /// Operations still running, oldest first.
+    pub(crate) fn snapshot(&self) -> Vec<CancelledOperation> {
+        self.lock().operations.values().cloned().collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(err) => err.into_inner(),
+        }
+    }
+}
+
+/// Returned by [`InFlightOperations::track`]; unregisters on drop.
+pub(crate) struct InFlightGuard {
+    operations: Arc<InFlightOperations>,
+    id: u64,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.operations.lock().operations.remove(&self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn snapshot_lists_running_operations_in_order() {
+        let operations = Arc::new(InFlightOperations::default());
+        let stream = operations.track(CancelledOperation::ModelStream);
+        let shell = operations.track(CancelledOperation::ToolCall {
+            call_id: "call-1".to_string(),
+            tool_name: "shell".to_string(),
+        });
+        let mcp = operations.track(CancelledOperation::McpToolCall {
+            call_id: "call-2".to_string(),
+            server: "docs".to_string(),
+            tool: "search".to_string(),
+        });
+
+        drop(shell);
+
+        assert_eq!(
+            operations.snapshot(),
+            vec![
+                CancelledOperation::ModelStream,
+                CancelledOperation::McpToolCall {
+                    call_id: "call-2".to_string(),
+                    server: "docs".to_string(),
+                    tool: "search".to_string(),
+                },
+            ]
+        );
+
+        drop(stream);
+        drop(mcp);
+        assert_eq!(operations.snapshot(), Vec::new());
+    }
+}
@@ -0,0 +1,240 @@
+//! Interceptors take part in a session's request/response pipeline.
+//!
+//! Where [`hooks`](crate::hooks) observe events after the fact and never
+//! block the session, an [`Interceptor`] is awaited inline: it can rewrite the
+//! prompt before each model request, inspect each item the model returns and
+//! end the turn early. Interceptors run one after another in registration
+//! order, so a slow interceptor slows the turn down.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use codex_protocol::ThreadId;
+use codex_protocol::models::ResponseItem;
+
+use crate::client_common::Prompt;
+
+/// What the session should do after an interceptor ran.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Interception {
+    /// Carry on with the turn.
+    Continue,
+    /// End the turn without sending the request or handling the item, and
+    /// without running later interceptors. `message`, if any, is recorded as
+    /// the agent's final message.
+    EndTurn { message: Option<String> },
+}
+
+/// The turn an interceptor is called for.
+#[derive(Debug, Clone)]
+pub struct TurnInfo {
+    pub thread_id: ThreadId,
+    /// Id of the submission that started the turn, as carried by its events.
+    pub turn_id: String,
+    pub model: String,
+    pub cwd: PathBuf,
+}
+
+/// Participates in every turn of the session it is registered on. Both
+/// methods default to [`Interception::Continue`].
+#[async_trait]
+pub trait Interceptor: Send + Sync {
+    /// Called before each model request. Changes to `prompt` only affect the
+    /// request being sent; the conversation history is left as it was.
+    async fn before_request(&self, _turn: &TurnInfo, _prompt: &mut Prompt) -> Interception {
+        Interception::Continue
+    }
+
+    /// Called for each completed item the model returns, before it is
+    /// recorded and before any tool call it contains runs.
+    async fn on_response_item(&self, _turn: &TurnInfo, _item: &ResponseItem) -> Interception {
+        Interception::Continue
+    }
+}
+
+/// Interceptors registered on a session, in registration order.
+#[derive(Default)]
+pub struct Interceptors {
+    interceptors: RwLock<Vec<(String, Arc<dyn Interceptor>)>>,
+}
+
+impl Interceptors {
+    /// Registers `interceptor` under `name`. An interceptor already registered
+    /// under that name is replaced in place.
+    pub fn register(&self, name: impl Into<String>, interceptor: Arc<dyn Interceptor>) {
+        let name = name.into();
+        let mut interceptors = match self.interceptors.write() {
+            Ok(interceptors) => interceptors,
+            Err(err) => err.into_inner(),
+        };
+        match interceptors
+            .iter_mut()
+            .find(|(existing, _)| *existing == name)
+        {
+            Some((_, existing)) => *existing = interceptor,
+            None => interceptors.push((name, interceptor)),
+        }
+    }
+
+    /// Removes the interceptor registered under `name`, returning whether
+    /// there was one.
+    pub fn unregister(&self, name: &str) -> bool {
+        let mut interceptors = match self.interceptors.write() {
+            Ok(interceptors) => interceptors,
+            Err(err) => err.into_inner(),
+        };
+        let before = interceptors.len();
+        interceptors.retain(|(existing, _)| existing != name);
+        interceptors.len() != before
+    }
+
+    /// Names of the registered interceptors in the order they run.
+    pub fn names(&self) -> Vec<String> {
+        self.snapshot().into_iter().map(|(name, _)| name).collect()
+    }
+
+    pub(crate) async fn before_request(
+        &self,
+        turn: &TurnInfo,
+        prompt: &mut Prompt,
+    ) -> Interception {
+        for (name, interceptor) in self.snapshot() {
+            let interception = interceptor.before_request(turn, prompt).await;
+            if interception != Interception::Continue {
+                tracing::info!("interceptor `{name}` ended turn {}", turn.turn_id);
+                return interception;
+            }
+        }
+        Interception::Continue
+    }
+
+    pub(crate) async fn on_response_item(
+        &self,
+        turn: &TurnInfo,
+        item: &ResponseItem,
+    ) -> Interception {
+        for (name, interceptor) in self.snapshot() {
+            let interception = interceptor.on_response_item(turn, item).await;
+            if interception != Interception::Continue {
+                tracing::info!("interceptor `{name}` ended turn {}", turn.turn_id);
+                return interception;
+            }
+        }
+        Interception::Continue
+    }
+
+    fn snapshot(&self) -> Vec<(String, Arc<dyn Interceptor>)> {
+        let interceptors = match self.interceptors.read() {
+            Ok(interceptors) => interceptors,
+            Err(err) => err.into_inner(),
+        };
+        interceptors.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ContentItem;
+    use pretty_assertions::assert_eq;
+    use std::sync::Mutex;
+
+    fn turn() -> TurnInfo {
+        TurnInfo {
+            thread_id: ThreadId::new(),
+            turn_id: "1".to_string(),
+            model: "test-model".to_string(),
+            cwd: PathBuf::from("/tmp"),
+        }
+    }
+
+    fn user_message(text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    /// Appends a message to the prompt and records that it ran.
+    struct Append {
+        text: &'static str,
+        calls: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    #[async_trait]
+    impl Interceptor for Append {
+        async fn before_request(&self, _turn: &TurnInfo, prompt: &mut Prompt) -> Interception {
+            self.calls.lock().unwrap().push(self.text);
+            prompt.input.push(user_message(self.text));
+            Interception::Continue
+        }
+    }
+
+    struct EndTurn;
+
+    #[async_trait]
+    impl Interceptor for EndTurn {
+        async fn before_request(&self, _turn: &TurnInfo, _prompt: &mut Prompt) -> Interception {
+            Interception::EndTurn {
+                message: Some("blocked".to_string()),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn interceptors_run_in_order_until_one_ends_the_turn() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let interceptors = Interceptors::default();
+        interceptors.register(
+            "first",
+            Arc::new(Append {
+                text: "first",
+                calls: Arc::clone(&calls),
+            }),
+        );
+        interceptors.register("end", Arc::new(EndTurn));
+        interceptors.register(
+            "never",
+            Arc::new(Append {
+                text: "never",
+                calls: Arc::clone(&calls),
+            }),
+        );
+        let mut prompt = Prompt::default();
+
+        let interception = interceptors.before_request(&turn(), &mut prompt).await;
+
+        assert_eq!(
+            interception,
+            Interception::EndTurn {
+                message: Some("blocked".to_string())
+            }
+        );
+        assert_eq!(*calls.lock().unwrap(), vec!["first"]);
+        assert_eq!(prompt.input, vec![user_message("first")]);
+        assert_eq!(
+            interceptors
+                .on_response_item(&turn(), &user_message("hi"))
+                .await,
+            Interception::Continue
+        );
+    }
+
+    #[test]
+    fn register_replaces_by_name_and_unregister_removes() {
+        let interceptors = Interceptors::default();
+        interceptors.register("a", Arc::new(EndTurn));
+        interceptors.register("b", Arc::new(EndTurn));
+        interceptors.register("a", Arc::new(EndTurn));
+
+        assert_eq!(interceptors.names(), vec!["a".to_string(), "b".to_string()]);
+        assert!(interceptors.unregister("a"));
+        assert!(!interceptors.unregister("a"));
+        assert_eq!(interceptors.names(), vec!["b".to_string()]);
+    }
+}
@@ -7,8 +7,11 @@
 
 pub mod api_bridge;
 mod apply_patch;
+pub mod approval_rules;
 pub mod auth;
 pub mod bash;
+pub mod best_of;
+pub mod checkpoints;
 mod client;
 mod client_common;
 pub mod codex;
@@ -19,18 +22,31 @@ mod agent;
 mod codex_delegate;
 mod command_safety;
 pub mod config;
+mod config_handle;
+pub use config_handle::ConfigChange;
+pub use config_handle::ConfigHandle;
 pub mod config_loader;
 mod context_manager;
+pub mod cost;
+pub mod crash_report;
 pub mod custom_prompts;
+pub mod devcontainer;
 pub mod env;
 mod environment_context;
+mod environment_fingerprint;
 pub mod error;
+pub mod event_socket;
 pub mod exec;
 pub mod exec_env;
 mod exec_policy;
 pub mod features;
 mod flags;
 pub mod git_info;
+mod guardrails;
+pub mod hooks;
+mod in_flight;
+mod injection;
+pub mod interceptors;
 pub mod landlock;
 pub mod mcp;
 mod mcp_connection_manager;
@@ -40,10 +56,19 @@ pub use mcp_connection_manager::MCP_SANDBOX_STATE_METHOD;
 pub use mcp_connection_manager::SandboxState;
 mod mcp_tool_call;
 mod message_history;
+mod mock_provider;
 mod model_provider_info;
+pub mod offline;
 pub mod parse_command;
+mod patch_validation;
 pub mod path_utils;
 pub mod powershell;
+mod prefetch;
+mod provenance;
+mod pull_request;
+mod reasoning_retention;
+mod redaction;
+pub mod releases;
 pub mod sandboxing;
 mod stream_events_utils;
 mod text_encoding;
@@ -51,17 +76,20 @@ pub mod token_data;
 mod truncate;
 mod unified_exec;
 mod user_instructions;
+pub mod variables;
 pub mod windows_sandbox;
 pub use model_provider_info::CHAT_WIRE_API_DEPRECATION_SUMMARY;
 pub use model_provider_info::DEFAULT_LMSTUDIO_PORT;
 pub use model_provider_info::DEFAULT_OLLAMA_PORT;
 pub use model_provider_info::LMSTUDIO_OSS_PROVIDER_ID;
+pub use model_provider_info::MOCK_PROVIDER_ID;
 pub use model_provider_info::ModelProviderInfo;
 pub use model_provider_info::OLLAMA_OSS_PROVIDER_ID;
 pub use model_provider_info::WireApi;
 pub use model_provider_info::built_in_model_providers;
 pub use model_provider_info::create_oss_provider_with_base_url;
 mod event_mapping;
+pub mod replay;
 pub mod review_format;
 pub mod review_prompts;
 mod thread_manager;
@@ -78,22 +106,30 @@ pub type CodexConversation = CodexThread;
 pub use auth::AuthManager;
 pub use auth::CodexAuth;
 pub mod default_client;
+mod file_watch;
 pub mod project_doc;
+mod project_doc_watcher;
+mod remote_exec;
 mod rollout;
 pub(crate) mod safety;
 pub mod seatbelt;
+pub mod session_bundle;
+pub mod session_logs;
 pub mod shell;
 pub mod shell_snapshot;
 pub mod skills;
 pub mod spawn;
+mod symbol_index;
 pub mod terminal;
 mod tools;
 pub mod turn_diff_tracker;
+mod turn_timing;
 pub use rollout::ARCHIVED_SESSIONS_SUBDIR;
 pub use rollout::INTERACTIVE_SESSION_SOURCES;
 pub use rollout::RolloutRecorder;
 pub use rollout::SESSIONS_SUBDIR;
 pub use rollout::SessionMeta;
+pub use rollout::annotations as rollout_annotations;
 #[deprecated(note = "use find_thread_path_by_id_str")]
 pub use rollout::find_conversation_path_by_id_str;
 pub use rollout::find_thread_path_by_id_str;
@@ -102,12 +138,16 @@ pub use rollout::list::ThreadItem;
 pub use rollout::list::ThreadsPage;
 pub use rollout::list::parse_cursor;
 pub use rollout::list::read_head_for_summary;
+pub use rollout::metadata as rollout_metadata;
 mod function_tool;
 mod state;
 mod tasks;
 mod user_notification;
 mod user_shell_command;
 pub mod util;
+mod verify;
+mod watchdog;
+pub mod workspace_snapshot;
 
 pub use apply_patch::CODEX_APPLY_PATCH_ARG1;
 pub use command_safety::is_dangerous_command;
@@ -1,4 +1,9 @@
 pub mod auth;
+mod prompt_expansion;
+mod resource_injection;
+mod roots;
+mod sampling;
+mod tool_cache;
 use std::collections::HashMap;
 use std::env;
 use std::path::PathBuf;
@@ -14,6 +19,14 @@ use crate::mcp::auth::compute_auth_statuses;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::mcp_connection_manager::SandboxState;
 
+pub(crate) use prompt_expansion::McpPromptExpansion;
+pub(crate) use prompt_expansion::expand_mcp_prompts;
+pub(crate) use resource_injection::McpResourceInjections;
+pub(crate) use resource_injection::build_mcp_resource_injections;
+pub(crate) use roots::workspace_roots;
+pub(crate) use sampling::session_sampler;
+pub(crate) use tool_cache::McpToolCache;
+
 const MCP_TOOL_NAME_PREFIX: &str = "mcp";
 const MCP_TOOL_NAME_DELIMITER: &str = "__";
 
@@ -23,6 +36,7 @@ pub async fn collect_mcp_snapshot(config: &Config) -> McpListToolsResponseEvent
             tools: HashMap::new(),
             resources: HashMap::new(),
             resource_templates: HashMap::new(),
+            prompts: HashMap::new(),
             auth_statuses: HashMap::new(),
         };
     }
@@ -53,6 +67,9 @@ pub async fn collect_mcp_snapshot(config: &Config) -> McpListToolsResponseEvent
             tx_event,
             cancel_token.clone(),
             sandbox_state,
+            None,
+            None,
+            config.proxy.clone(),
         )
         .await;
 
@@ -97,10 +114,11 @@ pub(crate) async fn collect_mcp_snapshot_from_manager(
     mcp_connection_manager: &McpConnectionManager,
     auth_status_entries: HashMap<String, crate::mcp::auth::McpAuthStatusEntry>,
 ) -> McpListToolsResponseEvent {
-    let (tools, resources, resource_templates) = tokio::join!(
+    let (tools, resources, resource_templates, prompts) = tokio::join!(
         mcp_connection_manager.list_all_tools(),
         mcp_connection_manager.list_all_resources(),
         mcp_connection_manager.list_all_resource_templates(),
+        mcp_connection_manager.list_all_prompts(),
     );
 
     let auth_statuses = auth_status_entries
@@ -115,6 +133,7 @@ pub(crate) async fn collect_mcp_snapshot_from_manager(
             .collect(),
         resources,
         resource_templates,
+        prompts,
         auth_statuses,
     }
 }
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use crate::codex::Session;
+use codex_protocol::user_input::UserInput;
+use mcp_types::ContentBlock;
+use mcp_types::EmbeddedResourceResource;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
+
+#[derive(Debug, Default)]
+pub(crate) struct McpPromptExpansion {
+    pub(crate) input: Vec<UserInput>,
+    pub(crate) warnings: Vec<String>,
+}
+
+/// Renders every MCP prompt the user invoked for this turn via `prompts/get`
+/// and splices the resulting messages into the turn input in place of the
+/// prompt reference. Prompts that fail to render are dropped with a warning.
+pub(crate) async fn expand_mcp_prompts(
+    input: Vec<UserInput>,
+    sess: &Session,
+) -> McpPromptExpansion {
+    let mut result = McpPromptExpansion {
+        input: Vec::with_capacity(input.len()),
+        warnings: Vec::new(),
+    };
+
+    for item in input {
+        let UserInput::McpPrompt {
+            server,
+            name,
+            arguments,
+        } = item
+        else {
+            result.input.push(item);
+            continue;
+        };
+
+        let params = GetPromptRequestParams {
+            arguments: prompt_arguments_to_json(arguments),
+            name: name.clone(),
+        };
+        match sess.get_prompt(&server, params).await {
+            Ok(prompt) => result.input.extend(render_prompt_messages(prompt)),
+            Err(err) => {
+                result.warnings.push(format!(
+                    "Failed to render MCP prompt {name} from {server}: {err:#}"
+                ));
+            }
+        }
+    }
+
+    result
+}
+
+fn prompt_arguments_to_json(arguments: HashMap<String, String>) -> Option<serde_json::Value> {
+    if arguments.is_empty() {
+        return None;
+    }
+    let map = arguments
+        .into_iter()
+        .map(|(key, value)| (key, serde_json::Value::String(value)))
+        .collect::<serde_json::Map<_, _>>();
+    Some(serde_json::Value::Object(map))
+}
+
+/// Converts prompt messages into user input. MCP prompts are templates for
+/// user-authored input, so message roles are flattened into the user turn.
+fn render_prompt_messages(prompt: GetPromptResult) -> Vec<UserInput> {
+    prompt
+        .messages
+        .into_iter()
+        .map(|message| match message.content {
+            ContentBlock::TextContent(text) => UserInput::Text { text: text.text },
+            ContentBlock::ImageContent(image) => UserInput::Image {
+                image_url: format!("data:{};base64,{}", image.mime_type, image.data),
+            },
+            ContentBlock::AudioContent(audio) => UserInput::Text {
+                text: format!("[audio content omitted: {}]", audio.mime_type),
+            },
+            ContentBlock::ResourceLink(link) => UserInput::Text {
+                text: format!("[resource {}: {}]", link.name, link.uri),
+            },
+            ContentBlock::EmbeddedResource(embedded) => match embedded.resource {
+                EmbeddedResourceResource::TextResourceContents(text) => {
+                    UserInput::Text { text: text.text }
+                }
+                EmbeddedResourceResource::BlobResourceContents(blob) => {
+                    let mime_type = blob
+                        .mime_type
+                        .as_deref()
+                        .unwrap_or("application/octet-stream");
+                    UserInput::Text {
+                        text: format!(
+                            "[binary resource {uri} omitted: {mime_type}]",
+                            uri = blob.uri
+                        ),
+                    }
+                }
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_types::ImageContent;
+    use mcp_types::PromptMessage;
+    use mcp_types::Role;
+    use mcp_types::TextContent;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn empty_arguments_are_omitted() {
+        assert_eq!(prompt_arguments_to_json(HashMap::new()), None);
+        assert_eq!(
+            prompt_arguments_to_json(HashMap::from([("topic".to_string(), "auth".to_string())])),
+            Some(json!({ "topic": "auth" }))
+        );
+    }
+
+    #[test]
+    fn renders_prompt_messages_as_user_input() {
+        let prompt = GetPromptResult {
+            description: None,
+            messages: vec![
+                PromptMessage {
+                    content: ContentBlock::TextContent(TextContent {
+                        annotations: None,
+                        text: "Review the auth module".to_string(),
+                        r#type: "text".to_string(),
+                    }),
+                    role: Role::User,
+                },
+                PromptMessage {
+                    content: ContentBlock::ImageContent(ImageContent {
+                        annotations: None,
+                        data: "AAAA".to_string(),
+                        mime_type: "image/png".to_string(),
+                        r#type: "image".to_string(),
+                    }),
+                    role: Role::User,
+                },
+            ],
+        };
+
+        assert_eq!(
+            render_prompt_messages(prompt),
+            vec![
+                UserInput::Text {
+                    text: "Review the auth module".to_string(),
+                },
+                UserInput::Image {
+                    image_url: "data:image/png;base64,AAAA".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::HashSet;
+
+use crate::codex::Session;
+use crate::user_instructions::McpResourceContents;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::user_input::UserInput;
+use mcp_types::ReadResourceRequestParams;
+use mcp_types::ReadResourceResult;
+use mcp_types::ReadResourceResultContents;
+
+#[derive(Debug, Default)]
+pub(crate) struct McpResourceInjections {
+    pub(crate) items: Vec<ResponseItem>,
+    pub(crate) warnings: Vec<String>,
+}
+
+/// Reads every MCP resource the user attached to this turn and converts the
+/// contents into conversation items.
+pub(crate) async fn build_mcp_resource_injections(
+    inputs: &[UserInput],
+    sess: &Session,
+) -> McpResourceInjections {
+    let attached = collect_attached_resources(inputs);
+    if attached.is_empty() {
+        return McpResourceInjections::default();
+    }
+
+    let mut result = McpResourceInjections {
+        items: Vec::with_capacity(attached.len()),
+        warnings: Vec::new(),
+    };
+
+    for (server, uri) in attached {
+        let params = ReadResourceRequestParams { uri: uri.clone() };
+        match sess.read_resource(&server, params).await {
+            Ok(read) => {
+                result.items.push(ResponseItem::from(McpResourceContents {
+                    contents: render_resource_contents(&read),
+                    server,
+                    uri,
+                }));
+            }
+            Err(err) => {
+                result.warnings.push(format!(
+                    "Failed to read MCP resource {uri} from {server}: {err:#}"
+                ));
+            }
+        }
+    }
+
+    result
+}
+
+fn collect_attached_resources(inputs: &[UserInput]) -> Vec<(String, String)> {
+    let mut selected = Vec::new();
+    let mut seen: HashSet<(String, String)> = HashSet::new();
+
+    for input in inputs {
+        if let UserInput::McpResource { server, uri } = input
+            && seen.insert((server.clone(), uri.clone()))
+        {
+            selected.push((server.clone(), uri.clone()));
+        }
+    }
+
+    selected
+}
+
+/// Flattens a `resources/read` result into text. Binary blobs are summarized
+/// rather than inlined because the model cannot use base64 payloads.
+fn render_resource_contents(result: &ReadResourceResult) -> String {
+    result
+        .contents
+        .iter()
+        .map(|contents| match contents {
+            ReadResourceResultContents::TextResourceContents(text) => text.text.clone(),
+            ReadResourceResultContents::BlobResourceContents(blob) => {
+                let mime_type = blob
+                    .mime_type
+                    .as_deref()
+                    .unwrap_or("application/octet-stream");
+                format!(
+                    "[binary resource {uri} omitted: {mime_type}, {len} base64 bytes]",
+                    uri = blob.uri,
+                    len = blob.blob.len()
+                )
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_types::BlobResourceContents;
+    use mcp_types::TextResourceContents;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn collects_unique_resource_attachments() {
+        let inputs = vec![
+            UserInput::Text {
+                text: "summarize".to_string(),
+            },
+            UserInput::McpResource {
+                server: "docs".to_string(),
+                uri: "file:///a.md".to_string(),
+            },
+            UserInput::McpResource {
+                server: "docs".to_string(),
+                uri: "file:///a.md".to_string(),
+            },
+            UserInput::McpResource {
+                server: "db".to_string(),
+                uri: "postgres://schema".to_string(),
+            },
+        ];
+
+        assert_eq!(
+            collect_attached_resources(&inputs),
+            vec![
+                ("docs".to_string(), "file:///a.md".to_string()),
+                ("db".to_string(), "postgres://schema".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_text_and_summarizes_blobs() {
+        let result = ReadResourceResult {
+            contents: vec![
+                ReadResourceResultContents::TextResourceContents(TextResourceContents {
+                    mime_type: Some("text/markdown".to_string()),
+                    text: "# Guide".to_string(),
+                    uri: "file:///guide.md".to_string(),
+                }),
+                ReadResourceResultContents::BlobResourceContents(BlobResourceContents {
+                    blob: "AAAA".to_string(),
+                    mime_type: Some("image/png".to_string()),
+                    uri: "file:///logo.png".to_string(),
+                }),
+            ],
+        };
+
+        assert_eq!(
+            render_resource_contents(&result),
+            "# Guide\n[binary resource file:///logo.png omitted: image/png, 4 base64 bytes]"
+        );
+    }
+}
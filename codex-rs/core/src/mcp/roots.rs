@@ -0,0 +1,90 @@
+use std::path::Path;
+
+use codex_protocol::protocol::SandboxPolicy;
+use mcp_types::Root;
+use url::Url;
+
+use crate::mcp_connection_manager::SandboxState;
+
+/// Workspace directories reported to MCP servers via `roots/list`: the session
+/// cwd followed by any additional writable roots from the sandbox policy.
+pub(crate) fn workspace_roots(sandbox_state: &SandboxState) -> Vec<Root> {
+    let mut paths = vec![sandbox_state.sandbox_cwd.as_path()];
+    if let SandboxPolicy::WorkspaceWrite { writable_roots, .. } = &sandbox_state.sandbox_policy {
+        for root in writable_roots {
+            let root = root.as_path();
+            if !paths.contains(&root) {
+                paths.push(root);
+            }
+        }
+    }
+    paths.into_iter().filter_map(root_for_path).collect()
+}
+
+fn root_for_path(path: &Path) -> Option<Root> {
+    let uri = Url::from_directory_path(path).ok()?;
+    Some(Root {
+        name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned()),
+        uri: uri.into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_utils_absolute_path::AbsolutePathBuf;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[cfg(unix)]
+    #[test]
+    fn includes_cwd_and_extra_writable_roots() {
+        let sandbox_state = SandboxState {
+            sandbox_policy: SandboxPolicy::WorkspaceWrite {
+                writable_roots: vec![
+                    AbsolutePathBuf::try_from("/work/app").expect("absolute"),
+                    AbsolutePathBuf::try_from("/work/shared lib").expect("absolute"),
+                ],
+                network_access: false,
+                exclude_tmpdir_env_var: false,
+                exclude_slash_tmp: false,
+            },
+            codex_linux_sandbox_exe: None,
+            sandbox_cwd: PathBuf::from("/work/app"),
+        };
+
+        assert_eq!(
+            workspace_roots(&sandbox_state),
+            vec![
+                Root {
+                    name: Some("app".to_string()),
+                    uri: "file:///work/app/".to_string(),
+                },
+                Root {
+                    name: Some("shared lib".to_string()),
+                    uri: "file:///work/shared%20lib/".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_only_policy_reports_only_cwd() {
+        let sandbox_state = SandboxState {
+            sandbox_policy: SandboxPolicy::ReadOnly,
+            codex_linux_sandbox_exe: None,
+            sandbox_cwd: PathBuf::from("/work/app"),
+        };
+
+        assert_eq!(
+            workspace_roots(&sandbox_state),
+            vec![Root {
+                name: Some("app".to_string()),
+                uri: "file:///work/app/".to_string(),
+            }]
+        );
+    }
+}
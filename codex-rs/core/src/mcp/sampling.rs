@@ -0,0 +1,243 @@
+use std::sync::Arc;
+use std::sync::Weak;
+
+use anyhow::Result;
+use anyhow::anyhow;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use futures::FutureExt;
+use futures::StreamExt;
+use mcp_types::CreateMessageRequestParams;
+use mcp_types::CreateMessageResult;
+use mcp_types::CreateMessageResultContent;
+use mcp_types::Role;
+use mcp_types::SamplingMessageContent;
+use mcp_types::TextContent;
+
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::codex::Session;
+use crate::mcp_connection_manager::McpSampler;
+use crate::mcp_connection_manager::McpSamplingOutcome;
+
+const STOP_REASON_END_TURN: &str = "endTurn";
+const STOP_REASON_STOP_SEQUENCE: &str = "stopSequence";
+
+/// Serves MCP sampling requests with the session's current model. Holds a weak
+/// reference so the connection manager does not keep the session alive.
+pub(crate) fn session_sampler(sess: Weak<Session>) -> McpSampler {
+    Arc::new(move |request| {
+        let sess = sess.clone();
+        async move {
+            let sess = sess
+                .upgrade()
+                .ok_or_else(|| anyhow!("session is no longer running"))?;
+            run_sampling_request(&sess, request).await
+        }
+        .boxed()
+    })
+}
+
+/// Sends the request's messages to the model as a standalone prompt, capped at
+/// the request's `maxTokens`. Nothing is recorded in the conversation history.
+/// `temperature` and `modelPreferences` are advisory and ignored; stop
+/// sequences are applied to the output.
+async fn run_sampling_request(
+    sess: &Session,
+    request: CreateMessageRequestParams,
+) -> Result<McpSamplingOutcome> {
+    let turn_context = sess.new_default_turn().await;
+    let prompt = sampling_prompt(&request);
+    let mut stream = turn_context.client.clone().stream(&prompt).await?;
+
+    let mut text = String::new();
+    let tokens_used = loop {
+        let Some(event) = stream.next().await else {
+            return Err(anyhow!("stream closed before response.completed"));
+        };
+        match event? {
+            ResponseEvent::OutputItemDone(ResponseItem::Message { content, .. }) => {
+                for item in content {
+                    if let ContentItem::OutputText { text: chunk } = item {
+                        text.push_str(&chunk);
+                    }
+                }
+            }
+            ResponseEvent::Completed { token_usage, .. } => {
+                break token_usage
+                    .map(|usage| u64::try_from(usage.total_tokens).unwrap_or_default())
+                    .unwrap_or_default();
+            }
+            _ => {}
+        }
+    };
+
+    let (text, stop_reason) =
+        apply_stop_sequences(text, request.stop_sequences.as_deref().unwrap_or_default());
+    Ok(McpSamplingOutcome {
+        result: CreateMessageResult {
+            content: CreateMessageResultContent::TextContent(TextContent {
+                annotations: None,
+                text,
+                r#type: "text".to_string(),
+            }),
+            model: turn_context.client.get_model(),
+            role: Role::Assistant,
+            stop_reason: Some(stop_reason.to_string()),
+        },
+        tokens_used,
+    })
+}
+
+fn sampling_prompt(request: &CreateMessageRequestParams) -> Prompt {
+    let input = request
+        .messages
+        .iter()
+        .map(|message| {
+            let (role, content) = match message.role {
+                Role::User => (
+                    "user",
+                    match &message.content {
+                        SamplingMessageContent::TextContent(text) => ContentItem::InputText {
+                            text: text.text.clone(),
+                        },
+                        SamplingMessageContent::ImageContent(image) => ContentItem::InputImage {
+                            image_url: format!("data:{};base64,{}", image.mime_type, image.data),
+                        },
+                        SamplingMessageContent::AudioContent(audio) => ContentItem::InputText {
+                            text: format!("[audio content omitted: {}]", audio.mime_type),
+                        },
+                    },
+                ),
+                Role::Assistant => (
+                    "assistant",
+                    ContentItem::OutputText {
+                        text: match &message.content {
+                            SamplingMessageContent::TextContent(text) => text.text.clone(),
+                            SamplingMessageContent::ImageContent(image) => {
+                                format!("[image content omitted: {}]", image.mime_type)
+                            }
+                            SamplingMessageContent::AudioContent(audio) => {
+                                format!("[audio content omitted: {}]", audio.mime_type)
+                            }
+                        },
+                    },
+                ),
+            };
+            ResponseItem::Message {
+                id: None,
+                role: role.to_string(),
+                content: vec![content],
+            }
+        })
+        .collect();
+
+    Prompt {
+        input,
+        base_instructions_override: Some(request.system_prompt.clone().unwrap_or_default()),
+        max_output_tokens: u64::try_from(request.max_tokens).ok(),
+        ..Default::default()
+    }
+}
+
+/// Truncates `text` at the earliest stop sequence and reports why generation
+/// ended.
+fn apply_stop_sequences(mut text: String, stop_sequences: &[String]) -> (String, &'static str) {
+    let earliest = stop_sequences
+        .iter()
+        .filter(|sequence| !sequence.is_empty())
+        .filter_map(|sequence| text.find(sequence.as_str()))
+        .min();
+    match earliest {
+        Some(index) => {
+            text.truncate(index);
+            (text, STOP_REASON_STOP_SEQUENCE)
+        }
+        None => (text, STOP_REASON_END_TURN),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_types::SamplingMessage;
+    use pretty_assertions::assert_eq;
+
+    fn text_message(role: Role, text: &str) -> SamplingMessage {
+        SamplingMessage {
+            content: SamplingMessageContent::TextContent(TextContent {
+                annotations: None,
+                text: text.to_string(),
+                r#type: "text".to_string(),
+            }),
+            role,
+        }
+    }
+
+    #[test]
+    fn sampling_prompt_maps_roles_and_system_prompt() {
+        let request = CreateMessageRequestParams {
+            include_context: None,
+            max_tokens: 100,
+            messages: vec![
+                text_message(Role::User, "What is 2 + 2?"),
+                text_message(Role::Assistant, "4"),
+                text_message(Role::User, "And times 3?"),
+            ],
+            metadata: None,
+            model_preferences: None,
+            stop_sequences: None,
+            system_prompt: Some("Answer tersely.".to_string()),
+            temperature: None,
+        };
+
+        let prompt = sampling_prompt(&request);
+
+        assert_eq!(
+            prompt.base_instructions_override,
+            Some("Answer tersely.".to_string())
+        );
+        assert_eq!(prompt.max_output_tokens, Some(100));
+        assert_eq!(
+            prompt.input,
+            vec![
+                ResponseItem::Message {
+                    id: None,
+                    role: "user".to_string(),
+                    content: vec![ContentItem::InputText {
+                        text: "What is 2 + 2?".to_string(),
+                    }],
+                },
+                ResponseItem::Message {
+                    id: None,
+                    role: "assistant".to_string(),
+                    content: vec![ContentItem::OutputText {
+                        text: "4".to_string(),
+                    }],
+                },
+                ResponseItem::Message {
+                    id: None,
+                    role: "user".to_string(),
+                    content: vec![ContentItem::InputText {
+                        text: "And times 3?".to_string(),
+                    }],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn stop_sequences_truncate_at_earliest_match() {
+        assert_eq!(
+            apply_stop_sequences(
+                "alpha END beta STOP".to_string(),
+                &["STOP".to_string(), "END".to_string()],
+            ),
+            ("alpha ".to_string(), STOP_REASON_STOP_SEQUENCE)
+        );
+        assert_eq!(
+            apply_stop_sequences("alpha".to_string(), &["STOP".to_string()]),
+            ("alpha".to_string(), STOP_REASON_END_TURN)
+        );
+    }
+}
@@ -0,0 +1,269 @@
+//! On-disk cache of MCP tool schemas.
+//!
+//! A server whose tools are cached is not started with the session; the cached
+//! schemas are offered to the model and the server is launched the first time
+//! it is actually used. Each entry is keyed by the server's launch command (or
+//! URL) and by the size and modification time of the executable the command
+//! resolves to, so upgrading the server binary misses the cache. Entries also
+//! expire after [`MAX_ENTRY_AGE`] for servers whose version the launch command
+//! does not pin, such as `npx some-server`, and are rewritten whenever a
+//! started server reports a different version or tool list.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use mcp_types::Tool;
+use serde::Deserialize;
+use serde::Serialize;
+use sha1::Digest;
+use sha1::Sha1;
+use tracing::warn;
+
+use crate::config::types::McpServerTransportConfig;
+
+const TOOL_CACHE_DIR: &str = "cache/mcp_tools";
+
+/// Entries older than this are ignored, and rewritten by the next start.
+const MAX_ENTRY_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Clone, Debug)]
+pub(crate) struct McpToolCache {
+    dir: PathBuf,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct CachedTools {
+    server_version: String,
+    tools: Vec<Tool>,
+}
+
+impl McpToolCache {
+    pub(crate) fn new(codex_home: &Path) -> Self {
+        Self {
+            dir: codex_home.join(TOOL_CACHE_DIR),
+        }
+    }
+
+    /// Returns the tools recorded for this launch command, if any.
+    pub(crate) async fn load(&self, transport: &McpServerTransportConfig) -> Option<Vec<Tool>> {
+        let path = self.entry_path(transport);
+        if !is_fresh(&path).await {
+            return None;
+        }
+        let contents = tokio::fs::read(&path).await.ok()?;
+        match serde_json::from_slice::<CachedTools>(&contents) {
+            Ok(cached) => Some(cached.tools),
+            Err(err) => {
+                warn!(
+                    "ignoring unreadable MCP tool cache {}: {err}",
+                    path.display()
+                );
+                None
+            }
+        }
+    }
+
+    /// Records the tools a started server reported. Unchanged entries are not
+    /// rewritten until they expire.
+    pub(crate) async fn store(
+        &self,
+        transport: &McpServerTransportConfig,
+        server_version: &str,
+        tools: &[Tool],
+    ) {
+        let path = self.entry_path(transport);
+        let entry = CachedTools {
+            server_version: server_version.to_string(),
+            tools: tools.to_vec(),
+        };
+        if is_fresh(&path).await
+            && let Ok(existing) = tokio::fs::read(&path).await
+            && serde_json::from_slice::<CachedTools>(&existing)
+                .ok()
+                .as_ref()
+                == Some(&entry)
+        {
+            return;
+        }
+
+        let result = async {
+            tokio::fs::create_dir_all(&self.dir).await?;
+            let json = serde_json::to_vec(&entry)?;
+            tokio::fs::write(&path, json).await?;
+            anyhow::Ok(())
+        }
+        .await;
+        if let Err(err) = result {
+            warn!("failed to write MCP tool cache {}: {err:#}", path.display());
+        }
+    }
+
+    fn entry_path(&self, transport: &McpServerTransportConfig) -> PathBuf {
+        self.dir.join(format!("{}.json", cache_key(transport)))
+    }
+}
+
+async fn is_fresh(path: &Path) -> bool {
+    tokio::fs::metadata(path)
+        .await
+        .and_then(|metadata| metadata.modified())
+        .is_ok_and(|modified| {
+            SystemTime::now()
+                .duration_since(modified)
+                .is_ok_and(|age| age < MAX_ENTRY_AGE)
+        })
+}
+
+/// Hashes the parts of the transport that identify which server binary (or
+/// endpoint) is launched. Environment values are sorted so the key is stable.
+fn cache_key(transport: &McpServerTransportConfig) -> String {
+    let mut hasher = Sha1::new();
+    match transport {
+        McpServerTransportConfig::Stdio {
+            command,
+            args,
+            env,
+            env_vars: _,
+            cwd,
+        } => {
+            hasher.update(b"stdio\0");
+            hasher.update(command.as_bytes());
+            for arg in args {
+                hasher.update(b"\0");
+                hasher.update(arg.as_bytes());
+            }
+            if let Some(env) = env {
+                let mut pairs: Vec<_> = env.iter().collect();
+                pairs.sort();
+                for (key, value) in pairs {
+                    hasher.update(format!("\0{key}={value}").as_bytes());
+                }
+            }
+            if let Some(cwd) = cwd {
+                hasher.update(b"\0");
+                hasher.update(cwd.to_string_lossy().as_bytes());
+            }
+            if let Some(stamp) = executable_stamp(command, cwd.as_deref()) {
+                hasher.update(b"\0");
+                hasher.update(stamp.as_bytes());
+            }
+        }
+        McpServerTransportConfig::StreamableHttp { url, .. } => {
+            hasher.update(b"http\0");
+            hasher.update(url.as_bytes());
+        }
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Identifies the installed executable `command` resolves to, so that
+/// replacing it changes the key even though the command is the same.
+fn executable_stamp(command: &str, cwd: Option<&Path>) -> Option<String> {
+    let cwd = match cwd {
+        Some(cwd) => cwd.to_path_buf(),
+        None => std::env::current_dir().ok()?,
+    };
+    let path = which::which_in(command, std::env::var_os("PATH"), cwd).ok()?;
+    let metadata = std::fs::metadata(&path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .ok()?
+        .as_nanos();
+    Some(format!(
+        "{}\0{}\0{modified}",
+        path.display(),
+        metadata.len()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_types::ToolInputSchema;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn stdio(command: &str, env: &[(&str, &str)]) -> McpServerTransportConfig {
+        McpServerTransportConfig::Stdio {
+            command: command.to_string(),
+            args: vec!["--stdio".to_string()],
+            env: Some(
+                env.iter()
+                    .map(|(key, value)| (key.to_string(), value.to_string()))
+                    .collect::<HashMap<_, _>>(),
+            ),
+            env_vars: Vec::new(),
+            cwd: None,
+        }
+    }
+
+    fn tool(name: &str) -> Tool {
+        Tool {
+            annotations: None,
+            description: None,
+            input_schema: ToolInputSchema {
+                properties: None,
+                required: None,
+                r#type: "object".to_string(),
+            },
+            name: name.to_string(),
+            output_schema: None,
+            title: None,
+        }
+    }
+
+    #[test]
+    fn cache_key_depends_on_command_not_env_order() {
+        assert_eq!(
+            cache_key(&stdio("docs-server", &[("A", "1"), ("B", "2")])),
+            cache_key(&stdio("docs-server", &[("B", "2"), ("A", "1")]))
+        );
+        assert_ne!(
+            cache_key(&stdio("docs-server", &[])),
+            cache_key(&stdio("other-server", &[]))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cache_key_changes_when_the_executable_is_replaced() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().expect("tempdir");
+        let server = dir.path().join("docs-server");
+        std::fs::write(&server, "v1").expect("write server");
+        std::fs::set_permissions(&server, std::fs::Permissions::from_mode(0o755))
+            .expect("make server executable");
+        let transport = stdio(&server.to_string_lossy(), &[]);
+        let before = cache_key(&transport);
+
+        std::fs::write(&server, "version 2").expect("write server");
+
+        assert_ne!(cache_key(&transport), before);
+    }
+
+    #[tokio::test]
+    async fn stores_and_loads_tools() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cache = McpToolCache::new(codex_home.path());
+        let transport = stdio("docs-server", &[]);
+
+        assert_eq!(cache.load(&transport).await, None);
+
+        cache.store(&transport, "1.0.0", &[tool("search")]).await;
+        assert_eq!(cache.load(&transport).await, Some(vec![tool("search")]));
+
+        cache
+            .store(&transport, "1.1.0", &[tool("search"), tool("fetch")])
+            .await;
+        assert_eq!(
+            cache.load(&transport).await,
+            Some(vec![tool("search"), tool("fetch")])
+        );
+    }
+}
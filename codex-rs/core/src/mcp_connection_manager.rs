@@ -12,6 +12,11 @@ use std::env;
 use std::ffi::OsString;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::sync::MutexGuard as StdMutexGuard;
+use std::sync::RwLock as StdRwLock;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 
 use crate::mcp::auth::McpAuthStatusEntry;
@@ -24,29 +29,42 @@ use codex_async_utils::OrCancelExt;
 use codex_protocol::approvals::ElicitationRequestEvent;
 use codex_protocol::protocol::Event;
 use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::McpServerDownEvent;
+use codex_protocol::protocol::McpServerRestartedEvent;
 use codex_protocol::protocol::McpStartupCompleteEvent;
 use codex_protocol::protocol::McpStartupFailure;
 use codex_protocol::protocol::McpStartupStatus;
 use codex_protocol::protocol::McpStartupUpdateEvent;
 use codex_protocol::protocol::SandboxPolicy;
+use codex_rmcp_client::ElicitationAction;
 use codex_rmcp_client::ElicitationResponse;
+use codex_rmcp_client::ListRoots;
 use codex_rmcp_client::OAuthCredentialsStoreMode;
 use codex_rmcp_client::RmcpClient;
 use codex_rmcp_client::SendElicitation;
+use codex_rmcp_client::SendSampling;
 use futures::future::BoxFuture;
 use futures::future::FutureExt;
 use futures::future::Shared;
 use mcp_types::ClientCapabilities;
+use mcp_types::ClientCapabilitiesRoots;
+use mcp_types::CreateMessageRequestParams;
+use mcp_types::CreateMessageResult;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
 use mcp_types::Implementation;
+use mcp_types::ListPromptsRequestParams;
 use mcp_types::ListResourceTemplatesRequestParams;
 use mcp_types::ListResourceTemplatesResult;
 use mcp_types::ListResourcesRequestParams;
 use mcp_types::ListResourcesResult;
+use mcp_types::Prompt;
 use mcp_types::ReadResourceRequestParams;
 use mcp_types::ReadResourceResult;
 use mcp_types::RequestId;
 use mcp_types::Resource;
 use mcp_types::ResourceTemplate;
+use mcp_types::SamplingMessageContent;
 use mcp_types::Tool;
 
 use serde::Deserialize;
@@ -55,6 +73,7 @@ use serde_json::json;
 use sha1::Digest;
 use sha1::Sha1;
 use tokio::sync::Mutex;
+use tokio::sync::Notify;
 use tokio::sync::oneshot;
 use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
@@ -62,8 +81,15 @@ use tracing::instrument;
 use tracing::warn;
 
 use crate::codex::INITIAL_SUBMIT_ID;
+use crate::config::types::McpSamplingApproval;
+use crate::config::types::McpSamplingConfig;
 use crate::config::types::McpServerConfig;
 use crate::config::types::McpServerTransportConfig;
+use crate::config::types::ProxyConfig;
+use crate::default_client::ProxyDestination;
+use crate::default_client::proxy_for;
+use crate::mcp::McpToolCache;
+use crate::mcp::workspace_roots;
 
 /// Delimiter used to separate the server name from the tool name in a fully
 /// qualified tool name.
@@ -79,6 +105,25 @@ pub const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
 /// Default timeout for individual tool calls.
 const DEFAULT_TOOL_TIMEOUT: Duration = Duration::from_secs(60);
 
+/// How often running servers are checked for a closed connection.
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often running servers are pinged, and how long a ping may take.
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+const PING_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Restart backoff starts here and doubles per failed attempt up to the max.
+const RESTART_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const RESTART_MAX_BACKOFF: Duration = Duration::from_secs(60);
+const MAX_RESTART_ATTEMPTS: u32 = 5;
+
+/// Tokens a server may consume through sampling per session unless its
+/// `sampling.token_budget` says otherwise.
+const DEFAULT_SAMPLING_TOKEN_BUDGET: u64 = 100_000;
+
+/// Longest excerpt of a sampling request shown in the approval prompt.
+const SAMPLING_PREVIEW_MAX_CHARS: usize = 200;
+
 /// The Responses API requires tool names to match `^[a-zA-Z0-9_-]+$`.
 /// MCP server/tool names are user-controlled, so sanitize the fully-qualified
 /// name we expose to the model by replacing any disallowed character with `_`.
@@ -178,36 +223,220 @@ impl ElicitationRequestManager {
             .map_err(|e| anyhow!("failed to send elicitation response: {e:?}"))
     }
 
+    /// Asks the user to respond to `message` and waits for the answer.
+    async fn request(
+        &self,
+        server_name: String,
+        id: RequestId,
+        message: String,
+        tx_event: &Sender<Event>,
+    ) -> Result<ElicitationResponse> {
+        let (tx, rx) = oneshot::channel();
+        {
+            let mut lock = self.requests.lock().await;
+            lock.insert((server_name.clone(), id.clone()), tx);
+        }
+        let _ = tx_event
+            .send(Event {
+                id: "mcp_elicitation_request".to_string(),
+                msg: EventMsg::ElicitationRequest(ElicitationRequestEvent {
+                    server_name,
+                    id,
+                    message,
+                }),
+            })
+            .await;
+        rx.await
+            .context("elicitation request channel closed unexpectedly")
+    }
+
     fn make_sender(&self, server_name: String, tx_event: Sender<Event>) -> SendElicitation {
-        let elicitation_requests = self.requests.clone();
+        let elicitation_requests = self.clone();
         Box::new(move |id, elicitation| {
             let elicitation_requests = elicitation_requests.clone();
             let tx_event = tx_event.clone();
             let server_name = server_name.clone();
             async move {
-                let (tx, rx) = oneshot::channel();
-                {
-                    let mut lock = elicitation_requests.lock().await;
-                    lock.insert((server_name.clone(), id.clone()), tx);
-                }
-                let _ = tx_event
-                    .send(Event {
-                        id: "mcp_elicitation_request".to_string(),
-                        msg: EventMsg::ElicitationRequest(ElicitationRequestEvent {
-                            server_name,
-                            id,
-                            message: elicitation.message,
-                        }),
-                    })
-                    .await;
-                rx.await
-                    .context("elicitation request channel closed unexpectedly")
+                elicitation_requests
+                    .request(server_name, id, elicitation.message, &tx_event)
+                    .await
             }
             .boxed()
         })
     }
 }
 
+/// Completion produced for a sampling request, along with the tokens it cost.
+pub(crate) struct McpSamplingOutcome {
+    pub(crate) result: CreateMessageResult,
+    pub(crate) tokens_used: u64,
+}
+
+/// Runs a `sampling/createMessage` request against the session's model.
+pub(crate) type McpSampler = Arc<
+    dyn Fn(CreateMessageRequestParams) -> BoxFuture<'static, Result<McpSamplingOutcome>>
+        + Send
+        + Sync,
+>;
+
+/// Gates server-initiated sampling behind the per-server approval policy and
+/// token budget before handing requests to the session's model.
+#[derive(Clone)]
+struct SamplingRequestManager {
+    sampler: McpSampler,
+    elicitation_requests: ElicitationRequestManager,
+    /// Tokens consumed through sampling so far, keyed by server name. Shared
+    /// across restarts so a crashing server cannot reset its budget.
+    tokens_used: Arc<StdMutex<HashMap<String, u64>>>,
+}
+
+impl SamplingRequestManager {
+    fn new(sampler: McpSampler, elicitation_requests: ElicitationRequestManager) -> Self {
+        Self {
+            sampler,
+            elicitation_requests,
+            tokens_used: Arc::new(StdMutex::new(HashMap::new())),
+        }
+    }
+
+    /// Returns `None` when the server is not allowed to sample, in which case
+    /// the capability is not advertised during the handshake.
+    fn make_sender(
+        &self,
+        server_name: String,
+        config: McpSamplingConfig,
+        tx_event: Sender<Event>,
+    ) -> Option<SendSampling> {
+        if config.approval == McpSamplingApproval::Deny {
+            return None;
+        }
+        let sampling_requests = self.clone();
+        Some(Box::new(move |id, request| {
+            let sampling_requests = sampling_requests.clone();
+            let server_name = server_name.clone();
+            let config = config.clone();
+            let tx_event = tx_event.clone();
+            async move {
+                sampling_requests
+                    .sample(server_name, &config, &tx_event, id, request)
+                    .await
+            }
+            .boxed()
+        }))
+    }
+
+    async fn sample(
+        &self,
+        server_name: String,
+        config: &McpSamplingConfig,
+        tx_event: &Sender<Event>,
+        id: RequestId,
+        request: CreateMessageRequestParams,
+    ) -> Result<CreateMessageResult> {
+        let budget = config.token_budget.unwrap_or(DEFAULT_SAMPLING_TOKEN_BUDGET);
+        let requested = u64::try_from(request.max_tokens).unwrap_or_default();
+        self.reserve(&server_name, requested, budget)?;
+        let outcome = self
+            .sample_reserved(&server_name, config, tx_event, id, request)
+            .await;
+        let used = outcome.as_ref().map_or(0, |outcome| outcome.tokens_used);
+        self.settle(&server_name, requested, used);
+        outcome.map(|outcome| outcome.result)
+    }
+
+    async fn sample_reserved(
+        &self,
+        server_name: &str,
+        config: &McpSamplingConfig,
+        tx_event: &Sender<Event>,
+        id: RequestId,
+        request: CreateMessageRequestParams,
+    ) -> Result<McpSamplingOutcome> {
+        match config.approval {
+            McpSamplingApproval::Auto => {}
+            McpSamplingApproval::Deny => {
+                return Err(anyhow!(
+                    "sampling is disabled for MCP server `{server_name}`"
+                ));
+            }
+            McpSamplingApproval::Prompt => {
+                let message = sampling_approval_message(server_name, &request);
+                let response = self
+                    .elicitation_requests
+                    .request(server_name.to_string(), id, message, tx_event)
+                    .await?;
+                if !matches!(response.action, ElicitationAction::Accept) {
+                    return Err(anyhow!("user declined the sampling request"));
+                }
+            }
+        }
+
+        (self.sampler)(request).await
+    }
+
+    fn tokens_used(&self, server_name: &str) -> u64 {
+        self.lock_usage()
+            .get(server_name)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Sets aside `tokens` of the server's budget, or fails if they do not
+    /// fit. Checking and recording under one lock keeps concurrent requests
+    /// from overspending the budget together.
+    fn reserve(&self, server_name: &str, tokens: u64, budget: u64) -> Result<()> {
+        let mut guard = self.lock_usage();
+        let used = guard.entry(server_name.to_string()).or_default();
+        if used.saturating_add(tokens) > budget {
+            return Err(anyhow!(
+                "sampling token budget exceeded for MCP server `{server_name}` ({used} of {budget} tokens used, {tokens} requested)"
+            ));
+        }
+        *used += tokens;
+        Ok(())
+    }
+
+    /// Replaces a reservation with the tokens the request actually used.
+    fn settle(&self, server_name: &str, reserved: u64, tokens: u64) {
+        let mut guard = self.lock_usage();
+        let used = guard.entry(server_name.to_string()).or_default();
+        *used = used.saturating_sub(reserved).saturating_add(tokens);
+    }
+
+    fn lock_usage(&self) -> StdMutexGuard<'_, HashMap<String, u64>> {
+        match self.tokens_used.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        }
+    }
+}
+
+/// Summarizes a sampling request for the approval prompt.
+fn sampling_approval_message(server_name: &str, request: &CreateMessageRequestParams) -> String {
+    let max_tokens = request.max_tokens;
+    let mut message = format!(
+        "{server_name} wants to use the model (up to {max_tokens} tokens). Allow this request?"
+    );
+    let preview = request
+        .messages
+        .iter()
+        .rev()
+        .find_map(|message| match &message.content {
+            SamplingMessageContent::TextContent(text) => Some(text.text.as_str()),
+            SamplingMessageContent::ImageContent(_) | SamplingMessageContent::AudioContent(_) => {
+                None
+            }
+        });
+    if let Some(preview) = preview {
+        let mut excerpt: String = preview.chars().take(SAMPLING_PREVIEW_MAX_CHARS).collect();
+        if preview.chars().count() > SAMPLING_PREVIEW_MAX_CHARS {
+            excerpt.push('…');
+        }
+        message.push_str(&format!("\n\n{excerpt}"));
+    }
+    message
+}
+
 #[derive(Clone)]
 struct ManagedClient {
     client: Arc<RmcpClient>,
@@ -215,6 +444,8 @@ struct ManagedClient {
     tool_filter: ToolFilter,
     tool_timeout: Option<Duration>,
     server_supports_sandbox_state_capability: bool,
+    server_supports_prompts: bool,
+    server_version: String,
 }
 
 impl ManagedClient {
@@ -235,9 +466,48 @@ impl ManagedClient {
     }
 }
 
+type StartupFuture = Shared<BoxFuture<'static, Result<ManagedClient, StartupOutcomeError>>>;
+
+/// Spawns a fresh server process/connection and performs the handshake.
+type ServerLauncher = Arc<dyn Fn(CancellationToken) -> StartupFuture + Send + Sync>;
+
+/// Manager-wide handlers shared by every server connection.
+#[derive(Clone)]
+struct ClientServices {
+    elicitation_requests: ElicitationRequestManager,
+    sampling_requests: Option<SamplingRequestManager>,
+    tool_cache: Option<McpToolCache>,
+    /// Latest sandbox state, which also determines the workspace roots.
+    sandbox_state: Arc<StdRwLock<Option<SandboxState>>>,
+    /// `[proxy]` settings for servers reached over HTTP.
+    proxy: Arc<ProxyConfig>,
+}
+
+impl ClientServices {
+    fn make_roots_lister(&self) -> ListRoots {
+        let sandbox_state = Arc::clone(&self.sandbox_state);
+        Box::new(move || {
+            latest_sandbox_state(&sandbox_state)
+                .map(|state| workspace_roots(&state))
+                .unwrap_or_default()
+        })
+    }
+}
+
 #[derive(Clone)]
 struct AsyncManagedClient {
-    client: Shared<BoxFuture<'static, Result<ManagedClient, StartupOutcomeError>>>,
+    // Swapped out when the health monitor restarts the server.
+    client: Arc<StdRwLock<StartupFuture>>,
+    launcher: ServerLauncher,
+    tool_filter: ToolFilter,
+    /// Tools loaded from the on-disk cache. While set, these are reported
+    /// until the server has been used and has finished starting.
+    cached_tools: Option<Vec<ToolInfo>>,
+    /// Set by the first caller that needs the live connection.
+    started: Arc<AtomicBool>,
+    first_use: Arc<Notify>,
+    /// Set once the health monitor has exhausted its restart attempts.
+    gave_up: Arc<AtomicBool>,
 }
 
 impl AsyncManagedClient {
@@ -247,45 +517,155 @@ impl AsyncManagedClient {
         store_mode: OAuthCredentialsStoreMode,
         cancel_token: CancellationToken,
         tx_event: Sender<Event>,
-        elicitation_requests: ElicitationRequestManager,
+        services: ClientServices,
+        cached_tools: Option<Vec<Tool>>,
     ) -> Self {
         let tool_filter = ToolFilter::from_config(&config);
-        let fut = async move {
-            if let Err(error) = validate_mcp_server_name(&server_name) {
-                return Err(error.into());
-            }
+        let cached_tools = cached_tools.map(|tools| {
+            tools
+                .into_iter()
+                .map(|tool| ToolInfo {
+                    server_name: server_name.clone(),
+                    tool_name: tool.name.clone(),
+                    tool,
+                })
+                .collect::<Vec<_>>()
+        });
+        let started = Arc::new(AtomicBool::new(cached_tools.is_none()));
+        let launcher_tool_filter = tool_filter.clone();
+        let launcher: ServerLauncher = Arc::new(move |cancel_token: CancellationToken| {
+            let server_name = server_name.clone();
+            let config = config.clone();
+            let tool_filter = launcher_tool_filter.clone();
+            let tool_cache = services.tool_cache.clone();
+            let proxy = Arc::clone(&services.proxy);
+            let send_elicitation = services
+                .elicitation_requests
+                .make_sender(server_name.clone(), tx_event.clone());
+            let list_roots = services.make_roots_lister();
+            let send_sampling = services
+                .sampling_requests
+                .as_ref()
+                .and_then(|sampling_requests| {
+                    sampling_requests.make_sender(
+                        server_name.clone(),
+                        config.sampling.clone().unwrap_or_default(),
+                        tx_event.clone(),
+                    )
+                });
+            async move {
+                if let Err(error) = validate_mcp_server_name(&server_name) {
+                    return Err(error.into());
+                }
 
-            let client =
-                Arc::new(make_rmcp_client(&server_name, config.transport, store_mode).await?);
-            match start_server_task(
-                server_name,
-                client,
-                config.startup_timeout_sec.or(Some(DEFAULT_STARTUP_TIMEOUT)),
-                config.tool_timeout_sec.unwrap_or(DEFAULT_TOOL_TIMEOUT),
-                tool_filter,
-                tx_event,
-                elicitation_requests,
-            )
-            .or_cancel(&cancel_token)
-            .await
-            {
-                Ok(result) => result,
-                Err(CancelErr::Cancelled) => Err(StartupOutcomeError::Cancelled),
+                let transport = config.transport.clone();
+                let client = Arc::new(
+                    make_rmcp_client(&server_name, config.transport, store_mode, &proxy).await?,
+                );
+                let managed = match start_server_task(
+                    server_name,
+                    client,
+                    config.startup_timeout_sec.or(Some(DEFAULT_STARTUP_TIMEOUT)),
+                    config.tool_timeout_sec.unwrap_or(DEFAULT_TOOL_TIMEOUT),
+                    tool_filter,
+                    ServerHandlers {
+                        send_elicitation,
+                        send_sampling,
+                        list_roots,
+                    },
+                )
+                .or_cancel(&cancel_token)
+                .await
+                {
+                    Ok(result) => result?,
+                    Err(CancelErr::Cancelled) => return Err(StartupOutcomeError::Cancelled),
+                };
+                if let Some(tool_cache) = tool_cache {
+                    let tools: Vec<Tool> =
+                        managed.tools.iter().map(|info| info.tool.clone()).collect();
+                    tool_cache
+                        .store(&transport, &managed.server_version, &tools)
+                        .await;
+                }
+                Ok(managed)
             }
-        };
+            .boxed()
+            .shared()
+        });
+        let client = launcher(cancel_token);
         Self {
-            client: fut.boxed().shared(),
+            client: Arc::new(StdRwLock::new(client)),
+            launcher,
+            tool_filter,
+            cached_tools,
+            started,
+            first_use: Arc::new(Notify::new()),
+            gave_up: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Cached tools for a server that has not been started yet or is still
+    /// starting, so listing tools never waits for a deferred launch.
+    fn deferred_tools(&self) -> Option<Vec<ToolInfo>> {
+        let cached = self.cached_tools.as_ref()?;
+        if !self.is_deferred() && self.current().peek().is_some() {
+            return None;
+        }
+        Some(cached.clone())
+    }
+
+    fn is_deferred(&self) -> bool {
+        !self.started.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once something needs the live connection to this server.
+    async fn wait_for_first_use(&self) {
+        if self.is_deferred() {
+            self.first_use.notified().await;
         }
     }
 
     async fn client(&self) -> Result<ManagedClient, StartupOutcomeError> {
-        self.client.clone().await
+        if !self.started.swap(true, Ordering::SeqCst) {
+            self.first_use.notify_one();
+        }
+        self.current().await
+    }
+
+    fn current(&self) -> StartupFuture {
+        match self.client.read() {
+            Ok(guard) => guard.clone(),
+            Err(err) => err.into_inner().clone(),
+        }
+    }
+
+    /// Replaces the current connection with a freshly launched one. Callers
+    /// awaiting `client()` afterwards observe the new startup outcome.
+    fn restart(&self, cancel_token: CancellationToken) {
+        let next = (self.launcher)(cancel_token);
+        match self.client.write() {
+            Ok(mut guard) => *guard = next,
+            Err(err) => *err.into_inner() = next,
+        }
     }
 
     async fn notify_sandbox_state_change(&self, sandbox_state: &SandboxState) -> Result<()> {
+        // Deferred servers receive the latest state once they start.
+        if self.is_deferred() {
+            return Ok(());
+        }
         let managed = self.client().await?;
         managed.notify_sandbox_state_change(sandbox_state).await
     }
+
+    /// Deferred servers ask for roots when they start, so they are skipped.
+    async fn notify_roots_list_changed(&self) -> Result<()> {
+        if self.is_deferred() {
+            return Ok(());
+        }
+        let managed = self.client().await?;
+        managed.client.notify_roots_list_changed().await
+    }
 }
 
 pub const MCP_SANDBOX_STATE_CAPABILITY: &str = "codex/sandbox-state";
@@ -307,9 +687,20 @@ pub struct SandboxState {
 pub(crate) struct McpConnectionManager {
     clients: HashMap<String, AsyncManagedClient>,
     elicitation_requests: ElicitationRequestManager,
+    /// Stops the per-server health monitors when the manager is dropped.
+    health_cancel_token: CancellationToken,
+    /// Most recent sandbox state, re-sent to servers after a restart.
+    sandbox_state: Arc<StdRwLock<Option<SandboxState>>>,
+}
+
+impl Drop for McpConnectionManager {
+    fn drop(&mut self) {
+        self.health_cancel_token.cancel();
+    }
 }
 
 impl McpConnectionManager {
+    #[allow(clippy::too_many_arguments)]
     pub async fn initialize(
         &mut self,
         mcp_servers: HashMap<String, McpServerConfig>,
@@ -318,6 +709,9 @@ impl McpConnectionManager {
         tx_event: Sender<Event>,
         cancel_token: CancellationToken,
         initial_sandbox_state: SandboxState,
+        sampler: Option<McpSampler>,
+        tool_cache: Option<McpToolCache>,
+        proxy: ProxyConfig,
     ) {
         if cancel_token.is_cancelled() {
             return;
@@ -325,7 +719,48 @@ impl McpConnectionManager {
         let mut clients = HashMap::new();
         let mut join_set = JoinSet::new();
         let elicitation_requests = ElicitationRequestManager::default();
+        let sampling_requests = sampler
+            .map(|sampler| SamplingRequestManager::new(sampler, elicitation_requests.clone()));
+        self.health_cancel_token.cancel();
+        self.health_cancel_token = CancellationToken::new();
+        set_latest_sandbox_state(&self.sandbox_state, &initial_sandbox_state);
+        let services = ClientServices {
+            elicitation_requests: elicitation_requests.clone(),
+            sampling_requests,
+            tool_cache: tool_cache.clone(),
+            sandbox_state: Arc::clone(&self.sandbox_state),
+            proxy: Arc::new(proxy),
+        };
+        let mut deferred = Vec::new();
         for (server_name, cfg) in mcp_servers.into_iter().filter(|(_, cfg)| cfg.enabled) {
+            let cached_tools = match &tool_cache {
+                Some(tool_cache) => tool_cache.load(&cfg.transport).await,
+                None => None,
+            };
+            if cached_tools.is_some() {
+                // Interrupting startup must not cancel a server that has not
+                // been launched yet, so tie it to the manager's lifetime.
+                let async_managed_client = AsyncManagedClient::new(
+                    server_name.clone(),
+                    cfg,
+                    store_mode,
+                    self.health_cancel_token.child_token(),
+                    tx_event.clone(),
+                    services.clone(),
+                    cached_tools,
+                );
+                clients.insert(server_name.clone(), async_managed_client.clone());
+                tokio::spawn(start_deferred_server(
+                    server_name.clone(),
+                    async_managed_client,
+                    tx_event.clone(),
+                    Arc::clone(&self.sandbox_state),
+                    self.health_cancel_token.clone(),
+                ));
+                deferred.push(server_name);
+                continue;
+            }
+
             let cancel_token = cancel_token.child_token();
             let _ = emit_update(
                 &tx_event,
@@ -341,12 +776,15 @@ impl McpConnectionManager {
                 store_mode,
                 cancel_token.clone(),
                 tx_event.clone(),
-                elicitation_requests.clone(),
+                services.clone(),
+                None,
             );
             clients.insert(server_name.clone(), async_managed_client.clone());
             let tx_event = tx_event.clone();
             let auth_entry = auth_entries.get(&server_name).cloned();
             let sandbox_state = initial_sandbox_state.clone();
+            let health_cancel_token = self.health_cancel_token.clone();
+            let latest_sandbox_state = Arc::clone(&self.sandbox_state);
             join_set.spawn(async move {
                 let outcome = async_managed_client.client().await;
                 if cancel_token.is_cancelled() {
@@ -363,6 +801,13 @@ impl McpConnectionManager {
                                 "Failed to notify sandbox state to MCP server {server_name}: {e:#}",
                             );
                         }
+                        tokio::spawn(monitor_server_health(
+                            server_name.clone(),
+                            async_managed_client.clone(),
+                            tx_event.clone(),
+                            latest_sandbox_state,
+                            health_cancel_token,
+                        ));
                         McpStartupStatus::Ready
                     }
                     Err(error) => {
@@ -391,7 +836,10 @@ impl McpConnectionManager {
         self.elicitation_requests = elicitation_requests.clone();
         tokio::spawn(async move {
             let outcomes = join_set.join_all().await;
-            let mut summary = McpStartupCompleteEvent::default();
+            let mut summary = McpStartupCompleteEvent {
+                deferred,
+                ..Default::default()
+            };
             for (server_name, outcome) in outcomes {
                 match outcome {
                     Ok(_) => summary.ready.push(server_name),
@@ -414,12 +862,15 @@ impl McpConnectionManager {
     }
 
     async fn client_by_name(&self, name: &str) -> Result<ManagedClient> {
-        self.clients
+        let client = self
+            .clients
             .get(name)
-            .ok_or_else(|| anyhow!("unknown MCP server '{name}'"))?
-            .client()
-            .await
-            .context("failed to get client")
+            .ok_or_else(|| anyhow!("unknown MCP server '{name}'"))?;
+        let result = client.client().await;
+        if client.gave_up.load(Ordering::SeqCst) {
+            return result.context(server_down_message(name, false));
+        }
+        result.context("failed to get client")
     }
 
     pub async fn resolve_elicitation(
@@ -439,6 +890,13 @@ impl McpConnectionManager {
     pub async fn list_all_tools(&self) -> HashMap<String, ToolInfo> {
         let mut tools = HashMap::new();
         for managed_client in self.clients.values() {
+            if let Some(cached) = managed_client.deferred_tools() {
+                tools.extend(qualify_tools(filter_tools(
+                    cached,
+                    managed_client.tool_filter.clone(),
+                )));
+                continue;
+            }
             if let Ok(client) = managed_client.client().await {
                 tools.extend(qualify_tools(filter_tools(
                     client.tools,
@@ -450,13 +908,17 @@ impl McpConnectionManager {
     }
 
     /// Returns a single map that contains all resources. Each key is the
-    /// server name and the value is a vector of resources.
+    /// server name and the value is a vector of resources. Servers that have
+    /// not been started yet are skipped.
     pub async fn list_all_resources(&self) -> HashMap<String, Vec<Resource>> {
         let mut join_set = JoinSet::new();
 
         let clients_snapshot = &self.clients;
 
         for (server_name, async_managed_client) in clients_snapshot {
+            if async_managed_client.is_deferred() {
+                continue;
+            }
             let server_name = server_name.clone();
             let Ok(managed_client) = async_managed_client.client().await else {
                 continue;
@@ -522,6 +984,9 @@ impl McpConnectionManager {
         let clients_snapshot = &self.clients;
 
         for (server_name, async_managed_client) in clients_snapshot {
+            if async_managed_client.is_deferred() {
+                continue;
+            }
             let server_name_cloned = server_name.clone();
             let Ok(managed_client) = async_managed_client.client().await else {
                 continue;
@@ -585,6 +1050,76 @@ impl McpConnectionManager {
         aggregated
     }
 
+    /// Returns a single map that contains all prompts. Each key is the
+    /// server name and the value is a vector of prompts. Servers that did not
+    /// advertise the `prompts` capability are skipped.
+    pub async fn list_all_prompts(&self) -> HashMap<String, Vec<Prompt>> {
+        let mut join_set = JoinSet::new();
+
+        for (server_name, async_managed_client) in &self.clients {
+            if async_managed_client.is_deferred() {
+                continue;
+            }
+            let server_name = server_name.clone();
+            let Ok(managed_client) = async_managed_client.client().await else {
+                continue;
+            };
+            if !managed_client.server_supports_prompts {
+                continue;
+            }
+            let timeout = managed_client.tool_timeout;
+            let client = managed_client.client.clone();
+
+            join_set.spawn(async move {
+                let mut collected: Vec<Prompt> = Vec::new();
+                let mut cursor: Option<String> = None;
+
+                loop {
+                    let params = cursor.as_ref().map(|next| ListPromptsRequestParams {
+                        cursor: Some(next.clone()),
+                    });
+                    let response = match client.list_prompts(params, timeout).await {
+                        Ok(result) => result,
+                        Err(err) => return (server_name, Err(err)),
+                    };
+
+                    collected.extend(response.prompts);
+
+                    match response.next_cursor {
+                        Some(next) => {
+                            if cursor.as_ref() == Some(&next) {
+                                return (
+                                    server_name,
+                                    Err(anyhow!("prompts/list returned duplicate cursor")),
+                                );
+                            }
+                            cursor = Some(next);
+                        }
+                        None => return (server_name, Ok(collected)),
+                    }
+                }
+            });
+        }
+
+        let mut aggregated: HashMap<String, Vec<Prompt>> = HashMap::new();
+
+        while let Some(join_res) = join_set.join_next().await {
+            match join_res {
+                Ok((server_name, Ok(prompts))) => {
+                    aggregated.insert(server_name, prompts);
+                }
+                Ok((server_name, Err(err))) => {
+                    warn!("Failed to list prompts for MCP server '{server_name}': {err:#}");
+                }
+                Err(err) => {
+                    warn!("Task panic when listing prompts for MCP server: {err:#}");
+                }
+            }
+        }
+
+        aggregated
+    }
+
     /// Invoke the tool indicated by the (server, tool) pair.
     pub async fn call_tool(
         &self,
@@ -598,6 +1133,9 @@ impl McpConnectionManager {
                 "tool '{tool}' is disabled for MCP server '{server}'"
             ));
         }
+        if client.client.is_transport_closed().await {
+            return Err(anyhow!(server_down_message(server, true)));
+        }
 
         client
             .client
@@ -655,6 +1193,23 @@ impl McpConnectionManager {
             .with_context(|| format!("resources/read failed for `{server}` ({uri})"))
     }
 
+    /// Render a prompt from the specified server.
+    pub async fn get_prompt(
+        &self,
+        server: &str,
+        params: GetPromptRequestParams,
+    ) -> Result<GetPromptResult> {
+        let managed = self.client_by_name(server).await?;
+        let client = managed.client.clone();
+        let timeout = managed.tool_timeout;
+        let name = params.name.clone();
+
+        client
+            .get_prompt(params, timeout)
+            .await
+            .with_context(|| format!("prompts/get failed for `{server}` ({name})"))
+    }
+
     pub async fn parse_tool_name(&self, tool_name: &str) -> Option<(String, String)> {
         self.list_all_tools()
             .await
@@ -663,12 +1218,20 @@ impl McpConnectionManager {
     }
 
     pub async fn notify_sandbox_state_change(&self, sandbox_state: &SandboxState) -> Result<()> {
+        let roots_changed = latest_sandbox_state(&self.sandbox_state)
+            .is_none_or(|previous| workspace_roots(&previous) != workspace_roots(sandbox_state));
+        set_latest_sandbox_state(&self.sandbox_state, sandbox_state);
         let mut join_set = JoinSet::new();
 
         for async_managed_client in self.clients.values() {
             let sandbox_state = sandbox_state.clone();
             let async_managed_client = async_managed_client.clone();
             join_set.spawn(async move {
+                if roots_changed
+                    && let Err(err) = async_managed_client.notify_roots_list_changed().await
+                {
+                    warn!("Failed to notify roots change to MCP server: {err:#}");
+                }
                 async_managed_client
                     .notify_sandbox_state_change(&sandbox_state)
                     .await
@@ -691,6 +1254,190 @@ impl McpConnectionManager {
     }
 }
 
+fn set_latest_sandbox_state(cell: &StdRwLock<Option<SandboxState>>, state: &SandboxState) {
+    match cell.write() {
+        Ok(mut guard) => *guard = Some(state.clone()),
+        Err(err) => *err.into_inner() = Some(state.clone()),
+    }
+}
+
+fn latest_sandbox_state(cell: &StdRwLock<Option<SandboxState>>) -> Option<SandboxState> {
+    match cell.read() {
+        Ok(guard) => guard.clone(),
+        Err(err) => err.into_inner().clone(),
+    }
+}
+
+/// Waits until a server whose tools came from the cache is first used, then
+/// performs the post-startup work that eager servers get during `initialize`.
+async fn start_deferred_server(
+    server_name: String,
+    async_managed_client: AsyncManagedClient,
+    tx_event: Sender<Event>,
+    sandbox_state: Arc<StdRwLock<Option<SandboxState>>>,
+    cancel_token: CancellationToken,
+) {
+    tokio::select! {
+        _ = cancel_token.cancelled() => return,
+        _ = async_managed_client.wait_for_first_use() => {}
+    }
+    if let Err(error) = async_managed_client.client().await {
+        warn!("Deferred MCP server {server_name} failed to start: {error:?}");
+        return;
+    }
+    if let Some(state) = latest_sandbox_state(&sandbox_state)
+        && let Err(e) = async_managed_client
+            .notify_sandbox_state_change(&state)
+            .await
+    {
+        warn!("Failed to notify sandbox state to MCP server {server_name}: {e:#}");
+    }
+    monitor_server_health(
+        server_name,
+        async_managed_client,
+        tx_event,
+        sandbox_state,
+        cancel_token,
+    )
+    .await;
+}
+
+/// Watches a running server for crashes (closed transport) and hangs (failed
+/// pings). When the server goes down it is restarted with exponential
+/// backoff, emitting `McpServerDown` / `McpServerRestarted` events.
+async fn monitor_server_health(
+    server_name: String,
+    async_managed_client: AsyncManagedClient,
+    tx_event: Sender<Event>,
+    sandbox_state: Arc<StdRwLock<Option<SandboxState>>>,
+    cancel_token: CancellationToken,
+) {
+    let mut last_ping = tokio::time::Instant::now();
+    loop {
+        tokio::select! {
+            _ = cancel_token.cancelled() => return,
+            _ = tokio::time::sleep(HEALTH_CHECK_INTERVAL) => {}
+        }
+
+        let Ok(managed) = async_managed_client.client().await else {
+            return;
+        };
+        let reason = if managed.client.is_transport_closed().await {
+            Some("connection closed (the server process may have exited)".to_string())
+        } else if last_ping.elapsed() >= PING_INTERVAL {
+            last_ping = tokio::time::Instant::now();
+            managed
+                .client
+                .ping(Some(PING_TIMEOUT))
+                .await
+                .err()
+                .map(|err| format!("{err:#}"))
+        } else {
+            None
+        };
+        let Some(reason) = reason else {
+            continue;
+        };
+
+        warn!("MCP server {server_name} is down: {reason}");
+        emit_health_event(
+            &tx_event,
+            EventMsg::McpServerDown(McpServerDownEvent {
+                server: server_name.clone(),
+                reason,
+                restarting: true,
+            }),
+        )
+        .await;
+
+        match restart_with_backoff(&async_managed_client, &cancel_token).await {
+            Ok(attempts) => {
+                if let Some(state) = latest_sandbox_state(&sandbox_state)
+                    && let Err(e) = async_managed_client
+                        .notify_sandbox_state_change(&state)
+                        .await
+                {
+                    warn!("Failed to notify sandbox state to MCP server {server_name}: {e:#}");
+                }
+                emit_health_event(
+                    &tx_event,
+                    EventMsg::McpServerRestarted(McpServerRestartedEvent {
+                        server: server_name.clone(),
+                        attempts,
+                    }),
+                )
+                .await;
+                last_ping = tokio::time::Instant::now();
+            }
+            Err(StartupOutcomeError::Cancelled) => return,
+            Err(StartupOutcomeError::Failed { error }) => {
+                async_managed_client.gave_up.store(true, Ordering::SeqCst);
+                emit_health_event(
+                    &tx_event,
+                    EventMsg::McpServerDown(McpServerDownEvent {
+                        server: server_name.clone(),
+                        reason: format!("{MAX_RESTART_ATTEMPTS} restart attempts failed: {error}"),
+                        restarting: false,
+                    }),
+                )
+                .await;
+                return;
+            }
+        }
+    }
+}
+
+/// Relaunches the server until a handshake succeeds, returning the number of
+/// attempts it took, or the last error once `MAX_RESTART_ATTEMPTS` is reached.
+async fn restart_with_backoff(
+    async_managed_client: &AsyncManagedClient,
+    cancel_token: &CancellationToken,
+) -> Result<u32, StartupOutcomeError> {
+    let mut last_error = StartupOutcomeError::Cancelled;
+    for attempt in 1..=MAX_RESTART_ATTEMPTS {
+        tokio::select! {
+            _ = cancel_token.cancelled() => return Err(StartupOutcomeError::Cancelled),
+            _ = tokio::time::sleep(restart_backoff(attempt)) => {}
+        }
+        async_managed_client.restart(cancel_token.child_token());
+        match async_managed_client.client().await {
+            Ok(_) => return Ok(attempt),
+            Err(StartupOutcomeError::Cancelled) => return Err(StartupOutcomeError::Cancelled),
+            Err(error) => last_error = error,
+        }
+    }
+    Err(last_error)
+}
+
+/// Error reported for calls to a server that is down, depending on whether
+/// the health monitor is still trying to restart it.
+fn server_down_message(server: &str, restarting: bool) -> String {
+    if restarting {
+        format!("MCP server '{server}' is not running; it will be restarted automatically")
+    } else {
+        format!(
+            "MCP server '{server}' is not running; gave up restarting it after {MAX_RESTART_ATTEMPTS} attempts"
+        )
+    }
+}
+
+/// Delay before the given (1-based) restart attempt.
+fn restart_backoff(attempt: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+    RESTART_INITIAL_BACKOFF
+        .saturating_mul(factor)
+        .min(RESTART_MAX_BACKOFF)
+}
+
+async fn emit_health_event(tx_event: &Sender<Event>, msg: EventMsg) {
+    let _ = tx_event
+        .send(Event {
+            id: INITIAL_SUBMIT_ID.to_owned(),
+            msg,
+        })
+        .await;
+}
+
 async fn emit_update(
     tx_event: &Sender<Event>,
     update: McpStartupUpdateEvent,
@@ -790,20 +1537,35 @@ impl From<anyhow::Error> for StartupOutcomeError {
     }
 }
 
+/// Callbacks that answer server-initiated requests on one connection.
+struct ServerHandlers {
+    send_elicitation: SendElicitation,
+    send_sampling: Option<SendSampling>,
+    list_roots: ListRoots,
+}
+
 async fn start_server_task(
     server_name: String,
     client: Arc<RmcpClient>,
     startup_timeout: Option<Duration>, // TODO: cancel_token should handle this.
     tool_timeout: Duration,
     tool_filter: ToolFilter,
-    tx_event: Sender<Event>,
-    elicitation_requests: ElicitationRequestManager,
+    handlers: ServerHandlers,
 ) -> Result<ManagedClient, StartupOutcomeError> {
+    let ServerHandlers {
+        send_elicitation,
+        send_sampling,
+        list_roots,
+    } = handlers;
     let params = mcp_types::InitializeRequestParams {
         capabilities: ClientCapabilities {
             experimental: None,
-            roots: None,
-            sampling: None,
+            // https://modelcontextprotocol.io/specification/2025-06-18/client/roots#capabilities
+            roots: Some(ClientCapabilitiesRoots {
+                list_changed: Some(true),
+            }),
+            // https://modelcontextprotocol.io/specification/2025-06-18/client/sampling#capabilities
+            sampling: send_sampling.is_some().then(|| json!({})),
             // https://modelcontextprotocol.io/specification/2025-06-18/client/elicitation#capabilities
             // indicates this should be an empty object.
             elicitation: Some(json!({})),
@@ -820,10 +1582,14 @@ async fn start_server_task(
         protocol_version: mcp_types::MCP_SCHEMA_VERSION.to_owned(),
     };
 
-    let send_elicitation = elicitation_requests.make_sender(server_name.clone(), tx_event);
-
     let initialize_result = client
-        .initialize(params, startup_timeout, send_elicitation)
+        .initialize(
+            params,
+            startup_timeout,
+            send_elicitation,
+            send_sampling,
+            Some(list_roots),
+        )
         .await
         .map_err(StartupOutcomeError::from)?;
 
@@ -838,12 +1604,17 @@ async fn start_server_task(
         .and_then(|exp| exp.get(MCP_SANDBOX_STATE_CAPABILITY))
         .is_some();
 
+    let server_supports_prompts = initialize_result.capabilities.prompts.is_some();
+    let server_version = initialize_result.server_info.version;
+
     let managed = ManagedClient {
         client: Arc::clone(&client),
         tools,
         tool_timeout: Some(tool_timeout),
         tool_filter,
         server_supports_sandbox_state_capability,
+        server_supports_prompts,
+        server_version,
     };
 
     Ok(managed)
@@ -853,6 +1624,7 @@ async fn make_rmcp_client(
     server_name: &str,
     transport: McpServerTransportConfig,
     store_mode: OAuthCredentialsStoreMode,
+    proxy: &ProxyConfig,
 ) -> Result<RmcpClient, StartupOutcomeError> {
     match transport {
         McpServerTransportConfig::Stdio {
@@ -886,6 +1658,7 @@ async fn make_rmcp_client(
                 http_headers,
                 env_http_headers,
                 store_mode,
+                proxy_for(proxy, ProxyDestination::Mcp),
             )
             .await
             .map_err(StartupOutcomeError::from)
@@ -1174,6 +1947,7 @@ mod tests {
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
             auth_status: McpAuthStatus::Unsupported,
         };
@@ -1218,6 +1992,7 @@ mod tests {
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                sampling: None,
             },
             auth_status: McpAuthStatus::Unsupported,
         };
@@ -1242,4 +2017,244 @@ mod tests {
             display
         );
     }
+
+    #[test]
+    fn restart_backoff_doubles_up_to_max() {
+        let delays: Vec<Duration> = (1..=8).map(restart_backoff).collect();
+
+        assert_eq!(
+            delays,
+            vec![
+                Duration::from_secs(1),
+                Duration::from_secs(2),
+                Duration::from_secs(4),
+                Duration::from_secs(8),
+                Duration::from_secs(16),
+                Duration::from_secs(32),
+                Duration::from_secs(60),
+                Duration::from_secs(60),
+            ]
+        );
+    }
+
+    fn sampling_request(max_tokens: i64) -> CreateMessageRequestParams {
+        CreateMessageRequestParams {
+            include_context: None,
+            max_tokens,
+            messages: Vec::new(),
+            metadata: None,
+            model_preferences: None,
+            stop_sequences: None,
+            system_prompt: None,
+            temperature: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn sampling_stops_once_token_budget_is_spent() {
+        let sampler: McpSampler = Arc::new(|_| {
+            async {
+                Ok(McpSamplingOutcome {
+                    result: CreateMessageResult {
+                        content: mcp_types::CreateMessageResultContent::TextContent(
+                            mcp_types::TextContent {
+                                annotations: None,
+                                text: "ok".to_string(),
+                                r#type: "text".to_string(),
+                            },
+                        ),
+                        model: "test-model".to_string(),
+                        role: mcp_types::Role::Assistant,
+                        stop_reason: None,
+                    },
+                    tokens_used: 60,
+                })
+            }
+            .boxed()
+        });
+        let manager = SamplingRequestManager::new(sampler, ElicitationRequestManager::default());
+        let config = McpSamplingConfig {
+            approval: McpSamplingApproval::Auto,
+            token_budget: Some(100),
+        };
+        let (tx_event, _rx_event) = async_channel::unbounded();
+
+        let first = manager
+            .sample(
+                "docs".to_string(),
+                &config,
+                &tx_event,
+                RequestId::Integer(1),
+                sampling_request(20),
+            )
+            .await;
+        assert!(first.is_ok());
+        assert_eq!(manager.tokens_used("docs"), 60);
+
+        let second = manager
+            .sample(
+                "docs".to_string(),
+                &config,
+                &tx_event,
+                RequestId::Integer(2),
+                sampling_request(50),
+            )
+            .await;
+        assert!(second.is_err());
+        assert_eq!(manager.tokens_used("docs"), 60);
+    }
+
+    #[tokio::test]
+    async fn in_flight_sampling_reserves_its_budget() {
+        let release = Arc::new(Notify::new());
+        let sampler: McpSampler = {
+            let release = Arc::clone(&release);
+            Arc::new(move |_| {
+                let release = Arc::clone(&release);
+                async move {
+                    release.notified().await;
+                    Err(anyhow!("model unavailable"))
+                }
+                .boxed()
+            })
+        };
+        let manager = SamplingRequestManager::new(sampler, ElicitationRequestManager::default());
+        let config = McpSamplingConfig {
+            approval: McpSamplingApproval::Auto,
+            token_budget: Some(100),
+        };
+        let (tx_event, _rx_event) = async_channel::unbounded();
+
+        let first = tokio::spawn({
+            let (manager, config, tx_event) = (manager.clone(), config.clone(), tx_event.clone());
+            async move {
+                manager
+                    .sample(
+                        "docs".to_string(),
+                        &config,
+                        &tx_event,
+                        RequestId::Integer(1),
+                        sampling_request(60),
+                    )
+                    .await
+            }
+        });
+        while manager.tokens_used("docs") == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let second = manager
+            .sample(
+                "docs".to_string(),
+                &config,
+                &tx_event,
+                RequestId::Integer(2),
+                sampling_request(60),
+            )
+            .await;
+        assert!(second.is_err());
+        assert_eq!(manager.tokens_used("docs"), 60);
+
+        release.notify_one();
+        assert!(first.await.expect("join").is_err());
+        assert_eq!(manager.tokens_used("docs"), 0);
+    }
+
+    #[test]
+    fn server_down_message_reports_when_restarts_are_exhausted() {
+        assert_eq!(
+            server_down_message("docs", true),
+            "MCP server 'docs' is not running; it will be restarted automatically"
+        );
+        assert_eq!(
+            server_down_message("docs", false),
+            format!(
+                "MCP server 'docs' is not running; gave up restarting it after {MAX_RESTART_ATTEMPTS} attempts"
+            )
+        );
+    }
+
+    #[test]
+    fn denied_sampling_is_not_advertised() {
+        let sampler: McpSampler = Arc::new(|_| async { Err(anyhow!("unused")) }.boxed());
+        let manager = SamplingRequestManager::new(sampler, ElicitationRequestManager::default());
+        let (tx_event, _rx_event) = async_channel::unbounded();
+        let config = McpSamplingConfig {
+            approval: McpSamplingApproval::Deny,
+            token_budget: None,
+        };
+
+        assert!(
+            manager
+                .make_sender("docs".to_string(), config, tx_event)
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn cached_servers_are_deferred_until_first_use() {
+        let codex_home = tempfile::TempDir::new().expect("tempdir");
+        let tool_cache = McpToolCache::new(codex_home.path());
+        let transport = McpServerTransportConfig::Stdio {
+            command: "codex-test-missing-mcp-server".to_string(),
+            args: Vec::new(),
+            env: None,
+            env_vars: Vec::new(),
+            cwd: None,
+        };
+        tool_cache
+            .store(
+                &transport,
+                "1.0.0",
+                &[create_test_tool("docs", "search").tool],
+            )
+            .await;
+        let config = McpServerConfig {
+            transport,
+            enabled: true,
+            startup_timeout_sec: None,
+            tool_timeout_sec: None,
+            enabled_tools: None,
+            disabled_tools: None,
+            sampling: None,
+        };
+        let (tx_event, rx_event) = async_channel::unbounded();
+        let mut manager = McpConnectionManager::default();
+
+        manager
+            .initialize(
+                HashMap::from([("docs".to_string(), config)]),
+                OAuthCredentialsStoreMode::File,
+                HashMap::new(),
+                tx_event,
+                CancellationToken::new(),
+                SandboxState {
+                    sandbox_policy: SandboxPolicy::ReadOnly,
+                    codex_linux_sandbox_exe: None,
+                    sandbox_cwd: PathBuf::from("/"),
+                },
+                None,
+                Some(tool_cache),
+                ProxyConfig::default(),
+            )
+            .await;
+
+        let event = rx_event.recv().await.expect("startup complete event");
+        let EventMsg::McpStartupComplete(summary) = event.msg else {
+            panic!("expected McpStartupComplete, got {:?}", event.msg);
+        };
+        assert_eq!(summary.deferred, vec!["docs".to_string()]);
+        assert!(summary.ready.is_empty());
+
+        let tools = manager.list_all_tools().await;
+        assert_eq!(
+            tools.keys().cloned().collect::<Vec<_>>(),
+            vec!["mcp__docs__search".to_string()]
+        );
+
+        // The first real use launches the server, which fails here because
+        // the command does not exist.
+        assert!(manager.call_tool("docs", "search", None).await.is_err());
+        assert!(manager.list_all_tools().await.is_empty());
+    }
 }
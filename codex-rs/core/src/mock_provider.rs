@@ -0,0 +1,257 @@
+//! The built-in `mock` model provider.
+//!
+//! Instead of calling a model, the `mock` provider replays responses scripted
+//! in a JSON fixture, so embedders and hook authors can run deterministic
+//! end-to-end sessions without network access. The fixture named by
+//! `mock_provider.fixture` lists one entry per model request:
+//!
+//! ```json
+//! {
+//!   "responses": [
+//!     [
+//!       {"type": "response.output_item.done", "item": {"type": "function_call", "call_id": "call-1", "name": "shell", "arguments": "{\"command\":[\"ls\"]}"}},
+//!       {"type": "response.completed", "response": {"id": "resp-1"}}
+//!     ],
+//!     [
+//!       {"type": "response.output_text.delta", "delta": "Done", "delay_ms": 50},
+//!       {"type": "response.completed", "response": {"id": "resp-2"}}
+//!     ]
+//!   ]
+//! }
+//! ```
+//!
+//! Each entry is a list of Responses API stream events, parsed exactly like a
+//! live stream. `delay_ms` holds an event back to script stream timing. The
+//! n-th model request of a session replays the n-th entry, including retries,
+//! so an entry without `response.completed` exercises the retry path.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use bytes::Bytes;
+use codex_api::ResponseEvent;
+use codex_api::ResponseStream as ApiResponseStream;
+use codex_api::TransportError;
+use codex_api::error::ApiError;
+use codex_protocol::ThreadId;
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use serde_json::Map;
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use crate::config::Config;
+use crate::error::CodexErr;
+use crate::error::Result;
+
+/// Index of the next scripted response for each session. Model clients are
+/// created per turn, so the position has to outlive them.
+static NEXT_RESPONSE: Lazy<Mutex<HashMap<ThreadId, usize>>> = Lazy::new(Mutex::default);
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MockFixture {
+    responses: Vec<Vec<MockEvent>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MockEvent {
+    #[serde(default)]
+    delay_ms: u64,
+    #[serde(flatten)]
+    event: Map<String, Value>,
+}
+
+/// Replays the next scripted response for `conversation_id`.
+pub(crate) fn stream(
+    config: &Config,
+    conversation_id: ThreadId,
+    idle_timeout: Duration,
+) -> Result<ApiResponseStream> {
+    let mut fixture = load_fixture(config)?;
+    let index = take_next_index(conversation_id);
+    if index >= fixture.responses.len() {
+        return Err(CodexErr::Fatal(format!(
+            "mock provider fixture has {} responses but request {} was made",
+            fixture.responses.len(),
+            index + 1
+        )));
+    }
+    let events = fixture.responses.swap_remove(index);
+    Ok(replay(events, idle_timeout))
+}
+
+fn load_fixture(config: &Config) -> Result<MockFixture> {
+    let Some(path) = &config.mock_provider.fixture else {
+        return Err(CodexErr::Fatal(
+            "model_provider is \"mock\" but mock_provider.fixture is not set".to_string(),
+        ));
+    };
+    let path = config.cwd.join(path);
+    let contents = std::fs::read_to_string(&path).map_err(|err| {
+        CodexErr::Fatal(format!(
+            "failed to read mock provider fixture {}: {err}",
+            path.display()
+        ))
+    })?;
+    serde_json::from_str(&contents).map_err(|err| {
+        CodexErr::Fatal(format!(
+            "invalid mock provider fixture {}: {err}",
+            path.display()
+        ))
+    })
+}
+
+fn take_next_index(conversation_id: ThreadId) -> usize {
+    let mut next = match NEXT_RESPONSE.lock() {
+        Ok(next) => next,
+        Err(err) => err.into_inner(),
+    };
+    let index = next.entry(conversation_id).or_default();
+    let current = *index;
+    *index += 1;
+    current
+}
+
+/// Feeds `events` through the regular SSE parser, sleeping before each one
+/// for its `delay_ms`.
+fn replay(events: Vec<MockEvent>, idle_timeout: Duration) -> ApiResponseStream {
+    let bytes = futures::stream::iter(events)
+        .then(|MockEvent { delay_ms, event }| async move {
+            if delay_ms > 0 {
+                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+            }
+            Ok::<_, TransportError>(Bytes::from(encode_sse(&event)))
+        })
+        .boxed();
+
+    let (tx_event, rx_event) = mpsc::channel::<std::result::Result<ResponseEvent, ApiError>>(1600);
+    let closed = tx_event.clone();
+    tokio::spawn(async move {
+        tokio::select! {
+            () = codex_api::sse::process_sse(bytes, tx_event, idle_timeout, None) => {}
+            () = closed.closed() => {}
+        }
+    });
+    ApiResponseStream { rx_event }
+}
+
+fn encode_sse(event: &Map<String, Value>) -> String {
+    let data = Value::Object(event.clone()).to_string();
+    match event.get("type").and_then(Value::as_str) {
+        Some(kind) => format!("event: {kind}\ndata: {data}\n\n"),
+        None => format!("data: {data}\n\n"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigBuilder;
+    use codex_protocol::models::ContentItem;
+    use codex_protocol::models::ResponseItem;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    async fn config_with_fixture(fixture: Value) -> anyhow::Result<(tempfile::TempDir, Config)> {
+        let codex_home = tempfile::tempdir()?;
+        let fixture_path = codex_home.path().join("fixture.json");
+        std::fs::write(&fixture_path, fixture.to_string())?;
+        let mut config = ConfigBuilder::default()
+            .codex_home(codex_home.path().to_path_buf())
+            .build()
+            .await?;
+        config.mock_provider.fixture = Some(fixture_path);
+        Ok((codex_home, config))
+    }
+
+    async fn collect(
+        stream: ApiResponseStream,
+    ) -> Vec<std::result::Result<ResponseEvent, ApiError>> {
+        stream.collect().await
+    }
+
+    #[tokio::test]
+    async fn replays_scripted_responses_in_order() -> anyhow::Result<()> {
+        let (_home, config) = config_with_fixture(json!({
+            "responses": [
+                [
+                    {
+                        "type": "response.output_item.done",
+                        "item": {
+                            "type": "function_call",
+                            "call_id": "call-1",
+                            "name": "shell",
+                            "arguments": "{\"command\":[\"ls\"]}"
+                        }
+                    },
+                    {"type": "response.completed", "response": {"id": "resp-1"}}
+                ],
+                [
+                    {
+                        "type": "response.output_item.done",
+                        "delay_ms": 20,
+                        "item": {
+                            "type": "message",
+                            "role": "assistant",
+                            "content": [{"type": "output_text", "text": "done"}]
+                        }
+                    },
+                    {"type": "response.completed", "response": {"id": "resp-2"}}
+                ]
+            ]
+        }))
+        .await?;
+        let conversation_id = ThreadId::new();
+        let idle_timeout = Duration::from_secs(5);
+
+        let first = collect(stream(&config, conversation_id, idle_timeout)?).await;
+        let first: Vec<ResponseEvent> = first.into_iter().collect::<std::result::Result<_, _>>()?;
+        assert_eq!(first.len(), 2);
+        assert!(matches!(
+            &first[0],
+            ResponseEvent::OutputItemDone(ResponseItem::FunctionCall { call_id, name, .. })
+                if call_id == "call-1" && name == "shell"
+        ));
+        assert!(matches!(
+            &first[1],
+            ResponseEvent::Completed { response_id, .. } if response_id == "resp-1"
+        ));
+
+        let started = std::time::Instant::now();
+        let second = collect(stream(&config, conversation_id, idle_timeout)?).await;
+        assert!(started.elapsed() >= Duration::from_millis(20));
+        let second: Vec<ResponseEvent> =
+            second.into_iter().collect::<std::result::Result<_, _>>()?;
+        assert!(matches!(
+            &second[0],
+            ResponseEvent::OutputItemDone(ResponseItem::Message { content, .. })
+                if content == &vec![ContentItem::OutputText { text: "done".to_string() }]
+        ));
+
+        let err = stream(&config, conversation_id, idle_timeout).expect_err("script exhausted");
+        assert!(matches!(err, CodexErr::Fatal(_)));
+
+        // Another session starts from the top of the script.
+        assert!(stream(&config, ThreadId::new(), idle_timeout).is_ok());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn response_without_completed_fails_the_stream() -> anyhow::Result<()> {
+        let (_home, config) = config_with_fixture(json!({
+            "responses": [[{"type": "response.output_text.delta", "delta": "partial"}]]
+        }))
+        .await?;
+
+        let events = collect(stream(&config, ThreadId::new(), Duration::from_secs(5))?).await;
+
+        assert!(matches!(
+            events.last(),
+            Some(Err(ApiError::Stream(message))) if message.contains("response.completed")
+        ));
+        Ok(())
+    }
+}
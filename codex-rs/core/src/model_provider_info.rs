@@ -12,6 +12,7 @@ use codex_app_server_protocol::AuthMode;
 use http::HeaderMap;
 use http::header::HeaderName;
 use http::header::HeaderValue;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::HashMap;
@@ -36,7 +37,7 @@ const OPENAI_PROVIDER_NAME: &str = "OpenAI";
 /// *Responses* API. The two protocols use different request/response shapes
 /// and *cannot* be auto-detected at runtime, therefore each provider entry
 /// must declare which one it expects.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum WireApi {
     /// The Responses API exposed by OpenAI at `/v1/responses`.
@@ -47,8 +48,46 @@ pub enum WireApi {
     Chat,
 }
 
+/// How a provider wants tool parameter schemas written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ToolSchemaStyle {
+    /// Schemas as Codex builds them.
+    #[default]
+    Standard,
+    /// OpenAI strict mode: every object lists all of its properties as
+    /// required and allows no others, and optional properties accept `null`.
+    Strict,
+    /// Only `type`, `description`, `properties`, `required`, `items` and
+    /// `enum`, for providers that reject other JSON Schema keywords.
+    Minimal,
+}
+
+/// What a provider accepts in tool definitions. Tools are rewritten to fit
+/// before each request, and the model's calls are mapped back, so the same
+/// tools work with every provider.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ToolFormat {
+    #[serde(default)]
+    pub schema: ToolSchemaStyle,
+    /// Longest tool name the provider accepts. Defaults to 64.
+    pub max_name_length: Option<usize>,
+    /// Whether the provider accepts free-form (grammar) tools. Defaults to
+    /// `true` for the Responses API; otherwise they are sent as functions
+    /// taking a single `input` string.
+    pub freeform_tools: Option<bool>,
+    /// Set to `false` for providers that cannot run several tool calls from
+    /// one response.
+    pub parallel_tool_calls: Option<bool>,
+    /// For models without function calling: describe the tools in the
+    /// instructions and read calls from fenced `tool_call` blocks in the
+    /// model's text instead of sending tool definitions.
+    #[serde(default)]
+    pub text_tool_calls: bool,
+}
+
 /// Serializable representation of a provider definition.
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema)]
 pub struct ModelProviderInfo {
     /// Friendly display name.
     pub name: String,
@@ -93,12 +132,20 @@ pub struct ModelProviderInfo {
     /// the connection as lost.
     pub stream_idle_timeout_ms: Option<u64>,
 
+    /// Caps requests to and responses from this provider at this many bytes
+    /// per second, for metered connections.
+    pub bandwidth_limit: Option<u64>,
+
     /// Does this provider require an OpenAI API Key or ChatGPT login token? If true,
     /// user is presented with login screen on first run, and login preference and token/key
     /// are stored in auth.json. If false (which is the default), login screen is skipped,
     /// and API key (if needed) comes from the "env_key" environment variable.
     #[serde(default)]
     pub requires_openai_auth: bool,
+
+    /// Rewrites tool definitions for providers with different requirements.
+    #[serde(default)]
+    pub tool_format: ToolFormat,
 }
 
 impl ModelProviderInfo {
@@ -161,6 +208,7 @@ impl ModelProviderInfo {
             headers,
             retry,
             stream_idle_timeout: self.stream_idle_timeout(),
+            bandwidth_limit: self.bandwidth_limit,
         })
     }
 
@@ -246,7 +294,9 @@ impl ModelProviderInfo {
             request_max_retries: None,
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
+            bandwidth_limit: None,
             requires_openai_auth: true,
+            tool_format: Default::default(),
         }
     }
 
@@ -261,6 +311,10 @@ pub const DEFAULT_OLLAMA_PORT: u16 = 11434;
 pub const LMSTUDIO_OSS_PROVIDER_ID: &str = "lmstudio";
 pub const OLLAMA_OSS_PROVIDER_ID: &str = "ollama";
 
+/// Built-in provider that replays the scripted responses configured under
+/// `[mock_provider]` instead of calling a model. See [`crate::mock_provider`].
+pub const MOCK_PROVIDER_ID: &str = "mock";
+
 /// Built-in default provider list.
 pub fn built_in_model_providers() -> HashMap<String, ModelProviderInfo> {
     use ModelProviderInfo as P;
@@ -279,6 +333,7 @@ pub fn built_in_model_providers() -> HashMap<String, ModelProviderInfo> {
             LMSTUDIO_OSS_PROVIDER_ID,
             create_oss_provider(DEFAULT_LMSTUDIO_PORT, WireApi::Responses),
         ),
+        (MOCK_PROVIDER_ID, create_mock_provider()),
     ]
     .into_iter()
     .map(|(k, v)| (k.to_string(), v))
@@ -319,7 +374,29 @@ pub fn create_oss_provider_with_base_url(base_url: &str, wire_api: WireApi) -> M
         request_max_retries: None,
         stream_max_retries: None,
         stream_idle_timeout_ms: None,
+        bandwidth_limit: None,
+        requires_openai_auth: false,
+        tool_format: Default::default(),
+    }
+}
+
+fn create_mock_provider() -> ModelProviderInfo {
+    ModelProviderInfo {
+        name: "Mock".into(),
+        base_url: None,
+        env_key: None,
+        env_key_instructions: None,
+        experimental_bearer_token: None,
+        wire_api: WireApi::Responses,
+        query_params: None,
+        http_headers: None,
+        env_http_headers: None,
+        request_max_retries: None,
+        stream_max_retries: None,
+        stream_idle_timeout_ms: None,
+        bandwidth_limit: None,
         requires_openai_auth: false,
+        tool_format: Default::default(),
     }
 }
 
@@ -347,7 +424,9 @@ base_url = "http://localhost:11434/v1"
             request_max_retries: None,
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
+            bandwidth_limit: None,
             requires_openai_auth: false,
+            tool_format: Default::default(),
         };
 
         let provider: ModelProviderInfo = toml::from_str(azure_provider_toml).unwrap();
@@ -377,7 +456,9 @@ query_params = { api-version = "2025-04-01-preview" }
             request_max_retries: None,
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
+            bandwidth_limit: None,
             requires_openai_auth: false,
+            tool_format: Default::default(),
         };
 
         let provider: ModelProviderInfo = toml::from_str(azure_provider_toml).unwrap();
@@ -410,7 +491,9 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             request_max_retries: None,
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
+            bandwidth_limit: None,
             requires_openai_auth: false,
+            tool_format: Default::default(),
         };
 
         let provider: ModelProviderInfo = toml::from_str(azure_provider_toml).unwrap();
@@ -441,7 +524,9 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
                 request_max_retries: None,
                 stream_max_retries: None,
                 stream_idle_timeout_ms: None,
+                bandwidth_limit: None,
                 requires_openai_auth: false,
+                tool_format: Default::default(),
             };
             let api = provider.to_api_provider(None).expect("api provider");
             assert!(
@@ -463,7 +548,9 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             request_max_retries: None,
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
+            bandwidth_limit: None,
             requires_openai_auth: false,
+            tool_format: Default::default(),
         };
         let named_api = named_provider.to_api_provider(None).expect("api provider");
         assert!(named_api.is_azure_responses_endpoint());
@@ -487,7 +574,9 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
                 request_max_retries: None,
                 stream_max_retries: None,
                 stream_idle_timeout_ms: None,
+                bandwidth_limit: None,
                 requires_openai_auth: false,
+                tool_format: Default::default(),
             };
             let api = provider.to_api_provider(None).expect("api provider");
             assert!(
@@ -21,7 +21,9 @@ use crate::api_bridge::auth_provider_from_auth;
 use crate::api_bridge::map_api_error;
 use crate::auth::AuthManager;
 use crate::config::Config;
-use crate::default_client::build_reqwest_client;
+use crate::config::types::ProxyConfig;
+use crate::default_client::ProxyDestination;
+use crate::default_client::build_reqwest_client_for;
 use crate::error::CodexErr;
 use crate::error::Result as CoreResult;
 use crate::features::Feature;
@@ -82,7 +84,8 @@ impl ModelsManager {
 
     /// Fetch the latest remote models, using the on-disk cache when still fresh.
     pub async fn refresh_available_models_with_cache(&self, config: &Config) -> CoreResult<()> {
-        if !config.features.enabled(Feature::RemoteModels)
+        if config.offline
+            || !config.features.enabled(Feature::RemoteModels)
             || self.auth_manager.get_auth_mode() == Some(AuthMode::ApiKey)
         {
             return Ok(());
@@ -90,13 +93,17 @@ impl ModelsManager {
         if self.try_load_cache().await {
             return Ok(());
         }
-        self.refresh_available_models_no_cache(config.features.enabled(Feature::RemoteModels))
-            .await
+        self.refresh_available_models_no_cache(
+            config.features.enabled(Feature::RemoteModels),
+            &config.proxy,
+        )
+        .await
     }
 
     pub(crate) async fn refresh_available_models_no_cache(
         &self,
         remote_models_feature: bool,
+        proxy: &ProxyConfig,
     ) -> CoreResult<()> {
         if !remote_models_feature || self.auth_manager.get_auth_mode() == Some(AuthMode::ApiKey) {
             return Ok(());
@@ -104,7 +111,10 @@ impl ModelsManager {
         let auth = self.auth_manager.auth().await;
         let api_provider = self.provider.to_api_provider(Some(AuthMode::ChatGPT))?;
         let api_auth = auth_provider_from_auth(auth.clone(), &self.provider)?;
-        let transport = ReqwestTransport::new(build_reqwest_client());
+        let transport = ReqwestTransport::new(build_reqwest_client_for(
+            proxy,
+            ProxyDestination::ModelProvider,
+        ));
         let client = ModelsClient::new(transport, api_provider, api_auth);
 
         let client_version = format_client_version_to_whole();
@@ -172,13 +182,18 @@ impl ModelsManager {
         }
         OPENAI_DEFAULT_API_MODEL.to_string()
     }
-    pub async fn refresh_if_new_etag(&self, etag: String, remote_models_feature: bool) {
+    pub async fn refresh_if_new_etag(
+        &self,
+        etag: String,
+        remote_models_feature: bool,
+        proxy: &ProxyConfig,
+    ) {
         let current_etag = self.get_etag().await;
         if current_etag.clone().is_some() && current_etag.as_deref() == Some(etag.as_str()) {
             return;
         }
         if let Err(err) = self
-            .refresh_available_models_no_cache(remote_models_feature)
+            .refresh_available_models_no_cache(remote_models_feature, proxy)
             .await
         {
             error!("failed to refresh available models: {err}");
@@ -401,7 +416,9 @@ mod tests {
             request_max_retries: Some(0),
             stream_max_retries: Some(0),
             stream_idle_timeout_ms: Some(5_000),
+            bandwidth_limit: None,
             requires_openai_auth: false,
+            tool_format: Default::default(),
         }
     }
 
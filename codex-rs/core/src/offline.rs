@@ -0,0 +1,149 @@
+//! Support for `offline = true`, which keeps Codex off the network.
+//!
+//! Features that always need the network (web search, update checks,
+//! analytics, feedback uploads) are turned off when the config loads, and
+//! features that may point anywhere (model providers, MCP servers,
+//! OpenTelemetry exporters) keep working only while they stay on this
+//! machine or the local network.
+
+use url::Host;
+use url::Url;
+
+use crate::config::Config;
+use crate::config::types::OtelExporterKind;
+use crate::error::CodexErr;
+use crate::error::Result;
+use crate::model_provider_info::MOCK_PROVIDER_ID;
+use crate::model_provider_info::ModelProviderInfo;
+
+/// Whether `url` points at this machine or a private network address.
+pub fn is_local_url(url: &str) -> bool {
+    let Ok(url) = Url::parse(url) else {
+        return false;
+    };
+    match url.host() {
+        Some(Host::Domain(domain)) => domain == "localhost" || domain.ends_with(".localhost"),
+        Some(Host::Ipv4(ip)) => {
+            ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified()
+        }
+        Some(Host::Ipv6(ip)) => {
+            ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_unique_local()
+                || ip.is_unicast_link_local()
+        }
+        None => false,
+    }
+}
+
+/// Refuses model requests to a provider that is not local while offline.
+pub(crate) fn ensure_provider_allowed(config: &Config, provider: &ModelProviderInfo) -> Result<()> {
+    if !config.offline || config.model_provider_id == MOCK_PROVIDER_ID {
+        return Ok(());
+    }
+    match provider.base_url.as_deref() {
+        Some(base_url) if is_local_url(base_url) => Ok(()),
+        base_url => Err(CodexErr::Offline(format!(
+            "model provider `{}` ({}) is not on this machine or the local network; \
+             switch to a local provider such as `ollama` or set `offline = false`",
+            provider.name,
+            base_url.unwrap_or("the OpenAI API"),
+        ))),
+    }
+}
+
+/// The exporter to use while offline: exporters that send data off the local
+/// network become [`OtelExporterKind::None`], with a warning when the user
+/// configured them explicitly.
+pub(crate) fn offline_exporter(
+    exporter: OtelExporterKind,
+    setting: &str,
+    explicit: bool,
+    warnings: &mut Vec<String>,
+) -> OtelExporterKind {
+    let remote = match &exporter {
+        OtelExporterKind::None => return exporter,
+        OtelExporterKind::Statsig => None,
+        OtelExporterKind::OtlpHttp { endpoint, .. }
+        | OtelExporterKind::OtlpGrpc { endpoint, .. } => {
+            if is_local_url(endpoint) {
+                return exporter;
+            }
+            Some(endpoint.as_str())
+        }
+    };
+    if explicit {
+        let target = remote.unwrap_or("Statsig");
+        warnings.push(format!(
+            "`otel.{setting}` is turned off because `offline = true`: {target} is not on this machine or the local network."
+        ));
+    }
+    OtelExporterKind::None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn local_urls_are_loopback_or_private() {
+        for url in [
+            "http://localhost:11434/v1",
+            "http://127.0.0.1:1234/v1",
+            "http://[::1]:8080",
+            "http://192.168.1.20:8000/v1",
+            "http://10.0.0.5",
+            "http://[fd00::1]:9000",
+        ] {
+            assert!(is_local_url(url), "{url}");
+        }
+        for url in [
+            "https://api.openai.com/v1",
+            "http://8.8.8.8",
+            "http://localhost.example.com",
+            "not a url",
+        ] {
+            assert!(!is_local_url(url), "{url}");
+        }
+    }
+
+    #[test]
+    fn remote_exporters_are_dropped_with_a_warning() {
+        let mut warnings = Vec::new();
+        let local = OtelExporterKind::OtlpGrpc {
+            endpoint: "http://localhost:4317".to_string(),
+            headers: Default::default(),
+            tls: None,
+        };
+        assert_eq!(
+            offline_exporter(local.clone(), "exporter", true, &mut warnings),
+            local
+        );
+        let remote = OtelExporterKind::OtlpGrpc {
+            endpoint: "https://otel.example.com:4317".to_string(),
+            headers: Default::default(),
+            tls: None,
+        };
+        assert_eq!(
+            offline_exporter(remote, "trace_exporter", true, &mut warnings),
+            OtelExporterKind::None
+        );
+        assert_eq!(
+            offline_exporter(
+                OtelExporterKind::Statsig,
+                "metrics_exporter",
+                false,
+                &mut warnings
+            ),
+            OtelExporterKind::None
+        );
+        assert_eq!(
+            warnings,
+            vec![
+                "`otel.trace_exporter` is turned off because `offline = true`: https://otel.example.com:4317 is not on this machine or the local network."
+                    .to_string()
+            ]
+        );
+    }
+}
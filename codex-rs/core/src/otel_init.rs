@@ -89,6 +89,18 @@ pub fn build_provider(
     })
 }
 
+/// Start serving Prometheus metrics when `otel.prometheus_listen_addr` is set.
+///
+/// Intended for long-running server modes; a no-op otherwise.
+pub fn start_prometheus_endpoint(config: &Config) -> std::io::Result<()> {
+    let Some(addr) = config.otel.prometheus_listen_addr else {
+        return Ok(());
+    };
+    codex_otel::prometheus::serve(addr)?;
+    tracing::info!("serving Prometheus metrics on http://{addr}/metrics");
+    Ok(())
+}
+
 /// Filter predicate for exporting only Codex-owned events via OTEL.
 /// Keeps events that originated from codex_otel module
 pub fn codex_export_filter(meta: &tracing::Metadata<'_>) -> bool {
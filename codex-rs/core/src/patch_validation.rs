@@ -0,0 +1,459 @@
+//! Opt-in `[patch_validation]`: before a patch is applied, parse the files
+//! it would write and report syntax errors to the model, so a broken edit is
+//! caught at once rather than by the next build.
+//!
+//! Shell, Rust, Python, JavaScript and Go files are parsed with tree-sitter
+//! grammars; JSON, TOML and YAML with their serde parsers. Files known to be
+//! JSON with comments (`devcontainer.json`, `tsconfig.json`, `.vscode`
+//! settings, `*.jsonc`) may also have comments and trailing commas. Files in
+//! other languages are not checked. Only errors the patch introduces count: a
+//! file that did not parse before the patch is left alone.
+
+use std::path::Path;
+
+use codex_apply_patch::ApplyPatchAction;
+use codex_apply_patch::ApplyPatchFileChange;
+use serde::Deserialize;
+use serde::Serialize;
+use tree_sitter::Node;
+use tree_sitter::Parser;
+
+use crate::bash::try_parse_shell;
+
+/// Errors reported per file, so one badly broken file does not flood the
+/// feedback.
+const MAX_ERRORS_PER_FILE: usize = 3;
+const MAX_SNIPPET_CHARS: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Language {
+    Shell,
+    Rust,
+    Python,
+    JavaScript,
+    Go,
+    Json,
+    /// JSON with comments and trailing commas.
+    Jsonc,
+    Toml,
+    Yaml,
+}
+
+/// A syntax error in the content a patch would write.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct SyntaxError {
+    /// Relative to the patch's working directory when inside it.
+    pub(crate) path: String,
+    pub(crate) language: Language,
+    /// 1-based.
+    pub(crate) line: usize,
+    /// 1-based.
+    pub(crate) column: usize,
+    pub(crate) message: String,
+}
+
+/// Syntax errors the patch would introduce in the files it writes.
+pub(crate) fn validate_patch(action: &ApplyPatchAction) -> Vec<SyntaxError> {
+    let mut errors = Vec::new();
+    for (path, change) in action.changes() {
+        let (dest, content) = match change {
+            ApplyPatchFileChange::Add { content } => (path, content),
+            ApplyPatchFileChange::Update {
+                move_path,
+                new_content,
+                ..
+            } => (move_path.as_ref().unwrap_or(path), new_content),
+            ApplyPatchFileChange::Delete { .. } => continue,
+        };
+        let Some(language) = detect_language(dest, content) else {
+            continue;
+        };
+        let found = syntax_errors(language, content);
+        if found.is_empty() {
+            continue;
+        }
+        // The file was already broken; the patch is not to blame.
+        if let ApplyPatchFileChange::Update { .. } = change
+            && let Ok(original) = std::fs::read_to_string(path)
+            && !syntax_errors(language, &original).is_empty()
+        {
+            continue;
+        }
+        let display = dest
+            .strip_prefix(&action.cwd)
+            .unwrap_or(dest)
+            .display()
+            .to_string();
+        errors.extend(
+            found
+                .into_iter()
+                .map(|(line, column, message)| SyntaxError {
+                    path: display.clone(),
+                    language,
+                    line,
+                    column,
+                    message,
+                }),
+        );
+    }
+    errors.sort_by(|a, b| (&a.path, a.line, a.column).cmp(&(&b.path, b.line, b.column)));
+    errors
+}
+
+/// `errors` as JSON, for the model.
+pub(crate) fn syntax_errors_feedback(errors: &[SyntaxError]) -> String {
+    serde_json::to_string_pretty(errors).unwrap_or_default()
+}
+
+fn detect_language(path: &Path, content: &str) -> Option<Language> {
+    let file_name = path.file_name()?.to_string_lossy();
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase());
+    match extension.as_deref() {
+        Some("sh" | "bash") => Some(Language::Shell),
+        Some("rs") => Some(Language::Rust),
+        Some("py" | "pyi") => Some(Language::Python),
+        Some("js" | "mjs" | "cjs" | "jsx") => Some(Language::JavaScript),
+        Some("go") => Some(Language::Go),
+        Some("jsonc") => Some(Language::Jsonc),
+        Some("json")
+            if file_name.starts_with("tsconfig")
+                || file_name.starts_with("jsconfig")
+                || matches!(
+                    file_name.as_ref(),
+                    "devcontainer.json" | ".devcontainer.json" | ".eslintrc.json"
+                )
+                || path.components().any(|part| part.as_os_str() == ".vscode") =>
+        {
+            Some(Language::Jsonc)
+        }
+        Some("json") => Some(Language::Json),
+        Some("toml") => Some(Language::Toml),
+        Some("yaml" | "yml") => Some(Language::Yaml),
+        Some(_) => None,
+        None => {
+            let shebang = content.lines().next()?.strip_prefix("#!")?;
+            let interpreter = shebang.split_whitespace().last()?;
+            match interpreter.rsplit('/').next()? {
+                "sh" | "bash" => Some(Language::Shell),
+                "python" | "python3" => Some(Language::Python),
+                "node" => Some(Language::JavaScript),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// `(line, column, message)` of each error, at most [`MAX_ERRORS_PER_FILE`].
+fn syntax_errors(language: Language, content: &str) -> Vec<(usize, usize, String)> {
+    match language {
+        Language::Shell => shell_errors(content),
+        Language::Rust => tree_sitter_errors(tree_sitter_rust::LANGUAGE.into(), content),
+        Language::Python => tree_sitter_errors(tree_sitter_python::LANGUAGE.into(), content),
+        Language::JavaScript => {
+            tree_sitter_errors(tree_sitter_javascript::LANGUAGE.into(), content)
+        }
+        Language::Go => tree_sitter_errors(tree_sitter_go::LANGUAGE.into(), content),
+        Language::Json => json_errors(content),
+        Language::Jsonc => json_errors(&strip_jsonc_extensions(content)),
+        Language::Toml => match toml::from_str::<toml::Table>(content) {
+            Ok(_) => Vec::new(),
+            Err(err) => {
+                let offset = err.span().map_or(0, |span| span.start);
+                let (line, column) = line_and_column(content, offset);
+                vec![(line, column, err.message().trim().to_string())]
+            }
+        },
+        Language::Yaml => {
+            for document in serde_yaml::Deserializer::from_str(content) {
+                if let Err(err) = serde_yaml::Value::deserialize(document) {
+                    let (line, column) = err
+                        .location()
+                        .map_or((1, 1), |location| (location.line(), location.column()));
+                    return vec![(line, column, strip_location(&err.to_string(), line, column))];
+                }
+            }
+            Vec::new()
+        }
+    }
+}
+
+fn shell_errors(content: &str) -> Vec<(usize, usize, String)> {
+    let Some(tree) = try_parse_shell(content) else {
+        return Vec::new();
+    };
+    let mut errors = Vec::new();
+    collect_error_nodes(tree.root_node(), content, &mut errors);
+    errors
+}
+
+fn tree_sitter_errors(
+    language: tree_sitter::Language,
+    content: &str,
+) -> Vec<(usize, usize, String)> {
+    let mut parser = Parser::new();
+    if parser.set_language(&language).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(content, None) else {
+        return Vec::new();
+    };
+    let mut errors = Vec::new();
+    collect_error_nodes(tree.root_node(), content, &mut errors);
+    errors
+}
+
+fn json_errors(content: &str) -> Vec<(usize, usize, String)> {
+    match serde_json::from_str::<serde_json::Value>(content) {
+        Ok(_) => Vec::new(),
+        Err(err) => vec![(
+            err.line(),
+            err.column(),
+            strip_location(&err.to_string(), err.line(), err.column()),
+        )],
+    }
+}
+
+/// `content` with comments and trailing commas blanked out, so that JSON with
+/// comments parses as JSON. Lines and columns stay where they were.
+fn strip_jsonc_extensions(content: &str) -> String {
+    let mut chars: Vec<char> = content.chars().collect();
+    // Comments first, so that a comma followed by a comment and then a
+    // closing bracket is seen as trailing.
+    let mut in_string = false;
+    let mut index = 0;
+    while index < chars.len() {
+        match (in_string, chars[index], chars.get(index + 1).copied()) {
+            (true, '\\', _) => index += 1,
+            (_, '"', _) => in_string = !in_string,
+            (false, '/', Some('/')) => {
+                while index < chars.len() && chars[index] != '\n' {
+                    chars[index] = ' ';
+                    index += 1;
+                }
+                continue;
+            }
+            (false, '/', Some('*')) => {
+                let end = (index + 2..chars.len().saturating_sub(1))
+                    .find(|&end| chars[end] == '*' && chars[end + 1] == '/')
+                    .map_or(chars.len(), |end| end + 2);
+                for ch in &mut chars[index..end] {
+                    if *ch != '\n' {
+                        *ch = ' ';
+                    }
+                }
+                index = end;
+                continue;
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+
+    let mut in_string = false;
+    let mut index = 0;
+    while index < chars.len() {
+        match (in_string, chars[index]) {
+            (true, '\\') => index += 1,
+            (_, '"') => in_string = !in_string,
+            (false, ',') => {
+                let next = chars[index + 1..]
+                    .iter()
+                    .copied()
+                    .find(|ch| !ch.is_whitespace());
+                if matches!(next, Some('}' | ']')) {
+                    chars[index] = ' ';
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    chars.into_iter().collect()
+}
+
+fn collect_error_nodes(node: Node, content: &str, errors: &mut Vec<(usize, usize, String)>) {
+    if errors.len() >= MAX_ERRORS_PER_FILE || !node.has_error() {
+        return;
+    }
+    if node.is_error() || node.is_missing() {
+        let start = node.start_position();
+        let message = if node.is_missing() {
+            format!("missing `{}`", node.kind())
+        } else {
+            let snippet: String = content
+                .get(node.byte_range())
+                .unwrap_or_default()
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .chars()
+                .take(MAX_SNIPPET_CHARS)
+                .collect();
+            format!("unexpected `{}`", snippet.trim())
+        };
+        errors.push((start.row + 1, start.column + 1, message));
+        return;
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_error_nodes(child, content, errors);
+    }
+}
+
+fn line_and_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = content.get(..offset).unwrap_or(content);
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .count()
+        + 1;
+    (line, column)
+}
+
+/// Drops the "at line L column C" serde appends; the position is reported
+/// separately.
+fn strip_location(message: &str, line: usize, column: usize) -> String {
+    message
+        .trim_end_matches(&format!(" at line {line} column {column}"))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    fn add_files(cwd: &Path, files: &[(&str, &str)]) -> ApplyPatchAction {
+        let mut patch = String::from("*** Begin Patch\n");
+        for (name, content) in files {
+            patch.push_str(&format!("*** Add File: {name}\n"));
+            for line in content.lines() {
+                patch.push_str(&format!("+{line}\n"));
+            }
+        }
+        patch.push_str("*** End Patch");
+        let argv = vec!["apply_patch".to_string(), patch];
+        match codex_apply_patch::maybe_parse_apply_patch_verified(&argv, cwd) {
+            codex_apply_patch::MaybeApplyPatchVerified::Body(action) => action,
+            other => panic!("expected a patch, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn reports_errors_in_recognized_languages() {
+        let dir = tempdir().expect("tempdir");
+        let action = add_files(
+            dir.path(),
+            &[
+                ("ok.sh", "if true; then\n  echo hi\nfi\n"),
+                ("broken.sh", "if true; then\n  echo hi\n"),
+                ("config.json", "{\"a\": 1,}\n"),
+                ("Cargo.toml", "[package]\nname = \"x\"\nname = \"y\"\n"),
+                ("ci.yml", "jobs:\n  - a\n  b: c\n"),
+                ("main.rs", "fn main( {\n"),
+                ("lib.rs", "pub fn ok() -> u8 {\n    1\n}\n"),
+                ("app.py", "def run(:\n    pass\n"),
+                ("index.js", "const a = {;\n"),
+                ("main.go", "package main\n\nfunc main() {\n"),
+                ("tsconfig.json", "{ // comments are fine here\n}\n"),
+                ("README.md", "# not checked {\n"),
+            ],
+        );
+        let errors = validate_patch(&action);
+        let mut files: Vec<(&str, Language)> = errors
+            .iter()
+            .map(|error| (error.path.as_str(), error.language))
+            .collect();
+        files.dedup();
+        assert_eq!(
+            files,
+            vec![
+                ("Cargo.toml", Language::Toml),
+                ("app.py", Language::Python),
+                ("broken.sh", Language::Shell),
+                ("ci.yml", Language::Yaml),
+                ("config.json", Language::Json),
+                ("index.js", Language::JavaScript),
+                ("main.go", Language::Go),
+                ("main.rs", Language::Rust),
+            ]
+        );
+        let json = errors
+            .iter()
+            .find(|error| error.language == Language::Json)
+            .expect("json error");
+        assert_eq!((json.line, json.column), (1, 9));
+        assert!(!json.message.contains(" at line "), "{}", json.message);
+    }
+
+    #[test]
+    fn known_jsonc_files_allow_comments_and_trailing_commas() {
+        let devcontainer = "{\n  // the image\n  \"image\": \"rust:1\", /* pinned */\n  \"features\": {\"a\": \"//not a comment\",},\n}\n";
+        assert_eq!(
+            detect_language(Path::new(".devcontainer/devcontainer.json"), devcontainer),
+            Some(Language::Jsonc)
+        );
+        assert_eq!(syntax_errors(Language::Jsonc, devcontainer), Vec::new());
+        assert_eq!(
+            syntax_errors(Language::Jsonc, "{\n  // a\n  \"a\": 1 \"b\": 2\n}\n")
+                .into_iter()
+                .map(|(line, _, _)| line)
+                .collect::<Vec<_>>(),
+            vec![3]
+        );
+        assert_eq!(
+            syntax_errors(Language::Json, "{\"a\": 1,}\n").len(),
+            1,
+            "plain JSON stays strict"
+        );
+    }
+
+    #[test]
+    fn files_that_were_already_broken_are_not_blamed_on_the_patch() {
+        let dir = tempdir().expect("tempdir");
+        let script = dir.path().join("run");
+        let update = |cwd: &Path, hunk: &str| {
+            let patch = format!("*** Begin Patch\n*** Update File: run\n@@\n{hunk}*** End Patch");
+            let argv = vec!["apply_patch".to_string(), patch];
+            match codex_apply_patch::maybe_parse_apply_patch_verified(&argv, cwd) {
+                codex_apply_patch::MaybeApplyPatchVerified::Body(action) => action,
+                other => panic!("expected a patch, got {other:?}"),
+            }
+        };
+
+        std::fs::write(&script, "#!/bin/bash\nif true; then\necho old\n").expect("write");
+        let action = update(dir.path(), "-echo old\n+echo new\n");
+        assert_eq!(validate_patch(&action), Vec::new());
+
+        std::fs::write(&script, "#!/bin/bash\nif true; then\necho old\nfi\n").expect("write");
+        let action = update(dir.path(), " echo old\n-fi\n");
+        let errors = validate_patch(&action);
+        assert!(!errors.is_empty());
+        assert!(errors.iter().all(|error| error.path == "run"));
+    }
+
+    #[test]
+    fn shebang_marks_extensionless_shell_scripts() {
+        assert_eq!(
+            detect_language(&PathBuf::from("bin/run"), "#!/usr/bin/env bash\n"),
+            Some(Language::Shell)
+        );
+        assert_eq!(
+            detect_language(&PathBuf::from("bin/run"), "#!/usr/bin/env python3\n"),
+            Some(Language::Python)
+        );
+        assert_eq!(
+            detect_language(&PathBuf::from("bin/run"), "#!/usr/bin/env ruby\n"),
+            None
+        );
+        assert_eq!(line_and_column("a\nbc\nd", 4), (2, 3));
+    }
+}
@@ -0,0 +1,253 @@
+//! Speculative reads for `read_file` calls that are still streaming.
+//!
+//! With the `prefetch_reads` feature, the arguments of a `read_file` call are
+//! watched while the model streams them. As soon as the `file_path` value is
+//! complete the file is read in the background, so by the time the call
+//! finishes streaming its contents are usually in memory. A prefetched read
+//! is only used if the file has not changed since; otherwise, or if the read
+//! has not finished, the handler reads the file as usual.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use codex_file_search::is_codex_ignored;
+use codex_protocol::models::ResponseItem;
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+use crate::file_watch::FileStamp;
+
+/// Files larger than this are left to the handler.
+const MAX_PREFETCH_BYTES: u64 = 1024 * 1024;
+
+/// Upper bound on reads in flight or waiting to be claimed.
+const MAX_PENDING_PREFETCHES: usize = 16;
+
+const READ_FILE_TOOL: &str = "read_file";
+
+struct PrefetchedFile {
+    stamp: FileStamp,
+    contents: Vec<u8>,
+}
+
+/// Background reads of files the model is about to ask for. The default
+/// prefetches nothing.
+#[derive(Default)]
+pub(crate) struct Prefetcher {
+    enabled: bool,
+    pending: Mutex<HashMap<PathBuf, JoinHandle<Option<PrefetchedFile>>>>,
+}
+
+impl Prefetcher {
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Starts reading `path` in the background unless it is already being
+    /// read. `cwd` is the turn's, which `.codexignore` rules are resolved
+    /// against.
+    pub(crate) fn start(&self, cwd: &Path, path: PathBuf) {
+        if !self.enabled || !path.is_absolute() {
+            return;
+        }
+        let mut pending = self.lock();
+        if pending.len() >= MAX_PENDING_PREFETCHES || pending.contains_key(&path) {
+            return;
+        }
+        let handle = tokio::spawn(read_file(cwd.to_path_buf(), path.clone()));
+        pending.insert(path, handle);
+    }
+
+    /// Returns the prefetched contents of `path`, waiting for the read if it
+    /// is still running. `None` if the file was not prefetched or changed
+    /// after it was read.
+    pub(crate) async fn take(&self, path: &Path) -> Option<Vec<u8>> {
+        let handle = self.lock().remove(path)?;
+        let file = handle.await.ok()??;
+        if FileStamp::of(path) != Some(file.stamp) {
+            debug!("discarding stale prefetch of {}", path.display());
+            return None;
+        }
+        Some(file.contents)
+    }
+
+    /// Drops every unclaimed read. Called when a turn ends.
+    pub(crate) fn clear(&self) {
+        for (_, handle) in self.lock().drain() {
+            handle.abort();
+        }
+    }
+
+    fn lock(
+        &self,
+    ) -> std::sync::MutexGuard<'_, HashMap<PathBuf, JoinHandle<Option<PrefetchedFile>>>> {
+        match self.pending.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        }
+    }
+}
+
+async fn read_file(cwd: PathBuf, path: PathBuf) -> Option<PrefetchedFile> {
+    let metadata = tokio::fs::metadata(&path).await.ok()?;
+    // The handler refuses files hidden by a `.codexignore`; don't read them.
+    if !metadata.is_file()
+        || metadata.len() > MAX_PREFETCH_BYTES
+        || is_codex_ignored(&cwd, &path, false)
+    {
+        return None;
+    }
+    let stamp = FileStamp::of(&path)?;
+    let contents = tokio::fs::read(&path).await.ok()?;
+    // A write that raced with the read changes the stamp, so `take` rejects
+    // the torn contents.
+    Some(PrefetchedFile { stamp, contents })
+}
+
+/// Follows the `read_file` calls of one model response while their arguments
+/// stream in.
+#[derive(Default)]
+pub(crate) struct StreamedReads {
+    /// Arguments received so far, by item id, for calls whose path is not
+    /// complete yet.
+    calls: HashMap<String, String>,
+}
+
+impl StreamedReads {
+    pub(crate) fn on_item_added(&mut self, item: &ResponseItem) {
+        if let ResponseItem::FunctionCall {
+            id: Some(id), name, ..
+        } = item
+            && name == READ_FILE_TOOL
+        {
+            self.calls.insert(id.clone(), String::new());
+        }
+    }
+
+    /// Appends `delta` to the arguments of `item_id` and returns the file
+    /// path once it has been streamed in full.
+    pub(crate) fn on_input_delta(&mut self, item_id: &str, delta: &str) -> Option<PathBuf> {
+        let arguments = self.calls.get_mut(item_id)?;
+        arguments.push_str(delta);
+        let path = streamed_string_field(arguments, "file_path")?;
+        self.calls.remove(item_id);
+        Some(PathBuf::from(path))
+    }
+}
+
+/// Extracts the string value of `key` from a JSON object that may still be
+/// incomplete. Returns `None` until the value's closing quote has arrived.
+fn streamed_string_field(partial: &str, key: &str) -> Option<String> {
+    let needle = format!("\"{key}\"");
+    let after_key = &partial[partial.find(&needle)? + needle.len()..];
+    let value = after_key.trim_start().strip_prefix(':')?.trim_start();
+    if !value.starts_with('"') {
+        return None;
+    }
+    let mut escaped = false;
+    for (idx, ch) in value.char_indices().skip(1) {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return serde_json::from_str(&value[..=idx]).ok(),
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn read_file_call(id: &str) -> ResponseItem {
+        ResponseItem::FunctionCall {
+            id: Some(id.to_string()),
+            name: READ_FILE_TOOL.to_string(),
+            arguments: String::new(),
+            call_id: "call-1".to_string(),
+        }
+    }
+
+    #[test]
+    fn path_is_reported_once_its_value_is_complete() {
+        let mut reads = StreamedReads::default();
+        reads.on_item_added(&read_file_call("fc_1"));
+
+        assert_eq!(
+            reads.on_input_delta("fc_1", "{\"file_path\": \"/repo/sr"),
+            None
+        );
+        assert_eq!(
+            reads.on_input_delta("fc_1", "c/a \\\"b\\\".rs\", \"offset\""),
+            Some(PathBuf::from("/repo/src/a \"b\".rs"))
+        );
+        assert_eq!(reads.on_input_delta("fc_1", ": 1}"), None);
+        assert_eq!(
+            reads.on_input_delta("fc_2", "{\"file_path\": \"/x\"}"),
+            None
+        );
+    }
+
+    #[test]
+    fn other_tools_are_ignored() {
+        let mut reads = StreamedReads::default();
+        reads.on_item_added(&ResponseItem::FunctionCall {
+            id: Some("fc_1".to_string()),
+            name: "shell".to_string(),
+            arguments: String::new(),
+            call_id: "call-1".to_string(),
+        });
+
+        assert_eq!(
+            reads.on_input_delta("fc_1", "{\"file_path\": \"/x\"}"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn prefetched_contents_are_dropped_when_the_file_changes() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let fresh = dir.path().join("fresh.txt");
+        let stale = dir.path().join("stale.txt");
+        std::fs::write(&fresh, "fresh")?;
+        std::fs::write(&stale, "stale")?;
+        let prefetcher = Prefetcher::new(true);
+        prefetcher.start(dir.path(), fresh.clone());
+        prefetcher.start(dir.path(), stale.clone());
+        // Let the stale read finish before the file changes.
+        let handle = prefetcher.lock().remove(&stale).expect("pending");
+        let contents = handle.await?;
+        prefetcher
+            .lock()
+            .insert(stale.clone(), tokio::spawn(async { contents }));
+        std::fs::write(&stale, "changed contents")?;
+
+        assert_eq!(prefetcher.take(&fresh).await, Some(b"fresh".to_vec()));
+        assert_eq!(prefetcher.take(&fresh).await, None);
+        assert_eq!(prefetcher.take(&stale).await, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn disabled_prefetcher_reads_nothing() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("file.txt");
+        std::fs::write(&path, "contents")?;
+        let prefetcher = Prefetcher::default();
+        prefetcher.start(dir.path(), path.clone());
+
+        assert_eq!(prefetcher.take(&path).await, None);
+        Ok(())
+    }
+}
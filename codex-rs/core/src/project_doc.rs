@@ -10,13 +10,19 @@
 //!     root is found, only the current working directory is considered.
 //! 2.  Collect every `AGENTS.md` found from the repository root down to the
 //!     current working directory (inclusive) and concatenate their contents in
-//!     that order.
+//!     that order. When several files are found, a closing note tells the model
+//!     that the file closest to the working directory wins on conflicts.
 //! 3.  We do **not** walk past the Git root.
+//!
+//! During a session the searched directories are watched (see
+//! [`crate::project_doc_watcher`]) so edits to these files reach the model on
+//! the next turn.
 
 use crate::config::Config;
 use crate::features::Feature;
 use crate::skills::SkillMetadata;
 use crate::skills::render_skills_section;
+use codex_file_search::is_codex_ignored;
 use dunce::canonicalize as normalize_path;
 use std::path::PathBuf;
 use tokio::io::AsyncReadExt;
@@ -34,6 +40,10 @@ pub const LOCAL_PROJECT_DOC_FILENAME: &str = "AGENTS.override.md";
 /// be concatenated with the following separator.
 const PROJECT_DOC_SEPARATOR: &str = "\n\n--- project-doc ---\n\n";
 
+/// Appended after the docs when more than one was found so the model resolves
+/// conflicts in favour of the doc closest to the working directory.
+const PROJECT_DOC_PRECEDENCE_NOTE: &str = "When the instructions above conflict, follow the later ones: they come from files closer to the current working directory.";
+
 /// Combines `Config::instructions` and `AGENTS.md` (if present) into a single
 /// string of instructions.
 pub(crate) async fn get_user_instructions(
@@ -136,10 +146,12 @@ pub async fn read_project_docs(config: &Config) -> std::io::Result<Option<String
     }
 
     if parts.is_empty() {
-        Ok(None)
-    } else {
-        Ok(Some(parts.join("\n\n")))
+        return Ok(None);
     }
+    if parts.len() > 1 {
+        parts.push(PROJECT_DOC_PRECEDENCE_NOTE.to_string());
+    }
+    Ok(Some(parts.join("\n\n")))
 }
 
 /// Discover the list of AGENTS.md files using the same search rules as
@@ -148,6 +160,40 @@ pub async fn read_project_docs(config: &Config) -> std::io::Result<Option<String
 /// directory (inclusive). Symlinks are allowed. When `project_doc_max_bytes`
 /// is zero, returns an empty list.
 pub fn discover_project_doc_paths(config: &Config) -> std::io::Result<Vec<PathBuf>> {
+    let mut found: Vec<PathBuf> = Vec::new();
+    let candidate_filenames = candidate_filenames(config);
+    for d in project_doc_search_dirs(config)? {
+        for name in &candidate_filenames {
+            let candidate = d.join(name);
+            if is_codex_ignored(&config.cwd, &candidate, false) {
+                continue;
+            }
+            match std::fs::symlink_metadata(&candidate) {
+                Ok(md) => {
+                    let ft = md.file_type();
+                    // Allow regular files and symlinks; opening will later fail for dangling links.
+                    if ft.is_file() || ft.is_symlink() {
+                        found.push(candidate);
+                        break;
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    Ok(found)
+}
+
+/// Directories searched for project docs, ordered from the repository root to
+/// the current working directory (inclusive). None for a project whose trust
+/// the user denied.
+pub(crate) fn project_doc_search_dirs(config: &Config) -> std::io::Result<Vec<PathBuf>> {
+    if config.active_project.is_denied() {
+        return Ok(Vec::new());
+    }
+
     let mut dir = config.cwd.clone();
     if let Ok(canon) = normalize_path(&dir) {
         dir = canon;
@@ -174,7 +220,7 @@ pub fn discover_project_doc_paths(config: &Config) -> std::io::Result<Vec<PathBu
         cursor = parent.to_path_buf();
     }
 
-    let search_dirs: Vec<PathBuf> = if let Some(root) = git_root {
+    let search_dirs = if let Some(root) = git_root {
         let mut dirs: Vec<PathBuf> = Vec::new();
         let mut saw_root = false;
         for p in chain.iter().rev() {
@@ -192,30 +238,10 @@ pub fn discover_project_doc_paths(config: &Config) -> std::io::Result<Vec<PathBu
         vec![config.cwd.clone()]
     };
 
-    let mut found: Vec<PathBuf> = Vec::new();
-    let candidate_filenames = candidate_filenames(config);
-    for d in search_dirs {
-        for name in &candidate_filenames {
-            let candidate = d.join(name);
-            match std::fs::symlink_metadata(&candidate) {
-                Ok(md) => {
-                    let ft = md.file_type();
-                    // Allow regular files and symlinks; opening will later fail for dangling links.
-                    if ft.is_file() || ft.is_symlink() {
-                        found.push(candidate);
-                        break;
-                    }
-                }
-                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
-                Err(e) => return Err(e),
-            }
-        }
-    }
-
-    Ok(found)
+    Ok(search_dirs)
 }
 
-fn candidate_filenames<'a>(config: &'a Config) -> Vec<&'a str> {
+pub(crate) fn candidate_filenames<'a>(config: &'a Config) -> Vec<&'a str> {
     let mut names: Vec<&'a str> =
         Vec::with_capacity(2 + config.project_doc_fallback_filenames.len());
     names.push(LOCAL_PROJECT_DOC_FILENAME);
@@ -304,6 +330,23 @@ mod tests {
         );
     }
 
+    /// A project whose trust was denied contributes no instructions.
+    #[tokio::test]
+    async fn denied_project_docs_are_ignored() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        fs::write(tmp.path().join("AGENTS.md"), "run ./install.sh first").unwrap();
+
+        let mut config = make_config(&tmp, 4096, Some("user instructions")).await;
+        config.active_project.trust_level = Some(codex_protocol::config_types::TrustLevel::Deny);
+
+        let res = get_user_instructions(&config, None).await;
+        assert_eq!(res.as_deref(), Some("user instructions"));
+        assert_eq!(
+            discover_project_doc_paths(&config).unwrap(),
+            Vec::<PathBuf>::new()
+        );
+    }
+
     /// Oversize file is truncated to `project_doc_max_bytes`.
     #[tokio::test]
     async fn doc_larger_than_limit_is_truncated() {
@@ -397,7 +440,8 @@ mod tests {
     }
 
     /// When both the repository root and the working directory contain
-    /// AGENTS.md files, their contents are concatenated from root to cwd.
+    /// AGENTS.md files, their contents are concatenated from root to cwd and
+    /// followed by a note that the closest doc wins on conflicts.
     #[tokio::test]
     async fn concatenates_root_and_cwd_docs() {
         let repo = tempfile::tempdir().expect("tempdir");
@@ -423,7 +467,10 @@ mod tests {
         let res = get_user_instructions(&cfg, None)
             .await
             .expect("doc expected");
-        assert_eq!(res, "root doc\n\ncrate doc");
+        assert_eq!(
+            res,
+            format!("root doc\n\ncrate doc\n\n{PROJECT_DOC_PRECEDENCE_NOTE}")
+        );
     }
 
     /// AGENTS.override.md is preferred over AGENTS.md when both are present.
@@ -0,0 +1,171 @@
+//! Live reload of project docs.
+//!
+//! A [`ProjectDocWatcher`] watches every directory searched for project docs
+//! (see [`crate::project_doc`]) and remembers whether one of the candidate
+//! files was created, edited or removed. At the start of the next turn the
+//! session re-reads the docs and, if they changed, records an update notice so
+//! the model follows the new instructions.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use notify::EventKind;
+use notify::RecommendedWatcher;
+use notify::RecursiveMode;
+use notify::Watcher;
+use tracing::warn;
+
+use crate::config::Config;
+use crate::project_doc::candidate_filenames;
+use crate::project_doc::project_doc_search_dirs;
+
+/// Prepended to the project docs recorded when they change mid-session.
+const PROJECT_DOCS_UPDATED_NOTICE: &str = "The AGENTS.md instructions were updated during this session. They replace the earlier AGENTS.md instructions:";
+
+/// Recorded instead when every project doc was removed mid-session.
+const PROJECT_DOCS_REMOVED_NOTICE: &str = "The AGENTS.md instructions were removed during this session. Disregard the earlier AGENTS.md instructions.";
+
+/// Watches project docs for changes. The default watches nothing.
+#[derive(Default)]
+pub(crate) struct ProjectDocWatcher {
+    changed: Arc<AtomicBool>,
+    _watcher: Option<RecommendedWatcher>,
+}
+
+impl ProjectDocWatcher {
+    /// Starts watching the project doc search directories for `config`. If the
+    /// watcher cannot be set up the failure is logged and docs are only read
+    /// at session start.
+    pub(crate) fn new(config: &Config) -> Self {
+        let changed = Arc::new(AtomicBool::new(false));
+        let watcher = if config.project_doc_max_bytes == 0 {
+            None
+        } else {
+            match start_watcher(config, Arc::clone(&changed)) {
+                Ok(watcher) => Some(watcher),
+                Err(err) => {
+                    warn!("failed to watch project docs for changes: {err}");
+                    None
+                }
+            }
+        };
+        Self {
+            changed,
+            _watcher: watcher,
+        }
+    }
+
+    /// Returns whether a project doc changed since the last call.
+    pub(crate) fn take_changed(&self) -> bool {
+        self.changed.swap(false, Ordering::SeqCst)
+    }
+}
+
+/// Text of the message recorded when the project docs change mid-session.
+/// `user_instructions` is the freshly assembled instructions, if any remain.
+pub(crate) fn project_docs_update_notice(user_instructions: Option<&str>) -> String {
+    match user_instructions {
+        Some(instructions) => format!("{PROJECT_DOCS_UPDATED_NOTICE}\n\n{instructions}"),
+        None => PROJECT_DOCS_REMOVED_NOTICE.to_string(),
+    }
+}
+
+fn start_watcher(config: &Config, changed: Arc<AtomicBool>) -> notify::Result<RecommendedWatcher> {
+    let search_dirs = project_doc_search_dirs(config)?;
+    let filenames: Vec<String> = candidate_filenames(config)
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let Ok(event) = res else {
+            return;
+        };
+        if matches!(event.kind, EventKind::Access(_)) {
+            return;
+        }
+        if event
+            .paths
+            .iter()
+            .any(|path| is_project_doc(path, &filenames))
+        {
+            changed.store(true, Ordering::SeqCst);
+        }
+    })?;
+    for dir in search_dirs {
+        watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+    }
+    Ok(watcher)
+}
+
+fn is_project_doc(path: &Path, filenames: &[String]) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| filenames.iter().any(|candidate| candidate == name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigBuilder;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    #[test]
+    fn only_candidate_filenames_count_as_project_docs() {
+        let filenames = vec!["AGENTS.override.md".to_string(), "AGENTS.md".to_string()];
+
+        assert!(is_project_doc(Path::new("/repo/AGENTS.md"), &filenames));
+        assert!(is_project_doc(
+            Path::new("/repo/crate/AGENTS.override.md"),
+            &filenames
+        ));
+        assert!(!is_project_doc(Path::new("/repo/README.md"), &filenames));
+        assert!(!is_project_doc(
+            Path::new("/repo/AGENTS.md.swp"),
+            &filenames
+        ));
+    }
+
+    #[test]
+    fn update_notice_carries_new_instructions() {
+        assert_eq!(
+            project_docs_update_notice(Some("use tabs")),
+            format!("{PROJECT_DOCS_UPDATED_NOTICE}\n\nuse tabs")
+        );
+        assert_eq!(
+            project_docs_update_notice(None),
+            PROJECT_DOCS_REMOVED_NOTICE.to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn editing_agents_md_marks_docs_changed() -> anyhow::Result<()> {
+        let codex_home = tempfile::tempdir()?;
+        let repo = tempfile::tempdir()?;
+        std::fs::write(repo.path().join("AGENTS.md"), "first")?;
+        let mut config = ConfigBuilder::default()
+            .codex_home(codex_home.path().to_path_buf())
+            .build()
+            .await?;
+        config.cwd = repo.path().to_path_buf();
+
+        let watcher = ProjectDocWatcher::new(&config);
+        assert!(!watcher.take_changed());
+
+        std::fs::write(repo.path().join("AGENTS.md"), "second")?;
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !watcher.take_changed() {
+            assert!(
+                Instant::now() < deadline,
+                "timed out waiting for change notification"
+            );
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,242 @@
+//! Provenance of the context the model is given.
+//!
+//! Every tool output that enters the conversation is attributed to the
+//! files, web pages and MCP servers it was read from. The attribution is
+//! inferred from the call that produced the output: `read_file`, `list_dir`
+//! and `view_image` name their path, shell commands name the files they read
+//! as recognised by [`parse_command`] (`cat`, `sed -n`, `head`, ...),
+//! `read_mcp_resource` names its resource, and MCP tool calls their server
+//! and tool. Pages opened by web search are attributed to their URL. Each
+//! attribution is emitted as an `EventMsg::ContextSources` and persisted in
+//! the rollout, where `/sources`, session bundles and `[redaction] sources`
+//! find it.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::models::ShellCommandToolCallParams;
+use codex_protocol::models::ShellToolCallParams;
+use codex_protocol::models::WebSearchAction;
+use codex_protocol::parse_command::ParsedCommand;
+use codex_protocol::protocol::ContentSource;
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+
+use crate::parse_command::parse_command;
+
+#[derive(Deserialize)]
+struct ExecCommandArgs {
+    cmd: String,
+    #[serde(default)]
+    workdir: Option<String>,
+}
+
+/// The sources a tool call or web search reads. `mcp_tool` is the server
+/// and tool the call's name resolves to when it is an MCP tool. Calls that
+/// read nothing attributable, such as `apply_patch` or a build, have none.
+pub(crate) fn sources_for_call(
+    call: &ResponseItem,
+    cwd: &Path,
+    mcp_tool: Option<(String, String)>,
+) -> Vec<ContentSource> {
+    let mut sources = match call {
+        ResponseItem::FunctionCall {
+            name, arguments, ..
+        } => match mcp_tool {
+            Some((server, tool)) => vec![ContentSource::McpTool { server, tool }],
+            None => function_call_sources(name, arguments, cwd),
+        },
+        ResponseItem::LocalShellCall {
+            action: LocalShellAction::Exec(exec),
+            ..
+        } => command_sources(&exec.command, exec.working_directory.as_deref(), cwd),
+        ResponseItem::WebSearchCall {
+            action:
+                WebSearchAction::OpenPage { url: Some(url) }
+                | WebSearchAction::FindInPage { url: Some(url), .. },
+            ..
+        } => vec![ContentSource::Url { url: url.clone() }],
+        _ => Vec::new(),
+    };
+    sources.sort();
+    sources.dedup();
+    sources
+}
+
+/// The most recent call in `items` whose output has `call_id`.
+pub(crate) fn find_call<'a>(items: &'a [ResponseItem], call_id: &str) -> Option<&'a ResponseItem> {
+    items.iter().rev().find(|item| match item {
+        ResponseItem::FunctionCall { call_id: id, .. }
+        | ResponseItem::CustomToolCall { call_id: id, .. } => id == call_id,
+        ResponseItem::LocalShellCall {
+            call_id: Some(id), ..
+        } => id == call_id,
+        _ => false,
+    })
+}
+
+fn function_call_sources(name: &str, arguments: &str, cwd: &Path) -> Vec<ContentSource> {
+    let path_argument = |key: &str| {
+        let args: JsonValue = serde_json::from_str(arguments).ok()?;
+        let path = args.get(key)?.as_str()?;
+        Some(ContentSource::File {
+            path: resolve(cwd, path),
+        })
+    };
+    match name {
+        "read_file" => path_argument("file_path").into_iter().collect(),
+        "list_dir" => path_argument("dir_path").into_iter().collect(),
+        "view_image" => path_argument("path").into_iter().collect(),
+        "read_mcp_resource" => {
+            let Ok(args) = serde_json::from_str::<JsonValue>(arguments) else {
+                return Vec::new();
+            };
+            match (
+                args.get("server").and_then(JsonValue::as_str),
+                args.get("uri").and_then(JsonValue::as_str),
+            ) {
+                (Some(server), Some(uri)) => vec![ContentSource::McpResource {
+                    server: server.to_string(),
+                    uri: uri.to_string(),
+                }],
+                _ => Vec::new(),
+            }
+        }
+        "shell" | "container.exec" => serde_json::from_str::<ShellToolCallParams>(arguments)
+            .map(|params| command_sources(&params.command, params.workdir.as_deref(), cwd))
+            .unwrap_or_default(),
+        "shell_command" => serde_json::from_str::<ShellCommandToolCallParams>(arguments)
+            .map(|params| script_sources(params.command, params.workdir.as_deref(), cwd))
+            .unwrap_or_default(),
+        "exec_command" => serde_json::from_str::<ExecCommandArgs>(arguments)
+            .map(|args| script_sources(args.cmd, args.workdir.as_deref(), cwd))
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+fn script_sources(script: String, workdir: Option<&str>, cwd: &Path) -> Vec<ContentSource> {
+    let command = ["bash".to_string(), "-lc".to_string(), script];
+    command_sources(&command, workdir, cwd)
+}
+
+fn command_sources(command: &[String], workdir: Option<&str>, cwd: &Path) -> Vec<ContentSource> {
+    let cwd = match workdir {
+        Some(workdir) => resolve(cwd, workdir),
+        None => cwd.to_path_buf(),
+    };
+    parse_command(command)
+        .into_iter()
+        .filter_map(|parsed| match parsed {
+            ParsedCommand::Read { path, .. } => Some(ContentSource::File {
+                path: resolve(&cwd, &path.to_string_lossy()),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// `path` made absolute against `cwd`, without `.` components.
+fn resolve(cwd: &Path, path: &str) -> PathBuf {
+    cwd.join(path).components().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn function_call(name: &str, arguments: serde_json::Value) -> ResponseItem {
+        ResponseItem::FunctionCall {
+            id: None,
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+            call_id: "call-1".to_string(),
+        }
+    }
+
+    #[test]
+    fn calls_are_attributed_to_what_they_read() {
+        let cwd = Path::new("/work/api");
+        let file = |path: &str| ContentSource::File {
+            path: PathBuf::from(path),
+        };
+
+        assert_eq!(
+            sources_for_call(
+                &function_call(
+                    "read_file",
+                    serde_json::json!({"file_path": "/work/api/src/lib.rs"})
+                ),
+                cwd,
+                None,
+            ),
+            vec![file("/work/api/src/lib.rs")]
+        );
+        assert_eq!(
+            sources_for_call(
+                &function_call(
+                    "shell_command",
+                    serde_json::json!({"command": "cat Cargo.toml", "workdir": "core"}),
+                ),
+                cwd,
+                None,
+            ),
+            vec![file("/work/api/core/Cargo.toml")]
+        );
+        assert_eq!(
+            sources_for_call(
+                &function_call(
+                    "read_mcp_resource",
+                    serde_json::json!({"server": "docs", "uri": "docs://guide"}),
+                ),
+                cwd,
+                None,
+            ),
+            vec![ContentSource::McpResource {
+                server: "docs".to_string(),
+                uri: "docs://guide".to_string(),
+            }]
+        );
+        assert_eq!(
+            sources_for_call(
+                &function_call("docs__search", serde_json::json!({})),
+                cwd,
+                Some(("docs".to_string(), "search".to_string())),
+            ),
+            vec![ContentSource::McpTool {
+                server: "docs".to_string(),
+                tool: "search".to_string(),
+            }]
+        );
+        assert_eq!(
+            sources_for_call(
+                &ResponseItem::WebSearchCall {
+                    id: None,
+                    status: None,
+                    action: WebSearchAction::OpenPage {
+                        url: Some("https://example.com/".to_string()),
+                    },
+                },
+                cwd,
+                None,
+            ),
+            vec![ContentSource::Url {
+                url: "https://example.com/".to_string(),
+            }]
+        );
+        assert_eq!(
+            sources_for_call(
+                &function_call(
+                    "shell_command",
+                    serde_json::json!({"command": "cargo test"})
+                ),
+                cwd,
+                None,
+            ),
+            Vec::new()
+        );
+    }
+}
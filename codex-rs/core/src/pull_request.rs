@@ -0,0 +1,432 @@
+//! Opt-in `[pull_request]` workflow: when a task finishes with uncommitted
+//! changes, commit them on a new branch, push it, and open a draft pull
+//! request with `gh`.
+//!
+//! Only the files the task changed are committed: the uncommitted files are
+//! recorded when the task starts, and files that were already uncommitted
+//! are left out unless the task changed them further.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::PullRequestPublishedEvent;
+use tokio::process::Command;
+use tracing::warn;
+
+use crate::config::types::PullRequestConfig;
+
+const DEFAULT_BRANCH_PREFIX: &str = "codex/";
+const DEFAULT_REMOTE: &str = "origin";
+/// Commit subjects longer than this are cut at a word boundary.
+const MAX_TITLE_CHARS: usize = 72;
+const MAX_SLUG_CHARS: usize = 40;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PullRequestDraft {
+    pub(crate) branch: String,
+    pub(crate) title: String,
+    pub(crate) commit_message: String,
+    pub(crate) body: String,
+}
+
+/// Builds the commit message and pull request description from the
+/// session's prompts and the agent's final message.
+pub(crate) fn draft_pull_request(
+    prompts: &[String],
+    last_agent_message: Option<&str>,
+    config: &PullRequestConfig,
+    conversation_id: ThreadId,
+) -> PullRequestDraft {
+    let title = prompts
+        .last()
+        .and_then(|prompt| prompt.lines().map(str::trim).find(|line| !line.is_empty()))
+        .map(truncate_title)
+        .unwrap_or_else(|| "Apply changes from Codex".to_string());
+    let summary = last_agent_message
+        .map(str::trim)
+        .filter(|message| !message.is_empty())
+        .unwrap_or("Changes made by Codex.");
+
+    let prefix = config
+        .branch_prefix
+        .as_deref()
+        .unwrap_or(DEFAULT_BRANCH_PREFIX);
+    let session = conversation_id.to_string();
+    let short_session: String = session.chars().take(8).collect();
+    let branch = format!("{prefix}{}-{short_session}", slugify(&title));
+
+    let mut body = format!("{summary}\n\n## Prompts\n\n");
+    for prompt in prompts {
+        let first_line = prompt.lines().next().unwrap_or_default().trim();
+        body.push_str(&format!("- {first_line}\n"));
+    }
+    body.push_str(&format!("\nCodex session: `{session}`\n"));
+
+    PullRequestDraft {
+        branch,
+        commit_message: format!("{title}\n\n{summary}\n"),
+        title,
+        body,
+    }
+}
+
+fn truncate_title(line: &str) -> String {
+    if line.chars().count() <= MAX_TITLE_CHARS {
+        return line.to_string();
+    }
+    let mut title = String::new();
+    for word in line.split_whitespace() {
+        if title.chars().count() + word.chars().count() + 1 > MAX_TITLE_CHARS {
+            break;
+        }
+        if !title.is_empty() {
+            title.push(' ');
+        }
+        title.push_str(word);
+    }
+    if title.is_empty() {
+        line.chars().take(MAX_TITLE_CHARS).collect()
+    } else {
+        title
+    }
+}
+
+fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') && !slug.is_empty() {
+            slug.push('-');
+        }
+        if slug.len() >= MAX_SLUG_CHARS {
+            break;
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "changes".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// The uncommitted files of a repository, by path relative to its root,
+/// with the hash of their contents or `None` if they were deleted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct UncommittedFiles(BTreeMap<String, Option<String>>);
+
+impl UncommittedFiles {
+    /// Lists the uncommitted files of the repository containing `cwd`, or
+    /// returns `None` if there is none.
+    pub(crate) async fn read(cwd: &Path) -> Option<Self> {
+        let root = git(cwd, &["rev-parse", "--show-toplevel"]).await.ok()?;
+        let status = git_output(
+            Path::new(&root),
+            &["status", "--porcelain", "-z", "--untracked-files=all"],
+        )
+        .await
+        .ok()?;
+        let mut paths = Vec::new();
+        let mut entries = status.split('\0').filter(|entry| !entry.is_empty());
+        while let Some(entry) = entries.next() {
+            let (Some(code), Some(path)) = (entry.get(..2), entry.get(3..)) else {
+                continue;
+            };
+            paths.push(path.to_string());
+            // Renames and copies are followed by the path they came from.
+            if code.starts_with(['R', 'C'])
+                && let Some(from) = entries.next()
+            {
+                paths.push(from.to_string());
+            }
+        }
+        let root = Path::new(&root);
+        let existing: Vec<&str> = paths
+            .iter()
+            .filter(|path| root.join(path).is_file())
+            .map(String::as_str)
+            .collect();
+        let mut hashes = BTreeMap::new();
+        if !existing.is_empty() {
+            let mut args = vec!["hash-object", "--"];
+            args.extend(&existing);
+            let output = git(root, &args).await.ok()?;
+            for (path, hash) in existing.iter().zip(output.lines()) {
+                hashes.insert(path.to_string(), hash.to_string());
+            }
+        }
+        let files = paths
+            .into_iter()
+            .map(|path| {
+                let hash = hashes.get(&path).cloned();
+                (path, hash)
+            })
+            .collect();
+        Some(Self(files))
+    }
+
+    /// Files uncommitted now that were not uncommitted as in `before`.
+    fn changed_since(&self, before: &UncommittedFiles) -> Vec<String> {
+        self.0
+            .iter()
+            .filter(|(path, hash)| before.0.get(*path) != Some(*hash))
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+}
+
+/// Commits the files under `cwd` that changed since `before` and opens a
+/// draft pull request. Returns `Ok(None)` when `cwd` is not a Git repository
+/// or no file changed.
+///
+/// Successive tasks on a branch the workflow created keep committing to it,
+/// so one session produces one pull request.
+pub(crate) async fn publish(
+    cwd: &Path,
+    config: &PullRequestConfig,
+    draft: PullRequestDraft,
+    before: &UncommittedFiles,
+) -> Result<Option<PullRequestPublishedEvent>, String> {
+    let Some(now) = UncommittedFiles::read(cwd).await else {
+        return Ok(None);
+    };
+    let changed = now.changed_since(before);
+    if changed.is_empty() {
+        return Ok(None);
+    }
+    let root = git(cwd, &["rev-parse", "--show-toplevel"]).await?;
+    let cwd = Path::new(&root);
+
+    let prefix = config
+        .branch_prefix
+        .as_deref()
+        .unwrap_or(DEFAULT_BRANCH_PREFIX);
+    let current_branch = git(cwd, &["branch", "--show-current"]).await?;
+    let (branch, new_branch) = if current_branch.starts_with(prefix) {
+        (current_branch, false)
+    } else {
+        git(cwd, &["switch", "--create", &draft.branch]).await?;
+        (draft.branch.clone(), true)
+    };
+
+    // Literal pathspecs, so that file names are never read as patterns.
+    let pathspecs: Vec<String> = changed
+        .iter()
+        .map(|path| format!(":(literal){path}"))
+        .collect();
+    let mut add = vec!["add", "--all", "--"];
+    add.extend(pathspecs.iter().map(String::as_str));
+    git(cwd, &add).await?;
+    // `--only` leaves out whatever else was staged.
+    let mut commit = vec![
+        "commit",
+        "--quiet",
+        "--only",
+        "--message",
+        &draft.commit_message,
+        "--",
+    ];
+    commit.extend(pathspecs.iter().map(String::as_str));
+    git(cwd, &commit).await?;
+    let commit = git(cwd, &["rev-parse", "HEAD"]).await?;
+    let remote = config.remote.as_deref().unwrap_or(DEFAULT_REMOTE);
+    git(cwd, &["push", "--quiet", "--set-upstream", remote, &branch]).await?;
+
+    let url = if new_branch {
+        let mut args = vec![
+            "pr",
+            "create",
+            "--draft",
+            "--head",
+            &branch,
+            "--title",
+            &draft.title,
+            "--body",
+            &draft.body,
+        ];
+        if let Some(base) = config.base.as_deref() {
+            args.extend(["--base", base]);
+        }
+        gh(cwd, &args).await
+    } else {
+        gh(
+            cwd,
+            &["pr", "view", &branch, "--json", "url", "--jq", ".url"],
+        )
+        .await
+    };
+
+    Ok(Some(PullRequestPublishedEvent {
+        branch,
+        commit,
+        url,
+    }))
+}
+
+async fn git(cwd: &Path, args: &[&str]) -> Result<String, String> {
+    git_output(cwd, args)
+        .await
+        .map(|output| output.trim().to_string())
+}
+
+/// Like [`git`], without trimming the output.
+async fn git_output(cwd: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .await
+        .map_err(|err| format!("failed to run git: {err}"))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(format!(
+            "`git {}` failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Runs `gh` and returns the pull request URL it printed, if any.
+async fn gh(cwd: &Path, args: &[&str]) -> Option<String> {
+    let output = match Command::new("gh")
+        .args(args)
+        .current_dir(cwd)
+        .output()
+        .await
+    {
+        Ok(output) => output,
+        Err(err) => {
+            warn!("failed to run gh: {err}");
+            return None;
+        }
+    };
+    if !output.status.success() {
+        warn!(
+            "`gh {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .rfind(|line| line.starts_with("https://"))
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn drafts_branch_and_messages_from_the_session() {
+        let conversation_id =
+            ThreadId::from_string("67e55044-10b1-426f-9247-bb680e5fe0c8").expect("thread id");
+        let prompts = vec![
+            "Look around the repo".to_string(),
+            "Fix the flaky retry test in client.rs\nIt fails on CI.".to_string(),
+        ];
+
+        let draft = draft_pull_request(
+            &prompts,
+            Some("Replaced the sleep with a paused clock."),
+            &PullRequestConfig::default(),
+            conversation_id,
+        );
+
+        assert_eq!(
+            draft,
+            PullRequestDraft {
+                branch: "codex/fix-the-flaky-retry-test-in-client-rs-67e55044".to_string(),
+                title: "Fix the flaky retry test in client.rs".to_string(),
+                commit_message: "Fix the flaky retry test in client.rs\n\n\
+                                 Replaced the sleep with a paused clock.\n"
+                    .to_string(),
+                body: "Replaced the sleep with a paused clock.\n\n## Prompts\n\n\
+                       - Look around the repo\n\
+                       - Fix the flaky retry test in client.rs\n\n\
+                       Codex session: `67e55044-10b1-426f-9247-bb680e5fe0c8`\n"
+                    .to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn commits_and_pushes_changes_on_a_new_branch() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let remote = dir.path().join("remote.git");
+        let repo = dir.path().join("repo");
+        std::fs::create_dir(&repo).expect("create repo");
+        git(dir.path(), &["init", "--quiet", "--bare", "remote.git"])
+            .await
+            .expect("init remote");
+        for args in [
+            vec!["init", "--quiet"],
+            vec!["config", "user.email", "dev@example.com"],
+            vec!["config", "user.name", "Dev"],
+            vec![
+                "remote",
+                "add",
+                "origin",
+                remote.to_str().expect("utf-8 path"),
+            ],
+            vec!["commit", "--quiet", "--allow-empty", "--message", "init"],
+        ] {
+            git(&repo, &args).await.expect("set up repo");
+        }
+        let draft = PullRequestDraft {
+            branch: "codex/add-notes".to_string(),
+            title: "Add notes".to_string(),
+            commit_message: "Add notes\n".to_string(),
+            body: String::new(),
+        };
+        let config = PullRequestConfig::default();
+        std::fs::write(repo.join("scratch.txt"), "mine\n").expect("write file");
+        let before = UncommittedFiles::read(&repo).await.expect("git repo");
+
+        assert!(
+            matches!(
+                publish(&repo, &config, draft.clone(), &before).await,
+                Ok(None)
+            ),
+            "files the task did not change are left alone"
+        );
+
+        std::fs::write(repo.join("notes.txt"), "hello\n").expect("write file");
+        let event = publish(&repo, &config, draft, &before)
+            .await
+            .expect("publish")
+            .expect("changes were committed");
+
+        assert_eq!(event.branch, "codex/add-notes");
+        assert_eq!(
+            git(&remote, &["rev-parse", "codex/add-notes"]).await,
+            Ok(event.commit)
+        );
+        assert_eq!(
+            git(&repo, &["log", "-1", "--format=%s"]).await,
+            Ok("Add notes".to_string())
+        );
+        assert_eq!(
+            git(&repo, &["show", "--name-only", "--format=", "HEAD"]).await,
+            Ok("notes.txt".to_string())
+        );
+        assert_eq!(
+            git(&repo, &["status", "--porcelain"]).await,
+            Ok("?? scratch.txt".to_string())
+        );
+    }
+
+    #[test]
+    fn long_titles_are_cut_at_a_word_boundary() {
+        let title = truncate_title(&"word ".repeat(30));
+        assert_eq!(title.len(), 69);
+        assert!(title.ends_with("word"));
+    }
+}
@@ -0,0 +1,194 @@
+//! `[reasoning]`: how much of the model's reasoning reaches clients and hooks
+//! (`capture`) and the rollout file (`persist`).
+//!
+//! `summarize` keeps the reasoning summaries and drops the raw reasoning
+//! text; `discard` drops every reasoning event and item. Both settings only
+//! filter what leaves the session: the conversation history sent back to the
+//! model is unchanged.
+
+use codex_protocol::items::TurnItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ItemCompletedEvent;
+use codex_protocol::protocol::ItemStartedEvent;
+use codex_protocol::protocol::RawResponseItemEvent;
+use codex_protocol::protocol::RolloutItem;
+
+use crate::config::types::ReasoningRetention;
+
+/// What is left of `msg` under `retention`, or `None` to drop the event.
+pub(crate) fn retain_event(msg: EventMsg, retention: ReasoningRetention) -> Option<EventMsg> {
+    if retention == ReasoningRetention::Keep {
+        return Some(msg);
+    }
+    let discard = retention == ReasoningRetention::Discard;
+    match msg {
+        EventMsg::AgentReasoningRawContent(_)
+        | EventMsg::AgentReasoningRawContentDelta(_)
+        | EventMsg::ReasoningRawContentDelta(_) => None,
+        EventMsg::AgentReasoning(_)
+        | EventMsg::AgentReasoningDelta(_)
+        | EventMsg::AgentReasoningSectionBreak(_)
+        | EventMsg::ReasoningContentDelta(_)
+            if discard =>
+        {
+            None
+        }
+        EventMsg::ItemStarted(ItemStartedEvent {
+            thread_id,
+            turn_id,
+            item,
+        }) => Some(EventMsg::ItemStarted(ItemStartedEvent {
+            thread_id,
+            turn_id,
+            item: retain_turn_item(item, retention)?,
+        })),
+        EventMsg::ItemCompleted(ItemCompletedEvent {
+            thread_id,
+            turn_id,
+            item,
+        }) => Some(EventMsg::ItemCompleted(ItemCompletedEvent {
+            thread_id,
+            turn_id,
+            item: retain_turn_item(item, retention)?,
+        })),
+        EventMsg::RawResponseItem(RawResponseItemEvent { item }) => {
+            Some(EventMsg::RawResponseItem(RawResponseItemEvent {
+                item: retain_response_item(item, retention)?,
+            }))
+        }
+        msg => Some(msg),
+    }
+}
+
+/// What is left of `item` under `retention`, or `None` to drop the item.
+pub(crate) fn retain_response_item(
+    item: ResponseItem,
+    retention: ReasoningRetention,
+) -> Option<ResponseItem> {
+    match (retention, item) {
+        (ReasoningRetention::Discard, ResponseItem::Reasoning { .. }) => None,
+        (
+            ReasoningRetention::Summarize,
+            ResponseItem::Reasoning {
+                id,
+                summary,
+                encrypted_content,
+                ..
+            },
+        ) => Some(ResponseItem::Reasoning {
+            id,
+            summary,
+            content: None,
+            encrypted_content,
+        }),
+        (_, item) => Some(item),
+    }
+}
+
+/// What is left of a rollout line under `retention`, or `None` to skip it.
+pub(crate) fn retain_rollout_item(
+    item: RolloutItem,
+    retention: ReasoningRetention,
+) -> Option<RolloutItem> {
+    match item {
+        RolloutItem::ResponseItem(item) => {
+            retain_response_item(item, retention).map(RolloutItem::ResponseItem)
+        }
+        RolloutItem::EventMsg(msg) => retain_event(msg, retention).map(RolloutItem::EventMsg),
+        item => Some(item),
+    }
+}
+
+fn retain_turn_item(item: TurnItem, retention: ReasoningRetention) -> Option<TurnItem> {
+    match (retention, item) {
+        (ReasoningRetention::Discard, TurnItem::Reasoning(_)) => None,
+        (ReasoningRetention::Summarize, TurnItem::Reasoning(mut reasoning)) => {
+            reasoning.raw_content.clear();
+            Some(TurnItem::Reasoning(reasoning))
+        }
+        (_, item) => Some(item),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ReasoningItemContent;
+    use codex_protocol::models::ReasoningItemReasoningSummary;
+    use codex_protocol::protocol::AgentMessageEvent;
+    use codex_protocol::protocol::AgentReasoningEvent;
+    use codex_protocol::protocol::AgentReasoningRawContentEvent;
+    use pretty_assertions::assert_eq;
+
+    fn reasoning_item() -> ResponseItem {
+        ResponseItem::Reasoning {
+            id: "rs_1".to_string(),
+            summary: vec![ReasoningItemReasoningSummary::SummaryText {
+                text: "Checked the tests".to_string(),
+            }],
+            content: Some(vec![ReasoningItemContent::ReasoningText {
+                text: "The user wants the tests fixed...".to_string(),
+            }]),
+            encrypted_content: Some("opaque".to_string()),
+        }
+    }
+
+    fn event_names(retention: ReasoningRetention) -> Vec<String> {
+        [
+            EventMsg::AgentReasoning(AgentReasoningEvent {
+                text: "Checked the tests".to_string(),
+            }),
+            EventMsg::AgentReasoningRawContent(AgentReasoningRawContentEvent {
+                text: "The user wants the tests fixed...".to_string(),
+            }),
+            EventMsg::AgentMessage(AgentMessageEvent {
+                message: "Fixed.".to_string(),
+            }),
+        ]
+        .into_iter()
+        .filter_map(|msg| retain_event(msg, retention))
+        .map(|msg| msg.to_string())
+        .collect()
+    }
+
+    #[test]
+    fn answers_are_kept_whatever_happens_to_reasoning() {
+        assert_eq!(
+            event_names(ReasoningRetention::Keep),
+            vec![
+                "agent_reasoning",
+                "agent_reasoning_raw_content",
+                "agent_message"
+            ]
+        );
+        assert_eq!(
+            event_names(ReasoningRetention::Summarize),
+            vec!["agent_reasoning", "agent_message"]
+        );
+        assert_eq!(
+            event_names(ReasoningRetention::Discard),
+            vec!["agent_message"]
+        );
+    }
+
+    #[test]
+    fn summarized_reasoning_items_lose_their_raw_text() {
+        let Some(ResponseItem::Reasoning {
+            summary, content, ..
+        }) = retain_response_item(reasoning_item(), ReasoningRetention::Summarize)
+        else {
+            panic!("expected the reasoning item to be kept");
+        };
+        assert_eq!(summary.len(), 1);
+        assert_eq!(content, None);
+
+        assert!(
+            retain_rollout_item(
+                RolloutItem::ResponseItem(reasoning_item()),
+                ReasoningRetention::Discard
+            )
+            .is_none()
+        );
+    }
+}
@@ -0,0 +1,406 @@
+//! Published Codex releases: which versions a [`UpdateChannel`] offers, the
+//! lines of their release notes that concern a user's `config.toml`, and the
+//! standalone binaries `codex self-update` installs.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use serde::Deserialize;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::config::types::UpdateChannel;
+use crate::default_client::create_client;
+
+const RELEASES_URL: &str = "https://api.github.com/repos/openai/codex/releases?per_page=30";
+const DOWNLOAD_URL: &str = "https://github.com/openai/codex/releases/download";
+/// Prefix of the git tags releases are published under.
+const TAG_PREFIX: &str = "rust-v";
+
+/// A published release.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    /// Version without the tag prefix, e.g. `0.60.0` or `0.61.0-beta.1`.
+    pub version: String,
+    /// Release notes, in markdown.
+    pub notes: String,
+    /// SHA-256 digests, in hex, of the release assets GitHub published one
+    /// for, by asset name.
+    pub checksums: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct GithubRelease {
+    tag_name: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Deserialize, Debug)]
+struct GithubAsset {
+    name: String,
+    /// E.g. `sha256:3f2a…`.
+    #[serde(default)]
+    digest: Option<String>,
+}
+
+/// Fetches the releases `channel` offers, newest first.
+pub async fn fetch_releases(channel: UpdateChannel) -> anyhow::Result<Vec<Release>> {
+    let releases = create_client()
+        .get(RELEASES_URL)
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<Vec<GithubRelease>>()
+        .await?;
+    Ok(select_releases(releases, channel))
+}
+
+fn select_releases(releases: Vec<GithubRelease>, channel: UpdateChannel) -> Vec<Release> {
+    let mut selected: Vec<(Version, Release)> = releases
+        .into_iter()
+        .filter(|release| !release.draft)
+        .filter(|release| channel == UpdateChannel::Beta || !release.prerelease)
+        .filter_map(|release| {
+            let version = release.tag_name.strip_prefix(TAG_PREFIX)?;
+            Some((
+                Version::parse(version)?,
+                Release {
+                    version: version.to_string(),
+                    notes: release.body.unwrap_or_default(),
+                    checksums: release
+                        .assets
+                        .into_iter()
+                        .filter_map(|asset| {
+                            let sha256 = asset.digest?.strip_prefix("sha256:")?.to_lowercase();
+                            Some((asset.name, sha256))
+                        })
+                        .collect(),
+                },
+            ))
+        })
+        .collect();
+    selected.sort_by(|(a, _), (b, _)| b.cmp(a));
+    selected.into_iter().map(|(_, release)| release).collect()
+}
+
+/// Whether `latest` is a later version than `current`. Pre-releases sort
+/// before the release they lead up to. `None` if either does not parse.
+pub fn is_newer(latest: &str, current: &str) -> Option<bool> {
+    Some(Version::parse(latest)? > Version::parse(current)?)
+}
+
+/// A `major.minor.patch` version with an optional `-alpha.N`/`-beta.N` suffix.
+#[derive(Debug, PartialEq, Eq)]
+struct Version {
+    release: (u64, u64, u64),
+    pre: Option<(String, u64)>,
+}
+
+impl Version {
+    fn parse(version: &str) -> Option<Self> {
+        let (release, pre) = match version.trim().split_once('-') {
+            Some((release, pre)) => (release, Some(pre)),
+            None => (version.trim(), None),
+        };
+        let mut parts = release.split('.').map(|part| part.parse::<u64>().ok());
+        let release = (parts.next()??, parts.next()??, parts.next()??);
+        if parts.next().is_some() {
+            return None;
+        }
+        let pre = match pre {
+            Some(pre) => {
+                let (label, number) = pre.split_once('.').unwrap_or((pre, "0"));
+                Some((label.to_string(), number.parse().ok()?))
+            }
+            None => None,
+        };
+        Some(Self { release, pre })
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.release
+            .cmp(&other.release)
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => a.cmp(b),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Lines from the notes of the releases after `current`, up to and including
+/// `latest`, that name a key set in `config` in backticks, e.g.
+/// "`experimental_instructions_file` is deprecated". Oldest release first,
+/// each line prefixed with its version.
+pub fn relevant_notes(
+    releases: &[Release],
+    current: &str,
+    latest: &str,
+    config: &toml::Table,
+) -> Vec<String> {
+    let keys = config_keys(config);
+    let (Some(current), Some(latest)) = (Version::parse(current), Version::parse(latest)) else {
+        return Vec::new();
+    };
+    let mut notes = Vec::new();
+    for release in releases.iter().rev() {
+        let Some(version) = Version::parse(&release.version) else {
+            continue;
+        };
+        if version <= current || version > latest {
+            continue;
+        }
+        for line in release.notes.lines() {
+            let line = line.trim().trim_start_matches(['-', '*']).trim();
+            if mentions_key(line, &keys) {
+                notes.push(format!("{}: {line}", release.version));
+            }
+        }
+    }
+    notes
+}
+
+/// Every key in `config`, both by name and by dotted path.
+fn config_keys(config: &toml::Table) -> BTreeSet<String> {
+    fn collect(table: &toml::Table, prefix: &str, keys: &mut BTreeSet<String>) {
+        for (key, value) in table {
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{prefix}.{key}")
+            };
+            if let toml::Value::Table(table) = value {
+                collect(table, &path, keys);
+            }
+            keys.insert(key.clone());
+            keys.insert(path);
+        }
+    }
+    let mut keys = BTreeSet::new();
+    collect(config, "", &mut keys);
+    keys
+}
+
+/// Whether a backticked span of `line` names one of `keys`, alone or as in
+/// `key = value` or `[table]`.
+fn mentions_key(line: &str, keys: &BTreeSet<String>) -> bool {
+    line.split('`').skip(1).step_by(2).any(|span| {
+        let key = span
+            .trim()
+            .trim_start_matches('[')
+            .split(|c: char| c == '=' || c == ']' || c.is_whitespace())
+            .next()
+            .unwrap_or_default();
+        keys.contains(key)
+    })
+}
+
+/// Target triple of the standalone binary published for this platform.
+fn release_target() -> Option<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("linux", "x86_64") => Some("x86_64-unknown-linux-musl"),
+        ("linux", "aarch64") => Some("aarch64-unknown-linux-musl"),
+        ("macos", "x86_64") => Some("x86_64-apple-darwin"),
+        ("macos", "aarch64") => Some("aarch64-apple-darwin"),
+        ("windows", "x86_64") => Some("x86_64-pc-windows-msvc"),
+        ("windows", "aarch64") => Some("aarch64-pc-windows-msvc"),
+        _ => None,
+    }
+}
+
+/// Downloads the standalone binary of `release` for this platform, checks it
+/// against the SHA-256 digest published with the release, and puts it in
+/// place of `exe`.
+pub async fn install_release(release: &Release, exe: &Path) -> anyhow::Result<()> {
+    let Some(target) = release_target() else {
+        anyhow::bail!(
+            "no Codex binary is published for {}-{}",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        );
+    };
+    let version = &release.version;
+    let asset = format!("codex-{target}{}.zst", std::env::consts::EXE_SUFFIX);
+    let Some(expected) = release.checksums.get(&asset) else {
+        anyhow::bail!("Codex {version} publishes no checksum for {asset}; not installing it");
+    };
+    let url = format!("{DOWNLOAD_URL}/{TAG_PREFIX}{version}/{asset}");
+    let compressed = create_client()
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    verify_checksum(&compressed, expected)
+        .map_err(|err| anyhow::anyhow!("{asset} of Codex {version}: {err}; not installing it"))?;
+    let binary = zstd::stream::decode_all(compressed.as_ref())?;
+
+    // Stage the binary next to `exe` so the final rename stays on one
+    // filesystem.
+    let staged = exe.with_extension("download");
+    tokio::fs::write(&staged, binary).await?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        tokio::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755)).await?;
+    }
+    // Windows will not overwrite a running executable but lets it be renamed.
+    #[cfg(windows)]
+    {
+        let previous = exe.with_extension("old.exe");
+        let _ = tokio::fs::remove_file(&previous).await;
+        tokio::fs::rename(exe, &previous).await?;
+    }
+    tokio::fs::rename(&staged, exe).await?;
+    Ok(())
+}
+
+/// Checks that `contents` hash to `expected`, a hex SHA-256 digest.
+fn verify_checksum(contents: &[u8], expected: &str) -> Result<(), String> {
+    let actual = format!("{:x}", Sha256::digest(contents));
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(format!(
+            "checksum mismatch (expected {expected}, got {actual})"
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn github_release(tag_name: &str, prerelease: bool, body: &str) -> GithubRelease {
+        GithubRelease {
+            tag_name: tag_name.to_string(),
+            body: Some(body.to_string()),
+            draft: false,
+            prerelease,
+            assets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn channels_select_and_order_releases() {
+        let releases = || {
+            vec![
+                github_release("rust-v0.60.0", false, ""),
+                github_release("rust-v0.61.0-beta.2", true, ""),
+                github_release("rust-v0.61.0-beta.10", true, ""),
+                github_release("rust-v0.59.1", false, ""),
+                github_release("not-a-release", false, ""),
+            ]
+        };
+        let versions = |channel| {
+            select_releases(releases(), channel)
+                .into_iter()
+                .map(|release| release.version)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(versions(UpdateChannel::Stable), vec!["0.60.0", "0.59.1"]);
+        assert_eq!(
+            versions(UpdateChannel::Beta),
+            vec!["0.61.0-beta.10", "0.61.0-beta.2", "0.60.0", "0.59.1"]
+        );
+    }
+
+    #[test]
+    fn published_sha256_digests_become_checksums() {
+        let mut release = github_release("rust-v0.60.0", false, "");
+        release.assets = vec![
+            GithubAsset {
+                name: "codex-x86_64-unknown-linux-musl.zst".to_string(),
+                digest: Some("sha256:ABC123".to_string()),
+            },
+            GithubAsset {
+                name: "codex-aarch64-apple-darwin.zst".to_string(),
+                digest: None,
+            },
+        ];
+
+        let releases = select_releases(vec![release], UpdateChannel::Stable);
+
+        assert_eq!(
+            releases[0].checksums,
+            BTreeMap::from([(
+                "codex-x86_64-unknown-linux-musl.zst".to_string(),
+                "abc123".to_string()
+            )])
+        );
+    }
+
+    #[test]
+    fn downloads_must_match_their_checksum() {
+        let digest = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        assert_eq!(verify_checksum(b"hello", digest), Ok(()));
+        assert!(verify_checksum(b"hello!", digest).is_err());
+    }
+
+    #[test]
+    fn pre_releases_sort_before_their_release() {
+        assert_eq!(is_newer("0.61.0-beta.1", "0.60.0"), Some(true));
+        assert_eq!(is_newer("0.61.0-beta.1", "0.61.0"), Some(false));
+        assert_eq!(is_newer("0.61.0", "0.61.0-beta.3"), Some(true));
+        assert_eq!(is_newer("0.61.0-beta.1", "0.61.0-alpha.4"), Some(true));
+        assert_eq!(is_newer("0.61", "0.60.0"), None);
+    }
+
+    #[test]
+    fn notes_are_limited_to_new_releases_and_configured_keys() {
+        let releases = vec![
+            Release {
+                version: "0.62.0".to_string(),
+                notes: "- `tools.web_search` is deprecated; use `web_search`.".to_string(),
+                checksums: BTreeMap::new(),
+            },
+            Release {
+                version: "0.61.0".to_string(),
+                notes: "## Changes\n- `model_verbosity` now applies to all providers\n- Faster `codex resume`\n* `[otel]` exporters reconnect on failure".to_string(),
+                checksums: BTreeMap::new(),
+            },
+            Release {
+                version: "0.60.0".to_string(),
+                notes: "- `model_verbosity` accepts `medium`".to_string(),
+                checksums: BTreeMap::new(),
+            },
+        ];
+        let config: toml::Table = toml::from_str(
+            "model_verbosity = \"low\"\n[tools]\nweb_search = true\n[otel]\nenvironment = \"ci\"\n",
+        )
+        .expect("valid toml");
+
+        assert_eq!(
+            relevant_notes(&releases, "0.60.0", "0.61.0", &config),
+            vec![
+                "0.61.0: `model_verbosity` now applies to all providers",
+                "0.61.0: `[otel]` exporters reconnect on failure",
+            ]
+        );
+        assert_eq!(
+            relevant_notes(&releases, "0.60.0", "0.62.0", &config).last(),
+            Some(&"0.62.0: `tools.web_search` is deprecated; use `web_search`.".to_string())
+        );
+    }
+}
@@ -0,0 +1,671 @@
+//! SSH execution backend (`[exec_backend] type = "ssh"`).
+//!
+//! Tool commands run on another host instead of this machine: each command
+//! becomes an `ssh` invocation that changes to the working directory's
+//! counterpart on the host and runs the command there. The local platform
+//! sandbox cannot reach the host, so the sandbox policy is enforced there with
+//! bubblewrap: the host's filesystem is bound read-only, the policy's writable
+//! roots are bound read-write, and a policy without network access gets its
+//! own network namespace.
+//!
+//! `apply_patch` still edits the local files, so diffs, checkpoints and
+//! `read_file` keep working on the local checkout; the files a patch touched
+//! are then copied to the host, or removed there when the patch deleted them.
+//!
+//! The dev container backend (`type = "devcontainer"`) works the same way
+//! with `docker exec` in place of `ssh`. The container mounts the checkout,
+//! so patches need no copying; see [`crate::devcontainer`] for how the
+//! container is found and started.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use codex_apply_patch::ApplyPatchAction;
+use codex_apply_patch::ApplyPatchFileChange;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+use crate::config::types::ExecBackendConfig;
+use crate::config::types::RemoteSandbox;
+use crate::config::types::RemoteSync;
+use crate::exec::SandboxType;
+use crate::protocol::SandboxPolicy;
+use crate::sandboxing::CommandSpec;
+use crate::sandboxing::ExecEnv;
+
+/// How long copying one patched file to the host may take.
+const SYNC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Directory under `CODEX_HOME` holding the sockets of shared SSH connections.
+const SSH_CONTROL_DIR: &str = "ssh";
+
+/// Shells named by absolute path locally are looked up on the host's `PATH`,
+/// since the host may keep them elsewhere.
+const SHELLS: &[&str] = &["bash", "zsh", "sh", "dash", "fish"];
+
+/// Where and how tool commands run on the remote host.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RemoteExec {
+    target: Target,
+    forward_env: Vec<String>,
+    local_root: PathBuf,
+    remote_root: PathBuf,
+    sync: RemoteSync,
+    sandbox: RemoteSandbox,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    Ssh {
+        host: String,
+        ssh_args: Vec<String>,
+        /// Where consecutive commands share one connection, if they do.
+        control_dir: Option<PathBuf>,
+    },
+    Container {
+        id: String,
+        user: Option<String>,
+    },
+}
+
+impl RemoteExec {
+    /// `None` for the local backend, and for the dev container backend,
+    /// which needs a running container first; see
+    /// [`crate::devcontainer::Devcontainer::up`]. `cwd` is the session's
+    /// working directory; shared connections keep their sockets under
+    /// `codex_home`.
+    pub(crate) fn new(config: &ExecBackendConfig, cwd: &Path, codex_home: &Path) -> Option<Self> {
+        let ExecBackendConfig::Ssh(ssh) = config else {
+            return None;
+        };
+        Some(Self {
+            target: Target::Ssh {
+                host: ssh.host.clone(),
+                ssh_args: ssh.ssh_args.clone(),
+                control_dir: control_dir(codex_home),
+            },
+            forward_env: ssh.forward_env.clone(),
+            local_root: cwd.to_path_buf(),
+            remote_root: ssh.remote_cwd.clone().unwrap_or_else(|| cwd.to_path_buf()),
+            sync: ssh.sync,
+            sandbox: ssh.sandbox,
+        })
+    }
+
+    /// Commands run in container `id` as `user`, with `local_root` mounted
+    /// at `remote_root`.
+    pub(crate) fn container(
+        id: String,
+        user: Option<String>,
+        local_root: PathBuf,
+        remote_root: PathBuf,
+        forward_env: Vec<String>,
+        sandbox: RemoteSandbox,
+    ) -> Self {
+        Self {
+            target: Target::Container { id, user },
+            forward_env,
+            local_root,
+            remote_root,
+            sync: RemoteSync::None,
+            sandbox,
+        }
+    }
+
+    /// The host, or the container, for messages.
+    pub(crate) fn host(&self) -> &str {
+        match &self.target {
+            Target::Ssh { host, .. } => host,
+            Target::Container { id, .. } => id,
+        }
+    }
+
+    /// Whether commands run in a dev container rather than over SSH.
+    pub(crate) fn is_container(&self) -> bool {
+        matches!(self.target, Target::Container { .. })
+    }
+
+    /// `path` on the host. Paths in the working directory move to its
+    /// counterpart there; others are assumed to be at the same place.
+    pub(crate) fn remote_path(&self, path: &Path) -> PathBuf {
+        match path.strip_prefix(&self.local_root) {
+            Ok(relative) if relative.as_os_str().is_empty() => self.remote_root.clone(),
+            Ok(relative) => self.remote_root.join(relative),
+            Err(_) => path.to_path_buf(),
+        }
+    }
+
+    /// Turns `spec` into an `ssh` or `docker exec` command that runs it on
+    /// the host.
+    /// `sandboxed` is false for attempts that run outside the sandbox, e.g.
+    /// after the user approved an escalation.
+    pub(crate) fn transform(
+        &self,
+        spec: CommandSpec,
+        policy: &SandboxPolicy,
+        sandboxed: bool,
+        sandbox_policy_cwd: &Path,
+    ) -> ExecEnv {
+        let sandboxed = sandboxed
+            && self.sandbox == RemoteSandbox::Bwrap
+            && !matches!(
+                policy,
+                SandboxPolicy::DangerFullAccess | SandboxPolicy::ExternalSandbox { .. }
+            );
+
+        let mut remote_env: Vec<(String, String)> = spec
+            .env
+            .iter()
+            .filter(|(name, _)| name.starts_with("CODEX_") || self.forward_env.contains(name))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        remote_env.sort();
+        remote_env.dedup_by(|a, b| a.0 == b.0);
+
+        let remote_cwd = self.remote_path(&spec.cwd);
+        let mut remote_command = Vec::new();
+        if sandboxed {
+            remote_command.extend(self.bwrap_args(policy, sandbox_policy_cwd));
+        }
+        if !remote_env.is_empty() {
+            remote_command.push("env".to_string());
+            remote_command.extend(
+                remote_env
+                    .into_iter()
+                    .map(|(name, value)| format!("{name}={value}")),
+            );
+        }
+        remote_command.push(remote_program(&spec.program));
+        remote_command.extend(spec.args);
+
+        let command = match &self.target {
+            Target::Ssh { .. } => {
+                let script = format!(
+                    "cd {} && exec {}",
+                    quote(&remote_cwd.to_string_lossy()),
+                    remote_command
+                        .iter()
+                        .map(String::as_str)
+                        .map(quote)
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                );
+                let mut command = vec!["ssh".to_string()];
+                command.extend(self.ssh_options());
+                command.push(script);
+                command
+            }
+            Target::Container { id, user } => {
+                let mut command = vec!["docker".to_string(), "exec".to_string(), "-i".to_string()];
+                if let Some(user) = user {
+                    command.extend(["-u".to_string(), user.clone()]);
+                }
+                command.extend([
+                    "-w".to_string(),
+                    remote_cwd.to_string_lossy().to_string(),
+                    id.clone(),
+                ]);
+                command.extend(remote_command);
+                command
+            }
+        };
+
+        ExecEnv {
+            command,
+            cwd: self.local_root.clone(),
+            env: spec.env,
+            expiration: spec.expiration,
+            // Reported as the Linux sandbox so denials by bubblewrap are
+            // detected, and retried with approval, the same way.
+            sandbox: if sandboxed {
+                SandboxType::LinuxSeccomp
+            } else {
+                SandboxType::None
+            },
+            sandbox_permissions: spec.sandbox_permissions,
+            justification: spec.justification,
+            arg0: None,
+        }
+    }
+
+    /// Copies the files `action` touched to the host, or removes them there
+    /// when the patch deleted them. Does nothing with `sync = "none"`.
+    pub(crate) async fn sync_patch(&self, action: &ApplyPatchAction) -> Result<(), String> {
+        if self.sync == RemoteSync::None {
+            return Ok(());
+        }
+        let mut paths: Vec<&PathBuf> = Vec::new();
+        for (path, change) in action.changes() {
+            paths.push(path);
+            if let ApplyPatchFileChange::Update {
+                move_path: Some(destination),
+                ..
+            } = change
+            {
+                paths.push(destination);
+            }
+        }
+        paths.sort();
+        for path in paths {
+            self.sync_file(path).await?;
+        }
+        Ok(())
+    }
+
+    async fn sync_file(&self, path: &Path) -> Result<(), String> {
+        let remote = self.remote_path(path);
+        let remote_file = quote(&remote.to_string_lossy());
+        let (script, contents) = match tokio::fs::read(path).await {
+            Ok(contents) => {
+                let remote_dir = remote
+                    .parent()
+                    .map(|dir| quote(&dir.to_string_lossy()))
+                    .unwrap_or_else(|| ".".to_string());
+                (
+                    format!("mkdir -p {remote_dir} && cat > {remote_file}"),
+                    Some(contents),
+                )
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                (format!("rm -f {remote_file}"), None)
+            }
+            Err(err) => return Err(format!("failed to read {}: {err}", path.display())),
+        };
+
+        let mut child = Command::new("ssh")
+            .args(self.ssh_options())
+            .arg(script)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|err| format!("failed to run ssh: {err}"))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            if let Some(contents) = contents {
+                stdin
+                    .write_all(&contents)
+                    .await
+                    .map_err(|err| format!("failed to send {}: {err}", path.display()))?;
+            }
+            drop(stdin);
+        }
+        let output = timeout(SYNC_TIMEOUT, child.wait_with_output())
+            .await
+            .map_err(|_| format!("copying {} timed out", path.display()))?
+            .map_err(|err| format!("failed to run ssh: {err}"))?;
+        if !output.status.success() {
+            return Err(format!(
+                "copying {} failed: {}",
+                path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Arguments up to and including the destination. `BatchMode` makes ssh
+    /// fail instead of prompting for a password the TUI cannot show, and a
+    /// shared connection spares each command a new handshake.
+    fn ssh_options(&self) -> Vec<String> {
+        let Target::Ssh {
+            host,
+            ssh_args,
+            control_dir,
+        } = &self.target
+        else {
+            return Vec::new();
+        };
+        let mut options = vec!["-o".to_string(), "BatchMode=yes".to_string()];
+        if let Some(control_dir) = control_dir {
+            options.extend([
+                "-o".to_string(),
+                "ControlMaster=auto".to_string(),
+                "-o".to_string(),
+                format!("ControlPath={}/%C", control_dir.display()),
+                "-o".to_string(),
+                "ControlPersist=60".to_string(),
+            ]);
+        }
+        options.extend(ssh_args.iter().cloned());
+        options.push("--".to_string());
+        options.push(host.clone());
+        options
+    }
+
+    /// `bwrap` and its arguments, up to and including `--`, enforcing
+    /// `policy` on the host.
+    fn bwrap_args(&self, policy: &SandboxPolicy, sandbox_policy_cwd: &Path) -> Vec<String> {
+        let mut args: Vec<String> = [
+            "bwrap",
+            "--die-with-parent",
+            "--ro-bind",
+            "/",
+            "/",
+            "--dev",
+            "/dev",
+            "--proc",
+            "/proc",
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+        for writable in policy.get_writable_roots_with_cwd(sandbox_policy_cwd) {
+            let root = self.remote_path(writable.root.as_path());
+            let root = root.to_string_lossy();
+            args.extend(["--bind-try".to_string(), root.to_string(), root.to_string()]);
+            for read_only in &writable.read_only_subpaths {
+                let path = self.remote_path(read_only.as_path());
+                let path = path.to_string_lossy();
+                args.extend([
+                    "--ro-bind-try".to_string(),
+                    path.to_string(),
+                    path.to_string(),
+                ]);
+            }
+        }
+        if !policy.has_full_network_access() {
+            args.push("--unshare-net".to_string());
+        }
+        args.push("--".to_string());
+        args
+    }
+}
+
+fn remote_program(program: &str) -> String {
+    let path = Path::new(program);
+    match path.file_name().and_then(|name| name.to_str()) {
+        Some(name) if path.is_absolute() && SHELLS.contains(&name) => name.to_string(),
+        _ => program.to_string(),
+    }
+}
+
+fn quote(arg: &str) -> String {
+    let arg = arg.replace('\0', "");
+    shlex::try_quote(&arg).map_or(arg.clone(), |quoted| quoted.into_owned())
+}
+
+/// The directory for the sockets of shared SSH connections, made private to
+/// this user first: whoever can create or reach a socket there can run
+/// commands over the connection. Without one, connections are not shared.
+#[cfg(unix)]
+fn control_dir(codex_home: &Path) -> Option<PathBuf> {
+    let dir = codex_home.join(SSH_CONTROL_DIR);
+    match make_private_dir(&dir) {
+        Ok(()) => Some(dir),
+        Err(err) => {
+            tracing::warn!(
+                "not sharing SSH connections: cannot use {}: {err}",
+                dir.display()
+            );
+            None
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn control_dir(_codex_home: &Path) -> Option<PathBuf> {
+    None
+}
+
+/// Creates `dir` with mode 0700, or checks that the existing one is a
+/// directory of the current user and takes away everyone else's access.
+#[cfg(unix)]
+fn make_private_dir(dir: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+    use std::os::unix::fs::MetadataExt;
+    use std::os::unix::fs::PermissionsExt;
+
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)?;
+    let metadata = std::fs::symlink_metadata(dir)?;
+    // SAFETY: `geteuid` has no preconditions and cannot fail.
+    let uid = unsafe { libc::geteuid() };
+    if !metadata.is_dir() || metadata.uid() != uid {
+        return Err(std::io::Error::other(
+            "not a directory owned by the current user",
+        ));
+    }
+    if metadata.mode() & 0o077 != 0 {
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::SshExecBackend;
+    use crate::exec::ExecExpiration;
+    use crate::sandboxing::SandboxPermissions;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+
+    fn remote(remote_cwd: Option<&str>) -> RemoteExec {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let config = ExecBackendConfig::Ssh(SshExecBackend {
+            host: "buildbox".to_string(),
+            remote_cwd: remote_cwd.map(PathBuf::from),
+            ssh_args: vec!["-p".to_string(), "2222".to_string()],
+            forward_env: vec!["RUST_LOG".to_string()],
+            sync: RemoteSync::Patches,
+            sandbox: RemoteSandbox::Bwrap,
+        });
+        RemoteExec::new(&config, Path::new("/home/me/api"), codex_home.path()).expect("ssh backend")
+    }
+
+    fn spec(command: &[&str], cwd: &str) -> CommandSpec {
+        CommandSpec {
+            program: command[0].to_string(),
+            args: command[1..].iter().map(ToString::to_string).collect(),
+            cwd: PathBuf::from(cwd),
+            env: HashMap::from([
+                ("PATH".to_string(), "/opt/homebrew/bin".to_string()),
+                ("RUST_LOG".to_string(), "debug".to_string()),
+            ]),
+            expiration: ExecExpiration::DefaultTimeout,
+            sandbox_permissions: SandboxPermissions::UseDefault,
+            justification: None,
+        }
+    }
+
+    #[test]
+    fn paths_in_the_workspace_move_to_the_remote_checkout() {
+        let remote = remote(Some("/srv/api"));
+        assert_eq!(
+            remote.remote_path(Path::new("/home/me/api")),
+            PathBuf::from("/srv/api")
+        );
+        assert_eq!(
+            remote.remote_path(Path::new("/home/me/api/src/lib.rs")),
+            PathBuf::from("/srv/api/src/lib.rs")
+        );
+        assert_eq!(
+            remote.remote_path(Path::new("/tmp/out")),
+            PathBuf::from("/tmp/out")
+        );
+        assert_eq!(
+            RemoteExec::new(
+                &ExecBackendConfig::Local,
+                Path::new("/home/me/api"),
+                Path::new("/home/me/.codex")
+            ),
+            None
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn shared_connections_use_a_private_directory_in_codex_home() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let dir = codex_home.path().join("ssh");
+        std::fs::create_dir(&dir).expect("create dir");
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o777))
+            .expect("loosen permissions");
+        let config = ExecBackendConfig::Ssh(SshExecBackend {
+            host: "buildbox".to_string(),
+            remote_cwd: None,
+            ssh_args: Vec::new(),
+            forward_env: Vec::new(),
+            sync: RemoteSync::Patches,
+            sandbox: RemoteSandbox::Bwrap,
+        });
+
+        let remote = RemoteExec::new(&config, Path::new("/home/me/api"), codex_home.path())
+            .expect("ssh backend");
+
+        assert!(
+            remote
+                .ssh_options()
+                .contains(&format!("ControlPath={}/%C", dir.display()))
+        );
+        let mode = std::fs::metadata(&dir)
+            .expect("metadata")
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o700);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn connections_are_not_shared_through_a_symlinked_directory() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let elsewhere = tempfile::tempdir().expect("tempdir");
+        std::os::unix::fs::symlink(elsewhere.path(), codex_home.path().join("ssh"))
+            .expect("symlink");
+
+        assert_eq!(control_dir(codex_home.path()), None);
+    }
+
+    fn remote_script(env: &ExecEnv) -> Vec<String> {
+        shlex::split(env.command.last().expect("script")).expect("split")
+    }
+
+    #[test]
+    fn unsandboxed_commands_run_over_ssh_in_the_remote_cwd() {
+        let remote = remote(Some("/srv/api"));
+        let env = remote.transform(
+            spec(&["/bin/zsh", "-lc", "cargo test"], "/home/me/api/core"),
+            &SandboxPolicy::DangerFullAccess,
+            true,
+            Path::new("/home/me/api"),
+        );
+        assert_eq!(env.sandbox, SandboxType::None);
+        assert_eq!(env.cwd, PathBuf::from("/home/me/api"));
+        assert_eq!(env.command[0], "ssh");
+        assert_eq!(
+            env.command[env.command.len() - 4..env.command.len() - 1],
+            ["2222", "--", "buildbox"]
+        );
+        assert_eq!(
+            remote_script(&env),
+            [
+                "cd",
+                "/srv/api/core",
+                "&&",
+                "exec",
+                "env",
+                "RUST_LOG=debug",
+                "zsh",
+                "-lc",
+                "cargo test"
+            ]
+        );
+        // The local environment still reaches ssh itself.
+        assert_eq!(
+            env.env.get("PATH").map(String::as_str),
+            Some("/opt/homebrew/bin")
+        );
+    }
+
+    #[test]
+    fn sandbox_policy_is_enforced_with_bwrap_on_the_host() {
+        let remote = remote(None);
+        let policy = SandboxPolicy::new_workspace_write_policy();
+        let env = remote.transform(
+            spec(&["ls"], "/home/me/api"),
+            &policy,
+            true,
+            Path::new("/home/me/api"),
+        );
+        assert_eq!(env.sandbox, SandboxType::LinuxSeccomp);
+        let script = remote_script(&env);
+        assert_eq!(
+            script[..9],
+            [
+                "cd",
+                "/home/me/api",
+                "&&",
+                "exec",
+                "bwrap",
+                "--die-with-parent",
+                "--ro-bind",
+                "/",
+                "/"
+            ]
+        );
+        assert!(
+            script
+                .windows(3)
+                .any(|bind| bind == ["--bind-try", "/home/me/api", "/home/me/api"]),
+            "{script:?}"
+        );
+        assert_eq!(
+            script[script.len() - 5..],
+            ["--unshare-net", "--", "env", "RUST_LOG=debug", "ls"]
+        );
+
+        // Escalated attempts skip the sandbox.
+        let env = remote.transform(
+            spec(&["ls"], "/home/me/api"),
+            &policy,
+            false,
+            Path::new("/home/me/api"),
+        );
+        assert!(!remote_script(&env).contains(&"bwrap".to_string()));
+    }
+
+    #[test]
+    fn container_commands_run_with_docker_exec_in_the_mounted_checkout() {
+        let remote = RemoteExec::container(
+            "f00dcafe".to_string(),
+            Some("vscode".to_string()),
+            PathBuf::from("/home/me/api"),
+            PathBuf::from("/workspaces/api"),
+            vec!["RUST_LOG".to_string()],
+            RemoteSandbox::None,
+        );
+        let env = remote.transform(
+            spec(&["/bin/bash", "-lc", "cargo test"], "/home/me/api/core"),
+            &SandboxPolicy::new_workspace_write_policy(),
+            true,
+            Path::new("/home/me/api"),
+        );
+        assert_eq!(env.sandbox, SandboxType::None);
+        assert_eq!(
+            env.command,
+            [
+                "docker",
+                "exec",
+                "-i",
+                "-u",
+                "vscode",
+                "-w",
+                "/workspaces/api/core",
+                "f00dcafe",
+                "env",
+                "RUST_LOG=debug",
+                "bash",
+                "-lc",
+                "cargo test"
+            ]
+        );
+    }
+}
@@ -0,0 +1,646 @@
+//! Replays the tool calls recorded in a rollout against a workspace.
+//!
+//! [`parse_recorded_session`] turns a rollout into the shell commands and
+//! patches the agent ran, in order, leaving out turns that were rolled back.
+//! [`run_step`] then runs one of them under any sandbox policy, so a session
+//! can be repeated as a script or used to see how a sandbox change would have
+//! treated it. Calls that cannot be replayed (reads, MCP tools, input to a
+//! running process) become skipped steps. Steps only run inside the replay
+//! workspace: recorded directories and patch paths are moved into it, and
+//! steps that name absolute paths outside the original working directory are
+//! refused.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_apply_patch::Hunk;
+use codex_apply_patch::MaybeApplyPatch;
+use codex_apply_patch::maybe_parse_apply_patch;
+use codex_apply_patch::parse_patch;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::models::SandboxPermissions;
+use codex_protocol::models::ShellCommandToolCallParams;
+use codex_protocol::models::ShellToolCallParams;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::SandboxPolicy;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::CODEX_APPLY_PATCH_ARG1;
+use crate::bash::extract_bash_command;
+use crate::error::CodexErr;
+use crate::error::SandboxErr;
+use crate::exec::ExecParams;
+use crate::exec::process_exec_tool_call;
+use crate::parse_command::shlex_join;
+use crate::shell::Shell;
+use crate::shell::get_shell_by_model_provided_path;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReplayStep {
+    pub call_id: String,
+    /// 1-based turn in which the agent made the call.
+    pub turn: usize,
+    #[serde(flatten)]
+    pub action: ReplayAction,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReplayAction {
+    Command {
+        command: Vec<String>,
+        /// Directory as recorded; see [`ReplayContext::workdir`].
+        workdir: Option<PathBuf>,
+        timeout_ms: Option<u64>,
+    },
+    Patch {
+        patch: String,
+        workdir: Option<PathBuf>,
+    },
+    /// A call that does not change the workspace or cannot be repeated.
+    Skipped { tool: String },
+}
+
+impl ReplayAction {
+    /// One line describing the step: the command as the agent wrote it, the
+    /// files a patch touches, or the skipped tool.
+    pub fn describe(&self) -> String {
+        match self {
+            ReplayAction::Command { command, .. } => match extract_bash_command(command) {
+                Some((_, script)) => script.to_string(),
+                None => shlex_join(command),
+            },
+            ReplayAction::Patch { patch, .. } => {
+                let Ok(args) = parse_patch(patch) else {
+                    return "apply_patch (unparsable patch)".to_string();
+                };
+                let files = args
+                    .hunks
+                    .iter()
+                    .map(|hunk| match hunk {
+                        Hunk::AddFile { path, .. } => format!("add {}", path.display()),
+                        Hunk::DeleteFile { path } => format!("delete {}", path.display()),
+                        Hunk::UpdateFile {
+                            path,
+                            move_path: Some(to),
+                            ..
+                        } => format!("move {} to {}", path.display(), to.display()),
+                        Hunk::UpdateFile { path, .. } => format!("update {}", path.display()),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("apply_patch: {files}")
+            }
+            ReplayAction::Skipped { tool } => format!("skip {tool}"),
+        }
+    }
+}
+
+/// The replayable part of a rollout.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecordedSession {
+    /// Working directory of the original session.
+    pub cwd: Option<PathBuf>,
+    pub steps: Vec<ReplayStep>,
+}
+
+/// Arguments of the `exec_command` tool that matter for a replay.
+#[derive(Debug, Deserialize)]
+struct ExecCommandArgs {
+    cmd: String,
+    #[serde(default)]
+    workdir: Option<String>,
+    #[serde(default)]
+    shell: Option<String>,
+    #[serde(default = "default_login")]
+    login: bool,
+}
+
+fn default_login() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+struct ApplyPatchArgs {
+    input: String,
+}
+
+/// Reads the steps of a rollout file's contents. `shell` turns the scripts
+/// of `shell_command` and `exec_command` calls into commands, as the session
+/// did.
+pub fn parse_recorded_session(contents: &str, shell: &Shell) -> RecordedSession {
+    let mut session = RecordedSession::default();
+    let mut turn = 0;
+    for line in contents.lines() {
+        let Ok(RolloutLine { item, .. }) = serde_json::from_str::<RolloutLine>(line) else {
+            continue;
+        };
+        match item {
+            RolloutItem::SessionMeta(meta) if session.cwd.is_none() => {
+                session.cwd = Some(meta.meta.cwd);
+            }
+            RolloutItem::EventMsg(EventMsg::UserMessage(_)) => turn += 1,
+            RolloutItem::EventMsg(EventMsg::ThreadRolledBack(event)) => {
+                turn = turn.saturating_sub(usize::try_from(event.num_turns).unwrap_or(usize::MAX));
+                session.steps.retain(|step| step.turn <= turn);
+            }
+            RolloutItem::ResponseItem(item) => {
+                if let Some((call_id, action)) = replay_action(item, shell) {
+                    session.steps.push(ReplayStep {
+                        call_id,
+                        turn: turn.max(1),
+                        action,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    session
+}
+
+fn replay_action(item: ResponseItem, shell: &Shell) -> Option<(String, ReplayAction)> {
+    match item {
+        ResponseItem::FunctionCall {
+            name,
+            arguments,
+            call_id,
+            ..
+        } => Some((call_id, function_call_action(name, &arguments, shell))),
+        ResponseItem::LocalShellCall {
+            id,
+            call_id,
+            action: LocalShellAction::Exec(exec),
+            ..
+        } => Some((
+            call_id.or(id).unwrap_or_default(),
+            command_action(
+                exec.command,
+                exec.working_directory.map(PathBuf::from),
+                exec.timeout_ms,
+            ),
+        )),
+        ResponseItem::CustomToolCall {
+            name,
+            input,
+            call_id,
+            ..
+        } => Some((
+            call_id,
+            if name == "apply_patch" {
+                ReplayAction::Patch {
+                    patch: input,
+                    workdir: None,
+                }
+            } else {
+                ReplayAction::Skipped { tool: name }
+            },
+        )),
+        _ => None,
+    }
+}
+
+fn function_call_action(name: String, arguments: &str, shell: &Shell) -> ReplayAction {
+    let action = match name.as_str() {
+        "shell" | "container.exec" => serde_json::from_str::<ShellToolCallParams>(arguments)
+            .ok()
+            .map(|params| {
+                command_action(
+                    params.command,
+                    params.workdir.map(PathBuf::from),
+                    params.timeout_ms,
+                )
+            }),
+        "shell_command" => serde_json::from_str::<ShellCommandToolCallParams>(arguments)
+            .ok()
+            .map(|params| {
+                command_action(
+                    shell.derive_exec_args(&params.command, params.login.unwrap_or(true)),
+                    params.workdir.map(PathBuf::from),
+                    params.timeout_ms,
+                )
+            }),
+        "exec_command" => serde_json::from_str::<ExecCommandArgs>(arguments)
+            .ok()
+            .map(|args| {
+                let command = match &args.shell {
+                    Some(path) => get_shell_by_model_provided_path(&PathBuf::from(path))
+                        .derive_exec_args(&args.cmd, args.login),
+                    None => shell.derive_exec_args(&args.cmd, args.login),
+                };
+                command_action(command, args.workdir.map(PathBuf::from), None)
+            }),
+        "apply_patch" => serde_json::from_str::<ApplyPatchArgs>(arguments)
+            .ok()
+            .map(|args| ReplayAction::Patch {
+                patch: args.input,
+                workdir: None,
+            }),
+        _ => None,
+    };
+    action.unwrap_or(ReplayAction::Skipped { tool: name })
+}
+
+/// A command, or the patch it applies when it is an `apply_patch`
+/// invocation.
+fn command_action(
+    command: Vec<String>,
+    workdir: Option<PathBuf>,
+    timeout_ms: Option<u64>,
+) -> ReplayAction {
+    match maybe_parse_apply_patch(&command) {
+        MaybeApplyPatch::Body(args) => ReplayAction::Patch {
+            patch: args.patch,
+            workdir: match (workdir, args.workdir) {
+                (Some(workdir), Some(inner)) => Some(workdir.join(inner)),
+                (workdir, inner) => workdir.or(inner.map(PathBuf::from)),
+            },
+        },
+        _ => ReplayAction::Command {
+            command,
+            workdir,
+            timeout_ms,
+        },
+    }
+}
+
+/// Path markers of the patch format whose paths [`ReplayContext`] moves into
+/// the workspace.
+const PATCH_PATH_MARKERS: &[&str] = &[
+    "*** Add File: ",
+    "*** Delete File: ",
+    "*** Update File: ",
+    "*** Move to: ",
+];
+
+/// Creates an empty directory to replay a session in, which is kept after
+/// the replay so its results can be inspected.
+pub fn fresh_workspace() -> std::io::Result<PathBuf> {
+    Ok(tempfile::Builder::new()
+        .prefix("codex-replay-")
+        .tempdir()?
+        .keep())
+}
+
+/// Where and how steps run.
+pub struct ReplayContext {
+    /// Directory the session is replayed in.
+    pub workspace: PathBuf,
+    /// Working directory of the original session.
+    pub recorded_cwd: Option<PathBuf>,
+    pub sandbox_policy: SandboxPolicy,
+    /// Executable that applies patches with `--codex-run-as-apply-patch`.
+    pub codex_exe: PathBuf,
+    pub codex_linux_sandbox_exe: Option<PathBuf>,
+    pub env: HashMap<String, String>,
+}
+
+impl ReplayContext {
+    /// The directory a step runs in: the workspace, or the recorded
+    /// directory moved into it; see [`reroot`](Self::reroot).
+    pub fn workdir(&self, recorded: Option<&Path>) -> Result<PathBuf, String> {
+        match recorded {
+            Some(recorded) => self.reroot(recorded),
+            None => Ok(self.workspace.clone()),
+        }
+    }
+
+    /// `path` moved into the workspace. Relative paths are joined to it and
+    /// absolute paths inside the original working directory move to their
+    /// counterpart. Other absolute paths are refused, since replaying them
+    /// would touch files outside the workspace.
+    pub fn reroot(&self, path: &Path) -> Result<PathBuf, String> {
+        if path.is_relative() {
+            return Ok(self.workspace.join(path));
+        }
+        match self
+            .recorded_cwd
+            .as_deref()
+            .and_then(|cwd| path.strip_prefix(cwd).ok())
+        {
+            Some(relative) => Ok(self.workspace.join(relative)),
+            None => Err(format!(
+                "{} is outside the recorded working directory; refusing to replay this step",
+                path.display()
+            )),
+        }
+    }
+
+    /// `patch` with the absolute paths of the files it touches moved into
+    /// the workspace.
+    pub fn reroot_patch(&self, patch: &str) -> Result<String, String> {
+        let mut lines = Vec::new();
+        for line in patch.split('\n') {
+            let header = PATCH_PATH_MARKERS
+                .iter()
+                .find_map(|marker| Some((*marker, line.trim_start().strip_prefix(marker)?)));
+            match header {
+                Some((marker, path)) if Path::new(path.trim()).is_absolute() => {
+                    let path = self.reroot(Path::new(path.trim()))?;
+                    lines.push(format!("{marker}{}", path.display()));
+                }
+                _ => lines.push(line.to_string()),
+            }
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Succeeded,
+    Failed,
+    /// The sandbox blocked the step.
+    Denied,
+    TimedOut,
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StepResult {
+    pub status: StepStatus,
+    pub exit_code: Option<i32>,
+    pub output: String,
+}
+
+/// Runs `step` in `ctx` under its sandbox policy.
+pub async fn run_step(step: &ReplayStep, ctx: &ReplayContext) -> StepResult {
+    let refused = |reason: String| StepResult {
+        status: StepStatus::Failed,
+        exit_code: None,
+        output: reason,
+    };
+    let (command, workdir, timeout_ms, env) = match &step.action {
+        ReplayAction::Skipped { .. } => {
+            return StepResult {
+                status: StepStatus::Skipped,
+                exit_code: None,
+                output: String::new(),
+            };
+        }
+        ReplayAction::Command {
+            command,
+            workdir,
+            timeout_ms,
+        } => (command.clone(), workdir, *timeout_ms, ctx.env.clone()),
+        // Patches run the way the agent applies them, with an empty
+        // environment.
+        ReplayAction::Patch { patch, workdir } => {
+            let patch = match ctx.reroot_patch(patch) {
+                Ok(patch) => patch,
+                Err(reason) => return refused(reason),
+            };
+            (
+                vec![
+                    ctx.codex_exe.to_string_lossy().into_owned(),
+                    CODEX_APPLY_PATCH_ARG1.to_string(),
+                    patch,
+                ],
+                workdir,
+                None,
+                HashMap::new(),
+            )
+        }
+    };
+    let cwd = match ctx.workdir(workdir.as_deref()) {
+        Ok(cwd) => cwd,
+        Err(reason) => return refused(reason),
+    };
+    let params = ExecParams {
+        command,
+        cwd,
+        expiration: timeout_ms.into(),
+        env,
+        sandbox_permissions: SandboxPermissions::UseDefault,
+        justification: None,
+        arg0: None,
+    };
+    match process_exec_tool_call(
+        params,
+        &ctx.sandbox_policy,
+        &ctx.workspace,
+        &ctx.codex_linux_sandbox_exe,
+        None,
+    )
+    .await
+    {
+        Ok(output) => StepResult {
+            status: if output.exit_code == 0 {
+                StepStatus::Succeeded
+            } else {
+                StepStatus::Failed
+            },
+            exit_code: Some(output.exit_code),
+            output: output.aggregated_output.text,
+        },
+        Err(CodexErr::Sandbox(SandboxErr::Denied { output })) => StepResult {
+            status: StepStatus::Denied,
+            exit_code: Some(output.exit_code),
+            output: output.aggregated_output.text,
+        },
+        Err(CodexErr::Sandbox(SandboxErr::Timeout { output })) => StepResult {
+            status: StepStatus::TimedOut,
+            exit_code: None,
+            output: output.aggregated_output.text,
+        },
+        Err(err) => StepResult {
+            status: StepStatus::Failed,
+            exit_code: None,
+            output: err.to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shell::default_user_shell;
+    use pretty_assertions::assert_eq;
+
+    fn line(kind: &str, payload: serde_json::Value) -> String {
+        serde_json::json!({
+            "timestamp": "2025-01-31T10:00:00.000Z",
+            "type": kind,
+            "payload": payload,
+        })
+        .to_string()
+    }
+
+    fn user_message() -> String {
+        line(
+            "event_msg",
+            serde_json::json!({ "type": "user_message", "message": "go" }),
+        )
+    }
+
+    fn function_call(call_id: &str, name: &str, arguments: serde_json::Value) -> String {
+        line(
+            "response_item",
+            serde_json::json!({
+                "type": "function_call",
+                "name": name,
+                "arguments": arguments.to_string(),
+                "call_id": call_id,
+            }),
+        )
+    }
+
+    #[test]
+    fn recorded_calls_become_steps_without_rolled_back_turns() {
+        let shell = default_user_shell();
+        let patch = "*** Begin Patch\n*** Add File: a.txt\n+hi\n*** End Patch";
+        let contents = [
+            user_message(),
+            function_call(
+                "c1",
+                "shell",
+                serde_json::json!({ "command": ["cargo", "fmt"], "workdir": "/repo/crates" }),
+            ),
+            function_call(
+                "c2",
+                "shell",
+                serde_json::json!({ "command": ["apply_patch", patch] }),
+            ),
+            function_call("c3", "read_file", serde_json::json!({ "path": "a.txt" })),
+            user_message(),
+            function_call(
+                "c4",
+                "shell_command",
+                serde_json::json!({ "command": "rm -rf target" }),
+            ),
+            line(
+                "event_msg",
+                serde_json::json!({ "type": "thread_rolled_back", "num_turns": 1 }),
+            ),
+            user_message(),
+            function_call(
+                "c5",
+                "shell_command",
+                serde_json::json!({ "command": "cargo test", "login": false }),
+            ),
+        ]
+        .join("\n");
+
+        let session = parse_recorded_session(&contents, &shell);
+
+        let step = |call_id: &str, turn, action| ReplayStep {
+            call_id: call_id.to_string(),
+            turn,
+            action,
+        };
+        assert_eq!(
+            session.steps,
+            vec![
+                step(
+                    "c1",
+                    1,
+                    ReplayAction::Command {
+                        command: vec!["cargo".to_string(), "fmt".to_string()],
+                        workdir: Some(PathBuf::from("/repo/crates")),
+                        timeout_ms: None,
+                    }
+                ),
+                step(
+                    "c2",
+                    1,
+                    ReplayAction::Patch {
+                        patch: patch.to_string(),
+                        workdir: None,
+                    }
+                ),
+                step(
+                    "c3",
+                    1,
+                    ReplayAction::Skipped {
+                        tool: "read_file".to_string(),
+                    }
+                ),
+                step(
+                    "c5",
+                    2,
+                    ReplayAction::Command {
+                        command: shell.derive_exec_args("cargo test", false),
+                        workdir: None,
+                        timeout_ms: None,
+                    }
+                ),
+            ]
+        );
+        let described: Vec<String> = session
+            .steps
+            .iter()
+            .map(|step| step.action.describe())
+            .collect();
+        assert_eq!(
+            described[..3],
+            ["cargo fmt", "apply_patch: add a.txt", "skip read_file"]
+        );
+    }
+
+    fn replay_context() -> ReplayContext {
+        ReplayContext {
+            workspace: PathBuf::from("/replay"),
+            recorded_cwd: Some(PathBuf::from("/repo")),
+            sandbox_policy: SandboxPolicy::ReadOnly,
+            codex_exe: PathBuf::from("codex"),
+            codex_linux_sandbox_exe: None,
+            env: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn recorded_directories_move_to_the_workspace() {
+        let ctx = replay_context();
+        assert_eq!(ctx.workdir(None), Ok(PathBuf::from("/replay")));
+        assert_eq!(
+            ctx.workdir(Some(Path::new("/repo/crates/core"))),
+            Ok(PathBuf::from("/replay/crates/core"))
+        );
+        assert_eq!(
+            ctx.workdir(Some(Path::new("web"))),
+            Ok(PathBuf::from("/replay/web"))
+        );
+        assert!(ctx.workdir(Some(Path::new("/tmp"))).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn absolute_patch_paths_move_to_the_workspace_or_are_refused() {
+        let ctx = replay_context();
+        let patch = "*** Begin Patch\n*** Update File: /repo/src/lib.rs\n*** Move to: src/main.rs\n@@\n-a\n+b\n*** Add File: notes.txt\n+hi\n*** End Patch";
+        assert_eq!(
+            ctx.reroot_patch(patch),
+            Ok(patch.replace("/repo/src/lib.rs", "/replay/src/lib.rs"))
+        );
+        assert!(
+            ctx.reroot_patch("*** Begin Patch\n*** Delete File: /etc/hosts\n*** End Patch")
+                .is_err()
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn steps_outside_the_recorded_directory_are_refused() {
+        let step = ReplayStep {
+            call_id: "c1".to_string(),
+            turn: 1,
+            action: ReplayAction::Command {
+                command: vec!["touch".to_string(), "x".to_string()],
+                workdir: Some(PathBuf::from("/elsewhere")),
+                timeout_ms: None,
+            },
+        };
+
+        let result = run_step(&step, &replay_context()).await;
+
+        assert_eq!(result.status, StepStatus::Failed);
+        assert_eq!(result.exit_code, None);
+        assert!(result.output.contains("/elsewhere"), "{}", result.output);
+    }
+}
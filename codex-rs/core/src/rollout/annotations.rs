@@ -0,0 +1,242 @@
+//! Annotations reviewers attach to the turns of a recorded session.
+//!
+//! `rollout-<time>-<id>.jsonl` gets a `rollout-<time>-<id>.annotations.ndjson`
+//! sibling with one [`TurnAnnotation`] per line: a comment, a rating or a
+//! label on one turn. Its name must not end in `.jsonl`, or session listings
+//! would take it for a rollout. The rollout itself is never modified, so annotating a
+//! session that is still running is safe. Turns are numbered from 1 in the
+//! order the user started them, counting only the turns left after any
+//! rollback, the same way `thread/resume` numbers them (`turn-1`, ...).
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+use tokio::io::AsyncWriteExt;
+use uuid::Uuid;
+
+/// Replaces the `.jsonl` extension of a rollout to name its annotations.
+pub const ANNOTATIONS_SUFFIX: &str = ".annotations.ndjson";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnnotationBody {
+    Comment {
+        text: String,
+    },
+    /// Positive for a good turn and negative for a bad one; the scale is
+    /// up to the reviewer.
+    Rating {
+        score: i32,
+    },
+    Label {
+        label: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TurnAnnotation {
+    pub id: String,
+    /// 1-based number of the annotated turn.
+    pub turn: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    pub created_at: String,
+    #[serde(flatten)]
+    pub body: AnnotationBody,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AnnotationError {
+    #[error("turn {turn} does not exist; the session has {turns} turn(s)")]
+    TurnNotFound { turn: u32, turns: u32 },
+    #[error("no annotation with id {0}")]
+    NotFound(String),
+    #[error("annotation is empty")]
+    Empty,
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+pub fn annotations_path(rollout_path: &Path) -> PathBuf {
+    let name = rollout_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let stem = name.strip_suffix(".jsonl").unwrap_or(&name);
+    rollout_path.with_file_name(format!("{stem}{ANNOTATIONS_SUFFIX}"))
+}
+
+/// Annotations of the session recorded at `rollout_path`, oldest first.
+/// Lines that cannot be parsed are skipped.
+pub async fn read_annotations(rollout_path: &Path) -> io::Result<Vec<TurnAnnotation>> {
+    let contents = match tokio::fs::read_to_string(annotations_path(rollout_path)).await {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Attaches `body` to turn `turn` of the session recorded at `rollout_path`.
+pub async fn add_annotation(
+    rollout_path: &Path,
+    turn: u32,
+    author: Option<String>,
+    body: AnnotationBody,
+) -> Result<TurnAnnotation, AnnotationError> {
+    let is_empty = match &body {
+        AnnotationBody::Comment { text } => text.trim().is_empty(),
+        AnnotationBody::Label { label } => label.trim().is_empty(),
+        AnnotationBody::Rating { .. } => false,
+    };
+    if is_empty {
+        return Err(AnnotationError::Empty);
+    }
+    let turns = count_turns(rollout_path).await?;
+    if turn == 0 || turn > turns {
+        return Err(AnnotationError::TurnNotFound { turn, turns });
+    }
+
+    let annotation = TurnAnnotation {
+        id: Uuid::new_v4().to_string(),
+        turn,
+        author: author.filter(|author| !author.trim().is_empty()),
+        created_at: OffsetDateTime::now_utc()
+            .format(&Rfc3339)
+            .map_err(|err| io::Error::other(format!("failed to format timestamp: {err}")))?,
+        body,
+    };
+    let mut line = serde_json::to_vec(&annotation)?;
+    line.push(b'\n');
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(annotations_path(rollout_path))
+        .await?;
+    file.write_all(&line).await?;
+    file.flush().await?;
+    Ok(annotation)
+}
+
+/// Deletes the annotation whose id is `id`.
+pub async fn remove_annotation(rollout_path: &Path, id: &str) -> Result<(), AnnotationError> {
+    let mut annotations = read_annotations(rollout_path).await?;
+    let before = annotations.len();
+    annotations.retain(|annotation| annotation.id != id);
+    if annotations.len() == before {
+        return Err(AnnotationError::NotFound(id.to_string()));
+    }
+    let mut contents = Vec::new();
+    for annotation in &annotations {
+        serde_json::to_writer(&mut contents, annotation)?;
+        contents.push(b'\n');
+    }
+    let path = annotations_path(rollout_path);
+    let tmp = path.with_extension("jsonl.tmp");
+    tokio::fs::write(&tmp, contents).await?;
+    tokio::fs::rename(&tmp, &path).await?;
+    Ok(())
+}
+
+/// Number of turns in the rollout, after rollbacks.
+async fn count_turns(rollout_path: &Path) -> io::Result<u32> {
+    let contents = tokio::fs::read_to_string(rollout_path).await?;
+    let mut turns: u32 = 0;
+    for line in contents.lines() {
+        let Ok(line) = serde_json::from_str::<RolloutLine>(line) else {
+            continue;
+        };
+        match line.item {
+            RolloutItem::EventMsg(EventMsg::UserMessage(_)) => turns += 1,
+            RolloutItem::EventMsg(EventMsg::ThreadRolledBack(event)) => {
+                turns = turns.saturating_sub(event.num_turns);
+            }
+            _ => {}
+        }
+    }
+    Ok(turns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn user_message(text: &str) -> String {
+        format!(
+            r#"{{"timestamp":"2025-01-31T10:00:00.000Z","type":"event_msg","payload":{{"type":"user_message","message":"{text}"}}}}"#
+        )
+    }
+
+    #[tokio::test]
+    async fn annotations_are_attached_to_existing_turns() -> Result<(), AnnotationError> {
+        let dir = tempfile::tempdir()?;
+        let rollout = dir.path().join("rollout-2025-01-31T10-00-00-x.jsonl");
+        let rolled_back = r#"{"timestamp":"2025-01-31T10:00:00.000Z","type":"event_msg","payload":{"type":"thread_rolled_back","num_turns":1}}"#;
+        let lines = [
+            user_message("one"),
+            user_message("two"),
+            rolled_back.to_string(),
+            user_message("three"),
+        ];
+        std::fs::write(&rollout, lines.join("\n"))?;
+
+        let comment = add_annotation(
+            &rollout,
+            2,
+            Some("reviewer".to_string()),
+            AnnotationBody::Comment {
+                text: "Should have run the tests first".to_string(),
+            },
+        )
+        .await?;
+        let rating = add_annotation(&rollout, 1, None, AnnotationBody::Rating { score: 1 }).await?;
+        assert_eq!(
+            read_annotations(&rollout).await?,
+            vec![comment.clone(), rating]
+        );
+        assert_eq!(
+            annotations_path(&rollout),
+            dir.path()
+                .join("rollout-2025-01-31T10-00-00-x.annotations.ndjson")
+        );
+
+        assert!(matches!(
+            add_annotation(&rollout, 3, None, AnnotationBody::Rating { score: -1 }).await,
+            Err(AnnotationError::TurnNotFound { turn: 3, turns: 2 })
+        ));
+        assert!(matches!(
+            add_annotation(
+                &rollout,
+                1,
+                None,
+                AnnotationBody::Label {
+                    label: " ".to_string()
+                }
+            )
+            .await,
+            Err(AnnotationError::Empty)
+        ));
+
+        remove_annotation(&rollout, &comment.id).await?;
+        assert_eq!(read_annotations(&rollout).await?.len(), 1);
+        assert!(matches!(
+            remove_annotation(&rollout, &comment.id).await,
+            Err(AnnotationError::NotFound(_))
+        ));
+        Ok(())
+    }
+}
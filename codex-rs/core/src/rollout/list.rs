@@ -424,7 +424,7 @@ async fn read_head_summary(path: &Path, head_limit: usize) -> io::Result<HeadTai
                     summary.head.push(val);
                 }
             }
-            RolloutItem::TurnContext(_) => {
+            RolloutItem::TurnContext(_) | RolloutItem::TurnDiff(_) => {
                 // Not included in `head`; skip.
             }
             RolloutItem::Compacted(_) => {
@@ -479,9 +479,12 @@ pub async fn find_thread_path_by_id_str(
     if !root.exists() {
         return Ok(None);
     }
+    // The rollout shares its id with its `.meta.json` and
+    // `.annotations.ndjson` sidecars, so ask for all of them and keep the
+    // rollout, the only one ending in `.jsonl`.
     // This is safe because we know the values are valid.
     #[allow(clippy::unwrap_used)]
-    let limit = NonZero::new(1).unwrap();
+    let limit = NonZero::new(3).unwrap();
     // This is safe because we know the values are valid.
     #[allow(clippy::unwrap_used)]
     let threads = NonZero::new(2).unwrap();
@@ -504,6 +507,6 @@ pub async fn find_thread_path_by_id_str(
     Ok(results
         .matches
         .into_iter()
-        .next()
+        .find(|m| m.path.ends_with(".jsonl"))
         .map(|m| root.join(m.path)))
 }
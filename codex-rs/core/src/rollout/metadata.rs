@@ -0,0 +1,501 @@
+//! Metadata kept next to each rollout so sessions can be listed without
+//! reading them in full.
+//!
+//! `rollout-<time>-<id>.jsonl` gets a `rollout-<time>-<id>.meta.json` sibling
+//! with the session's title, project, last activity, token totals and
+//! status. The rollout writer keeps it current as items are recorded;
+//! rollouts recorded before sidecars existed get one the first time they are
+//! listed. A title set with [`rename_thread`] and tags set with
+//! [`tag_thread`] are kept by the writer.
+
+use std::collections::BTreeSet;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::TokenCountEvent;
+use codex_protocol::protocol::TokenUsage;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::SESSIONS_SUBDIR;
+use super::annotations::annotations_path;
+
+/// Replaces the `.jsonl` extension of a rollout to name its sidecar.
+pub const METADATA_SUFFIX: &str = ".meta.json";
+
+/// Longest title derived from the first user message, in characters.
+const MAX_TITLE_CHARS: usize = 60;
+
+/// Openings dropped from the first user message when titling a session,
+/// matched case-insensitively and repeatedly (`hey, can you please ...`).
+const TITLE_FILLER_PREFIXES: &[&str] = &[
+    "hey codex",
+    "hi codex",
+    "hey",
+    "hi",
+    "hello",
+    "please",
+    "can you",
+    "could you",
+    "would you",
+    "i want you to",
+    "i'd like you to",
+    "i need you to",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThreadStatus {
+    /// A process is recording the session. Stays set if that process
+    /// crashed, until the session is resumed and closed again.
+    Active,
+    #[default]
+    Closed,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThreadMetadata {
+    pub thread_id: String,
+    /// Set by `codex sessions rename`, or else generated from the first
+    /// user message.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Lowercase labels added with `/tag` or `codex sessions tag`, sorted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Working directory the session started in.
+    #[serde(default)]
+    pub project: PathBuf,
+    #[serde(default)]
+    pub created_at: String,
+    /// Time the last item was recorded.
+    #[serde(default)]
+    pub last_activity: String,
+    /// Token totals as last reported by the model provider.
+    #[serde(default)]
+    pub tokens: TokenUsage,
+    #[serde(default)]
+    pub status: ThreadStatus,
+}
+
+/// A recorded session and its metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ThreadSummary {
+    pub rollout_path: PathBuf,
+    #[serde(flatten)]
+    pub metadata: ThreadMetadata,
+}
+
+impl ThreadMetadata {
+    /// Updates the metadata with an item recorded at `timestamp`.
+    pub(crate) fn observe(&mut self, item: &RolloutItem, timestamp: &str) {
+        match item {
+            RolloutItem::SessionMeta(line) => {
+                self.thread_id = line.meta.id.to_string();
+                self.project = line.meta.cwd.clone();
+                self.created_at = line.meta.timestamp.clone();
+            }
+            RolloutItem::EventMsg(EventMsg::UserMessage(event)) if self.title.is_none() => {
+                self.title = generate_title(&event.message);
+            }
+            RolloutItem::EventMsg(EventMsg::TokenCount(TokenCountEvent {
+                info: Some(info),
+                ..
+            })) => {
+                self.tokens = info.total_token_usage.clone();
+            }
+            _ => {}
+        }
+        self.last_activity = timestamp.to_string();
+    }
+
+    /// Builds the metadata of a closed session from its rollout.
+    pub async fn from_rollout(rollout_path: &Path) -> io::Result<Self> {
+        let contents = tokio::fs::read_to_string(rollout_path).await?;
+        let mut metadata = Self::default();
+        for line in contents.lines() {
+            if let Ok(line) = serde_json::from_str::<RolloutLine>(line) {
+                metadata.observe(&line.item, &line.timestamp);
+            }
+        }
+        Ok(metadata)
+    }
+}
+
+/// A short title for a session that started with `message`: its first
+/// line without greetings and requests for help, capitalized, and cut at a
+/// word boundary to [`MAX_TITLE_CHARS`].
+fn generate_title(message: &str) -> Option<String> {
+    let mut line = message
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())?;
+    while let Some(rest) = TITLE_FILLER_PREFIXES.iter().find_map(|prefix| {
+        let head = line.get(..prefix.len())?;
+        let rest = &line[prefix.len()..];
+        (head.eq_ignore_ascii_case(prefix) && !rest.starts_with(char::is_alphanumeric))
+            .then_some(rest.trim_start_matches([',', '!', ':', ' ']))
+    }) {
+        if rest.is_empty() {
+            break;
+        }
+        line = rest;
+    }
+    let line = line.trim_end_matches(['?', '.', '!', ' ']);
+    let mut chars = line.chars();
+    let first = chars.next()?;
+    let title: String = first.to_uppercase().chain(chars).collect();
+    if title.chars().count() <= MAX_TITLE_CHARS {
+        return Some(title);
+    }
+    let cut: String = title.chars().take(MAX_TITLE_CHARS - 1).collect();
+    let cut = match cut.rfind(' ') {
+        Some(space) if space > MAX_TITLE_CHARS / 2 => &cut[..space],
+        _ => cut.as_str(),
+    };
+    Some(format!("{}…", cut.trim_end_matches([',', ' '])))
+}
+
+/// The tag `tag` is stored as: lowercase, without a leading `#`. `None` for
+/// an empty tag.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().trim_start_matches('#').to_lowercase();
+    (!tag.is_empty()).then_some(tag)
+}
+
+/// Which sessions `codex sessions list` shows.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ThreadFilter {
+    /// Tags a session must all have.
+    pub tags: Vec<String>,
+    /// Text the id, title, project or a tag must contain, ignoring case.
+    pub text: Option<String>,
+}
+
+impl ThreadFilter {
+    pub fn matches(&self, metadata: &ThreadMetadata) -> bool {
+        let has_tags = self
+            .tags
+            .iter()
+            .filter_map(|tag| normalize_tag(tag))
+            .all(|tag| metadata.tags.contains(&tag));
+        let has_text = self.text.as_deref().is_none_or(|text| {
+            let text = text.to_lowercase();
+            metadata.thread_id.contains(&text)
+                || metadata
+                    .title
+                    .as_deref()
+                    .is_some_and(|title| title.to_lowercase().contains(&text))
+                || metadata
+                    .project
+                    .to_string_lossy()
+                    .to_lowercase()
+                    .contains(&text)
+                || metadata.tags.iter().any(|tag| tag.contains(&text))
+        });
+        has_tags && has_text
+    }
+}
+
+pub fn metadata_path(rollout_path: &Path) -> PathBuf {
+    let name = rollout_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let stem = name.strip_suffix(".jsonl").unwrap_or(&name);
+    rollout_path.with_file_name(format!("{stem}{METADATA_SUFFIX}"))
+}
+
+/// Reads the sidecar of `rollout_path`, or `None` if it has none.
+pub async fn read_metadata(rollout_path: &Path) -> io::Result<Option<ThreadMetadata>> {
+    match tokio::fs::read(metadata_path(rollout_path)).await {
+        Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Replaces the sidecar of `rollout_path` without leaving a partial file
+/// behind for readers.
+pub async fn write_metadata(rollout_path: &Path, metadata: &ThreadMetadata) -> io::Result<()> {
+    let path = metadata_path(rollout_path);
+    let tmp = path.with_extension("json.tmp");
+    tokio::fs::write(&tmp, serde_json::to_vec_pretty(metadata)?).await?;
+    tokio::fs::rename(&tmp, &path).await
+}
+
+/// The sidecar of `rollout_path`, created from the rollout if missing or
+/// unreadable.
+pub async fn load_metadata(rollout_path: &Path) -> io::Result<ThreadMetadata> {
+    if let Ok(Some(metadata)) = read_metadata(rollout_path).await {
+        return Ok(metadata);
+    }
+    let metadata = ThreadMetadata::from_rollout(rollout_path).await?;
+    write_metadata(rollout_path, &metadata).await?;
+    Ok(metadata)
+}
+
+/// Every session under `codex_home`, most recently active first.
+pub async fn list_thread_metadata(codex_home: &Path) -> io::Result<Vec<ThreadSummary>> {
+    let mut dirs = vec![codex_home.join(SESSIONS_SUBDIR)];
+    let mut threads = Vec::new();
+    while let Some(dir) = dirs.pop() {
+        let mut entries = match tokio::fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if entry.file_type().await?.is_dir() {
+                dirs.push(path);
+                continue;
+            }
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if !name.starts_with("rollout-") || !name.ends_with(".jsonl") {
+                continue;
+            }
+            let metadata = load_metadata(&path).await?;
+            threads.push(ThreadSummary {
+                rollout_path: path,
+                metadata,
+            });
+        }
+    }
+    threads.sort_by(|a, b| b.metadata.last_activity.cmp(&a.metadata.last_activity));
+    Ok(threads)
+}
+
+/// Sets the title shown for a session. An empty title goes back to the one
+/// derived from the first user message.
+pub async fn rename_thread(rollout_path: &Path, title: &str) -> io::Result<ThreadMetadata> {
+    let mut metadata = load_metadata(rollout_path).await?;
+    let title = title.trim();
+    metadata.title = if title.is_empty() {
+        ThreadMetadata::from_rollout(rollout_path).await?.title
+    } else {
+        Some(title.to_string())
+    };
+    write_metadata(rollout_path, &metadata).await?;
+    Ok(metadata)
+}
+
+/// Adds and removes tags of a session.
+pub async fn tag_thread(
+    rollout_path: &Path,
+    add: &[String],
+    remove: &[String],
+) -> io::Result<ThreadMetadata> {
+    let mut metadata = load_metadata(rollout_path).await?;
+    let remove: Vec<String> = remove.iter().filter_map(|tag| normalize_tag(tag)).collect();
+    let tags: BTreeSet<String> = metadata
+        .tags
+        .drain(..)
+        .chain(add.iter().filter_map(|tag| normalize_tag(tag)))
+        .filter(|tag| !remove.contains(tag))
+        .collect();
+    metadata.tags = tags.into_iter().collect();
+    write_metadata(rollout_path, &metadata).await?;
+    Ok(metadata)
+}
+
+/// Deletes a session's rollout, metadata and annotations.
+pub async fn remove_thread(rollout_path: &Path) -> io::Result<()> {
+    tokio::fs::remove_file(rollout_path).await?;
+    for sidecar in [metadata_path(rollout_path), annotations_path(rollout_path)] {
+        match tokio::fs::remove_file(sidecar).await {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rollout::annotations::AnnotationBody;
+    use crate::rollout::annotations::add_annotation;
+    use crate::rollout::list::find_thread_path_by_id_str;
+    use codex_protocol::ThreadId;
+    use codex_protocol::protocol::SessionMeta;
+    use codex_protocol::protocol::SessionMetaLine;
+    use codex_protocol::protocol::TokenUsageInfo;
+    use codex_protocol::protocol::UserMessageEvent;
+    use pretty_assertions::assert_eq;
+
+    fn rollout_line(timestamp: &str, item: RolloutItem) -> String {
+        serde_json::to_string(&RolloutLine {
+            timestamp: timestamp.to_string(),
+            item,
+        })
+        .expect("serialize rollout line")
+    }
+
+    fn write_rollout(dir: &Path, id: ThreadId, message: &str) -> PathBuf {
+        let day = dir.join(SESSIONS_SUBDIR).join("2025/01/31");
+        std::fs::create_dir_all(&day).expect("create sessions dir");
+        let path = day.join(format!("rollout-2025-01-31T10-00-00-{id}.jsonl"));
+        let lines = [
+            rollout_line(
+                "2025-01-31T10:00:00.000Z",
+                RolloutItem::SessionMeta(SessionMetaLine {
+                    meta: SessionMeta {
+                        id,
+                        timestamp: "2025-01-31T10:00:00.000Z".to_string(),
+                        cwd: PathBuf::from("/work/app"),
+                        ..Default::default()
+                    },
+                    git: None,
+                }),
+            ),
+            rollout_line(
+                "2025-01-31T10:00:01.000Z",
+                RolloutItem::EventMsg(EventMsg::UserMessage(UserMessageEvent {
+                    message: message.to_string(),
+                    images: None,
+                })),
+            ),
+            rollout_line(
+                "2025-01-31T10:05:00.000Z",
+                RolloutItem::EventMsg(EventMsg::TokenCount(TokenCountEvent {
+                    info: Some(TokenUsageInfo {
+                        total_token_usage: TokenUsage {
+                            total_tokens: 1200,
+                            ..Default::default()
+                        },
+                        last_token_usage: TokenUsage::default(),
+                        model_context_window: None,
+                    }),
+                    rate_limits: None,
+                })),
+            ),
+        ];
+        std::fs::write(&path, lines.join("\n")).expect("write rollout");
+        path
+    }
+
+    #[tokio::test]
+    async fn lists_sessions_from_their_rollouts() -> io::Result<()> {
+        let home = tempfile::tempdir()?;
+        let id = ThreadId::new();
+        let path = write_rollout(
+            home.path(),
+            id,
+            "\n  Fix the flaky login test\nIt fails on CI",
+        );
+
+        let threads = list_thread_metadata(home.path()).await?;
+        assert_eq!(
+            threads,
+            vec![ThreadSummary {
+                rollout_path: path.clone(),
+                metadata: ThreadMetadata {
+                    thread_id: id.to_string(),
+                    title: Some("Fix the flaky login test".to_string()),
+                    tags: Vec::new(),
+                    project: PathBuf::from("/work/app"),
+                    created_at: "2025-01-31T10:00:00.000Z".to_string(),
+                    last_activity: "2025-01-31T10:05:00.000Z".to_string(),
+                    tokens: TokenUsage {
+                        total_tokens: 1200,
+                        ..Default::default()
+                    },
+                    status: ThreadStatus::Closed,
+                },
+            }]
+        );
+        assert!(metadata_path(&path).exists());
+
+        let renamed = rename_thread(&path, "Login flake").await?;
+        assert_eq!(renamed.title.as_deref(), Some("Login flake"));
+        assert_eq!(
+            read_metadata(&path).await?.and_then(|m| m.title).as_deref(),
+            Some("Login flake")
+        );
+        let restored = rename_thread(&path, " ").await?;
+        assert_eq!(restored.title.as_deref(), Some("Fix the flaky login test"));
+
+        let tagged = tag_thread(&path, &["#Backend".to_string(), "auth".to_string()], &[]).await?;
+        assert_eq!(tagged.tags, vec!["auth".to_string(), "backend".to_string()]);
+        let tagged = tag_thread(&path, &[], &["auth".to_string()]).await?;
+        assert_eq!(tagged.tags, vec!["backend".to_string()]);
+
+        remove_thread(&path).await?;
+        assert!(!path.exists());
+        assert!(!metadata_path(&path).exists());
+        assert_eq!(list_thread_metadata(home.path()).await?, Vec::new());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn annotated_sessions_are_listed_once() -> anyhow::Result<()> {
+        let home = tempfile::tempdir()?;
+        let id = ThreadId::new();
+        let path = write_rollout(home.path(), id, "Fix the flaky login test");
+        add_annotation(&path, 1, None, AnnotationBody::Rating { score: 1 }).await?;
+        assert!(annotations_path(&path).exists());
+
+        let threads = list_thread_metadata(home.path()).await?;
+        assert_eq!(
+            threads
+                .into_iter()
+                .map(|thread| thread.rollout_path)
+                .collect::<Vec<_>>(),
+            vec![path.clone()]
+        );
+        assert_eq!(
+            find_thread_path_by_id_str(home.path(), &id.to_string()).await?,
+            Some(path)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn titles_are_generated_from_the_first_message() {
+        assert_eq!(
+            generate_title("hey, can you please fix the login test?\nIt fails on CI").as_deref(),
+            Some("Fix the login test")
+        );
+        assert_eq!(
+            generate_title("Highlight errors in red.").as_deref(),
+            Some("Highlight errors in red")
+        );
+        assert_eq!(generate_title("Please").as_deref(), Some("Please"));
+        assert_eq!(generate_title("  \n "), None);
+
+        let title =
+            generate_title(&"refactor the session metadata writer ".repeat(4)).expect("title");
+        assert_eq!(
+            title,
+            "Refactor the session metadata writer refactor the session…"
+        );
+        assert!(title.chars().count() <= MAX_TITLE_CHARS);
+    }
+
+    #[test]
+    fn filters_match_tags_and_text() {
+        let metadata = ThreadMetadata {
+            thread_id: "0194d1a0".to_string(),
+            title: Some("Fix the login test".to_string()),
+            project: PathBuf::from("/work/app"),
+            tags: vec!["auth".to_string(), "backend".to_string()],
+            ..Default::default()
+        };
+        let filter = |tags: &[&str], text: Option<&str>| ThreadFilter {
+            tags: tags.iter().map(ToString::to_string).collect(),
+            text: text.map(str::to_string),
+        };
+        assert!(filter(&[], None).matches(&metadata));
+        assert!(filter(&["#Backend", "auth"], None).matches(&metadata));
+        assert!(!filter(&["backend", "ui"], None).matches(&metadata));
+        assert!(filter(&[], Some("LOGIN")).matches(&metadata));
+        assert!(filter(&["backend"], Some("work/app")).matches(&metadata));
+        assert!(!filter(&[], Some("signup")).matches(&metadata));
+    }
+}
@@ -7,8 +7,10 @@ pub const ARCHIVED_SESSIONS_SUBDIR: &str = "archived_sessions";
 pub const INTERACTIVE_SESSION_SOURCES: &[SessionSource] =
     &[SessionSource::Cli, SessionSource::VSCode];
 
+pub mod annotations;
 pub(crate) mod error;
 pub mod list;
+pub mod metadata;
 pub(crate) mod policy;
 pub mod recorder;
 pub(crate) mod truncation;
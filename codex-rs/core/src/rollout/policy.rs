@@ -9,9 +9,10 @@ pub(crate) fn is_persisted_response_item(item: &RolloutItem) -> bool {
         RolloutItem::ResponseItem(item) => should_persist_response_item(item),
         RolloutItem::EventMsg(ev) => should_persist_event_msg(ev),
         // Persist Codex executive markers so we can analyze flows (e.g., compaction, API turns).
-        RolloutItem::Compacted(_) | RolloutItem::TurnContext(_) | RolloutItem::SessionMeta(_) => {
-            true
-        }
+        RolloutItem::Compacted(_)
+        | RolloutItem::TurnContext(_)
+        | RolloutItem::TurnDiff(_)
+        | RolloutItem::SessionMeta(_) => true,
     }
 }
 
@@ -46,7 +47,11 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::EnteredReviewMode(_)
         | EventMsg::ExitedReviewMode(_)
         | EventMsg::ThreadRolledBack(_)
+        | EventMsg::VariablesUpdated(_)
+        | EventMsg::EnvironmentFingerprint(_)
+        | EventMsg::ContextSources(_)
         | EventMsg::UndoCompleted(_)
+        | EventMsg::PullRequestPublished(_)
         | EventMsg::TurnAborted(_) => true,
         EventMsg::Error(_)
         | EventMsg::Warning(_)
@@ -58,6 +63,8 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::AgentReasoningSectionBreak(_)
         | EventMsg::RawResponseItem(_)
         | EventMsg::SessionConfigured(_)
+        | EventMsg::ConfigOverrideApplied(_)
+        | EventMsg::ModelSettingsChanged(_)
         | EventMsg::McpToolCallBegin(_)
         | EventMsg::McpToolCallEnd(_)
         | EventMsg::WebSearchBegin(_)
@@ -79,11 +86,24 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::McpListToolsResponse(_)
         | EventMsg::McpStartupUpdate(_)
         | EventMsg::McpStartupComplete(_)
+        | EventMsg::McpServerDown(_)
+        | EventMsg::McpServerRestarted(_)
         | EventMsg::ListCustomPromptsResponse(_)
         | EventMsg::ListSkillsResponse(_)
+        | EventMsg::ListHooksResponse(_)
+        | EventMsg::UserCommand(_)
         | EventMsg::PlanUpdate(_)
         | EventMsg::ShutdownComplete
         | EventMsg::ViewImageToolCall(_)
+        | EventMsg::ContextRepaired(_)
+        | EventMsg::OutputBudget(_)
+        | EventMsg::RedactionApplied(_)
+        | EventMsg::InjectionSuspected(_)
+        | EventMsg::GuardrailTripped(_)
+        | EventMsg::WatchdogFired(_)
+        | EventMsg::ProjectDocsUpdated(_)
+        | EventMsg::WatchedFilesChanged(_)
+        | EventMsg::WorkspaceSnapshot(_)
         | EventMsg::DeprecationNotice(_)
         | EventMsg::ItemStarted(_)
         | EventMsg::ItemCompleted(_)
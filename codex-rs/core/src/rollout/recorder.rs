@@ -5,6 +5,7 @@ use std::fs::{self};
 use std::io::Error as IoError;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use codex_protocol::ThreadId;
 use serde_json::Value;
@@ -15,6 +16,7 @@ use tokio::io::AsyncWriteExt;
 use tokio::sync::mpsc::Sender;
 use tokio::sync::mpsc::{self};
 use tokio::sync::oneshot;
+use tokio::time::MissedTickBehavior;
 use tracing::info;
 use tracing::warn;
 
@@ -22,6 +24,11 @@ use super::SESSIONS_SUBDIR;
 use super::list::Cursor;
 use super::list::ThreadsPage;
 use super::list::get_threads;
+use super::metadata::ThreadMetadata;
+use super::metadata::ThreadStatus;
+use super::metadata::load_metadata;
+use super::metadata::read_metadata;
+use super::metadata::write_metadata;
 use super::policy::is_persisted_response_item;
 use crate::config::Config;
 use crate::default_client::originator;
@@ -34,8 +41,9 @@ use codex_protocol::protocol::SessionMeta;
 use codex_protocol::protocol::SessionMetaLine;
 use codex_protocol::protocol::SessionSource;
 
-/// Records all [`ResponseItem`]s for a session and flushes them to disk after
-/// every update.
+/// Records all [`ResponseItem`]s for a session. Lines are written in batches
+/// and fsynced every `persistence.flush_interval_ms`, and whenever
+/// [`RolloutRecorder::flush`] or [`RolloutRecorder::shutdown`] is called.
 ///
 /// Rollouts are recorded as JSONL and can be inspected with tools such as:
 ///
@@ -148,6 +156,7 @@ impl RolloutRecorder {
                         instructions,
                         source,
                         model_provider: Some(config.model_provider_id.clone()),
+                        labels: config.session_labels.clone(),
                     }),
                 )
             }
@@ -160,6 +169,15 @@ impl RolloutRecorder {
                 None,
             ),
         };
+        let metadata = if meta.is_some() {
+            ThreadMetadata::default()
+        } else {
+            load_metadata(&rollout_path).await.unwrap_or_else(|err| {
+                warn!("failed to read session metadata for {rollout_path:?}: {err}");
+                ThreadMetadata::default()
+            })
+        };
+        let sidecar = MetadataSidecar::new(rollout_path.clone(), metadata);
 
         // Clone the cwd for the spawned task to collect git info asynchronously
         let cwd = config.cwd.clone();
@@ -172,7 +190,8 @@ impl RolloutRecorder {
         // Spawn a Tokio task that owns the file handle and performs async
         // writes. Using `tokio::fs::File` keeps everything on the async I/O
         // driver instead of blocking the runtime.
-        tokio::task::spawn(rollout_writer(file, rx, meta, cwd));
+        let flush_interval = Duration::from_millis(config.persistence.flush_interval_ms);
+        tokio::task::spawn(rollout_writer(file, rx, meta, cwd, flush_interval, sidecar));
 
         Ok(Self { tx, rollout_path })
     }
@@ -248,6 +267,9 @@ impl RolloutRecorder {
                     RolloutItem::TurnContext(item) => {
                         items.push(RolloutItem::TurnContext(item));
                     }
+                    RolloutItem::TurnDiff(item) => {
+                        items.push(RolloutItem::TurnDiff(item));
+                    }
                     RolloutItem::EventMsg(_ev) => {
                         items.push(RolloutItem::EventMsg(_ev));
                     }
@@ -348,8 +370,10 @@ async fn rollout_writer(
     mut rx: mpsc::Receiver<RolloutCmd>,
     mut meta: Option<SessionMeta>,
     cwd: std::path::PathBuf,
+    flush_interval: Duration,
+    mut sidecar: MetadataSidecar,
 ) -> std::io::Result<()> {
-    let mut writer = JsonlWriter { file };
+    let mut writer = JsonlWriter::new(file);
 
     // If we have a meta, collect git info asynchronously and write meta first
     if let Some(session_meta) = meta.take() {
@@ -359,63 +383,271 @@ async fn rollout_writer(
             git: git_info,
         };
 
-        // Write the SessionMeta as the first item in the file, wrapped in a rollout line
-        writer
-            .write_rollout_item(RolloutItem::SessionMeta(session_meta_line))
-            .await?;
+        // Write the SessionMeta as the first item in the file, wrapped in a
+        // rollout line. It is written right away because thread listings read
+        // it from the head of the file.
+        let item = RolloutItem::SessionMeta(session_meta_line);
+        sidecar.observe(&item, &rollout_timestamp()?);
+        writer.write_rollout_item(item)?;
+        writer.write_pending().await?;
     }
+    sidecar.save(ThreadStatus::Active).await;
+
+    // `interval` panics on a zero period; the ticker is unused in that case.
+    let mut ticker = tokio::time::interval(flush_interval.max(Duration::from_millis(1)));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
     // Process rollout commands
-    while let Some(cmd) = rx.recv().await {
+    loop {
+        let cmd = tokio::select! {
+            cmd = rx.recv() => cmd,
+            _ = ticker.tick(), if writer.has_pending() => {
+                writer.sync().await?;
+                sidecar.save(ThreadStatus::Active).await;
+                continue;
+            }
+        };
+        let Some(cmd) = cmd else {
+            break;
+        };
         match cmd {
             RolloutCmd::AddItems(items) => {
+                let had_pending = writer.has_pending();
+                let timestamp = rollout_timestamp()?;
                 for item in items {
                     if is_persisted_response_item(&item) {
-                        writer.write_rollout_item(item).await?;
+                        sidecar.observe(&item, &timestamp);
+                        writer.write_rollout_item(item)?;
                     }
                 }
+                if flush_interval.is_zero() || writer.pending_len() >= MAX_PENDING_BYTES {
+                    writer.write_pending().await?;
+                } else if !had_pending && writer.has_pending() {
+                    // Give the new batch a full interval to fill up.
+                    ticker.reset();
+                }
             }
             RolloutCmd::Flush { ack } => {
-                // Ensure underlying file is flushed and then ack.
-                if let Err(e) = writer.file.flush().await {
-                    let _ = ack.send(());
-                    return Err(e);
-                }
+                // Write and fsync everything buffered so far, then ack.
+                let result = writer.sync().await;
+                sidecar.save(ThreadStatus::Active).await;
                 let _ = ack.send(());
+                result?;
             }
             RolloutCmd::Shutdown { ack } => {
+                let result = writer.sync().await;
+                sidecar.save(ThreadStatus::Closed).await;
                 let _ = ack.send(());
+                result?;
             }
         }
     }
 
-    Ok(())
+    let result = writer.sync().await;
+    sidecar.save(ThreadStatus::Closed).await;
+    result
+}
+
+/// Keeps the `.meta.json` sidecar of a rollout in step with what the writer
+/// records. Failing to write it is logged and does not stop recording.
+struct MetadataSidecar {
+    rollout_path: PathBuf,
+    metadata: ThreadMetadata,
+    /// Whether `metadata` changed since it was last written.
+    dirty: bool,
 }
 
+impl MetadataSidecar {
+    fn new(rollout_path: PathBuf, metadata: ThreadMetadata) -> Self {
+        Self {
+            rollout_path,
+            metadata,
+            dirty: true,
+        }
+    }
+
+    fn observe(&mut self, item: &RolloutItem, timestamp: &str) {
+        self.metadata.observe(item, timestamp);
+        self.dirty = true;
+    }
+
+    async fn save(&mut self, status: ThreadStatus) {
+        if !self.dirty && self.metadata.status == status {
+            return;
+        }
+        self.metadata.status = status;
+        // Keep a title and tags set with `codex sessions rename` or `/tag`
+        // while the session ran.
+        if let Ok(Some(on_disk)) = read_metadata(&self.rollout_path).await {
+            if on_disk.title.is_some() {
+                self.metadata.title = on_disk.title;
+            }
+            self.metadata.tags = on_disk.tags;
+        }
+        match write_metadata(&self.rollout_path, &self.metadata).await {
+            Ok(()) => self.dirty = false,
+            Err(err) => warn!(
+                "failed to write session metadata for {:?}: {err}",
+                self.rollout_path
+            ),
+        }
+    }
+}
+
+/// Time of a rollout line, in UTC with millisecond precision.
+fn rollout_timestamp() -> std::io::Result<String> {
+    let timestamp_format: &[FormatItem] =
+        format_description!("[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z");
+    OffsetDateTime::now_utc()
+        .format(timestamp_format)
+        .map_err(|e| IoError::other(format!("failed to format timestamp: {e}")))
+}
+
+/// Buffered bytes that force a write before the flush interval elapses.
+const MAX_PENDING_BYTES: usize = 1024 * 1024;
+
+/// Serializes rollout lines into a reused buffer and writes them to `file` in
+/// batches.
 struct JsonlWriter {
     file: tokio::fs::File,
+    /// Serialized lines not yet written to `file`.
+    pending: Vec<u8>,
+    /// Whether `file` has writes that were not fsynced yet.
+    unsynced: bool,
 }
 
 impl JsonlWriter {
-    async fn write_rollout_item(&mut self, rollout_item: RolloutItem) -> std::io::Result<()> {
-        let timestamp_format: &[FormatItem] = format_description!(
-            "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z"
-        );
-        let timestamp = OffsetDateTime::now_utc()
-            .format(timestamp_format)
-            .map_err(|e| IoError::other(format!("failed to format timestamp: {e}")))?;
+    fn new(file: tokio::fs::File) -> Self {
+        Self {
+            file,
+            pending: Vec::new(),
+            unsynced: false,
+        }
+    }
 
+    fn write_rollout_item(&mut self, rollout_item: RolloutItem) -> std::io::Result<()> {
         let line = RolloutLine {
-            timestamp,
+            timestamp: rollout_timestamp()?,
             item: rollout_item,
         };
-        self.write_line(&line).await
+        self.write_line(&line)
+    }
+
+    /// Appends `item` as one JSON line to the pending batch.
+    fn write_line(&mut self, item: &impl serde::Serialize) -> std::io::Result<()> {
+        let start = self.pending.len();
+        if let Err(e) = serde_json::to_writer(&mut self.pending, item) {
+            // Drop the partial line so the batch stays valid JSONL.
+            self.pending.truncate(start);
+            return Err(e.into());
+        }
+        self.pending.push(b'\n');
+        Ok(())
+    }
+
+    fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    fn pending_len(&self) -> usize {
+        self.pending.len()
     }
-    async fn write_line(&mut self, item: &impl serde::Serialize) -> std::io::Result<()> {
-        let mut json = serde_json::to_string(item)?;
-        json.push('\n');
-        self.file.write_all(json.as_bytes()).await?;
+
+    /// Writes the pending batch to the file without waiting for it to reach
+    /// the disk. The buffer keeps its capacity for the next batch.
+    async fn write_pending(&mut self) -> std::io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        self.file.write_all(&self.pending).await?;
         self.file.flush().await?;
+        self.pending.clear();
+        self.unsynced = true;
+        Ok(())
+    }
+
+    /// Writes the pending batch and fsyncs the file.
+    async fn sync(&mut self) -> std::io::Result<()> {
+        self.write_pending().await?;
+        if self.unsynced {
+            self.file.sync_data().await?;
+            self.unsynced = false;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ContentItem;
+    use codex_protocol::models::ResponseItem;
+    use pretty_assertions::assert_eq;
+
+    fn message(text: &str) -> RolloutItem {
+        RolloutItem::ResponseItem(ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: text.to_string(),
+            }],
+        })
+    }
+
+    fn line_count(path: &Path) -> usize {
+        std::fs::read_to_string(path)
+            .expect("read rollout")
+            .lines()
+            .count()
+    }
+
+    #[tokio::test]
+    async fn buffers_lines_until_synced() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("rollout.jsonl");
+        let mut writer = JsonlWriter::new(tokio::fs::File::create(&path).await?);
+
+        writer.write_rollout_item(message("one"))?;
+        writer.write_rollout_item(message("two"))?;
+        assert_eq!(line_count(&path), 0);
+
+        writer.sync().await?;
+        assert_eq!(line_count(&path), 2);
+        assert!(!writer.has_pending());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn flush_writes_items_recorded_within_the_interval() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("rollout.jsonl");
+        let file = tokio::fs::File::create(&path).await?;
+        let (tx, rx) = mpsc::channel(8);
+        let writer = tokio::spawn(rollout_writer(
+            file,
+            rx,
+            None,
+            dir.path().to_path_buf(),
+            Duration::from_secs(3600),
+            MetadataSidecar::new(path.clone(), ThreadMetadata::default()),
+        ));
+
+        tx.send(RolloutCmd::AddItems(vec![message("one"), message("two")]))
+            .await
+            .expect("queue items");
+        let (ack, done) = oneshot::channel();
+        tx.send(RolloutCmd::Flush { ack })
+            .await
+            .expect("queue flush");
+        done.await.expect("flush acked");
+        assert_eq!(line_count(&path), 2);
+
+        tx.send(RolloutCmd::AddItems(vec![message("three")]))
+            .await
+            .expect("queue items");
+        drop(tx);
+        writer.await.expect("writer task")?;
+        assert_eq!(line_count(&path), 3);
         Ok(())
     }
 }
@@ -64,6 +64,15 @@ pub(crate) enum SandboxTransformError {
     SeatbeltUnavailable,
 }
 
+/// Directories sandboxed commands may neither read nor write, whatever the
+/// policy grants: the event sockets of running sessions and the tokens that
+/// authorize approvals through them.
+pub(crate) fn protected_dirs() -> Vec<PathBuf> {
+    crate::config::find_codex_home()
+        .map(|codex_home| vec![codex_home.join(crate::event_socket::EVENT_SOCKET_DIR)])
+        .unwrap_or_default()
+}
+
 #[derive(Default)]
 pub struct SandboxManager;
 
@@ -119,8 +128,12 @@ impl SandboxManager {
             SandboxType::MacosSeatbelt => {
                 let mut seatbelt_env = HashMap::new();
                 seatbelt_env.insert(CODEX_SANDBOX_ENV_VAR.to_string(), "seatbelt".to_string());
-                let mut args =
-                    create_seatbelt_command_args(command.clone(), policy, sandbox_policy_cwd);
+                let mut args = create_seatbelt_command_args(
+                    command.clone(),
+                    policy,
+                    sandbox_policy_cwd,
+                    &protected_dirs(),
+                );
                 let mut full_command = Vec::with_capacity(1 + args.len());
                 full_command.push(MACOS_PATH_TO_SEATBELT_EXECUTABLE.to_string());
                 full_command.append(&mut args);
@@ -131,8 +144,12 @@ impl SandboxManager {
             SandboxType::LinuxSeccomp => {
                 let exe = codex_linux_sandbox_exe
                     .ok_or(SandboxTransformError::MissingLinuxSandboxExecutable)?;
-                let mut args =
-                    create_linux_sandbox_command_args(command.clone(), policy, sandbox_policy_cwd);
+                let mut args = create_linux_sandbox_command_args(
+                    command.clone(),
+                    policy,
+                    sandbox_policy_cwd,
+                    &protected_dirs(),
+                );
                 let mut full_command = Vec::with_capacity(1 + args.len());
                 full_command.push(exe.to_string_lossy().to_string());
                 full_command.append(&mut args);
@@ -28,7 +28,12 @@ pub async fn spawn_command_under_seatbelt(
     stdio_policy: StdioPolicy,
     mut env: HashMap<String, String>,
 ) -> std::io::Result<Child> {
-    let args = create_seatbelt_command_args(command, sandbox_policy, sandbox_policy_cwd);
+    let args = create_seatbelt_command_args(
+        command,
+        sandbox_policy,
+        sandbox_policy_cwd,
+        &crate::sandboxing::protected_dirs(),
+    );
     let arg0 = None;
     env.insert(CODEX_SANDBOX_ENV_VAR.to_string(), "seatbelt".to_string());
     spawn_child_async(
@@ -47,6 +52,7 @@ pub(crate) fn create_seatbelt_command_args(
     command: Vec<String>,
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
+    protected_dirs: &[PathBuf],
 ) -> Vec<String> {
     let (file_write_policy, file_write_dir_params) = {
         if sandbox_policy.has_full_disk_write_access() {
@@ -118,11 +124,28 @@ pub(crate) fn create_seatbelt_command_args(
         ""
     };
 
+    // The last matching rule wins, so these override the allows above.
+    let mut protected_dir_params = Vec::new();
+    let mut protected_dir_policy = String::new();
+    for (index, dir) in protected_dirs.iter().enumerate() {
+        let canonical_dir = dir.canonicalize().unwrap_or_else(|_| dir.clone());
+        let param = format!("PROTECTED_DIR_{index}");
+        protected_dir_policy.push_str(&format!(
+            "(deny file-read* file-write* (subpath (param \"{param}\")))\n"
+        ));
+        protected_dir_params.push((param, canonical_dir));
+    }
+
     let full_policy = format!(
-        "{MACOS_SEATBELT_BASE_POLICY}\n{file_read_policy}\n{file_write_policy}\n{network_policy}"
+        "{MACOS_SEATBELT_BASE_POLICY}\n{file_read_policy}\n{file_write_policy}\n{network_policy}{protected_dir_policy}"
     );
 
-    let dir_params = [file_write_dir_params, macos_dir_params()].concat();
+    let dir_params = [
+        file_write_dir_params,
+        protected_dir_params,
+        macos_dir_params(),
+    ]
+    .concat();
 
     let mut seatbelt_args: Vec<String> = vec!["-p".to_string(), full_policy];
     let definition_args = dir_params
@@ -217,7 +240,7 @@ mod tests {
         .iter()
         .map(std::string::ToString::to_string)
         .collect();
-        let args = create_seatbelt_command_args(shell_command.clone(), &policy, &cwd);
+        let args = create_seatbelt_command_args(shell_command.clone(), &policy, &cwd, &[]);
 
         // Build the expected policy text using a raw string for readability.
         // Note that the policy includes:
@@ -308,7 +331,8 @@ mod tests {
         .iter()
         .map(std::string::ToString::to_string)
         .collect();
-        let write_hooks_file_args = create_seatbelt_command_args(shell_command_git, &policy, &cwd);
+        let write_hooks_file_args =
+            create_seatbelt_command_args(shell_command_git, &policy, &cwd, &[]);
         let output = Command::new(MACOS_PATH_TO_SEATBELT_EXECUTABLE)
             .args(&write_hooks_file_args)
             .current_dir(&cwd)
@@ -345,7 +369,7 @@ mod tests {
         .map(std::string::ToString::to_string)
         .collect();
         let write_allowed_file_args =
-            create_seatbelt_command_args(shell_command_allowed, &policy, &cwd);
+            create_seatbelt_command_args(shell_command_allowed, &policy, &cwd, &[]);
         let output = Command::new(MACOS_PATH_TO_SEATBELT_EXECUTABLE)
             .args(&write_allowed_file_args)
             .current_dir(&cwd)
@@ -364,6 +388,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn protected_dirs_are_neither_readable_nor_writable() {
+        let tmp = TempDir::new().expect("tempdir");
+        let cwd = tmp.path().join("cwd");
+        let run_dir = tmp.path().join("run");
+        fs::create_dir_all(&cwd).expect("create cwd");
+        fs::create_dir_all(&run_dir).expect("create run dir");
+        let token = run_dir.join("session.token");
+        fs::write(&token, "secret").expect("write token");
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![tmp.path().to_path_buf().try_into().unwrap()],
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+        };
+
+        let run = |script: &str| {
+            let shell_command: Vec<String> = ["bash", "-c", script, "bash"]
+                .iter()
+                .map(std::string::ToString::to_string)
+                .chain([token.to_string_lossy().to_string()])
+                .collect();
+            let args = create_seatbelt_command_args(
+                shell_command,
+                &policy,
+                &cwd,
+                std::slice::from_ref(&run_dir),
+            );
+            Command::new(MACOS_PATH_TO_SEATBELT_EXECUTABLE)
+                .args(&args)
+                .current_dir(&cwd)
+                .output()
+                .expect("execute seatbelt command")
+        };
+
+        assert!(!run("cat \"$1\"").status.success());
+        assert!(!run("echo pwned > \"$1\"").status.success());
+        assert_eq!(
+            "secret",
+            fs::read_to_string(&token).expect("read token"),
+            "{} should keep its contents",
+            token.display()
+        );
+        assert!(run("echo allowed > ../allowed.txt").status.success());
+    }
+
     #[test]
     fn create_seatbelt_args_for_cwd_as_git_repo() {
         // Create a temporary workspace with two writable roots: one containing
@@ -400,8 +470,12 @@ mod tests {
         .iter()
         .map(std::string::ToString::to_string)
         .collect();
-        let args =
-            create_seatbelt_command_args(shell_command.clone(), &policy, vulnerable_root.as_path());
+        let args = create_seatbelt_command_args(
+            shell_command.clone(),
+            &policy,
+            vulnerable_root.as_path(),
+            &[],
+        );
 
         let tmpdir_env_var = std::env::var("TMPDIR")
             .ok()
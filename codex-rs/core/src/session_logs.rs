@@ -0,0 +1,534 @@
+//! Per-session structured log files.
+//!
+//! [`SessionLogLayer`] is a `tracing` layer that writes every event as one
+//! JSON object per line under `<log_dir>/sessions/<session id>/`. Events are
+//! attributed to the nearest enclosing span that records a `conversation_id`
+//! field (the session span owned by `OtelManager`); events outside any
+//! session span go to the most recently started session, or to
+//! [`UNSCOPED_DIR`] before the first session starts. Each session's log is
+//! capped at [`MAX_LOG_FILE_BYTES`] and rotated, keeping
+//! [`MAX_ROTATED_FILES`] older files.
+//!
+//! Files are written on a dedicated thread so logging never blocks the
+//! caller on disk IO; a session's file is closed when its span closes.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use std::time::SystemTime;
+
+use chrono::SecondsFormat;
+use chrono::Utc;
+use serde_json::Map;
+use serde_json::Value;
+use tracing::Event;
+use tracing::Metadata;
+use tracing::Subscriber;
+use tracing::field::Field;
+use tracing::field::Visit;
+use tracing::span::Attributes;
+use tracing::span::Id;
+use tracing_subscriber::filter::FilterFn;
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::layer::Layer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Directory under the log dir that holds one subdirectory per session.
+pub const SESSIONS_SUBDIR: &str = "sessions";
+
+/// Session directory used for events logged before any session starts.
+pub const UNSCOPED_DIR: &str = "unscoped";
+
+/// Name of the active log file inside a session directory. Rotated files
+/// append `.1` (newest) through `.N` (oldest).
+pub const LOG_FILE_NAME: &str = "codex.jsonl";
+
+pub const MAX_LOG_FILE_BYTES: u64 = 10 * 1024 * 1024;
+pub const MAX_ROTATED_FILES: usize = 5;
+
+/// Span field that marks a span as belonging to a session.
+const SESSION_FIELD: &str = "conversation_id";
+
+pub fn sessions_root(log_dir: &Path) -> PathBuf {
+    log_dir.join(SESSIONS_SUBDIR)
+}
+
+/// Filter that admits session spans so [`SessionLogLayer`] can attribute
+/// events to them. Combine it with the layer's event filter using
+/// `FilterExt::or`.
+pub fn session_span_filter() -> FilterFn<fn(&Metadata<'_>) -> bool> {
+    filter_fn(is_session_span as fn(&Metadata<'_>) -> bool)
+}
+
+fn is_session_span(metadata: &Metadata<'_>) -> bool {
+    metadata.is_span() && metadata.fields().field(SESSION_FIELD).is_some()
+}
+
+pub struct SessionLogLayer {
+    /// `None` if the writer thread could not be started.
+    sender: Option<mpsc::Sender<WriterMessage>>,
+    writer: Option<JoinHandle<()>>,
+    current_session: Mutex<Option<String>>,
+}
+
+enum WriterMessage {
+    Line {
+        session: String,
+        line: Vec<u8>,
+    },
+    /// The session ended, so its file can be closed.
+    Close {
+        session: String,
+    },
+}
+
+/// Session id stored in the extensions of a session span.
+struct SessionId(String);
+
+impl SessionLogLayer {
+    pub fn new(root: PathBuf) -> Self {
+        Self::with_max_file_bytes(root, MAX_LOG_FILE_BYTES)
+    }
+
+    fn with_max_file_bytes(root: PathBuf, max_file_bytes: u64) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let writer = std::thread::Builder::new()
+            .name("session-logs".to_string())
+            .spawn(move || run_writer(&root, max_file_bytes, receiver))
+            .ok();
+        Self {
+            sender: writer.as_ref().map(|_| sender),
+            writer,
+            current_session: Mutex::new(None),
+        }
+    }
+
+    fn current_session(&self) -> std::sync::MutexGuard<'_, Option<String>> {
+        match self.current_session.lock() {
+            Ok(guard) => guard,
+            Err(err) => err.into_inner(),
+        }
+    }
+
+    fn send(&self, message: WriterMessage) {
+        if let Some(sender) = &self.sender {
+            // There is nowhere sensible to report a failure to write the log.
+            let _ = sender.send(message);
+        }
+    }
+
+    fn write_line(&self, session: Option<String>, line: Vec<u8>) {
+        let session = session
+            .or_else(|| self.current_session().clone())
+            .unwrap_or_else(|| UNSCOPED_DIR.to_string());
+        self.send(WriterMessage::Line { session, line });
+    }
+}
+
+/// Writes everything queued before the layer is dropped.
+impl Drop for SessionLogLayer {
+    fn drop(&mut self) {
+        self.sender = None;
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+fn run_writer(root: &Path, max_file_bytes: u64, receiver: mpsc::Receiver<WriterMessage>) {
+    let mut files: HashMap<String, RotatingFile> = HashMap::new();
+    for message in receiver {
+        match message {
+            WriterMessage::Line { session, line } => {
+                let file = match files.entry(session) {
+                    std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+                    std::collections::hash_map::Entry::Vacant(entry) => {
+                        let Ok(file) = RotatingFile::open(root.join(entry.key())) else {
+                            continue;
+                        };
+                        entry.insert(file)
+                    }
+                };
+                let _ = file.write_line(&line, max_file_bytes);
+            }
+            WriterMessage::Close { session } => {
+                files.remove(&session);
+            }
+        }
+    }
+}
+
+impl<S> Layer<S> for SessionLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut visitor = SessionIdVisitor::default();
+        attrs.record(&mut visitor);
+        let (Some(session), Some(span)) = (visitor.session, ctx.span(id)) else {
+            return;
+        };
+        let session = sanitize_dir_name(&session);
+        span.extensions_mut().insert(SessionId(session.clone()));
+        *self.current_session() = Some(session);
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(SessionId(session)) = span.extensions_mut().remove::<SessionId>() else {
+            return;
+        };
+        {
+            let mut current = self.current_session();
+            if current.as_deref() == Some(session.as_str()) {
+                *current = None;
+            }
+        }
+        self.send(WriterMessage::Close { session });
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut session = None;
+        let mut spans = Vec::new();
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope {
+                if session.is_none()
+                    && let Some(SessionId(id)) = span.extensions().get::<SessionId>()
+                {
+                    session = Some(id.clone());
+                }
+                spans.push(Value::String(span.name().to_string()));
+            }
+        }
+        spans.reverse();
+
+        let metadata = event.metadata();
+        let mut visitor = JsonVisitor::default();
+        event.record(&mut visitor);
+
+        let mut record = Map::new();
+        record.insert(
+            "timestamp".to_string(),
+            Value::String(Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)),
+        );
+        record.insert(
+            "level".to_string(),
+            Value::String(metadata.level().to_string()),
+        );
+        record.insert(
+            "target".to_string(),
+            Value::String(metadata.target().to_string()),
+        );
+        if let Some(message) = visitor.message {
+            record.insert("message".to_string(), Value::String(message));
+        }
+        if !visitor.fields.is_empty() {
+            record.insert("fields".to_string(), Value::Object(visitor.fields));
+        }
+        if !spans.is_empty() {
+            record.insert("spans".to_string(), Value::Array(spans));
+        }
+
+        let Ok(mut line) = serde_json::to_vec(&Value::Object(record)) else {
+            return;
+        };
+        line.push(b'\n');
+        self.write_line(session, line);
+    }
+}
+
+struct RotatingFile {
+    dir: PathBuf,
+    file: File,
+    len: u64,
+}
+
+impl RotatingFile {
+    fn open(dir: PathBuf) -> io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        let file = open_log_file(&dir.join(LOG_FILE_NAME))?;
+        let len = file.metadata()?.len();
+        Ok(Self { dir, file, len })
+    }
+
+    fn write_line(&mut self, line: &[u8], max_file_bytes: u64) -> io::Result<()> {
+        if self.len > 0 && self.len + line.len() as u64 > max_file_bytes {
+            self.rotate()?;
+        }
+        self.file.write_all(line)?;
+        self.len += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        let path = |index: usize| rotated_path(&self.dir, index);
+        let _ = std::fs::remove_file(path(MAX_ROTATED_FILES));
+        for index in (1..MAX_ROTATED_FILES).rev() {
+            let from = path(index);
+            if from.exists() {
+                std::fs::rename(from, path(index + 1))?;
+            }
+        }
+        let active = self.dir.join(LOG_FILE_NAME);
+        std::fs::rename(&active, path(1))?;
+        self.file = open_log_file(&active)?;
+        self.len = 0;
+        Ok(())
+    }
+}
+
+fn open_log_file(path: &Path) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    // Logs may contain prompts and command output, so keep them private.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+}
+
+fn rotated_path(dir: &Path, index: usize) -> PathBuf {
+    dir.join(format!("{LOG_FILE_NAME}.{index}"))
+}
+
+fn sanitize_dir_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// A session directory under [`sessions_root`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionLogDir {
+    pub session: String,
+    pub path: PathBuf,
+    pub modified: SystemTime,
+}
+
+/// Lists session log directories, most recently written first.
+pub fn list_session_log_dirs(log_dir: &Path) -> io::Result<Vec<SessionLogDir>> {
+    let root = sessions_root(log_dir);
+    let entries = match std::fs::read_dir(&root) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut dirs = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let path = entry.path();
+        let modified = std::fs::metadata(path.join(LOG_FILE_NAME))
+            .or_else(|_| entry.metadata())?
+            .modified()?;
+        dirs.push(SessionLogDir {
+            session: entry.file_name().to_string_lossy().into_owned(),
+            path,
+            modified,
+        });
+    }
+    dirs.sort_by(|a, b| b.modified.cmp(&a.modified));
+    Ok(dirs)
+}
+
+/// Log files of a session directory in the order they were written: the
+/// oldest rotated file first and the active file last.
+pub fn session_log_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = (1..=MAX_ROTATED_FILES)
+        .rev()
+        .map(|index| rotated_path(dir, index))
+        .filter(|path| path.exists())
+        .collect();
+    let active = dir.join(LOG_FILE_NAME);
+    if active.exists() {
+        files.push(active);
+    }
+    files
+}
+
+#[derive(Default)]
+struct SessionIdVisitor {
+    session: Option<String>,
+}
+
+impl Visit for SessionIdVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == SESSION_FIELD {
+            self.session = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == SESSION_FIELD {
+            // `%id` and `?id` both arrive here; Display values are not quoted.
+            self.session = Some(format!("{value:?}").trim_matches('"').to_string());
+        }
+    }
+}
+
+#[derive(Default)]
+struct JsonVisitor {
+    message: Option<String>,
+    fields: Map<String, Value>,
+}
+
+impl JsonVisitor {
+    fn insert(&mut self, field: &Field, value: Value) {
+        if field.name() == "message" {
+            let message = match value {
+                Value::String(message) => message,
+                other => other.to_string(),
+            };
+            self.message = Some(message);
+        } else {
+            self.fields.insert(field.name().to_string(), value);
+        }
+    }
+}
+
+impl Visit for JsonVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.insert(field, Value::String(format!("{value:?}")));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    fn read_lines(dir: &Path) -> Vec<Value> {
+        session_log_files(dir)
+            .into_iter()
+            .flat_map(|path| {
+                std::fs::read_to_string(path)
+                    .expect("read log")
+                    .lines()
+                    .map(|line| serde_json::from_str(line).expect("json line"))
+                    .collect::<Vec<Value>>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn routes_events_to_session_directories() {
+        let tmp = TempDir::new().expect("tempdir");
+        let subscriber =
+            tracing_subscriber::registry().with(SessionLogLayer::new(tmp.path().to_path_buf()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("before any session");
+            let first = tracing::info_span!("new_session", conversation_id = %"thread-a");
+            let second = tracing::info_span!("new_session", conversation_id = %"thread-b");
+            first.in_scope(|| tracing::info!(turn = 1, "inside first"));
+            tracing::warn!("outside any span");
+            second.in_scope(|| tracing::info!("inside second"));
+        });
+
+        let unscoped = read_lines(&tmp.path().join(UNSCOPED_DIR));
+        assert_eq!(unscoped.len(), 1);
+        assert_eq!(unscoped[0]["message"], "before any session");
+
+        let first = read_lines(&tmp.path().join("thread-a"));
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0]["message"], "inside first");
+        assert_eq!(first[0]["fields"]["turn"], 1);
+        assert_eq!(first[0]["spans"], serde_json::json!(["new_session"]));
+
+        // Unscoped events after a session starts go to the latest session.
+        let messages: Vec<Value> = read_lines(&tmp.path().join("thread-b"))
+            .into_iter()
+            .map(|line| line["message"].clone())
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                Value::from("outside any span"),
+                Value::from("inside second")
+            ]
+        );
+    }
+
+    #[test]
+    fn closed_sessions_stop_receiving_unscoped_events() {
+        let tmp = TempDir::new().expect("tempdir");
+        let subscriber =
+            tracing_subscriber::registry().with(SessionLogLayer::new(tmp.path().to_path_buf()));
+        tracing::subscriber::with_default(subscriber, || {
+            let session = tracing::info_span!("new_session", conversation_id = %"thread-a");
+            session.in_scope(|| tracing::info!("inside"));
+            drop(session);
+            tracing::info!("after the session ended");
+        });
+
+        let session = read_lines(&tmp.path().join("thread-a"));
+        assert_eq!(session.len(), 1);
+        assert_eq!(session[0]["message"], "inside");
+        let unscoped = read_lines(&tmp.path().join(UNSCOPED_DIR));
+        assert_eq!(unscoped.len(), 1);
+        assert_eq!(unscoped[0]["message"], "after the session ended");
+    }
+
+    #[test]
+    fn rotates_when_the_active_file_is_full() {
+        let tmp = TempDir::new().expect("tempdir");
+        let layer = SessionLogLayer::with_max_file_bytes(tmp.path().to_path_buf(), 10);
+        for index in 0..(MAX_ROTATED_FILES + 3) {
+            layer.write_line(Some("s".to_string()), format!("{index:08}\n").into_bytes());
+        }
+        drop(layer);
+
+        let dir = tmp.path().join("s");
+        let files = session_log_files(&dir);
+        assert_eq!(files.len(), MAX_ROTATED_FILES + 1);
+        let contents: Vec<String> = files
+            .iter()
+            .map(|path| std::fs::read_to_string(path).expect("read"))
+            .collect();
+        let expected: Vec<String> = (2..(MAX_ROTATED_FILES + 3))
+            .map(|index| format!("{index:08}\n"))
+            .collect();
+        assert_eq!(contents, expected);
+    }
+}
@@ -148,6 +148,7 @@ fn skill_roots_from_layer_stack_inner(config_layer_stack: &ConfigLayerStack) ->
                 });
             }
             ConfigLayerSource::Mdm { .. }
+            | ConfigLayerSource::Environment
             | ConfigLayerSource::SessionFlags
             | ConfigLayerSource::LegacyManagedConfigTomlFromFile { .. }
             | ConfigLayerSource::LegacyManagedConfigTomlFromMdm => {}
@@ -407,10 +408,12 @@ mod tests {
     use super::*;
     use crate::config::ConfigBuilder;
     use crate::config::ConfigOverrides;
+    use crate::config::set_project_trust_level;
     use crate::config_loader::ConfigLayerEntry;
     use crate::config_loader::ConfigLayerStack;
     use crate::config_loader::ConfigRequirements;
     use crate::config_loader::ConfigRequirementsToml;
+    use codex_protocol::config_types::TrustLevel;
     use codex_protocol::protocol::SkillScope;
     use codex_utils_absolute_path::AbsolutePathBuf;
     use pretty_assertions::assert_eq;
@@ -425,6 +428,9 @@ mod tests {
     }
 
     async fn make_config_for_cwd(codex_home: &TempDir, cwd: PathBuf) -> Config {
+        // Repo skills only load for trusted projects.
+        set_project_trust_level(codex_home.path(), &cwd, TrustLevel::Trusted)
+            .expect("trust project");
         let harness_overrides = ConfigOverrides {
             cwd: Some(cwd),
             ..Default::default()
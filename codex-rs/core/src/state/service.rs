@@ -1,15 +1,33 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::AuthManager;
 use crate::RolloutRecorder;
 use crate::agent::AgentControl;
+use crate::approval_rules::ApprovalRules;
+use crate::config::types::ReasoningConfig;
+use crate::devcontainer::DevcontainerState;
+use crate::event_socket::EventSocket;
 use crate::exec_policy::ExecPolicyManager;
+use crate::file_watch::FileWatch;
+use crate::guardrails::ToolFailureBreaker;
+use crate::hooks::Hooks;
+use crate::injection::InjectionDetector;
+use crate::interceptors::Interceptors;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::models_manager::manager::ModelsManager;
+use crate::prefetch::Prefetcher;
+use crate::project_doc_watcher::ProjectDocWatcher;
+use crate::redaction::Redactor;
 use crate::skills::SkillsManager;
+use crate::symbol_index::SymbolIndex;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::unified_exec::UnifiedExecProcessManager;
 use crate::user_notification::UserNotifier;
+use crate::variables::Variables;
+use crate::watchdog::Watchdogs;
+use crate::workspace_snapshot::WorkspaceSnapshots;
 use codex_otel::OtelManager;
 use tokio::sync::Mutex;
 use tokio::sync::RwLock;
@@ -20,10 +38,29 @@ pub(crate) struct SessionServices {
     pub(crate) mcp_startup_cancellation_token: CancellationToken,
     pub(crate) unified_exec_manager: UnifiedExecProcessManager,
     pub(crate) notifier: UserNotifier,
+    pub(crate) hooks: Arc<Hooks>,
+    pub(crate) event_socket: Option<EventSocket>,
+    pub(crate) tool_failure_breaker: Arc<ToolFailureBreaker>,
+    pub(crate) watchdogs: Watchdogs,
+    pub(crate) interceptors: Arc<Interceptors>,
+    pub(crate) redactor: Redactor,
+    pub(crate) injection_detector: InjectionDetector,
+    pub(crate) project_doc_watcher: ProjectDocWatcher,
+    pub(crate) file_watch: FileWatch,
+    pub(crate) workspace_snapshots: WorkspaceSnapshots,
+    pub(crate) prefetch: Prefetcher,
+    pub(crate) symbol_index: SymbolIndex,
+    /// Dev containers of this session, by working directory.
+    pub(crate) devcontainers: Mutex<HashMap<PathBuf, DevcontainerState>>,
+    /// Held while the `[pull_request]` workflow publishes a task's changes.
+    pub(crate) publishing: Mutex<()>,
     pub(crate) rollout: Mutex<Option<RolloutRecorder>>,
     pub(crate) user_shell: Arc<crate::shell::Shell>,
     pub(crate) show_raw_agent_reasoning: bool,
+    pub(crate) reasoning: ReasoningConfig,
+    pub(crate) variables: Variables,
     pub(crate) exec_policy: ExecPolicyManager,
+    pub(crate) approval_rules: ApprovalRules,
     pub(crate) auth_manager: Arc<AuthManager>,
     pub(crate) models_manager: Arc<ModelsManager>,
     pub(crate) otel_manager: OtelManager,
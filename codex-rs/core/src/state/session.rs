@@ -36,6 +36,11 @@ impl SessionState {
         self.history.record_items(items, policy);
     }
 
+    /// The call in the history that produced the output with `call_id`.
+    pub(crate) fn find_call(&self, call_id: &str) -> Option<ResponseItem> {
+        crate::provenance::find_call(self.history.raw_items(), call_id).cloned()
+    }
+
     pub(crate) fn clone_history(&self) -> ContextManager {
         self.history.clone()
     }
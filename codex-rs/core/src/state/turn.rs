@@ -37,6 +37,16 @@ pub(crate) enum TaskKind {
     Compact,
 }
 
+impl TaskKind {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            TaskKind::Regular => "regular",
+            TaskKind::Review => "review",
+            TaskKind::Compact => "compact",
+        }
+    }
+}
+
 #[derive(Clone)]
 pub(crate) struct RunningTask {
     pub(crate) done: Arc<Notify>,
@@ -45,6 +55,8 @@ pub(crate) struct RunningTask {
     pub(crate) cancellation_token: CancellationToken,
     pub(crate) handle: Arc<AbortOnDropHandle<()>>,
     pub(crate) turn_context: Arc<TurnContext>,
+    /// Turn watchdog timer, stopped when the task is dropped.
+    pub(crate) watchdog: Option<Arc<AbortOnDropHandle<()>>>,
 }
 
 impl ActiveTurn {
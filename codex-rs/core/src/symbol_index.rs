@@ -0,0 +1,723 @@
+//! Workspace symbol index behind the `find_symbol` tool (`symbol_index`
+//! feature).
+//!
+//! Finding where something is defined with `grep_files` and `read_file` costs
+//! the model several round trips in a large repository. The index keeps the
+//! definitions of every source file in the workspace, taking the file list
+//! from a [`FileIndex`] (which honors ignore files and follows changes to the
+//! tree), and remembers the size and modification time of each file so a
+//! query re-parses only the files that changed since the previous one.
+//!
+//! Definitions are found ctags-style, with a few patterns per language, and
+//! with the tree-sitter grammar for shell scripts. References are the lines
+//! that mention a name as a whole word; only files whose identifiers include
+//! the name are read to find them.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_file_search::FileIndex;
+use regex::Regex;
+use serde::Deserialize;
+use tree_sitter::Node;
+
+use crate::bash::try_parse_shell;
+use crate::file_watch::FileStamp;
+
+/// Larger files are usually generated or vendored and are not indexed.
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Upper bound on indexed files, so a huge monorepo does not make every
+/// query stat hundreds of thousands of files.
+const MAX_INDEXED_FILES: usize = 50_000;
+
+/// How long the first query waits for the initial walk of the workspace.
+const INITIAL_SCAN_TIMEOUT: Duration = Duration::from_secs(10);
+
+const MAX_LINE_CHARS: usize = 160;
+
+/// Words the patterns can mistake for names, e.g. `if (x) {` for a method.
+const KEYWORDS: &[&str] = &[
+    "if", "else", "for", "while", "switch", "catch", "return", "sizeof", "function", "new",
+    "delete", "throw", "do", "elif", "case",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Language {
+    Rust,
+    Python,
+    JavaScript,
+    Go,
+    /// Java, Kotlin, C#, Scala and Swift, whose declarations look alike.
+    Jvm,
+    C,
+    Ruby,
+    Shell,
+}
+
+/// A symbol defined in a workspace file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Definition {
+    pub(crate) name: String,
+    /// `function`, `struct`, `class`, ...
+    pub(crate) kind: String,
+    /// 1-based.
+    pub(crate) line: usize,
+}
+
+/// What to look up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SymbolLookup {
+    #[default]
+    Definitions,
+    References,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SymbolQuery {
+    pub(crate) name: String,
+    pub(crate) lookup: SymbolLookup,
+    /// Only files below this directory, relative to the workspace root.
+    pub(crate) within: Option<PathBuf>,
+    pub(crate) limit: usize,
+}
+
+/// A line that defines or mentions the symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SymbolMatch {
+    /// Relative to the workspace root.
+    pub(crate) path: String,
+    /// 1-based.
+    pub(crate) line: usize,
+    /// Set for definitions.
+    pub(crate) kind: Option<String>,
+    /// The line itself, trimmed and shortened.
+    pub(crate) text: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct SymbolSearch {
+    pub(crate) matches: Vec<SymbolMatch>,
+    /// More matches exist than the limit allowed.
+    pub(crate) truncated: bool,
+    /// No definition has the exact name; these have similar names.
+    pub(crate) approximate: bool,
+    /// Not every file was searched: the initial walk of the workspace had
+    /// not finished, or the workspace has more files than are indexed.
+    pub(crate) incomplete: bool,
+}
+
+struct IndexedFile {
+    stamp: FileStamp,
+    definitions: Vec<Definition>,
+    identifiers: HashSet<String>,
+}
+
+struct Workspace {
+    files: FileIndex,
+    indexed: HashMap<String, IndexedFile>,
+    max_files: usize,
+    /// The last refresh stopped at `max_files`.
+    capped: bool,
+}
+
+/// Symbols of the session's workspace, built on the first query. Cloning
+/// shares the index.
+#[derive(Clone, Default)]
+pub(crate) struct SymbolIndex {
+    workspace: Arc<Mutex<Option<Workspace>>>,
+}
+
+impl SymbolIndex {
+    /// Brings the index of `root` up to date and runs `query` against it.
+    /// Blocks while files are parsed, so call it off the async runtime.
+    pub(crate) fn search(&self, root: &Path, query: &SymbolQuery) -> SymbolSearch {
+        let mut slot = self
+            .workspace
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let workspace = match slot.take() {
+            Some(existing) if existing.files.root() == root => slot.insert(existing),
+            _ => slot.insert(Workspace::new(root)),
+        };
+        workspace.refresh();
+        let mut search = match query.lookup {
+            SymbolLookup::Definitions => workspace.definitions(query),
+            SymbolLookup::References => workspace.references(query),
+        };
+        search.incomplete = workspace.is_incomplete();
+        search
+    }
+}
+
+impl Workspace {
+    fn new(root: &Path) -> Self {
+        let files = FileIndex::new(root.to_path_buf());
+        let deadline = Instant::now() + INITIAL_SCAN_TIMEOUT;
+        while !files.is_ready() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        Self {
+            files,
+            indexed: HashMap::new(),
+            max_files: MAX_INDEXED_FILES,
+            capped: false,
+        }
+    }
+
+    fn is_incomplete(&self) -> bool {
+        !self.files.is_ready() || self.capped
+    }
+
+    /// Re-parses new and changed files and forgets deleted ones.
+    fn refresh(&mut self) {
+        let root = self.files.root().to_path_buf();
+        let mut present = HashSet::new();
+        self.capped = false;
+        for rel in self.files.files() {
+            if present.len() >= self.max_files {
+                self.capped = true;
+                break;
+            }
+            let path = root.join(&rel);
+            let Some(language) = language_of(&path) else {
+                continue;
+            };
+            let Some(stamp) = FileStamp::of(&path) else {
+                continue;
+            };
+            if self
+                .indexed
+                .get(&rel)
+                .is_none_or(|indexed| indexed.stamp != stamp)
+            {
+                let file = index_file(&path, language, stamp);
+                self.indexed.insert(rel.clone(), file);
+            }
+            present.insert(rel);
+        }
+        self.indexed.retain(|rel, _| present.contains(rel));
+    }
+
+    /// Indexed paths below `within`, sorted.
+    fn paths(&self, within: Option<&Path>) -> Vec<&String> {
+        let mut paths: Vec<&String> = self
+            .indexed
+            .keys()
+            .filter(|rel| within.is_none_or(|dir| Path::new(rel.as_str()).starts_with(dir)))
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    fn definitions(&self, query: &SymbolQuery) -> SymbolSearch {
+        let name = unqualified(&query.name);
+        let mut search = self.collect_definitions(query, |definition| definition.name == name);
+        if search.matches.is_empty() {
+            let lowercase = name.to_lowercase();
+            search = self.collect_definitions(query, |definition| {
+                definition.name.to_lowercase().contains(&lowercase)
+            });
+            search.approximate = !search.matches.is_empty();
+        }
+        search
+    }
+
+    fn collect_definitions(
+        &self,
+        query: &SymbolQuery,
+        matches: impl Fn(&Definition) -> bool,
+    ) -> SymbolSearch {
+        let root = self.files.root();
+        let mut search = SymbolSearch::default();
+        for rel in self.paths(query.within.as_deref()) {
+            let found: Vec<&Definition> = self.indexed[rel]
+                .definitions
+                .iter()
+                .filter(|definition| matches(definition))
+                .collect();
+            if found.is_empty() {
+                continue;
+            }
+            let lines = read_lines(&root.join(rel));
+            for definition in found {
+                if search.matches.len() == query.limit {
+                    search.truncated = true;
+                    return search;
+                }
+                search.matches.push(SymbolMatch {
+                    path: rel.clone(),
+                    line: definition.line,
+                    kind: Some(definition.kind.clone()),
+                    text: line_text(&lines, definition.line),
+                });
+            }
+        }
+        search
+    }
+
+    fn references(&self, query: &SymbolQuery) -> SymbolSearch {
+        let name = unqualified(&query.name);
+        let root = self.files.root();
+        let mut search = SymbolSearch::default();
+        for rel in self.paths(query.within.as_deref()) {
+            let indexed = &self.indexed[rel];
+            if !indexed.identifiers.contains(name) {
+                continue;
+            }
+            let defined_on: HashSet<usize> = indexed
+                .definitions
+                .iter()
+                .filter(|definition| definition.name == name)
+                .map(|definition| definition.line)
+                .collect();
+            let lines = read_lines(&root.join(rel));
+            for (index, line) in lines.iter().enumerate() {
+                if defined_on.contains(&(index + 1)) || !mentions(line, name) {
+                    continue;
+                }
+                if search.matches.len() == query.limit {
+                    search.truncated = true;
+                    return search;
+                }
+                search.matches.push(SymbolMatch {
+                    path: rel.clone(),
+                    line: index + 1,
+                    kind: None,
+                    text: line_text(&lines, index + 1),
+                });
+            }
+        }
+        search
+    }
+}
+
+fn index_file(path: &Path, language: Language, stamp: FileStamp) -> IndexedFile {
+    let content = std::fs::metadata(path)
+        .ok()
+        .filter(|metadata| metadata.len() <= MAX_FILE_BYTES)
+        .and_then(|_| std::fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    IndexedFile {
+        stamp,
+        definitions: extract_definitions(language, &content),
+        identifiers: content
+            .split(|c: char| !is_identifier_char(c))
+            .filter(|word| !word.is_empty())
+            .map(str::to_string)
+            .collect(),
+    }
+}
+
+fn language_of(path: &Path) -> Option<Language> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    Some(match extension.as_str() {
+        "rs" => Language::Rust,
+        "py" | "pyi" => Language::Python,
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "mts" | "cts" => Language::JavaScript,
+        "go" => Language::Go,
+        "java" | "kt" | "kts" | "cs" | "scala" | "swift" => Language::Jvm,
+        "c" | "h" | "cc" | "cpp" | "cxx" | "hh" | "hpp" | "hxx" => Language::C,
+        "rb" => Language::Ruby,
+        "sh" | "bash" | "zsh" => Language::Shell,
+        _ => return None,
+    })
+}
+
+/// Each pattern captures `name`, and `kind` when the keyword names the kind;
+/// otherwise the kind paired with the pattern applies.
+type Patterns = Vec<(Regex, &'static str)>;
+
+fn patterns(source: &[(&str, &'static str)]) -> Patterns {
+    source
+        .iter()
+        .filter_map(|(pattern, kind)| Regex::new(pattern).ok().map(|regex| (regex, *kind)))
+        .collect()
+}
+
+static RUST: LazyLock<Patterns> = LazyLock::new(|| {
+    const VIS: &str = r"^\s*(?:pub(?:\([^)]*\))?\s+)?";
+    patterns(&[
+        (
+            format!(
+                r#"{VIS}(?:(?:const|async|unsafe|extern(?:\s+"[^"]*")?)\s+)*fn\s+(?P<name>[A-Za-z_]\w*)"#
+            ).as_str(),
+            "function",
+        ),
+        (
+            format!(
+                r"{VIS}(?:unsafe\s+)?(?P<kind>struct|enum|union|trait|type|mod)\s+(?P<name>[A-Za-z_]\w*)"
+            ).as_str(),
+            "",
+        ),
+        (
+            format!(r"{VIS}(?:const|static(?:\s+mut)?)\s+(?P<name>[A-Za-z_]\w*)\s*:").as_str(),
+            "constant",
+        ),
+        (r"^\s*macro_rules!\s*(?P<name>[A-Za-z_]\w*)", "macro"),
+    ])
+});
+
+static PYTHON: LazyLock<Patterns> = LazyLock::new(|| {
+    patterns(&[
+        (r"^\s*(?:async\s+)?def\s+(?P<name>[A-Za-z_]\w*)", "function"),
+        (r"^\s*(?P<kind>class)\s+(?P<name>[A-Za-z_]\w*)", ""),
+        (r"^(?P<name>[A-Z][A-Z0-9_]*)\s*(?::[^=]+)?=[^=]", "constant"),
+    ])
+});
+
+static JAVASCRIPT: LazyLock<Patterns> = LazyLock::new(|| {
+    const EXPORT: &str = r"^\s*(?:export\s+(?:default\s+)?)?(?:declare\s+)?";
+    const NAME: &str = r"(?P<name>[A-Za-z_$][\w$]*)";
+    patterns(&[
+        (
+            format!(r"{EXPORT}(?:async\s+)?function\s*\*?\s*{NAME}").as_str(),
+            "function",
+        ),
+        (
+            format!(r"{EXPORT}(?:abstract\s+)?(?P<kind>class|interface)\s+{NAME}").as_str(),
+            "",
+        ),
+        (
+            format!(r"{EXPORT}type\s+{NAME}\s*(?:<[^>]*>)?\s*=").as_str(),
+            "type",
+        ),
+        (
+            format!(r"{EXPORT}(?:const\s+)?enum\s+{NAME}").as_str(),
+            "enum",
+        ),
+        (
+            format!(
+                r"{EXPORT}(?:const|let|var)\s+{NAME}\s*(?::[^=]+)?=\s*(?:async\s+)?(?:function\b|\([^)]*\)\s*(?::[^=]+)?=>|[A-Za-z_$][\w$]*\s*=>).as_str()"
+            ),
+            "function",
+        ),
+        (
+            format!(
+                r"^\s+(?:(?:public|private|protected|static|async|readonly|override|get|set)\s+)*{NAME}\s*\([^)]*\).as_str()\s*(?::[^{{]+)?\{{\s*$"
+            ),
+            "method",
+        ),
+    ])
+});
+
+static GO: LazyLock<Patterns> = LazyLock::new(|| {
+    patterns(&[
+        (
+            r"^func\s+(?:\([^)]*\)\s*)?(?P<name>[A-Za-z_]\w*)",
+            "function",
+        ),
+        (r"^\s*type\s+(?P<name>[A-Za-z_]\w*)\s+\S", "type"),
+        (r"^(?P<kind>const|var)\s+(?P<name>[A-Za-z_]\w*)", ""),
+    ])
+});
+
+static JVM: LazyLock<Patterns> = LazyLock::new(|| {
+    patterns(&[
+        (
+            r"^\s*(?:(?:public|private|protected|internal|static|final|abstract|sealed|data|open|partial|inline|value|case|annotation|fileprivate)\s+)*(?P<kind>class|interface|enum|record|struct|object|trait|protocol|extension)\s+(?P<name>[A-Za-z_]\w*)",
+            "",
+        ),
+        (
+            r"^\s*(?:(?:public|private|protected|internal|static|final|abstract|override|open|suspend|inline|operator|fileprivate|mutating)\s+)*(?:fun|func|def)\s+(?:<[^>]*>\s*)?(?:[\w.]+\.)?(?P<name>[A-Za-z_]\w*)",
+            "function",
+        ),
+        (
+            r"^\s*(?:(?:public|private|protected|internal|static|final|abstract|virtual|override|async|synchronized|native)\s+)+[\w<>\[\],.?\s]+?\s+(?P<name>[A-Za-z_]\w*)\s*\(",
+            "method",
+        ),
+    ])
+});
+
+static C: LazyLock<Patterns> = LazyLock::new(|| {
+    patterns(&[
+        (
+            r"^\s*(?:typedef\s+)?(?P<kind>struct|class|union|enum)(?:\s+class)?\s+(?P<name>[A-Za-z_]\w*)\s*(?:final\s*)?(?:[:{]|$)",
+            "",
+        ),
+        (r"^#\s*define\s+(?P<name>[A-Za-z_]\w*)", "macro"),
+        (
+            r"^[A-Za-z_][\w:<>,\s*&]*[\s*&](?:\w+::)*(?P<name>~?[A-Za-z_]\w*)\s*\([^;]*$",
+            "function",
+        ),
+    ])
+});
+
+static RUBY: LazyLock<Patterns> = LazyLock::new(|| {
+    patterns(&[
+        (
+            r"^\s*def\s+(?:self\.)?(?P<name>[A-Za-z_]\w*[?!=]?)",
+            "method",
+        ),
+        (
+            r"^\s*(?P<kind>class|module)\s+(?:[A-Z]\w*::)*(?P<name>[A-Z]\w*)",
+            "",
+        ),
+    ])
+});
+
+/// Definitions in `content`, in line order.
+fn extract_definitions(language: Language, content: &str) -> Vec<Definition> {
+    let patterns = match language {
+        Language::Shell => return shell_functions(content),
+        Language::Rust => &*RUST,
+        Language::Python => &*PYTHON,
+        Language::JavaScript => &*JAVASCRIPT,
+        Language::Go => &*GO,
+        Language::Jvm => &*JVM,
+        Language::C => &*C,
+        Language::Ruby => &*RUBY,
+    };
+    let mut definitions = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let Some(captures) = patterns
+            .iter()
+            .find_map(|(regex, kind)| regex.captures(line).map(|captures| (captures, kind)))
+        else {
+            continue;
+        };
+        let (captures, kind) = captures;
+        let Some(name) = captures.name("name").map(|name| name.as_str()) else {
+            continue;
+        };
+        if KEYWORDS.contains(&name) {
+            continue;
+        }
+        let kind = captures.name("kind").map_or(*kind, |kind| kind.as_str());
+        definitions.push(Definition {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            line: index + 1,
+        });
+    }
+    definitions
+}
+
+fn shell_functions(content: &str) -> Vec<Definition> {
+    let Some(tree) = try_parse_shell(content) else {
+        return Vec::new();
+    };
+    let mut definitions = Vec::new();
+    collect_shell_functions(tree.root_node(), content, &mut definitions);
+    definitions
+}
+
+fn collect_shell_functions(node: Node, content: &str, definitions: &mut Vec<Definition>) {
+    if node.kind() == "function_definition"
+        && let Some(name) = node.child_by_field_name("name")
+        && let Some(text) = content.get(name.byte_range())
+    {
+        definitions.push(Definition {
+            name: text.to_string(),
+            kind: "function".to_string(),
+            line: name.start_position().row + 1,
+        });
+    }
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_shell_functions(child, content, definitions);
+    }
+}
+
+/// `Foo::bar` and `Foo.bar` name `bar`.
+fn unqualified(name: &str) -> &str {
+    let name = name.trim();
+    name.rsplit([':', '.']).next().unwrap_or(name)
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Whether `line` contains `name` as a whole word.
+fn mentions(line: &str, name: &str) -> bool {
+    line.match_indices(name).any(|(start, _)| {
+        let before = line[..start].chars().next_back();
+        let after = line[start + name.len()..].chars().next();
+        !before.is_some_and(is_identifier_char) && !after.is_some_and(is_identifier_char)
+    })
+}
+
+fn read_lines(path: &Path) -> Vec<String> {
+    std::fs::read_to_string(path)
+        .map(|content| content.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn line_text(lines: &[String], line: usize) -> String {
+    let text = lines
+        .get(line.saturating_sub(1))
+        .map(|line| line.trim())
+        .unwrap_or_default();
+    if text.chars().count() > MAX_LINE_CHARS {
+        let mut shortened: String = text.chars().take(MAX_LINE_CHARS).collect();
+        shortened.push('…');
+        shortened
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    fn names(language: Language, content: &str) -> Vec<(String, String, usize)> {
+        extract_definitions(language, content)
+            .into_iter()
+            .map(|definition| (definition.name, definition.kind, definition.line))
+            .collect()
+    }
+
+    fn owned(expected: &[(&str, &str, usize)]) -> Vec<(String, String, usize)> {
+        expected
+            .iter()
+            .map(|(name, kind, line)| (name.to_string(), kind.to_string(), *line))
+            .collect()
+    }
+
+    #[test]
+    fn definitions_are_found_per_language() {
+        let rust = "pub(crate) struct Index;\nimpl Index {\n    pub async fn search(&self) {}\n}\nconst LIMIT: usize = 3;\nmacro_rules! log {}\n";
+        assert_eq!(
+            names(Language::Rust, rust),
+            owned(&[
+                ("Index", "struct", 1),
+                ("search", "function", 3),
+                ("LIMIT", "constant", 5),
+                ("log", "macro", 6),
+            ])
+        );
+
+        let python = "class Parser:\n    async def parse(self):\n        if x:\n            pass\nMAX_DEPTH = 4\n";
+        assert_eq!(
+            names(Language::Python, python),
+            owned(&[
+                ("Parser", "class", 1),
+                ("parse", "function", 2),
+                ("MAX_DEPTH", "constant", 5),
+            ])
+        );
+
+        let typescript = "export interface Props {}\nexport const render = async (props: Props) => {\n  if (props) {\n  }\n};\nclass View {\n  draw(ctx) {\n  }\n}\n";
+        assert_eq!(
+            names(Language::JavaScript, typescript),
+            owned(&[
+                ("Props", "interface", 1),
+                ("render", "function", 2),
+                ("View", "class", 6),
+                ("draw", "method", 7),
+            ])
+        );
+
+        let go = "func (s *Server) Serve() error {\n\treturn nil\n}\ntype Server struct {}\n";
+        assert_eq!(
+            names(Language::Go, go),
+            owned(&[("Serve", "function", 1), ("Server", "type", 4)])
+        );
+
+        let shell = "#!/bin/bash\nsetup() {\n  echo hi\n}\nfunction teardown {\n  :\n}\n";
+        assert_eq!(
+            names(Language::Shell, shell),
+            owned(&[("setup", "function", 2), ("teardown", "function", 5)])
+        );
+    }
+
+    #[test]
+    fn queries_follow_changes_to_the_workspace() {
+        let dir = tempdir().expect("tempdir");
+        let root = dir.path().canonicalize().expect("canonicalize");
+        std::fs::create_dir(root.join("src")).expect("mkdir");
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "pub fn parse_config() {}\n\nfn main() {\n    parse_config();\n}\n",
+        )
+        .expect("write");
+        std::fs::write(root.join("build.sh"), "parse_config() {\n  :\n}\n").expect("write");
+        std::fs::write(root.join("notes.txt"), "fn parse_config() {}\n").expect("write");
+
+        let index = SymbolIndex::default();
+        let query = |name: &str, lookup: SymbolLookup| SymbolQuery {
+            name: name.to_string(),
+            lookup,
+            within: None,
+            limit: 10,
+        };
+        let locations = |search: &SymbolSearch| -> Vec<(String, usize)> {
+            search
+                .matches
+                .iter()
+                .map(|found| (found.path.replace('\\', "/"), found.line))
+                .collect()
+        };
+
+        let search = index.search(&root, &query("parse_config", SymbolLookup::Definitions));
+        assert_eq!(
+            locations(&search),
+            vec![("build.sh".to_string(), 1), ("src/lib.rs".to_string(), 1)]
+        );
+        assert_eq!(search.matches[1].text, "pub fn parse_config() {}");
+        assert!(!search.approximate);
+
+        let search = index.search(&root, &query("parse_config", SymbolLookup::References));
+        assert_eq!(locations(&search), vec![("src/lib.rs".to_string(), 4)]);
+
+        let search = index.search(&root, &query("config", SymbolLookup::Definitions));
+        assert!(search.approximate);
+        assert_eq!(search.matches.len(), 2);
+
+        std::fs::write(
+            root.join("src/lib.rs"),
+            "fn main() {}\n\npub fn parse_config_file() {}\n",
+        )
+        .expect("write");
+        std::fs::remove_file(root.join("build.sh")).expect("remove");
+        let search = index.search(
+            &root,
+            &query("parse_config_file", SymbolLookup::Definitions),
+        );
+        assert_eq!(locations(&search), vec![("src/lib.rs".to_string(), 3)]);
+        let search = index.search(&root, &query("parse_config", SymbolLookup::Definitions));
+        assert!(search.approximate);
+
+        let mut limited = query("main", SymbolLookup::Definitions);
+        limited.within = Some(PathBuf::from("tests"));
+        assert_eq!(index.search(&root, &limited), SymbolSearch::default());
+    }
+
+    #[test]
+    fn hitting_the_file_cap_marks_results_incomplete() {
+        let dir = tempdir().expect("tempdir");
+        let root = dir.path().canonicalize().expect("canonicalize");
+        std::fs::write(root.join("a.rs"), "fn first() {}\n").expect("write");
+        std::fs::write(root.join("b.rs"), "fn second() {}\n").expect("write");
+
+        let mut workspace = Workspace::new(&root);
+        workspace.refresh();
+        assert!(!workspace.is_incomplete());
+
+        workspace.max_files = 1;
+        workspace.refresh();
+        assert_eq!(workspace.indexed.len(), 1);
+        assert!(workspace.is_incomplete());
+    }
+
+    #[test]
+    fn references_match_whole_words() {
+        assert!(mentions("let x = parse(y);", "parse"));
+        assert!(!mentions("let x = parser(y);", "parse"));
+        assert!(!mentions("let x = $parse;", "parse"));
+        assert_eq!(unqualified("Config::load"), "load");
+        assert_eq!(unqualified("app.render"), "render");
+    }
+}
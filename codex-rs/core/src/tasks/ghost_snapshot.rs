@@ -113,10 +113,13 @@ impl SessionTask for GhostSnapshotTask {
                             info!("ghost commit captured: {}", ghost_commit.id());
                         }
                         Ok(Err(err)) => match err {
-                            GitToolingError::NotAGitRepository { .. } => info!(
-                                sub_id = ctx_for_task.sub_id.as_str(),
-                                "skipping ghost snapshot because current directory is not a Git repository"
-                            ),
+                            GitToolingError::NotAGitRepository { .. } => {
+                                info!(
+                                    sub_id = ctx_for_task.sub_id.as_str(),
+                                    "current directory is not a Git repository; taking a workspace snapshot instead"
+                                );
+                                snapshot_workspace(&session, &ctx_for_task).await;
+                            }
                             _ => {
                                 warn!(
                                     sub_id = ctx_for_task.sub_id.as_str(),
@@ -162,6 +165,32 @@ impl GhostSnapshotTask {
     }
 }
 
+/// Checkpoints a workspace that is not a git repository, and tells the model
+/// about files changed outside the session since the last turn.
+async fn snapshot_workspace(session: &SessionTaskContext, ctx: &TurnContext) {
+    let sess = &session.session;
+    match sess
+        .services
+        .workspace_snapshots
+        .before_turn(&ctx.cwd)
+        .await
+    {
+        Ok(Some((event, changes))) => {
+            info!("workspace snapshot captured: {}", event.snapshot_id);
+            if let Some(changes) = changes {
+                sess.report_changed_files(ctx, changes).await;
+            }
+            sess.send_event(ctx, EventMsg::WorkspaceSnapshot(event))
+                .await;
+        }
+        Ok(None) => {}
+        Err(err) => warn!(
+            sub_id = ctx.sub_id.as_str(),
+            "failed to capture workspace snapshot: {err}"
+        ),
+    }
+}
+
 fn format_snapshot_warnings(
     ignore_large_untracked_files: Option<i64>,
     ignore_large_untracked_dirs: Option<i64>,
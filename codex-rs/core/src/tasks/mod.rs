@@ -7,12 +7,14 @@ mod user_shell;
 
 use std::sync::Arc;
 use std::time::Duration;
+use std::time::Instant;
 
 use async_trait::async_trait;
 use tokio::select;
 use tokio::sync::Notify;
 use tokio_util::sync::CancellationToken;
 use tokio_util::task::AbortOnDropHandle;
+use tracing::Instrument;
 use tracing::trace;
 use tracing::warn;
 
@@ -27,6 +29,12 @@ use crate::protocol::TurnCompleteEvent;
 use crate::state::ActiveTurn;
 use crate::state::RunningTask;
 use crate::state::TaskKind;
+use crate::watchdog::start_turn_watchdog;
+use codex_protocol::items::AgentMessageContent;
+use codex_protocol::items::AgentMessageItem;
+use codex_protocol::items::TurnItem;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
 use codex_protocol::user_input::UserInput;
 
 pub(crate) use compact::CompactTask;
@@ -123,25 +131,39 @@ impl Session {
             let ctx = Arc::clone(&turn_context);
             let task_for_run = Arc::clone(&task);
             let task_cancellation_token = cancellation_token.child_token();
-            tokio::spawn(async move {
-                let ctx_for_finish = Arc::clone(&ctx);
-                let last_agent_message = task_for_run
-                    .run(
-                        Arc::clone(&session_ctx),
-                        ctx,
-                        input,
-                        task_cancellation_token.child_token(),
-                    )
-                    .await;
-                session_ctx.clone_session().flush_rollout().await;
-                if !task_cancellation_token.is_cancelled() {
-                    // Emit completion uniformly from spawn site so all tasks share the same lifecycle.
-                    let sess = session_ctx.clone_session();
-                    sess.on_task_finished(ctx_for_finish, last_agent_message)
+            tokio::spawn(
+                async move {
+                    let ctx_for_finish = Arc::clone(&ctx);
+                    let started = Instant::now();
+                    let last_agent_message = task_for_run
+                        .run(
+                            Arc::clone(&session_ctx),
+                            ctx,
+                            input,
+                            task_cancellation_token.child_token(),
+                        )
                         .await;
+                    session_ctx.clone_session().flush_rollout().await;
+                    let status = if task_cancellation_token.is_cancelled() {
+                        "aborted"
+                    } else {
+                        "completed"
+                    };
+                    session_ctx
+                        .clone_session()
+                        .services
+                        .otel_manager
+                        .turn_finished(task_kind.as_str(), status, started.elapsed());
+                    if !task_cancellation_token.is_cancelled() {
+                        // Emit completion uniformly from spawn site so all tasks share the same lifecycle.
+                        let sess = session_ctx.clone_session();
+                        sess.on_task_finished(ctx_for_finish, last_agent_message)
+                            .await;
+                    }
+                    done_clone.notify_waiters();
                 }
-                done_clone.notify_waiters();
-            })
+                .in_current_span(),
+            )
         };
 
         let running_task = RunningTask {
@@ -150,6 +172,7 @@ impl Session {
             kind: task_kind,
             task,
             cancellation_token,
+            watchdog: start_turn_watchdog(self, &turn_context).map(Arc::new),
             turn_context: Arc::clone(&turn_context),
         };
         self.register_new_active_task(running_task).await;
@@ -162,6 +185,34 @@ impl Session {
         self.close_unified_exec_processes().await;
     }
 
+    /// Aborts the running turn only while it is still the one started for
+    /// `sub_id`, so a timer that fires as that turn ends cannot abort the
+    /// next one.
+    pub(crate) async fn abort_turn(self: &Arc<Self>, sub_id: &str, reason: TurnAbortReason) {
+        let tasks = {
+            let mut active = self.active_turn.lock().await;
+            match active.take_if(|at| at.tasks.contains_key(sub_id)) {
+                Some(mut at) => {
+                    at.clear_pending().await;
+                    at.drain_tasks()
+                }
+                None => return,
+            }
+        };
+        for task in tasks {
+            self.handle_task_abort(task, reason.clone()).await;
+        }
+        self.close_unified_exec_processes().await;
+    }
+
+    pub(crate) async fn is_turn_running(&self, sub_id: &str) -> bool {
+        self.active_turn
+            .lock()
+            .await
+            .as_ref()
+            .is_some_and(|at| at.tasks.contains_key(sub_id))
+    }
+
     pub async fn on_task_finished(
         self: &Arc<Self>,
         turn_context: Arc<TurnContext>,
@@ -180,7 +231,20 @@ impl Session {
         if should_close_processes {
             self.close_unified_exec_processes().await;
         }
-        let event = EventMsg::TurnComplete(TurnCompleteEvent { last_agent_message });
+        // Whatever the turn did to the files it saw is now known to the model.
+        self.services.file_watch.refresh();
+        self.services
+            .workspace_snapshots
+            .after_turn(&turn_context.cwd)
+            .await;
+        self.services.prefetch.clear();
+        let event = EventMsg::TurnComplete(TurnCompleteEvent {
+            last_agent_message,
+            timing: Some(turn_context.timing.snapshot()),
+            unified_diff: turn_context.take_unified_diff(),
+            verification: turn_context.take_verification(),
+            context_dropped: turn_context.take_context_dropped(),
+        });
         self.send_event(turn_context.as_ref(), event).await;
     }
 
@@ -217,6 +281,8 @@ impl Session {
         }
 
         trace!(task_kind = ?task.kind, sub_id, "aborting running task");
+        // Snapshot before cancelling: cancelled work unregisters as it unwinds.
+        let cancelled = task.turn_context.in_flight_operations.snapshot();
         task.cancellation_token.cancel();
         let session_task = task.task;
 
@@ -234,10 +300,43 @@ impl Session {
         session_task
             .abort(session_ctx, Arc::clone(&task.turn_context))
             .await;
+        self.services.file_watch.refresh();
+        self.services
+            .workspace_snapshots
+            .after_turn(&task.turn_context.cwd)
+            .await;
+        self.services.prefetch.clear();
+        if reason == TurnAbortReason::Stopped {
+            self.keep_partial_message(&task.turn_context).await;
+        }
 
-        let event = EventMsg::TurnAborted(TurnAbortedEvent { reason });
+        let event = EventMsg::TurnAborted(TurnAbortedEvent { reason, cancelled });
         self.send_event(task.turn_context.as_ref(), event).await;
     }
+
+    /// Records the reply the model was streaming when the turn was stopped,
+    /// so the next turn continues from it.
+    async fn keep_partial_message(&self, turn_context: &TurnContext) {
+        let Some((item_id, text)) = turn_context.take_partial_message() else {
+            return;
+        };
+        if text.trim().is_empty() {
+            return;
+        }
+        let item = ResponseItem::Message {
+            id: None,
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText { text: text.clone() }],
+        };
+        self.record_conversation_items(turn_context, std::slice::from_ref(&item))
+            .await;
+        let message = AgentMessageItem {
+            id: item_id,
+            content: vec![AgentMessageContent::Text { text }],
+        };
+        self.emit_turn_item_completed(turn_context, TurnItem::AgentMessage(message))
+            .await;
+    }
 }
 
 #[cfg(test)]
@@ -29,10 +29,23 @@ impl SessionTask for RegularTask {
         cancellation_token: CancellationToken,
     ) -> Option<String> {
         let sess = session.clone_session();
+        let baseline = sess.pull_request_baseline(ctx.as_ref()).await;
         let run_turn_span =
             trace_span!(parent: sess.services.otel_manager.current_span(), "run_turn");
-        run_turn(sess, ctx, input, cancellation_token)
-            .instrument(run_turn_span)
-            .await
+        let last_agent_message = run_turn(
+            Arc::clone(&sess),
+            Arc::clone(&ctx),
+            input,
+            cancellation_token.clone(),
+        )
+        .instrument(run_turn_span)
+        .await;
+        if !cancellation_token.is_cancelled()
+            && let Some(baseline) = baseline
+        {
+            sess.maybe_publish_pull_request(ctx, last_agent_message.clone(), baseline)
+                .await;
+        }
+        last_agent_message
     }
 }
@@ -64,10 +64,10 @@ impl SessionTask for ReviewTask {
             Some(receiver) => process_review_events(session.clone(), ctx.clone(), receiver).await,
             None => None,
         };
-        if !cancellation_token.is_cancelled() {
-            exit_review_mode(session.clone_session(), output.clone(), ctx.clone()).await;
+        if cancellation_token.is_cancelled() {
+            return None;
         }
-        None
+        Some(exit_review_mode(session.clone_session(), output, ctx).await)
     }
 
     async fn abort(&self, session: Arc<SessionTaskContext>, ctx: Arc<TurnContext>) {
@@ -182,11 +182,14 @@ fn parse_review_output_event(text: &str) -> ReviewOutputEvent {
 
 /// Emits an ExitedReviewMode Event with optional ReviewOutput,
 /// and records a developer message with the review output.
+///
+/// Returns the user-facing rendering of the review, which becomes the turn's
+/// last agent message.
 pub(crate) async fn exit_review_mode(
     session: Arc<Session>,
     review_output: Option<ReviewOutputEvent>,
     ctx: Arc<TurnContext>,
-) {
+) -> String {
     const REVIEW_USER_MESSAGE_ID: &str = "review:rollout:user";
     const REVIEW_ASSISTANT_MESSAGE_ID: &str = "review:rollout:assistant";
     let (user_message, assistant_message) = if let Some(out) = review_output.clone() {
@@ -234,9 +237,10 @@ pub(crate) async fn exit_review_mode(
                 id: Some(REVIEW_ASSISTANT_MESSAGE_ID.to_string()),
                 role: "assistant".to_string(),
                 content: vec![ContentItem::OutputText {
-                    text: assistant_message,
+                    text: assistant_message.clone(),
                 }],
             },
         )
         .await;
+    assistant_message
 }
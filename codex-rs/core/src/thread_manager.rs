@@ -9,13 +9,19 @@ use crate::codex::CodexSpawnOk;
 use crate::codex::INITIAL_SUBMIT_ID;
 use crate::codex_thread::CodexThread;
 use crate::config::Config;
+use crate::config_handle::ConfigHandle;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
+use crate::hooks::HookRegistration;
+use crate::hooks::HooksSnapshot;
 use crate::models_manager::manager::ModelsManager;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::SessionConfiguredEvent;
 use crate::rollout::RolloutRecorder;
+use crate::rollout::metadata::ThreadStatus;
+use crate::rollout::metadata::ThreadSummary;
+use crate::rollout::metadata::list_thread_metadata;
 use crate::rollout::truncation;
 use crate::skills::SkillsManager;
 use codex_protocol::ThreadId;
@@ -55,7 +61,12 @@ pub(crate) struct ThreadManagerState {
     auth_manager: Arc<AuthManager>,
     models_manager: Arc<ModelsManager>,
     skills_manager: Arc<SkillsManager>,
+    codex_home: PathBuf,
+    config_handle: ConfigHandle,
     session_source: SessionSource,
+    /// Hooks registered with [`ThreadManager::register_hook`], added to every
+    /// thread started afterwards.
+    embedder_hooks: std::sync::RwLock<Vec<HookRegistration>>,
 }
 
 impl ThreadManager {
@@ -64,16 +75,20 @@ impl ThreadManager {
         auth_manager: Arc<AuthManager>,
         session_source: SessionSource,
     ) -> Self {
+        let threads = Arc::new(RwLock::new(HashMap::new()));
         Self {
             state: Arc::new(ThreadManagerState {
-                threads: Arc::new(RwLock::new(HashMap::new())),
+                config_handle: ConfigHandle::new(Arc::clone(&threads)),
+                threads,
                 models_manager: Arc::new(ModelsManager::new(
                     codex_home.clone(),
                     auth_manager.clone(),
                 )),
-                skills_manager: Arc::new(SkillsManager::new(codex_home)),
+                skills_manager: Arc::new(SkillsManager::new(codex_home.clone())),
+                codex_home,
                 auth_manager,
                 session_source,
+                embedder_hooks: Default::default(),
             }),
             #[cfg(any(test, feature = "test-support"))]
             _test_codex_home_guard: None,
@@ -100,17 +115,21 @@ impl ThreadManager {
         codex_home: PathBuf,
     ) -> Self {
         let auth_manager = AuthManager::from_auth_for_testing(auth);
+        let threads = Arc::new(RwLock::new(HashMap::new()));
         Self {
             state: Arc::new(ThreadManagerState {
-                threads: Arc::new(RwLock::new(HashMap::new())),
+                config_handle: ConfigHandle::new(Arc::clone(&threads)),
+                threads,
                 models_manager: Arc::new(ModelsManager::with_provider(
                     codex_home.clone(),
                     auth_manager.clone(),
                     provider,
                 )),
-                skills_manager: Arc::new(SkillsManager::new(codex_home)),
+                skills_manager: Arc::new(SkillsManager::new(codex_home.clone())),
+                codex_home,
                 auth_manager,
                 session_source: SessionSource::Exec,
+                embedder_hooks: Default::default(),
             }),
             _test_codex_home_guard: None,
         }
@@ -124,6 +143,12 @@ impl ThreadManager {
         self.state.skills_manager.clone()
     }
 
+    /// Handle for changing the model, approval policy or sandbox policy of
+    /// every thread at runtime.
+    pub fn config_handle(&self) -> ConfigHandle {
+        self.state.config_handle.clone()
+    }
+
     pub fn get_models_manager(&self) -> Arc<ModelsManager> {
         self.state.models_manager.clone()
     }
@@ -132,6 +157,33 @@ impl ThreadManager {
         self.state.models_manager.list_models(config).await
     }
 
+    /// Runs `hook` in every thread started, resumed or forked from now on.
+    /// A hook with the same name as an earlier registration replaces it.
+    pub fn register_hook(&self, hook: HookRegistration) {
+        let mut hooks = match self.state.embedder_hooks.write() {
+            Ok(hooks) => hooks,
+            Err(err) => err.into_inner(),
+        };
+        hooks.retain(|existing| existing.name != hook.name);
+        hooks.push(hook);
+    }
+
+    /// Every recorded thread with its metadata, most recently active first.
+    /// Threads held by this manager are reported as active.
+    pub async fn list(&self) -> std::io::Result<Vec<ThreadSummary>> {
+        let mut summaries = list_thread_metadata(&self.state.codex_home).await?;
+        let threads = self.state.threads.read().await;
+        for summary in &mut summaries {
+            if threads
+                .keys()
+                .any(|id| id.to_string() == summary.metadata.thread_id)
+            {
+                summary.metadata.status = ThreadStatus::Active;
+            }
+        }
+        Ok(summaries)
+    }
+
     pub async fn list_thread_ids(&self) -> Vec<ThreadId> {
         self.state.threads.read().await.keys().copied().collect()
     }
@@ -202,6 +254,23 @@ impl ThreadManager {
             .await
     }
 
+    /// Like [`ThreadManager::fork_thread`], but the fork runs with the user
+    /// hooks in `hooks`, usually the parent's
+    /// [`CodexThread::hooks_snapshot`], instead of the ones its config
+    /// registers. They are in place before the fork's first turn. The fork
+    /// keeps its own built-in and managed hooks.
+    pub async fn fork_thread_with_hooks(
+        &self,
+        nth_user_message: usize,
+        config: Config,
+        path: PathBuf,
+        hooks: HooksSnapshot,
+    ) -> CodexResult<NewThread> {
+        let forked = self.fork_thread(nth_user_message, config, path).await?;
+        forked.thread.restore_hooks(hooks);
+        Ok(forked)
+    }
+
     fn agent_control(&self) -> AgentControl {
         AgentControl::new(Arc::downgrade(&self.state))
     }
@@ -237,11 +306,16 @@ impl ThreadManagerState {
 
     pub(crate) async fn spawn_thread(
         &self,
-        config: Config,
+        mut config: Config,
         initial_history: InitialHistory,
         auth_manager: Arc<AuthManager>,
         agent_control: AgentControl,
     ) -> CodexResult<NewThread> {
+        self.config_handle.apply_to_config(&mut config);
+        let embedder_hooks = match self.embedder_hooks.read() {
+            Ok(hooks) => hooks.clone(),
+            Err(err) => err.into_inner().clone(),
+        };
         let CodexSpawnOk {
             codex, thread_id, ..
         } = Codex::spawn(
@@ -252,6 +326,7 @@ impl ThreadManagerState {
             initial_history,
             self.session_source.clone(),
             agent_control,
+            embedder_hooks,
         )
         .await?;
         self.finalize_thread_spawn(codex, thread_id).await
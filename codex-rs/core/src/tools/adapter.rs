@@ -0,0 +1,872 @@
+//! Rewrites tool definitions and calls for a provider's
+//! [`ToolFormat`](crate::model_provider_info::ToolFormat).
+//!
+//! Tools are built once, in the Responses API shape. Before a request the
+//! adapter fits them to the provider: names are shortened or sanitized,
+//! free-form tools become functions taking an `input` string, and parameter
+//! schemas are rewritten for strict or minimal JSON Schema. The conversation
+//! history is rewritten the same way, and the calls in the model's response
+//! are mapped back to the tools Codex knows, so the rest of the session never
+//! sees the provider's format.
+//!
+//! Models without function calling get the tools described in their
+//! instructions instead ([`ToolFormat::text_tool_calls`]). They call a tool
+//! by writing a fenced `tool_call` block, which is parsed out of the reply;
+//! past calls and their results are replayed to them as plain messages.
+//!
+//! [`ToolFormat::text_tool_calls`]: crate::model_provider_info::ToolFormat::text_tool_calls
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use serde_json::Value;
+use serde_json::json;
+use sha1::Digest;
+use sha1::Sha1;
+use uuid::Uuid;
+
+use crate::client_common::tools::ToolSpec;
+use crate::model_provider_info::ModelProviderInfo;
+use crate::model_provider_info::ToolSchemaStyle;
+use crate::model_provider_info::WireApi;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::FunctionCallOutputPayload;
+use codex_protocol::models::ResponseItem;
+
+/// Name length limit of the OpenAI APIs, used when the provider sets none.
+const DEFAULT_MAX_TOOL_NAME_LENGTH: usize = 64;
+
+/// Keywords kept by [`ToolSchemaStyle::Minimal`].
+const MINIMAL_SCHEMA_KEYWORDS: &[&str] = &[
+    "type",
+    "description",
+    "properties",
+    "required",
+    "items",
+    "enum",
+];
+
+/// Opens the tool list appended to the instructions for
+/// [`ToolFormat::text_tool_calls`](crate::model_provider_info::ToolFormat::text_tool_calls).
+const TEXT_TOOL_CALLS_PROMPT: &str = r#"# Tools
+
+Call a tool by writing a fenced `tool_call` block that holds a JSON object with the tool's `name` and its `arguments`:
+
+```tool_call
+{"name": "<tool name>", "arguments": {"<argument>": "<value>"}}
+```
+
+Write one block per call and end your reply after the last block. The result of each call comes back in a message that starts with `Tool result`. Reply without a block once the task is done.
+
+These are the tools you can call:"#;
+
+/// Starts each tool result replayed to a model without function calling.
+const TEXT_TOOL_RESULT_PREFIX: &str = "Tool result";
+
+#[derive(Debug, Clone)]
+pub(crate) struct ToolAdapter {
+    schema: ToolSchemaStyle,
+    freeform_tools: bool,
+    text_tool_calls: bool,
+    /// Provider names of the tools a model can call from text.
+    text_callable: HashSet<String>,
+    parallel_tool_calls: Option<bool>,
+    /// Provider name of each tool whose name had to change, by Codex name.
+    renamed: HashMap<String, String>,
+    /// Codex name of each renamed tool, by provider name.
+    original: HashMap<String, String>,
+    /// Free-form tools sent as functions, by Codex name.
+    freeform_as_function: HashSet<String>,
+}
+
+impl ToolAdapter {
+    pub(crate) fn new(provider: &ModelProviderInfo, tools: &[ToolSpec]) -> Self {
+        let format = &provider.tool_format;
+        let max_name_length = format
+            .max_name_length
+            .unwrap_or(DEFAULT_MAX_TOOL_NAME_LENGTH)
+            .max(1);
+        let text_tool_calls = format.text_tool_calls;
+        let freeform_tools = !text_tool_calls
+            && format
+                .freeform_tools
+                .unwrap_or(provider.wire_api == WireApi::Responses);
+
+        let mut renamed = HashMap::new();
+        let mut original = HashMap::new();
+        let mut freeform_as_function = HashSet::new();
+        let mut text_callable = HashSet::new();
+        for tool in tools {
+            let name = tool.name();
+            if matches!(tool, ToolSpec::Freeform(_)) && !freeform_tools {
+                freeform_as_function.insert(name.to_string());
+            }
+            let adapted = provider_tool_name(name, max_name_length);
+            if text_tool_calls && matches!(tool, ToolSpec::Function(_) | ToolSpec::Freeform(_)) {
+                text_callable.insert(adapted.clone());
+            }
+            if adapted != name {
+                original.insert(adapted.clone(), name.to_string());
+                renamed.insert(name.to_string(), adapted);
+            }
+        }
+        Self {
+            schema: format.schema,
+            freeform_tools,
+            text_tool_calls,
+            text_callable,
+            parallel_tool_calls: format.parallel_tool_calls,
+            renamed,
+            original,
+            freeform_as_function,
+        }
+    }
+
+    /// Whether to let the model call several tools at once.
+    pub(crate) fn parallel_tool_calls(&self, model_supports_parallel: bool) -> bool {
+        model_supports_parallel && self.parallel_tool_calls.unwrap_or(true)
+    }
+
+    /// Whether the model calls tools by writing them in its text.
+    pub(crate) fn text_tool_calls(&self) -> bool {
+        self.text_tool_calls
+    }
+
+    /// The instructions and tools to send. With text tool calls, the adapted
+    /// tools are described at the end of the instructions and none are sent.
+    pub(crate) fn embed_tools(
+        &self,
+        instructions: String,
+        tools: Vec<Value>,
+    ) -> (String, Vec<Value>) {
+        if !self.text_tool_calls || tools.is_empty() {
+            return (instructions, tools);
+        }
+        let mut instructions = instructions;
+        instructions.push_str("\n\n");
+        instructions.push_str(TEXT_TOOL_CALLS_PROMPT);
+        for tool in &tools {
+            if tool.get("type").and_then(Value::as_str) != Some("function") {
+                continue;
+            }
+            let Some(name) = tool.get("name").and_then(Value::as_str) else {
+                continue;
+            };
+            instructions.push_str(&format!("\n\n## {name}\n"));
+            if let Some(description) = tool.get("description").and_then(Value::as_str)
+                && !description.is_empty()
+            {
+                instructions.push_str(description);
+                instructions.push('\n');
+            }
+            if let Some(parameters) = tool.get("parameters") {
+                instructions.push_str(&format!("Arguments (JSON Schema): {parameters}\n"));
+            }
+        }
+        (instructions, Vec::new())
+    }
+
+    /// Fits tools serialized for the Responses API to the provider.
+    pub(crate) fn adapt_tools(&self, tools: Vec<Value>) -> Vec<Value> {
+        tools
+            .into_iter()
+            .map(|mut tool| {
+                if tool.get("type").and_then(Value::as_str) == Some("custom")
+                    && let Some(name) = tool.get("name").and_then(Value::as_str)
+                    && self.freeform_as_function.contains(name)
+                {
+                    tool = freeform_to_function(&tool);
+                }
+                if let Some(name) = tool.get("name").and_then(Value::as_str)
+                    && let Some(adapted) = self.renamed.get(name)
+                {
+                    tool["name"] = Value::String(adapted.clone());
+                }
+                if tool.get("type").and_then(Value::as_str) == Some("function") {
+                    self.adapt_function(&mut tool);
+                }
+                tool
+            })
+            .collect()
+    }
+
+    fn adapt_function(&self, tool: &mut Value) {
+        let Some(parameters) = tool.get_mut("parameters") else {
+            return;
+        };
+        match self.schema {
+            ToolSchemaStyle::Standard => {}
+            ToolSchemaStyle::Strict => {
+                make_strict(parameters);
+                tool["strict"] = Value::Bool(true);
+            }
+            ToolSchemaStyle::Minimal => {
+                make_minimal(parameters);
+                if let Some(tool) = tool.as_object_mut() {
+                    tool.remove("strict");
+                }
+            }
+        }
+    }
+
+    /// Rewrites the tool calls in the history sent to the provider.
+    pub(crate) fn adapt_input(&self, items: Vec<ResponseItem>) -> Vec<ResponseItem> {
+        let items = items.into_iter().map(|item| match item {
+            ResponseItem::FunctionCall {
+                id,
+                name,
+                arguments,
+                call_id,
+            } => ResponseItem::FunctionCall {
+                id,
+                name: self.provider_name(name),
+                arguments,
+                call_id,
+            },
+            ResponseItem::CustomToolCall {
+                id,
+                status: _,
+                call_id,
+                name,
+                input,
+            } if !self.freeform_tools => ResponseItem::FunctionCall {
+                id,
+                name: self.provider_name(name),
+                arguments: json!({ "input": input }).to_string(),
+                call_id,
+            },
+            ResponseItem::CustomToolCall {
+                id,
+                status,
+                call_id,
+                name,
+                input,
+            } => ResponseItem::CustomToolCall {
+                id,
+                status,
+                call_id,
+                name: self.provider_name(name),
+                input,
+            },
+            ResponseItem::CustomToolCallOutput { call_id, output } if !self.freeform_tools => {
+                ResponseItem::FunctionCallOutput {
+                    call_id,
+                    output: FunctionCallOutputPayload {
+                        content: output,
+                        ..Default::default()
+                    },
+                }
+            }
+            item => item,
+        });
+        if !self.text_tool_calls {
+            return items.collect();
+        }
+        let mut call_names = HashMap::new();
+        items
+            .map(|item| match item {
+                ResponseItem::FunctionCall {
+                    name,
+                    arguments,
+                    call_id,
+                    ..
+                } => {
+                    let arguments = serde_json::from_str::<Value>(&arguments)
+                        .unwrap_or(Value::String(arguments));
+                    let call = json!({ "name": name, "arguments": arguments });
+                    call_names.insert(call_id, name);
+                    ResponseItem::Message {
+                        id: None,
+                        role: "assistant".to_string(),
+                        content: vec![ContentItem::OutputText {
+                            text: format!("```tool_call\n{call}\n```"),
+                        }],
+                    }
+                }
+                ResponseItem::FunctionCallOutput { call_id, output } => {
+                    let header = match call_names.get(&call_id) {
+                        Some(name) => format!("{TEXT_TOOL_RESULT_PREFIX} from {name}:"),
+                        None => format!("{TEXT_TOOL_RESULT_PREFIX}:"),
+                    };
+                    ResponseItem::Message {
+                        id: None,
+                        role: "user".to_string(),
+                        content: vec![ContentItem::InputText {
+                            text: format!("{header}\n{}", output.content),
+                        }],
+                    }
+                }
+                item => item,
+            })
+            .collect()
+    }
+
+    /// Maps an item of the provider's response back to Codex's items. With
+    /// text tool calls, an assistant message is split into its remaining text
+    /// and the calls written in it.
+    pub(crate) fn restore_items(&self, item: ResponseItem) -> Vec<ResponseItem> {
+        let ResponseItem::Message { id, role, content } = item else {
+            return vec![self.restore_output(item)];
+        };
+        let mut calls = Vec::new();
+        let mut kept = Vec::with_capacity(content.len());
+        for part in &content {
+            match part {
+                ContentItem::OutputText { text } if self.text_tool_calls && role == "assistant" => {
+                    let (text, found) = self.parse_text_calls(text);
+                    calls.extend(found);
+                    if !text.is_empty() {
+                        kept.push(ContentItem::OutputText { text });
+                    }
+                }
+                part => kept.push(part.clone()),
+            }
+        }
+        if calls.is_empty() {
+            return vec![ResponseItem::Message { id, role, content }];
+        }
+        let mut items = Vec::with_capacity(calls.len() + 1);
+        if !kept.is_empty() {
+            items.push(ResponseItem::Message {
+                id,
+                role,
+                content: kept,
+            });
+        }
+        items.extend(calls.into_iter().map(|call| self.restore_output(call)));
+        items
+    }
+
+    /// Splits `text` into the text around its tool call blocks and the calls
+    /// in them. Blocks tagged `tool_call` are always calls; untagged and
+    /// `json` blocks only when they name a known tool.
+    fn parse_text_calls(&self, text: &str) -> (String, Vec<ResponseItem>) {
+        let mut kept = String::new();
+        let mut calls = Vec::new();
+        let mut rest = text;
+        while let Some(start) = rest.find("```") {
+            let after_fence = &rest[start + 3..];
+            let Some(info_len) = after_fence.find('\n') else {
+                break;
+            };
+            let body = &after_fence[info_len + 1..];
+            let Some(body_len) = body.find("```") else {
+                break;
+            };
+            let block_end = start + 3 + info_len + 1 + body_len + 3;
+            let info = after_fence[..info_len].trim();
+            let call = match info {
+                "tool_call" => self.parse_text_call(&body[..body_len], false),
+                "" | "json" => self.parse_text_call(&body[..body_len], true),
+                _ => None,
+            };
+            match call {
+                Some(call) => {
+                    kept.push_str(&rest[..start]);
+                    calls.push(call);
+                }
+                None => kept.push_str(&rest[..block_end]),
+            }
+            rest = &rest[block_end..];
+        }
+        kept.push_str(rest);
+        (kept.trim().to_string(), calls)
+    }
+
+    fn parse_text_call(&self, body: &str, known_only: bool) -> Option<ResponseItem> {
+        let call: Value = serde_json::from_str(body.trim()).ok()?;
+        let name = call.get("name")?.as_str()?;
+        if known_only && !self.text_callable.contains(name) {
+            return None;
+        }
+        let arguments = match call.get("arguments") {
+            Some(Value::String(arguments)) => arguments.clone(),
+            Some(arguments) => arguments.to_string(),
+            None => "{}".to_string(),
+        };
+        Some(ResponseItem::FunctionCall {
+            id: None,
+            name: name.to_string(),
+            arguments,
+            call_id: format!("call_{}", Uuid::new_v4().simple()),
+        })
+    }
+
+    /// Maps a tool call from the provider back to the tool Codex defined.
+    pub(crate) fn restore_output(&self, item: ResponseItem) -> ResponseItem {
+        match item {
+            ResponseItem::FunctionCall {
+                id,
+                name,
+                arguments,
+                call_id,
+            } => {
+                let name = self.codex_name(name);
+                if !self.freeform_as_function.contains(&name) {
+                    return ResponseItem::FunctionCall {
+                        id,
+                        name,
+                        arguments,
+                        call_id,
+                    };
+                }
+                let input = serde_json::from_str::<Value>(&arguments)
+                    .ok()
+                    .and_then(|arguments| arguments.get("input")?.as_str().map(str::to_string))
+                    .unwrap_or(arguments);
+                ResponseItem::CustomToolCall {
+                    id,
+                    status: None,
+                    call_id,
+                    name,
+                    input,
+                }
+            }
+            ResponseItem::CustomToolCall {
+                id,
+                status,
+                call_id,
+                name,
+                input,
+            } => ResponseItem::CustomToolCall {
+                id,
+                status,
+                call_id,
+                name: self.codex_name(name),
+                input,
+            },
+            item => item,
+        }
+    }
+
+    fn provider_name(&self, name: String) -> String {
+        self.renamed.get(&name).cloned().unwrap_or(name)
+    }
+
+    fn codex_name(&self, name: String) -> String {
+        self.original.get(&name).cloned().unwrap_or(name)
+    }
+}
+
+/// `name` restricted to the characters and length providers accept. Names
+/// that need changing get a hash of the original, so two tools never share
+/// a name.
+fn provider_tool_name(name: &str, max_len: usize) -> String {
+    let valid = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+    if !name.is_empty() && name.len() <= max_len && name.chars().all(valid) {
+        return name.to_string();
+    }
+    let sanitized: String = name
+        .chars()
+        .map(|c| if valid(c) { c } else { '_' })
+        .collect();
+    let hash = format!("{:x}", Sha1::digest(name.as_bytes()));
+    let hash = &hash[..8.min(max_len)];
+    let prefix_len = max_len.saturating_sub(hash.len() + 1);
+    if prefix_len == 0 {
+        return hash.to_string();
+    }
+    let prefix: String = sanitized.chars().take(prefix_len).collect();
+    format!("{prefix}_{hash}")
+}
+
+/// A free-form tool as a function whose one argument is the raw input.
+fn freeform_to_function(tool: &Value) -> Value {
+    let mut description = tool
+        .get("description")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    if let Some(format) = tool.get("format")
+        && let Some(definition) = format.get("definition").and_then(Value::as_str)
+    {
+        let syntax = format
+            .get("syntax")
+            .and_then(Value::as_str)
+            .unwrap_or("grammar");
+        description.push_str(&format!(
+            "\n\nPass the input as the `input` string. It must match this {syntax} grammar:\n{definition}"
+        ));
+    }
+    json!({
+        "type": "function",
+        "name": tool.get("name").cloned().unwrap_or(Value::Null),
+        "description": description,
+        "strict": false,
+        "parameters": {
+            "type": "object",
+            "properties": {
+                "input": { "type": "string", "description": "The tool input." },
+            },
+            "required": ["input"],
+            "additionalProperties": false,
+        },
+    })
+}
+
+/// Closes every object schema and marks all of its properties required,
+/// letting the ones that were optional be `null` instead.
+fn make_strict(schema: &mut Value) {
+    let Some(map) = schema.as_object_mut() else {
+        return;
+    };
+    if let Some(items) = map.get_mut("items") {
+        make_strict(items);
+    }
+    for combiner in ["anyOf", "oneOf", "allOf"] {
+        if let Some(Value::Array(variants)) = map.get_mut(combiner) {
+            variants.iter_mut().for_each(make_strict);
+        }
+    }
+    if !matches!(map.get("properties"), Some(Value::Object(_))) {
+        return;
+    }
+    let required: HashSet<String> = match map.get("required") {
+        Some(Value::Array(required)) => required
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => HashSet::new(),
+    };
+    let Some(Value::Object(properties)) = map.get_mut("properties") else {
+        return;
+    };
+    let mut names = Vec::with_capacity(properties.len());
+    for (name, property) in properties.iter_mut() {
+        make_strict(property);
+        if !required.contains(name)
+            && let Some(Value::String(ty)) = property.get("type").cloned()
+        {
+            property["type"] = json!([ty, "null"]);
+        }
+        names.push(Value::String(name.clone()));
+    }
+    map.insert("required".to_string(), Value::Array(names));
+    map.insert("additionalProperties".to_string(), Value::Bool(false));
+}
+
+/// Drops every keyword outside [`MINIMAL_SCHEMA_KEYWORDS`].
+fn make_minimal(schema: &mut Value) {
+    let Some(map) = schema.as_object_mut() else {
+        return;
+    };
+    map.retain(|key, _| MINIMAL_SCHEMA_KEYWORDS.contains(&key.as_str()));
+    if let Some(items) = map.get_mut("items") {
+        make_minimal(items);
+    }
+    if let Some(Value::Object(properties)) = map.get_mut("properties") {
+        properties.values_mut().for_each(make_minimal);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client_common::tools::FreeformTool;
+    use crate::client_common::tools::FreeformToolFormat;
+    use crate::client_common::tools::ResponsesApiTool;
+    use crate::model_provider_info::ToolFormat;
+    use crate::model_provider_info::create_oss_provider_with_base_url;
+    use crate::tools::spec::JsonSchema;
+    use crate::tools::spec::create_tools_json_for_responses_api;
+    use pretty_assertions::assert_eq;
+    use std::collections::BTreeMap;
+
+    fn function(name: &str) -> ToolSpec {
+        ToolSpec::Function(ResponsesApiTool {
+            name: name.to_string(),
+            description: "Searches".to_string(),
+            strict: false,
+            parameters: JsonSchema::Object {
+                properties: BTreeMap::from([
+                    (
+                        "query".to_string(),
+                        JsonSchema::String { description: None },
+                    ),
+                    (
+                        "limit".to_string(),
+                        JsonSchema::Number { description: None },
+                    ),
+                ]),
+                required: Some(vec!["query".to_string()]),
+                additional_properties: Some(true.into()),
+            },
+        })
+    }
+
+    fn freeform(name: &str) -> ToolSpec {
+        ToolSpec::Freeform(FreeformTool {
+            name: name.to_string(),
+            description: "Edits files".to_string(),
+            format: FreeformToolFormat {
+                r#type: "grammar".to_string(),
+                syntax: "lark".to_string(),
+                definition: "start: /.+/".to_string(),
+            },
+        })
+    }
+
+    fn provider(wire_api: WireApi, tool_format: ToolFormat) -> ModelProviderInfo {
+        ModelProviderInfo {
+            tool_format,
+            ..create_oss_provider_with_base_url("http://localhost:1234/v1", wire_api)
+        }
+    }
+
+    fn adapted(adapter: &ToolAdapter, tools: &[ToolSpec]) -> Vec<Value> {
+        adapter.adapt_tools(create_tools_json_for_responses_api(tools).expect("tools json"))
+    }
+
+    #[test]
+    fn long_names_are_shortened_and_mapped_back() {
+        let long_name = format!("mcp__{}__search", "docs".repeat(20));
+        let tools = vec![function(&long_name), function("shell")];
+        let adapter = ToolAdapter::new(
+            &provider(
+                WireApi::Responses,
+                ToolFormat {
+                    max_name_length: Some(32),
+                    ..Default::default()
+                },
+            ),
+            &tools,
+        );
+
+        let json = adapted(&adapter, &tools);
+        let short_name = json[0]["name"].as_str().expect("name").to_string();
+        assert_eq!(short_name.len(), 32);
+        assert_eq!(json[1]["name"], "shell");
+
+        let call = adapter.restore_output(ResponseItem::FunctionCall {
+            id: None,
+            name: short_name.clone(),
+            arguments: "{}".to_string(),
+            call_id: "call-1".to_string(),
+        });
+        let ResponseItem::FunctionCall { name, .. } = call else {
+            panic!("expected a function call, got {call:?}");
+        };
+        assert_eq!(name, long_name);
+
+        let history = adapter.adapt_input(vec![ResponseItem::FunctionCall {
+            id: None,
+            name: long_name,
+            arguments: "{}".to_string(),
+            call_id: "call-1".to_string(),
+        }]);
+        assert!(
+            matches!(&history[0], ResponseItem::FunctionCall { name, .. } if *name == short_name)
+        );
+    }
+
+    #[test]
+    fn freeform_tools_become_functions_without_provider_support() {
+        let tools = vec![freeform("apply_patch")];
+        let adapter = ToolAdapter::new(&provider(WireApi::Chat, ToolFormat::default()), &tools);
+
+        let json = adapted(&adapter, &tools);
+        assert_eq!(json[0]["type"], "function");
+        assert_eq!(json[0]["parameters"]["required"], json!(["input"]));
+
+        let call = adapter.restore_output(ResponseItem::FunctionCall {
+            id: None,
+            name: "apply_patch".to_string(),
+            arguments: json!({ "input": "*** Begin Patch" }).to_string(),
+            call_id: "call-1".to_string(),
+        });
+        assert_eq!(
+            call,
+            ResponseItem::CustomToolCall {
+                id: None,
+                status: None,
+                call_id: "call-1".to_string(),
+                name: "apply_patch".to_string(),
+                input: "*** Begin Patch".to_string(),
+            }
+        );
+        let history = adapter.adapt_input(vec![
+            call,
+            ResponseItem::CustomToolCallOutput {
+                call_id: "call-1".to_string(),
+                output: "Done".to_string(),
+            },
+        ]);
+        assert!(matches!(&history[0], ResponseItem::FunctionCall { .. }));
+        assert!(matches!(
+            &history[1],
+            ResponseItem::FunctionCallOutput { .. }
+        ));
+
+        // The Responses API takes free-form tools as they are.
+        let responses =
+            ToolAdapter::new(&provider(WireApi::Responses, ToolFormat::default()), &tools);
+        assert_eq!(adapted(&responses, &tools)[0]["type"], "custom");
+    }
+
+    #[test]
+    fn schemas_are_rewritten_for_the_provider_style() {
+        let tools = vec![function("search")];
+        let strict = ToolAdapter::new(
+            &provider(
+                WireApi::Responses,
+                ToolFormat {
+                    schema: ToolSchemaStyle::Strict,
+                    ..Default::default()
+                },
+            ),
+            &tools,
+        );
+        let json = adapted(&strict, &tools);
+        assert_eq!(json[0]["strict"], true);
+        assert_eq!(
+            json[0]["parameters"],
+            json!({
+                "type": "object",
+                "properties": {
+                    "limit": { "type": ["number", "null"] },
+                    "query": { "type": "string" },
+                },
+                "required": ["limit", "query"],
+                "additionalProperties": false,
+            })
+        );
+
+        let minimal = ToolAdapter::new(
+            &provider(
+                WireApi::Chat,
+                ToolFormat {
+                    schema: ToolSchemaStyle::Minimal,
+                    parallel_tool_calls: Some(false),
+                    ..Default::default()
+                },
+            ),
+            &tools,
+        );
+        let json = adapted(&minimal, &tools);
+        assert_eq!(json[0].get("strict"), None);
+        assert_eq!(json[0]["parameters"].get("additionalProperties"), None);
+        assert!(!minimal.parallel_tool_calls(true));
+    }
+
+    #[test]
+    fn text_tool_calls_are_described_parsed_and_replayed() {
+        let tools = vec![function("search"), freeform("apply_patch")];
+        let adapter = ToolAdapter::new(
+            &provider(
+                WireApi::Chat,
+                ToolFormat {
+                    text_tool_calls: true,
+                    ..Default::default()
+                },
+            ),
+            &tools,
+        );
+
+        let (instructions, json) =
+            adapter.embed_tools("Be helpful.".to_string(), adapted(&adapter, &tools));
+        assert_eq!(json, Vec::<Value>::new());
+        assert!(instructions.starts_with("Be helpful.\n\n# Tools"));
+        assert!(instructions.contains("## search\nSearches\nArguments (JSON Schema): "));
+        assert!(instructions.contains("## apply_patch\nEdits files"));
+
+        let reply = ResponseItem::Message {
+            id: None,
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText {
+                text: concat!(
+                    "Let me look.\n",
+                    "```tool_call\n{\"name\": \"search\", \"arguments\": {\"query\": \"adapter\"}}\n```\n",
+                    "```json\n{\"name\": \"apply_patch\", \"arguments\": {\"input\": \"*** Begin Patch\"}}\n```\n",
+                    "```json\n{\"name\": \"unknown\"}\n```",
+                )
+                .to_string(),
+            }],
+        };
+        let items = adapter.restore_items(reply);
+        assert_eq!(items.len(), 3);
+        assert_eq!(
+            items[0],
+            ResponseItem::Message {
+                id: None,
+                role: "assistant".to_string(),
+                content: vec![ContentItem::OutputText {
+                    text: "Let me look.\n\n\n```json\n{\"name\": \"unknown\"}\n```".to_string(),
+                }],
+            }
+        );
+        let ResponseItem::FunctionCall {
+            name,
+            arguments,
+            call_id,
+            ..
+        } = items[1].clone()
+        else {
+            panic!("expected a function call, got {:?}", items[1]);
+        };
+        assert_eq!(name, "search");
+        assert_eq!(arguments, r#"{"query":"adapter"}"#);
+        assert!(matches!(
+            &items[2],
+            ResponseItem::CustomToolCall { name, input, .. }
+                if name == "apply_patch" && input == "*** Begin Patch"
+        ));
+
+        let history = adapter.adapt_input(vec![
+            items[1].clone(),
+            ResponseItem::FunctionCallOutput {
+                call_id,
+                output: FunctionCallOutputPayload {
+                    content: "1 match".to_string(),
+                    ..Default::default()
+                },
+            },
+        ]);
+        let [
+            ResponseItem::Message {
+                role: call_role,
+                content: call_content,
+                ..
+            },
+            ResponseItem::Message {
+                role: result_role,
+                content: result_content,
+                ..
+            },
+        ] = history.as_slice()
+        else {
+            panic!("expected two messages, got {history:?}");
+        };
+        assert_eq!(
+            (call_role.as_str(), result_role.as_str()),
+            ("assistant", "user")
+        );
+        let [ContentItem::OutputText { text }] = call_content.as_slice() else {
+            panic!("expected the call as text, got {call_content:?}");
+        };
+        let call = text
+            .strip_prefix("```tool_call\n")
+            .and_then(|text| text.strip_suffix("\n```"))
+            .expect("fenced call");
+        assert_eq!(
+            serde_json::from_str::<Value>(call).expect("call json"),
+            json!({ "name": "search", "arguments": { "query": "adapter" } })
+        );
+        assert_eq!(
+            result_content,
+            &vec![ContentItem::InputText {
+                text: "Tool result from search:\n1 match".to_string(),
+            }]
+        );
+
+        // Plain replies pass through untouched.
+        let plain = ResponseItem::Message {
+            id: None,
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText {
+                text: "All done.".to_string(),
+            }],
+        };
+        assert_eq!(adapter.restore_items(plain.clone()), vec![plain]);
+    }
+}
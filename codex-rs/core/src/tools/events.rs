@@ -22,6 +22,7 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 use super::format_exec_output_str;
+use super::format_exec_output_summary;
 
 #[derive(Clone, Copy)]
 pub(crate) struct ToolEventCtx<'a> {
@@ -260,11 +261,13 @@ impl ToolEmitter {
         output: &ExecToolCallOutput,
         ctx: ToolEventCtx<'_>,
     ) -> String {
+        let policy = ctx.turn.truncation_policy;
+        let summarize = ctx.turn.summarizes_tool_output();
         match self {
             Self::Shell { freeform: true, .. } => {
-                super::format_exec_output_for_model_freeform(output, ctx.turn.truncation_policy)
+                super::format_exec_output_for_model_freeform(output, policy, summarize)
             }
-            _ => super::format_exec_output_for_model_structured(output, ctx.turn.truncation_policy),
+            _ => super::format_exec_output_for_model_structured(output, policy, summarize),
         }
     }
 
@@ -385,7 +388,11 @@ async fn emit_exec_stage(
                 aggregated_output: output.aggregated_output.text.clone(),
                 exit_code: output.exit_code,
                 duration: output.duration,
-                formatted_output: format_exec_output_str(&output, ctx.turn.truncation_policy),
+                formatted_output: if ctx.turn.summarizes_tool_output() {
+                    format_exec_output_summary(&output, ctx.turn.truncation_policy)
+                } else {
+                    format_exec_output_str(&output, ctx.turn.truncation_policy)
+                },
             };
             emit_exec_end(ctx, exec_input, exec_result).await;
         }
@@ -409,6 +416,13 @@ async fn emit_exec_end(
     exec_input: ExecCommandInput<'_>,
     exec_result: ExecCommandResult,
 ) {
+    if exec_result.exit_code == 0 {
+        for parsed in exec_input.parsed_cmd {
+            if let ParsedCommand::Read { path, .. } = parsed {
+                ctx.session.services.file_watch.track(exec_input.cwd, path);
+            }
+        }
+    }
     ctx.session
         .send_event(
             ctx.turn,
@@ -439,6 +453,19 @@ async fn emit_patch_end(
     stderr: String,
     success: bool,
 ) {
+    if success {
+        for (path, change) in &changes {
+            let path = match change {
+                FileChange::Update {
+                    move_path: Some(move_path),
+                    ..
+                } => move_path,
+                FileChange::Delete { .. } => continue,
+                FileChange::Add { .. } | FileChange::Update { .. } => path,
+            };
+            ctx.session.services.file_watch.track(&ctx.turn.cwd, path);
+        }
+    }
     ctx.session
         .send_event(
             ctx.turn,
@@ -4,6 +4,7 @@ use std::path::Path;
 use crate::apply_patch;
 use crate::apply_patch::InternalApplyPatchInvocation;
 use crate::apply_patch::convert_apply_patch_to_protocol;
+use crate::apply_patch::rejected_hunks_feedback;
 use crate::client_common::tools::FreeformTool;
 use crate::client_common::tools::FreeformToolFormat;
 use crate::client_common::tools::ResponsesApiTool;
@@ -11,6 +12,8 @@ use crate::client_common::tools::ToolSpec;
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::function_tool::FunctionCallError;
+use crate::patch_validation::SyntaxError;
+use crate::patch_validation::syntax_errors_feedback;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
@@ -59,6 +62,32 @@ fn to_abs_path(cwd: &Path, path: &Path) -> Option<AbsolutePathBuf> {
     AbsolutePathBuf::resolve_path_against_base(path, cwd).ok()
 }
 
+/// Appends the hunks the user rejected to the tool output so the model knows
+/// which parts of its patch were not applied.
+/// Appends what the model should know about how the patch went: hunks the
+/// user rejected and syntax errors `[patch_validation]` found.
+fn with_patch_feedback(
+    mut content: String,
+    req: &ApplyPatchRequest,
+    runtime: &ApplyPatchRuntime,
+    syntax_errors: &[SyntaxError],
+) -> String {
+    let rejected = runtime.rejected_hunks();
+    if !rejected.is_empty() {
+        content = format!(
+            "{content}\n\n{}",
+            rejected_hunks_feedback(&req.action, rejected)
+        );
+    }
+    if !syntax_errors.is_empty() {
+        content = format!(
+            "{content}\n\nThe patch left these files with syntax errors; fix them:\n{}",
+            syntax_errors_feedback(syntax_errors)
+        );
+    }
+    content
+}
+
 #[async_trait]
 impl ToolHandler for ApplyPatchHandler {
     fn kind(&self) -> ToolKind {
@@ -105,7 +134,7 @@ impl ToolHandler for ApplyPatchHandler {
         let command = vec!["apply_patch".to_string(), patch_input.clone()];
         match codex_apply_patch::maybe_parse_apply_patch_verified(&command, &cwd) {
             codex_apply_patch::MaybeApplyPatchVerified::Body(changes) => {
-                match apply_patch::apply_patch(turn.as_ref(), changes).await {
+                match apply_patch::apply_patch(session.as_ref(), turn.as_ref(), changes).await {
                     InternalApplyPatchInvocation::Output(item) => {
                         let content = item?;
                         Ok(ToolOutput::Function {
@@ -154,6 +183,8 @@ impl ToolHandler for ApplyPatchHandler {
                             Some(&tracker),
                         );
                         let content = emitter.finish(event_ctx, out).await?;
+                        let content =
+                            with_patch_feedback(content, &req, &runtime, &apply.syntax_errors);
                         Ok(ToolOutput::Function {
                             content,
                             content_items: None,
@@ -201,7 +232,7 @@ pub(crate) async fn intercept_apply_patch(
                     turn,
                 )
                 .await;
-            match apply_patch::apply_patch(turn, changes).await {
+            match apply_patch::apply_patch(session, turn, changes).await {
                 InternalApplyPatchInvocation::Output(item) => {
                     let content = item?;
                     Ok(Some(ToolOutput::Function {
@@ -241,6 +272,8 @@ pub(crate) async fn intercept_apply_patch(
                     let event_ctx =
                         ToolEventCtx::new(session, turn, call_id, tracker.as_ref().copied());
                     let content = emitter.finish(event_ctx, out).await?;
+                    let content =
+                        with_patch_feedback(content, &req, &runtime, &apply.syntax_errors);
                     Ok(Some(ToolOutput::Function {
                         content,
                         content_items: None,
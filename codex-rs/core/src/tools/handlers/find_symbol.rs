@@ -0,0 +1,194 @@
+use std::path::Component;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::symbol_index::SymbolLookup;
+use crate::symbol_index::SymbolQuery;
+use crate::symbol_index::SymbolSearch;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct FindSymbolHandler;
+
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 500;
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+#[derive(Deserialize)]
+struct FindSymbolArgs {
+    name: String,
+    #[serde(default)]
+    find: SymbolLookup,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+#[async_trait]
+impl ToolHandler for FindSymbolHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "find_symbol handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: FindSymbolArgs = parse_arguments(&arguments)?;
+
+        let name = args.name.trim().to_string();
+        if name.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "name must not be empty".to_string(),
+            ));
+        }
+
+        if args.limit == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "limit must be greater than zero".to_string(),
+            ));
+        }
+
+        let within = match args.path {
+            Some(path) => {
+                let resolved = turn.resolve_path(Some(path));
+                match resolved.strip_prefix(&turn.cwd) {
+                    Ok(relative) if relative.as_os_str().is_empty() => None,
+                    Ok(relative)
+                        if !relative
+                            .components()
+                            .any(|component| component == Component::ParentDir) =>
+                    {
+                        Some(relative.to_path_buf())
+                    }
+                    _ => {
+                        return Err(FunctionCallError::RespondToModel(format!(
+                            "`{}` is outside the workspace `{}`",
+                            resolved.display(),
+                            turn.cwd.display()
+                        )));
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let query = SymbolQuery {
+            name,
+            lookup: args.find,
+            within,
+            limit: args.limit.min(MAX_LIMIT),
+        };
+        let index = session.services.symbol_index.clone();
+        let root = turn.cwd.clone();
+        let background_query = query.clone();
+        let search = tokio::task::spawn_blocking(move || index.search(&root, &background_query))
+            .await
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!("symbol search failed: {err}"))
+            })?;
+
+        let success = !search.matches.is_empty();
+        Ok(ToolOutput::Function {
+            content: format_search(&query, &search),
+            content_items: None,
+            success: Some(success),
+        })
+    }
+}
+
+fn format_search(query: &SymbolQuery, search: &SymbolSearch) -> String {
+    let what = match query.lookup {
+        SymbolLookup::Definitions => "definitions",
+        SymbolLookup::References => "references",
+    };
+    let mut lines = Vec::new();
+    if search.matches.is_empty() {
+        lines.push(format!("No {what} of `{}` found.", query.name));
+    } else if search.approximate {
+        lines.push(format!(
+            "No definition named exactly `{}`; similar names:",
+            query.name
+        ));
+    }
+    for found in &search.matches {
+        let location = format!("{}:{}", found.path, found.line);
+        lines.push(match &found.kind {
+            Some(kind) => format!("{location} [{kind}] {}", found.text),
+            None => format!("{location} {}", found.text),
+        });
+    }
+    if search.truncated {
+        lines.push(format!(
+            "More {what} were omitted; narrow the search with `path` or raise `limit`."
+        ));
+    }
+    if search.incomplete {
+        lines.push(
+            "Not every file in the workspace has been indexed; results may be incomplete."
+                .to_string(),
+        );
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbol_index::SymbolMatch;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn formats_matches_and_notes() {
+        let query = SymbolQuery {
+            name: "parse".to_string(),
+            lookup: SymbolLookup::Definitions,
+            within: None,
+            limit: 1,
+        };
+        let search = SymbolSearch {
+            matches: vec![SymbolMatch {
+                path: "src/lib.rs".to_string(),
+                line: 12,
+                kind: Some("function".to_string()),
+                text: "pub fn parse_args() {".to_string(),
+            }],
+            truncated: true,
+            approximate: true,
+            incomplete: false,
+        };
+        assert_eq!(
+            format_search(&query, &search),
+            "No definition named exactly `parse`; similar names:\n\
+             src/lib.rs:12 [function] pub fn parse_args() {\n\
+             More definitions were omitted; narrow the search with `path` or raise `limit`."
+        );
+        assert_eq!(
+            format_search(&query, &SymbolSearch::default()),
+            "No definitions of `parse` found."
+        );
+    }
+}
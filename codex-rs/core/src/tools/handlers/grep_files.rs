@@ -2,6 +2,7 @@ use std::path::Path;
 use std::time::Duration;
 
 use async_trait::async_trait;
+use codex_file_search::is_codex_ignored;
 use serde::Deserialize;
 use tokio::process::Command;
 use tokio::time::timeout;
@@ -141,7 +142,7 @@ async fn run_rg_search(
         })?;
 
     match output.status.code() {
-        Some(0) => Ok(parse_results(&output.stdout, limit)),
+        Some(0) => Ok(parse_results(&output.stdout, limit, cwd)),
         Some(1) => Ok(Vec::new()),
         _ => {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -152,14 +153,16 @@ async fn run_rg_search(
     }
 }
 
-fn parse_results(stdout: &[u8], limit: usize) -> Vec<String> {
+/// Paths printed by `rg`, resolved against `cwd`, leaving out those hidden by
+/// a `.codexignore`.
+fn parse_results(stdout: &[u8], limit: usize, cwd: &Path) -> Vec<String> {
     let mut results = Vec::new();
     for line in stdout.split(|byte| *byte == b'\n') {
         if line.is_empty() {
             continue;
         }
         if let Ok(text) = std::str::from_utf8(line) {
-            if text.is_empty() {
+            if text.is_empty() || is_codex_ignored(cwd, Path::new(text), false) {
                 continue;
             }
             results.push(text.to_string());
@@ -180,7 +183,7 @@ mod tests {
     #[test]
     fn parses_basic_results() {
         let stdout = b"/tmp/file_a.rs\n/tmp/file_b.rs\n";
-        let parsed = parse_results(stdout, 10);
+        let parsed = parse_results(stdout, 10, Path::new("/"));
         assert_eq!(
             parsed,
             vec!["/tmp/file_a.rs".to_string(), "/tmp/file_b.rs".to_string()]
@@ -190,7 +193,7 @@ mod tests {
     #[test]
     fn parse_truncates_after_limit() {
         let stdout = b"/tmp/file_a.rs\n/tmp/file_b.rs\n/tmp/file_c.rs\n";
-        let parsed = parse_results(stdout, 2);
+        let parsed = parse_results(stdout, 2, Path::new("/"));
         assert_eq!(
             parsed,
             vec!["/tmp/file_a.rs".to_string(), "/tmp/file_b.rs".to_string()]
@@ -247,6 +250,24 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn run_search_skips_codex_ignored_files() -> anyhow::Result<()> {
+        if !rg_available() {
+            return Ok(());
+        }
+        let temp = tempdir().expect("create temp dir");
+        let dir = temp.path();
+        std::fs::create_dir(dir.join("fixtures")).unwrap();
+        std::fs::write(dir.join(".codexignore"), "fixtures/\n").unwrap();
+        std::fs::write(dir.join("fixtures/dump.txt"), "alpha dump").unwrap();
+        std::fs::write(dir.join("source.txt"), "alpha source").unwrap();
+
+        let results = run_rg_search("alpha", None, dir, 10, dir).await?;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].ends_with("source.txt"));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn run_search_handles_no_matches() -> anyhow::Result<()> {
         if !rg_available() {
@@ -5,6 +5,7 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use async_trait::async_trait;
+use codex_file_search::is_codex_ignored;
 use codex_utils_string::take_bytes_at_char_boundary;
 use serde::Deserialize;
 use tokio::fs;
@@ -13,6 +14,7 @@ use crate::function_tool::FunctionCallError;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
+use crate::tools::handlers::ensure_not_codex_ignored;
 use crate::tools::handlers::parse_arguments;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
@@ -52,7 +54,7 @@ impl ToolHandler for ListDirHandler {
     }
 
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
-        let ToolInvocation { payload, .. } = invocation;
+        let ToolInvocation { payload, turn, .. } = invocation;
 
         let arguments = match payload {
             ToolPayload::Function { arguments } => arguments,
@@ -96,6 +98,7 @@ impl ToolHandler for ListDirHandler {
                 "dir_path must be an absolute path".to_string(),
             ));
         }
+        ensure_not_codex_ignored(&turn.cwd, &path, true)?;
 
         let entries = list_dir_slice(&path, offset, limit, depth).await?;
         let mut output = Vec::with_capacity(entries.len() + 1);
@@ -171,6 +174,11 @@ async fn collect_entries(
                 FunctionCallError::RespondToModel(format!("failed to inspect entry: {err}"))
             })?;
 
+            let kind = DirEntryKind::from(&file_type);
+            if is_codex_ignored(&current_dir, &entry.path(), kind == DirEntryKind::Directory) {
+                continue;
+            }
+
             let file_name = entry.file_name();
             let relative_path = if prefix.as_os_str().is_empty() {
                 PathBuf::from(&file_name)
@@ -181,7 +189,6 @@ async fn collect_entries(
             let display_name = format_entry_component(&file_name);
             let display_depth = prefix.components().count();
             let sort_key = format_entry_name(&relative_path);
-            let kind = DirEntryKind::from(&file_type);
             dir_entries.push((
                 entry.path(),
                 relative_path,
@@ -1,5 +1,6 @@
 pub mod apply_patch;
 pub(crate) mod collab;
+mod find_symbol;
 mod grep_files;
 mod list_dir;
 mod mcp;
@@ -11,12 +12,16 @@ mod test_sync;
 mod unified_exec;
 mod view_image;
 
+use std::path::Path;
+
+use codex_file_search::is_codex_ignored;
 pub use plan::PLAN_TOOL;
 use serde::Deserialize;
 
 use crate::function_tool::FunctionCallError;
 pub use apply_patch::ApplyPatchHandler;
 pub use collab::CollabHandler;
+pub use find_symbol::FindSymbolHandler;
 pub use grep_files::GrepFilesHandler;
 pub use list_dir::ListDirHandler;
 pub use mcp::McpHandler;
@@ -37,3 +42,19 @@ where
         FunctionCallError::RespondToModel(format!("failed to parse function arguments: {err}"))
     })
 }
+
+/// Refuses `path` when a `.codexignore` hides it from the agent. `cwd` is
+/// the turn's.
+fn ensure_not_codex_ignored(
+    cwd: &Path,
+    path: &Path,
+    is_dir: bool,
+) -> Result<(), FunctionCallError> {
+    if is_codex_ignored(cwd, path, is_dir) {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "`{}` is excluded by a .codexignore file",
+            path.display()
+        )));
+    }
+    Ok(())
+}
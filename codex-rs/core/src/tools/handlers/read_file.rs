@@ -1,14 +1,19 @@
 use std::collections::VecDeque;
+use std::path::Path;
 use std::path::PathBuf;
 
 use async_trait::async_trait;
 use codex_utils_string::take_bytes_at_char_boundary;
 use serde::Deserialize;
+use tokio::fs::File;
+use tokio::io::AsyncBufRead;
+use tokio::io::BufReader;
 
 use crate::function_tool::FunctionCallError;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
+use crate::tools::handlers::ensure_not_codex_ignored;
 use crate::tools::handlers::parse_arguments;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
@@ -97,7 +102,12 @@ impl ToolHandler for ReadFileHandler {
     }
 
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
-        let ToolInvocation { payload, .. } = invocation;
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            ..
+        } = invocation;
 
         let arguments = match payload {
             ToolPayload::Function { arguments } => arguments,
@@ -136,14 +146,17 @@ impl ToolHandler for ReadFileHandler {
                 "file_path must be an absolute path".to_string(),
             ));
         }
+        ensure_not_codex_ignored(&turn.cwd, &path, false)?;
 
+        let prefetched = session.services.prefetch.take(&path).await;
         let collected = match mode {
-            ReadMode::Slice => slice::read(&path, offset, limit).await?,
+            ReadMode::Slice => slice::read(&path, prefetched, offset, limit).await?,
             ReadMode::Indentation => {
                 let indentation = indentation.unwrap_or_default();
-                indentation::read_block(&path, offset, limit, indentation).await?
+                indentation::read_block(&path, prefetched, offset, limit, indentation).await?
             }
         };
+        session.services.file_watch.track(&turn.cwd, &path);
         Ok(ToolOutput::Function {
             content: collected.join("\n"),
             content_items: None,
@@ -152,24 +165,35 @@ impl ToolHandler for ReadFileHandler {
     }
 }
 
+/// Opens `path` for reading line by line, or reads `prefetched` instead when
+/// the file was already read in the background.
+async fn open(
+    path: &Path,
+    prefetched: Option<Vec<u8>>,
+) -> Result<Box<dyn AsyncBufRead + Send + Unpin>, FunctionCallError> {
+    if let Some(contents) = prefetched {
+        return Ok(Box::new(std::io::Cursor::new(contents)));
+    }
+    let file = File::open(path)
+        .await
+        .map_err(|err| FunctionCallError::RespondToModel(format!("failed to read file: {err}")))?;
+    Ok(Box::new(BufReader::new(file)))
+}
+
 mod slice {
     use crate::function_tool::FunctionCallError;
     use crate::tools::handlers::read_file::format_line;
+    use crate::tools::handlers::read_file::open;
     use std::path::Path;
-    use tokio::fs::File;
     use tokio::io::AsyncBufReadExt;
-    use tokio::io::BufReader;
 
     pub async fn read(
         path: &Path,
+        prefetched: Option<Vec<u8>>,
         offset: usize,
         limit: usize,
     ) -> Result<Vec<String>, FunctionCallError> {
-        let file = File::open(path).await.map_err(|err| {
-            FunctionCallError::RespondToModel(format!("failed to read file: {err}"))
-        })?;
-
-        let mut reader = BufReader::new(file);
+        let mut reader = open(path, prefetched).await?;
         let mut collected = Vec::new();
         let mut seen = 0usize;
         let mut buffer = Vec::new();
@@ -225,15 +249,15 @@ mod indentation {
     use crate::tools::handlers::read_file::LineRecord;
     use crate::tools::handlers::read_file::TAB_WIDTH;
     use crate::tools::handlers::read_file::format_line;
+    use crate::tools::handlers::read_file::open;
     use crate::tools::handlers::read_file::trim_empty_lines;
     use std::collections::VecDeque;
     use std::path::Path;
-    use tokio::fs::File;
     use tokio::io::AsyncBufReadExt;
-    use tokio::io::BufReader;
 
     pub async fn read_block(
         path: &Path,
+        prefetched: Option<Vec<u8>>,
         offset: usize,
         limit: usize,
         options: IndentationArgs,
@@ -252,7 +276,7 @@ mod indentation {
             ));
         }
 
-        let collected = collect_file_lines(path).await?;
+        let collected = collect_file_lines(path, prefetched).await?;
         if collected.is_empty() || anchor_line > collected.len() {
             return Err(FunctionCallError::RespondToModel(
                 "anchor_line exceeds file length".to_string(),
@@ -364,12 +388,11 @@ mod indentation {
             .collect())
     }
 
-    async fn collect_file_lines(path: &Path) -> Result<Vec<LineRecord>, FunctionCallError> {
-        let file = File::open(path).await.map_err(|err| {
-            FunctionCallError::RespondToModel(format!("failed to read file: {err}"))
-        })?;
-
-        let mut reader = BufReader::new(file);
+    async fn collect_file_lines(
+        path: &Path,
+        prefetched: Option<Vec<u8>>,
+    ) -> Result<Vec<LineRecord>, FunctionCallError> {
+        let mut reader = open(path, prefetched).await?;
         let mut buffer = Vec::new();
         let mut lines = Vec::new();
         let mut number = 0usize;
@@ -508,18 +531,27 @@ gamma
 "
         )?;
 
-        let lines = read(temp.path(), 2, 2).await?;
+        let lines = read(temp.path(), None, 2, 2).await?;
         assert_eq!(lines, vec!["L2: beta".to_string(), "L3: gamma".to_string()]);
         Ok(())
     }
 
+    #[tokio::test]
+    async fn prefers_prefetched_contents() -> anyhow::Result<()> {
+        let temp = NamedTempFile::new()?;
+
+        let lines = read(temp.path(), Some(b"alpha\r\nbeta\n".to_vec()), 1, 5).await?;
+        assert_eq!(lines, vec!["L1: alpha".to_string(), "L2: beta".to_string()]);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn errors_when_offset_exceeds_length() -> anyhow::Result<()> {
         let mut temp = NamedTempFile::new()?;
         use std::io::Write as _;
         writeln!(temp, "only")?;
 
-        let err = read(temp.path(), 3, 1)
+        let err = read(temp.path(), None, 3, 1)
             .await
             .expect_err("offset exceeds length");
         assert_eq!(
@@ -535,7 +567,7 @@ gamma
         use std::io::Write as _;
         temp.as_file_mut().write_all(b"\xff\xfe\nplain\n")?;
 
-        let lines = read(temp.path(), 1, 2).await?;
+        let lines = read(temp.path(), None, 1, 2).await?;
         let expected_first = format!("L1: {}{}", '\u{FFFD}', '\u{FFFD}');
         assert_eq!(lines, vec![expected_first, "L2: plain".to_string()]);
         Ok(())
@@ -547,7 +579,7 @@ gamma
         use std::io::Write as _;
         write!(temp, "one\r\ntwo\r\n")?;
 
-        let lines = read(temp.path(), 1, 2).await?;
+        let lines = read(temp.path(), None, 1, 2).await?;
         assert_eq!(lines, vec!["L1: one".to_string(), "L2: two".to_string()]);
         Ok(())
     }
@@ -564,7 +596,7 @@ third
 "
         )?;
 
-        let lines = read(temp.path(), 1, 2).await?;
+        let lines = read(temp.path(), None, 1, 2).await?;
         assert_eq!(
             lines,
             vec!["L1: first".to_string(), "L2: second".to_string()]
@@ -579,7 +611,7 @@ third
         let long_line = "x".repeat(MAX_LINE_LENGTH + 50);
         writeln!(temp, "{long_line}")?;
 
-        let lines = read(temp.path(), 1, 1).await?;
+        let lines = read(temp.path(), None, 1, 1).await?;
         let expected = "x".repeat(MAX_LINE_LENGTH);
         assert_eq!(lines, vec![format!("L1: {expected}")]);
         Ok(())
@@ -607,7 +639,7 @@ third
             ..Default::default()
         };
 
-        let lines = read_block(temp.path(), 3, 10, options).await?;
+        let lines = read_block(temp.path(), None, 3, 10, options).await?;
 
         assert_eq!(
             lines,
@@ -642,7 +674,7 @@ third
             ..Default::default()
         };
 
-        let lines = read_block(temp.path(), 4, 50, options.clone()).await?;
+        let lines = read_block(temp.path(), None, 4, 50, options.clone()).await?;
         assert_eq!(
             lines,
             vec![
@@ -655,7 +687,7 @@ third
         );
 
         options.max_levels = 3;
-        let expanded = read_block(temp.path(), 4, 50, options).await?;
+        let expanded = read_block(temp.path(), None, 4, 50, options).await?;
         assert_eq!(
             expanded,
             vec![
@@ -695,7 +727,7 @@ third
             ..Default::default()
         };
 
-        let lines = read_block(temp.path(), 3, 50, options.clone()).await?;
+        let lines = read_block(temp.path(), None, 3, 50, options.clone()).await?;
         assert_eq!(
             lines,
             vec![
@@ -706,7 +738,7 @@ third
         );
 
         options.include_siblings = true;
-        let with_siblings = read_block(temp.path(), 3, 50, options).await?;
+        let with_siblings = read_block(temp.path(), None, 3, 50, options).await?;
         assert_eq!(
             with_siblings,
             vec![
@@ -749,7 +781,7 @@ class Bar:
             ..Default::default()
         };
 
-        let lines = read_block(temp.path(), 1, 200, options).await?;
+        let lines = read_block(temp.path(), None, 1, 200, options).await?;
         assert_eq!(
             lines,
             vec![
@@ -805,7 +837,7 @@ export function other() {{
             ..Default::default()
         };
 
-        let lines = read_block(temp.path(), 15, 200, options).await?;
+        let lines = read_block(temp.path(), None, 15, 200, options).await?;
         assert_eq!(
             lines,
             vec![
@@ -877,7 +909,7 @@ private:
             ..Default::default()
         };
 
-        let lines = read_block(temp.path(), 18, 200, options).await?;
+        let lines = read_block(temp.path(), None, 18, 200, options).await?;
         assert_eq!(
             lines,
             vec![
@@ -905,7 +937,7 @@ private:
             ..Default::default()
         };
 
-        let lines = read_block(temp.path(), 18, 200, options).await?;
+        let lines = read_block(temp.path(), None, 18, 200, options).await?;
         assert_eq!(
             lines,
             vec![
@@ -937,7 +969,7 @@ private:
             ..Default::default()
         };
 
-        let lines = read_block(temp.path(), 18, 200, options).await?;
+        let lines = read_block(temp.path(), None, 18, 200, options).await?;
         assert_eq!(
             lines,
             vec![
@@ -968,7 +1000,7 @@ private:
             ..Default::default()
         };
 
-        let lines = read_block(temp.path(), 18, 200, options).await?;
+        let lines = read_block(temp.path(), None, 18, 200, options).await?;
         assert_eq!(
             lines,
             vec![
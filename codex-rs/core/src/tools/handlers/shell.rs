@@ -3,6 +3,7 @@ use codex_protocol::models::ShellCommandToolCallParams;
 use codex_protocol::models::ShellToolCallParams;
 use std::sync::Arc;
 
+use crate::approval_rules::exec_approval_requirement;
 use crate::codex::TurnContext;
 use crate::exec::ExecParams;
 use crate::exec_env::create_env;
@@ -244,18 +245,13 @@ impl ShellHandler {
         let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, None);
         emitter.begin(event_ctx).await;
 
-        let features = session.features();
-        let exec_approval_requirement = session
-            .services
-            .exec_policy
-            .create_exec_approval_requirement_for_command(
-                &features,
-                &exec_params.command,
-                turn.approval_policy,
-                &turn.sandbox_policy,
-                exec_params.sandbox_permissions,
-            )
-            .await;
+        let exec_approval_requirement = exec_approval_requirement(
+            session.as_ref(),
+            turn.as_ref(),
+            &exec_params.command,
+            exec_params.sandbox_permissions,
+        )
+        .await;
 
         let req = ShellRequest {
             command: exec_params.command.clone(),
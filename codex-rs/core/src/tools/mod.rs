@@ -1,5 +1,6 @@
 pub mod context;
 pub mod events;
+pub(crate) mod adapter;
 pub(crate) mod handlers;
 pub mod orchestrator;
 pub mod parallel;
@@ -12,6 +13,7 @@ pub mod spec;
 use crate::exec::ExecToolCallOutput;
 use crate::truncate::TruncationPolicy;
 use crate::truncate::formatted_truncate_text;
+use crate::truncate::summarize_text;
 use crate::truncate::truncate_text;
 pub use router::ToolRouter;
 use serde::Serialize;
@@ -23,10 +25,12 @@ pub(crate) const TELEMETRY_PREVIEW_TRUNCATION_NOTICE: &str =
     "[... telemetry preview truncated ...]";
 
 /// Format the combined exec output for sending back to the model.
-/// Includes exit code and duration metadata; truncates large bodies safely.
+/// Includes exit code and duration metadata; truncates large bodies safely,
+/// or condenses them with `summarize`.
 pub fn format_exec_output_for_model_structured(
     exec_output: &ExecToolCallOutput,
     truncation_policy: TruncationPolicy,
+    summarize: bool,
 ) -> String {
     let ExecToolCallOutput {
         exit_code,
@@ -49,7 +53,11 @@ pub fn format_exec_output_for_model_structured(
     // round to 1 decimal place
     let duration_seconds = ((duration.as_secs_f32()) * 10.0).round() / 10.0;
 
-    let formatted_output = format_exec_output_str(exec_output, truncation_policy);
+    let formatted_output = if summarize {
+        format_exec_output_summary(exec_output, truncation_policy)
+    } else {
+        format_exec_output_str(exec_output, truncation_policy)
+    };
 
     let payload = ExecOutput {
         output: &formatted_output,
@@ -66,6 +74,7 @@ pub fn format_exec_output_for_model_structured(
 pub fn format_exec_output_for_model_freeform(
     exec_output: &ExecToolCallOutput,
     truncation_policy: TruncationPolicy,
+    summarize: bool,
 ) -> String {
     // round to 1 decimal place
     let duration_seconds = ((exec_output.duration.as_secs_f32()) * 10.0).round() / 10.0;
@@ -74,13 +83,18 @@ pub fn format_exec_output_for_model_freeform(
 
     let total_lines = content.lines().count();
 
-    let formatted_output = truncate_text(&content, truncation_policy);
+    let formatted_output = if summarize {
+        summarize_text(&content, truncation_policy)
+    } else {
+        truncate_text(&content, truncation_policy)
+    };
 
     let mut sections = Vec::new();
 
     sections.push(format!("Exit code: {}", exec_output.exit_code));
     sections.push(format!("Wall time: {duration_seconds} seconds"));
-    if total_lines != formatted_output.lines().count() {
+    // A summary states the line count itself.
+    if !summarize && total_lines != formatted_output.lines().count() {
         sections.push(format!("Total output lines: {total_lines}"));
     }
 
@@ -100,6 +114,16 @@ pub fn format_exec_output_str(
     formatted_truncate_text(&content, truncation_policy)
 }
 
+/// Exec output condensed to the lines reporting errors or warnings and the
+/// last lines, for turns with little context left.
+pub(crate) fn format_exec_output_summary(
+    exec_output: &ExecToolCallOutput,
+    truncation_policy: TruncationPolicy,
+) -> String {
+    let content = build_content_with_timeout(exec_output);
+    summarize_text(&content, truncation_policy)
+}
+
 /// Extracts exec output content and prepends a timeout message if the command timed out.
 fn build_content_with_timeout(exec_output: &ExecToolCallOutput) -> String {
     if exec_output.timed_out {
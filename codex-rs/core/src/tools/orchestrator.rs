@@ -79,7 +79,8 @@ impl ToolOrchestrator {
                     }
                     ReviewDecision::Approved
                     | ReviewDecision::ApprovedExecpolicyAmendment { .. }
-                    | ReviewDecision::ApprovedForSession => {}
+                    | ReviewDecision::ApprovedForSession
+                    | ReviewDecision::ApprovedHunks { .. } => {}
                 }
                 already_approved = true;
             }
@@ -101,6 +102,7 @@ impl ToolOrchestrator {
             manager: &self.sandbox,
             sandbox_cwd: &turn_ctx.cwd,
             codex_linux_sandbox_exe: turn_ctx.codex_linux_sandbox_exe.as_ref(),
+            remote: turn_ctx.remote_exec.as_ref(),
         };
 
         match tool.run(req, &initial_attempt, tool_ctx).await {
@@ -141,7 +143,8 @@ impl ToolOrchestrator {
                         }
                         ReviewDecision::Approved
                         | ReviewDecision::ApprovedExecpolicyAmendment { .. }
-                        | ReviewDecision::ApprovedForSession => {}
+                        | ReviewDecision::ApprovedForSession
+                        | ReviewDecision::ApprovedHunks { .. } => {}
                     }
                 }
 
@@ -151,6 +154,7 @@ impl ToolOrchestrator {
                     manager: &self.sandbox,
                     sandbox_cwd: &turn_ctx.cwd,
                     codex_linux_sandbox_exe: None,
+                    remote: turn_ctx.remote_exec.as_ref(),
                 };
 
                 // Second attempt.
@@ -19,6 +19,7 @@ use crate::tools::router::ToolCall;
 use crate::tools::router::ToolRouter;
 use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseInputItem;
+use codex_protocol::protocol::CancelledOperation;
 
 #[derive(Clone)]
 pub(crate) struct ToolCallRuntime {
@@ -68,8 +69,22 @@ impl ToolCallRuntime {
             aborted = false,
         );
 
+        let operation = match &call.payload {
+            ToolPayload::Mcp { server, tool, .. } => CancelledOperation::McpToolCall {
+                call_id: call.call_id.clone(),
+                server: server.clone(),
+                tool: tool.clone(),
+            },
+            _ => CancelledOperation::ToolCall {
+                call_id: call.call_id.clone(),
+                tool_name: call.tool_name.clone(),
+            },
+        };
+        let in_flight = turn.in_flight_operations.track(operation);
+
         let handle: AbortOnDropHandle<Result<ResponseInputItem, FunctionCallError>> =
             AbortOnDropHandle::new(tokio::spawn(async move {
+                let _in_flight = in_flight;
                 tokio::select! {
                     _ = cancellation_token.cancelled() => {
                         let secs = started.elapsed().as_secs_f32().max(0.1);
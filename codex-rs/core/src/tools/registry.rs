@@ -195,6 +195,12 @@ impl ToolRegistryBuilder {
         }
     }
 
+    /// Drops every spec and handler whose tool name fails `keep`.
+    pub fn retain(&mut self, keep: impl Fn(&str) -> bool) {
+        self.specs.retain(|configured| keep(configured.spec.name()));
+        self.handlers.retain(|name, _| keep(name));
+    }
+
     // TODO(jif) for dynamic tools.
     // pub fn register_many<I>(&mut self, names: I, handler: Arc<dyn ToolHandler>)
     // where
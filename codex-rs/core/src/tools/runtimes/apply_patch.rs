@@ -3,8 +3,13 @@
 //! Assumes `apply_patch` verification/approval happened upstream. Reuses that
 //! decision to avoid re-prompting, builds the self-invocation command for
 //! `codex --codex-run-as-apply-patch`, and runs under the current
-//! `SandboxAttempt` with a minimal environment.
+//! `SandboxAttempt` with a minimal environment. When the user approved only
+//! some hunks, the patch is rebuilt without the rejected ones before it runs.
+//! With a remote execution backend the patch still runs locally, and the
+//! files it touched are then copied to the host.
 use crate::CODEX_APPLY_PATCH_ARG1;
+use crate::apply_patch::patch_without_rejected_hunks;
+use crate::apply_patch::rejected_hunks_feedback;
 use crate::exec::ExecToolCallOutput;
 use crate::sandboxing::CommandSpec;
 use crate::sandboxing::SandboxPermissions;
@@ -19,9 +24,11 @@ use crate::tools::sandboxing::ToolCtx;
 use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
 use crate::tools::sandboxing::with_cached_approval;
+use crate::watchdog::await_approval;
 use codex_apply_patch::ApplyPatchAction;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::FileChange;
+use codex_protocol::protocol::RejectedHunk;
 use codex_protocol::protocol::ReviewDecision;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use futures::future::BoxFuture;
@@ -39,14 +46,41 @@ pub struct ApplyPatchRequest {
 }
 
 #[derive(Default)]
-pub struct ApplyPatchRuntime;
+pub struct ApplyPatchRuntime {
+    /// Hunks the user declined in the most recent approval.
+    rejected_hunks: Vec<RejectedHunk>,
+}
 
 impl ApplyPatchRuntime {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    pub(crate) fn rejected_hunks(&self) -> &[RejectedHunk] {
+        &self.rejected_hunks
+    }
+
+    /// The patch to run: the original one, or the accepted hunks only.
+    fn patch_to_apply(&self, req: &ApplyPatchRequest) -> Result<String, ToolError> {
+        if self.rejected_hunks.is_empty() {
+            return Ok(req.action.patch.clone());
+        }
+        match patch_without_rejected_hunks(&req.action, &self.rejected_hunks) {
+            Ok(Some(patch)) => Ok(patch),
+            Ok(None) => Err(ToolError::Rejected(format!(
+                "patch rejected by user\n\n{}",
+                rejected_hunks_feedback(&req.action, &self.rejected_hunks)
+            ))),
+            Err(err) => Err(ToolError::Rejected(format!(
+                "failed to apply the hunks approved by the user: {err}"
+            ))),
+        }
     }
 
-    fn build_command_spec(req: &ApplyPatchRequest) -> Result<CommandSpec, ToolError> {
+    fn build_command_spec(
+        req: &ApplyPatchRequest,
+        patch: String,
+    ) -> Result<CommandSpec, ToolError> {
         use std::env;
         let exe = if let Some(path) = &req.codex_exe {
             path.clone()
@@ -57,7 +91,7 @@ impl ApplyPatchRuntime {
         let program = exe.to_string_lossy().to_string();
         Ok(CommandSpec {
             program,
-            args: vec![CODEX_APPLY_PATCH_ARG1.to_string(), req.action.patch.clone()],
+            args: vec![CODEX_APPLY_PATCH_ARG1.to_string(), patch],
             cwd: req.action.cwd.clone(),
             expiration: req.timeout_ms.into(),
             // Run apply_patch with a minimal environment for determinism and to avoid leaks.
@@ -104,25 +138,30 @@ impl Approvable<ApplyPatchRequest> for ApplyPatchRuntime {
         let approval_keys = self.approval_keys(req);
         let changes = req.changes.clone();
         Box::pin(async move {
-            if let Some(reason) = retry_reason {
+            let decision = if let Some(reason) = retry_reason {
                 let rx_approve = session
                     .request_patch_approval(turn, call_id, changes.clone(), Some(reason), None)
                     .await;
-                return rx_approve.await.unwrap_or_default();
-            }
-
-            with_cached_approval(
-                &session.services,
-                "apply_patch",
-                approval_keys,
-                || async move {
-                    let rx_approve = session
-                        .request_patch_approval(turn, call_id, changes, None, None)
-                        .await;
-                    rx_approve.await.unwrap_or_default()
-                },
-            )
-            .await
+                await_approval(session, turn, rx_approve).await
+            } else {
+                with_cached_approval(
+                    &session.services,
+                    "apply_patch",
+                    approval_keys,
+                    || async move {
+                        let rx_approve = session
+                            .request_patch_approval(turn, call_id, changes, None, None)
+                            .await;
+                        await_approval(session, turn, rx_approve).await
+                    },
+                )
+                .await
+            };
+            self.rejected_hunks = match &decision {
+                ReviewDecision::ApprovedHunks { rejected_hunks } => rejected_hunks.clone(),
+                _ => Vec::new(),
+            };
+            decision
         })
     }
 
@@ -149,13 +188,24 @@ impl ToolRuntime<ApplyPatchRequest, ExecToolCallOutput> for ApplyPatchRuntime {
         attempt: &SandboxAttempt<'_>,
         ctx: &ToolCtx<'_>,
     ) -> Result<ExecToolCallOutput, ToolError> {
-        let spec = Self::build_command_spec(req)?;
+        let patch = self.patch_to_apply(req)?;
+        let spec = Self::build_command_spec(req, patch)?;
+        // Patches edit the local checkout; a remote host gets a copy below.
         let env = attempt
-            .env_for(spec)
+            .local_env_for(spec)
             .map_err(|err| ToolError::Codex(err.into()))?;
         let out = execute_env(env, attempt.policy, Self::stdout_stream(ctx))
             .await
             .map_err(ToolError::Codex)?;
+        if out.exit_code == 0
+            && let Some(remote) = attempt.remote
+            && let Err(err) = remote.sync_patch(&req.action).await
+        {
+            return Err(ToolError::Rejected(format!(
+                "the patch was applied to the local files, but copying them to {} failed: {err}",
+                remote.host()
+            )));
+        }
         Ok(out)
     }
 }
@@ -22,7 +22,7 @@ use crate::tools::sandboxing::SandboxablePreference;
 use crate::tools::sandboxing::ToolCtx;
 use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
-use crate::tools::sandboxing::with_cached_approval;
+use crate::tools::sandboxing::with_cached_command_approval;
 use codex_protocol::protocol::ReviewDecision;
 use futures::future::BoxFuture;
 use std::path::PathBuf;
@@ -98,20 +98,26 @@ impl Approvable<ShellRequest> for ShellRuntime {
         let turn = ctx.turn;
         let call_id = ctx.call_id.to_string();
         Box::pin(async move {
-            with_cached_approval(&session.services, "shell", keys, move || async move {
-                session
-                    .request_command_approval(
-                        turn,
-                        call_id,
-                        command,
-                        cwd,
-                        reason,
-                        req.exec_approval_requirement
-                            .proposed_execpolicy_amendment()
-                            .cloned(),
-                    )
-                    .await
-            })
+            with_cached_command_approval(
+                &session.services,
+                "shell",
+                &req.command,
+                keys,
+                move || async move {
+                    session
+                        .request_command_approval(
+                            turn,
+                            call_id,
+                            command,
+                            cwd,
+                            reason,
+                            req.exec_approval_requirement
+                                .proposed_execpolicy_amendment()
+                                .cloned(),
+                        )
+                        .await
+                },
+            )
             .await
         })
     }
@@ -146,7 +152,12 @@ impl ToolRuntime<ShellRequest, ExecToolCallOutput> for ShellRuntime {
     ) -> Result<ExecToolCallOutput, ToolError> {
         let base_command = &req.command;
         let session_shell = ctx.session.user_shell();
-        let command = maybe_wrap_shell_lc_with_snapshot(base_command, session_shell.as_ref());
+        // The shell snapshot is a file on this machine, useless on a remote host.
+        let command = if attempt.remote.is_some() {
+            base_command.clone()
+        } else {
+            maybe_wrap_shell_lc_with_snapshot(base_command, session_shell.as_ref())
+        };
         let command = if matches!(session_shell.shell_type, ShellType::PowerShell)
             && ctx.session.features().enabled(Feature::PowershellUtf8)
         {
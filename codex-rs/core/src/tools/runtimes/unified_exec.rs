@@ -23,7 +23,7 @@ use crate::tools::sandboxing::SandboxablePreference;
 use crate::tools::sandboxing::ToolCtx;
 use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
-use crate::tools::sandboxing::with_cached_approval;
+use crate::tools::sandboxing::with_cached_command_approval;
 use crate::unified_exec::UnifiedExecError;
 use crate::unified_exec::UnifiedExecProcess;
 use crate::unified_exec::UnifiedExecProcessManager;
@@ -116,20 +116,26 @@ impl Approvable<UnifiedExecRequest> for UnifiedExecRuntime<'_> {
             .clone()
             .or_else(|| req.justification.clone());
         Box::pin(async move {
-            with_cached_approval(&session.services, "unified_exec", keys, || async move {
-                session
-                    .request_command_approval(
-                        turn,
-                        call_id,
-                        command,
-                        cwd,
-                        reason,
-                        req.exec_approval_requirement
-                            .proposed_execpolicy_amendment()
-                            .cloned(),
-                    )
-                    .await
-            })
+            with_cached_command_approval(
+                &session.services,
+                "unified_exec",
+                &req.command,
+                keys,
+                || async move {
+                    session
+                        .request_command_approval(
+                            turn,
+                            call_id,
+                            command,
+                            cwd,
+                            reason,
+                            req.exec_approval_requirement
+                                .proposed_execpolicy_amendment()
+                                .cloned(),
+                        )
+                        .await
+                },
+            )
             .await
         })
     }
@@ -167,7 +173,12 @@ impl<'a> ToolRuntime<UnifiedExecRequest, UnifiedExecProcess> for UnifiedExecRunt
     ) -> Result<UnifiedExecProcess, ToolError> {
         let base_command = &req.command;
         let session_shell = ctx.session.user_shell();
-        let command = maybe_wrap_shell_lc_with_snapshot(base_command, session_shell.as_ref());
+        // The shell snapshot is a file on this machine, useless on a remote host.
+        let command = if attempt.remote.is_some() {
+            base_command.clone()
+        } else {
+            maybe_wrap_shell_lc_with_snapshot(base_command, session_shell.as_ref())
+        };
         let command = if matches!(session_shell.shell_type, ShellType::PowerShell)
             && ctx.session.features().enabled(Feature::PowershellUtf8)
         {
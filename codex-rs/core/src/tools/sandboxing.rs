@@ -4,10 +4,13 @@
 //! `ApprovalCtx`, `Approvable`) together with the sandbox orchestration traits
 //! and helpers (`Sandboxable`, `ToolRuntime`, `SandboxAttempt`, etc.).
 
+use crate::bash::extract_bash_command;
+use crate::bash::parse_shell_lc_plain_commands;
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::error::CodexErr;
 use crate::protocol::SandboxPolicy;
+use crate::remote_exec::RemoteExec;
 use crate::sandboxing::CommandSpec;
 use crate::sandboxing::SandboxManager;
 use crate::sandboxing::SandboxTransformError;
@@ -28,6 +31,8 @@ use serde::Serialize;
 pub(crate) struct ApprovalStore {
     // Store serialized keys for generic caching across requests.
     map: HashMap<String, ReviewDecision>,
+    // Command prefixes pre-approved with `Op::AllowCommandPrefix`.
+    command_prefixes: Vec<Vec<String>>,
 }
 
 impl ApprovalStore {
@@ -47,6 +52,34 @@ impl ApprovalStore {
             self.map.insert(s, value);
         }
     }
+
+    pub fn allow_command_prefix(&mut self, prefix: Vec<String>) {
+        if !prefix.is_empty() && !self.command_prefixes.contains(&prefix) {
+            self.command_prefixes.push(prefix);
+        }
+    }
+
+    /// Whether `command` is covered by a pre-approved prefix. A shell script
+    /// is covered only when it parses into plain commands that all are.
+    pub fn allows_command(&self, command: &[String]) -> bool {
+        if self.command_prefixes.is_empty() {
+            return false;
+        }
+        let commands = if extract_bash_command(command).is_some() {
+            match parse_shell_lc_plain_commands(command) {
+                Some(commands) => commands,
+                None => return false,
+            }
+        } else {
+            vec![command.to_vec()]
+        };
+        !commands.is_empty()
+            && commands.iter().all(|command| {
+                self.command_prefixes
+                    .iter()
+                    .any(|prefix| command.starts_with(prefix))
+            })
+    }
 }
 
 /// Takes a vector of approval keys and returns a ReviewDecision.
@@ -103,6 +136,26 @@ where
     decision
 }
 
+/// [`with_cached_approval`] for a command, which is also approved without
+/// prompting when it matches a pre-approved command prefix.
+pub(crate) async fn with_cached_command_approval<K, F, Fut>(
+    services: &SessionServices,
+    tool_name: &str,
+    command: &[String],
+    keys: Vec<K>,
+    fetch: F,
+) -> ReviewDecision
+where
+    K: Serialize,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = ReviewDecision>,
+{
+    if services.tool_approvals.lock().await.allows_command(command) {
+        return ReviewDecision::ApprovedForSession;
+    }
+    with_cached_approval(services, tool_name, keys, fetch).await
+}
+
 #[derive(Clone)]
 pub(crate) struct ApprovalCtx<'a> {
     pub session: &'a Session,
@@ -274,12 +327,31 @@ pub(crate) struct SandboxAttempt<'a> {
     pub(crate) manager: &'a SandboxManager,
     pub(crate) sandbox_cwd: &'a Path,
     pub codex_linux_sandbox_exe: Option<&'a std::path::PathBuf>,
+    /// Set when commands run on another host (`[exec_backend]`).
+    pub(crate) remote: Option<&'a RemoteExec>,
 }
 
 impl<'a> SandboxAttempt<'a> {
     pub fn env_for(
         &self,
         spec: CommandSpec,
+    ) -> Result<crate::sandboxing::ExecEnv, SandboxTransformError> {
+        match self.remote {
+            Some(remote) => Ok(remote.transform(
+                spec,
+                self.policy,
+                self.sandbox != crate::exec::SandboxType::None,
+                self.sandbox_cwd,
+            )),
+            None => self.local_env_for(spec),
+        }
+    }
+
+    /// Like [`env_for`](Self::env_for), but always on this machine, for
+    /// work on the local files such as applying a patch.
+    pub fn local_env_for(
+        &self,
+        spec: CommandSpec,
     ) -> Result<crate::sandboxing::ExecEnv, SandboxTransformError> {
         self.manager.transform(
             spec,
@@ -297,6 +369,24 @@ mod tests {
     use codex_protocol::protocol::NetworkAccess;
     use pretty_assertions::assert_eq;
 
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn allowed_prefixes_cover_plain_commands_and_simple_scripts() {
+        let mut store = ApprovalStore::default();
+        assert!(!store.allows_command(&argv(&["cargo", "test"])));
+
+        store.allow_command_prefix(argv(&["cargo", "test"]));
+
+        assert!(store.allows_command(&argv(&["cargo", "test", "-p", "codex-core"])));
+        assert!(store.allows_command(&argv(&["bash", "-lc", "cargo test && cargo test -p x"])));
+        assert!(!store.allows_command(&argv(&["cargo", "build"])));
+        assert!(!store.allows_command(&argv(&["bash", "-lc", "cargo test && rm -rf target"])));
+        assert!(!store.allows_command(&argv(&["bash", "-lc", "cargo test > $(whoami)"])));
+    }
+
     #[test]
     fn external_sandbox_skips_exec_approval_on_request() {
         assert_eq!(
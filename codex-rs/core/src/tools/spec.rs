@@ -26,9 +26,22 @@ pub(crate) struct ToolsConfig {
     pub web_search_request: bool,
     pub web_search_cached: bool,
     pub collab_tools: bool,
+    pub find_symbol: bool,
     pub experimental_supported_tools: Vec<String>,
+    pub banned_tools: Vec<String>,
 }
 
+/// Names the shell tool is exposed under. Banning any of them removes the
+/// shell entirely so it cannot be reached through another alias.
+const SHELL_TOOL_NAMES: &[&str] = &[
+    "shell",
+    "container.exec",
+    "local_shell",
+    "shell_command",
+    "exec_command",
+    "write_stdin",
+];
+
 pub(crate) struct ToolsConfigParams<'a> {
     pub(crate) model_info: &'a ModelInfo,
     pub(crate) features: &'a Features,
@@ -44,6 +57,7 @@ impl ToolsConfig {
         let include_web_search_request = features.enabled(Feature::WebSearchRequest);
         let include_web_search_cached = features.enabled(Feature::WebSearchCached);
         let include_collab_tools = features.enabled(Feature::Collab);
+        let include_find_symbol = features.enabled(Feature::SymbolIndex);
 
         let shell_type = if !features.enabled(Feature::ShellTool) {
             ConfigShellToolType::Disabled
@@ -76,9 +90,35 @@ impl ToolsConfig {
             web_search_request: include_web_search_request,
             web_search_cached: include_web_search_cached,
             collab_tools: include_collab_tools,
+            find_symbol: include_find_symbol,
             experimental_supported_tools: model_info.experimental_supported_tools.clone(),
+            banned_tools: Vec::new(),
         }
     }
+
+    /// Removes the tools matching `banned_tools` (see
+    /// [`ConfigRequirements::banned_tools`](crate::config_loader::ConfigRequirements::banned_tools)).
+    pub fn with_banned_tools(mut self, banned_tools: Vec<String>) -> Self {
+        if SHELL_TOOL_NAMES
+            .iter()
+            .any(|name| is_banned_tool(&banned_tools, name))
+        {
+            self.shell_type = ConfigShellToolType::Disabled;
+        }
+        self.banned_tools = banned_tools;
+        self
+    }
+}
+
+/// Whether `name` matches one of `banned_tools`. A pattern ending in `*`
+/// matches every name starting with the rest of the pattern.
+pub(crate) fn is_banned_tool(banned_tools: &[String], name: &str) -> bool {
+    banned_tools
+        .iter()
+        .any(|pattern| match pattern.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => pattern == name,
+        })
 }
 
 /// Generic JSON‑Schema subset needed for our tool definitions
@@ -641,6 +681,61 @@ fn create_grep_files_tool() -> ToolSpec {
     })
 }
 
+fn create_find_symbol_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "name".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Symbol to look up, e.g. \"parse_config\" or \"Config::load\".".to_string(),
+                ),
+            },
+        ),
+        (
+            "find".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "\"definitions\" (default) to find where the symbol is defined, or \
+                     \"references\" to find the lines that use it."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "path".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Directory to limit the search to. Defaults to the session's working \
+                     directory."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "limit".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Maximum number of results to return (defaults to 50).".to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "find_symbol".to_string(),
+        description: "Finds where a function, type, class or constant is defined, or where it is \
+                      referenced, using an index of the workspace. Prefer it to grepping when you \
+                      know the name of the symbol."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["name".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_read_file_tool() -> ToolSpec {
     let indentation_properties = BTreeMap::from([
         (
@@ -914,16 +1009,13 @@ pub fn create_tools_json_for_responses_api(
 
     Ok(tools_json)
 }
-/// Returns JSON values that are compatible with Function Calling in the
-/// Chat Completions API:
+/// Rewrites tools serialized for the Responses API to be compatible with
+/// Function Calling in the Chat Completions API:
 /// https://platform.openai.com/docs/guides/function-calling?api-mode=chat
 pub(crate) fn create_tools_json_for_chat_completions_api(
-    tools: &[ToolSpec],
-) -> crate::error::Result<Vec<serde_json::Value>> {
-    // We start with the JSON for the Responses API and than rewrite it to match
-    // the chat completions tool call format.
-    let responses_api_tools_json = create_tools_json_for_responses_api(tools)?;
-    let tools_json = responses_api_tools_json
+    responses_api_tools_json: Vec<serde_json::Value>,
+) -> Vec<serde_json::Value> {
+    responses_api_tools_json
         .into_iter()
         .filter_map(|mut tool| {
             if tool.get("type") != Some(&serde_json::Value::String("function".to_string())) {
@@ -947,8 +1039,7 @@ pub(crate) fn create_tools_json_for_chat_completions_api(
                 None
             }
         })
-        .collect::<Vec<serde_json::Value>>();
-    Ok(tools_json)
+        .collect::<Vec<serde_json::Value>>()
 }
 
 pub(crate) fn mcp_tool_to_openai_tool(
@@ -1104,6 +1195,7 @@ pub(crate) fn build_specs(
 ) -> ToolRegistryBuilder {
     use crate::tools::handlers::ApplyPatchHandler;
     use crate::tools::handlers::CollabHandler;
+    use crate::tools::handlers::FindSymbolHandler;
     use crate::tools::handlers::GrepFilesHandler;
     use crate::tools::handlers::ListDirHandler;
     use crate::tools::handlers::McpHandler;
@@ -1188,6 +1280,11 @@ pub(crate) fn build_specs(
         builder.register_handler("grep_files", grep_files_handler);
     }
 
+    if config.find_symbol {
+        builder.push_spec_with_parallel_support(create_find_symbol_tool(), true);
+        builder.register_handler("find_symbol", Arc::new(FindSymbolHandler));
+    }
+
     if config
         .experimental_supported_tools
         .contains(&"read_file".to_string())
@@ -1259,6 +1356,10 @@ pub(crate) fn build_specs(
         }
     }
 
+    if !config.banned_tools.is_empty() {
+        builder.retain(|name| !is_banned_tool(&config.banned_tools, name));
+    }
+
     builder
 }
 
@@ -1438,6 +1539,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_build_specs_find_symbol_follows_feature() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert!(
+            !tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "find_symbol")
+        );
+
+        features.enable(Feature::SymbolIndex);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+        });
+        let (tools, _) = build_specs(&tools_config, None).build();
+        assert!(find_tool(&tools, "find_symbol").supports_parallel_tool_calls);
+    }
+
+    #[test]
+    fn test_build_specs_drops_banned_tools() {
+        let config = test_config();
+        let model_info = ModelsManager::construct_model_info_offline("gpt-5-codex", &config);
+        let features = Features::with_defaults();
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+        })
+        .with_banned_tools(vec!["shell".to_string(), "mcp__github__*".to_string()]);
+        let mcp_tool = |name: &str| mcp_types::Tool {
+            name: name.to_string(),
+            input_schema: ToolInputSchema {
+                properties: None,
+                required: None,
+                r#type: "object".to_string(),
+            },
+            output_schema: None,
+            title: None,
+            annotations: None,
+            description: None,
+        };
+
+        let (tools, registry) = build_specs(
+            &tools_config,
+            Some(HashMap::from([
+                (
+                    "mcp__github__create_issue".to_string(),
+                    mcp_tool("create_issue"),
+                ),
+                ("mcp__docs__search".to_string(), mcp_tool("search")),
+            ])),
+        )
+        .build();
+
+        assert_eq!(tools_config.shell_type, ConfigShellToolType::Disabled);
+        let tool_names = tools.iter().map(|t| t.spec.name()).collect::<Vec<_>>();
+        assert!(tool_names.contains(&"mcp__docs__search"));
+        for banned in ["mcp__github__create_issue", "shell", "shell_command"] {
+            assert!(!tool_names.contains(&banned), "{banned} was not removed");
+        }
+        for alias in ["shell", "container.exec", "local_shell", "exec_command"] {
+            assert!(
+                registry.handler(alias).is_none(),
+                "{alias} is still handled"
+            );
+        }
+        assert!(registry.handler("mcp__github__create_issue").is_none());
+    }
+
     fn assert_model_tools(model_slug: &str, features: &Features, expected_tools: &[&str]) {
         let config = test_config();
         let model_info = ModelsManager::construct_model_info_offline(model_slug, &config);
@@ -2341,7 +2517,7 @@ Examples of valid command strings:
             })]
         );
 
-        let tools_json = create_tools_json_for_chat_completions_api(&tools).unwrap();
+        let tools_json = create_tools_json_for_chat_completions_api(responses_json);
 
         assert_eq!(
             tools_json,
@@ -9,6 +9,21 @@ use codex_protocol::protocol::TruncationPolicy as ProtocolTruncationPolicy;
 
 const APPROX_BYTES_PER_TOKEN: usize = 4;
 
+/// With `adaptive_truncation`, one tool output may take up this fraction of
+/// the tokens left in the context window...
+const ADAPTIVE_OUTPUT_SHARE: usize = 8;
+/// ...but no more than this multiple of the model's usual limit...
+const ADAPTIVE_MAX_SCALE: usize = 4;
+/// ...and never less than this many tokens.
+const ADAPTIVE_MIN_TOKENS: usize = 256;
+/// Below this fraction of the usual limit, command output is summarized
+/// instead of cut in the middle.
+const ADAPTIVE_SUMMARIZE_BELOW: usize = 4;
+
+/// Lines of command output worth keeping in a summary.
+const NOTABLE_LINE_MARKERS: &[&str] = &["error", "warning", "fail", "panic", "exception"];
+const MAX_NOTABLE_LINES: usize = 40;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TruncationPolicy {
     Bytes(usize),
@@ -73,6 +88,80 @@ impl TruncationPolicy {
     }
 }
 
+/// Limit on each tool output for a turn with `remaining_tokens` left in the
+/// context window, scaled from the model's usual limit `base`, and whether
+/// command output should be summarized because that limit is tight.
+pub(crate) fn adaptive_policy(
+    base: TruncationPolicy,
+    remaining_tokens: usize,
+) -> (TruncationPolicy, bool) {
+    let base_tokens = base.token_budget().max(1);
+    let tokens = (remaining_tokens / ADAPTIVE_OUTPUT_SHARE)
+        .min(base_tokens.saturating_mul(ADAPTIVE_MAX_SCALE))
+        .max(ADAPTIVE_MIN_TOKENS);
+    let policy = match base {
+        TruncationPolicy::Bytes(_) => TruncationPolicy::Bytes(approx_bytes_for_tokens(tokens)),
+        TruncationPolicy::Tokens(_) => TruncationPolicy::Tokens(tokens),
+    };
+    (policy, tokens < base_tokens / ADAPTIVE_SUMMARIZE_BELOW)
+}
+
+/// Condenses `content` to fit `policy`: the total line count, the lines
+/// reporting errors or warnings, and as many of the last lines as fit.
+pub(crate) fn summarize_text(content: &str, policy: TruncationPolicy) -> String {
+    if content.len() <= policy.byte_budget() {
+        return content.to_string();
+    }
+    let total_lines = content.lines().count();
+    let notable = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| {
+            let line = line.to_ascii_lowercase();
+            NOTABLE_LINE_MARKERS
+                .iter()
+                .any(|marker| line.contains(marker))
+        })
+        .take(MAX_NOTABLE_LINES)
+        .map(|(idx, line)| format!("{}: {}", idx + 1, line.trim_end()))
+        .collect::<Vec<_>>();
+
+    let mut sections = vec![format!("Total output lines: {total_lines}")];
+    let tail_policy = if notable.is_empty() {
+        policy
+    } else {
+        let half = policy.mul(0.5);
+        sections.push(format!(
+            "Lines reporting errors or warnings:\n{}",
+            truncate_text(&notable.join("\n"), half)
+        ));
+        half
+    };
+    sections.push(format!(
+        "Last lines:\n{}",
+        last_lines(content, tail_policy.byte_budget())
+    ));
+    sections.join("\n\n")
+}
+
+/// The whole lines at the end of `content` that fit in `max_bytes`, or the
+/// end of the last line when not even that fits.
+fn last_lines(content: &str, max_bytes: usize) -> &str {
+    let content = content.trim_end();
+    if content.len() <= max_bytes {
+        return content;
+    }
+    let earliest = content.len() - max_bytes;
+    let line_start = content.as_bytes()[earliest - 1..]
+        .iter()
+        .position(|byte| *byte == b'\n')
+        .map(|pos| earliest + pos);
+    match line_start {
+        Some(start) if start < content.len() => &content[start..],
+        _ => split_string(content, 0, max_bytes).2,
+    }
+}
+
 pub(crate) fn formatted_truncate_text(content: &str, policy: TruncationPolicy) -> String {
     if content.len() <= policy.byte_budget() {
         return content.to_string();
@@ -305,15 +394,72 @@ pub(crate) fn approx_tokens_from_byte_count(bytes: usize) -> u64 {
 mod tests {
 
     use super::TruncationPolicy;
+    use super::adaptive_policy;
     use super::approx_token_count;
     use super::formatted_truncate_text;
     use super::split_string;
+    use super::summarize_text;
     use super::truncate_function_output_items_with_policy;
     use super::truncate_text;
     use super::truncate_with_token_budget;
     use codex_protocol::models::FunctionCallOutputContentItem;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn adaptive_policy_scales_with_remaining_context() {
+        let base = TruncationPolicy::Tokens(10_000);
+        assert_eq!(
+            adaptive_policy(base, 1_000_000),
+            (TruncationPolicy::Tokens(40_000), false)
+        );
+        assert_eq!(
+            adaptive_policy(base, 80_000),
+            (TruncationPolicy::Tokens(10_000), false)
+        );
+        assert_eq!(
+            adaptive_policy(base, 8_000),
+            (TruncationPolicy::Tokens(1_000), true)
+        );
+        assert_eq!(
+            adaptive_policy(base, 0),
+            (TruncationPolicy::Tokens(256), true)
+        );
+        assert_eq!(
+            adaptive_policy(TruncationPolicy::Bytes(10_000), 8_000),
+            (TruncationPolicy::Bytes(4_000), false)
+        );
+    }
+
+    #[test]
+    fn summarize_keeps_errors_and_last_lines() {
+        let mut content = String::new();
+        for idx in 1..=200 {
+            content.push_str(&format!("   Compiling crate-{idx} v0.1.0\n"));
+            if idx == 120 {
+                content.push_str("error[E0308]: mismatched types\n");
+            }
+        }
+        content.push_str("error: could not compile `app`\n");
+
+        let summary = summarize_text(&content, TruncationPolicy::Bytes(200));
+
+        assert_eq!(
+            summary,
+            "Total output lines: 202\n\n\
+             Lines reporting errors or warnings:\n\
+             121: error[E0308]: mismatched types\n\
+             202: error: could not compile `app`\n\n\
+             Last lines:\n\
+             \x20  Compiling crate-199 v0.1.0\n\
+             \x20  Compiling crate-200 v0.1.0\n\
+             error: could not compile `app`"
+        );
+        assert_eq!(
+            summarize_text("short", TruncationPolicy::Bytes(200)),
+            "short"
+        );
+    }
+
     #[test]
     fn split_string_works() {
         assert_eq!(split_string("hello world", 5, 5), (1, "hello", "world"));
@@ -0,0 +1,185 @@
+//! Per-turn timing.
+//!
+//! Every [`TurnContext`](crate::codex::TurnContext) owns a [`TurnTimer`]
+//! started when the turn is submitted. The sampling loop records each model
+//! request and its first output, tool calls are timed as they run, and the
+//! resulting [`TurnTiming`] is attached to `TurnComplete`.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_protocol::protocol::TurnTiming;
+use futures::FutureExt;
+use futures::future::BoxFuture;
+
+#[derive(Debug)]
+pub(crate) struct TurnTimer {
+    submitted_at: Instant,
+    state: Mutex<TimerState>,
+}
+
+#[derive(Debug, Default)]
+struct TimerState {
+    first_request_at: Option<Instant>,
+    first_token: Option<Duration>,
+    model: Duration,
+    tool: Duration,
+    model_requests: u32,
+}
+
+impl Default for TurnTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TurnTimer {
+    pub(crate) fn new() -> Self {
+        Self {
+            submitted_at: Instant::now(),
+            state: Mutex::default(),
+        }
+    }
+
+    /// Marks a model request as sent. Dropping the returned guard adds the
+    /// time since then to the turn's model time.
+    pub(crate) fn start_model_request(&self) -> ModelRequestTimer<'_> {
+        let now = Instant::now();
+        self.with_state(|state| {
+            state.model_requests += 1;
+            state.first_request_at.get_or_insert(now);
+        });
+        ModelRequestTimer {
+            timer: self,
+            started_at: now,
+        }
+    }
+
+    /// Records that model output arrived. Only the first call of the turn
+    /// counts towards first-token latency.
+    pub(crate) fn record_first_output(&self) {
+        let now = Instant::now();
+        self.with_state(|state| {
+            if state.first_token.is_none()
+                && let Some(first_request_at) = state.first_request_at
+            {
+                state.first_token = Some(now.saturating_duration_since(first_request_at));
+            }
+        });
+    }
+
+    /// Wraps a tool call so the time until it resolves counts as tool time.
+    pub(crate) fn time_tool<T: Send + 'static>(
+        self: &Arc<Self>,
+        tool_future: BoxFuture<'static, T>,
+    ) -> BoxFuture<'static, T> {
+        let timer = Arc::clone(self);
+        let started_at = Instant::now();
+        async move {
+            let output = tool_future.await;
+            let elapsed = started_at.elapsed();
+            timer.with_state(|state| state.tool += elapsed);
+            output
+        }
+        .boxed()
+    }
+
+    pub(crate) fn snapshot(&self) -> TurnTiming {
+        let total = self.submitted_at.elapsed();
+        self.with_state(|state| TurnTiming {
+            queue_ms: duration_ms(
+                state
+                    .first_request_at
+                    .map_or(total, |at| at.saturating_duration_since(self.submitted_at)),
+            ),
+            first_token_ms: state.first_token.map(duration_ms),
+            model_ms: duration_ms(state.model),
+            tool_ms: duration_ms(state.tool),
+            total_ms: duration_ms(total),
+            model_requests: state.model_requests,
+        })
+    }
+
+    fn with_state<R>(&self, f: impl FnOnce(&mut TimerState) -> R) -> R {
+        match self.state.lock() {
+            Ok(mut state) => f(&mut state),
+            Err(err) => f(&mut err.into_inner()),
+        }
+    }
+}
+
+/// Guard returned by [`TurnTimer::start_model_request`].
+pub(crate) struct ModelRequestTimer<'a> {
+    timer: &'a TurnTimer,
+    started_at: Instant,
+}
+
+impl Drop for ModelRequestTimer<'_> {
+    fn drop(&mut self) {
+        let elapsed = self.started_at.elapsed();
+        self.timer.with_state(|state| state.model += elapsed);
+    }
+}
+
+fn duration_ms(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn turn_without_requests_reports_no_first_token() {
+        let timer = TurnTimer::new();
+        timer.record_first_output();
+
+        let timing = timer.snapshot();
+
+        assert_eq!(timing.first_token_ms, None);
+        assert_eq!(timing.model_requests, 0);
+        assert_eq!(timing.queue_ms, timing.total_ms);
+    }
+
+    #[tokio::test]
+    async fn records_requests_first_token_and_tool_time() {
+        let timer = Arc::new(TurnTimer::new());
+
+        {
+            let _request = timer.start_model_request();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            timer.record_first_output();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        timer
+            .time_tool(
+                async {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+                .boxed(),
+            )
+            .await;
+        {
+            let _request = timer.start_model_request();
+            timer.record_first_output();
+        }
+
+        let timing = timer.snapshot();
+
+        assert_eq!(timing.model_requests, 2);
+        let first_token_ms = timing.first_token_ms.expect("first token recorded");
+        assert!(
+            (10..timing.model_ms).contains(&first_token_ms),
+            "{timing:?}"
+        );
+        assert!(timing.model_ms >= 20, "{timing:?}");
+        assert!(timing.tool_ms >= 10, "{timing:?}");
+        assert!(
+            timing.total_ms >= timing.model_ms + timing.tool_ms,
+            "{timing:?}"
+        );
+    }
+}
@@ -7,6 +7,7 @@ use codex_protocol::models::ResponseItem;
 pub const USER_INSTRUCTIONS_OPEN_TAG_LEGACY: &str = "<user_instructions>";
 pub const USER_INSTRUCTIONS_PREFIX: &str = "# AGENTS.md instructions for ";
 pub const SKILL_INSTRUCTIONS_PREFIX: &str = "<skill";
+pub const MCP_RESOURCE_CONTENTS_PREFIX: &str = "<mcp_resource";
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename = "user_instructions", rename_all = "snake_case")]
@@ -75,6 +76,39 @@ impl From<SkillInstructions> for ResponseItem {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename = "mcp_resource_contents", rename_all = "snake_case")]
+pub(crate) struct McpResourceContents {
+    pub server: String,
+    pub uri: String,
+    pub contents: String,
+}
+
+impl McpResourceContents {
+    pub fn is_mcp_resource_contents(message: &[ContentItem]) -> bool {
+        if let [ContentItem::InputText { text }] = message {
+            text.starts_with(MCP_RESOURCE_CONTENTS_PREFIX)
+        } else {
+            false
+        }
+    }
+}
+
+impl From<McpResourceContents> for ResponseItem {
+    fn from(rc: McpResourceContents) -> Self {
+        ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: format!(
+                    "<mcp_resource>\n<server>{}</server>\n<uri>{}</uri>\n{}\n</mcp_resource>",
+                    rc.server, rc.uri, rc.contents
+                ),
+            }],
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename = "developer_instructions", rename_all = "snake_case")]
 pub(crate) struct DeveloperInstructions {
@@ -190,4 +224,30 @@ mod tests {
             }
         ]));
     }
+
+    #[test]
+    fn test_mcp_resource_contents() {
+        let resource_contents = McpResourceContents {
+            server: "docs".to_string(),
+            uri: "file:///guide.md".to_string(),
+            contents: "body".to_string(),
+        };
+        let response_item: ResponseItem = resource_contents.into();
+
+        let ResponseItem::Message { role, content, .. } = response_item else {
+            panic!("expected ResponseItem::Message");
+        };
+
+        assert_eq!(role, "user");
+
+        let [ContentItem::InputText { text }] = content.as_slice() else {
+            panic!("expected one InputText content item");
+        };
+
+        assert_eq!(
+            text,
+            "<mcp_resource>\n<server>docs</server>\n<uri>file:///guide.md</uri>\nbody\n</mcp_resource>",
+        );
+        assert!(McpResourceContents::is_mcp_resource_contents(&content));
+    }
 }
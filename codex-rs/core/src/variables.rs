@@ -0,0 +1,255 @@
+//! Conversation variables: named values set with `/set name=value` or
+//! [`Op::SetVariables`] and substituted for `{{name}}` in user-defined slash
+//! commands, custom prompts and hook command arguments. In the script of a
+//! `sh -c` hook they are passed through the environment instead. Every change
+//! is recorded in the rollout as a `VariablesUpdated` event, so a resumed
+//! session keeps its variables.
+//!
+//! [`Op::SetVariables`]: codex_protocol::protocol::Op::SetVariables
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::PoisonError;
+use std::sync::RwLock;
+
+use regex_lite::Regex;
+
+#[expect(clippy::expect_used)]
+static PLACEHOLDER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").expect("valid placeholder regex")
+});
+
+/// Whether `name` can be used as a variable name: a letter or underscore
+/// followed by letters, digits and underscores.
+pub fn is_valid_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Replaces every `{{name}}` in `template` with the value of the variable
+/// `name`. Placeholders naming unset variables are left as they are.
+pub fn interpolate(template: &str, variables: &BTreeMap<String, String>) -> String {
+    if variables.is_empty() {
+        return template.to_string();
+    }
+    PLACEHOLDER
+        .replace_all(template, |caps: &regex_lite::Captures<'_>| {
+            variables
+                .get(&caps[1])
+                .cloned()
+                .unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+}
+
+/// Prefix of the environment variables that carry variable values into the
+/// script of a shell hook, e.g. `CODEX_VAR_branch_name`.
+pub const SHELL_VARIABLE_ENV_PREFIX: &str = "CODEX_VAR_";
+
+/// A shell script whose placeholders were turned into references to
+/// environment variables, and the values to set those variables to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShellScript {
+    pub script: String,
+    pub env: BTreeMap<String, String>,
+}
+
+/// Replaces every `{{name}}` in the shell script `template` with a reference
+/// to the environment variable `CODEX_VAR_name`, set to the variable's value.
+/// Values never become part of the script, so the shell cannot run anything
+/// in them, whatever quotes surround the placeholder. Each reference expands
+/// to a single word: it is double-quoted outside quotes, bare inside double
+/// quotes, and spliced out of single quotes. Placeholders naming unset
+/// variables are left as they are.
+pub fn shell_script(template: &str, variables: &BTreeMap<String, String>) -> ShellScript {
+    let mut script = String::with_capacity(template.len());
+    let mut env = BTreeMap::new();
+    let mut quote = Quote::None;
+    let mut last = 0;
+    for caps in PLACEHOLDER.captures_iter(template) {
+        let (Some(placeholder), Some(name)) = (caps.get(0), caps.get(1)) else {
+            continue;
+        };
+        let before = &template[last..placeholder.start()];
+        quote = quote.after(before);
+        script.push_str(before);
+        last = placeholder.end();
+        let Some(value) = variables.get(name.as_str()) else {
+            script.push_str(placeholder.as_str());
+            continue;
+        };
+        let var = format!("{SHELL_VARIABLE_ENV_PREFIX}{}", name.as_str());
+        script.push_str(&match quote {
+            Quote::None => format!("\"${{{var}}}\""),
+            Quote::Double => format!("${{{var}}}"),
+            Quote::Single => format!("'\"${{{var}}}\"'"),
+        });
+        env.insert(var, value.clone());
+    }
+    script.push_str(&template[last..]);
+    ShellScript { script, env }
+}
+
+/// The quoting in effect at a point of a shell script.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Quote {
+    None,
+    Single,
+    Double,
+}
+
+impl Quote {
+    /// The quoting in effect once `text` follows a point quoted as `self`.
+    fn after(self, text: &str) -> Self {
+        let mut quote = self;
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            quote = match (quote, c) {
+                (Quote::None, '\'') => Quote::Single,
+                (Quote::None, '"') => Quote::Double,
+                (Quote::Single, '\'') | (Quote::Double, '"') => Quote::None,
+                (Quote::None | Quote::Double, '\\') => {
+                    chars.next();
+                    quote
+                }
+                _ => quote,
+            };
+        }
+        quote
+    }
+}
+
+/// The variables of a session, shared with the hooks that interpolate them.
+#[derive(Clone, Debug, Default)]
+pub struct Variables {
+    values: Arc<RwLock<BTreeMap<String, String>>>,
+}
+
+impl Variables {
+    /// The current values, by name.
+    pub fn snapshot(&self) -> BTreeMap<String, String> {
+        self.values
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Sets each variable in `changes` to its value, or clears it when the
+    /// value is `None`, and returns the resulting variables. Nothing changes
+    /// when one of the names is invalid.
+    pub fn update(
+        &self,
+        changes: BTreeMap<String, Option<String>>,
+    ) -> Result<BTreeMap<String, String>, String> {
+        if let Some(name) = changes.keys().find(|name| !is_valid_variable_name(name)) {
+            return Err(format!(
+                "invalid variable name `{name}`: use letters, digits and underscores, not starting with a digit"
+            ));
+        }
+        let mut values = self.values.write().unwrap_or_else(PoisonError::into_inner);
+        for (name, value) in changes {
+            match value {
+                Some(value) => values.insert(name, value),
+                None => values.remove(&name),
+            };
+        }
+        Ok(values.clone())
+    }
+
+    /// Replaces every variable, e.g. with the values recorded in a resumed
+    /// rollout.
+    pub(crate) fn replace(&self, variables: BTreeMap<String, String>) {
+        *self.values.write().unwrap_or_else(PoisonError::into_inner) = variables;
+    }
+
+    /// [`interpolate`]s `template` with the current values.
+    pub fn interpolate(&self, template: &str) -> String {
+        interpolate(
+            template,
+            &self.values.read().unwrap_or_else(PoisonError::into_inner),
+        )
+    }
+
+    /// [`shell_script`] for `template` with the current values.
+    pub fn shell_script(&self, template: &str) -> ShellScript {
+        shell_script(
+            template,
+            &self.values.read().unwrap_or_else(PoisonError::into_inner),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn placeholders_are_replaced_by_set_variables_only() {
+        let variables = Variables::default();
+        let updated = variables
+            .update(BTreeMap::from([
+                ("branch_name".to_string(), Some("fix/login".to_string())),
+                ("ticket".to_string(), Some("ENG-12".to_string())),
+            ]))
+            .expect("valid names");
+        assert_eq!(updated.len(), 2);
+
+        assert_eq!(
+            variables.interpolate("Rebase {{branch_name}} for {{ ticket }}, not {{unset}}."),
+            "Rebase fix/login for ENG-12, not {{unset}}."
+        );
+
+        variables
+            .update(BTreeMap::from([("ticket".to_string(), None)]))
+            .expect("valid names");
+        assert_eq!(
+            variables.snapshot(),
+            BTreeMap::from([("branch_name".to_string(), "fix/login".to_string())])
+        );
+    }
+
+    #[test]
+    fn shell_scripts_reference_values_through_the_environment() {
+        let variables = BTreeMap::from([
+            ("branch".to_string(), "fix/login".to_string()),
+            ("title".to_string(), "it's $(rm -rf ~)".to_string()),
+        ]);
+
+        assert_eq!(
+            shell_script(
+                r#"notify {{branch}} "re: {{title}}" 'x{{ title }}' \"{{unset}}"#,
+                &variables
+            ),
+            ShellScript {
+                script: r#"notify "${CODEX_VAR_branch}" "re: ${CODEX_VAR_title}" 'x'"${CODEX_VAR_title}"'' \"{{unset}}"#
+                    .to_string(),
+                env: BTreeMap::from([
+                    ("CODEX_VAR_branch".to_string(), "fix/login".to_string()),
+                    ("CODEX_VAR_title".to_string(), "it's $(rm -rf ~)".to_string()),
+                ]),
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_names_change_nothing() {
+        let variables = Variables::default();
+        let err = variables
+            .update(BTreeMap::from([
+                ("ok".to_string(), Some("1".to_string())),
+                ("2fast".to_string(), Some("2".to_string())),
+            ]))
+            .expect_err("invalid name");
+
+        assert_eq!(
+            err,
+            "invalid variable name `2fast`: use letters, digits and underscores, not starting with a digit"
+        );
+        assert_eq!(variables.snapshot(), BTreeMap::new());
+    }
+}
@@ -0,0 +1,227 @@
+//! Opt-in `[verify]` loop: after a turn edits files, run the configured
+//! command and send its failures back to the model until it passes or the
+//! iteration budget runs out.
+
+use std::time::Duration;
+
+use codex_async_utils::OrCancelExt;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::VerificationStatus;
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::approval_rules::exec_approval_requirement;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::config::types::VerifyConfig;
+use crate::error::CodexErr;
+use crate::error::SandboxErr;
+use crate::exec::ExecToolCallOutput;
+use crate::exec::StreamOutput;
+use crate::exec_env::create_env;
+use crate::parse_command::parse_command;
+use crate::protocol::EventMsg;
+use crate::protocol::ExecCommandBeginEvent;
+use crate::protocol::ExecCommandEndEvent;
+use crate::protocol::ExecCommandSource;
+use crate::sandboxing::SandboxPermissions;
+use crate::tools::context::SharedTurnDiffTracker;
+use crate::tools::format_exec_output_str;
+use crate::tools::orchestrator::ToolOrchestrator;
+use crate::tools::runtimes::shell::ShellRequest;
+use crate::tools::runtimes::shell::ShellRuntime;
+use crate::tools::sandboxing::ToolCtx;
+use crate::tools::sandboxing::ToolError;
+
+const VERIFY_TIMEOUT_MS: u64 = 30 * 60 * 1000;
+
+/// Tracks the verification runs of one turn.
+pub(crate) struct Verifier {
+    command: String,
+    max_iterations: u32,
+    runs: u32,
+    /// Turn diff at the last run, so a follow-up that changes nothing does
+    /// not run the command again.
+    verified_diff: Option<String>,
+    status: Option<VerificationStatus>,
+}
+
+impl Verifier {
+    /// Returns `None` when no verification command is configured.
+    pub(crate) fn new(config: &VerifyConfig) -> Option<Self> {
+        let command = config.command.as_deref()?.trim();
+        if command.is_empty() {
+            return None;
+        }
+        Some(Self {
+            command: command.to_string(),
+            max_iterations: config.max_iterations,
+            runs: 0,
+            verified_diff: None,
+            status: None,
+        })
+    }
+
+    /// Runs the command if the turn changed files since the last run. Returns
+    /// the message to send back to the model when the run failed and the
+    /// iteration budget allows another attempt.
+    pub(crate) async fn verify(
+        &mut self,
+        sess: &Session,
+        turn_context: &TurnContext,
+        turn_diff_tracker: &SharedTurnDiffTracker,
+        cancellation_token: CancellationToken,
+    ) -> Option<ResponseItem> {
+        let diff = match turn_diff_tracker.lock().await.get_unified_diff() {
+            Ok(diff) => diff,
+            Err(err) => {
+                warn!("failed to compute the turn diff for verification: {err:#}");
+                return None;
+            }
+        };
+        if diff.is_none() || diff == self.verified_diff {
+            return None;
+        }
+        self.verified_diff = diff;
+
+        let output = self.run(sess, turn_context, cancellation_token).await?;
+        self.runs += 1;
+        let passed = output.exit_code == 0;
+        self.status = Some(VerificationStatus {
+            command: self.command.clone(),
+            passed,
+            exit_code: output.exit_code,
+            runs: self.runs,
+        });
+        if passed || self.runs > self.max_iterations {
+            return None;
+        }
+
+        let text = format!(
+            "The verification command `{}` failed with exit code {} after your changes. Fix the failures and finish your turn; the command runs again afterwards ({} of {} attempts used).\n\n{}",
+            self.command,
+            output.exit_code,
+            self.runs,
+            self.max_iterations + 1,
+            format_exec_output_str(&output, turn_context.truncation_policy),
+        );
+        Some(ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText { text }],
+        })
+    }
+
+    /// Outcome of the last run, if the command ran at all.
+    pub(crate) fn into_status(self) -> Option<VerificationStatus> {
+        self.status
+    }
+
+    /// Runs the command like a model's shell command, under the turn's
+    /// sandbox and approval policy, and reports it with exec events. Returns
+    /// `None` if the turn was cancelled.
+    async fn run(
+        &self,
+        sess: &Session,
+        turn_context: &TurnContext,
+        cancellation_token: CancellationToken,
+    ) -> Option<ExecToolCallOutput> {
+        let command = sess.user_shell().derive_exec_args(&self.command, true);
+        let call_id = Uuid::new_v4().to_string();
+        let cwd = turn_context.cwd.clone();
+        let parsed_cmd = parse_command(&command);
+        sess.send_event(
+            turn_context,
+            EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
+                call_id: call_id.clone(),
+                process_id: None,
+                turn_id: turn_context.sub_id.clone(),
+                command: command.clone(),
+                cwd: cwd.clone(),
+                parsed_cmd: parsed_cmd.clone(),
+                source: ExecCommandSource::UserShell,
+                interaction_input: None,
+            }),
+        )
+        .await;
+
+        let req = ShellRequest {
+            command: command.clone(),
+            cwd: cwd.clone(),
+            timeout_ms: Some(VERIFY_TIMEOUT_MS),
+            env: create_env(&turn_context.shell_environment_policy),
+            sandbox_permissions: SandboxPermissions::UseDefault,
+            justification: None,
+            exec_approval_requirement: exec_approval_requirement(
+                sess,
+                turn_context,
+                &command,
+                SandboxPermissions::UseDefault,
+            )
+            .await,
+        };
+        let tool_ctx = ToolCtx {
+            session: sess,
+            turn: turn_context,
+            call_id: call_id.clone(),
+            tool_name: "verify".to_string(),
+        };
+        let mut orchestrator = ToolOrchestrator::new();
+        let mut runtime = ShellRuntime::new();
+        let out = orchestrator
+            .run(
+                &mut runtime,
+                &req,
+                &tool_ctx,
+                turn_context,
+                turn_context.approval_policy,
+            )
+            .or_cancel(&cancellation_token)
+            .await;
+        let output = match out {
+            Err(_) => return None,
+            Ok(Ok(output)) => output,
+            Ok(Err(ToolError::Codex(CodexErr::Sandbox(
+                SandboxErr::Timeout { output } | SandboxErr::Denied { output },
+            )))) => *output,
+            Ok(Err(err)) => {
+                let message = match err {
+                    ToolError::Rejected(message) => message,
+                    ToolError::Codex(err) => format!("execution error: {err:?}"),
+                };
+                ExecToolCallOutput {
+                    exit_code: -1,
+                    stdout: StreamOutput::new(String::new()),
+                    stderr: StreamOutput::new(message.clone()),
+                    aggregated_output: StreamOutput::new(message),
+                    duration: Duration::ZERO,
+                    timed_out: false,
+                }
+            }
+        };
+
+        sess.send_event(
+            turn_context,
+            EventMsg::ExecCommandEnd(ExecCommandEndEvent {
+                call_id,
+                process_id: None,
+                turn_id: turn_context.sub_id.clone(),
+                command,
+                cwd,
+                parsed_cmd,
+                source: ExecCommandSource::UserShell,
+                interaction_input: None,
+                stdout: output.stdout.text.clone(),
+                stderr: output.stderr.text.clone(),
+                aggregated_output: output.aggregated_output.text.clone(),
+                exit_code: output.exit_code,
+                duration: output.duration,
+                formatted_output: format_exec_output_str(&output, turn_context.truncation_policy),
+            }),
+        )
+        .await;
+        Some(output)
+    }
+}
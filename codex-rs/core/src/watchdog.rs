@@ -0,0 +1,223 @@
+//! Time limits for unattended sessions.
+//!
+//! `[watchdogs]` bounds how long a turn may run, how long the session may
+//! run, and how long an approval request may wait for an answer. When a limit
+//! runs out a `watchdog_fired` event is emitted and the watchdog's action is
+//! taken: `abort` ends the running turn with [`TurnAbortReason::TimedOut`]
+//! (an expired session also refuses new turns), `notify` only emits the event,
+//! and `deny` answers a pending approval request with
+//! [`ReviewDecision::Denied`]. An approval request that times out with
+//! `abort` is rejected and the turn stops before the next model request.
+
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use codex_protocol::protocol::ReviewDecision;
+use codex_protocol::protocol::TurnAbortReason;
+use codex_protocol::protocol::WatchdogAction;
+use codex_protocol::protocol::WatchdogFiredEvent;
+use codex_protocol::protocol::WatchdogKind;
+use tokio::sync::oneshot;
+use tokio_util::task::AbortOnDropHandle;
+
+use crate::codex::INITIAL_SUBMIT_ID;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::config::types::WatchdogConfig;
+use crate::config::types::WatchdogsConfig;
+use crate::protocol::Event;
+use crate::protocol::EventMsg;
+
+/// The configured watchdogs of a session. The default has none.
+#[derive(Default)]
+pub(crate) struct Watchdogs {
+    turn: Option<WatchdogConfig>,
+    session: Option<WatchdogConfig>,
+    approval: Option<WatchdogConfig>,
+    session_expired: AtomicBool,
+    approval_expired: AtomicBool,
+}
+
+impl Watchdogs {
+    pub(crate) fn new(config: &WatchdogsConfig) -> Self {
+        Self {
+            turn: config.turn,
+            session: config.session,
+            approval: config.approval,
+            session_expired: AtomicBool::new(false),
+            approval_expired: AtomicBool::new(false),
+        }
+    }
+
+    /// Whether the session watchdog fired with `abort`, after which no new
+    /// turn may start.
+    pub(crate) fn session_expired(&self) -> bool {
+        self.session_expired.load(Ordering::Relaxed)
+    }
+
+    /// Whether an approval request timed out with `abort` since the last
+    /// call, in which case the turn should stop.
+    pub(crate) fn take_approval_expired(&self) -> bool {
+        self.approval_expired.swap(false, Ordering::Relaxed)
+    }
+}
+
+/// Starts the turn watchdog for the task running `turn_context`. The timer
+/// stops when the returned handle is dropped with the task.
+pub(crate) fn start_turn_watchdog(
+    sess: &Arc<Session>,
+    turn_context: &Arc<TurnContext>,
+) -> Option<AbortOnDropHandle<()>> {
+    let watchdog = sess.services.watchdogs.turn?;
+    let sess = Arc::clone(sess);
+    let turn_context = Arc::clone(turn_context);
+    Some(AbortOnDropHandle::new(tokio::spawn(async move {
+        tokio::time::sleep(limit(&watchdog)).await;
+        // Aborting the task drops this timer, so finish on a task of its own.
+        // By then the turn may have ended and another one started, so only
+        // the turn this timer belongs to is aborted.
+        tokio::spawn(async move {
+            let sub_id = &turn_context.sub_id;
+            if !sess.is_turn_running(sub_id).await {
+                return;
+            }
+            fire(&sess, sub_id, WatchdogKind::Turn, watchdog).await;
+            if watchdog.action == WatchdogAction::Abort {
+                sess.abort_turn(sub_id, TurnAbortReason::TimedOut).await;
+            }
+        });
+    })))
+}
+
+/// Starts the session watchdog. The timer holds only a weak reference, so
+/// it does not keep a closed session alive.
+pub(crate) fn start_session_watchdog(sess: &Arc<Session>) {
+    let Some(watchdog) = sess.services.watchdogs.session else {
+        return;
+    };
+    let weak = Arc::downgrade(sess);
+    tokio::spawn(async move {
+        tokio::time::sleep(limit(&watchdog)).await;
+        let Some(sess) = weak.upgrade() else {
+            return;
+        };
+        if watchdog.action == WatchdogAction::Abort {
+            sess.services
+                .watchdogs
+                .session_expired
+                .store(true, Ordering::Relaxed);
+        }
+        fire(&sess, INITIAL_SUBMIT_ID, WatchdogKind::Session, watchdog).await;
+        if watchdog.action == WatchdogAction::Abort {
+            sess.abort_all_tasks(TurnAbortReason::TimedOut).await;
+        }
+    });
+}
+
+/// Waits for the answer to an approval request, applying the approval
+/// watchdog. A closed channel counts as [`ReviewDecision::Denied`].
+pub(crate) async fn await_approval(
+    sess: &Session,
+    turn_context: &TurnContext,
+    mut rx: oneshot::Receiver<ReviewDecision>,
+) -> ReviewDecision {
+    let Some(watchdog) = sess.services.watchdogs.approval else {
+        return rx.await.unwrap_or_default();
+    };
+    if let Ok(decision) = tokio::time::timeout(limit(&watchdog), &mut rx).await {
+        return decision.unwrap_or_default();
+    }
+    fire(sess, &turn_context.sub_id, WatchdogKind::Approval, watchdog).await;
+    match watchdog.action {
+        WatchdogAction::Notify => rx.await.unwrap_or_default(),
+        WatchdogAction::Deny => ReviewDecision::Denied,
+        WatchdogAction::Abort => {
+            sess.services
+                .watchdogs
+                .approval_expired
+                .store(true, Ordering::Relaxed);
+            ReviewDecision::Abort
+        }
+    }
+}
+
+/// Message explaining what happened when `kind` ran past `watchdog`'s limit.
+pub(crate) fn fired_message(kind: WatchdogKind, watchdog: WatchdogConfig) -> String {
+    let limit = format_limit(watchdog.timeout_secs);
+    let what = match kind {
+        WatchdogKind::Turn => format!("The turn has run for more than {limit}"),
+        WatchdogKind::Session => format!("The session has run for more than {limit}"),
+        WatchdogKind::Approval => format!("An approval request went unanswered for {limit}"),
+    };
+    let outcome = match (kind, watchdog.action) {
+        (_, WatchdogAction::Notify) => "",
+        (WatchdogKind::Session, WatchdogAction::Abort) => {
+            "; the running turn was aborted and no new turns will start"
+        }
+        (WatchdogKind::Approval, WatchdogAction::Deny) => "; it was denied",
+        (WatchdogKind::Approval, WatchdogAction::Abort) => "; the turn was stopped",
+        (_, WatchdogAction::Abort | WatchdogAction::Deny) => "; the turn was aborted",
+    };
+    format!("{what}{outcome}.")
+}
+
+async fn fire(sess: &Session, sub_id: &str, kind: WatchdogKind, watchdog: WatchdogConfig) {
+    let event = WatchdogFiredEvent {
+        watchdog: kind,
+        action: watchdog.action,
+        limit_secs: watchdog.timeout_secs,
+        message: fired_message(kind, watchdog),
+    };
+    sess.send_event_raw(Event {
+        id: sub_id.to_string(),
+        msg: EventMsg::WatchdogFired(event),
+    })
+    .await;
+}
+
+fn limit(watchdog: &WatchdogConfig) -> Duration {
+    Duration::from_secs(watchdog.timeout_secs)
+}
+
+fn format_limit(secs: u64) -> String {
+    match secs {
+        secs if secs >= 3600 && secs.is_multiple_of(3600) => format!("{}h", secs / 3600),
+        secs if secs >= 60 && secs.is_multiple_of(60) => format!("{}m", secs / 60),
+        secs => format!("{secs}s"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn watchdog(timeout_secs: u64, action: WatchdogAction) -> WatchdogConfig {
+        WatchdogConfig {
+            timeout_secs,
+            action,
+        }
+    }
+
+    #[test]
+    fn messages_describe_the_limit_and_the_action() {
+        assert_eq!(
+            fired_message(WatchdogKind::Turn, watchdog(1800, WatchdogAction::Abort)),
+            "The turn has run for more than 30m; the turn was aborted."
+        );
+        assert_eq!(
+            fired_message(WatchdogKind::Session, watchdog(7200, WatchdogAction::Abort)),
+            "The session has run for more than 2h; the running turn was aborted and no new turns will start."
+        );
+        assert_eq!(
+            fired_message(WatchdogKind::Approval, watchdog(90, WatchdogAction::Deny)),
+            "An approval request went unanswered for 90s; it was denied."
+        );
+        assert_eq!(
+            fired_message(WatchdogKind::Turn, watchdog(600, WatchdogAction::Notify)),
+            "The turn has run for more than 10m."
+        );
+    }
+}
@@ -0,0 +1,899 @@
+//! Snapshots of workspaces that are not git repositories.
+//!
+//! Checkpoints are git ghost commits, so a directory outside any repository
+//! (a config directory, a generated site) gets none. When the `undo` feature
+//! is enabled, such a workspace is instead captured as a [`WorkspaceSnapshot`]:
+//! a manifest of every file's path, SHA-256 and executable bit. File contents
+//! are stored once per hash under `CODEX_HOME/workspace_snapshots/objects`, so
+//! unchanged files cost nothing after the first snapshot.
+//!
+//! A snapshot is taken before each turn's tools run and recorded in the
+//! rollout, which lets `codex rollback` restore it. The first snapshot of a
+//! session is the baseline `/diff` compares against. The manifest taken when
+//! a turn ends tells which files changed outside the session before the next
+//! one starts; files whose size and modification time did not change since
+//! then are not hashed again.
+//!
+//! Sessions' manifests are kept for [`MAX_SESSION_AGE`]; contents no manifest
+//! refers to any more are then deleted by [`SnapshotStore::gc`], which each
+//! session runs once.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use chrono::Utc;
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::WatchedFilesChangedEvent;
+use codex_protocol::protocol::WorkspaceSnapshotEvent;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::git_info::get_git_repo_root;
+
+/// Directory under `CODEX_HOME` holding snapshots and their file contents.
+pub const WORKSPACE_SNAPSHOTS_DIR: &str = "workspace_snapshots";
+
+const OBJECTS_DIR: &str = "objects";
+const SESSIONS_DIR: &str = "sessions";
+
+/// Directories never captured: dependencies and build output that are
+/// large and can be regenerated.
+pub(crate) const SKIPPED_DIRS: &[&str] = &[".git", "node_modules", "target", ".venv", "__pycache__"];
+
+/// The contents of files larger than this are not captured, so they are
+/// neither diffed nor restored; see [`WorkspaceSnapshot::oversized`].
+const MAX_FILE_BYTES: u64 = 4 * 1024 * 1024;
+
+/// A workspace with more files than this is not snapshotted at all.
+const MAX_FILES: usize = 10_000;
+
+/// Manifests of sessions that took no snapshot for this long are deleted.
+const MAX_SESSION_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Objects younger than this are never collected, since a snapshot being
+/// taken stores its objects before its manifest.
+const MIN_OBJECT_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Files as they were when last hashed, by path relative to the workspace:
+/// size, modification time and the resulting entry.
+type StatCache = HashMap<String, (u64, SystemTime, FileEntry)>;
+
+/// A captured file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileEntry {
+    pub sha256: String,
+    pub size: u64,
+    #[serde(default)]
+    pub executable: bool,
+}
+
+/// The files of a workspace at one point in time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceSnapshot {
+    pub cwd: PathBuf,
+    /// RFC 3339 time the snapshot was taken.
+    pub taken_at: String,
+    /// Captured files by path relative to `cwd`, with `/` separators.
+    pub files: BTreeMap<String, FileEntry>,
+    /// Files too large to capture. Their contents are unknown, so they count
+    /// as unchanged rather than as absent.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub oversized: BTreeSet<String>,
+}
+
+/// What a capture stores and skips besides the defaults.
+#[derive(Default)]
+struct CaptureOptions<'a> {
+    /// Where file contents are stored by hash.
+    objects: Option<&'a Path>,
+    /// Directories left out, e.g. `CODEX_HOME` when the workspace holds it.
+    excluded: &'a [PathBuf],
+    /// Hashes to reuse for files whose size and modification time match.
+    stat_cache: Option<&'a StatCache>,
+}
+
+impl WorkspaceSnapshot {
+    /// Hashes every file under `cwd`. With `objects`, file contents are also
+    /// stored there by hash.
+    pub fn capture(cwd: &Path, objects: Option<&Path>) -> io::Result<Self> {
+        let options = CaptureOptions {
+            objects,
+            ..Default::default()
+        };
+        Self::capture_with(cwd, &options).map(|(snapshot, _)| snapshot)
+    }
+
+    /// Like [`Self::capture`], also returning what the files' hashes were
+    /// computed from for the next capture to reuse.
+    fn capture_with(cwd: &Path, options: &CaptureOptions<'_>) -> io::Result<(Self, StatCache)> {
+        // Excluded directories are matched against the paths the walk builds
+        // from `cwd`, whatever links lead to either.
+        let canonical_cwd = cwd.canonicalize()?;
+        let excluded: Vec<PathBuf> = options
+            .excluded
+            .iter()
+            .filter_map(|dir| {
+                let dir = dir.canonicalize().ok()?;
+                Some(cwd.join(dir.strip_prefix(&canonical_cwd).ok()?))
+            })
+            .collect();
+        let mut paths = Vec::new();
+        let mut oversized = BTreeSet::new();
+        collect_files(cwd, cwd, &excluded, &mut paths, &mut oversized)?;
+        if let Some(objects) = options.objects {
+            std::fs::create_dir_all(objects)?;
+        }
+        let mut files = BTreeMap::new();
+        let mut stat_cache = StatCache::new();
+        for (relative, path) in paths {
+            let metadata = std::fs::metadata(&path)?;
+            let modified = metadata.modified()?;
+            let cached = options
+                .stat_cache
+                .and_then(|cache| cache.get(&relative))
+                .filter(|(size, time, _)| *size == metadata.len() && *time == modified)
+                .map(|(_, _, entry)| entry.clone())
+                .filter(|entry| {
+                    options
+                        .objects
+                        .is_none_or(|objects| objects.join(&entry.sha256).exists())
+                });
+            let entry = match cached {
+                Some(entry) => FileEntry {
+                    executable: is_executable(&path),
+                    ..entry
+                },
+                None => {
+                    let contents = std::fs::read(&path)?;
+                    let sha256 = format!("{:x}", Sha256::digest(&contents));
+                    if let Some(objects) = options.objects {
+                        let object = objects.join(&sha256);
+                        if !object.exists() {
+                            std::fs::write(&object, &contents)?;
+                        }
+                    }
+                    FileEntry {
+                        sha256,
+                        size: contents.len() as u64,
+                        executable: is_executable(&path),
+                    }
+                }
+            };
+            stat_cache.insert(relative.clone(), (entry.size, modified, entry.clone()));
+            files.insert(relative, entry);
+        }
+        let snapshot = Self {
+            cwd: cwd.to_path_buf(),
+            taken_at: Utc::now().to_rfc3339(),
+            files,
+            oversized,
+        };
+        Ok((snapshot, stat_cache))
+    }
+
+    /// Whether the contents of `path` are unknown to this snapshot.
+    fn is_unknown(&self, path: &str) -> bool {
+        self.oversized.contains(path)
+    }
+
+    /// Files added or modified, and files removed, in `later`, as absolute
+    /// paths. A file that became too large to capture, or stopped being so,
+    /// counts as modified; one that stayed too large is unknown and is left
+    /// out.
+    pub fn changes(&self, later: &WorkspaceSnapshot) -> WatchedFilesChangedEvent {
+        let grown = later
+            .oversized
+            .iter()
+            .filter(|path| self.files.contains_key(*path));
+        let changed = later
+            .files
+            .iter()
+            .filter(|(path, entry)| self.files.get(*path) != Some(entry))
+            .map(|(path, _)| path)
+            .chain(grown)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|path| later.cwd.join(path))
+            .collect();
+        let removed = self
+            .files
+            .keys()
+            .chain(&self.oversized)
+            .filter(|path| !later.files.contains_key(*path) && !later.is_unknown(path))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|path| self.cwd.join(path))
+            .collect();
+        WatchedFilesChangedEvent { changed, removed }
+    }
+}
+
+/// Lists the files under `dir` that snapshots capture, up to [`MAX_FILES`],
+/// and the ones too large to capture.
+fn collect_files(
+    root: &Path,
+    dir: &Path,
+    excluded: &[PathBuf],
+    files: &mut Vec<(String, PathBuf)>,
+    oversized: &mut BTreeSet<String>,
+) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        // Symlinks are not followed, so a link out of the workspace is never
+        // captured or overwritten.
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            let name = entry.file_name();
+            if !SKIPPED_DIRS.iter().any(|skipped| name == *skipped)
+                && !excluded.iter().any(|excluded| *excluded == path)
+            {
+                collect_files(root, &path, excluded, files, oversized)?;
+            }
+            continue;
+        }
+        if !file_type.is_file() {
+            continue;
+        }
+        let Some(relative) = relative_path(root, &path) else {
+            continue;
+        };
+        if entry.metadata()?.len() > MAX_FILE_BYTES {
+            oversized.insert(relative);
+            continue;
+        }
+        if files.len() == MAX_FILES {
+            return Err(io::Error::other(format!(
+                "{} has more than {MAX_FILES} files",
+                root.display()
+            )));
+        }
+        files.push((relative, path));
+    }
+    Ok(())
+}
+
+/// `path` relative to `root` with `/` separators, or `None` if a component
+/// is not valid UTF-8.
+pub(crate) fn relative_path(root: &Path, path: &Path) -> Option<String> {
+    let components = path
+        .strip_prefix(root)
+        .ok()?
+        .components()
+        .map(|component| component.as_os_str().to_str())
+        .collect::<Option<Vec<_>>>()?;
+    Some(components.join("/"))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).is_ok_and(|metadata| metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path, executable: bool) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut permissions = std::fs::metadata(path)?.permissions();
+    let mode = permissions.mode();
+    permissions.set_mode(if executable {
+        mode | 0o111
+    } else {
+        mode & !0o111
+    });
+    std::fs::set_permissions(path, permissions)
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path, _executable: bool) -> io::Result<()> {
+    Ok(())
+}
+
+/// Saved snapshots and the file contents they refer to.
+#[derive(Debug, Clone)]
+pub struct SnapshotStore {
+    root: PathBuf,
+}
+
+impl SnapshotStore {
+    pub fn new(codex_home: &Path) -> Self {
+        Self {
+            root: codex_home.join(WORKSPACE_SNAPSHOTS_DIR),
+        }
+    }
+
+    fn objects(&self) -> PathBuf {
+        self.root.join(OBJECTS_DIR)
+    }
+
+    /// Directories never captured: the store itself and everything else in
+    /// `CODEX_HOME`, should the workspace contain it.
+    fn excluded(&self) -> Vec<PathBuf> {
+        self.root
+            .parent()
+            .into_iter()
+            .map(Path::to_path_buf)
+            .collect()
+    }
+
+    /// Captures `cwd`, storing its file contents, and saves the manifest
+    /// under the session `thread_id`. Returns the snapshot and its id.
+    pub fn take(&self, thread_id: &str, cwd: &Path) -> io::Result<(String, WorkspaceSnapshot)> {
+        self.take_with(thread_id, cwd, None)
+            .map(|(id, snapshot, _)| (id, snapshot))
+    }
+
+    /// Like [`Self::take`], reusing the hashes in `stat_cache` and returning
+    /// the ones computed.
+    fn take_with(
+        &self,
+        thread_id: &str,
+        cwd: &Path,
+        stat_cache: Option<&StatCache>,
+    ) -> io::Result<(String, WorkspaceSnapshot, StatCache)> {
+        let objects = self.objects();
+        let excluded = self.excluded();
+        let options = CaptureOptions {
+            objects: Some(&objects),
+            excluded: &excluded,
+            stat_cache,
+        };
+        let (snapshot, stat_cache) = WorkspaceSnapshot::capture_with(cwd, &options)?;
+        let id = format!("{thread_id}/{}", Utc::now().format("%Y%m%dT%H%M%S%6f"));
+        let path = self.manifest_path(&id)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let manifest = serde_json::to_vec_pretty(&snapshot).map_err(io::Error::other)?;
+        std::fs::write(path, manifest)?;
+        Ok((id, snapshot, stat_cache))
+    }
+
+    /// Captures `cwd` without storing anything.
+    fn capture_current(&self, cwd: &Path) -> io::Result<WorkspaceSnapshot> {
+        let excluded = self.excluded();
+        let options = CaptureOptions {
+            excluded: &excluded,
+            ..Default::default()
+        };
+        WorkspaceSnapshot::capture_with(cwd, &options).map(|(snapshot, _)| snapshot)
+    }
+
+    pub fn load(&self, id: &str) -> io::Result<WorkspaceSnapshot> {
+        let manifest = std::fs::read(self.manifest_path(id)?)?;
+        serde_json::from_slice(&manifest).map_err(io::Error::other)
+    }
+
+    /// Ids of the snapshots of the session `thread_id`, oldest first.
+    pub fn list(&self, thread_id: &str) -> io::Result<Vec<String>> {
+        let dir = self.root.join(SESSIONS_DIR).join(thread_id);
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let mut ids = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json")
+                && let Some(stem) = path.file_stem().and_then(|stem| stem.to_str())
+            {
+                ids.push(format!("{thread_id}/{stem}"));
+            }
+        }
+        ids.sort();
+        Ok(ids)
+    }
+
+    /// Puts the workspace back as it was in `snapshot`: captured files get
+    /// their recorded contents, and files it did not have are deleted.
+    /// Skipped directories and oversized files are left alone.
+    ///
+    /// Every file is first written next to its target, so a missing object
+    /// or a full disk leaves the workspace untouched.
+    pub fn restore(&self, snapshot: &WorkspaceSnapshot) -> io::Result<()> {
+        let current = self.capture_current(&snapshot.cwd)?;
+        let mut staged = Vec::new();
+        for (path, entry) in &snapshot.files {
+            if current.files.get(path) == Some(entry) {
+                continue;
+            }
+            let target = snapshot.cwd.join(path);
+            match self.stage(&target, entry) {
+                Ok(staging) => staged.push((staging, target)),
+                Err(err) => {
+                    for (staging, _) in staged {
+                        let _ = std::fs::remove_file(staging);
+                    }
+                    return Err(err);
+                }
+            }
+        }
+        for path in current.files.keys() {
+            if !snapshot.files.contains_key(path) && !snapshot.is_unknown(path) {
+                std::fs::remove_file(snapshot.cwd.join(path))?;
+            }
+        }
+        for (staging, target) in staged {
+            std::fs::rename(staging, target)?;
+        }
+        Ok(())
+    }
+
+    /// Copies the contents of `entry` next to `target`, returning where.
+    fn stage(&self, target: &Path, entry: &FileEntry) -> io::Result<PathBuf> {
+        let (Some(dir), Some(name)) = (target.parent(), target.file_name()) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("cannot restore {}", target.display()),
+            ));
+        };
+        std::fs::create_dir_all(dir)?;
+        let staging = dir.join(format!(".{}.codex-restore", name.to_string_lossy()));
+        let staged = std::fs::copy(self.objects().join(&entry.sha256), &staging)
+            .and_then(|_| set_executable(&staging, entry.executable));
+        if let Err(err) = staged {
+            let _ = std::fs::remove_file(&staging);
+            return Err(err);
+        }
+        Ok(staging)
+    }
+
+    /// Unified diff from `snapshot` to the workspace as it is now.
+    /// Files too large to capture on either side are left out.
+    pub fn diff(&self, snapshot: &WorkspaceSnapshot) -> io::Result<String> {
+        let current = self.capture_current(&snapshot.cwd)?;
+        let paths: BTreeSet<&String> = snapshot.files.keys().chain(current.files.keys()).collect();
+        let mut diff = String::new();
+        for path in paths {
+            if snapshot.is_unknown(path) || current.is_unknown(path) {
+                continue;
+            }
+            let before = snapshot.files.get(path);
+            let after = current.files.get(path);
+            if before == after {
+                continue;
+            }
+            let old = before
+                .map(|entry| std::fs::read(self.objects().join(&entry.sha256)))
+                .transpose()?;
+            let new = after
+                .map(|_| std::fs::read(snapshot.cwd.join(path)))
+                .transpose()?;
+            diff.push_str(&file_diff(path, old.as_deref(), new.as_deref()));
+        }
+        Ok(diff)
+    }
+
+    /// Where the manifest `id` lives. Ids come from rollouts, so anything
+    /// but `<session>/<snapshot>` is rejected.
+    fn manifest_path(&self, id: &str) -> io::Result<PathBuf> {
+        let relative = Path::new(id);
+        let valid = relative.components().count() == 2
+            && relative
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)));
+        if !valid {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid snapshot id `{id}`"),
+            ));
+        }
+        Ok(self.root.join(SESSIONS_DIR).join(format!("{id}.json")))
+    }
+
+    /// Deletes the manifests of sessions that took no snapshot for
+    /// [`MAX_SESSION_AGE`], then the objects no manifest refers to.
+    pub fn gc(&self) -> io::Result<()> {
+        let now = SystemTime::now();
+        let is_older_than = |path: &Path, age: Duration| {
+            std::fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .is_ok_and(|modified| now.duration_since(modified).is_ok_and(|since| since > age))
+        };
+        let mut referenced = HashSet::new();
+        for session in read_dir_paths(&self.root.join(SESSIONS_DIR))? {
+            // A session that just started may not have written a manifest.
+            let manifests = read_dir_paths(&session)?;
+            if is_older_than(&session, MAX_SESSION_AGE)
+                && manifests
+                    .iter()
+                    .all(|manifest| is_older_than(manifest, MAX_SESSION_AGE))
+            {
+                std::fs::remove_dir_all(&session)?;
+                continue;
+            }
+            for manifest in manifests {
+                // A manifest that cannot be read keeps nothing alive; it
+                // could not be restored either.
+                let Ok(contents) = std::fs::read(&manifest) else {
+                    continue;
+                };
+                if let Ok(snapshot) = serde_json::from_slice::<WorkspaceSnapshot>(&contents) {
+                    referenced.extend(snapshot.files.into_values().map(|entry| entry.sha256));
+                }
+            }
+        }
+        for object in read_dir_paths(&self.objects())? {
+            let name = object.file_name().and_then(|name| name.to_str());
+            if name.is_some_and(|name| !referenced.contains(name))
+                && is_older_than(&object, MIN_OBJECT_AGE)
+            {
+                std::fs::remove_file(&object)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The entries of `dir`, or none if it does not exist.
+fn read_dir_paths(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    entries
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect()
+}
+
+/// Diff of one file; `None` means the file does not exist on that side.
+pub(crate) fn file_diff(path: &str, old: Option<&[u8]>, new: Option<&[u8]>) -> String {
+    let old_header = if old.is_some() {
+        format!("a/{path}")
+    } else {
+        "/dev/null".to_string()
+    };
+    let new_header = if new.is_some() {
+        format!("b/{path}")
+    } else {
+        "/dev/null".to_string()
+    };
+    let mut diff = format!("diff --git a/{path} b/{path}\n");
+    let texts = (
+        std::str::from_utf8(old.unwrap_or_default()),
+        std::str::from_utf8(new.unwrap_or_default()),
+    );
+    let (Ok(old_text), Ok(new_text)) = texts else {
+        diff.push_str(&format!(
+            "--- {old_header}\n+++ {new_header}\nBinary files differ\n"
+        ));
+        return diff;
+    };
+    diff.push_str(
+        &similar::TextDiff::from_lines(old_text, new_text)
+            .unified_diff()
+            .context_radius(3)
+            .header(&old_header, &new_header)
+            .to_string(),
+    );
+    diff
+}
+
+/// Diff of `cwd` since the first snapshot the session `thread_id` took of
+/// it, or `None` if it has none, e.g. because `cwd` is a git repository.
+pub fn diff_since_session_start(
+    codex_home: &Path,
+    thread_id: &str,
+    cwd: &Path,
+) -> io::Result<Option<String>> {
+    let store = SnapshotStore::new(codex_home);
+    for id in store.list(thread_id)? {
+        let snapshot = store.load(&id)?;
+        if snapshot.cwd == cwd {
+            return store.diff(&snapshot).map(Some);
+        }
+    }
+    Ok(None)
+}
+
+/// The workspace snapshots of a session. The default takes none.
+#[derive(Default)]
+pub(crate) struct WorkspaceSnapshots {
+    store: Option<SnapshotStore>,
+    thread_id: String,
+    /// Files as they were when the last turn ended.
+    turn_end: Mutex<Option<WorkspaceSnapshot>>,
+    /// The workspace last captured and its files' hashes, so that a file is
+    /// only hashed again once it changes.
+    stat_cache: Mutex<Option<(PathBuf, StatCache)>>,
+    /// Whether the store was garbage collected yet.
+    collected: AtomicBool,
+}
+
+impl WorkspaceSnapshots {
+    pub(crate) fn new(enabled: bool, codex_home: &Path, thread_id: ThreadId) -> Self {
+        Self {
+            store: enabled.then(|| SnapshotStore::new(codex_home)),
+            thread_id: thread_id.to_string(),
+            turn_end: Mutex::new(None),
+            stat_cache: Mutex::new(None),
+            collected: AtomicBool::new(false),
+        }
+    }
+
+    /// Snapshots `cwd` before a turn's tools run, unless it is inside a git
+    /// repository. Returns the event recording the snapshot and, if any
+    /// files changed since the last turn ended, those changes.
+    pub(crate) async fn before_turn(
+        &self,
+        cwd: &Path,
+    ) -> io::Result<Option<(WorkspaceSnapshotEvent, Option<WatchedFilesChangedEvent>)>> {
+        let Some(store) = self.store.clone() else {
+            return Ok(None);
+        };
+        if get_git_repo_root(cwd).is_some() {
+            return Ok(None);
+        }
+        if !self.collected.swap(true, Ordering::Relaxed) {
+            let store = store.clone();
+            tokio::task::spawn_blocking(move || {
+                if let Err(err) = store.gc() {
+                    tracing::warn!("failed to clean up workspace snapshots: {err}");
+                }
+            });
+        }
+        let thread_id = self.thread_id.clone();
+        let snapshot_cwd = cwd.to_path_buf();
+        let stat_cache = self.take_stat_cache(cwd);
+        let (snapshot_id, snapshot, stat_cache) = tokio::task::spawn_blocking(move || {
+            store.take_with(&thread_id, &snapshot_cwd, stat_cache.as_ref())
+        })
+        .await
+        .map_err(io::Error::other)??;
+        *lock(&self.stat_cache) = Some((cwd.to_path_buf(), stat_cache));
+        let changes = self
+            .take_turn_end()
+            .filter(|turn_end| turn_end.cwd == snapshot.cwd)
+            .map(|turn_end| turn_end.changes(&snapshot))
+            .filter(|changes| !changes.changed.is_empty() || !changes.removed.is_empty());
+        let event = WorkspaceSnapshotEvent {
+            snapshot_id,
+            cwd: snapshot.cwd,
+            files: snapshot.files.len(),
+        };
+        Ok(Some((event, changes)))
+    }
+
+    /// Remembers the files in `cwd` as the turn left them.
+    pub(crate) async fn after_turn(&self, cwd: &Path) {
+        let Some(store) = self.store.clone() else {
+            return;
+        };
+        if get_git_repo_root(cwd).is_some() {
+            return;
+        }
+        let snapshot_cwd = cwd.to_path_buf();
+        let stat_cache = self.take_stat_cache(cwd);
+        let captured = tokio::task::spawn_blocking(move || {
+            let excluded = store.excluded();
+            let options = CaptureOptions {
+                excluded: &excluded,
+                stat_cache: stat_cache.as_ref(),
+                ..Default::default()
+            };
+            WorkspaceSnapshot::capture_with(&snapshot_cwd, &options)
+        })
+        .await
+        .map_err(io::Error::other)
+        .and_then(|result| result);
+        let snapshot = match captured {
+            Ok((snapshot, stat_cache)) => {
+                *lock(&self.stat_cache) = Some((cwd.to_path_buf(), stat_cache));
+                Some(snapshot)
+            }
+            Err(err) => {
+                tracing::warn!("failed to capture {} after the turn: {err}", cwd.display());
+                None
+            }
+        };
+        *lock(&self.turn_end) = snapshot;
+    }
+
+    fn take_turn_end(&self) -> Option<WorkspaceSnapshot> {
+        lock(&self.turn_end).take()
+    }
+
+    /// The hashes of the last capture, if it was of `cwd`.
+    fn take_stat_cache(&self, cwd: &Path) -> Option<StatCache> {
+        lock(&self.stat_cache)
+            .take()
+            .filter(|(cached_cwd, _)| cached_cwd == cwd)
+            .map(|(_, stat_cache)| stat_cache)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(err) => err.into_inner(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn restore_undoes_edits_additions_and_deletions() -> anyhow::Result<()> {
+        let codex_home = tempfile::tempdir()?;
+        let workspace = tempfile::tempdir()?;
+        let cwd = workspace.path();
+        std::fs::create_dir_all(cwd.join("conf.d"))?;
+        std::fs::write(cwd.join("conf.d/app.conf"), "port = 80\n")?;
+        std::fs::write(cwd.join("deleted.txt"), "keep me\n")?;
+        std::fs::create_dir_all(cwd.join("node_modules"))?;
+        std::fs::write(cwd.join("node_modules/dep.js"), "skipped")?;
+        let store = SnapshotStore::new(codex_home.path());
+
+        let (id, _) = store.take("thread", cwd)?;
+        std::fs::write(cwd.join("conf.d/app.conf"), "port = 8080\n")?;
+        std::fs::remove_file(cwd.join("deleted.txt"))?;
+        std::fs::write(cwd.join("added.txt"), "new\n")?;
+
+        let snapshot = store.load(&id)?;
+        assert_eq!(store.list("thread")?, vec![id]);
+        assert_eq!(
+            store.diff(&snapshot)?,
+            "diff --git a/added.txt b/added.txt\n--- /dev/null\n+++ b/added.txt\n@@ -0,0 +1 @@\n+new\n\
+             diff --git a/conf.d/app.conf b/conf.d/app.conf\n--- a/conf.d/app.conf\n+++ b/conf.d/app.conf\n@@ -1 +1 @@\n-port = 80\n+port = 8080\n\
+             diff --git a/deleted.txt b/deleted.txt\n--- a/deleted.txt\n+++ /dev/null\n@@ -1 +0,0 @@\n-keep me\n"
+        );
+
+        store.restore(&snapshot)?;
+
+        assert_eq!(store.diff(&snapshot)?, "");
+        assert!(!cwd.join("added.txt").exists());
+        assert!(cwd.join("node_modules/dep.js").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn changes_list_files_changed_between_snapshots() -> anyhow::Result<()> {
+        let workspace = tempfile::tempdir()?;
+        let cwd = workspace.path();
+        std::fs::write(cwd.join("edited.txt"), "one")?;
+        std::fs::write(cwd.join("removed.txt"), "two")?;
+        std::fs::write(cwd.join("same.txt"), "three")?;
+        let before = WorkspaceSnapshot::capture(cwd, None)?;
+
+        std::fs::write(cwd.join("edited.txt"), "one!")?;
+        std::fs::remove_file(cwd.join("removed.txt"))?;
+        std::fs::write(cwd.join("added.txt"), "four")?;
+        let after = WorkspaceSnapshot::capture(cwd, None)?;
+
+        assert_eq!(
+            before.changes(&after),
+            WatchedFilesChangedEvent {
+                changed: vec![cwd.join("added.txt"), cwd.join("edited.txt")],
+                removed: vec![cwd.join("removed.txt")],
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn snapshot_ids_cannot_escape_the_store() {
+        let store = SnapshotStore::new(Path::new("/codex"));
+        for id in ["../../etc/passwd", "/etc/passwd", "thread", "thread/../x"] {
+            assert!(store.load(id).is_err(), "{id}");
+        }
+    }
+
+    #[test]
+    fn oversized_files_are_unknown_rather_than_absent() -> anyhow::Result<()> {
+        let codex_home = tempfile::tempdir()?;
+        let workspace = tempfile::tempdir()?;
+        let cwd = workspace.path();
+        let large = vec![b'x'; MAX_FILE_BYTES as usize + 1];
+        std::fs::write(cwd.join("large.bin"), &large)?;
+        std::fs::write(cwd.join("grows.txt"), "small")?;
+        let store = SnapshotStore::new(codex_home.path());
+        let (_, before) = store.take("thread", cwd)?;
+
+        std::fs::write(cwd.join("grows.txt"), &large)?;
+        let after = WorkspaceSnapshot::capture(cwd, None)?;
+
+        assert_eq!(
+            before.changes(&after),
+            WatchedFilesChangedEvent {
+                changed: vec![cwd.join("grows.txt")],
+                removed: Vec::new(),
+            }
+        );
+        store.restore(&before)?;
+        assert!(cwd.join("large.bin").exists());
+        assert_eq!(std::fs::read_to_string(cwd.join("grows.txt"))?, "small");
+        Ok(())
+    }
+
+    #[test]
+    fn codex_home_inside_the_workspace_is_not_captured() -> anyhow::Result<()> {
+        let workspace = tempfile::tempdir()?;
+        let cwd = workspace.path();
+        let codex_home = cwd.join(".codex-home");
+        std::fs::create_dir_all(&codex_home)?;
+        std::fs::write(codex_home.join("config.toml"), "model = \"o3\"\n")?;
+        std::fs::write(cwd.join("main.rs"), "fn main() {}\n")?;
+        let store = SnapshotStore::new(&codex_home);
+
+        let (_, snapshot) = store.take("thread", cwd)?;
+
+        assert_eq!(snapshot.files.keys().collect::<Vec<_>>(), vec!["main.rs"]);
+        Ok(())
+    }
+
+    #[test]
+    fn failed_restore_leaves_the_workspace_untouched() -> anyhow::Result<()> {
+        let codex_home = tempfile::tempdir()?;
+        let workspace = tempfile::tempdir()?;
+        let cwd = workspace.path();
+        std::fs::write(cwd.join("a.txt"), "a")?;
+        std::fs::write(cwd.join("b.txt"), "b")?;
+        let store = SnapshotStore::new(codex_home.path());
+        let (_, snapshot) = store.take("thread", cwd)?;
+        std::fs::write(cwd.join("a.txt"), "a!")?;
+        std::fs::write(cwd.join("b.txt"), "b!")?;
+        std::fs::write(cwd.join("c.txt"), "c")?;
+        std::fs::remove_file(store.objects().join(&snapshot.files["b.txt"].sha256))?;
+
+        assert!(store.restore(&snapshot).is_err());
+
+        let mut names = std::fs::read_dir(cwd)?
+            .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+            .collect::<io::Result<Vec<_>>>()?;
+        names.sort();
+        assert_eq!(names, vec!["a.txt", "b.txt", "c.txt"]);
+        assert_eq!(std::fs::read_to_string(cwd.join("a.txt"))?, "a!");
+        Ok(())
+    }
+
+    #[test]
+    fn gc_keeps_objects_of_recent_sessions() -> anyhow::Result<()> {
+        let codex_home = tempfile::tempdir()?;
+        let workspace = tempfile::tempdir()?;
+        let cwd = workspace.path();
+        std::fs::write(cwd.join("kept.txt"), "kept")?;
+        let store = SnapshotStore::new(codex_home.path());
+        let (id, snapshot) = store.take("thread", cwd)?;
+        let old = SystemTime::now() - MIN_OBJECT_AGE * 2;
+        let orphan = store.objects().join("orphan");
+        std::fs::write(&orphan, "orphan")?;
+        std::fs::File::options()
+            .write(true)
+            .open(&orphan)?
+            .set_modified(old)?;
+        let kept = store.objects().join(&snapshot.files["kept.txt"].sha256);
+        std::fs::File::options()
+            .write(true)
+            .open(&kept)?
+            .set_modified(old)?;
+
+        store.gc()?;
+
+        assert!(!orphan.exists());
+        assert!(kept.exists());
+        assert_eq!(store.list("thread")?, vec![id]);
+        Ok(())
+    }
+}
@@ -57,7 +57,9 @@ async fn run_stream_with_bytes(sse_body: &[u8]) -> Vec<ResponseEvent> {
         request_max_retries: Some(0),
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
+        bandwidth_limit: None,
         requires_openai_auth: false,
+        tool_format: Default::default(),
     };
 
     let codex_home = match TempDir::new() {
@@ -52,7 +52,9 @@ async fn responses_stream_includes_subagent_header_on_review() {
         request_max_retries: Some(0),
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
+        bandwidth_limit: None,
         requires_openai_auth: false,
+        tool_format: Default::default(),
     };
 
     let codex_home = TempDir::new().expect("failed to create TempDir");
@@ -146,7 +148,9 @@ async fn responses_stream_includes_subagent_header_on_other() {
         request_max_retries: Some(0),
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
+        bandwidth_limit: None,
         requires_openai_auth: false,
+        tool_format: Default::default(),
     };
 
     let codex_home = TempDir::new().expect("failed to create TempDir");
@@ -236,7 +240,9 @@ async fn responses_respects_model_info_overrides_from_config() {
         request_max_retries: Some(0),
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
+        bandwidth_limit: None,
         requires_openai_auth: false,
+        tool_format: Default::default(),
     };
 
     let codex_home = TempDir::new().expect("failed to create TempDir");
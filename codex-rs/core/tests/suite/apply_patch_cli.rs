@@ -1252,12 +1252,16 @@ async fn apply_patch_aggregates_diff_across_multiple_tool_calls() -> Result<()>
         .await?;
 
     let mut last_diff: Option<String> = None;
+    let mut completed_diff: Option<String> = None;
     wait_for_event(&codex, |event| match event {
         EventMsg::TurnDiff(ev) => {
             last_diff = Some(ev.unified_diff.clone());
             false
         }
-        EventMsg::TurnComplete(_) => true,
+        EventMsg::TurnComplete(ev) => {
+            completed_diff = ev.unified_diff.clone();
+            true
+        }
         _ => false,
     })
     .await;
@@ -1267,6 +1271,8 @@ async fn apply_patch_aggregates_diff_across_multiple_tool_calls() -> Result<()>
     assert!(diff.contains("agg/b.txt"), "diff missing b.txt");
     // Final content reflects v2 for a.txt
     assert!(diff.contains("+v2\n") || diff.contains("v2\n"));
+    // The whole turn's diff is also reported when the turn completes.
+    assert_eq!(completed_diff, Some(diff));
     Ok(())
 }
 
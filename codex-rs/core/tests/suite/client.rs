@@ -1005,6 +1005,62 @@ async fn configured_verbosity_is_sent() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn runtime_verbosity_override_applies_to_next_request() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+    let server = MockServer::start().await;
+
+    let resp_mock = mount_sse_once(&server, sse_completed("resp1")).await;
+    let TestCodex { codex, .. } = test_codex().with_model("gpt-5.1").build(&server).await?;
+
+    codex
+        .submit(Op::OverrideTurnContext {
+            cwd: None,
+            approval_policy: None,
+            sandbox_policy: None,
+            model: None,
+            effort: Some(Some(ReasoningEffort::Low)),
+            summary: None,
+            verbosity: Some(Some(Verbosity::Low)),
+        })
+        .await?;
+    let changed =
+        wait_for_event(&codex, |ev| matches!(ev, EventMsg::ModelSettingsChanged(_))).await;
+    let EventMsg::ModelSettingsChanged(changed) = changed else {
+        unreachable!("wait_for_event returned an unexpected event");
+    };
+    assert_eq!(changed.effort, Some(ReasoningEffort::Low));
+    assert_eq!(changed.verbosity, Some(Verbosity::Low));
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello".into(),
+            }],
+            final_output_json_schema: None,
+        })
+        .await?;
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TurnComplete(_))).await;
+
+    let request_body = resp_mock.single_request().body_json();
+    assert_eq!(
+        request_body
+            .get("text")
+            .and_then(|t| t.get("verbosity"))
+            .and_then(|v| v.as_str()),
+        Some("low")
+    );
+    assert_eq!(
+        request_body
+            .get("reasoning")
+            .and_then(|r| r.get("effort"))
+            .and_then(|v| v.as_str()),
+        Some("low")
+    );
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn includes_developer_instructions_message_in_request() {
     skip_if_no_network!();
@@ -1095,7 +1151,9 @@ async fn azure_responses_request_includes_store_and_reasoning_ids() {
         request_max_retries: Some(0),
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
+        bandwidth_limit: None,
         requires_openai_auth: false,
+        tool_format: Default::default(),
     };
 
     let codex_home = TempDir::new().unwrap();
@@ -1612,7 +1670,9 @@ async fn azure_overrides_assign_properties_used_for_responses_url() {
         request_max_retries: None,
         stream_max_retries: None,
         stream_idle_timeout_ms: None,
+        bandwidth_limit: None,
         requires_openai_auth: false,
+        tool_format: Default::default(),
     };
 
     // Init session
@@ -1695,7 +1755,9 @@ async fn env_var_overrides_loaded_auth() {
         request_max_retries: None,
         stream_max_retries: None,
         stream_idle_timeout_ms: None,
+        bandwidth_limit: None,
         requires_openai_auth: false,
+        tool_format: Default::default(),
     };
 
     // Init session
@@ -0,0 +1,71 @@
+use anyhow::Result;
+use codex_core::features::Feature;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodexHarness;
+use core_test_support::test_codex::test_codex;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn files_edited_between_turns_are_reported_to_the_model() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = TestCodexHarness::with_builder(test_codex().with_config(|config| {
+        config.features.enable(Feature::WatchReadFiles);
+    }))
+    .await?;
+    let notes = harness.cwd().join("notes.txt");
+    std::fs::write(&notes, "first draft\n")?;
+
+    let args = json!({ "command": "cat notes.txt", "timeout_ms": 2_000 });
+    let responses = mount_sse_sequence(
+        harness.server(),
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_function_call("call-1", "shell_command", &serde_json::to_string(&args)?),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-1", "read it"),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-2", "noted"),
+                ev_completed("resp-3"),
+            ]),
+        ],
+    )
+    .await;
+
+    harness.test().submit_turn("read the notes").await?;
+    std::fs::write(&notes, "second draft, edited in the editor\n")?;
+    harness.test().submit_turn("what changed?").await?;
+
+    let requests = responses.requests();
+    assert_eq!(requests.len(), 3);
+    let first_turn_notices: Vec<String> = requests[1]
+        .message_input_texts("user")
+        .into_iter()
+        .filter(|text| text.starts_with("<changed_files>"))
+        .collect();
+    assert_eq!(first_turn_notices, Vec::<String>::new());
+    let notices: Vec<String> = requests[2]
+        .message_input_texts("user")
+        .into_iter()
+        .filter(|text| text.starts_with("<changed_files>"))
+        .collect();
+    assert_eq!(notices.len(), 1, "expected one notice, got {notices:?}");
+    assert!(
+        notices[0].contains("modified: notes.txt"),
+        "unexpected notice: {}",
+        notices[0]
+    );
+    Ok(())
+}
@@ -16,6 +16,7 @@ pub static CODEX_ALIASES_TEMP_DIR: TempDir = unsafe {
 #[cfg(not(target_os = "windows"))]
 mod abort_tasks;
 mod apply_patch_cli;
+mod approval_rules;
 #[cfg(not(target_os = "windows"))]
 mod approvals;
 mod auth_refresh;
@@ -28,6 +29,8 @@ mod compact_resume_fork;
 mod deprecation_notice;
 mod exec;
 mod exec_policy;
+#[cfg(not(target_os = "windows"))]
+mod file_watch;
 mod fork_thread;
 mod grep_files;
 mod hierarchical_agents;
@@ -67,5 +70,7 @@ mod undo;
 mod unified_exec;
 mod user_notification;
 mod user_shell_cmd;
+#[cfg(not(target_os = "windows"))]
+mod verify;
 mod view_image;
 mod web_search_cached;
@@ -40,6 +40,7 @@ async fn override_turn_context_does_not_persist_when_config_exists() {
             model: Some("o3".to_string()),
             effort: Some(Some(ReasoningEffort::High)),
             summary: None,
+            verbosity: None,
         })
         .await
         .expect("submit override");
@@ -82,6 +83,7 @@ async fn override_turn_context_does_not_create_config_file() {
             model: Some("o3".to_string()),
             effort: Some(Some(ReasoningEffort::Medium)),
             summary: None,
+            verbosity: None,
         })
         .await
         .expect("submit override");
@@ -94,3 +96,45 @@ async fn override_turn_context_does_not_create_config_file() {
         "override should not create config.toml"
     );
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn config_handle_updates_live_and_new_threads() {
+    let codex_home = TempDir::new().unwrap();
+    let mut config = load_default_config_for_test(&codex_home).await;
+    config.model = Some("gpt-4o".to_string());
+
+    let thread_manager = ThreadManager::with_models_provider(
+        CodexAuth::from_api_key("Test API Key"),
+        config.model_provider.clone(),
+    );
+    let codex = thread_manager
+        .start_thread(config.clone())
+        .await
+        .expect("create conversation")
+        .thread;
+
+    thread_manager.config_handle().set_model("o3").await;
+
+    let changed = wait_for_event(&codex, |ev| {
+        matches!(
+            ev,
+            EventMsg::ModelSettingsChanged(_) | EventMsg::ConfigOverrideApplied(_)
+        )
+    })
+    .await;
+    let EventMsg::ModelSettingsChanged(changed) = changed else {
+        panic!("expected only model_settings_changed for a model change, got {changed:?}");
+    };
+    assert_eq!(changed.model, "o3");
+
+    let second = thread_manager
+        .start_thread(config)
+        .await
+        .expect("create second conversation");
+    assert_eq!(second.session_configured.model, "o3");
+
+    for thread in [codex, second.thread] {
+        thread.submit(Op::Shutdown).await.expect("request shutdown");
+        wait_for_event(&thread, |ev| matches!(ev, EventMsg::ShutdownComplete)).await;
+    }
+}
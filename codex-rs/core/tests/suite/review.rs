@@ -116,7 +116,16 @@ async fn review_op_emits_lifecycle_and_review_output() {
         overall_confidence_score: 0.8,
     };
     assert_eq!(expected, review);
-    let _complete = wait_for_event(&codex, |ev| matches!(ev, EventMsg::TurnComplete(_))).await;
+    let complete = wait_for_event(&codex, |ev| matches!(ev, EventMsg::TurnComplete(_))).await;
+    let EventMsg::TurnComplete(complete) = complete else {
+        panic!("expected TurnComplete, got {complete:?}");
+    };
+    // The rendered review is the turn's final message, so `codex exec review`
+    // prints it and `--output-last-message` captures it.
+    assert_eq!(
+        complete.last_agent_message,
+        Some(render_review_output_text(&expected))
+    );
 
     // Also verify that a user message with the header and a formatted finding
     // was recorded back in the parent session's rollout.
@@ -769,6 +778,7 @@ async fn review_uses_overridden_cwd_for_base_branch_merge_base() {
             model: None,
             effort: None,
             summary: None,
+            verbosity: None,
         })
         .await
         .unwrap();
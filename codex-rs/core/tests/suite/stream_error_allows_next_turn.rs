@@ -72,7 +72,9 @@ async fn continue_after_stream_error() {
         request_max_retries: Some(1),
         stream_max_retries: Some(1),
         stream_idle_timeout_ms: Some(2_000),
+        bandwidth_limit: None,
         requires_openai_auth: false,
+        tool_format: Default::default(),
     };
 
     let TestCodex { codex, .. } = test_codex()
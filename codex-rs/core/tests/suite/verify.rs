@@ -0,0 +1,106 @@
+use anyhow::Result;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_core::protocol::VerificationStatus;
+use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_apply_patch_function_call;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodexHarness;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn failing_verification_is_sent_back_until_it_passes() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = TestCodexHarness::with_builder(test_codex().with_config(|config| {
+        config.include_apply_patch_tool = true;
+        config.verify.command = Some("test -f fixed.txt".to_string());
+        config.verify.max_iterations = 1;
+    }))
+    .await?;
+    let test = harness.test();
+
+    let responses = mount_sse_sequence(
+        harness.server(),
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_apply_patch_function_call(
+                    "patch-1",
+                    "*** Begin Patch\n*** Add File: draft.txt\n+draft\n*** End Patch",
+                ),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-1", "done"),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-3"),
+                ev_apply_patch_function_call(
+                    "patch-2",
+                    "*** Begin Patch\n*** Add File: fixed.txt\n+fixed\n*** End Patch",
+                ),
+                ev_completed("resp-3"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-2", "fixed"),
+                ev_completed("resp-4"),
+            ]),
+        ],
+    )
+    .await;
+
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "make the check pass".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: harness.cwd().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: test.session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+    let complete = wait_for_event(&test.codex, |event| {
+        matches!(event, EventMsg::TurnComplete(_))
+    })
+    .await;
+    let EventMsg::TurnComplete(complete) = complete else {
+        panic!("expected TurnComplete, got {complete:?}");
+    };
+
+    assert_eq!(complete.last_agent_message, Some("fixed".to_string()));
+    assert_eq!(
+        complete.verification,
+        Some(VerificationStatus {
+            command: "test -f fixed.txt".to_string(),
+            passed: true,
+            exit_code: 0,
+            runs: 2,
+        })
+    );
+    let requests = responses.requests();
+    assert_eq!(requests.len(), 4);
+    let feedback = requests[2].message_input_texts("user");
+    assert!(
+        feedback
+            .iter()
+            .any(|text| text.contains("`test -f fixed.txt` failed with exit code 1")),
+        "expected verification feedback, got {feedback:?}"
+    );
+    Ok(())
+}
@@ -0,0 +1,632 @@
+//! `codex run`: run the prompts listed in a task file as a batch.
+//!
+//! Each task runs as its own `codex exec` process so tasks are isolated from
+//! each other and can run in parallel. After the agent finishes, the task's
+//! optional `check` command decides whether it passed. The outcome of every
+//! task is collected into a JSON report.
+
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+
+use anyhow::Context;
+use codex_protocol::config_types::SandboxMode;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::process::Command;
+use tokio::task::JoinSet;
+
+use crate::cli::RunCli;
+
+/// Version of the report format, bumped on breaking changes.
+pub const REPORT_VERSION: u32 = 1;
+
+/// A task file, usually `tasks.yaml`.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TaskFile {
+    /// How many tasks run at once. Defaults to 1 (sequential).
+    #[serde(default)]
+    pub parallel: Option<usize>,
+    /// Defaults for tasks that do not set these fields.
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
+    #[serde(default)]
+    pub sandbox: Option<SandboxMode>,
+    #[serde(default)]
+    pub model: Option<String>,
+    pub tasks: Vec<TaskSpec>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TaskSpec {
+    /// Defaults to `task-<n>`.
+    #[serde(default)]
+    pub name: Option<String>,
+    pub prompt: String,
+    /// Working directory, relative to the task file's directory.
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
+    /// What the agent may do without approval. Batch tasks never prompt, so
+    /// this is the task's whole approval policy.
+    #[serde(default)]
+    pub sandbox: Option<SandboxMode>,
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Shell command run in the task's directory after the agent finishes;
+    /// the task passes when it exits with status 0.
+    #[serde(default)]
+    pub check: Option<String>,
+}
+
+/// A task with the file's defaults applied.
+#[derive(Debug, Clone, PartialEq)]
+struct Task {
+    name: String,
+    prompt: String,
+    cwd: PathBuf,
+    sandbox: Option<SandboxMode>,
+    model: Option<String>,
+    check: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    /// The agent finished and the check (if any) succeeded.
+    Passed,
+    /// The agent exited with an error or the check failed.
+    Failed,
+    /// The task could not be started, or its runner panicked.
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct CheckResult {
+    pub command: String,
+    pub exit_code: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TaskResult {
+    pub name: String,
+    pub cwd: PathBuf,
+    pub status: TaskStatus,
+    /// Exit code of `codex exec`.
+    pub exit_code: Option<i32>,
+    pub check: Option<CheckResult>,
+    pub duration_ms: u64,
+    pub last_message: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BatchReport {
+    pub version: u32,
+    pub passed: usize,
+    pub failed: usize,
+    pub tasks: Vec<TaskResult>,
+}
+
+impl TaskResult {
+    /// The result of a task that has not run yet.
+    fn new(task: &Task) -> Self {
+        Self {
+            name: task.name.clone(),
+            cwd: task.cwd.clone(),
+            status: TaskStatus::Error,
+            exit_code: None,
+            check: None,
+            duration_ms: 0,
+            last_message: None,
+            error: None,
+        }
+    }
+}
+
+impl BatchReport {
+    pub fn all_passed(&self) -> bool {
+        self.failed == 0
+    }
+}
+
+/// Runs `codex run`, printing or writing the report. Returns whether every
+/// task passed.
+pub async fn run_batch(cli: RunCli) -> anyhow::Result<bool> {
+    let exe = std::env::current_exe().context("failed to locate the codex executable")?;
+    let config_args = cli
+        .config_overrides
+        .raw_overrides
+        .iter()
+        .flat_map(|raw| ["-c".to_string(), raw.clone()])
+        .collect();
+    let runner = BatchRunner {
+        exe,
+        config_args,
+        logs_dir: cli.logs_dir,
+    };
+    let report = runner
+        .run_file(
+            &cli.tasks_file,
+            cli.parallel.map(std::num::NonZeroUsize::get),
+        )
+        .await?;
+
+    let json = serde_json::to_string_pretty(&report)?;
+    match cli.report {
+        Some(path) => std::fs::write(&path, format!("{json}\n"))
+            .with_context(|| format!("failed to write report to {}", path.display()))?,
+        None => {
+            #[allow(clippy::print_stdout)]
+            {
+                println!("{json}");
+            }
+        }
+    }
+    Ok(report.all_passed())
+}
+
+pub fn parse_task_file(contents: &str) -> anyhow::Result<TaskFile> {
+    let file: TaskFile = serde_yaml::from_str(contents)?;
+    if file.tasks.is_empty() {
+        anyhow::bail!("the task file does not define any tasks");
+    }
+    if file.parallel == Some(0) {
+        anyhow::bail!("`parallel` must be at least 1");
+    }
+    Ok(file)
+}
+
+/// Applies the file's defaults. Task names name the tasks' log files, so
+/// they must stay distinct once made safe for a path.
+fn resolve_tasks(file: TaskFile, base_dir: &Path) -> anyhow::Result<Vec<Task>> {
+    let default_cwd = base_dir.join(file.cwd.unwrap_or_default());
+    let tasks: Vec<Task> = file
+        .tasks
+        .into_iter()
+        .enumerate()
+        .map(|(index, spec)| Task {
+            name: spec.name.unwrap_or_else(|| format!("task-{}", index + 1)),
+            prompt: spec.prompt,
+            cwd: spec
+                .cwd
+                .map_or_else(|| default_cwd.clone(), |cwd| base_dir.join(cwd)),
+            sandbox: spec.sandbox.or(file.sandbox),
+            model: spec.model.or_else(|| file.model.clone()),
+            check: spec.check,
+        })
+        .collect();
+    let mut seen: HashMap<String, &str> = HashMap::new();
+    for task in &tasks {
+        let component = path_component(&task.name);
+        if component.is_empty() || component == "." || component == ".." {
+            anyhow::bail!("task `{}` cannot be used as a file name", task.name);
+        }
+        if let Some(other) = seen.insert(component.clone(), &task.name) {
+            if other == task.name {
+                anyhow::bail!("task `{other}` is defined more than once");
+            }
+            anyhow::bail!(
+                "tasks `{other}` and `{}` would share the log file `{component}.log`; rename one",
+                task.name
+            );
+        }
+    }
+    Ok(tasks)
+}
+
+/// Runs tasks by spawning `exe exec …` for each one.
+struct BatchRunner {
+    exe: PathBuf,
+    /// `-c key=value` pairs forwarded to every task.
+    config_args: Vec<String>,
+    logs_dir: Option<PathBuf>,
+}
+
+impl BatchRunner {
+    async fn run_file(
+        self,
+        tasks_file: &Path,
+        parallel: Option<usize>,
+    ) -> anyhow::Result<BatchReport> {
+        let contents = std::fs::read_to_string(tasks_file)
+            .with_context(|| format!("failed to read {}", tasks_file.display()))?;
+        let file = parse_task_file(&contents)
+            .with_context(|| format!("invalid task file {}", tasks_file.display()))?;
+        let parallel = parallel.or(file.parallel).unwrap_or(1);
+        let base_dir = std::path::absolute(tasks_file)?
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        if let Some(logs_dir) = &self.logs_dir {
+            std::fs::create_dir_all(logs_dir)
+                .with_context(|| format!("failed to create {}", logs_dir.display()))?;
+        }
+        let tasks = resolve_tasks(file, &base_dir)
+            .with_context(|| format!("invalid task file {}", tasks_file.display()))?;
+        Ok(self.run(tasks, parallel).await)
+    }
+
+    /// Runs up to `parallel` tasks at once; results keep the tasks' order.
+    async fn run(self, tasks: Vec<Task>, parallel: usize) -> BatchReport {
+        let runner = std::sync::Arc::new(self);
+        let mut results: Vec<TaskResult> = tasks.iter().map(TaskResult::new).collect();
+        let mut pending = tasks.into_iter().enumerate();
+        let mut running = JoinSet::new();
+        let mut indices = HashMap::new();
+        loop {
+            while running.len() < parallel.max(1) {
+                let Some((index, task)) = pending.next() else {
+                    break;
+                };
+                let runner = std::sync::Arc::clone(&runner);
+                let handle = running.spawn(async move { runner.run_task(task).await });
+                indices.insert(handle.id(), index);
+            }
+            match running.join_next_with_id().await {
+                Some(Ok((id, result))) => {
+                    if let Some(index) = indices.remove(&id) {
+                        results[index] = result;
+                    }
+                }
+                // A task that panicked keeps its placeholder result, marked
+                // as an error.
+                Some(Err(err)) => {
+                    if let Some(index) = indices.remove(&err.id()) {
+                        results[index].error = Some(format!("the task panicked: {err}"));
+                    }
+                }
+                None => break,
+            }
+        }
+
+        let tasks = results;
+        let passed = tasks
+            .iter()
+            .filter(|task| task.status == TaskStatus::Passed)
+            .count();
+        BatchReport {
+            version: REPORT_VERSION,
+            passed,
+            failed: tasks.len() - passed,
+            tasks,
+        }
+    }
+
+    async fn run_task(&self, task: Task) -> TaskResult {
+        let start = Instant::now();
+        let mut result = TaskResult::new(&task);
+        match self.run_agent(&task).await {
+            Ok((exit_code, last_message)) => {
+                result.exit_code = exit_code;
+                result.last_message = last_message;
+                let agent_ok = exit_code == Some(0);
+                let check_ok = match (&task.check, agent_ok) {
+                    (Some(check), true) => {
+                        let exit_code = run_check(check, &task.cwd).await;
+                        result.check = Some(CheckResult {
+                            command: check.clone(),
+                            exit_code,
+                        });
+                        exit_code == Some(0)
+                    }
+                    _ => true,
+                };
+                result.status = if agent_ok && check_ok {
+                    TaskStatus::Passed
+                } else {
+                    TaskStatus::Failed
+                };
+            }
+            Err(err) => result.error = Some(format!("{err:#}")),
+        }
+        result.duration_ms = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
+        result
+    }
+
+    /// Runs the agent for `task`, returning its exit code and final message.
+    async fn run_agent(&self, task: &Task) -> anyhow::Result<(Option<i32>, Option<String>)> {
+        let last_message_file = scratch_file(&task.name)?;
+        let mut command = Command::new(&self.exe);
+        command
+            .args(self.exec_args(task, last_message_file.path()))
+            .stdin(Stdio::null())
+            .kill_on_drop(true);
+        match &self.logs_dir {
+            Some(logs_dir) => {
+                let log_name = path_component(&task.name);
+                let log = std::fs::File::create(logs_dir.join(format!("{log_name}.log")))?;
+                command.stdout(log.try_clone()?).stderr(log);
+            }
+            None => {
+                command.stdout(Stdio::null()).stderr(Stdio::null());
+            }
+        }
+        let status = command
+            .status()
+            .await
+            .with_context(|| format!("failed to run {}", self.exe.display()))?;
+        let last_message = std::fs::read_to_string(last_message_file.path())
+            .ok()
+            .map(|message| message.trim_end().to_string())
+            .filter(|message| !message.is_empty());
+        Ok((status.code(), last_message))
+    }
+
+    fn exec_args(&self, task: &Task, last_message_file: &Path) -> Vec<String> {
+        let mut args = vec!["exec".to_string()];
+        args.extend(self.config_args.iter().cloned());
+        args.push("--cd".to_string());
+        args.push(task.cwd.display().to_string());
+        if let Some(sandbox) = task.sandbox {
+            args.push("--sandbox".to_string());
+            args.push(sandbox.to_string());
+        }
+        if let Some(model) = &task.model {
+            args.push("--model".to_string());
+            args.push(model.clone());
+        }
+        args.push("--output-last-message".to_string());
+        args.push(last_message_file.display().to_string());
+        args.push("--".to_string());
+        args.push(task.prompt.clone());
+        args
+    }
+}
+
+/// A file in the temp dir, removed on drop.
+pub(crate) struct ScratchFile(PathBuf);
+
+impl ScratchFile {
+    pub(crate) fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for ScratchFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Creates an empty file for `--output-last-message` that no other run
+/// shares.
+pub(crate) fn scratch_file(task_name: &str) -> io::Result<ScratchFile> {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let sanitized = path_component(task_name);
+    loop {
+        let path = std::env::temp_dir().join(format!(
+            "codex-run-{}-{}-{sanitized}.txt",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => return Ok(ScratchFile(path)),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {}
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// `name` with everything but ASCII letters, digits, `.` and `_` replaced
+/// by `-`, so it can be used as a single path component.
+pub(crate) fn path_component(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// A command that runs `script` with the platform shell.
+pub(crate) fn shell_command(script: &str) -> Command {
+    if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(script);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(script);
+        command
+    }
+}
+
+pub(crate) async fn run_check(check: &str, cwd: &Path) -> Option<i32> {
+    match shell_command(check)
+        .current_dir(cwd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await
+    {
+        Ok(status) => status.code(),
+        Err(err) => {
+            tracing::warn!("failed to run check `{check}`: {err}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const TASKS: &str = r#"
+parallel: 2
+sandbox: workspace-write
+tasks:
+  - name: bump
+    prompt: Bump the version
+    cwd: crates/app
+    check: cargo test
+  - prompt: Fix the lints
+    sandbox: read-only
+    model: gpt-5.1-codex
+"#;
+
+    #[test]
+    fn task_file_defaults_apply_to_tasks() {
+        let file = parse_task_file(TASKS).expect("parse");
+        assert_eq!(file.parallel, Some(2));
+
+        let tasks = resolve_tasks(file, Path::new("/work")).expect("resolve");
+
+        assert_eq!(
+            tasks,
+            vec![
+                Task {
+                    name: "bump".to_string(),
+                    prompt: "Bump the version".to_string(),
+                    cwd: PathBuf::from("/work/crates/app"),
+                    sandbox: Some(SandboxMode::WorkspaceWrite),
+                    model: None,
+                    check: Some("cargo test".to_string()),
+                },
+                Task {
+                    name: "task-2".to_string(),
+                    prompt: "Fix the lints".to_string(),
+                    cwd: PathBuf::from("/work/"),
+                    sandbox: Some(SandboxMode::ReadOnly),
+                    model: Some("gpt-5.1-codex".to_string()),
+                    check: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_empty_and_unknown_fields() {
+        assert!(parse_task_file("tasks: []").is_err());
+        assert!(parse_task_file("tasks:\n  - prompt: hi\n    approve: yes\n").is_err());
+    }
+
+    #[test]
+    fn rejects_task_names_that_share_a_log_file() {
+        let resolve = |contents: &str| {
+            resolve_tasks(
+                parse_task_file(contents).expect("parse"),
+                Path::new("/work"),
+            )
+        };
+        assert!(
+            resolve("tasks:\n  - {name: a/b, prompt: x}\n  - {name: a b, prompt: y}\n").is_err()
+        );
+        assert!(resolve("tasks:\n  - {prompt: x}\n  - {name: task-1, prompt: y}\n").is_err());
+        assert!(resolve("tasks:\n  - {name: .., prompt: x}\n").is_err());
+        assert!(resolve("tasks:\n  - {name: a/b, prompt: x}\n  - {prompt: y}\n").is_ok());
+    }
+
+    #[test]
+    fn scratch_files_are_unique_and_removed_on_drop() {
+        let first = scratch_file("same").expect("scratch file");
+        let second = scratch_file("same").expect("scratch file");
+        assert_ne!(first.path(), second.path());
+        let path = first.path().to_path_buf();
+        drop(first);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn builds_exec_arguments() {
+        let runner = BatchRunner {
+            exe: PathBuf::from("codex"),
+            config_args: vec!["-c".to_string(), "model_reasoning_effort=high".to_string()],
+            logs_dir: None,
+        };
+        let task = Task {
+            name: "bump".to_string(),
+            prompt: "-v is not a flag".to_string(),
+            cwd: PathBuf::from("/work"),
+            sandbox: Some(SandboxMode::DangerFullAccess),
+            model: Some("gpt-5.1-codex".to_string()),
+            check: None,
+        };
+
+        assert_eq!(
+            runner.exec_args(&task, Path::new("/tmp/last.txt")),
+            vec![
+                "exec",
+                "-c",
+                "model_reasoning_effort=high",
+                "--cd",
+                "/work",
+                "--sandbox",
+                "danger-full-access",
+                "--model",
+                "gpt-5.1-codex",
+                "--output-last-message",
+                "/tmp/last.txt",
+                "--",
+                "-v is not a flag",
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn runs_tasks_in_parallel_and_reports_checks() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        // Stands in for `codex`: writes the prompt as the last message.
+        let exe = dir.path().join("fake-codex");
+        std::fs::write(
+            &exe,
+            "#!/bin/sh\nwhile [ \"$1\" != \"--output-last-message\" ]; do shift; done\n\
+             out=\"$2\"; shift 3; printf 'did: %s\\n' \"$1\" > \"$out\"\n",
+        )
+        .expect("write fake exe");
+        std::fs::set_permissions(&exe, std::fs::Permissions::from_mode(0o755)).expect("chmod");
+        let task = |name: &str, check: &str| Task {
+            name: name.to_string(),
+            prompt: format!("{name} it"),
+            cwd: dir.path().to_path_buf(),
+            sandbox: None,
+            model: None,
+            check: Some(check.to_string()),
+        };
+        let runner = BatchRunner {
+            exe,
+            config_args: Vec::new(),
+            logs_dir: None,
+        };
+
+        let report = runner
+            .run(vec![task("pass", "true"), task("fail", "exit 3")], 2)
+            .await;
+
+        let summary: Vec<_> = report
+            .tasks
+            .iter()
+            .map(|task| {
+                (
+                    task.name.as_str(),
+                    task.status,
+                    task.last_message.as_deref(),
+                    task.check.as_ref().and_then(|check| check.exit_code),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("pass", TaskStatus::Passed, Some("did: pass it"), Some(0)),
+                ("fail", TaskStatus::Failed, Some("did: fail it"), Some(3)),
+            ]
+        );
+        assert_eq!((report.passed, report.failed), (1, 1));
+    }
+}
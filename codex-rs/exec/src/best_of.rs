@@ -0,0 +1,186 @@
+//! `codex exec --best-of N`: run the prompt as several candidates and keep
+//! the best one. The orchestration lives in [`codex_core::best_of`]; this
+//! module picks the candidates and reports the outcome.
+
+use codex_core::ThreadManager;
+use codex_core::best_of::BestOfRun;
+use codex_core::best_of::CandidateEvent;
+use codex_core::best_of::CandidateOutcome;
+use codex_core::best_of::CandidateSpec;
+use codex_core::best_of::apply_candidate;
+use codex_core::best_of::run_best_of;
+use codex_core::config::Config;
+use codex_protocol::user_input::UserInput;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use crate::cli::OutputFormat;
+use crate::event_processor::handle_last_message;
+use crate::event_processor_with_event_stream::EventProcessorWithEventStream;
+
+pub(crate) struct BestOfOptions {
+    pub count: Option<u32>,
+    pub candidates: Vec<String>,
+    pub keep_candidates: bool,
+    pub json: bool,
+    pub output_format: OutputFormat,
+    pub last_message_file: Option<std::path::PathBuf>,
+}
+
+/// `count` candidates, cycling through the `--candidate` specs, or as many
+/// as there are specs when no count is given.
+pub(crate) fn candidate_specs(count: Option<u32>, candidates: &[String]) -> Vec<CandidateSpec> {
+    let specs: Vec<CandidateSpec> = candidates
+        .iter()
+        .map(String::as_str)
+        .map(CandidateSpec::parse)
+        .collect();
+    let count = count.map_or(specs.len(), |count| count as usize);
+    if specs.is_empty() {
+        return vec![CandidateSpec::default(); count];
+    }
+    specs.into_iter().cycle().take(count).collect()
+}
+
+/// Runs the candidates and copies the winner's changes into the workspace.
+/// Returns whether a candidate was picked.
+pub(crate) async fn run(
+    thread_manager: &ThreadManager,
+    config: &Config,
+    items: Vec<UserInput>,
+    options: BestOfOptions,
+) -> anyhow::Result<bool> {
+    let specs = candidate_specs(options.count, &options.candidates);
+    if specs.len() < 2 {
+        anyhow::bail!("--best-of needs at least two candidates");
+    }
+    #[allow(clippy::print_stderr)]
+    {
+        eprintln!(
+            "Running {} candidates in scratch copies of {}",
+            specs.len(),
+            config.cwd.display()
+        );
+    }
+    let (events, printer) = if options.output_format == OutputFormat::Jsonl {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        (Some(tx), Some(tokio::spawn(print_candidate_events(rx))))
+    } else {
+        (None, None)
+    };
+    let run = run_best_of(thread_manager, config, &specs, items, events).await?;
+    if let Some(printer) = printer {
+        let _ = printer.await;
+    }
+    let cleanup = run.cleanup();
+    if options.keep_candidates {
+        cleanup.keep();
+    }
+    let winner = run.best.map(|best| &run.candidates[best]);
+    if let Some(winner) = winner {
+        apply_candidate(winner, &config.cwd)?;
+    }
+
+    if options.json {
+        #[allow(clippy::print_stdout)]
+        {
+            println!("{}", serde_json::to_string(&run)?);
+        }
+    } else {
+        print_summary(&run, winner, options.keep_candidates);
+        // With `jsonl`, the winner's final message is already part of the
+        // event stream.
+        if options.output_format == OutputFormat::Human
+            && let Some(message) = winner.and_then(|winner| winner.last_agent_message.as_deref())
+        {
+            #[allow(clippy::print_stdout)]
+            {
+                println!("{message}");
+            }
+        }
+    }
+    if let Some(path) = options.last_message_file.as_deref()
+        && let Some(winner) = winner
+    {
+        handle_last_message(winner.last_agent_message.as_deref(), path);
+    }
+    Ok(winner.is_some())
+}
+
+/// Prints the candidates' events as `--output-format jsonl` lines until every
+/// candidate is done.
+async fn print_candidate_events(mut rx: UnboundedReceiver<CandidateEvent>) {
+    while let Some(CandidateEvent { candidate, event }) = rx.recv().await {
+        if let Some(line) = EventProcessorWithEventStream::format_candidate_event(candidate, event)
+        {
+            #[allow(clippy::print_stdout)]
+            {
+                println!("{line}");
+            }
+        }
+    }
+}
+
+#[allow(clippy::print_stderr)]
+fn print_summary(run: &BestOfRun, winner: Option<&CandidateOutcome>, kept: bool) {
+    for candidate in &run.candidates {
+        let result = match (&candidate.error, &candidate.verification) {
+            (Some(error), _) => format!("failed: {error}"),
+            (None, Some(verification)) if verification.passed => {
+                format!("verification passed after {} run(s)", verification.runs)
+            }
+            (None, Some(verification)) => {
+                format!("verification failed (exit {})", verification.exit_code)
+            }
+            (None, None) => "not verified".to_string(),
+        };
+        let files = candidate.changed.len() + candidate.removed.len();
+        eprintln!(
+            "[{}] {}: {result}, {files} file(s) changed, {} tokens",
+            candidate.index, candidate.label, candidate.total_tokens
+        );
+        if kept {
+            eprintln!("    {}", candidate.workspace.display());
+        }
+    }
+    match winner {
+        Some(winner) => eprintln!(
+            "Picked candidate {} ({}); its changes were copied into the workspace.",
+            winner.index, winner.label
+        ),
+        None => eprintln!("No candidate finished its turn; the workspace is unchanged."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::openai_models::ReasoningEffort;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn candidates_cycle_up_to_the_count() {
+        let specs = candidate_specs(Some(3), &["a:high".to_string(), "b".to_string()]);
+        assert_eq!(
+            specs,
+            vec![
+                CandidateSpec {
+                    model: Some("a".to_string()),
+                    effort: Some(ReasoningEffort::High),
+                },
+                CandidateSpec {
+                    model: Some("b".to_string()),
+                    effort: None,
+                },
+                CandidateSpec {
+                    model: Some("a".to_string()),
+                    effort: Some(ReasoningEffort::High),
+                },
+            ]
+        );
+        assert_eq!(candidate_specs(None, &["a".to_string()]).len(), 1);
+        assert_eq!(
+            candidate_specs(Some(2), &[]),
+            vec![CandidateSpec::default(); 2]
+        );
+    }
+}
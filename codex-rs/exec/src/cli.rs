@@ -73,6 +73,26 @@ pub struct Cli {
     #[arg(long = "output-schema", value_name = "FILE")]
     pub output_schema: Option<PathBuf>,
 
+    /// Command to run after each turn that edits files, such as `cargo test`.
+    /// Failures are sent back to the model to fix.
+    #[arg(long = "verify", value_name = "COMMAND")]
+    pub verify: Option<String>,
+
+    /// Run the prompt as N candidates in parallel, each in a scratch copy of
+    /// the workspace, and copy the changes of the best one back. Candidates
+    /// are ranked by the outcome of the verification command.
+    #[arg(long = "best-of", value_name = "N", value_parser = clap::value_parser!(u32).range(2..))]
+    pub best_of: Option<u32>,
+
+    /// Model and optional reasoning effort of a best-of candidate. Repeat for
+    /// each candidate; with `--best-of N`, the list is cycled through.
+    #[arg(long = "candidate", value_name = "MODEL[:EFFORT]")]
+    pub candidates: Vec<String>,
+
+    /// Keep the candidates' scratch copies of the workspace for inspection.
+    #[arg(long = "keep-candidates", default_value_t = false)]
+    pub keep_candidates: bool,
+
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 
@@ -89,6 +109,17 @@ pub struct Cli {
     )]
     pub json: bool,
 
+    /// Format of the output printed to stdout. `jsonl` prints every agent
+    /// event, unabridged, as one schema-versioned JSON object per line.
+    #[arg(
+        long = "output-format",
+        value_enum,
+        default_value_t = OutputFormat::Human,
+        global = true,
+        conflicts_with = "json"
+    )]
+    pub output_format: OutputFormat,
+
     /// Specifies file where the last message from the agent should be written.
     #[arg(long = "output-last-message", short = 'o', value_name = "FILE")]
     pub last_message_file: Option<PathBuf>,
@@ -167,6 +198,78 @@ pub struct ReviewArgs {
     /// Custom review instructions. If `-` is used, read from stdin.
     #[arg(value_name = "PROMPT", value_hint = clap::ValueHint::Other)]
     pub prompt: Option<String>,
+
+    /// Write the review's findings as JSON to this file.
+    #[arg(long = "findings-json", value_name = "FILE")]
+    pub findings_json: Option<PathBuf>,
+}
+
+/// Arguments for `codex run`.
+#[derive(Parser, Debug)]
+pub struct RunCli {
+    /// YAML file listing the tasks to run.
+    #[arg(value_name = "TASKS_FILE")]
+    pub tasks_file: PathBuf,
+
+    /// Number of tasks to run at once. Overrides `parallel` in the task file.
+    #[arg(long = "parallel", short = 'j', value_name = "N")]
+    pub parallel: Option<std::num::NonZeroUsize>,
+
+    /// Write the JSON report to this file instead of stdout.
+    #[arg(long = "report", value_name = "FILE")]
+    pub report: Option<PathBuf>,
+
+    /// Directory that receives each task's output as `<name>.log`.
+    #[arg(long = "logs-dir", value_name = "DIR")]
+    pub logs_dir: Option<PathBuf>,
+
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
+}
+
+/// Arguments for `codex eval`.
+#[derive(Parser, Debug)]
+pub struct EvalCli {
+    /// YAML file defining the tasks, variants and scorers of the suite.
+    #[arg(value_name = "SUITE_FILE")]
+    pub suite_file: PathBuf,
+
+    /// Number of runs to carry out at once. Overrides `parallel` in the suite.
+    #[arg(long = "parallel", short = 'j', value_name = "N")]
+    pub parallel: Option<std::num::NonZeroUsize>,
+
+    /// Only run this task; repeat to run several.
+    #[arg(long = "task", value_name = "NAME")]
+    pub tasks: Vec<String>,
+
+    /// Only run this variant; repeat to run several.
+    #[arg(long = "variant", value_name = "NAME")]
+    pub variants: Vec<String>,
+
+    /// Print the JSON report to stdout instead of the summary.
+    #[arg(long = "json", default_value_t = false)]
+    pub json: bool,
+
+    /// Also write the JSON report to this file.
+    #[arg(long = "report", value_name = "FILE")]
+    pub report: Option<PathBuf>,
+
+    /// Directory that receives each run's output as `<variant>/<task>.log`
+    /// and its events as `<variant>/<task>.jsonl`.
+    #[arg(long = "logs-dir", value_name = "DIR")]
+    pub logs_dir: Option<PathBuf>,
+
+    /// Directory in which the runs' workspaces are created. Defaults to a
+    /// temporary directory.
+    #[arg(long = "work-dir", value_name = "DIR")]
+    pub work_dir: Option<PathBuf>,
+
+    /// Keep every run's workspace instead of deleting it once scored.
+    #[arg(long = "keep-workspaces", default_value_t = false)]
+    pub keep_workspaces: bool,
+
+    #[clap(skip)]
+    pub config_overrides: CliConfigOverrides,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
@@ -177,3 +280,11 @@ pub enum Color {
     #[default]
     Auto,
 }
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    #[default]
+    Human,
+    Jsonl,
+}
@@ -0,0 +1,1143 @@
+//! `codex eval`: score a suite of tasks across models and settings.
+//!
+//! Every task of the suite runs once per variant, as its own `codex exec`
+//! process, in a fresh copy of the task's fixture directory. A run is scored
+//! by the task's `check` command (1 when it exits with status 0, 0
+//! otherwise) and by the suite's scorer commands, which print a number. The
+//! report lists every run and, for each variant, how many runs passed, the
+//! mean scores, the time the agent took and the tokens it used.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use anyhow::Context;
+use codex_protocol::config_types::SandboxMode;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::task::JoinSet;
+
+use crate::batch::TaskStatus;
+use crate::batch::path_component;
+use crate::batch::run_check;
+use crate::batch::scratch_file;
+use crate::batch::shell_command;
+use crate::cli::EvalCli;
+use crate::exec_events::ThreadEvent;
+use crate::exec_events::Usage;
+
+/// Version of the report format, bumped on breaking changes.
+pub const REPORT_VERSION: u32 = 1;
+
+/// Name under which a task's `check` command is scored.
+pub const CHECK_SCORER: &str = "check";
+
+/// Name of the only variant of a suite that does not define any.
+const DEFAULT_VARIANT: &str = "default";
+
+const DEFAULT_SCORER_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// A suite file, usually `eval.yaml`.
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SuiteFile {
+    /// How many runs happen at once. Defaults to 1 (sequential).
+    #[serde(default)]
+    pub parallel: Option<usize>,
+    /// Sandbox for tasks that do not set one.
+    #[serde(default)]
+    pub sandbox: Option<SandboxMode>,
+    /// Models or settings to compare. A suite without variants runs every
+    /// task once with the configured defaults.
+    #[serde(default)]
+    pub variants: Vec<VariantSpec>,
+    /// Commands that score every run, in addition to the task's `check`.
+    #[serde(default)]
+    pub scorers: Vec<ScorerSpec>,
+    pub tasks: Vec<EvalTaskSpec>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct VariantSpec {
+    pub name: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Profile from config.toml.
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// `key=value` overrides, as passed to `-c`.
+    #[serde(default)]
+    pub config: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ScorerSpec {
+    pub name: String,
+    /// Shell command run in the run's workspace once the agent finishes. It
+    /// reads the run as JSON on stdin and prints its score as the last line
+    /// of its output.
+    pub command: String,
+    /// Weight of the scorer in a run's overall score. Defaults to 1.
+    #[serde(default)]
+    pub weight: Option<f64>,
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct EvalTaskSpec {
+    pub name: String,
+    pub prompt: String,
+    /// Directory copied into a fresh workspace for every run, relative to
+    /// the suite file. Without one, runs start in an empty directory.
+    #[serde(default)]
+    pub fixture: Option<PathBuf>,
+    #[serde(default)]
+    pub sandbox: Option<SandboxMode>,
+    /// Shell command run in the workspace after the agent finishes; the run
+    /// passes when it exits with status 0.
+    #[serde(default)]
+    pub check: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct EvalTask {
+    name: String,
+    prompt: String,
+    fixture: Option<PathBuf>,
+    sandbox: Option<SandboxMode>,
+    check: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Variant {
+    name: String,
+    model: Option<String>,
+    profile: Option<String>,
+    config: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Scorer {
+    name: String,
+    command: String,
+    weight: f64,
+    timeout: Duration,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RunResult {
+    pub task: String,
+    pub variant: String,
+    pub status: TaskStatus,
+    /// Exit code of `codex exec`.
+    pub exit_code: Option<i32>,
+    /// Time the agent took, excluding the check and the scorers.
+    pub duration_ms: u64,
+    pub usage: Usage,
+    /// Score of every scorer that produced one, `check` included.
+    pub scores: BTreeMap<String, f64>,
+    /// Weighted mean of `scores`.
+    pub score: Option<f64>,
+    /// Why a scorer produced no score.
+    pub scorer_errors: BTreeMap<String, String>,
+    pub last_message: Option<String>,
+    /// Kept only with `--keep-workspaces`.
+    pub workspace: Option<PathBuf>,
+    pub error: Option<String>,
+}
+
+impl RunResult {
+    /// A run that has not produced anything yet.
+    fn new(task: &EvalTask, variant: &Variant) -> Self {
+        Self {
+            task: task.name.clone(),
+            variant: variant.name.clone(),
+            status: TaskStatus::Error,
+            exit_code: None,
+            duration_ms: 0,
+            usage: Usage::default(),
+            scores: BTreeMap::new(),
+            score: None,
+            scorer_errors: BTreeMap::new(),
+            last_message: None,
+            workspace: None,
+            error: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct VariantSummary {
+    pub variant: String,
+    pub runs: usize,
+    pub passed: usize,
+    pub failed: usize,
+    /// Runs that could not be started.
+    pub errors: usize,
+    /// Mean overall score of the runs that have one.
+    pub score: Option<f64>,
+    /// Mean of every scorer over the runs it scored.
+    pub scores: BTreeMap<String, f64>,
+    pub mean_duration_ms: u64,
+    /// Tokens used by all runs.
+    pub usage: Usage,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct EvalReport {
+    pub version: u32,
+    pub variants: Vec<VariantSummary>,
+    pub runs: Vec<RunResult>,
+}
+
+impl EvalReport {
+    /// Whether every run could be carried out, whatever its score.
+    pub fn completed(&self) -> bool {
+        self.runs.iter().all(|run| run.status != TaskStatus::Error)
+    }
+}
+
+/// What a scorer reads on stdin.
+#[derive(Debug, Serialize)]
+struct ScorerInput<'a> {
+    task: &'a str,
+    variant: &'a str,
+    prompt: &'a str,
+    workspace: &'a Path,
+    exit_code: Option<i32>,
+    /// Whether the agent succeeded and the task's check (if any) passed.
+    passed: bool,
+    last_message: Option<&'a str>,
+    usage: &'a Usage,
+}
+
+/// Runs `codex eval`, printing the summary or the report. Returns whether
+/// every run could be carried out.
+pub async fn run_eval(cli: EvalCli) -> anyhow::Result<bool> {
+    let exe = std::env::current_exe().context("failed to locate the codex executable")?;
+    let contents = std::fs::read_to_string(&cli.suite_file)
+        .with_context(|| format!("failed to read {}", cli.suite_file.display()))?;
+    let file = parse_suite_file(&contents)
+        .with_context(|| format!("invalid suite file {}", cli.suite_file.display()))?;
+    let parallel = cli
+        .parallel
+        .map(std::num::NonZeroUsize::get)
+        .or(file.parallel)
+        .unwrap_or(1);
+    let base_dir = std::path::absolute(&cli.suite_file)?
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    let suite = resolve_suite(file, &base_dir, &cli.tasks, &cli.variants)?;
+    for (task, _) in &suite.runs {
+        if let Some(fixture) = &task.fixture
+            && !fixture.is_dir()
+        {
+            anyhow::bail!(
+                "the fixture of task `{}` is not a directory: {}",
+                task.name,
+                fixture.display()
+            );
+        }
+    }
+
+    let default_work_dir = cli.work_dir.is_none();
+    let work_dir = match cli.work_dir {
+        Some(work_dir) => std::path::absolute(work_dir)?,
+        None => std::env::temp_dir().join(format!("codex-eval-{}", std::process::id())),
+    };
+    for dir in std::iter::once(&work_dir).chain(cli.logs_dir.as_ref()) {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    let runner = EvalRunner {
+        exe,
+        config_args: cli
+            .config_overrides
+            .raw_overrides
+            .iter()
+            .flat_map(|raw| ["-c".to_string(), raw.clone()])
+            .collect(),
+        logs_dir: cli.logs_dir,
+        work_dir: work_dir.clone(),
+        keep_workspaces: cli.keep_workspaces,
+        scorers: suite.scorers,
+    };
+    let report = runner.run(suite.runs, parallel).await;
+    if default_work_dir && !cli.keep_workspaces {
+        let _ = std::fs::remove_dir_all(&work_dir);
+    }
+
+    let json = serde_json::to_string_pretty(&report)?;
+    if let Some(path) = &cli.report {
+        std::fs::write(path, format!("{json}\n"))
+            .with_context(|| format!("failed to write report to {}", path.display()))?;
+    }
+    #[allow(clippy::print_stdout)]
+    {
+        if cli.json {
+            println!("{json}");
+        } else {
+            print!("{}", render_summary(&report));
+        }
+    }
+    Ok(report.completed())
+}
+
+pub fn parse_suite_file(contents: &str) -> anyhow::Result<SuiteFile> {
+    let file: SuiteFile = serde_yaml::from_str(contents)?;
+    if file.tasks.is_empty() {
+        anyhow::bail!("the suite does not define any tasks");
+    }
+    if file.parallel == Some(0) {
+        anyhow::bail!("`parallel` must be at least 1");
+    }
+    ensure_unique("task", file.tasks.iter().map(|task| task.name.as_str()))?;
+    ensure_unique(
+        "variant",
+        file.variants.iter().map(|variant| variant.name.as_str()),
+    )?;
+    ensure_distinct_paths("task", file.tasks.iter().map(|task| task.name.as_str()))?;
+    ensure_distinct_paths(
+        "variant",
+        file.variants.iter().map(|variant| variant.name.as_str()),
+    )?;
+    ensure_unique(
+        "scorer",
+        std::iter::once(CHECK_SCORER).chain(file.scorers.iter().map(|scorer| scorer.name.as_str())),
+    )?;
+    for scorer in &file.scorers {
+        if let Some(weight) = scorer.weight
+            && !(weight.is_finite() && weight >= 0.0)
+        {
+            anyhow::bail!(
+                "scorer `{}` has an invalid weight {weight}; weights must be zero or positive",
+                scorer.name
+            );
+        }
+    }
+    for variant in &file.variants {
+        if let Some(raw) = variant.config.iter().find(|raw| !raw.contains('=')) {
+            anyhow::bail!(
+                "variant `{}` has an invalid override `{raw}`; expected key=value",
+                variant.name
+            );
+        }
+    }
+    Ok(file)
+}
+
+fn ensure_unique<'a>(kind: &str, names: impl Iterator<Item = &'a str>) -> anyhow::Result<()> {
+    let mut seen = HashSet::new();
+    for name in names {
+        if name.trim().is_empty() {
+            anyhow::bail!("every {kind} needs a name");
+        }
+        if !seen.insert(name) {
+            if name == CHECK_SCORER && kind == "scorer" {
+                anyhow::bail!("`{CHECK_SCORER}` is reserved for the tasks' check commands");
+            }
+            anyhow::bail!("{kind} `{name}` is defined more than once");
+        }
+    }
+    Ok(())
+}
+
+/// Task and variant names become directory names, so they must stay
+/// distinct, and stay put, once made safe for a path.
+fn ensure_distinct_paths<'a>(
+    kind: &str,
+    names: impl Iterator<Item = &'a str>,
+) -> anyhow::Result<()> {
+    let mut seen: HashMap<String, &str> = HashMap::new();
+    for name in names {
+        let component = path_component(name);
+        if component.is_empty() || component == "." || component == ".." {
+            anyhow::bail!("{kind} `{name}` cannot be used as a directory name");
+        }
+        if let Some(other) = seen.get(&component) {
+            anyhow::bail!(
+                "{kind}s `{other}` and `{name}` would share the directory `{component}`; rename one"
+            );
+        }
+        seen.insert(component, name);
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+struct Suite {
+    runs: Vec<(EvalTask, Variant)>,
+    scorers: Vec<Scorer>,
+}
+
+/// Applies the suite's defaults and the `--task`/`--variant` filters.
+fn resolve_suite(
+    file: SuiteFile,
+    base_dir: &Path,
+    task_filter: &[String],
+    variant_filter: &[String],
+) -> anyhow::Result<Suite> {
+    let tasks: Vec<EvalTask> = file
+        .tasks
+        .into_iter()
+        .map(|spec| EvalTask {
+            name: spec.name,
+            prompt: spec.prompt,
+            fixture: spec.fixture.map(|fixture| base_dir.join(fixture)),
+            sandbox: spec.sandbox.or(file.sandbox),
+            check: spec.check,
+        })
+        .collect();
+    let variants: Vec<Variant> = if file.variants.is_empty() {
+        vec![Variant {
+            name: DEFAULT_VARIANT.to_string(),
+            model: None,
+            profile: None,
+            config: Vec::new(),
+        }]
+    } else {
+        file.variants
+            .into_iter()
+            .map(|spec| Variant {
+                name: spec.name,
+                model: spec.model,
+                profile: spec.profile,
+                config: spec.config,
+            })
+            .collect()
+    };
+    let tasks = select("task", tasks, task_filter, |task| &task.name)?;
+    let variants = select("variant", variants, variant_filter, |variant| &variant.name)?;
+
+    let runs = tasks
+        .iter()
+        .flat_map(|task| {
+            variants
+                .iter()
+                .map(move |variant| (task.clone(), variant.clone()))
+        })
+        .collect();
+    let scorers = file
+        .scorers
+        .into_iter()
+        .map(|spec| Scorer {
+            name: spec.name,
+            command: spec.command,
+            weight: spec.weight.unwrap_or(1.0),
+            timeout: spec
+                .timeout_ms
+                .map_or(DEFAULT_SCORER_TIMEOUT, Duration::from_millis),
+        })
+        .collect();
+    Ok(Suite { runs, scorers })
+}
+
+/// Keeps the items named in `filter`, or all of them when it is empty.
+fn select<T>(
+    kind: &str,
+    items: Vec<T>,
+    filter: &[String],
+    name: impl Fn(&T) -> &String,
+) -> anyhow::Result<Vec<T>> {
+    if filter.is_empty() {
+        return Ok(items);
+    }
+    if let Some(unknown) = filter
+        .iter()
+        .find(|wanted| !items.iter().any(|item| name(item) == *wanted))
+    {
+        anyhow::bail!("the suite has no {kind} named `{unknown}`");
+    }
+    Ok(items
+        .into_iter()
+        .filter(|item| filter.contains(name(item)))
+        .collect())
+}
+
+/// What `codex exec` produced for one run.
+struct AgentOutcome {
+    exit_code: Option<i32>,
+    last_message: Option<String>,
+    usage: Usage,
+}
+
+/// Runs every (task, variant) pair by spawning `exe exec …` in a workspace
+/// of its own.
+struct EvalRunner {
+    exe: PathBuf,
+    /// `-c key=value` pairs forwarded to every run.
+    config_args: Vec<String>,
+    logs_dir: Option<PathBuf>,
+    /// Parent of the runs' workspaces.
+    work_dir: PathBuf,
+    keep_workspaces: bool,
+    scorers: Vec<Scorer>,
+}
+
+impl EvalRunner {
+    /// Runs up to `parallel` runs at once; results keep the suite's order.
+    async fn run(self, runs: Vec<(EvalTask, Variant)>, parallel: usize) -> EvalReport {
+        let variant_names = runs.iter().fold(Vec::new(), |mut names, (_, variant)| {
+            if !names.contains(&variant.name) {
+                names.push(variant.name.clone());
+            }
+            names
+        });
+        let runner = Arc::new(self);
+        let mut results: Vec<RunResult> = runs
+            .iter()
+            .map(|(task, variant)| RunResult::new(task, variant))
+            .collect();
+        let mut pending = runs.into_iter().enumerate();
+        let mut running = JoinSet::new();
+        let mut indices = HashMap::new();
+        loop {
+            while running.len() < parallel.max(1) {
+                let Some((index, (task, variant))) = pending.next() else {
+                    break;
+                };
+                let runner = Arc::clone(&runner);
+                let handle =
+                    running.spawn(async move { runner.run_one(index, task, variant).await });
+                indices.insert(handle.id(), index);
+            }
+            match running.join_next_with_id().await {
+                Some(Ok((id, result))) => {
+                    if let Some(index) = indices.remove(&id) {
+                        results[index] = result;
+                    }
+                }
+                // A run that panicked keeps its placeholder result, marked
+                // as an error.
+                Some(Err(err)) => {
+                    if let Some(index) = indices.remove(&err.id()) {
+                        results[index].error = Some(format!("the run panicked: {err}"));
+                    }
+                }
+                None => break,
+            }
+        }
+
+        let runs = results;
+        EvalReport {
+            version: REPORT_VERSION,
+            variants: variant_names
+                .into_iter()
+                .map(|variant| summarize(variant, &runs))
+                .collect(),
+            runs,
+        }
+    }
+
+    async fn run_one(&self, index: usize, task: EvalTask, variant: Variant) -> RunResult {
+        let workspace = self
+            .work_dir
+            .join(path_component(&variant.name))
+            .join(path_component(&task.name));
+        let mut result = RunResult::new(&task, &variant);
+        if let Err(err) = self
+            .score_run(index, &task, &variant, &workspace, &mut result)
+            .await
+        {
+            result.error = Some(format!("{err:#}"));
+        }
+        result.score = self.overall_score(&result.scores);
+        if self.keep_workspaces {
+            result.workspace = Some(workspace);
+        } else {
+            let _ = std::fs::remove_dir_all(&workspace);
+        }
+        result
+    }
+
+    async fn score_run(
+        &self,
+        index: usize,
+        task: &EvalTask,
+        variant: &Variant,
+        workspace: &Path,
+        result: &mut RunResult,
+    ) -> anyhow::Result<()> {
+        prepare_workspace(task.fixture.as_deref(), workspace)?;
+        let start = Instant::now();
+        let agent = self.run_agent(index, task, variant, workspace).await?;
+        result.duration_ms = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
+        result.exit_code = agent.exit_code;
+        result.usage = agent.usage;
+        result.last_message = agent.last_message;
+
+        // Like `codex run`, the check only runs when the agent succeeded.
+        let mut passed = result.exit_code == Some(0);
+        if let Some(check) = &task.check {
+            passed = passed && run_check(check, workspace).await == Some(0);
+            result
+                .scores
+                .insert(CHECK_SCORER.to_string(), if passed { 1.0 } else { 0.0 });
+        }
+        result.status = if passed {
+            TaskStatus::Passed
+        } else {
+            TaskStatus::Failed
+        };
+
+        let input = serde_json::to_vec(&ScorerInput {
+            task: &task.name,
+            variant: &variant.name,
+            prompt: &task.prompt,
+            workspace,
+            exit_code: result.exit_code,
+            passed,
+            last_message: result.last_message.as_deref(),
+            usage: &result.usage,
+        })?;
+        for scorer in &self.scorers {
+            match run_scorer(scorer, &input, workspace, &task.name, &variant.name).await {
+                Ok(score) => {
+                    result.scores.insert(scorer.name.clone(), score);
+                }
+                Err(err) => {
+                    result.scorer_errors.insert(scorer.name.clone(), err);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Weighted mean of a run's scores; `check` weighs 1.
+    fn overall_score(&self, scores: &BTreeMap<String, f64>) -> Option<f64> {
+        let mut total = 0.0;
+        let mut weights = 0.0;
+        for (name, score) in scores {
+            let weight = self
+                .scorers
+                .iter()
+                .find(|scorer| &scorer.name == name)
+                .map_or(1.0, |scorer| scorer.weight);
+            total += score * weight;
+            weights += weight;
+        }
+        (weights > 0.0).then_some(total / weights)
+    }
+
+    async fn run_agent(
+        &self,
+        index: usize,
+        task: &EvalTask,
+        variant: &Variant,
+        workspace: &Path,
+    ) -> anyhow::Result<AgentOutcome> {
+        let last_message_file = scratch_file(&format!("eval-{index}"))?;
+        let mut command = Command::new(&self.exe);
+        command
+            .args(self.exec_args(task, variant, workspace, last_message_file.path()))
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .kill_on_drop(true);
+        // Logs mirror the workspaces: one directory per variant.
+        let log_dir = self
+            .logs_dir
+            .as_ref()
+            .map(|logs_dir| logs_dir.join(path_component(&variant.name)));
+        let log_name = path_component(&task.name);
+        match &log_dir {
+            Some(log_dir) => {
+                std::fs::create_dir_all(log_dir)?;
+                command.stderr(std::fs::File::create(
+                    log_dir.join(format!("{log_name}.log")),
+                )?);
+            }
+            None => {
+                command.stderr(Stdio::null());
+            }
+        }
+        let output = command
+            .output()
+            .await
+            .with_context(|| format!("failed to run {}", self.exe.display()))?;
+        let events = String::from_utf8_lossy(&output.stdout);
+        if let Some(log_dir) = &log_dir {
+            std::fs::write(log_dir.join(format!("{log_name}.jsonl")), events.as_bytes())?;
+        }
+        let last_message = std::fs::read_to_string(last_message_file.path())
+            .ok()
+            .map(|message| message.trim_end().to_string())
+            .filter(|message| !message.is_empty());
+        Ok(AgentOutcome {
+            exit_code: output.status.code(),
+            last_message,
+            usage: total_usage(&events),
+        })
+    }
+
+    fn exec_args(
+        &self,
+        task: &EvalTask,
+        variant: &Variant,
+        workspace: &Path,
+        last_message_file: &Path,
+    ) -> Vec<String> {
+        let mut args = vec!["exec".to_string()];
+        // Variant overrides come last so they win over the command line's.
+        args.extend(self.config_args.iter().cloned());
+        for raw in &variant.config {
+            args.push("-c".to_string());
+            args.push(raw.clone());
+        }
+        args.push("--json".to_string());
+        args.push("--skip-git-repo-check".to_string());
+        args.push("--cd".to_string());
+        args.push(workspace.display().to_string());
+        if let Some(sandbox) = task.sandbox {
+            args.push("--sandbox".to_string());
+            args.push(sandbox.to_string());
+        }
+        if let Some(model) = &variant.model {
+            args.push("--model".to_string());
+            args.push(model.clone());
+        }
+        if let Some(profile) = &variant.profile {
+            args.push("--profile".to_string());
+            args.push(profile.clone());
+        }
+        args.push("--output-last-message".to_string());
+        args.push(last_message_file.display().to_string());
+        args.push("--".to_string());
+        args.push(task.prompt.clone());
+        args
+    }
+}
+
+/// Recreates `workspace` as a copy of `fixture`, or as an empty directory.
+fn prepare_workspace(fixture: Option<&Path>, workspace: &Path) -> anyhow::Result<()> {
+    if workspace.exists() {
+        std::fs::remove_dir_all(workspace)
+            .with_context(|| format!("failed to clear {}", workspace.display()))?;
+    }
+    std::fs::create_dir_all(workspace)
+        .with_context(|| format!("failed to create {}", workspace.display()))?;
+    if let Some(fixture) = fixture {
+        copy_dir(fixture, workspace)
+            .with_context(|| format!("failed to copy the fixture {}", fixture.display()))?;
+    }
+    Ok(())
+}
+
+fn copy_dir(src: &Path, dst: &Path) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            std::fs::create_dir_all(&target)?;
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Sums the usage of every `turn.completed` event in `codex exec --json`
+/// output.
+fn total_usage(events: &str) -> Usage {
+    let mut total = Usage::default();
+    for line in events.lines() {
+        if let Ok(ThreadEvent::TurnCompleted(event)) = serde_json::from_str(line) {
+            add_usage(&mut total, &event.usage);
+        }
+    }
+    total
+}
+
+fn add_usage(total: &mut Usage, usage: &Usage) {
+    total.input_tokens += usage.input_tokens;
+    total.cached_input_tokens += usage.cached_input_tokens;
+    total.output_tokens += usage.output_tokens;
+}
+
+async fn run_scorer(
+    scorer: &Scorer,
+    input: &[u8],
+    workspace: &Path,
+    task: &str,
+    variant: &str,
+) -> Result<f64, String> {
+    let mut child = shell_command(&scorer.command)
+        .current_dir(workspace)
+        .env("CODEX_EVAL_TASK", task)
+        .env("CODEX_EVAL_VARIANT", variant)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|err| format!("failed to run `{}`: {err}", scorer.command))?;
+    let run = async move {
+        if let Some(mut stdin) = child.stdin.take() {
+            // Scorers are free to ignore their input, so a closed pipe is fine.
+            let _ = stdin.write_all(input).await;
+        }
+        child.wait_with_output().await
+    };
+    let output = match tokio::time::timeout(scorer.timeout, run).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(err)) => return Err(format!("failed to wait for `{}`: {err}", scorer.command)),
+        Err(_) => {
+            return Err(format!(
+                "`{}` timed out after {}ms",
+                scorer.command,
+                scorer.timeout.as_millis()
+            ));
+        }
+    };
+    if !output.status.success() {
+        return Err(format!(
+            "`{}` exited with {}",
+            scorer.command, output.status
+        ));
+    }
+    parse_score(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// The score a scorer printed: the last non-empty line of its output.
+fn parse_score(stdout: &str) -> Result<f64, String> {
+    let Some(line) = stdout.lines().map(str::trim).rfind(|line| !line.is_empty()) else {
+        return Err("printed no score".to_string());
+    };
+    line.parse::<f64>()
+        .ok()
+        .filter(|score| score.is_finite())
+        .ok_or_else(|| format!("printed `{line}` instead of a score"))
+}
+
+fn mean(values: impl IntoIterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values
+        .into_iter()
+        .fold((0.0, 0u32), |(sum, count), value| (sum + value, count + 1));
+    (count > 0).then_some(sum / f64::from(count))
+}
+
+fn summarize(variant: String, runs: &[RunResult]) -> VariantSummary {
+    let runs: Vec<&RunResult> = runs.iter().filter(|run| run.variant == variant).collect();
+    let count = |status: TaskStatus| runs.iter().filter(|run| run.status == status).count();
+    let scorer_names: std::collections::BTreeSet<&String> =
+        runs.iter().flat_map(|run| run.scores.keys()).collect();
+    let mut usage = Usage::default();
+    for run in &runs {
+        add_usage(&mut usage, &run.usage);
+    }
+    let durations: u64 = runs.iter().map(|run| run.duration_ms).sum();
+    VariantSummary {
+        runs: runs.len(),
+        passed: count(TaskStatus::Passed),
+        failed: count(TaskStatus::Failed),
+        errors: count(TaskStatus::Error),
+        score: mean(runs.iter().filter_map(|run| run.score)),
+        scores: scorer_names
+            .into_iter()
+            .filter_map(|name| {
+                mean(runs.iter().filter_map(|run| run.scores.get(name).copied()))
+                    .map(|score| (name.clone(), score))
+            })
+            .collect(),
+        mean_duration_ms: durations / u64::try_from(runs.len().max(1)).unwrap_or(1),
+        usage,
+        variant,
+    }
+}
+
+/// The per-run lines and the per-variant table printed without `--json`.
+fn render_summary(report: &EvalReport) -> String {
+    let format_score =
+        |score: Option<f64>| score.map_or_else(|| "-".to_string(), |s| format!("{s:.2}"));
+    let task_width = report
+        .runs
+        .iter()
+        .map(|run| run.task.len())
+        .chain(std::iter::once("task".len()))
+        .max()
+        .unwrap_or_default();
+    let variant_width = report
+        .variants
+        .iter()
+        .map(|variant| variant.variant.len())
+        .chain(std::iter::once("variant".len()))
+        .max()
+        .unwrap_or_default();
+
+    let mut out = format!(
+        "{:task_width$}  {:variant_width$}  {:6}  score\n",
+        "task", "variant", "status"
+    );
+    for run in &report.runs {
+        let status = match run.status {
+            TaskStatus::Passed => "passed",
+            TaskStatus::Failed => "failed",
+            TaskStatus::Error => "error",
+        };
+        out.push_str(&format!(
+            "{:task_width$}  {:variant_width$}  {status:6}  {}\n",
+            run.task,
+            run.variant,
+            format_score(run.score)
+        ));
+    }
+    out.push_str(&format!(
+        "\n{:variant_width$}  {:>6}  {:>6}  {:>5}  {:>9}  {:>10}\n",
+        "variant", "runs", "passed", "score", "mean time", "tokens"
+    ));
+    for variant in &report.variants {
+        let tokens = variant.usage.input_tokens + variant.usage.output_tokens;
+        out.push_str(&format!(
+            "{:variant_width$}  {:>6}  {:>6}  {:>5}  {:>8.1}s  {tokens:>10}\n",
+            variant.variant,
+            variant.runs,
+            variant.passed,
+            format_score(variant.score),
+            variant.mean_duration_ms as f64 / 1000.0,
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const SUITE: &str = r#"
+sandbox: workspace-write
+variants:
+  - name: baseline
+    model: gpt-5.1-codex
+  - name: high-effort
+    model: gpt-5.1-codex
+    config: ["model_reasoning_effort=high"]
+scorers:
+  - name: diff-size
+    command: ./score-diff.sh
+    weight: 0.5
+tasks:
+  - name: login
+    prompt: Fix the login test
+    fixture: fixtures/login
+    check: cargo test
+  - name: docs
+    prompt: Fix typos in the README
+    sandbox: read-only
+"#;
+
+    #[test]
+    fn every_task_runs_once_per_selected_variant() {
+        let file = parse_suite_file(SUITE).expect("parse");
+        let suite = resolve_suite(file, Path::new("/work"), &[], &["high-effort".to_string()])
+            .expect("resolve");
+
+        let high_effort = Variant {
+            name: "high-effort".to_string(),
+            model: Some("gpt-5.1-codex".to_string()),
+            profile: None,
+            config: vec!["model_reasoning_effort=high".to_string()],
+        };
+        assert_eq!(
+            suite,
+            Suite {
+                runs: vec![
+                    (
+                        EvalTask {
+                            name: "login".to_string(),
+                            prompt: "Fix the login test".to_string(),
+                            fixture: Some(PathBuf::from("/work/fixtures/login")),
+                            sandbox: Some(SandboxMode::WorkspaceWrite),
+                            check: Some("cargo test".to_string()),
+                        },
+                        high_effort.clone(),
+                    ),
+                    (
+                        EvalTask {
+                            name: "docs".to_string(),
+                            prompt: "Fix typos in the README".to_string(),
+                            fixture: None,
+                            sandbox: Some(SandboxMode::ReadOnly),
+                            check: None,
+                        },
+                        high_effort,
+                    ),
+                ],
+                scorers: vec![Scorer {
+                    name: "diff-size".to_string(),
+                    command: "./score-diff.sh".to_string(),
+                    weight: 0.5,
+                    timeout: DEFAULT_SCORER_TIMEOUT,
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_suites() {
+        assert!(parse_suite_file("tasks: []").is_err());
+        let duplicate = "tasks:\n  - {name: a, prompt: x}\n  - {name: a, prompt: y}\n";
+        assert!(parse_suite_file(duplicate).is_err());
+        let reserved =
+            "scorers:\n  - {name: check, command: x}\ntasks:\n  - {name: a, prompt: x}\n";
+        assert!(parse_suite_file(reserved).is_err());
+        let override_without_value =
+            "variants:\n  - {name: v, config: [model]}\ntasks:\n  - {name: a, prompt: x}\n";
+        assert!(parse_suite_file(override_without_value).is_err());
+        let dot_dot = "tasks:\n  - {name: '..', prompt: x}\n";
+        assert!(parse_suite_file(dot_dot).is_err());
+        let same_directory = "tasks:\n  - {name: a b, prompt: x}\n  - {name: a/b, prompt: y}\n";
+        assert!(parse_suite_file(same_directory).is_err());
+        let file = parse_suite_file("tasks:\n  - {name: a, prompt: x}\n").expect("parse");
+        assert!(resolve_suite(file, Path::new("/work"), &["b".to_string()], &[]).is_err());
+    }
+
+    #[test]
+    fn parses_the_last_line_of_a_scorer_as_its_score() {
+        assert_eq!(parse_score("diff: 12 lines\n0.75\n\n"), Ok(0.75));
+        assert!(parse_score("").is_err());
+        assert!(parse_score("good\n").is_err());
+        assert!(parse_score("NaN\n").is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn scores_every_run_in_a_copy_of_the_fixture() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        // Stands in for `codex`: writes the prompt as the last message and
+        // reports one turn's usage.
+        let exe = dir.path().join("fake-codex");
+        std::fs::write(
+            &exe,
+            "#!/bin/sh\nfor arg; do prompt=\"$arg\"; done\n\
+             while [ \"$1\" != \"--output-last-message\" ]; do shift; done\n\
+             printf 'did: %s\\n' \"$prompt\" > \"$2\"\n\
+             echo '{\"type\":\"turn.completed\",\"usage\":\
+             {\"input_tokens\":10,\"cached_input_tokens\":4,\"output_tokens\":5}}'\n",
+        )
+        .expect("write fake exe");
+        std::fs::set_permissions(&exe, std::fs::Permissions::from_mode(0o755)).expect("chmod");
+        let fixture = dir.path().join("fixture");
+        std::fs::create_dir_all(fixture.join("src")).expect("create fixture");
+        std::fs::write(fixture.join("src/lib.rs"), "").expect("write fixture");
+
+        let task = |name: &str, check: &str| EvalTask {
+            name: name.to_string(),
+            prompt: format!("{name} it"),
+            fixture: Some(fixture.clone()),
+            sandbox: None,
+            check: Some(check.to_string()),
+        };
+        let variant = |name: &str| Variant {
+            name: name.to_string(),
+            model: None,
+            profile: None,
+            config: Vec::new(),
+        };
+        let runner = EvalRunner {
+            exe,
+            config_args: Vec::new(),
+            logs_dir: None,
+            work_dir: dir.path().join("work"),
+            keep_workspaces: false,
+            scorers: vec![
+                Scorer {
+                    name: "quarter".to_string(),
+                    command: "cat > /dev/null; echo 0.25".to_string(),
+                    weight: 1.0,
+                    timeout: DEFAULT_SCORER_TIMEOUT,
+                },
+                Scorer {
+                    name: "broken".to_string(),
+                    command: "exit 2".to_string(),
+                    weight: 1.0,
+                    timeout: DEFAULT_SCORER_TIMEOUT,
+                },
+            ],
+        };
+
+        let report = runner
+            .run(
+                vec![
+                    (task("pass", "test -f src/lib.rs"), variant("a")),
+                    (task("fail", "exit 1"), variant("a")),
+                    (task("pass", "test -f src/lib.rs"), variant("b")),
+                ],
+                2,
+            )
+            .await;
+
+        let summary: Vec<_> = report
+            .runs
+            .iter()
+            .map(|run| {
+                (
+                    run.task.as_str(),
+                    run.variant.as_str(),
+                    run.status,
+                    run.score,
+                    run.last_message.as_deref(),
+                    run.scorer_errors.contains_key("broken"),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "pass",
+                    "a",
+                    TaskStatus::Passed,
+                    Some(0.625),
+                    Some("did: pass it"),
+                    true
+                ),
+                (
+                    "fail",
+                    "a",
+                    TaskStatus::Failed,
+                    Some(0.125),
+                    Some("did: fail it"),
+                    true
+                ),
+                (
+                    "pass",
+                    "b",
+                    TaskStatus::Passed,
+                    Some(0.625),
+                    Some("did: pass it"),
+                    true
+                ),
+            ]
+        );
+        let variant_a = &report.variants[0];
+        assert_eq!(
+            (
+                variant_a.variant.as_str(),
+                variant_a.runs,
+                variant_a.passed,
+                variant_a.score,
+                variant_a.scores.get(CHECK_SCORER).copied(),
+                variant_a.usage.input_tokens,
+            ),
+            ("a", 2, 1, Some(0.375), Some(0.5), 20)
+        );
+        assert!(report.completed());
+        assert!(!dir.path().join("work/a/pass").exists());
+    }
+}
@@ -0,0 +1,102 @@
+use std::path::PathBuf;
+
+use codex_core::config::Config;
+use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::SessionConfiguredEvent;
+use codex_core::protocol::TurnCompleteEvent;
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::error;
+
+use crate::event_processor::CodexStatus;
+use crate::event_processor::EventProcessor;
+use crate::event_processor::handle_last_message;
+
+/// Version of the line format printed by `--output-format jsonl`. Bumped
+/// whenever the envelope changes incompatibly; the `msg` payload follows the
+/// protocol's `EventMsg` schema.
+pub const EVENT_STREAM_SCHEMA_VERSION: u32 = 1;
+
+/// One line of `--output-format jsonl` output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventStreamLine {
+    pub schema_version: u32,
+    /// Id of the submission that produced the event.
+    pub id: String,
+    /// 1-based candidate whose session emitted the event, under `--best-of`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub candidate: Option<usize>,
+    pub msg: EventMsg,
+}
+
+/// Prints every event the agent emits, unabridged, as one JSON object per
+/// line. Unlike `--json`, nothing is aggregated or dropped, so wrappers see the
+/// same lifecycle and hook events as interactive clients.
+pub struct EventProcessorWithEventStream {
+    last_message_path: Option<PathBuf>,
+}
+
+impl EventProcessorWithEventStream {
+    pub fn new(last_message_path: Option<PathBuf>) -> Self {
+        Self { last_message_path }
+    }
+
+    pub fn format_event(event: Event) -> Option<String> {
+        Self::format_line(None, event)
+    }
+
+    /// Formats an event emitted by one of the `--best-of` candidates.
+    pub fn format_candidate_event(candidate: usize, event: Event) -> Option<String> {
+        Self::format_line(Some(candidate), event)
+    }
+
+    fn format_line(candidate: Option<usize>, event: Event) -> Option<String> {
+        let line = EventStreamLine {
+            schema_version: EVENT_STREAM_SCHEMA_VERSION,
+            id: event.id,
+            candidate,
+            msg: event.msg,
+        };
+        match serde_json::to_string(&line) {
+            Ok(line) => Some(line),
+            Err(e) => {
+                error!("Failed to serialize event: {e:?}");
+                None
+            }
+        }
+    }
+
+    #[allow(clippy::print_stdout)]
+    fn print_event(event: Event) {
+        if let Some(line) = Self::format_event(event) {
+            println!("{line}");
+        }
+    }
+}
+
+impl EventProcessor for EventProcessorWithEventStream {
+    fn print_config_summary(&mut self, _: &Config, _: &str, ev: &SessionConfiguredEvent) {
+        Self::print_event(Event {
+            id: String::new(),
+            msg: EventMsg::SessionConfigured(ev.clone()),
+        });
+    }
+
+    fn process_event(&mut self, event: Event) -> CodexStatus {
+        let status = match &event.msg {
+            EventMsg::TurnComplete(TurnCompleteEvent {
+                last_agent_message, ..
+            }) => {
+                if let Some(output_file) = self.last_message_path.as_deref() {
+                    handle_last_message(last_agent_message.as_deref(), output_file);
+                }
+                CodexStatus::InitiateShutdown
+            }
+            EventMsg::ShutdownComplete => CodexStatus::Shutdown,
+            _ => CodexStatus::Running,
+        };
+        Self::print_event(event);
+        status
+    }
+}
@@ -4,24 +4,36 @@ use codex_core::config::Config;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::CancelledOperation;
+use codex_core::protocol::ContextRepairedEvent;
+use codex_core::protocol::ContextSource;
 use codex_core::protocol::DeprecationNoticeEvent;
+use codex_core::protocol::DroppedContext;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::FileChange;
+use codex_core::protocol::GuardrailTrippedEvent;
+use codex_core::protocol::InjectionSuspectedEvent;
 use codex_core::protocol::McpInvocation;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::ProjectDocsUpdatedEvent;
+use codex_core::protocol::PullRequestPublishedEvent;
+use codex_core::protocol::RedactionAppliedEvent;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::StreamErrorEvent;
 use codex_core::protocol::TurnAbortReason;
 use codex_core::protocol::TurnCompleteEvent;
 use codex_core::protocol::TurnDiffEvent;
+use codex_core::protocol::VerificationStatus;
 use codex_core::protocol::WarningEvent;
+use codex_core::protocol::WatchdogFiredEvent;
+use codex_core::protocol::WatchedFilesChangedEvent;
 use codex_core::protocol::WebSearchEndEvent;
 use codex_protocol::num_format::format_with_separators;
 use owo_colors::OwoColorize;
@@ -173,6 +185,22 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     "warning:".style(self.yellow).style(self.bold)
                 );
             }
+            EventMsg::PullRequestPublished(PullRequestPublishedEvent {
+                branch,
+                commit: _,
+                url,
+            }) => match url {
+                Some(url) => ts_msg!(
+                    self,
+                    "{} {url} ({branch})",
+                    "pull request:".style(self.green).style(self.bold)
+                ),
+                None => ts_msg!(
+                    self,
+                    "{} pushed {branch}",
+                    "pull request:".style(self.green).style(self.bold)
+                ),
+            },
             EventMsg::DeprecationNotice(DeprecationNoticeEvent { summary, details }) => {
                 ts_msg!(
                     self,
@@ -212,6 +240,9 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 if !summary.cancelled.is_empty() {
                     parts.push(format!("cancelled: {}", summary.cancelled.join(", ")));
                 }
+                if !summary.deferred.is_empty() {
+                    parts.push(format!("deferred: {}", summary.deferred.join(", ")));
+                }
                 let joined = if parts.is_empty() {
                     "no servers".to_string()
                 } else {
@@ -219,6 +250,23 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 };
                 ts_msg!(self, "{} {}", "mcp startup:".style(self.cyan), joined);
             }
+            EventMsg::McpServerDown(ev) => {
+                let suffix = if ev.restarting {
+                    " (restarting)"
+                } else {
+                    " (gave up restarting)"
+                };
+                ts_msg!(
+                    self,
+                    "{} {} down: {}{suffix}",
+                    "mcp:".style(self.cyan),
+                    ev.server,
+                    ev.reason
+                );
+            }
+            EventMsg::McpServerRestarted(ev) => {
+                ts_msg!(self, "{} {} restarted", "mcp:".style(self.cyan), ev.server);
+            }
             EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
                 ts_msg!(self, "{}", message.style(self.dimmed));
             }
@@ -249,7 +297,46 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     "auto-cancelling (not supported in exec mode)".style(self.dimmed)
                 );
             }
-            EventMsg::TurnComplete(TurnCompleteEvent { last_agent_message }) => {
+            EventMsg::TurnComplete(TurnCompleteEvent {
+                last_agent_message,
+                verification,
+                context_dropped,
+                ..
+            }) => {
+                for DroppedContext {
+                    source,
+                    items,
+                    tokens,
+                } in context_dropped
+                {
+                    let source = format_context_source(source);
+                    ts_msg!(
+                        self,
+                        "{} trimmed {items} item(s) of {source}, ~{tokens} tokens",
+                        "context budget:".style(self.dimmed)
+                    );
+                }
+                if let Some(VerificationStatus {
+                    command,
+                    passed,
+                    exit_code,
+                    runs,
+                }) = verification
+                {
+                    if passed {
+                        ts_msg!(
+                            self,
+                            "{} `{command}` passed after {runs} run(s)",
+                            "verification:".style(self.green).style(self.bold)
+                        );
+                    } else {
+                        ts_msg!(
+                            self,
+                            "{} `{command}` still failing with exit code {exit_code} after {runs} run(s)",
+                            "verification:".style(self.red).style(self.bold)
+                        );
+                    }
+                }
                 let last_message = last_agent_message.as_deref();
                 if let Some(output_file) = self.last_message_path.as_deref() {
                     handle_last_message(last_message, output_file);
@@ -557,20 +644,129 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     view.path.display()
                 );
             }
+            EventMsg::RedactionApplied(RedactionAppliedEvent { redactions, .. }) => {
+                let rules = redactions
+                    .iter()
+                    .map(|redaction| format!("{} ({})", redaction.rule, redaction.count))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                ts_msg!(
+                    self,
+                    "{} {rules}",
+                    "redacted tool output:".style(self.dimmed)
+                );
+            }
+            EventMsg::InjectionSuspected(InjectionSuspectedEvent {
+                reasons, stripped, ..
+            }) => {
+                let action = if stripped { "stripped" } else { "flagged" };
+                ts_msg!(
+                    self,
+                    "{} {action} ({})",
+                    "suspected prompt injection:".style(self.yellow),
+                    reasons.join(", ")
+                );
+            }
+            EventMsg::GuardrailTripped(GuardrailTrippedEvent { message, .. }) => {
+                ts_msg!(self, "{} {message}", "guardrail tripped:".style(self.red));
+            }
+            EventMsg::WatchdogFired(WatchdogFiredEvent { message, .. }) => {
+                ts_msg!(self, "{} {message}", "watchdog:".style(self.red));
+            }
+            EventMsg::ProjectDocsUpdated(ProjectDocsUpdatedEvent { paths }) => {
+                let paths = paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                ts_msg!(
+                    self,
+                    "{} {paths}",
+                    "reloaded project docs:".style(self.dimmed)
+                );
+            }
+            EventMsg::WatchedFilesChanged(WatchedFilesChangedEvent { changed, removed }) => {
+                let paths = changed
+                    .iter()
+                    .chain(&removed)
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                ts_msg!(
+                    self,
+                    "{} {paths}",
+                    "files changed outside the session:".style(self.dimmed)
+                );
+            }
+            EventMsg::EnvironmentFingerprint(fingerprint) => {
+                let mut parts = vec![format!("{} ({})", fingerprint.os, fingerprint.arch)];
+                parts.extend(fingerprint.container);
+                parts.extend(
+                    fingerprint
+                        .toolchains
+                        .iter()
+                        .map(|(name, version)| format!("{name} {version}")),
+                );
+                ts_msg!(
+                    self,
+                    "{} {}",
+                    "environment:".style(self.dimmed),
+                    parts.join(", ")
+                );
+            }
             EventMsg::TurnAborted(abort_reason) => match abort_reason.reason {
-                TurnAbortReason::Interrupted => {
+                TurnAbortReason::Interrupted if abort_reason.cancelled.is_empty() => {
                     ts_msg!(self, "task interrupted");
                 }
+                TurnAbortReason::Interrupted => {
+                    let cancelled = abort_reason
+                        .cancelled
+                        .iter()
+                        .map(format_cancelled_operation)
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    ts_msg!(self, "task interrupted, cancelled: {cancelled}");
+                }
+                TurnAbortReason::Stopped => {
+                    ts_msg!(self, "task stopped; partial output kept");
+                }
                 TurnAbortReason::Replaced => {
                     ts_msg!(self, "task aborted: replaced by a new task");
                 }
                 TurnAbortReason::ReviewEnded => {
                     ts_msg!(self, "task aborted: review ended");
                 }
+                TurnAbortReason::TimedOut => {
+                    ts_msg!(self, "task aborted: time limit reached");
+                }
             },
             EventMsg::ContextCompacted(_) => {
                 ts_msg!(self, "context compacted");
             }
+            EventMsg::ContextRepaired(ContextRepairedEvent { dropped, compacted }) => {
+                let action = if compacted {
+                    "compacted the conversation"
+                } else {
+                    "trimmed the request"
+                };
+                let dropped = dropped
+                    .iter()
+                    .map(|dropped| {
+                        format!(
+                            "{} item(s) of {}, ~{} tokens",
+                            dropped.items,
+                            format_context_source(dropped.source),
+                            dropped.tokens
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                ts_msg!(
+                    self,
+                    "{} {action} and retried ({dropped})",
+                    "context window exceeded:".style(self.yellow)
+                );
+            }
             EventMsg::ShutdownComplete => return CodexStatus::Shutdown,
             EventMsg::WebSearchBegin(_)
             | EventMsg::ExecApprovalRequest(_)
@@ -580,7 +776,11 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::GetHistoryEntryResponse(_)
             | EventMsg::McpListToolsResponse(_)
             | EventMsg::ListCustomPromptsResponse(_)
+            | EventMsg::ConfigOverrideApplied(_)
+            | EventMsg::ModelSettingsChanged(_)
             | EventMsg::ListSkillsResponse(_)
+            | EventMsg::ListHooksResponse(_)
+            | EventMsg::UserCommand(_)
             | EventMsg::RawResponseItem(_)
             | EventMsg::UserMessage(_)
             | EventMsg::EnteredReviewMode(_)
@@ -596,7 +796,11 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::SkillsUpdateAvailable
             | EventMsg::UndoCompleted(_)
             | EventMsg::UndoStarted(_)
-            | EventMsg::ThreadRolledBack(_) => {}
+            | EventMsg::ThreadRolledBack(_)
+            | EventMsg::WorkspaceSnapshot(_)
+            | EventMsg::ContextSources(_)
+            | EventMsg::OutputBudget(_)
+            | EventMsg::VariablesUpdated(_) => {}
         }
         CodexStatus::Running
     }
@@ -642,6 +846,23 @@ fn format_file_change(change: &FileChange) -> &'static str {
     }
 }
 
+fn format_context_source(source: ContextSource) -> &'static str {
+    match source {
+        ContextSource::ProjectDocs => "project docs",
+        ContextSource::ToolOutputs => "tool outputs",
+        ContextSource::Pinned => "pinned items",
+        ContextSource::History => "history",
+    }
+}
+
+fn format_cancelled_operation(operation: &CancelledOperation) -> String {
+    match operation {
+        CancelledOperation::ModelStream => "model response".to_string(),
+        CancelledOperation::ToolCall { tool_name, .. } => tool_name.clone(),
+        CancelledOperation::McpToolCall { server, tool, .. } => format!("{server}.{tool}"),
+    }
+}
+
 fn format_mcp_invocation(invocation: &McpInvocation) -> String {
     // Build fully-qualified tool name: server.tool
     let fq_tool_name = format!("{}.{}", invocation.server, invocation.tool);
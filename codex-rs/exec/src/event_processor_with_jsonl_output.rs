@@ -538,6 +538,7 @@ impl EventProcessor for EventProcessorWithJsonOutput {
 
         if let protocol::EventMsg::TurnComplete(protocol::TurnCompleteEvent {
             last_agent_message,
+            ..
         }) = msg
         {
             if let Some(output_file) = self.last_message_path.as_deref() {
@@ -1,18 +1,25 @@
 // - In the default output mode, it is paramount that the only thing written to
 //   stdout is the final message (if any).
-// - In --json mode, stdout must be valid JSONL, one event per line.
+// - In --json and --output-format jsonl modes, stdout must be valid JSONL, one
+//   event per line.
 // For both modes, any other output must be written to stderr.
 #![deny(clippy::print_stdout)]
 
+pub mod batch;
+mod best_of;
 mod cli;
+pub mod eval;
 mod event_processor;
+pub mod event_processor_with_event_stream;
 mod event_processor_with_human_output;
 pub mod event_processor_with_jsonl_output;
 pub mod exec_events;
 
 pub use cli::Cli;
 pub use cli::Command;
+pub use cli::EvalCli;
 pub use cli::ReviewArgs;
+pub use cli::RunCli;
 use codex_common::oss::ensure_oss_provider_ready;
 use codex_common::oss::get_default_model_for_oss_provider;
 use codex_core::AuthManager;
@@ -31,18 +38,23 @@ use codex_core::protocol::AskForApproval;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
+use codex_core::protocol::ReviewOutputEvent;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::ReviewTarget;
 use codex_core::protocol::SessionSource;
+use codex_core::protocol::TurnAbortReason;
+use codex_core::protocol::WatchdogAction;
 use codex_protocol::approvals::ElicitationAction;
 use codex_protocol::config_types::SandboxMode;
 use codex_protocol::user_input::UserInput;
 use codex_utils_absolute_path::AbsolutePathBuf;
+use event_processor_with_event_stream::EventProcessorWithEventStream;
 use event_processor_with_human_output::EventProcessorWithHumanOutput;
 use event_processor_with_jsonl_output::EventProcessorWithJsonOutput;
 use serde_json::Value;
 use std::io::IsTerminal;
 use std::io::Read;
+use std::path::Path;
 use std::path::PathBuf;
 use supports_color::Stream;
 use tracing::debug;
@@ -52,6 +64,7 @@ use tracing_subscriber::EnvFilter;
 use tracing_subscriber::prelude::*;
 
 use crate::cli::Command as ExecCommand;
+use crate::cli::OutputFormat;
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
 use codex_core::default_client::set_default_originator;
@@ -87,9 +100,14 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         color,
         last_message_file,
         json: json_mode,
+        output_format,
         sandbox_mode: sandbox_mode_cli_arg,
         prompt,
         output_schema: output_schema_path,
+        verify,
+        best_of,
+        candidates,
+        keep_candidates,
         config_overrides,
     } = cli;
 
@@ -213,6 +231,8 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         show_raw_agent_reasoning: oss.then_some(true),
         tools_web_search_request: None,
         additional_writable_roots: add_dir,
+        session_labels: Vec::new(),
+        verify_command: verify,
     };
 
     let config =
@@ -245,9 +265,14 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         .with(otel_logger_layer)
         .try_init();
 
-    let mut event_processor: Box<dyn EventProcessor> = match json_mode {
-        true => Box::new(EventProcessorWithJsonOutput::new(last_message_file.clone())),
-        _ => Box::new(EventProcessorWithHumanOutput::create_with_ansi(
+    codex_core::crash_report::install(&config, "codex-exec");
+
+    let mut event_processor: Box<dyn EventProcessor> = match (json_mode, output_format) {
+        (true, _) => Box::new(EventProcessorWithJsonOutput::new(last_message_file.clone())),
+        (false, OutputFormat::Jsonl) => Box::new(EventProcessorWithEventStream::new(
+            last_message_file.clone(),
+        )),
+        (false, OutputFormat::Human) => Box::new(EventProcessorWithHumanOutput::create_with_ansi(
             stdout_with_ansi,
             &config,
             last_message_file.clone(),
@@ -297,6 +322,31 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         .get_model(&config.model, &config)
         .await;
 
+    if best_of.is_some() || !candidates.is_empty() {
+        if command.is_some() {
+            anyhow::bail!("--best-of only runs a new session");
+        }
+        let mut items: Vec<UserInput> = images
+            .into_iter()
+            .map(|path| UserInput::LocalImage { path })
+            .collect();
+        items.push(UserInput::Text {
+            text: resolve_prompt(prompt),
+        });
+        let options = best_of::BestOfOptions {
+            count: best_of,
+            candidates,
+            keep_candidates,
+            json: json_mode,
+            output_format,
+            last_message_file,
+        };
+        if !best_of::run(&thread_manager, &config, items, options).await? {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     // Handle resume subcommand by resolving a rollout path and using explicit resume API.
     let NewThread {
         thread_id: _,
@@ -315,6 +365,10 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     } else {
         thread_manager.start_thread(config.clone()).await?
     };
+    let findings_json_path = match &command {
+        Some(ExecCommand::Review(args)) => args.findings_json.clone(),
+        _ => None,
+    };
     let (initial_operation, prompt_summary) = match (command, prompt, images) {
         (Some(ExecCommand::Review(review_cli)), _, _) => {
             let review_request = build_review_request(review_cli)?;
@@ -461,7 +515,39 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         if matches!(event.msg, EventMsg::Error(_)) {
             error_seen = true;
         }
-        let shutdown: CodexStatus = event_processor.process_event(event);
+        // A watchdog that aborted the turn leaves nothing to wait for, and the
+        // run did not finish its job.
+        let timed_out = matches!(
+            &event.msg,
+            EventMsg::TurnAborted(ev) if ev.reason == TurnAbortReason::TimedOut
+        );
+        if timed_out
+            || matches!(
+                &event.msg,
+                EventMsg::WatchdogFired(ev) if ev.action != WatchdogAction::Notify
+            )
+        {
+            error_seen = true;
+        }
+        // A turn that ends with its verification command still failing did
+        // not get the job done.
+        if let EventMsg::TurnComplete(ev) = &event.msg
+            && ev
+                .verification
+                .as_ref()
+                .is_some_and(|verification| !verification.passed)
+        {
+            error_seen = true;
+        }
+        if let EventMsg::ExitedReviewMode(ev) = &event.msg
+            && let Some(path) = findings_json_path.as_deref()
+        {
+            write_review_findings(ev.review_output.as_ref(), path);
+        }
+        let mut shutdown: CodexStatus = event_processor.process_event(event);
+        if timed_out && matches!(shutdown, CodexStatus::Running) {
+            shutdown = CodexStatus::InitiateShutdown;
+        }
         match shutdown {
             CodexStatus::Running => continue,
             CodexStatus::InitiateShutdown => {
@@ -510,6 +596,24 @@ async fn resolve_resume_path(
     }
 }
 
+/// Writes the review's findings to `path`. An interrupted review has no
+/// findings and writes `null`.
+fn write_review_findings(review_output: Option<&ReviewOutputEvent>, path: &Path) {
+    let json = match serde_json::to_string(&review_output) {
+        Ok(json) => json,
+        Err(err) => {
+            eprintln!("Failed to serialize review findings: {err}");
+            return;
+        }
+    };
+    if let Err(err) = std::fs::write(path, json) {
+        eprintln!(
+            "Failed to write review findings to {}: {err}",
+            path.display()
+        );
+    }
+}
+
 fn load_output_schema(path: Option<PathBuf>) -> Option<Value> {
     let path = path?;
 
@@ -608,6 +712,7 @@ mod tests {
             commit: None,
             commit_title: None,
             prompt: None,
+            findings_json: None,
         })
         .expect("builds uncommitted review request");
 
@@ -627,6 +732,7 @@ mod tests {
             commit: Some("123456789".to_string()),
             commit_title: Some("Add review command".to_string()),
             prompt: None,
+            findings_json: None,
         })
         .expect("builds commit review request");
 
@@ -649,6 +755,7 @@ mod tests {
             commit: None,
             commit_title: None,
             prompt: Some("  custom review instructions  ".to_string()),
+            findings_json: None,
         })
         .expect("builds custom review request");
 
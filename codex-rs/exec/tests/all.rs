@@ -2,4 +2,5 @@
 // The submodules live in `tests/suite/`.
 mod suite;
 
+mod event_processor_with_event_stream;
 mod event_processor_with_json_output;
@@ -0,0 +1,56 @@
+use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::WarningEvent;
+use codex_exec::event_processor_with_event_stream::EVENT_STREAM_SCHEMA_VERSION;
+use codex_exec::event_processor_with_event_stream::EventProcessorWithEventStream;
+use codex_exec::event_processor_with_event_stream::EventStreamLine;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+#[test]
+fn event_stream_lines_wrap_events_with_schema_version() {
+    let line = EventProcessorWithEventStream::format_event(Event {
+        id: "1".to_string(),
+        msg: EventMsg::Warning(WarningEvent {
+            message: "low disk space".to_string(),
+        }),
+    })
+    .expect("serializable event");
+
+    assert!(!line.contains('\n'));
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&line).expect("valid json"),
+        json!({
+            "schema_version": EVENT_STREAM_SCHEMA_VERSION,
+            "id": "1",
+            "msg": { "type": "warning", "message": "low disk space" },
+        })
+    );
+
+    let parsed: EventStreamLine = serde_json::from_str(&line).expect("round trip");
+    assert!(matches!(parsed.msg, EventMsg::Warning(_)));
+}
+
+#[test]
+fn candidate_events_name_their_candidate() {
+    let line = EventProcessorWithEventStream::format_candidate_event(
+        2,
+        Event {
+            id: "1".to_string(),
+            msg: EventMsg::Warning(WarningEvent {
+                message: "low disk space".to_string(),
+            }),
+        },
+    )
+    .expect("serializable event");
+
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&line).expect("valid json"),
+        json!({
+            "schema_version": EVENT_STREAM_SCHEMA_VERSION,
+            "id": "1",
+            "candidate": 2,
+            "msg": { "type": "warning", "message": "low disk space" },
+        })
+    );
+}
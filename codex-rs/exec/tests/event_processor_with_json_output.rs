@@ -219,6 +219,10 @@ fn plan_update_emits_todo_list_started_updated_and_completed() {
         "p3",
         EventMsg::TurnComplete(codex_core::protocol::TurnCompleteEvent {
             last_agent_message: None,
+            timing: None,
+            unified_diff: None,
+            verification: None,
+            context_dropped: Vec::new(),
         }),
     );
     let out_complete = ep.collect_thread_events(&complete);
@@ -463,6 +467,10 @@ fn plan_update_after_complete_starts_new_todo_list_with_new_id() {
         "t2",
         EventMsg::TurnComplete(codex_core::protocol::TurnCompleteEvent {
             last_agent_message: None,
+            timing: None,
+            unified_diff: None,
+            verification: None,
+            context_dropped: Vec::new(),
         }),
     );
     let _ = ep.collect_thread_events(&complete);
@@ -615,6 +623,10 @@ fn error_followed_by_task_complete_produces_turn_failed() {
         "e2",
         EventMsg::TurnComplete(codex_core::protocol::TurnCompleteEvent {
             last_agent_message: None,
+            timing: None,
+            unified_diff: None,
+            verification: None,
+            context_dropped: Vec::new(),
         }),
     );
     assert_eq!(
@@ -1062,6 +1074,10 @@ fn task_complete_produces_turn_completed_with_usage() {
         "e2",
         EventMsg::TurnComplete(codex_core::protocol::TurnCompleteEvent {
             last_agent_message: Some("done".to_string()),
+            timing: None,
+            unified_diff: None,
+            verification: None,
+            context_dropped: Vec::new(),
         }),
     );
     let out = ep.collect_thread_events(&complete_event);
@@ -0,0 +1,159 @@
+#ifndef CODEX_FFI_H
+#define CODEX_FFI_H
+
+/* Generated by cbindgen from codex-rs/ffi. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Collects settings and hooks for a [`CodexClient`].
+typedef struct CodexClientBuilder CodexClientBuilder;
+
+// Starts conversations. Owns the threads that run them.
+typedef struct CodexClient CodexClient;
+
+// A running conversation.
+typedef struct CodexConversation CodexConversation;
+
+// Called with each event a hook subscribes to, serialized as JSON. Return 0
+// on success; any other value is recorded as a failed hook run. Callbacks run
+// on a worker thread, possibly several at once, and must not call back into
+// this library.
+typedef int32_t (*CodexHookCallback)(const char *event_json, void *user_data);
+
+// Describes the last failed call made on this thread, or returns NULL if the
+// last call succeeded. The string stays valid until the next call into this
+// library on the same thread and must not be freed.
+const char *codex_last_error(void);
+
+// Creates a builder from `options_json`, a JSON object with any of
+// `codex_home`, `cwd`, `model`, `model_provider`, `profile`,
+// `approval_policy`, `sandbox_mode` and `developer_instructions`. NULL
+// keeps every setting from `config.toml`.
+//
+// # Safety
+//
+// `options_json` must be NULL or a valid NUL-terminated string.
+CodexClientBuilder *codex_client_builder_new(const char *options_json);
+
+// Runs `callback` for the events named in `events_json` (a JSON array of
+// event names such as `["task_complete"]`; NULL means every event) in every
+// conversation the client starts.
+//
+// # Safety
+//
+// `builder` must come from [`codex_client_builder_new`] and not have been
+// built or freed. `name` must be a valid NUL-terminated string and
+// `events_json` NULL or one. `callback` must be safe to call from any thread
+// with `user_data` until the client and its conversations are freed.
+int32_t codex_client_builder_add_hook(CodexClientBuilder *builder,
+                                      const char *name,
+                                      const char *events_json,
+                                      CodexHookCallback callback,
+                                      void *user_data);
+
+// Loads the configuration and creates a client. Consumes `builder`, even on
+// failure.
+//
+// # Safety
+//
+// `builder` must come from [`codex_client_builder_new`] and not have been
+// built or freed.
+CodexClient *codex_client_builder_build(CodexClientBuilder *builder);
+
+// Frees a builder that was not built.
+//
+// # Safety
+//
+// `builder` must be NULL or come from [`codex_client_builder_new`] and not
+// have been built or freed.
+void codex_client_builder_free(CodexClientBuilder *builder);
+
+// Frees a client. Conversations it started keep running until freed.
+//
+// # Safety
+//
+// `client` must be NULL or come from [`codex_client_builder_build`] and not
+// have been freed. Must not be called from a hook callback.
+void codex_client_free(CodexClient *client);
+
+// Starts a new conversation.
+//
+// # Safety
+//
+// `client` must come from [`codex_client_builder_build`] and not have been
+// freed.
+CodexConversation *codex_conversation_start(const CodexClient *client);
+
+// Resumes the conversation recorded at `rollout_path`.
+//
+// # Safety
+//
+// `client` must come from [`codex_client_builder_build`] and not have been
+// freed. `rollout_path` must be a valid NUL-terminated string.
+CodexConversation *codex_conversation_resume(const CodexClient *client, const char *rollout_path);
+
+// Sends `text` as a user message. If a turn is running, the message starts
+// the next turn once it ends.
+//
+// # Safety
+//
+// `conversation` must come from [`codex_conversation_start`] or
+// [`codex_conversation_resume`] and not have been freed. `text` must be a
+// valid NUL-terminated string.
+int32_t codex_conversation_send(const CodexConversation *conversation, const char *text);
+
+// Asks the agent to stop the running turn, if any. The turn's remaining
+// events, ending with `turn_aborted`, are still delivered.
+//
+// # Safety
+//
+// `conversation` must come from [`codex_conversation_start`] or
+// [`codex_conversation_resume`] and not have been freed.
+int32_t codex_conversation_interrupt(const CodexConversation *conversation);
+
+// Answers `request_json`, an `exec_approval_request` or
+// `apply_patch_approval_request` event as returned by
+// [`codex_conversation_next_event`], with `decision_json`, a decision such
+// as `"approved"`, `"approved_for_session"`, `"denied"` or `"abort"`. Under
+// an approval policy that asks, the turn waits for the answer.
+//
+// # Safety
+//
+// `conversation` must come from [`codex_conversation_start`] or
+// [`codex_conversation_resume`] and not have been freed. `request_json` and
+// `decision_json` must be valid NUL-terminated strings.
+int32_t codex_conversation_respond(const CodexConversation *conversation,
+                                   const char *request_json,
+                                   const char *decision_json);
+
+// Waits up to `timeout_ms` for the next event and returns it as JSON, to be
+// freed with [`codex_string_free`]. Returns NULL on timeout, with
+// [`codex_last_error`] also NULL, or once the conversation has shut down.
+//
+// # Safety
+//
+// `conversation` must come from [`codex_conversation_start`] or
+// [`codex_conversation_resume`] and not have been freed.
+char *codex_conversation_next_event(const CodexConversation *conversation, uint64_t timeout_ms);
+
+// Shuts the conversation down, interrupting any running turn, and frees it.
+//
+// # Safety
+//
+// `conversation` must be NULL or come from [`codex_conversation_start`] or
+// [`codex_conversation_resume`] and not have been freed. Must not be called
+// from a hook callback.
+void codex_conversation_free(CodexConversation *conversation);
+
+// Frees a string returned by this library.
+//
+// # Safety
+//
+// `value` must be NULL or a string returned by this library that has not
+// been freed.
+void codex_string_free(char *value);
+
+#endif  /* CODEX_FFI_H */
@@ -0,0 +1,107 @@
+use std::collections::VecDeque;
+use std::sync::mpsc;
+
+use codex_protocol::protocol::ErrorEvent;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ReviewDecision;
+use codex_sdk::Conversation;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+pub(crate) enum Command {
+    Send(String),
+    Interrupt,
+    /// Answers an approval request of the running turn.
+    Respond(Box<Event>, ReviewDecision),
+}
+
+/// Runs `conversation` until `commands` closes, forwarding every event to
+/// `events` as JSON. Messages sent while a turn is running start their own
+/// turn once it ends.
+pub(crate) async fn drive(
+    mut conversation: Conversation,
+    mut commands: UnboundedReceiver<Command>,
+    events: mpsc::Sender<String>,
+) {
+    let mut pending = VecDeque::new();
+    let mut closed = false;
+    while !closed {
+        let text = match pending.pop_front() {
+            Some(text) => text,
+            None => match commands.recv().await {
+                Some(Command::Send(text)) => text,
+                Some(Command::Interrupt) => continue,
+                Some(Command::Respond(..)) => {
+                    emit_error(&events, "no turn is running".to_string());
+                    continue;
+                }
+                None => break,
+            },
+        };
+
+        let mut turn = match conversation.send(text).await {
+            Ok(turn) => turn,
+            Err(err) => {
+                emit_error(&events, err.to_string());
+                continue;
+            }
+        };
+        loop {
+            tokio::select! {
+                event = turn.next() => match event {
+                    Some(Ok(event)) => emit(&events, &event),
+                    Some(Err(err)) => emit_error(&events, err.to_string()),
+                    None => break,
+                },
+                command = commands.recv(), if !closed => {
+                    let interrupt = match command {
+                        Some(Command::Send(text)) => {
+                            pending.push_back(text);
+                            false
+                        }
+                        Some(Command::Interrupt) => true,
+                        Some(Command::Respond(request, decision)) => {
+                            if let Err(err) = turn.respond(&request, decision).await {
+                                emit_error(&events, err.to_string());
+                            }
+                            false
+                        }
+                        None => {
+                            closed = true;
+                            true
+                        }
+                    };
+                    if interrupt && let Err(err) = turn.interrupt().await {
+                        emit_error(&events, err.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    if let Err(err) = conversation.shutdown().await {
+        emit_error(&events, err.to_string());
+    }
+}
+
+fn emit(events: &mpsc::Sender<String>, event: &Event) {
+    match serde_json::to_string(event) {
+        Ok(json) => {
+            let _ = events.send(json);
+        }
+        Err(err) => emit_error(events, format!("failed to serialize event: {err}")),
+    }
+}
+
+fn emit_error(events: &mpsc::Sender<String>, message: String) {
+    let event = Event {
+        id: String::new(),
+        msg: EventMsg::Error(ErrorEvent {
+            message,
+            codex_error_info: None,
+        }),
+    };
+    if let Ok(json) = serde_json::to_string(&event) {
+        let _ = events.send(json);
+    }
+}
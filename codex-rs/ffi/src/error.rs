@@ -0,0 +1,73 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::ffi::c_char;
+use std::panic::AssertUnwindSafe;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+pub(crate) fn set_last_error(message: impl Into<String>) {
+    let message = message.into().replace('\0', " ");
+    let message = CString::new(message).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+pub(crate) fn clear_last_error() {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+}
+
+/// Runs the body of an exported function, returning `on_panic` instead of
+/// unwinding into the host, which is undefined behavior.
+pub(crate) fn catch_panic<T>(on_panic: T, body: impl FnOnce() -> T) -> T {
+    match std::panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(value) => value,
+        Err(payload) => {
+            set_last_error(format!("internal error: {}", panic_message(&*payload)));
+            on_panic
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "panic"
+    }
+}
+
+/// Describes the last failed call made on this thread, or returns NULL if the
+/// last call succeeded. The string stays valid until the next call into this
+/// library on the same thread and must not be freed.
+#[unsafe(no_mangle)]
+pub extern "C" fn codex_last_error() -> *const c_char {
+    catch_panic(std::ptr::null(), || {
+        LAST_ERROR.with(|last| {
+            last.borrow()
+                .as_ref()
+                .map_or(std::ptr::null(), |message| message.as_ptr())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::ffi::CStr;
+
+    #[test]
+    fn panics_become_errors() {
+        let result = catch_panic(-1, || panic!("boom"));
+
+        assert_eq!(result, -1);
+        // SAFETY: a panic always records an error, so the pointer is a valid
+        // C string.
+        let error = unsafe { CStr::from_ptr(codex_last_error()) };
+        assert_eq!(error.to_string_lossy(), "internal error: boom");
+    }
+}
@@ -0,0 +1,69 @@
+use std::ffi::CString;
+use std::ffi::c_char;
+use std::ffi::c_void;
+
+use async_trait::async_trait;
+use codex_protocol::protocol::Event;
+use codex_sdk::HookHandler;
+
+/// Called with each event a hook subscribes to, serialized as JSON. Return 0
+/// on success; any other value is recorded as a failed hook run. Callbacks run
+/// on a worker thread, possibly several at once, and must not call back into
+/// this library.
+pub type CodexHookCallback =
+    unsafe extern "C" fn(event_json: *const c_char, user_data: *mut c_void) -> i32;
+
+/// Opaque pointer handed back to the host with every callback.
+#[derive(Clone, Copy)]
+struct UserData(*mut c_void);
+
+// SAFETY: the host promises, when registering the hook, that `user_data` may
+// be used from any thread.
+unsafe impl Send for UserData {}
+// SAFETY: as above.
+unsafe impl Sync for UserData {}
+
+impl UserData {
+    fn as_ptr(self) -> *mut c_void {
+        self.0
+    }
+}
+
+pub(crate) struct CallbackHook {
+    callback: CodexHookCallback,
+    user_data: UserData,
+}
+
+impl CallbackHook {
+    pub(crate) fn new(callback: CodexHookCallback, user_data: *mut c_void) -> Self {
+        Self {
+            callback,
+            user_data: UserData(user_data),
+        }
+    }
+}
+
+#[async_trait]
+impl HookHandler for CallbackHook {
+    async fn handle(&self, event: &Event) -> Result<(), String> {
+        let json = serde_json::to_string(event)
+            .map_err(|err| format!("failed to serialize event: {err}"))?;
+        let json = CString::new(json).map_err(|err| err.to_string())?;
+        let callback = self.callback;
+        let user_data = self.user_data;
+        // Host callbacks may block, so keep them off the async workers.
+        let status = tokio::task::spawn_blocking(move || {
+            // SAFETY: `json` is a valid NUL-terminated string that outlives
+            // the call, and the host vouched for `callback` and `user_data`
+            // when registering the hook.
+            unsafe { callback(json.as_ptr(), user_data.as_ptr()) }
+        })
+        .await
+        .map_err(|err| format!("hook callback panicked: {err}"))?;
+        if status == 0 {
+            Ok(())
+        } else {
+            Err(format!("hook callback returned {status}"))
+        }
+    }
+}
@@ -0,0 +1,594 @@
+//! C ABI for embedding Codex in non-Rust hosts.
+//!
+//! The library wraps [`codex_sdk`] behind opaque handles. A host builds a
+//! client, starts conversations, sends messages and polls for events, which
+//! are returned as JSON strings in the shape command hooks receive on stdin.
+//! `include/codex_ffi.h` is generated from this crate with
+//! `cbindgen --config cbindgen.toml --output include/codex_ffi.h`.
+//!
+//! Functions returning `int32_t` return 0 on success and -1 on failure;
+//! functions returning a pointer return NULL on failure. After a failure,
+//! [`codex_last_error`] describes what went wrong. A panic inside the
+//! library is caught at the boundary and reported the same way.
+
+mod conversation;
+mod error;
+mod hook;
+
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::ffi::c_char;
+use std::ffi::c_void;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use codex_sdk::Client;
+use codex_sdk::ClientBuilder;
+use codex_sdk::Conversation;
+use codex_sdk::Hook;
+use codex_sdk::protocol::AskForApproval;
+use codex_sdk::protocol::Event;
+use codex_sdk::protocol::ReviewDecision;
+use codex_sdk::protocol::SandboxMode;
+use serde::Deserialize;
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
+
+use crate::conversation::Command;
+use crate::error::catch_panic;
+use crate::error::clear_last_error;
+use crate::error::set_last_error;
+use crate::hook::CallbackHook;
+
+pub use crate::error::codex_last_error;
+pub use crate::hook::CodexHookCallback;
+
+const OK: i32 = 0;
+const ERR: i32 = -1;
+
+/// Settings accepted by [`codex_client_builder_new`] as a JSON object. Omitted
+/// settings come from `config.toml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ClientOptions {
+    codex_home: Option<PathBuf>,
+    cwd: Option<PathBuf>,
+    model: Option<String>,
+    model_provider: Option<String>,
+    profile: Option<String>,
+    approval_policy: Option<AskForApproval>,
+    sandbox_mode: Option<SandboxMode>,
+    developer_instructions: Option<String>,
+}
+
+impl ClientOptions {
+    fn into_builder(self) -> ClientBuilder {
+        let mut builder = Client::builder();
+        if let Some(codex_home) = self.codex_home {
+            builder = builder.codex_home(codex_home);
+        }
+        if let Some(cwd) = self.cwd {
+            builder = builder.cwd(cwd);
+        }
+        if let Some(model) = self.model {
+            builder = builder.model(model);
+        }
+        if let Some(model_provider) = self.model_provider {
+            builder = builder.model_provider(model_provider);
+        }
+        if let Some(profile) = self.profile {
+            builder = builder.profile(profile);
+        }
+        if let Some(approval_policy) = self.approval_policy {
+            builder = builder.approval_policy(approval_policy);
+        }
+        if let Some(sandbox_mode) = self.sandbox_mode {
+            builder = builder.sandbox_mode(sandbox_mode);
+        }
+        if let Some(instructions) = self.developer_instructions {
+            builder = builder.developer_instructions(instructions);
+        }
+        builder
+    }
+}
+
+/// Collects settings and hooks for a [`CodexClient`].
+pub struct CodexClientBuilder {
+    builder: ClientBuilder,
+}
+
+/// Starts conversations. Owns the threads that run them.
+pub struct CodexClient {
+    // Declared first so the client is dropped before the runtime.
+    client: Client,
+    runtime: Arc<Runtime>,
+}
+
+/// A running conversation.
+pub struct CodexConversation {
+    runtime: Arc<Runtime>,
+    commands: Option<UnboundedSender<Command>>,
+    events: Mutex<mpsc::Receiver<String>>,
+    driver: Option<JoinHandle<()>>,
+}
+
+/// Creates a builder from `options_json`, a JSON object with any of
+/// `codex_home`, `cwd`, `model`, `model_provider`, `profile`,
+/// `approval_policy`, `sandbox_mode` and `developer_instructions`. NULL
+/// keeps every setting from `config.toml`.
+///
+/// # Safety
+///
+/// `options_json` must be NULL or a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn codex_client_builder_new(
+    options_json: *const c_char,
+) -> *mut CodexClientBuilder {
+    catch_panic(std::ptr::null_mut(), || {
+        clear_last_error();
+        // SAFETY: guaranteed by the caller.
+        let options = match unsafe { optional_str(options_json) } {
+            Ok(Some(json)) => match serde_json::from_str::<ClientOptions>(json) {
+                Ok(options) => options,
+                Err(err) => {
+                    set_last_error(format!("invalid client options: {err}"));
+                    return std::ptr::null_mut();
+                }
+            },
+            Ok(None) => ClientOptions::default(),
+            Err(err) => {
+                set_last_error(err);
+                return std::ptr::null_mut();
+            }
+        };
+        Box::into_raw(Box::new(CodexClientBuilder {
+            builder: options.into_builder(),
+        }))
+    })
+}
+
+/// Runs `callback` for the events named in `events_json` (a JSON array of
+/// event names such as `["task_complete"]`; NULL means every event) in every
+/// conversation the client starts.
+///
+/// # Safety
+///
+/// `builder` must come from [`codex_client_builder_new`] and not have been
+/// built or freed. `name` must be a valid NUL-terminated string and
+/// `events_json` NULL or one. `callback` must be safe to call from any thread
+/// with `user_data` until the client and its conversations are freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn codex_client_builder_add_hook(
+    builder: *mut CodexClientBuilder,
+    name: *const c_char,
+    events_json: *const c_char,
+    callback: CodexHookCallback,
+    user_data: *mut c_void,
+) -> i32 {
+    catch_panic(ERR, || {
+        clear_last_error();
+        // SAFETY: guaranteed by the caller.
+        let Some(builder) = (unsafe { builder.as_mut() }) else {
+            set_last_error("builder is NULL");
+            return ERR;
+        };
+        // SAFETY: guaranteed by the caller.
+        let name = match unsafe { optional_str(name) } {
+            Ok(Some(name)) => name.to_string(),
+            Ok(None) => {
+                set_last_error("hook name is NULL");
+                return ERR;
+            }
+            Err(err) => {
+                set_last_error(err);
+                return ERR;
+            }
+        };
+        // SAFETY: guaranteed by the caller.
+        let events = match unsafe { optional_str(events_json) } {
+            Ok(Some(json)) => match serde_json::from_str::<Vec<String>>(json) {
+                Ok(events) => Some(events),
+                Err(err) => {
+                    set_last_error(format!("invalid hook events: {err}"));
+                    return ERR;
+                }
+            },
+            Ok(None) => None,
+            Err(err) => {
+                set_last_error(err);
+                return ERR;
+            }
+        };
+
+        let mut hook = Hook::new(name, CallbackHook::new(callback, user_data));
+        if let Some(events) = events {
+            hook = hook.on(events);
+        }
+        let inner = std::mem::take(&mut builder.builder);
+        builder.builder = inner.hook(hook);
+        OK
+    })
+}
+
+/// Loads the configuration and creates a client. Consumes `builder`, even on
+/// failure.
+///
+/// # Safety
+///
+/// `builder` must come from [`codex_client_builder_new`] and not have been
+/// built or freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn codex_client_builder_build(
+    builder: *mut CodexClientBuilder,
+) -> *mut CodexClient {
+    catch_panic(std::ptr::null_mut(), || {
+        clear_last_error();
+        if builder.is_null() {
+            set_last_error("builder is NULL");
+            return std::ptr::null_mut();
+        }
+        // SAFETY: guaranteed by the caller; ownership returns to Rust here.
+        let builder = unsafe { Box::from_raw(builder) };
+        let runtime = match tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(runtime) => Arc::new(runtime),
+            Err(err) => {
+                set_last_error(format!("failed to start runtime: {err}"));
+                return std::ptr::null_mut();
+            }
+        };
+        match runtime.block_on(builder.builder.build()) {
+            Ok(client) => Box::into_raw(Box::new(CodexClient { runtime, client })),
+            Err(err) => {
+                set_last_error(err.to_string());
+                std::ptr::null_mut()
+            }
+        }
+    })
+}
+
+/// Frees a builder that was not built.
+///
+/// # Safety
+///
+/// `builder` must be NULL or come from [`codex_client_builder_new`] and not
+/// have been built or freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn codex_client_builder_free(builder: *mut CodexClientBuilder) {
+    catch_panic((), || {
+        if !builder.is_null() {
+            // SAFETY: guaranteed by the caller.
+            drop(unsafe { Box::from_raw(builder) });
+        }
+    })
+}
+
+/// Frees a client. Conversations it started keep running until freed.
+///
+/// # Safety
+///
+/// `client` must be NULL or come from [`codex_client_builder_build`] and not
+/// have been freed. Must not be called from a hook callback.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn codex_client_free(client: *mut CodexClient) {
+    catch_panic((), || {
+        if !client.is_null() {
+            // SAFETY: guaranteed by the caller.
+            drop(unsafe { Box::from_raw(client) });
+        }
+    })
+}
+
+/// Starts a new conversation.
+///
+/// # Safety
+///
+/// `client` must come from [`codex_client_builder_build`] and not have been
+/// freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn codex_conversation_start(
+    client: *const CodexClient,
+) -> *mut CodexConversation {
+    catch_panic(std::ptr::null_mut(), || {
+        clear_last_error();
+        // SAFETY: guaranteed by the caller.
+        let Some(client) = (unsafe { client.as_ref() }) else {
+            set_last_error("client is NULL");
+            return std::ptr::null_mut();
+        };
+        let result = client.runtime.block_on(client.client.start_conversation());
+        spawn_conversation(client, result)
+    })
+}
+
+/// Resumes the conversation recorded at `rollout_path`.
+///
+/// # Safety
+///
+/// `client` must come from [`codex_client_builder_build`] and not have been
+/// freed. `rollout_path` must be a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn codex_conversation_resume(
+    client: *const CodexClient,
+    rollout_path: *const c_char,
+) -> *mut CodexConversation {
+    catch_panic(std::ptr::null_mut(), || {
+        clear_last_error();
+        // SAFETY: guaranteed by the caller.
+        let Some(client) = (unsafe { client.as_ref() }) else {
+            set_last_error("client is NULL");
+            return std::ptr::null_mut();
+        };
+        // SAFETY: guaranteed by the caller.
+        let rollout_path = match unsafe { optional_str(rollout_path) } {
+            Ok(Some(path)) => PathBuf::from(path),
+            Ok(None) => {
+                set_last_error("rollout path is NULL");
+                return std::ptr::null_mut();
+            }
+            Err(err) => {
+                set_last_error(err);
+                return std::ptr::null_mut();
+            }
+        };
+        let result = client
+            .runtime
+            .block_on(client.client.resume_conversation(rollout_path));
+        spawn_conversation(client, result)
+    })
+}
+
+fn spawn_conversation(
+    client: &CodexClient,
+    result: codex_sdk::Result<Conversation>,
+) -> *mut CodexConversation {
+    let conversation = match result {
+        Ok(conversation) => conversation,
+        Err(err) => {
+            set_last_error(err.to_string());
+            return std::ptr::null_mut();
+        }
+    };
+    let (commands_tx, commands_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (events_tx, events_rx) = mpsc::channel();
+    let driver = client
+        .runtime
+        .spawn(conversation::drive(conversation, commands_rx, events_tx));
+    Box::into_raw(Box::new(CodexConversation {
+        runtime: Arc::clone(&client.runtime),
+        commands: Some(commands_tx),
+        events: Mutex::new(events_rx),
+        driver: Some(driver),
+    }))
+}
+
+/// Sends `text` as a user message. If a turn is running, the message starts
+/// the next turn once it ends.
+///
+/// # Safety
+///
+/// `conversation` must come from [`codex_conversation_start`] or
+/// [`codex_conversation_resume`] and not have been freed. `text` must be a
+/// valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn codex_conversation_send(
+    conversation: *const CodexConversation,
+    text: *const c_char,
+) -> i32 {
+    catch_panic(ERR, || {
+        clear_last_error();
+        // SAFETY: guaranteed by the caller.
+        let text = match unsafe { optional_str(text) } {
+            Ok(Some(text)) => text.to_string(),
+            Ok(None) => {
+                set_last_error("text is NULL");
+                return ERR;
+            }
+            Err(err) => {
+                set_last_error(err);
+                return ERR;
+            }
+        };
+        // SAFETY: guaranteed by the caller.
+        unsafe { send_command(conversation, Command::Send(text)) }
+    })
+}
+
+/// Asks the agent to stop the running turn, if any. The turn's remaining
+/// events, ending with `turn_aborted`, are still delivered.
+///
+/// # Safety
+///
+/// `conversation` must come from [`codex_conversation_start`] or
+/// [`codex_conversation_resume`] and not have been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn codex_conversation_interrupt(
+    conversation: *const CodexConversation,
+) -> i32 {
+    catch_panic(ERR, || {
+        clear_last_error();
+        // SAFETY: guaranteed by the caller.
+        unsafe { send_command(conversation, Command::Interrupt) }
+    })
+}
+
+/// Answers `request_json`, an `exec_approval_request` or
+/// `apply_patch_approval_request` event as returned by
+/// [`codex_conversation_next_event`], with `decision_json`, a decision such
+/// as `"approved"`, `"approved_for_session"`, `"denied"` or `"abort"`. Under
+/// an approval policy that asks, the turn waits for the answer.
+///
+/// # Safety
+///
+/// `conversation` must come from [`codex_conversation_start`] or
+/// [`codex_conversation_resume`] and not have been freed. `request_json` and
+/// `decision_json` must be valid NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn codex_conversation_respond(
+    conversation: *const CodexConversation,
+    request_json: *const c_char,
+    decision_json: *const c_char,
+) -> i32 {
+    catch_panic(ERR, || {
+        clear_last_error();
+        // SAFETY: guaranteed by the caller.
+        let request = match unsafe { optional_str(request_json) } {
+            Ok(Some(json)) => match serde_json::from_str::<Event>(json) {
+                Ok(request) => request,
+                Err(err) => {
+                    set_last_error(format!("invalid approval request: {err}"));
+                    return ERR;
+                }
+            },
+            Ok(None) => {
+                set_last_error("approval request is NULL");
+                return ERR;
+            }
+            Err(err) => {
+                set_last_error(err);
+                return ERR;
+            }
+        };
+        // SAFETY: guaranteed by the caller.
+        let decision = match unsafe { optional_str(decision_json) } {
+            Ok(Some(json)) => match serde_json::from_str::<ReviewDecision>(json) {
+                Ok(decision) => decision,
+                Err(err) => {
+                    set_last_error(format!("invalid decision: {err}"));
+                    return ERR;
+                }
+            },
+            Ok(None) => {
+                set_last_error("decision is NULL");
+                return ERR;
+            }
+            Err(err) => {
+                set_last_error(err);
+                return ERR;
+            }
+        };
+        // SAFETY: guaranteed by the caller.
+        unsafe { send_command(conversation, Command::Respond(Box::new(request), decision)) }
+    })
+}
+
+/// # Safety
+///
+/// `conversation` must be NULL or a live conversation handle.
+unsafe fn send_command(conversation: *const CodexConversation, command: Command) -> i32 {
+    // SAFETY: guaranteed by the caller.
+    let Some(conversation) = (unsafe { conversation.as_ref() }) else {
+        set_last_error("conversation is NULL");
+        return ERR;
+    };
+    let sent = conversation
+        .commands
+        .as_ref()
+        .is_some_and(|commands| commands.send(command).is_ok());
+    if sent {
+        OK
+    } else {
+        set_last_error("conversation has shut down");
+        ERR
+    }
+}
+
+/// Waits up to `timeout_ms` for the next event and returns it as JSON, to be
+/// freed with [`codex_string_free`]. Returns NULL on timeout, with
+/// [`codex_last_error`] also NULL, or once the conversation has shut down.
+///
+/// # Safety
+///
+/// `conversation` must come from [`codex_conversation_start`] or
+/// [`codex_conversation_resume`] and not have been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn codex_conversation_next_event(
+    conversation: *const CodexConversation,
+    timeout_ms: u64,
+) -> *mut c_char {
+    catch_panic(std::ptr::null_mut(), || {
+        clear_last_error();
+        // SAFETY: guaranteed by the caller.
+        let Some(conversation) = (unsafe { conversation.as_ref() }) else {
+            set_last_error("conversation is NULL");
+            return std::ptr::null_mut();
+        };
+        let events = match conversation.events.lock() {
+            Ok(events) => events,
+            Err(err) => err.into_inner(),
+        };
+        match events.recv_timeout(Duration::from_millis(timeout_ms)) {
+            Ok(json) => match CString::new(json) {
+                Ok(json) => json.into_raw(),
+                Err(err) => {
+                    set_last_error(err.to_string());
+                    std::ptr::null_mut()
+                }
+            },
+            Err(mpsc::RecvTimeoutError::Timeout) => std::ptr::null_mut(),
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                set_last_error("conversation has shut down");
+                std::ptr::null_mut()
+            }
+        }
+    })
+}
+
+/// Shuts the conversation down, interrupting any running turn, and frees it.
+///
+/// # Safety
+///
+/// `conversation` must be NULL or come from [`codex_conversation_start`] or
+/// [`codex_conversation_resume`] and not have been freed. Must not be called
+/// from a hook callback.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn codex_conversation_free(conversation: *mut CodexConversation) {
+    catch_panic((), || {
+        if conversation.is_null() {
+            return;
+        }
+        // SAFETY: guaranteed by the caller.
+        let mut conversation = unsafe { Box::from_raw(conversation) };
+        // Closing the command channel makes the driver shut the session down.
+        conversation.commands.take();
+        if let Some(driver) = conversation.driver.take() {
+            let _ = conversation.runtime.block_on(driver);
+        }
+    })
+}
+
+/// Frees a string returned by this library.
+///
+/// # Safety
+///
+/// `value` must be NULL or a string returned by this library that has not
+/// been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn codex_string_free(value: *mut c_char) {
+    catch_panic((), || {
+        if !value.is_null() {
+            // SAFETY: guaranteed by the caller.
+            drop(unsafe { CString::from_raw(value) });
+        }
+    })
+}
+
+/// # Safety
+///
+/// `value` must be NULL or a valid NUL-terminated string.
+unsafe fn optional_str<'a>(value: *const c_char) -> Result<Option<&'a str>, String> {
+    if value.is_null() {
+        return Ok(None);
+    }
+    // SAFETY: guaranteed by the caller.
+    unsafe { CStr::from_ptr(value) }
+        .to_str()
+        .map(Some)
+        .map_err(|err| format!("string is not valid UTF-8: {err}"))
+}
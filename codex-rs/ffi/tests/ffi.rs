@@ -0,0 +1,175 @@
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::ffi::c_char;
+use std::ffi::c_void;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_ffi::codex_client_builder_add_hook;
+use codex_ffi::codex_client_builder_build;
+use codex_ffi::codex_client_builder_new;
+use codex_ffi::codex_client_free;
+use codex_ffi::codex_conversation_free;
+use codex_ffi::codex_conversation_next_event;
+use codex_ffi::codex_conversation_respond;
+use codex_ffi::codex_conversation_send;
+use codex_ffi::codex_conversation_start;
+use codex_ffi::codex_last_error;
+use codex_ffi::codex_string_free;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+
+unsafe extern "C" fn count_events(_event_json: *const c_char, user_data: *mut c_void) -> i32 {
+    // SAFETY: the test passes a pointer to an `AtomicUsize` that outlives the
+    // client.
+    let counter = unsafe { &*(user_data as *const AtomicUsize) };
+    counter.fetch_add(1, Ordering::SeqCst);
+    0
+}
+
+fn last_error() -> Option<String> {
+    let error = codex_last_error();
+    if error.is_null() {
+        return None;
+    }
+    // SAFETY: non-NULL results of `codex_last_error` are valid C strings.
+    Some(
+        unsafe { CStr::from_ptr(error) }
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
+
+#[test]
+fn drives_a_scripted_conversation_through_the_c_api() -> anyhow::Result<()> {
+    let codex_home = tempfile::tempdir()?;
+    let fixture = codex_home.path().join("fixture.json");
+    std::fs::write(
+        &fixture,
+        json!({
+            "responses": [[
+                {
+                    "type": "response.output_item.done",
+                    "item": {
+                        "type": "message",
+                        "role": "assistant",
+                        "content": [{"type": "output_text", "text": "Hello over FFI"}]
+                    }
+                },
+                {"type": "response.completed", "response": {"id": "resp-1"}}
+            ]]
+        })
+        .to_string(),
+    )?;
+    std::fs::write(
+        codex_home.path().join("config.toml"),
+        format!(
+            "[mock_provider]\nfixture = {:?}\n",
+            fixture.display().to_string()
+        ),
+    )?;
+    let options = CString::new(
+        json!({
+            "codex_home": codex_home.path(),
+            "cwd": codex_home.path(),
+            "model_provider": "mock",
+        })
+        .to_string(),
+    )?;
+    let hook_name = CString::new("counter")?;
+    let hook_events = CString::new(r#"["task_complete"]"#)?;
+    let completed_turns = AtomicUsize::new(0);
+    let text = CString::new("Say hello")?;
+
+    // SAFETY: every pointer passed below is either a live handle returned by
+    // the library or a valid C string owned by this test.
+    unsafe {
+        let builder = codex_client_builder_new(options.as_ptr());
+        assert!(!builder.is_null(), "{:?}", last_error());
+        assert_eq!(
+            codex_client_builder_add_hook(
+                builder,
+                hook_name.as_ptr(),
+                hook_events.as_ptr(),
+                count_events,
+                &completed_turns as *const AtomicUsize as *mut c_void,
+            ),
+            0
+        );
+        let client = codex_client_builder_build(builder);
+        assert!(!client.is_null(), "{:?}", last_error());
+        let conversation = codex_conversation_start(client);
+        assert!(!conversation.is_null(), "{:?}", last_error());
+
+        assert_eq!(codex_conversation_send(conversation, text.as_ptr()), 0);
+
+        let mut agent_messages = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            assert!(Instant::now() < deadline, "turn did not complete");
+            let event = codex_conversation_next_event(conversation, 100);
+            if event.is_null() {
+                assert_eq!(last_error(), None);
+                continue;
+            }
+            let json: Value = serde_json::from_str(&CStr::from_ptr(event).to_string_lossy())?;
+            codex_string_free(event);
+            match json["msg"]["type"].as_str() {
+                Some("agent_message") => agent_messages.push(json["msg"]["message"].clone()),
+                Some("task_complete") => break,
+                _ => {}
+            }
+        }
+        assert_eq!(agent_messages, vec![json!("Hello over FFI")]);
+
+        // Hooks run in the background, so the count may trail the events.
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while completed_turns.load(Ordering::SeqCst) == 0 {
+            assert!(Instant::now() < deadline, "hook never ran");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        codex_conversation_free(conversation);
+        codex_client_free(client);
+    }
+    Ok(())
+}
+
+#[test]
+fn invalid_options_are_reported_through_last_error() -> anyhow::Result<()> {
+    let options = CString::new(r#"{"unknown": true}"#)?;
+
+    // SAFETY: `options` is a valid C string.
+    let builder = unsafe { codex_client_builder_new(options.as_ptr()) };
+
+    assert!(builder.is_null());
+    let error = last_error().unwrap_or_default();
+    assert!(error.starts_with("invalid client options"), "{error}");
+    Ok(())
+}
+
+#[test]
+fn invalid_approval_answers_are_reported_through_last_error() -> anyhow::Result<()> {
+    let request = CString::new(
+        json!({
+            "id": "1",
+            "msg": {"type": "agent_message", "message": "hi"},
+        })
+        .to_string(),
+    )?;
+    let decision = CString::new(r#""maybe""#)?;
+
+    // SAFETY: the strings are valid C strings, and a NULL conversation is
+    // rejected before it is used.
+    let result = unsafe {
+        codex_conversation_respond(std::ptr::null(), request.as_ptr(), decision.as_ptr())
+    };
+
+    assert_eq!(result, -1);
+    let error = last_error().unwrap_or_default();
+    assert!(error.starts_with("invalid decision"), "{error}");
+    Ok(())
+}
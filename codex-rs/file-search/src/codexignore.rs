@@ -0,0 +1,224 @@
+//! `.codexignore` files: paths the agent must not see.
+//!
+//! A `.codexignore` uses `.gitignore` syntax and applies to the directory it
+//! lives in and everything below it; rules in deeper files override those in
+//! shallower ones, so `!pattern` can re-include a file an outer rule hid. File
+//! search skips the matching paths, and the agent's file tools refuse to read
+//! or list them.
+//!
+//! Compiled ignore files are cached per directory and only re-read once they
+//! change, so checking every line of a search result stays cheap.
+
+use std::collections::HashMap;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+
+use ignore::Match;
+use ignore::gitignore::Gitignore;
+
+/// Name of the per-directory ignore file.
+pub const CODEX_IGNORE_FILENAME: &str = ".codexignore";
+
+/// How long a directory's cached ignore file is trusted before it is checked
+/// for changes again.
+const RECHECK_AFTER: Duration = Duration::from_secs(2);
+
+struct CachedIgnore {
+    checked_at: Instant,
+    /// Modification time of the ignore file, `None` when there is none.
+    modified: Option<SystemTime>,
+    matcher: Option<Arc<Gitignore>>,
+}
+
+static IGNORE_FILES: LazyLock<Mutex<HashMap<PathBuf, CachedIgnore>>> =
+    LazyLock::new(Mutex::default);
+
+/// Whether `path` is hidden by a `.codexignore` in one of its ancestor
+/// directories. A relative `path` is resolved against `root`, the project
+/// root. `..` segments and symlinks are resolved first, and the path is
+/// hidden if either how it is spelled or what it resolves to is. `is_dir`
+/// tells whether it names a directory, which matters for patterns such as
+/// `build/`.
+pub fn is_codex_ignored(root: &Path, path: &Path, is_dir: bool) -> bool {
+    let path = normalize(&root.join(path));
+    if is_ignored_as_spelled(&path, is_dir) {
+        return true;
+    }
+    match canonicalize(&path) {
+        Some(canonical) if canonical != path => is_ignored_as_spelled(&canonical, is_dir),
+        _ => false,
+    }
+}
+
+fn is_ignored_as_spelled(path: &Path, is_dir: bool) -> bool {
+    // Nearest ignore file first: deeper rules take precedence.
+    for dir in path.ancestors().skip(1) {
+        let Some(matcher) = ignore_file(dir) else {
+            continue;
+        };
+        match matcher.matched_path_or_any_parents(path, is_dir) {
+            Match::Ignore(_) => return true,
+            Match::Whitelist(_) => return false,
+            Match::None => {}
+        }
+    }
+    false
+}
+
+/// The compiled `.codexignore` of `dir`, if it has one.
+fn ignore_file(dir: &Path) -> Option<Arc<Gitignore>> {
+    let mut cache = match IGNORE_FILES.lock() {
+        Ok(cache) => cache,
+        Err(err) => err.into_inner(),
+    };
+    if let Some(cached) = cache.get(dir)
+        && cached.checked_at.elapsed() < RECHECK_AFTER
+    {
+        return cached.matcher.clone();
+    }
+    let ignore_file = dir.join(CODEX_IGNORE_FILENAME);
+    let modified = std::fs::metadata(&ignore_file)
+        .ok()
+        .filter(std::fs::Metadata::is_file)
+        .map(|metadata| metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH));
+    let matcher = match cache.get(dir) {
+        Some(cached) if cached.modified == modified => cached.matcher.clone(),
+        _ => modified.map(|_| Arc::new(Gitignore::new(&ignore_file).0)),
+    };
+    cache.insert(
+        dir.to_path_buf(),
+        CachedIgnore {
+            checked_at: Instant::now(),
+            modified,
+            matcher: matcher.clone(),
+        },
+    );
+    matcher
+}
+
+/// `path` with `.` and `..` segments removed without touching the file
+/// system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// `path` with symlinks resolved. When `path` does not exist, its deepest
+/// existing ancestor is resolved instead and the rest appended.
+fn canonicalize(path: &Path) -> Option<PathBuf> {
+    let mut missing = Vec::new();
+    let mut existing = path;
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return Some(
+                missing
+                    .iter()
+                    .rev()
+                    .fold(canonical, |path, name| path.join(name)),
+            );
+        }
+        missing.push(existing.file_name()?);
+        existing = existing.parent()?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_apply_below_their_directory_and_nest() -> std::io::Result<()> {
+        let temp = tempfile::tempdir()?;
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("web/node_modules/react"))?;
+        std::fs::create_dir_all(root.join("fixtures"))?;
+        std::fs::write(
+            root.join(CODEX_IGNORE_FILENAME),
+            "node_modules/\n*.sql\nsecrets.env\n",
+        )?;
+        std::fs::write(
+            root.join("fixtures").join(CODEX_IGNORE_FILENAME),
+            "!schema.sql\n",
+        )?;
+
+        let ignored = |rel: &str, is_dir: bool| is_codex_ignored(root, Path::new(rel), is_dir);
+        assert!(ignored("web/node_modules/react/index.js", false));
+        assert!(ignored("web/node_modules", true));
+        assert!(ignored("config/secrets.env", false));
+        assert!(ignored("fixtures/dump.sql", false));
+        assert!(!ignored("fixtures/schema.sql", false));
+        assert!(ignored("schema.sql", false));
+        assert!(!ignored("web/src/main.ts", false));
+        Ok(())
+    }
+
+    #[test]
+    fn parent_segments_cannot_escape_anchored_rules() -> std::io::Result<()> {
+        let temp = tempfile::tempdir()?;
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("src"))?;
+        std::fs::write(root.join(CODEX_IGNORE_FILENAME), "/.env\n")?;
+        std::fs::write(root.join(".env"), "TOKEN=1")?;
+
+        assert!(is_codex_ignored(root, Path::new("src/../.env"), false));
+        assert!(is_codex_ignored(root, &root.join("src/./../.env"), false));
+        assert!(!is_codex_ignored(root, Path::new("src/.env"), false));
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_to_ignored_paths_are_ignored() -> std::io::Result<()> {
+        let temp = tempfile::tempdir()?;
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("secrets"))?;
+        std::fs::write(root.join(CODEX_IGNORE_FILENAME), "/secrets/\n")?;
+        std::fs::write(root.join("secrets/key.pem"), "key")?;
+        std::os::unix::fs::symlink(root.join("secrets"), root.join("public"))?;
+
+        assert!(is_codex_ignored(root, Path::new("public/key.pem"), false));
+        assert!(is_codex_ignored(root, Path::new("public"), true));
+        Ok(())
+    }
+
+    #[test]
+    fn edited_ignore_files_are_reloaded() -> std::io::Result<()> {
+        let temp = tempfile::tempdir()?;
+        let root = &temp.path().canonicalize()?;
+        let ignore_file = root.join(CODEX_IGNORE_FILENAME);
+        std::fs::write(&ignore_file, "a.txt\n")?;
+        assert!(is_codex_ignored(root, Path::new("a.txt"), false));
+
+        std::fs::write(&ignore_file, "b.txt\n")?;
+        let file = std::fs::File::options().write(true).open(&ignore_file)?;
+        file.set_modified(SystemTime::now() + Duration::from_secs(60))?;
+        // Skip the wait before the ignore file is checked again.
+        if let Some(cached) = IGNORE_FILES
+            .lock()
+            .map_err(|_| std::io::Error::other("poisoned"))?
+            .get_mut(root)
+        {
+            cached.checked_at -= RECHECK_AFTER;
+        }
+
+        assert!(!is_codex_ignored(root, Path::new("a.txt"), false));
+        assert!(is_codex_ignored(root, Path::new("b.txt"), false));
+        Ok(())
+    }
+}
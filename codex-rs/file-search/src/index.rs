@@ -0,0 +1,575 @@
+//! A file index kept up to date in the background.
+//!
+//! [`run`](crate::run) walks the whole tree for every query, which is too slow
+//! for interactive completion in very large repositories. A [`FileIndex`]
+//! walks the tree once on a background thread, honoring the same ignore files,
+//! and then watches it for changes: each batch of filesystem events rescans
+//! only the directories they touched. Queries are matched against the
+//! in-memory list, so they can run while the initial scan is still going and
+//! see the files found so far.
+
+use std::collections::BTreeSet;
+use std::collections::HashSet;
+use std::num::NonZero;
+use std::path::MAIN_SEPARATOR_STR;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::RwLock;
+use std::sync::RwLockReadGuard;
+use std::sync::RwLockWriteGuard;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+use ignore::WalkBuilder;
+use ignore::WalkState;
+use notify::EventKind;
+use notify::RecommendedWatcher;
+use notify::RecursiveMode;
+use notify::Watcher;
+use nucleo_matcher::Matcher;
+
+use crate::BestMatchesList;
+use crate::CODEX_IGNORE_FILENAME;
+use crate::FileSearchResults;
+use crate::build_file_matches;
+use crate::create_pattern;
+
+/// Events arriving within this window of the first one are applied together.
+const EVENT_BATCH_WINDOW: Duration = Duration::from_millis(50);
+
+/// How often the whole tree is rescanned when it cannot be watched, e.g.
+/// because the OS limit on watches was reached.
+const UNWATCHED_RESCAN_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Entries found by a walk are added to the index in batches of this size.
+const SCAN_BATCH_SIZE: usize = 4096;
+
+/// Ignore files whose edits can change what is indexed below them.
+const IGNORE_FILE_NAMES: &[&str] = &[".gitignore", ".ignore", CODEX_IGNORE_FILENAME];
+
+type WatchEvent = notify::Result<notify::Event>;
+
+/// In-memory list of the files under a directory, kept current by a
+/// background thread. Dropping the index stops the thread.
+pub struct FileIndex {
+    state: Arc<IndexState>,
+    // Dropping both ends of the event channel's senders disconnects the
+    // indexer thread, which then exits.
+    _watcher: Option<RecommendedWatcher>,
+    _events_tx: mpsc::Sender<WatchEvent>,
+}
+
+struct IndexState {
+    root: PathBuf,
+    entries: RwLock<Entries>,
+    ready: AtomicBool,
+}
+
+/// Paths relative to the index root. The root itself is the empty string.
+#[derive(Default)]
+struct Entries {
+    files: BTreeSet<String>,
+    dirs: BTreeSet<String>,
+}
+
+impl FileIndex {
+    /// Starts indexing `root` on a background thread. If `root` cannot be
+    /// watched, the index falls back to rescanning it periodically.
+    pub fn new(root: PathBuf) -> Self {
+        let state = Arc::new(IndexState {
+            root,
+            entries: RwLock::new(Entries::default()),
+            ready: AtomicBool::new(false),
+        });
+        let (events_tx, events_rx) = mpsc::channel();
+
+        // Start watching before the initial scan so no change made during the
+        // scan is missed.
+        let watcher = notify::recommended_watcher(events_tx.clone())
+            .and_then(|mut watcher| {
+                watcher.watch(&state.root, RecursiveMode::Recursive)?;
+                Ok(watcher)
+            })
+            .ok();
+
+        let thread_state = Arc::clone(&state);
+        let watching = watcher.is_some();
+        thread::spawn(move || thread_state.run(events_rx, watching));
+
+        Self {
+            state,
+            _watcher: watcher,
+            _events_tx: events_tx,
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.state.root
+    }
+
+    /// Whether the initial scan has finished. Before that, searches only see
+    /// the files found so far.
+    pub fn is_ready(&self) -> bool {
+        self.state.ready.load(Ordering::Acquire)
+    }
+
+    /// The indexed files, as paths relative to the root in sorted order.
+    pub fn files(&self) -> Vec<String> {
+        self.state.read().files.iter().cloned().collect()
+    }
+
+    /// Fuzzy-matches `pattern_text` against the indexed paths, returning the
+    /// best `limit` matches in the same form as [`run`](crate::run). Returns
+    /// no matches if `cancel_flag` is set while matching.
+    pub fn search(
+        &self,
+        pattern_text: &str,
+        limit: NonZero<usize>,
+        compute_indices: bool,
+        cancel_flag: &AtomicBool,
+    ) -> FileSearchResults {
+        const CHECK_INTERVAL: usize = 1024;
+
+        let pattern = create_pattern(pattern_text);
+        let mut best = BestMatchesList::new(
+            limit.get(),
+            pattern.clone(),
+            Matcher::new(nucleo_matcher::Config::DEFAULT),
+        );
+        {
+            let entries = self.state.read();
+            for (processed, path) in entries.files.iter().enumerate() {
+                if processed % CHECK_INTERVAL == 0 && cancel_flag.load(Ordering::Relaxed) {
+                    return FileSearchResults {
+                        matches: Vec::new(),
+                        total_match_count: 0,
+                    };
+                }
+                best.insert(path);
+            }
+        }
+
+        let raw_matches = best.binary_heap.into_iter().map(|r| r.0).collect();
+        FileSearchResults {
+            matches: build_file_matches(raw_matches, &pattern, compute_indices),
+            total_match_count: best.num_matches,
+        }
+    }
+}
+
+impl IndexState {
+    fn run(&self, events_rx: mpsc::Receiver<WatchEvent>, watching: bool) {
+        self.scan_initial();
+        self.ready.store(true, Ordering::Release);
+
+        loop {
+            let first = if watching {
+                match events_rx.recv() {
+                    Ok(event) => event,
+                    Err(mpsc::RecvError) => return,
+                }
+            } else {
+                match events_rx.recv_timeout(UNWATCHED_RESCAN_INTERVAL) {
+                    Ok(event) => event,
+                    Err(mpsc::RecvTimeoutError::Timeout) => {
+                        self.rescan_tree("");
+                        continue;
+                    }
+                    Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                }
+            };
+            let mut batch = vec![first];
+            let deadline = Instant::now() + EVENT_BATCH_WINDOW;
+            while let Some(remaining) = deadline.checked_duration_since(Instant::now())
+                && let Ok(event) = events_rx.recv_timeout(remaining)
+            {
+                batch.push(event);
+            }
+            self.apply_events(batch);
+        }
+    }
+
+    /// Rescans what a batch of events touched: the whole tree below an ignore
+    /// file that changed, otherwise only the parent directory of each path.
+    fn apply_events(&self, batch: Vec<WatchEvent>) {
+        let mut trees = BTreeSet::new();
+        let mut dirs = BTreeSet::new();
+        for event in batch {
+            let event = match event {
+                Ok(event) if !event.need_rescan() => event,
+                // The watcher lost track of changes.
+                _ => {
+                    self.rescan_tree("");
+                    return;
+                }
+            };
+            if matches!(event.kind, EventKind::Access(_)) {
+                continue;
+            }
+            for path in &event.paths {
+                let Some(rel) = self.relative(path) else {
+                    continue;
+                };
+                if is_in_git_dir(&rel) {
+                    continue;
+                }
+                let parent = parent_of(&rel).to_string();
+                let is_ignore_file = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| IGNORE_FILE_NAMES.contains(&name));
+                if is_ignore_file {
+                    trees.insert(parent);
+                } else {
+                    dirs.insert(parent);
+                }
+            }
+        }
+
+        trees.retain(|tree| self.is_indexed_dir(tree));
+        for tree in &trees {
+            self.rescan_tree(tree);
+        }
+        // Parents before children, so directories created in this batch are
+        // known by the time their own contents are looked at.
+        let mut dirs: Vec<String> = dirs
+            .into_iter()
+            .filter(|dir| !trees.iter().any(|tree| is_within(dir, tree)))
+            .collect();
+        dirs.sort_by_key(|dir| dir.matches(MAIN_SEPARATOR_STR).count());
+        for dir in dirs {
+            self.rescan_dir(&dir);
+        }
+    }
+
+    fn scan_initial(&self) {
+        self.walk("", None, |batch| {
+            let mut entries = self.write();
+            for (rel, is_dir) in batch {
+                entries.insert(rel, is_dir);
+            }
+        });
+    }
+
+    /// Re-reads the direct children of `dir`. Subdirectories that appeared
+    /// are scanned in full and those that disappeared are dropped.
+    fn rescan_dir(&self, dir: &str) {
+        if !self.is_indexed_dir(dir) {
+            return;
+        }
+        if !self.root.join(dir).is_dir() {
+            self.write().remove_tree(dir);
+            return;
+        }
+
+        let found: HashSet<(String, bool)> = self.collect(dir, Some(1)).into_iter().collect();
+
+        let new_dirs = {
+            let mut entries = self.write();
+            let previous = entries.children(dir);
+            for (rel, is_dir) in &previous {
+                if !found.contains(&(rel.clone(), *is_dir)) {
+                    if *is_dir {
+                        entries.remove_tree(rel);
+                    } else {
+                        entries.files.remove(rel);
+                    }
+                }
+            }
+            let mut new_dirs = Vec::new();
+            for (rel, is_dir) in found {
+                if is_dir && !entries.dirs.contains(&rel) {
+                    new_dirs.push(rel);
+                } else {
+                    entries.insert(rel, is_dir);
+                }
+            }
+            new_dirs
+        };
+        for new_dir in new_dirs {
+            self.rescan_tree(&new_dir);
+        }
+    }
+
+    /// Scans everything below `dir` and replaces what the index held there.
+    fn rescan_tree(&self, dir: &str) {
+        if !self.root.join(dir).is_dir() {
+            self.write().remove_tree(dir);
+            return;
+        }
+        let found = self.collect(dir, None);
+
+        let mut entries = self.write();
+        entries.remove_tree(dir);
+        if !dir.is_empty() {
+            entries.dirs.insert(dir.to_string());
+        }
+        for (rel, is_dir) in found {
+            entries.insert(rel, is_dir);
+        }
+    }
+
+    /// Ignored directories are not indexed, and neither is anything below
+    /// them, so changes there need no rescan.
+    fn is_indexed_dir(&self, dir: &str) -> bool {
+        dir.is_empty() || self.read().dirs.contains(dir)
+    }
+
+    /// Walks `dir` and returns every entry below it.
+    fn collect(&self, dir: &str, max_depth: Option<usize>) -> Vec<(String, bool)> {
+        let found = Mutex::new(Vec::new());
+        self.walk(dir, max_depth, |batch| {
+            found
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .extend(batch);
+        });
+        found
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Walks `dir` (relative to the root) with the same ignore rules as
+    /// [`run`](crate::run), passing batches of `(relative path, is_dir)` to
+    /// `on_batch`. `dir` itself is not reported.
+    fn walk<F>(&self, dir: &str, max_depth: Option<usize>, on_batch: F)
+    where
+        F: Fn(Vec<(String, bool)>) + Sync,
+    {
+        let mut walk_builder = WalkBuilder::new(self.root.join(dir));
+        walk_builder
+            .max_depth(max_depth)
+            // Allow hidden entries.
+            .hidden(false)
+            // Follow symlinks to search their contents.
+            .follow_links(true)
+            // Don't require git to be present to apply to apply git-related ignore rules.
+            .require_git(false)
+            .add_custom_ignore_filename(CODEX_IGNORE_FILENAME)
+            // Git's own metadata changes constantly and is never worth
+            // completing.
+            .filter_entry(|entry| entry.file_name() != ".git");
+
+        walk_builder.build_parallel().run(|| {
+            let mut sink = BatchSink {
+                batch: Vec::new(),
+                on_batch: &on_batch,
+            };
+            Box::new(move |entry| {
+                if let Ok(entry) = entry
+                    && entry.depth() > 0
+                    && let Some(rel) = self.relative(entry.path())
+                {
+                    let is_dir = entry.file_type().is_some_and(|ft| ft.is_dir());
+                    sink.push((rel, is_dir));
+                }
+                WalkState::Continue
+            })
+        });
+    }
+
+    fn relative(&self, path: &Path) -> Option<String> {
+        path.strip_prefix(&self.root)
+            .ok()
+            .and_then(Path::to_str)
+            .map(str::to_string)
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Entries> {
+        self.entries
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Entries> {
+        self.entries
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl Entries {
+    fn insert(&mut self, rel: String, is_dir: bool) {
+        if is_dir {
+            self.dirs.insert(rel);
+        } else {
+            self.files.insert(rel);
+        }
+    }
+
+    /// Direct children of `dir`, as `(relative path, is_dir)`.
+    fn children(&self, dir: &str) -> Vec<(String, bool)> {
+        let prefix = child_prefix(dir);
+        let direct = |rel: &&String| !rel[prefix.len()..].contains(MAIN_SEPARATOR_STR);
+        let files = self
+            .files
+            .range(prefix.clone()..)
+            .take_while(|rel| rel.starts_with(&prefix))
+            .filter(direct)
+            .map(|rel| (rel.clone(), false));
+        let dirs = self
+            .dirs
+            .range(prefix.clone()..)
+            .take_while(|rel| rel.starts_with(&prefix))
+            .filter(direct)
+            .map(|rel| (rel.clone(), true));
+        files.chain(dirs).collect()
+    }
+
+    /// Removes `dir` and everything below it. The empty string clears the
+    /// whole index.
+    fn remove_tree(&mut self, dir: &str) {
+        if dir.is_empty() {
+            self.files.clear();
+            self.dirs.clear();
+            return;
+        }
+        let prefix = child_prefix(dir);
+        self.files.retain(|rel| !rel.starts_with(&prefix));
+        self.dirs.retain(|rel| !rel.starts_with(&prefix));
+        self.dirs.remove(dir);
+    }
+}
+
+/// Collects a walker thread's entries and hands them over in batches. The
+/// last partial batch is handed over when the walker drops its visitor.
+struct BatchSink<'a, F: Fn(Vec<(String, bool)>)> {
+    batch: Vec<(String, bool)>,
+    on_batch: &'a F,
+}
+
+impl<F: Fn(Vec<(String, bool)>)> BatchSink<'_, F> {
+    fn push(&mut self, entry: (String, bool)) {
+        self.batch.push(entry);
+        if self.batch.len() >= SCAN_BATCH_SIZE {
+            (self.on_batch)(std::mem::take(&mut self.batch));
+        }
+    }
+}
+
+impl<F: Fn(Vec<(String, bool)>)> Drop for BatchSink<'_, F> {
+    fn drop(&mut self) {
+        if !self.batch.is_empty() {
+            (self.on_batch)(std::mem::take(&mut self.batch));
+        }
+    }
+}
+
+fn child_prefix(dir: &str) -> String {
+    if dir.is_empty() {
+        String::new()
+    } else {
+        format!("{dir}{MAIN_SEPARATOR_STR}")
+    }
+}
+
+fn parent_of(rel: &str) -> &str {
+    Path::new(rel)
+        .parent()
+        .and_then(Path::to_str)
+        .unwrap_or_default()
+}
+
+fn is_within(path: &str, dir: &str) -> bool {
+    dir.is_empty() || path == dir || path.starts_with(&child_prefix(dir))
+}
+
+fn is_in_git_dir(rel: &str) -> bool {
+    Path::new(rel)
+        .components()
+        .any(|component| component.as_os_str() == ".git")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn paths(index: &FileIndex, pattern: &str) -> Vec<String> {
+        let mut paths: Vec<String> = index
+            .search(
+                pattern,
+                NonZero::new(100).expect("non-zero"),
+                false,
+                &AtomicBool::new(false),
+            )
+            .matches
+            .into_iter()
+            .map(|m| m.path)
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    fn wait_for(mut condition: impl FnMut() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !condition() {
+            assert!(Instant::now() < deadline, "timed out waiting for index");
+            thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    #[test]
+    fn children_and_remove_tree_respect_directory_boundaries() {
+        let mut entries = Entries::default();
+        for dir in ["a", "a/b", "ab"] {
+            entries.insert(path(dir), true);
+        }
+        for file in ["a/one.rs", "a/b/two.rs", "ab/three.rs", "top.rs"] {
+            entries.insert(path(file), false);
+        }
+
+        let mut children = entries.children("a");
+        children.sort();
+        assert_eq!(
+            children,
+            vec![(path("a/b"), true), (path("a/one.rs"), false)]
+        );
+
+        entries.remove_tree("a");
+        assert_eq!(
+            entries.files.into_iter().collect::<Vec<_>>(),
+            vec![path("ab/three.rs"), path("top.rs")]
+        );
+        assert_eq!(
+            entries.dirs.into_iter().collect::<Vec<_>>(),
+            vec![path("ab")]
+        );
+    }
+
+    #[test]
+    fn indexes_files_and_follows_changes() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let root = dir.path();
+        std::fs::write(root.join(".gitignore"), "build/\n")?;
+        std::fs::create_dir_all(root.join("src"))?;
+        std::fs::create_dir_all(root.join("build"))?;
+        std::fs::write(root.join("src/main.rs"), "")?;
+        std::fs::write(root.join("build/main.rs"), "")?;
+
+        let index = FileIndex::new(root.to_path_buf());
+        wait_for(|| index.is_ready());
+        assert_eq!(paths(&index, "main"), vec![path("src/main.rs")]);
+
+        std::fs::create_dir_all(root.join("src/nested"))?;
+        std::fs::write(root.join("src/nested/main_test.rs"), "")?;
+        std::fs::write(root.join("build/main_test.rs"), "")?;
+        wait_for(|| {
+            paths(&index, "main") == vec![path("src/main.rs"), path("src/nested/main_test.rs")]
+        });
+
+        std::fs::remove_file(root.join("src/main.rs"))?;
+        wait_for(|| paths(&index, "main") == vec![path("src/nested/main_test.rs")]);
+        Ok(())
+    }
+
+    fn path(rel: &str) -> String {
+        rel.replace('/', MAIN_SEPARATOR_STR)
+    }
+}
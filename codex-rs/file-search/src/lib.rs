@@ -19,8 +19,13 @@ use std::sync::atomic::Ordering;
 use tokio::process::Command;
 
 mod cli;
+mod codexignore;
+mod index;
 
 pub use cli::Cli;
+pub use codexignore::CODEX_IGNORE_FILENAME;
+pub use codexignore::is_codex_ignored;
+pub use index::FileIndex;
 
 /// A single match result returned from the search.
 ///
@@ -170,7 +175,9 @@ pub fn run(
         // Follow symlinks to search their contents.
         .follow_links(true)
         // Don't require git to be present to apply to apply git-related ignore rules.
-        .require_git(false);
+        .require_git(false)
+        // `.codexignore` applies whether or not `.gitignore` is respected.
+        .add_custom_ignore_filename(CODEX_IGNORE_FILENAME);
     if !respect_gitignore {
         walk_builder
             .git_ignore(false)
@@ -265,7 +272,22 @@ pub fn run(
         }
     }
 
-    let mut raw_matches: Vec<(u32, String)> = global_heap.into_iter().map(|r| r.0).collect();
+    let raw_matches: Vec<(u32, String)> = global_heap.into_iter().map(|r| r.0).collect();
+    let matches = build_file_matches(raw_matches, &pattern, compute_indices);
+
+    Ok(FileSearchResults {
+        matches,
+        total_match_count,
+    })
+}
+
+/// Sorts the best `(score, path)` pairs and turns them into [`FileMatch`]es,
+/// optionally computing the matched indices for highlighting.
+fn build_file_matches(
+    mut raw_matches: Vec<(u32, String)>,
+    pattern: &Pattern,
+    compute_indices: bool,
+) -> Vec<FileMatch> {
     sort_matches(&mut raw_matches);
 
     // Transform into `FileMatch`, optionally computing indices.
@@ -275,7 +297,7 @@ pub fn run(
         None
     };
 
-    let matches: Vec<FileMatch> = raw_matches
+    raw_matches
         .into_iter()
         .map(|(score, path)| {
             let indices = if compute_indices {
@@ -299,12 +321,7 @@ pub fn run(
                 indices,
             }
         })
-        .collect();
-
-    Ok(FileSearchResults {
-        matches,
-        total_match_count,
-    })
+        .collect()
 }
 
 /// Sort matches in-place by descending score, then ascending path.
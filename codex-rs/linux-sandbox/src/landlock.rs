@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
 use std::path::Path;
+use std::path::PathBuf;
 
 use codex_core::error::CodexErr;
 use codex_core::error::Result;
@@ -30,6 +31,7 @@ use seccompiler::apply_filter;
 pub(crate) fn apply_sandbox_policy_to_current_thread(
     sandbox_policy: &SandboxPolicy,
     cwd: &Path,
+    protected_dirs: &[PathBuf],
 ) -> Result<()> {
     if !sandbox_policy.has_full_network_access() {
         install_network_seccomp_filter_on_current_thread()?;
@@ -41,7 +43,7 @@ pub(crate) fn apply_sandbox_policy_to_current_thread(
             .into_iter()
             .map(|writable_root| writable_root.root)
             .collect();
-        install_filesystem_landlock_rules_on_current_thread(writable_roots)?;
+        install_filesystem_landlock_rules_on_current_thread(writable_roots, protected_dirs)?;
     }
 
     // TODO(ragona): Add appropriate restrictions if
@@ -52,27 +54,68 @@ pub(crate) fn apply_sandbox_policy_to_current_thread(
 
 /// Installs Landlock file-system rules on the current thread allowing read
 /// access to the entire file-system while restricting write access to
-/// `/dev/null` and the provided list of `writable_roots`.
+/// `/dev/null` and the provided list of `writable_roots`. Nothing in
+/// `protected_dirs` is readable or writable.
 ///
 /// # Errors
 /// Returns [`CodexErr::Sandbox`] variants when the ruleset fails to apply.
 fn install_filesystem_landlock_rules_on_current_thread(
     writable_roots: Vec<AbsolutePathBuf>,
+    protected_dirs: &[PathBuf],
 ) -> Result<()> {
     let abi = ABI::V5;
     let access_rw = AccessFs::from_all(abi);
     let access_ro = AccessFs::from_read(abi);
+    // Only directories that exist now need protecting; canonical paths let
+    // them be compared with the directory entries walked below.
+    let protected_dirs = protected_dirs
+        .iter()
+        .filter_map(|dir| dir.canonicalize().ok())
+        .collect::<Vec<_>>();
 
     let mut ruleset = Ruleset::default()
         .set_compatibility(CompatLevel::BestEffort)
         .handle_access(access_rw)?
         .create()?
-        .add_rules(landlock::path_beneath_rules(&["/"], access_ro))?
         .add_rules(landlock::path_beneath_rules(&["/dev/null"], access_rw))?
         .set_no_new_privs(true);
 
-    if !writable_roots.is_empty() {
-        ruleset = ruleset.add_rules(landlock::path_beneath_rules(&writable_roots, access_rw))?;
+    if protected_dirs.is_empty() {
+        ruleset = ruleset.add_rules(landlock::path_beneath_rules(&["/"], access_ro))?;
+    } else {
+        // Landlock rules only grant access, so read access to "/" is spelled
+        // out as every path beside the protected directories. Listing stays
+        // allowed everywhere, so names are visible but contents are not.
+        ruleset = ruleset
+            .add_rules(landlock::path_beneath_rules(&["/"], AccessFs::ReadDir))?
+            .add_rules(landlock::path_beneath_rules(
+                paths_beneath_excluding(Path::new("/"), &protected_dirs),
+                access_ro,
+            ))?;
+    }
+
+    for root in &writable_roots {
+        let canonical_root = root
+            .as_path()
+            .canonicalize()
+            .unwrap_or_else(|_| root.to_path_buf());
+        if protected_dirs
+            .iter()
+            .any(|dir| canonical_root.starts_with(dir))
+        {
+            continue;
+        }
+        if protected_dirs
+            .iter()
+            .any(|dir| dir.starts_with(&canonical_root))
+        {
+            ruleset = ruleset.add_rules(landlock::path_beneath_rules(
+                paths_beneath_excluding(&canonical_root, &protected_dirs),
+                access_rw,
+            ))?;
+        } else {
+            ruleset = ruleset.add_rules(landlock::path_beneath_rules(&[root], access_rw))?;
+        }
     }
 
     let status = ruleset.restrict_self()?;
@@ -84,6 +127,37 @@ fn install_filesystem_landlock_rules_on_current_thread(
     Ok(())
 }
 
+/// Every entry under `root` whose subtree does not contain one of `excluded`,
+/// descending into the directories on the way to them. Entries created after
+/// the walk, such as new files directly in those directories, are not
+/// covered.
+fn paths_beneath_excluding(root: &Path, excluded: &[PathBuf]) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return paths;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if excluded.iter().any(|dir| dir == &path) {
+            continue;
+        }
+        if excluded.iter().any(|dir| dir.starts_with(&path)) {
+            paths.extend(paths_beneath_excluding(&path, excluded));
+            continue;
+        }
+        // A symlink elsewhere must not lead into an excluded directory.
+        if let Ok(target) = path.canonicalize()
+            && excluded
+                .iter()
+                .any(|dir| dir.starts_with(&target) || target.starts_with(dir))
+        {
+            continue;
+        }
+        paths.push(path);
+    }
+    paths
+}
+
 /// Installs a seccomp filter that blocks outbound network access except for
 /// AF_UNIX domain sockets.
 fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(), SandboxErr> {
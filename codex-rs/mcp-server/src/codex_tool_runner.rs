@@ -177,6 +177,7 @@ async fn run_codex_tool_session_inner(
                         reason: _,
                         proposed_execpolicy_amendment: _,
                         parsed_cmd,
+                        operations: _,
                     }) => {
                         handle_exec_approval_request(
                             command,
@@ -228,7 +229,9 @@ async fn run_codex_tool_session_inner(
                         .await;
                         continue;
                     }
-                    EventMsg::TurnComplete(TurnCompleteEvent { last_agent_message }) => {
+                    EventMsg::TurnComplete(TurnCompleteEvent {
+                        last_agent_message, ..
+                    }) => {
                         let text = match last_agent_message {
                             Some(msg) => msg,
                             None => "".to_string(),
@@ -259,7 +262,10 @@ async fn run_codex_tool_session_inner(
                     EventMsg::AgentReasoningDelta(_) => {
                         // TODO: think how we want to support this in the MCP
                     }
-                    EventMsg::McpStartupUpdate(_) | EventMsg::McpStartupComplete(_) => {
+                    EventMsg::McpStartupUpdate(_)
+                    | EventMsg::McpStartupComplete(_)
+                    | EventMsg::McpServerDown(_)
+                    | EventMsg::McpServerRestarted(_) => {
                         // Ignored in MCP tool runner.
                     }
                     EventMsg::AgentMessage(AgentMessageEvent { .. }) => {
@@ -276,6 +282,10 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::McpListToolsResponse(_)
                     | EventMsg::ListCustomPromptsResponse(_)
                     | EventMsg::ListSkillsResponse(_)
+                    | EventMsg::ListHooksResponse(_)
+                    | EventMsg::UserCommand(_)
+                    | EventMsg::ConfigOverrideApplied(_)
+                    | EventMsg::ModelSettingsChanged(_)
                     | EventMsg::ExecCommandBegin(_)
                     | EventMsg::TerminalInteraction(_)
                     | EventMsg::ExecCommandOutputDelta(_)
@@ -293,6 +303,18 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::UserMessage(_)
                     | EventMsg::ShutdownComplete
                     | EventMsg::ViewImageToolCall(_)
+                    | EventMsg::ContextRepaired(_)
+                    | EventMsg::RedactionApplied(_)
+                    | EventMsg::InjectionSuspected(_)
+                    | EventMsg::ContextSources(_)
+                    | EventMsg::OutputBudget(_)
+                    | EventMsg::GuardrailTripped(_)
+                    | EventMsg::WatchdogFired(_)
+                    | EventMsg::ProjectDocsUpdated(_)
+                    | EventMsg::WatchedFilesChanged(_)
+                    | EventMsg::WorkspaceSnapshot(_)
+                    | EventMsg::VariablesUpdated(_)
+                    | EventMsg::EnvironmentFingerprint(_)
                     | EventMsg::RawResponseItem(_)
                     | EventMsg::EnteredReviewMode(_)
                     | EventMsg::ItemStarted(_)
@@ -303,6 +325,7 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::SkillsUpdateAvailable
                     | EventMsg::UndoStarted(_)
                     | EventMsg::UndoCompleted(_)
+                    | EventMsg::PullRequestPublished(_)
                     | EventMsg::ExitedReviewMode(_)
                     | EventMsg::ContextCompacted(_)
                     | EventMsg::ThreadRolledBack(_)
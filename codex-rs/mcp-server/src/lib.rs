@@ -94,6 +94,10 @@ pub async fn run_main(
         .map_err(|e| {
             std::io::Error::new(ErrorKind::InvalidData, format!("error loading config: {e}"))
         })?;
+    codex_core::crash_report::install(&config, "codex-mcp-server");
+    codex_core::otel_init::start_prometheus_endpoint(&config).map_err(|e| {
+        std::io::Error::new(e.kind(), format!("error starting Prometheus endpoint: {e}"))
+    })?;
 
     // Task: process incoming messages.
     let processor_handle = tokio::spawn({
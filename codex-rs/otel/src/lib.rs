@@ -1,6 +1,7 @@
 pub mod config;
 pub mod metrics;
 pub mod otel_provider;
+pub mod prometheus;
 pub mod traces;
 
 mod otlp;
@@ -79,6 +80,9 @@ impl OtelManager {
     }
 
     pub fn counter(&self, name: &str, inc: i64, tags: &[(&str, &str)]) {
+        if let Some(registry) = prometheus::global() {
+            registry.counter(name, inc, tags);
+        }
         let res: MetricsResult<()> = (|| {
             let Some(metrics) = &self.metrics else {
                 return Ok(());
@@ -94,6 +98,9 @@ impl OtelManager {
     }
 
     pub fn histogram(&self, name: &str, value: i64, tags: &[(&str, &str)]) {
+        if let Some(registry) = prometheus::global() {
+            registry.histogram(name, value, tags);
+        }
         let res: MetricsResult<()> = (|| {
             let Some(metrics) = &self.metrics else {
                 return Ok(());
@@ -109,6 +116,10 @@ impl OtelManager {
     }
 
     pub fn record_duration(&self, name: &str, duration: Duration, tags: &[(&str, &str)]) {
+        if let Some(registry) = prometheus::global() {
+            let millis = i64::try_from(duration.as_millis()).unwrap_or(i64::MAX);
+            registry.histogram(name, millis, tags);
+        }
         let res: MetricsResult<()> = (|| {
             let Some(metrics) = &self.metrics else {
                 return Ok(());
@@ -0,0 +1,193 @@
+//! In-process Prometheus registry and a `/metrics` endpoint for long-running
+//! server modes (`codex app-server`, `codex mcp-server`).
+//!
+//! Once [`serve`] has been called, every counter and histogram recorded
+//! through [`OtelManager`](crate::OtelManager) is mirrored here, independent
+//! of whether an OTLP metrics exporter is configured. Metric names are
+//! translated to Prometheus conventions (`codex.tool.call` becomes
+//! `codex_tool_call_total`); histograms are exposed as summaries with `_sum`
+//! and `_count` series.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use std::thread::JoinHandle;
+
+use tiny_http::Header;
+use tiny_http::Response;
+use tiny_http::Server;
+
+static GLOBAL_REGISTRY: OnceLock<PrometheusRegistry> = OnceLock::new();
+
+type Labels = Vec<(String, String)>;
+
+#[derive(Default)]
+struct Summary {
+    sum: i64,
+    count: u64,
+}
+
+#[derive(Default)]
+struct Series {
+    counters: BTreeMap<String, BTreeMap<Labels, i64>>,
+    summaries: BTreeMap<String, BTreeMap<Labels, Summary>>,
+}
+
+/// Accumulates counters and histogram samples for the text exposition format.
+#[derive(Default)]
+pub struct PrometheusRegistry {
+    series: Mutex<Series>,
+}
+
+impl PrometheusRegistry {
+    pub fn counter(&self, name: &str, inc: i64, tags: &[(&str, &str)]) {
+        let mut series = self.lock();
+        *series
+            .counters
+            .entry(name.to_string())
+            .or_default()
+            .entry(labels(tags))
+            .or_default() += inc;
+    }
+
+    pub fn histogram(&self, name: &str, value: i64, tags: &[(&str, &str)]) {
+        let mut series = self.lock();
+        let summary = series
+            .summaries
+            .entry(name.to_string())
+            .or_default()
+            .entry(labels(tags))
+            .or_default();
+        summary.sum = summary.sum.saturating_add(value);
+        summary.count += 1;
+    }
+
+    /// Renders every series in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let series = self.lock();
+        let mut out = String::new();
+        for (name, values) in &series.counters {
+            let name = format!("{}_total", metric_name(name));
+            let _ = writeln!(out, "# TYPE {name} counter");
+            for (labels, value) in values {
+                let _ = writeln!(out, "{name}{} {value}", render_labels(labels));
+            }
+        }
+        for (name, values) in &series.summaries {
+            let name = metric_name(name);
+            let _ = writeln!(out, "# TYPE {name} summary");
+            for (labels, summary) in values {
+                let labels = render_labels(labels);
+                let _ = writeln!(out, "{name}_sum{labels} {}", summary.sum);
+                let _ = writeln!(out, "{name}_count{labels} {}", summary.count);
+            }
+        }
+        out
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Series> {
+        match self.series.lock() {
+            Ok(series) => series,
+            Err(err) => err.into_inner(),
+        }
+    }
+}
+
+/// The process-wide registry, if [`serve`] has been called.
+pub(crate) fn global() -> Option<&'static PrometheusRegistry> {
+    GLOBAL_REGISTRY.get()
+}
+
+/// Starts serving `GET /metrics` on `addr` from a background thread and
+/// begins mirroring metrics into the process-wide registry.
+pub fn serve(addr: SocketAddr) -> io::Result<JoinHandle<()>> {
+    let server = Server::http(addr).map_err(io::Error::other)?;
+    let registry = GLOBAL_REGISTRY.get_or_init(PrometheusRegistry::default);
+    Ok(std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let path = request.url().split('?').next().unwrap_or_default();
+            let response = if path == "/metrics" {
+                let mut response = Response::from_string(registry.render());
+                if let Ok(header) = Header::from_bytes(
+                    &b"Content-Type"[..],
+                    &b"text/plain; version=0.0.4; charset=utf-8"[..],
+                ) {
+                    response.add_header(header);
+                }
+                response
+            } else {
+                Response::from_string("Not Found").with_status_code(404)
+            };
+            if let Err(err) = request.respond(response) {
+                tracing::warn!("failed to serve /metrics: {err}");
+            }
+        }
+    }))
+}
+
+fn labels(tags: &[(&str, &str)]) -> Labels {
+    let mut labels: Labels = tags
+        .iter()
+        .map(|(key, value)| (metric_name(key), (*value).to_string()))
+        .collect();
+    labels.sort();
+    labels
+}
+
+fn metric_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn render_labels(labels: &Labels) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let rendered: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| {
+            let value = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            format!("{key}=\"{value}\"")
+        })
+        .collect();
+    format!("{{{}}}", rendered.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn renders_counters_and_summaries() {
+        let registry = PrometheusRegistry::default();
+        registry.counter(
+            "codex.tool.call",
+            1,
+            &[("tool", "shell"), ("success", "true")],
+        );
+        registry.counter(
+            "codex.tool.call",
+            2,
+            &[("success", "true"), ("tool", "shell")],
+        );
+        registry.histogram("codex.token_usage", 120, &[("token_type", "input")]);
+        registry.histogram("codex.token_usage", 80, &[("token_type", "input")]);
+
+        assert_eq!(
+            registry.render(),
+            "# TYPE codex_tool_call_total counter\n\
+             codex_tool_call_total{success=\"true\",tool=\"shell\"} 3\n\
+             # TYPE codex_token_usage summary\n\
+             codex_token_usage_sum{token_type=\"input\"} 200\n\
+             codex_token_usage_count{token_type=\"input\"} 2\n"
+        );
+    }
+}
@@ -153,6 +153,14 @@ impl OtelManager {
         error: Option<&str>,
         duration: Duration,
     ) {
+        let status_str = status.map_or_else(|| "none".to_string(), |status| status.to_string());
+        let success_str = if error.is_none() { "true" } else { "false" };
+        let tags = [("status", status_str.as_str()), ("success", success_str)];
+        self.counter("codex.api_request", 1, &tags);
+        self.record_duration("codex.api_request.duration_ms", duration, &tags);
+        if error.is_some() {
+            self.counter("codex.provider.error", 1, &[("stage", "request")]);
+        }
         tracing::event!(
             tracing::Level::INFO,
             event.name = "codex.api_request",
@@ -241,6 +249,7 @@ impl OtelManager {
     where
         T: Display,
     {
+        self.counter("codex.provider.error", 1, &[("stage", "stream")]);
         match kind {
             Some(kind) => tracing::event!(
                 tracing::Level::INFO,
@@ -280,6 +289,7 @@ impl OtelManager {
     where
         T: Display,
     {
+        self.counter("codex.provider.error", 1, &[("stage", "stream")]);
         tracing::event!(
             tracing::Level::INFO,
             event.name = "codex.sse_event",
@@ -305,6 +315,16 @@ impl OtelManager {
         reasoning_token_count: Option<i64>,
         tool_token_count: i64,
     ) {
+        for (token_type, count) in [
+            ("input", Some(input_token_count)),
+            ("output", Some(output_token_count)),
+            ("cached", cached_token_count),
+            ("reasoning", reasoning_token_count),
+        ] {
+            if let Some(count) = count {
+                self.histogram("codex.token_usage", count, &[("token_type", token_type)]);
+            }
+        }
         tracing::event!(
             tracing::Level::INFO,
             event.name = "codex.sse_event",
@@ -447,11 +467,9 @@ impl OtelManager {
         output: &str,
     ) {
         let success_str = if success { "true" } else { "false" };
-        self.counter(
-            "codex.tool.call",
-            1,
-            &[("tool", tool_name), ("success", success_str)],
-        );
+        let tags = [("tool", tool_name), ("success", success_str)];
+        self.counter("codex.tool.call", 1, &tags);
+        self.record_duration("codex.tool.call.duration_ms", duration, &tags);
         tracing::event!(
             tracing::Level::INFO,
             event.name = "codex.tool_result",
@@ -473,6 +491,34 @@ impl OtelManager {
         );
     }
 
+    /// Records the duration of a finished turn. `kind` is the task kind
+    /// (`regular`, `review`, `compact`) and `status` is `completed` or
+    /// `aborted`.
+    pub fn turn_finished(&self, kind: &str, status: &str, duration: Duration) {
+        let tags = [("kind", kind), ("status", status)];
+        self.counter("codex.turn", 1, &tags);
+        self.record_duration("codex.turn.duration_ms", duration, &tags);
+    }
+
+    /// Records one run of a hook.
+    pub fn hook_run(&self, hook: &str, event: &str, success: bool, duration: Duration) {
+        let success_str = if success { "true" } else { "false" };
+        let tags = [("event", event), ("success", success_str)];
+        self.counter("codex.hook.run", 1, &tags);
+        self.record_duration("codex.hook.run.duration_ms", duration, &tags);
+        tracing::event!(
+            tracing::Level::INFO,
+            event.name = "codex.hook_run",
+            event.timestamp = %timestamp(),
+            conversation.id = %self.metadata.conversation_id,
+            app.version = %self.metadata.app_version,
+            hook_name = %hook,
+            hook_event = %event,
+            duration_ms = %duration.as_millis(),
+            success = %success_str,
+        );
+    }
+
     fn responses_type(event: &ResponseEvent) -> String {
         match event {
             ResponseEvent::Created => "created".into(),
@@ -480,6 +526,7 @@ impl OtelManager {
             ResponseEvent::OutputItemAdded(item) => OtelManager::responses_item_type(item),
             ResponseEvent::Completed { .. } => "completed".into(),
             ResponseEvent::OutputTextDelta(_) => "text_delta".into(),
+            ResponseEvent::ToolCallInputDelta { .. } => "tool_call_input_delta".into(),
             ResponseEvent::ReasoningSummaryDelta { .. } => "reasoning_summary_delta".into(),
             ResponseEvent::ReasoningContentDelta { .. } => "reasoning_content_delta".into(),
             ResponseEvent::ReasoningSummaryPartAdded { .. } => {
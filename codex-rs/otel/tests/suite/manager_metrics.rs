@@ -1,6 +1,7 @@
 use crate::harness::attributes_to_map;
 use crate::harness::build_metrics_with_defaults;
 use crate::harness::find_metric;
+use crate::harness::histogram_data;
 use crate::harness::latest_metrics;
 use codex_app_server_protocol::AuthMode;
 use codex_otel::OtelManager;
@@ -11,6 +12,7 @@ use opentelemetry_sdk::metrics::data::AggregatedMetrics;
 use opentelemetry_sdk::metrics::data::MetricData;
 use pretty_assertions::assert_eq;
 use std::collections::BTreeMap;
+use std::time::Duration;
 
 // Ensures OtelManager attaches metadata tags when forwarding metrics.
 #[test]
@@ -102,3 +104,48 @@ fn manager_allows_disabling_metadata_tags() -> Result<()> {
 
     Ok(())
 }
+
+// Ensures turn and hook runs are recorded as counters plus duration histograms.
+#[test]
+fn manager_records_turn_and_hook_runs() -> Result<()> {
+    let (metrics, exporter) = build_metrics_with_defaults(&[])?;
+    let manager = OtelManager::new(
+        ThreadId::new(),
+        "gpt-5.1",
+        "gpt-5.1",
+        None,
+        None,
+        None,
+        false,
+        "tty".to_string(),
+        SessionSource::Cli,
+    )
+    .with_metrics_without_metadata_tags(metrics);
+
+    manager.turn_finished("regular", "completed", Duration::from_millis(1200));
+    manager.hook_run("notify", "task_complete", false, Duration::from_millis(30));
+    manager.shutdown_metrics()?;
+
+    let resource_metrics = latest_metrics(&exporter);
+    let (_, _, sum, count) = histogram_data(&resource_metrics, "codex.turn.duration_ms");
+    assert_eq!((sum, count), (1200.0, 1));
+    let (_, _, sum, count) = histogram_data(&resource_metrics, "codex.hook.run.duration_ms");
+    assert_eq!((sum, count), (30.0, 1));
+
+    let metric = find_metric(&resource_metrics, "codex.hook.run").expect("hook counter missing");
+    let attrs = match metric.data() {
+        AggregatedMetrics::U64(MetricData::Sum(sum)) => {
+            let points: Vec<_> = sum.data_points().collect();
+            assert_eq!(points.len(), 1);
+            attributes_to_map(points[0].attributes())
+        }
+        _ => panic!("unexpected counter data type"),
+    };
+    let expected = BTreeMap::from([
+        ("event".to_string(), "task_complete".to_string()),
+        ("success".to_string(), "false".to_string()),
+    ]);
+    assert_eq!(attrs, expected);
+
+    Ok(())
+}
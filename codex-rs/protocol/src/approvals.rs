@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use crate::parse_command::CommandOperation;
 use crate::parse_command::ParsedCommand;
 use crate::protocol::FileChange;
 use mcp_types::RequestId;
@@ -57,6 +58,10 @@ pub struct ExecApprovalRequestEvent {
     #[ts(optional)]
     pub proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
     pub parsed_cmd: Vec<ParsedCommand>,
+    /// What the command may do, found by parsing every command of the
+    /// invocation, least severe first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub operations: Vec<CommandOperation>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -72,13 +72,23 @@ pub enum ForcedLoginMethod {
 }
 
 /// Represents the trust level for a project directory.
-/// This determines the approval policy and sandbox mode applied.
+/// This determines the approval policy and sandbox mode applied, and whether
+/// the project's own `.codex/` config and `AGENTS.md` are loaded.
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Display, JsonSchema, TS)]
 #[serde(rename_all = "lowercase")]
 #[strum(serialize_all = "lowercase")]
 pub enum TrustLevel {
+    /// Full trust: the project's `.codex/` config, hooks and rules are loaded.
     Trusted,
+    /// Codex may read the project but not write to it.
+    #[serde(rename = "read-only")]
+    #[strum(serialize = "read-only")]
+    ReadOnly,
+    /// Edits and commands need approval.
     Untrusted,
+    /// Read-only, every command needs approval, and the project's `AGENTS.md`
+    /// is ignored as well.
+    Deny,
 }
 
 /// Controls whether the TUI uses the terminal's alternate screen buffer.
@@ -406,6 +406,8 @@ impl From<Vec<UserInput>> for ResponseInputItem {
                         local_image_content_items_with_label_number(&path, Some(image_index))
                     }
                     UserInput::Skill { .. } => Vec::new(), // Skill bodies are injected later in core
+                    UserInput::McpResource { .. } => Vec::new(), // Resource contents are injected later in core
+                    UserInput::McpPrompt { .. } => Vec::new(), // Prompts are expanded earlier in core
                 })
                 .collect::<Vec<ContentItem>>(),
         }
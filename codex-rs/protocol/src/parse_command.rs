@@ -29,3 +29,19 @@ pub enum ParsedCommand {
         cmd: String,
     },
 }
+
+/// A kind of effect a shell command may have, from least to most severe.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize, JsonSchema, TS,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandOperation {
+    /// Only reads files or reports state.
+    ReadOnly,
+    /// May modify files, including commands that could not be classified.
+    Write,
+    /// Talks to other machines.
+    Network,
+    /// Deletes or overwrites data in a way that is hard to undo.
+    Destructive,
+}
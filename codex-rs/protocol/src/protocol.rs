@@ -3,6 +3,7 @@
 //! Uses a SQ (Submission Queue) / EQ (Event Queue) pattern to asynchronously communicate
 //! between user and agent.
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::fmt;
 use std::path::Path;
@@ -13,6 +14,7 @@ use std::time::Duration;
 use crate::ThreadId;
 use crate::approvals::ElicitationRequestEvent;
 use crate::config_types::ReasoningSummary as ReasoningSummaryConfig;
+use crate::config_types::Verbosity;
 use crate::custom_prompts::CustomPrompt;
 use crate::items::TurnItem;
 use crate::message_history::HistoryEntry;
@@ -25,6 +27,7 @@ use crate::plan_tool::UpdatePlanArgs;
 use crate::user_input::UserInput;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use mcp_types::CallToolResult;
+use mcp_types::Prompt as McpPrompt;
 use mcp_types::RequestId;
 use mcp_types::Resource as McpResource;
 use mcp_types::ResourceTemplate as McpResourceTemplate;
@@ -49,6 +52,10 @@ pub const USER_INSTRUCTIONS_OPEN_TAG: &str = "<user_instructions>";
 pub const USER_INSTRUCTIONS_CLOSE_TAG: &str = "</user_instructions>";
 pub const ENVIRONMENT_CONTEXT_OPEN_TAG: &str = "<environment_context>";
 pub const ENVIRONMENT_CONTEXT_CLOSE_TAG: &str = "</environment_context>";
+pub const CHANGED_FILES_OPEN_TAG: &str = "<changed_files>";
+pub const CHANGED_FILES_CLOSE_TAG: &str = "</changed_files>";
+pub const ENVIRONMENT_FINGERPRINT_OPEN_TAG: &str = "<environment_fingerprint>";
+pub const ENVIRONMENT_FINGERPRINT_CLOSE_TAG: &str = "</environment_fingerprint>";
 pub const USER_MESSAGE_BEGIN: &str = "## My request for Codex:";
 
 /// Submission Queue Entry - requests from user
@@ -70,6 +77,12 @@ pub enum Op {
     /// This server sends [`EventMsg::TurnAborted`] in response.
     Interrupt,
 
+    /// Stop the current task like [`Op::Interrupt`], but keep the reply the
+    /// model was streaming in the history, so the next user message picks up
+    /// from it. The server sends [`EventMsg::TurnAborted`] with
+    /// [`TurnAbortReason::Stopped`] in response.
+    Stop,
+
     /// Input from the user
     UserInput {
         /// User input items, see `InputItem`
@@ -142,6 +155,13 @@ pub enum Op {
         /// Updated reasoning summary preference (honored only for reasoning-capable models).
         #[serde(skip_serializing_if = "Option::is_none")]
         summary: Option<ReasoningSummaryConfig>,
+
+        /// Updated output verbosity (honored only for models that support it).
+        ///
+        /// Same convention as `effort`: `Some(None)` falls back to the model's
+        /// default.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        verbosity: Option<Option<Verbosity>>,
     },
 
     /// Approve a command execution
@@ -160,6 +180,11 @@ pub enum Op {
         decision: ReviewDecision,
     },
 
+    /// Run commands that start with `prefix` without asking for approval for
+    /// the rest of this session. For `bash -lc` scripts, every command in the
+    /// script must start with an allowed prefix.
+    AllowCommandPrefix { prefix: Vec<String> },
+
     /// Resolve an MCP elicitation request.
     ResolveElicitation {
         /// Name of the MCP server that issued the request.
@@ -189,6 +214,27 @@ pub enum Op {
     /// Request the list of available custom prompts.
     ListCustomPrompts,
 
+    /// Request the registered hooks and their recent firings.
+    /// Reply is delivered via `EventMsg::ListHooksResponse`.
+    ListHooks,
+
+    /// Enable or disable an external hook for the rest of this session.
+    /// Reply is delivered via `EventMsg::ListHooksResponse`.
+    SetHookEnabled { name: String, enabled: bool },
+
+    /// Report an invocation of the user-defined slash command `/name`, so the
+    /// hooks subscribed to `user_command` run for it.
+    /// Reply is delivered via `EventMsg::UserCommand`.
+    RunUserCommand { name: String, arguments: String },
+
+    /// Set conversation variables, substituted for `{{name}}` in user-defined
+    /// slash commands, custom prompts and hook command arguments. A `null`
+    /// value clears the variable.
+    /// Reply is delivered via `EventMsg::VariablesUpdated`.
+    SetVariables {
+        variables: BTreeMap<String, Option<String>>,
+    },
+
     /// Request the list of skills for the provided `cwd` values or the session default.
     ListSkills {
         /// Working directories to scope repo skills discovery.
@@ -547,6 +593,14 @@ pub enum EventMsg {
     /// Conversation history was compacted (either automatically or manually).
     ContextCompacted(ContextCompactedEvent),
 
+    /// The model rejected a request for exceeding its context window and the
+    /// request was retried with less context.
+    ContextRepaired(ContextRepairedEvent),
+
+    /// How much of each tool output the turn includes, chosen from the room
+    /// left in the context window.
+    OutputBudget(OutputBudgetEvent),
+
     /// Conversation history was rolled back by dropping the last N user turns.
     ThreadRolledBack(ThreadRolledBackEvent),
 
@@ -590,12 +644,27 @@ pub enum EventMsg {
     /// Ack the client's configure message.
     SessionConfigured(SessionConfiguredEvent),
 
+    /// The model, approval policy or sandbox policy of the session changed
+    /// at runtime.
+    ConfigOverrideApplied(ConfigOverrideAppliedEvent),
+
+    /// The model, reasoning effort, reasoning summary or verbosity used for
+    /// the next turns changed at runtime.
+    ModelSettingsChanged(ModelSettingsChangedEvent),
+
     /// Incremental MCP startup progress updates.
     McpStartupUpdate(McpStartupUpdateEvent),
 
     /// Aggregate MCP startup completion summary.
     McpStartupComplete(McpStartupCompleteEvent),
 
+    /// A running MCP server crashed, closed its connection, or stopped
+    /// answering pings.
+    McpServerDown(McpServerDownEvent),
+
+    /// An MCP server that went down was restarted successfully.
+    McpServerRestarted(McpServerRestartedEvent),
+
     McpToolCallBegin(McpToolCallBeginEvent),
 
     McpToolCallEnd(McpToolCallEndEvent),
@@ -618,6 +687,44 @@ pub enum EventMsg {
     /// Notification that the agent attached a local image via the view_image tool.
     ViewImageToolCall(ViewImageToolCallEvent),
 
+    /// Secrets were replaced in a tool's output before it was sent to the
+    /// model or written to the rollout.
+    RedactionApplied(RedactionAppliedEvent),
+
+    /// A tool's output looked like a prompt injection and was flagged or had
+    /// the suspicious lines removed before it was sent to the model.
+    InjectionSuspected(InjectionSuspectedEvent),
+
+    /// The files, web pages or MCP servers that an item entering the context
+    /// was read from.
+    ContextSources(ContextSourcesEvent),
+
+    /// A guardrail stopped the turn, e.g. because the same tool kept failing.
+    GuardrailTripped(GuardrailTrippedEvent),
+
+    /// A turn, the session or an approval request ran past its time limit.
+    WatchdogFired(WatchdogFiredEvent),
+
+    /// AGENTS.md files changed during the session and the model was given
+    /// the updated instructions.
+    ProjectDocsUpdated(ProjectDocsUpdatedEvent),
+
+    /// Files the agent read or edited were changed outside the session
+    /// between turns, and the model was told to re-read them.
+    WatchedFilesChanged(WatchedFilesChangedEvent),
+
+    /// The session's conversation variables changed.
+    VariablesUpdated(VariablesUpdatedEvent),
+
+    /// The machine the session runs on was probed (OS, toolchains, git,
+    /// container) when the session started or resumed, and the model was
+    /// given a summary.
+    EnvironmentFingerprint(EnvironmentFingerprintEvent),
+
+    /// A workspace outside any git repository was snapshotted before the
+    /// turn's tools ran, so the turn can be rolled back.
+    WorkspaceSnapshot(WorkspaceSnapshotEvent),
+
     ExecApprovalRequest(ExecApprovalRequestEvent),
 
     ElicitationRequest(ElicitationRequestEvent),
@@ -634,6 +741,10 @@ pub enum EventMsg {
 
     UndoCompleted(UndoCompletedEvent),
 
+    /// The task's changes were committed on a new branch and, when possible,
+    /// a draft pull request was opened for them.
+    PullRequestPublished(PullRequestPublishedEvent),
+
     /// Notification that a model stream experienced an error or disconnect
     /// and the system is handling it (e.g., retrying with backoff).
     StreamError(StreamErrorEvent),
@@ -659,6 +770,12 @@ pub enum EventMsg {
     /// List of skills available to the agent.
     ListSkillsResponse(ListSkillsResponseEvent),
 
+    /// Registered hooks and their recent firings.
+    ListHooksResponse(ListHooksResponseEvent),
+
+    /// The user invoked a user-defined slash command.
+    UserCommand(UserCommandEvent),
+
     /// Notification that skill data may have been updated and clients may want to reload.
     SkillsUpdateAvailable,
 
@@ -727,6 +844,9 @@ pub enum CodexErrorInfo {
     ResponseStreamDisconnected {
         http_status_code: Option<u16>,
     },
+    /// The response SSE stream delivered data that could not be parsed; the
+    /// request is retried.
+    ResponseStreamMalformed,
     /// Reached the retry limit for responses.
     ResponseTooManyFailedAttempts {
         http_status_code: Option<u16>,
@@ -874,6 +994,97 @@ pub struct ContextCompactedEvent;
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct TurnCompleteEvent {
     pub last_agent_message: Option<String>,
+    /// Where the time in the turn went. Absent in rollouts recorded before
+    /// timing was tracked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timing: Option<TurnTiming>,
+    /// Unified diff of the files the agent edited during the turn. Absent
+    /// when the turn changed no files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unified_diff: Option<String>,
+    /// Outcome of the configured verification command. Absent when none is
+    /// configured or the turn changed no files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification: Option<VerificationStatus>,
+    /// Content left out of the turn's last model request to keep each source
+    /// within its `[context_budget]`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context_dropped: Vec<DroppedContext>,
+}
+
+/// Kind of content a `[context_budget]` limit applies to.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextSource {
+    /// AGENTS.md instructions.
+    ProjectDocs,
+    /// Output of tool calls.
+    ToolOutputs,
+    /// Skills and MCP resources attached to a message.
+    Pinned,
+    /// User and assistant messages.
+    History,
+}
+
+/// What was trimmed from one source to fit its budget.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct DroppedContext {
+    pub source: ContextSource,
+    /// Items shortened or left out.
+    pub items: u32,
+    /// Approximate number of tokens removed.
+    pub tokens: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ContextRepairedEvent {
+    /// Content left out of the retried request.
+    pub dropped: Vec<DroppedContext>,
+    /// Whether the conversation was compacted into a summary to make room.
+    pub compacted: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct OutputBudgetEvent {
+    /// Approximate tokens left in the model's context window when the turn
+    /// started.
+    pub remaining_tokens: i64,
+    /// The model's usual limit on each tool output.
+    pub default_policy: TruncationPolicy,
+    /// The limit on each tool output for this turn.
+    pub policy: TruncationPolicy,
+    /// Whether long command output is condensed to the lines reporting
+    /// errors or warnings and its last lines.
+    pub summarize: bool,
+}
+
+/// Result of the last verification run of a turn.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct VerificationStatus {
+    pub command: String,
+    pub passed: bool,
+    pub exit_code: i32,
+    /// How many times the command ran during the turn.
+    pub runs: u32,
+}
+
+/// Per-turn timing breakdown. Durations are in milliseconds.
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct TurnTiming {
+    /// From the turn being submitted until its first model request was sent.
+    pub queue_ms: u64,
+    /// From the first model request being sent until its first output arrived.
+    pub first_token_ms: Option<u64>,
+    /// Time spent streaming model responses, summed across requests.
+    pub model_ms: u64,
+    /// Time spent running tool calls, summed across calls. Calls that run in
+    /// parallel or while the model is still streaming are each counted in
+    /// full, so `model_ms + tool_ms` can exceed `total_ms`.
+    pub tool_ms: u64,
+    /// From the turn being submitted until it completed.
+    pub total_ms: u64,
+    /// Number of model requests sent, including retries and follow-ups.
+    pub model_requests: u32,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -1301,6 +1512,10 @@ pub struct SessionMeta {
     #[serde(default)]
     pub source: SessionSource,
     pub model_provider: Option<String>,
+    /// Labels attached when the session was started, such as the issue it
+    /// was opened for (`issue:github.com/owner/repo#123`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
 }
 
 impl Default for SessionMeta {
@@ -1314,6 +1529,7 @@ impl Default for SessionMeta {
             instructions: None,
             source: SessionSource::default(),
             model_provider: None,
+            labels: Vec::new(),
         }
     }
 }
@@ -1333,6 +1549,7 @@ pub enum RolloutItem {
     ResponseItem(ResponseItem),
     Compacted(CompactedItem),
     TurnContext(TurnContextItem),
+    TurnDiff(TurnDiffItem),
     EventMsg(EventMsg),
 }
 
@@ -1355,6 +1572,14 @@ impl From<CompactedItem> for ResponseItem {
     }
 }
 
+/// The changes a turn made to files, recorded once it finishes.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, TS)]
+pub struct TurnDiffItem {
+    /// Id of the submission that started the turn.
+    pub turn_id: String,
+    pub unified_diff: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, TS)]
 pub struct TurnContextItem {
     pub cwd: PathBuf,
@@ -1390,7 +1615,7 @@ pub struct RolloutLine {
     pub item: RolloutItem,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, TS)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, TS)]
 pub struct GitInfo {
     /// Current commit hash (SHA)
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -1576,6 +1801,179 @@ pub struct ViewImageToolCallEvent {
     pub path: PathBuf,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct RedactionAppliedEvent {
+    /// Identifier of the tool call whose output was redacted.
+    pub call_id: String,
+    /// Number of replacements made by each redaction rule.
+    pub redactions: Vec<RedactionCount>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct InjectionSuspectedEvent {
+    /// Identifier of the tool call whose output was screened.
+    pub call_id: String,
+    /// Heuristics that matched (e.g. `ignore_instructions`) and the
+    /// classifier's reason, if it objected.
+    pub reasons: Vec<String>,
+    /// Whether suspicious lines were removed rather than only flagged.
+    pub stripped: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ContextSourcesEvent {
+    /// Identifier of the tool call or web search whose output the sources
+    /// contributed to; absent for the session's instructions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub call_id: Option<String>,
+    pub sources: Vec<ContentSource>,
+}
+
+/// Where a piece of context the model was given came from.
+#[derive(
+    Debug, Clone, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash, JsonSchema, TS,
+)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentSource {
+    /// A local file or directory.
+    File { path: PathBuf },
+    /// An instructions file such as `AGENTS.md`.
+    Instructions { path: PathBuf },
+    /// A web page opened by web search.
+    Url { url: String },
+    /// A resource read from an MCP server.
+    McpResource { server: String, uri: String },
+    /// The result of an MCP tool call.
+    McpTool { server: String, tool: String },
+}
+
+/// The form redaction rules match against: the path of a file, the URL of a
+/// page, `mcp:<server>:<uri>` for a resource and `mcp:<server>.<tool>` for a
+/// tool.
+impl fmt::Display for ContentSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContentSource::File { path } | ContentSource::Instructions { path } => {
+                write!(f, "{}", path.display())
+            }
+            ContentSource::Url { url } => f.write_str(url),
+            ContentSource::McpResource { server, uri } => write!(f, "mcp:{server}:{uri}"),
+            ContentSource::McpTool { server, tool } => write!(f, "mcp:{server}.{tool}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct VariablesUpdatedEvent {
+    /// Every variable of the session after the change, by name.
+    pub variables: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct EnvironmentFingerprintEvent {
+    /// Operating system family, e.g. `linux` or `macos`.
+    pub os: String,
+    /// Operating system release, when it could be determined.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub os_version: Option<String>,
+    /// CPU architecture, e.g. `x86_64` or `aarch64`.
+    pub arch: String,
+    /// Versions of the toolchains found on `PATH`, by command name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub toolchains: BTreeMap<String, String>,
+    /// Repository the working directory belongs to, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<GitInfo>,
+    /// Container runtime the session appears to run in, e.g. `docker`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct GuardrailTrippedEvent {
+    /// Name of the guardrail, e.g. `tool_failure_breaker`.
+    pub guardrail: String,
+    /// Tool whose calls kept failing: a program name such as `cargo`,
+    /// `apply_patch`, or `server/tool` for MCP tools.
+    pub tool: String,
+    /// Consecutive failed calls that tripped the guardrail.
+    pub failures: u32,
+    /// Explanation shown to the user and recorded in the conversation.
+    pub message: String,
+}
+
+/// What a watchdog keeps a time limit on.
+#[derive(Debug, Clone, Copy, Display, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum WatchdogKind {
+    /// A single turn, from the user's input to the agent's final answer.
+    Turn,
+    /// The whole session, from its start.
+    Session,
+    /// A pending approval request.
+    Approval,
+}
+
+/// What a watchdog does when its time limit runs out.
+#[derive(
+    Debug, Clone, Copy, Default, Display, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum WatchdogAction {
+    /// Abort the running turn. A session watchdog also refuses new turns.
+    #[default]
+    Abort,
+    /// Only emit the `watchdog_fired` event and keep going.
+    Notify,
+    /// Deny the pending request. Only valid for the approval watchdog.
+    Deny,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct WatchdogFiredEvent {
+    pub watchdog: WatchdogKind,
+    pub action: WatchdogAction,
+    /// The time limit that ran out, in seconds.
+    pub limit_secs: u64,
+    /// Explanation shown to the user.
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ProjectDocsUpdatedEvent {
+    /// Project docs now in effect, ordered from the repository root to the
+    /// working directory.
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct WatchedFilesChangedEvent {
+    /// Files whose size or modification time changed.
+    pub changed: Vec<PathBuf>,
+    /// Files that no longer exist.
+    pub removed: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct WorkspaceSnapshotEvent {
+    /// Id of the snapshot under `CODEX_HOME/workspace_snapshots`.
+    pub snapshot_id: String,
+    /// Directory the snapshot covers.
+    pub cwd: PathBuf,
+    /// Number of files captured.
+    pub files: usize,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct RedactionCount {
+    /// Name of the rule, e.g. `aws_access_key_id` or a `[redaction.patterns]` key.
+    pub rule: String,
+    pub count: u32,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecOutputStream {
@@ -1635,6 +2033,16 @@ pub struct UndoCompletedEvent {
     pub message: Option<String>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct PullRequestPublishedEvent {
+    pub branch: String,
+    pub commit: String,
+    /// URL of the draft pull request; `None` when the branch was pushed but no
+    /// pull request could be opened.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ThreadRolledBackEvent {
     /// Number of user turns that were removed from context.
@@ -1713,6 +2121,9 @@ pub struct McpListToolsResponseEvent {
     pub resources: std::collections::HashMap<String, Vec<McpResource>>,
     /// Known resource templates grouped by server name.
     pub resource_templates: std::collections::HashMap<String, Vec<McpResourceTemplate>>,
+    /// Known prompts grouped by server name.
+    #[serde(default)]
+    pub prompts: std::collections::HashMap<String, Vec<McpPrompt>>,
     /// Authentication status for each configured MCP server.
     pub auth_statuses: std::collections::HashMap<String, McpAuthStatus>,
 }
@@ -1740,6 +2151,27 @@ pub struct McpStartupCompleteEvent {
     pub ready: Vec<String>,
     pub failed: Vec<McpStartupFailure>,
     pub cancelled: Vec<String>,
+    /// Servers whose tools were loaded from the schema cache. They are started
+    /// the first time one of their tools or resources is used.
+    #[serde(default)]
+    pub deferred: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct McpServerDownEvent {
+    pub server: String,
+    /// Why the server is considered down.
+    pub reason: String,
+    /// Whether Codex will try to restart the server. False once all restart
+    /// attempts have been exhausted.
+    pub restarting: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct McpServerRestartedEvent {
+    pub server: String,
+    /// Number of restart attempts it took to bring the server back.
+    pub attempts: u32,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -1774,6 +2206,67 @@ impl fmt::Display for McpAuthStatus {
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ListCustomPromptsResponseEvent {
     pub custom_prompts: Vec<CustomPrompt>,
+    /// User-defined slash commands from `$CODEX_HOME/commands` and the
+    /// `[commands]` table in `config.toml`.
+    #[serde(default)]
+    pub custom_commands: Vec<CustomPrompt>,
+}
+
+/// Response payload for `Op::ListHooks` and `Op::SetHookEnabled`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ListHooksResponseEvent {
+    pub hooks: Vec<HookSummary>,
+    /// Most recent firings, oldest first.
+    pub recent_firings: Vec<HookFiring>,
+}
+
+/// Payload for `EventMsg::UserCommand`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct UserCommandEvent {
+    /// Command name, without the leading `/`.
+    pub name: String,
+    /// Everything typed after the command name, trimmed.
+    pub arguments: String,
+}
+
+/// Where a hook was registered from.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum HookSource {
+    /// Shipped with Codex.
+    BuiltIn,
+    /// Configured by the user, e.g. under `[hooks.commands]`.
+    External,
+    /// Required by an administrator in `requirements.toml`.
+    Managed,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct HookSummary {
+    pub name: String,
+    /// Event names that fire the hook; `"*"` matches every event.
+    pub events: Vec<String>,
+    pub source: HookSource,
+    pub enabled: bool,
+    /// Hooks with a higher priority run first.
+    #[serde(default)]
+    pub priority: i32,
+    /// Whether the session waits for the hook, which can then veto the event.
+    #[serde(default)]
+    pub blocking: bool,
+}
+
+/// One completed run of a hook.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct HookFiring {
+    pub hook: String,
+    /// Name of the event that fired the hook.
+    pub event: String,
+    /// RFC 3339 timestamp of when the hook started.
+    pub started_at: String,
+    pub duration_ms: u64,
+    /// Why the hook failed, if it did.
+    pub error: Option<String>,
 }
 
 /// Response payload for `Op::ListSkills`.
@@ -1854,6 +2347,34 @@ pub struct SessionConfiguredEvent {
     pub rollout_path: PathBuf,
 }
 
+/// Policies changed by an `Op::OverrideTurnContext`. Only the settings whose
+/// value changed are set; model changes are reported by
+/// [`ModelSettingsChangedEvent`] instead.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct ConfigOverrideAppliedEvent {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approval_policy: Option<AskForApproval>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sandbox_policy: Option<SandboxPolicy>,
+}
+
+/// The model settings in effect after an `Op::OverrideTurnContext` changed
+/// any of them. Unlike [`ConfigOverrideAppliedEvent`], every field carries
+/// the current value, not only the ones that changed.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct ModelSettingsChangedEvent {
+    pub model: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effort: Option<ReasoningEffortConfig>,
+
+    pub summary: ReasoningSummaryConfig,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verbosity: Option<Verbosity>,
+}
+
 /// User's decision in response to an ExecApprovalRequest.
 #[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq, Eq, Display, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
@@ -1872,6 +2393,10 @@ pub enum ReviewDecision {
     /// remainder of the session.
     ApprovedForSession,
 
+    /// User has approved part of a proposed patch. Every hunk except the ones
+    /// listed is applied, and the rejected hunks are reported to the model.
+    ApprovedHunks { rejected_hunks: Vec<RejectedHunk> },
+
     /// User has denied this command and the agent should not execute it, but
     /// it should continue the session and try something else.
     #[default]
@@ -1890,12 +2415,22 @@ impl ReviewDecision {
             ReviewDecision::Approved => "approved",
             ReviewDecision::ApprovedExecpolicyAmendment { .. } => "approved_with_amendment",
             ReviewDecision::ApprovedForSession => "approved_for_session",
+            ReviewDecision::ApprovedHunks { .. } => "approved_hunks",
             ReviewDecision::Denied => "denied",
             ReviewDecision::Abort => "abort",
         }
     }
 }
 
+/// A hunk of a proposed patch that the user chose not to apply.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct RejectedHunk {
+    pub path: PathBuf,
+    /// Position of the hunk within the file's [`FileChange`]: the n-th `@@`
+    /// hunk of an update's unified diff, or 0 for an added or deleted file.
+    pub hunk_index: usize,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[ts(tag = "type")]
@@ -1923,14 +2458,39 @@ pub struct Chunk {
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct TurnAbortedEvent {
     pub reason: TurnAbortReason,
+    /// Work that was still running when the turn was aborted and was
+    /// cancelled with it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cancelled: Vec<CancelledOperation>,
+}
+
+/// In-flight work cancelled by an aborted turn.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CancelledOperation {
+    /// A model response that was still streaming.
+    ModelStream,
+    /// A tool call, such as a shell command or a patch.
+    ToolCall { call_id: String, tool_name: String },
+    /// A tool call to an MCP server.
+    McpToolCall {
+        call_id: String,
+        server: String,
+        tool: String,
+    },
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 pub enum TurnAbortReason {
     Interrupted,
+    /// The user stopped the turn with [`Op::Stop`]; its partial output was
+    /// kept.
+    Stopped,
     Replaced,
     ReviewEnded,
+    /// A watchdog's time limit ran out.
+    TimedOut,
 }
 
 #[cfg(test)]
@@ -2138,6 +2698,7 @@ mod tests {
                     error: "bad".to_string(),
                 }],
                 cancelled: vec!["c".to_string()],
+                deferred: vec!["d".to_string()],
             }),
         };
 
@@ -2147,6 +2708,7 @@ mod tests {
         assert_eq!(value["msg"]["failed"][0]["server"], "b");
         assert_eq!(value["msg"]["failed"][0]["error"], "bad");
         assert_eq!(value["msg"]["cancelled"][0], "c");
+        assert_eq!(value["msg"]["deferred"][0], "d");
         Ok(())
     }
 }
@@ -27,4 +27,21 @@ pub enum UserInput {
         name: String,
         path: std::path::PathBuf,
     },
+
+    /// MCP resource attached by the user (server name + resource URI). The
+    /// resource contents are read from the server and injected in core.
+    McpResource {
+        server: String,
+        uri: String,
+    },
+
+    /// MCP prompt invoked by the user (server name + prompt name + arguments).
+    /// The prompt is rendered via `prompts/get` in core and its messages
+    /// replace this item in the turn input.
+    McpPrompt {
+        server: String,
+        name: String,
+        #[serde(default)]
+        arguments: std::collections::HashMap<String, String>,
+    },
 }
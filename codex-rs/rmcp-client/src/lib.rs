@@ -22,5 +22,9 @@ pub use perform_oauth_login::perform_oauth_login_return_url;
 pub use rmcp::model::ElicitationAction;
 pub use rmcp_client::Elicitation;
 pub use rmcp_client::ElicitationResponse;
+pub use rmcp_client::ListRoots;
 pub use rmcp_client::RmcpClient;
+pub use rmcp_client::Sampling;
+pub use rmcp_client::SamplingResponse;
 pub use rmcp_client::SendElicitation;
+pub use rmcp_client::SendSampling;
@@ -6,6 +6,10 @@ use rmcp::model::CancelledNotificationParam;
 use rmcp::model::ClientInfo;
 use rmcp::model::CreateElicitationRequestParam;
 use rmcp::model::CreateElicitationResult;
+use rmcp::model::CreateMessageRequestMethod;
+use rmcp::model::CreateMessageRequestParam;
+use rmcp::model::CreateMessageResult;
+use rmcp::model::ListRootsResult;
 use rmcp::model::LoggingLevel;
 use rmcp::model::LoggingMessageNotificationParam;
 use rmcp::model::ProgressNotificationParam;
@@ -18,38 +22,89 @@ use tracing::error;
 use tracing::info;
 use tracing::warn;
 
+use crate::rmcp_client::ListRoots;
 use crate::rmcp_client::SendElicitation;
+use crate::rmcp_client::SendSampling;
+use crate::utils::convert_to_mcp;
+use crate::utils::convert_to_rmcp;
 
 #[derive(Clone)]
 pub(crate) struct LoggingClientHandler {
     client_info: ClientInfo,
     send_elicitation: Arc<SendElicitation>,
+    send_sampling: Option<Arc<SendSampling>>,
+    list_roots: Option<Arc<ListRoots>>,
 }
 
 impl LoggingClientHandler {
-    pub(crate) fn new(client_info: ClientInfo, send_elicitation: SendElicitation) -> Self {
+    pub(crate) fn new(
+        client_info: ClientInfo,
+        send_elicitation: SendElicitation,
+        send_sampling: Option<SendSampling>,
+        list_roots: Option<ListRoots>,
+    ) -> Self {
         Self {
             client_info,
             send_elicitation: Arc::new(send_elicitation),
+            send_sampling: send_sampling.map(Arc::new),
+            list_roots: list_roots.map(Arc::new),
         }
     }
 }
 
+fn to_mcp_request_id(id: RequestId) -> mcp_types::RequestId {
+    match id {
+        RequestId::String(id) => mcp_types::RequestId::String(id.to_string()),
+        RequestId::Number(id) => mcp_types::RequestId::Integer(id),
+    }
+}
+
 impl ClientHandler for LoggingClientHandler {
     async fn create_elicitation(
         &self,
         request: CreateElicitationRequestParam,
         context: RequestContext<RoleClient>,
     ) -> Result<CreateElicitationResult, rmcp::ErrorData> {
-        let id = match context.id {
-            RequestId::String(id) => mcp_types::RequestId::String(id.to_string()),
-            RequestId::Number(id) => mcp_types::RequestId::Integer(id),
-        };
+        let id = to_mcp_request_id(context.id);
         (self.send_elicitation)(id, request)
             .await
             .map_err(|err| rmcp::ErrorData::internal_error(err.to_string(), None))
     }
 
+    async fn create_message(
+        &self,
+        params: CreateMessageRequestParam,
+        context: RequestContext<RoleClient>,
+    ) -> Result<CreateMessageResult, rmcp::ErrorData> {
+        let Some(send_sampling) = &self.send_sampling else {
+            return Err(rmcp::ErrorData::method_not_found::<
+                CreateMessageRequestMethod,
+            >());
+        };
+        let id = to_mcp_request_id(context.id);
+        let params = convert_to_mcp(params)
+            .map_err(|err| rmcp::ErrorData::invalid_params(err.to_string(), None))?;
+        let result = send_sampling(id, params)
+            .await
+            .map_err(|err| rmcp::ErrorData::internal_error(err.to_string(), None))?;
+        convert_to_rmcp(result)
+            .map_err(|err| rmcp::ErrorData::internal_error(err.to_string(), None))
+    }
+
+    async fn list_roots(
+        &self,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<ListRootsResult, rmcp::ErrorData> {
+        let Some(list_roots) = &self.list_roots else {
+            return Ok(ListRootsResult::default());
+        };
+        let result = mcp_types::ListRootsResult {
+            roots: list_roots(),
+        };
+        convert_to_rmcp(result)
+            .map_err(|err| rmcp::ErrorData::internal_error(err.to_string(), None))
+    }
+
     async fn on_cancelled(
         &self,
         params: CancelledNotificationParam,
@@ -26,6 +26,7 @@ use oauth2::Scope;
 use oauth2::TokenResponse;
 use oauth2::basic::BasicTokenType;
 use rmcp::transport::auth::OAuthTokenResponse;
+use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value;
@@ -63,7 +64,7 @@ pub struct StoredOAuthTokens {
 }
 
 /// Determine where Codex should store and read MCP credentials.
-#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum OAuthCredentialsStoreMode {
     /// `Keyring` when available; otherwise, `File`.
@@ -102,7 +103,7 @@ pub(crate) fn load_oauth_tokens(
         }
         OAuthCredentialsStoreMode::File => load_oauth_tokens_from_file(server_name, url),
         OAuthCredentialsStoreMode::Keyring => {
-            load_oauth_tokens_from_keyring(&keyring_store, server_name, url)
+            load_oauth_tokens_from_keyring_or_migrate(&keyring_store, server_name, url)
                 .with_context(|| "failed to read OAuth tokens from keyring".to_string())
         }
     }
@@ -139,7 +140,13 @@ fn load_oauth_tokens_from_keyring_with_fallback_to_file<K: KeyringStore>(
 ) -> Result<Option<StoredOAuthTokens>> {
     match load_oauth_tokens_from_keyring(keyring_store, server_name, url) {
         Ok(Some(tokens)) => Ok(Some(tokens)),
-        Ok(None) => load_oauth_tokens_from_file(server_name, url),
+        Ok(None) => match migrate_oauth_tokens_from_file(keyring_store, server_name, url) {
+            Ok(tokens) => Ok(tokens),
+            Err(error) => {
+                warn!("failed to move OAuth tokens into keyring: {error}");
+                load_oauth_tokens_from_file(server_name, url)
+            }
+        },
         Err(error) => {
             warn!("failed to read OAuth tokens from keyring: {error}");
             load_oauth_tokens_from_file(server_name, url)
@@ -148,6 +155,32 @@ fn load_oauth_tokens_from_keyring_with_fallback_to_file<K: KeyringStore>(
     }
 }
 
+fn load_oauth_tokens_from_keyring_or_migrate<K: KeyringStore>(
+    keyring_store: &K,
+    server_name: &str,
+    url: &str,
+) -> Result<Option<StoredOAuthTokens>> {
+    match load_oauth_tokens_from_keyring(keyring_store, server_name, url)? {
+        Some(tokens) => Ok(Some(tokens)),
+        None => migrate_oauth_tokens_from_file(keyring_store, server_name, url),
+    }
+}
+
+/// Moves the server's tokens from the plaintext fallback file, where they may
+/// have been written before the keyring was used, into the keyring.
+fn migrate_oauth_tokens_from_file<K: KeyringStore>(
+    keyring_store: &K,
+    server_name: &str,
+    url: &str,
+) -> Result<Option<StoredOAuthTokens>> {
+    let Some(tokens) = load_oauth_tokens_from_file(server_name, url)? else {
+        return Ok(None);
+    };
+    // Saving to the keyring also removes the entry from the fallback file.
+    save_oauth_tokens_with_keyring(keyring_store, server_name, &tokens)?;
+    Ok(Some(tokens))
+}
+
 fn load_oauth_tokens_from_keyring<K: KeyringStore>(
     keyring_store: &K,
     server_name: &str,
@@ -680,6 +713,27 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn load_oauth_tokens_moves_fallback_tokens_into_keyring() -> Result<()> {
+        let _env = TempCodexHome::new();
+        let store = MockKeyringStore::default();
+        let tokens = sample_tokens();
+        let key = super::compute_store_key(&tokens.server_name, &tokens.url)?;
+
+        super::save_oauth_tokens_to_file(&tokens)?;
+
+        let loaded = super::load_oauth_tokens_from_keyring_or_migrate(
+            &store,
+            &tokens.server_name,
+            &tokens.url,
+        )?
+        .expect("tokens should be migrated from fallback");
+        assert_tokens_match_without_expiry(&loaded, &tokens);
+        assert!(store.saved_value(&key).is_some());
+        assert!(super::load_oauth_tokens_from_file(&tokens.server_name, &tokens.url)?.is_none());
+        Ok(())
+    }
+
     #[test]
     fn load_oauth_tokens_falls_back_when_keyring_errors() -> Result<()> {
         let _env = TempCodexHome::new();
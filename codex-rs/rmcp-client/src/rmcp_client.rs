@@ -12,8 +12,14 @@ use futures::FutureExt;
 use futures::future::BoxFuture;
 use mcp_types::CallToolRequestParams;
 use mcp_types::CallToolResult;
+use mcp_types::CreateMessageRequestParams;
+use mcp_types::CreateMessageResult;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
 use mcp_types::InitializeRequestParams;
 use mcp_types::InitializeResult;
+use mcp_types::ListPromptsRequestParams;
+use mcp_types::ListPromptsResult;
 use mcp_types::ListResourceTemplatesRequestParams;
 use mcp_types::ListResourceTemplatesResult;
 use mcp_types::ListResourcesRequestParams;
@@ -23,8 +29,12 @@ use mcp_types::ListToolsResult;
 use mcp_types::ReadResourceRequestParams;
 use mcp_types::ReadResourceResult;
 use mcp_types::RequestId;
+use mcp_types::Root;
 use reqwest::header::HeaderMap;
+use rmcp::model::CallToolRequest;
 use rmcp::model::CallToolRequestParam;
+use rmcp::model::CallToolResult as RmcpCallToolResult;
+use rmcp::model::CancelledNotificationParam;
 use rmcp::model::ClientNotification;
 use rmcp::model::ClientRequest;
 use rmcp::model::CreateElicitationRequestParam;
@@ -32,12 +42,17 @@ use rmcp::model::CreateElicitationResult;
 use rmcp::model::CustomNotification;
 use rmcp::model::CustomRequest;
 use rmcp::model::Extensions;
+use rmcp::model::GetPromptRequestParam;
 use rmcp::model::InitializeRequestParam;
 use rmcp::model::PaginatedRequestParam;
+use rmcp::model::PingRequest;
 use rmcp::model::ReadResourceRequestParam;
 use rmcp::model::ServerResult;
+use rmcp::service::Peer;
+use rmcp::service::PeerRequestOptions;
 use rmcp::service::RoleClient;
 use rmcp::service::RunningService;
+use rmcp::service::ServiceError;
 use rmcp::service::{self};
 use rmcp::transport::StreamableHttpClientTransport;
 use rmcp::transport::auth::AuthClient;
@@ -95,6 +110,18 @@ pub type SendElicitation = Box<
     dyn Fn(RequestId, Elicitation) -> BoxFuture<'static, Result<ElicitationResponse>> + Send + Sync,
 >;
 
+pub type Sampling = CreateMessageRequestParams;
+pub type SamplingResponse = CreateMessageResult;
+
+/// Interface for serving `sampling/createMessage` requests with the client's
+/// model and awaiting the completion.
+pub type SendSampling =
+    Box<dyn Fn(RequestId, Sampling) -> BoxFuture<'static, Result<SamplingResponse>> + Send + Sync>;
+
+/// Interface for answering `roots/list` requests with the client's current
+/// workspace roots.
+pub type ListRoots = Box<dyn Fn() -> Vec<Root> + Send + Sync>;
+
 /// MCP client implemented on top of the official `rmcp` SDK.
 /// https://github.com/modelcontextprotocol/rust-sdk
 pub struct RmcpClient {
@@ -166,8 +193,16 @@ impl RmcpClient {
         http_headers: Option<HashMap<String, String>>,
         env_http_headers: Option<HashMap<String, String>>,
         store_mode: OAuthCredentialsStoreMode,
+        proxy: Option<reqwest::Proxy>,
     ) -> Result<Self> {
         let default_headers = build_default_headers(http_headers, env_http_headers)?;
+        let client_builder = || {
+            let builder = reqwest::Client::builder();
+            match proxy.clone() {
+                Some(proxy) => builder.proxy(proxy),
+                None => builder,
+            }
+        };
 
         let initial_oauth_tokens = match bearer_token {
             Some(_) => None,
@@ -187,6 +222,7 @@ impl RmcpClient {
                 initial_tokens,
                 store_mode,
                 default_headers.clone(),
+                client_builder(),
             )
             .await?;
             PendingTransport::StreamableHttpWithOAuth {
@@ -199,8 +235,7 @@ impl RmcpClient {
                 http_config = http_config.auth_header(bearer_token);
             }
 
-            let http_client =
-                apply_default_headers(reqwest::Client::builder(), &default_headers).build()?;
+            let http_client = apply_default_headers(client_builder(), &default_headers).build()?;
 
             let transport = StreamableHttpClientTransport::with_client(http_client, http_config);
             PendingTransport::StreamableHttp { transport }
@@ -219,9 +254,12 @@ impl RmcpClient {
         params: InitializeRequestParams,
         timeout: Option<Duration>,
         send_elicitation: SendElicitation,
+        send_sampling: Option<SendSampling>,
+        list_roots: Option<ListRoots>,
     ) -> Result<InitializeResult> {
         let rmcp_params: InitializeRequestParam = convert_to_rmcp(params.clone())?;
-        let client_handler = LoggingClientHandler::new(rmcp_params, send_elicitation);
+        let client_handler =
+            LoggingClientHandler::new(rmcp_params, send_elicitation, send_sampling, list_roots);
 
         let (transport, oauth_persistor) = {
             let mut guard = self.state.lock().await;
@@ -350,6 +388,39 @@ impl RmcpClient {
         Ok(converted)
     }
 
+    pub async fn list_prompts(
+        &self,
+        params: Option<ListPromptsRequestParams>,
+        timeout: Option<Duration>,
+    ) -> Result<ListPromptsResult> {
+        self.refresh_oauth_if_needed().await;
+        let service = self.service().await?;
+        let rmcp_params = params
+            .map(convert_to_rmcp::<_, PaginatedRequestParam>)
+            .transpose()?;
+
+        let fut = service.list_prompts(rmcp_params);
+        let result = run_with_timeout(fut, timeout, "prompts/list").await?;
+        let converted = convert_to_mcp(result)?;
+        self.persist_oauth_tokens().await;
+        Ok(converted)
+    }
+
+    pub async fn get_prompt(
+        &self,
+        params: GetPromptRequestParams,
+        timeout: Option<Duration>,
+    ) -> Result<GetPromptResult> {
+        self.refresh_oauth_if_needed().await;
+        let service = self.service().await?;
+        let rmcp_params: GetPromptRequestParam = convert_to_rmcp(params)?;
+        let fut = service.get_prompt(rmcp_params);
+        let result = run_with_timeout(fut, timeout, "prompts/get").await?;
+        let converted = convert_to_mcp(result)?;
+        self.persist_oauth_tokens().await;
+        Ok(converted)
+    }
+
     pub async fn call_tool(
         &self,
         name: String,
@@ -360,13 +431,39 @@ impl RmcpClient {
         let service = self.service().await?;
         let params = CallToolRequestParams { arguments, name };
         let rmcp_params: CallToolRequestParam = convert_to_rmcp(params)?;
-        let fut = service.call_tool(rmcp_params);
+        let fut = call_tool_cancel_on_drop(service.peer().clone(), rmcp_params);
         let rmcp_result = run_with_timeout(fut, timeout, "tools/call").await?;
         let converted = convert_call_tool_result(rmcp_result)?;
         self.persist_oauth_tokens().await;
         Ok(converted)
     }
 
+    /// Sends an MCP `ping` request and waits for the (empty) response.
+    pub async fn ping(&self, timeout: Option<Duration>) -> Result<()> {
+        let service = self.service().await?;
+        let fut = service.send_request(ClientRequest::PingRequest(PingRequest::default()));
+        run_with_timeout(fut, timeout, "ping").await?;
+        Ok(())
+    }
+
+    /// Returns true once the connection to the server has been torn down, for
+    /// example because a stdio server process exited. Clients that have not
+    /// finished the handshake are not considered closed.
+    pub async fn is_transport_closed(&self) -> bool {
+        let guard = self.state.lock().await;
+        match &*guard {
+            ClientState::Ready { service, .. } => service.peer().is_transport_closed(),
+            ClientState::Connecting { .. } => false,
+        }
+    }
+
+    /// Tells the server that the result of `roots/list` has changed.
+    pub async fn notify_roots_list_changed(&self) -> Result<()> {
+        let service = self.service().await?;
+        service.peer().notify_roots_list_changed().await?;
+        Ok(())
+    }
+
     pub async fn send_custom_notification(
         &self,
         method: &str,
@@ -435,18 +532,71 @@ impl RmcpClient {
     }
 }
 
+/// Sends `tools/call` and waits for the result. If the returned future is
+/// dropped first, because the turn was interrupted or the call timed out, the
+/// server is sent `notifications/cancelled` so it can stop the work too.
+async fn call_tool_cancel_on_drop(
+    peer: Peer<RoleClient>,
+    params: CallToolRequestParam,
+) -> std::result::Result<RmcpCallToolResult, ServiceError> {
+    let request = ClientRequest::CallToolRequest(CallToolRequest {
+        method: Default::default(),
+        params,
+        extensions: Extensions::new(),
+    });
+    let handle = peer
+        .send_cancellable_request(request, PeerRequestOptions::no_options())
+        .await?;
+    let mut cancel_guard = CancelOnDrop {
+        peer: Some(peer),
+        request_id: handle.id.clone(),
+    };
+    let response = handle.await_response().await;
+    cancel_guard.peer = None;
+    match response? {
+        ServerResult::CallToolResult(result) => Ok(result),
+        _ => Err(ServiceError::UnexpectedResponse),
+    }
+}
+
+/// Cancels an outstanding request when dropped while `peer` is set.
+struct CancelOnDrop {
+    peer: Option<Peer<RoleClient>>,
+    request_id: rmcp::model::RequestId,
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        let Some(peer) = self.peer.take() else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let params = CancelledNotificationParam {
+            request_id: self.request_id.clone(),
+            reason: Some("cancelled by the client".to_string()),
+        };
+        runtime.spawn(async move {
+            if let Err(err) = peer.notify_cancelled(params).await {
+                warn!("failed to send MCP cancellation: {err}");
+            }
+        });
+    }
+}
+
 async fn create_oauth_transport_and_runtime(
     server_name: &str,
     url: &str,
     initial_tokens: StoredOAuthTokens,
     credentials_store: OAuthCredentialsStoreMode,
     default_headers: HeaderMap,
+    client_builder: reqwest::ClientBuilder,
 ) -> Result<(
     StreamableHttpClientTransport<AuthClient<reqwest::Client>>,
     OAuthPersistor,
 )> {
-    let http_client =
-        apply_default_headers(reqwest::Client::builder(), &default_headers).build()?;
+    let http_client = apply_default_headers(client_builder, &default_headers).build()?;
     let mut oauth_state = OAuthState::new(url.to_string(), Some(http_client.clone())).await?;
 
     oauth_state
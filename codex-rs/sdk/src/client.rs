@@ -0,0 +1,196 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use codex_core::AuthManager;
+use codex_core::ThreadManager;
+use codex_core::config::Config;
+use codex_core::config::ConfigBuilder;
+use codex_core::config::ConfigOverrides;
+use codex_core::config::find_codex_home;
+use codex_core::hooks::ALL_EVENTS;
+use codex_core::hooks::HookHandler;
+use codex_core::hooks::HookRegistration;
+use codex_protocol::config_types::SandboxMode;
+use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::SessionSource;
+
+use crate::conversation::Conversation;
+use crate::error::Error;
+use crate::error::Result;
+
+/// Starts and resumes conversations that share one configuration.
+pub struct Client {
+    thread_manager: ThreadManager,
+    auth_manager: Arc<AuthManager>,
+    config: Config,
+}
+
+impl Client {
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    /// Starts a new conversation.
+    pub async fn start_conversation(&self) -> Result<Conversation> {
+        let thread = self
+            .thread_manager
+            .start_thread(self.config.clone())
+            .await?;
+        Ok(Conversation::new(thread))
+    }
+
+    /// Resumes the conversation recorded at `rollout_path` (see
+    /// [`Conversation::rollout_path`]).
+    pub async fn resume_conversation(
+        &self,
+        rollout_path: impl Into<PathBuf>,
+    ) -> Result<Conversation> {
+        let thread = self
+            .thread_manager
+            .resume_thread_from_rollout(
+                self.config.clone(),
+                rollout_path.into(),
+                Arc::clone(&self.auth_manager),
+            )
+            .await?;
+        Ok(Conversation::new(thread))
+    }
+}
+
+/// Builder for [`Client`]. Settings not given here come from `config.toml`
+/// in the Codex home directory, as they would for the CLI.
+#[derive(Default)]
+pub struct ClientBuilder {
+    codex_home: Option<PathBuf>,
+    overrides: ConfigOverrides,
+    hooks: Vec<Hook>,
+}
+
+impl ClientBuilder {
+    /// Directory holding `config.toml`, credentials and session rollouts.
+    /// Defaults to `$CODEX_HOME`, or `~/.codex`.
+    pub fn codex_home(mut self, codex_home: impl Into<PathBuf>) -> Self {
+        self.codex_home = Some(codex_home.into());
+        self
+    }
+
+    /// Directory the agent works in. Defaults to the current directory.
+    pub fn cwd(mut self, cwd: impl Into<PathBuf>) -> Self {
+        self.overrides.cwd = Some(cwd.into());
+        self
+    }
+
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.overrides.model = Some(model.into());
+        self
+    }
+
+    /// Key of the provider to use, from `model_providers` or a built-in one
+    /// such as `"openai"` or `"mock"`.
+    pub fn model_provider(mut self, model_provider: impl Into<String>) -> Self {
+        self.overrides.model_provider = Some(model_provider.into());
+        self
+    }
+
+    /// Profile from `config.toml` to apply.
+    pub fn profile(mut self, profile: impl Into<String>) -> Self {
+        self.overrides.config_profile = Some(profile.into());
+        self
+    }
+
+    pub fn approval_policy(mut self, approval_policy: AskForApproval) -> Self {
+        self.overrides.approval_policy = Some(approval_policy);
+        self
+    }
+
+    pub fn sandbox_mode(mut self, sandbox_mode: SandboxMode) -> Self {
+        self.overrides.sandbox_mode = Some(sandbox_mode);
+        self
+    }
+
+    /// Extra instructions sent to the model as a developer message.
+    pub fn developer_instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.overrides.developer_instructions = Some(instructions.into());
+        self
+    }
+
+    /// Runs `hook` in every conversation started by the client.
+    pub fn hook(mut self, hook: Hook) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+
+    /// Runs each of `hooks` in every conversation started by the client.
+    pub fn hooks(mut self, hooks: impl IntoIterator<Item = Hook>) -> Self {
+        self.hooks.extend(hooks);
+        self
+    }
+
+    pub async fn build(self) -> Result<Client> {
+        let codex_home = match self.codex_home {
+            Some(codex_home) => codex_home,
+            None => find_codex_home().map_err(Error::Config)?,
+        };
+        let config = ConfigBuilder::default()
+            .codex_home(codex_home)
+            .harness_overrides(self.overrides)
+            .build()
+            .await
+            .map_err(Error::Config)?;
+
+        let auth_manager = AuthManager::shared(
+            config.codex_home.clone(),
+            true,
+            config.cli_auth_credentials_store_mode,
+        );
+        let thread_manager = ThreadManager::new(
+            config.codex_home.clone(),
+            Arc::clone(&auth_manager),
+            SessionSource::Exec,
+        );
+        for hook in self.hooks {
+            thread_manager.register_hook(hook.registration);
+        }
+
+        Ok(Client {
+            thread_manager,
+            auth_manager,
+            config,
+        })
+    }
+}
+
+/// A [`HookHandler`] together with the events it runs on. Hooks registered
+/// through the SDK run alongside those in `config.toml` and cannot be turned
+/// off from `/hooks`.
+pub struct Hook {
+    registration: HookRegistration,
+}
+
+impl Hook {
+    /// Creates a hook named `name` that runs on every event.
+    pub fn new(name: impl Into<String>, handler: impl HookHandler + 'static) -> Self {
+        Self::from_arc(name, Arc::new(handler))
+    }
+
+    /// Like [`Hook::new`], for a handler the caller keeps a reference to.
+    pub fn from_arc(name: impl Into<String>, handler: Arc<dyn HookHandler>) -> Self {
+        Self {
+            registration: HookRegistration {
+                name: name.into(),
+                events: vec![ALL_EVENTS.to_string()],
+                handler,
+            },
+        }
+    }
+
+    /// Limits the hook to the named events, such as `"task_complete"`.
+    pub fn on<I, S>(mut self, events: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.registration.events = events.into_iter().map(Into::into).collect();
+        self
+    }
+}
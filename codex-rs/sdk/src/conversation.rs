@@ -0,0 +1,252 @@
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use codex_core::CodexThread;
+use codex_core::NewThread;
+use codex_core::hooks::EventFilter;
+use codex_core::hooks::HookEvent;
+use codex_core::interceptors::Interceptor;
+use codex_protocol::ThreadId;
+use codex_protocol::config_types::ReasoningSummary;
+use codex_protocol::config_types::Verbosity;
+use codex_protocol::openai_models::ReasoningEffort;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::Op;
+use codex_protocol::protocol::ReviewDecision;
+use codex_protocol::user_input::UserInput;
+use futures::Stream;
+
+use crate::error::Error;
+use crate::error::Result;
+
+/// A conversation with the agent. Turns run one at a time: the
+/// [`TurnStream`] returned by [`Conversation::send`] borrows the
+/// conversation until it is dropped.
+pub struct Conversation {
+    id: ThreadId,
+    model: String,
+    rollout_path: PathBuf,
+    thread: Arc<CodexThread>,
+}
+
+impl Conversation {
+    pub(crate) fn new(new_thread: NewThread) -> Self {
+        let NewThread {
+            thread_id,
+            thread,
+            session_configured,
+        } = new_thread;
+        Self {
+            id: thread_id,
+            model: session_configured.model,
+            rollout_path: session_configured.rollout_path,
+            thread,
+        }
+    }
+
+    pub fn id(&self) -> ThreadId {
+        self.id
+    }
+
+    /// The model the conversation started with.
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Where the conversation is recorded; pass it to
+    /// [`Client::resume_conversation`](crate::Client::resume_conversation)
+    /// to continue later.
+    pub fn rollout_path(&self) -> &Path {
+        &self.rollout_path
+    }
+
+    /// Streams the conversation's events matching `filter`, across turns,
+    /// without borrowing the conversation. Useful for observers such as
+    /// progress panels that run alongside the code driving the turns.
+    pub fn subscribe(&self, filter: EventFilter) -> impl Stream<Item = HookEvent> + Send + 'static {
+        self.thread.subscribe(filter)
+    }
+
+    /// Registers `interceptor` under `name`, replacing any interceptor with
+    /// that name. Unlike hooks, interceptors are awaited inline and can
+    /// rewrite prompts or end turns; they apply from the next model request.
+    pub fn intercept(&self, name: impl Into<String>, interceptor: impl Interceptor + 'static) {
+        self.thread
+            .register_interceptor(name, Arc::new(interceptor));
+    }
+
+    /// Removes the interceptor registered under `name`, returning whether
+    /// there was one.
+    pub fn remove_interceptor(&self, name: &str) -> bool {
+        self.thread.unregister_interceptor(name)
+    }
+
+    /// Sends `text` as the next user message and streams the turn it starts.
+    pub async fn send(&mut self, text: impl Into<String>) -> Result<TurnStream<'_>> {
+        self.send_input(vec![UserInput::Text { text: text.into() }])
+            .await
+    }
+
+    /// Like [`Conversation::send`], for input that includes images.
+    pub async fn send_input(&mut self, items: Vec<UserInput>) -> Result<TurnStream<'_>> {
+        let submission_id = self
+            .thread
+            .submit(Op::UserInput {
+                items,
+                final_output_json_schema: None,
+            })
+            .await?;
+        Ok(TurnStream {
+            thread: &self.thread,
+            submission_id,
+            finished: false,
+        })
+    }
+
+    /// Changes the model settings used from the next turn on, without
+    /// restarting the conversation. If anything changed, the next turn's
+    /// events start with a `model_settings_changed` event carrying the
+    /// settings now in effect.
+    pub async fn update_model_settings(&self, settings: ModelSettings) -> Result<()> {
+        let ModelSettings {
+            model,
+            effort,
+            summary,
+            verbosity,
+        } = settings;
+        self.thread
+            .submit(Op::OverrideTurnContext {
+                cwd: None,
+                approval_policy: None,
+                sandbox_policy: None,
+                model,
+                effort,
+                summary,
+                verbosity,
+            })
+            .await?;
+        Ok(())
+    }
+
+    /// Shuts the conversation down, waiting for in-flight work to stop.
+    pub async fn shutdown(self) -> Result<()> {
+        self.thread.submit(Op::Shutdown).await?;
+        loop {
+            let event = self.thread.next_event().await?;
+            if matches!(event.msg, EventMsg::ShutdownComplete) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Model settings to change with [`Conversation::update_model_settings`].
+/// Settings that are not set keep their current value.
+#[derive(Debug, Clone, Default)]
+pub struct ModelSettings {
+    model: Option<String>,
+    effort: Option<Option<ReasoningEffort>>,
+    summary: Option<ReasoningSummary>,
+    verbosity: Option<Option<Verbosity>>,
+}
+
+impl ModelSettings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Switches to another model.
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// Reasoning effort for models that support it; `None` uses the model's
+    /// default.
+    pub fn effort(mut self, effort: Option<ReasoningEffort>) -> Self {
+        self.effort = Some(effort);
+        self
+    }
+
+    /// Reasoning summary preference for models that support it.
+    pub fn summary(mut self, summary: ReasoningSummary) -> Self {
+        self.summary = Some(summary);
+        self
+    }
+
+    /// Output verbosity for models that support it; `None` uses the model's
+    /// default.
+    pub fn verbosity(mut self, verbosity: Option<Verbosity>) -> Self {
+        self.verbosity = Some(verbosity);
+        self
+    }
+}
+
+/// The events of one turn, ending with its `task_complete` or
+/// `turn_aborted` event. Under an approval policy that asks, the turn waits
+/// at each `exec_approval_request` and `apply_patch_approval_request` event
+/// until it is answered with [`TurnStream::respond`].
+pub struct TurnStream<'a> {
+    thread: &'a CodexThread,
+    submission_id: String,
+    finished: bool,
+}
+
+impl TurnStream<'_> {
+    /// Waits for the next event, or returns `None` once the turn has ended.
+    pub async fn next(&mut self) -> Option<Result<Event>> {
+        if self.finished {
+            return None;
+        }
+        match self.thread.next_event().await {
+            Ok(event) => {
+                self.finished = matches!(event.msg, EventMsg::ShutdownComplete)
+                    || (event.id == self.submission_id
+                        && matches!(
+                            event.msg,
+                            EventMsg::TurnComplete(_) | EventMsg::TurnAborted(_)
+                        ));
+                Some(Ok(event))
+            }
+            Err(err) => {
+                self.finished = true;
+                Some(Err(err.into()))
+            }
+        }
+    }
+
+    /// Asks the agent to stop the turn. The stream still yields the
+    /// remaining events, ending with `turn_aborted`.
+    pub async fn interrupt(&self) -> Result<()> {
+        self.thread.submit(Op::Interrupt).await?;
+        Ok(())
+    }
+
+    /// Answers `request`, an `exec_approval_request` or
+    /// `apply_patch_approval_request` event of this turn.
+    pub async fn respond(&self, request: &Event, decision: ReviewDecision) -> Result<()> {
+        let id = request.id.clone();
+        let op = match request.msg {
+            EventMsg::ExecApprovalRequest(_) => Op::ExecApproval { id, decision },
+            EventMsg::ApplyPatchApprovalRequest(_) => Op::PatchApproval { id, decision },
+            _ => return Err(Error::NotAnApprovalRequest),
+        };
+        self.thread.submit(op).await?;
+        Ok(())
+    }
+
+    /// Drains the turn and returns the agent's final message.
+    pub async fn final_message(mut self) -> Result<Option<String>> {
+        let mut last_agent_message = None;
+        while let Some(event) = self.next().await {
+            match event?.msg {
+                EventMsg::TurnComplete(event) => last_agent_message = event.last_agent_message,
+                EventMsg::TurnAborted(event) => return Err(Error::TurnAborted(event.reason)),
+                _ => {}
+            }
+        }
+        Ok(last_agent_message)
+    }
+}
@@ -0,0 +1,32 @@
+use codex_core::error::CodexErr;
+use codex_protocol::protocol::TurnAbortReason;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors returned by the SDK.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// `config.toml` or the builder settings could not be loaded.
+    #[error("failed to load configuration: {0}")]
+    Config(#[source] std::io::Error),
+
+    /// The session failed or has shut down.
+    #[error(transparent)]
+    Session(Box<dyn std::error::Error + Send + Sync>),
+
+    /// The turn ended without completing, e.g. because it was interrupted.
+    #[error("turn aborted: {0:?}")]
+    TurnAborted(TurnAbortReason),
+
+    /// [`TurnStream::respond`](crate::TurnStream::respond) was given an event
+    /// that does not ask for approval.
+    #[error("event is not an approval request")]
+    NotAnApprovalRequest,
+}
+
+impl From<CodexErr> for Error {
+    fn from(err: CodexErr) -> Self {
+        Self::Session(Box::new(err))
+    }
+}
@@ -0,0 +1,88 @@
+//! Embed Codex in a Rust application.
+//!
+//! `codex-sdk` is a small, documented facade over `codex-core`. Build a
+//! [`Client`], start a [`Conversation`] and stream the events of each turn:
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), codex_sdk::Error> {
+//! use codex_sdk::Client;
+//! use codex_sdk::protocol::EventMsg;
+//!
+//! let client = Client::builder().model("gpt-5.1-codex").build().await?;
+//! let mut conversation = client.start_conversation().await?;
+//!
+//! let mut turn = conversation.send("Summarize the README").await?;
+//! while let Some(event) = turn.next().await {
+//!     if let EventMsg::AgentMessage(message) = event?.msg {
+//!         println!("{}", message.message);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Stability
+//!
+//! Everything defined in this crate follows semver: breaking changes to
+//! [`Client`], [`ClientBuilder`], [`Conversation`], [`ModelSettings`],
+//! [`TurnStream`], [`Hook`] and [`Error`] only happen in a new major version,
+//! and so do breaking changes to the hook and interceptor types re-exported
+//! below, which this crate's API is expressed in. The event types in
+//! [`protocol`] are re-exported from `codex-protocol`; new event variants and
+//! fields may be added in minor versions, so match on them with a wildcard
+//! arm. Nothing else from `codex-core` is part of the API, and depending on it
+//! directly carries no such guarantee.
+
+mod client;
+mod conversation;
+mod error;
+
+pub use client::Client;
+pub use client::ClientBuilder;
+pub use client::Hook;
+pub use conversation::Conversation;
+pub use conversation::ModelSettings;
+pub use conversation::TurnStream;
+pub use error::Error;
+pub use error::Result;
+
+/// Implemented by [`Hook`] handlers. Implementations use [`async_trait`].
+pub use codex_core::hooks::HookHandler;
+pub use codex_core::hooks::HookIsolation;
+
+/// Used with [`Conversation::subscribe`].
+pub use codex_core::hooks::EventExpr;
+pub use codex_core::hooks::EventFilter;
+pub use codex_core::hooks::FilterError;
+pub use codex_core::hooks::HookEvent;
+pub use codex_core::hooks::LagPolicy;
+
+/// Used with [`Conversation::intercept`]. Implementations use
+/// [`async_trait`].
+pub use codex_core::interceptors::Interception;
+pub use codex_core::interceptors::Interceptor;
+pub use codex_core::interceptors::TurnInfo;
+
+/// The model request an [`Interceptor`] may rewrite.
+pub use codex_core::Prompt;
+
+pub use async_trait::async_trait;
+
+/// Session events and the types they carry.
+pub mod protocol {
+    pub use codex_protocol::ThreadId;
+    pub use codex_protocol::config_types::ReasoningSummary;
+    pub use codex_protocol::config_types::SandboxMode;
+    pub use codex_protocol::config_types::Verbosity;
+    pub use codex_protocol::models::ContentItem;
+    pub use codex_protocol::models::ResponseItem;
+    pub use codex_protocol::openai_models::ReasoningEffort;
+    pub use codex_protocol::protocol::ApplyPatchApprovalRequestEvent;
+    pub use codex_protocol::protocol::AskForApproval;
+    pub use codex_protocol::protocol::Event;
+    pub use codex_protocol::protocol::EventMsg;
+    pub use codex_protocol::protocol::ExecApprovalRequestEvent;
+    pub use codex_protocol::protocol::ReviewDecision;
+    pub use codex_protocol::protocol::TurnAbortReason;
+    pub use codex_protocol::user_input::UserInput;
+}
@@ -0,0 +1,239 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use codex_core::hooks::testing::RecordingHooks;
+use codex_sdk::Client;
+use codex_sdk::EventFilter;
+use codex_sdk::Hook;
+use codex_sdk::Interception;
+use codex_sdk::Interceptor;
+use codex_sdk::ModelSettings;
+use codex_sdk::Prompt;
+use codex_sdk::TurnInfo;
+use codex_sdk::async_trait;
+use codex_sdk::protocol::AskForApproval;
+use codex_sdk::protocol::EventMsg;
+use codex_sdk::protocol::ReasoningEffort;
+use codex_sdk::protocol::ReviewDecision;
+use codex_sdk::protocol::Verbosity;
+use futures::StreamExt;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn conversation_streams_a_scripted_turn_to_hooks_and_caller() -> anyhow::Result<()> {
+    let codex_home = tempfile::tempdir()?;
+    let fixture = codex_home.path().join("fixture.json");
+    std::fs::write(
+        &fixture,
+        json!({
+            "responses": [[
+                {
+                    "type": "response.output_item.done",
+                    "item": {
+                        "type": "message",
+                        "role": "assistant",
+                        "content": [{"type": "output_text", "text": "Hello from the fixture"}]
+                    }
+                },
+                {"type": "response.completed", "response": {"id": "resp-1"}}
+            ]]
+        })
+        .to_string(),
+    )?;
+    std::fs::write(
+        codex_home.path().join("config.toml"),
+        format!(
+            "[mock_provider]\nfixture = {:?}\n",
+            fixture.display().to_string()
+        ),
+    )?;
+
+    let recorder = RecordingHooks::new();
+    let client = Client::builder()
+        .codex_home(codex_home.path())
+        .cwd(codex_home.path())
+        .model_provider("mock")
+        .hook(
+            Hook::from_arc("recorder", Arc::clone(&recorder)).on(["task_started", "task_complete"]),
+        )
+        .build()
+        .await?;
+    let mut conversation = client.start_conversation().await?;
+    let mut completions = Box::pin(conversation.subscribe(EventFilter::events(["task_complete"])));
+
+    let mut turn = conversation.send("Say hello").await?;
+    let mut agent_messages = Vec::new();
+    while let Some(event) = turn.next().await {
+        if let EventMsg::AgentMessage(message) = event?.msg {
+            agent_messages.push(message.message);
+        }
+    }
+    assert_eq!(agent_messages, vec!["Hello from the fixture".to_string()]);
+
+    recorder
+        .wait_for("task_complete", Duration::from_secs(5))
+        .await?;
+    recorder.assert_order(&["task_started", "task_complete"]);
+    let completed = tokio::time::timeout(Duration::from_secs(5), completions.next()).await?;
+    assert_eq!(
+        completed.map(|event| event.name),
+        Some("task_complete".to_string())
+    );
+
+    conversation.shutdown().await?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn approval_requests_are_answered_through_the_turn() -> anyhow::Result<()> {
+    let codex_home = tempfile::tempdir()?;
+    let fixture = codex_home.path().join("fixture.json");
+    std::fs::write(
+        &fixture,
+        json!({
+            "responses": [
+                [
+                    {
+                        "type": "response.output_item.done",
+                        "item": {
+                            "type": "function_call",
+                            "call_id": "call-1",
+                            "name": "shell",
+                            "arguments": json!({"command": ["touch", "denied.txt"]}).to_string()
+                        }
+                    },
+                    {"type": "response.completed", "response": {"id": "resp-1"}}
+                ],
+                [
+                    {
+                        "type": "response.output_item.done",
+                        "item": {
+                            "type": "message",
+                            "role": "assistant",
+                            "content": [{"type": "output_text", "text": "Skipped it"}]
+                        }
+                    },
+                    {"type": "response.completed", "response": {"id": "resp-2"}}
+                ]
+            ]
+        })
+        .to_string(),
+    )?;
+    std::fs::write(
+        codex_home.path().join("config.toml"),
+        format!(
+            "[mock_provider]\nfixture = {:?}\n",
+            fixture.display().to_string()
+        ),
+    )?;
+    let client = Client::builder()
+        .codex_home(codex_home.path())
+        .cwd(codex_home.path())
+        .model_provider("mock")
+        .approval_policy(AskForApproval::UnlessTrusted)
+        .build()
+        .await?;
+    let mut conversation = client.start_conversation().await?;
+
+    let mut turn = conversation.send("Create a file").await?;
+    let mut requests = 0;
+    let mut final_message = None;
+    while let Some(event) = turn.next().await {
+        let event = event?;
+        match &event.msg {
+            EventMsg::ExecApprovalRequest(_) => {
+                requests += 1;
+                turn.respond(&event, ReviewDecision::Denied).await?;
+            }
+            EventMsg::AgentMessage(_) => {
+                assert!(
+                    turn.respond(&event, ReviewDecision::Approved)
+                        .await
+                        .is_err()
+                );
+            }
+            EventMsg::TurnComplete(complete) => {
+                final_message = complete.last_agent_message.clone();
+            }
+            _ => {}
+        }
+    }
+
+    assert_eq!(requests, 1);
+    assert_eq!(final_message, Some("Skipped it".to_string()));
+    assert!(!codex_home.path().join("denied.txt").exists());
+    conversation.shutdown().await?;
+    Ok(())
+}
+
+struct BlockEverything;
+
+#[async_trait]
+impl Interceptor for BlockEverything {
+    async fn before_request(&self, _turn: &TurnInfo, _prompt: &mut Prompt) -> Interception {
+        Interception::EndTurn {
+            message: Some("blocked by policy".to_string()),
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn interceptors_can_end_a_turn_before_the_model_is_called() -> anyhow::Result<()> {
+    let codex_home = tempfile::tempdir()?;
+    // No fixture is configured, so reaching the mock provider would fail the
+    // turn.
+    let client = Client::builder()
+        .codex_home(codex_home.path())
+        .cwd(codex_home.path())
+        .model_provider("mock")
+        .build()
+        .await?;
+    let mut conversation = client.start_conversation().await?;
+    conversation.intercept("block", BlockEverything);
+
+    let final_message = conversation
+        .send("Say hello")
+        .await?
+        .final_message()
+        .await?;
+
+    assert_eq!(final_message, Some("blocked by policy".to_string()));
+    conversation.shutdown().await?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn model_settings_changes_are_reported_before_the_next_turn() -> anyhow::Result<()> {
+    let codex_home = tempfile::tempdir()?;
+    let client = Client::builder()
+        .codex_home(codex_home.path())
+        .cwd(codex_home.path())
+        .model_provider("mock")
+        .build()
+        .await?;
+    let mut conversation = client.start_conversation().await?;
+    conversation.intercept("block", BlockEverything);
+
+    conversation
+        .update_model_settings(
+            ModelSettings::new()
+                .effort(Some(ReasoningEffort::High))
+                .verbosity(Some(Verbosity::Low)),
+        )
+        .await?;
+    let mut turn = conversation.send("Say hello").await?;
+    let mut changed = None;
+    while let Some(event) = turn.next().await {
+        if let EventMsg::ModelSettingsChanged(event) = event?.msg {
+            changed = Some(event);
+        }
+    }
+
+    let changed = changed.expect("expected a model_settings_changed event");
+    assert_eq!(changed.effort, Some(ReasoningEffort::High));
+    assert_eq!(changed.verbosity, Some(Verbosity::Low));
+    assert_eq!(changed.model, conversation.model());
+    conversation.shutdown().await?;
+    Ok(())
+}
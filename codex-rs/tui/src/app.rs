@@ -9,18 +9,30 @@ use crate::bottom_pane::ApprovalRequest;
 use crate::chatwidget::ChatWidget;
 use crate::chatwidget::ExternalEditorState;
 use crate::diff_render::DiffSummary;
+use crate::event_inspector::EventLog;
 use crate::exec_command::strip_bash_lc_and_escape;
 use crate::external_editor;
 use crate::file_search::FileSearchManager;
 use crate::history_cell;
 use crate::history_cell::HistoryCell;
+use crate::keybindings::KeyAction;
 use crate::model_migration::ModelMigrationOutcome;
 use crate::model_migration::migration_copy_for_models;
 use crate::model_migration::run_model_migration_prompt;
+use crate::notifications::DesktopNotificationBackend;
+use crate::notifications::DesktopNotifier;
 use crate::pager_overlay::Overlay;
 use crate::render::highlight::highlight_bash_to_lines;
 use crate::render::renderable::Renderable;
 use crate::resume_picker::ResumeSelection;
+use crate::status_export::ExportedStatus;
+use crate::status_export::StatusExporter;
+use crate::tabs;
+use crate::tabs::ParkedTab;
+use crate::tabs::TabId;
+use crate::tabs::Tabs;
+use crate::theme;
+use crate::theme::Theme;
 use crate::tui;
 use crate::tui::TuiEvent;
 use crate::update_action::UpdateAction;
@@ -51,6 +63,7 @@ use color_eyre::eyre::WrapErr;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
+use ratatui::layout::Rect;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::widgets::Paragraph;
@@ -299,14 +312,22 @@ pub(crate) struct App {
 
     pub(crate) transcript_cells: Vec<Arc<dyn HistoryCell>>,
 
+    /// Open conversation tabs. `chat_widget` and `transcript_cells` belong to
+    /// the active one.
+    pub(crate) tabs: Tabs,
+
     // Pager overlay state (Transcript or Static like Diff)
     pub(crate) overlay: Option<Overlay>,
     pub(crate) deferred_history_lines: Vec<Line<'static>>,
     has_emitted_history_lines: bool,
 
+    /// Recent agent events, replayed by the event inspector overlay.
+    pub(crate) event_log: EventLog,
+
     pub(crate) enhanced_keys_supported: bool,
 
-    /// Controls the animation thread that sends CommitTick events.
+    /// Controls the animation thread that sends CommitTick events; it runs
+    /// while any tab is animating (see [`Tabs::any_commit_animating`]).
     pub(crate) commit_anim_running: Arc<AtomicBool>,
 
     // Esc-backtracking state grouped
@@ -321,6 +342,9 @@ pub(crate) struct App {
 
     // One-shot suppression of the next world-writable scan after user confirmation.
     skip_world_writable_scan_once: bool,
+
+    /// Publishes tab state for multiplexer status bars (`[tui.status_export]`).
+    status_exporter: Option<StatusExporter>,
 }
 
 impl App {
@@ -330,6 +354,181 @@ impl App {
             self.chat_widget.submit_op(Op::Shutdown);
             self.server.remove_thread(&thread_id).await;
         }
+        // The replacement widget starts idle; don't keep ticking for this one.
+        self.tabs.set_commit_animating(self.tabs.active_id(), false);
+        self.update_commit_animation();
+    }
+
+    /// Runs the CommitTick thread while any tab is streaming and stops it
+    /// once none is.
+    fn update_commit_animation(&self) {
+        if !self.tabs.any_commit_animating() {
+            self.commit_anim_running.store(false, Ordering::Release);
+            return;
+        }
+        if self
+            .commit_anim_running
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+        {
+            let tx = self.app_event_tx.clone();
+            let running = self.commit_anim_running.clone();
+            thread::spawn(move || {
+                while running.load(Ordering::Relaxed) {
+                    thread::sleep(Duration::from_millis(50));
+                    tx.send(AppEvent::CommitTick);
+                }
+            });
+        }
+    }
+
+    fn open_new_tab(&mut self, tui: &mut tui::Tui) {
+        let init = crate::chatwidget::ChatWidgetInit {
+            config: self.config.clone(),
+            frame_requester: tui.frame_requester(),
+            app_event_tx: self.app_event_tx.for_tab(self.tabs.next_id()),
+            initial_prompt: None,
+            initial_images: Vec::new(),
+            enhanced_keys_supported: self.enhanced_keys_supported,
+            auth_manager: self.auth_manager.clone(),
+            models_manager: self.server.get_models_manager(),
+            feedback: self.feedback.clone(),
+            is_first_run: false,
+            model: self.current_model.clone(),
+        };
+        let chat_widget = ChatWidget::new(init, self.server.clone());
+        let previous = self.install_tab(ParkedTab::new(chat_widget, Vec::new()));
+        self.tabs.open(previous);
+        self.show_active_tab(tui, Vec::new());
+    }
+
+    fn switch_tab(&mut self, tui: &mut tui::Tui, id: TabId) {
+        if id == self.tabs.active_id() {
+            return;
+        }
+        let Some(mut next) = self.tabs.take_parked(id) else {
+            return;
+        };
+        let deferred_events = std::mem::take(&mut next.deferred_events);
+        let previous = self.install_tab(next);
+        self.tabs.activate(id, previous);
+        self.show_active_tab(tui, deferred_events);
+    }
+
+    async fn close_active_tab(&mut self, tui: &mut tui::Tui) {
+        let Some(mut next) = self.tabs.close_active() else {
+            return;
+        };
+        let deferred_events = std::mem::take(&mut next.deferred_events);
+        let closed = self.install_tab(next);
+        self.shutdown_tab(closed).await;
+        self.update_commit_animation();
+        self.show_active_tab(tui, deferred_events);
+    }
+
+    fn export_status(&mut self) {
+        let Some(exporter) = self.status_exporter.as_mut() else {
+            return;
+        };
+        let session_id = self.chat_widget.thread_id().map(|id| id.to_string());
+        exporter.update(ExportedStatus::from_tabs(
+            &self.tabs.summaries(&self.transcript_cells),
+            session_id,
+            &self.config.cwd,
+        ));
+    }
+
+    /// Puts `next` on screen, returning the state of the tab it replaces.
+    fn install_tab(&mut self, next: ParkedTab) -> ParkedTab {
+        self.backtrack = BacktrackState::default();
+        ParkedTab::new(
+            std::mem::replace(&mut self.chat_widget, next.chat_widget),
+            std::mem::replace(&mut self.transcript_cells, next.transcript_cells),
+        )
+    }
+
+    /// Reprints the newly active tab's transcript under a divider and replays
+    /// the events it received while parked.
+    fn show_active_tab(&mut self, tui: &mut tui::Tui, deferred_events: Vec<AppEvent>) {
+        if let Some(summary) = self
+            .tabs
+            .summaries(&self.transcript_cells)
+            .into_iter()
+            .find(|summary| summary.is_active)
+        {
+            tui.insert_history_lines(vec![
+                Line::from(""),
+                Line::from(vec![
+                    "── ".dim(),
+                    format!("Tab {}: {}", summary.number, summary.title).bold(),
+                    " ──".dim(),
+                ]),
+            ]);
+        }
+        self.render_transcript_once(tui);
+        self.export_status();
+        let tx = self.app_event_tx.for_tab(self.tabs.active_id());
+        for event in deferred_events {
+            tx.send(event);
+        }
+        tui.frame_requester().schedule_frame();
+    }
+
+    async fn shutdown_tab(&self, tab: ParkedTab) {
+        if let Some(thread_id) = tab.chat_widget.thread_id() {
+            tab.chat_widget.submit_op(Op::Shutdown);
+            self.server.remove_thread(&thread_id).await;
+        }
+    }
+
+    /// Handles an event from the widget of a tab that is not on screen.
+    async fn handle_background_tab_event(
+        &mut self,
+        tui: &mut tui::Tui,
+        id: TabId,
+        event: AppEvent,
+    ) {
+        // Events from tabs that have since been closed are dropped.
+        let Some(tab) = self.tabs.get_mut(id) else {
+            return;
+        };
+        let Some(parked) = tab.parked.as_mut() else {
+            return;
+        };
+        match event {
+            AppEvent::CodexEvent(event) => {
+                let status = tab.status.after(&event.msg);
+                let changed = status != tab.status;
+                tab.status = status;
+                parked.chat_widget.handle_codex_event(event);
+                if changed {
+                    self.export_status();
+                    // Redraw the tab strip with the new status.
+                    tui.frame_requester().schedule_frame();
+                }
+            }
+            AppEvent::InsertHistoryCell(cell) => parked.transcript_cells.push(cell.into()),
+            AppEvent::CodexOp(op) => parked.chat_widget.submit_op(op),
+            AppEvent::StartCommitAnimation => {
+                tab.commit_animating = true;
+                self.update_commit_animation();
+            }
+            AppEvent::StopCommitAnimation => {
+                tab.commit_animating = false;
+                self.update_commit_animation();
+            }
+            AppEvent::CommitTick => {}
+            AppEvent::ExitRequest => {
+                if let Some(closed) = self.tabs.close_parked(id) {
+                    self.shutdown_tab(closed).await;
+                    self.update_commit_animation();
+                    self.export_status();
+                    tui.frame_requester().schedule_frame();
+                }
+            }
+            // Anything else needs the screen; replay it when the tab is shown.
+            event => parked.deferred_events.push(event),
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -353,6 +552,15 @@ impl App {
             auth_manager.clone(),
             SessionSource::Cli,
         ));
+        if let Some(hook) = DesktopNotifier::registration(
+            DesktopNotificationBackend::from_config(config.tui_notification_backend),
+            &config.tui_notifications,
+            tui.terminal_focused(),
+            config.cwd.clone(),
+            app_event_tx.clone(),
+        ) {
+            thread_manager.register_hook(hook);
+        }
         let mut model = thread_manager
             .get_models_manager()
             .get_model(&config.model, &config)
@@ -373,12 +581,13 @@ impl App {
         }
 
         let enhanced_keys_supported = tui.enhanced_keys_supported();
+        let tabs = Tabs::default();
         let mut chat_widget = match resume_selection {
             ResumeSelection::StartFresh | ResumeSelection::Exit => {
                 let init = crate::chatwidget::ChatWidgetInit {
                     config: config.clone(),
                     frame_requester: tui.frame_requester(),
-                    app_event_tx: app_event_tx.clone(),
+                    app_event_tx: app_event_tx.for_tab(tabs.active_id()),
                     initial_prompt: initial_prompt.clone(),
                     initial_images: initial_images.clone(),
                     enhanced_keys_supported,
@@ -400,7 +609,7 @@ impl App {
                 let init = crate::chatwidget::ChatWidgetInit {
                     config: config.clone(),
                     frame_requester: tui.frame_requester(),
-                    app_event_tx: app_event_tx.clone(),
+                    app_event_tx: app_event_tx.for_tab(tabs.active_id()),
                     initial_prompt: initial_prompt.clone(),
                     initial_images: initial_images.clone(),
                     enhanced_keys_supported,
@@ -419,7 +628,10 @@ impl App {
         let file_search = FileSearchManager::new(config.cwd.clone(), app_event_tx.clone());
         #[cfg(not(debug_assertions))]
         let upgrade_version = crate::updates::get_upgrade_version(&config);
+        #[cfg(not(debug_assertions))]
+        let upgrade_notes = crate::updates::get_upgrade_notes(&config);
 
+        let status_exporter = StatusExporter::new(&config.tui_status_export);
         let mut app = Self {
             server: thread_manager.clone(),
             app_event_tx,
@@ -431,16 +643,20 @@ impl App {
             file_search,
             enhanced_keys_supported,
             transcript_cells: Vec::new(),
+            tabs,
             overlay: None,
             deferred_history_lines: Vec::new(),
             has_emitted_history_lines: false,
+            event_log: EventLog::default(),
             commit_anim_running: Arc::new(AtomicBool::new(false)),
             backtrack: BacktrackState::default(),
             feedback: feedback.clone(),
             pending_update_action: None,
             suppress_shutdown_complete: false,
             skip_world_writable_scan_once: false,
+            status_exporter,
         };
+        app.export_status();
 
         // On startup, if Agent mode (workspace-write) or ReadOnly is active, warn about world-writable dirs on Windows.
         #[cfg(target_os = "windows")]
@@ -472,6 +688,7 @@ impl App {
                 tui,
                 AppEvent::InsertHistoryCell(Box::new(UpdateAvailableHistoryCell::new(
                     latest_version,
+                    upgrade_notes,
                     crate::update_action::get_update_action(),
                 ))),
             )
@@ -520,18 +737,30 @@ impl App {
                     self.chat_widget.handle_paste(pasted);
                 }
                 TuiEvent::Draw => {
-                    self.chat_widget.maybe_post_pending_notification(tui);
                     if self
                         .chat_widget
                         .handle_paste_burst_tick(tui.frame_requester())
                     {
                         return Ok(true);
                     }
+                    let tab_strip =
+                        (self.tabs.len() > 1).then(|| self.tabs.summaries(&self.transcript_cells));
+                    let strip_height = u16::from(tab_strip.is_some());
                     tui.draw(
-                        self.chat_widget.desired_height(tui.terminal.size()?.width),
+                        self.chat_widget.desired_height(tui.terminal.size()?.width) + strip_height,
                         |frame| {
-                            self.chat_widget.render(frame.area(), frame.buffer);
-                            if let Some((x, y)) = self.chat_widget.cursor_pos(frame.area()) {
+                            let mut area = frame.area();
+                            if let Some(summaries) = &tab_strip {
+                                tabs::render_tab_strip(
+                                    summaries,
+                                    Rect { height: 1, ..area },
+                                    frame.buffer,
+                                );
+                                area.y += strip_height;
+                                area.height = area.height.saturating_sub(strip_height);
+                            }
+                            self.chat_widget.render(area, frame.buffer);
+                            if let Some((x, y)) = self.chat_widget.cursor_pos(area) {
                                 frame.set_cursor_position((x, y));
                             }
                         },
@@ -548,6 +777,24 @@ impl App {
     }
 
     async fn handle_event(&mut self, tui: &mut tui::Tui, event: AppEvent) -> Result<bool> {
+        let event = match event {
+            AppEvent::TabEvent { tab, event } if tab == self.tabs.active_id() => {
+                if let AppEvent::CodexEvent(codex_event) = event.as_ref() {
+                    let status = self.tabs.active_status().after(&codex_event.msg);
+                    // Most events (deltas above all) leave the status as is.
+                    if status != self.tabs.active_status() {
+                        self.tabs.set_active_status(status);
+                        self.export_status();
+                    }
+                }
+                *event
+            }
+            AppEvent::TabEvent { tab, event } => {
+                self.handle_background_tab_event(tui, tab, *event).await;
+                return Ok(true);
+            }
+            event => event,
+        };
         let model_info = self
             .server
             .get_models_manager()
@@ -561,7 +808,7 @@ impl App {
                 let init = crate::chatwidget::ChatWidgetInit {
                     config: self.config.clone(),
                     frame_requester: tui.frame_requester(),
-                    app_event_tx: self.app_event_tx.clone(),
+                    app_event_tx: self.app_event_tx.for_tab(self.tabs.active_id()),
                     initial_prompt: None,
                     initial_images: Vec::new(),
                     enhanced_keys_supported: self.enhanced_keys_supported,
@@ -611,7 +858,7 @@ impl App {
                                 let init = crate::chatwidget::ChatWidgetInit {
                                     config: self.config.clone(),
                                     frame_requester: tui.frame_requester(),
-                                    app_event_tx: self.app_event_tx.clone(),
+                                    app_event_tx: self.app_event_tx.for_tab(self.tabs.active_id()),
                                     initial_prompt: None,
                                     initial_images: Vec::new(),
                                     enhanced_keys_supported: self.enhanced_keys_supported,
@@ -681,28 +928,45 @@ impl App {
                 }
             }
             AppEvent::StartCommitAnimation => {
-                if self
-                    .commit_anim_running
-                    .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
-                    .is_ok()
-                {
-                    let tx = self.app_event_tx.clone();
-                    let running = self.commit_anim_running.clone();
-                    thread::spawn(move || {
-                        while running.load(Ordering::Relaxed) {
-                            thread::sleep(Duration::from_millis(50));
-                            tx.send(AppEvent::CommitTick);
-                        }
-                    });
-                }
+                self.tabs.set_commit_animating(self.tabs.active_id(), true);
+                self.update_commit_animation();
             }
             AppEvent::StopCommitAnimation => {
-                self.commit_anim_running.store(false, Ordering::Release);
+                self.tabs.set_commit_animating(self.tabs.active_id(), false);
+                self.update_commit_animation();
+            }
+            AppEvent::PostNotification(message) => {
+                tui.notify(message);
             }
             AppEvent::CommitTick => {
-                self.chat_widget.on_commit_tick();
+                if self.tabs.is_commit_animating(self.tabs.active_id()) {
+                    self.chat_widget.on_commit_tick();
+                }
+                // Background tabs keep streaming off screen.
+                for parked in self.tabs.commit_animating_parked_mut() {
+                    parked.chat_widget.on_commit_tick();
+                }
             }
+            AppEvent::TabEvent { .. } => {}
+            AppEvent::OpenCommandPrefixPicker { command } => {
+                self.chat_widget.open_command_prefix_picker(&command);
+            }
+            AppEvent::AllowCommandPrefix(prefix) => {
+                self.chat_widget.allow_command_prefix(prefix);
+            }
+            AppEvent::OpenTabsPopup => {
+                let summaries = self.tabs.summaries(&self.transcript_cells);
+                self.chat_widget.open_tabs_popup(summaries);
+            }
+            AppEvent::NewTab => self.open_new_tab(tui),
+            AppEvent::SwitchTab(id) => self.switch_tab(tui, id),
+            AppEvent::CloseTab => self.close_active_tab(tui).await,
             AppEvent::CodexEvent(event) => {
+                let entry = self.event_log.record(self.chat_widget.thread_id(), &event);
+                if let Some(Overlay::EventInspector(inspector)) = &mut self.overlay {
+                    inspector.push_entry(entry);
+                    tui.frame_requester().schedule_frame();
+                }
                 if self.suppress_shutdown_complete
                     && matches!(event.msg, EventMsg::ShutdownComplete)
                 {
@@ -902,6 +1166,7 @@ impl App {
                                         model: None,
                                         effort: None,
                                         summary: None,
+                                        verbosity: None,
                                     },
                                 ));
                                 self.app_event_tx
@@ -974,6 +1239,30 @@ impl App {
                     }
                 }
             }
+            AppEvent::SetTheme { name } => {
+                match Theme::resolve(&name, &self.config.tui_themes) {
+                    Ok(theme) => {
+                        theme::set_current(theme);
+                        self.config.tui_theme = Some(name.clone());
+                        match ConfigEditsBuilder::new(&self.config.codex_home)
+                            .set_tui_theme(&name)
+                            .apply()
+                            .await
+                        {
+                            Ok(()) => self
+                                .chat_widget
+                                .add_info_message(format!("Theme changed to {name}"), None),
+                            Err(err) => {
+                                tracing::error!(error = %err, "failed to persist theme");
+                                self.chat_widget
+                                    .add_error_message(format!("Failed to save theme: {err}"));
+                            }
+                        }
+                    }
+                    Err(err) => self.chat_widget.add_error_message(err),
+                }
+                tui.frame_requester().schedule_frame();
+            }
             AppEvent::UpdateAskForApprovalPolicy(policy) => {
                 self.chat_widget.set_approval_policy(policy);
             }
@@ -1154,9 +1443,23 @@ impl App {
             AppEvent::OpenReviewCommitPicker(cwd) => {
                 self.chat_widget.show_review_commit_picker(&cwd).await;
             }
+            AppEvent::AttachMcpResource { server, uri } => {
+                self.chat_widget.attach_mcp_resource(server, uri);
+            }
             AppEvent::OpenReviewCustomPrompt => {
                 self.chat_widget.show_review_custom_prompt();
             }
+            AppEvent::OpenPatchHunkReview(request) => {
+                if let Some(overlay) = Overlay::new_patch_review(request, self.app_event_tx.clone())
+                {
+                    let _ = tui.enter_alt_screen();
+                    self.overlay = Some(overlay);
+                    tui.frame_requester().schedule_frame();
+                }
+            }
+            AppEvent::ReopenApprovalRequest(request) => {
+                self.chat_widget.reopen_approval_request(request);
+            }
             AppEvent::FullScreenApprovalRequest(request) => match request {
                 ApprovalRequest::ApplyPatch { cwd, changes, .. } => {
                     let _ = tui.enter_alt_screen();
@@ -1287,16 +1590,46 @@ impl App {
     }
 
     async fn handle_key_event(&mut self, tui: &mut tui::Tui, key_event: KeyEvent) {
+        if key_event.kind == KeyEventKind::Press && self.tabs.len() > 1 {
+            let keymap = self.chat_widget.keymap();
+            let offset = if keymap.is_press(KeyAction::NextTab, key_event) {
+                Some(1)
+            } else if keymap.is_press(KeyAction::PrevTab, key_event) {
+                Some(-1)
+            } else {
+                None
+            };
+            if let Some(offset) = offset {
+                self.switch_tab(tui, self.tabs.neighbor_id(offset));
+                return;
+            }
+        }
         match key_event {
+            key_event
+                if key_event.kind == KeyEventKind::Press
+                    && self
+                        .chat_widget
+                        .keymap()
+                        .is_press(KeyAction::ToggleTranscript, key_event) =>
+            {
+                // Enter alternate screen and set viewport to full size.
+                let _ = tui.enter_alt_screen();
+                self.overlay = Some(Overlay::new_transcript(
+                    self.transcript_cells.clone(),
+                    self.chat_widget.keymap().toggle_transcript.clone(),
+                ));
+                tui.frame_requester().schedule_frame();
+            }
+            // Ctrl+E is taken by the composer (end of line), so the event
+            // inspector lives on Ctrl+X.
             KeyEvent {
-                code: KeyCode::Char('t'),
+                code: KeyCode::Char('x'),
                 modifiers: crossterm::event::KeyModifiers::CONTROL,
                 kind: KeyEventKind::Press,
                 ..
             } => {
-                // Enter alternate screen and set viewport to full size.
                 let _ = tui.enter_alt_screen();
-                self.overlay = Some(Overlay::new_transcript(self.transcript_cells.clone()));
+                self.overlay = Some(Overlay::new_event_inspector(self.event_log.entries()));
                 tui.frame_requester().schedule_frame();
             }
             KeyEvent {
@@ -1401,6 +1734,8 @@ mod tests {
     use crate::history_cell::HistoryCell;
     use crate::history_cell::UserHistoryCell;
     use crate::history_cell::new_session_info;
+    use crate::tabs::TabStatus;
+    use crate::tabs::TabSummary;
     use codex_core::AuthManager;
     use codex_core::CodexAuth;
     use codex_core::ThreadManager;
@@ -1440,9 +1775,11 @@ mod tests {
             active_profile: None,
             file_search,
             transcript_cells: Vec::new(),
+            tabs: Tabs::default(),
             overlay: None,
             deferred_history_lines: Vec::new(),
             has_emitted_history_lines: false,
+            event_log: EventLog::default(),
             enhanced_keys_supported: false,
             commit_anim_running: Arc::new(AtomicBool::new(false)),
             backtrack: BacktrackState::default(),
@@ -1450,6 +1787,7 @@ mod tests {
             pending_update_action: None,
             suppress_shutdown_complete: false,
             skip_world_writable_scan_once: false,
+            status_exporter: None,
         }
     }
 
@@ -1480,9 +1818,11 @@ mod tests {
                 active_profile: None,
                 file_search,
                 transcript_cells: Vec::new(),
+                tabs: Tabs::default(),
                 overlay: None,
                 deferred_history_lines: Vec::new(),
                 has_emitted_history_lines: false,
+                event_log: EventLog::default(),
                 enhanced_keys_supported: false,
                 commit_anim_running: Arc::new(AtomicBool::new(false)),
                 backtrack: BacktrackState::default(),
@@ -1490,6 +1830,7 @@ mod tests {
                 pending_update_action: None,
                 suppress_shutdown_complete: false,
                 skip_world_writable_scan_once: false,
+                status_exporter: None,
             },
             rx,
             op_rx,
@@ -1518,6 +1859,72 @@ mod tests {
         s
     }
 
+    #[tokio::test]
+    async fn new_tab_parks_previous_conversation() {
+        let mut app = make_test_app().await;
+        let first_thread = app.chat_widget.thread_id();
+        let (second, _tx, _rx, _op_rx) = make_chatwidget_manual_with_sender().await;
+        let first = app.install_tab(ParkedTab::new(second, Vec::new()));
+        let second_id = app.tabs.open(first);
+        app.transcript_cells.push(Arc::new(UserHistoryCell {
+            message: "fix the   flaky\ntest".to_string(),
+        }) as Arc<dyn HistoryCell>);
+        if let Some(tab) = app.tabs.get_mut(0) {
+            tab.status = TabStatus::NeedsApproval;
+        }
+
+        assert_eq!(
+            app.tabs.summaries(&app.transcript_cells),
+            vec![
+                TabSummary {
+                    id: 0,
+                    number: 1,
+                    title: "new conversation".to_string(),
+                    status: TabStatus::NeedsApproval,
+                    is_active: false,
+                },
+                TabSummary {
+                    id: second_id,
+                    number: 2,
+                    title: "fix the flaky test".to_string(),
+                    status: TabStatus::Waiting,
+                    is_active: true,
+                },
+            ]
+        );
+        assert_eq!(app.tabs.neighbor_id(1), 0);
+        assert_eq!(app.tabs.neighbor_id(-1), 0);
+
+        let first = app.tabs.close_active().expect("first tab is parked");
+        assert_eq!(first.chat_widget.thread_id(), first_thread);
+        assert_eq!(app.tabs.len(), 1);
+        assert_eq!(app.tabs.active_id(), 0);
+        assert!(app.tabs.close_active().is_none());
+    }
+
+    #[tokio::test]
+    async fn commit_animation_runs_while_any_tab_streams() {
+        let mut app = make_test_app().await;
+        let (second, _tx, _rx, _op_rx) = make_chatwidget_manual_with_sender().await;
+        let first = app.install_tab(ParkedTab::new(second, Vec::new()));
+        let second_id = app.tabs.open(first);
+
+        app.tabs.set_commit_animating(0, true);
+        app.tabs.set_commit_animating(second_id, true);
+        app.update_commit_animation();
+        assert!(app.commit_anim_running.load(Ordering::Acquire));
+
+        // The active tab finishing its stream leaves the parked one ticking.
+        app.tabs.set_commit_animating(second_id, false);
+        app.update_commit_animation();
+        assert!(app.commit_anim_running.load(Ordering::Acquire));
+        assert!(!app.tabs.is_commit_animating(second_id));
+
+        app.tabs.set_commit_animating(0, false);
+        app.update_commit_animation();
+        assert!(!app.commit_anim_running.load(Ordering::Acquire));
+    }
+
     #[tokio::test]
     async fn model_migration_prompt_only_shows_for_deprecated_models() {
         let seen = BTreeMap::new();
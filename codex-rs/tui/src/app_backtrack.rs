@@ -40,6 +40,30 @@ impl App {
         tui: &mut tui::Tui,
         event: TuiEvent,
     ) -> Result<bool> {
+        if matches!(
+            self.overlay,
+            Some(Overlay::EventInspector(_) | Overlay::PatchReview(_))
+        ) {
+            // These overlays handle Esc themselves and take no part in backtracking.
+            self.overlay_forward_event(tui, event)?;
+            return Ok(true);
+        }
+        if let Some(Overlay::Transcript(transcript)) = &self.overlay
+            && (transcript.is_prompt_active()
+                || (transcript.has_search()
+                    && matches!(
+                        event,
+                        TuiEvent::Key(KeyEvent {
+                            code: KeyCode::Esc,
+                            ..
+                        })
+                    )))
+        {
+            // A `/` or `:` prompt owns the keyboard, and Esc clears an active
+            // search before it starts a backtrack.
+            self.overlay_forward_event(tui, event)?;
+            return Ok(true);
+        }
         if self.backtrack.overlay_preview_active {
             match event {
                 TuiEvent::Key(KeyEvent {
@@ -118,7 +142,10 @@ impl App {
     /// Open transcript overlay (enters alternate screen and shows full transcript).
     pub(crate) fn open_transcript_overlay(&mut self, tui: &mut tui::Tui) {
         let _ = tui.enter_alt_screen();
-        self.overlay = Some(Overlay::new_transcript(self.transcript_cells.clone()));
+        self.overlay = Some(Overlay::new_transcript(
+            self.transcript_cells.clone(),
+            self.chat_widget.keymap().toggle_transcript.clone(),
+        ));
         tui.frame_requester().schedule_frame();
     }
 
@@ -340,7 +367,7 @@ impl App {
             config: cfg,
             model: self.current_model.clone(),
             frame_requester: tui.frame_requester(),
-            app_event_tx: self.app_event_tx.clone(),
+            app_event_tx: self.app_event_tx.for_tab(self.tabs.active_id()),
             initial_prompt: None,
             initial_images: Vec::new(),
             enhanced_keys_supported: self.enhanced_keys_supported,
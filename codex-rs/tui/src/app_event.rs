@@ -9,6 +9,7 @@ use codex_protocol::openai_models::ModelPreset;
 
 use crate::bottom_pane::ApprovalRequest;
 use crate::history_cell::HistoryCell;
+use crate::tabs::TabId;
 
 use codex_core::features::Feature;
 use codex_core::protocol::AskForApproval;
@@ -33,6 +34,34 @@ pub(crate) enum WindowsSandboxFallbackReason {
 pub(crate) enum AppEvent {
     CodexEvent(Event),
 
+    /// An event sent by the widget of conversation tab `tab`.
+    TabEvent {
+        tab: TabId,
+        event: Box<AppEvent>,
+    },
+
+    /// Open the prefixes of `command` that can be pre-approved from the
+    /// `/shell-commands` palette.
+    OpenCommandPrefixPicker {
+        command: Vec<String>,
+    },
+
+    /// Run commands starting with this prefix without approval for the rest
+    /// of the session.
+    AllowCommandPrefix(Vec<String>),
+
+    /// Open the `/tabs` popup listing the open conversation tabs.
+    OpenTabsPopup,
+
+    /// Start a new conversation in its own tab.
+    NewTab,
+
+    /// Bring conversation tab `TabId` to the screen.
+    SwitchTab(TabId),
+
+    /// Shut down the active conversation and close its tab.
+    CloseTab,
+
     /// Start a new session.
     NewSession,
 
@@ -71,6 +100,9 @@ pub(crate) enum AppEvent {
     StopCommitAnimation,
     CommitTick,
 
+    /// Write an OSC 9 desktop notification from the TUI thread.
+    PostNotification(String),
+
     /// Update the current reasoning effort in the running app and widget.
     UpdateReasoningEffort(Option<ReasoningEffort>),
 
@@ -83,6 +115,11 @@ pub(crate) enum AppEvent {
         effort: Option<ReasoningEffort>,
     },
 
+    /// Switch the TUI color theme and persist the choice to `config.toml`.
+    SetTheme {
+        name: String,
+    },
+
     /// Open the reasoning selection popup after picking a model.
     OpenReasoningPopup {
         model: ModelPreset,
@@ -198,6 +235,13 @@ pub(crate) enum AppEvent {
     /// Open the approval popup.
     FullScreenApprovalRequest(ApprovalRequest),
 
+    /// Open the per-hunk review of a patch approval request.
+    OpenPatchHunkReview(ApprovalRequest),
+
+    /// Show an approval request again after leaving the per-hunk review
+    /// without deciding.
+    ReopenApprovalRequest(ApprovalRequest),
+
     /// Open the feedback note entry overlay after the user selects a category.
     OpenFeedbackNote {
         category: FeedbackCategory,
@@ -211,6 +255,12 @@ pub(crate) enum AppEvent {
 
     /// Launch the external editor after a normal draw has completed.
     LaunchExternalEditor,
+
+    /// Attach an MCP resource to the next user message.
+    AttachMcpResource {
+        server: String,
+        uri: String,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
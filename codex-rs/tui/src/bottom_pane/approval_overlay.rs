@@ -9,6 +9,7 @@ use crate::bottom_pane::list_selection_view::ListSelectionView;
 use crate::bottom_pane::list_selection_view::SelectionItem;
 use crate::bottom_pane::list_selection_view::SelectionViewParams;
 use crate::diff_render::DiffSummary;
+use crate::diff_render::hunk_count;
 use crate::exec_command::strip_bash_lc_and_escape;
 use crate::history_cell;
 use crate::key_hint;
@@ -23,6 +24,7 @@ use codex_core::protocol::ExecPolicyAmendment;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDecision;
+use codex_protocol::parse_command::CommandOperation;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
@@ -44,6 +46,7 @@ pub(crate) enum ApprovalRequest {
         command: Vec<String>,
         reason: Option<String>,
         proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
+        operations: Vec<CommandOperation>,
     },
     ApplyPatch {
         id: String,
@@ -69,10 +72,16 @@ pub(crate) struct ApprovalOverlay {
     current_complete: bool,
     done: bool,
     features: Features,
+    approve_keys: Vec<KeyBinding>,
 }
 
 impl ApprovalOverlay {
-    pub fn new(request: ApprovalRequest, app_event_tx: AppEventSender, features: Features) -> Self {
+    pub fn new(
+        request: ApprovalRequest,
+        app_event_tx: AppEventSender,
+        features: Features,
+        approve_keys: Vec<KeyBinding>,
+    ) -> Self {
         let mut view = Self {
             current_request: None,
             current_variant: None,
@@ -83,6 +92,7 @@ impl ApprovalOverlay {
             current_complete: false,
             done: false,
             features,
+            approve_keys,
         };
         view.set_current(request);
         view
@@ -97,7 +107,8 @@ impl ApprovalOverlay {
         let ApprovalRequestState { variant, header } = ApprovalRequestState::from(request);
         self.current_variant = Some(variant.clone());
         self.current_complete = false;
-        let (options, params) = Self::build_options(variant, header, &self.features);
+        let (options, params) =
+            Self::build_options(variant, header, &self.features, &self.approve_keys);
         self.options = options;
         self.list = ListSelectionView::new(params, self.app_event_tx.clone());
     }
@@ -106,21 +117,26 @@ impl ApprovalOverlay {
         variant: ApprovalVariant,
         header: Box<dyn Renderable>,
         features: &Features,
+        approve_keys: &[KeyBinding],
     ) -> (Vec<ApprovalOption>, SelectionViewParams) {
         let (options, title) = match &variant {
             ApprovalVariant::Exec {
                 proposed_execpolicy_amendment,
                 ..
             } => (
-                exec_options(proposed_execpolicy_amendment.clone(), features),
+                exec_options(
+                    proposed_execpolicy_amendment.clone(),
+                    features,
+                    approve_keys,
+                ),
                 "Would you like to run the following command?".to_string(),
             ),
-            ApprovalVariant::ApplyPatch { .. } => (
-                patch_options(),
+            ApprovalVariant::ApplyPatch { multiple_hunks, .. } => (
+                patch_options(approve_keys, *multiple_hunks),
                 "Would you like to make the following edits?".to_string(),
             ),
             ApprovalVariant::McpElicitation { server_name, .. } => (
-                elicitation_options(),
+                elicitation_options(approve_keys),
                 format!("{server_name} needs your approval."),
             ),
         };
@@ -174,6 +190,12 @@ impl ApprovalOverlay {
                 (ApprovalVariant::ApplyPatch { id, .. }, ApprovalDecision::Review(decision)) => {
                     self.handle_patch_decision(id, decision.clone());
                 }
+                (ApprovalVariant::ApplyPatch { .. }, ApprovalDecision::ReviewHunks) => {
+                    if let Some(request) = self.current_request.clone() {
+                        self.app_event_tx
+                            .send(AppEvent::OpenPatchHunkReview(request));
+                    }
+                }
                 (
                     ApprovalVariant::McpElicitation {
                         server_name,
@@ -343,6 +365,7 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                 command,
                 reason,
                 proposed_execpolicy_amendment,
+                operations,
             } => {
                 let mut header: Vec<Line<'static>> = Vec::new();
                 if let Some(reason) = reason {
@@ -355,6 +378,10 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                     first.spans.insert(0, Span::from("$ "));
                 }
                 header.extend(full_cmd_lines);
+                if let Some(effects) = effects_line(&operations) {
+                    header.push(Line::from(""));
+                    header.push(effects);
+                }
                 Self {
                     variant: ApprovalVariant::Exec {
                         id,
@@ -380,9 +407,10 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                     ));
                     header.push(Box::new(Line::from("")));
                 }
+                let multiple_hunks = changes.values().map(hunk_count).sum::<usize>() > 1;
                 header.push(DiffSummary::new(changes, cwd).into());
                 Self {
-                    variant: ApprovalVariant::ApplyPatch { id },
+                    variant: ApprovalVariant::ApplyPatch { id, multiple_hunks },
                     header: Box::new(ColumnRenderable::with(header)),
                 }
             }
@@ -418,6 +446,8 @@ enum ApprovalVariant {
     },
     ApplyPatch {
         id: String,
+        /// Whether the patch has more than one hunk to review separately.
+        multiple_hunks: bool,
     },
     McpElicitation {
         server_name: String,
@@ -428,6 +458,8 @@ enum ApprovalVariant {
 #[derive(Clone)]
 enum ApprovalDecision {
     Review(ReviewDecision),
+    /// Open the full-screen per-hunk review instead of deciding here.
+    ReviewHunks,
     McpElicitation(ElicitationAction),
 }
 
@@ -447,15 +479,41 @@ impl ApprovalOption {
     }
 }
 
+/// Names what a command may do beyond reading, with destructive effects
+/// highlighted. `None` for read-only commands.
+fn effects_line(operations: &[CommandOperation]) -> Option<Line<'static>> {
+    let effects: Vec<Span<'static>> = operations
+        .iter()
+        .filter_map(|operation| match operation {
+            CommandOperation::ReadOnly => None,
+            CommandOperation::Write => Some("modify files".into()),
+            CommandOperation::Network => Some("access the network".into()),
+            CommandOperation::Destructive => Some("delete or overwrite data".red().bold()),
+        })
+        .collect();
+    if effects.is_empty() {
+        return None;
+    }
+    let mut spans = vec!["May ".dim()];
+    for (idx, effect) in effects.into_iter().enumerate() {
+        if idx > 0 {
+            spans.push(", ".dim());
+        }
+        spans.push(effect);
+    }
+    Some(Line::from(spans))
+}
+
 fn exec_options(
     proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
     features: &Features,
+    approve_keys: &[KeyBinding],
 ) -> Vec<ApprovalOption> {
     vec![ApprovalOption {
         label: "Yes, proceed".to_string(),
         decision: ApprovalDecision::Review(ReviewDecision::Approved),
         display_shortcut: None,
-        additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+        additional_shortcuts: approve_keys.to_vec(),
     }]
     .into_iter()
     .chain(
@@ -490,13 +548,13 @@ fn exec_options(
     .collect()
 }
 
-fn patch_options() -> Vec<ApprovalOption> {
-    vec![
+fn patch_options(approve_keys: &[KeyBinding], multiple_hunks: bool) -> Vec<ApprovalOption> {
+    let mut options = vec![
         ApprovalOption {
             label: "Yes, proceed".to_string(),
             decision: ApprovalDecision::Review(ReviewDecision::Approved),
             display_shortcut: None,
-            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+            additional_shortcuts: approve_keys.to_vec(),
         },
         ApprovalOption {
             label: "Yes, and don't ask again for these files".to_string(),
@@ -504,22 +562,31 @@ fn patch_options() -> Vec<ApprovalOption> {
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
         },
-        ApprovalOption {
-            label: "No, and tell Codex what to do differently".to_string(),
-            decision: ApprovalDecision::Review(ReviewDecision::Abort),
-            display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
-            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
-        },
-    ]
+    ];
+    if multiple_hunks {
+        options.push(ApprovalOption {
+            label: "Review each hunk".to_string(),
+            decision: ApprovalDecision::ReviewHunks,
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('r'))],
+        });
+    }
+    options.push(ApprovalOption {
+        label: "No, and tell Codex what to do differently".to_string(),
+        decision: ApprovalDecision::Review(ReviewDecision::Abort),
+        display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
+        additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
+    });
+    options
 }
 
-fn elicitation_options() -> Vec<ApprovalOption> {
+fn elicitation_options(approve_keys: &[KeyBinding]) -> Vec<ApprovalOption> {
     vec![
         ApprovalOption {
             label: "Yes, provide the requested info".to_string(),
             decision: ApprovalDecision::McpElicitation(ElicitationAction::Accept),
             display_shortcut: None,
-            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+            additional_shortcuts: approve_keys.to_vec(),
         },
         ApprovalOption {
             label: "No, but continue without it".to_string(),
@@ -543,20 +610,76 @@ mod tests {
     use pretty_assertions::assert_eq;
     use tokio::sync::mpsc::unbounded_channel;
 
+    fn default_approve_keys() -> Vec<KeyBinding> {
+        crate::keybindings::Keymap::default().approve
+    }
+
     fn make_exec_request() -> ApprovalRequest {
         ApprovalRequest::Exec {
             id: "test".to_string(),
             command: vec!["echo".to_string(), "hi".to_string()],
             reason: Some("reason".to_string()),
             proposed_execpolicy_amendment: None,
+            operations: vec![],
+        }
+    }
+
+    fn make_patch_request(unified_diff: &str) -> ApprovalRequest {
+        ApprovalRequest::ApplyPatch {
+            id: "patch".to_string(),
+            reason: None,
+            cwd: PathBuf::from("/repo"),
+            changes: HashMap::from([(
+                PathBuf::from("/repo/a.txt"),
+                FileChange::Update {
+                    unified_diff: unified_diff.to_string(),
+                    move_path: None,
+                },
+            )]),
+        }
+    }
+
+    #[test]
+    fn hunk_review_is_offered_only_for_multi_hunk_patches() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let mut view = ApprovalOverlay::new(
+            make_patch_request("@@ -1 +1 @@\n-one\n+uno\n"),
+            AppEventSender::new(tx.clone()),
+            Features::with_defaults(),
+            default_approve_keys(),
+        );
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE));
+        assert!(!view.is_complete());
+
+        let mut view = ApprovalOverlay::new(
+            make_patch_request("@@ -1 +1 @@\n-one\n+uno\n@@ -9 +9 @@\n-nine\n+nueve\n"),
+            AppEventSender::new(tx),
+            Features::with_defaults(),
+            default_approve_keys(),
+        );
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE));
+        assert!(view.is_complete());
+        let mut opened_review = false;
+        while let Ok(ev) = rx.try_recv() {
+            assert!(
+                !matches!(ev, AppEvent::CodexOp(_)),
+                "review must not decide the approval yet"
+            );
+            opened_review |= matches!(ev, AppEvent::OpenPatchHunkReview(_));
         }
+        assert!(opened_review);
     }
 
     #[test]
     fn ctrl_c_aborts_and_clears_queue() {
         let (tx, _rx) = unbounded_channel::<AppEvent>();
         let tx = AppEventSender::new(tx);
-        let mut view = ApprovalOverlay::new(make_exec_request(), tx, Features::with_defaults());
+        let mut view = ApprovalOverlay::new(
+            make_exec_request(),
+            tx,
+            Features::with_defaults(),
+            default_approve_keys(),
+        );
         view.enqueue_request(make_exec_request());
         assert_eq!(CancellationEvent::Handled, view.on_ctrl_c());
         assert!(view.queue.is_empty());
@@ -567,7 +690,12 @@ mod tests {
     fn shortcut_triggers_selection() {
         let (tx, mut rx) = unbounded_channel::<AppEvent>();
         let tx = AppEventSender::new(tx);
-        let mut view = ApprovalOverlay::new(make_exec_request(), tx, Features::with_defaults());
+        let mut view = ApprovalOverlay::new(
+            make_exec_request(),
+            tx,
+            Features::with_defaults(),
+            default_approve_keys(),
+        );
         assert!(!view.is_complete());
         view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
         // We expect at least one CodexOp message in the queue.
@@ -593,9 +721,11 @@ mod tests {
                 proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(vec![
                     "echo".to_string(),
                 ])),
+                operations: vec![],
             },
             tx,
             Features::with_defaults(),
+            default_approve_keys(),
         );
         view.handle_key_event(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE));
         let mut saw_op = false;
@@ -631,6 +761,7 @@ mod tests {
                 proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(vec![
                     "echo".to_string(),
                 ])),
+                operations: vec![],
             },
             tx,
             {
@@ -638,6 +769,7 @@ mod tests {
                 features.disable(Feature::ExecPolicy);
                 features
             },
+            default_approve_keys(),
         );
         assert_eq!(view.options.len(), 2);
         view.handle_key_event(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE));
@@ -655,9 +787,15 @@ mod tests {
             command,
             reason: None,
             proposed_execpolicy_amendment: None,
+            operations: vec![],
         };
 
-        let view = ApprovalOverlay::new(exec_request, tx, Features::with_defaults());
+        let view = ApprovalOverlay::new(
+            exec_request,
+            tx,
+            Features::with_defaults(),
+            default_approve_keys(),
+        );
         let mut buf = Buffer::empty(Rect::new(0, 0, 80, view.desired_height(80)));
         view.render(Rect::new(0, 0, 80, view.desired_height(80)), &mut buf);
 
@@ -676,6 +814,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn header_lists_what_the_command_may_do() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let exec_request = ApprovalRequest::Exec {
+            id: "test".into(),
+            command: vec!["bash".into(), "-lc".into(), "curl -O x && rm -rf y".into()],
+            reason: None,
+            proposed_execpolicy_amendment: None,
+            operations: vec![CommandOperation::Network, CommandOperation::Destructive],
+        };
+
+        let view = ApprovalOverlay::new(
+            exec_request,
+            tx,
+            Features::with_defaults(),
+            default_approve_keys(),
+        );
+        let mut buf = Buffer::empty(Rect::new(0, 0, 80, view.desired_height(80)));
+        view.render(Rect::new(0, 0, 80, view.desired_height(80)), &mut buf);
+
+        let rendered: Vec<String> = (0..buf.area.height)
+            .map(|row| {
+                (0..buf.area.width)
+                    .map(|col| buf[(col, row)].symbol().to_string())
+                    .collect()
+            })
+            .collect();
+        assert!(
+            rendered
+                .iter()
+                .any(|line| line.contains("May access the network, delete or overwrite data")),
+            "expected header to list the command's effects, got {rendered:?}"
+        );
+        assert_eq!(effects_line(&[CommandOperation::ReadOnly]), None);
+    }
+
     #[test]
     fn exec_history_cell_wraps_with_two_space_indent() {
         let command = vec![
@@ -707,7 +882,12 @@ mod tests {
     fn enter_sets_last_selected_index_without_dismissing() {
         let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
         let tx = AppEventSender::new(tx_raw);
-        let mut view = ApprovalOverlay::new(make_exec_request(), tx, Features::with_defaults());
+        let mut view = ApprovalOverlay::new(
+            make_exec_request(),
+            tx,
+            Features::with_defaults(),
+            default_approve_keys(),
+        );
         view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
 
         assert!(
@@ -31,11 +31,15 @@ use super::paste_burst::CharDecision;
 use super::paste_burst::PasteBurst;
 use super::skill_popup::SkillPopup;
 use crate::bottom_pane::paste_burst::FlushResult;
+use crate::bottom_pane::prompt_args::McpPromptCommand;
+use crate::bottom_pane::prompt_args::McpPromptInvocation;
+use crate::bottom_pane::prompt_args::command_with_arg_placeholders;
 use crate::bottom_pane::prompt_args::expand_custom_prompt;
 use crate::bottom_pane::prompt_args::expand_if_numeric_with_positional_args;
+use crate::bottom_pane::prompt_args::expand_user_command;
+use crate::bottom_pane::prompt_args::parse_mcp_prompt_invocation;
 use crate::bottom_pane::prompt_args::parse_slash_name;
 use crate::bottom_pane::prompt_args::prompt_argument_names;
-use crate::bottom_pane::prompt_args::prompt_command_with_arg_placeholders;
 use crate::bottom_pane::prompt_args::prompt_has_numeric_placeholders;
 use crate::render::Insets;
 use crate::render::RectExt;
@@ -47,6 +51,7 @@ use codex_common::fuzzy_match::fuzzy_match;
 use codex_protocol::custom_prompts::CustomPrompt;
 use codex_protocol::custom_prompts::PROMPTS_CMD_PREFIX;
 use codex_protocol::models::local_image_label_text;
+use codex_protocol::protocol::Op;
 
 use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
@@ -57,10 +62,13 @@ use crate::clipboard_paste::pasted_image_format;
 use crate::history_cell;
 use crate::ui_consts::LIVE_PREFIX_COLS;
 use codex_core::skills::model::SkillMetadata;
+use codex_core::variables::interpolate;
 use codex_file_search::FileMatch;
 use std::cell::RefCell;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::path::Path;
 use std::path::PathBuf;
 use std::time::Duration;
 use std::time::Instant;
@@ -76,12 +84,29 @@ fn windows_degraded_sandbox_active() -> bool {
 /// placeholder in the UI.
 const LARGE_PASTE_CHAR_THRESHOLD: usize = 1000;
 
+/// Most images that can be attached to a single message.
+const MAX_ATTACHED_IMAGES: usize = 10;
+
+/// Largest image file that can be attached. Images are downscaled before they
+/// are sent, but decoding anything bigger would stall the UI.
+const MAX_IMAGE_ATTACHMENT_BYTES: u64 = 20 * 1024 * 1024;
+
+fn format_megabytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
 /// Result returned when the user interacts with the text area.
 #[derive(Debug, PartialEq)]
 pub enum InputResult {
     Submitted(String),
     Command(SlashCommand),
     CommandWithArgs(SlashCommand, String),
+    /// An MCP server prompt invocation along with the command text the user
+    /// submitted, which is what gets shown in the transcript.
+    McpPrompt {
+        text: String,
+        invocation: McpPromptInvocation,
+    },
     None,
 }
 
@@ -126,6 +151,10 @@ pub(crate) struct ChatComposer {
     // When true, disables paste-burst logic and inserts characters immediately.
     disable_paste_burst: bool,
     custom_prompts: Vec<CustomPrompt>,
+    custom_commands: Vec<CustomPrompt>,
+    mcp_prompts: Vec<McpPromptCommand>,
+    // Conversation variables filled into expanded prompts and commands.
+    variables: BTreeMap<String, String>,
     footer_mode: FooterMode,
     footer_hint_override: Option<Vec<(String, String)>>,
     context_window_percent: Option<i64>,
@@ -176,6 +205,9 @@ impl ChatComposer {
             paste_burst: PasteBurst::default(),
             disable_paste_burst: false,
             custom_prompts: Vec::new(),
+            custom_commands: Vec::new(),
+            mcp_prompts: Vec::new(),
+            variables: BTreeMap::new(),
             footer_mode: FooterMode::ShortcutSummary,
             footer_hint_override: None,
             context_window_percent: None,
@@ -280,8 +312,7 @@ impl ChatComposer {
                 tracing::debug!("image dimensions={}x{}", width, height);
                 let format = pasted_image_format(&path_buf);
                 tracing::debug!("attached image format={}", format.label());
-                self.attach_image(path_buf);
-                true
+                self.try_attach_image(path_buf)
             }
             Err(err) => {
                 tracing::trace!("ERR: {err}");
@@ -421,6 +452,43 @@ impl ChatComposer {
             .push(AttachedImage { placeholder, path });
     }
 
+    /// Attaches the image at `path` unless that would break the attachment
+    /// limits, in which case the reason is shown and `false` returned.
+    pub fn try_attach_image(&mut self, path: PathBuf) -> bool {
+        match self.check_image_attachment(&path) {
+            Ok(()) => {
+                self.attach_image(path);
+                true
+            }
+            Err(message) => {
+                self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                    history_cell::new_error_event(message),
+                )));
+                false
+            }
+        }
+    }
+
+    fn check_image_attachment(&self, path: &Path) -> Result<(), String> {
+        if self.attached_images.len() >= MAX_ATTACHED_IMAGES {
+            return Err(format!(
+                "Cannot attach more than {MAX_ATTACHED_IMAGES} images to one message."
+            ));
+        }
+        let size = std::fs::metadata(path)
+            .map_err(|err| format!("Cannot attach image {}: {err}", path.display()))?
+            .len();
+        if size > MAX_IMAGE_ATTACHMENT_BYTES {
+            return Err(format!(
+                "Image {} is too large to attach ({}; the limit is {}).",
+                path.display(),
+                format_megabytes(size),
+                format_megabytes(MAX_IMAGE_ATTACHMENT_BYTES),
+            ));
+        }
+        Ok(())
+    }
+
     pub fn take_recent_submission_images(&mut self) -> Vec<PathBuf> {
         let images = std::mem::take(&mut self.attached_images);
         images.into_iter().map(|img| img.path).collect()
@@ -583,6 +651,7 @@ impl ChatComposer {
                         CommandItem::UserPrompt(idx) => {
                             if let Some(prompt) = popup.prompt(idx) {
                                 match prompt_selection_action(
+                                    &format!("{PROMPTS_CMD_PREFIX}:{}", prompt.name),
                                     prompt,
                                     first_line,
                                     PromptSelectionMode::Completion,
@@ -596,6 +665,30 @@ impl ChatComposer {
                                 }
                             }
                         }
+                        CommandItem::UserCommand(idx) => {
+                            if let Some(command) = popup.user_command(idx) {
+                                match prompt_selection_action(
+                                    &command.name,
+                                    command,
+                                    first_line,
+                                    PromptSelectionMode::Completion,
+                                ) {
+                                    PromptSelectionAction::Insert { text, cursor } => {
+                                        let target = cursor.unwrap_or(text.len());
+                                        self.textarea.set_text(&text);
+                                        cursor_target = Some(target);
+                                    }
+                                    PromptSelectionAction::Submit { .. } => {}
+                                }
+                            }
+                        }
+                        CommandItem::McpPrompt(idx) => {
+                            if let Some(command) = popup.mcp_prompt(idx) {
+                                let (text, cursor) = mcp_prompt_completion(command);
+                                self.textarea.set_text(&text);
+                                cursor_target = Some(cursor);
+                            }
+                        }
                     }
                     if let Some(pos) = cursor_target {
                         self.textarea.set_cursor(pos);
@@ -608,18 +701,26 @@ impl ChatComposer {
                 modifiers: KeyModifiers::NONE,
                 ..
             } => {
-                // If the current line starts with a custom prompt name and includes
-                // positional args for a numeric-style template, expand and submit
-                // immediately regardless of the popup selection.
+                // If the current line starts with a custom prompt or user command
+                // name and includes positional args for a numeric-style template,
+                // expand and submit immediately regardless of the popup selection.
                 let first_line = self.textarea.text().lines().next().unwrap_or("");
-                if let Some((name, _rest)) = parse_slash_name(first_line)
-                    && let Some(prompt_name) = name.strip_prefix(&format!("{PROMPTS_CMD_PREFIX}:"))
-                    && let Some(prompt) = self.custom_prompts.iter().find(|p| p.name == prompt_name)
-                    && let Some(expanded) =
-                        expand_if_numeric_with_positional_args(prompt, first_line)
-                {
-                    self.textarea.set_text("");
-                    return (InputResult::Submitted(expanded), true);
+                if let Some((name, rest)) = parse_slash_name(first_line) {
+                    let prompt = match name.strip_prefix(&format!("{PROMPTS_CMD_PREFIX}:")) {
+                        Some(prompt_name) => {
+                            self.custom_prompts.iter().find(|p| p.name == prompt_name)
+                        }
+                        None => self.custom_commands.iter().find(|c| c.name == name),
+                    };
+                    if let Some(prompt) = prompt
+                        && let Some(expanded) =
+                            expand_if_numeric_with_positional_args(name, prompt, first_line)
+                    {
+                        self.report_user_command(name, rest);
+                        let expanded = interpolate(&expanded, &self.variables);
+                        self.textarea.set_text("");
+                        return (InputResult::Submitted(expanded), true);
+                    }
                 }
 
                 if let Some(sel) = popup.selected_item() {
@@ -631,12 +732,44 @@ impl ChatComposer {
                         CommandItem::UserPrompt(idx) => {
                             if let Some(prompt) = popup.prompt(idx) {
                                 match prompt_selection_action(
+                                    &format!("{PROMPTS_CMD_PREFIX}:{}", prompt.name),
                                     prompt,
                                     first_line,
                                     PromptSelectionMode::Submit,
                                 ) {
                                     PromptSelectionAction::Submit { text } => {
+                                        let text = interpolate(&text, &self.variables);
+                                        self.textarea.set_text("");
+                                        return (InputResult::Submitted(text), true);
+                                    }
+                                    PromptSelectionAction::Insert { text, cursor } => {
+                                        let target = cursor.unwrap_or(text.len());
+                                        self.textarea.set_text(&text);
+                                        self.textarea.set_cursor(target);
+                                        return (InputResult::None, true);
+                                    }
+                                }
+                            }
+                            return (InputResult::None, true);
+                        }
+                        CommandItem::UserCommand(idx) => {
+                            if let Some(command) = popup.user_command(idx) {
+                                match prompt_selection_action(
+                                    &command.name,
+                                    command,
+                                    first_line,
+                                    PromptSelectionMode::Submit,
+                                ) {
+                                    PromptSelectionAction::Submit { text } => {
+                                        let arguments = parse_slash_name(first_line)
+                                            .map_or("", |(_, rest)| rest);
+                                        self.report_user_command(&command.name, arguments);
+                                        let text = interpolate(&text, &self.variables);
                                         self.textarea.set_text("");
+                                        // A command without a prompt only runs hooks.
+                                        if text.is_empty() {
+                                            return (InputResult::None, true);
+                                        }
                                         return (InputResult::Submitted(text), true);
                                     }
                                     PromptSelectionAction::Insert { text, cursor } => {
@@ -649,6 +782,32 @@ impl ChatComposer {
                             }
                             return (InputResult::None, true);
                         }
+                        CommandItem::McpPrompt(idx) => {
+                            if let Some(command) = popup.mcp_prompt(idx) {
+                                // The full command is already typed (possibly with
+                                // arguments), so submit it as written.
+                                if parse_slash_name(first_line)
+                                    .is_some_and(|(name, _)| name == command.command_name())
+                                {
+                                    return self.handle_key_event_without_popup(key_event);
+                                }
+                                if command.argument_names().is_empty() {
+                                    let text = format!("/{}", command.command_name());
+                                    let invocation = McpPromptInvocation {
+                                        server: command.server.clone(),
+                                        name: command.prompt.name.clone(),
+                                        arguments: HashMap::new(),
+                                    };
+                                    self.textarea.set_text("");
+                                    self.history.record_local_submission(&text);
+                                    return (InputResult::McpPrompt { text, invocation }, true);
+                                }
+                                let (text, cursor) = mcp_prompt_completion(command);
+                                self.textarea.set_text(&text);
+                                self.textarea.set_cursor(cursor);
+                            }
+                            return (InputResult::None, true);
+                        }
                     }
                 }
                 // Fallback to default newline handling if no command selected.
@@ -829,9 +988,12 @@ impl ChatComposer {
                             self.textarea.replace_range(start_idx..end_idx, "");
                             self.textarea.set_cursor(start_idx);
 
-                            self.attach_image(path_buf);
-                            // Add a trailing space to keep typing fluid.
-                            self.textarea.insert_str(" ");
+                            if self.try_attach_image(path_buf) {
+                                // Add a trailing space to keep typing fluid.
+                                self.textarea.insert_str(" ");
+                            } else {
+                                self.insert_selected_path(&sel_path);
+                            }
                         }
                         Err(err) => {
                             tracing::trace!("image dimensions lookup failed: {err}");
@@ -1300,7 +1462,14 @@ impl ChatComposer {
                                     .any(|prompt| prompt.name == prompt_name)
                             })
                             .unwrap_or(false);
-                        if !is_builtin && !is_known_prompt {
+                        let is_known_command = self.custom_commands.iter().any(|c| c.name == name);
+                        let is_known_mcp_prompt =
+                            self.mcp_prompts.iter().any(|p| p.command_name() == name);
+                        if !is_builtin
+                            && !is_known_prompt
+                            && !is_known_command
+                            && !is_known_mcp_prompt
+                        {
                             let message = format!(
                                 r#"Unrecognized command '/{name}'. Type "/" for a list of supported commands."#
                             );
@@ -1321,11 +1490,34 @@ impl ChatComposer {
                     && let Some((_n, cmd)) = built_in_slash_commands()
                         .into_iter()
                         .find(|(command_name, _)| *command_name == name)
-                    && cmd == SlashCommand::Review
+                    && matches!(
+                        cmd,
+                        SlashCommand::Review
+                            | SlashCommand::Diff
+                            | SlashCommand::Model
+                            | SlashCommand::Tag
+                            | SlashCommand::Set
+                    )
                 {
                     return (InputResult::CommandWithArgs(cmd, rest.to_string()), true);
                 }
 
+                match parse_mcp_prompt_invocation(&text, &self.mcp_prompts) {
+                    Ok(Some(invocation)) => {
+                        self.history.record_local_submission(&text);
+                        return (InputResult::McpPrompt { text, invocation }, true);
+                    }
+                    Ok(None) => {}
+                    Err(err) => {
+                        self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                            history_cell::new_error_event(err.user_message()),
+                        )));
+                        self.textarea.set_text(&original_input);
+                        self.textarea.set_cursor(original_input.len());
+                        return (InputResult::None, true);
+                    }
+                }
+
                 let expanded_prompt = match expand_custom_prompt(&text, &self.custom_prompts) {
                     Ok(expanded) => expanded,
                     Err(err) => {
@@ -1337,8 +1529,24 @@ impl ChatComposer {
                         return (InputResult::None, true);
                     }
                 };
-                if let Some(expanded) = expanded_prompt {
-                    text = expanded;
+                let expanded_command = match expand_user_command(&text, &self.custom_commands) {
+                    Ok(expanded) => expanded,
+                    Err(err) => {
+                        self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                            history_cell::new_error_event(err.user_message()),
+                        )));
+                        self.textarea.set_text(&original_input);
+                        self.textarea.set_cursor(original_input.len());
+                        return (InputResult::None, true);
+                    }
+                };
+                if expanded_command.is_some()
+                    && let Some((name, rest)) = parse_slash_name(&text)
+                {
+                    self.report_user_command(name, rest);
+                }
+                if let Some(expanded) = expanded_prompt.or(expanded_command) {
+                    text = interpolate(&expanded, &self.variables);
                 }
                 if text.is_empty() && !has_attachments {
                     return (InputResult::None, true);
@@ -1671,7 +1879,7 @@ impl ChatComposer {
     }
 
     /// Heuristic for whether the typed slash command looks like a valid
-    /// prefix for any known command (built-in or custom prompt).
+    /// prefix for any known command (built-in, custom prompt, or user command).
     /// Empty names only count when there is no extra content after the '/'.
     fn looks_like_slash_prefix(&self, name: &str, rest_after_name: &str) -> bool {
         if name.is_empty() {
@@ -1693,6 +1901,14 @@ impl ChatComposer {
         self.custom_prompts
             .iter()
             .any(|p| fuzzy_match(&format!("{prompt_prefix}{}", p.name), name).is_some())
+            || self
+                .custom_commands
+                .iter()
+                .any(|c| fuzzy_match(&c.name, name).is_some())
+            || self
+                .mcp_prompts
+                .iter()
+                .any(|p| fuzzy_match(&p.command_name(), name).is_some())
     }
 
     /// Synchronize `self.command_popup` with the current text in the
@@ -1738,6 +1954,8 @@ impl ChatComposer {
                     let skills_enabled = self.skills_enabled();
                     let mut command_popup =
                         CommandPopup::new(self.custom_prompts.clone(), skills_enabled);
+                    command_popup.set_user_commands(self.custom_commands.clone());
+                    command_popup.set_mcp_prompts(self.mcp_prompts.clone());
                     command_popup.on_composer_text_change(first_line.to_string());
                     self.active_popup = ActivePopup::Command(command_popup);
                 }
@@ -1752,6 +1970,46 @@ impl ChatComposer {
         }
     }
 
+    /// Set the user-defined slash commands, dropping any that shadow a
+    /// built-in command.
+    /// Reports an invocation of the user-defined command `/name` so the
+    /// hooks subscribed to `user_command` run for it.
+    fn report_user_command(&self, name: &str, arguments: &str) {
+        if !self.custom_commands.iter().any(|c| c.name == name) {
+            return;
+        }
+        self.app_event_tx
+            .send(AppEvent::CodexOp(Op::RunUserCommand {
+                name: name.to_string(),
+                arguments: arguments.trim().to_string(),
+            }));
+    }
+
+    pub(crate) fn set_custom_commands(&mut self, mut commands: Vec<CustomPrompt>) {
+        commands.retain(|c| {
+            !built_in_slash_commands()
+                .into_iter()
+                .any(|(name, _)| name == c.name)
+        });
+        self.custom_commands = commands.clone();
+        if let ActivePopup::Command(popup) = &mut self.active_popup {
+            popup.set_user_commands(commands);
+        }
+    }
+
+    /// Set the conversation variables filled into `{{name}}` placeholders of
+    /// expanded custom prompts and user-defined slash commands.
+    pub(crate) fn set_variables(&mut self, variables: BTreeMap<String, String>) {
+        self.variables = variables;
+    }
+
+    pub(crate) fn set_mcp_prompts(&mut self, mcp_prompts: Vec<McpPromptCommand>) {
+        self.mcp_prompts = mcp_prompts.clone();
+        if let ActivePopup::Command(popup) = &mut self.active_popup {
+            popup.set_mcp_prompts(mcp_prompts);
+        }
+    }
+
     /// Synchronize `self.file_search_popup` with the current text in the textarea.
     /// Note this is only called when self.active_popup is NOT Command.
     fn sync_file_search_popup(&mut self, query: String) {
@@ -1966,7 +2224,23 @@ impl Renderable for ChatComposer {
     }
 }
 
+/// Command text (and cursor position) inserted when an MCP prompt is chosen
+/// from the slash popup: `/mcp:<server>:<prompt>` followed by an empty
+/// `NAME=""` placeholder for each declared argument.
+fn mcp_prompt_completion(command: &McpPromptCommand) -> (String, usize) {
+    let args = command.argument_names();
+    if args.is_empty() {
+        let text = format!("/{}", command.command_name());
+        let cursor = text.len();
+        return (text, cursor);
+    }
+    command_with_arg_placeholders(&command.command_name(), &args)
+}
+
+/// Decides what picking `prompt` (invoked as `/command`) from the popup does:
+/// insert argument placeholders for the user to fill in, or submit directly.
 fn prompt_selection_action(
+    command: &str,
     prompt: &CustomPrompt,
     first_line: &str,
     mode: PromptSelectionMode,
@@ -1977,34 +2251,34 @@ fn prompt_selection_action(
     match mode {
         PromptSelectionMode::Completion => {
             if !named_args.is_empty() {
-                let (text, cursor) =
-                    prompt_command_with_arg_placeholders(&prompt.name, &named_args);
+                let (text, cursor) = command_with_arg_placeholders(command, &named_args);
                 return PromptSelectionAction::Insert {
                     text,
                     cursor: Some(cursor),
                 };
             }
             if has_numeric {
-                let text = format!("/{PROMPTS_CMD_PREFIX}:{} ", prompt.name);
+                let text = format!("/{command} ");
                 return PromptSelectionAction::Insert { text, cursor: None };
             }
-            let text = format!("/{PROMPTS_CMD_PREFIX}:{}", prompt.name);
+            let text = format!("/{command}");
             PromptSelectionAction::Insert { text, cursor: None }
         }
         PromptSelectionMode::Submit => {
             if !named_args.is_empty() {
-                let (text, cursor) =
-                    prompt_command_with_arg_placeholders(&prompt.name, &named_args);
+                let (text, cursor) = command_with_arg_placeholders(command, &named_args);
                 return PromptSelectionAction::Insert {
                     text,
                     cursor: Some(cursor),
                 };
             }
             if has_numeric {
-                if let Some(expanded) = expand_if_numeric_with_positional_args(prompt, first_line) {
+                if let Some(expanded) =
+                    expand_if_numeric_with_positional_args(command, prompt, first_line)
+                {
                     return PromptSelectionAction::Submit { text: expanded };
                 }
-                let text = format!("/{PROMPTS_CMD_PREFIX}:{} ", prompt.name);
+                let text = format!("/{command} ");
                 return PromptSelectionAction::Insert { text, cursor: None };
             }
             PromptSelectionAction::Submit {
@@ -2029,7 +2303,7 @@ mod tests {
     use crate::bottom_pane::InputResult;
     use crate::bottom_pane::chat_composer::AttachedImage;
     use crate::bottom_pane::chat_composer::LARGE_PASTE_CHAR_THRESHOLD;
-    use crate::bottom_pane::prompt_args::extract_positional_args_for_prompt_line;
+    use crate::bottom_pane::prompt_args::extract_positional_args_for_command_line;
     use crate::bottom_pane::textarea::TextArea;
     use tokio::sync::mpsc::unbounded_channel;
 
@@ -2830,7 +3104,11 @@ mod tests {
                 Some(CommandItem::Builtin(cmd)) => {
                     assert_eq!(cmd.command(), "model")
                 }
-                Some(CommandItem::UserPrompt(_)) => {
+                Some(
+                    CommandItem::UserPrompt(_)
+                    | CommandItem::UserCommand(_)
+                    | CommandItem::McpPrompt(_),
+                ) => {
                     panic!("unexpected prompt selected for '/mo'")
                 }
                 None => panic!("no selected command for '/mo'"),
@@ -2886,7 +3164,11 @@ mod tests {
                 Some(CommandItem::Builtin(cmd)) => {
                     assert_eq!(cmd.command(), "resume")
                 }
-                Some(CommandItem::UserPrompt(_)) => {
+                Some(
+                    CommandItem::UserPrompt(_)
+                    | CommandItem::UserCommand(_)
+                    | CommandItem::McpPrompt(_),
+                ) => {
                     panic!("unexpected prompt selected for '/res'")
                 }
                 None => panic!("no selected command for '/res'"),
@@ -2944,6 +3226,9 @@ mod tests {
             InputResult::CommandWithArgs(_, _) => {
                 panic!("expected command dispatch without args for '/init'")
             }
+            InputResult::McpPrompt { text, .. } => {
+                panic!("expected command dispatch, but composer submitted MCP prompt: {text}")
+            }
             InputResult::Submitted(text) => {
                 panic!("expected command dispatch, but composer submitted literal text: {text}")
             }
@@ -2954,17 +3239,19 @@ mod tests {
 
     #[test]
     fn extract_args_supports_quoted_paths_single_arg() {
-        let args = extract_positional_args_for_prompt_line(
+        let args = extract_positional_args_for_command_line(
             "/prompts:review \"docs/My File.md\"",
-            "review",
+            "prompts:review",
         );
         assert_eq!(args, vec!["docs/My File.md".to_string()]);
     }
 
     #[test]
     fn extract_args_supports_mixed_quoted_and_unquoted() {
-        let args =
-            extract_positional_args_for_prompt_line("/prompts:cmd \"with spaces\" simple", "cmd");
+        let args = extract_positional_args_for_command_line(
+            "/prompts:cmd \"with spaces\" simple",
+            "prompts:cmd",
+        );
         assert_eq!(args, vec!["with spaces".to_string(), "simple".to_string()]);
     }
 
@@ -3020,6 +3307,9 @@ mod tests {
             InputResult::CommandWithArgs(_, _) => {
                 panic!("expected command dispatch without args for '/diff'")
             }
+            InputResult::McpPrompt { text, .. } => {
+                panic!("expected command dispatch, but composer submitted MCP prompt: {text}")
+            }
             InputResult::Submitted(text) => {
                 panic!("expected command dispatch after Tab completion, got literal submit: {text}")
             }
@@ -3056,6 +3346,9 @@ mod tests {
             InputResult::CommandWithArgs(_, _) => {
                 panic!("expected command dispatch without args for '/mention'")
             }
+            InputResult::McpPrompt { text, .. } => {
+                panic!("expected command dispatch, but composer submitted MCP prompt: {text}")
+            }
             InputResult::Submitted(text) => {
                 panic!("expected command dispatch, but composer submitted literal text: {text}")
             }
@@ -3603,6 +3896,66 @@ mod tests {
         assert_eq!(imgs, vec![tmp_path]);
     }
 
+    #[test]
+    fn pasting_oversized_image_path_inserts_text() {
+        let tmp = tempdir().expect("create TempDir");
+        let tmp_path: PathBuf = tmp.path().join("huge.png");
+        let img: ImageBuffer<Rgba<u8>, Vec<u8>> =
+            ImageBuffer::from_fn(3, 2, |_x, _y| Rgba([1, 2, 3, 255]));
+        img.save(&tmp_path).expect("failed to write temp png");
+        // Pad the file past the limit; only the PNG header is read for its
+        // dimensions.
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(&tmp_path)
+            .and_then(|file| file.set_len(MAX_IMAGE_ATTACHMENT_BYTES + 1))
+            .expect("grow file");
+
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+
+        let pasted = tmp_path.to_string_lossy().to_string();
+        composer.handle_paste(pasted.clone());
+
+        assert_eq!(composer.textarea.text(), pasted);
+        assert!(composer.take_recent_submission_images().is_empty());
+        assert!(matches!(rx.try_recv(), Ok(AppEvent::InsertHistoryCell(_))));
+    }
+
+    #[test]
+    fn try_attach_image_enforces_image_count() {
+        let tmp = tempdir().expect("create TempDir");
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+
+        for idx in 0..=MAX_ATTACHED_IMAGES {
+            let path = tmp.path().join(format!("image-{idx}.png"));
+            std::fs::write(&path, b"png").expect("write image");
+            assert_eq!(
+                composer.try_attach_image(path),
+                idx < MAX_ATTACHED_IMAGES,
+                "image {idx}"
+            );
+        }
+
+        assert_eq!(composer.attached_images.len(), MAX_ATTACHED_IMAGES);
+        assert!(matches!(rx.try_recv(), Ok(AppEvent::InsertHistoryCell(_))));
+    }
+
     #[test]
     fn selecting_custom_prompt_without_args_submits_content() {
         let prompt_text = "Hello from saved prompt";
@@ -3641,6 +3994,117 @@ mod tests {
         assert!(composer.textarea.is_empty());
     }
 
+    #[test]
+    fn user_command_with_positional_args_expands_on_enter() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+
+        composer.set_custom_commands(vec![CustomPrompt {
+            name: "deploy".to_string(),
+            path: "/tmp/config.toml".to_string().into(),
+            content: "Deploy $1 to $2".to_string(),
+            description: None,
+            argument_hint: None,
+        }]);
+
+        type_chars_humanlike(
+            &mut composer,
+            &[
+                '/', 'd', 'e', 'p', 'l', 'o', 'y', ' ', 'a', 'p', 'i', ' ', 'p', 'r', 'o', 'd',
+            ],
+        );
+
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert_eq!(
+            InputResult::Submitted("Deploy api to prod".to_string()),
+            result
+        );
+        assert!(composer.textarea.is_empty());
+    }
+
+    #[test]
+    fn user_command_without_prompt_only_runs_hooks() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+
+        composer.set_custom_commands(vec![CustomPrompt {
+            name: "lint".to_string(),
+            path: "/tmp/config.toml".to_string().into(),
+            content: String::new(),
+            description: None,
+            argument_hint: None,
+        }]);
+
+        type_chars_humanlike(
+            &mut composer,
+            &['/', 'l', 'i', 'n', 't', ' ', 's', 'r', 'c'],
+        );
+
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert_eq!(InputResult::None, result);
+        assert!(composer.textarea.is_empty());
+        let mut invocations = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            if let AppEvent::CodexOp(Op::RunUserCommand { name, arguments }) = event {
+                invocations.push((name, arguments));
+            }
+        }
+        assert_eq!(invocations, vec![("lint".to_string(), "src".to_string())]);
+    }
+
+    #[test]
+    fn user_command_fills_conversation_variables() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+
+        composer.set_custom_commands(vec![CustomPrompt {
+            name: "ship".to_string(),
+            path: "/tmp/config.toml".to_string().into(),
+            content: "Open a PR from {{branch_name}} for {{ticket}}".to_string(),
+            description: None,
+            argument_hint: None,
+        }]);
+        composer.set_variables(BTreeMap::from([(
+            "branch_name".to_string(),
+            "fix/login".to_string(),
+        )]));
+
+        type_chars_humanlike(&mut composer, &['/', 's', 'h', 'i', 'p']);
+
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert_eq!(
+            InputResult::Submitted("Open a PR from fix/login for {{ticket}}".to_string()),
+            result
+        );
+    }
+
     #[test]
     fn custom_prompt_submission_expands_arguments() {
         let (tx, _rx) = unbounded_channel::<AppEvent>();
@@ -3,6 +3,7 @@ use ratatui::layout::Rect;
 use ratatui::widgets::WidgetRef;
 
 use super::popup_consts::MAX_POPUP_ROWS;
+use super::prompt_args::McpPromptCommand;
 use super::scroll_state::ScrollState;
 use super::selection_popup_common::GenericDisplayRow;
 use super::selection_popup_common::render_rows;
@@ -22,18 +23,25 @@ fn windows_degraded_sandbox_active() -> bool {
         && !codex_core::is_windows_elevated_sandbox_enabled()
 }
 
-/// A selectable item in the popup: either a built-in command or a user prompt.
+/// A selectable item in the popup: a built-in command, a user prompt, a
+/// user-defined command, or a prompt exposed by an MCP server.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) enum CommandItem {
     Builtin(SlashCommand),
     // Index into `prompts`
     UserPrompt(usize),
+    // Index into `user_commands`
+    UserCommand(usize),
+    // Index into `mcp_prompts`
+    McpPrompt(usize),
 }
 
 pub(crate) struct CommandPopup {
     command_filter: String,
     builtins: Vec<(&'static str, SlashCommand)>,
     prompts: Vec<CustomPrompt>,
+    user_commands: Vec<CustomPrompt>,
+    mcp_prompts: Vec<McpPromptCommand>,
     state: ScrollState,
 }
 
@@ -53,6 +61,8 @@ impl CommandPopup {
             command_filter: String::new(),
             builtins,
             prompts,
+            user_commands: Vec::new(),
+            mcp_prompts: Vec::new(),
             state: ScrollState::new(),
         }
     }
@@ -72,6 +82,27 @@ impl CommandPopup {
         self.prompts.get(idx)
     }
 
+    /// Replace the user-defined commands; names that shadow a built-in
+    /// command are dropped.
+    pub(crate) fn set_user_commands(&mut self, mut commands: Vec<CustomPrompt>) {
+        commands.retain(|c| !self.builtins.iter().any(|(name, _)| *name == c.name));
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+        self.user_commands = commands;
+    }
+
+    pub(crate) fn user_command(&self, idx: usize) -> Option<&CustomPrompt> {
+        self.user_commands.get(idx)
+    }
+
+    pub(crate) fn set_mcp_prompts(&mut self, mut mcp_prompts: Vec<McpPromptCommand>) {
+        mcp_prompts.sort_by_key(McpPromptCommand::command_name);
+        self.mcp_prompts = mcp_prompts;
+    }
+
+    pub(crate) fn mcp_prompt(&self, idx: usize) -> Option<&McpPromptCommand> {
+        self.mcp_prompts.get(idx)
+    }
+
     /// Update the filter string based on the current composer text. The text
     /// passed in is expected to start with a leading '/'. Everything after the
     /// *first* '/" on the *first* line becomes the active filter that is used
@@ -127,6 +158,14 @@ impl CommandPopup {
             for idx in 0..self.prompts.len() {
                 out.push((CommandItem::UserPrompt(idx), None, 0));
             }
+            // Then user-defined commands, already sorted by name.
+            for idx in 0..self.user_commands.len() {
+                out.push((CommandItem::UserCommand(idx), None, 0));
+            }
+            // Then MCP prompts, already sorted by command name.
+            for idx in 0..self.mcp_prompts.len() {
+                out.push((CommandItem::McpPrompt(idx), None, 0));
+            }
             return out;
         }
 
@@ -144,23 +183,33 @@ impl CommandPopup {
                 out.push((CommandItem::UserPrompt(idx), Some(indices), score));
             }
         }
+        for (idx, c) in self.user_commands.iter().enumerate() {
+            if let Some((indices, score)) = fuzzy_match(&c.name, filter) {
+                out.push((CommandItem::UserCommand(idx), Some(indices), score));
+            }
+        }
+        for (idx, p) in self.mcp_prompts.iter().enumerate() {
+            if let Some((indices, score)) = fuzzy_match(&p.command_name(), filter) {
+                out.push((CommandItem::McpPrompt(idx), Some(indices), score));
+            }
+        }
         // When filtering, sort by ascending score and then by name for stability.
         out.sort_by(|a, b| {
-            a.2.cmp(&b.2).then_with(|| {
-                let an = match a.0 {
-                    CommandItem::Builtin(c) => c.command(),
-                    CommandItem::UserPrompt(i) => &self.prompts[i].name,
-                };
-                let bn = match b.0 {
-                    CommandItem::Builtin(c) => c.command(),
-                    CommandItem::UserPrompt(i) => &self.prompts[i].name,
-                };
-                an.cmp(bn)
-            })
+            a.2.cmp(&b.2)
+                .then_with(|| self.item_name(a.0).cmp(&self.item_name(b.0)))
         });
         out
     }
 
+    fn item_name(&self, item: CommandItem) -> String {
+        match item {
+            CommandItem::Builtin(c) => c.command().to_string(),
+            CommandItem::UserPrompt(i) => self.prompts[i].name.clone(),
+            CommandItem::UserCommand(i) => self.user_commands[i].name.clone(),
+            CommandItem::McpPrompt(i) => self.mcp_prompts[i].command_name(),
+        }
+    }
+
     fn filtered_items(&self) -> Vec<CommandItem> {
         self.filtered().into_iter().map(|(c, _, _)| c).collect()
     }
@@ -187,6 +236,24 @@ impl CommandPopup {
                             description,
                         )
                     }
+                    CommandItem::UserCommand(i) => {
+                        let command = &self.user_commands[i];
+                        let description = command
+                            .description
+                            .clone()
+                            .unwrap_or_else(|| "run user command".to_string());
+                        (format!("/{}", command.name), description)
+                    }
+                    CommandItem::McpPrompt(i) => {
+                        let command = &self.mcp_prompts[i];
+                        let description = command
+                            .prompt
+                            .description
+                            .clone()
+                            .or_else(|| command.prompt.title.clone())
+                            .unwrap_or_else(|| format!("run prompt from {}", command.server));
+                        (format!("/{}", command.command_name()), description)
+                    }
                 };
                 GenericDisplayRow {
                     name,
@@ -255,7 +322,9 @@ mod tests {
         let matches = popup.filtered_items();
         let has_init = matches.iter().any(|item| match item {
             CommandItem::Builtin(cmd) => cmd.command() == "init",
-            CommandItem::UserPrompt(_) => false,
+            CommandItem::UserPrompt(_)
+            | CommandItem::UserCommand(_)
+            | CommandItem::McpPrompt(_) => false,
         });
         assert!(
             has_init,
@@ -273,7 +342,13 @@ mod tests {
         let selected = popup.selected_item();
         match selected {
             Some(CommandItem::Builtin(cmd)) => assert_eq!(cmd.command(), "init"),
-            Some(CommandItem::UserPrompt(_)) => panic!("unexpected prompt selected for '/init'"),
+            Some(
+                CommandItem::UserPrompt(_)
+                | CommandItem::UserCommand(_)
+                | CommandItem::McpPrompt(_),
+            ) => {
+                panic!("unexpected prompt selected for '/init'")
+            }
             None => panic!("expected a selected command for exact match"),
         }
     }
@@ -285,7 +360,11 @@ mod tests {
         let matches = popup.filtered_items();
         match matches.first() {
             Some(CommandItem::Builtin(cmd)) => assert_eq!(cmd.command(), "model"),
-            Some(CommandItem::UserPrompt(_)) => {
+            Some(
+                CommandItem::UserPrompt(_)
+                | CommandItem::UserCommand(_)
+                | CommandItem::McpPrompt(_),
+            ) => {
                 panic!("unexpected prompt ranked before '/model' for '/mo'")
             }
             None => panic!("expected at least one match for '/mo'"),
@@ -384,6 +463,38 @@ mod tests {
         assert_eq!(description, Some("send saved prompt"));
     }
 
+    #[test]
+    fn user_commands_are_listed_without_prefix_and_skip_builtins() {
+        let mut popup = CommandPopup::new(Vec::new(), false);
+        popup.set_user_commands(vec![
+            CustomPrompt {
+                name: "deploy".to_string(),
+                path: "/tmp/config.toml".to_string().into(),
+                content: "Deploy $1".to_string(),
+                description: None,
+                argument_hint: None,
+            },
+            CustomPrompt {
+                name: "model".to_string(),
+                path: "/tmp/model.md".to_string().into(),
+                content: "shadowed".to_string(),
+                description: None,
+                argument_hint: None,
+            },
+        ]);
+        popup.on_composer_text_change("/depl".to_string());
+
+        assert_eq!(popup.selected_item(), Some(CommandItem::UserCommand(0)));
+        let rows = popup.rows_from_matches(vec![(CommandItem::UserCommand(0), None, 0)]);
+        assert_eq!(rows[0].name, "/deploy");
+        assert_eq!(rows[0].description.as_deref(), Some("run user command"));
+        assert_eq!(
+            popup.user_command(1).map(|c| c.name.as_str()),
+            None,
+            "commands shadowing built-ins should be dropped"
+        );
+    }
+
     #[test]
     fn fuzzy_filter_matches_subsequence_for_ac() {
         let mut popup = CommandPopup::new(Vec::new(), false);
@@ -394,7 +505,9 @@ mod tests {
             .into_iter()
             .filter_map(|item| match item {
                 CommandItem::Builtin(cmd) => Some(cmd.command()),
-                CommandItem::UserPrompt(_) => None,
+                CommandItem::UserPrompt(_)
+                | CommandItem::UserCommand(_)
+                | CommandItem::McpPrompt(_) => None,
             })
             .collect();
         assert!(
@@ -1,9 +1,12 @@
 //! Bottom pane: shows the ChatComposer or a BottomPaneView, if one is active.
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 use crate::app_event_sender::AppEventSender;
 use crate::bottom_pane::queued_user_messages::QueuedUserMessages;
 use crate::bottom_pane::unified_exec_footer::UnifiedExecFooter;
+use crate::keybindings::KeyAction;
+use crate::keybindings::Keymap;
 use crate::render::renderable::FlexRenderable;
 use crate::render::renderable::Renderable;
 use crate::render::renderable::RenderableItem;
@@ -31,6 +34,8 @@ mod file_search_popup;
 mod footer;
 mod list_selection_view;
 mod prompt_args;
+pub(crate) use prompt_args::McpPromptCommand;
+pub(crate) use prompt_args::McpPromptInvocation;
 mod skill_popup;
 pub(crate) use list_selection_view::SelectionViewParams;
 mod feedback_view;
@@ -79,6 +84,7 @@ pub(crate) struct BottomPane {
     ctrl_c_quit_hint: bool,
     esc_backtrack_hint: bool,
     animations_enabled: bool,
+    screen_reader: bool,
 
     /// Inline status indicator shown above the composer while a task is running.
     status: Option<StatusIndicatorWidget>,
@@ -88,6 +94,7 @@ pub(crate) struct BottomPane {
     queued_user_messages: QueuedUserMessages,
     context_window_percent: Option<i64>,
     context_window_used_tokens: Option<i64>,
+    keymap: Keymap,
 }
 
 pub(crate) struct BottomPaneParams {
@@ -98,7 +105,9 @@ pub(crate) struct BottomPaneParams {
     pub(crate) placeholder_text: String,
     pub(crate) disable_paste_burst: bool,
     pub(crate) animations_enabled: bool,
+    pub(crate) screen_reader: bool,
     pub(crate) skills: Option<Vec<SkillMetadata>>,
+    pub(crate) keymap: Keymap,
 }
 
 impl BottomPane {
@@ -111,7 +120,9 @@ impl BottomPane {
             placeholder_text,
             disable_paste_burst,
             animations_enabled,
+            screen_reader,
             skills,
+            keymap,
         } = params;
         let mut composer = ChatComposer::new(
             has_input_focus,
@@ -135,8 +146,10 @@ impl BottomPane {
             queued_user_messages: QueuedUserMessages::new(),
             esc_backtrack_hint: false,
             animations_enabled,
+            screen_reader,
             context_window_percent: None,
             context_window_used_tokens: None,
+            keymap,
         }
     }
 
@@ -192,9 +205,10 @@ impl BottomPane {
             self.request_redraw();
             InputResult::None
         } else {
-            // If a task is running and a status line is visible, allow Esc to
-            // send an interrupt even while the composer has focus.
-            if matches!(key_event.code, crossterm::event::KeyCode::Esc)
+            // If a task is running and a status line is visible, allow the
+            // interrupt key (Esc by default) to send an interrupt even while
+            // the composer has focus.
+            if self.keymap.is_press(KeyAction::Interrupt, key_event)
                 && self.is_task_running
                 && let Some(status) = &self.status
             {
@@ -203,6 +217,14 @@ impl BottomPane {
                 self.request_redraw();
                 return InputResult::None;
             }
+            if self.keymap.is_press(KeyAction::Stop, key_event)
+                && self.is_task_running
+                && let Some(status) = &self.status
+            {
+                status.stop();
+                self.request_redraw();
+                return InputResult::None;
+            }
             let (input_result, needs_redraw) = self.composer.handle_key_event(key_event);
             if needs_redraw {
                 self.request_redraw();
@@ -364,6 +386,8 @@ impl BottomPane {
                         self.app_event_tx.clone(),
                         self.frame_requester.clone(),
                         self.animations_enabled,
+                        self.screen_reader,
+                        self.screen_reader,
                     ));
                 }
                 if let Some(status) = self.status.as_mut() {
@@ -427,6 +451,12 @@ impl BottomPane {
         self.request_redraw();
     }
 
+    /// Mark the queued messages as waiting on an interrupt of the running turn.
+    pub(crate) fn set_queued_messages_interrupting(&mut self, interrupting: bool) {
+        self.queued_user_messages.interrupting = interrupting;
+        self.request_redraw();
+    }
+
     pub(crate) fn set_unified_exec_processes(&mut self, processes: Vec<String>) {
         if self.unified_exec_footer.set_processes(processes) {
             self.request_redraw();
@@ -439,6 +469,23 @@ impl BottomPane {
         self.request_redraw();
     }
 
+    /// Update the user-defined slash commands available in the composer.
+    pub(crate) fn set_custom_commands(&mut self, commands: Vec<CustomPrompt>) {
+        self.composer.set_custom_commands(commands);
+        self.request_redraw();
+    }
+
+    /// Update the conversation variables filled into expanded commands.
+    pub(crate) fn set_variables(&mut self, variables: BTreeMap<String, String>) {
+        self.composer.set_variables(variables);
+    }
+
+    /// Update MCP server prompts available for the slash popup.
+    pub(crate) fn set_mcp_prompts(&mut self, mcp_prompts: Vec<McpPromptCommand>) {
+        self.composer.set_mcp_prompts(mcp_prompts);
+        self.request_redraw();
+    }
+
     pub(crate) fn composer_is_empty(&self) -> bool {
         self.composer.is_empty()
     }
@@ -478,7 +525,12 @@ impl BottomPane {
         };
 
         // Otherwise create a new approval modal overlay.
-        let modal = ApprovalOverlay::new(request, self.app_event_tx.clone(), features.clone());
+        let modal = ApprovalOverlay::new(
+            request,
+            self.app_event_tx.clone(),
+            features.clone(),
+            self.keymap.approve.clone(),
+        );
         self.pause_status_timer_for_modal();
         self.push_view(Box::new(modal));
     }
@@ -549,6 +601,14 @@ impl BottomPane {
         }
     }
 
+    /// Like [`BottomPane::attach_image`], but enforces the attachment limits.
+    pub(crate) fn try_attach_image(&mut self, path: PathBuf) {
+        if self.view_stack.is_empty() {
+            self.composer.try_attach_image(path);
+            self.request_redraw();
+        }
+    }
+
     pub(crate) fn take_recent_submission_images(&mut self) -> Vec<PathBuf> {
         self.composer.take_recent_submission_images()
     }
@@ -624,6 +684,7 @@ mod tests {
             command: vec!["echo".into(), "ok".into()],
             reason: None,
             proposed_execpolicy_amendment: None,
+            operations: vec![],
         }
     }
 
@@ -640,7 +701,9 @@ mod tests {
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
             animations_enabled: true,
+            screen_reader: false,
             skills: Some(Vec::new()),
+            keymap: Keymap::default(),
         });
         pane.push_approval_request(exec_request(), &features);
         assert_eq!(CancellationEvent::Handled, pane.on_ctrl_c());
@@ -663,7 +726,9 @@ mod tests {
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
             animations_enabled: true,
+            screen_reader: false,
             skills: Some(Vec::new()),
+            keymap: Keymap::default(),
         });
 
         // Create an approval modal (active view).
@@ -697,7 +762,9 @@ mod tests {
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
             animations_enabled: true,
+            screen_reader: false,
             skills: Some(Vec::new()),
+            keymap: Keymap::default(),
         });
 
         // Start a running task so the status indicator is active above the composer.
@@ -764,7 +831,9 @@ mod tests {
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
             animations_enabled: true,
+            screen_reader: false,
             skills: Some(Vec::new()),
+            keymap: Keymap::default(),
         });
 
         // Begin a task: show initial status.
@@ -791,7 +860,9 @@ mod tests {
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
             animations_enabled: true,
+            screen_reader: false,
             skills: Some(Vec::new()),
+            keymap: Keymap::default(),
         });
 
         // Activate spinner (status view replaces composer) with no live ring.
@@ -822,7 +893,9 @@ mod tests {
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
             animations_enabled: true,
+            screen_reader: false,
             skills: Some(Vec::new()),
+            keymap: Keymap::default(),
         });
 
         pane.set_task_running(true);
@@ -850,7 +923,9 @@ mod tests {
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
             animations_enabled: true,
+            screen_reader: false,
             skills: Some(Vec::new()),
+            keymap: Keymap::default(),
         });
 
         pane.set_task_running(true);
@@ -1,11 +1,16 @@
 use codex_protocol::custom_prompts::CustomPrompt;
 use codex_protocol::custom_prompts::PROMPTS_CMD_PREFIX;
 use lazy_static::lazy_static;
+use mcp_types::Prompt as McpPrompt;
 use regex_lite::Regex;
 use shlex::Shlex;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
+/// Slash-command prefix for prompts exposed by MCP servers, e.g.
+/// `/mcp:<server>:<prompt>`.
+pub const MCP_PROMPT_CMD_PREFIX: &str = "mcp";
+
 lazy_static! {
     static ref PROMPT_ARG_REGEX: Regex =
         Regex::new(r"\$[A-Z][A-Z0-9_]*").unwrap_or_else(|_| std::process::abort());
@@ -56,6 +61,50 @@ impl PromptExpansionError {
     }
 }
 
+/// A prompt advertised by an MCP server via `prompts/list`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct McpPromptCommand {
+    pub server: String,
+    pub prompt: McpPrompt,
+}
+
+impl McpPromptCommand {
+    /// Slash command name without the leading `/`.
+    pub fn command_name(&self) -> String {
+        format!(
+            "{MCP_PROMPT_CMD_PREFIX}:{}:{}",
+            self.server, self.prompt.name
+        )
+    }
+
+    pub fn argument_names(&self) -> Vec<String> {
+        self.prompt
+            .arguments
+            .iter()
+            .flatten()
+            .map(|arg| arg.name.clone())
+            .collect()
+    }
+
+    fn required_argument_names(&self) -> Vec<String> {
+        self.prompt
+            .arguments
+            .iter()
+            .flatten()
+            .filter(|arg| arg.required.unwrap_or(false))
+            .map(|arg| arg.name.clone())
+            .collect()
+    }
+}
+
+/// A fully parsed `/mcp:<server>:<prompt> key=value …` invocation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct McpPromptInvocation {
+    pub server: String,
+    pub name: String,
+    pub arguments: HashMap<String, String>,
+}
+
 /// Parse a first-line slash command of the form `/name <rest>`.
 /// Returns `(name, rest_after_name)` if the line begins with `/` and contains
 /// a non-empty name; otherwise returns `None`.
@@ -147,10 +196,34 @@ pub fn expand_custom_prompt(
         return Ok(None);
     };
 
-    let prompt = match custom_prompts.iter().find(|p| p.name == prompt_name) {
-        Some(prompt) => prompt,
-        None => return Ok(None),
+    match custom_prompts.iter().find(|p| p.name == prompt_name) {
+        Some(prompt) => expand_prompt_template(name, rest, prompt).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Expands a message of the form `/name [value] …` using a matching
+/// user-defined slash command. Placeholders work exactly as for
+/// [`expand_custom_prompt`]; returns `Ok(None)` when no command is named `name`.
+pub fn expand_user_command(
+    text: &str,
+    commands: &[CustomPrompt],
+) -> Result<Option<String>, PromptExpansionError> {
+    let Some((name, rest)) = parse_slash_name(text) else {
+        return Ok(None);
     };
+    match commands.iter().find(|c| c.name == name) {
+        Some(command) => expand_prompt_template(name, rest, command).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Fills `prompt`'s placeholders from the text following `/command`.
+fn expand_prompt_template(
+    name: &str,
+    rest: &str,
+    prompt: &CustomPrompt,
+) -> Result<String, PromptExpansionError> {
     // If there are named placeholders, expect key=value inputs.
     let required = prompt_argument_names(&prompt.content);
     if !required.is_empty() {
@@ -183,13 +256,54 @@ pub fn expand_custom_prompt(
                 .cloned()
                 .unwrap_or_else(|| whole.to_string())
         });
-        return Ok(Some(replaced.into_owned()));
+        return Ok(replaced.into_owned());
     }
 
     // Otherwise, treat it as numeric/positional placeholder prompt (or none).
     let pos_args: Vec<String> = Shlex::new(rest).collect();
-    let expanded = expand_numeric_placeholders(&prompt.content, &pos_args);
-    Ok(Some(expanded))
+    Ok(expand_numeric_placeholders(&prompt.content, &pos_args))
+}
+
+/// Parses a message of the form `/mcp:<server>:<prompt> key=value …` against the
+/// known MCP prompts.
+///
+/// Returns `Ok(None)` when the text is not an invocation of a known MCP prompt.
+/// Values are passed to the server verbatim and empty values are dropped, so
+/// unfilled `ARG=""` placeholders for optional arguments are ignored. Required
+/// arguments declared by the prompt must be present.
+pub fn parse_mcp_prompt_invocation(
+    text: &str,
+    mcp_prompts: &[McpPromptCommand],
+) -> Result<Option<McpPromptInvocation>, PromptExpansionError> {
+    let Some((name, rest)) = parse_slash_name(text) else {
+        return Ok(None);
+    };
+    let Some(prompt) = mcp_prompts.iter().find(|p| p.command_name() == name) else {
+        return Ok(None);
+    };
+
+    let mut arguments = parse_prompt_inputs(rest).map_err(|error| PromptExpansionError::Args {
+        command: format!("/{name}"),
+        error,
+    })?;
+    arguments.retain(|_, value| !value.is_empty());
+    let missing: Vec<String> = prompt
+        .required_argument_names()
+        .into_iter()
+        .filter(|k| !arguments.contains_key(k))
+        .collect();
+    if !missing.is_empty() {
+        return Err(PromptExpansionError::MissingArgs {
+            command: format!("/{name}"),
+            missing,
+        });
+    }
+
+    Ok(Some(McpPromptInvocation {
+        server: prompt.server.clone(),
+        name: prompt.prompt.name.clone(),
+        arguments,
+    }))
 }
 
 /// Detect whether `content` contains numeric placeholders ($1..$9) or `$ARGUMENTS`.
@@ -211,20 +325,16 @@ pub fn prompt_has_numeric_placeholders(content: &str) -> bool {
     false
 }
 
-/// Extract positional arguments from a composer first line like "/name a b" for a given prompt name.
+/// Extract positional arguments from a composer first line like "/command a b".
 /// Returns empty when the command name does not match or when there are no args.
-pub fn extract_positional_args_for_prompt_line(line: &str, prompt_name: &str) -> Vec<String> {
+pub fn extract_positional_args_for_command_line(line: &str, command: &str) -> Vec<String> {
     let trimmed = line.trim_start();
     let Some(rest) = trimmed.strip_prefix('/') else {
         return Vec::new();
     };
-    // Require the explicit prompts prefix for custom prompt invocations.
-    let Some(after_prefix) = rest.strip_prefix(&format!("{PROMPTS_CMD_PREFIX}:")) else {
-        return Vec::new();
-    };
-    let mut parts = after_prefix.splitn(2, char::is_whitespace);
+    let mut parts = rest.splitn(2, char::is_whitespace);
     let cmd = parts.next().unwrap_or("");
-    if cmd != prompt_name {
+    if cmd != command {
         return Vec::new();
     }
     let args_str = parts.next().unwrap_or("").trim();
@@ -234,9 +344,11 @@ pub fn extract_positional_args_for_prompt_line(line: &str, prompt_name: &str) ->
     parse_positional_args(args_str)
 }
 
-/// If the prompt only uses numeric placeholders and the first line contains
-/// positional args for it, expand and return Some(expanded); otherwise None.
+/// If the prompt invoked as `/command` only uses numeric placeholders and the
+/// first line contains positional args for it, expand and return
+/// Some(expanded); otherwise None.
 pub fn expand_if_numeric_with_positional_args(
+    command: &str,
     prompt: &CustomPrompt,
     first_line: &str,
 ) -> Option<String> {
@@ -246,7 +358,7 @@ pub fn expand_if_numeric_with_positional_args(
     if !prompt_has_numeric_placeholders(&prompt.content) {
         return None;
     }
-    let args = extract_positional_args_for_prompt_line(first_line, &prompt.name);
+    let args = extract_positional_args_for_command_line(first_line, command);
     if args.is_empty() {
         return None;
     }
@@ -296,10 +408,10 @@ pub fn expand_numeric_placeholders(content: &str, args: &[String]) -> String {
     out
 }
 
-/// Constructs a command text for a custom prompt with arguments.
+/// Constructs `/command ARG=""…` for an arbitrary slash command name.
 /// Returns the text and the cursor position (inside the first double quote).
-pub fn prompt_command_with_arg_placeholders(name: &str, args: &[String]) -> (String, usize) {
-    let mut text = format!("/{PROMPTS_CMD_PREFIX}:{name}");
+pub fn command_with_arg_placeholders(command: &str, args: &[String]) -> (String, usize) {
+    let mut text = format!("/{command}");
     let mut cursor: usize = text.len();
     for (i, arg) in args.iter().enumerate() {
         text.push_str(format!(" {arg}=\"\"").as_str());
@@ -313,6 +425,7 @@ pub fn prompt_command_with_arg_placeholders(name: &str, args: &[String]) -> (Str
 #[cfg(test)]
 mod tests {
     use super::*;
+    use pretty_assertions::assert_eq;
 
     #[test]
     fn expand_arguments_basic() {
@@ -403,4 +516,90 @@ mod tests {
         let out = expand_custom_prompt("/prompts:my-prompt", &prompts).unwrap();
         assert_eq!(out, Some("literal $$USER".to_string()));
     }
+
+    #[test]
+    fn user_commands_expand_without_a_prefix() {
+        let commands = vec![CustomPrompt {
+            name: "deploy".to_string(),
+            path: "/tmp/config.toml".to_string().into(),
+            content: "Deploy $1 to $2".to_string(),
+            description: None,
+            argument_hint: None,
+        }];
+
+        assert_eq!(
+            expand_user_command("/deploy api staging", &commands).unwrap(),
+            Some("Deploy api to staging".to_string())
+        );
+        assert_eq!(
+            expand_user_command("/prompts:deploy api", &commands).unwrap(),
+            None
+        );
+        assert_eq!(
+            expand_if_numeric_with_positional_args("deploy", &commands[0], "/deploy web prod"),
+            Some("Deploy web to prod".to_string())
+        );
+    }
+
+    fn mcp_prompt(server: &str, name: &str, args: &[(&str, bool)]) -> McpPromptCommand {
+        McpPromptCommand {
+            server: server.to_string(),
+            prompt: McpPrompt {
+                arguments: Some(
+                    args.iter()
+                        .map(|(arg, required)| mcp_types::PromptArgument {
+                            description: None,
+                            name: (*arg).to_string(),
+                            required: Some(*required),
+                            title: None,
+                        })
+                        .collect(),
+                ),
+                description: None,
+                name: name.to_string(),
+                title: None,
+            },
+        }
+    }
+
+    #[test]
+    fn mcp_prompt_invocation_collects_arguments() {
+        let prompts = vec![mcp_prompt(
+            "docs",
+            "summarize",
+            &[("topic", true), ("tone", false)],
+        )];
+
+        let out = parse_mcp_prompt_invocation(
+            "/mcp:docs:summarize topic=\"auth flow\" tone=\"\"",
+            &prompts,
+        )
+        .unwrap();
+        assert_eq!(
+            out,
+            Some(McpPromptInvocation {
+                server: "docs".to_string(),
+                name: "summarize".to_string(),
+                arguments: HashMap::from([("topic".to_string(), "auth flow".to_string())]),
+            })
+        );
+    }
+
+    #[test]
+    fn mcp_prompt_invocation_reports_missing_required_args() {
+        let prompts = vec![mcp_prompt("docs", "summarize", &[("topic", true)])];
+
+        let err = parse_mcp_prompt_invocation("/mcp:docs:summarize topic=\"\"", &prompts)
+            .unwrap_err()
+            .user_message();
+        assert!(err.contains("Missing required args for /mcp:docs:summarize: topic"));
+    }
+
+    #[test]
+    fn unknown_mcp_prompt_is_not_an_invocation() {
+        let prompts = vec![mcp_prompt("docs", "summarize", &[])];
+
+        let out = parse_mcp_prompt_invocation("/mcp:docs:other", &prompts).unwrap();
+        assert_eq!(out, None);
+    }
 }
@@ -13,12 +13,15 @@ use crate::wrapping::word_wrap_lines;
 /// Widget that displays a list of user messages queued while a turn is in progress.
 pub(crate) struct QueuedUserMessages {
     pub messages: Vec<String>,
+    /// The running turn is being interrupted so the first message can be sent.
+    pub interrupting: bool,
 }
 
 impl QueuedUserMessages {
     pub(crate) fn new() -> Self {
         Self {
             messages: Vec::new(),
+            interrupting: false,
         }
     }
 
@@ -45,14 +48,15 @@ impl QueuedUserMessages {
             }
         }
 
-        lines.push(
-            Line::from(vec![
-                "    ".into(),
-                key_hint::alt(KeyCode::Up).into(),
-                " edit".into(),
-            ])
-            .dim(),
-        );
+        let mut hint = vec![
+            "    ".into(),
+            key_hint::alt(KeyCode::Up).into(),
+            " edit".into(),
+        ];
+        if self.interrupting {
+            hint.push(" · interrupting to send".into());
+        }
+        lines.push(Line::from(hint).dim());
 
         Paragraph::new(lines).into()
     }
@@ -142,6 +146,18 @@ mod tests {
         assert_snapshot!("render_wrapped_message", format!("{buf:?}"));
     }
 
+    #[test]
+    fn render_interrupting_hint() {
+        let mut queue = QueuedUserMessages::new();
+        queue.messages.push("Stop, use the other API".to_string());
+        queue.interrupting = true;
+        let width = 40;
+        let height = queue.desired_height(width);
+        let mut buf = Buffer::empty(Rect::new(0, 0, width, height));
+        queue.render(Rect::new(0, 0, width, height), &mut buf);
+        assert_snapshot!("render_interrupting_hint", format!("{buf:?}"));
+    }
+
     #[test]
     fn render_many_line_message() {
         let mut queue = QueuedUserMessages::new();
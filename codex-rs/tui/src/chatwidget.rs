@@ -1,15 +1,21 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
+use std::time::Instant;
 
 use codex_app_server_protocol::AuthMode;
 use codex_backend_client::Client as BackendClient;
 use codex_core::config::Config;
 use codex_core::config::ConstraintResult;
-use codex_core::config::types::Notifications;
+use codex_core::config::types::QueuedMessageDelivery;
+use codex_core::cost::SessionStats;
+use codex_core::cost::TurnStats;
+use codex_core::cost::pricing_for;
 use codex_core::features::FEATURES;
 use codex_core::features::Feature;
 use codex_core::git_info::current_branch_name;
@@ -24,6 +30,11 @@ use codex_core::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::ConfigOverrideAppliedEvent;
+use codex_core::protocol::ContentSource;
+use codex_core::protocol::ContextRepairedEvent;
+use codex_core::protocol::ContextSource;
+use codex_core::protocol::ContextSourcesEvent;
 use codex_core::protocol::CreditsSnapshot;
 use codex_core::protocol::DeprecationNoticeEvent;
 use codex_core::protocol::ErrorEvent;
@@ -34,17 +45,27 @@ use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::ExecCommandSource;
 use codex_core::protocol::ExitedReviewModeEvent;
+use codex_core::protocol::GuardrailTrippedEvent;
+use codex_core::protocol::HookSource;
+use codex_core::protocol::InjectionSuspectedEvent;
 use codex_core::protocol::ListCustomPromptsResponseEvent;
+use codex_core::protocol::ListHooksResponseEvent;
 use codex_core::protocol::ListSkillsResponseEvent;
 use codex_core::protocol::McpListToolsResponseEvent;
+use codex_core::protocol::McpServerDownEvent;
+use codex_core::protocol::McpServerRestartedEvent;
 use codex_core::protocol::McpStartupCompleteEvent;
 use codex_core::protocol::McpStartupStatus;
 use codex_core::protocol::McpStartupUpdateEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
+use codex_core::protocol::ModelSettingsChangedEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
+use codex_core::protocol::ProjectDocsUpdatedEvent;
+use codex_core::protocol::PullRequestPublishedEvent;
 use codex_core::protocol::RateLimitSnapshot;
+use codex_core::protocol::RedactionAppliedEvent;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::ReviewTarget;
 use codex_core::protocol::SkillsListEntry;
@@ -55,23 +76,32 @@ use codex_core::protocol::TokenUsageInfo;
 use codex_core::protocol::TurnAbortReason;
 use codex_core::protocol::TurnCompleteEvent;
 use codex_core::protocol::TurnDiffEvent;
+use codex_core::protocol::TurnTiming;
 use codex_core::protocol::UndoCompletedEvent;
 use codex_core::protocol::UndoStartedEvent;
 use codex_core::protocol::UserMessageEvent;
+use codex_core::protocol::VariablesUpdatedEvent;
 use codex_core::protocol::ViewImageToolCallEvent;
 use codex_core::protocol::WarningEvent;
+use codex_core::protocol::WatchdogAction;
+use codex_core::protocol::WatchdogFiredEvent;
+use codex_core::protocol::WatchedFilesChangedEvent;
 use codex_core::protocol::WebSearchBeginEvent;
 use codex_core::protocol::WebSearchEndEvent;
+use codex_core::rollout_metadata::tag_thread;
 use codex_core::skills::model::SkillMetadata;
 use codex_protocol::ThreadId;
 use codex_protocol::account::PlanType;
 use codex_protocol::approvals::ElicitationRequestEvent;
+use codex_protocol::config_types::Verbosity;
 use codex_protocol::parse_command::ParsedCommand;
 use codex_protocol::user_input::UserInput;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
 use crossterm::event::KeyModifiers;
+use mcp_types::Prompt;
+use mcp_types::Resource;
 use rand::Rng;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
@@ -96,23 +126,34 @@ use crate::bottom_pane::BottomPaneParams;
 use crate::bottom_pane::CancellationEvent;
 use crate::bottom_pane::ExperimentalFeaturesView;
 use crate::bottom_pane::InputResult;
+use crate::bottom_pane::McpPromptCommand;
+use crate::bottom_pane::McpPromptInvocation;
 use crate::bottom_pane::SelectionAction;
 use crate::bottom_pane::SelectionItem;
 use crate::bottom_pane::SelectionViewParams;
 use crate::bottom_pane::custom_prompt_view::CustomPromptView;
 use crate::bottom_pane::popup_consts::standard_popup_hint_line;
-use crate::clipboard_paste::paste_image_to_temp_png;
+use crate::clipboard_paste::PasteImageError;
+use crate::clipboard_paste::SessionAttachments;
+use crate::clipboard_paste::paste_image_to_png_file;
+use crate::command_palette::CommandHistory;
+use crate::command_palette::CommandState;
+use crate::command_palette::prefix_candidates;
 use crate::diff_render::display_path_for;
 use crate::exec_cell::CommandOutput;
 use crate::exec_cell::ExecCell;
 use crate::exec_cell::new_active_exec_command;
+use crate::exec_command::escape_command;
 use crate::exec_command::strip_bash_lc_and_escape;
 use crate::get_git_diff::get_git_diff;
+use crate::get_git_diff::get_workspace_diff;
 use crate::history_cell;
 use crate::history_cell::AgentMessageCell;
 use crate::history_cell::HistoryCell;
 use crate::history_cell::McpToolCallCell;
 use crate::history_cell::PlainHistoryCell;
+use crate::keybindings::KeyAction;
+use crate::keybindings::Keymap;
 use crate::markdown::append_markdown;
 use crate::render::Insets;
 use crate::render::renderable::ColumnRenderable;
@@ -122,7 +163,7 @@ use crate::render::renderable::RenderableExt;
 use crate::render::renderable::RenderableItem;
 use crate::slash_command::SlashCommand;
 use crate::status::RateLimitSnapshotDisplay;
-use crate::text_formatting::truncate_text;
+use crate::tabs::TabSummary;
 use crate::tui::FrameRequester;
 mod interrupts;
 use self::interrupts::InterruptManager;
@@ -151,6 +192,8 @@ use strum::IntoEnumIterator;
 const USER_SHELL_COMMAND_HELP_TITLE: &str = "Prefix a command with ! to run it locally";
 const USER_SHELL_COMMAND_HELP_HINT: &str = "Example: !ls";
 const DEFAULT_OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+/// Number of recent hook firings listed at the top of the `/hooks` popup.
+const HOOKS_POPUP_RECENT_FIRINGS: usize = 5;
 // Track information about an in-flight exec command.
 struct RunningCommand {
     command: Vec<String>,
@@ -184,6 +227,15 @@ fn is_unified_exec_source(source: ExecCommandSource) -> bool {
     )
 }
 
+/// Commands the agent started, as opposed to `!` shell commands or writes to a
+/// running process.
+fn is_agent_command(source: ExecCommandSource) -> bool {
+    matches!(
+        source,
+        ExecCommandSource::Agent | ExecCommandSource::UnifiedExecStartup
+    )
+}
+
 fn is_standard_tool_call(parsed_cmd: &[ParsedCommand]) -> bool {
     !parsed_cmd.is_empty()
         && parsed_cmd
@@ -337,6 +389,8 @@ pub(crate) struct ChatWidget {
     last_unified_wait: Option<UnifiedExecWaitState>,
     task_complete_pending: bool,
     unified_exec_processes: Vec<UnifiedExecProcessSummary>,
+    // Commands listed by `/shell-commands`.
+    command_history: CommandHistory,
     mcp_startup_status: Option<HashMap<String, McpStartupStatus>>,
     // Queue of interruptive UI events deferred during an active write cycle
     interrupts: InterruptManager,
@@ -357,8 +411,21 @@ pub(crate) struct ChatWidget {
     suppress_session_configured_redraw: bool,
     // User messages queued while a turn is in progress
     queued_user_messages: VecDeque<UserMessage>,
-    // Pending notification to show when unfocused on next Draw
-    pending_notification: Option<Notification>,
+    // An interrupt was sent so the first queued message can go out right away
+    // (`tui.queued_message_delivery = "interrupt"`).
+    interrupting_for_queued_message: bool,
+    // Diff of the most recent turn that edited files, shown by `/diff last`.
+    last_turn_diff: Option<String>,
+    // Token totals when the running turn started, to tell what it used.
+    turn_start_usage: TokenUsage,
+    // When the running turn started; aborted turns report no timing of their own.
+    turn_started_at: Option<Instant>,
+    // Totals over the finished turns, shown by `/stats`.
+    session_stats: SessionStats,
+    // Everything the model was given context from, shown by `/sources`.
+    context_sources: BTreeSet<ContentSource>,
+    // Images pasted from the clipboard during this session.
+    attachments: SessionAttachments,
     // Simple review mode flag; used to adjust layout and banners.
     is_review_mode: bool,
     // Snapshot of token usage to restore after review mode exits.
@@ -372,11 +439,23 @@ pub(crate) struct ChatWidget {
     // Current session rollout path (if known)
     current_rollout_path: Option<PathBuf>,
     external_editor_state: ExternalEditorState,
+    // Set while waiting for the MCP listing that backs the /resources picker.
+    mcp_resource_picker_pending: bool,
+    // Set while waiting for the MCP listing that refreshes `/mcp:` prompts
+    // after startup; the listing itself is not rendered.
+    mcp_prompt_refresh_pending: bool,
+    // MCP resources (server, uri) attached to the next submitted message.
+    attached_mcp_resources: Vec<(String, String)>,
+    // Key bindings from `[tui.keybindings]`, validated at startup.
+    keymap: Keymap,
 }
 
 struct UserMessage {
     text: String,
     image_paths: Vec<PathBuf>,
+    // Set when `text` is an `/mcp:<server>:<prompt>` invocation; the prompt is
+    // sent instead of the literal text.
+    mcp_prompt: Option<McpPromptInvocation>,
 }
 
 impl From<String> for UserMessage {
@@ -384,6 +463,7 @@ impl From<String> for UserMessage {
         Self {
             text,
             image_paths: Vec::new(),
+            mcp_prompt: None,
         }
     }
 }
@@ -393,6 +473,7 @@ impl From<&str> for UserMessage {
         Self {
             text: text.to_string(),
             image_paths: Vec::new(),
+            mcp_prompt: None,
         }
     }
 }
@@ -401,7 +482,11 @@ fn create_initial_user_message(text: String, image_paths: Vec<PathBuf>) -> Optio
     if text.is_empty() && image_paths.is_empty() {
         None
     } else {
-        Some(UserMessage { text, image_paths })
+        Some(UserMessage {
+            text,
+            image_paths,
+            mcp_prompt: None,
+        })
     }
 }
 
@@ -569,26 +654,37 @@ impl ChatWidget {
         self.set_status_header(String::from("Working"));
         self.full_reasoning_buffer.clear();
         self.reasoning_buffer.clear();
+        self.turn_start_usage = self
+            .token_info
+            .as_ref()
+            .map(|info| info.total_token_usage.clone())
+            .unwrap_or_default();
+        self.turn_started_at = Some(Instant::now());
+        self.announce("Codex is working.".to_string());
         self.request_redraw();
     }
 
-    fn on_task_complete(&mut self, last_agent_message: Option<String>) {
+    fn on_task_complete(&mut self, timing: Option<TurnTiming>) {
         // If a stream is currently active, finalize it.
         self.flush_answer_stream_with_separator();
         self.flush_wait_cell();
+        if let Some(timing) = timing {
+            self.add_turn_stats(timing);
+        }
+        self.turn_started_at = None;
         // Mark task stopped and request redraw now that all content is in history.
         self.bottom_pane.set_task_running(false);
         self.running_commands.clear();
         self.suppressed_exec_calls.clear();
         self.last_unified_wait = None;
+        // The turn finished before the interrupt landed; the queued message
+        // goes out as the next turn anyway.
+        self.interrupting_for_queued_message = false;
+        self.announce("Codex is done and waiting for you.".to_string());
         self.request_redraw();
 
         // If there is a queued user message, send exactly one now to begin the next turn.
         self.maybe_send_next_queued_input();
-        // Emit a notification when the turn completes (suppressed if focused).
-        self.notify(Notification::AgentTurnComplete {
-            response: last_agent_message.unwrap_or_default(),
-        });
 
         self.maybe_show_pending_rate_limit_prompt();
     }
@@ -793,22 +889,102 @@ impl ChatWidget {
             self.on_warning(format!("MCP startup incomplete ({})", parts.join("; ")));
         }
 
+        if !ev.ready.is_empty() {
+            self.mcp_prompt_refresh_pending = true;
+            self.submit_op(Op::ListMcpTools);
+        }
+
         self.mcp_startup_status = None;
         self.bottom_pane.set_task_running(false);
         self.maybe_send_next_queued_input();
         self.request_redraw();
     }
 
+    fn on_mcp_server_down(&mut self, ev: McpServerDownEvent) {
+        let McpServerDownEvent {
+            server,
+            reason,
+            restarting,
+        } = ev;
+        let action = if restarting {
+            "restarting it"
+        } else {
+            "gave up restarting it"
+        };
+        self.on_warning(format!(
+            "MCP server `{server}` is down ({reason}); {action}"
+        ));
+    }
+
+    fn on_mcp_server_restarted(&mut self, ev: McpServerRestartedEvent) {
+        self.add_info_message(format!("MCP server `{}` restarted", ev.server), None);
+        // The restarted server may advertise a different set of prompts.
+        self.mcp_prompt_refresh_pending = true;
+        self.submit_op(Op::ListMcpTools);
+    }
+
+    /// Keeps the widget in sync when the session settings are changed from
+    /// outside the widget, e.g. by an embedder holding a `ConfigHandle`.
+    fn on_config_override_applied(&mut self, ev: ConfigOverrideAppliedEvent) {
+        if let Some(policy) = ev.approval_policy {
+            self.set_approval_policy(policy);
+        }
+        if let Some(policy) = ev.sandbox_policy
+            && let Err(err) = self.set_sandbox_policy(policy)
+        {
+            tracing::warn!(%err, "failed to set sandbox_policy on chat config");
+        }
+        self.request_redraw();
+    }
+
+    fn on_model_settings_changed(&mut self, ev: ModelSettingsChangedEvent) {
+        // Route model and effort through the app so its copy stays in sync.
+        self.app_event_tx
+            .send(AppEvent::UpdateModel(ev.model.clone()));
+        self.app_event_tx
+            .send(AppEvent::UpdateReasoningEffort(ev.effort));
+        self.config.model_verbosity = ev.verbosity;
+        let effort = ev
+            .effort
+            .map_or_else(|| "default".to_string(), |effort| effort.to_string());
+        let verbosity = ev
+            .verbosity
+            .map_or_else(|| "default".to_string(), |verbosity| verbosity.to_string());
+        self.add_info_message(
+            format!(
+                "Model settings: {}, effort {effort}, verbosity {verbosity}",
+                ev.model
+            ),
+            None,
+        );
+    }
+
     /// Handle a turn aborted due to user interrupt (Esc).
     /// When there are queued user messages, restore them into the composer
-    /// separated by newlines rather than auto‑submitting the next one.
+    /// separated by newlines rather than auto‑submitting the next one, unless
+    /// the turn was interrupted to deliver them.
     fn on_interrupted_turn(&mut self, reason: TurnAbortReason) {
         // Finalize, log a gentle prompt, and clear running state.
         self.finalize_turn();
         self.unified_exec_processes.clear();
         self.sync_unified_exec_footer();
 
-        if reason != TurnAbortReason::ReviewEnded {
+        if std::mem::take(&mut self.interrupting_for_queued_message)
+            && reason == TurnAbortReason::Interrupted
+        {
+            self.add_info_message("Interrupted to send your queued message.".to_string(), None);
+            self.maybe_send_next_queued_input();
+            self.request_redraw();
+            return;
+        }
+
+        if reason == TurnAbortReason::Stopped {
+            self.add_info_message(
+                "Stopped - the partial reply is kept. Add guidance and send to continue."
+                    .to_string(),
+                None,
+            );
+        } else if reason != TurnAbortReason::ReviewEnded {
             self.add_to_history(history_cell::new_error_event(
                 "Conversation interrupted - tell the model what to do differently. Something went wrong? Hit `/feedback` to report the issue.".to_owned(),
             ));
@@ -844,6 +1020,8 @@ impl ChatWidget {
     }
 
     fn on_exec_approval_request(&mut self, id: String, ev: ExecApprovalRequestEvent) {
+        self.command_history
+            .record(&ev.call_id, &ev.command, CommandState::AwaitingApproval);
         let id2 = id.clone();
         let ev2 = ev.clone();
         self.defer_or_handle(
@@ -871,6 +1049,10 @@ impl ChatWidget {
 
     fn on_exec_command_begin(&mut self, ev: ExecCommandBeginEvent) {
         self.flush_answer_stream_with_separator();
+        if is_agent_command(ev.source) {
+            self.command_history
+                .record(&ev.call_id, &ev.command, CommandState::Running);
+        }
         if is_unified_exec_source(ev.source) {
             self.track_unified_exec_process_begin(&ev);
             if !is_standard_tool_call(&ev.parsed_cmd) {
@@ -961,6 +1143,115 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    fn on_redaction_applied(&mut self, event: RedactionAppliedEvent) {
+        let count: u32 = event
+            .redactions
+            .iter()
+            .map(|redaction| redaction.count)
+            .sum();
+        let rules = event
+            .redactions
+            .iter()
+            .map(|redaction| redaction.rule.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        if rules == "source" {
+            self.add_info_message(
+                "Withheld a tool output read from a source listed in `[redaction] sources`."
+                    .to_string(),
+                None,
+            );
+            return;
+        }
+        let noun = if count == 1 { "secret" } else { "secrets" };
+        self.add_info_message(
+            format!("Redacted {count} {noun} from tool output before sending it to the model."),
+            Some(rules),
+        );
+    }
+
+    fn on_context_sources(&mut self, event: ContextSourcesEvent) {
+        self.context_sources.extend(event.sources);
+    }
+
+    fn on_context_repaired(&mut self, event: ContextRepairedEvent) {
+        let message = if event.compacted {
+            "The request was too long for the model; compacted the conversation and retried."
+        } else {
+            "The request was too long for the model; trimmed older context and retried."
+        };
+        let dropped = event
+            .dropped
+            .iter()
+            .map(|dropped| {
+                let source = match dropped.source {
+                    ContextSource::ProjectDocs => "project docs",
+                    ContextSource::ToolOutputs => "tool outputs",
+                    ContextSource::Pinned => "pinned items",
+                    ContextSource::History => "history",
+                };
+                format!(
+                    "{source}: {} item(s), ~{} tokens",
+                    dropped.items, dropped.tokens
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.add_info_message(message.to_string(), Some(dropped));
+    }
+
+    fn on_injection_suspected(&mut self, event: InjectionSuspectedEvent) {
+        let message = if event.stripped {
+            "Removed suspected prompt injection from tool output."
+        } else {
+            "Tool output looks like a prompt injection; the model was told to treat it as data."
+        };
+        self.add_info_message(message.to_string(), Some(event.reasons.join(", ")));
+    }
+
+    fn on_guardrail_tripped(&mut self, event: GuardrailTrippedEvent) {
+        self.add_to_history(history_cell::new_error_event(event.message));
+        self.request_redraw();
+    }
+
+    fn on_watchdog_fired(&mut self, event: WatchdogFiredEvent) {
+        match event.action {
+            WatchdogAction::Notify => self.on_warning(event.message),
+            WatchdogAction::Abort | WatchdogAction::Deny => {
+                self.add_to_history(history_cell::new_error_event(event.message));
+                self.request_redraw();
+            }
+        }
+    }
+
+    fn on_project_docs_updated(&mut self, event: ProjectDocsUpdatedEvent) {
+        let paths = event
+            .paths
+            .iter()
+            .map(|path| display_path_for(path, &self.config.cwd))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let hint = (!paths.is_empty()).then_some(paths);
+        self.add_info_message(
+            "AGENTS.md changed; the model will follow the updated instructions.".to_string(),
+            hint,
+        );
+    }
+
+    fn on_watched_files_changed(&mut self, event: WatchedFilesChangedEvent) {
+        let paths = event
+            .changed
+            .iter()
+            .chain(&event.removed)
+            .map(|path| display_path_for(path, &self.config.cwd))
+            .collect::<Vec<_>>()
+            .join(", ");
+        self.add_info_message(
+            "Files changed outside the session; the model will re-read them.".to_string(),
+            Some(paths),
+        );
+    }
+
     fn on_patch_apply_end(&mut self, event: codex_core::protocol::PatchApplyEndEvent) {
         let ev2 = event.clone();
         self.defer_or_handle(
@@ -970,6 +1261,13 @@ impl ChatWidget {
     }
 
     fn on_exec_command_end(&mut self, ev: ExecCommandEndEvent) {
+        if is_agent_command(ev.source) {
+            self.command_history.record(
+                &ev.call_id,
+                &ev.command,
+                CommandState::Exited(ev.exit_code),
+            );
+        }
         if is_unified_exec_source(ev.source) {
             self.track_unified_exec_process_end(&ev);
             if !self.bottom_pane.is_task_running() {
@@ -1099,6 +1397,22 @@ impl ChatWidget {
         }
     }
 
+    fn on_pull_request_published(&mut self, event: PullRequestPublishedEvent) {
+        let PullRequestPublishedEvent { branch, url, .. } = event;
+        match url {
+            Some(url) => {
+                self.add_info_message(format!("Opened a draft pull request: {url}"), Some(branch))
+            }
+            None => self.add_info_message(
+                format!("Committed and pushed the changes to {branch}."),
+                Some(
+                    "Install the GitHub CLI (`gh`) to open pull requests automatically."
+                        .to_string(),
+                ),
+            ),
+        }
+    }
+
     fn on_stream_error(&mut self, message: String, additional_details: Option<String>) {
         if self.retry_status_header.is_none() {
             self.retry_status_header = Some(self.current_status_header.clone());
@@ -1173,7 +1487,16 @@ impl ChatWidget {
         if let Some(controller) = self.stream_controller.as_mut()
             && controller.push(&delta)
         {
-            self.app_event_tx.send(AppEvent::StartCommitAnimation);
+            if self.config.tui_screen_reader {
+                // Each commit tick redraws the viewport; a screen reader
+                // wants completed lines written once, as they arrive.
+                if let Some(cell) = controller.commit_queued() {
+                    self.bottom_pane.hide_status_indicator();
+                    self.add_boxed_history(cell);
+                }
+            } else {
+                self.app_event_tx.send(AppEvent::StartCommitAnimation);
+            }
         }
         self.request_redraw();
     }
@@ -1247,25 +1570,38 @@ impl ChatWidget {
         self.flush_answer_stream_with_separator();
         let command = shlex::try_join(ev.command.iter().map(String::as_str))
             .unwrap_or_else(|_| ev.command.join(" "));
-        self.notify(Notification::ExecApprovalRequested { command });
+        self.announce(format!("Approval needed to run: {command}"));
 
         let request = ApprovalRequest::Exec {
             id,
             command: ev.command,
             reason: ev.reason,
             proposed_execpolicy_amendment: ev.proposed_execpolicy_amendment,
+            operations: ev.operations,
         };
         self.bottom_pane
             .push_approval_request(request, &self.config.features);
         self.request_redraw();
     }
 
+    /// Shows an approval request again, e.g. after the user backed out of the
+    /// per-hunk patch review without deciding.
+    pub(crate) fn reopen_approval_request(&mut self, request: ApprovalRequest) {
+        self.bottom_pane
+            .push_approval_request(request, &self.config.features);
+        self.request_redraw();
+    }
+
     pub(crate) fn handle_apply_patch_approval_now(
         &mut self,
         id: String,
         ev: ApplyPatchApprovalRequestEvent,
     ) {
         self.flush_answer_stream_with_separator();
+        self.announce(match ev.changes.len() {
+            1 => "Approval needed to edit 1 file.".to_string(),
+            n => format!("Approval needed to edit {n} files."),
+        });
 
         let request = ApprovalRequest::ApplyPatch {
             id,
@@ -1276,18 +1612,12 @@ impl ChatWidget {
         self.bottom_pane
             .push_approval_request(request, &self.config.features);
         self.request_redraw();
-        self.notify(Notification::EditApprovalRequested {
-            cwd: self.config.cwd.clone(),
-            changes: ev.changes.keys().cloned().collect(),
-        });
     }
 
     pub(crate) fn handle_elicitation_request_now(&mut self, ev: ElicitationRequestEvent) {
         self.flush_answer_stream_with_separator();
 
-        self.notify(Notification::ElicitationRequested {
-            server_name: ev.server_name.clone(),
-        });
+        self.announce(format!("{} is asking for your input.", ev.server_name));
 
         let request = ApprovalRequest::McpElicitation {
             server_name: ev.server_name,
@@ -1425,6 +1755,9 @@ impl ChatWidget {
         let placeholder = EXAMPLE_PROMPTS[rng.random_range(0..EXAMPLE_PROMPTS.len())].to_string();
         let codex_op_tx = spawn_agent(config.clone(), app_event_tx.clone(), thread_manager);
 
+        // The keymap was validated at startup; fall back to defaults rather
+        // than failing if it somehow no longer parses.
+        let keymap = Keymap::from_config(&config.tui_keybindings).unwrap_or_default();
         let mut widget = Self {
             app_event_tx: app_event_tx.clone(),
             frame_requester: frame_requester.clone(),
@@ -1437,9 +1770,12 @@ impl ChatWidget {
                 placeholder_text: placeholder,
                 disable_paste_burst: config.disable_paste_burst,
                 animations_enabled: config.animations,
+                screen_reader: config.tui_screen_reader,
                 skills: None,
+                keymap: keymap.clone(),
             }),
             active_cell: None,
+            attachments: SessionAttachments::new(&config.codex_home),
             config,
             model: model.clone(),
             auth_manager,
@@ -1461,6 +1797,7 @@ impl ChatWidget {
             last_unified_wait: None,
             task_complete_pending: false,
             unified_exec_processes: Vec::new(),
+            command_history: CommandHistory::default(),
             mcp_startup_status: None,
             interrupts: InterruptManager::new(),
             reasoning_buffer: String::new(),
@@ -1469,9 +1806,14 @@ impl ChatWidget {
             retry_status_header: None,
             thread_id: None,
             queued_user_messages: VecDeque::new(),
+            interrupting_for_queued_message: false,
+            last_turn_diff: None,
+            turn_start_usage: TokenUsage::default(),
+            turn_started_at: None,
+            session_stats: SessionStats::default(),
+            context_sources: BTreeSet::new(),
             show_welcome_banner: is_first_run,
             suppress_session_configured_redraw: false,
-            pending_notification: None,
             is_review_mode: false,
             pre_review_token_info: None,
             needs_final_message_separator: false,
@@ -1479,6 +1821,10 @@ impl ChatWidget {
             feedback,
             current_rollout_path: None,
             external_editor_state: ExternalEditorState::Closed,
+            mcp_resource_picker_pending: false,
+            mcp_prompt_refresh_pending: false,
+            attached_mcp_resources: Vec::new(),
+            keymap,
         };
 
         widget.prefetch_rate_limits();
@@ -1511,6 +1857,9 @@ impl ChatWidget {
         let codex_op_tx =
             spawn_agent_from_existing(conversation, session_configured, app_event_tx.clone());
 
+        // The keymap was validated at startup; fall back to defaults rather
+        // than failing if it somehow no longer parses.
+        let keymap = Keymap::from_config(&config.tui_keybindings).unwrap_or_default();
         let mut widget = Self {
             app_event_tx: app_event_tx.clone(),
             frame_requester: frame_requester.clone(),
@@ -1523,9 +1872,12 @@ impl ChatWidget {
                 placeholder_text: placeholder,
                 disable_paste_burst: config.disable_paste_burst,
                 animations_enabled: config.animations,
+                screen_reader: config.tui_screen_reader,
                 skills: None,
+                keymap: keymap.clone(),
             }),
             active_cell: None,
+            attachments: SessionAttachments::new(&config.codex_home),
             config,
             model: model.clone(),
             auth_manager,
@@ -1547,6 +1899,7 @@ impl ChatWidget {
             last_unified_wait: None,
             task_complete_pending: false,
             unified_exec_processes: Vec::new(),
+            command_history: CommandHistory::default(),
             mcp_startup_status: None,
             interrupts: InterruptManager::new(),
             reasoning_buffer: String::new(),
@@ -1555,9 +1908,14 @@ impl ChatWidget {
             retry_status_header: None,
             thread_id: None,
             queued_user_messages: VecDeque::new(),
+            interrupting_for_queued_message: false,
+            last_turn_diff: None,
+            turn_start_usage: TokenUsage::default(),
+            turn_started_at: None,
+            session_stats: SessionStats::default(),
+            context_sources: BTreeSet::new(),
             show_welcome_banner: false,
             suppress_session_configured_redraw: true,
-            pending_notification: None,
             is_review_mode: false,
             pre_review_token_info: None,
             needs_final_message_separator: false,
@@ -1565,6 +1923,10 @@ impl ChatWidget {
             feedback,
             current_rollout_path: None,
             external_editor_state: ExternalEditorState::Closed,
+            mcp_resource_picker_pending: false,
+            mcp_prompt_refresh_pending: false,
+            attached_mcp_resources: Vec::new(),
+            keymap,
         };
 
         widget.prefetch_rate_limits();
@@ -1574,24 +1936,21 @@ impl ChatWidget {
 
     pub(crate) fn handle_key_event(&mut self, key_event: KeyEvent) {
         match key_event {
-            KeyEvent {
-                code: KeyCode::Char(c),
-                modifiers,
-                kind: KeyEventKind::Press,
-                ..
-            } if modifiers.contains(KeyModifiers::CONTROL) && c.eq_ignore_ascii_case(&'c') => {
+            key if key.kind == KeyEventKind::Press
+                && self.keymap.is_press(KeyAction::Quit, key) =>
+            {
                 self.on_ctrl_c();
                 return;
             }
-            KeyEvent {
-                code: KeyCode::Char(c),
-                modifiers,
-                kind: KeyEventKind::Press,
-                ..
-            } if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
-                && c.eq_ignore_ascii_case(&'v') =>
+            key if key.kind == KeyEventKind::Press
+                && self.keymap.is_press(KeyAction::Paste, key) =>
             {
-                match paste_image_to_temp_png() {
+                let pasted = self
+                    .attachments
+                    .dir()
+                    .map_err(|err| PasteImageError::IoError(err.to_string()))
+                    .and_then(paste_image_to_png_file);
+                match pasted {
                     Ok((path, info)) => {
                         tracing::debug!(
                             "pasted image size={}x{} format={}",
@@ -1637,6 +1996,15 @@ impl ChatWidget {
                         let user_message = UserMessage {
                             text,
                             image_paths: self.bottom_pane.take_recent_submission_images(),
+                            mcp_prompt: None,
+                        };
+                        self.queue_user_message(user_message);
+                    }
+                    InputResult::McpPrompt { text, invocation } => {
+                        let user_message = UserMessage {
+                            text,
+                            image_paths: self.bottom_pane.take_recent_submission_images(),
+                            mcp_prompt: Some(invocation),
                         };
                         self.queue_user_message(user_message);
                     }
@@ -1654,7 +2022,7 @@ impl ChatWidget {
 
     pub(crate) fn attach_image(&mut self, path: PathBuf) {
         tracing::info!("attach_image path={path:?}");
-        self.bottom_pane.attach_image(path);
+        self.bottom_pane.try_attach_image(path);
         self.request_redraw();
     }
 
@@ -1695,6 +2063,13 @@ impl ChatWidget {
         }
         match cmd {
             SlashCommand::Feedback => {
+                if self.config.offline {
+                    self.add_error_message(
+                        "Sending feedback needs the network and is unavailable while `offline = true`."
+                            .to_string(),
+                    );
+                    return;
+                }
                 if !self.config.feedback_enabled {
                     let params = crate::bottom_pane::feedback_disabled_params();
                     self.bottom_pane.show_selection_view(params);
@@ -1780,6 +2155,9 @@ impl ChatWidget {
             SlashCommand::Experimental => {
                 self.open_experimental_popup();
             }
+            SlashCommand::Theme => {
+                self.open_theme_popup();
+            }
             SlashCommand::Quit | SlashCommand::Exit => {
                 self.request_exit();
             }
@@ -1792,26 +2170,55 @@ impl ChatWidget {
                 }
                 self.request_exit();
             }
-            // SlashCommand::Undo => {
-            //     self.app_event_tx.send(AppEvent::CodexOp(Op::Undo));
-            // }
+            SlashCommand::Undo => {
+                if self.config.features.enabled(Feature::GhostCommit) {
+                    self.app_event_tx.send(AppEvent::CodexOp(Op::Undo));
+                } else {
+                    self.add_info_message(
+                        "Checkpoints are off, so there is nothing to undo.".to_string(),
+                        Some(
+                            "Enable them with `[features] undo = true` in config.toml.".to_string(),
+                        ),
+                    );
+                }
+            }
             SlashCommand::Diff => {
                 self.add_diff_in_progress();
                 let tx = self.app_event_tx.clone();
+                // Outside a git repository, diff against the session's first
+                // workspace snapshot instead.
+                let workspace = self.thread_id.map(|thread_id| {
+                    (
+                        self.config.codex_home.clone(),
+                        thread_id.to_string(),
+                        self.config.cwd.clone(),
+                    )
+                });
                 tokio::spawn(async move {
-                    let text = match get_git_diff().await {
-                        Ok((is_git_repo, diff_text)) => {
-                            if is_git_repo {
-                                diff_text
-                            } else {
-                                "`/diff` — _not inside a git repository_".to_string()
+                    let diff = match get_git_diff().await {
+                        Ok((true, diff_text)) => Ok(Some(diff_text)),
+                        Ok((false, _)) => match workspace {
+                            Some((codex_home, thread_id, cwd)) => {
+                                get_workspace_diff(codex_home, thread_id, cwd).await
                             }
-                        }
+                            None => Ok(None),
+                        },
+                        Err(e) => Err(e),
+                    };
+                    let text = match diff {
+                        Ok(Some(diff_text)) => diff_text,
+                        Ok(None) => "`/diff` — _not inside a git repository_".to_string(),
                         Err(e) => format!("Failed to compute diff: {e}"),
                     };
                     tx.send(AppEvent::DiffResult(text));
                 });
             }
+            SlashCommand::Tag => {
+                self.tag_session("");
+            }
+            SlashCommand::Set => {
+                self.set_variables("");
+            }
             SlashCommand::Mention => {
                 self.insert_str("@");
             }
@@ -1821,12 +2228,30 @@ impl ChatWidget {
             SlashCommand::Status => {
                 self.add_status_output();
             }
+            SlashCommand::Stats => {
+                self.add_stats_output();
+            }
+            SlashCommand::Sources => {
+                self.add_sources_output();
+            }
             SlashCommand::Ps => {
                 self.add_ps_output();
             }
             SlashCommand::Mcp => {
                 self.add_mcp_output();
             }
+            SlashCommand::Hooks => {
+                self.submit_op(Op::ListHooks);
+            }
+            SlashCommand::ShellCommands => {
+                self.open_command_palette();
+            }
+            SlashCommand::Tabs => {
+                self.app_event_tx.send(AppEvent::OpenTabsPopup);
+            }
+            SlashCommand::McpResources => {
+                self.open_mcp_resource_picker();
+            }
             SlashCommand::Rollout => {
                 if let Some(path) = self.rollout_path() {
                     self.add_info_message(
@@ -1891,6 +2316,30 @@ impl ChatWidget {
 
         let trimmed = args.trim();
         match cmd {
+            SlashCommand::Diff if trimmed == "last" => self.show_last_turn_diff(),
+            SlashCommand::Tag => self.tag_session(trimmed),
+            SlashCommand::Set => self.set_variables(trimmed),
+            SlashCommand::Diff if !trimmed.is_empty() => {
+                self.add_error_message(format!(
+                    "Unknown argument '{trimmed}'. Use `/diff` for the working tree or `/diff last` for the last turn's changes."
+                ));
+            }
+            SlashCommand::Model if !trimmed.is_empty() => {
+                match parse_model_settings_args(trimmed) {
+                    Ok(args) => self.submit_op(Op::OverrideTurnContext {
+                        cwd: None,
+                        approval_policy: None,
+                        sandbox_policy: None,
+                        model: args.model,
+                        effort: args.effort,
+                        summary: None,
+                        verbosity: args.verbosity,
+                    }),
+                    Err(err) => self.add_error_message(format!(
+                        "{err}. Usage: /model [MODEL] [effort=LEVEL|default] [verbosity=low|medium|high|default]"
+                    )),
+                }
+            }
             SlashCommand::Review if !trimmed.is_empty() => {
                 self.submit_op(Op::Review {
                     review_request: ReviewRequest {
@@ -1905,6 +2354,78 @@ impl ChatWidget {
         }
     }
 
+    /// Adds the tags in `args` to the session and removes the ones written
+    /// as `-tag`, then shows the session's tags.
+    fn tag_session(&mut self, args: &str) {
+        let Some(rollout_path) = self.rollout_path() else {
+            self.add_error_message(
+                "Tags are saved with the session, which has not started yet.".to_string(),
+            );
+            return;
+        };
+        let (remove, add): (Vec<String>, Vec<String>) = args
+            .split_whitespace()
+            .map(str::to_string)
+            .partition(|word| word.starts_with('-'));
+        let remove: Vec<String> = remove
+            .iter()
+            .map(|word| word.trim_start_matches('-').to_string())
+            .collect();
+        let tx = self.app_event_tx.clone();
+        tokio::spawn(async move {
+            let cell = match tag_thread(&rollout_path, &add, &remove).await {
+                Ok(metadata) if metadata.tags.is_empty() => history_cell::new_info_event(
+                    "This session has no tags.".to_string(),
+                    Some("Add one with `/tag <name>`.".to_string()),
+                ),
+                Ok(metadata) => {
+                    let tags = metadata
+                        .tags
+                        .iter()
+                        .map(|tag| format!("#{tag}"))
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    history_cell::new_info_event(
+                        format!("Session tags: {tags}"),
+                        Some("Filter by them with `codex sessions list --tag`.".to_string()),
+                    )
+                }
+                Err(err) => history_cell::new_error_event(format!("Failed to update tags: {err}")),
+            };
+            tx.send(AppEvent::InsertHistoryCell(Box::new(cell)));
+        });
+    }
+
+    /// Sends `/set` assignments to the session, which replies with every
+    /// variable; without arguments it only lists them.
+    fn set_variables(&mut self, args: &str) {
+        match parse_variable_assignments(args) {
+            Ok(variables) => self.submit_op(Op::SetVariables { variables }),
+            Err(err) => self.add_error_message(format!(
+                "{err}. Usage: /set name=value [name=value ...], or `/set name=` to clear"
+            )),
+        }
+    }
+
+    fn on_variables_updated(&mut self, event: VariablesUpdatedEvent) {
+        let message = if event.variables.is_empty() {
+            "No variables are set.".to_string()
+        } else {
+            let variables = event
+                .variables
+                .iter()
+                .map(|(name, value)| format!("{name}={value}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("Variables: {variables}")
+        };
+        self.bottom_pane.set_variables(event.variables);
+        self.add_info_message(
+            message,
+            Some("Use them as `{{name}}` in custom prompts and slash commands.".to_string()),
+        );
+    }
+
     pub(crate) fn handle_paste(&mut self, text: String) {
         self.bottom_pane.handle_paste(text);
     }
@@ -1972,6 +2493,12 @@ impl ChatWidget {
     fn queue_user_message(&mut self, user_message: UserMessage) {
         if self.bottom_pane.is_task_running() {
             self.queued_user_messages.push_back(user_message);
+            if self.config.tui_queued_message_delivery == QueuedMessageDelivery::Interrupt
+                && !self.interrupting_for_queued_message
+            {
+                self.interrupting_for_queued_message = true;
+                self.submit_op(Op::Interrupt);
+            }
             self.refresh_queued_user_messages();
         } else {
             self.submit_user_message(user_message);
@@ -1979,7 +2506,11 @@ impl ChatWidget {
     }
 
     fn submit_user_message(&mut self, user_message: UserMessage) {
-        let UserMessage { text, image_paths } = user_message;
+        let UserMessage {
+            text,
+            image_paths,
+            mcp_prompt,
+        } = user_message;
         if text.is_empty() && image_paths.is_empty() {
             return;
         }
@@ -1987,7 +2518,9 @@ impl ChatWidget {
         let mut items: Vec<UserInput> = Vec::new();
 
         // Special-case: "!cmd" executes a local shell command instead of sending to the model.
-        if let Some(stripped) = text.strip_prefix('!') {
+        if mcp_prompt.is_none()
+            && let Some(stripped) = text.strip_prefix('!')
+        {
             let cmd = stripped.trim();
             if cmd.is_empty() {
                 self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
@@ -2008,7 +2541,18 @@ impl ChatWidget {
             items.push(UserInput::LocalImage { path });
         }
 
-        if !text.is_empty() {
+        if let Some(McpPromptInvocation {
+            server,
+            name,
+            arguments,
+        }) = mcp_prompt
+        {
+            items.push(UserInput::McpPrompt {
+                server,
+                name,
+                arguments,
+            });
+        } else if !text.is_empty() {
             items.push(UserInput::Text { text: text.clone() });
         }
 
@@ -2022,6 +2566,10 @@ impl ChatWidget {
             }
         }
 
+        for (server, uri) in self.attached_mcp_resources.drain(..) {
+            items.push(UserInput::McpResource { server, uri });
+        }
+
         self.codex_op_tx
             .send(Op::UserInput {
                 items,
@@ -2090,6 +2638,8 @@ impl ChatWidget {
 
         match msg {
             EventMsg::SessionConfigured(e) => self.on_session_configured(e),
+            EventMsg::ConfigOverrideApplied(ev) => self.on_config_override_applied(ev),
+            EventMsg::ModelSettingsChanged(ev) => self.on_model_settings_changed(ev),
             EventMsg::AgentMessage(AgentMessageEvent { message }) => self.on_agent_message(message),
             EventMsg::AgentMessageDelta(AgentMessageDeltaEvent { delta }) => {
                 self.on_agent_message_delta(delta)
@@ -2105,8 +2655,15 @@ impl ChatWidget {
             }
             EventMsg::AgentReasoningSectionBreak(_) => self.on_reasoning_section_break(),
             EventMsg::TurnStarted(_) => self.on_task_started(),
-            EventMsg::TurnComplete(TurnCompleteEvent { last_agent_message }) => {
-                self.on_task_complete(last_agent_message)
+            EventMsg::TurnComplete(TurnCompleteEvent {
+                unified_diff,
+                timing,
+                ..
+            }) => {
+                if unified_diff.is_some() {
+                    self.last_turn_diff = unified_diff;
+                }
+                self.on_task_complete(timing)
             }
             EventMsg::TokenCount(ev) => {
                 self.set_token_info(ev.info);
@@ -2116,17 +2673,25 @@ impl ChatWidget {
             EventMsg::Error(ErrorEvent { message, .. }) => self.on_error(message),
             EventMsg::McpStartupUpdate(ev) => self.on_mcp_startup_update(ev),
             EventMsg::McpStartupComplete(ev) => self.on_mcp_startup_complete(ev),
-            EventMsg::TurnAborted(ev) => match ev.reason {
-                TurnAbortReason::Interrupted => {
-                    self.on_interrupted_turn(ev.reason);
-                }
-                TurnAbortReason::Replaced => {
-                    self.on_error("Turn aborted: replaced by a new task".to_owned())
-                }
-                TurnAbortReason::ReviewEnded => {
-                    self.on_interrupted_turn(ev.reason);
+            EventMsg::McpServerDown(ev) => self.on_mcp_server_down(ev),
+            EventMsg::McpServerRestarted(ev) => self.on_mcp_server_restarted(ev),
+            EventMsg::TurnAborted(ev) => {
+                self.record_aborted_turn_stats();
+                match ev.reason {
+                    TurnAbortReason::Interrupted | TurnAbortReason::Stopped => {
+                        self.on_interrupted_turn(ev.reason);
+                    }
+                    TurnAbortReason::Replaced => {
+                        self.on_error("Turn aborted: replaced by a new task".to_owned())
+                    }
+                    TurnAbortReason::ReviewEnded => {
+                        self.on_interrupted_turn(ev.reason);
+                    }
+                    TurnAbortReason::TimedOut => {
+                        self.on_error("Turn aborted: time limit reached".to_owned())
+                    }
                 }
-            },
+            }
             EventMsg::PlanUpdate(update) => self.on_plan_update(update),
             EventMsg::ExecApprovalRequest(ev) => {
                 // For replayed events, synthesize an empty id (these should not occur).
@@ -2145,6 +2710,13 @@ impl ChatWidget {
             EventMsg::PatchApplyEnd(ev) => self.on_patch_apply_end(ev),
             EventMsg::ExecCommandEnd(ev) => self.on_exec_command_end(ev),
             EventMsg::ViewImageToolCall(ev) => self.on_view_image_tool_call(ev),
+            EventMsg::RedactionApplied(ev) => self.on_redaction_applied(ev),
+            EventMsg::InjectionSuspected(ev) => self.on_injection_suspected(ev),
+            EventMsg::ContextSources(ev) => self.on_context_sources(ev),
+            EventMsg::GuardrailTripped(ev) => self.on_guardrail_tripped(ev),
+            EventMsg::WatchdogFired(ev) => self.on_watchdog_fired(ev),
+            EventMsg::ProjectDocsUpdated(ev) => self.on_project_docs_updated(ev),
+            EventMsg::WatchedFilesChanged(ev) => self.on_watched_files_changed(ev),
             EventMsg::McpToolCallBegin(ev) => self.on_mcp_tool_call_begin(ev),
             EventMsg::McpToolCallEnd(ev) => self.on_mcp_tool_call_end(ev),
             EventMsg::WebSearchBegin(ev) => self.on_web_search_begin(ev),
@@ -2153,6 +2725,7 @@ impl ChatWidget {
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
             EventMsg::ListSkillsResponse(ev) => self.on_list_skills(ev),
+            EventMsg::ListHooksResponse(ev) => self.open_hooks_popup(ev),
             EventMsg::SkillsUpdateAvailable => {
                 self.submit_op(Op::ListSkills {
                     cwds: Vec::new(),
@@ -2167,6 +2740,7 @@ impl ChatWidget {
             }
             EventMsg::UndoStarted(ev) => self.on_undo_started(ev),
             EventMsg::UndoCompleted(ev) => self.on_undo_completed(ev),
+            EventMsg::PullRequestPublished(ev) => self.on_pull_request_published(ev),
             EventMsg::StreamError(StreamErrorEvent {
                 message,
                 additional_details,
@@ -2182,8 +2756,13 @@ impl ChatWidget {
             }
             EventMsg::ExitedReviewMode(review) => self.on_exited_review_mode(review),
             EventMsg::ContextCompacted(_) => self.on_agent_message("Context compacted".to_owned()),
-            EventMsg::ThreadRolledBack(_) => {}
+            EventMsg::ContextRepaired(ev) => self.on_context_repaired(ev),
+            EventMsg::ThreadRolledBack(_) | EventMsg::UserCommand(_) => {}
+            EventMsg::VariablesUpdated(ev) => self.on_variables_updated(ev),
             EventMsg::RawResponseItem(_)
+            | EventMsg::WorkspaceSnapshot(_)
+            | EventMsg::EnvironmentFingerprint(_)
+            | EventMsg::OutputBudget(_)
             | EventMsg::ItemStarted(_)
             | EventMsg::ItemCompleted(_)
             | EventMsg::AgentMessageContentDelta(_)
@@ -2256,20 +2835,6 @@ impl ChatWidget {
         self.frame_requester.schedule_frame();
     }
 
-    fn notify(&mut self, notification: Notification) {
-        if !notification.allowed_for(&self.config.tui_notifications) {
-            return;
-        }
-        self.pending_notification = Some(notification);
-        self.request_redraw();
-    }
-
-    pub(crate) fn maybe_post_pending_notification(&mut self, tui: &mut crate::tui::Tui) {
-        if let Some(notif) = self.pending_notification.take() {
-            tui.notify(notif.display());
-        }
-    }
-
     /// Mark the active cell as failed (✗) and flush it into history.
     fn finalize_active_cell_as_failed(&mut self) {
         if let Some(mut cell) = self.active_cell.take() {
@@ -2303,12 +2868,24 @@ impl ChatWidget {
             .map(|m| m.text.clone())
             .collect();
         self.bottom_pane.set_queued_user_messages(messages);
+        self.bottom_pane
+            .set_queued_messages_interrupting(self.interrupting_for_queued_message);
     }
 
     pub(crate) fn add_diff_in_progress(&mut self) {
         self.request_redraw();
     }
 
+    fn show_last_turn_diff(&mut self) {
+        match self.last_turn_diff.clone() {
+            Some(unified_diff) => self.app_event_tx.send(AppEvent::DiffResult(unified_diff)),
+            None => self.add_info_message(
+                "No turn has changed files yet.".to_string(),
+                Some("`/diff` shows all uncommitted changes in the working tree.".to_string()),
+            ),
+        }
+    }
+
     pub(crate) fn on_diff_complete(&mut self) {
         self.request_redraw();
     }
@@ -2332,6 +2909,52 @@ impl ChatWidget {
         ));
     }
 
+    /// Footer with what the turn that just finished used, also counted
+    /// towards `/stats`.
+    fn add_turn_stats(&mut self, timing: TurnTiming) {
+        let stats = self.take_turn_stats(timing);
+        self.session_stats.record(&stats);
+        self.add_to_history(history_cell::new_turn_stats(&stats));
+    }
+
+    /// Counts an aborted turn towards `/stats`: its tokens were used all the
+    /// same. Only its wall-clock time is known.
+    fn record_aborted_turn_stats(&mut self) {
+        let Some(started_at) = self.turn_started_at else {
+            return;
+        };
+        let timing = TurnTiming {
+            total_ms: u64::try_from(started_at.elapsed().as_millis()).unwrap_or(u64::MAX),
+            ..TurnTiming::default()
+        };
+        let stats = self.take_turn_stats(timing);
+        self.session_stats.record_aborted(&stats);
+    }
+
+    fn take_turn_stats(&mut self, timing: TurnTiming) -> TurnStats {
+        let after = self
+            .token_info
+            .as_ref()
+            .map(|info| info.total_token_usage.clone())
+            .unwrap_or_default();
+        let pricing = pricing_for(&self.model, &self.config.model_pricing);
+        let stats = TurnStats::new(&self.turn_start_usage, &after, timing, pricing);
+        self.turn_start_usage = after;
+        self.turn_started_at = None;
+        stats
+    }
+
+    pub(crate) fn add_stats_output(&mut self) {
+        self.add_to_history(history_cell::new_session_stats_output(&self.session_stats));
+    }
+
+    pub(crate) fn add_sources_output(&mut self) {
+        self.add_to_history(history_cell::new_context_sources_output(
+            &self.context_sources,
+            &self.config.cwd,
+        ));
+    }
+
     pub(crate) fn add_ps_output(&mut self) {
         let processes = self
             .unified_exec_processes
@@ -2422,6 +3045,7 @@ impl ChatWidget {
                 model: Some(switch_model.clone()),
                 effort: Some(Some(default_effort)),
                 summary: None,
+                verbosity: None,
             }));
             tx.send(AppEvent::UpdateModel(switch_model.clone()));
             tx.send(AppEvent::UpdateReasoningEffort(Some(default_effort)));
@@ -2687,6 +3311,7 @@ impl ChatWidget {
                 model: Some(model_for_action.clone()),
                 effort: Some(effort_for_action),
                 summary: None,
+                verbosity: None,
             }));
             tx.send(AppEvent::UpdateModel(model_for_action.clone()));
             tx.send(AppEvent::UpdateReasoningEffort(effort_for_action));
@@ -2858,6 +3483,7 @@ impl ChatWidget {
                 model: Some(model.clone()),
                 effort: Some(effort),
                 summary: None,
+                verbosity: None,
             }));
         self.app_event_tx.send(AppEvent::UpdateModel(model.clone()));
         self.app_event_tx
@@ -2994,6 +3620,315 @@ impl ChatWidget {
         });
     }
 
+    pub(crate) fn open_theme_popup(&mut self) {
+        let current = crate::theme::current().name.clone();
+        let items = crate::theme::available_theme_names(&self.config.tui_themes)
+            .into_iter()
+            .map(|name| {
+                let description = match name.as_str() {
+                    "dark" => Some("Default colors for dark terminals".to_string()),
+                    "light" => Some("Avoids dimmed text on light terminals".to_string()),
+                    "high-contrast" => Some("Bright, bold colors".to_string()),
+                    _ => Some("Defined in config.toml".to_string()),
+                };
+                let is_current = name == current;
+                let selected = name.clone();
+                let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                    tx.send(AppEvent::SetTheme {
+                        name: selected.clone(),
+                    });
+                })];
+                SelectionItem {
+                    name,
+                    description,
+                    is_current,
+                    actions,
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Select Theme".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            header: Box::new(()),
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn open_command_palette(&mut self) {
+        if self.command_history.entries().is_empty() {
+            self.add_info_message(
+                "No commands have run in this session yet.".to_string(),
+                None,
+            );
+            return;
+        }
+
+        let items = self
+            .command_history
+            .entries()
+            .iter()
+            .rev()
+            .map(|entry| {
+                let mut description = entry.state.label();
+                if self.command_history.is_allowed(&entry.command) {
+                    description.push_str(" · always allowed");
+                }
+                let (actions, disabled_reason): (Vec<SelectionAction>, _) =
+                    if prefix_candidates(&entry.command).is_empty() {
+                        (Vec::new(), Some("too complex to pre-approve".to_string()))
+                    } else {
+                        let command = entry.command.clone();
+                        (
+                            vec![Box::new(move |tx: &AppEventSender| {
+                                tx.send(AppEvent::OpenCommandPrefixPicker {
+                                    command: command.clone(),
+                                });
+                            })],
+                            None,
+                        )
+                    };
+                SelectionItem {
+                    name: strip_bash_lc_and_escape(&entry.command),
+                    description: Some(description),
+                    actions,
+                    disabled_reason,
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        let subtitle = if self.command_history.allowed_prefixes().is_empty() {
+            "Select a command to always allow commands like it this session.".to_string()
+        } else {
+            let allowed = self
+                .command_history
+                .allowed_prefixes()
+                .iter()
+                .map(|prefix| escape_command(prefix))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("Always allowed: {allowed}")
+        };
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Session Commands".to_string()),
+            subtitle: Some(subtitle),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            header: Box::new(()),
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn open_command_prefix_picker(&mut self, command: &[String]) {
+        let items = prefix_candidates(command)
+            .into_iter()
+            .map(|prefix| {
+                let name = escape_command(&prefix);
+                let is_current = self.command_history.allowed_prefixes().contains(&prefix);
+                let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                    tx.send(AppEvent::AllowCommandPrefix(prefix.clone()));
+                })];
+                SelectionItem {
+                    description: Some(format!("Commands starting with `{name}`")),
+                    name,
+                    is_current,
+                    actions,
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Always Allow".to_string()),
+            subtitle: Some(
+                "Run matching commands without asking for the rest of this session.".to_string(),
+            ),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            header: Box::new(()),
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn allow_command_prefix(&mut self, prefix: Vec<String>) {
+        let display = escape_command(&prefix);
+        self.command_history.allow_prefix(prefix.clone());
+        self.submit_op(Op::AllowCommandPrefix { prefix });
+        self.add_info_message(
+            format!("Commands starting with `{display}` will run without approval for the rest of this session."),
+            None,
+        );
+    }
+
+    pub(crate) fn open_tabs_popup(&mut self, tabs: Vec<TabSummary>) {
+        let can_close = tabs.len() > 1;
+        let mut items: Vec<SelectionItem> = tabs
+            .into_iter()
+            .map(|tab| {
+                let id = tab.id;
+                let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                    tx.send(AppEvent::SwitchTab(id));
+                })];
+                SelectionItem {
+                    name: format!("{}. {}", tab.number, tab.title),
+                    description: Some(tab.status.label().to_string()),
+                    is_current: tab.is_active,
+                    actions,
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+        items.push(SelectionItem {
+            name: "New tab".to_string(),
+            description: Some("Start another conversation alongside this one".to_string()),
+            actions: vec![Box::new(|tx| tx.send(AppEvent::NewTab))],
+            dismiss_on_select: true,
+            ..Default::default()
+        });
+        if can_close {
+            items.push(SelectionItem {
+                name: "Close this tab".to_string(),
+                description: Some("Shut down this conversation".to_string()),
+                actions: vec![Box::new(|tx| tx.send(AppEvent::CloseTab))],
+                dismiss_on_select: true,
+                ..Default::default()
+            });
+        }
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Conversation Tabs".to_string()),
+            subtitle: Some(
+                "Conversations keep running in the background while another tab is shown."
+                    .to_string(),
+            ),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            header: Box::new(()),
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn open_hooks_popup(&mut self, ev: ListHooksResponseEvent) {
+        if ev.hooks.is_empty() {
+            self.add_info_message(
+                "No hooks are registered.".to_string(),
+                Some("Add external hooks under [hooks.commands] in config.toml.".to_string()),
+            );
+            return;
+        }
+
+        let items = ev
+            .hooks
+            .iter()
+            .map(|hook| {
+                let last_run = ev
+                    .recent_firings
+                    .iter()
+                    .rev()
+                    .find(|firing| firing.hook == hook.name);
+                let mut description = format!(
+                    "{} · on {}",
+                    if hook.enabled { "enabled" } else { "disabled" },
+                    hook.events.join(", ")
+                );
+                if hook.blocking {
+                    description.push_str(" · blocking");
+                }
+                if hook.priority != 0 {
+                    description.push_str(&format!(" · priority {}", hook.priority));
+                }
+                if let Some(firing) = last_run {
+                    let outcome = if firing.error.is_some() {
+                        "failed"
+                    } else {
+                        "ok"
+                    };
+                    description
+                        .push_str(&format!(" · last run {}ms, {outcome}", firing.duration_ms));
+                }
+                let (actions, disabled_reason): (Vec<SelectionAction>, _) = match hook.source {
+                    HookSource::External => {
+                        let name = hook.name.clone();
+                        let enabled = !hook.enabled;
+                        (
+                            vec![Box::new(move |tx: &AppEventSender| {
+                                tx.send(AppEvent::CodexOp(Op::SetHookEnabled {
+                                    name: name.clone(),
+                                    enabled,
+                                }));
+                            })],
+                            None,
+                        )
+                    }
+                    HookSource::BuiltIn => {
+                        (Vec::new(), Some("built-in hooks are always on".to_string()))
+                    }
+                    HookSource::Managed => (
+                        Vec::new(),
+                        Some("required by your administrator".to_string()),
+                    ),
+                };
+                SelectionItem {
+                    name: hook.name.clone(),
+                    description: Some(description),
+                    actions,
+                    dismiss_on_select: true,
+                    disabled_reason,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        let mut header = ColumnRenderable::new();
+        header.push(Line::from("Hooks".bold()));
+        header.push(Line::from(
+            "Select an external hook to enable or disable it for this session.".dim(),
+        ));
+        if !ev.recent_firings.is_empty() {
+            header.push(Line::from(""));
+            header.push(Line::from("Recent firings".bold()));
+            for firing in ev
+                .recent_firings
+                .iter()
+                .rev()
+                .take(HOOKS_POPUP_RECENT_FIRINGS)
+            {
+                let started = chrono::DateTime::parse_from_rfc3339(&firing.started_at)
+                    .map(|at| {
+                        at.with_timezone(&chrono::Local)
+                            .format("%H:%M:%S")
+                            .to_string()
+                    })
+                    .unwrap_or_else(|_| firing.started_at.clone());
+                let mut spans = vec![
+                    format!("  {started} ").dim(),
+                    firing.hook.clone().into(),
+                    format!(" ← {} ", firing.event).dim(),
+                    format!("{}ms ", firing.duration_ms).into(),
+                ];
+                spans.push(match &firing.error {
+                    Some(err) => format!("failed: {err}").red(),
+                    None => "ok".green(),
+                });
+                header.push(Line::from(spans));
+            }
+        }
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            header: Box::new(header),
+            ..Default::default()
+        });
+    }
+
     pub(crate) fn open_experimental_popup(&mut self) {
         let features: Vec<BetaFeatureItem> = FEATURES
             .iter()
@@ -3026,6 +3961,7 @@ impl ChatWidget {
                 model: None,
                 effort: None,
                 summary: None,
+                verbosity: None,
             }));
             tx.send(AppEvent::UpdateAskForApprovalPolicy(approval));
             tx.send(AppEvent::UpdateSandboxPolicy(sandbox_clone));
@@ -3609,6 +4545,14 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    /// In screen-reader mode, writes a state change to the transcript as a
+    /// plain line so it is read out; otherwise the bottom pane shows it.
+    fn announce(&mut self, message: String) {
+        if self.config.tui_screen_reader {
+            self.add_plain_history_lines(vec![Line::from(message)]);
+        }
+    }
+
     pub(crate) fn add_error_message(&mut self, message: String) {
         self.add_to_history(history_cell::new_error_event(message));
         self.request_redraw();
@@ -3622,6 +4566,72 @@ impl ChatWidget {
         }
     }
 
+    fn open_mcp_resource_picker(&mut self) {
+        if self.config.mcp_servers.is_empty() {
+            self.add_to_history(history_cell::empty_mcp_output());
+        } else {
+            self.mcp_resource_picker_pending = true;
+            self.submit_op(Op::ListMcpTools);
+        }
+    }
+
+    fn show_mcp_resource_picker(&mut self, resources: HashMap<String, Vec<Resource>>) {
+        let mut servers: Vec<_> = resources.into_iter().collect();
+        servers.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut items: Vec<SelectionItem> = Vec::new();
+        for (server, server_resources) in servers {
+            for resource in server_resources {
+                let label = resource.title.clone().unwrap_or(resource.name.clone());
+                let server_for_action = server.clone();
+                let uri = resource.uri.clone();
+                let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                    tx.send(AppEvent::AttachMcpResource {
+                        server: server_for_action.clone(),
+                        uri: uri.clone(),
+                    });
+                })];
+                items.push(SelectionItem {
+                    name: format!("{server}: {label}"),
+                    description: Some(resource.uri.clone()),
+                    search_value: Some(format!("{server} {label} {}", resource.uri)),
+                    actions,
+                    dismiss_on_select: true,
+                    ..Default::default()
+                });
+            }
+        }
+
+        if items.is_empty() {
+            self.add_info_message("No MCP resources available.".to_string(), None);
+            return;
+        }
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Attach MCP resource".to_string()),
+            subtitle: Some("The resource contents are sent with your next message.".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            is_searchable: true,
+            search_placeholder: Some("Type to search resources".to_string()),
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn attach_mcp_resource(&mut self, server: String, uri: String) {
+        let already_attached = self
+            .attached_mcp_resources
+            .iter()
+            .any(|(s, u)| *s == server && *u == uri);
+        if !already_attached {
+            self.add_info_message(
+                format!("Attached {uri} from {server}"),
+                Some("It will be sent with your next message.".to_string()),
+            );
+            self.attached_mcp_resources.push((server, uri));
+        }
+    }
+
     /// Forward file-search results to the bottom pane.
     pub(crate) fn apply_file_search_result(&mut self, query: String, matches: Vec<FileMatch>) {
         self.bottom_pane.on_file_search_result(query, matches);
@@ -3679,6 +4689,15 @@ impl ChatWidget {
     }
 
     fn on_list_mcp_tools(&mut self, ev: McpListToolsResponseEvent) {
+        self.bottom_pane
+            .set_mcp_prompts(mcp_prompt_commands(ev.prompts));
+        if std::mem::take(&mut self.mcp_prompt_refresh_pending) {
+            return;
+        }
+        if std::mem::take(&mut self.mcp_resource_picker_pending) {
+            self.show_mcp_resource_picker(ev.resources);
+            return;
+        }
         self.add_to_history(history_cell::new_mcp_tools_output(
             &self.config,
             ev.tools,
@@ -3693,6 +4712,7 @@ impl ChatWidget {
         debug!("received {len} custom prompts");
         // Forward to bottom pane so the slash popup can show them now.
         self.bottom_pane.set_custom_prompts(ev.custom_prompts);
+        self.bottom_pane.set_custom_commands(ev.custom_commands);
     }
 
     fn on_list_skills(&mut self, ev: ListSkillsResponseEvent) {
@@ -3865,6 +4885,10 @@ impl ChatWidget {
             .unwrap_or_default()
     }
 
+    pub(crate) fn keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
     pub(crate) fn thread_id(&self) -> Option<ThreadId> {
         self.thread_id
     }
@@ -3919,75 +4943,6 @@ impl Renderable for ChatWidget {
     }
 }
 
-enum Notification {
-    AgentTurnComplete { response: String },
-    ExecApprovalRequested { command: String },
-    EditApprovalRequested { cwd: PathBuf, changes: Vec<PathBuf> },
-    ElicitationRequested { server_name: String },
-}
-
-impl Notification {
-    fn display(&self) -> String {
-        match self {
-            Notification::AgentTurnComplete { response } => {
-                Notification::agent_turn_preview(response)
-                    .unwrap_or_else(|| "Agent turn complete".to_string())
-            }
-            Notification::ExecApprovalRequested { command } => {
-                format!("Approval requested: {}", truncate_text(command, 30))
-            }
-            Notification::EditApprovalRequested { cwd, changes } => {
-                format!(
-                    "Codex wants to edit {}",
-                    if changes.len() == 1 {
-                        #[allow(clippy::unwrap_used)]
-                        display_path_for(changes.first().unwrap(), cwd)
-                    } else {
-                        format!("{} files", changes.len())
-                    }
-                )
-            }
-            Notification::ElicitationRequested { server_name } => {
-                format!("Approval requested by {server_name}")
-            }
-        }
-    }
-
-    fn type_name(&self) -> &str {
-        match self {
-            Notification::AgentTurnComplete { .. } => "agent-turn-complete",
-            Notification::ExecApprovalRequested { .. }
-            | Notification::EditApprovalRequested { .. }
-            | Notification::ElicitationRequested { .. } => "approval-requested",
-        }
-    }
-
-    fn allowed_for(&self, settings: &Notifications) -> bool {
-        match settings {
-            Notifications::Enabled(enabled) => *enabled,
-            Notifications::Custom(allowed) => allowed.iter().any(|a| a == self.type_name()),
-        }
-    }
-
-    fn agent_turn_preview(response: &str) -> Option<String> {
-        let mut normalized = String::new();
-        for part in response.split_whitespace() {
-            if !normalized.is_empty() {
-                normalized.push(' ');
-            }
-            normalized.push_str(part);
-        }
-        let trimmed = normalized.trim();
-        if trimmed.is_empty() {
-            None
-        } else {
-            Some(truncate_text(trimmed, AGENT_NOTIFICATION_PREVIEW_GRAPHEMES))
-        }
-    }
-}
-
-const AGENT_NOTIFICATION_PREVIEW_GRAPHEMES: usize = 200;
-
 const EXAMPLE_PROMPTS: [&str; 6] = [
     "Explain this codebase",
     "Summarize recent commits",
@@ -4119,5 +5074,70 @@ fn find_skill_mentions(text: &str, skills: &[SkillMetadata]) -> Vec<SkillMetadat
     matches
 }
 
+/// Settings given inline to `/model`, e.g. `/model gpt-5.1 effort=high`.
+#[derive(Debug, Default, PartialEq)]
+struct ModelSettingsArgs {
+    model: Option<String>,
+    effort: Option<Option<ReasoningEffortConfig>>,
+    verbosity: Option<Option<Verbosity>>,
+}
+
+fn parse_model_settings_args(args: &str) -> Result<ModelSettingsArgs, String> {
+    let mut parsed = ModelSettingsArgs::default();
+    for token in args.split_whitespace() {
+        match token.split_once('=') {
+            Some(("effort", value)) => {
+                parsed.effort = Some(match value {
+                    "default" => None,
+                    _ => Some(
+                        ReasoningEffortConfig::iter()
+                            .find(|effort| effort.to_string() == value)
+                            .ok_or_else(|| format!("Unknown reasoning effort '{value}'"))?,
+                    ),
+                });
+            }
+            Some(("verbosity", value)) => {
+                parsed.verbosity = Some(match value {
+                    "default" => None,
+                    "low" => Some(Verbosity::Low),
+                    "medium" => Some(Verbosity::Medium),
+                    "high" => Some(Verbosity::High),
+                    _ => return Err(format!("Unknown verbosity '{value}'")),
+                });
+            }
+            Some((key, _)) => return Err(format!("Unknown setting '{key}'")),
+            None if parsed.model.is_none() => parsed.model = Some(token.to_string()),
+            None => return Err(format!("Unexpected argument '{token}'")),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Parses `/set` arguments: `name=value` sets a variable and `name=` clears
+/// it. Values may be quoted to contain spaces.
+fn parse_variable_assignments(args: &str) -> Result<BTreeMap<String, Option<String>>, String> {
+    let mut variables = BTreeMap::new();
+    for token in shlex::Shlex::new(args) {
+        let Some((name, value)) = token.split_once('=') else {
+            return Err(format!("Expected name=value but found '{token}'"));
+        };
+        let value = (!value.is_empty()).then(|| value.to_string());
+        variables.insert(name.to_string(), value);
+    }
+    Ok(variables)
+}
+
+fn mcp_prompt_commands(prompts: HashMap<String, Vec<Prompt>>) -> Vec<McpPromptCommand> {
+    prompts
+        .into_iter()
+        .flat_map(|(server, prompts)| {
+            prompts.into_iter().map(move |prompt| McpPromptCommand {
+                server: server.clone(),
+                prompt,
+            })
+        })
+        .collect()
+}
+
 #[cfg(test)]
 pub(crate) mod tests;
@@ -12,6 +12,7 @@ use codex_core::config::ConfigBuilder;
 use codex_core::config::Constrained;
 use codex_core::config::ConstraintError;
 use codex_core::config_loader::RequirementSource;
+use codex_core::cost::SessionStats;
 use codex_core::features::Feature;
 use codex_core::models_manager::manager::ModelsManager;
 use codex_core::protocol::AgentMessageDeltaEvent;
@@ -30,6 +31,10 @@ use codex_core::protocol::ExecCommandSource;
 use codex_core::protocol::ExecPolicyAmendment;
 use codex_core::protocol::ExitedReviewModeEvent;
 use codex_core::protocol::FileChange;
+use codex_core::protocol::HookFiring;
+use codex_core::protocol::HookSource;
+use codex_core::protocol::HookSummary;
+use codex_core::protocol::ListHooksResponseEvent;
 use codex_core::protocol::McpStartupStatus;
 use codex_core::protocol::McpStartupUpdateEvent;
 use codex_core::protocol::Op;
@@ -45,12 +50,14 @@ use codex_core::protocol::TokenUsage;
 use codex_core::protocol::TokenUsageInfo;
 use codex_core::protocol::TurnCompleteEvent;
 use codex_core::protocol::TurnStartedEvent;
+use codex_core::protocol::TurnTiming;
 use codex_core::protocol::UndoCompletedEvent;
 use codex_core::protocol::UndoStartedEvent;
 use codex_core::protocol::ViewImageToolCallEvent;
 use codex_core::protocol::WarningEvent;
 use codex_protocol::ThreadId;
 use codex_protocol::account::PlanType;
+use codex_protocol::config_types::ReasoningSummary;
 use codex_protocol::openai_models::ModelPreset;
 use codex_protocol::openai_models::ReasoningEffortPreset;
 use codex_protocol::parse_command::ParsedCommand;
@@ -286,6 +293,123 @@ async fn token_count_none_resets_context_indicator() {
     assert_eq!(chat.bottom_pane.context_window_percent(), None);
 }
 
+/// A finished turn gets a footer with the tokens it used and its latency, and
+/// `/stats` adds it up.
+#[tokio::test]
+async fn turn_complete_shows_turn_stats_and_stats_command_totals_them() {
+    let (mut chat, mut rx, _ops) = make_chatwidget_manual(None).await;
+    let usage = |input_tokens, output_tokens| TokenUsageInfo {
+        total_token_usage: TokenUsage {
+            input_tokens,
+            output_tokens,
+            total_tokens: input_tokens + output_tokens,
+            ..TokenUsage::default()
+        },
+        last_token_usage: TokenUsage::default(),
+        model_context_window: None,
+    };
+
+    chat.set_token_info(Some(usage(1_000, 100)));
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::TurnStarted(TurnStartedEvent {
+            model_context_window: None,
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::TokenCount(TokenCountEvent {
+            info: Some(usage(5_000, 400)),
+            rate_limits: None,
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::TurnComplete(TurnCompleteEvent {
+            last_agent_message: None,
+            timing: Some(TurnTiming {
+                total_ms: 2_000,
+                ..TurnTiming::default()
+            }),
+            unified_diff: None,
+            verification: None,
+            context_dropped: Vec::new(),
+        }),
+    });
+    let footer = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(
+        footer.contains("└ in 4K · out 300 · ") && footer.contains("· 2.00s"),
+        "unexpected footer: {footer}"
+    );
+
+    chat.dispatch_command(SlashCommand::Stats);
+    let stats = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(
+        stats.contains("Turns           1"),
+        "unexpected stats: {stats}"
+    );
+    assert!(
+        stats.contains("Tokens          in 4K · out 300"),
+        "unexpected stats: {stats}"
+    );
+}
+
+/// An interrupted turn used tokens too, so `/stats` counts it.
+#[tokio::test]
+async fn aborted_turn_counts_towards_stats() {
+    let (mut chat, mut rx, _ops) = make_chatwidget_manual(None).await;
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::TurnStarted(TurnStartedEvent {
+            model_context_window: None,
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::TokenCount(TokenCountEvent {
+            info: Some(TokenUsageInfo {
+                total_token_usage: TokenUsage {
+                    input_tokens: 2_000,
+                    output_tokens: 50,
+                    total_tokens: 2_050,
+                    ..TokenUsage::default()
+                },
+                last_token_usage: TokenUsage::default(),
+                model_context_window: None,
+            }),
+            rate_limits: None,
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::TurnAborted(codex_core::protocol::TurnAbortedEvent {
+            reason: TurnAbortReason::Interrupted,
+            cancelled: Vec::new(),
+        }),
+    });
+    drain_insert_history(&mut rx);
+
+    chat.dispatch_command(SlashCommand::Stats);
+    let stats = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(
+        stats.contains("Turns           1 (1 aborted)"),
+        "unexpected stats: {stats}"
+    );
+    assert!(
+        stats.contains("Tokens          in 2K · out 50"),
+        "unexpected stats: {stats}"
+    );
+}
+
 #[tokio::test]
 async fn context_indicator_shows_used_tokens_when_window_unknown() {
     let (mut chat, _rx, _ops) = make_chatwidget_manual(Some("unknown-model")).await;
@@ -380,7 +504,9 @@ async fn make_chatwidget_manual(
         placeholder_text: "Ask Codex to do anything".to_string(),
         disable_paste_burst: false,
         animations_enabled: cfg.animations,
+        screen_reader: cfg.tui_screen_reader,
         skills: None,
+        keymap: Keymap::default(),
     });
     let auth_manager = AuthManager::from_auth_for_testing(CodexAuth::from_api_key("test"));
     let codex_home = cfg.codex_home.clone();
@@ -389,6 +515,7 @@ async fn make_chatwidget_manual(
         codex_op_tx: op_tx,
         bottom_pane: bottom,
         active_cell: None,
+        attachments: SessionAttachments::new(&cfg.codex_home),
         config: cfg,
         model: resolved_model.clone(),
         auth_manager: auth_manager.clone(),
@@ -407,6 +534,7 @@ async fn make_chatwidget_manual(
         last_unified_wait: None,
         task_complete_pending: false,
         unified_exec_processes: Vec::new(),
+        command_history: CommandHistory::default(),
         mcp_startup_status: None,
         interrupts: InterruptManager::new(),
         reasoning_buffer: String::new(),
@@ -417,8 +545,13 @@ async fn make_chatwidget_manual(
         frame_requester: FrameRequester::test_dummy(),
         show_welcome_banner: true,
         queued_user_messages: VecDeque::new(),
+        interrupting_for_queued_message: false,
+        last_turn_diff: None,
+        turn_start_usage: TokenUsage::default(),
+        turn_started_at: None,
+        session_stats: SessionStats::default(),
+        context_sources: BTreeSet::new(),
         suppress_session_configured_redraw: false,
-        pending_notification: None,
         is_review_mode: false,
         pre_review_token_info: None,
         needs_final_message_separator: false,
@@ -426,6 +559,10 @@ async fn make_chatwidget_manual(
         feedback: codex_feedback::CodexFeedback::new(),
         current_rollout_path: None,
         external_editor_state: ExternalEditorState::Closed,
+        mcp_resource_picker_pending: false,
+        mcp_prompt_refresh_pending: false,
+        attached_mcp_resources: Vec::new(),
+        keymap: Keymap::default(),
     };
     (widget, rx, op_rx)
 }
@@ -745,6 +882,7 @@ async fn exec_approval_emits_proposed_command_and_decision_history() {
         ),
         proposed_execpolicy_amendment: None,
         parsed_cmd: vec![],
+        operations: vec![],
     };
     chat.handle_codex_event(Event {
         id: "sub-short".into(),
@@ -774,6 +912,82 @@ async fn exec_approval_emits_proposed_command_and_decision_history() {
     );
 }
 
+#[tokio::test]
+async fn screen_reader_mode_announces_state_changes_as_plain_lines() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+    chat.config.tui_screen_reader = true;
+
+    chat.handle_codex_event(Event {
+        id: "turn-1".into(),
+        msg: EventMsg::TurnStarted(TurnStartedEvent {
+            model_context_window: None,
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "turn-1".into(),
+        msg: EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
+            call_id: "call-1".into(),
+            turn_id: "turn-1".into(),
+            command: vec!["cargo".into(), "test".into()],
+            cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            reason: None,
+            proposed_execpolicy_amendment: None,
+            parsed_cmd: vec![],
+            operations: vec![],
+        }),
+    });
+    chat.handle_key_event(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE));
+    chat.handle_codex_event(Event {
+        id: "turn-1".into(),
+        msg: EventMsg::TurnComplete(TurnCompleteEvent {
+            last_agent_message: None,
+            timing: None,
+            unified_diff: None,
+            verification: None,
+            context_dropped: Vec::new(),
+        }),
+    });
+
+    let announcements: Vec<String> = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines).trim().to_string())
+        .filter(|text| !text.is_empty())
+        .collect();
+    assert!(announcements.contains(&"Codex is working.".to_string()));
+    assert!(announcements.contains(&"Approval needed to run: cargo test".to_string()));
+    assert_eq!(
+        announcements.last().map(String::as_str),
+        Some("Codex is done and waiting for you.")
+    );
+}
+
+#[tokio::test]
+async fn screen_reader_mode_commits_streamed_lines_without_animation() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+    chat.config.tui_screen_reader = true;
+
+    chat.handle_codex_event(Event {
+        id: "s1".into(),
+        msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+            delta: "first line\nsecond".into(),
+        }),
+    });
+
+    let mut inserted = Vec::new();
+    while let Ok(ev) = rx.try_recv() {
+        match ev {
+            AppEvent::StartCommitAnimation => panic!("commit animation started"),
+            AppEvent::InsertHistoryCell(cell) => {
+                inserted.push(lines_to_single_string(&cell.display_lines(80)));
+            }
+            _ => {}
+        }
+    }
+    assert_eq!(inserted.len(), 1);
+    assert!(inserted[0].contains("first line"));
+    assert!(!inserted[0].contains("second"));
+}
+
 #[tokio::test]
 async fn exec_approval_decision_truncates_multiline_and_long_commands() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
@@ -789,6 +1003,7 @@ async fn exec_approval_decision_truncates_multiline_and_long_commands() {
         ),
         proposed_execpolicy_amendment: None,
         parsed_cmd: vec![],
+        operations: vec![],
     };
     chat.handle_codex_event(Event {
         id: "sub-multi".into(),
@@ -839,6 +1054,7 @@ async fn exec_approval_decision_truncates_multiline_and_long_commands() {
         reason: None,
         proposed_execpolicy_amendment: None,
         parsed_cmd: vec![],
+        operations: vec![],
     };
     chat.handle_codex_event(Event {
         id: "sub-long".into(),
@@ -1322,6 +1538,10 @@ async fn unified_exec_waiting_multiple_empty_snapshots() {
         id: "turn-wait-1".into(),
         msg: EventMsg::TurnComplete(TurnCompleteEvent {
             last_agent_message: None,
+            timing: None,
+            unified_diff: None,
+            verification: None,
+            context_dropped: Vec::new(),
         }),
     });
 
@@ -1371,6 +1591,10 @@ async fn unified_exec_non_empty_then_empty_snapshots() {
         id: "turn-wait-3".into(),
         msg: EventMsg::TurnComplete(TurnCompleteEvent {
             last_agent_message: None,
+            timing: None,
+            unified_diff: None,
+            verification: None,
+            context_dropped: Vec::new(),
         }),
     });
 
@@ -1417,6 +1641,113 @@ async fn review_popup_custom_prompt_action_sends_event() {
     assert!(found, "expected OpenReviewCustomPrompt event to be sent");
 }
 
+#[tokio::test]
+async fn slash_model_with_args_overrides_effort_and_verbosity() {
+    let (mut chat, _rx, mut op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command_with_args(
+        SlashCommand::Model,
+        "effort=high verbosity=default".to_string(),
+    );
+
+    match op_rx.try_recv() {
+        Ok(Op::OverrideTurnContext {
+            model,
+            effort,
+            verbosity,
+            ..
+        }) => {
+            assert_eq!(model, None);
+            assert_eq!(effort, Some(Some(ReasoningEffortConfig::High)));
+            assert_eq!(verbosity, Some(None));
+        }
+        other => panic!("expected OverrideTurnContext, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn slash_model_rejects_unknown_settings() {
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command_with_args(SlashCommand::Model, "effort=extreme".to_string());
+
+    assert_matches!(op_rx.try_recv(), Err(TryRecvError::Empty));
+    let cells = drain_insert_history(&mut rx);
+    assert_eq!(cells.len(), 1, "expected one error message");
+    let rendered = lines_to_single_string(&cells[0]);
+    assert!(
+        rendered.contains("Unknown reasoning effort 'extreme'"),
+        "unexpected error: {rendered:?}"
+    );
+}
+
+#[test]
+fn model_settings_args_accept_a_model_and_settings() {
+    assert_eq!(
+        parse_model_settings_args("gpt-5.1 verbosity=low effort=xhigh"),
+        Ok(ModelSettingsArgs {
+            model: Some("gpt-5.1".to_string()),
+            effort: Some(Some(ReasoningEffortConfig::XHigh)),
+            verbosity: Some(Some(Verbosity::Low)),
+        })
+    );
+    assert_eq!(
+        parse_model_settings_args("gpt-5.1 o3"),
+        Err("Unexpected argument 'o3'".to_string())
+    );
+}
+
+#[test]
+fn variable_assignments_set_and_clear_variables() {
+    assert_eq!(
+        parse_variable_assignments(r#"branch_name=fix/login ticket= title="Fix login""#),
+        Ok(BTreeMap::from([
+            ("branch_name".to_string(), Some("fix/login".to_string())),
+            ("ticket".to_string(), None),
+            ("title".to_string(), Some("Fix login".to_string())),
+        ]))
+    );
+    assert_eq!(
+        parse_variable_assignments("branch_name"),
+        Err("Expected name=value but found 'branch_name'".to_string())
+    );
+}
+
+#[tokio::test]
+async fn model_settings_changed_updates_config_and_reports_settings() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.handle_codex_event(Event {
+        id: "override".into(),
+        msg: EventMsg::ModelSettingsChanged(ModelSettingsChangedEvent {
+            model: "gpt-5.1-codex".to_string(),
+            effort: Some(ReasoningEffortConfig::Low),
+            summary: ReasoningSummary::Auto,
+            verbosity: Some(Verbosity::Low),
+        }),
+    });
+
+    assert_eq!(chat.config.model_verbosity, Some(Verbosity::Low));
+    let mut model_update = None;
+    let mut rendered = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        match event {
+            AppEvent::UpdateModel(model) => model_update = Some(model),
+            AppEvent::InsertHistoryCell(cell) => {
+                rendered.push(lines_to_single_string(&cell.display_lines(80)));
+            }
+            _ => {}
+        }
+    }
+    assert_eq!(model_update, Some("gpt-5.1-codex".to_string()));
+    assert!(
+        rendered
+            .iter()
+            .any(|text| text.contains("gpt-5.1-codex, effort low, verbosity low")),
+        "expected a settings notice, got {rendered:?}"
+    );
+}
+
 #[tokio::test]
 async fn slash_init_skips_when_project_doc_exists() {
     let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual(None).await;
@@ -1476,6 +1807,84 @@ async fn slash_resume_opens_picker() {
     assert_matches!(rx.try_recv(), Ok(AppEvent::OpenResumePicker));
 }
 
+#[tokio::test]
+async fn attached_mcp_resources_are_sent_with_next_message() {
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual(None).await;
+
+    chat.attach_mcp_resource("docs".to_string(), "file:///guide.md".to_string());
+    chat.attach_mcp_resource("docs".to_string(), "file:///guide.md".to_string());
+    let cells = drain_insert_history(&mut rx);
+    assert_eq!(cells.len(), 1, "duplicate attachments should be ignored");
+
+    chat.submit_user_message(UserMessage::from("summarize the guide"));
+
+    match op_rx.try_recv() {
+        Ok(Op::UserInput { items, .. }) => assert_eq!(
+            items,
+            vec![
+                UserInput::Text {
+                    text: "summarize the guide".to_string(),
+                },
+                UserInput::McpResource {
+                    server: "docs".to_string(),
+                    uri: "file:///guide.md".to_string(),
+                },
+            ]
+        ),
+        other => panic!("expected UserInput op, got {other:?}"),
+    }
+    assert!(chat.attached_mcp_resources.is_empty());
+}
+
+#[tokio::test]
+async fn mcp_prompt_slash_command_submits_prompt_input() {
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual(None).await;
+    chat.mcp_prompt_refresh_pending = true;
+    chat.handle_codex_event(Event {
+        id: "list-mcp".into(),
+        msg: EventMsg::McpListToolsResponse(McpListToolsResponseEvent {
+            tools: HashMap::new(),
+            resources: HashMap::new(),
+            resource_templates: HashMap::new(),
+            prompts: HashMap::from([(
+                "docs".to_string(),
+                vec![Prompt {
+                    arguments: Some(vec![mcp_types::PromptArgument {
+                        description: None,
+                        name: "topic".to_string(),
+                        required: Some(true),
+                        title: None,
+                    }]),
+                    description: Some("Summarize a topic".to_string()),
+                    name: "summarize".to_string(),
+                    title: None,
+                }],
+            )]),
+            auth_statuses: HashMap::new(),
+        }),
+    });
+    assert!(
+        drain_insert_history(&mut rx).is_empty(),
+        "startup refresh should not render the MCP listing"
+    );
+
+    chat.bottom_pane
+        .set_composer_text("/mcp:docs:summarize topic=auth".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    match op_rx.try_recv() {
+        Ok(Op::UserInput { items, .. }) => assert_eq!(
+            items,
+            vec![UserInput::McpPrompt {
+                server: "docs".to_string(),
+                name: "summarize".to_string(),
+                arguments: HashMap::from([("topic".to_string(), "auth".to_string())]),
+            }]
+        ),
+        other => panic!("expected UserInput op, got {other:?}"),
+    }
+}
+
 #[tokio::test]
 async fn slash_rollout_displays_current_path() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
@@ -1740,6 +2149,7 @@ async fn interrupt_exec_marks_failed_snapshot() {
         id: "call-int".into(),
         msg: EventMsg::TurnAborted(codex_core::protocol::TurnAbortedEvent {
             reason: TurnAbortReason::Interrupted,
+            cancelled: Vec::new(),
         }),
     });
 
@@ -1773,6 +2183,7 @@ async fn interrupted_turn_error_message_snapshot() {
         id: "task-1".into(),
         msg: EventMsg::TurnAborted(codex_core::protocol::TurnAbortedEvent {
             reason: TurnAbortReason::Interrupted,
+            cancelled: Vec::new(),
         }),
     });
 
@@ -2477,6 +2888,7 @@ async fn approval_modal_exec_snapshot() -> anyhow::Result<()> {
             "world".into(),
         ])),
         parsed_cmd: vec![],
+        operations: vec![],
     };
     chat.handle_codex_event(Event {
         id: "sub-approve".into(),
@@ -2530,6 +2942,7 @@ async fn approval_modal_exec_without_reason_snapshot() -> anyhow::Result<()> {
             "world".into(),
         ])),
         parsed_cmd: vec![],
+        operations: vec![],
     };
     chat.handle_codex_event(Event {
         id: "sub-approve-noreason".into(),
@@ -2570,6 +2983,7 @@ async fn approval_modal_exec_multiline_prefix_hides_execpolicy_option_snapshot()
         reason: None,
         proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(command)),
         parsed_cmd: vec![],
+        operations: vec![],
     };
     chat.handle_codex_event(Event {
         id: "sub-approve-multiline-trunc".into(),
@@ -2655,6 +3069,7 @@ async fn interrupt_restores_queued_messages_into_composer() {
         id: "turn-1".into(),
         msg: EventMsg::TurnAborted(codex_core::protocol::TurnAbortedEvent {
             reason: TurnAbortReason::Interrupted,
+            cancelled: Vec::new(),
         }),
     });
 
@@ -2675,6 +3090,44 @@ async fn interrupt_restores_queued_messages_into_composer() {
     let _ = drain_insert_history(&mut rx);
 }
 
+#[tokio::test]
+async fn interrupt_delivery_sends_queued_message_after_abort() {
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual(None).await;
+    chat.config.tui_queued_message_delivery = QueuedMessageDelivery::Interrupt;
+    chat.on_task_started();
+
+    chat.bottom_pane
+        .set_composer_text("use the other API instead".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    assert_matches!(op_rx.try_recv(), Ok(Op::Interrupt));
+    assert_eq!(chat.queued_user_messages.len(), 1);
+    let pane = render_bottom_popup(&chat, 80);
+    assert!(pane.contains("interrupting to send"), "pane: {pane}");
+
+    chat.handle_codex_event(Event {
+        id: "turn-1".into(),
+        msg: EventMsg::TurnAborted(codex_core::protocol::TurnAbortedEvent {
+            reason: TurnAbortReason::Interrupted,
+            cancelled: Vec::new(),
+        }),
+    });
+
+    assert!(chat.queued_user_messages.is_empty());
+    assert_eq!(chat.bottom_pane.composer_text(), "");
+    let sent = std::iter::from_fn(|| op_rx.try_recv().ok()).find_map(|op| match op {
+        Op::UserInput { items, .. } => Some(items),
+        _ => None,
+    });
+    assert_eq!(
+        sent,
+        Some(vec![UserInput::Text {
+            text: "use the other API instead".to_string(),
+        }])
+    );
+    let _ = drain_insert_history(&mut rx);
+}
+
 #[tokio::test]
 async fn interrupt_prepends_queued_messages_before_existing_composer_text() {
     let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual(None).await;
@@ -2693,6 +3146,7 @@ async fn interrupt_prepends_queued_messages_before_existing_composer_text() {
         id: "turn-1".into(),
         msg: EventMsg::TurnAborted(codex_core::protocol::TurnAbortedEvent {
             reason: TurnAbortReason::Interrupted,
+            cancelled: Vec::new(),
         }),
     });
 
@@ -2721,6 +3175,7 @@ async fn interrupt_clears_unified_exec_processes() {
         id: "turn-1".into(),
         msg: EventMsg::TurnAborted(codex_core::protocol::TurnAbortedEvent {
             reason: TurnAbortReason::Interrupted,
+            cancelled: Vec::new(),
         }),
     });
 
@@ -2813,6 +3268,7 @@ async fn status_widget_and_approval_modal_snapshot() {
             "hello world".into(),
         ])),
         parsed_cmd: vec![],
+        operations: vec![],
     };
     chat.handle_codex_event(Event {
         id: "sub-approve-exec".into(),
@@ -3481,6 +3937,10 @@ async fn multiple_agent_messages_in_single_turn_emit_multiple_headers() {
         id: "s1".into(),
         msg: EventMsg::TurnComplete(TurnCompleteEvent {
             last_agent_message: None,
+            timing: None,
+            unified_diff: None,
+            verification: None,
+            context_dropped: Vec::new(),
         }),
     });
 
@@ -3766,6 +4226,10 @@ printf 'fenced within fenced\n'
         id: "t1".into(),
         msg: EventMsg::TurnComplete(TurnCompleteEvent {
             last_agent_message: None,
+            timing: None,
+            unified_diff: None,
+            verification: None,
+            context_dropped: Vec::new(),
         }),
     });
     for lines in drain_insert_history(&mut rx) {
@@ -3800,3 +4264,168 @@ async fn chatwidget_tall() {
     .unwrap();
     assert_snapshot!(term.backend().vt100().screen().contents());
 }
+
+#[tokio::test]
+async fn hooks_popup_lists_hooks_and_toggles_external_ones() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command(SlashCommand::Hooks);
+    chat.handle_codex_event(Event {
+        id: "hooks".into(),
+        msg: EventMsg::ListHooksResponse(ListHooksResponseEvent {
+            hooks: vec![HookSummary {
+                name: "lint".to_string(),
+                events: vec!["task_complete".to_string()],
+                source: HookSource::External,
+                enabled: true,
+                priority: 0,
+                blocking: false,
+            }],
+            recent_firings: vec![HookFiring {
+                hook: "lint".to_string(),
+                event: "task_complete".to_string(),
+                started_at: "2025-01-01T12:00:00.000Z".to_string(),
+                duration_ms: 42,
+                error: Some("`lint.sh` exited with exit status: 1".to_string()),
+            }],
+        }),
+    });
+
+    let popup = render_bottom_popup(&chat, 100);
+    assert!(popup.contains("Recent firings"), "popup: {popup}");
+    assert!(
+        popup.contains("lint ← task_complete 42ms failed: `lint.sh` exited with exit status: 1"),
+        "popup: {popup}"
+    );
+    assert!(
+        popup.contains("enabled · on task_complete · last run 42ms, failed"),
+        "popup: {popup}"
+    );
+    assert!(!popup.contains("(current)"), "popup: {popup}");
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    let toggled = std::iter::from_fn(|| rx.try_recv().ok()).find_map(|event| match event {
+        AppEvent::CodexOp(Op::SetHookEnabled { name, enabled }) => Some((name, enabled)),
+        _ => None,
+    });
+    assert_eq!(toggled, Some(("lint".to_string(), false)));
+}
+
+#[tokio::test]
+async fn command_palette_pre_approves_a_command_prefix() {
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual(None).await;
+    let begin = begin_exec(&mut chat, "call-1", "cargo test -p codex-tui");
+    end_exec(&mut chat, begin, "ok", "", 0);
+    while rx.try_recv().is_ok() {}
+
+    chat.dispatch_command(SlashCommand::ShellCommands);
+    let popup = render_bottom_popup(&chat, 100);
+    assert!(
+        popup.contains("cargo test -p codex-tui") && popup.contains("succeeded"),
+        "popup: {popup}"
+    );
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    let command = std::iter::from_fn(|| rx.try_recv().ok())
+        .find_map(|event| match event {
+            AppEvent::OpenCommandPrefixPicker { command } => Some(command),
+            _ => None,
+        })
+        .expect("prefix picker requested");
+    chat.open_command_prefix_picker(&command);
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    let prefix = std::iter::from_fn(|| rx.try_recv().ok())
+        .find_map(|event| match event {
+            AppEvent::AllowCommandPrefix(prefix) => Some(prefix),
+            _ => None,
+        })
+        .expect("prefix selected");
+    assert_eq!(prefix, vec!["cargo".to_string(), "test".to_string()]);
+
+    chat.allow_command_prefix(prefix);
+    let op = std::iter::from_fn(|| op_rx.try_recv().ok())
+        .find(|op| matches!(op, Op::AllowCommandPrefix { .. }));
+    assert_eq!(
+        op,
+        Some(Op::AllowCommandPrefix {
+            prefix: vec!["cargo".to_string(), "test".to_string()],
+        })
+    );
+}
+
+#[tokio::test]
+async fn undo_requires_checkpoints() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command(SlashCommand::Undo);
+    let cells = drain_insert_history(&mut rx);
+    let rendered = cells
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(rendered.contains("Checkpoints are off"), "{rendered}");
+
+    chat.config.features.enable(Feature::GhostCommit);
+    chat.dispatch_command(SlashCommand::Undo);
+    assert!(
+        std::iter::from_fn(|| rx.try_recv().ok())
+            .any(|event| matches!(event, AppEvent::CodexOp(Op::Undo))),
+        "expected Op::Undo"
+    );
+}
+
+#[tokio::test]
+async fn diff_last_shows_the_last_turn_that_changed_files() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+    let diff = "diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n@@ -1 +1 @@\n-old\n+new\n";
+    for unified_diff in [Some(diff.to_string()), None] {
+        chat.handle_codex_event(Event {
+            id: "turn".into(),
+            msg: EventMsg::TurnComplete(TurnCompleteEvent {
+                last_agent_message: None,
+                timing: None,
+                unified_diff,
+                verification: None,
+                context_dropped: Vec::new(),
+            }),
+        });
+    }
+    while rx.try_recv().is_ok() {}
+
+    chat.dispatch_command_with_args(SlashCommand::Diff, "last".to_string());
+
+    let mut shown = None;
+    while let Ok(event) = rx.try_recv() {
+        if let AppEvent::DiffResult(text) = event {
+            shown = Some(text);
+        }
+    }
+    assert_eq!(shown.as_deref(), Some(diff));
+}
+
+#[tokio::test]
+async fn tag_command_updates_the_session_tags() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command_with_args(SlashCommand::Tag, "backend".to_string());
+    let cells = drain_insert_history(&mut rx);
+    assert!(lines_to_single_string(&cells[0]).contains("has not started yet"));
+
+    let dir = tempfile::tempdir().expect("tempdir");
+    let rollout_path = dir.path().join("rollout.jsonl");
+    std::fs::write(&rollout_path, "").expect("write rollout");
+    chat.current_rollout_path = Some(rollout_path);
+    chat.dispatch_command_with_args(SlashCommand::Tag, "backend #Auth -ui".to_string());
+
+    let cell = loop {
+        match rx.recv().await.expect("app event") {
+            AppEvent::InsertHistoryCell(cell) => break cell,
+            _ => continue,
+        }
+    };
+    let text = lines_to_single_string(&cell.display_lines(80));
+    assert!(text.contains("Session tags: #auth #backend"), "{text}");
+}
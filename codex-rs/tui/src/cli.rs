@@ -93,6 +93,11 @@ pub struct Cli {
     #[arg(long = "no-alt-screen", default_value_t = false)]
     pub no_alt_screen: bool,
 
+    /// Internal: labels recorded in the new session's metadata. Set by
+    /// `codex fix`.
+    #[clap(skip)]
+    pub session_labels: Vec<String>,
+
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 }
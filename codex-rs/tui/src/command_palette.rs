@@ -0,0 +1,163 @@
+//! Commands the agent asked to run this session, listed by `/shell-commands`.
+//!
+//! The palette lets the user pre-approve a command prefix (`cargo test`) for
+//! the rest of the session. Core keeps the authoritative list and applies it
+//! to approval requests; the copy here only annotates the palette.
+
+use codex_core::bash::extract_bash_command;
+use codex_core::bash::parse_shell_lc_plain_commands;
+
+/// Longest prefix offered besides the full command.
+const MAX_PREFIX_WORDS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CommandState {
+    AwaitingApproval,
+    Running,
+    Exited(i32),
+}
+
+impl CommandState {
+    pub(crate) fn label(self) -> String {
+        match self {
+            CommandState::AwaitingApproval => "awaiting approval".to_string(),
+            CommandState::Running => "running".to_string(),
+            CommandState::Exited(0) => "succeeded".to_string(),
+            CommandState::Exited(code) => format!("exited {code}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CommandEntry {
+    pub(crate) call_id: String,
+    pub(crate) command: Vec<String>,
+    pub(crate) state: CommandState,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct CommandHistory {
+    entries: Vec<CommandEntry>,
+    allowed_prefixes: Vec<Vec<String>>,
+}
+
+impl CommandHistory {
+    /// Records `command`'s latest state, updating the entry for `call_id`
+    /// when there is one.
+    pub(crate) fn record(&mut self, call_id: &str, command: &[String], state: CommandState) {
+        match self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.call_id == call_id)
+        {
+            Some(entry) => entry.state = state,
+            None => self.entries.push(CommandEntry {
+                call_id: call_id.to_string(),
+                command: command.to_vec(),
+                state,
+            }),
+        }
+    }
+
+    /// Entries in the order the commands were first seen.
+    pub(crate) fn entries(&self) -> &[CommandEntry] {
+        &self.entries
+    }
+
+    pub(crate) fn allow_prefix(&mut self, prefix: Vec<String>) {
+        if !self.allowed_prefixes.contains(&prefix) {
+            self.allowed_prefixes.push(prefix);
+        }
+    }
+
+    pub(crate) fn allowed_prefixes(&self) -> &[Vec<String>] {
+        &self.allowed_prefixes
+    }
+
+    /// Whether every command in `command` starts with an allowed prefix,
+    /// mirroring how core applies the prefixes.
+    pub(crate) fn is_allowed(&self, command: &[String]) -> bool {
+        let Some(commands) = plain_commands(command) else {
+            return false;
+        };
+        !commands.is_empty()
+            && commands.iter().all(|command| {
+                self.allowed_prefixes
+                    .iter()
+                    .any(|prefix| command.starts_with(prefix))
+            })
+    }
+}
+
+/// Prefixes of `command` that can be pre-approved, longest first: the whole
+/// command, then its first few words. Scripts are only offered when they
+/// consist of a single plain command.
+pub(crate) fn prefix_candidates(command: &[String]) -> Vec<Vec<String>> {
+    let Some(commands) = plain_commands(command) else {
+        return Vec::new();
+    };
+    let [words] = commands.as_slice() else {
+        return Vec::new();
+    };
+    let mut candidates = vec![words.clone()];
+    for len in (1..words.len().min(MAX_PREFIX_WORDS + 1)).rev() {
+        candidates.push(words[..len].to_vec());
+    }
+    candidates
+}
+
+fn plain_commands(command: &[String]) -> Option<Vec<Vec<String>>> {
+    if extract_bash_command(command).is_some() {
+        parse_shell_lc_plain_commands(command)
+    } else {
+        Some(vec![command.to_vec()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn offers_whole_command_then_leading_words() {
+        assert_eq!(
+            prefix_candidates(&argv(&["bash", "-lc", "cargo test -p codex-core --lib"])),
+            vec![
+                argv(&["cargo", "test", "-p", "codex-core", "--lib"]),
+                argv(&["cargo", "test", "-p"]),
+                argv(&["cargo", "test"]),
+                argv(&["cargo"]),
+            ]
+        );
+        assert_eq!(prefix_candidates(&argv(&["ls"])), vec![argv(&["ls"])]);
+        assert_eq!(
+            prefix_candidates(&argv(&["bash", "-lc", "cargo fmt && cargo test"])),
+            Vec::<Vec<String>>::new()
+        );
+    }
+
+    #[test]
+    fn records_state_changes_per_call() {
+        let mut history = CommandHistory::default();
+        let command = argv(&["bash", "-lc", "cargo test && cargo test -p x"]);
+        history.record("call-1", &command, CommandState::AwaitingApproval);
+        history.record("call-1", &command, CommandState::Exited(101));
+        history.allow_prefix(argv(&["cargo", "test"]));
+
+        assert_eq!(
+            history.entries(),
+            &[CommandEntry {
+                call_id: "call-1".to_string(),
+                command: command.clone(),
+                state: CommandState::Exited(101),
+            }]
+        );
+        assert!(history.is_allowed(&command));
+        assert!(!history.is_allowed(&argv(&["cargo", "build"])));
+    }
+}
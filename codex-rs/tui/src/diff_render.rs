@@ -1,8 +1,6 @@
 use diffy::Hunk;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
-use ratatui::style::Color;
-use ratatui::style::Modifier;
 use ratatui::style::Style;
 use ratatui::style::Stylize;
 use ratatui::text::Line as RtLine;
@@ -252,53 +250,97 @@ fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usi
                         out.push(RtLine::from(vec![spacer_span, "⋮".dim()]));
                     }
                     is_first_hunk = false;
-
-                    let mut old_ln = h.old_range().start();
-                    let mut new_ln = h.new_range().start();
-                    for l in h.lines() {
-                        match l {
-                            diffy::Line::Insert(text) => {
-                                let s = text.trim_end_matches('\n');
-                                out.extend(push_wrapped_diff_line(
-                                    new_ln,
-                                    DiffLineType::Insert,
-                                    s,
-                                    width,
-                                    line_number_width,
-                                ));
-                                new_ln += 1;
-                            }
-                            diffy::Line::Delete(text) => {
-                                let s = text.trim_end_matches('\n');
-                                out.extend(push_wrapped_diff_line(
-                                    old_ln,
-                                    DiffLineType::Delete,
-                                    s,
-                                    width,
-                                    line_number_width,
-                                ));
-                                old_ln += 1;
-                            }
-                            diffy::Line::Context(text) => {
-                                let s = text.trim_end_matches('\n');
-                                out.extend(push_wrapped_diff_line(
-                                    new_ln,
-                                    DiffLineType::Context,
-                                    s,
-                                    width,
-                                    line_number_width,
-                                ));
-                                old_ln += 1;
-                                new_ln += 1;
-                            }
-                        }
-                    }
+                    render_hunk(h, out, width, line_number_width);
                 }
             }
         }
     }
 }
 
+fn render_hunk(
+    h: &Hunk<'_, str>,
+    out: &mut Vec<RtLine<'static>>,
+    width: usize,
+    line_number_width: usize,
+) {
+    let mut old_ln = h.old_range().start();
+    let mut new_ln = h.new_range().start();
+    for l in h.lines() {
+        match l {
+            diffy::Line::Insert(text) => {
+                let s = text.trim_end_matches('\n');
+                out.extend(push_wrapped_diff_line(
+                    new_ln,
+                    DiffLineType::Insert,
+                    s,
+                    width,
+                    line_number_width,
+                ));
+                new_ln += 1;
+            }
+            diffy::Line::Delete(text) => {
+                let s = text.trim_end_matches('\n');
+                out.extend(push_wrapped_diff_line(
+                    old_ln,
+                    DiffLineType::Delete,
+                    s,
+                    width,
+                    line_number_width,
+                ));
+                old_ln += 1;
+            }
+            diffy::Line::Context(text) => {
+                let s = text.trim_end_matches('\n');
+                out.extend(push_wrapped_diff_line(
+                    new_ln,
+                    DiffLineType::Context,
+                    s,
+                    width,
+                    line_number_width,
+                ));
+                old_ln += 1;
+                new_ln += 1;
+            }
+        }
+    }
+}
+
+/// Number of hunks a user can accept or reject individually: one per `@@`
+/// hunk of an update, or a single hunk for an added or deleted file.
+pub(crate) fn hunk_count(change: &FileChange) -> usize {
+    match change {
+        FileChange::Add { .. } | FileChange::Delete { .. } => 1,
+        FileChange::Update { unified_diff, .. } => diffy::Patch::from_str(unified_diff)
+            .map(|patch| patch.hunks().len())
+            .unwrap_or(0),
+    }
+}
+
+/// Renders a single hunk of `change`, numbered as in [`hunk_count`].
+pub(crate) fn render_change_hunk(
+    change: &FileChange,
+    hunk_index: usize,
+    width: usize,
+) -> Vec<RtLine<'static>> {
+    let mut out = Vec::new();
+    match change {
+        FileChange::Add { .. } | FileChange::Delete { .. } => {
+            if hunk_index == 0 {
+                render_change(change, &mut out, width);
+            }
+        }
+        FileChange::Update { unified_diff, .. } => {
+            if let Ok(patch) = diffy::Patch::from_str(unified_diff)
+                && let Some(h) = patch.hunks().get(hunk_index)
+            {
+                let max_line_number = h.old_range().end().max(h.new_range().end());
+                render_hunk(h, &mut out, width, line_number_width(max_line_number));
+            }
+        }
+    }
+    out
+}
+
 /// Format a path for display relative to the current working directory when
 /// possible, keeping output stable in jj/no-`.git` workspaces (e.g. image
 /// tool calls should show `example.png` instead of an absolute path).
@@ -412,19 +454,19 @@ fn line_number_width(max_line_number: usize) -> usize {
 }
 
 fn style_gutter() -> Style {
-    Style::default().add_modifier(Modifier::DIM)
+    crate::theme::current().diff_gutter
 }
 
 fn style_context() -> Style {
-    Style::default()
+    crate::theme::current().diff_context
 }
 
 fn style_add() -> Style {
-    Style::default().fg(Color::Green)
+    crate::theme::current().diff_add
 }
 
 fn style_del() -> Style {
-    Style::default().fg(Color::Red)
+    crate::theme::current().diff_del
 }
 
 #[cfg(test)]
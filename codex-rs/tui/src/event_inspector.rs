@@ -0,0 +1,214 @@
+//! Replay buffer behind the event inspector overlay.
+//!
+//! Every event the agent emits is recorded here as it arrives, so the
+//! inspector (toggled with Ctrl+X) can show recent history as soon as it
+//! opens and then keep tailing new events.
+
+use std::collections::VecDeque;
+
+use chrono::DateTime;
+use chrono::Local;
+use codex_core::protocol::Event;
+use codex_protocol::ThreadId;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+
+/// Number of events retained for replay when the inspector is opened.
+const EVENT_LOG_CAPACITY: usize = 1_000;
+
+/// Payload summaries longer than this are truncated with an ellipsis.
+const PAYLOAD_PREVIEW_MAX_CHARS: usize = 240;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct EventLogEntry {
+    pub(crate) seq: u64,
+    pub(crate) received_at: DateTime<Local>,
+    pub(crate) conversation: Option<ThreadId>,
+    pub(crate) submission_id: String,
+    /// Wire name of the event, e.g. `exec_command_begin`.
+    pub(crate) name: String,
+    /// Compact JSON of the event payload without the `type` tag.
+    pub(crate) payload: String,
+}
+
+impl EventLogEntry {
+    fn new(seq: u64, conversation: Option<ThreadId>, event: &Event) -> Self {
+        let (name, payload) = match serde_json::to_value(&event.msg) {
+            Ok(serde_json::Value::Object(mut fields)) => {
+                let name = match fields.remove("type") {
+                    Some(serde_json::Value::String(name)) => name,
+                    _ => "unknown".to_string(),
+                };
+                let payload = if fields.is_empty() {
+                    String::new()
+                } else {
+                    serde_json::Value::Object(fields).to_string()
+                };
+                (name, payload)
+            }
+            Ok(other) => ("unknown".to_string(), other.to_string()),
+            Err(err) => ("unknown".to_string(), format!("<unserializable: {err}>")),
+        };
+        Self {
+            seq,
+            received_at: Local::now(),
+            conversation,
+            submission_id: event.id.clone(),
+            name,
+            payload: truncate_payload(payload),
+        }
+    }
+
+    /// Whether every whitespace-separated term of `filter` appears in either
+    /// the event name or the conversation id (case-insensitive).
+    pub(crate) fn matches(&self, filter: &str) -> bool {
+        let name = self.name.to_lowercase();
+        let conversation = self
+            .conversation
+            .map(|id| id.to_string().to_lowercase())
+            .unwrap_or_default();
+        filter.split_whitespace().all(|term| {
+            let term = term.to_lowercase();
+            name.contains(&term) || conversation.contains(&term)
+        })
+    }
+
+    pub(crate) fn display_line(&self) -> Line<'static> {
+        let conversation = self
+            .conversation
+            .map(|id| id.to_string().chars().take(8).collect::<String>())
+            .unwrap_or_else(|| "-".repeat(8));
+        let mut spans: Vec<Span<'static>> = vec![
+            self.received_at.format("%H:%M:%S%.3f").to_string().dim(),
+            " ".into(),
+            format!("#{}", self.seq).dim(),
+            " ".into(),
+            conversation.magenta(),
+            " ".into(),
+            self.name.clone().cyan().bold(),
+        ];
+        if !self.submission_id.is_empty() {
+            spans.push(format!(" [{}]", self.submission_id).dim());
+        }
+        if !self.payload.is_empty() {
+            spans.push(" ".into());
+            spans.push(self.payload.clone().into());
+        }
+        Line::from(spans)
+    }
+}
+
+/// Bounded buffer of the most recent agent events.
+pub(crate) struct EventLog {
+    entries: VecDeque<EventLogEntry>,
+    next_seq: u64,
+    capacity: usize,
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::with_capacity(EVENT_LOG_CAPACITY)
+    }
+}
+
+impl EventLog {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            next_seq: 0,
+            capacity,
+        }
+    }
+
+    /// Records `event`, evicting the oldest entry when full, and returns the
+    /// new entry.
+    pub(crate) fn record(
+        &mut self,
+        conversation: Option<ThreadId>,
+        event: &Event,
+    ) -> EventLogEntry {
+        let entry = EventLogEntry::new(self.next_seq, conversation, event);
+        self.next_seq += 1;
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry.clone());
+        entry
+    }
+
+    pub(crate) fn entries(&self) -> Vec<EventLogEntry> {
+        self.entries.iter().cloned().collect()
+    }
+}
+
+fn truncate_payload(payload: String) -> String {
+    if payload.chars().count() <= PAYLOAD_PREVIEW_MAX_CHARS {
+        return payload;
+    }
+    let mut truncated: String = payload.chars().take(PAYLOAD_PREVIEW_MAX_CHARS).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::EventMsg;
+    use codex_core::protocol::WarningEvent;
+    use pretty_assertions::assert_eq;
+
+    fn warning(message: &str) -> Event {
+        Event {
+            id: "sub-1".to_string(),
+            msg: EventMsg::Warning(WarningEvent {
+                message: message.to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn records_event_name_and_payload() {
+        let mut log = EventLog::default();
+        let entry = log.record(None, &warning("disk almost full"));
+
+        assert_eq!(entry.seq, 0);
+        assert_eq!(entry.name, "warning");
+        assert_eq!(entry.submission_id, "sub-1");
+        assert_eq!(entry.payload, r#"{"message":"disk almost full"}"#);
+    }
+
+    #[test]
+    fn evicts_oldest_entries_when_full() {
+        let mut log = EventLog::with_capacity(2);
+        for message in ["one", "two", "three"] {
+            log.record(None, &warning(message));
+        }
+
+        let seqs: Vec<u64> = log.entries().iter().map(|entry| entry.seq).collect();
+        assert_eq!(seqs, vec![1, 2]);
+    }
+
+    #[test]
+    fn filter_terms_match_event_name_or_conversation() {
+        let conversation = ThreadId::new();
+        let mut log = EventLog::default();
+        let entry = log.record(Some(conversation), &warning("hello"));
+        let conversation_prefix: String = conversation.to_string().chars().take(6).collect();
+
+        assert!(entry.matches(""));
+        assert!(entry.matches("WARN"));
+        assert!(entry.matches(&format!("warn {conversation_prefix}")));
+        assert!(!entry.matches("exec"));
+        assert!(!entry.matches("warn exec"));
+    }
+
+    #[test]
+    fn long_payloads_are_truncated() {
+        let mut log = EventLog::default();
+        let entry = log.record(None, &warning(&"x".repeat(PAYLOAD_PREVIEW_MAX_CHARS * 2)));
+
+        assert_eq!(entry.payload.chars().count(), PAYLOAD_PREVIEW_MAX_CHARS + 1);
+        assert!(entry.payload.ends_with('…'));
+    }
+}
@@ -27,6 +27,8 @@ use unicode_width::UnicodeWidthStr;
 pub(crate) const TOOL_CALL_MAX_LINES: usize = 5;
 const USER_SHELL_TOOL_CALL_MAX_LINES: usize = 50;
 const MAX_INTERACTION_PREVIEW_CHARS: usize = 80;
+/// Lines of command output kept at each end when the transcript folds it.
+const TRANSCRIPT_FOLDED_EDGE_LINES: usize = 10;
 
 pub(crate) struct OutputLinesParams {
     pub(crate) line_limit: usize,
@@ -207,6 +209,46 @@ impl HistoryCell for ExecCell {
     }
 
     fn transcript_lines(&self, width: u16) -> Vec<Line<'static>> {
+        self.transcript_lines_with_output(width, false)
+    }
+
+    fn folded_transcript_lines(&self, width: u16) -> Vec<Line<'static>> {
+        self.transcript_lines_with_output(width, true)
+    }
+
+    fn desired_folded_transcript_height(&self, width: u16) -> u16 {
+        self.folded_transcript_lines(width).len() as u16
+    }
+
+    fn has_folded_output(&self) -> bool {
+        self.iter_calls().any(|call| {
+            !call.is_unified_exec_interaction()
+                && call
+                    .output
+                    .as_ref()
+                    .is_some_and(|output| folded_line_count(&output.formatted_output) > 0)
+        })
+    }
+}
+
+/// Number of lines of `output` the folded transcript leaves out. Folding away
+/// a single line would not save any space, so that is never done.
+fn folded_line_count(output: &str) -> usize {
+    match output
+        .lines()
+        .count()
+        .saturating_sub(2 * TRANSCRIPT_FOLDED_EDGE_LINES)
+    {
+        1 => 0,
+        omitted => omitted,
+    }
+}
+
+impl ExecCell {
+    /// Transcript lines for every call: the command, its output and its
+    /// result. With `fold`, output longer than twice
+    /// [`TRANSCRIPT_FOLDED_EDGE_LINES`] keeps only its first and last lines.
+    fn transcript_lines_with_output(&self, width: u16, fold: bool) -> Vec<Line<'static>> {
         let mut lines: Vec<Line<'static>> = vec![];
         for (i, call) in self.iter_calls().enumerate() {
             if i > 0 {
@@ -226,9 +268,28 @@ impl HistoryCell for ExecCell {
                 if !call.is_unified_exec_interaction() {
                     let wrap_width = width.max(1) as usize;
                     let wrap_opts = RtOptions::new(wrap_width);
-                    for unwrapped in output.formatted_output.lines().map(ansi_escape_line) {
-                        let wrapped = word_wrap_line(&unwrapped, wrap_opts.clone());
-                        push_owned_lines(&wrapped, &mut lines);
+                    let output_lines = output.formatted_output.lines().collect::<Vec<_>>();
+                    let omitted = folded_line_count(&output.formatted_output);
+                    let (head, tail) = if fold && omitted > 0 {
+                        (
+                            &output_lines[..TRANSCRIPT_FOLDED_EDGE_LINES],
+                            &output_lines[output_lines.len() - TRANSCRIPT_FOLDED_EDGE_LINES..],
+                        )
+                    } else {
+                        (&output_lines[..], &[][..])
+                    };
+                    let push_output = |unwrapped: &str, lines: &mut Vec<Line<'static>>| {
+                        let unwrapped = ansi_escape_line(unwrapped);
+                        push_owned_lines(&word_wrap_line(&unwrapped, wrap_opts.clone()), lines);
+                    };
+                    for unwrapped in head {
+                        push_output(unwrapped, &mut lines);
+                    }
+                    if !tail.is_empty() {
+                        lines.push(format!("… +{omitted} lines (o to expand)").dim().into());
+                        for unwrapped in tail {
+                            push_output(unwrapped, &mut lines);
+                        }
                     }
                 }
                 let duration = call
@@ -249,9 +310,7 @@ impl HistoryCell for ExecCell {
         }
         lines
     }
-}
 
-impl ExecCell {
     fn exploring_display_lines(&self, width: u16) -> Vec<Line<'static>> {
         let mut out: Vec<Line<'static>> = Vec::new();
         out.push(Line::from(vec![
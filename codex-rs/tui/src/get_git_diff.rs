@@ -5,8 +5,10 @@
 //! untracked files. When the current directory is not inside a Git
 //! repository, the function returns `Ok((false, String::new()))`.
 
+use codex_core::workspace_snapshot::diff_since_session_start;
 use std::io;
 use std::path::Path;
+use std::path::PathBuf;
 use std::process::Stdio;
 use tokio::process::Command;
 
@@ -61,6 +63,19 @@ pub(crate) async fn get_git_diff() -> io::Result<(bool, String)> {
     Ok((true, format!("{tracked_diff}{untracked_diff}")))
 }
 
+/// Diff of `cwd` since the first workspace snapshot the session `thread_id`
+/// took of it, for directories that are not git repositories. Returns `None`
+/// if the session has no snapshot of `cwd`.
+pub(crate) async fn get_workspace_diff(
+    codex_home: PathBuf,
+    thread_id: String,
+    cwd: PathBuf,
+) -> io::Result<Option<String>> {
+    tokio::task::spawn_blocking(move || diff_since_session_start(&codex_home, &thread_id, &cwd))
+        .await
+        .map_err(io::Error::other)?
+}
+
 /// Helper that executes `git` with the given `args` and returns `stdout` as a
 /// UTF-8 string. Any non-zero exit status is considered an *error*.
 async fn run_git_capture_stdout(args: &[&str]) -> io::Result<String> {
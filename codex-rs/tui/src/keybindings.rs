@@ -0,0 +1,457 @@
+//! User-configurable key bindings for common TUI actions.
+//!
+//! `[tui.keybindings]` in `config.toml` maps action names to key specs such as
+//! `"ctrl+t"`, `"alt+x"`, `"esc"` or `"f2"`. [`Keymap::from_config`] parses
+//! the overrides and rejects bindings that collide with each other or with
+//! keys the TUI reserves, so conflicts are reported once at startup instead of
+//! silently shadowing an action.
+
+use std::fmt;
+
+use codex_core::config::types::TuiKeybindings;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+
+use crate::key_hint;
+use crate::key_hint::KeyBinding;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum KeyAction {
+    Interrupt,
+    Stop,
+    Approve,
+    ToggleTranscript,
+    Paste,
+    Quit,
+    NextTab,
+    PrevTab,
+}
+
+impl KeyAction {
+    const ALL: [KeyAction; 8] = [
+        KeyAction::Interrupt,
+        KeyAction::Stop,
+        KeyAction::Approve,
+        KeyAction::ToggleTranscript,
+        KeyAction::Paste,
+        KeyAction::Quit,
+        KeyAction::NextTab,
+        KeyAction::PrevTab,
+    ];
+
+    /// Name of the action as written in `[tui.keybindings]`.
+    pub(crate) fn config_name(self) -> &'static str {
+        match self {
+            KeyAction::Interrupt => "interrupt",
+            KeyAction::Stop => "stop",
+            KeyAction::Approve => "approve",
+            KeyAction::ToggleTranscript => "toggle_transcript",
+            KeyAction::Paste => "paste",
+            KeyAction::Quit => "quit",
+            KeyAction::NextTab => "next_tab",
+            KeyAction::PrevTab => "prev_tab",
+        }
+    }
+
+    /// Keys with fixed meanings where the action is handled. Approve only
+    /// applies inside the approval modal, which has its own option shortcuts;
+    /// every other action is handled globally.
+    fn reserved_keys(self) -> &'static [(KeyBinding, &'static str)] {
+        match self {
+            KeyAction::Approve => APPROVAL_RESERVED_KEYS,
+            _ => GLOBAL_RESERVED_KEYS,
+        }
+    }
+}
+
+const GLOBAL_RESERVED_KEYS: &[(KeyBinding, &str)] = &[
+    (key_hint::plain(KeyCode::Enter), "submit"),
+    (key_hint::plain(KeyCode::Tab), "queue message"),
+    (key_hint::ctrl(KeyCode::Char('g')), "external editor"),
+    (key_hint::ctrl(KeyCode::Char('x')), "event inspector"),
+];
+
+const APPROVAL_RESERVED_KEYS: &[(KeyBinding, &str)] = &[
+    (key_hint::plain(KeyCode::Enter), "select option"),
+    (key_hint::plain(KeyCode::Esc), "deny"),
+    (key_hint::plain(KeyCode::Char('n')), "deny"),
+    (key_hint::plain(KeyCode::Char('a')), "approve for session"),
+    (key_hint::plain(KeyCode::Char('p')), "approve prefix"),
+    (key_hint::plain(KeyCode::Char('r')), "review hunks"),
+    (key_hint::plain(KeyCode::Char('c')), "cancel"),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Keymap {
+    pub(crate) interrupt: Vec<KeyBinding>,
+    pub(crate) stop: Vec<KeyBinding>,
+    pub(crate) approve: Vec<KeyBinding>,
+    pub(crate) toggle_transcript: Vec<KeyBinding>,
+    pub(crate) paste: Vec<KeyBinding>,
+    pub(crate) quit: Vec<KeyBinding>,
+    pub(crate) next_tab: Vec<KeyBinding>,
+    pub(crate) prev_tab: Vec<KeyBinding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            interrupt: vec![key_hint::plain(KeyCode::Esc)],
+            stop: vec![key_hint::ctrl(KeyCode::Char('s'))],
+            approve: vec![key_hint::plain(KeyCode::Char('y'))],
+            toggle_transcript: vec![key_hint::ctrl(KeyCode::Char('t'))],
+            // Terminals under WSL often swallow Ctrl+V, so Alt+V and
+            // Ctrl+Alt+V are accepted as well.
+            paste: vec![
+                key_hint::ctrl(KeyCode::Char('v')),
+                key_hint::alt(KeyCode::Char('v')),
+                key_hint::ctrl_alt(KeyCode::Char('v')),
+            ],
+            quit: vec![key_hint::ctrl(KeyCode::Char('c'))],
+            next_tab: vec![key_hint::alt(KeyCode::Char('.'))],
+            prev_tab: vec![key_hint::alt(KeyCode::Char(','))],
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum KeymapError {
+    InvalidKey {
+        action: &'static str,
+        spec: String,
+        reason: String,
+    },
+    Conflict {
+        key: String,
+        first: &'static str,
+        second: &'static str,
+    },
+}
+
+impl fmt::Display for KeymapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeymapError::InvalidKey {
+                action,
+                spec,
+                reason,
+            } => write!(
+                f,
+                "invalid key `{spec}` for tui.keybindings.{action}: {reason}"
+            ),
+            KeymapError::Conflict { key, first, second } => write!(
+                f,
+                "key `{key}` is bound to both `{first}` and `{second}` in tui.keybindings"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for KeymapError {}
+
+impl Keymap {
+    /// Applies the configured overrides on top of the defaults and checks the
+    /// result for conflicts.
+    pub(crate) fn from_config(config: &TuiKeybindings) -> Result<Self, KeymapError> {
+        let mut keymap = Self::default();
+        for action in KeyAction::ALL {
+            let spec = match action {
+                KeyAction::Interrupt => &config.interrupt,
+                KeyAction::Stop => &config.stop,
+                KeyAction::Approve => &config.approve,
+                KeyAction::ToggleTranscript => &config.toggle_transcript,
+                KeyAction::Paste => &config.paste,
+                KeyAction::Quit => &config.quit,
+                KeyAction::NextTab => &config.next_tab,
+                KeyAction::PrevTab => &config.prev_tab,
+            };
+            if let Some(spec) = spec {
+                let binding =
+                    parse_key_binding(spec).map_err(|reason| KeymapError::InvalidKey {
+                        action: action.config_name(),
+                        spec: spec.clone(),
+                        reason,
+                    })?;
+                *keymap.bindings_mut(action) = vec![binding];
+            }
+        }
+        keymap.check_conflicts()?;
+        Ok(keymap)
+    }
+
+    pub(crate) fn bindings(&self, action: KeyAction) -> &[KeyBinding] {
+        match action {
+            KeyAction::Interrupt => &self.interrupt,
+            KeyAction::Stop => &self.stop,
+            KeyAction::Approve => &self.approve,
+            KeyAction::ToggleTranscript => &self.toggle_transcript,
+            KeyAction::Paste => &self.paste,
+            KeyAction::Quit => &self.quit,
+            KeyAction::NextTab => &self.next_tab,
+            KeyAction::PrevTab => &self.prev_tab,
+        }
+    }
+
+    fn bindings_mut(&mut self, action: KeyAction) -> &mut Vec<KeyBinding> {
+        match action {
+            KeyAction::Interrupt => &mut self.interrupt,
+            KeyAction::Stop => &mut self.stop,
+            KeyAction::Approve => &mut self.approve,
+            KeyAction::ToggleTranscript => &mut self.toggle_transcript,
+            KeyAction::Paste => &mut self.paste,
+            KeyAction::Quit => &mut self.quit,
+            KeyAction::NextTab => &mut self.next_tab,
+            KeyAction::PrevTab => &mut self.prev_tab,
+        }
+    }
+
+    /// Whether `event` is a press of any key bound to `action`.
+    pub(crate) fn is_press(&self, action: KeyAction, event: KeyEvent) -> bool {
+        self.bindings(action)
+            .iter()
+            .any(|binding| binding.is_press(event) || binding.is_press(normalize_chord(event)))
+    }
+
+    fn check_conflicts(&self) -> Result<(), KeymapError> {
+        for (i, first) in KeyAction::ALL.iter().enumerate() {
+            for second in &KeyAction::ALL[i + 1..] {
+                if let Some(binding) = self
+                    .bindings(*first)
+                    .iter()
+                    .find(|binding| self.bindings(*second).contains(binding))
+                {
+                    return Err(KeymapError::Conflict {
+                        key: key_label(binding),
+                        first: first.config_name(),
+                        second: second.config_name(),
+                    });
+                }
+            }
+            for binding in self.bindings(*first) {
+                if *first != KeyAction::Approve && is_typing_key(binding) {
+                    return Err(KeymapError::Conflict {
+                        key: key_label(binding),
+                        first: first.config_name(),
+                        second: "typing",
+                    });
+                }
+                if let Some((reserved, purpose)) = first
+                    .reserved_keys()
+                    .iter()
+                    .find(|(reserved, _)| reserved == binding)
+                {
+                    return Err(KeymapError::Conflict {
+                        key: key_label(reserved),
+                        first: first.config_name(),
+                        second: purpose,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Ctrl/Alt chords are matched case-insensitively and regardless of Shift,
+/// matching how terminals report e.g. Ctrl+Shift+C.
+fn normalize_chord(mut event: KeyEvent) -> KeyEvent {
+    if let KeyCode::Char(c) = event.code
+        && event
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+    {
+        event.code = KeyCode::Char(c.to_ascii_lowercase());
+        event.modifiers.remove(KeyModifiers::SHIFT);
+    }
+    event
+}
+
+/// Plain or shifted characters would be swallowed while typing in the composer.
+fn is_typing_key(binding: &KeyBinding) -> bool {
+    matches!(binding.key(), KeyCode::Char(_))
+        && (binding.modifiers() - KeyModifiers::SHIFT).is_empty()
+}
+
+fn key_label(binding: &KeyBinding) -> String {
+    ratatui::text::Span::from(binding).content.into_owned()
+}
+
+/// Parses a key spec like `ctrl+shift+t`, `alt+enter`, `esc` or `f5`.
+pub(crate) fn parse_key_binding(spec: &str) -> Result<KeyBinding, String> {
+    let spec = spec.trim().to_ascii_lowercase();
+    let mut parts: Vec<&str> = spec.split('+').map(str::trim).collect();
+    // A trailing empty part means the key itself is `+` (e.g. `ctrl++`).
+    if parts.len() > 1 && parts.last() == Some(&"") {
+        parts.pop();
+        if let Some(last) = parts.last_mut() {
+            *last = "+";
+        }
+    }
+    let Some((key, modifier_names)) = parts.split_last() else {
+        return Err("empty key".to_string());
+    };
+
+    let mut modifiers = KeyModifiers::NONE;
+    for name in modifier_names {
+        let modifier = match *name {
+            "ctrl" | "control" => KeyModifiers::CONTROL,
+            "alt" | "option" | "meta" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            other => return Err(format!("unknown modifier `{other}`")),
+        };
+        modifiers.insert(modifier);
+    }
+
+    let code = match *key {
+        "" => return Err("empty key".to_string()),
+        "esc" | "escape" => KeyCode::Esc,
+        "enter" | "return" => KeyCode::Enter,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "insert" | "ins" => KeyCode::Insert,
+        "space" => KeyCode::Char(' '),
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" | "pgup" => KeyCode::PageUp,
+        "pagedown" | "pgdn" => KeyCode::PageDown,
+        other => {
+            let mut chars = other.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => match other.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=24) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key `{other}`")),
+                },
+            }
+        }
+    };
+    Ok(KeyBinding::new(code, modifiers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_key_specs() {
+        assert_eq!(
+            parse_key_binding("ctrl+t"),
+            Ok(key_hint::ctrl(KeyCode::Char('t')))
+        );
+        assert_eq!(
+            parse_key_binding(" Ctrl + Alt + K "),
+            Ok(key_hint::ctrl_alt(KeyCode::Char('k')))
+        );
+        assert_eq!(parse_key_binding("esc"), Ok(key_hint::plain(KeyCode::Esc)));
+        assert_eq!(parse_key_binding("f2"), Ok(key_hint::plain(KeyCode::F(2))));
+        assert_eq!(
+            parse_key_binding("alt++"),
+            Ok(key_hint::alt(KeyCode::Char('+')))
+        );
+        assert_eq!(
+            parse_key_binding("hyper+x"),
+            Err("unknown modifier `hyper`".to_string())
+        );
+        assert_eq!(
+            parse_key_binding("ctrl+nope"),
+            Err("unknown key `nope`".to_string())
+        );
+    }
+
+    #[test]
+    fn overrides_replace_defaults() {
+        let keymap = Keymap::from_config(&TuiKeybindings {
+            toggle_transcript: Some("f2".to_string()),
+            ..Default::default()
+        })
+        .expect("valid keymap");
+
+        assert_eq!(
+            keymap.toggle_transcript,
+            vec![key_hint::plain(KeyCode::F(2))]
+        );
+        assert_eq!(keymap.quit, Keymap::default().quit);
+    }
+
+    #[test]
+    fn rejects_actions_sharing_a_key() {
+        let err = Keymap::from_config(&TuiKeybindings {
+            quit: Some("ctrl+t".to_string()),
+            ..Default::default()
+        })
+        .expect_err("conflict");
+
+        assert_eq!(
+            err,
+            KeymapError::Conflict {
+                key: "ctrl + t".to_string(),
+                first: "toggle_transcript",
+                second: "quit",
+            }
+        );
+    }
+
+    #[test]
+    fn reserved_keys_depend_on_where_the_action_applies() {
+        let err = Keymap::from_config(&TuiKeybindings {
+            interrupt: Some("ctrl+g".to_string()),
+            ..Default::default()
+        })
+        .expect_err("reserved");
+        assert_eq!(
+            err.to_string(),
+            "key `ctrl + g` is bound to both `interrupt` and `external editor` in tui.keybindings"
+        );
+
+        assert!(
+            Keymap::from_config(&TuiKeybindings {
+                approve: Some("ctrl+g".to_string()),
+                ..Default::default()
+            })
+            .is_ok()
+        );
+        assert!(
+            Keymap::from_config(&TuiKeybindings {
+                approve: Some("n".to_string()),
+                ..Default::default()
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn rejects_plain_characters_for_global_actions() {
+        let err = Keymap::from_config(&TuiKeybindings {
+            interrupt: Some("q".to_string()),
+            ..Default::default()
+        })
+        .expect_err("typing key");
+
+        assert_eq!(
+            err.to_string(),
+            "key `q` is bound to both `interrupt` and `typing` in tui.keybindings"
+        );
+    }
+
+    #[test]
+    fn chords_match_regardless_of_shift() {
+        let keymap = Keymap::default();
+        let ctrl_shift_c = KeyEvent::new(
+            KeyCode::Char('C'),
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+        );
+
+        assert!(keymap.is_press(KeyAction::Quit, ctrl_shift_c));
+        assert!(!keymap.is_press(
+            KeyAction::Approve,
+            KeyEvent::new(KeyCode::Char('Y'), KeyModifiers::SHIFT)
+        ));
+    }
+}
@@ -19,18 +19,23 @@ use codex_core::config::ConfigOverrides;
 use codex_core::config::find_codex_home;
 use codex_core::config::load_config_as_toml_with_cli_overrides;
 use codex_core::config::resolve_oss_provider;
+use codex_core::config::types::ExecBackendConfig;
+use codex_core::devcontainer::find_devcontainer_config;
 use codex_core::find_thread_path_by_id_str;
 use codex_core::get_platform_sandbox;
 use codex_core::protocol::AskForApproval;
+use codex_core::session_logs;
+use codex_core::session_logs::SessionLogLayer;
 use codex_core::terminal::Multiplexer;
 use codex_protocol::config_types::AltScreenMode;
 use codex_protocol::config_types::SandboxMode;
 use codex_utils_absolute_path::AbsolutePathBuf;
-use std::fs::OpenOptions;
+use keybindings::Keymap;
 use std::path::PathBuf;
+use theme::Theme;
 use tracing::error;
-use tracing_appender::non_blocking;
 use tracing_subscriber::EnvFilter;
+use tracing_subscriber::filter::FilterExt;
 use tracing_subscriber::prelude::*;
 
 mod additional_dirs;
@@ -44,8 +49,10 @@ mod chatwidget;
 mod cli;
 mod clipboard_paste;
 mod color;
+mod command_palette;
 pub mod custom_terminal;
 mod diff_render;
+mod event_inspector;
 mod exec_cell;
 mod exec_command;
 mod external_editor;
@@ -55,6 +62,7 @@ mod get_git_diff;
 mod history_cell;
 pub mod insert_history;
 mod key_hint;
+mod keybindings;
 pub mod live_wrap;
 mod markdown;
 mod markdown_render;
@@ -72,12 +80,16 @@ mod session_log;
 mod shimmer;
 mod slash_command;
 mod status;
+mod status_export;
 mod status_indicator_widget;
 mod streaming;
 mod style;
+mod tabs;
 mod terminal_palette;
 mod text_formatting;
+mod theme;
 mod tooltips;
+mod transcript_search;
 mod tui;
 mod ui_consts;
 pub mod update_action;
@@ -89,8 +101,7 @@ mod wrapping;
 
 #[cfg(test)]
 pub mod test_backend;
-
-use crate::onboarding::TrustDirectorySelection;
+use crate::onboarding::DevcontainerSelection;
 use crate::onboarding::onboarding_screen::OnboardingScreenArgs;
 use crate::onboarding::onboarding_screen::run_onboarding_app;
 use crate::tui::Tui;
@@ -223,6 +234,7 @@ pub async fn run_main(
         codex_linux_sandbox_exe,
         show_raw_agent_reasoning: cli.oss.then_some(true),
         additional_writable_roots: additional_dirs,
+        session_labels: cli.session_labels.clone(),
         ..Default::default()
     };
 
@@ -236,6 +248,24 @@ pub async fn run_main(
         }
     }
 
+    #[allow(clippy::print_stderr)]
+    if let Err(err) = Keymap::from_config(&config.tui_keybindings) {
+        eprintln!("Error in config.toml: {err}");
+        std::process::exit(1);
+    }
+
+    #[allow(clippy::print_stderr)]
+    match Theme::resolve(
+        config.tui_theme.as_deref().unwrap_or(theme::DEFAULT_THEME),
+        &config.tui_themes,
+    ) {
+        Ok(theme) => theme::set_current(theme),
+        Err(err) => {
+            eprintln!("Error in config.toml: {err}");
+            std::process::exit(1);
+        }
+    }
+
     #[allow(clippy::print_stderr)]
     if let Err(err) = enforce_login_restrictions(&config) {
         eprintln!("{err}");
@@ -244,25 +274,10 @@ pub async fn run_main(
 
     let active_profile = config.active_profile.clone();
     let log_dir = codex_core::config::log_dir(&config)?;
-    std::fs::create_dir_all(&log_dir)?;
-    // Open (or create) your log file, appending to it.
-    let mut log_file_opts = OpenOptions::new();
-    log_file_opts.create(true).append(true);
-
-    // Ensure the file is only readable and writable by the current user.
-    // Doing the equivalent to `chmod 600` on Windows is quite a bit more code
-    // and requires the Windows API crates, so we can reconsider that when
-    // Codex CLI is officially supported on Windows.
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::OpenOptionsExt;
-        log_file_opts.mode(0o600);
-    }
-
-    let log_file = log_file_opts.open(log_dir.join("codex-tui.log"))?;
-
-    // Wrap file in non‑blocking writer.
-    let (non_blocking, _guard) = non_blocking(log_file);
+    // Each session logs to its own directory of rotated JSON-lines files,
+    // which `codex logs` reads back.
+    let session_logs_root = codex_core::session_logs::sessions_root(&log_dir);
+    std::fs::create_dir_all(&session_logs_root)?;
 
     // use RUST_LOG env var, default to info for codex crates.
     let env_filter = || {
@@ -271,15 +286,10 @@ pub async fn run_main(
         })
     };
 
-    let file_layer = tracing_subscriber::fmt::layer()
-        .with_writer(non_blocking)
-        // `with_target(true)` is the default, but we previously disabled it for file output.
-        // Keep it enabled so we can selectively enable targets via `RUST_LOG=...` and then
-        // grep for a specific module/target while troubleshooting.
-        .with_target(true)
-        .with_ansi(false)
-        .with_span_events(tracing_subscriber::fmt::format::FmtSpan::FULL)
-        .with_filter(env_filter());
+    // Session spans pass regardless of `RUST_LOG` so that events can be
+    // attributed to the session they belong to.
+    let file_layer = SessionLogLayer::new(session_logs_root)
+        .with_filter(env_filter().or(session_logs::session_span_filter()));
 
     let feedback = codex_feedback::CodexFeedback::new();
     let feedback_layer = feedback.logger_layer();
@@ -345,6 +355,8 @@ async fn run_ratatui_app(
     feedback: codex_feedback::CodexFeedback,
 ) -> color_eyre::Result<AppExitInfo> {
     color_eyre::install()?;
+    // After color_eyre, which replaces rather than chains the panic hook.
+    codex_core::crash_report::install(&initial_config, "codex-tui");
 
     tooltips::announcement::prewarm();
 
@@ -392,14 +404,16 @@ async fn run_ratatui_app(
     );
     let login_status = get_login_status(&initial_config);
     let should_show_trust_screen = should_show_trust_screen(&initial_config);
-    let should_show_onboarding =
-        should_show_onboarding(login_status, &initial_config, should_show_trust_screen);
+    let devcontainer_to_offer = devcontainer_to_offer(&initial_config);
+    let should_show_onboarding = devcontainer_to_offer.is_some()
+        || should_show_onboarding(login_status, &initial_config, should_show_trust_screen);
 
     let config = if should_show_onboarding {
         let onboarding_result = run_onboarding_app(
             OnboardingScreenArgs {
                 show_login_screen: should_show_login_screen(login_status, &initial_config),
                 show_trust_screen: should_show_trust_screen,
+                devcontainer_to_offer,
                 login_status,
                 auth_manager: auth_manager.clone(),
                 config: initial_config.clone(),
@@ -417,11 +431,9 @@ async fn run_ratatui_app(
                 update_action: None,
             });
         }
-        // if the user acknowledged windows or made an explicit decision ato trust the directory, reload the config accordingly
-        if onboarding_result
-            .directory_trust_decision
-            .map(|d| d == TrustDirectorySelection::Trust)
-            .unwrap_or(false)
+        // if the user made an explicit trust decision for the directory, reload the config accordingly
+        if onboarding_result.directory_trust_decision.is_some()
+            || onboarding_result.devcontainer_decision == Some(DevcontainerSelection::UseContainer)
         {
             load_config_or_exit(cli_kv_overrides, overrides).await
         } else {
@@ -503,7 +515,11 @@ async fn run_ratatui_app(
         ..
     } = cli;
 
-    let use_alt_screen = determine_alt_screen_mode(no_alt_screen, config.tui_alternate_screen);
+    // Screen readers follow the scrollback, which the alternate screen hides.
+    let use_alt_screen = determine_alt_screen_mode(
+        no_alt_screen || config.tui_screen_reader,
+        config.tui_alternate_screen,
+    );
     tui.set_alt_screen_enabled(use_alt_screen);
 
     let app_result = App::run(
@@ -623,6 +639,20 @@ fn should_show_trust_screen(config: &Config) -> bool {
     config.active_project.trust_level.is_none()
 }
 
+/// The project's `devcontainer.json`, if there is one and the user has not
+/// yet been asked whether to run commands in it. Only trusted projects are
+/// offered one, since starting the container runs the project's setup
+/// commands.
+fn devcontainer_to_offer(config: &Config) -> Option<PathBuf> {
+    if config.exec_backend != ExecBackendConfig::Local
+        || !config.active_project.is_trusted()
+        || config.active_project.devcontainer.is_some()
+    {
+        return None;
+    }
+    find_devcontainer_config(&config.cwd)
+}
+
 fn should_show_onboarding(
     login_status: LoginStatus,
     config: &Config,
@@ -666,7 +696,7 @@ mod tests {
         let temp_dir = TempDir::new()?;
         let mut config = build_config(&temp_dir).await?;
         config.did_user_set_custom_approval_policy_or_sandbox_mode = false;
-        config.active_project = ProjectConfig { trust_level: None };
+        config.active_project = ProjectConfig::default();
         config.set_windows_sandbox_globally(false);
 
         let should_show = should_show_trust_screen(&config);
@@ -689,7 +719,7 @@ mod tests {
         let temp_dir = TempDir::new()?;
         let mut config = build_config(&temp_dir).await?;
         config.did_user_set_custom_approval_policy_or_sandbox_mode = false;
-        config.active_project = ProjectConfig { trust_level: None };
+        config.active_project = ProjectConfig::default();
         config.set_windows_sandbox_globally(true);
 
         let should_show = should_show_trust_screen(&config);
@@ -714,6 +744,7 @@ mod tests {
         config.did_user_set_custom_approval_policy_or_sandbox_mode = false;
         config.active_project = ProjectConfig {
             trust_level: Some(TrustLevel::Untrusted),
+            ..Default::default()
         };
 
         let should_show = should_show_trust_screen(&config);
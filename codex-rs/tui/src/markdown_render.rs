@@ -1,3 +1,4 @@
+use crate::render::highlight::highlight_code_to_lines;
 use crate::render::line_utils::line_to_static;
 use crate::wrapping::RtOptions;
 use crate::wrapping::word_wrap_line;
@@ -99,6 +100,8 @@ where
     pending_marker_line: bool,
     in_paragraph: bool,
     in_code_block: bool,
+    /// Language of the fenced code block being written, for highlighting.
+    code_block_lang: Option<String>,
     wrap_width: Option<usize>,
     current_line_content: Option<Line<'static>>,
     current_initial_indent: Vec<Span<'static>>,
@@ -124,6 +127,7 @@ where
             pending_marker_line: false,
             in_paragraph: false,
             in_code_block: false,
+            code_block_lang: None,
             wrap_width,
             current_line_content: None,
             current_initial_indent: Vec::new(),
@@ -294,6 +298,13 @@ where
                 self.push_line(Line::default());
             }
         }
+        let mut highlighted = match self.code_block_lang.as_deref() {
+            Some(lang) if self.in_code_block => {
+                let code = text.lines().collect::<Vec<_>>().join("\n");
+                highlight_code_to_lines(&code, lang).map(Vec::into_iter)
+            }
+            _ => None,
+        };
         for (i, line) in text.lines().enumerate() {
             if self.needs_newline {
                 self.push_line(Line::default());
@@ -302,6 +313,15 @@ where
             if i > 0 {
                 self.push_line(Line::default());
             }
+            if let Some(lines) = highlighted.as_mut()
+                && let Some(highlighted_line) = lines.next()
+                && !highlighted_line.spans.is_empty()
+            {
+                for span in highlighted_line.spans {
+                    self.push_span(span);
+                }
+                continue;
+            }
             let content = line.to_string();
             let span = Span::styled(
                 content,
@@ -394,12 +414,13 @@ where
         self.needs_newline = false;
     }
 
-    fn start_codeblock(&mut self, _lang: Option<String>, indent: Option<Span<'static>>) {
+    fn start_codeblock(&mut self, lang: Option<String>, indent: Option<Span<'static>>) {
         self.flush_current_line();
         if !self.text.lines.is_empty() {
             self.push_blank_line();
         }
         self.in_code_block = true;
+        self.code_block_lang = lang.filter(|lang| !lang.is_empty());
         self.indent_stack.push(IndentContext::new(
             vec![indent.unwrap_or_default()],
             None,
@@ -411,6 +432,7 @@ where
     fn end_codeblock(&mut self) {
         self.needs_newline = true;
         self.in_code_block = false;
+        self.code_block_lang = None;
         self.indent_stack.pop();
     }
 
@@ -1,22 +1,32 @@
+mod notifier;
+mod notify_send;
+mod osascript;
 mod osc9;
 mod windows_toast;
 
 use std::env;
-use std::io;
 
+use codex_core::config::types::NotificationBackend;
 use codex_core::env::is_wsl;
-use osc9::Osc9Backend;
+pub(crate) use notifier::DesktopNotifier;
+use notify_send::NotifySendBackend;
+use osascript::OsascriptBackend;
+pub use osc9::Osc9Backend;
 use windows_toast::WindowsToastBackend;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NotificationBackendKind {
     Osc9,
+    Osascript,
+    NotifySend,
     WindowsToast,
 }
 
 #[derive(Debug)]
 pub enum DesktopNotificationBackend {
     Osc9(Osc9Backend),
+    Osascript(OsascriptBackend),
+    NotifySend(NotifySendBackend),
     WindowsToast(WindowsToastBackend),
 }
 
@@ -25,27 +35,47 @@ impl DesktopNotificationBackend {
         Self::Osc9(Osc9Backend)
     }
 
+    pub fn osascript() -> Self {
+        Self::Osascript(OsascriptBackend)
+    }
+
+    pub fn notify_send() -> Self {
+        Self::NotifySend(NotifySendBackend)
+    }
+
     pub fn windows_toast() -> Self {
         Self::WindowsToast(WindowsToastBackend::default())
     }
 
-    pub fn kind(&self) -> NotificationBackendKind {
-        match self {
-            DesktopNotificationBackend::Osc9(_) => NotificationBackendKind::Osc9,
-            DesktopNotificationBackend::WindowsToast(_) => NotificationBackendKind::WindowsToast,
+    /// The backend selected by `tui.notification_backend`.
+    pub fn from_config(backend: NotificationBackend) -> Self {
+        Self::from_config_in(backend, should_use_windows_toasts())
+    }
+
+    /// [`Self::from_config`] for a session that is, or is not, running in
+    /// Windows Terminal under WSL.
+    fn from_config_in(backend: NotificationBackend, windows_terminal_in_wsl: bool) -> Self {
+        match backend {
+            NotificationBackend::Auto => detect_backend_in(windows_terminal_in_wsl),
+            NotificationBackend::Osc9 => Self::osc9(),
+            NotificationBackend::Osascript => Self::osascript(),
+            NotificationBackend::NotifySend => Self::notify_send(),
+            NotificationBackend::WindowsToast => Self::windows_toast(),
         }
     }
 
-    pub fn notify(&mut self, message: &str) -> io::Result<()> {
+    pub fn kind(&self) -> NotificationBackendKind {
         match self {
-            DesktopNotificationBackend::Osc9(backend) => backend.notify(message),
-            DesktopNotificationBackend::WindowsToast(backend) => backend.notify(message),
+            DesktopNotificationBackend::Osc9(_) => NotificationBackendKind::Osc9,
+            DesktopNotificationBackend::Osascript(_) => NotificationBackendKind::Osascript,
+            DesktopNotificationBackend::NotifySend(_) => NotificationBackendKind::NotifySend,
+            DesktopNotificationBackend::WindowsToast(_) => NotificationBackendKind::WindowsToast,
         }
     }
 }
 
-pub fn detect_backend() -> DesktopNotificationBackend {
-    if should_use_windows_toasts() {
+fn detect_backend_in(windows_terminal_in_wsl: bool) -> DesktopNotificationBackend {
+    if windows_terminal_in_wsl {
         tracing::info!(
             "Windows Terminal session detected under WSL; using Windows toast notifications"
         );
@@ -61,8 +91,11 @@ fn should_use_windows_toasts() -> bool {
 
 #[cfg(test)]
 mod tests {
+    use super::DesktopNotificationBackend;
+    use super::NotificationBackend;
     use super::NotificationBackendKind;
-    use super::detect_backend;
+    use super::detect_backend_in;
+    use super::should_use_windows_toasts;
     use serial_test::serial;
     use std::ffi::OsString;
 
@@ -105,7 +138,10 @@ mod tests {
     fn defaults_to_osc9_outside_wsl() {
         let _wsl_guard = EnvVarGuard::remove("WSL_DISTRO_NAME");
         let _wt_guard = EnvVarGuard::remove("WT_SESSION");
-        assert_eq!(detect_backend().kind(), NotificationBackendKind::Osc9);
+        assert_eq!(
+            detect_backend_in(should_use_windows_toasts()).kind(),
+            NotificationBackendKind::Osc9
+        );
     }
 
     #[test]
@@ -113,7 +149,10 @@ mod tests {
     fn waits_for_windows_terminal() {
         let _wsl_guard = EnvVarGuard::set("WSL_DISTRO_NAME", "Ubuntu");
         let _wt_guard = EnvVarGuard::remove("WT_SESSION");
-        assert_eq!(detect_backend().kind(), NotificationBackendKind::Osc9);
+        assert_eq!(
+            detect_backend_in(should_use_windows_toasts()).kind(),
+            NotificationBackendKind::Osc9
+        );
     }
 
     #[cfg(target_os = "linux")]
@@ -123,7 +162,7 @@ mod tests {
         let _wsl_guard = EnvVarGuard::set("WSL_DISTRO_NAME", "Ubuntu");
         let _wt_guard = EnvVarGuard::set("WT_SESSION", "abc");
         assert_eq!(
-            detect_backend().kind(),
+            detect_backend_in(should_use_windows_toasts()).kind(),
             NotificationBackendKind::WindowsToast
         );
     }
@@ -134,6 +173,31 @@ mod tests {
     fn stays_on_osc9_outside_linux_even_with_wsl_env() {
         let _wsl_guard = EnvVarGuard::set("WSL_DISTRO_NAME", "Ubuntu");
         let _wt_guard = EnvVarGuard::set("WT_SESSION", "abc");
-        assert_eq!(detect_backend().kind(), NotificationBackendKind::Osc9);
+        assert_eq!(
+            detect_backend_in(should_use_windows_toasts()).kind(),
+            NotificationBackendKind::Osc9
+        );
+    }
+
+    #[test]
+    fn explicit_backends_ignore_environment_detection() {
+        let in_windows_terminal =
+            |backend| DesktopNotificationBackend::from_config_in(backend, true).kind();
+        assert_eq!(
+            in_windows_terminal(NotificationBackend::Auto),
+            NotificationBackendKind::WindowsToast
+        );
+        assert_eq!(
+            in_windows_terminal(NotificationBackend::NotifySend),
+            NotificationBackendKind::NotifySend
+        );
+        assert_eq!(
+            in_windows_terminal(NotificationBackend::Osascript),
+            NotificationBackendKind::Osascript
+        );
+        assert_eq!(
+            in_windows_terminal(NotificationBackend::Osc9),
+            NotificationBackendKind::Osc9
+        );
     }
 }
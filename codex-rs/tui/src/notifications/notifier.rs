@@ -0,0 +1,318 @@
+//! Desktop notifications driven by session events.
+//!
+//! [`DesktopNotifier`] is registered with the thread manager as the
+//! `tui.notifications` hook, so it runs for the events it subscribes to like
+//! any configured hook and is listed in `/hooks`. Each run happens on a hook
+//! task, so command-based backends never block the TUI thread. OSC 9
+//! notifications are escape sequences and are handed to the TUI instead,
+//! which writes them between frames.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use async_trait::async_trait;
+use codex_core::config::types::Notifications;
+use codex_core::hooks::HookHandler;
+use codex_core::hooks::HookRegistration;
+use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::TurnAbortReason;
+
+use super::DesktopNotificationBackend;
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+use crate::diff_render::display_path_for;
+use crate::text_formatting::truncate_text;
+
+/// Name the notifier is registered under in `/hooks`.
+const HOOK_NAME: &str = "tui.notifications";
+
+const AGENT_NOTIFICATION_PREVIEW_GRAPHEMES: usize = 200;
+
+/// Posts a desktop notification for the events that need the user while the
+/// terminal is unfocused.
+pub(crate) struct DesktopNotifier {
+    backend: DesktopNotificationBackend,
+    /// Set once the backend's command failed; OSC 9 is used from then on.
+    fell_back: AtomicBool,
+    terminal_focused: Arc<AtomicBool>,
+    cwd: PathBuf,
+    app_event_tx: AppEventSender,
+}
+
+impl DesktopNotifier {
+    /// The hook to register for `settings`, or `None` when notifications are
+    /// turned off.
+    pub(crate) fn registration(
+        backend: DesktopNotificationBackend,
+        settings: &Notifications,
+        terminal_focused: Arc<AtomicBool>,
+        cwd: PathBuf,
+        app_event_tx: AppEventSender,
+    ) -> Option<HookRegistration> {
+        let events = subscribed_events(settings);
+        if events.is_empty() {
+            return None;
+        }
+        Some(HookRegistration {
+            name: HOOK_NAME.to_string(),
+            events,
+            handler: Arc::new(Self {
+                backend,
+                fell_back: AtomicBool::new(false),
+                terminal_focused,
+                cwd,
+                app_event_tx,
+            }),
+        })
+    }
+
+    fn post_to_terminal(&self, message: String) {
+        self.app_event_tx.send(AppEvent::PostNotification(message));
+    }
+}
+
+#[async_trait]
+impl HookHandler for DesktopNotifier {
+    async fn handle(&self, event: &Event) -> Result<(), String> {
+        let Some(notification) = Notification::from_event(&event.msg) else {
+            return Ok(());
+        };
+        if self.terminal_focused.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let message = notification.display(&self.cwd);
+        if self.fell_back.load(Ordering::Relaxed) {
+            self.post_to_terminal(message);
+            return Ok(());
+        }
+        let result = match &self.backend {
+            DesktopNotificationBackend::Osc9(_) => {
+                self.post_to_terminal(message);
+                return Ok(());
+            }
+            DesktopNotificationBackend::Osascript(backend) => backend.notify(&message).await,
+            DesktopNotificationBackend::NotifySend(backend) => backend.notify(&message).await,
+            DesktopNotificationBackend::WindowsToast(backend) => backend.notify(&message).await,
+        };
+        if let Err(err) = result {
+            let kind = self.backend.kind();
+            tracing::error!(
+                error = %err,
+                backend = ?kind,
+                "Failed to send desktop notification; falling back to OSC 9"
+            );
+            self.fell_back.store(true, Ordering::Relaxed);
+            self.post_to_terminal(message);
+            return Err(format!("{kind:?} notification failed: {err}"));
+        }
+        Ok(())
+    }
+}
+
+/// The events that produce the notifications `settings` allows.
+fn subscribed_events(settings: &Notifications) -> Vec<String> {
+    let allowed = |type_name: &str| match settings {
+        Notifications::Enabled(enabled) => *enabled,
+        Notifications::Custom(allowed) => allowed.iter().any(|a| a == type_name),
+    };
+    let mut events = Vec::new();
+    if allowed("agent-turn-complete") {
+        events.push("turn_complete");
+    }
+    if allowed("approval-requested") {
+        events.extend([
+            "exec_approval_request",
+            "apply_patch_approval_request",
+            "elicitation_request",
+        ]);
+    }
+    if allowed("error") {
+        events.extend(["error", "turn_aborted"]);
+    }
+    events.into_iter().map(str::to_string).collect()
+}
+
+#[derive(Debug)]
+enum Notification {
+    AgentTurnComplete { response: String },
+    ExecApprovalRequested { command: String },
+    EditApprovalRequested { changes: Vec<PathBuf> },
+    ElicitationRequested { server_name: String },
+    Error { message: String },
+}
+
+impl Notification {
+    fn from_event(msg: &EventMsg) -> Option<Self> {
+        let notification = match msg {
+            EventMsg::TurnComplete(ev) => Notification::AgentTurnComplete {
+                response: ev.last_agent_message.clone().unwrap_or_default(),
+            },
+            EventMsg::ExecApprovalRequest(ev) => Notification::ExecApprovalRequested {
+                command: shlex::try_join(ev.command.iter().map(String::as_str))
+                    .unwrap_or_else(|_| ev.command.join(" ")),
+            },
+            EventMsg::ApplyPatchApprovalRequest(ev) => Notification::EditApprovalRequested {
+                changes: ev.changes.keys().cloned().collect(),
+            },
+            EventMsg::ElicitationRequest(ev) => Notification::ElicitationRequested {
+                server_name: ev.server_name.clone(),
+            },
+            EventMsg::Error(ev) => Notification::Error {
+                message: ev.message.clone(),
+            },
+            EventMsg::TurnAborted(ev) => {
+                let message = match ev.reason {
+                    TurnAbortReason::Replaced => "Turn aborted: replaced by a new task",
+                    TurnAbortReason::TimedOut => "Turn aborted: time limit reached",
+                    TurnAbortReason::Interrupted
+                    | TurnAbortReason::Stopped
+                    | TurnAbortReason::ReviewEnded => return None,
+                };
+                Notification::Error {
+                    message: message.to_string(),
+                }
+            }
+            _ => return None,
+        };
+        Some(notification)
+    }
+
+    fn display(&self, cwd: &std::path::Path) -> String {
+        match self {
+            Notification::AgentTurnComplete { response } => {
+                Notification::agent_turn_preview(response)
+                    .unwrap_or_else(|| "Agent turn complete".to_string())
+            }
+            Notification::ExecApprovalRequested { command } => {
+                format!("Approval requested: {}", truncate_text(command, 30))
+            }
+            Notification::EditApprovalRequested { changes } => {
+                format!(
+                    "Codex wants to edit {}",
+                    match changes.as_slice() {
+                        [path] => display_path_for(path, cwd),
+                        changes => format!("{} files", changes.len()),
+                    }
+                )
+            }
+            Notification::ElicitationRequested { server_name } => {
+                format!("Approval requested by {server_name}")
+            }
+            Notification::Error { message } => {
+                format!(
+                    "Error: {}",
+                    Notification::agent_turn_preview(message)
+                        .unwrap_or_else(|| "turn failed".to_string())
+                )
+            }
+        }
+    }
+
+    fn agent_turn_preview(response: &str) -> Option<String> {
+        let mut normalized = String::new();
+        for part in response.split_whitespace() {
+            if !normalized.is_empty() {
+                normalized.push(' ');
+            }
+            normalized.push_str(part);
+        }
+        let trimmed = normalized.trim();
+        if trimmed.is_empty() {
+            None
+        } else {
+            Some(truncate_text(trimmed, AGENT_NOTIFICATION_PREVIEW_GRAPHEMES))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::ErrorEvent;
+    use codex_core::protocol::TurnAbortedEvent;
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn error_event(message: &str) -> Event {
+        Event {
+            id: "sub-1".into(),
+            msg: EventMsg::Error(ErrorEvent {
+                message: message.to_string(),
+                codex_error_info: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn notifications_come_from_the_events_that_need_the_user() {
+        let display =
+            |msg: &EventMsg| Notification::from_event(msg).map(|n| n.display(Path::new("/repo")));
+        assert_eq!(
+            display(&error_event("stream disconnected\nretry later").msg),
+            Some("Error: stream disconnected retry later".to_string())
+        );
+        assert_eq!(
+            display(&EventMsg::TurnAborted(TurnAbortedEvent {
+                reason: TurnAbortReason::TimedOut,
+            })),
+            Some("Error: Turn aborted: time limit reached".to_string())
+        );
+        assert_eq!(
+            display(&EventMsg::TurnAborted(TurnAbortedEvent {
+                reason: TurnAbortReason::Interrupted,
+            })),
+            None
+        );
+    }
+
+    #[test]
+    fn subscribes_only_to_allowed_notifications() {
+        assert_eq!(
+            subscribed_events(&Notifications::Custom(vec!["error".to_string()])),
+            vec!["error", "turn_aborted"]
+        );
+        assert_eq!(
+            subscribed_events(&Notifications::Enabled(false)),
+            Vec::<String>::new()
+        );
+        assert!(
+            DesktopNotifier::registration(
+                DesktopNotificationBackend::osc9(),
+                &Notifications::Enabled(false),
+                Arc::new(AtomicBool::new(false)),
+                PathBuf::from("/repo"),
+                AppEventSender::new(unbounded_channel().0),
+            )
+            .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn osc9_notifications_are_written_by_the_tui_while_unfocused() {
+        let (tx, mut rx) = unbounded_channel();
+        let focused = Arc::new(AtomicBool::new(true));
+        let registration = DesktopNotifier::registration(
+            DesktopNotificationBackend::osc9(),
+            &Notifications::Enabled(true),
+            Arc::clone(&focused),
+            PathBuf::from("/repo"),
+            AppEventSender::new(tx),
+        )
+        .expect("notifications are enabled");
+
+        let event = error_event("boom");
+        registration.handler.handle(&event).await.expect("handled");
+        assert!(rx.try_recv().is_err());
+
+        focused.store(false, Ordering::Relaxed);
+        registration.handler.handle(&event).await.expect("handled");
+        match rx.try_recv() {
+            Ok(AppEvent::PostNotification(message)) => assert_eq!(message, "Error: boom"),
+            other => panic!("expected a notification for the TUI, got {other:?}"),
+        }
+    }
+}
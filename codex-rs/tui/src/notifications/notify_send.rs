@@ -0,0 +1,54 @@
+use std::io;
+use std::process::Stdio;
+
+use tokio::process::Command;
+
+const NOTIFY_SEND: &str = "notify-send";
+const APP_NAME: &str = "Codex";
+
+/// Posts a freedesktop notification through `notify-send`.
+#[derive(Debug, Default)]
+pub struct NotifySendBackend;
+
+impl NotifySendBackend {
+    pub async fn notify(&self, message: &str) -> io::Result<()> {
+        let status = Command::new(NOTIFY_SEND)
+            .args(build_args(message))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!(
+                "{NOTIFY_SEND} exited with status {status}"
+            )))
+        }
+    }
+}
+
+fn build_args(message: &str) -> Vec<String> {
+    vec![
+        format!("--app-name={APP_NAME}"),
+        // Keep messages that start with `-` from being parsed as options.
+        "--".to_string(),
+        APP_NAME.to_string(),
+        message.to_string(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_args;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn ends_options_before_summary_and_body() {
+        assert_eq!(
+            build_args("-rf done"),
+            vec!["--app-name=Codex", "--", "Codex", "-rf done"]
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use std::io;
+use std::process::Stdio;
+
+use tokio::process::Command;
+
+const OSASCRIPT: &str = "osascript";
+const TITLE: &str = "Codex";
+
+/// Posts to the macOS Notification Center through `osascript`.
+#[derive(Debug, Default)]
+pub struct OsascriptBackend;
+
+impl OsascriptBackend {
+    pub async fn notify(&self, message: &str) -> io::Result<()> {
+        let status = Command::new(OSASCRIPT)
+            .args(build_args(message))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!(
+                "{OSASCRIPT} exited with status {status}"
+            )))
+        }
+    }
+}
+
+/// The message is passed through `argv` rather than spliced into the script,
+/// so it never needs AppleScript quoting.
+fn build_args(message: &str) -> Vec<String> {
+    vec![
+        "-e".to_string(),
+        "on run argv".to_string(),
+        "-e".to_string(),
+        format!("display notification (item 1 of argv) with title \"{TITLE}\""),
+        "-e".to_string(),
+        "end run".to_string(),
+        message.to_string(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_args;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn passes_message_as_script_argument() {
+        assert_eq!(
+            build_args(r#"say "hi" \ bye"#),
+            vec![
+                "-e",
+                "on run argv",
+                "-e",
+                "display notification (item 1 of argv) with title \"Codex\"",
+                "-e",
+                "end run",
+                r#"say "hi" \ bye"#,
+            ]
+        );
+    }
+}
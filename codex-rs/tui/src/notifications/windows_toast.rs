@@ -1,9 +1,9 @@
 use std::io;
-use std::process::Command;
 use std::process::Stdio;
 
 use base64::Engine as _;
 use base64::engine::general_purpose::STANDARD as BASE64;
+use tokio::process::Command;
 
 const APP_ID: &str = "Codex";
 const POWERSHELL_EXE: &str = "powershell.exe";
@@ -14,10 +14,10 @@ pub struct WindowsToastBackend {
 }
 
 impl WindowsToastBackend {
-    pub fn notify(&mut self, message: &str) -> io::Result<()> {
+    pub async fn notify(&self, message: &str) -> io::Result<()> {
         let encoded_body = encode_argument(message);
         let encoded_command = build_encoded_command(&self.encoded_title, &encoded_body);
-        spawn_powershell(encoded_command)
+        spawn_powershell(encoded_command).await
     }
 }
 
@@ -29,7 +29,7 @@ impl Default for WindowsToastBackend {
     }
 }
 
-fn spawn_powershell(encoded_command: String) -> io::Result<()> {
+async fn spawn_powershell(encoded_command: String) -> io::Result<()> {
     let mut command = Command::new(POWERSHELL_EXE);
     command
         .arg("-NoProfile")
@@ -40,7 +40,7 @@ fn spawn_powershell(encoded_command: String) -> io::Result<()> {
         .stdout(Stdio::null())
         .stderr(Stdio::null());
 
-    let status = command.status()?;
+    let status = command.status().await?;
     if status.success() {
         Ok(())
     } else {
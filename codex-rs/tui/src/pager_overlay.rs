@@ -1,7 +1,16 @@
+use std::collections::HashMap;
 use std::io::Result;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+use crate::bottom_pane::ApprovalRequest;
+use crate::diff_render::display_path_for;
+use crate::diff_render::hunk_count;
+use crate::diff_render::render_change_hunk;
+use crate::event_inspector::EventLogEntry;
 use crate::history_cell::HistoryCell;
 use crate::history_cell::UserHistoryCell;
 use crate::key_hint;
@@ -10,10 +19,19 @@ use crate::render::Insets;
 use crate::render::renderable::InsetRenderable;
 use crate::render::renderable::Renderable;
 use crate::style::user_message_style;
+use crate::transcript_search::highlight_line;
+use crate::transcript_search::line_text;
+use crate::transcript_search::match_ranges;
 use crate::tui;
 use crate::tui::TuiEvent;
+use codex_core::protocol::FileChange;
+use codex_core::protocol::Op;
+use codex_core::protocol::RejectedHunk;
+use codex_core::protocol::ReviewDecision;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
 use ratatui::buffer::Buffer;
 use ratatui::buffer::Cell;
 use ratatui::layout::Rect;
@@ -31,11 +49,18 @@ use ratatui::widgets::Wrap;
 pub(crate) enum Overlay {
     Transcript(TranscriptOverlay),
     Static(StaticOverlay),
+    EventInspector(EventInspectorOverlay),
+    PatchReview(PatchReviewOverlay),
 }
 
 impl Overlay {
-    pub(crate) fn new_transcript(cells: Vec<Arc<dyn HistoryCell>>) -> Self {
-        Self::Transcript(TranscriptOverlay::new(cells))
+    pub(crate) fn new_transcript(
+        cells: Vec<Arc<dyn HistoryCell>>,
+        toggle_keys: Vec<KeyBinding>,
+    ) -> Self {
+        let mut overlay = TranscriptOverlay::new(cells);
+        overlay.toggle_keys = toggle_keys;
+        Self::Transcript(overlay)
     }
 
     pub(crate) fn new_static_with_lines(lines: Vec<Line<'static>>, title: String) -> Self {
@@ -49,10 +74,25 @@ impl Overlay {
         Self::Static(StaticOverlay::with_renderables(renderables, title))
     }
 
+    pub(crate) fn new_event_inspector(entries: Vec<EventLogEntry>) -> Self {
+        Self::EventInspector(EventInspectorOverlay::new(entries))
+    }
+
+    /// Opens the per-hunk review for a patch approval request; returns `None`
+    /// for other kinds of requests.
+    pub(crate) fn new_patch_review(
+        request: ApprovalRequest,
+        app_event_tx: AppEventSender,
+    ) -> Option<Self> {
+        PatchReviewOverlay::new(request, app_event_tx).map(Self::PatchReview)
+    }
+
     pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
         match self {
             Overlay::Transcript(o) => o.handle_event(tui, event),
             Overlay::Static(o) => o.handle_event(tui, event),
+            Overlay::EventInspector(o) => o.handle_event(tui, event),
+            Overlay::PatchReview(o) => o.handle_event(tui, event),
         }
     }
 
@@ -60,6 +100,8 @@ impl Overlay {
         match self {
             Overlay::Transcript(o) => o.is_done(),
             Overlay::Static(o) => o.is_done(),
+            Overlay::EventInspector(o) => o.is_done(),
+            Overlay::PatchReview(o) => o.is_done(),
         }
     }
 }
@@ -83,6 +125,14 @@ const KEY_ESC: KeyBinding = key_hint::plain(KeyCode::Esc);
 const KEY_ENTER: KeyBinding = key_hint::plain(KeyCode::Enter);
 const KEY_CTRL_T: KeyBinding = key_hint::ctrl(KeyCode::Char('t'));
 const KEY_CTRL_C: KeyBinding = key_hint::ctrl(KeyCode::Char('c'));
+const KEY_CTRL_X: KeyBinding = key_hint::ctrl(KeyCode::Char('x'));
+const KEY_BACKSPACE: KeyBinding = key_hint::plain(KeyCode::Backspace);
+const KEY_Y: KeyBinding = key_hint::plain(KeyCode::Char('y'));
+const KEY_N: KeyBinding = key_hint::plain(KeyCode::Char('n'));
+const KEY_SHIFT_N: KeyBinding = key_hint::plain(KeyCode::Char('N'));
+const KEY_SLASH: KeyBinding = key_hint::plain(KeyCode::Char('/'));
+const KEY_COLON: KeyBinding = key_hint::plain(KeyCode::Char(':'));
+const KEY_O: KeyBinding = key_hint::plain(KeyCode::Char('o'));
 
 // Common pager navigation hints rendered on the first line
 const PAGER_KEY_HINTS: &[(&[KeyBinding], &str)] = &[
@@ -386,17 +436,94 @@ impl Renderable for CachedRenderable {
 struct CellRenderable {
     cell: Arc<dyn HistoryCell>,
     style: Style,
+    search: Option<CellSearch>,
+    expand_output: bool,
+}
+
+/// Lines of `cell` as the transcript shows them: long tool output stays
+/// folded unless `expand_output` is set.
+fn cell_lines(cell: &dyn HistoryCell, width: u16, expand_output: bool) -> Vec<Line<'static>> {
+    if expand_output {
+        cell.transcript_lines(width)
+    } else {
+        cell.folded_transcript_lines(width)
+    }
+}
+
+fn cell_height(cell: &dyn HistoryCell, width: u16, expand_output: bool) -> u16 {
+    if expand_output {
+        cell.desired_transcript_height(width)
+    } else {
+        cell.desired_folded_transcript_height(width)
+    }
+}
+
+/// Search highlighting for one transcript cell.
+struct CellSearch {
+    query: String,
+    /// Line of the cell holding the selected match, if any.
+    current_line: Option<usize>,
 }
 
 impl Renderable for CellRenderable {
     fn render(&self, area: Rect, buf: &mut Buffer) {
-        let p =
-            Paragraph::new(Text::from(self.cell.transcript_lines(area.width))).style(self.style);
+        let mut lines = cell_lines(self.cell.as_ref(), area.width, self.expand_output);
+        if let Some(search) = &self.search {
+            lines = lines
+                .into_iter()
+                .enumerate()
+                .map(|(i, line)| {
+                    let style = if search.current_line == Some(i) {
+                        Style::new().cyan().reversed()
+                    } else {
+                        Style::new().reversed()
+                    };
+                    highlight_line(line, &search.query, style)
+                })
+                .collect();
+        }
+        let p = Paragraph::new(Text::from(lines)).style(self.style);
         p.render(area, buf);
     }
 
     fn desired_height(&self, width: u16) -> u16 {
-        self.cell.desired_transcript_height(width)
+        cell_height(self.cell.as_ref(), width, self.expand_output)
+    }
+}
+
+/// Text being typed on the hint line of the transcript overlay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TranscriptPrompt {
+    /// `/` search query.
+    Search(String),
+    /// `:` number of the turn to jump to.
+    Turn(String),
+}
+
+/// One transcript line containing a search match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SearchMatch {
+    cell: usize,
+    line: usize,
+    /// Row of the match in the rendered transcript.
+    row: usize,
+}
+
+/// The active `/` search. An empty query means no search.
+#[derive(Debug, Default)]
+struct TranscriptSearch {
+    query: String,
+    matches: Vec<SearchMatch>,
+    current: usize,
+    /// Width `matches` was computed at; `None` when they need recomputing.
+    width: Option<u16>,
+    /// Scroll the current match into view on the next render.
+    pending_scroll: bool,
+}
+
+impl TranscriptSearch {
+    fn current_match(&self) -> Option<SearchMatch> {
+        self.matches.get(self.current).copied()
     }
 }
 
@@ -404,6 +531,17 @@ pub(crate) struct TranscriptOverlay {
     view: PagerView,
     cells: Vec<Arc<dyn HistoryCell>>,
     highlight_cell: Option<usize>,
+    /// Keys that close the overlay in addition to `q` and Ctrl+C; the same
+    /// keys open it.
+    toggle_keys: Vec<KeyBinding>,
+    prompt: Option<TranscriptPrompt>,
+    search: TranscriptSearch,
+    /// Cell to scroll to the top of the view on the next render.
+    pending_jump_cell: Option<usize>,
+    /// Feedback for the last jump or search, shown on the hint line.
+    status: Option<String>,
+    /// Show long tool output in full instead of its first and last lines.
+    expand_output: bool,
     is_done: bool,
 }
 
@@ -411,12 +549,18 @@ impl TranscriptOverlay {
     pub(crate) fn new(transcript_cells: Vec<Arc<dyn HistoryCell>>) -> Self {
         Self {
             view: PagerView::new(
-                Self::render_cells(&transcript_cells, None),
+                Self::render_cells(&transcript_cells, None, &TranscriptSearch::default(), false),
                 "T R A N S C R I P T".to_string(),
                 usize::MAX,
             ),
             cells: transcript_cells,
             highlight_cell: None,
+            toggle_keys: vec![KEY_CTRL_T],
+            prompt: None,
+            search: TranscriptSearch::default(),
+            pending_jump_cell: None,
+            status: None,
+            expand_output: false,
             is_done: false,
         }
     }
@@ -424,12 +568,23 @@ impl TranscriptOverlay {
     fn render_cells(
         cells: &[Arc<dyn HistoryCell>],
         highlight_cell: Option<usize>,
+        search: &TranscriptSearch,
+        expand_output: bool,
     ) -> Vec<Box<dyn Renderable>> {
+        let current = search.current_match();
         cells
             .iter()
             .enumerate()
             .flat_map(|(i, c)| {
                 let mut v: Vec<Box<dyn Renderable>> = Vec::new();
+                let cell_search = search
+                    .matches
+                    .iter()
+                    .any(|m| m.cell == i)
+                    .then(|| CellSearch {
+                        query: search.query.clone(),
+                        current_line: current.filter(|m| m.cell == i).map(|m| m.line),
+                    });
                 let mut cell_renderable = if c.as_any().is::<UserHistoryCell>() {
                     Box::new(CachedRenderable::new(CellRenderable {
                         cell: c.clone(),
@@ -438,11 +593,15 @@ impl TranscriptOverlay {
                         } else {
                             user_message_style()
                         },
+                        search: cell_search,
+                        expand_output,
                     })) as Box<dyn Renderable>
                 } else {
                     Box::new(CachedRenderable::new(CellRenderable {
                         cell: c.clone(),
                         style: Style::default(),
+                        search: cell_search,
+                        expand_output,
                     })) as Box<dyn Renderable>
                 };
                 if !c.is_stream_continuation() && i > 0 {
@@ -460,7 +619,8 @@ impl TranscriptOverlay {
     pub(crate) fn insert_cell(&mut self, cell: Arc<dyn HistoryCell>) {
         let follow_bottom = self.view.is_scrolled_to_bottom();
         self.cells.push(cell);
-        self.view.renderables = Self::render_cells(&self.cells, self.highlight_cell);
+        self.search.width = None;
+        self.rebuild();
         if follow_bottom {
             self.view.scroll_offset = usize::MAX;
         }
@@ -468,44 +628,309 @@ impl TranscriptOverlay {
 
     pub(crate) fn set_highlight_cell(&mut self, cell: Option<usize>) {
         self.highlight_cell = cell;
-        self.view.renderables = Self::render_cells(&self.cells, self.highlight_cell);
+        self.rebuild();
         if let Some(idx) = self.highlight_cell {
             self.view.scroll_chunk_into_view(idx);
         }
     }
 
+    /// True while a `/` or `:` prompt is taking keyboard input.
+    pub(crate) fn is_prompt_active(&self) -> bool {
+        self.prompt.is_some()
+    }
+
+    /// True while search results are highlighted; Esc clears them first.
+    pub(crate) fn has_search(&self) -> bool {
+        !self.search.query.is_empty()
+    }
+
+    fn rebuild(&mut self) {
+        self.view.renderables = Self::render_cells(
+            &self.cells,
+            self.highlight_cell,
+            &self.search,
+            self.expand_output,
+        );
+    }
+
+    /// Switches between folded and full tool output. Row positions change, so
+    /// search matches are recomputed.
+    fn toggle_expand_output(&mut self) {
+        self.expand_output = !self.expand_output;
+        self.search.width = None;
+        self.rebuild();
+    }
+
+    fn start_search(&mut self, query: String) {
+        self.search = TranscriptSearch {
+            query,
+            pending_scroll: true,
+            ..Default::default()
+        };
+        self.status = None;
+        self.rebuild();
+    }
+
+    fn clear_search(&mut self) {
+        self.search = TranscriptSearch::default();
+        self.status = None;
+        self.rebuild();
+    }
+
+    /// Moves the selected match forward (`delta > 0`) or backward, wrapping
+    /// around the transcript.
+    fn step_match(&mut self, delta: isize) {
+        let len = self.search.matches.len();
+        if len == 0 {
+            return;
+        }
+        self.search.current =
+            (self.search.current as isize + delta).rem_euclid(len as isize) as usize;
+        self.search.pending_scroll = true;
+        self.rebuild();
+    }
+
+    /// Scrolls to the start of the `turn`th user message (1-based).
+    fn jump_to_turn(&mut self, turn: &str) {
+        let cell = turn
+            .parse::<usize>()
+            .ok()
+            .and_then(|turn| turn.checked_sub(1))
+            .and_then(|nth| {
+                self.cells
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, cell)| cell.as_any().is::<UserHistoryCell>())
+                    .nth(nth)
+                    .map(|(idx, _)| idx)
+            });
+        match cell {
+            Some(idx) => {
+                self.pending_jump_cell = Some(idx);
+                self.status = None;
+            }
+            None => self.status = Some(format!("no turn {turn}")),
+        }
+    }
+
+    /// Recomputes matches when the query, transcript, or width changed.
+    fn refresh_search(&mut self, width: u16) {
+        if !self.has_search() || self.search.width == Some(width) {
+            return;
+        }
+        let mut matches = Vec::new();
+        let mut top = 0;
+        for (cell_idx, (cell, renderable)) in
+            self.cells.iter().zip(&self.view.renderables).enumerate()
+        {
+            let chunk_height = renderable.desired_height(width) as usize;
+            // Rows above the cell's own content, i.e. the separator inset.
+            let cell_height = cell_height(cell.as_ref(), width, self.expand_output) as usize;
+            let mut row = top + chunk_height.saturating_sub(cell_height);
+            for (line_idx, line) in cell_lines(cell.as_ref(), width, self.expand_output)
+                .into_iter()
+                .enumerate()
+            {
+                if !match_ranges(&line_text(&line), &self.search.query).is_empty() {
+                    matches.push(SearchMatch {
+                        cell: cell_idx,
+                        line: line_idx,
+                        row,
+                    });
+                }
+                row += Paragraph::new(line)
+                    .wrap(Wrap { trim: false })
+                    .line_count(width)
+                    .max(1);
+            }
+            top += chunk_height;
+        }
+        let previous = self.search.current_match();
+        self.search.current = match previous {
+            // Keep the selection stable when new output arrives.
+            Some(previous) => matches
+                .iter()
+                .position(|m| m.cell == previous.cell && m.line == previous.line)
+                .unwrap_or(0),
+            None => {
+                // Start from the first match in or below the current view.
+                let view_top = self
+                    .view
+                    .scroll_offset
+                    .min(top.saturating_sub(self.view.page_height(Rect::default())));
+                matches
+                    .iter()
+                    .position(|m| m.row >= view_top)
+                    .unwrap_or(matches.len().saturating_sub(1))
+            }
+        };
+        self.search.matches = matches;
+        self.search.width = Some(width);
+        self.status = self
+            .search
+            .matches
+            .is_empty()
+            .then(|| format!("no matches for \"{}\"", self.search.query));
+        self.rebuild();
+    }
+
     fn render_hints(&self, area: Rect, buf: &mut Buffer) {
         let line1 = Rect::new(area.x, area.y, area.width, 1);
         let line2 = Rect::new(area.x, area.y.saturating_add(1), area.width, 1);
         render_key_hints(line1, buf, PAGER_KEY_HINTS);
 
-        let mut pairs: Vec<(&[KeyBinding], &str)> =
-            vec![(&[KEY_Q], "to quit"), (&[KEY_ESC], "to edit prev")];
+        match &self.prompt {
+            Some(TranscriptPrompt::Search(query)) => {
+                Paragraph::new(Line::from(vec![
+                    " /".cyan(),
+                    query.clone().into(),
+                    "▏".dim(),
+                ]))
+                .render_ref(line2, buf);
+                return;
+            }
+            Some(TranscriptPrompt::Turn(turn)) => {
+                Paragraph::new(Line::from(vec![
+                    " jump to turn: ".cyan(),
+                    turn.clone().into(),
+                    "▏".dim(),
+                ]))
+                .render_ref(line2, buf);
+                return;
+            }
+            None => {}
+        }
+
+        let mut pairs: Vec<(&[KeyBinding], &str)> = vec![(&[KEY_Q], "to quit")];
+        if self.has_search() {
+            pairs.push((&[KEY_N, KEY_SHIFT_N], "for next/prev"));
+            pairs.push((&[KEY_ESC], "to clear search"));
+        } else {
+            pairs.push((&[KEY_ESC], "to edit prev"));
+            pairs.push((&[KEY_SLASH], "to search"));
+            pairs.push((&[KEY_COLON], "to jump to turn"));
+        }
+        if self.cells.iter().any(|cell| cell.has_folded_output()) {
+            pairs.push((
+                &[KEY_O],
+                if self.expand_output {
+                    "to fold output"
+                } else {
+                    "to expand output"
+                },
+            ));
+        }
         if self.highlight_cell.is_some() {
             pairs.push((&[KEY_ENTER], "to edit message"));
         }
         render_key_hints(line2, buf, &pairs);
+
+        let status = match (&self.status, self.search.current_match()) {
+            (Some(status), _) => status.clone(),
+            (None, Some(_)) => format!("{}/{}", self.search.current + 1, self.search.matches.len()),
+            (None, None) => return,
+        };
+        let status = format!("{status} ");
+        let width = (status.chars().count() as u16).min(line2.width);
+        Span::from(status)
+            .dim()
+            .render_ref(Rect::new(line2.right() - width, line2.y, width, 1), buf);
     }
 
     pub(crate) fn render(&mut self, area: Rect, buf: &mut Buffer) {
         let top_h = area.height.saturating_sub(3);
         let top = Rect::new(area.x, area.y, area.width, top_h);
         let bottom = Rect::new(area.x, area.y + top_h, area.width, 3);
+        self.refresh_search(top.width);
+        let page_height = self.view.content_area(top).height as usize;
+        if let Some(idx) = self.pending_jump_cell.take() {
+            self.view.scroll_offset = self
+                .view
+                .renderables
+                .iter()
+                .take(idx)
+                .map(|r| r.desired_height(top.width) as usize)
+                .sum();
+        } else if std::mem::take(&mut self.search.pending_scroll)
+            && let Some(current) = self.search.current_match()
+        {
+            // Leave some context above the match.
+            self.view.scroll_offset = current.row.saturating_sub(page_height / 3);
+        }
         self.view.render(top, buf);
         self.render_hints(bottom, buf);
     }
+
+    fn handle_prompt_key(&mut self, key_event: KeyEvent) {
+        let Some(prompt) = self.prompt.as_mut() else {
+            return;
+        };
+        let is_search = matches!(prompt, TranscriptPrompt::Search(_));
+        let input = match prompt {
+            TranscriptPrompt::Search(input) | TranscriptPrompt::Turn(input) => input,
+        };
+        match key_event {
+            e if KEY_ESC.is_press(e) || KEY_CTRL_C.is_press(e) => {
+                self.prompt = None;
+            }
+            e if KEY_BACKSPACE.is_press(e) => {
+                if input.pop().is_none() {
+                    self.prompt = None;
+                }
+            }
+            e if KEY_ENTER.is_press(e) => match self.prompt.take() {
+                Some(TranscriptPrompt::Search(query)) if query.is_empty() => self.clear_search(),
+                Some(TranscriptPrompt::Search(query)) => self.start_search(query),
+                Some(TranscriptPrompt::Turn(turn)) => self.jump_to_turn(&turn),
+                None => {}
+            },
+            KeyEvent {
+                code: KeyCode::Char(c),
+                modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
+                kind: KeyEventKind::Press | KeyEventKind::Repeat,
+                ..
+            } => {
+                if is_search || c.is_ascii_digit() {
+                    input.push(c);
+                }
+            }
+            _ => {}
+        }
+    }
 }
 
 impl TranscriptOverlay {
     pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
         match event {
-            TuiEvent::Key(key_event) => match key_event {
-                e if KEY_Q.is_press(e) || KEY_CTRL_C.is_press(e) || KEY_CTRL_T.is_press(e) => {
-                    self.is_done = true;
-                    Ok(())
+            TuiEvent::Key(key_event) => {
+                if self.prompt.is_some() {
+                    self.handle_prompt_key(key_event);
+                    tui.frame_requester().schedule_frame();
+                    return Ok(());
                 }
-                other => self.view.handle_key_event(tui, other),
-            },
+                match key_event {
+                    e if KEY_Q.is_press(e)
+                        || KEY_CTRL_C.is_press(e)
+                        || self.toggle_keys.iter().any(|key| key.is_press(e)) =>
+                    {
+                        self.is_done = true;
+                    }
+                    e if is_char_press(e, '/') => {
+                        self.prompt = Some(TranscriptPrompt::Search(String::new()));
+                    }
+                    e if is_char_press(e, ':') => {
+                        self.prompt = Some(TranscriptPrompt::Turn(String::new()));
+                        self.status = None;
+                    }
+                    e if KEY_O.is_press(e) => self.toggle_expand_output(),
+                    e if self.has_search() && KEY_N.is_press(e) => self.step_match(1),
+                    e if self.has_search() && is_char_press(e, 'N') => self.step_match(-1),
+                    e if self.has_search() && KEY_ESC.is_press(e) => self.clear_search(),
+                    other => return self.view.handle_key_event(tui, other),
+                }
+                tui.frame_requester().schedule_frame();
+                Ok(())
+            }
             TuiEvent::Draw => {
                 tui.draw(u16::MAX, |frame| {
                     self.render(frame.area(), frame.buffer);
@@ -579,6 +1004,386 @@ impl StaticOverlay {
     }
 }
 
+/// Live tail of agent events, filterable by event name and conversation id.
+pub(crate) struct EventInspectorOverlay {
+    view: PagerView,
+    entries: Vec<EventLogEntry>,
+    filter: String,
+    is_done: bool,
+}
+
+impl EventInspectorOverlay {
+    pub(crate) fn new(entries: Vec<EventLogEntry>) -> Self {
+        let mut overlay = Self {
+            view: PagerView::new(Vec::new(), "E V E N T S".to_string(), usize::MAX),
+            entries,
+            filter: String::new(),
+            is_done: false,
+        };
+        overlay.rebuild();
+        overlay
+    }
+
+    pub(crate) fn push_entry(&mut self, entry: EventLogEntry) {
+        let follow_bottom = self.view.is_scrolled_to_bottom();
+        let matches = entry.matches(&self.filter);
+        self.entries.push(entry);
+        if matches {
+            self.rebuild();
+            if follow_bottom {
+                self.view.scroll_offset = usize::MAX;
+            }
+        }
+    }
+
+    fn rebuild(&mut self) {
+        let lines: Vec<Line<'static>> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.matches(&self.filter))
+            .map(EventLogEntry::display_line)
+            .collect();
+        let lines = if lines.is_empty() {
+            vec!["No matching events.".italic().into()]
+        } else {
+            lines
+        };
+        let paragraph = Paragraph::new(Text::from(lines)).wrap(Wrap { trim: false });
+        self.view.renderables = vec![Box::new(CachedRenderable::new(paragraph))];
+    }
+
+    fn set_filter(&mut self, filter: String) {
+        self.filter = filter;
+        self.rebuild();
+        self.view.scroll_offset = usize::MAX;
+    }
+
+    fn render_hints(&self, area: Rect, buf: &mut Buffer) {
+        let line1 = Rect::new(area.x, area.y, area.width, 1);
+        let line2 = Rect::new(area.x, area.y.saturating_add(1), area.width, 1);
+        render_key_hints(line1, buf, PAGER_KEY_HINTS);
+
+        let mut spans: Vec<Span<'static>> = vec![" filter: ".dim()];
+        if self.filter.is_empty() {
+            spans.push("type an event name or conversation id".dim().italic());
+        } else {
+            spans.push(self.filter.clone().cyan());
+        }
+        spans.push("   ".into());
+        spans.push(Span::from(KEY_ESC));
+        spans.push(
+            if self.filter.is_empty() {
+                " to close"
+            } else {
+                " to clear"
+            }
+            .dim(),
+        );
+        Paragraph::new(Line::from(spans)).render_ref(line2, buf);
+    }
+
+    pub(crate) fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let top_h = area.height.saturating_sub(3);
+        let top = Rect::new(area.x, area.y, area.width, top_h);
+        let bottom = Rect::new(area.x, area.y + top_h, area.width, 3);
+        self.view.render(top, buf);
+        self.render_hints(bottom, buf);
+    }
+}
+
+impl EventInspectorOverlay {
+    pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
+        match event {
+            TuiEvent::Key(key_event) => {
+                match key_event {
+                    e if KEY_CTRL_C.is_press(e) || KEY_CTRL_X.is_press(e) => {
+                        self.is_done = true;
+                    }
+                    e if KEY_ESC.is_press(e) => {
+                        if self.filter.is_empty() {
+                            self.is_done = true;
+                        } else {
+                            self.set_filter(String::new());
+                        }
+                    }
+                    e if KEY_BACKSPACE.is_press(e) => {
+                        let mut filter = self.filter.clone();
+                        filter.pop();
+                        self.set_filter(filter);
+                    }
+                    KeyEvent {
+                        code: KeyCode::Char(c),
+                        modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
+                        kind: KeyEventKind::Press | KeyEventKind::Repeat,
+                        ..
+                    } => {
+                        let mut filter = self.filter.clone();
+                        filter.push(c);
+                        self.set_filter(filter);
+                    }
+                    other => return self.view.handle_key_event(tui, other),
+                }
+                tui.frame_requester().schedule_frame();
+                Ok(())
+            }
+            TuiEvent::Draw => {
+                tui.draw(u16::MAX, |frame| {
+                    self.render(frame.area(), frame.buffer);
+                })?;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.is_done
+    }
+}
+
+/// One hunk of a patch under review: a file header plus the hunk's diff,
+/// wrapped to the width it is rendered at.
+struct PatchHunkRenderable {
+    header: Line<'static>,
+    change: FileChange,
+    hunk_index: usize,
+}
+
+impl PatchHunkRenderable {
+    fn lines(&self, width: u16) -> Vec<Line<'static>> {
+        let mut lines = vec![self.header.clone()];
+        lines.extend(render_change_hunk(
+            &self.change,
+            self.hunk_index,
+            width as usize,
+        ));
+        lines.push(Line::from(""));
+        lines
+    }
+}
+
+impl Renderable for PatchHunkRenderable {
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        Paragraph::new(Text::from(self.lines(area.width))).render(area, buf);
+    }
+
+    fn desired_height(&self, width: u16) -> u16 {
+        self.lines(width).len() as u16
+    }
+}
+
+/// Full-screen review of a proposed patch where each hunk can be accepted or
+/// rejected before the patch is applied.
+pub(crate) struct PatchReviewOverlay {
+    view: PagerView,
+    request: ApprovalRequest,
+    id: String,
+    cwd: PathBuf,
+    changes: HashMap<PathBuf, FileChange>,
+    /// Every hunk in display order, with whether it is currently accepted.
+    hunks: Vec<(RejectedHunk, bool)>,
+    selected: usize,
+    app_event_tx: AppEventSender,
+    is_done: bool,
+}
+
+impl PatchReviewOverlay {
+    fn new(request: ApprovalRequest, app_event_tx: AppEventSender) -> Option<Self> {
+        let ApprovalRequest::ApplyPatch {
+            id, cwd, changes, ..
+        } = &request
+        else {
+            return None;
+        };
+        let mut paths: Vec<&PathBuf> = changes.keys().collect();
+        paths.sort();
+        let hunks = paths
+            .into_iter()
+            .flat_map(|path| {
+                let count = changes.get(path).map(hunk_count).unwrap_or(0);
+                (0..count).map(move |hunk_index| {
+                    (
+                        RejectedHunk {
+                            path: path.clone(),
+                            hunk_index,
+                        },
+                        true,
+                    )
+                })
+            })
+            .collect();
+        let mut overlay = Self {
+            view: PagerView::new(Vec::new(), "R E V I E W".to_string(), 0),
+            id: id.clone(),
+            cwd: cwd.clone(),
+            changes: changes.clone(),
+            request,
+            hunks,
+            selected: 0,
+            app_event_tx,
+            is_done: false,
+        };
+        overlay.rebuild();
+        Some(overlay)
+    }
+
+    fn rebuild(&mut self) {
+        let total = self.hunks.len();
+        self.view.renderables = self
+            .hunks
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (hunk, accepted))| {
+                let change = self.changes.get(&hunk.path)?.clone();
+                let marker = if *accepted {
+                    "✔ ".green()
+                } else {
+                    "✗ ".red()
+                };
+                let mut header = Line::from(vec![
+                    marker,
+                    display_path_for(&hunk.path, &self.cwd).bold(),
+                    format!(" ({}/{total})", i + 1).dim(),
+                ]);
+                if i == self.selected {
+                    header = header.reversed();
+                }
+                Some(Box::new(PatchHunkRenderable {
+                    header,
+                    change,
+                    hunk_index: hunk.hunk_index,
+                }) as Box<dyn Renderable>)
+            })
+            .collect();
+        self.view.scroll_chunk_into_view(self.selected);
+    }
+
+    fn select(&mut self, selected: usize) {
+        self.selected = selected.min(self.hunks.len().saturating_sub(1));
+        self.rebuild();
+    }
+
+    /// Marks the selected hunk and moves on to the next one.
+    fn decide_selected(&mut self, accepted: bool) {
+        if let Some((_, state)) = self.hunks.get_mut(self.selected) {
+            *state = accepted;
+        }
+        self.select(self.selected + 1);
+    }
+
+    fn decision(&self) -> ReviewDecision {
+        let rejected_hunks: Vec<RejectedHunk> = self
+            .hunks
+            .iter()
+            .filter(|(_, accepted)| !accepted)
+            .map(|(hunk, _)| hunk.clone())
+            .collect();
+        if rejected_hunks.is_empty() {
+            ReviewDecision::Approved
+        } else {
+            ReviewDecision::ApprovedHunks { rejected_hunks }
+        }
+    }
+
+    fn submit(&mut self) {
+        self.app_event_tx.send(AppEvent::CodexOp(Op::PatchApproval {
+            id: self.id.clone(),
+            decision: self.decision(),
+        }));
+        self.is_done = true;
+    }
+
+    fn cancel(&mut self) {
+        self.app_event_tx
+            .send(AppEvent::ReopenApprovalRequest(self.request.clone()));
+        self.is_done = true;
+    }
+
+    fn render_hints(&self, area: Rect, buf: &mut Buffer) {
+        let line1 = Rect::new(area.x, area.y, area.width, 1);
+        let line2 = Rect::new(area.x, area.y.saturating_add(1), area.width, 1);
+        render_key_hints(
+            line1,
+            buf,
+            &[
+                (&[KEY_UP, KEY_DOWN], "to pick a hunk"),
+                (&[KEY_PAGE_UP, KEY_PAGE_DOWN], "to scroll"),
+            ],
+        );
+        let rejected = self.hunks.iter().filter(|(_, accepted)| !accepted).count();
+        let pairs: Vec<(&[KeyBinding], &str)> = vec![
+            (&[KEY_Y], "to accept"),
+            (&[KEY_N], "to reject"),
+            (
+                &[KEY_ENTER],
+                if rejected == self.hunks.len() {
+                    "to reject all"
+                } else {
+                    "to apply accepted"
+                },
+            ),
+            (&[KEY_ESC], "to go back"),
+        ];
+        render_key_hints(line2, buf, &pairs);
+    }
+
+    pub(crate) fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        let top_h = area.height.saturating_sub(3);
+        let top = Rect::new(area.x, area.y, area.width, top_h);
+        let bottom = Rect::new(area.x, area.y + top_h, area.width, 3);
+        self.view.render(top, buf);
+        self.render_hints(bottom, buf);
+    }
+}
+
+impl PatchReviewOverlay {
+    pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
+        match event {
+            TuiEvent::Key(key_event) => {
+                match key_event {
+                    e if KEY_ESC.is_press(e) || KEY_CTRL_C.is_press(e) => self.cancel(),
+                    e if KEY_ENTER.is_press(e) => self.submit(),
+                    e if KEY_Y.is_press(e) => self.decide_selected(true),
+                    e if KEY_N.is_press(e) => self.decide_selected(false),
+                    e if KEY_UP.is_press(e) || KEY_K.is_press(e) => {
+                        self.select(self.selected.saturating_sub(1));
+                    }
+                    e if KEY_DOWN.is_press(e) || KEY_J.is_press(e) => {
+                        self.select(self.selected + 1);
+                    }
+                    other => return self.view.handle_key_event(tui, other),
+                }
+                tui.frame_requester().schedule_frame();
+                Ok(())
+            }
+            TuiEvent::Draw => {
+                tui.draw(u16::MAX, |frame| {
+                    self.render(frame.area(), frame.buffer);
+                })?;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn is_done(&self) -> bool {
+        self.is_done
+    }
+}
+
+/// Matches a printable key regardless of whether the terminal reports Shift.
+fn is_char_press(event: KeyEvent, c: char) -> bool {
+    matches!(
+        event,
+        KeyEvent {
+            code: KeyCode::Char(pressed),
+            modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
+            kind: KeyEventKind::Press | KeyEventKind::Repeat,
+            ..
+        } if pressed == c
+    )
+}
+
 fn render_offset_content(
     area: Rect,
     buf: &mut Buffer,
@@ -625,6 +1430,7 @@ mod tests {
     use codex_protocol::parse_command::ParsedCommand;
     use ratatui::Terminal;
     use ratatui::backend::TestBackend;
+    use ratatui::style::Modifier;
     use ratatui::text::Text;
 
     #[derive(Debug)]
@@ -778,6 +1584,49 @@ mod tests {
         assert_snapshot!("transcript_overlay_apply_patch_scroll_vt100", snapshot);
     }
 
+    #[test]
+    fn transcript_folds_long_command_output_until_expanded() {
+        let mut exec_cell = crate::exec_cell::new_active_exec_command(
+            "exec-1".into(),
+            vec!["bash".into(), "-lc".into(), "cargo test".into()],
+            vec![ParsedCommand::Unknown {
+                cmd: "cargo test".into(),
+            }],
+            ExecCommandSource::Agent,
+            None,
+            true,
+        );
+        let output = (0..40).map(|i| format!("out-{i}\n")).collect::<String>();
+        exec_cell.complete_call(
+            "exec-1",
+            CommandOutput {
+                exit_code: 2,
+                aggregated_output: output.clone(),
+                formatted_output: output,
+            },
+            Duration::from_millis(420),
+        );
+        let mut overlay = TranscriptOverlay::new(vec![Arc::new(exec_cell) as Arc<dyn HistoryCell>]);
+        let area = Rect::new(0, 0, 80, 60);
+        let render = |overlay: &mut TranscriptOverlay| {
+            let mut buf = Buffer::empty(area);
+            overlay.render(area, &mut buf);
+            buffer_to_text(&buf, area)
+        };
+
+        let folded = render(&mut overlay);
+        assert!(folded.contains("out-9\n… +20 lines (o to expand)\nout-30\n"));
+        assert!(!folded.contains("out-20"));
+        assert!(folded.contains("✗ (2) • 420ms"));
+        assert!(folded.contains("o to expand output"));
+
+        overlay.toggle_expand_output();
+        let expanded = render(&mut overlay);
+        assert!(expanded.contains("out-19\nout-20\n"));
+        assert!(!expanded.contains("+20 lines"));
+        assert!(expanded.contains("o to fold output"));
+    }
+
     #[test]
     fn transcript_overlay_keeps_scroll_pinned_at_bottom() {
         let mut overlay = TranscriptOverlay::new(
@@ -868,6 +1717,92 @@ mod tests {
         nums
     }
 
+    fn press(overlay: &mut TranscriptOverlay, keys: &str) {
+        for c in keys.chars() {
+            overlay.handle_prompt_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        overlay.handle_prompt_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn transcript_search_highlights_and_cycles_matches() {
+        let mut overlay = TranscriptOverlay::new(
+            (0..50)
+                .map(|i| {
+                    let text = if i == 10 || i == 30 {
+                        format!("line-{i:02} Needle")
+                    } else {
+                        format!("line-{i:02}")
+                    };
+                    Arc::new(TestCell {
+                        lines: vec![Line::from(text)],
+                    }) as Arc<dyn HistoryCell>
+                })
+                .collect(),
+        );
+        let area = Rect::new(0, 0, 40, 15);
+        transcript_line_numbers(&mut overlay, area);
+
+        overlay.prompt = Some(TranscriptPrompt::Search(String::new()));
+        press(&mut overlay, "needle");
+
+        // Opened at the bottom, the search selects the closest match above.
+        assert!(transcript_line_numbers(&mut overlay, area).contains(&30));
+        assert_eq!(overlay.search.matches.len(), 2);
+        assert_eq!(overlay.search.current, 1);
+        let mut buf = Buffer::empty(area);
+        overlay.render(area, &mut buf);
+        let text = buffer_to_text(&buf, area);
+        assert!(text.contains("2/2"), "expected match position in {text:?}");
+        let (x, y) = (0..area.height)
+            .find_map(|y| {
+                let row: String = (0..area.width).map(|x| buf[(x, y)].symbol()).collect();
+                row.find("Needle").map(|x| (x as u16, y))
+            })
+            .expect("match is visible");
+        assert!(buf[(x, y)].modifier.contains(Modifier::REVERSED));
+
+        overlay.step_match(1);
+        assert!(transcript_line_numbers(&mut overlay, area).contains(&10));
+        assert_eq!(overlay.search.current, 0);
+
+        overlay.clear_search();
+        assert!(!overlay.has_search());
+    }
+
+    #[test]
+    fn transcript_jumps_to_turn_by_number() {
+        let mut cells: Vec<Arc<dyn HistoryCell>> = Vec::new();
+        for turn in 1..=4 {
+            cells.push(Arc::new(UserHistoryCell {
+                message: format!("prompt {turn}"),
+            }));
+            for i in 0..10 {
+                cells.push(Arc::new(TestCell {
+                    lines: vec![Line::from(format!("line-{turn}{i}"))],
+                }));
+            }
+        }
+        let mut overlay = TranscriptOverlay::new(cells);
+        let area = Rect::new(0, 0, 40, 12);
+
+        overlay.prompt = Some(TranscriptPrompt::Turn(String::new()));
+        press(&mut overlay, "2");
+        let mut buf = Buffer::empty(area);
+        overlay.render(area, &mut buf);
+        let text = buffer_to_text(&buf, area);
+        let first_content_row = text.lines().nth(1).unwrap_or_default();
+        assert!(
+            text.lines().take(4).any(|line| line.contains("prompt 2")),
+            "expected turn 2 at the top, got {text:?}"
+        );
+        assert!(!first_content_row.contains("line-1"));
+
+        overlay.prompt = Some(TranscriptPrompt::Turn(String::new()));
+        press(&mut overlay, "9");
+        assert_eq!(overlay.status.as_deref(), Some("no turn 9"));
+    }
+
     #[test]
     fn transcript_overlay_paging_is_continuous_and_round_trips() {
         let mut overlay = TranscriptOverlay::new(
@@ -1034,4 +1969,137 @@ mod tests {
             "expected view to report at bottom after scrolling to end"
         );
     }
+
+    fn logged_events(messages: &[&str]) -> Vec<EventLogEntry> {
+        let mut log = crate::event_inspector::EventLog::default();
+        for message in messages {
+            log.record(
+                None,
+                &codex_core::protocol::Event {
+                    id: "sub".to_string(),
+                    msg: codex_core::protocol::EventMsg::Warning(
+                        codex_core::protocol::WarningEvent {
+                            message: message.to_string(),
+                        },
+                    ),
+                },
+            );
+        }
+        log.record(
+            None,
+            &codex_core::protocol::Event {
+                id: "sub".to_string(),
+                msg: codex_core::protocol::EventMsg::TurnAborted(
+                    codex_core::protocol::TurnAbortedEvent {
+                        reason: codex_core::protocol::TurnAbortReason::Interrupted,
+                        cancelled: Vec::new(),
+                    },
+                ),
+            },
+        );
+        log.entries()
+    }
+
+    #[test]
+    fn event_inspector_filters_by_event_name() {
+        let mut overlay = EventInspectorOverlay::new(logged_events(&["first", "second"]));
+        overlay.set_filter("abort".to_string());
+
+        let area = Rect::new(0, 0, 120, 10);
+        let mut buf = Buffer::empty(area);
+        overlay.render(area, &mut buf);
+        let text = buffer_to_text(&buf, area);
+
+        assert!(text.contains("turn_aborted"), "{text}");
+        assert!(!text.contains("warning"), "{text}");
+        assert!(text.contains("filter: abort"), "{text}");
+    }
+
+    #[test]
+    fn event_inspector_tails_new_events_at_bottom() {
+        let mut overlay = EventInspectorOverlay::new(logged_events(
+            &(0..20).map(|_| "noise").collect::<Vec<_>>(),
+        ));
+        let mut term = Terminal::new(TestBackend::new(80, 12)).expect("term");
+        term.draw(|f| overlay.render(f.area(), f.buffer_mut()))
+            .expect("draw");
+        assert!(overlay.view.is_scrolled_to_bottom());
+
+        for entry in logged_events(&["late"]) {
+            overlay.push_entry(entry);
+        }
+
+        assert_eq!(overlay.view.scroll_offset, usize::MAX);
+    }
+
+    fn two_file_patch_request() -> ApprovalRequest {
+        let mut changes = HashMap::new();
+        changes.insert(
+            PathBuf::from("/repo/a.txt"),
+            FileChange::Update {
+                unified_diff:
+                    "@@ -1,2 +1,2 @@\n-one\n+uno\n two\n@@ -9,2 +9,2 @@\n nine\n-ten\n+diez\n"
+                        .to_string(),
+                move_path: None,
+            },
+        );
+        changes.insert(
+            PathBuf::from("/repo/b.txt"),
+            FileChange::Add {
+                content: "new\n".to_string(),
+            },
+        );
+        ApprovalRequest::ApplyPatch {
+            id: "patch-1".to_string(),
+            reason: None,
+            cwd: PathBuf::from("/repo"),
+            changes,
+        }
+    }
+
+    #[test]
+    fn patch_review_reports_rejected_hunks() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut overlay =
+            PatchReviewOverlay::new(two_file_patch_request(), AppEventSender::new(tx))
+                .expect("patch request");
+        assert_eq!(overlay.hunks.len(), 3);
+        assert_eq!(overlay.decision(), ReviewDecision::Approved);
+
+        overlay.decide_selected(true);
+        overlay.decide_selected(false);
+        overlay.submit();
+
+        let Ok(AppEvent::CodexOp(Op::PatchApproval { id, decision })) = rx.try_recv() else {
+            panic!("expected a patch approval op");
+        };
+        assert_eq!(id, "patch-1");
+        assert_eq!(
+            decision,
+            ReviewDecision::ApprovedHunks {
+                rejected_hunks: vec![RejectedHunk {
+                    path: PathBuf::from("/repo/a.txt"),
+                    hunk_index: 1,
+                }],
+            }
+        );
+        assert!(overlay.is_done());
+    }
+
+    #[test]
+    fn patch_review_cancel_reopens_the_approval() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut overlay =
+            PatchReviewOverlay::new(two_file_patch_request(), AppEventSender::new(tx))
+                .expect("patch request");
+
+        overlay.cancel();
+
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(AppEvent::ReopenApprovalRequest(
+                ApprovalRequest::ApplyPatch { .. }
+            ))
+        ));
+    }
 }
@@ -20,14 +20,23 @@ pub enum SlashCommand {
     Skills,
     Review,
     New,
+    Tabs,
     Resume,
+    Tag,
+    Set,
     Init,
     Compact,
-    // Undo,
+    Undo,
     Diff,
     Mention,
     Status,
+    Stats,
+    Sources,
+    Theme,
     Mcp,
+    McpResources,
+    Hooks,
+    ShellCommands,
     Logout,
     Quit,
     Exit,
@@ -47,18 +56,29 @@ impl SlashCommand {
             SlashCommand::Compact => "summarize conversation to prevent hitting the context limit",
             SlashCommand::Review => "review my current changes and find issues",
             SlashCommand::Resume => "resume a saved chat",
-            // SlashCommand::Undo => "ask Codex to undo a turn",
+            SlashCommand::Tabs => "switch between, open, or close conversation tabs",
+            SlashCommand::Tag => "tag this session (`/tag backend`, `/tag -backend` to remove)",
+            SlashCommand::Set => "set variables for `{{name}}` in commands (`/set name=value`)",
+            SlashCommand::Undo => "restore the files changed by the last turn",
             SlashCommand::Quit | SlashCommand::Exit => "exit Codex",
-            SlashCommand::Diff => "show git diff (including untracked files)",
+            SlashCommand::Diff => "show git diff, or `/diff last` for the last turn's changes",
             SlashCommand::Mention => "mention a file",
             SlashCommand::Skills => "use skills to improve how Codex performs specific tasks",
             SlashCommand::Status => "show current session configuration and token usage",
+            SlashCommand::Stats => "show tokens, estimated cost, and time used this session",
+            SlashCommand::Sources => "list the files, pages, and MCP servers the model was given",
             SlashCommand::Ps => "list background terminals",
+            SlashCommand::Theme => "choose a color theme",
             SlashCommand::Model => "choose what model and reasoning effort to use",
             SlashCommand::Approvals => "choose what Codex can do without approval",
             SlashCommand::ElevateSandbox => "set up elevated agent sandbox",
             SlashCommand::Experimental => "toggle beta features",
             SlashCommand::Mcp => "list configured MCP tools",
+            SlashCommand::McpResources => "attach an MCP resource to your next message",
+            SlashCommand::Hooks => "list hooks and enable or disable them for this session",
+            SlashCommand::ShellCommands => {
+                "list this session's commands and pre-approve similar ones"
+            }
             SlashCommand::Logout => "log out of Codex",
             SlashCommand::Rollout => "print the rollout file path",
             SlashCommand::TestApproval => "test approval request",
@@ -78,7 +98,7 @@ impl SlashCommand {
             | SlashCommand::Resume
             | SlashCommand::Init
             | SlashCommand::Compact
-            // | SlashCommand::Undo
+            | SlashCommand::Undo
             | SlashCommand::Model
             | SlashCommand::Approvals
             | SlashCommand::ElevateSandbox
@@ -89,8 +109,17 @@ impl SlashCommand {
             | SlashCommand::Mention
             | SlashCommand::Skills
             | SlashCommand::Status
+            | SlashCommand::Stats
+            | SlashCommand::Sources
+            | SlashCommand::Theme
             | SlashCommand::Ps
             | SlashCommand::Mcp
+            | SlashCommand::McpResources
+            | SlashCommand::Hooks
+            | SlashCommand::ShellCommands
+            | SlashCommand::Tabs
+            | SlashCommand::Tag
+            | SlashCommand::Set
             | SlashCommand::Feedback
             | SlashCommand::Quit
             | SlashCommand::Exit => true,
@@ -0,0 +1,247 @@
+//! Status export for terminal multiplexers (`[tui.status_export]`).
+//!
+//! With several Codex sessions in tmux or zellij panes there is no telling
+//! from outside which one is waiting on you. When configured, the TUI writes
+//! its state to a JSON file a status bar script can read, and/or to the
+//! terminal title, which tmux shows as `#{pane_title}`. The state covers every
+//! tab: the pane needs approval if any of its tabs does.
+
+use std::fmt;
+use std::io;
+use std::io::stdout;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_core::config::types::TuiStatusExport;
+use crossterm::Command;
+use ratatui::crossterm::execute;
+use serde::Serialize;
+
+use crate::tabs::TabStatus;
+use crate::tabs::TabSummary;
+use crate::tabs::UNTITLED_TAB;
+
+/// What is written to the status file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct ExportedStatus {
+    /// `waiting`, `running`, or `needs_approval`.
+    pub(crate) state: &'static str,
+    /// Number of tabs blocked on an approval.
+    pub(crate) pending_approvals: usize,
+    /// Title of the tab the state comes from: its first user message.
+    pub(crate) task: Option<String>,
+    /// Session of the tab on screen, for `codex resume`.
+    pub(crate) session_id: Option<String>,
+    pub(crate) cwd: PathBuf,
+    pub(crate) pid: u32,
+}
+
+impl ExportedStatus {
+    /// The state of the most urgent tab, preferring the active one on ties.
+    pub(crate) fn from_tabs(
+        summaries: &[TabSummary],
+        session_id: Option<String>,
+        cwd: &Path,
+    ) -> Self {
+        let urgent = summaries
+            .iter()
+            .max_by_key(|summary| (urgency(summary.status), summary.is_active));
+        let status = urgent.map(|summary| summary.status).unwrap_or_default();
+        Self {
+            state: match status {
+                TabStatus::Waiting => "waiting",
+                TabStatus::Running => "running",
+                TabStatus::NeedsApproval => "needs_approval",
+            },
+            pending_approvals: summaries
+                .iter()
+                .filter(|summary| summary.status == TabStatus::NeedsApproval)
+                .count(),
+            task: urgent
+                .filter(|summary| summary.title != UNTITLED_TAB)
+                .map(|summary| summary.title.clone()),
+            session_id,
+            cwd: cwd.to_path_buf(),
+            pid: std::process::id(),
+        }
+    }
+
+    fn title(&self) -> String {
+        let state = self.state.replace('_', " ");
+        match &self.task {
+            Some(task) => format!("codex: {state} · {task}"),
+            None => format!("codex: {state}"),
+        }
+    }
+}
+
+fn urgency(status: TabStatus) -> u8 {
+    match status {
+        TabStatus::Waiting => 0,
+        TabStatus::Running => 1,
+        TabStatus::NeedsApproval => 2,
+    }
+}
+
+/// Publishes [`ExportedStatus`] where `[tui.status_export]` asks for it,
+/// skipping writes when nothing changed.
+pub(crate) struct StatusExporter {
+    file: Option<PathBuf>,
+    title: bool,
+    last: Option<ExportedStatus>,
+}
+
+impl StatusExporter {
+    /// `None` when nothing is configured.
+    pub(crate) fn new(config: &TuiStatusExport) -> Option<Self> {
+        let file = config.file.as_ref().map(|file| {
+            let pid = std::process::id().to_string();
+            let pane = std::env::var("TMUX_PANE")
+                .or_else(|_| std::env::var("ZELLIJ_PANE_ID"))
+                .unwrap_or_else(|_| pid.clone());
+            expand_placeholders(file.as_path(), &pid, &pane)
+        });
+        if file.is_none() && !config.title {
+            return None;
+        }
+        Some(Self {
+            file,
+            title: config.title,
+            last: None,
+        })
+    }
+
+    pub(crate) fn update(&mut self, status: ExportedStatus) {
+        if self.last.as_ref() == Some(&status) {
+            return;
+        }
+        if let Some(file) = &self.file
+            && let Err(err) = write_status_file(file, &status)
+        {
+            tracing::warn!("failed to write status file {}: {err}", file.display());
+        }
+        if self.title
+            && let Err(err) = execute!(stdout(), SetTitle(status.title()))
+        {
+            tracing::warn!("failed to set terminal title: {err}");
+        }
+        self.last = Some(status);
+    }
+}
+
+impl Drop for StatusExporter {
+    fn drop(&mut self) {
+        if let Some(file) = &self.file {
+            let _ = std::fs::remove_file(file);
+        }
+        if self.title && self.last.is_some() {
+            let _ = execute!(stdout(), SetTitle(String::new()));
+        }
+    }
+}
+
+fn expand_placeholders(path: &Path, pid: &str, pane: &str) -> PathBuf {
+    PathBuf::from(
+        path.to_string_lossy()
+            .replace("{pid}", pid)
+            .replace("{pane}", pane),
+    )
+}
+
+/// Writes through a temporary file so readers never see a partial status.
+fn write_status_file(file: &Path, status: &ExportedStatus) -> io::Result<()> {
+    if let Some(parent) = file.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut contents = serde_json::to_string(status).map_err(io::Error::other)?;
+    contents.push('\n');
+    let staged = file.with_extension("tmp");
+    std::fs::write(&staged, contents)?;
+    std::fs::rename(&staged, file)
+}
+
+/// Command that sets the terminal title (OSC 2).
+#[derive(Debug, Clone)]
+struct SetTitle(String);
+
+impl Command for SetTitle {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        let title: String = self.0.chars().filter(|c| !c.is_control()).collect();
+        write!(f, "\x1b]2;{title}\x07")
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        Err(std::io::Error::other(
+            "tried to execute SetTitle using WinAPI; use ANSI instead",
+        ))
+    }
+
+    #[cfg(windows)]
+    fn is_ansi_code_supported(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn summary(id: u64, title: &str, status: TabStatus, is_active: bool) -> TabSummary {
+        TabSummary {
+            id,
+            number: id as usize + 1,
+            title: title.to_string(),
+            status,
+            is_active,
+        }
+    }
+
+    #[test]
+    fn most_urgent_tab_sets_the_state() {
+        let cwd = Path::new("/repo");
+        let summaries = vec![
+            summary(0, UNTITLED_TAB, TabStatus::Waiting, true),
+            summary(1, "fix the build", TabStatus::Running, false),
+            summary(2, "bump deps", TabStatus::NeedsApproval, false),
+        ];
+        let status = ExportedStatus::from_tabs(&summaries, None, cwd);
+        assert_eq!(status.state, "needs_approval");
+        assert_eq!(status.pending_approvals, 1);
+        assert_eq!(status.task.as_deref(), Some("bump deps"));
+        assert_eq!(status.title(), "codex: needs approval · bump deps");
+
+        let status = ExportedStatus::from_tabs(&summaries[..1], None, cwd);
+        assert_eq!(status.state, "waiting");
+        assert_eq!(status.task, None);
+        assert_eq!(status.title(), "codex: waiting");
+    }
+
+    #[test]
+    fn status_file_is_written_and_removed() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = expand_placeholders(&dir.path().join("status/{pane}.json"), "42", "%3");
+        assert_eq!(file, dir.path().join("status/%3.json"));
+
+        let mut exporter = StatusExporter {
+            file: Some(file.clone()),
+            title: false,
+            last: None,
+        };
+        exporter.update(ExportedStatus::from_tabs(
+            &[summary(0, "fix the build", TabStatus::Running, true)],
+            Some("thread-1".to_string()),
+            Path::new("/repo"),
+        ));
+        let written: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&file).expect("status file"))
+                .expect("json");
+        assert_eq!(written["state"], "running");
+        assert_eq!(written["task"], "fix the build");
+        assert_eq!(written["session_id"], "thread-1");
+
+        drop(exporter);
+        assert!(!file.exists());
+    }
+}
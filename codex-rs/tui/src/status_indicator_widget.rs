@@ -42,6 +42,9 @@ pub(crate) struct StatusIndicatorWidget {
     app_event_tx: AppEventSender,
     frame_requester: FrameRequester,
     animations_enabled: bool,
+    /// Screen-reader mode (`tui.screen_reader`): the header line is static and
+    /// never schedules frames, since every redraw would be read out again.
+    screen_reader: bool,
 }
 
 // Format elapsed seconds into a compact human-friendly form used by the status line.
@@ -66,6 +69,7 @@ impl StatusIndicatorWidget {
         app_event_tx: AppEventSender,
         frame_requester: FrameRequester,
         animations_enabled: bool,
+        screen_reader: bool,
     ) -> Self {
         Self {
             header: String::from("Working"),
@@ -78,6 +82,7 @@ impl StatusIndicatorWidget {
             app_event_tx,
             frame_requester,
             animations_enabled,
+            screen_reader,
         }
     }
 
@@ -85,6 +90,11 @@ impl StatusIndicatorWidget {
         self.app_event_tx.send(AppEvent::CodexOp(Op::Interrupt));
     }
 
+    /// Ends the turn like [`Self::interrupt`] but keeps the partial reply.
+    pub(crate) fn stop(&self) {
+        self.app_event_tx.send(AppEvent::CodexOp(Op::Stop));
+    }
+
     /// Update the animated header label (left of the brackets).
     pub(crate) fn update_header(&mut self, header: String) {
         self.header = header;
@@ -157,6 +167,52 @@ impl StatusIndicatorWidget {
         self.elapsed_seconds_at(Instant::now())
     }
 
+    /// Spinner, header and elapsed time; redrawn every frame.
+    fn animated_header_line(&self) -> Line<'static> {
+        let now = Instant::now();
+        let elapsed_duration = self.elapsed_duration_at(now);
+        let pretty_elapsed = fmt_elapsed_compact(elapsed_duration.as_secs());
+
+        let mut spans = Vec::with_capacity(5);
+        spans.push(spinner(Some(self.last_resume_at), self.animations_enabled));
+        spans.push(" ".into());
+        if self.animations_enabled {
+            spans.extend(shimmer_spans(&self.header));
+        } else if !self.header.is_empty() {
+            spans.push(Span::styled(
+                self.header.clone(),
+                crate::theme::current().status_header,
+            ));
+        }
+        spans.push(" ".into());
+        if self.show_interrupt_hint {
+            spans.extend(vec![
+                format!("({pretty_elapsed} • ").dim(),
+                key_hint::plain(KeyCode::Esc).into(),
+                " to interrupt)".dim(),
+            ]);
+        } else {
+            spans.push(format!("({pretty_elapsed})").dim());
+        }
+        Line::from(spans)
+    }
+
+    /// The header alone, which only changes when the header does.
+    fn static_header_line(&self) -> Line<'static> {
+        let mut spans = vec![Span::styled(
+            self.header.clone(),
+            crate::theme::current().status_header,
+        )];
+        if self.show_interrupt_hint {
+            spans.extend(vec![
+                " (".dim(),
+                key_hint::plain(KeyCode::Esc).into(),
+                " to interrupt)".dim(),
+            ]);
+        }
+        Line::from(spans)
+    }
+
     /// Wrap the details text into a fixed width and return the lines, truncating if necessary.
     fn wrapped_details_lines(&self, width: u16) -> Vec<Line<'static>> {
         let Some(details) = self.details.as_deref() else {
@@ -172,7 +228,13 @@ impl StatusIndicatorWidget {
             .subsequent_indent(Line::from(Span::from(" ".repeat(prefix_width)).dim()))
             .break_words(true);
 
-        let mut out = word_wrap_lines(details.lines().map(|line| vec![line.dim()]), opts);
+        let detail_style = crate::theme::current().status_detail;
+        let mut out = word_wrap_lines(
+            details
+                .lines()
+                .map(|line| vec![Span::styled(line, detail_style)]),
+            opts,
+        );
 
         if out.len() > DETAILS_MAX_LINES {
             out.truncate(DETAILS_MAX_LINES);
@@ -182,7 +244,7 @@ impl StatusIndicatorWidget {
                 && let Some(span) = last.spans.last_mut()
             {
                 let trimmed: String = span.content.as_ref().chars().take(max_base_len).collect();
-                *span = format!("{trimmed}…").dim();
+                *span = Span::styled(format!("{trimmed}…"), detail_style);
             }
         }
 
@@ -200,34 +262,16 @@ impl Renderable for StatusIndicatorWidget {
             return;
         }
 
-        // Schedule next animation frame.
-        self.frame_requester
-            .schedule_frame_in(Duration::from_millis(32));
-        let now = Instant::now();
-        let elapsed_duration = self.elapsed_duration_at(now);
-        let pretty_elapsed = fmt_elapsed_compact(elapsed_duration.as_secs());
-
-        let mut spans = Vec::with_capacity(5);
-        spans.push(spinner(Some(self.last_resume_at), self.animations_enabled));
-        spans.push(" ".into());
-        if self.animations_enabled {
-            spans.extend(shimmer_spans(&self.header));
-        } else if !self.header.is_empty() {
-            spans.push(self.header.clone().into());
-        }
-        spans.push(" ".into());
-        if self.show_interrupt_hint {
-            spans.extend(vec![
-                format!("({pretty_elapsed} • ").dim(),
-                key_hint::plain(KeyCode::Esc).into(),
-                " to interrupt)".dim(),
-            ]);
+        let header_line = if self.screen_reader {
+            self.static_header_line()
         } else {
-            spans.push(format!("({pretty_elapsed})").dim());
-        }
+            // Schedule next animation frame.
+            self.frame_requester
+                .schedule_frame_in(Duration::from_millis(32));
+            self.animated_header_line()
+        };
 
-        let mut lines = Vec::new();
-        lines.push(Line::from(spans));
+        let mut lines = vec![header_line];
         if area.height > 1 {
             // If there is enough space, add the details lines below the header.
             let details = self.wrapped_details_lines(area.width);
@@ -270,7 +314,8 @@ mod tests {
     fn renders_with_working_header() {
         let (tx_raw, _rx) = unbounded_channel::<AppEvent>();
         let tx = AppEventSender::new(tx_raw);
-        let w = StatusIndicatorWidget::new(tx, crate::tui::FrameRequester::test_dummy(), true);
+        let w =
+            StatusIndicatorWidget::new(tx, crate::tui::FrameRequester::test_dummy(), true, false);
 
         // Render into a fixed-size test terminal and snapshot the backend.
         let mut terminal = Terminal::new(TestBackend::new(80, 2)).expect("terminal");
@@ -284,7 +329,8 @@ mod tests {
     fn renders_truncated() {
         let (tx_raw, _rx) = unbounded_channel::<AppEvent>();
         let tx = AppEventSender::new(tx_raw);
-        let w = StatusIndicatorWidget::new(tx, crate::tui::FrameRequester::test_dummy(), true);
+        let w =
+            StatusIndicatorWidget::new(tx, crate::tui::FrameRequester::test_dummy(), true, false);
 
         // Render into a fixed-size test terminal and snapshot the backend.
         let mut terminal = Terminal::new(TestBackend::new(20, 2)).expect("terminal");
@@ -298,7 +344,8 @@ mod tests {
     fn renders_wrapped_details_panama_two_lines() {
         let (tx_raw, _rx) = unbounded_channel::<AppEvent>();
         let tx = AppEventSender::new(tx_raw);
-        let mut w = StatusIndicatorWidget::new(tx, crate::tui::FrameRequester::test_dummy(), false);
+        let mut w =
+            StatusIndicatorWidget::new(tx, crate::tui::FrameRequester::test_dummy(), false, false);
         w.update_details(Some("A man a plan a canal panama".to_string()));
         w.set_interrupt_hint_visible(false);
 
@@ -320,7 +367,7 @@ mod tests {
         let (tx_raw, _rx) = unbounded_channel::<AppEvent>();
         let tx = AppEventSender::new(tx_raw);
         let mut widget =
-            StatusIndicatorWidget::new(tx, crate::tui::FrameRequester::test_dummy(), true);
+            StatusIndicatorWidget::new(tx, crate::tui::FrameRequester::test_dummy(), true, false);
 
         let baseline = Instant::now();
         widget.last_resume_at = baseline;
@@ -337,11 +384,33 @@ mod tests {
         assert_eq!(after_resume, before_pause + 3);
     }
 
+    #[test]
+    fn screen_reader_mode_renders_header_without_timer() {
+        let (tx_raw, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let w =
+            StatusIndicatorWidget::new(tx, crate::tui::FrameRequester::test_dummy(), false, true);
+
+        let mut terminal = Terminal::new(TestBackend::new(40, 1)).expect("terminal");
+        terminal
+            .draw(|f| w.render(f.area(), f.buffer_mut()))
+            .expect("draw");
+        let rendered: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(ratatui::buffer::Cell::symbol)
+            .collect();
+        assert_eq!(rendered.trim_end(), "Working (esc to interrupt)");
+    }
+
     #[test]
     fn details_overflow_adds_ellipsis() {
         let (tx_raw, _rx) = unbounded_channel::<AppEvent>();
         let tx = AppEventSender::new(tx_raw);
-        let mut w = StatusIndicatorWidget::new(tx, crate::tui::FrameRequester::test_dummy(), true);
+        let mut w =
+            StatusIndicatorWidget::new(tx, crate::tui::FrameRequester::test_dummy(), true, false);
         w.update_details(Some("abcd abcd abcd abcd".to_string()));
 
         let lines = w.wrapped_details_lines(6);
@@ -68,6 +68,12 @@ impl StreamController {
         (self.emit(step), self.state.is_idle())
     }
 
+    /// Commit every queued line at once, skipping the animation.
+    pub(crate) fn commit_queued(&mut self) -> Option<Box<dyn HistoryCell>> {
+        let lines = self.state.drain_all();
+        self.emit(lines)
+    }
+
     fn emit(&mut self, lines: Vec<Line<'static>>) -> Option<Box<dyn HistoryCell>> {
         if lines.is_empty() {
             return None;
@@ -0,0 +1,335 @@
+//! Color themes for diffs, syntax highlighting, and the status indicator.
+//!
+//! The built-in `dark` theme matches the styles in `styles.md`; `light` and
+//! `high-contrast` avoid `dim`, which is unreadable on many light terminals.
+//! Users can define additional palettes under `[tui.themes.<name>]`, each
+//! extending a built-in theme. The active theme is process-wide so the free
+//! style helpers used throughout rendering can read it, and `/theme` swaps it
+//! at runtime.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::RwLock;
+
+use codex_core::config::types::TuiThemePalette;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+
+use crate::terminal_palette::best_color;
+
+pub(crate) const DEFAULT_THEME: &str = "dark";
+
+/// Built-in theme names, in the order `/theme` lists them.
+pub(crate) const BUILTIN_THEMES: [&str; 3] = ["dark", "light", "high-contrast"];
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Theme {
+    pub(crate) name: String,
+    pub(crate) diff_add: Style,
+    pub(crate) diff_del: Style,
+    pub(crate) diff_gutter: Style,
+    pub(crate) diff_context: Style,
+    pub(crate) syntax_comment: Style,
+    pub(crate) syntax_string: Style,
+    pub(crate) syntax_keyword: Style,
+    pub(crate) syntax_function: Style,
+    pub(crate) syntax_number: Style,
+    pub(crate) syntax_operator: Style,
+    pub(crate) status_header: Style,
+    pub(crate) status_detail: Style,
+}
+
+impl Theme {
+    fn dark() -> Self {
+        let dim = Style::default().add_modifier(Modifier::DIM);
+        Self {
+            name: "dark".to_string(),
+            diff_add: Style::default().fg(Color::Green),
+            diff_del: Style::default().fg(Color::Red),
+            diff_gutter: dim,
+            diff_context: Style::default(),
+            syntax_comment: dim,
+            syntax_string: dim,
+            syntax_keyword: Style::default(),
+            syntax_function: Style::default(),
+            syntax_number: Style::default(),
+            syntax_operator: dim,
+            status_header: Style::default(),
+            status_detail: dim,
+        }
+    }
+
+    fn light() -> Self {
+        let muted = Style::default().fg(Color::DarkGray);
+        Self {
+            name: "light".to_string(),
+            diff_add: Style::default().fg(Color::Green),
+            diff_del: Style::default().fg(Color::Red),
+            diff_gutter: muted,
+            diff_context: Style::default(),
+            syntax_comment: muted.add_modifier(Modifier::ITALIC),
+            syntax_string: Style::default().fg(Color::Cyan),
+            syntax_keyword: Style::default().add_modifier(Modifier::BOLD),
+            syntax_function: Style::default(),
+            syntax_number: Style::default().fg(Color::Magenta),
+            syntax_operator: muted,
+            status_header: Style::default(),
+            status_detail: muted,
+        }
+    }
+
+    fn high_contrast() -> Self {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        Self {
+            name: "high-contrast".to_string(),
+            diff_add: bold.fg(Color::LightGreen),
+            diff_del: bold.fg(Color::LightRed),
+            diff_gutter: Style::default(),
+            diff_context: Style::default(),
+            syntax_comment: Style::default().add_modifier(Modifier::ITALIC),
+            syntax_string: Style::default().fg(Color::LightCyan),
+            syntax_keyword: bold,
+            syntax_function: bold,
+            syntax_number: Style::default().fg(Color::LightMagenta),
+            syntax_operator: Style::default(),
+            status_header: bold,
+            status_detail: Style::default(),
+        }
+    }
+
+    fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    /// Looks up `name` among user palettes first, then built-in themes.
+    pub(crate) fn resolve(
+        name: &str,
+        palettes: &BTreeMap<String, TuiThemePalette>,
+    ) -> Result<Self, String> {
+        let Some(palette) = palettes.get(name) else {
+            return Self::builtin(name).ok_or_else(|| {
+                format!(
+                    "unknown theme `{name}`; expected one of {} or a [tui.themes] entry",
+                    BUILTIN_THEMES.join(", ")
+                )
+            });
+        };
+
+        let base = palette.base.as_deref().unwrap_or(DEFAULT_THEME);
+        let mut theme = Self::builtin(base)
+            .ok_or_else(|| format!("theme `{name}` extends unknown built-in theme `{base}`"))?;
+        theme.name = name.to_string();
+        let overrides = [
+            (&palette.diff_add, &mut theme.diff_add, "diff_add"),
+            (&palette.diff_del, &mut theme.diff_del, "diff_del"),
+            (&palette.diff_gutter, &mut theme.diff_gutter, "diff_gutter"),
+            (
+                &palette.diff_context,
+                &mut theme.diff_context,
+                "diff_context",
+            ),
+            (
+                &palette.syntax_comment,
+                &mut theme.syntax_comment,
+                "syntax_comment",
+            ),
+            (
+                &palette.syntax_string,
+                &mut theme.syntax_string,
+                "syntax_string",
+            ),
+            (
+                &palette.syntax_keyword,
+                &mut theme.syntax_keyword,
+                "syntax_keyword",
+            ),
+            (
+                &palette.syntax_function,
+                &mut theme.syntax_function,
+                "syntax_function",
+            ),
+            (
+                &palette.syntax_number,
+                &mut theme.syntax_number,
+                "syntax_number",
+            ),
+            (
+                &palette.syntax_operator,
+                &mut theme.syntax_operator,
+                "syntax_operator",
+            ),
+            (
+                &palette.status_header,
+                &mut theme.status_header,
+                "status_header",
+            ),
+            (
+                &palette.status_detail,
+                &mut theme.status_detail,
+                "status_detail",
+            ),
+        ];
+        for (spec, style, key) in overrides {
+            if let Some(spec) = spec {
+                *style = parse_style(spec)
+                    .map_err(|err| format!("invalid tui.themes.{name}.{key}: {err}"))?;
+            }
+        }
+        Ok(theme)
+    }
+}
+
+static CURRENT_THEME: LazyLock<RwLock<Arc<Theme>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(Theme::dark())));
+
+/// The active theme.
+pub(crate) fn current() -> Arc<Theme> {
+    match CURRENT_THEME.read() {
+        Ok(theme) => theme.clone(),
+        Err(err) => err.into_inner().clone(),
+    }
+}
+
+pub(crate) fn set_current(theme: Theme) {
+    let theme = Arc::new(theme);
+    match CURRENT_THEME.write() {
+        Ok(mut current) => *current = theme,
+        Err(err) => *err.into_inner() = theme,
+    }
+}
+
+/// Names offered by `/theme`: built-ins followed by user palettes.
+pub(crate) fn available_theme_names(palettes: &BTreeMap<String, TuiThemePalette>) -> Vec<String> {
+    BUILTIN_THEMES
+        .iter()
+        .map(ToString::to_string)
+        .chain(
+            palettes
+                .keys()
+                .filter(|name| !BUILTIN_THEMES.contains(&name.as_str()))
+                .cloned(),
+        )
+        .collect()
+}
+
+/// Parses a palette entry such as `"bold #859900"` or `"dark-gray italic"`.
+fn parse_style(spec: &str) -> Result<Style, String> {
+    let mut style = Style::default();
+    let mut has_color = false;
+    for token in spec.split_whitespace() {
+        let modifier = match token.to_ascii_lowercase().as_str() {
+            "bold" => Some(Modifier::BOLD),
+            "dim" => Some(Modifier::DIM),
+            "italic" => Some(Modifier::ITALIC),
+            "underlined" | "underline" => Some(Modifier::UNDERLINED),
+            _ => None,
+        };
+        if let Some(modifier) = modifier {
+            style = style.add_modifier(modifier);
+            continue;
+        }
+        if has_color {
+            return Err(format!("`{spec}` names more than one color"));
+        }
+        let color = Color::from_str(token).map_err(|_| format!("unknown color `{token}`"))?;
+        style = style.fg(match color {
+            Color::Rgb(r, g, b) => best_color((r, g, b)),
+            other => other,
+        });
+        has_color = true;
+    }
+    Ok(style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_colors_and_modifiers() {
+        assert_eq!(
+            parse_style("bold light-green"),
+            Ok(Style::default()
+                .fg(Color::LightGreen)
+                .add_modifier(Modifier::BOLD))
+        );
+        assert_eq!(
+            parse_style("Dark_Gray italic"),
+            Ok(Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::ITALIC))
+        );
+        assert_eq!(
+            parse_style("red green"),
+            Err("`red green` names more than one color".to_string())
+        );
+        assert_eq!(
+            parse_style("sparkly"),
+            Err("unknown color `sparkly`".to_string())
+        );
+    }
+
+    #[test]
+    fn user_palettes_extend_their_base_theme() {
+        let palettes = BTreeMap::from([(
+            "paper".to_string(),
+            TuiThemePalette {
+                base: Some("light".to_string()),
+                diff_add: Some("cyan".to_string()),
+                ..Default::default()
+            },
+        )]);
+
+        let theme = Theme::resolve("paper", &palettes).expect("theme");
+
+        assert_eq!(theme.name, "paper");
+        assert_eq!(theme.diff_add, Style::default().fg(Color::Cyan));
+        assert_eq!(theme.diff_del, Theme::light().diff_del);
+        assert_eq!(theme.syntax_comment, Theme::light().syntax_comment);
+    }
+
+    #[test]
+    fn reports_unknown_themes_and_bad_entries() {
+        let palettes = BTreeMap::from([(
+            "broken".to_string(),
+            TuiThemePalette {
+                status_header: Some("bold plaid".to_string()),
+                ..Default::default()
+            },
+        )]);
+
+        assert_eq!(
+            Theme::resolve("broken", &palettes),
+            Err("invalid tui.themes.broken.status_header: unknown color `plaid`".to_string())
+        );
+        assert_eq!(
+            Theme::resolve("solarized", &palettes),
+            Err(
+                "unknown theme `solarized`; expected one of dark, light, high-contrast or a [tui.themes] entry"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn lists_builtins_before_user_palettes() {
+        let palettes = BTreeMap::from([
+            ("paper".to_string(), TuiThemePalette::default()),
+            ("light".to_string(), TuiThemePalette::default()),
+        ]);
+
+        assert_eq!(
+            available_theme_names(&palettes),
+            vec!["dark", "light", "high-contrast", "paper"]
+        );
+    }
+}
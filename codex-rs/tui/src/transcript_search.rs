@@ -0,0 +1,105 @@
+//! Text matching for `/` search in the transcript overlay.
+//!
+//! Matching is ASCII case-insensitive so byte offsets in the lowered text
+//! line up with the original, which lets matches be highlighted by splitting
+//! the spans of an already-styled line.
+
+use std::ops::Range;
+
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+
+/// Byte ranges of the non-overlapping occurrences of `query` in `text`.
+pub(crate) fn match_ranges(text: &str, query: &str) -> Vec<Range<usize>> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let text = text.to_ascii_lowercase();
+    let query = query.to_ascii_lowercase();
+    text.match_indices(&query)
+        .map(|(start, matched)| start..start + matched.len())
+        .collect()
+}
+
+pub(crate) fn line_text(line: &Line<'_>) -> String {
+    line.spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
+
+/// Patches `style` onto every occurrence of `query` in `line`, keeping the
+/// styles of the surrounding text.
+pub(crate) fn highlight_line(line: Line<'static>, query: &str, style: Style) -> Line<'static> {
+    let ranges = match_ranges(&line_text(&line), query);
+    if ranges.is_empty() {
+        return line;
+    }
+
+    let mut spans: Vec<Span<'static>> = Vec::with_capacity(line.spans.len() + ranges.len() * 2);
+    let mut offset = 0;
+    for span in line.spans {
+        let content = span.content.as_ref();
+        let span_range = offset..offset + content.len();
+        let mut cursor = span_range.start;
+        for range in &ranges {
+            let start = range.start.max(span_range.start);
+            let end = range.end.min(span_range.end);
+            if start >= end {
+                continue;
+            }
+            if cursor < start {
+                spans.push(Span::styled(
+                    content[cursor - offset..start - offset].to_string(),
+                    span.style,
+                ));
+            }
+            spans.push(Span::styled(
+                content[start - offset..end - offset].to_string(),
+                span.style.patch(style),
+            ));
+            cursor = end;
+        }
+        if cursor < span_range.end {
+            spans.push(Span::styled(
+                content[cursor - offset..].to_string(),
+                span.style,
+            ));
+        }
+        offset = span_range.end;
+    }
+    Line { spans, ..line }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use ratatui::style::Stylize;
+
+    #[test]
+    fn matches_case_insensitively_without_overlap() {
+        assert_eq!(match_ranges("Error: error", "ERROR"), vec![0..5, 7..12]);
+        assert_eq!(match_ranges("aaaa", "aa"), vec![0..2, 2..4]);
+        assert_eq!(match_ranges("anything", ""), Vec::<Range<usize>>::new());
+    }
+
+    #[test]
+    fn highlights_matches_spanning_styled_spans() {
+        let line = Line::from(vec!["cargo ".dim(), "test".bold(), " ok".into()]);
+
+        let highlighted = highlight_line(line, "o te", Style::new().reversed());
+
+        assert_eq!(
+            highlighted.spans,
+            vec![
+                "carg".dim(),
+                "o ".dim().reversed(),
+                "te".bold().reversed(),
+                "st".bold(),
+                " ok".into(),
+            ]
+        );
+    }
+}
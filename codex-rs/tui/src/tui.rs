@@ -38,9 +38,7 @@ use tokio_stream::Stream;
 pub use self::frame_requester::FrameRequester;
 use crate::custom_terminal;
 use crate::custom_terminal::Terminal as CustomTerminal;
-use crate::notifications::DesktopNotificationBackend;
-use crate::notifications::NotificationBackendKind;
-use crate::notifications::detect_backend;
+use crate::notifications::Osc9Backend;
 use crate::tui::event_stream::EventBroker;
 use crate::tui::event_stream::TuiEventStream;
 #[cfg(unix)]
@@ -246,7 +244,7 @@ pub struct Tui {
     // True when terminal/tab is focused; updated internally from crossterm events
     terminal_focused: Arc<AtomicBool>,
     enhanced_keys_supported: bool,
-    notification_backend: Option<DesktopNotificationBackend>,
+    notification_backend: Option<Osc9Backend>,
     // When false, enter_alt_screen() becomes a no-op (for Zellij scrollback support)
     alt_screen_enabled: bool,
 }
@@ -275,7 +273,7 @@ impl Tui {
             alt_screen_active: Arc::new(AtomicBool::new(false)),
             terminal_focused: Arc::new(AtomicBool::new(true)),
             enhanced_keys_supported,
-            notification_backend: Some(detect_backend()),
+            notification_backend: Some(Osc9Backend),
             alt_screen_enabled: true,
         }
     }
@@ -347,8 +345,9 @@ impl Tui {
         output
     }
 
-    /// Emit a desktop notification now if the terminal is unfocused.
-    /// Returns true if a notification was posted.
+    /// Emit an OSC 9 notification now if the terminal is unfocused.
+    /// Returns true if a notification was posted. Other backends run as the
+    /// desktop notification hook (see [`crate::notifications::DesktopNotifier`]).
     pub fn notify(&mut self, message: impl AsRef<str>) -> bool {
         if self.terminal_focused.load(Ordering::Relaxed) {
             return false;
@@ -358,42 +357,24 @@ impl Tui {
             return false;
         };
 
-        let message = message.as_ref().to_string();
-        match backend.notify(&message) {
+        match backend.notify(message.as_ref()) {
             Ok(()) => true,
-            Err(err) => match backend.kind() {
-                NotificationBackendKind::WindowsToast => {
-                    tracing::error!(
-                        error = %err,
-                        "Failed to send Windows toast notification; falling back to OSC 9"
-                    );
-                    self.notification_backend = Some(DesktopNotificationBackend::osc9());
-                    if let Some(backend) = self.notification_backend.as_mut() {
-                        if let Err(osc_err) = backend.notify(&message) {
-                            tracing::warn!(
-                                error = %osc_err,
-                                "Failed to emit OSC 9 notification after toast fallback; \
-                                 disabling future notifications"
-                            );
-                            self.notification_backend = None;
-                            return false;
-                        }
-                        return true;
-                    }
-                    false
-                }
-                NotificationBackendKind::Osc9 => {
-                    tracing::warn!(
-                        error = %err,
-                        "Failed to emit OSC 9 notification; disabling future notifications"
-                    );
-                    self.notification_backend = None;
-                    false
-                }
-            },
+            Err(err) => {
+                tracing::warn!(
+                    error = %err,
+                    "Failed to emit OSC 9 notification; disabling future notifications"
+                );
+                self.notification_backend = None;
+                false
+            }
         }
     }
 
+    /// Whether the terminal is focused, as last reported by the terminal.
+    pub fn terminal_focused(&self) -> Arc<AtomicBool> {
+        self.terminal_focused.clone()
+    }
+
     pub fn event_stream(&self) -> Pin<Box<dyn Stream<Item = TuiEvent> + Send + 'static>> {
         #[cfg(unix)]
         let stream = TuiEventStream::new(
@@ -27,8 +27,9 @@ impl UpdateAction {
     }
 }
 
-#[cfg(not(debug_assertions))]
-pub(crate) fn get_update_action() -> Option<UpdateAction> {
+/// How this installation of Codex updates itself, or `None` for a standalone
+/// binary.
+pub fn get_update_action() -> Option<UpdateAction> {
     let exe = std::env::current_exe().unwrap_or_default();
     let managed_by_npm = std::env::var_os("CODEX_MANAGED_BY_NPM").is_some();
     let managed_by_bun = std::env::var_os("CODEX_MANAGED_BY_BUN").is_some();
@@ -41,7 +42,6 @@ pub(crate) fn get_update_action() -> Option<UpdateAction> {
     )
 }
 
-#[cfg(any(not(debug_assertions), test))]
 fn detect_update_action(
     is_macos: bool,
     current_exe: &std::path::Path,
@@ -20,6 +20,13 @@ use crate::pager_overlay::Overlay;
 use crate::render::highlight::highlight_bash_to_lines;
 use crate::render::renderable::Renderable;
 use crate::resume_picker::ResumeSelection;
+use crate::status_export::ExportedStatus;
+use crate::status_export::StatusExporter;
+use crate::tabs;
+use crate::tabs::ParkedTab;
+use crate::tabs::TabId;
+use crate::tabs::TabStatus;
+use crate::tabs::Tabs;
 use crate::transcript_copy_action::TranscriptCopyAction;
 use crate::transcript_copy_action::TranscriptCopyFeedback;
 use crate::transcript_copy_ui::TranscriptCopyUi;
@@ -338,6 +345,10 @@ pub(crate) struct App {
     pub(crate) transcript_cells: Vec<Arc<dyn HistoryCell>>,
     transcript_view_cache: TranscriptViewCache,
 
+    /// Open conversation tabs. `chat_widget`, `transcript_cells` and the
+    /// transcript view state belong to the active one.
+    pub(crate) tabs: Tabs,
+
     #[allow(dead_code)]
     transcript_scroll: TranscriptScroll,
     transcript_selection: TranscriptSelection,
@@ -367,7 +378,8 @@ pub(crate) struct App {
 
     pub(crate) enhanced_keys_supported: bool,
 
-    /// Controls the animation thread that sends CommitTick events.
+    /// Controls the animation thread that sends CommitTick events; it runs
+    /// while any tab is animating (see [`Tabs::any_commit_animating`]).
     pub(crate) commit_anim_running: Arc<AtomicBool>,
 
     scroll_config: ScrollConfig,
@@ -385,6 +397,9 @@ pub(crate) struct App {
 
     // One-shot suppression of the next world-writable scan after user confirmation.
     skip_world_writable_scan_once: bool,
+
+    /// Publishes tab state for multiplexer status bars (`[tui.status_export]`).
+    status_exporter: Option<StatusExporter>,
 }
 impl App {
     async fn shutdown_current_conversation(&mut self) {
@@ -393,6 +408,180 @@ impl App {
             self.chat_widget.submit_op(Op::Shutdown);
             self.server.remove_thread(&conversation_id).await;
         }
+        // The replacement widget starts idle; don't keep ticking for this one.
+        self.tabs.set_commit_animating(self.tabs.active_id(), false);
+        self.update_commit_animation();
+    }
+
+    /// Records the active tab's status, exporting it only when it changes so
+    /// deltas and other chatter don't rewrite the status file.
+    fn set_active_status(&mut self, status: TabStatus) {
+        if status != self.tabs.active_status() {
+            self.tabs.set_active_status(status);
+            self.export_status();
+        }
+    }
+
+    fn export_status(&mut self) {
+        let Some(exporter) = self.status_exporter.as_mut() else {
+            return;
+        };
+        let session_id = self.chat_widget.conversation_id().map(|id| id.to_string());
+        exporter.update(ExportedStatus::from_tabs(
+            &self.tabs.summaries(&self.transcript_cells),
+            session_id,
+            &self.config.cwd,
+        ));
+    }
+
+    fn open_new_tab(&mut self, tui: &mut tui::Tui) {
+        let init = crate::chatwidget::ChatWidgetInit {
+            config: self.config.clone(),
+            frame_requester: tui.frame_requester(),
+            app_event_tx: self.app_event_tx.for_tab(self.tabs.next_id()),
+            initial_prompt: None,
+            initial_images: Vec::new(),
+            enhanced_keys_supported: self.enhanced_keys_supported,
+            auth_manager: self.auth_manager.clone(),
+            models_manager: self.server.get_models_manager(),
+            feedback: self.feedback.clone(),
+            is_first_run: false,
+            model: self.current_model.clone(),
+        };
+        let chat_widget = ChatWidget::new(init, self.server.clone());
+        let previous = self.install_tab(ParkedTab::new(chat_widget, Vec::new()));
+        self.tabs.open(previous);
+        self.show_active_tab(tui, Vec::new());
+    }
+
+    fn switch_tab(&mut self, tui: &mut tui::Tui, id: TabId) {
+        if id == self.tabs.active_id() {
+            return;
+        }
+        let Some(mut next) = self.tabs.take_parked(id) else {
+            return;
+        };
+        let deferred_events = std::mem::take(&mut next.deferred_events);
+        let previous = self.install_tab(next);
+        self.tabs.activate(id, previous);
+        self.show_active_tab(tui, deferred_events);
+    }
+
+    async fn close_active_tab(&mut self, tui: &mut tui::Tui) {
+        let Some(mut next) = self.tabs.close_active() else {
+            return;
+        };
+        let deferred_events = std::mem::take(&mut next.deferred_events);
+        let closed = self.install_tab(next);
+        self.shutdown_tab(closed).await;
+        self.update_commit_animation();
+        self.show_active_tab(tui, deferred_events);
+    }
+
+    /// Puts `next` on screen, returning the state of the tab it replaces.
+    ///
+    /// The transcript view state belongs to the transcript on screen, so it
+    /// starts over at the bottom of the newly installed one.
+    fn install_tab(&mut self, next: ParkedTab) -> ParkedTab {
+        self.backtrack = BacktrackState::default();
+        self.transcript_view_cache = TranscriptViewCache::new();
+        self.transcript_scroll = TranscriptScroll::default();
+        self.transcript_selection = TranscriptSelection::default();
+        self.transcript_multi_click = TranscriptMultiClick::default();
+        ParkedTab::new(
+            std::mem::replace(&mut self.chat_widget, next.chat_widget),
+            std::mem::replace(&mut self.transcript_cells, next.transcript_cells),
+        )
+    }
+
+    /// Replays the events the newly active tab received while parked.
+    fn show_active_tab(&mut self, tui: &mut tui::Tui, deferred_events: Vec<AppEvent>) {
+        self.export_status();
+        let tx = self.app_event_tx.for_tab(self.tabs.active_id());
+        for event in deferred_events {
+            tx.send(event);
+        }
+        tui.frame_requester().schedule_frame();
+    }
+
+    async fn shutdown_tab(&self, tab: ParkedTab) {
+        if let Some(conversation_id) = tab.chat_widget.conversation_id() {
+            tab.chat_widget.submit_op(Op::Shutdown);
+            self.server.remove_thread(&conversation_id).await;
+        }
+    }
+
+    /// Handles an event from the widget of a tab that is not on screen.
+    async fn handle_background_tab_event(
+        &mut self,
+        tui: &mut tui::Tui,
+        id: TabId,
+        event: AppEvent,
+    ) {
+        // Events from tabs that have since been closed are dropped.
+        let Some(tab) = self.tabs.get_mut(id) else {
+            return;
+        };
+        let Some(parked) = tab.parked.as_mut() else {
+            return;
+        };
+        match event {
+            AppEvent::CodexEvent(event) => {
+                let status = tab.status.after(&event.msg);
+                let changed = status != tab.status;
+                tab.status = status;
+                parked.chat_widget.handle_codex_event(event);
+                if changed {
+                    self.export_status();
+                    // Redraw the tab strip with the new status.
+                    tui.frame_requester().schedule_frame();
+                }
+            }
+            AppEvent::InsertHistoryCell(cell) => parked.transcript_cells.push(cell.into()),
+            AppEvent::CodexOp(op) => parked.chat_widget.submit_op(op),
+            AppEvent::StartCommitAnimation => {
+                tab.commit_animating = true;
+                self.update_commit_animation();
+            }
+            AppEvent::StopCommitAnimation => {
+                tab.commit_animating = false;
+                self.update_commit_animation();
+            }
+            AppEvent::CommitTick => {}
+            AppEvent::ExitRequest => {
+                if let Some(closed) = self.tabs.close_parked(id) {
+                    self.shutdown_tab(closed).await;
+                    self.update_commit_animation();
+                    self.export_status();
+                    tui.frame_requester().schedule_frame();
+                }
+            }
+            // Anything else needs the screen; replay it when the tab is shown.
+            event => parked.deferred_events.push(event),
+        }
+    }
+
+    /// Runs the CommitTick thread while any tab is streaming and stops it
+    /// once none is.
+    fn update_commit_animation(&self) {
+        if !self.tabs.any_commit_animating() {
+            self.commit_anim_running.store(false, Ordering::Release);
+            return;
+        }
+        if self
+            .commit_anim_running
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+        {
+            let tx = self.app_event_tx.clone();
+            let running = self.commit_anim_running.clone();
+            thread::spawn(move || {
+                while running.load(Ordering::Relaxed) {
+                    thread::sleep(Duration::from_millis(50));
+                    tx.send(AppEvent::CommitTick);
+                }
+            });
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -436,12 +625,13 @@ impl App {
         }
 
         let enhanced_keys_supported = tui.enhanced_keys_supported();
+        let tabs = Tabs::default();
         let mut chat_widget = match resume_selection {
             ResumeSelection::StartFresh | ResumeSelection::Exit => {
                 let init = crate::chatwidget::ChatWidgetInit {
                     config: config.clone(),
                     frame_requester: tui.frame_requester(),
-                    app_event_tx: app_event_tx.clone(),
+                    app_event_tx: app_event_tx.for_tab(tabs.active_id()),
                     initial_prompt: initial_prompt.clone(),
                     initial_images: initial_images.clone(),
                     enhanced_keys_supported,
@@ -463,7 +653,7 @@ impl App {
                 let init = crate::chatwidget::ChatWidgetInit {
                     config: config.clone(),
                     frame_requester: tui.frame_requester(),
-                    app_event_tx: app_event_tx.clone(),
+                    app_event_tx: app_event_tx.for_tab(tabs.active_id()),
                     initial_prompt: initial_prompt.clone(),
                     initial_images: initial_images.clone(),
                     enhanced_keys_supported,
@@ -482,6 +672,8 @@ impl App {
         let file_search = FileSearchManager::new(config.cwd.clone(), app_event_tx.clone());
         #[cfg(not(debug_assertions))]
         let upgrade_version = crate::updates::get_upgrade_version(&config);
+        #[cfg(not(debug_assertions))]
+        let upgrade_notes = crate::updates::get_upgrade_notes(&config);
         let scroll_config = ScrollConfig::from_terminal(
             &terminal_info(),
             ScrollConfigOverrides {
@@ -498,6 +690,7 @@ impl App {
         );
 
         let copy_selection_shortcut = crate::transcript_copy_ui::detect_copy_selection_shortcut();
+        let status_exporter = StatusExporter::new(&config.tui_status_export);
 
         let mut app = Self {
             server: thread_manager.clone(),
@@ -511,6 +704,7 @@ impl App {
             enhanced_keys_supported,
             transcript_cells: Vec::new(),
             transcript_view_cache: TranscriptViewCache::new(),
+            tabs,
             transcript_scroll: TranscriptScroll::default(),
             transcript_selection: TranscriptSelection::default(),
             transcript_multi_click: TranscriptMultiClick::default(),
@@ -530,7 +724,9 @@ impl App {
             pending_update_action: None,
             suppress_shutdown_complete: false,
             skip_world_writable_scan_once: false,
+            status_exporter,
         };
+        app.export_status();
 
         // On startup, if Agent mode (workspace-write) or ReadOnly is active, warn about world-writable dirs on Windows.
         #[cfg(target_os = "windows")]
@@ -562,6 +758,7 @@ impl App {
                 tui,
                 AppEvent::InsertHistoryCell(Box::new(UpdateAvailableHistoryCell::new(
                     latest_version,
+                    upgrade_notes,
                     crate::update_action::get_update_action(),
                 ))),
             )
@@ -634,6 +831,9 @@ impl App {
                 }
                 TuiEvent::Draw => {
                     self.chat_widget.maybe_post_pending_notification(tui);
+                    for parked in self.tabs.parked_mut() {
+                        parked.chat_widget.maybe_post_pending_notification(tui);
+                    }
                     if self
                         .chat_widget
                         .handle_paste_burst_tick(tui.frame_requester())
@@ -641,10 +841,12 @@ impl App {
                         return Ok(true);
                     }
                     let cells = self.transcript_cells.clone();
+                    let tab_strip =
+                        (self.tabs.len() > 1).then(|| self.tabs.summaries(&self.transcript_cells));
                     tui.draw(tui.terminal.size()?.height, |frame| {
-                        let chat_height = self.chat_widget.desired_height(frame.area().width);
+                        let chat_height = self.chat_area_height(frame.area().width);
                         let chat_top = self.render_transcript_cells(frame, &cells, chat_height);
-                        let chat_area = Rect {
+                        let mut chat_area = Rect {
                             x: frame.area().x,
                             y: chat_top,
                             width: frame.area().width,
@@ -655,6 +857,19 @@ impl App {
                                     .saturating_sub(chat_top.saturating_sub(frame.area().y)),
                             ),
                         };
+                        if let Some(summaries) = &tab_strip {
+                            let strip_height = chat_area.height.min(1);
+                            tabs::render_tab_strip(
+                                summaries,
+                                Rect {
+                                    height: strip_height,
+                                    ..chat_area
+                                },
+                                frame.buffer,
+                            );
+                            chat_area.y += strip_height;
+                            chat_area.height -= strip_height;
+                        }
                         self.chat_widget.render(chat_area, frame.buffer);
                         let chat_bottom = chat_area.y.saturating_add(chat_area.height);
                         if chat_bottom < frame.area().bottom() {
@@ -701,6 +916,12 @@ impl App {
         Ok(true)
     }
 
+    /// Height of the area below the transcript: the tab strip, while more
+    /// than one tab is open, above the chat widget.
+    fn chat_area_height(&self, width: u16) -> u16 {
+        self.chat_widget.desired_height(width) + u16::from(self.tabs.len() > 1)
+    }
+
     pub(crate) fn render_transcript_cells(
         &mut self,
         frame: &mut Frame,
@@ -869,7 +1090,7 @@ impl App {
             return;
         }
 
-        let chat_height = self.chat_widget.desired_height(width);
+        let chat_height = self.chat_area_height(width);
         if chat_height >= height {
             return;
         }
@@ -1122,7 +1343,7 @@ impl App {
             return None;
         }
 
-        let chat_height = self.chat_widget.desired_height(width);
+        let chat_height = self.chat_area_height(width);
         if chat_height >= height {
             return None;
         }
@@ -1350,6 +1571,14 @@ impl App {
     }
 
     async fn handle_event(&mut self, tui: &mut tui::Tui, event: AppEvent) -> Result<bool> {
+        let event = match event {
+            AppEvent::TabEvent { tab, event } if tab == self.tabs.active_id() => *event,
+            AppEvent::TabEvent { tab, event } => {
+                self.handle_background_tab_event(tui, tab, *event).await;
+                return Ok(true);
+            }
+            event => event,
+        };
         match event {
             AppEvent::NewSession => {
                 let summary = session_summary(
@@ -1360,7 +1589,7 @@ impl App {
                 let init = crate::chatwidget::ChatWidgetInit {
                     config: self.config.clone(),
                     frame_requester: tui.frame_requester(),
-                    app_event_tx: self.app_event_tx.clone(),
+                    app_event_tx: self.app_event_tx.for_tab(self.tabs.active_id()),
                     initial_prompt: None,
                     initial_images: Vec::new(),
                     enhanced_keys_supported: self.enhanced_keys_supported,
@@ -1371,6 +1600,7 @@ impl App {
                     model: self.current_model.clone(),
                 };
                 self.chat_widget = ChatWidget::new(init, self.server.clone());
+                self.set_active_status(TabStatus::Waiting);
                 if let Some(summary) = summary {
                     let mut lines: Vec<Line<'static>> = vec![summary.usage_line.clone().into()];
                     if let Some(command) = summary.resume_command {
@@ -1409,7 +1639,7 @@ impl App {
                                 let init = crate::chatwidget::ChatWidgetInit {
                                     config: self.config.clone(),
                                     frame_requester: tui.frame_requester(),
-                                    app_event_tx: self.app_event_tx.clone(),
+                                    app_event_tx: self.app_event_tx.for_tab(self.tabs.active_id()),
                                     initial_prompt: None,
                                     initial_images: Vec::new(),
                                     enhanced_keys_supported: self.enhanced_keys_supported,
@@ -1424,6 +1654,7 @@ impl App {
                                     resumed.thread,
                                     resumed.session_configured,
                                 );
+                                self.set_active_status(TabStatus::Waiting);
                                 if let Some(summary) = summary {
                                     let mut lines: Vec<Line<'static>> =
                                         vec![summary.usage_line.clone().into()];
@@ -1463,27 +1694,30 @@ impl App {
                 }
             }
             AppEvent::StartCommitAnimation => {
-                if self
-                    .commit_anim_running
-                    .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
-                    .is_ok()
-                {
-                    let tx = self.app_event_tx.clone();
-                    let running = self.commit_anim_running.clone();
-                    thread::spawn(move || {
-                        while running.load(Ordering::Relaxed) {
-                            thread::sleep(Duration::from_millis(50));
-                            tx.send(AppEvent::CommitTick);
-                        }
-                    });
-                }
+                self.tabs.set_commit_animating(self.tabs.active_id(), true);
+                self.update_commit_animation();
             }
             AppEvent::StopCommitAnimation => {
-                self.commit_anim_running.store(false, Ordering::Release);
+                self.tabs.set_commit_animating(self.tabs.active_id(), false);
+                self.update_commit_animation();
             }
             AppEvent::CommitTick => {
-                self.chat_widget.on_commit_tick();
+                if self.tabs.is_commit_animating(self.tabs.active_id()) {
+                    self.chat_widget.on_commit_tick();
+                }
+                // Background tabs keep streaming off screen.
+                for parked in self.tabs.commit_animating_parked_mut() {
+                    parked.chat_widget.on_commit_tick();
+                }
+            }
+            AppEvent::TabEvent { .. } => {}
+            AppEvent::OpenTabsPopup => {
+                let summaries = self.tabs.summaries(&self.transcript_cells);
+                self.chat_widget.open_tabs_popup(summaries);
             }
+            AppEvent::NewTab => self.open_new_tab(tui),
+            AppEvent::SwitchTab(id) => self.switch_tab(tui, id),
+            AppEvent::CloseTab => self.close_active_tab(tui).await,
             AppEvent::CodexEvent(event) => {
                 if self.suppress_shutdown_complete
                     && matches!(event.msg, EventMsg::ShutdownComplete)
@@ -1496,6 +1730,7 @@ impl App {
                     let errors = errors_for_cwd(&cwd, response);
                     emit_skill_load_warnings(&self.app_event_tx, &errors);
                 }
+                self.set_active_status(self.tabs.active_status().after(&event.msg));
                 self.chat_widget.handle_codex_event(event);
             }
             AppEvent::ConversationHistory(ev) => {
@@ -1679,6 +1914,7 @@ impl App {
                                         model: None,
                                         effort: None,
                                         summary: None,
+                                        verbosity: None,
                                     },
                                 ));
                                 self.app_event_tx
@@ -1967,6 +2203,15 @@ impl App {
 
     async fn handle_key_event(&mut self, tui: &mut tui::Tui, key_event: KeyEvent) {
         match key_event {
+            KeyEvent {
+                code: KeyCode::Char(c @ ('.' | ',')),
+                modifiers: crossterm::event::KeyModifiers::ALT,
+                kind: KeyEventKind::Press,
+                ..
+            } if self.tabs.len() > 1 => {
+                let offset = if c == '.' { 1 } else { -1 };
+                self.switch_tab(tui, self.tabs.neighbor_id(offset));
+            }
             KeyEvent {
                 code: KeyCode::Char('t'),
                 modifiers: crossterm::event::KeyModifiers::CONTROL,
@@ -2008,7 +2253,7 @@ impl App {
                     return;
                 }
 
-                let chat_height = self.chat_widget.desired_height(width);
+                let chat_height = self.chat_area_height(width);
                 if self.transcript_copy_action.copy_and_handle(
                     tui,
                     chat_height,
@@ -2027,7 +2272,7 @@ impl App {
                 let width = size.width;
                 let height = size.height;
                 if width > 0 && height > 0 {
-                    let chat_height = self.chat_widget.desired_height(width);
+                    let chat_height = self.chat_area_height(width);
                     if chat_height < height {
                         let transcript_height = height.saturating_sub(chat_height);
                         if transcript_height > 0 {
@@ -2052,7 +2297,7 @@ impl App {
                 let width = size.width;
                 let height = size.height;
                 if width > 0 && height > 0 {
-                    let chat_height = self.chat_widget.desired_height(width);
+                    let chat_height = self.chat_area_height(width);
                     if chat_height < height {
                         let transcript_height = height.saturating_sub(chat_height);
                         if transcript_height > 0 {
@@ -2159,6 +2404,7 @@ mod tests {
     use crate::history_cell::HistoryCell;
     use crate::history_cell::UserHistoryCell;
     use crate::history_cell::new_session_info;
+    use crate::tabs::TabSummary;
     use crate::transcript_copy_ui::CopySelectionShortcut;
     use crate::tui::scrolling::TranscriptLineMeta;
     use codex_core::AuthManager;
@@ -2202,6 +2448,7 @@ mod tests {
             file_search,
             transcript_cells: Vec::new(),
             transcript_view_cache: TranscriptViewCache::new(),
+            tabs: Tabs::default(),
             transcript_scroll: TranscriptScroll::default(),
             transcript_selection: TranscriptSelection::default(),
             transcript_multi_click: TranscriptMultiClick::default(),
@@ -2224,6 +2471,7 @@ mod tests {
             pending_update_action: None,
             suppress_shutdown_complete: false,
             skip_world_writable_scan_once: false,
+            status_exporter: None,
         }
     }
 
@@ -2255,6 +2503,7 @@ mod tests {
                 file_search,
                 transcript_cells: Vec::new(),
                 transcript_view_cache: TranscriptViewCache::new(),
+                tabs: Tabs::default(),
                 transcript_scroll: TranscriptScroll::default(),
                 transcript_selection: TranscriptSelection::default(),
                 transcript_multi_click: TranscriptMultiClick::default(),
@@ -2277,6 +2526,7 @@ mod tests {
                 pending_update_action: None,
                 suppress_shutdown_complete: false,
                 skip_world_writable_scan_once: false,
+                status_exporter: None,
             },
             rx,
             op_rx,
@@ -2305,6 +2555,72 @@ mod tests {
         s
     }
 
+    #[tokio::test]
+    async fn new_tab_parks_previous_conversation() {
+        let mut app = make_test_app().await;
+        let first_conversation = app.chat_widget.conversation_id();
+        let (second, _tx, _rx, _op_rx) = make_chatwidget_manual_with_sender().await;
+        let first = app.install_tab(ParkedTab::new(second, Vec::new()));
+        let second_id = app.tabs.open(first);
+        app.transcript_cells.push(Arc::new(UserHistoryCell {
+            message: "fix the   flaky\ntest".to_string(),
+        }) as Arc<dyn HistoryCell>);
+        if let Some(tab) = app.tabs.get_mut(0) {
+            tab.status = TabStatus::NeedsApproval;
+        }
+
+        assert_eq!(
+            app.tabs.summaries(&app.transcript_cells),
+            vec![
+                TabSummary {
+                    id: 0,
+                    number: 1,
+                    title: "new conversation".to_string(),
+                    status: TabStatus::NeedsApproval,
+                    is_active: false,
+                },
+                TabSummary {
+                    id: second_id,
+                    number: 2,
+                    title: "fix the flaky test".to_string(),
+                    status: TabStatus::Waiting,
+                    is_active: true,
+                },
+            ]
+        );
+        assert_eq!(app.tabs.neighbor_id(1), 0);
+        assert_eq!(app.tabs.neighbor_id(-1), 0);
+
+        let first = app.tabs.close_active().expect("first tab is parked");
+        assert_eq!(first.chat_widget.conversation_id(), first_conversation);
+        assert_eq!(app.tabs.len(), 1);
+        assert_eq!(app.tabs.active_id(), 0);
+        assert!(app.tabs.close_active().is_none());
+    }
+
+    #[tokio::test]
+    async fn commit_animation_runs_while_any_tab_streams() {
+        let mut app = make_test_app().await;
+        let (second, _tx, _rx, _op_rx) = make_chatwidget_manual_with_sender().await;
+        let first = app.install_tab(ParkedTab::new(second, Vec::new()));
+        let second_id = app.tabs.open(first);
+
+        app.tabs.set_commit_animating(0, true);
+        app.tabs.set_commit_animating(second_id, true);
+        app.update_commit_animation();
+        assert!(app.commit_anim_running.load(Ordering::Acquire));
+
+        // The active tab finishing its stream leaves the parked one ticking.
+        app.tabs.set_commit_animating(second_id, false);
+        app.update_commit_animation();
+        assert!(app.commit_anim_running.load(Ordering::Acquire));
+        assert!(!app.tabs.is_commit_animating(second_id));
+
+        app.tabs.set_commit_animating(0, false);
+        app.update_commit_animation();
+        assert!(!app.commit_anim_running.load(Ordering::Acquire));
+    }
+
     #[tokio::test]
     async fn model_migration_prompt_only_shows_for_deprecated_models() {
         let seen = BTreeMap::new();
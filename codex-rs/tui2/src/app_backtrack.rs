@@ -370,7 +370,7 @@ impl App {
             config: cfg,
             model: self.current_model.clone(),
             frame_requester: tui.frame_requester(),
-            app_event_tx: self.app_event_tx.clone(),
+            app_event_tx: self.app_event_tx.for_tab(self.tabs.active_id()),
             initial_prompt: None,
             initial_images: Vec::new(),
             enhanced_keys_supported: self.enhanced_keys_supported,
@@ -9,6 +9,7 @@ use codex_protocol::openai_models::ModelPreset;
 
 use crate::bottom_pane::ApprovalRequest;
 use crate::history_cell::HistoryCell;
+use crate::tabs::TabId;
 
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
@@ -32,6 +33,24 @@ pub(crate) enum WindowsSandboxFallbackReason {
 pub(crate) enum AppEvent {
     CodexEvent(Event),
 
+    /// An event sent by the widget of conversation tab `tab`.
+    TabEvent {
+        tab: TabId,
+        event: Box<AppEvent>,
+    },
+
+    /// Open the `/tabs` popup listing the open conversation tabs.
+    OpenTabsPopup,
+
+    /// Start a new conversation in its own tab.
+    NewTab,
+
+    /// Bring conversation tab `TabId` to the screen.
+    SwitchTab(TabId),
+
+    /// Shut down the active conversation and close its tab.
+    CloseTab,
+
     /// Start a new session.
     NewSession,
 
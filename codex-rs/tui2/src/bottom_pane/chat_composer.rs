@@ -64,6 +64,7 @@ use codex_file_search::FileMatch;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::path::Path;
 use std::path::PathBuf;
 use std::time::Duration;
 use std::time::Instant;
@@ -79,6 +80,17 @@ fn windows_degraded_sandbox_active() -> bool {
 /// placeholder in the UI.
 const LARGE_PASTE_CHAR_THRESHOLD: usize = 1000;
 
+/// Most images that can be attached to a single message.
+const MAX_ATTACHED_IMAGES: usize = 10;
+
+/// Largest image file that can be attached. Images are downscaled before they
+/// are sent, but decoding anything bigger would stall the UI.
+const MAX_IMAGE_ATTACHMENT_BYTES: u64 = 20 * 1024 * 1024;
+
+fn format_megabytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
 /// Result returned when the user interacts with the text area.
 #[derive(Debug, PartialEq)]
 pub enum InputResult {
@@ -293,8 +305,7 @@ impl ChatComposer {
                 tracing::debug!("image dimensions={}x{}", width, height);
                 let format = pasted_image_format(&path_buf);
                 tracing::debug!("attached image format={}", format.label());
-                self.attach_image(path_buf);
-                true
+                self.try_attach_image(path_buf)
             }
             Err(err) => {
                 tracing::trace!("ERR: {err}");
@@ -355,6 +366,43 @@ impl ChatComposer {
             .push(AttachedImage { placeholder, path });
     }
 
+    /// Attaches the image at `path` unless that would break the attachment
+    /// limits, in which case the reason is shown and `false` returned.
+    pub fn try_attach_image(&mut self, path: PathBuf) -> bool {
+        match self.check_image_attachment(&path) {
+            Ok(()) => {
+                self.attach_image(path);
+                true
+            }
+            Err(message) => {
+                self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                    history_cell::new_error_event(message),
+                )));
+                false
+            }
+        }
+    }
+
+    fn check_image_attachment(&self, path: &Path) -> Result<(), String> {
+        if self.attached_images.len() >= MAX_ATTACHED_IMAGES {
+            return Err(format!(
+                "Cannot attach more than {MAX_ATTACHED_IMAGES} images to one message."
+            ));
+        }
+        let size = std::fs::metadata(path)
+            .map_err(|err| format!("Cannot attach image {}: {err}", path.display()))?
+            .len();
+        if size > MAX_IMAGE_ATTACHMENT_BYTES {
+            return Err(format!(
+                "Image {} is too large to attach ({}; the limit is {}).",
+                path.display(),
+                format_megabytes(size),
+                format_megabytes(MAX_IMAGE_ATTACHMENT_BYTES),
+            ));
+        }
+        Ok(())
+    }
+
     pub fn take_recent_submission_images(&mut self) -> Vec<PathBuf> {
         let images = std::mem::take(&mut self.attached_images);
         images.into_iter().map(|img| img.path).collect()
@@ -763,9 +811,12 @@ impl ChatComposer {
                             self.textarea.replace_range(start_idx..end_idx, "");
                             self.textarea.set_cursor(start_idx);
 
-                            self.attach_image(path_buf);
-                            // Add a trailing space to keep typing fluid.
-                            self.textarea.insert_str(" ");
+                            if self.try_attach_image(path_buf) {
+                                // Add a trailing space to keep typing fluid.
+                                self.textarea.insert_str(" ");
+                            } else {
+                                self.insert_selected_path(&sel_path);
+                            }
                         }
                         Err(err) => {
                             tracing::trace!("image dimensions lookup failed: {err}");
@@ -1255,7 +1306,10 @@ impl ChatComposer {
                     && let Some((_n, cmd)) = built_in_slash_commands()
                         .into_iter()
                         .find(|(command_name, _)| *command_name == name)
-                    && cmd == SlashCommand::Review
+                    && matches!(
+                        cmd,
+                        SlashCommand::Review | SlashCommand::Diff | SlashCommand::Model
+                    )
                 {
                     return (InputResult::CommandWithArgs(cmd, rest.to_string()), true);
                 }
@@ -3293,6 +3347,66 @@ mod tests {
     }
 
     // --- Image attachment tests ---
+    #[test]
+    fn pasting_oversized_image_path_inserts_text() {
+        let tmp = tempdir().expect("create TempDir");
+        let tmp_path: PathBuf = tmp.path().join("huge.png");
+        let img: ImageBuffer<Rgba<u8>, Vec<u8>> =
+            ImageBuffer::from_fn(3, 2, |_x, _y| Rgba([1, 2, 3, 255]));
+        img.save(&tmp_path).expect("failed to write temp png");
+        // Pad the file past the limit; only the PNG header is read for its
+        // dimensions.
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(&tmp_path)
+            .and_then(|file| file.set_len(MAX_IMAGE_ATTACHMENT_BYTES + 1))
+            .expect("grow file");
+
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+
+        let pasted = tmp_path.to_string_lossy().to_string();
+        composer.handle_paste(pasted.clone());
+
+        assert_eq!(composer.textarea.text(), pasted);
+        assert!(composer.take_recent_submission_images().is_empty());
+        assert!(matches!(rx.try_recv(), Ok(AppEvent::InsertHistoryCell(_))));
+    }
+
+    #[test]
+    fn try_attach_image_enforces_image_count() {
+        let tmp = tempdir().expect("create TempDir");
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+
+        for idx in 0..=MAX_ATTACHED_IMAGES {
+            let path = tmp.path().join(format!("image-{idx}.png"));
+            std::fs::write(&path, b"png").expect("write image");
+            assert_eq!(
+                composer.try_attach_image(path),
+                idx < MAX_ATTACHED_IMAGES,
+                "image {idx}"
+            );
+        }
+
+        assert_eq!(composer.attached_images.len(), MAX_ATTACHED_IMAGES);
+        assert!(matches!(rx.try_recv(), Ok(AppEvent::InsertHistoryCell(_))));
+    }
+
     #[test]
     fn attach_image_and_submit_includes_image_paths() {
         let (tx, _rx) = unbounded_channel::<AppEvent>();
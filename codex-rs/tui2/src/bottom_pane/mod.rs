@@ -74,6 +74,7 @@ pub(crate) struct BottomPane {
     ctrl_c_quit_hint: bool,
     esc_backtrack_hint: bool,
     animations_enabled: bool,
+    screen_reader: bool,
 
     /// Inline status indicator shown above the composer while a task is running.
     status: Option<StatusIndicatorWidget>,
@@ -91,6 +92,7 @@ pub(crate) struct BottomPaneParams {
     pub(crate) placeholder_text: String,
     pub(crate) disable_paste_burst: bool,
     pub(crate) animations_enabled: bool,
+    pub(crate) screen_reader: bool,
     pub(crate) skills: Option<Vec<SkillMetadata>>,
 }
 
@@ -104,6 +106,7 @@ impl BottomPane {
             placeholder_text,
             disable_paste_burst,
             animations_enabled,
+            screen_reader,
             skills,
         } = params;
         let mut composer = ChatComposer::new(
@@ -127,6 +130,7 @@ impl BottomPane {
             queued_user_messages: QueuedUserMessages::new(),
             esc_backtrack_hint: false,
             animations_enabled,
+            screen_reader,
             context_window_percent: None,
             context_window_used_tokens: None,
         }
@@ -342,6 +346,8 @@ impl BottomPane {
                         self.app_event_tx.clone(),
                         self.frame_requester.clone(),
                         self.animations_enabled,
+                        self.screen_reader,
+                        self.screen_reader,
                     ));
                 }
                 if let Some(status) = self.status.as_mut() {
@@ -536,6 +542,14 @@ impl BottomPane {
         }
     }
 
+    /// Like [`BottomPane::attach_image`], but enforces the attachment limits.
+    pub(crate) fn try_attach_image(&mut self, path: PathBuf) {
+        if self.view_stack.is_empty() {
+            self.composer.try_attach_image(path);
+            self.request_redraw();
+        }
+    }
+
     pub(crate) fn take_recent_submission_images(&mut self) -> Vec<PathBuf> {
         self.composer.take_recent_submission_images()
     }
@@ -621,6 +635,7 @@ mod tests {
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
             animations_enabled: true,
+            screen_reader: false,
             skills: Some(Vec::new()),
         });
         pane.push_approval_request(exec_request(), &features);
@@ -644,6 +659,7 @@ mod tests {
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
             animations_enabled: true,
+            screen_reader: false,
             skills: Some(Vec::new()),
         });
 
@@ -678,6 +694,7 @@ mod tests {
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
             animations_enabled: true,
+            screen_reader: false,
             skills: Some(Vec::new()),
         });
 
@@ -745,6 +762,7 @@ mod tests {
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
             animations_enabled: true,
+            screen_reader: false,
             skills: Some(Vec::new()),
         });
 
@@ -772,6 +790,7 @@ mod tests {
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
             animations_enabled: true,
+            screen_reader: false,
             skills: Some(Vec::new()),
         });
 
@@ -803,6 +822,7 @@ mod tests {
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
             animations_enabled: true,
+            screen_reader: false,
             skills: Some(Vec::new()),
         });
 
@@ -831,6 +851,7 @@ mod tests {
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
             animations_enabled: true,
+            screen_reader: false,
             skills: Some(Vec::new()),
         });
 
@@ -1,15 +1,20 @@
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
+use std::time::Instant;
 
 use codex_app_server_protocol::AuthMode;
 use codex_backend_client::Client as BackendClient;
 use codex_core::config::Config;
 use codex_core::config::ConstraintResult;
 use codex_core::config::types::Notifications;
+use codex_core::cost::SessionStats;
+use codex_core::cost::TurnStats;
+use codex_core::cost::pricing_for;
 use codex_core::features::Feature;
 use codex_core::git_info::current_branch_name;
 use codex_core::git_info::local_git_branches;
@@ -23,6 +28,9 @@ use codex_core::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::ConfigOverrideAppliedEvent;
+use codex_core::protocol::ContentSource;
+use codex_core::protocol::ContextSourcesEvent;
 use codex_core::protocol::CreditsSnapshot;
 use codex_core::protocol::DeprecationNoticeEvent;
 use codex_core::protocol::ErrorEvent;
@@ -33,14 +41,19 @@ use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::ExecCommandSource;
 use codex_core::protocol::ExitedReviewModeEvent;
+use codex_core::protocol::HookSource;
 use codex_core::protocol::ListCustomPromptsResponseEvent;
+use codex_core::protocol::ListHooksResponseEvent;
 use codex_core::protocol::ListSkillsResponseEvent;
 use codex_core::protocol::McpListToolsResponseEvent;
+use codex_core::protocol::McpServerDownEvent;
+use codex_core::protocol::McpServerRestartedEvent;
 use codex_core::protocol::McpStartupCompleteEvent;
 use codex_core::protocol::McpStartupStatus;
 use codex_core::protocol::McpStartupUpdateEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
+use codex_core::protocol::ModelSettingsChangedEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::RateLimitSnapshot;
@@ -54,6 +67,7 @@ use codex_core::protocol::TokenUsageInfo;
 use codex_core::protocol::TurnAbortReason;
 use codex_core::protocol::TurnCompleteEvent;
 use codex_core::protocol::TurnDiffEvent;
+use codex_core::protocol::TurnTiming;
 use codex_core::protocol::UndoCompletedEvent;
 use codex_core::protocol::UndoStartedEvent;
 use codex_core::protocol::UserMessageEvent;
@@ -65,6 +79,7 @@ use codex_core::skills::model::SkillMetadata;
 use codex_protocol::ThreadId;
 use codex_protocol::account::PlanType;
 use codex_protocol::approvals::ElicitationRequestEvent;
+use codex_protocol::config_types::Verbosity;
 use codex_protocol::parse_command::ParsedCommand;
 use codex_protocol::user_input::UserInput;
 use crossterm::event::KeyCode;
@@ -98,12 +113,15 @@ use crate::bottom_pane::SelectionItem;
 use crate::bottom_pane::SelectionViewParams;
 use crate::bottom_pane::custom_prompt_view::CustomPromptView;
 use crate::bottom_pane::popup_consts::standard_popup_hint_line;
-use crate::clipboard_paste::paste_image_to_temp_png;
+use crate::clipboard_paste::PasteImageError;
+use crate::clipboard_paste::SessionAttachments;
+use crate::clipboard_paste::paste_image_to_png_file;
 use crate::diff_render::display_path_for;
 use crate::exec_cell::CommandOutput;
 use crate::exec_cell::ExecCell;
 use crate::exec_cell::new_active_exec_command;
 use crate::get_git_diff::get_git_diff;
+use crate::get_git_diff::get_workspace_diff;
 use crate::history_cell;
 use crate::history_cell::AgentMessageCell;
 use crate::history_cell::HistoryCell;
@@ -118,6 +136,7 @@ use crate::render::renderable::RenderableExt;
 use crate::render::renderable::RenderableItem;
 use crate::slash_command::SlashCommand;
 use crate::status::RateLimitSnapshotDisplay;
+use crate::tabs::TabSummary;
 use crate::text_formatting::truncate_text;
 use crate::tui::FrameRequester;
 mod interrupts;
@@ -170,6 +189,8 @@ impl UnifiedExecWaitState {
 const RATE_LIMIT_WARNING_THRESHOLDS: [f64; 3] = [75.0, 90.0, 95.0];
 const NUDGE_MODEL_SLUG: &str = "gpt-5.1-codex-mini";
 const RATE_LIMIT_SWITCH_PROMPT_THRESHOLD: f64 = 90.0;
+/// Number of recent hook firings listed at the top of the `/hooks` popup.
+const HOOKS_POPUP_RECENT_FIRINGS: usize = 5;
 
 #[derive(Default)]
 struct RateLimitWarningState {
@@ -324,6 +345,18 @@ pub(crate) struct ChatWidget {
     suppress_session_configured_redraw: bool,
     // User messages queued while a turn is in progress
     queued_user_messages: VecDeque<UserMessage>,
+    // Token totals when the running turn started, to tell what it used.
+    turn_start_usage: TokenUsage,
+    // When the running turn started; aborted turns report no timing of their own.
+    turn_started_at: Option<Instant>,
+    // Totals over the finished turns, shown by `/stats`.
+    session_stats: SessionStats,
+    // Everything the model was given context from, shown by `/sources`.
+    context_sources: BTreeSet<ContentSource>,
+    // Diff of the most recent turn that edited files, shown by `/diff last`.
+    last_turn_diff: Option<String>,
+    // Images pasted from the clipboard during this session.
+    attachments: SessionAttachments,
     // Pending notification to show when unfocused on next Draw
     pending_notification: Option<Notification>,
     // Simple review mode flag; used to adjust layout and banners.
@@ -535,17 +568,29 @@ impl ChatWidget {
         self.set_status_header(String::from("Working"));
         self.full_reasoning_buffer.clear();
         self.reasoning_buffer.clear();
+        self.turn_start_usage = self
+            .token_info
+            .as_ref()
+            .map(|info| info.total_token_usage.clone())
+            .unwrap_or_default();
+        self.turn_started_at = Some(Instant::now());
+        self.announce("Codex is working.".to_string());
         self.request_redraw();
     }
 
-    fn on_task_complete(&mut self, last_agent_message: Option<String>) {
+    fn on_task_complete(&mut self, last_agent_message: Option<String>, timing: Option<TurnTiming>) {
         // If a stream is currently active, finalize it.
         self.flush_answer_stream_with_separator();
+        if let Some(timing) = timing {
+            self.add_turn_stats(timing);
+        }
+        self.turn_started_at = None;
         // Mark task stopped and request redraw now that all content is in history.
         self.bottom_pane.set_task_running(false);
         self.running_commands.clear();
         self.suppressed_exec_calls.clear();
         self.last_unified_wait = None;
+        self.announce("Codex is done and waiting for you.".to_string());
         self.request_redraw();
 
         // If there is a queued user message, send exactly one now to begin the next turn.
@@ -764,6 +809,62 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    fn on_mcp_server_down(&mut self, ev: McpServerDownEvent) {
+        let McpServerDownEvent {
+            server,
+            reason,
+            restarting,
+        } = ev;
+        let action = if restarting {
+            "restarting it"
+        } else {
+            "gave up restarting it"
+        };
+        self.on_warning(format!(
+            "MCP server `{server}` is down ({reason}); {action}"
+        ));
+    }
+
+    fn on_mcp_server_restarted(&mut self, ev: McpServerRestartedEvent) {
+        self.add_info_message(format!("MCP server `{}` restarted", ev.server), None);
+    }
+
+    /// Keeps the widget in sync when the session settings are changed from
+    /// outside the widget, e.g. by an embedder holding a `ConfigHandle`.
+    fn on_config_override_applied(&mut self, ev: ConfigOverrideAppliedEvent) {
+        if let Some(policy) = ev.approval_policy {
+            self.set_approval_policy(policy);
+        }
+        if let Some(policy) = ev.sandbox_policy
+            && let Err(err) = self.set_sandbox_policy(policy)
+        {
+            tracing::warn!(%err, "failed to set sandbox_policy on chat config");
+        }
+        self.request_redraw();
+    }
+
+    fn on_model_settings_changed(&mut self, ev: ModelSettingsChangedEvent) {
+        // Route model and effort through the app so its copy stays in sync.
+        self.app_event_tx
+            .send(AppEvent::UpdateModel(ev.model.clone()));
+        self.app_event_tx
+            .send(AppEvent::UpdateReasoningEffort(ev.effort));
+        self.config.model_verbosity = ev.verbosity;
+        let effort = ev
+            .effort
+            .map_or_else(|| "default".to_string(), |effort| effort.to_string());
+        let verbosity = ev
+            .verbosity
+            .map_or_else(|| "default".to_string(), |verbosity| verbosity.to_string());
+        self.add_info_message(
+            format!(
+                "Model settings: {}, effort {effort}, verbosity {verbosity}",
+                ev.model
+            ),
+            None,
+        );
+    }
+
     /// Handle a turn aborted due to user interrupt (Esc).
     /// When there are queued user messages, restore them into the composer
     /// separated by newlines rather than auto‑submitting the next one.
@@ -771,7 +872,13 @@ impl ChatWidget {
         // Finalize, log a gentle prompt, and clear running state.
         self.finalize_turn();
 
-        if reason != TurnAbortReason::ReviewEnded {
+        if reason == TurnAbortReason::Stopped {
+            self.add_info_message(
+                "Stopped - the partial reply is kept. Add guidance and send to continue."
+                    .to_string(),
+                None,
+            );
+        } else if reason != TurnAbortReason::ReviewEnded {
             self.add_to_history(history_cell::new_error_event(
                 "Conversation interrupted - tell the model what to do differently. Something went wrong? Hit `/feedback` to report the issue.".to_owned(),
             ));
@@ -865,6 +972,10 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    fn on_context_sources(&mut self, event: ContextSourcesEvent) {
+        self.context_sources.extend(event.sources);
+    }
+
     fn on_patch_apply_end(&mut self, event: codex_core::protocol::PatchApplyEndEvent) {
         let ev2 = event.clone();
         self.defer_or_handle(
@@ -1034,7 +1145,16 @@ impl ChatWidget {
         if let Some(controller) = self.stream_controller.as_mut()
             && controller.push(&delta)
         {
-            self.app_event_tx.send(AppEvent::StartCommitAnimation);
+            if self.config.tui_screen_reader {
+                // Each commit tick redraws the viewport; a screen reader
+                // wants completed lines written once, as they arrive.
+                if let Some(cell) = controller.commit_queued() {
+                    self.bottom_pane.hide_status_indicator();
+                    self.add_boxed_history(cell);
+                }
+            } else {
+                self.app_event_tx.send(AppEvent::StartCommitAnimation);
+            }
         }
         if needs_redraw {
             self.request_redraw();
@@ -1110,6 +1230,7 @@ impl ChatWidget {
         self.flush_answer_stream_with_separator();
         let command = shlex::try_join(ev.command.iter().map(String::as_str))
             .unwrap_or_else(|_| ev.command.join(" "));
+        self.announce(format!("Approval needed to run: {command}"));
         self.notify(Notification::ExecApprovalRequested { command });
 
         let request = ApprovalRequest::Exec {
@@ -1129,6 +1250,10 @@ impl ChatWidget {
         ev: ApplyPatchApprovalRequestEvent,
     ) {
         self.flush_answer_stream_with_separator();
+        self.announce(match ev.changes.len() {
+            1 => "Approval needed to edit 1 file.".to_string(),
+            n => format!("Approval needed to edit {n} files."),
+        });
 
         let request = ApprovalRequest::ApplyPatch {
             id,
@@ -1148,6 +1273,7 @@ impl ChatWidget {
     pub(crate) fn handle_elicitation_request_now(&mut self, ev: ElicitationRequestEvent) {
         self.flush_answer_stream_with_separator();
 
+        self.announce(format!("{} is asking for your input.", ev.server_name));
         self.notify(Notification::ElicitationRequested {
             server_name: ev.server_name.clone(),
         });
@@ -1300,9 +1426,11 @@ impl ChatWidget {
                 placeholder_text: placeholder,
                 disable_paste_burst: config.disable_paste_burst,
                 animations_enabled: config.animations,
+                screen_reader: config.tui_screen_reader,
                 skills: None,
             }),
             active_cell: None,
+            attachments: SessionAttachments::new(&config.codex_home),
             config,
             model: model.clone(),
             auth_manager,
@@ -1333,6 +1461,11 @@ impl ChatWidget {
             queued_user_messages: VecDeque::new(),
             show_welcome_banner: is_first_run,
             suppress_session_configured_redraw: false,
+            turn_start_usage: TokenUsage::default(),
+            turn_started_at: None,
+            session_stats: SessionStats::default(),
+            context_sources: BTreeSet::new(),
+            last_turn_diff: None,
             pending_notification: None,
             is_review_mode: false,
             pre_review_token_info: None,
@@ -1384,9 +1517,11 @@ impl ChatWidget {
                 placeholder_text: placeholder,
                 disable_paste_burst: config.disable_paste_burst,
                 animations_enabled: config.animations,
+                screen_reader: config.tui_screen_reader,
                 skills: None,
             }),
             active_cell: None,
+            attachments: SessionAttachments::new(&config.codex_home),
             config,
             model: model.clone(),
             auth_manager,
@@ -1417,6 +1552,11 @@ impl ChatWidget {
             queued_user_messages: VecDeque::new(),
             show_welcome_banner: false,
             suppress_session_configured_redraw: true,
+            turn_start_usage: TokenUsage::default(),
+            turn_started_at: None,
+            session_stats: SessionStats::default(),
+            context_sources: BTreeSet::new(),
+            last_turn_diff: None,
             pending_notification: None,
             is_review_mode: false,
             pre_review_token_info: None,
@@ -1450,7 +1590,12 @@ impl ChatWidget {
             } if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
                 && c.eq_ignore_ascii_case(&'v') =>
             {
-                match paste_image_to_temp_png() {
+                let pasted = self
+                    .attachments
+                    .dir()
+                    .map_err(|err| PasteImageError::IoError(err.to_string()))
+                    .and_then(paste_image_to_png_file);
+                match pasted {
                     Ok((path, info)) => {
                         tracing::debug!(
                             "pasted image size={}x{} format={}",
@@ -1513,7 +1658,7 @@ impl ChatWidget {
 
     pub(crate) fn attach_image(&mut self, path: PathBuf) {
         tracing::info!("attach_image path={path:?}");
-        self.bottom_pane.attach_image(path);
+        self.bottom_pane.try_attach_image(path);
         self.request_redraw();
     }
 
@@ -1529,6 +1674,13 @@ impl ChatWidget {
         }
         match cmd {
             SlashCommand::Feedback => {
+                if self.config.offline {
+                    self.add_error_message(
+                        "Sending feedback needs the network and is unavailable while `offline = true`."
+                            .to_string(),
+                    );
+                    return;
+                }
                 if !self.config.feedback_enabled {
                     let params = crate::bottom_pane::feedback_disabled_params();
                     self.bottom_pane.show_selection_view(params);
@@ -1629,15 +1781,29 @@ impl ChatWidget {
             SlashCommand::Diff => {
                 self.add_diff_in_progress();
                 let tx = self.app_event_tx.clone();
+                // Outside a git repository, diff against the session's first
+                // workspace snapshot instead.
+                let workspace = self.conversation_id.map(|thread_id| {
+                    (
+                        self.config.codex_home.clone(),
+                        thread_id.to_string(),
+                        self.config.cwd.clone(),
+                    )
+                });
                 tokio::spawn(async move {
-                    let text = match get_git_diff().await {
-                        Ok((is_git_repo, diff_text)) => {
-                            if is_git_repo {
-                                diff_text
-                            } else {
-                                "`/diff` — _not inside a git repository_".to_string()
+                    let diff = match get_git_diff().await {
+                        Ok((true, diff_text)) => Ok(Some(diff_text)),
+                        Ok((false, _)) => match workspace {
+                            Some((codex_home, thread_id, cwd)) => {
+                                get_workspace_diff(codex_home, thread_id, cwd).await
                             }
-                        }
+                            None => Ok(None),
+                        },
+                        Err(e) => Err(e),
+                    };
+                    let text = match diff {
+                        Ok(Some(diff_text)) => diff_text,
+                        Ok(None) => "`/diff` — _not inside a git repository_".to_string(),
                         Err(e) => format!("Failed to compute diff: {e}"),
                     };
                     tx.send(AppEvent::DiffResult(text));
@@ -1652,9 +1818,21 @@ impl ChatWidget {
             SlashCommand::Status => {
                 self.add_status_output();
             }
+            SlashCommand::Stats => {
+                self.add_stats_output();
+            }
+            SlashCommand::Sources => {
+                self.add_sources_output();
+            }
             SlashCommand::Mcp => {
                 self.add_mcp_output();
             }
+            SlashCommand::Hooks => {
+                self.submit_op(Op::ListHooks);
+            }
+            SlashCommand::Tabs => {
+                self.app_event_tx.send(AppEvent::OpenTabsPopup);
+            }
             SlashCommand::Rollout => {
                 if let Some(path) = self.rollout_path() {
                     self.add_info_message(
@@ -1719,6 +1897,28 @@ impl ChatWidget {
 
         let trimmed = args.trim();
         match cmd {
+            SlashCommand::Diff if trimmed == "last" => self.show_last_turn_diff(),
+            SlashCommand::Diff if !trimmed.is_empty() => {
+                self.add_error_message(format!(
+                    "Unknown argument '{trimmed}'. Use `/diff` for the working tree or `/diff last` for the last turn's changes."
+                ));
+            }
+            SlashCommand::Model if !trimmed.is_empty() => {
+                match parse_model_settings_args(trimmed) {
+                    Ok(args) => self.submit_op(Op::OverrideTurnContext {
+                        cwd: None,
+                        approval_policy: None,
+                        sandbox_policy: None,
+                        model: args.model,
+                        effort: args.effort,
+                        summary: None,
+                        verbosity: args.verbosity,
+                    }),
+                    Err(err) => self.add_error_message(format!(
+                        "{err}. Usage: /model [MODEL] [effort=LEVEL|default] [verbosity=low|medium|high|default]"
+                    )),
+                }
+            }
             SlashCommand::Review if !trimmed.is_empty() => {
                 self.submit_op(Op::Review {
                     review_request: ReviewRequest {
@@ -1911,8 +2111,16 @@ impl ChatWidget {
             }
             EventMsg::AgentReasoningSectionBreak(_) => self.on_reasoning_section_break(),
             EventMsg::TurnStarted(_) => self.on_task_started(),
-            EventMsg::TurnComplete(TurnCompleteEvent { last_agent_message }) => {
-                self.on_task_complete(last_agent_message)
+            EventMsg::TurnComplete(TurnCompleteEvent {
+                last_agent_message,
+                unified_diff,
+                timing,
+                ..
+            }) => {
+                if unified_diff.is_some() {
+                    self.last_turn_diff = unified_diff;
+                }
+                self.on_task_complete(last_agent_message, timing)
             }
             EventMsg::TokenCount(ev) => {
                 self.set_token_info(ev.info);
@@ -1922,17 +2130,25 @@ impl ChatWidget {
             EventMsg::Error(ErrorEvent { message, .. }) => self.on_error(message),
             EventMsg::McpStartupUpdate(ev) => self.on_mcp_startup_update(ev),
             EventMsg::McpStartupComplete(ev) => self.on_mcp_startup_complete(ev),
-            EventMsg::TurnAborted(ev) => match ev.reason {
-                TurnAbortReason::Interrupted => {
-                    self.on_interrupted_turn(ev.reason);
-                }
-                TurnAbortReason::Replaced => {
-                    self.on_error("Turn aborted: replaced by a new task".to_owned())
-                }
-                TurnAbortReason::ReviewEnded => {
-                    self.on_interrupted_turn(ev.reason);
+            EventMsg::McpServerDown(ev) => self.on_mcp_server_down(ev),
+            EventMsg::McpServerRestarted(ev) => self.on_mcp_server_restarted(ev),
+            EventMsg::TurnAborted(ev) => {
+                self.record_aborted_turn_stats();
+                match ev.reason {
+                    TurnAbortReason::Interrupted | TurnAbortReason::Stopped => {
+                        self.on_interrupted_turn(ev.reason);
+                    }
+                    TurnAbortReason::Replaced => {
+                        self.on_error("Turn aborted: replaced by a new task".to_owned())
+                    }
+                    TurnAbortReason::ReviewEnded => {
+                        self.on_interrupted_turn(ev.reason);
+                    }
+                    TurnAbortReason::TimedOut => {
+                        self.on_error("Turn aborted: time limit reached".to_owned())
+                    }
                 }
-            },
+            }
             EventMsg::PlanUpdate(update) => self.on_plan_update(update),
             EventMsg::ExecApprovalRequest(ev) => {
                 // For replayed events, synthesize an empty id (these should not occur).
@@ -1951,6 +2167,18 @@ impl ChatWidget {
             EventMsg::PatchApplyEnd(ev) => self.on_patch_apply_end(ev),
             EventMsg::ExecCommandEnd(ev) => self.on_exec_command_end(ev),
             EventMsg::ViewImageToolCall(ev) => self.on_view_image_tool_call(ev),
+            EventMsg::ContextSources(ev) => self.on_context_sources(ev),
+            EventMsg::ContextRepaired(_)
+            | EventMsg::RedactionApplied(_)
+            | EventMsg::InjectionSuspected(_)
+            | EventMsg::OutputBudget(_)
+            | EventMsg::GuardrailTripped(_)
+            | EventMsg::WatchdogFired(_)
+            | EventMsg::ProjectDocsUpdated(_)
+            | EventMsg::WatchedFilesChanged(_)
+            | EventMsg::WorkspaceSnapshot(_)
+            | EventMsg::VariablesUpdated(_)
+            | EventMsg::EnvironmentFingerprint(_) => {}
             EventMsg::McpToolCallBegin(ev) => self.on_mcp_tool_call_begin(ev),
             EventMsg::McpToolCallEnd(ev) => self.on_mcp_tool_call_end(ev),
             EventMsg::WebSearchBegin(ev) => self.on_web_search_begin(ev),
@@ -1959,6 +2187,9 @@ impl ChatWidget {
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
             EventMsg::ListSkillsResponse(ev) => self.on_list_skills(ev),
+            EventMsg::ListHooksResponse(ev) => self.open_hooks_popup(ev),
+            EventMsg::ConfigOverrideApplied(ev) => self.on_config_override_applied(ev),
+            EventMsg::ModelSettingsChanged(ev) => self.on_model_settings_changed(ev),
             EventMsg::SkillsUpdateAvailable => {
                 self.submit_op(Op::ListSkills {
                     cwds: Vec::new(),
@@ -1973,6 +2204,7 @@ impl ChatWidget {
             }
             EventMsg::UndoStarted(ev) => self.on_undo_started(ev),
             EventMsg::UndoCompleted(ev) => self.on_undo_completed(ev),
+            EventMsg::PullRequestPublished(_) => {}
             EventMsg::StreamError(StreamErrorEvent {
                 message,
                 additional_details,
@@ -1990,6 +2222,7 @@ impl ChatWidget {
             EventMsg::ContextCompacted(_) => self.on_agent_message("Context compacted".to_owned()),
             EventMsg::RawResponseItem(_)
             | EventMsg::ThreadRolledBack(_)
+            | EventMsg::UserCommand(_)
             | EventMsg::ItemStarted(_)
             | EventMsg::ItemCompleted(_)
             | EventMsg::AgentMessageContentDelta(_)
@@ -2115,10 +2348,66 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    fn show_last_turn_diff(&mut self) {
+        match self.last_turn_diff.clone() {
+            Some(unified_diff) => self.app_event_tx.send(AppEvent::DiffResult(unified_diff)),
+            None => self.add_info_message(
+                "No turn has changed files yet.".to_string(),
+                Some("`/diff` shows all uncommitted changes in the working tree.".to_string()),
+            ),
+        }
+    }
+
     pub(crate) fn on_diff_complete(&mut self) {
         self.request_redraw();
     }
 
+    /// Footer with what the turn that just finished used, also counted
+    /// towards `/stats`.
+    fn add_turn_stats(&mut self, timing: TurnTiming) {
+        let stats = self.take_turn_stats(timing);
+        self.session_stats.record(&stats);
+        self.add_to_history(history_cell::new_turn_stats(&stats));
+    }
+
+    /// Counts an aborted turn towards `/stats`: its tokens were used all the
+    /// same. Only its wall-clock time is known.
+    fn record_aborted_turn_stats(&mut self) {
+        let Some(started_at) = self.turn_started_at else {
+            return;
+        };
+        let timing = TurnTiming {
+            total_ms: u64::try_from(started_at.elapsed().as_millis()).unwrap_or(u64::MAX),
+            ..TurnTiming::default()
+        };
+        let stats = self.take_turn_stats(timing);
+        self.session_stats.record_aborted(&stats);
+    }
+
+    fn take_turn_stats(&mut self, timing: TurnTiming) -> TurnStats {
+        let after = self
+            .token_info
+            .as_ref()
+            .map(|info| info.total_token_usage.clone())
+            .unwrap_or_default();
+        let pricing = pricing_for(&self.model, &self.config.model_pricing);
+        let stats = TurnStats::new(&self.turn_start_usage, &after, timing, pricing);
+        self.turn_start_usage = after;
+        self.turn_started_at = None;
+        stats
+    }
+
+    pub(crate) fn add_stats_output(&mut self) {
+        self.add_to_history(history_cell::new_session_stats_output(&self.session_stats));
+    }
+
+    pub(crate) fn add_sources_output(&mut self) {
+        self.add_to_history(history_cell::new_context_sources_output(
+            &self.context_sources,
+            &self.config.cwd,
+        ));
+    }
+
     pub(crate) fn add_status_output(&mut self) {
         let default_usage = TokenUsage::default();
         let token_info = self.token_info.as_ref();
@@ -2218,6 +2507,7 @@ impl ChatWidget {
                 model: Some(switch_model.clone()),
                 effort: Some(Some(default_effort)),
                 summary: None,
+                verbosity: None,
             }));
             tx.send(AppEvent::UpdateModel(switch_model.clone()));
             tx.send(AppEvent::UpdateReasoningEffort(Some(default_effort)));
@@ -2441,6 +2731,7 @@ impl ChatWidget {
                 model: Some(model_for_action.clone()),
                 effort: Some(effort_for_action),
                 summary: None,
+                verbosity: None,
             }));
             tx.send(AppEvent::UpdateModel(model_for_action.clone()));
             tx.send(AppEvent::UpdateReasoningEffort(effort_for_action));
@@ -2612,6 +2903,7 @@ impl ChatWidget {
                 model: Some(model.clone()),
                 effort: Some(effort),
                 summary: None,
+                verbosity: None,
             }));
         self.app_event_tx.send(AppEvent::UpdateModel(model.clone()));
         self.app_event_tx
@@ -2630,6 +2922,169 @@ impl ChatWidget {
     }
 
     /// Open a popup to choose the approvals mode (ask for approval policy + sandbox policy).
+    pub(crate) fn open_tabs_popup(&mut self, tabs: Vec<TabSummary>) {
+        let can_close = tabs.len() > 1;
+        let mut items: Vec<SelectionItem> = tabs
+            .into_iter()
+            .map(|tab| {
+                let id = tab.id;
+                let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                    tx.send(AppEvent::SwitchTab(id));
+                })];
+                SelectionItem {
+                    name: format!("{}. {}", tab.number, tab.title),
+                    description: Some(tab.status.label().to_string()),
+                    is_current: tab.is_active,
+                    actions,
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+        items.push(SelectionItem {
+            name: "New tab".to_string(),
+            description: Some("Start another conversation alongside this one".to_string()),
+            actions: vec![Box::new(|tx| tx.send(AppEvent::NewTab))],
+            dismiss_on_select: true,
+            ..Default::default()
+        });
+        if can_close {
+            items.push(SelectionItem {
+                name: "Close this tab".to_string(),
+                description: Some("Shut down this conversation".to_string()),
+                actions: vec![Box::new(|tx| tx.send(AppEvent::CloseTab))],
+                dismiss_on_select: true,
+                ..Default::default()
+            });
+        }
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Conversation Tabs".to_string()),
+            subtitle: Some(
+                "Conversations keep running in the background while another tab is shown."
+                    .to_string(),
+            ),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            header: Box::new(()),
+            ..Default::default()
+        });
+    }
+
+    pub(crate) fn open_hooks_popup(&mut self, ev: ListHooksResponseEvent) {
+        if ev.hooks.is_empty() {
+            self.add_info_message(
+                "No hooks are registered.".to_string(),
+                Some("Add external hooks under [hooks.commands] in config.toml.".to_string()),
+            );
+            return;
+        }
+
+        let items = ev
+            .hooks
+            .iter()
+            .map(|hook| {
+                let last_run = ev
+                    .recent_firings
+                    .iter()
+                    .rev()
+                    .find(|firing| firing.hook == hook.name);
+                let mut description = format!(
+                    "{} · on {}",
+                    if hook.enabled { "enabled" } else { "disabled" },
+                    hook.events.join(", ")
+                );
+                if hook.blocking {
+                    description.push_str(" · blocking");
+                }
+                if hook.priority != 0 {
+                    description.push_str(&format!(" · priority {}", hook.priority));
+                }
+                if let Some(firing) = last_run {
+                    let outcome = if firing.error.is_some() {
+                        "failed"
+                    } else {
+                        "ok"
+                    };
+                    description
+                        .push_str(&format!(" · last run {}ms, {outcome}", firing.duration_ms));
+                }
+                let (actions, disabled_reason): (Vec<SelectionAction>, _) = match hook.source {
+                    HookSource::External => {
+                        let name = hook.name.clone();
+                        let enabled = !hook.enabled;
+                        (
+                            vec![Box::new(move |tx: &AppEventSender| {
+                                tx.send(AppEvent::CodexOp(Op::SetHookEnabled {
+                                    name: name.clone(),
+                                    enabled,
+                                }));
+                            })],
+                            None,
+                        )
+                    }
+                    HookSource::BuiltIn => {
+                        (Vec::new(), Some("built-in hooks are always on".to_string()))
+                    }
+                    HookSource::Managed => (
+                        Vec::new(),
+                        Some("required by your administrator".to_string()),
+                    ),
+                };
+                SelectionItem {
+                    name: hook.name.clone(),
+                    description: Some(description),
+                    actions,
+                    dismiss_on_select: true,
+                    disabled_reason,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        let mut header = ColumnRenderable::new();
+        header.push(Line::from("Hooks".bold()));
+        header.push(Line::from(
+            "Select an external hook to enable or disable it for this session.".dim(),
+        ));
+        if !ev.recent_firings.is_empty() {
+            header.push(Line::from(""));
+            header.push(Line::from("Recent firings".bold()));
+            for firing in ev
+                .recent_firings
+                .iter()
+                .rev()
+                .take(HOOKS_POPUP_RECENT_FIRINGS)
+            {
+                let started = chrono::DateTime::parse_from_rfc3339(&firing.started_at)
+                    .map(|at| {
+                        at.with_timezone(&chrono::Local)
+                            .format("%H:%M:%S")
+                            .to_string()
+                    })
+                    .unwrap_or_else(|_| firing.started_at.clone());
+                let mut spans = vec![
+                    format!("  {started} ").dim(),
+                    firing.hook.clone().into(),
+                    format!(" ← {} ", firing.event).dim(),
+                    format!("{}ms ", firing.duration_ms).into(),
+                ];
+                spans.push(match &firing.error {
+                    Some(err) => format!("failed: {err}").red(),
+                    None => "ok".green(),
+                });
+                header.push(Line::from(spans));
+            }
+        }
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            header: Box::new(header),
+            ..Default::default()
+        });
+    }
+
     pub(crate) fn open_approvals_popup(&mut self) {
         let current_approval = self.config.approval_policy.value();
         let current_sandbox = self.config.sandbox_policy.get();
@@ -2757,6 +3212,7 @@ impl ChatWidget {
                 model: None,
                 effort: None,
                 summary: None,
+                verbosity: None,
             }));
             tx.send(AppEvent::UpdateAskForApprovalPolicy(approval));
             tx.send(AppEvent::UpdateSandboxPolicy(sandbox_clone));
@@ -3340,6 +3796,14 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    /// In screen-reader mode, writes a state change to the transcript as a
+    /// plain line so it is read out; otherwise the bottom pane shows it.
+    fn announce(&mut self, message: String) {
+        if self.config.tui_screen_reader {
+            self.add_plain_history_lines(vec![Line::from(message)]);
+        }
+    }
+
     pub(crate) fn add_error_message(&mut self, message: String) {
         self.add_to_history(history_cell::new_error_event(message));
         self.request_redraw();
@@ -3761,6 +4225,59 @@ const EXAMPLE_PROMPTS: [&str; 6] = [
 
 // Extract the first bold (Markdown) element in the form **...** from `s`.
 // Returns the inner text if found; otherwise `None`.
+/// Settings given inline to `/model`, e.g. `/model gpt-5.1 effort=high`.
+#[derive(Debug, Default, PartialEq)]
+struct ModelSettingsArgs {
+    model: Option<String>,
+    effort: Option<Option<ReasoningEffortConfig>>,
+    verbosity: Option<Option<Verbosity>>,
+}
+
+fn parse_model_settings_args(args: &str) -> Result<ModelSettingsArgs, String> {
+    let mut parsed = ModelSettingsArgs::default();
+    for token in args.split_whitespace() {
+        match token.split_once('=') {
+            Some(("effort", value)) => {
+                parsed.effort = Some(match value {
+                    "default" => None,
+                    _ => Some(
+                        ReasoningEffortConfig::iter()
+                            .find(|effort| effort.to_string() == value)
+                            .ok_or_else(|| format!("Unknown reasoning effort '{value}'"))?,
+                    ),
+                });
+            }
+            Some(("verbosity", value)) => {
+                parsed.verbosity = Some(match value {
+                    "default" => None,
+                    "low" => Some(Verbosity::Low),
+                    "medium" => Some(Verbosity::Medium),
+                    "high" => Some(Verbosity::High),
+                    _ => return Err(format!("Unknown verbosity '{value}'")),
+                });
+            }
+            Some((key, _)) => return Err(format!("Unknown setting '{key}'")),
+            None if parsed.model.is_none() => parsed.model = Some(token.to_string()),
+            None => return Err(format!("Unexpected argument '{token}'")),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Parses `/set` arguments: `name=value` sets a variable and `name=` clears
+/// it. Values may be quoted to contain spaces.
+fn parse_variable_assignments(args: &str) -> Result<BTreeMap<String, Option<String>>, String> {
+    let mut variables = BTreeMap::new();
+    for token in shlex::Shlex::new(args) {
+        let Some((name, value)) = token.split_once('=') else {
+            return Err(format!("Expected name=value but found '{token}'"));
+        };
+        let value = (!value.is_empty()).then(|| value.to_string());
+        variables.insert(name.to_string(), value);
+    }
+    Ok(variables)
+}
+
 fn extract_first_bold(s: &str) -> Option<String> {
     let bytes = s.as_bytes();
     let mut i = 0usize;
@@ -10,6 +10,7 @@ use codex_core::CodexAuth;
 use codex_core::config::Config;
 use codex_core::config::ConfigBuilder;
 use codex_core::config::Constrained;
+use codex_core::cost::SessionStats;
 #[cfg(target_os = "windows")]
 use codex_core::features::Feature;
 use codex_core::models_manager::manager::ModelsManager;
@@ -29,6 +30,10 @@ use codex_core::protocol::ExecCommandSource;
 use codex_core::protocol::ExecPolicyAmendment;
 use codex_core::protocol::ExitedReviewModeEvent;
 use codex_core::protocol::FileChange;
+use codex_core::protocol::HookFiring;
+use codex_core::protocol::HookSource;
+use codex_core::protocol::HookSummary;
+use codex_core::protocol::ListHooksResponseEvent;
 use codex_core::protocol::McpStartupStatus;
 use codex_core::protocol::McpStartupUpdateEvent;
 use codex_core::protocol::Op;
@@ -43,12 +48,14 @@ use codex_core::protocol::TokenUsage;
 use codex_core::protocol::TokenUsageInfo;
 use codex_core::protocol::TurnCompleteEvent;
 use codex_core::protocol::TurnStartedEvent;
+use codex_core::protocol::TurnTiming;
 use codex_core::protocol::UndoCompletedEvent;
 use codex_core::protocol::UndoStartedEvent;
 use codex_core::protocol::ViewImageToolCallEvent;
 use codex_core::protocol::WarningEvent;
 use codex_protocol::ThreadId;
 use codex_protocol::account::PlanType;
+use codex_protocol::config_types::ReasoningSummary;
 use codex_protocol::openai_models::ModelPreset;
 use codex_protocol::openai_models::ReasoningEffortPreset;
 use codex_protocol::parse_command::ParsedCommand;
@@ -64,6 +71,7 @@ use insta::assert_snapshot;
 use pretty_assertions::assert_eq;
 #[cfg(target_os = "windows")]
 use serial_test::serial;
+use std::collections::BTreeSet;
 use std::collections::HashSet;
 use std::path::PathBuf;
 use tempfile::NamedTempFile;
@@ -275,6 +283,123 @@ async fn token_count_none_resets_context_indicator() {
     assert_eq!(chat.bottom_pane.context_window_percent(), None);
 }
 
+/// A finished turn gets a footer with the tokens it used and its latency, and
+/// `/stats` adds it up.
+#[tokio::test]
+async fn turn_complete_shows_turn_stats_and_stats_command_totals_them() {
+    let (mut chat, mut rx, _ops) = make_chatwidget_manual(None).await;
+    let usage = |input_tokens, output_tokens| TokenUsageInfo {
+        total_token_usage: TokenUsage {
+            input_tokens,
+            output_tokens,
+            total_tokens: input_tokens + output_tokens,
+            ..TokenUsage::default()
+        },
+        last_token_usage: TokenUsage::default(),
+        model_context_window: None,
+    };
+
+    chat.set_token_info(Some(usage(1_000, 100)));
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::TurnStarted(TurnStartedEvent {
+            model_context_window: None,
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::TokenCount(TokenCountEvent {
+            info: Some(usage(5_000, 400)),
+            rate_limits: None,
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::TurnComplete(TurnCompleteEvent {
+            last_agent_message: None,
+            timing: Some(TurnTiming {
+                total_ms: 2_000,
+                ..TurnTiming::default()
+            }),
+            unified_diff: None,
+            verification: None,
+            context_dropped: Vec::new(),
+        }),
+    });
+    let footer = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(
+        footer.contains("└ in 4K · out 300 · ") && footer.contains("· 2.00s"),
+        "unexpected footer: {footer}"
+    );
+
+    chat.dispatch_command(SlashCommand::Stats);
+    let stats = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(
+        stats.contains("Turns           1"),
+        "unexpected stats: {stats}"
+    );
+    assert!(
+        stats.contains("Tokens          in 4K · out 300"),
+        "unexpected stats: {stats}"
+    );
+}
+
+/// An interrupted turn used tokens too, so `/stats` counts it.
+#[tokio::test]
+async fn aborted_turn_counts_towards_stats() {
+    let (mut chat, mut rx, _ops) = make_chatwidget_manual(None).await;
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::TurnStarted(TurnStartedEvent {
+            model_context_window: None,
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::TokenCount(TokenCountEvent {
+            info: Some(TokenUsageInfo {
+                total_token_usage: TokenUsage {
+                    input_tokens: 2_000,
+                    output_tokens: 50,
+                    total_tokens: 2_050,
+                    ..TokenUsage::default()
+                },
+                last_token_usage: TokenUsage::default(),
+                model_context_window: None,
+            }),
+            rate_limits: None,
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "turn".into(),
+        msg: EventMsg::TurnAborted(codex_core::protocol::TurnAbortedEvent {
+            reason: TurnAbortReason::Interrupted,
+            cancelled: Vec::new(),
+        }),
+    });
+    drain_insert_history(&mut rx);
+
+    chat.dispatch_command(SlashCommand::Stats);
+    let stats = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines))
+        .collect::<String>();
+    assert!(
+        stats.contains("Turns           1 (1 aborted)"),
+        "unexpected stats: {stats}"
+    );
+    assert!(
+        stats.contains("Tokens          in 2K · out 50"),
+        "unexpected stats: {stats}"
+    );
+}
+
 #[tokio::test]
 async fn context_indicator_shows_used_tokens_when_window_unknown() {
     let (mut chat, _rx, _ops) = make_chatwidget_manual(Some("unknown-model")).await;
@@ -369,6 +494,7 @@ async fn make_chatwidget_manual(
         placeholder_text: "Ask Codex to do anything".to_string(),
         disable_paste_burst: false,
         animations_enabled: cfg.animations,
+        screen_reader: cfg.tui_screen_reader,
         skills: None,
     });
     let auth_manager = AuthManager::from_auth_for_testing(CodexAuth::from_api_key("test"));
@@ -378,6 +504,7 @@ async fn make_chatwidget_manual(
         codex_op_tx: op_tx,
         bottom_pane: bottom,
         active_cell: None,
+        attachments: SessionAttachments::new(&cfg.codex_home),
         config: cfg,
         model: resolved_model.clone(),
         auth_manager: auth_manager.clone(),
@@ -406,6 +533,11 @@ async fn make_chatwidget_manual(
         show_welcome_banner: true,
         queued_user_messages: VecDeque::new(),
         suppress_session_configured_redraw: false,
+        turn_start_usage: TokenUsage::default(),
+        turn_started_at: None,
+        session_stats: SessionStats::default(),
+        context_sources: BTreeSet::new(),
+        last_turn_diff: None,
         pending_notification: None,
         is_review_mode: false,
         pre_review_token_info: None,
@@ -732,6 +864,7 @@ async fn exec_approval_emits_proposed_command_and_decision_history() {
         ),
         proposed_execpolicy_amendment: None,
         parsed_cmd: vec![],
+        operations: vec![],
     };
     chat.handle_codex_event(Event {
         id: "sub-short".into(),
@@ -761,6 +894,82 @@ async fn exec_approval_emits_proposed_command_and_decision_history() {
     );
 }
 
+#[tokio::test]
+async fn screen_reader_mode_announces_state_changes_as_plain_lines() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+    chat.config.tui_screen_reader = true;
+
+    chat.handle_codex_event(Event {
+        id: "turn-1".into(),
+        msg: EventMsg::TurnStarted(TurnStartedEvent {
+            model_context_window: None,
+        }),
+    });
+    chat.handle_codex_event(Event {
+        id: "turn-1".into(),
+        msg: EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
+            call_id: "call-1".into(),
+            turn_id: "turn-1".into(),
+            command: vec!["cargo".into(), "test".into()],
+            cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            reason: None,
+            proposed_execpolicy_amendment: None,
+            parsed_cmd: vec![],
+            operations: vec![],
+        }),
+    });
+    chat.handle_key_event(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE));
+    chat.handle_codex_event(Event {
+        id: "turn-1".into(),
+        msg: EventMsg::TurnComplete(TurnCompleteEvent {
+            last_agent_message: None,
+            timing: None,
+            unified_diff: None,
+            verification: None,
+            context_dropped: Vec::new(),
+        }),
+    });
+
+    let announcements: Vec<String> = drain_insert_history(&mut rx)
+        .iter()
+        .map(|lines| lines_to_single_string(lines).trim().to_string())
+        .filter(|text| !text.is_empty())
+        .collect();
+    assert!(announcements.contains(&"Codex is working.".to_string()));
+    assert!(announcements.contains(&"Approval needed to run: cargo test".to_string()));
+    assert_eq!(
+        announcements.last().map(String::as_str),
+        Some("Codex is done and waiting for you.")
+    );
+}
+
+#[tokio::test]
+async fn screen_reader_mode_commits_streamed_lines_without_animation() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+    chat.config.tui_screen_reader = true;
+
+    chat.handle_codex_event(Event {
+        id: "s1".into(),
+        msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent {
+            delta: "first line\nsecond".into(),
+        }),
+    });
+
+    let mut inserted = Vec::new();
+    while let Ok(ev) = rx.try_recv() {
+        match ev {
+            AppEvent::StartCommitAnimation => panic!("commit animation started"),
+            AppEvent::InsertHistoryCell(cell) => {
+                inserted.push(lines_to_single_string(&cell.display_lines(80)));
+            }
+            _ => {}
+        }
+    }
+    assert_eq!(inserted.len(), 1);
+    assert!(inserted[0].contains("first line"));
+    assert!(!inserted[0].contains("second"));
+}
+
 #[tokio::test]
 async fn exec_approval_decision_truncates_multiline_and_long_commands() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
@@ -776,6 +985,7 @@ async fn exec_approval_decision_truncates_multiline_and_long_commands() {
         ),
         proposed_execpolicy_amendment: None,
         parsed_cmd: vec![],
+        operations: vec![],
     };
     chat.handle_codex_event(Event {
         id: "sub-multi".into(),
@@ -826,6 +1036,7 @@ async fn exec_approval_decision_truncates_multiline_and_long_commands() {
         reason: None,
         proposed_execpolicy_amendment: None,
         parsed_cmd: vec![],
+        operations: vec![],
     };
     chat.handle_codex_event(Event {
         id: "sub-long".into(),
@@ -1566,6 +1777,7 @@ async fn interrupt_exec_marks_failed_snapshot() {
         id: "call-int".into(),
         msg: EventMsg::TurnAborted(codex_core::protocol::TurnAbortedEvent {
             reason: TurnAbortReason::Interrupted,
+            cancelled: Vec::new(),
         }),
     });
 
@@ -1599,6 +1811,7 @@ async fn interrupted_turn_error_message_snapshot() {
         id: "task-1".into(),
         msg: EventMsg::TurnAborted(codex_core::protocol::TurnAbortedEvent {
             reason: TurnAbortReason::Interrupted,
+            cancelled: Vec::new(),
         }),
     });
 
@@ -2133,6 +2346,7 @@ async fn approval_modal_exec_snapshot() {
             "world".into(),
         ])),
         parsed_cmd: vec![],
+        operations: vec![],
     };
     chat.handle_codex_event(Event {
         id: "sub-approve".into(),
@@ -2184,6 +2398,7 @@ async fn approval_modal_exec_without_reason_snapshot() {
             "world".into(),
         ])),
         parsed_cmd: vec![],
+        operations: vec![],
     };
     chat.handle_codex_event(Event {
         id: "sub-approve-noreason".into(),
@@ -2221,6 +2436,7 @@ async fn approval_modal_exec_multiline_prefix_hides_execpolicy_option_snapshot()
         reason: None,
         proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(command)),
         parsed_cmd: vec![],
+        operations: vec![],
     };
     chat.handle_codex_event(Event {
         id: "sub-approve-multiline-trunc".into(),
@@ -2302,6 +2518,7 @@ async fn interrupt_restores_queued_messages_into_composer() {
         id: "turn-1".into(),
         msg: EventMsg::TurnAborted(codex_core::protocol::TurnAbortedEvent {
             reason: TurnAbortReason::Interrupted,
+            cancelled: Vec::new(),
         }),
     });
 
@@ -2340,6 +2557,7 @@ async fn interrupt_prepends_queued_messages_before_existing_composer_text() {
         id: "turn-1".into(),
         msg: EventMsg::TurnAborted(codex_core::protocol::TurnAbortedEvent {
             reason: TurnAbortReason::Interrupted,
+            cancelled: Vec::new(),
         }),
     });
 
@@ -2440,6 +2658,7 @@ async fn status_widget_and_approval_modal_snapshot() {
             "hello world".into(),
         ])),
         parsed_cmd: vec![],
+        operations: vec![],
     };
     chat.handle_codex_event(Event {
         id: "sub-approve-exec".into(),
@@ -3104,6 +3323,10 @@ async fn multiple_agent_messages_in_single_turn_emit_multiple_headers() {
         id: "s1".into(),
         msg: EventMsg::TurnComplete(TurnCompleteEvent {
             last_agent_message: None,
+            timing: None,
+            unified_diff: None,
+            verification: None,
+            context_dropped: Vec::new(),
         }),
     });
 
@@ -3389,6 +3612,10 @@ printf 'fenced within fenced\n'
         id: "t1".into(),
         msg: EventMsg::TurnComplete(TurnCompleteEvent {
             last_agent_message: None,
+            timing: None,
+            unified_diff: None,
+            verification: None,
+            context_dropped: Vec::new(),
         }),
     });
     for lines in drain_insert_history(&mut rx) {
@@ -3423,3 +3650,169 @@ async fn chatwidget_tall() {
     .unwrap();
     assert_snapshot!(term.backend().vt100().screen().contents());
 }
+
+#[tokio::test]
+async fn hooks_popup_lists_hooks_and_toggles_external_ones() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command(SlashCommand::Hooks);
+    chat.handle_codex_event(Event {
+        id: "hooks".into(),
+        msg: EventMsg::ListHooksResponse(ListHooksResponseEvent {
+            hooks: vec![HookSummary {
+                name: "lint".to_string(),
+                events: vec!["task_complete".to_string()],
+                source: HookSource::External,
+                enabled: true,
+                priority: 0,
+                blocking: false,
+            }],
+            recent_firings: vec![HookFiring {
+                hook: "lint".to_string(),
+                event: "task_complete".to_string(),
+                started_at: "2025-01-01T12:00:00.000Z".to_string(),
+                duration_ms: 42,
+                error: Some("`lint.sh` exited with exit status: 1".to_string()),
+            }],
+        }),
+    });
+
+    let popup = render_bottom_popup(&chat, 100);
+    assert!(popup.contains("Recent firings"), "popup: {popup}");
+    assert!(
+        popup.contains("lint ← task_complete 42ms failed: `lint.sh` exited with exit status: 1"),
+        "popup: {popup}"
+    );
+    assert!(
+        popup.contains("enabled · on task_complete · last run 42ms, failed"),
+        "popup: {popup}"
+    );
+    assert!(!popup.contains("(current)"), "popup: {popup}");
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    let toggled = std::iter::from_fn(|| rx.try_recv().ok()).find_map(|event| match event {
+        AppEvent::CodexOp(Op::SetHookEnabled { name, enabled }) => Some((name, enabled)),
+        _ => None,
+    });
+    assert_eq!(toggled, Some(("lint".to_string(), false)));
+}
+
+#[tokio::test]
+async fn slash_model_with_args_overrides_effort_and_verbosity() {
+    let (mut chat, _rx, mut op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command_with_args(
+        SlashCommand::Model,
+        "effort=high verbosity=default".to_string(),
+    );
+
+    match op_rx.try_recv() {
+        Ok(Op::OverrideTurnContext {
+            model,
+            effort,
+            verbosity,
+            ..
+        }) => {
+            assert_eq!(model, None);
+            assert_eq!(effort, Some(Some(ReasoningEffortConfig::High)));
+            assert_eq!(verbosity, Some(None));
+        }
+        other => panic!("expected OverrideTurnContext, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn slash_model_rejects_unknown_settings() {
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual(None).await;
+
+    chat.dispatch_command_with_args(SlashCommand::Model, "effort=extreme".to_string());
+
+    assert_matches!(op_rx.try_recv(), Err(TryRecvError::Empty));
+    let cells = drain_insert_history(&mut rx);
+    assert_eq!(cells.len(), 1, "expected one error message");
+    let rendered = lines_to_single_string(&cells[0]);
+    assert!(
+        rendered.contains("Unknown reasoning effort 'extreme'"),
+        "unexpected error: {rendered:?}"
+    );
+}
+
+#[test]
+fn model_settings_args_accept_a_model_and_settings() {
+    assert_eq!(
+        parse_model_settings_args("gpt-5.1 verbosity=low effort=xhigh"),
+        Ok(ModelSettingsArgs {
+            model: Some("gpt-5.1".to_string()),
+            effort: Some(Some(ReasoningEffortConfig::XHigh)),
+            verbosity: Some(Some(Verbosity::Low)),
+        })
+    );
+    assert_eq!(
+        parse_model_settings_args("gpt-5.1 o3"),
+        Err("Unexpected argument 'o3'".to_string())
+    );
+}
+
+#[tokio::test]
+async fn model_settings_changed_updates_config_and_reports_settings() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.handle_codex_event(Event {
+        id: "override".into(),
+        msg: EventMsg::ModelSettingsChanged(ModelSettingsChangedEvent {
+            model: "gpt-5.1-codex".to_string(),
+            effort: Some(ReasoningEffortConfig::Low),
+            summary: ReasoningSummary::Auto,
+            verbosity: Some(Verbosity::Low),
+        }),
+    });
+
+    assert_eq!(chat.config.model_verbosity, Some(Verbosity::Low));
+    let mut model_update = None;
+    let mut rendered = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        match event {
+            AppEvent::UpdateModel(model) => model_update = Some(model),
+            AppEvent::InsertHistoryCell(cell) => {
+                rendered.push(lines_to_single_string(&cell.display_lines(80)));
+            }
+            _ => {}
+        }
+    }
+    assert_eq!(model_update, Some("gpt-5.1-codex".to_string()));
+    assert!(
+        rendered
+            .iter()
+            .any(|text| text.contains("gpt-5.1-codex, effort low, verbosity low")),
+        "expected a settings notice, got {rendered:?}"
+    );
+}
+
+#[tokio::test]
+async fn diff_last_shows_the_last_turn_that_changed_files() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
+    let diff = "diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n@@ -1 +1 @@\n-old\n+new\n";
+    for unified_diff in [Some(diff.to_string()), None] {
+        chat.handle_codex_event(Event {
+            id: "turn".into(),
+            msg: EventMsg::TurnComplete(TurnCompleteEvent {
+                last_agent_message: None,
+                timing: None,
+                unified_diff,
+                verification: None,
+                context_dropped: Vec::new(),
+            }),
+        });
+    }
+    while rx.try_recv().is_ok() {}
+
+    chat.dispatch_command_with_args(SlashCommand::Diff, "last".to_string());
+
+    let mut shown = None;
+    while let Ok(event) = rx.try_recv() {
+        if let AppEvent::DiffResult(text) = event {
+            shown = Some(text);
+        }
+    }
+    assert_eq!(shown.as_deref(), Some(diff));
+}
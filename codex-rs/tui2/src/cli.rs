@@ -90,6 +90,11 @@ pub struct Cli {
     #[arg(long = "no-alt-screen", default_value_t = false)]
     pub no_alt_screen: bool,
 
+    /// Internal: labels recorded in the new session's metadata. Set by
+    /// `codex fix`.
+    #[clap(skip)]
+    pub session_labels: Vec<String>,
+
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 }
@@ -115,6 +120,7 @@ impl From<codex_tui::Cli> for Cli {
             web_search: cli.web_search,
             add_dir: cli.add_dir,
             no_alt_screen: cli.no_alt_screen,
+            session_labels: cli.session_labels,
             config_overrides: cli.config_overrides,
         }
     }
@@ -1,6 +1,7 @@
 use std::path::Path;
 use std::path::PathBuf;
 use tempfile::Builder;
+use tempfile::TempDir;
 
 #[derive(Debug, Clone)]
 pub enum PasteImageError {
@@ -116,17 +117,52 @@ pub fn paste_image_as_png() -> Result<(Vec<u8>, PastedImageInfo), PasteImageErro
     ))
 }
 
-/// Convenience: write to a temp file and return its path + info.
+/// The directory holding the images pasted during one session, under
+/// `$CODEX_HOME/attachments`. It is created on the first paste and removed,
+/// with everything in it, when the session ends and this is dropped.
+#[derive(Debug)]
+pub(crate) struct SessionAttachments {
+    root: PathBuf,
+    dir: Option<TempDir>,
+}
+
+impl SessionAttachments {
+    pub(crate) fn new(codex_home: &Path) -> Self {
+        Self {
+            root: codex_home.join("attachments"),
+            dir: None,
+        }
+    }
+
+    /// The session's directory, created if needed.
+    pub(crate) fn dir(&mut self) -> std::io::Result<&Path> {
+        let dir = match self.dir.take() {
+            Some(dir) => dir,
+            None => {
+                std::fs::create_dir_all(&self.root)?;
+                Builder::new().prefix("session-").tempdir_in(&self.root)?
+            }
+        };
+        Ok(self.dir.insert(dir).path())
+    }
+}
+
+/// Convenience: write the clipboard image to a new PNG file in
+/// `attachments_dir` (created if needed) and return its path + info.
 #[cfg(not(target_os = "android"))]
-pub fn paste_image_to_temp_png() -> Result<(PathBuf, PastedImageInfo), PasteImageError> {
+pub fn paste_image_to_png_file(
+    attachments_dir: &Path,
+) -> Result<(PathBuf, PastedImageInfo), PasteImageError> {
     // First attempt: read image from system clipboard via arboard (native paths or image data).
     match paste_image_as_png() {
         Ok((png, info)) => {
-            // Create a unique temporary file with a .png suffix to avoid collisions.
+            std::fs::create_dir_all(attachments_dir)
+                .map_err(|e| PasteImageError::IoError(e.to_string()))?;
+            // Create a unique file with a .png suffix to avoid collisions.
             let tmp = Builder::new()
-                .prefix("codex-clipboard-")
+                .prefix("clipboard-")
                 .suffix(".png")
-                .tempfile()
+                .tempfile_in(attachments_dir)
                 .map_err(|e| PasteImageError::IoError(e.to_string()))?;
             std::fs::write(tmp.path(), &png)
                 .map_err(|e| PasteImageError::IoError(e.to_string()))?;
@@ -229,7 +265,9 @@ fn try_dump_windows_clipboard_image() -> Option<String> {
 }
 
 #[cfg(target_os = "android")]
-pub fn paste_image_to_temp_png() -> Result<(PathBuf, PastedImageInfo), PasteImageError> {
+pub fn paste_image_to_png_file(
+    _attachments_dir: &Path,
+) -> Result<(PathBuf, PastedImageInfo), PasteImageError> {
     // Keep error consistent with paste_image_as_png.
     Err(PasteImageError::ClipboardUnavailable(
         "clipboard image paste is unsupported on Android".into(),
@@ -357,6 +395,22 @@ pub fn pasted_image_format(path: &Path) -> EncodedImageFormat {
 mod pasted_paths_tests {
     use super::*;
 
+    #[test]
+    fn session_attachments_are_removed_when_the_session_ends() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let mut attachments = SessionAttachments::new(codex_home.path());
+        let dir = attachments.dir().expect("attachments dir").to_path_buf();
+        assert_eq!(
+            dir.parent(),
+            Some(codex_home.path().join("attachments").as_path())
+        );
+        assert_eq!(attachments.dir().expect("attachments dir"), dir);
+        std::fs::write(dir.join("clipboard-1.png"), b"png").expect("write image");
+
+        drop(attachments);
+        assert!(!dir.exists());
+    }
+
     #[cfg(not(windows))]
     #[test]
     fn normalize_file_url() {
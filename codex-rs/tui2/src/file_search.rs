@@ -17,12 +17,17 @@
 //!    recent query.
 //! 4. If there is a in-flight search that is not a prefix of the latest thing
 //!    the user typed, it is cancelled.
+//!
+//! Searches run against a [`FileIndex`] built in the background on the first
+//! query and kept current from filesystem events, rather than walking the
+//! whole tree on every keystroke.
 
-use codex_file_search as file_search;
+use codex_file_search::FileIndex;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::sync::OnceLock;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::thread;
@@ -32,7 +37,6 @@ use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
 
 const MAX_FILE_SEARCH_RESULTS: NonZeroUsize = NonZeroUsize::new(20).unwrap();
-const NUM_FILE_SEARCH_THREADS: NonZeroUsize = NonZeroUsize::new(2).unwrap();
 
 /// How long to wait after a keystroke before firing the first search when none
 /// is currently running. Keeps early queries more meaningful.
@@ -46,6 +50,9 @@ pub(crate) struct FileSearchManager {
     state: Arc<Mutex<SearchState>>,
 
     search_dir: PathBuf,
+    /// Created on the first query, so sessions that never use `@` do not
+    /// index or watch the workspace.
+    index: OnceLock<Arc<FileIndex>>,
     app_tx: AppEventSender,
 }
 
@@ -74,6 +81,7 @@ impl FileSearchManager {
                 active_search: None,
             })),
             search_dir,
+            index: OnceLock::new(),
             app_tx: tx,
         }
     }
@@ -115,7 +123,7 @@ impl FileSearchManager {
         // dropping the lock. This means we are the only thread that can spawn a
         // debounce timer.
         let state = self.state.clone();
-        let search_dir = self.search_dir.clone();
+        let index = self.index();
         let tx_clone = self.app_tx.clone();
         thread::spawn(move || {
             // Always do a minimum debounce, but then poll until the
@@ -145,37 +153,34 @@ impl FileSearchManager {
                 query
             };
 
-            FileSearchManager::spawn_file_search(
-                query,
-                search_dir,
-                tx_clone,
-                cancellation_token,
-                state,
-            );
+            FileSearchManager::spawn_file_search(query, index, tx_clone, cancellation_token, state);
         });
     }
 
+    fn index(&self) -> Arc<FileIndex> {
+        Arc::clone(
+            self.index
+                .get_or_init(|| Arc::new(FileIndex::new(self.search_dir.clone()))),
+        )
+    }
+
     fn spawn_file_search(
         query: String,
-        search_dir: PathBuf,
+        index: Arc<FileIndex>,
         tx: AppEventSender,
         cancellation_token: Arc<AtomicBool>,
         search_state: Arc<Mutex<SearchState>>,
     ) {
         let compute_indices = true;
         std::thread::spawn(move || {
-            let matches = file_search::run(
-                &query,
-                MAX_FILE_SEARCH_RESULTS,
-                &search_dir,
-                Vec::new(),
-                NUM_FILE_SEARCH_THREADS,
-                cancellation_token.clone(),
-                compute_indices,
-                true,
-            )
-            .map(|res| res.matches)
-            .unwrap_or_default();
+            let matches = index
+                .search(
+                    &query,
+                    MAX_FILE_SEARCH_RESULTS,
+                    compute_indices,
+                    &cancellation_token,
+                )
+                .matches;
 
             let is_cancelled = cancellation_token.load(Ordering::Relaxed);
             if !is_cancelled {
@@ -11,6 +11,7 @@ use crate::markdown::append_markdown;
 use crate::render::line_utils::line_to_static;
 use crate::render::line_utils::prefix_lines;
 use crate::render::renderable::Renderable;
+use crate::status::format_tokens_compact;
 use crate::style::user_message_style;
 use crate::text_formatting::format_and_truncate_tool_result;
 use crate::text_formatting::truncate_text;
@@ -21,13 +22,18 @@ use crate::version::CODEX_CLI_VERSION;
 use crate::wrapping::RtOptions;
 use crate::wrapping::word_wrap_line;
 use base64::Engine;
+use codex_common::elapsed::format_duration;
 use codex_common::format_env_display::format_env_display;
 use codex_core::config::Config;
 use codex_core::config::types::McpServerTransportConfig;
+use codex_core::cost::SessionStats;
+use codex_core::cost::TurnStats;
+use codex_core::protocol::ContentSource;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::McpAuthStatus;
 use codex_core::protocol::McpInvocation;
 use codex_core::protocol::SessionConfiguredEvent;
+use codex_core::protocol::TokenUsage;
 use codex_protocol::openai_models::ReasoningEffort as ReasoningEffortConfig;
 use codex_protocol::plan_tool::PlanItemArg;
 use codex_protocol::plan_tool::StepStatus;
@@ -46,6 +52,7 @@ use ratatui::style::Stylize;
 use ratatui::widgets::Paragraph;
 use ratatui::widgets::Wrap;
 use std::any::Any;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::io::Cursor;
 use std::path::Path;
@@ -518,14 +525,21 @@ impl HistoryCell for PlainHistoryCell {
 #[derive(Debug)]
 pub(crate) struct UpdateAvailableHistoryCell {
     latest_version: String,
+    /// Release notes that concern the user's config.
+    notes: Vec<String>,
     update_action: Option<UpdateAction>,
 }
 
 #[cfg_attr(debug_assertions, allow(dead_code))]
 impl UpdateAvailableHistoryCell {
-    pub(crate) fn new(latest_version: String, update_action: Option<UpdateAction>) -> Self {
+    pub(crate) fn new(
+        latest_version: String,
+        notes: Vec<String>,
+        update_action: Option<UpdateAction>,
+    ) -> Self {
         Self {
             latest_version,
+            notes,
             update_action,
         }
     }
@@ -538,14 +552,10 @@ impl HistoryCell for UpdateAvailableHistoryCell {
         let update_instruction = if let Some(update_action) = self.update_action {
             line!["Run ", update_action.command_str().cyan(), " to update."]
         } else {
-            line![
-                "See ",
-                "https://github.com/openai/codex".cyan().underlined(),
-                " for installation options."
-            ]
+            line!["Run ", "codex self-update".cyan(), " to update."]
         };
 
-        let content = text![
+        let mut content = text![
             line![
                 padded_emoji("✨").bold().cyan(),
                 "Update available!".bold().cyan(),
@@ -553,12 +563,28 @@ impl HistoryCell for UpdateAvailableHistoryCell {
                 format!("{CODEX_CLI_VERSION} -> {}", self.latest_version).bold(),
             ],
             update_instruction,
-            "",
-            "See full release notes:",
-            "https://github.com/openai/codex/releases/latest"
-                .cyan()
-                .underlined(),
         ];
+        if !self.notes.is_empty() {
+            content.lines.push(Line::from(""));
+            content
+                .lines
+                .push(Line::from("Changes that affect your config:".bold()));
+            content.lines.extend(
+                self.notes
+                    .iter()
+                    .map(|note| line!["  • ".dim(), note.clone()]),
+            );
+        }
+        content.lines.push(Line::from(""));
+        content.lines.push(Line::from("See full release notes:"));
+        content.lines.push(Line::from(
+            format!(
+                "https://github.com/openai/codex/releases/tag/rust-v{}",
+                self.latest_version
+            )
+            .cyan()
+            .underlined(),
+        ));
 
         let inner_width = content
             .width()
@@ -617,6 +643,134 @@ impl HistoryCell for PrefixedWrappedHistoryCell {
     }
 }
 
+/// Dim line after a turn with the tokens it used, its estimated cost, and how
+/// long it took.
+pub(crate) fn new_turn_stats(stats: &TurnStats) -> PlainHistoryCell {
+    let mut parts = vec![token_breakdown(&stats.usage)];
+    if let Some(cost) = stats.cost_usd {
+        parts.push(format!("~{}", format_usd(cost)));
+    }
+    let mut latency = format_duration(Duration::from_millis(stats.timing.total_ms));
+    if let Some(first_token_ms) = stats.timing.first_token_ms {
+        let first_token = format_duration(Duration::from_millis(first_token_ms));
+        latency.push_str(&format!(" (first token {first_token})"));
+    }
+    parts.push(latency);
+    PlainHistoryCell::new(vec![Line::from(format!("  └ {}", parts.join(" · "))).dim()])
+}
+
+/// `/stats`: totals over the turns of this session.
+pub(crate) fn new_session_stats_output(stats: &SessionStats) -> CompositeHistoryCell {
+    let command = PlainHistoryCell::new(vec!["/stats".magenta().into()]);
+    let row =
+        |label: &str, value: String| Line::from(vec![format!("  {label:<16}").dim(), value.into()]);
+    let mut lines = Vec::new();
+    if stats.turns == 0 {
+        lines.push("  No turns have finished yet.".italic().into());
+    } else {
+        let mut turns = stats.turns.to_string();
+        if stats.aborted_turns > 0 {
+            turns.push_str(&format!(" ({} aborted)", stats.aborted_turns));
+        }
+        lines.push(row("Turns", turns));
+        lines.push(row("Tokens", token_breakdown(&stats.usage)));
+        let mut cost = format!("~{}", format_usd(stats.cost_usd));
+        if stats.unpriced_turns > 0 {
+            cost.push_str(&format!(
+                " (excludes {} turn(s) on models without a known price)",
+                stats.unpriced_turns
+            ));
+        }
+        lines.push(row("Estimated cost", cost));
+        let ms = |ms: u64| format_duration(Duration::from_millis(ms));
+        let mut time = format!("{} total", ms(stats.total_ms));
+        if let Some(average) = stats.average_turn_ms() {
+            time.push_str(&format!(" · {} per turn", ms(average)));
+        }
+        time.push_str(&format!(" · slowest {}", ms(stats.slowest_turn_ms)));
+        lines.push(row("Time", time));
+        if let Some(first_token) = stats.average_first_token_ms() {
+            lines.push(row("First token", format!("{} average", ms(first_token))));
+        }
+        lines.push(row(
+            "Model / tools",
+            format!("{} / {}", ms(stats.model_ms), ms(stats.tool_ms)),
+        ));
+    }
+    CompositeHistoryCell::new(vec![
+        Box::new(command),
+        Box::new(PlainHistoryCell::new(lines)),
+    ])
+}
+
+/// The `/sources` listing: every instructions file, file, page, and MCP
+/// server the model was given context from, grouped by kind.
+pub(crate) fn new_context_sources_output(
+    sources: &BTreeSet<ContentSource>,
+    cwd: &Path,
+) -> CompositeHistoryCell {
+    let command = PlainHistoryCell::new(vec!["/sources".magenta().into()]);
+    let mut groups: [(&str, Vec<String>); 4] = [
+        ("Instructions", Vec::new()),
+        ("Files", Vec::new()),
+        ("Pages", Vec::new()),
+        ("MCP", Vec::new()),
+    ];
+    for source in sources {
+        let (group, entry) = match source {
+            ContentSource::Instructions { path } => (0, display_path_for(path, cwd)),
+            ContentSource::File { path } => (1, display_path_for(path, cwd)),
+            ContentSource::Url { url } => (2, url.clone()),
+            ContentSource::McpResource { server, uri } => (3, format!("{server}: {uri}")),
+            ContentSource::McpTool { server, tool } => (3, format!("{server}: {tool} (tool)")),
+        };
+        groups[group].1.push(entry);
+    }
+    let mut lines = Vec::new();
+    if sources.is_empty() {
+        lines.push("  No sources yet.".italic().into());
+    }
+    for (label, entries) in groups {
+        for (index, entry) in entries.into_iter().enumerate() {
+            let label = if index == 0 { label } else { "" };
+            lines.push(Line::from(vec![
+                format!("  {label:<14}").dim(),
+                entry.into(),
+            ]));
+        }
+    }
+    CompositeHistoryCell::new(vec![
+        Box::new(command),
+        Box::new(PlainHistoryCell::new(lines)),
+    ])
+}
+
+fn token_breakdown(usage: &TokenUsage) -> String {
+    let mut input = format!("in {}", format_tokens_compact(usage.input_tokens));
+    if usage.cached_input() > 0 {
+        input.push_str(&format!(
+            " ({} cached)",
+            format_tokens_compact(usage.cached_input())
+        ));
+    }
+    let mut output = format!("out {}", format_tokens_compact(usage.output_tokens));
+    if usage.reasoning_output_tokens > 0 {
+        output.push_str(&format!(
+            " ({} reasoning)",
+            format_tokens_compact(usage.reasoning_output_tokens)
+        ));
+    }
+    format!("{input} · {output}")
+}
+
+fn format_usd(cost: f64) -> String {
+    if cost > 0.0 && cost < 0.01 {
+        "<$0.01".to_string()
+    } else {
+        format!("${cost:.2}")
+    }
+}
+
 fn truncate_exec_snippet(full_cmd: &str) -> String {
     let mut snippet = match full_cmd.split_once('\n') {
         Some((first, _)) => format!("{first} ..."),
@@ -638,7 +792,8 @@ pub fn new_approval_decision_cell(
     use codex_core::protocol::ReviewDecision::*;
 
     let (symbol, summary): (Span<'static>, Vec<Span<'static>>) = match decision {
-        Approved => {
+        // Hunk-level approval only applies to patches, never to commands.
+        Approved | ApprovedHunks { .. } => {
             let snippet = Span::from(exec_snippet(&command)).dim();
             (
                 "✔ ".green(),
@@ -1755,6 +1910,7 @@ mod tests {
     use codex_core::config::types::McpServerConfig;
     use codex_core::config::types::McpServerTransportConfig;
     use codex_core::protocol::McpAuthStatus;
+    use codex_core::protocol::TurnTiming;
     use codex_protocol::parse_command::ParsedCommand;
     use dirs::home_dir;
     use pretty_assertions::assert_eq;
@@ -1935,6 +2091,7 @@ mod tests {
             tool_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
+            sampling: None,
         };
         config.mcp_servers.insert("docs".to_string(), stdio_config);
 
@@ -1954,6 +2111,7 @@ mod tests {
             tool_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
+            sampling: None,
         };
         config.mcp_servers.insert("http".to_string(), http_config);
 
@@ -2812,4 +2970,117 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn turn_stats_footer_shows_tokens_cost_and_latency() {
+        let stats = TurnStats {
+            usage: TokenUsage {
+                input_tokens: 45_200,
+                cached_input_tokens: 38_000,
+                output_tokens: 1_200,
+                reasoning_output_tokens: 0,
+                total_tokens: 46_400,
+            },
+            cost_usd: Some(0.0214),
+            timing: TurnTiming {
+                first_token_ms: Some(1_100),
+                total_ms: 14_200,
+                ..Default::default()
+            },
+        };
+        assert_eq!(
+            render_lines(&new_turn_stats(&stats).display_lines(80)),
+            vec!["  └ in 45.2K (38K cached) · out 1.2K · ~$0.02 · 14.20s (first token 1.10s)"]
+        );
+    }
+
+    #[test]
+    fn session_stats_output_lists_totals() {
+        let mut stats = SessionStats::default();
+        assert_eq!(
+            render_lines(&new_session_stats_output(&stats).display_lines(80)),
+            vec!["/stats", "", "  No turns have finished yet."]
+        );
+
+        stats.record(&TurnStats {
+            usage: TokenUsage {
+                input_tokens: 2_000,
+                output_tokens: 300,
+                total_tokens: 2_300,
+                ..Default::default()
+            },
+            cost_usd: None,
+            timing: TurnTiming {
+                total_ms: 3_000,
+                model_ms: 2_500,
+                tool_ms: 400,
+                ..Default::default()
+            },
+        });
+        stats.record_aborted(&TurnStats {
+            usage: TokenUsage {
+                input_tokens: 1_000,
+                total_tokens: 1_000,
+                ..Default::default()
+            },
+            cost_usd: None,
+            timing: TurnTiming {
+                total_ms: 1_000,
+                ..Default::default()
+            },
+        });
+        assert_eq!(
+            render_lines(&new_session_stats_output(&stats).display_lines(80)),
+            vec![
+                "/stats",
+                "",
+                "  Turns           2 (1 aborted)",
+                "  Tokens          in 3K · out 300",
+                "  Estimated cost  ~$0.00 (excludes 2 turn(s) on models without a known price)",
+                "  Time            4.00s total · 2.00s per turn · slowest 3.00s",
+                "  Model / tools   2.50s / 400ms",
+            ]
+        );
+    }
+
+    #[test]
+    fn context_sources_output_groups_by_kind() {
+        let cwd = Path::new("/work/api");
+        let mut sources = BTreeSet::new();
+        assert_eq!(
+            render_lines(&new_context_sources_output(&sources, cwd).display_lines(80)),
+            vec!["/sources", "", "  No sources yet."]
+        );
+
+        sources.extend([
+            ContentSource::File {
+                path: PathBuf::from("/work/api/src/lib.rs"),
+            },
+            ContentSource::File {
+                path: PathBuf::from("/work/api/Cargo.toml"),
+            },
+            ContentSource::Instructions {
+                path: PathBuf::from("/work/api/AGENTS.md"),
+            },
+            ContentSource::Url {
+                url: "https://docs.rs/serde".to_string(),
+            },
+            ContentSource::McpTool {
+                server: "jira".to_string(),
+                tool: "search".to_string(),
+            },
+        ]);
+        assert_eq!(
+            render_lines(&new_context_sources_output(&sources, cwd).display_lines(80)),
+            vec![
+                "/sources",
+                "",
+                "  Instructions  AGENTS.md",
+                "  Files         Cargo.toml",
+                "                src/lib.rs",
+                "  Pages         https://docs.rs/serde",
+                "  MCP           jira: search (tool)",
+            ]
+        );
+    }
 }
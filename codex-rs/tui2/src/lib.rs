@@ -19,18 +19,21 @@ use codex_core::config::ConfigOverrides;
 use codex_core::config::find_codex_home;
 use codex_core::config::load_config_as_toml_with_cli_overrides;
 use codex_core::config::resolve_oss_provider;
+use codex_core::config::types::ExecBackendConfig;
+use codex_core::devcontainer::find_devcontainer_config;
 use codex_core::find_thread_path_by_id_str;
 use codex_core::get_platform_sandbox;
 use codex_core::protocol::AskForApproval;
+use codex_core::session_logs;
+use codex_core::session_logs::SessionLogLayer;
 use codex_core::terminal::Multiplexer;
 use codex_protocol::config_types::AltScreenMode;
 use codex_protocol::config_types::SandboxMode;
 use codex_utils_absolute_path::AbsolutePathBuf;
-use std::fs::OpenOptions;
 use std::path::PathBuf;
 use tracing::error;
-use tracing_appender::non_blocking;
 use tracing_subscriber::EnvFilter;
+use tracing_subscriber::filter::FilterExt;
 use tracing_subscriber::prelude::*;
 
 mod additional_dirs;
@@ -72,9 +75,11 @@ mod session_log;
 mod shimmer;
 mod slash_command;
 mod status;
+mod status_export;
 mod status_indicator_widget;
 mod streaming;
 mod style;
+mod tabs;
 mod terminal_palette;
 mod text_formatting;
 mod tooltips;
@@ -98,8 +103,7 @@ mod wrapping;
 
 #[cfg(test)]
 pub mod test_backend;
-
-use crate::onboarding::TrustDirectorySelection;
+use crate::onboarding::DevcontainerSelection;
 use crate::onboarding::onboarding_screen::OnboardingScreenArgs;
 use crate::onboarding::onboarding_screen::run_onboarding_app;
 use crate::tui::Tui;
@@ -239,6 +243,7 @@ pub async fn run_main(
         show_raw_agent_reasoning: cli.oss.then_some(true),
         tools_web_search_request: None,
         additional_writable_roots: additional_dirs,
+        session_labels: cli.session_labels.clone(),
     };
 
     let config = load_config_or_exit(cli_kv_overrides.clone(), overrides.clone()).await;
@@ -259,25 +264,10 @@ pub async fn run_main(
 
     let active_profile = config.active_profile.clone();
     let log_dir = codex_core::config::log_dir(&config)?;
-    std::fs::create_dir_all(&log_dir)?;
-    // Open (or create) your log file, appending to it.
-    let mut log_file_opts = OpenOptions::new();
-    log_file_opts.create(true).append(true);
-
-    // Ensure the file is only readable and writable by the current user.
-    // Doing the equivalent to `chmod 600` on Windows is quite a bit more code
-    // and requires the Windows API crates, so we can reconsider that when
-    // Codex CLI is officially supported on Windows.
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::OpenOptionsExt;
-        log_file_opts.mode(0o600);
-    }
-
-    let log_file = log_file_opts.open(log_dir.join("codex-tui.log"))?;
-
-    // Wrap file in non‑blocking writer.
-    let (non_blocking, _guard) = non_blocking(log_file);
+    // Each session logs to its own directory of rotated JSON-lines files,
+    // which `codex logs` reads back.
+    let session_logs_root = codex_core::session_logs::sessions_root(&log_dir);
+    std::fs::create_dir_all(&session_logs_root)?;
 
     // use RUST_LOG env var, default to info for codex crates.
     let env_filter = || {
@@ -286,15 +276,10 @@ pub async fn run_main(
         })
     };
 
-    let file_layer = tracing_subscriber::fmt::layer()
-        .with_writer(non_blocking)
-        // `with_target(true)` is the default, but we previously disabled it for file output.
-        // Keep it enabled so we can selectively enable targets via `RUST_LOG=...` and then
-        // grep for a specific module/target while troubleshooting.
-        .with_target(true)
-        .with_ansi(false)
-        .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
-        .with_filter(env_filter());
+    // Session spans pass regardless of `RUST_LOG` so that events can be
+    // attributed to the session they belong to.
+    let file_layer = SessionLogLayer::new(session_logs_root)
+        .with_filter(env_filter().or(session_logs::session_span_filter()));
 
     let feedback = codex_feedback::CodexFeedback::new();
     let feedback_layer = feedback.logger_layer();
@@ -363,6 +348,8 @@ async fn run_ratatui_app(
     feedback: codex_feedback::CodexFeedback,
 ) -> color_eyre::Result<AppExitInfo> {
     color_eyre::install()?;
+    // After color_eyre, which replaces rather than chains the panic hook.
+    codex_core::crash_report::install(&initial_config, "codex-tui2");
 
     tooltips::announcement::prewarm();
 
@@ -411,14 +398,16 @@ async fn run_ratatui_app(
     );
     let login_status = get_login_status(&initial_config);
     let should_show_trust_screen = should_show_trust_screen(&initial_config);
-    let should_show_onboarding =
-        should_show_onboarding(login_status, &initial_config, should_show_trust_screen);
+    let devcontainer_to_offer = devcontainer_to_offer(&initial_config);
+    let should_show_onboarding = devcontainer_to_offer.is_some()
+        || should_show_onboarding(login_status, &initial_config, should_show_trust_screen);
 
     let config = if should_show_onboarding {
         let onboarding_result = run_onboarding_app(
             OnboardingScreenArgs {
                 show_login_screen: should_show_login_screen(login_status, &initial_config),
                 show_trust_screen: should_show_trust_screen,
+                devcontainer_to_offer,
                 login_status,
                 auth_manager: auth_manager.clone(),
                 config: initial_config.clone(),
@@ -437,11 +426,9 @@ async fn run_ratatui_app(
                 session_lines: Vec::new(),
             });
         }
-        // if the user acknowledged windows or made an explicit decision ato trust the directory, reload the config accordingly
-        if onboarding_result
-            .directory_trust_decision
-            .map(|d| d == TrustDirectorySelection::Trust)
-            .unwrap_or(false)
+        // if the user made an explicit trust decision for the directory, reload the config accordingly
+        if onboarding_result.directory_trust_decision.is_some()
+            || onboarding_result.devcontainer_decision == Some(DevcontainerSelection::UseContainer)
         {
             load_config_or_exit(cli_kv_overrides, overrides).await
         } else {
@@ -537,6 +524,9 @@ async fn run_ratatui_app(
     let use_alt_screen = if no_alt_screen {
         // CLI flag explicitly disables alternate screen
         false
+    } else if config.tui_screen_reader {
+        // Screen readers follow the scrollback, which the alternate screen hides.
+        false
     } else {
         match config.tui_alternate_screen {
             AltScreenMode::Always => true,
@@ -649,6 +639,20 @@ fn should_show_trust_screen(config: &Config) -> bool {
     config.active_project.trust_level.is_none()
 }
 
+/// The project's `devcontainer.json`, if there is one and the user has not
+/// yet been asked whether to run commands in it. Only trusted projects are
+/// offered one, since starting the container runs the project's setup
+/// commands.
+fn devcontainer_to_offer(config: &Config) -> Option<PathBuf> {
+    if config.exec_backend != ExecBackendConfig::Local
+        || !config.active_project.is_trusted()
+        || config.active_project.devcontainer.is_some()
+    {
+        return None;
+    }
+    find_devcontainer_config(&config.cwd)
+}
+
 fn should_show_onboarding(
     login_status: LoginStatus,
     config: &Config,
@@ -692,7 +696,7 @@ mod tests {
         let temp_dir = TempDir::new()?;
         let mut config = build_config(&temp_dir).await?;
         config.did_user_set_custom_approval_policy_or_sandbox_mode = false;
-        config.active_project = ProjectConfig { trust_level: None };
+        config.active_project = ProjectConfig::default();
         config.set_windows_sandbox_globally(false);
 
         let should_show = should_show_trust_screen(&config);
@@ -715,7 +719,7 @@ mod tests {
         let temp_dir = TempDir::new()?;
         let mut config = build_config(&temp_dir).await?;
         config.did_user_set_custom_approval_policy_or_sandbox_mode = false;
-        config.active_project = ProjectConfig { trust_level: None };
+        config.active_project = ProjectConfig::default();
         config.set_windows_sandbox_globally(true);
 
         let should_show = should_show_trust_screen(&config);
@@ -740,6 +744,7 @@ mod tests {
         config.did_user_set_custom_approval_policy_or_sandbox_mode = false;
         config.active_project = ProjectConfig {
             trust_level: Some(TrustLevel::Untrusted),
+            ..Default::default()
         };
 
         let should_show = should_show_trust_screen(&config);
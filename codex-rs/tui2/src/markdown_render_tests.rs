@@ -1,5 +1,6 @@
 use pretty_assertions::assert_eq;
 use ratatui::style::Color;
+use ratatui::style::Modifier;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::text::Span;
@@ -646,11 +647,33 @@ fn link() {
 
 #[test]
 fn code_block_unhighlighted() {
-    let text = render_markdown_text("```rust\nfn main() {}\n```\n");
+    let text = render_markdown_text("```text\nfn main() {}\n```\n");
     let expected = Text::from_iter([Line::from_iter(["", "fn main() {}"]).cyan()]);
     assert_eq!(text, expected);
 }
 
+#[test]
+fn code_block_highlighted_by_language() {
+    let text = render_markdown_text("```rust\n// entry\nfn main() {}\n```\n");
+    let lines: Vec<String> = text
+        .lines
+        .iter()
+        .map(|line| {
+            line.spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect::<String>()
+        })
+        .collect();
+    assert_eq!(lines, vec!["// entry", "fn main() {}"]);
+    let comment = text.lines[0]
+        .spans
+        .iter()
+        .find(|span| span.content.contains("// entry"))
+        .expect("comment span");
+    assert!(comment.style.add_modifier.contains(Modifier::DIM));
+}
+
 #[test]
 fn code_block_multiple_lines_root() {
     let md = "```\nfirst\nsecond\n```\n";
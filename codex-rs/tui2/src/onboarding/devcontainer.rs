@@ -0,0 +1,202 @@
+use std::path::PathBuf;
+
+use codex_core::config::edit::ConfigEditsBuilder;
+use codex_core::git_info::resolve_root_git_project_for_trust;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::WidgetRef;
+use ratatui::widgets::Wrap;
+
+use crate::key_hint;
+use crate::onboarding::onboarding_screen::KeyboardHandler;
+use crate::onboarding::onboarding_screen::StepStateProvider;
+use crate::render::Insets;
+use crate::render::renderable::ColumnRenderable;
+use crate::render::renderable::Renderable;
+use crate::render::renderable::RenderableExt as _;
+use crate::selection_list::selection_option_row;
+
+use super::onboarding_screen::StepState;
+
+/// Offers to run commands in the dev container the project defines.
+pub(crate) struct DevcontainerWidget {
+    pub codex_home: PathBuf,
+    pub cwd: PathBuf,
+    /// The `devcontainer.json` that was found.
+    pub config: PathBuf,
+    pub selection: Option<DevcontainerSelection>,
+    pub highlighted: DevcontainerSelection,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DevcontainerSelection {
+    UseContainer,
+    RunLocally,
+}
+
+impl WidgetRef for &DevcontainerWidget {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let mut column = ColumnRenderable::new();
+
+        let config = self
+            .config
+            .strip_prefix(&self.cwd)
+            .unwrap_or(&self.config)
+            .display()
+            .to_string();
+        column.push(Line::from(vec![
+            "> ".into(),
+            "This project defines a dev container in ".bold(),
+            config.into(),
+        ]));
+        column.push("");
+        column.push(
+            Paragraph::new(
+                "Running commands inside it gives Codex the project's own toolchain instead of \
+                 the one installed here. This needs Docker and the devcontainer CLI, and starting \
+                 the container runs the setup commands in devcontainer.json; the first start may \
+                 build the image.",
+            )
+            .wrap(Wrap { trim: true })
+            .inset(Insets::tlbr(0, 2, 0, 0)),
+        );
+        column.push("");
+
+        let options = [
+            (
+                "Yes, run commands in the dev container",
+                DevcontainerSelection::UseContainer,
+            ),
+            (
+                "No, run commands on this machine",
+                DevcontainerSelection::RunLocally,
+            ),
+        ];
+        for (idx, (text, selection)) in options.iter().enumerate() {
+            column.push(selection_option_row(
+                idx,
+                text.to_string(),
+                self.highlighted == *selection,
+            ));
+        }
+
+        column.push("");
+
+        if let Some(error) = &self.error {
+            column.push(
+                Paragraph::new(error.to_string())
+                    .red()
+                    .wrap(Wrap { trim: true })
+                    .inset(Insets::tlbr(0, 2, 0, 0)),
+            );
+            column.push("");
+        }
+
+        column.push(
+            Line::from(vec![
+                "Press ".dim(),
+                key_hint::plain(KeyCode::Enter).into(),
+                " to continue".dim(),
+            ])
+            .inset(Insets::tlbr(0, 2, 0, 0)),
+        );
+
+        column.render(area, buf);
+    }
+}
+
+impl KeyboardHandler for DevcontainerWidget {
+    fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if key_event.kind == KeyEventKind::Release {
+            return;
+        }
+
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.highlighted = DevcontainerSelection::UseContainer;
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.highlighted = DevcontainerSelection::RunLocally;
+            }
+            KeyCode::Char('1') | KeyCode::Char('y') => {
+                self.select(DevcontainerSelection::UseContainer)
+            }
+            KeyCode::Char('2') | KeyCode::Char('n') => {
+                self.select(DevcontainerSelection::RunLocally)
+            }
+            KeyCode::Enter => self.select(self.highlighted),
+            _ => {}
+        }
+    }
+}
+
+impl StepStateProvider for DevcontainerWidget {
+    fn get_step_state(&self) -> StepState {
+        match self.selection {
+            Some(_) => StepState::Complete,
+            None => StepState::InProgress,
+        }
+    }
+}
+
+impl DevcontainerWidget {
+    /// Records the choice for the project so Codex does not ask again.
+    fn select(&mut self, selection: DevcontainerSelection) {
+        self.highlighted = selection;
+        let target =
+            resolve_root_git_project_for_trust(&self.cwd).unwrap_or_else(|| self.cwd.clone());
+        let enabled = selection == DevcontainerSelection::UseContainer;
+        if let Err(e) = ConfigEditsBuilder::new(&self.codex_home)
+            .set_project_devcontainer(&target, enabled)
+            .apply_blocking()
+        {
+            tracing::error!("Failed to save the dev container choice: {e:?}");
+            self.error = Some(format!(
+                "Failed to save the dev container choice for {}: {e}",
+                target.display()
+            ));
+        }
+
+        self.selection = Some(selection);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::config::CONFIG_TOML_FILE;
+    use crossterm::event::KeyModifiers;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[test]
+    fn choice_is_saved_for_the_project() {
+        let codex_home = TempDir::new().expect("temp home");
+        let cwd = TempDir::new().expect("temp cwd");
+        let mut widget = DevcontainerWidget {
+            codex_home: codex_home.path().to_path_buf(),
+            cwd: cwd.path().to_path_buf(),
+            config: cwd.path().join(".devcontainer/devcontainer.json"),
+            selection: None,
+            highlighted: DevcontainerSelection::UseContainer,
+            error: None,
+        };
+
+        widget.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        assert_eq!(widget.selection, None);
+        widget.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert_eq!(widget.selection, Some(DevcontainerSelection::UseContainer));
+        assert_eq!(widget.error, None);
+
+        let contents =
+            std::fs::read_to_string(codex_home.path().join(CONFIG_TOML_FILE)).expect("read config");
+        assert!(contents.contains("devcontainer = true"), "{contents}");
+    }
+}
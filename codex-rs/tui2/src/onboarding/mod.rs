@@ -1,4 +1,6 @@
 mod auth;
+mod devcontainer;
+pub use devcontainer::DevcontainerSelection;
 pub mod onboarding_screen;
 mod trust_directory;
 pub use trust_directory::TrustDirectorySelection;
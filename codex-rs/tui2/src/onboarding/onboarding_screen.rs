@@ -17,6 +17,8 @@ use codex_protocol::config_types::ForcedLoginMethod;
 use crate::LoginStatus;
 use crate::onboarding::auth::AuthModeWidget;
 use crate::onboarding::auth::SignInState;
+use crate::onboarding::devcontainer::DevcontainerSelection;
+use crate::onboarding::devcontainer::DevcontainerWidget;
 use crate::onboarding::trust_directory::TrustDirectorySelection;
 use crate::onboarding::trust_directory::TrustDirectoryWidget;
 use crate::onboarding::welcome::WelcomeWidget;
@@ -24,6 +26,7 @@ use crate::tui::FrameRequester;
 use crate::tui::Tui;
 use crate::tui::TuiEvent;
 use color_eyre::eyre::Result;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::RwLock;
 
@@ -32,6 +35,7 @@ enum Step {
     Welcome(WelcomeWidget),
     Auth(AuthModeWidget),
     TrustDirectory(TrustDirectoryWidget),
+    Devcontainer(DevcontainerWidget),
 }
 
 pub(crate) trait KeyboardHandler {
@@ -60,6 +64,9 @@ pub(crate) struct OnboardingScreen {
 pub(crate) struct OnboardingScreenArgs {
     pub show_trust_screen: bool,
     pub show_login_screen: bool,
+    /// The project's `devcontainer.json`, when Codex should offer to run
+    /// commands in it.
+    pub devcontainer_to_offer: Option<PathBuf>,
     pub login_status: LoginStatus,
     pub auth_manager: Arc<AuthManager>,
     pub config: Config,
@@ -67,6 +74,7 @@ pub(crate) struct OnboardingScreenArgs {
 
 pub(crate) struct OnboardingResult {
     pub directory_trust_decision: Option<TrustDirectorySelection>,
+    pub devcontainer_decision: Option<DevcontainerSelection>,
     pub should_exit: bool,
 }
 
@@ -75,6 +83,7 @@ impl OnboardingScreen {
         let OnboardingScreenArgs {
             show_trust_screen,
             show_login_screen,
+            devcontainer_to_offer,
             login_status,
             auth_manager,
             config,
@@ -118,14 +127,24 @@ impl OnboardingScreen {
         };
         if show_trust_screen {
             steps.push(Step::TrustDirectory(TrustDirectoryWidget {
-                cwd,
-                codex_home,
+                cwd: cwd.clone(),
+                codex_home: codex_home.clone(),
                 is_git_repo,
                 selection: None,
                 highlighted,
                 error: None,
             }))
         }
+        if let Some(config) = devcontainer_to_offer {
+            steps.push(Step::Devcontainer(DevcontainerWidget {
+                codex_home,
+                cwd,
+                config,
+                selection: None,
+                highlighted: DevcontainerSelection::UseContainer,
+                error: None,
+            }))
+        }
         // TODO: add git warning.
         Self {
             request_frame: tui.frame_requester(),
@@ -192,6 +211,13 @@ impl OnboardingScreen {
             .flatten()
     }
 
+    pub fn devcontainer_decision(&self) -> Option<DevcontainerSelection> {
+        self.steps.iter().find_map(|step| match step {
+            Step::Devcontainer(DevcontainerWidget { selection, .. }) => *selection,
+            _ => None,
+        })
+    }
+
     pub fn should_exit(&self) -> bool {
         self.should_exit
     }
@@ -335,6 +361,7 @@ impl KeyboardHandler for Step {
             Step::Welcome(widget) => widget.handle_key_event(key_event),
             Step::Auth(widget) => widget.handle_key_event(key_event),
             Step::TrustDirectory(widget) => widget.handle_key_event(key_event),
+            Step::Devcontainer(widget) => widget.handle_key_event(key_event),
         }
     }
 
@@ -343,6 +370,7 @@ impl KeyboardHandler for Step {
             Step::Welcome(_) => {}
             Step::Auth(widget) => widget.handle_paste(pasted),
             Step::TrustDirectory(widget) => widget.handle_paste(pasted),
+            Step::Devcontainer(widget) => widget.handle_paste(pasted),
         }
     }
 }
@@ -353,6 +381,7 @@ impl StepStateProvider for Step {
             Step::Welcome(w) => w.get_step_state(),
             Step::Auth(w) => w.get_step_state(),
             Step::TrustDirectory(w) => w.get_step_state(),
+            Step::Devcontainer(w) => w.get_step_state(),
         }
     }
 }
@@ -369,6 +398,9 @@ impl WidgetRef for Step {
             Step::TrustDirectory(widget) => {
                 widget.render_ref(area, buf);
             }
+            Step::Devcontainer(widget) => {
+                widget.render_ref(area, buf);
+            }
         }
     }
 }
@@ -440,6 +472,7 @@ pub(crate) async fn run_onboarding_app(
     }
     Ok(OnboardingResult {
         directory_trust_decision: onboarding_screen.directory_trust_decision(),
+        devcontainer_decision: onboarding_screen.devcontainer_decision(),
         should_exit: onboarding_screen.should_exit(),
     })
 }
@@ -36,7 +36,27 @@ pub(crate) struct TrustDirectoryWidget {
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TrustDirectorySelection {
     Trust,
+    ReadOnly,
     DontTrust,
+    Deny,
+}
+
+impl TrustDirectorySelection {
+    const ALL: [TrustDirectorySelection; 4] = [
+        TrustDirectorySelection::Trust,
+        TrustDirectorySelection::ReadOnly,
+        TrustDirectorySelection::DontTrust,
+        TrustDirectorySelection::Deny,
+    ];
+
+    fn trust_level(self) -> TrustLevel {
+        match self {
+            TrustDirectorySelection::Trust => TrustLevel::Trusted,
+            TrustDirectorySelection::ReadOnly => TrustLevel::ReadOnly,
+            TrustDirectorySelection::DontTrust => TrustLevel::Untrusted,
+            TrustDirectorySelection::Deny => TrustLevel::Deny,
+        }
+    }
 }
 
 impl WidgetRef for &TrustDirectoryWidget {
@@ -62,6 +82,13 @@ impl WidgetRef for &TrustDirectoryWidget {
                 .inset(Insets::tlbr(0, 2, 0, 0)),
         );
         column.push("");
+        column.push(
+            Paragraph::new("Only trusted folders can load their own .codex/ config and hooks.")
+                .dim()
+                .wrap(Wrap { trim: true })
+                .inset(Insets::tlbr(0, 2, 0, 0)),
+        );
+        column.push("");
 
         let mut options: Vec<(&str, TrustDirectorySelection)> = Vec::new();
         if self.is_git_repo {
@@ -69,19 +96,35 @@ impl WidgetRef for &TrustDirectoryWidget {
                 "Yes, allow Codex to work in this folder without asking for approval",
                 TrustDirectorySelection::Trust,
             ));
+            options.push((
+                "Read-only, let Codex read this folder but not change it",
+                TrustDirectorySelection::ReadOnly,
+            ));
             options.push((
                 "No, ask me to approve edits and commands",
                 TrustDirectorySelection::DontTrust,
             ));
+            options.push((
+                "Deny, ignore its AGENTS.md and ask before every command",
+                TrustDirectorySelection::Deny,
+            ));
         } else {
             options.push((
                 "Allow Codex to work in this folder without asking for approval",
                 TrustDirectorySelection::Trust,
             ));
+            options.push((
+                "Let Codex read this folder but not change it",
+                TrustDirectorySelection::ReadOnly,
+            ));
             options.push((
                 "Require approval of edits and commands",
                 TrustDirectorySelection::DontTrust,
             ));
+            options.push((
+                "Don't trust this folder; ignore its AGENTS.md and ask first",
+                TrustDirectorySelection::Deny,
+            ));
         }
 
         for (idx, (text, selection)) in options.iter().enumerate() {
@@ -124,18 +167,17 @@ impl KeyboardHandler for TrustDirectoryWidget {
         }
 
         match key_event.code {
-            KeyCode::Up | KeyCode::Char('k') => {
-                self.highlighted = TrustDirectorySelection::Trust;
+            KeyCode::Up | KeyCode::Char('k') => self.move_highlight(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_highlight(1),
+            KeyCode::Char('1') | KeyCode::Char('y') => self.select(TrustDirectorySelection::Trust),
+            KeyCode::Char('2') | KeyCode::Char('r') => {
+                self.select(TrustDirectorySelection::ReadOnly)
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                self.highlighted = TrustDirectorySelection::DontTrust;
+            KeyCode::Char('3') | KeyCode::Char('n') => {
+                self.select(TrustDirectorySelection::DontTrust)
             }
-            KeyCode::Char('1') | KeyCode::Char('y') => self.handle_trust(),
-            KeyCode::Char('2') | KeyCode::Char('n') => self.handle_dont_trust(),
-            KeyCode::Enter => match self.highlighted {
-                TrustDirectorySelection::Trust => self.handle_trust(),
-                TrustDirectorySelection::DontTrust => self.handle_dont_trust(),
-            },
+            KeyCode::Char('4') | KeyCode::Char('d') => self.select(TrustDirectorySelection::Deny),
+            KeyCode::Enter => self.select(self.highlighted),
             _ => {}
         }
     }
@@ -151,30 +193,28 @@ impl StepStateProvider for TrustDirectoryWidget {
 }
 
 impl TrustDirectoryWidget {
-    fn handle_trust(&mut self) {
-        let target =
-            resolve_root_git_project_for_trust(&self.cwd).unwrap_or_else(|| self.cwd.clone());
-        if let Err(e) = set_project_trust_level(&self.codex_home, &target, TrustLevel::Trusted) {
-            tracing::error!("Failed to set project trusted: {e:?}");
-            self.error = Some(format!("Failed to set trust for {}: {e}", target.display()));
-        }
-
-        self.selection = Some(TrustDirectorySelection::Trust);
+    fn move_highlight(&mut self, delta: isize) {
+        let options = TrustDirectorySelection::ALL;
+        let current = options
+            .iter()
+            .position(|option| *option == self.highlighted)
+            .unwrap_or(0);
+        let next = current.saturating_add_signed(delta).min(options.len() - 1);
+        self.highlighted = options[next];
     }
 
-    fn handle_dont_trust(&mut self) {
-        self.highlighted = TrustDirectorySelection::DontTrust;
+    /// Records the decision for the project so Codex does not ask again.
+    fn select(&mut self, selection: TrustDirectorySelection) {
+        self.highlighted = selection;
         let target =
             resolve_root_git_project_for_trust(&self.cwd).unwrap_or_else(|| self.cwd.clone());
-        if let Err(e) = set_project_trust_level(&self.codex_home, &target, TrustLevel::Untrusted) {
-            tracing::error!("Failed to set project untrusted: {e:?}");
-            self.error = Some(format!(
-                "Failed to set untrusted for {}: {e}",
-                target.display()
-            ));
+        let level = selection.trust_level();
+        if let Err(e) = set_project_trust_level(&self.codex_home, &target, level) {
+            tracing::error!("Failed to set project trust level to {level}: {e:?}");
+            self.error = Some(format!("Failed to set trust for {}: {e}", target.display()));
         }
 
-        self.selection = Some(TrustDirectorySelection::DontTrust);
+        self.selection = Some(selection);
     }
 }
 
@@ -216,6 +256,48 @@ mod tests {
         assert_eq!(widget.selection, Some(TrustDirectorySelection::DontTrust));
     }
 
+    #[test]
+    fn decision_is_recorded_as_trust_level() {
+        let codex_home = TempDir::new().expect("temp home");
+        let cwd = TempDir::new().expect("temp cwd");
+        let mut widget = TrustDirectoryWidget {
+            codex_home: codex_home.path().to_path_buf(),
+            cwd: cwd.path().to_path_buf(),
+            is_git_repo: false,
+            selection: None,
+            highlighted: TrustDirectorySelection::Trust,
+            error: None,
+        };
+
+        widget.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        assert_eq!(widget.highlighted, TrustDirectorySelection::ReadOnly);
+        widget.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(widget.selection, Some(TrustDirectorySelection::ReadOnly));
+        assert_eq!(widget.error, None);
+
+        let contents =
+            std::fs::read_to_string(codex_home.path().join("config.toml")).expect("read config");
+        assert!(
+            contents.contains(r#"trust_level = "read-only""#),
+            "{contents}"
+        );
+
+        widget.handle_key_event(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE));
+        assert_eq!(widget.selection, Some(TrustDirectorySelection::DontTrust));
+        let contents =
+            std::fs::read_to_string(codex_home.path().join("config.toml")).expect("read config");
+        assert!(
+            contents.contains(r#"trust_level = "untrusted""#),
+            "{contents}"
+        );
+
+        widget.handle_key_event(KeyEvent::new(KeyCode::Char('4'), KeyModifiers::NONE));
+        assert_eq!(widget.selection, Some(TrustDirectorySelection::Deny));
+        let contents =
+            std::fs::read_to_string(codex_home.path().join("config.toml")).expect("read config");
+        assert!(contents.contains(r#"trust_level = "deny""#), "{contents}");
+    }
+
     #[test]
     fn renders_snapshot_for_git_repo() {
         let codex_home = TempDir::new().expect("temp home");
@@ -8,9 +8,11 @@ use tree_sitter_highlight::HighlightConfiguration;
 use tree_sitter_highlight::HighlightEvent;
 use tree_sitter_highlight::Highlighter;
 
-// Ref: https://github.com/tree-sitter/tree-sitter-bash/blob/master/queries/highlights.scm
+// Refs: the `queries/highlights.scm` of each grammar, e.g.
+// https://github.com/tree-sitter/tree-sitter-bash/blob/master/queries/highlights.scm
+// Captures such as `function.method` resolve to the longest matching name.
 #[derive(Copy, Clone)]
-enum BashHighlight {
+enum CodeHighlight {
     Comment,
     Constant,
     Embedded,
@@ -22,7 +24,7 @@ enum BashHighlight {
     String,
 }
 
-impl BashHighlight {
+impl CodeHighlight {
     const ALL: [Self; 9] = [
         Self::Comment,
         Self::Constant,
@@ -57,34 +59,90 @@ impl BashHighlight {
     }
 }
 
-static HIGHLIGHT_CONFIG: OnceLock<HighlightConfiguration> = OnceLock::new();
+/// Languages with a tree-sitter grammar in the workspace.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum CodeLanguage {
+    Bash,
+    Rust,
+    Python,
+    JavaScript,
+    Go,
+}
+
+impl CodeLanguage {
+    const COUNT: usize = 5;
+
+    /// The language of a fenced code block whose info string starts with
+    /// `lang` (e.g. `rust` or `python title="x"`).
+    fn from_info_string(lang: &str) -> Option<Self> {
+        let lang = lang
+            .split(|c: char| c == ',' || c == '{' || c.is_whitespace())
+            .next()
+            .unwrap_or_default();
+        match lang.to_ascii_lowercase().as_str() {
+            "bash" | "sh" | "shell" | "zsh" | "console" => Some(Self::Bash),
+            "rust" | "rs" => Some(Self::Rust),
+            "python" | "py" | "python3" => Some(Self::Python),
+            "javascript" | "js" | "jsx" | "mjs" | "cjs" => Some(Self::JavaScript),
+            "go" | "golang" => Some(Self::Go),
+            _ => None,
+        }
+    }
+}
 
 fn highlight_names() -> &'static [&'static str] {
-    static NAMES: OnceLock<[&'static str; BashHighlight::ALL.len()]> = OnceLock::new();
+    static NAMES: OnceLock<[&'static str; CodeHighlight::ALL.len()]> = OnceLock::new();
     NAMES
-        .get_or_init(|| BashHighlight::ALL.map(BashHighlight::as_str))
+        .get_or_init(|| CodeHighlight::ALL.map(CodeHighlight::as_str))
         .as_slice()
 }
 
-fn highlight_config() -> &'static HighlightConfiguration {
-    HIGHLIGHT_CONFIG.get_or_init(|| {
-        let language = tree_sitter_bash::LANGUAGE.into();
+fn highlight_config(language: CodeLanguage) -> &'static HighlightConfiguration {
+    static CONFIGS: [OnceLock<HighlightConfiguration>; CodeLanguage::COUNT] =
+        [const { OnceLock::new() }; CodeLanguage::COUNT];
+    CONFIGS[language as usize].get_or_init(|| {
+        let (grammar, name, highlights, locals) = match language {
+            CodeLanguage::Bash => (
+                tree_sitter_bash::LANGUAGE.into(),
+                "bash",
+                tree_sitter_bash::HIGHLIGHT_QUERY,
+                "",
+            ),
+            CodeLanguage::Rust => (
+                tree_sitter_rust::LANGUAGE.into(),
+                "rust",
+                tree_sitter_rust::HIGHLIGHTS_QUERY,
+                "",
+            ),
+            CodeLanguage::Python => (
+                tree_sitter_python::LANGUAGE.into(),
+                "python",
+                tree_sitter_python::HIGHLIGHTS_QUERY,
+                "",
+            ),
+            CodeLanguage::JavaScript => (
+                tree_sitter_javascript::LANGUAGE.into(),
+                "javascript",
+                tree_sitter_javascript::HIGHLIGHT_QUERY,
+                tree_sitter_javascript::LOCALS_QUERY,
+            ),
+            CodeLanguage::Go => (
+                tree_sitter_go::LANGUAGE.into(),
+                "go",
+                tree_sitter_go::HIGHLIGHTS_QUERY,
+                "",
+            ),
+        };
         #[expect(clippy::expect_used)]
-        let mut config = HighlightConfiguration::new(
-            language,
-            "bash",
-            tree_sitter_bash::HIGHLIGHT_QUERY,
-            "",
-            "",
-        )
-        .expect("load bash highlight query");
+        let mut config = HighlightConfiguration::new(grammar, name, highlights, "", locals)
+            .expect("load bundled highlight query");
         config.configure(highlight_names());
         config
     })
 }
 
-fn highlight_for(highlight: Highlight) -> BashHighlight {
-    BashHighlight::ALL[highlight.0]
+fn highlight_for(highlight: Highlight) -> CodeHighlight {
+    CodeHighlight::ALL[highlight.0]
 }
 
 fn push_segment(lines: &mut Vec<Line<'static>>, segment: &str, style: Option<Style>) {
@@ -109,11 +167,24 @@ fn push_segment(lines: &mut Vec<Line<'static>>, segment: &str, style: Option<Sty
 /// bash highlight query. The highlighter is streamed so multi-line content is
 /// split into `Line`s while preserving style boundaries.
 pub(crate) fn highlight_bash_to_lines(script: &str) -> Vec<Line<'static>> {
+    highlight_to_lines(CodeLanguage::Bash, script)
+}
+
+/// Highlights the contents of a fenced code block whose info string starts
+/// with `lang`. `None` when there is no grammar for the language, in which
+/// case the block is shown as is.
+pub(crate) fn highlight_code_to_lines(code: &str, lang: &str) -> Option<Vec<Line<'static>>> {
+    CodeLanguage::from_info_string(lang).map(|language| highlight_to_lines(language, code))
+}
+
+fn highlight_to_lines(language: CodeLanguage, source: &str) -> Vec<Line<'static>> {
     let mut highlighter = Highlighter::new();
     let iterator =
-        match highlighter.highlight(highlight_config(), script.as_bytes(), None, |_| None) {
+        match highlighter.highlight(highlight_config(language), source.as_bytes(), None, |_| {
+            None
+        }) {
             Ok(iter) => iter,
-            Err(_) => return vec![script.to_string().into()],
+            Err(_) => return vec![source.to_string().into()],
         };
 
     let mut lines: Vec<Line<'static>> = vec![Line::from("")];
@@ -130,9 +201,9 @@ pub(crate) fn highlight_bash_to_lines(script: &str) -> Vec<Line<'static>> {
                     continue;
                 }
                 let style = highlight_stack.last().map(|h| highlight_for(*h).style());
-                push_segment(&mut lines, &script[start..end], style);
+                push_segment(&mut lines, &source[start..end], style);
             }
-            Err(_) => return vec![script.to_string().into()],
+            Err(_) => return vec![source.to_string().into()],
         }
     }
 
@@ -233,4 +304,36 @@ mod tests {
         let body_style = body_style.expect("missing heredoc span");
         assert!(body_style.add_modifier.contains(Modifier::DIM));
     }
+
+    #[test]
+    fn highlights_code_blocks_by_language() {
+        let s = "fn main() {\n    // greet\n    println!(\"hi {}\", 1 + 2);\n}";
+        let lines = highlight_code_to_lines(s, "rust").expect("rust has a grammar");
+        assert_eq!(reconstructed(&lines), s);
+        let dimmed = dimmed_tokens(&lines);
+        assert!(dimmed.contains(&"// greet".to_string()), "{dimmed:?}");
+        assert!(dimmed.contains(&"\"hi {}\"".to_string()), "{dimmed:?}");
+        assert!(!dimmed.contains(&"fn".to_string()), "{dimmed:?}");
+
+        let s = "package main\n\n// entry\nfunc main() { fmt.Println(\"hi\") }";
+        let lines = highlight_code_to_lines(s, "go").expect("go has a grammar");
+        assert_eq!(reconstructed(&lines), s);
+        let dimmed = dimmed_tokens(&lines);
+        assert!(dimmed.contains(&"// entry".to_string()), "{dimmed:?}");
+        assert!(dimmed.contains(&"\"hi\"".to_string()), "{dimmed:?}");
+    }
+
+    #[test]
+    fn code_block_language_comes_from_the_info_string() {
+        let s = "x = 'a # b'  # note";
+        let lines = highlight_code_to_lines(s, "python title=\"x.py\"").expect("python");
+        assert_eq!(reconstructed(&lines), s);
+        let dimmed = dimmed_tokens(&lines);
+        assert!(dimmed.contains(&"'a # b'".to_string()), "{dimmed:?}");
+        assert!(dimmed.contains(&"# note".to_string()), "{dimmed:?}");
+
+        assert!(highlight_code_to_lines("echo hi | wc", "sh").is_some());
+        assert!(highlight_code_to_lines("const x = `hi`;", "js").is_some());
+        assert_eq!(highlight_code_to_lines("SELECT 1", "sql"), None);
+    }
 }
@@ -19,6 +19,7 @@ pub enum SlashCommand {
     Skills,
     Review,
     New,
+    Tabs,
     Resume,
     Init,
     Compact,
@@ -26,7 +27,10 @@ pub enum SlashCommand {
     Diff,
     Mention,
     Status,
+    Stats,
+    Sources,
     Mcp,
+    Hooks,
     Logout,
     Quit,
     Exit,
@@ -45,16 +49,20 @@ impl SlashCommand {
             SlashCommand::Compact => "summarize conversation to prevent hitting the context limit",
             SlashCommand::Review => "review my current changes and find issues",
             SlashCommand::Resume => "resume a saved chat",
+            SlashCommand::Tabs => "switch between, open, or close conversation tabs",
             // SlashCommand::Undo => "ask Codex to undo a turn",
             SlashCommand::Quit | SlashCommand::Exit => "exit Codex",
-            SlashCommand::Diff => "show git diff (including untracked files)",
+            SlashCommand::Diff => "show git diff, or `/diff last` for the last turn's changes",
             SlashCommand::Mention => "mention a file",
             SlashCommand::Skills => "use skills to improve how Codex performs specific tasks",
             SlashCommand::Status => "show current session configuration and token usage",
+            SlashCommand::Stats => "show tokens, estimated cost, and time used this session",
+            SlashCommand::Sources => "list the files, pages, and MCP servers the model was given",
             SlashCommand::Model => "choose what model and reasoning effort to use",
             SlashCommand::Approvals => "choose what Codex can do without approval",
             SlashCommand::ElevateSandbox => "set up elevated agent sandbox",
             SlashCommand::Mcp => "list configured MCP tools",
+            SlashCommand::Hooks => "list hooks and enable or disable them for this session",
             SlashCommand::Logout => "log out of Codex",
             SlashCommand::Rollout => "print the rollout file path",
             SlashCommand::TestApproval => "test approval request",
@@ -84,7 +92,11 @@ impl SlashCommand {
             | SlashCommand::Mention
             | SlashCommand::Skills
             | SlashCommand::Status
+            | SlashCommand::Stats
+            | SlashCommand::Sources
             | SlashCommand::Mcp
+            | SlashCommand::Hooks
+            | SlashCommand::Tabs
             | SlashCommand::Feedback
             | SlashCommand::Quit
             | SlashCommand::Exit => true,
@@ -98,6 +98,12 @@ impl StreamController {
         (self.emit(step), self.state.is_idle())
     }
 
+    /// Commit every queued logical line at once, skipping the commit-tick animation.
+    pub(crate) fn commit_queued(&mut self) -> Option<Box<dyn HistoryCell>> {
+        let lines = self.state.drain_all();
+        self.emit(lines)
+    }
+
     fn emit(&mut self, lines: Vec<MarkdownLogicalLine>) -> Option<Box<dyn HistoryCell>> {
         if lines.is_empty() {
             return None;
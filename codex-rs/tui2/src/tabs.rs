@@ -0,0 +1,341 @@
+//! Several conversations in one TUI.
+//!
+//! The active tab's widget and transcript live on [`App`](crate::app::App)
+//! as usual; every other tab is parked here with its own [`ChatWidget`] and
+//! keeps running in the background. Each tab's widget sends its app events
+//! through an [`AppEventSender`](crate::app_event_sender::AppEventSender)
+//! tagged with the tab id, so the app can tell which conversation an event
+//! belongs to and route events for parked tabs to them.
+
+use std::sync::Arc;
+
+use codex_core::protocol::EventMsg;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::WidgetRef;
+
+use crate::app_event::AppEvent;
+use crate::chatwidget::ChatWidget;
+use crate::history_cell::HistoryCell;
+use crate::history_cell::UserHistoryCell;
+use crate::text_formatting::truncate_text;
+
+pub(crate) type TabId = u64;
+
+const TAB_TITLE_GRAPHEMES: usize = 24;
+/// Title of a tab before its first user message.
+pub(crate) const UNTITLED_TAB: &str = "new conversation";
+
+/// What a conversation is doing, as shown in the tab strip and `/tabs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum TabStatus {
+    /// Idle, waiting for the user's next message.
+    #[default]
+    Waiting,
+    Running,
+    NeedsApproval,
+}
+
+impl TabStatus {
+    /// The status after the conversation emitted `msg`.
+    pub(crate) fn after(self, msg: &EventMsg) -> Self {
+        match msg {
+            EventMsg::TurnStarted(_)
+            | EventMsg::AgentMessageDelta(_)
+            | EventMsg::AgentReasoningDelta(_)
+            | EventMsg::ExecCommandBegin(_)
+            | EventMsg::PatchApplyBegin(_)
+            | EventMsg::McpToolCallBegin(_) => TabStatus::Running,
+            EventMsg::ExecApprovalRequest(_)
+            | EventMsg::ApplyPatchApprovalRequest(_)
+            | EventMsg::ElicitationRequest(_) => TabStatus::NeedsApproval,
+            EventMsg::TurnComplete(_)
+            | EventMsg::TurnAborted(_)
+            | EventMsg::Error(_)
+            | EventMsg::ShutdownComplete => TabStatus::Waiting,
+            _ => self,
+        }
+    }
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            TabStatus::Waiting => "waiting",
+            TabStatus::Running => "running",
+            TabStatus::NeedsApproval => "needs approval",
+        }
+    }
+
+    fn marker(self) -> Span<'static> {
+        match self {
+            TabStatus::Waiting => "○".dim(),
+            TabStatus::Running => "●".cyan(),
+            TabStatus::NeedsApproval => "!".magenta().bold(),
+        }
+    }
+}
+
+/// A tab that is not on screen.
+pub(crate) struct ParkedTab {
+    pub(crate) chat_widget: ChatWidget,
+    pub(crate) transcript_cells: Vec<Arc<dyn HistoryCell>>,
+    /// Events that need the screen (popups, overlays), replayed when the tab
+    /// becomes active.
+    pub(crate) deferred_events: Vec<AppEvent>,
+}
+
+impl ParkedTab {
+    pub(crate) fn new(
+        chat_widget: ChatWidget,
+        transcript_cells: Vec<Arc<dyn HistoryCell>>,
+    ) -> Self {
+        Self {
+            chat_widget,
+            transcript_cells,
+            deferred_events: Vec::new(),
+        }
+    }
+}
+
+pub(crate) struct Tab {
+    pub(crate) id: TabId,
+    pub(crate) status: TabStatus,
+    /// Whether the tab's widget is streaming output and wants commit ticks.
+    pub(crate) commit_animating: bool,
+    /// `None` for the active tab, whose state lives on the app.
+    pub(crate) parked: Option<ParkedTab>,
+}
+
+/// A tab as listed in the tab strip and the `/tabs` popup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct TabSummary {
+    pub(crate) id: TabId,
+    pub(crate) number: usize,
+    pub(crate) title: String,
+    pub(crate) status: TabStatus,
+    pub(crate) is_active: bool,
+}
+
+/// The open tabs, in display order.
+pub(crate) struct Tabs {
+    tabs: Vec<Tab>,
+    active: usize,
+    next_id: TabId,
+}
+
+impl Default for Tabs {
+    fn default() -> Self {
+        Self {
+            tabs: vec![Tab {
+                id: 0,
+                status: TabStatus::default(),
+                commit_animating: false,
+                parked: None,
+            }],
+            active: 0,
+            next_id: 1,
+        }
+    }
+}
+
+impl Tabs {
+    pub(crate) fn len(&self) -> usize {
+        self.tabs.len()
+    }
+
+    pub(crate) fn active_id(&self) -> TabId {
+        self.tabs[self.active].id
+    }
+
+    /// Id the next call to [`Tabs::open`] will use.
+    pub(crate) fn next_id(&self) -> TabId {
+        self.next_id
+    }
+
+    pub(crate) fn get_mut(&mut self, id: TabId) -> Option<&mut Tab> {
+        self.tabs.iter_mut().find(|tab| tab.id == id)
+    }
+
+    pub(crate) fn parked_mut(&mut self) -> impl Iterator<Item = &mut ParkedTab> {
+        self.tabs.iter_mut().filter_map(|tab| tab.parked.as_mut())
+    }
+
+    pub(crate) fn set_commit_animating(&mut self, id: TabId, animating: bool) {
+        if let Some(tab) = self.get_mut(id) {
+            tab.commit_animating = animating;
+        }
+    }
+
+    pub(crate) fn is_commit_animating(&self, id: TabId) -> bool {
+        self.tabs
+            .iter()
+            .any(|tab| tab.id == id && tab.commit_animating)
+    }
+
+    pub(crate) fn any_commit_animating(&self) -> bool {
+        self.tabs.iter().any(|tab| tab.commit_animating)
+    }
+
+    /// Parked tabs whose widgets are streaming output.
+    pub(crate) fn commit_animating_parked_mut(&mut self) -> impl Iterator<Item = &mut ParkedTab> {
+        self.tabs
+            .iter_mut()
+            .filter(|tab| tab.commit_animating)
+            .filter_map(|tab| tab.parked.as_mut())
+    }
+
+    pub(crate) fn set_active_status(&mut self, status: TabStatus) {
+        self.tabs[self.active].status = status;
+    }
+
+    pub(crate) fn active_status(&self) -> TabStatus {
+        self.tabs[self.active].status
+    }
+
+    /// Adds a tab after the active one and makes it active, parking the
+    /// previously active tab's state.
+    pub(crate) fn open(&mut self, previous: ParkedTab) -> TabId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.tabs[self.active].parked = Some(previous);
+        self.active += 1;
+        self.tabs.insert(
+            self.active,
+            Tab {
+                id,
+                status: TabStatus::default(),
+                commit_animating: false,
+                parked: None,
+            },
+        );
+        id
+    }
+
+    /// Takes the state of parked tab `id` so it can be put on screen; follow
+    /// up with [`Tabs::activate`].
+    pub(crate) fn take_parked(&mut self, id: TabId) -> Option<ParkedTab> {
+        self.get_mut(id)?.parked.take()
+    }
+
+    /// Makes `id` the active tab, parking `previous` in the old active slot.
+    pub(crate) fn activate(&mut self, id: TabId, previous: ParkedTab) {
+        self.tabs[self.active].parked = Some(previous);
+        if let Some(index) = self.tabs.iter().position(|tab| tab.id == id) {
+            self.active = index;
+        }
+    }
+
+    /// Id of the tab `offset` positions from the active one, wrapping around.
+    pub(crate) fn neighbor_id(&self, offset: isize) -> TabId {
+        let len = self.tabs.len() as isize;
+        let index = (self.active as isize + offset).rem_euclid(len) as usize;
+        self.tabs[index].id
+    }
+
+    /// Removes the active tab and activates its neighbor, returning the
+    /// neighbor's state. Returns `None` when it is the only tab.
+    pub(crate) fn close_active(&mut self) -> Option<ParkedTab> {
+        if self.tabs.len() < 2 {
+            return None;
+        }
+        self.tabs.remove(self.active);
+        self.active = self.active.min(self.tabs.len() - 1);
+        self.tabs[self.active].parked.take()
+    }
+
+    /// Removes a parked tab, returning its state.
+    pub(crate) fn close_parked(&mut self, id: TabId) -> Option<ParkedTab> {
+        let index = self
+            .tabs
+            .iter()
+            .position(|tab| tab.id == id && tab.parked.is_some())?;
+        let tab = self.tabs.remove(index);
+        if index < self.active {
+            self.active -= 1;
+        }
+        tab.parked
+    }
+
+    /// Summaries of every tab; `active_cells` is the active tab's transcript.
+    pub(crate) fn summaries(&self, active_cells: &[Arc<dyn HistoryCell>]) -> Vec<TabSummary> {
+        self.tabs
+            .iter()
+            .enumerate()
+            .map(|(index, tab)| {
+                let cells = tab
+                    .parked
+                    .as_ref()
+                    .map_or(active_cells, |parked| &parked.transcript_cells);
+                TabSummary {
+                    id: tab.id,
+                    number: index + 1,
+                    title: tab_title(cells).unwrap_or_else(|| UNTITLED_TAB.to_string()),
+                    status: tab.status,
+                    is_active: index == self.active,
+                }
+            })
+            .collect()
+    }
+}
+
+/// A tab is titled after its first user message.
+fn tab_title(cells: &[Arc<dyn HistoryCell>]) -> Option<String> {
+    let cell = cells
+        .iter()
+        .find_map(|cell| cell.as_any().downcast_ref::<UserHistoryCell>())?;
+    let title = cell
+        .message
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!title.is_empty()).then(|| truncate_text(&title, TAB_TITLE_GRAPHEMES))
+}
+
+/// One-line strip listing the tabs, shown above the chat when more than one
+/// tab is open.
+pub(crate) fn render_tab_strip(summaries: &[TabSummary], area: Rect, buf: &mut Buffer) {
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    for summary in summaries {
+        spans.push(" ".into());
+        spans.push(summary.status.marker());
+        let label = format!(" {} {} ", summary.number, summary.title);
+        spans.push(if summary.is_active {
+            label.reversed()
+        } else {
+            label.dim()
+        });
+    }
+    Paragraph::new(Line::from(spans)).render_ref(area, buf);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::TurnCompleteEvent;
+    use codex_core::protocol::TurnStartedEvent;
+    use codex_core::protocol::WarningEvent;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn status_follows_turn_lifecycle() {
+        let status = TabStatus::Waiting.after(&EventMsg::TurnStarted(TurnStartedEvent {
+            model_context_window: None,
+        }));
+        assert_eq!(status, TabStatus::Running);
+        let status = status.after(&EventMsg::Warning(WarningEvent {
+            message: "careful".to_string(),
+        }));
+        assert_eq!(status, TabStatus::Running);
+        let status = status.after(&EventMsg::TurnComplete(TurnCompleteEvent {
+            last_agent_message: None,
+            timing: None,
+            unified_diff: None,
+            verification: None,
+            context_dropped: Vec::new(),
+        }));
+        assert_eq!(status, TabStatus::Waiting);
+    }
+}
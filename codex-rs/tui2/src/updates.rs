@@ -5,8 +5,11 @@ use crate::update_action::UpdateAction;
 use chrono::DateTime;
 use chrono::Duration;
 use chrono::Utc;
+use codex_core::config::CONFIG_TOML_FILE;
 use codex_core::config::Config;
+use codex_core::config::types::UpdateChannel;
 use codex_core::default_client::create_client;
+use codex_core::releases;
 use serde::Deserialize;
 use serde::Serialize;
 use std::path::Path;
@@ -19,8 +22,12 @@ pub fn get_upgrade_version(config: &Config) -> Option<String> {
         return None;
     }
 
+    let channel = config.update_channel;
     let version_file = version_filepath(config);
-    let info = read_version_info(&version_file).ok();
+    // A version cached for another channel does not count.
+    let info = read_version_info(&version_file)
+        .ok()
+        .filter(|info| info.channel == channel);
 
     if match &info {
         None => true,
@@ -29,15 +36,20 @@ pub fn get_upgrade_version(config: &Config) -> Option<String> {
         // Refresh the cached latest version in the background so TUI startup
         // isn’t blocked by a network call. The UI reads the previously cached
         // value (if any) for this run; the next run shows the banner if needed.
+        let config_file = config.codex_home.join(CONFIG_TOML_FILE);
         tokio::spawn(async move {
-            check_for_update(&version_file)
+            check_for_update(&version_file, channel, &config_file)
                 .await
                 .inspect_err(|e| tracing::error!("Failed to update version: {e}"))
         });
     }
 
     info.and_then(|info| {
-        if is_newer(&info.latest_version, CODEX_CLI_VERSION).unwrap_or(false) {
+        let newer = match channel {
+            UpdateChannel::Stable => is_newer(&info.latest_version, CODEX_CLI_VERSION),
+            UpdateChannel::Beta => releases::is_newer(&info.latest_version, CODEX_CLI_VERSION),
+        };
+        if newer.unwrap_or(false) {
             Some(info.latest_version)
         } else {
             None
@@ -45,6 +57,17 @@ pub fn get_upgrade_version(config: &Config) -> Option<String> {
     })
 }
 
+/// Lines of the release notes up to the cached latest version that mention
+/// keys set in the user's `config.toml`. Notes cached by another version of
+/// Codex do not count.
+pub fn get_upgrade_notes(config: &Config) -> Vec<String> {
+    read_version_info(&version_filepath(config))
+        .ok()
+        .filter(|info| info.notes_from.as_deref() == Some(CODEX_CLI_VERSION))
+        .map(|info| info.notes)
+        .unwrap_or_default()
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct VersionInfo {
     latest_version: String,
@@ -52,6 +75,15 @@ struct VersionInfo {
     last_checked_at: DateTime<Utc>,
     #[serde(default)]
     dismissed_version: Option<String>,
+    #[serde(default)]
+    channel: UpdateChannel,
+    /// Release notes since `notes_from` that concern the user's config.
+    #[serde(default)]
+    notes: Vec<String>,
+    /// Version of Codex `notes` were collected for, `None` if they could not
+    /// be.
+    #[serde(default)]
+    notes_from: Option<String>,
 }
 
 const VERSION_FILENAME: &str = "version.json";
@@ -74,9 +106,25 @@ fn read_version_info(version_file: &Path) -> anyhow::Result<VersionInfo> {
     Ok(serde_json::from_str(&contents)?)
 }
 
-async fn check_for_update(version_file: &Path) -> anyhow::Result<()> {
-    let latest_version = match update_action::get_update_action() {
-        Some(UpdateAction::BrewUpgrade) => {
+async fn check_for_update(
+    version_file: &Path,
+    channel: UpdateChannel,
+    config_file: &Path,
+) -> anyhow::Result<()> {
+    let mut releases = None;
+    let latest_version = match (channel, update_action::get_update_action()) {
+        (UpdateChannel::Beta, _) => {
+            let fetched = releases::fetch_releases(channel)
+                .await
+                .map_err(|err| anyhow::anyhow!("Failed to fetch releases: {err}"))?;
+            let latest = fetched
+                .first()
+                .map(|release| release.version.clone())
+                .ok_or_else(|| anyhow::anyhow!("No releases found"))?;
+            releases = Some(fetched);
+            latest
+        }
+        (UpdateChannel::Stable, Some(UpdateAction::BrewUpgrade)) => {
             let cask_contents = create_client()
                 .get(HOMEBREW_CASK_URL)
                 .send()
@@ -86,7 +134,7 @@ async fn check_for_update(version_file: &Path) -> anyhow::Result<()> {
                 .await?;
             extract_version_from_cask(&cask_contents)?
         }
-        _ => {
+        (UpdateChannel::Stable, _) => {
             let ReleaseInfo {
                 tag_name: latest_tag_name,
             } = create_client()
@@ -100,12 +148,50 @@ async fn check_for_update(version_file: &Path) -> anyhow::Result<()> {
         }
     };
 
-    // Preserve any previously dismissed version if present.
+    // The notes only change with the running or the latest version, so the
+    // list of releases is fetched for them only when one of those did. They
+    // are a bonus: a failure to fetch them does not hold back the latest
+    // version.
     let prev_info = read_version_info(version_file).ok();
+    let cached_notes = prev_info.as_ref().filter(|prev| {
+        prev.channel == channel
+            && prev.latest_version == latest_version
+            && prev.notes_from.as_deref() == Some(CODEX_CLI_VERSION)
+    });
+    let (notes, notes_from) = match cached_notes {
+        Some(prev) => (prev.notes.clone(), prev.notes_from.clone()),
+        None => {
+            let releases = match releases {
+                Some(releases) => Ok(releases),
+                None => releases::fetch_releases(channel).await,
+            };
+            match releases {
+                Ok(releases) => {
+                    let config = std::fs::read_to_string(config_file)
+                        .ok()
+                        .and_then(|contents| toml::from_str::<toml::Table>(&contents).ok())
+                        .unwrap_or_default();
+                    let notes = releases::relevant_notes(
+                        &releases,
+                        CODEX_CLI_VERSION,
+                        &latest_version,
+                        &config,
+                    );
+                    (notes, Some(CODEX_CLI_VERSION.to_string()))
+                }
+                Err(_) => (Vec::new(), None),
+            }
+        }
+    };
+
+    // Preserve any previously dismissed version if present.
     let info = VersionInfo {
         latest_version,
         last_checked_at: Utc::now(),
         dismissed_version: prev_info.and_then(|p| p.dismissed_version),
+        channel,
+        notes,
+        notes_from,
     };
 
     let json_line = format!("{}\n", serde_json::to_string(&info)?);
@@ -0,0 +1,364 @@
+//! Python bindings for the Codex agent engine.
+//!
+//! The `codex` extension module wraps [`codex_sdk`]: a `Client` starts
+//! conversations, `Conversation.send` returns an iterator over the turn's
+//! events, and `Hook`s run Python callables on session events. Events are
+//! plain dicts in the shape command hooks receive as JSON.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::ReviewDecision;
+use codex_sdk::HookHandler;
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use tokio::runtime::Runtime;
+use tokio::sync::Notify;
+
+create_exception!(codex, CodexError, PyException);
+
+/// How often a blocked iterator wakes up to let Python handle signals such
+/// as Ctrl-C.
+const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+fn codex_error(err: impl ToString) -> PyErr {
+    CodexError::new_err(err.to_string())
+}
+
+fn json_loads(py: Python<'_>, json: &str) -> PyResult<PyObject> {
+    Ok(py.import("json")?.call_method1("loads", (json,))?.unbind())
+}
+
+fn json_dumps(value: &Bound<'_, PyAny>) -> PyResult<String> {
+    value
+        .py()
+        .import("json")?
+        .call_method1("dumps", (value,))?
+        .extract()
+}
+
+/// A Python callable run on session events. It receives each event as a
+/// dict; raising an exception marks the run as failed.
+#[pyclass(module = "codex", frozen)]
+struct Hook {
+    name: String,
+    callback: Py<PyAny>,
+    events: Option<Vec<String>>,
+}
+
+#[pymethods]
+impl Hook {
+    /// `events` lists the event names to run on, such as `"task_complete"`;
+    /// `None` runs the hook on every event.
+    #[new]
+    #[pyo3(signature = (name, callback, events=None))]
+    fn new(name: String, callback: Py<PyAny>, events: Option<Vec<String>>) -> Self {
+        Self {
+            name,
+            callback,
+            events,
+        }
+    }
+}
+
+struct PythonHook {
+    callback: Arc<Py<PyAny>>,
+}
+
+#[async_trait]
+impl HookHandler for PythonHook {
+    async fn handle(&self, event: &Event) -> Result<(), String> {
+        let json = serde_json::to_string(event)
+            .map_err(|err| format!("failed to serialize event: {err}"))?;
+        let callback = Arc::clone(&self.callback);
+        // Waiting for the GIL can block, so keep it off the async workers.
+        tokio::task::spawn_blocking(move || {
+            Python::with_gil(|py| {
+                let event = json_loads(py, &json)?;
+                callback.call1(py, (event,))?;
+                Ok::<_, PyErr>(())
+            })
+            .map_err(|err| err.to_string())
+        })
+        .await
+        .map_err(|err| format!("hook callback panicked: {err}"))?
+    }
+}
+
+/// Starts and resumes conversations. Settings not given here come from
+/// `config.toml` in the Codex home directory.
+#[pyclass(module = "codex", frozen)]
+struct Client {
+    client: Arc<codex_sdk::Client>,
+    runtime: Arc<Runtime>,
+}
+
+#[pymethods]
+impl Client {
+    #[new]
+    #[pyo3(signature = (
+        *,
+        codex_home=None,
+        cwd=None,
+        model=None,
+        model_provider=None,
+        profile=None,
+        approval_policy=None,
+        developer_instructions=None,
+        hooks=Vec::new(),
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        py: Python<'_>,
+        codex_home: Option<PathBuf>,
+        cwd: Option<PathBuf>,
+        model: Option<String>,
+        model_provider: Option<String>,
+        profile: Option<String>,
+        approval_policy: Option<String>,
+        developer_instructions: Option<String>,
+        hooks: Vec<PyRef<'_, Hook>>,
+    ) -> PyResult<Self> {
+        let mut builder = codex_sdk::Client::builder();
+        if let Some(codex_home) = codex_home {
+            builder = builder.codex_home(codex_home);
+        }
+        if let Some(cwd) = cwd {
+            builder = builder.cwd(cwd);
+        }
+        if let Some(model) = model {
+            builder = builder.model(model);
+        }
+        if let Some(model_provider) = model_provider {
+            builder = builder.model_provider(model_provider);
+        }
+        if let Some(profile) = profile {
+            builder = builder.profile(profile);
+        }
+        if let Some(approval_policy) = approval_policy {
+            let approval_policy =
+                serde_json::from_value(serde_json::Value::String(approval_policy))
+                    .map_err(|err| codex_error(format!("invalid approval policy: {err}")))?;
+            builder = builder.approval_policy(approval_policy);
+        }
+        if let Some(instructions) = developer_instructions {
+            builder = builder.developer_instructions(instructions);
+        }
+        for hook in hooks {
+            let mut sdk_hook = codex_sdk::Hook::new(
+                hook.name.clone(),
+                PythonHook {
+                    callback: Arc::new(hook.callback.clone_ref(py)),
+                },
+            );
+            if let Some(events) = &hook.events {
+                sdk_hook = sdk_hook.on(events.iter().cloned());
+            }
+            builder = builder.hook(sdk_hook);
+        }
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .map_err(codex_error)?;
+        let client = py
+            .allow_threads(|| runtime.block_on(builder.build()))
+            .map_err(codex_error)?;
+        Ok(Self {
+            client: Arc::new(client),
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// Starts a new conversation.
+    fn start_conversation(&self, py: Python<'_>) -> PyResult<Conversation> {
+        let conversation = py
+            .allow_threads(|| self.runtime.block_on(self.client.start_conversation()))
+            .map_err(codex_error)?;
+        Ok(Conversation::new(conversation, Arc::clone(&self.runtime)))
+    }
+
+    /// Resumes the conversation recorded at `rollout_path`.
+    fn resume_conversation(&self, py: Python<'_>, rollout_path: PathBuf) -> PyResult<Conversation> {
+        let conversation = py
+            .allow_threads(|| {
+                self.runtime
+                    .block_on(self.client.resume_conversation(rollout_path))
+            })
+            .map_err(codex_error)?;
+        Ok(Conversation::new(conversation, Arc::clone(&self.runtime)))
+    }
+}
+
+/// A conversation with the agent. Turns run one at a time; a message sent
+/// while a turn is running starts once it ends.
+#[pyclass(module = "codex", frozen)]
+struct Conversation {
+    id: String,
+    rollout_path: PathBuf,
+    conversation: Arc<tokio::sync::Mutex<Option<codex_sdk::Conversation>>>,
+    runtime: Arc<Runtime>,
+}
+
+impl Conversation {
+    fn new(conversation: codex_sdk::Conversation, runtime: Arc<Runtime>) -> Self {
+        Self {
+            id: conversation.id().to_string(),
+            rollout_path: conversation.rollout_path().to_path_buf(),
+            conversation: Arc::new(tokio::sync::Mutex::new(Some(conversation))),
+            runtime,
+        }
+    }
+}
+
+#[pymethods]
+impl Conversation {
+    #[getter]
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Where the conversation is recorded; pass it to
+    /// `Client.resume_conversation` to continue later.
+    #[getter]
+    fn rollout_path(&self) -> PathBuf {
+        self.rollout_path.clone()
+    }
+
+    /// Sends `text` as a user message and returns an iterator over the
+    /// events of the turn it starts.
+    fn send(&self, text: String) -> Turn {
+        let (events_tx, events_rx) = mpsc::channel();
+        let interrupt = Arc::new(Notify::new());
+        let (responses_tx, mut responses_rx) = tokio::sync::mpsc::unbounded_channel();
+        let conversation = Arc::clone(&self.conversation);
+        let interrupt_requested = Arc::clone(&interrupt);
+        self.runtime.spawn(async move {
+            let mut conversation = conversation.lock().await;
+            let Some(conversation) = conversation.as_mut() else {
+                let _ = events_tx.send(Err("conversation has shut down".to_string()));
+                return;
+            };
+            let mut turn = match conversation.send(text).await {
+                Ok(turn) => turn,
+                Err(err) => {
+                    let _ = events_tx.send(Err(err.to_string()));
+                    return;
+                }
+            };
+            loop {
+                tokio::select! {
+                    event = turn.next() => {
+                        let event = match event {
+                            Some(Ok(event)) => serde_json::to_string(&event)
+                                .map_err(|err| format!("failed to serialize event: {err}")),
+                            Some(Err(err)) => Err(err.to_string()),
+                            None => break,
+                        };
+                        // Keep draining if the iterator was dropped, so the
+                        // next turn starts from a finished one.
+                        let _ = events_tx.send(event);
+                    }
+                    () = interrupt_requested.notified() => {
+                        if let Err(err) = turn.interrupt().await {
+                            let _ = events_tx.send(Err(err.to_string()));
+                        }
+                    }
+                    Some((request, decision)) = responses_rx.recv() => {
+                        if let Err(err) = turn.respond(&request, decision).await {
+                            let _ = events_tx.send(Err(err.to_string()));
+                        }
+                    }
+                }
+            }
+        });
+        Turn {
+            events: Mutex::new(events_rx),
+            interrupt,
+            responses: responses_tx,
+        }
+    }
+
+    /// Shuts the conversation down, waiting for running work to stop.
+    fn shutdown(&self, py: Python<'_>) -> PyResult<()> {
+        py.allow_threads(|| {
+            self.runtime.block_on(async {
+                match self.conversation.lock().await.take() {
+                    Some(conversation) => conversation.shutdown().await,
+                    None => Ok(()),
+                }
+            })
+        })
+        .map_err(codex_error)
+    }
+}
+
+/// Iterator over the events of one turn, ending after its `task_complete` or
+/// `turn_aborted` event.
+#[pyclass(module = "codex", frozen)]
+struct Turn {
+    events: Mutex<mpsc::Receiver<Result<String, String>>>,
+    interrupt: Arc<Notify>,
+    responses: tokio::sync::mpsc::UnboundedSender<(Event, ReviewDecision)>,
+}
+
+#[pymethods]
+impl Turn {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        loop {
+            let received = py.allow_threads(|| {
+                let events = match self.events.lock() {
+                    Ok(events) => events,
+                    Err(err) => err.into_inner(),
+                };
+                events.recv_timeout(SIGNAL_CHECK_INTERVAL)
+            });
+            match received {
+                Ok(Ok(json)) => return json_loads(py, &json).map(Some),
+                Ok(Err(message)) => return Err(CodexError::new_err(message)),
+                Err(mpsc::RecvTimeoutError::Timeout) => py.check_signals()?,
+                Err(mpsc::RecvTimeoutError::Disconnected) => return Ok(None),
+            }
+        }
+    }
+
+    /// Asks the agent to stop the turn. Iteration continues with the
+    /// remaining events, ending with `turn_aborted`.
+    fn interrupt(&self) {
+        self.interrupt.notify_one();
+    }
+
+    /// Answers `request`, an `exec_approval_request` or
+    /// `apply_patch_approval_request` event of this turn, with `decision`,
+    /// such as `"approved"`, `"approved_for_session"`, `"denied"` or
+    /// `"abort"`. The turn waits for the answer.
+    fn respond(&self, request: &Bound<'_, PyAny>, decision: &Bound<'_, PyAny>) -> PyResult<()> {
+        let request: Event = serde_json::from_str(&json_dumps(request)?)
+            .map_err(|err| codex_error(format!("invalid approval request: {err}")))?;
+        let decision: ReviewDecision = serde_json::from_str(&json_dumps(decision)?)
+            .map_err(|err| codex_error(format!("invalid decision: {err}")))?;
+        self.responses
+            .send((request, decision))
+            .map_err(|_| codex_error("the turn has ended"))
+    }
+}
+
+#[pymodule]
+fn codex(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Client>()?;
+    m.add_class::<Conversation>()?;
+    m.add_class::<Hook>()?;
+    m.add_class::<Turn>()?;
+    m.add("CodexError", m.py().get_type::<CodexError>())?;
+    Ok(())
+}
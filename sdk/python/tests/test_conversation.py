@@ -0,0 +1,136 @@
+import json
+import threading
+
+import codex
+
+
+def write_mock_config(codex_home, text):
+    fixture = codex_home / "fixture.json"
+    fixture.write_text(
+        json.dumps(
+            {
+                "responses": [
+                    [
+                        {
+                            "type": "response.output_item.done",
+                            "item": {
+                                "type": "message",
+                                "role": "assistant",
+                                "content": [{"type": "output_text", "text": text}],
+                            },
+                        },
+                        {"type": "response.completed", "response": {"id": "resp-1"}},
+                    ]
+                ]
+            }
+        )
+    )
+    (codex_home / "config.toml").write_text(
+        f"[mock_provider]\nfixture = {json.dumps(str(fixture))}\n"
+    )
+
+
+def test_streams_a_scripted_turn_and_runs_hooks(tmp_path):
+    write_mock_config(tmp_path, "Hello from Python")
+    completed = threading.Event()
+    seen = []
+
+    def on_complete(event):
+        seen.append(event["msg"]["type"])
+        completed.set()
+
+    client = codex.Client(
+        codex_home=tmp_path,
+        cwd=tmp_path,
+        model_provider="mock",
+        hooks=[codex.Hook("on-complete", on_complete, events=["task_complete"])],
+    )
+    conversation = client.start_conversation()
+
+    events = list(conversation.send("Say hello"))
+
+    types = [event["msg"]["type"] for event in events]
+    assert types[-1] == "task_complete"
+    messages = [
+        event["msg"]["message"]
+        for event in events
+        if event["msg"]["type"] == "agent_message"
+    ]
+    assert messages == ["Hello from Python"]
+    # Hooks run in the background, so they may finish after the turn.
+    assert completed.wait(timeout=5)
+    assert seen == ["task_complete"]
+
+    conversation.shutdown()
+
+
+def test_send_after_shutdown_raises(tmp_path):
+    write_mock_config(tmp_path, "unused")
+    client = codex.Client(codex_home=tmp_path, cwd=tmp_path, model_provider="mock")
+    conversation = client.start_conversation()
+    conversation.shutdown()
+
+    try:
+        next(conversation.send("Too late"))
+    except codex.CodexError as err:
+        assert "shut down" in str(err)
+    else:
+        raise AssertionError("expected CodexError")
+
+
+def test_approval_requests_are_answered_through_the_turn(tmp_path):
+    fixture = tmp_path / "fixture.json"
+    fixture.write_text(
+        json.dumps(
+            {
+                "responses": [
+                    [
+                        {
+                            "type": "response.output_item.done",
+                            "item": {
+                                "type": "function_call",
+                                "call_id": "call-1",
+                                "name": "shell",
+                                "arguments": json.dumps(
+                                    {"command": ["touch", "denied.txt"]}
+                                ),
+                            },
+                        },
+                        {"type": "response.completed", "response": {"id": "resp-1"}},
+                    ],
+                    [
+                        {
+                            "type": "response.output_item.done",
+                            "item": {
+                                "type": "message",
+                                "role": "assistant",
+                                "content": [{"type": "output_text", "text": "Skipped it"}],
+                            },
+                        },
+                        {"type": "response.completed", "response": {"id": "resp-2"}},
+                    ],
+                ]
+            }
+        )
+    )
+    (tmp_path / "config.toml").write_text(
+        f"[mock_provider]\nfixture = {json.dumps(str(fixture))}\n"
+    )
+    client = codex.Client(
+        codex_home=tmp_path,
+        cwd=tmp_path,
+        model_provider="mock",
+        approval_policy="untrusted",
+    )
+    conversation = client.start_conversation()
+
+    turn = conversation.send("Create a file")
+    requests = 0
+    for event in turn:
+        if event["msg"]["type"] == "exec_approval_request":
+            requests += 1
+            turn.respond(event, "denied")
+
+    assert requests == 1
+    assert not (tmp_path / "denied.txt").exists()
+    conversation.shutdown()